@@ -237,7 +237,7 @@ fn replace_custom_section(
         offset += 1;
 
         // Read section size (LEB128)
-        let (section_size, size_len) = read_leb128_u32(&wasm_bytes[offset..])?;
+        let (section_size, size_len) = read_leb128_u32(wasm_bytes, offset)?;
         offset += size_len;
 
         let section_end = offset + section_size as usize;
@@ -248,7 +248,7 @@ fn replace_custom_section(
         // Check if this is a custom section with our name
         if section_type == 0 && !found {
             // Read name from custom section
-            let (name_len, name_len_size) = read_leb128_u32(&wasm_bytes[offset..])?;
+            let (name_len, name_len_size) = read_leb128_u32(wasm_bytes, offset)?;
             let name_start = offset + name_len_size;
             let name_end = name_start + name_len as usize;
 
@@ -305,34 +305,10 @@ fn append_custom_section(output: &mut Vec<u8>, name: &str, data: &[u8]) {
     output.extend_from_slice(data);
 }
 
-/// Read LEB128 encoded u32
-fn read_leb128_u32(bytes: &[u8]) -> Result<(u32, usize)> {
-    let mut result = 0u32;
-    let mut shift = 0;
-    let mut offset = 0;
-
-    loop {
-        if offset >= bytes.len() {
-            anyhow::bail!("Unexpected end of LEB128");
-        }
-
-        let byte = bytes[offset];
-        offset += 1;
-
-        if shift >= 32 {
-            anyhow::bail!("LEB128 too large for u32");
-        }
-
-        result |= ((byte & 0x7F) as u32) << shift;
-
-        if byte & 0x80 == 0 {
-            break;
-        }
-
-        shift += 7;
-    }
-
-    Ok((result, offset))
+/// Read a LEB128 encoded u32 at `offset`, returning it with its length
+fn read_leb128_u32(bytes: &[u8], offset: usize) -> Result<(u32, usize)> {
+    wrt_foundation::leb128::read_u32(bytes, offset)
+        .map_err(|e| anyhow::anyhow!("Invalid LEB128: {}", e))
 }
 
 /// Encode u32 as LEB128
@@ -367,7 +343,7 @@ fn test_leb128_roundtrip() {
 
         for value in values {
             let encoded = encode_leb128_u32(value);
-            let (decoded, len) = read_leb128_u32(&encoded).unwrap();
+            let (decoded, len) = read_leb128_u32(&encoded, 0).unwrap();
             assert_eq!(decoded, value);
             assert_eq!(len, encoded.len());
         }
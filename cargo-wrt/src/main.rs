@@ -1089,6 +1089,13 @@ enum SafetyCommand {
         #[arg(long, default_value = "human")]
         format: String,
     },
+
+    /// Generate a machine-readable safety manifest from code annotations
+    Manifest {
+        /// Output file for the manifest
+        #[arg(long, default_value = "safety-manifest.json")]
+        output: String,
+    },
 }
 
 /// Test coverage type arguments
@@ -4838,6 +4845,37 @@ async fn cmd_safety(
 
             Ok(())
         },
+
+        SafetyCommand::Manifest { output } => {
+            use wrt_build_core::safety_manifest::SafetyManifestGenerator;
+
+            let output_path = workspace_root.join(&output);
+            let generator = SafetyManifestGenerator::new(workspace_root.clone())?;
+            let manifest = generator.write(&output_path)?;
+
+            match output_format {
+                OutputFormat::Json | OutputFormat::JsonLines => {
+                    println!("{}", serde_json::to_string(&manifest)?);
+                },
+                OutputFormat::Human => {
+                    let forbid_unsafe =
+                        manifest.crates.iter().filter(|info| info.forbid_unsafe).count();
+                    println!(
+                        "{} Safety manifest written to {}",
+                        "✅".bright_green(),
+                        output_path.display()
+                    );
+                    println!(
+                        "  {} crates ({} forbid unsafe code), {} requirement IDs",
+                        manifest.crates.len(),
+                        forbid_unsafe,
+                        manifest.requirements.len()
+                    );
+                },
+            }
+
+            Ok(())
+        },
     }
 }
 
@@ -0,0 +1,782 @@
+//! Code generation for the interfaces of a WIT document
+
+use proc_macro2::{
+    Ident,
+    Literal,
+    Span,
+    TokenStream,
+};
+use quote::{
+    format_ident,
+    quote,
+};
+
+use crate::parser::{
+    WitDocument,
+    WitFunction,
+    WitInterface,
+    WitInterfacePath,
+    WitResourceFunctionKind,
+    WitType,
+    WitTypeDef,
+    WitTypeDefKind,
+};
+
+/// Rust keywords that need a raw identifier
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+
+/// Generate one module per interface of the document
+pub(crate) fn generate(document: &WitDocument) -> Result<TokenStream, String> {
+    let mut tokens = TokenStream::new();
+    for interface in &document.interfaces {
+        tokens.extend(InterfaceGenerator::new(document, interface)?.generate()?);
+    }
+    Ok(tokens)
+}
+
+/// Convert a WIT identifier to `snake_case`
+pub(crate) fn snake_case(name: &str) -> Ident {
+    let name = name.to_lowercase().replace('-', "_");
+    if matches!(name.as_str(), "self" | "super" | "crate") {
+        format_ident!("{}_", name)
+    } else if KEYWORDS.contains(&name.as_str()) {
+        Ident::new_raw(&name, Span::call_site())
+    } else {
+        format_ident!("{}", name)
+    }
+}
+
+/// Convert a WIT identifier to `UpperCamelCase`
+pub(crate) fn camel_case(name: &str) -> Ident {
+    let name: String = name
+        .split('-')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect()
+            })
+        })
+        .collect();
+    if name == "Self" {
+        format_ident!("Self_")
+    } else {
+        format_ident!("{}", name)
+    }
+}
+
+/// A function of the interface as seen from Rust
+struct Function {
+    /// Name of the function in the component model
+    wit_name: String,
+    /// Name of the Rust method
+    name:     Ident,
+    /// WIT parameter names with their Rust names and types
+    params:   Vec<(String, Ident, TokenStream)>,
+    /// Rust result types
+    results:  Vec<TokenStream>,
+    /// Whether the function uses the async ABI
+    is_async: bool,
+}
+
+impl Function {
+    fn return_type(&self) -> TokenStream {
+        match self.results.as_slice() {
+            [] => quote!(()),
+            [ty] => ty.clone(),
+            types => quote!((#(#types),*)),
+        }
+    }
+}
+
+struct InterfaceGenerator<'a> {
+    document:  &'a WitDocument,
+    interface: &'a WitInterface,
+    /// WIT names of the types in scope, with their Rust names
+    scope:     Vec<(String, Ident)>,
+}
+
+impl<'a> InterfaceGenerator<'a> {
+    fn new(document: &'a WitDocument, interface: &'a WitInterface) -> Result<Self, String> {
+        let mut scope: Vec<(String, Ident)> = interface
+            .types
+            .iter()
+            .map(|def| (def.name.clone(), camel_case(&def.name)))
+            .collect();
+
+        for item in &interface.uses {
+            let WitInterfacePath::Local(used) = &item.interface else {
+                return Err(format!(
+                    "interface `{}` uses types of another package, which is not supported",
+                    interface.name
+                ));
+            };
+            let source = document
+                .interfaces
+                .iter()
+                .find(|candidate| candidate.name == *used)
+                .ok_or_else(|| format!("unknown interface `{used}`"))?;
+            for (name, alias) in &item.names {
+                if !source.types.iter().any(|def| def.name == *name) {
+                    return Err(format!("interface `{used}` has no type `{name}`"));
+                }
+                let local = alias.as_ref().unwrap_or(name);
+                scope.push((local.clone(), camel_case(local)));
+            }
+        }
+
+        Ok(Self {
+            document,
+            interface,
+            scope,
+        })
+    }
+
+    fn generate(&self) -> Result<TokenStream, String> {
+        let typed = typed();
+        let module = snake_case(&self.interface.name);
+        let instance = match &self.document.package {
+            Some(package) => package.interface_id(&self.interface.name),
+            None => self.interface.name.clone(),
+        };
+        let doc = format!(" Bindings for the WIT interface `{instance}`");
+
+        let uses = self.uses();
+        let types = self
+            .interface
+            .types
+            .iter()
+            .map(|def| self.type_def(def))
+            .collect::<Result<Vec<_>, _>>()?;
+        let functions = self.functions()?;
+        let component_instance =
+            quote!(::wrt_component::components::component_instantiation::ComponentInstance);
+
+        let function_types = functions.iter().enumerate().map(|(index, function)| {
+            let builder = format_ident!("__function_type_{}", index);
+            let wit_name = &function.wit_name;
+            let param_names = function.params.iter().map(|(name, ..)| name);
+            let param_types = function.params.iter().map(|(.., ty)| ty);
+            let results = &function.results;
+            let is_async = function.is_async;
+            quote! {
+                fn #builder() -> ::wrt_component::wit::WitFunctionType {
+                    ::wrt_component::wit::WitFunctionType {
+                        instance: ::std::string::String::from(INSTANCE),
+                        name:     ::std::string::String::from(#wit_name),
+                        params:   ::std::vec![#((
+                            ::std::string::String::from(#param_names),
+                            <#param_types as #typed::WitValue>::component_type(),
+                        )),*],
+                        results:  ::std::vec![
+                            #(<#results as #typed::WitValue>::component_type()),*
+                        ],
+                        is_async: #is_async,
+                    }
+                }
+            }
+        });
+        let builders = (0..functions.len()).map(|index| format_ident!("__function_type_{}", index));
+
+        let host_methods = functions.iter().map(|function| {
+            let name = &function.name;
+            let params = function.params.iter().map(|(_, name, ty)| quote!(#name: #ty));
+            let ret = function.return_type();
+            let doc = format!(" Implementation of `{}`", function.wit_name);
+            quote! {
+                #[doc = #doc]
+                fn #name(&self, #(#params),*) -> #typed::Result<#ret>;
+            }
+        });
+
+        let implementations = functions.iter().map(|function| {
+            let name = &function.name;
+            let wit_name = &function.wit_name;
+            let args = (0..function.params.len()).map(|index| {
+                let index = Literal::usize_unsuffixed(index);
+                quote!(#typed::lift_argument(args, #index)?)
+            });
+            let lower = match function.results.len() {
+                0 => quote! {
+                    host.#name(#(#args),*)?;
+                    Ok(::std::vec::Vec::new())
+                },
+                1 => quote! {
+                    let result = host.#name(#(#args),*)?;
+                    Ok(::std::vec![#typed::WitValue::into_value(result)])
+                },
+                count => {
+                    let results: Vec<Ident> =
+                        (0..count).map(|index| format_ident!("result_{}", index)).collect();
+                    quote! {
+                        let (#(#results),*) = host.#name(#(#args),*)?;
+                        Ok(::std::vec![#(#typed::WitValue::into_value(#results)),*])
+                    }
+                },
+            };
+            quote! {
+                {
+                    let host = ::std::sync::Arc::clone(&host);
+                    bindings.implement(INSTANCE, #wit_name, move |args| { #lower })?;
+                }
+            }
+        });
+
+        let guest_methods = functions.iter().enumerate().map(|(index, function)| {
+            let builder = format_ident!("__function_type_{}", index);
+            let name = &function.name;
+            let params = function.params.iter().map(|(_, name, ty)| quote!(#name: #ty));
+            let args = function.params.iter().map(|(_, name, _)| name);
+            let ret = function.return_type();
+            let lift = match function.results.len() {
+                0 => quote!(Ok(())),
+                1 => quote!(#typed::lift_result(results)),
+                _ => quote! {
+                    <#ret as #typed::WitValue>::from_value(
+                        ::wrt_component::canonical_abi::ComponentValue::Tuple(results),
+                    )
+                },
+            };
+            let doc = format!(" Call the exported `{}`", function.wit_name);
+            quote! {
+                #[doc = #doc]
+                #[allow(unused_variables)]
+                pub fn #name(&mut self, #(#params),*) -> #typed::Result<#ret> {
+                    let results = #builder()
+                        .call(self.instance, &[#(#typed::WitValue::into_value(#args)),*])?;
+                    #lift
+                }
+            }
+        });
+
+        Ok(quote! {
+            #[doc = #doc]
+            #[allow(dead_code, clippy::all)]
+            pub mod #module {
+                /// Instance name the interface's functions belong to
+                pub const INSTANCE: &str = #instance;
+
+                #uses
+                #(#types)*
+
+                /// Host implementation of the interface
+                pub trait Host {
+                    #(#host_methods)*
+                }
+
+                #(#function_types)*
+
+                /// Functions of the interface with their WIT types
+                pub fn functions() -> ::std::vec::Vec<::wrt_component::wit::WitFunctionType> {
+                    ::std::vec![#(#builders()),*]
+                }
+
+                /// Define every function of the interface in `linker`, calling
+                /// into `host`
+                pub fn add_to_linker<H: Host + Send + Sync + 'static>(
+                    linker: &mut ::wrt_component::components::component_linker::ComponentLinker,
+                    host: ::std::sync::Arc<H>,
+                ) -> #typed::Result<()> {
+                    let mut bindings =
+                        ::wrt_component::wit::HostBindings::from_functions(functions());
+                    #(#implementations)*
+                    bindings.install(linker)
+                }
+
+                /// Typed calls to a component instance exporting the interface
+                pub struct Guest<'a> {
+                    instance: &'a mut #component_instance,
+                }
+
+                impl<'a> Guest<'a> {
+                    /// Wrap a component instance
+                    pub fn new(
+                        instance: &'a mut #component_instance,
+                    ) -> Self {
+                        Self { instance }
+                    }
+
+                    #(#guest_methods)*
+                }
+            }
+        })
+    }
+
+    fn uses(&self) -> TokenStream {
+        let mut tokens = TokenStream::new();
+        for item in &self.interface.uses {
+            let WitInterfacePath::Local(used) = &item.interface else {
+                continue;
+            };
+            let module = snake_case(used);
+            for (name, alias) in &item.names {
+                let name = camel_case(name);
+                let alias = camel_case(alias.as_ref().map_or(name.to_string().as_str(), |a| a));
+                tokens.extend(quote!(pub use super::#module::#name as #alias;));
+            }
+        }
+        tokens
+    }
+
+    fn lookup(&self, name: &str) -> Result<&Ident, String> {
+        self.scope
+            .iter()
+            .find(|(wit, _)| wit == name)
+            .map(|(_, rust)| rust)
+            .ok_or_else(|| {
+                format!(
+                    "unknown type `{name}` in interface `{}`",
+                    self.interface.name
+                )
+            })
+    }
+
+    fn ty(&self, ty: &WitType) -> Result<TokenStream, String> {
+        let optional = |ty: &Option<Box<WitType>>| match ty {
+            Some(ty) => self.ty(ty),
+            None => Ok(quote!(())),
+        };
+
+        Ok(match ty {
+            WitType::Bool => quote!(bool),
+            WitType::S8 => quote!(i8),
+            WitType::U8 => quote!(u8),
+            WitType::S16 => quote!(i16),
+            WitType::U16 => quote!(u16),
+            WitType::S32 => quote!(i32),
+            WitType::U32 => quote!(u32),
+            WitType::S64 => quote!(i64),
+            WitType::U64 => quote!(u64),
+            WitType::F32 => quote!(f32),
+            WitType::F64 => quote!(f64),
+            WitType::Char => quote!(char),
+            WitType::String => quote!(::std::string::String),
+            WitType::List(element) => {
+                let element = self.ty(element)?;
+                quote!(::std::vec::Vec<#element>)
+            },
+            WitType::Option(inner) => {
+                let inner = self.ty(inner)?;
+                quote!(::core::option::Option<#inner>)
+            },
+            WitType::Result(ok, err) => {
+                let (ok, err) = (optional(ok)?, optional(err)?);
+                quote!(::core::result::Result<#ok, #err>)
+            },
+            WitType::Tuple(types) => {
+                let types = types.iter().map(|ty| self.ty(ty)).collect::<Result<Vec<_>, _>>()?;
+                quote!((#(#types,)*))
+            },
+            WitType::Borrow(resource) => {
+                let resource = self.lookup(resource)?;
+                let typed = typed();
+                quote!(#typed::Borrowed<#resource>)
+            },
+            WitType::Stream(element) => {
+                let (element, typed) = (optional(element)?, typed());
+                quote!(#typed::WitStream<#element>)
+            },
+            WitType::Future(value) => {
+                let (value, typed) = (optional(value)?, typed());
+                quote!(#typed::WitFuture<#value>)
+            },
+            WitType::ErrorContext => {
+                let typed = typed();
+                quote!(#typed::WitErrorContext)
+            },
+            WitType::Named(name) => {
+                let name = self.lookup(name)?;
+                quote!(#name)
+            },
+        })
+    }
+
+    fn functions(&self) -> Result<Vec<Function>, String> {
+        let mut functions = Vec::new();
+        for function in &self.interface.functions {
+            functions.push(self.function(
+                function.name.clone(),
+                snake_case(&function.name),
+                function,
+            )?);
+        }
+
+        for def in &self.interface.types {
+            let WitTypeDefKind::Resource(resource_functions) = &def.kind else {
+                continue;
+            };
+            let resource = camel_case(&def.name);
+            let prefix = def.name.replace('-', "_");
+
+            for resource_function in resource_functions {
+                let wit = &resource_function.function;
+                let function = match resource_function.kind {
+                    WitResourceFunctionKind::Constructor => {
+                        let name = snake_case(&format!("{prefix}_new"));
+                        let mut function =
+                            self.function(format!("[constructor]{}", def.name), name, wit)?;
+                        function.results = vec![quote!(#resource)];
+                        function
+                    },
+                    WitResourceFunctionKind::Method => {
+                        let name = snake_case(&format!("{prefix}_{}", wit.name));
+                        let wit_name = format!("[method]{}.{}", def.name, wit.name);
+                        let mut function = self.function(wit_name, name, wit)?;
+                        let typed = typed();
+                        function.params.insert(
+                            0,
+                            (
+                                "self".to_string(),
+                                snake_case("self"),
+                                quote!(#typed::Borrowed<#resource>),
+                            ),
+                        );
+                        function
+                    },
+                    WitResourceFunctionKind::Static => {
+                        let name = snake_case(&format!("{prefix}_{}", wit.name));
+                        let wit_name = format!("[static]{}.{}", def.name, wit.name);
+                        self.function(wit_name, name, wit)?
+                    },
+                };
+                functions.push(function);
+            }
+        }
+
+        Ok(functions)
+    }
+
+    fn function(
+        &self,
+        wit_name: String,
+        name: Ident,
+        function: &WitFunction,
+    ) -> Result<Function, String> {
+        let params = function
+            .params
+            .iter()
+            .map(|(param, ty)| Ok((param.clone(), snake_case(param), self.ty(ty)?)))
+            .collect::<Result<Vec<_>, String>>()?;
+        let results =
+            function.results.iter().map(|ty| self.ty(ty)).collect::<Result<Vec<_>, _>>()?;
+        Ok(Function {
+            wit_name,
+            name,
+            params,
+            results,
+            is_async: function.is_async,
+        })
+    }
+
+    fn type_def(&self, def: &WitTypeDef) -> Result<TokenStream, String> {
+        let typed = typed();
+        let abi = quote!(::wrt_component::canonical_abi);
+        let name = camel_case(&def.name);
+        let doc = format!(" WIT type `{}`", def.name);
+
+        Ok(match &def.kind {
+            WitTypeDefKind::Alias(ty) => {
+                let ty = self.ty(ty)?;
+                quote! {
+                    #[doc = #doc]
+                    pub type #name = #ty;
+                }
+            },
+            WitTypeDefKind::Record(fields) => {
+                let wit_fields: Vec<&String> = fields.iter().map(|(field, _)| field).collect();
+                let rust_fields: Vec<Ident> =
+                    fields.iter().map(|(field, _)| snake_case(field)).collect();
+                let types =
+                    fields.iter().map(|(_, ty)| self.ty(ty)).collect::<Result<Vec<_>, _>>()?;
+                quote! {
+                    #[doc = #doc]
+                    #[derive(Debug, Clone, PartialEq)]
+                    pub struct #name {
+                        #(pub #rust_fields: #types,)*
+                    }
+
+                    impl #typed::WitValue for #name {
+                        fn component_type() -> #abi::ComponentType {
+                            #abi::ComponentType::Record(::std::vec![#((
+                                ::std::string::String::from(#wit_fields),
+                                <#types as #typed::WitValue>::component_type(),
+                            )),*])
+                        }
+
+                        fn into_value(self) -> #abi::ComponentValue {
+                            #abi::ComponentValue::Record(::std::vec![#((
+                                ::std::string::String::from(#wit_fields),
+                                #typed::WitValue::into_value(self.#rust_fields),
+                            )),*])
+                        }
+
+                        #[allow(unused_mut, unused_variables)]
+                        fn from_value(value: #abi::ComponentValue) -> #typed::Result<Self> {
+                            match value {
+                                #abi::ComponentValue::Record(mut fields) => Ok(Self {
+                                    #(#rust_fields: #typed::take_field(&mut fields, #wit_fields)?,)*
+                                }),
+                                _ => Err(#typed::mismatch()),
+                            }
+                        }
+                    }
+                }
+            },
+            WitTypeDefKind::Variant(cases) => {
+                let mut variants = Vec::new();
+                let mut case_types = Vec::new();
+                let mut lower = Vec::new();
+                let mut lift = Vec::new();
+                for (case, ty) in cases {
+                    let variant = camel_case(case);
+                    match ty {
+                        Some(ty) => {
+                            let ty = self.ty(ty)?;
+                            variants.push(quote!(#variant(#ty)));
+                            case_types.push(quote! {
+                                (
+                                    ::std::string::String::from(#case),
+                                    Some(<#ty as #typed::WitValue>::component_type()),
+                                )
+                            });
+                            lower.push(quote! {
+                                Self::#variant(value) => #abi::ComponentValue::Variant(
+                                    ::std::string::String::from(#case),
+                                    Some(::std::boxed::Box::new(
+                                        #typed::WitValue::into_value(value),
+                                    )),
+                                )
+                            });
+                            lift.push(quote! {
+                                (#case, Some(value)) => {
+                                    Ok(Self::#variant(#typed::WitValue::from_value(*value)?))
+                                }
+                            });
+                        },
+                        None => {
+                            variants.push(quote!(#variant));
+                            case_types.push(quote!((::std::string::String::from(#case), None)));
+                            lower.push(quote! {
+                                Self::#variant => #abi::ComponentValue::Variant(
+                                    ::std::string::String::from(#case),
+                                    None,
+                                )
+                            });
+                            lift.push(quote!((#case, None) => Ok(Self::#variant)));
+                        },
+                    }
+                }
+                quote! {
+                    #[doc = #doc]
+                    #[derive(Debug, Clone, PartialEq)]
+                    pub enum #name {
+                        #(#variants,)*
+                    }
+
+                    impl #typed::WitValue for #name {
+                        fn component_type() -> #abi::ComponentType {
+                            #abi::ComponentType::Variant(::std::vec![#(#case_types),*])
+                        }
+
+                        fn into_value(self) -> #abi::ComponentValue {
+                            match self {
+                                #(#lower,)*
+                            }
+                        }
+
+                        fn from_value(value: #abi::ComponentValue) -> #typed::Result<Self> {
+                            match value {
+                                #abi::ComponentValue::Variant(case, payload) => {
+                                    match (case.as_str(), payload) {
+                                        #(#lift,)*
+                                        _ => Err(#typed::mismatch()),
+                                    }
+                                },
+                                _ => Err(#typed::mismatch()),
+                            }
+                        }
+                    }
+                }
+            },
+            WitTypeDefKind::Enum(cases) => {
+                let variants: Vec<Ident> = cases.iter().map(|case| camel_case(case)).collect();
+                quote! {
+                    #[doc = #doc]
+                    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+                    pub enum #name {
+                        #(#variants,)*
+                    }
+
+                    impl #typed::WitValue for #name {
+                        fn component_type() -> #abi::ComponentType {
+                            #abi::ComponentType::Enum(::std::vec![
+                                #(::std::string::String::from(#cases)),*
+                            ])
+                        }
+
+                        fn into_value(self) -> #abi::ComponentValue {
+                            let case = match self {
+                                #(Self::#variants => #cases,)*
+                            };
+                            #abi::ComponentValue::Enum(::std::string::String::from(case))
+                        }
+
+                        fn from_value(value: #abi::ComponentValue) -> #typed::Result<Self> {
+                            match value {
+                                #abi::ComponentValue::Enum(case) => match case.as_str() {
+                                    #(#cases => Ok(Self::#variants),)*
+                                    _ => Err(#typed::mismatch()),
+                                },
+                                _ => Err(#typed::mismatch()),
+                            }
+                        }
+                    }
+                }
+            },
+            WitTypeDefKind::Flags(flags) => {
+                let fields: Vec<Ident> = flags.iter().map(|flag| snake_case(flag)).collect();
+                quote! {
+                    #[doc = #doc]
+                    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+                    pub struct #name {
+                        #(pub #fields: bool,)*
+                    }
+
+                    impl #typed::WitValue for #name {
+                        fn component_type() -> #abi::ComponentType {
+                            #abi::ComponentType::Flags(::std::vec![
+                                #(::std::string::String::from(#flags)),*
+                            ])
+                        }
+
+                        #[allow(unused_mut)]
+                        fn into_value(self) -> #abi::ComponentValue {
+                            let mut active = ::std::vec::Vec::new();
+                            #(
+                                if self.#fields {
+                                    active.push(::std::string::String::from(#flags));
+                                }
+                            )*
+                            #abi::ComponentValue::Flags(active)
+                        }
+
+                        fn from_value(value: #abi::ComponentValue) -> #typed::Result<Self> {
+                            const FLAGS: &[&str] = &[#(#flags),*];
+                            match value {
+                                #abi::ComponentValue::Flags(active)
+                                    if active.iter().all(|flag| FLAGS.contains(&flag.as_str())) =>
+                                {
+                                    Ok(Self {
+                                        #(#fields: active.iter().any(|flag| flag == #flags),)*
+                                    })
+                                },
+                                _ => Err(#typed::mismatch()),
+                            }
+                        }
+                    }
+                }
+            },
+            WitTypeDefKind::Resource(_) => quote! {
+                #[doc = #doc]
+                #[derive(Debug, Clone, PartialEq, Eq)]
+                pub struct #name {
+                    handle: u32,
+                }
+
+                impl #typed::WitResource for #name {
+                    fn from_handle(handle: u32) -> Self {
+                        Self { handle }
+                    }
+
+                    fn handle(&self) -> u32 {
+                        self.handle
+                    }
+                }
+
+                impl #typed::WitValue for #name {
+                    fn component_type() -> #abi::ComponentType {
+                        #abi::ComponentType::Own(0)
+                    }
+
+                    fn into_value(self) -> #abi::ComponentValue {
+                        #abi::ComponentValue::Own(self.handle)
+                    }
+
+                    fn from_value(value: #abi::ComponentValue) -> #typed::Result<Self> {
+                        match value {
+                            #abi::ComponentValue::Own(handle) => Ok(Self { handle }),
+                            _ => Err(#typed::mismatch()),
+                        }
+                    }
+                }
+            },
+        })
+    }
+}
+
+fn typed() -> TokenStream {
+    quote!(::wrt_component::wit::typed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_wit;
+
+    fn generated(source: &str) -> Result<String, String> {
+        generate(&parse_wit(source).unwrap()).map(|tokens| tokens.to_string())
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(camel_case("http-request").to_string(), "HttpRequest");
+        assert_eq!(camel_case("URL").to_string(), "Url");
+        assert_eq!(snake_case("get-value").to_string(), "get_value");
+        assert_eq!(snake_case("type").to_string(), "r#type");
+        assert_eq!(snake_case("self").to_string(), "self_");
+    }
+
+    #[test]
+    fn test_generate_interface() {
+        let code = generated(
+            "package example:fs@0.1.0;
+
+             interface types {
+                 enum kind { file, directory }
+             }
+
+             interface files {
+                 use types.{kind as entry-kind};
+                 record entry { name: string, kind: entry-kind }
+                 resource file {
+                     constructor(path: string);
+                     read: func(len: u32) -> result<list<u8>, string>;
+                 }
+                 list-dir: func(path: string) -> list<entry>;
+             }",
+        )
+        .unwrap();
+
+        assert!(code.contains("pub mod types"));
+        assert!(code.contains("pub mod files"));
+        assert!(code.contains("\"example:fs/files@0.1.0\""));
+        assert!(code.contains("pub use super :: types :: Kind as EntryKind"));
+        assert!(code.contains("pub struct Entry"));
+        assert!(code.contains("fn list_dir (& self , path : :: std :: string :: String)"));
+        assert!(code.contains("fn file_new"));
+        assert!(code.contains("\"[method]file.read\""));
+        assert!(code.contains("fn file_read (& self , self_ : :: wrt_component"));
+    }
+
+    #[test]
+    fn test_unknown_types() {
+        assert!(generated("interface a { f: func(x: missing); }").is_err());
+        assert!(generated("interface a { use b.{t}; }").is_err());
+        assert!(generated("interface a { use wasi:io/streams.{error}; }").is_err());
+    }
+}
@@ -0,0 +1,161 @@
+//! Typed bindings for WIT interfaces
+//!
+//! The [`bindgen!`] macro reads a WIT document at compile time and generates
+//! a Rust module per interface, so embedders never marshal
+//! `ComponentValue`s by hand. Each module contains:
+//!
+//! - Rust types for the interface's records, variants, enums, flags and
+//!   resources, convertible to and from component values
+//! - a `Host` trait with one method per function, and `add_to_linker` to
+//!   install an implementation into a `ComponentLinker`
+//! - a `Guest` wrapper calling the functions exported by a component instance
+//!   with typed arguments and results
+//!
+//! Every call is checked against the WIT types through
+//! `wrt_component::wit::HostBindings` and `WitFunctionType::call`.
+//!
+//! # Example
+//!
+//! ```ignore
+//! wrt_bindgen::bindgen!({
+//!     inline: "
+//!         package example:greeter;
+//!
+//!         interface names {
+//!             record person { name: string, age: u8 }
+//!             greet: func(who: person) -> string;
+//!         }
+//!     ",
+//! });
+//!
+//! struct Greeter;
+//!
+//! impl names::Host for Greeter {
+//!     fn greet(&self, who: names::Person) -> wrt_error::Result<String> {
+//!         Ok(format!("Hello, {}", who.name))
+//!     }
+//! }
+//!
+//! let mut linker = ComponentLinker::new();
+//! names::add_to_linker(&mut linker, Arc::new(Greeter))?;
+//! ```
+//!
+//! The WIT document is given either `inline` or as a `path` relative to the
+//! manifest directory of the crate invoking the macro. Names follow Rust
+//! conventions: types become `UpperCamelCase`, functions, fields and modules
+//! `snake_case`. Resource functions are named after their resource, such as
+//! `file_new` for a constructor and `file_read` for a method, and methods
+//! take the borrowed resource as their first argument.
+
+#![forbid(unsafe_code)]
+
+mod generate;
+// The runtime's WIT parser, shared so that bindings and runtime checks agree
+// on the accepted syntax
+#[allow(clippy::std_instead_of_alloc, clippy::std_instead_of_core)]
+#[path = "../../wrt-component/src/wit/parser.rs"]
+mod parser;
+
+use proc_macro::TokenStream;
+use syn::{
+    braced,
+    parse::{
+        Parse,
+        ParseStream,
+    },
+    parse_macro_input,
+    punctuated::Punctuated,
+    Ident,
+    LitStr,
+    Token,
+};
+
+/// Generate typed bindings for the interfaces of a WIT document
+///
+/// See the [crate documentation](crate) for the generated items.
+#[proc_macro]
+pub fn bindgen(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as BindgenInput);
+    match input.expand() {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+/// Where the WIT source comes from
+enum Source {
+    Inline(LitStr),
+    Path(LitStr),
+}
+
+struct BindgenInput {
+    source: Source,
+}
+
+impl Parse for BindgenInput {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let content;
+        braced!(content in input);
+
+        let mut source = None;
+        for option in Punctuated::<(Ident, LitStr), Token![,]>::parse_terminated_with(
+            &content,
+            |input| {
+                let key = input.parse()?;
+                input.parse::<Token![:]>()?;
+                Ok((key, input.parse()?))
+            },
+        )? {
+            let (key, value) = option;
+            let option = match key.to_string().as_str() {
+                "inline" => Source::Inline(value),
+                "path" => Source::Path(value),
+                _ => return Err(syn::Error::new(key.span(), "expected `inline` or `path`")),
+            };
+            if source.replace(option).is_some() {
+                return Err(syn::Error::new(key.span(), "WIT source given twice"));
+            }
+        }
+
+        let source = source
+            .ok_or_else(|| syn::Error::new(input.span(), "expected `inline` or `path`"))?;
+        Ok(Self { source })
+    }
+}
+
+impl BindgenInput {
+    fn expand(&self) -> syn::Result<proc_macro2::TokenStream> {
+        let (text, span, path) = match &self.source {
+            Source::Inline(text) => (text.value(), text.span(), None),
+            Source::Path(path) => {
+                let root = std::env::var("CARGO_MANIFEST_DIR")
+                    .map_err(|_| syn::Error::new(path.span(), "CARGO_MANIFEST_DIR is not set"))?;
+                let file = std::path::Path::new(&root).join(path.value());
+                let text = std::fs::read_to_string(&file).map_err(|error| {
+                    syn::Error::new(
+                        path.span(),
+                        format!("failed to read {}: {error}", file.display()),
+                    )
+                })?;
+                (text, path.span(), Some(file))
+            },
+        };
+
+        let mut parser = parser::WitParser::new(&text);
+        let document = parser.parse().map_err(|error| {
+            let (line, column) = parser.line_column();
+            syn::Error::new(span, format!("{} at {line}:{column}", error.message))
+        })?;
+
+        let mut tokens =
+            generate::generate(&document).map_err(|message| syn::Error::new(span, message))?;
+
+        // Rebuild when the WIT file changes
+        if let Some(file) = path {
+            let file = file.display().to_string();
+            tokens.extend(quote::quote! { const _: &str = include_str!(#file); });
+        }
+
+        Ok(tokens)
+    }
+}
@@ -66,6 +66,7 @@
 pub mod memory;
 pub mod parsers;
 pub mod requirements;
+pub mod safety_manifest;
 pub mod test;
 pub mod text_search;
 pub mod tool_versions;
@@ -0,0 +1,359 @@
+//! Safety manifest generation from code annotations
+//!
+//! This module collects safety-relevant annotations from the workspace
+//! sources into a machine-readable safety manifest. The manifest supports
+//! users who must document the runtime for IEC 61508 / ISO 26262 assessments
+//! and records, per crate:
+//!
+//! - whether `unsafe` code is forbidden (`#![forbid(unsafe_code)]` or the
+//!   `unsafe_code = "forbid"` lint in `Cargo.toml`)
+//! - the safety level features the crate offers (`qm`, `asil-a` .. `asil-d`)
+//! - the requirement IDs referenced by `SW-REQ-ID:` source annotations
+//! - the declared bounds (`MAX_*` constants)
+//! - the verification levels referenced in the code
+//! - the Kani proof harnesses
+
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use regex::Regex;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use walkdir::WalkDir;
+
+use crate::error::{
+    BuildError,
+    BuildResult,
+};
+
+/// Version of the manifest format
+pub const SAFETY_MANIFEST_SCHEMA_VERSION: u32 = 1;
+
+/// Cargo features selecting a safety level preset
+const SAFETY_LEVEL_FEATURES: [&str; 5] = ["qm", "asil-a", "asil-b", "asil-c", "asil-d"];
+
+/// Default file name of the generated manifest
+pub const SAFETY_MANIFEST_FILE_NAME: &str = "safety-manifest.json";
+
+/// Safety manifest for the whole workspace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SafetyManifest {
+    /// Version of the manifest format
+    pub schema_version: u32,
+    /// Workspace version the manifest was generated for
+    pub version:        String,
+    /// Generation timestamp (RFC 3339)
+    pub generated_at:   String,
+    /// Per-crate safety information
+    pub crates:         Vec<CrateSafetyInfo>,
+    /// Requirement IDs mapped to the crates annotating them
+    pub requirements:   BTreeMap<String, RequirementTrace>,
+}
+
+/// Safety information collected for a single crate
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CrateSafetyInfo {
+    /// Crate name
+    pub name:                String,
+    /// Crate path relative to the workspace root
+    pub path:                String,
+    /// Whether `unsafe` code is forbidden for the crate
+    pub forbid_unsafe:       bool,
+    /// Safety level features offered by the crate
+    pub safety_features:     Vec<String>,
+    /// Requirement IDs referenced by source annotations
+    pub requirement_ids:     Vec<String>,
+    /// Declared bounds (`MAX_*` constants) and their values
+    pub bounds:              BTreeMap<String, String>,
+    /// Verification levels referenced in the sources
+    pub verification_levels: Vec<String>,
+    /// Names of the Kani proof harnesses
+    pub kani_harnesses:      Vec<String>,
+}
+
+/// Trace of a requirement ID through the workspace
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RequirementTrace {
+    /// Requirement title from `requirements.toml`, if defined there
+    pub title:      Option<String>,
+    /// ASIL level from `requirements.toml`, if defined there
+    pub asil_level: Option<String>,
+    /// Source files annotated with the requirement ID
+    pub files:      Vec<String>,
+}
+
+/// Generator collecting safety annotations into a [`SafetyManifest`]
+pub struct SafetyManifestGenerator {
+    workspace_root: PathBuf,
+    req_id:         Regex,
+    bound:          Regex,
+    verification:   Regex,
+    kani_harness:   Regex,
+}
+
+impl SafetyManifestGenerator {
+    /// Create a new generator for the given workspace root
+    pub fn new(workspace_root: PathBuf) -> BuildResult<Self> {
+        let regex = |pattern: &str| {
+            Regex::new(pattern)
+                .map_err(|e| BuildError::Config(format!("Invalid annotation pattern: {}", e)))
+        };
+
+        Ok(Self {
+            workspace_root,
+            req_id: regex(r"SW-REQ-ID:\s*([A-Za-z0-9_\-]+)")?,
+            bound: regex(r"pub\s+const\s+(MAX_[A-Z0-9_]+)\s*:\s*[A-Za-z0-9_]+\s*=\s*([^;]+);")?,
+            verification: regex(r"VerificationLevel::([A-Z][A-Za-z]+)")?,
+            kani_harness: regex(
+                r"#\[kani::proof\](?:\s*#\[[^\]]*\])*\s*(?:pub(?:\([a-z]+\))?\s+)?fn\s+([A-Za-z0-9_]+)",
+            )?,
+        })
+    }
+
+    /// Generate the manifest for all workspace member crates
+    pub fn generate(&self) -> BuildResult<SafetyManifest> {
+        let mut crates = Vec::new();
+        let mut requirements: BTreeMap<String, RequirementTrace> = BTreeMap::new();
+
+        for crate_path in self.workspace_members()? {
+            let (info, annotated_files) = self.scan_crate(&crate_path)?;
+            for (req_id, files) in annotated_files {
+                requirements.entry(req_id).or_default().files.extend(files);
+            }
+            crates.push(info);
+        }
+
+        self.merge_requirement_definitions(&mut requirements);
+
+        Ok(SafetyManifest {
+            schema_version: SAFETY_MANIFEST_SCHEMA_VERSION,
+            version: crate::VERSION.to_string(),
+            generated_at: chrono::Utc::now().to_rfc3339(),
+            crates,
+            requirements,
+        })
+    }
+
+    /// Generate the manifest and write it as pretty-printed JSON
+    pub fn write(&self, output: &Path) -> BuildResult<SafetyManifest> {
+        let manifest = self.generate()?;
+        let json = serde_json::to_string_pretty(&manifest)
+            .map_err(|e| BuildError::Other(anyhow::anyhow!(e)))?;
+        fs::write(output, json)?;
+        Ok(manifest)
+    }
+
+    /// Paths of the workspace member crates listed in the root `Cargo.toml`
+    fn workspace_members(&self) -> BuildResult<Vec<PathBuf>> {
+        let content = fs::read_to_string(self.workspace_root.join("Cargo.toml"))?;
+        let manifest: toml::Value = toml::from_str(&content)
+            .map_err(|e| BuildError::Workspace(format!("Failed to parse Cargo.toml: {}", e)))?;
+
+        let members = manifest
+            .get("workspace")
+            .and_then(|w| w.get("members"))
+            .and_then(toml::Value::as_array)
+            .ok_or_else(|| BuildError::Workspace("No workspace members found".to_string()))?;
+
+        Ok(members
+            .iter()
+            .filter_map(toml::Value::as_str)
+            .map(|member| self.workspace_root.join(member))
+            .filter(|path| path.join("Cargo.toml").exists())
+            .collect())
+    }
+
+    /// Collect the safety information of a single crate
+    ///
+    /// Also returns the files annotated with each requirement ID.
+    fn scan_crate(
+        &self,
+        crate_path: &Path,
+    ) -> BuildResult<(CrateSafetyInfo, BTreeMap<String, BTreeSet<String>>)> {
+        let cargo_toml = fs::read_to_string(crate_path.join("Cargo.toml"))?;
+        let cargo: toml::Value = toml::from_str(&cargo_toml).map_err(|e| {
+            BuildError::Workspace(format!(
+                "Failed to parse {}: {}",
+                crate_path.join("Cargo.toml").display(),
+                e
+            ))
+        })?;
+
+        let mut info = CrateSafetyInfo {
+            name: cargo
+                .get("package")
+                .and_then(|p| p.get("name"))
+                .and_then(toml::Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            path: self.relative(crate_path),
+            forbid_unsafe: lints_forbid_unsafe(&cargo),
+            safety_features: safety_features(&cargo),
+            ..CrateSafetyInfo::default()
+        };
+
+        let mut req_files: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        let mut verification_levels = BTreeSet::new();
+
+        let src_dir = crate_path.join("src");
+        for entry in WalkDir::new(&src_dir).into_iter().filter_map(Result::ok) {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "rs") {
+                continue;
+            }
+            let Ok(source) = fs::read_to_string(path) else {
+                continue;
+            };
+            let file = self.relative(path);
+
+            if path.file_name().is_some_and(|name| name == "lib.rs")
+                && source.contains("#![forbid(unsafe_code)]")
+            {
+                info.forbid_unsafe = true;
+            }
+            for capture in self.req_id.captures_iter(&source) {
+                req_files.entry(capture[1].to_string()).or_default().insert(file.clone());
+            }
+            for capture in self.bound.captures_iter(&source) {
+                info.bounds.insert(capture[1].to_string(), capture[2].trim().to_string());
+            }
+            for capture in self.verification.captures_iter(&source) {
+                verification_levels.insert(capture[1].to_string());
+            }
+            for capture in self.kani_harness.captures_iter(&source) {
+                info.kani_harnesses.push(capture[1].to_string());
+            }
+        }
+
+        info.requirement_ids = req_files.keys().cloned().collect();
+        info.verification_levels = verification_levels.into_iter().collect();
+        info.kani_harnesses.sort();
+
+        Ok((info, req_files))
+    }
+
+    /// Add titles and ASIL levels from `requirements.toml`, if present
+    fn merge_requirement_definitions(&self, requirements: &mut BTreeMap<String, RequirementTrace>) {
+        let Ok(content) = fs::read_to_string(self.workspace_root.join("requirements.toml")) else {
+            return;
+        };
+        let Ok(definitions) = toml::from_str::<toml::Value>(&content) else {
+            return;
+        };
+        let Some(entries) = definitions.get("requirement").and_then(toml::Value::as_array) else {
+            return;
+        };
+
+        for entry in entries {
+            let Some(id) = entry.get("id").and_then(toml::Value::as_str) else {
+                continue;
+            };
+            let trace = requirements.entry(id.to_string()).or_default();
+            trace.title = entry.get("title").and_then(toml::Value::as_str).map(str::to_string);
+            trace.asil_level =
+                entry.get("asil_level").and_then(toml::Value::as_str).map(str::to_string);
+        }
+    }
+
+    /// Path relative to the workspace root, using `/` separators
+    fn relative(&self, path: &Path) -> String {
+        path.strip_prefix(&self.workspace_root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+}
+
+/// Whether the crate's `[lints]` table forbids unsafe code
+fn lints_forbid_unsafe(cargo: &toml::Value) -> bool {
+    let Some(lints) = cargo.get("lints") else {
+        return false;
+    };
+    // Both `rust.unsafe_code = "forbid"` and `[lints.rust] unsafe_code = ...`
+    // parse into the same nested table
+    lints
+        .get("rust")
+        .and_then(|rust| rust.get("unsafe_code"))
+        .and_then(toml::Value::as_str)
+        .is_some_and(|level| level == "forbid")
+}
+
+/// Safety level features declared in the crate's `[features]` table
+fn safety_features(cargo: &toml::Value) -> Vec<String> {
+    let Some(features) = cargo.get("features").and_then(toml::Value::as_table) else {
+        return Vec::new();
+    };
+    features
+        .keys()
+        .filter(|name| SAFETY_LEVEL_FEATURES.contains(&name.as_str()))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_generate_collects_annotations() {
+        let root = tempfile::tempdir().unwrap();
+        write(
+            &root.path().join("Cargo.toml"),
+            "[workspace]\nmembers = [\"safe-crate\"]\n",
+        );
+        write(
+            &root.path().join("safe-crate/Cargo.toml"),
+            "[package]\nname = \"safe-crate\"\n\n[features]\nstd = []\nasil-d = []\n",
+        );
+        write(
+            &root.path().join("safe-crate/src/lib.rs"),
+            // Split so this file is not picked up as an annotation itself
+            &format!(
+                "// {}: REQ_MEM_001\n#![forbid(unsafe_code)]\npub const MAX_ITEMS: usize = \
+                 64;\nconst LEVEL: VerificationLevel = \
+                 VerificationLevel::Full;\n#[cfg(kani)]\n#[kani::proof]\nfn verify_bounds() {{}}\n",
+                "SW-REQ-ID"
+            ),
+        );
+        write(
+            &root.path().join("requirements.toml"),
+            "[[requirement]]\nid = \"REQ_MEM_001\"\ntitle = \"Memory Bounds \
+             Checking\"\nasil_level = \"AsilC\"\n",
+        );
+
+        let manifest = SafetyManifestGenerator::new(root.path().to_path_buf())
+            .unwrap()
+            .generate()
+            .unwrap();
+
+        assert_eq!(manifest.crates.len(), 1);
+        let info = &manifest.crates[0];
+        assert_eq!(info.name, "safe-crate");
+        assert!(info.forbid_unsafe);
+        assert_eq!(info.safety_features, vec!["asil-d".to_string()]);
+        assert_eq!(info.requirement_ids, vec!["REQ_MEM_001".to_string()]);
+        assert_eq!(info.bounds.get("MAX_ITEMS").map(String::as_str), Some("64"));
+        assert_eq!(info.verification_levels, vec!["Full".to_string()]);
+        assert_eq!(info.kani_harnesses, vec!["verify_bounds".to_string()]);
+
+        let trace = &manifest.requirements["REQ_MEM_001"];
+        assert_eq!(trace.title.as_deref(), Some("Memory Bounds Checking"));
+        assert_eq!(trace.files, vec!["safe-crate/src/lib.rs".to_string()]);
+    }
+}
@@ -0,0 +1,331 @@
+/* C API for the WRT WebAssembly runtime. Generated by cbindgen, do not edit. */
+
+#ifndef WRT_H
+#define WRT_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+// Configuration shared by the stores created from it
+typedef struct wrt_engine_t wrt_engine_t;
+
+// An error returned by a fallible function
+typedef struct wrt_error_t wrt_error_t;
+
+// A function exported by an instance
+typedef struct wrt_func_t wrt_func_t;
+
+// An instance of a module
+typedef struct wrt_instance_t wrt_instance_t;
+
+// A memory exported by an instance
+//
+// The memory stays accessible after the store is deleted.
+typedef struct wrt_memory_t wrt_memory_t;
+
+// A module loaded into a store
+typedef struct wrt_module_t wrt_module_t;
+
+// Owner of the modules and instances created in it
+typedef struct wrt_store_t wrt_store_t;
+
+// Safety preset of an engine, one of the `WRT_PRESET_*` constants
+typedef uint8_t wrt_preset_t;
+
+// Kind of a value, one of the `WRT_I32`, `WRT_I64`, `WRT_F32` and
+// `WRT_F64` constants
+typedef uint8_t wrt_valkind_t;
+
+// Payload of a value, the field named by its kind
+typedef union {
+  // Payload of `WRT_I32`
+  int32_t i32;
+  // Payload of `WRT_I64`
+  int64_t i64;
+  // Payload of `WRT_F32`
+  float f32;
+  // Payload of `WRT_F64`
+  double f64;
+} wrt_val_union;
+
+// A value passed to or returned from a function
+typedef struct {
+  // Kind of the value
+  wrt_valkind_t kind;
+  // Payload of the value
+  wrt_val_union of;
+} wrt_val_t;
+
+// Quality management: dynamic allocation, flexible limits
+#define WRT_PRESET_QM 0
+
+// ASIL-A: bounded collections, sampling verification
+#define WRT_PRESET_ASIL_A 1
+
+// ASIL-B: static allocation, continuous verification
+#define WRT_PRESET_ASIL_B 2
+
+// ASIL-C: enhanced verification, strict memory bounds
+#define WRT_PRESET_ASIL_C 3
+
+// ASIL-D: deterministic execution, no host access
+#define WRT_PRESET_ASIL_D 4
+
+// 32-bit integer
+#define WRT_I32 0
+
+// 64-bit integer
+#define WRT_I64 1
+
+// 32-bit float
+#define WRT_F32 2
+
+// 64-bit float
+#define WRT_F64 3
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Create an engine with the quality management preset
+//
+// The engine must be deleted with `wrt_engine_delete`.
+wrt_engine_t *wrt_engine_new(void);
+
+// Create an engine with one of the `WRT_PRESET_*` presets
+//
+// Returns null for an unknown preset.
+wrt_engine_t *wrt_engine_new_with_preset(wrt_preset_t preset);
+
+// Delete an engine
+//
+// Stores created from the engine stay valid.
+//
+// # Safety
+//
+// `engine` must be null or an engine returned by this API that has not
+// been deleted.
+void wrt_engine_delete(wrt_engine_t *engine);
+
+// Create a store with the configuration of `engine`
+//
+// On success `*out` receives a store that must be deleted with
+// `wrt_store_delete`.
+//
+// # Safety
+//
+// `engine` must be null or a live engine, and `out` null or writable.
+wrt_error_t *wrt_store_new(const wrt_engine_t *engine, wrt_store_t **out);
+
+// Delete a store together with its modules and instances
+//
+// Handles of modules, instances and functions created in the store must
+// still be deleted, but can no longer be used.
+//
+// # Safety
+//
+// `store` must be null or a store returned by this API that has not been
+// deleted.
+void wrt_store_delete(wrt_store_t *store);
+
+// Get the message of an error
+//
+// The string is owned by the error and valid until it is deleted. Returns
+// null if `error` is null.
+//
+// # Safety
+//
+// `error` must be null or an error returned by this API that has not been
+// deleted.
+const char *wrt_error_message(const wrt_error_t *error);
+
+// Get the numeric code of an error, as defined by `wrt_error::codes`
+//
+// Returns 0 if `error` is null.
+//
+// # Safety
+//
+// `error` must be null or an error returned by this API that has not been
+// deleted.
+uint16_t wrt_error_code(const wrt_error_t *error);
+
+// Delete an error
+//
+// # Safety
+//
+// `error` must be null or an error returned by this API that has not been
+// deleted.
+void wrt_error_delete(wrt_error_t *error);
+
+// Look up the function `instance` exports as `name`
+//
+// On success `*out` receives a function that must be deleted with
+// `wrt_func_delete`.
+//
+// # Safety
+//
+// `store` and `instance` must be null or live, `name` null or a
+// NUL-terminated string, and `out` null or writable.
+wrt_error_t *wrt_instance_export_func(const wrt_store_t *store,
+                                      const wrt_instance_t *instance,
+                                      const char *name,
+                                      wrt_func_t **out);
+
+// Number of parameters of a function, 0 if `func` is null
+//
+// # Safety
+//
+// `func` must be null or a live function.
+size_t wrt_func_param_arity(const wrt_func_t *func);
+
+// Number of results of a function, 0 if `func` is null
+//
+// # Safety
+//
+// `func` must be null or a live function.
+size_t wrt_func_result_arity(const wrt_func_t *func);
+
+// Call a function
+//
+// `args` must hold exactly the parameters of the function, with matching
+// kinds, and `results` room for exactly its results. A trap is reported as
+// an error.
+//
+// # Safety
+//
+// `store` must be null or a live store not used concurrently, `func` null
+// or a live function, `args` must point to `nargs` values and `results` to
+// `nresults` writable values unless the count is 0.
+wrt_error_t *wrt_func_call(wrt_store_t *store,
+                           const wrt_func_t *func,
+                           const wrt_val_t *args,
+                           size_t nargs,
+                           wrt_val_t *results,
+                           size_t nresults);
+
+// Delete a function handle
+//
+// # Safety
+//
+// `func` must be null or a function returned by this API that has not
+// been deleted.
+void wrt_func_delete(wrt_func_t *func);
+
+// Check that `binary` is a well-formed module without loading it
+//
+// # Safety
+//
+// `binary` must point to `len` readable bytes unless `len` is 0.
+wrt_error_t *wrt_module_validate(const uint8_t *binary, size_t len);
+
+// Load a module binary into a store
+//
+// The binary is copied, so it may be released once the function returns.
+// On success `*out` receives a module that must be deleted with
+// `wrt_module_delete`.
+//
+// # Safety
+//
+// `store` must be null or a live store not used concurrently, `binary`
+// must point to `len` readable bytes unless `len` is 0, and `out` must be
+// null or writable.
+wrt_error_t *wrt_module_new(wrt_store_t *store,
+                            const uint8_t *binary,
+                            size_t len,
+                            wrt_module_t **out);
+
+// Delete a module handle
+//
+// Instances of the module stay valid.
+//
+// # Safety
+//
+// `module` must be null or a module returned by this API that has not been
+// deleted.
+void wrt_module_delete(wrt_module_t *module);
+
+// Instantiate a module of `store`
+//
+// The start function, if any, runs before the function returns. On
+// success `*out` receives an instance that must be deleted with
+// `wrt_instance_delete`.
+//
+// # Safety
+//
+// `store` must be null or a live store not used concurrently, `module`
+// null or a live module, and `out` null or writable.
+wrt_error_t *wrt_instance_new(wrt_store_t *store, const wrt_module_t *module, wrt_instance_t **out);
+
+// Delete an instance handle
+//
+// # Safety
+//
+// `instance` must be null or an instance returned by this API that has not
+// been deleted.
+void wrt_instance_delete(wrt_instance_t *instance);
+
+// Look up the memory `instance` exports as `name`
+//
+// On success `*out` receives a memory that must be deleted with
+// `wrt_memory_delete`.
+//
+// # Safety
+//
+// `store` and `instance` must be null or live, `name` null or a
+// NUL-terminated string, and `out` null or writable.
+wrt_error_t *wrt_instance_export_memory(const wrt_store_t *store,
+                                        const wrt_instance_t *instance,
+                                        const char *name,
+                                        wrt_memory_t **out);
+
+// Size of a memory in 64 KiB pages, 0 if `memory` is null
+//
+// # Safety
+//
+// `memory` must be null or a live memory.
+uint32_t wrt_memory_size(const wrt_memory_t *memory);
+
+// Size of a memory in bytes, 0 if `memory` is null
+//
+// # Safety
+//
+// `memory` must be null or a live memory.
+size_t wrt_memory_data_size(const wrt_memory_t *memory);
+
+// Copy `len` bytes starting at `offset` out of a memory
+//
+// # Safety
+//
+// `memory` must be null or a live memory and `buffer` must point to `len`
+// writable bytes unless `len` is 0.
+wrt_error_t *wrt_memory_read(const wrt_memory_t *memory,
+                             uint32_t offset,
+                             uint8_t *buffer,
+                             size_t len);
+
+// Copy `len` bytes into a memory starting at `offset`
+//
+// Writes go to the memory in place, so instances sharing it see them.
+//
+// # Safety
+//
+// `memory` must be null or a live memory and `data` must point to `len`
+// readable bytes unless `len` is 0.
+wrt_error_t *wrt_memory_write(wrt_memory_t *memory,
+                              uint32_t offset,
+                              const uint8_t *data,
+                              size_t len);
+
+// Delete a memory handle
+//
+// # Safety
+//
+// `memory` must be null or a memory returned by this API that has not been
+// deleted.
+void wrt_memory_delete(wrt_memory_t *memory);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* WRT_H */
@@ -0,0 +1,207 @@
+//! Engines and stores
+
+use core::sync::atomic::{
+    AtomicU64,
+    Ordering,
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_foundation::memory_init::MemoryInitializer;
+use wrt_runtime::engine::{
+    CapabilityAwareEngine,
+    EnginePreset,
+};
+
+use crate::{
+    error::{
+        into_raw,
+        wrt_error_t,
+    },
+    ffi,
+};
+
+/// Safety preset of an engine, one of the `WRT_PRESET_*` constants
+pub type wrt_preset_t = u8;
+
+/// Quality management: dynamic allocation, flexible limits
+pub const WRT_PRESET_QM: wrt_preset_t = 0;
+/// ASIL-A: bounded collections, sampling verification
+pub const WRT_PRESET_ASIL_A: wrt_preset_t = 1;
+/// ASIL-B: static allocation, continuous verification
+pub const WRT_PRESET_ASIL_B: wrt_preset_t = 2;
+/// ASIL-C: enhanced verification, strict memory bounds
+pub const WRT_PRESET_ASIL_C: wrt_preset_t = 3;
+/// ASIL-D: deterministic execution, no host access
+pub const WRT_PRESET_ASIL_D: wrt_preset_t = 4;
+
+/// Configuration shared by the stores created from it
+pub struct wrt_engine_t {
+    preset: EnginePreset,
+}
+
+/// Create an engine with the quality management preset
+///
+/// The engine must be deleted with `wrt_engine_delete`.
+#[no_mangle]
+pub extern "C" fn wrt_engine_new() -> *mut wrt_engine_t {
+    Box::into_raw(Box::new(wrt_engine_t {
+        preset: EnginePreset::QM,
+    }))
+}
+
+/// Create an engine with one of the `WRT_PRESET_*` presets
+///
+/// Returns null for an unknown preset.
+#[no_mangle]
+pub extern "C" fn wrt_engine_new_with_preset(preset: wrt_preset_t) -> *mut wrt_engine_t {
+    let preset = match preset {
+        WRT_PRESET_QM => EnginePreset::QM,
+        WRT_PRESET_ASIL_A => EnginePreset::AsilA,
+        WRT_PRESET_ASIL_B => EnginePreset::AsilB,
+        WRT_PRESET_ASIL_C => EnginePreset::AsilC,
+        WRT_PRESET_ASIL_D => EnginePreset::AsilD,
+        _ => return core::ptr::null_mut(),
+    };
+    Box::into_raw(Box::new(wrt_engine_t { preset }))
+}
+
+/// Delete an engine
+///
+/// Stores created from the engine stay valid.
+///
+/// # Safety
+///
+/// `engine` must be null or an engine returned by this API that has not
+/// been deleted.
+#[no_mangle]
+pub unsafe extern "C" fn wrt_engine_delete(engine: *mut wrt_engine_t) {
+    // SAFETY: forwarded from the caller
+    unsafe { ffi::delete(engine) }
+}
+
+/// Source of the identifiers tying handles to their store
+static NEXT_STORE_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Owner of the modules and instances created in it
+pub struct wrt_store_t {
+    id:     u64,
+    engine: Box<CapabilityAwareEngine>,
+}
+
+impl wrt_store_t {
+    /// The underlying engine, after checking a handle created with `id`
+    /// belongs to this store
+    pub(crate) fn engine(&self, id: u64) -> Result<&CapabilityAwareEngine> {
+        self.check(id)?;
+        Ok(&self.engine)
+    }
+
+    /// The underlying engine, mutably, after checking a handle created with
+    /// `id` belongs to this store
+    pub(crate) fn engine_mut(&mut self, id: u64) -> Result<&mut CapabilityAwareEngine> {
+        self.check(id)?;
+        Ok(&mut self.engine)
+    }
+
+    /// Identifier recorded in the handles created in this store
+    pub(crate) fn id(&self) -> u64 {
+        self.id
+    }
+
+    fn check(&self, id: u64) -> Result<()> {
+        if id == self.id {
+            Ok(())
+        } else {
+            Err(Error::runtime_invalid_argument(
+                "Handle belongs to a different store",
+            ))
+        }
+    }
+}
+
+/// Create a store with the configuration of `engine`
+///
+/// On success `*out` receives a store that must be deleted with
+/// `wrt_store_delete`.
+///
+/// # Safety
+///
+/// `engine` must be null or a live engine, and `out` null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn wrt_store_new(
+    engine: *const wrt_engine_t,
+    out: *mut *mut wrt_store_t,
+) -> *mut wrt_error_t {
+    into_raw((|| {
+        // SAFETY: forwarded from the caller
+        let engine = unsafe { ffi::borrow(engine) }?;
+        MemoryInitializer::ensure_initialized()?;
+        let store = wrt_store_t {
+            id:     NEXT_STORE_ID.fetch_add(1, Ordering::Relaxed),
+            engine: Box::new(CapabilityAwareEngine::with_preset(engine.preset)?),
+        };
+        // SAFETY: forwarded from the caller
+        unsafe { ffi::write_out(out, store) }
+    })())
+}
+
+/// Delete a store together with its modules and instances
+///
+/// Handles of modules, instances and functions created in the store must
+/// still be deleted, but can no longer be used.
+///
+/// # Safety
+///
+/// `store` must be null or a store returned by this API that has not been
+/// deleted.
+#[no_mangle]
+pub unsafe extern "C" fn wrt_store_delete(store: *mut wrt_store_t) {
+    // SAFETY: forwarded from the caller
+    unsafe { ffi::delete(store) }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::ptr;
+
+    use super::*;
+
+    #[test]
+    fn test_engine_presets() {
+        unsafe {
+            for preset in WRT_PRESET_QM..=WRT_PRESET_ASIL_D {
+                let engine = wrt_engine_new_with_preset(preset);
+                assert!(!engine.is_null());
+                wrt_engine_delete(engine);
+            }
+            assert!(wrt_engine_new_with_preset(5).is_null());
+        }
+    }
+
+    #[test]
+    fn test_store_lifecycle() {
+        unsafe {
+            let engine = wrt_engine_new();
+            let mut first = ptr::null_mut();
+            let mut second = ptr::null_mut();
+            assert!(wrt_store_new(engine, &mut first).is_null());
+            assert!(wrt_store_new(engine, &mut second).is_null());
+            wrt_engine_delete(engine);
+
+            // Handles are tied to the store that created them
+            let first_id = (*first).id();
+            assert!((*first).engine(first_id).is_ok());
+            assert!((*second).engine(first_id).is_err());
+
+            let error = wrt_store_new(ptr::null(), &mut second);
+            assert!(!error.is_null());
+            crate::wrt_error_delete(error);
+
+            wrt_store_delete(first);
+            wrt_store_delete(second);
+        }
+    }
+}
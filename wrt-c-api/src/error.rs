@@ -0,0 +1,102 @@
+//! Errors reported to the host
+
+use core::{
+    ffi::c_char,
+    ptr,
+};
+use std::ffi::CString;
+
+use wrt_error::{
+    Error,
+    Result,
+};
+
+use crate::ffi;
+
+/// An error returned by a fallible function
+pub struct wrt_error_t {
+    error:   Error,
+    message: CString,
+}
+
+/// Turn the result of a fallible function into the error pointer returned
+/// to the host, null on success
+pub(crate) fn into_raw(result: Result<()>) -> *mut wrt_error_t {
+    match result {
+        Ok(()) => ptr::null_mut(),
+        Err(error) => Box::into_raw(Box::new(wrt_error_t {
+            message: CString::new(error.message).unwrap_or_default(),
+            error,
+        })),
+    }
+}
+
+/// Get the message of an error
+///
+/// The string is owned by the error and valid until it is deleted. Returns
+/// null if `error` is null.
+///
+/// # Safety
+///
+/// `error` must be null or an error returned by this API that has not been
+/// deleted.
+#[no_mangle]
+pub unsafe extern "C" fn wrt_error_message(error: *const wrt_error_t) -> *const c_char {
+    // SAFETY: forwarded from the caller
+    match unsafe { ffi::borrow(error) } {
+        Ok(error) => error.message.as_ptr(),
+        Err(_) => ptr::null(),
+    }
+}
+
+/// Get the numeric code of an error, as defined by `wrt_error::codes`
+///
+/// Returns 0 if `error` is null.
+///
+/// # Safety
+///
+/// `error` must be null or an error returned by this API that has not been
+/// deleted.
+#[no_mangle]
+pub unsafe extern "C" fn wrt_error_code(error: *const wrt_error_t) -> u16 {
+    // SAFETY: forwarded from the caller
+    unsafe { ffi::borrow(error) }.map_or(0, |error| error.error.code)
+}
+
+/// Delete an error
+///
+/// # Safety
+///
+/// `error` must be null or an error returned by this API that has not been
+/// deleted.
+#[no_mangle]
+pub unsafe extern "C" fn wrt_error_delete(error: *mut wrt_error_t) {
+    // SAFETY: forwarded from the caller
+    unsafe { ffi::delete(error) }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::ffi::CStr;
+
+    use super::*;
+
+    #[test]
+    fn test_error_accessors() {
+        assert!(into_raw(Ok(())).is_null());
+
+        let error = into_raw(Err(Error::runtime_trap("Unreachable executed")));
+        unsafe {
+            let message = CStr::from_ptr(wrt_error_message(error));
+            assert_eq!(message.to_str().unwrap(), "Unreachable executed");
+            assert_eq!(
+                wrt_error_code(error),
+                Error::runtime_trap("Unreachable executed").code
+            );
+            wrt_error_delete(error);
+
+            assert!(wrt_error_message(ptr::null()).is_null());
+            assert_eq!(wrt_error_code(ptr::null()), 0);
+        }
+    }
+}
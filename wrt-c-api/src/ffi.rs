@@ -0,0 +1,145 @@
+//! Conversion of the raw pointers passed by the host
+//!
+//! Every dereference of a host pointer goes through these helpers, which
+//! turn null pointers into errors instead of undefined behavior.
+
+use core::ffi::{
+    c_char,
+    CStr,
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+
+/// Borrow the object behind a pointer passed by the host
+///
+/// # Safety
+///
+/// `ptr` must be null or point to a live `T` that is not mutated for `'a`.
+pub(crate) unsafe fn borrow<'a, T>(ptr: *const T) -> Result<&'a T> {
+    // SAFETY: the caller guarantees `ptr` is null or valid for `'a`
+    unsafe { ptr.as_ref() }.ok_or_else(|| Error::runtime_null_reference("Null handle argument"))
+}
+
+/// Mutably borrow the object behind a pointer passed by the host
+///
+/// # Safety
+///
+/// `ptr` must be null or point to a live `T` that is not otherwise accessed
+/// for `'a`.
+pub(crate) unsafe fn borrow_mut<'a, T>(ptr: *mut T) -> Result<&'a mut T> {
+    // SAFETY: the caller guarantees `ptr` is null or valid and unaliased
+    unsafe { ptr.as_mut() }.ok_or_else(|| Error::runtime_null_reference("Null handle argument"))
+}
+
+/// Borrow an array passed by the host as pointer and length
+///
+/// A null pointer is accepted for an empty array.
+///
+/// # Safety
+///
+/// Unless `len` is 0, `ptr` must point to `len` initialized values that are
+/// not mutated for `'a`.
+pub(crate) unsafe fn slice<'a, T>(ptr: *const T, len: usize) -> Result<&'a [T]> {
+    if len == 0 {
+        return Ok(&[]);
+    }
+    if ptr.is_null() {
+        return Err(Error::runtime_null_reference("Null array argument"));
+    }
+    // SAFETY: non-null and the caller guarantees `len` valid values
+    Ok(unsafe { core::slice::from_raw_parts(ptr, len) })
+}
+
+/// Mutably borrow an array passed by the host as pointer and length
+///
+/// # Safety
+///
+/// Unless `len` is 0, `ptr` must point to `len` values that are not
+/// otherwise accessed for `'a`.
+pub(crate) unsafe fn slice_mut<'a, T>(ptr: *mut T, len: usize) -> Result<&'a mut [T]> {
+    if len == 0 {
+        return Ok(&mut []);
+    }
+    if ptr.is_null() {
+        return Err(Error::runtime_null_reference("Null array argument"));
+    }
+    // SAFETY: non-null and the caller guarantees `len` unaliased values
+    Ok(unsafe { core::slice::from_raw_parts_mut(ptr, len) })
+}
+
+/// Borrow a NUL-terminated UTF-8 string passed by the host
+///
+/// # Safety
+///
+/// `ptr` must be null or point to a NUL-terminated string that is not
+/// mutated for `'a`.
+pub(crate) unsafe fn c_str<'a>(ptr: *const c_char) -> Result<&'a str> {
+    if ptr.is_null() {
+        return Err(Error::runtime_null_reference("Null string argument"));
+    }
+    // SAFETY: non-null and the caller guarantees NUL termination
+    unsafe { CStr::from_ptr(ptr) }
+        .to_str()
+        .map_err(|_| Error::runtime_invalid_argument("String argument is not UTF-8"))
+}
+
+/// Hand a new object to the host through an out parameter
+///
+/// # Safety
+///
+/// `out` must be null or valid for writing a pointer.
+pub(crate) unsafe fn write_out<T>(out: *mut *mut T, value: T) -> Result<()> {
+    if out.is_null() {
+        return Err(Error::runtime_null_reference("Null out parameter"));
+    }
+    // SAFETY: non-null and the caller guarantees it is writable
+    unsafe { out.write(Box::into_raw(Box::new(value))) };
+    Ok(())
+}
+
+/// Release an object previously handed to the host
+///
+/// # Safety
+///
+/// `ptr` must be null or a pointer returned by [`write_out`] or
+/// [`Box::into_raw`] that has not been released yet.
+pub(crate) unsafe fn delete<T>(ptr: *mut T) {
+    if !ptr.is_null() {
+        // SAFETY: the caller guarantees `ptr` came from `Box::into_raw`
+        drop(unsafe { Box::from_raw(ptr) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::ptr;
+
+    use super::*;
+
+    #[test]
+    fn test_null_pointers_are_errors() {
+        unsafe {
+            assert!(borrow::<u32>(ptr::null()).is_err());
+            assert!(borrow_mut::<u32>(ptr::null_mut()).is_err());
+            assert!(slice::<u8>(ptr::null(), 1).is_err());
+            assert_eq!(slice::<u8>(ptr::null(), 0).unwrap(), &[] as &[u8]);
+            assert!(c_str(ptr::null()).is_err());
+            assert!(write_out::<u32>(ptr::null_mut(), 1).is_err());
+            delete::<u32>(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn test_write_out_and_delete() {
+        let mut out: *mut u32 = ptr::null_mut();
+        unsafe {
+            write_out(&mut out, 7).unwrap();
+            assert_eq!(*borrow(out).unwrap(), 7);
+            delete(out);
+        }
+        assert_eq!(unsafe { c_str(b"name\0".as_ptr().cast()) }.unwrap(), "name");
+    }
+}
@@ -0,0 +1,262 @@
+//! Exported functions and values
+
+use core::ffi::c_char;
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_foundation::{
+    values::{
+        FloatBits32,
+        FloatBits64,
+        Value,
+    },
+    ValueType,
+};
+use wrt_runtime::engine::{
+    CapabilityEngine,
+    InstanceHandle,
+};
+
+use crate::{
+    engine::wrt_store_t,
+    error::{
+        into_raw,
+        wrt_error_t,
+    },
+    ffi,
+    instance::wrt_instance_t,
+};
+
+/// Kind of a value, one of the `WRT_I32`, `WRT_I64`, `WRT_F32` and
+/// `WRT_F64` constants
+pub type wrt_valkind_t = u8;
+
+/// 32-bit integer
+pub const WRT_I32: wrt_valkind_t = 0;
+/// 64-bit integer
+pub const WRT_I64: wrt_valkind_t = 1;
+/// 32-bit float
+pub const WRT_F32: wrt_valkind_t = 2;
+/// 64-bit float
+pub const WRT_F64: wrt_valkind_t = 3;
+
+/// Payload of a value, the field named by its kind
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union wrt_val_union {
+    /// Payload of `WRT_I32`
+    pub i32: i32,
+    /// Payload of `WRT_I64`
+    pub i64: i64,
+    /// Payload of `WRT_F32`
+    pub f32: f32,
+    /// Payload of `WRT_F64`
+    pub f64: f64,
+}
+
+/// A value passed to or returned from a function
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct wrt_val_t {
+    /// Kind of the value
+    pub kind: wrt_valkind_t,
+    /// Payload of the value
+    pub of:   wrt_val_union,
+}
+
+impl wrt_val_t {
+    /// Convert to a runtime value of type `expected`
+    fn to_value(self, expected: ValueType) -> Result<Value> {
+        // SAFETY: each arm reads the field the kind says was written
+        let value = unsafe {
+            match (self.kind, expected) {
+                (WRT_I32, ValueType::I32) => Value::I32(self.of.i32),
+                (WRT_I64, ValueType::I64) => Value::I64(self.of.i64),
+                (WRT_F32, ValueType::F32) => Value::F32(FloatBits32::from_float(self.of.f32)),
+                (WRT_F64, ValueType::F64) => Value::F64(FloatBits64::from_float(self.of.f64)),
+                _ => {
+                    return Err(Error::runtime_type_mismatch(
+                        "Argument kind does not match the parameter type",
+                    ))
+                },
+            }
+        };
+        Ok(value)
+    }
+
+    /// Convert from a runtime value
+    fn from_value(value: &Value) -> Result<Self> {
+        let (kind, of) = match value {
+            Value::I32(value) => (WRT_I32, wrt_val_union { i32: *value }),
+            Value::I64(value) => (WRT_I64, wrt_val_union { i64: *value }),
+            Value::F32(value) => (WRT_F32, wrt_val_union { f32: value.value() }),
+            Value::F64(value) => (WRT_F64, wrt_val_union { f64: value.value() }),
+            _ => {
+                return Err(Error::runtime_unsupported_operation(
+                    "Result type is not supported by the C API",
+                ))
+            },
+        };
+        Ok(Self { kind, of })
+    }
+}
+
+/// A function exported by an instance
+pub struct wrt_func_t {
+    store:    u64,
+    instance: InstanceHandle,
+    name:     String,
+    params:   Vec<ValueType>,
+    results:  Vec<ValueType>,
+}
+
+/// Look up the function `instance` exports as `name`
+///
+/// On success `*out` receives a function that must be deleted with
+/// `wrt_func_delete`.
+///
+/// # Safety
+///
+/// `store` and `instance` must be null or live, `name` null or a
+/// NUL-terminated string, and `out` null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn wrt_instance_export_func(
+    store: *const wrt_store_t,
+    instance: *const wrt_instance_t,
+    name: *const c_char,
+    out: *mut *mut wrt_func_t,
+) -> *mut wrt_error_t {
+    into_raw((|| {
+        // SAFETY: forwarded from the caller
+        let store = unsafe { ffi::borrow(store) }?;
+        // SAFETY: forwarded from the caller
+        let instance = unsafe { ffi::borrow(instance) }?;
+        // SAFETY: forwarded from the caller
+        let name = unsafe { ffi::c_str(name) }?;
+        let (params, results) =
+            store.engine(instance.store)?.get_export_types(instance.handle, name)?;
+        let func = wrt_func_t {
+            store: instance.store,
+            instance: instance.handle,
+            name: name.into(),
+            params,
+            results,
+        };
+        // SAFETY: forwarded from the caller
+        unsafe { ffi::write_out(out, func) }
+    })())
+}
+
+/// Number of parameters of a function, 0 if `func` is null
+///
+/// # Safety
+///
+/// `func` must be null or a live function.
+#[no_mangle]
+pub unsafe extern "C" fn wrt_func_param_arity(func: *const wrt_func_t) -> usize {
+    // SAFETY: forwarded from the caller
+    unsafe { ffi::borrow(func) }.map_or(0, |func| func.params.len())
+}
+
+/// Number of results of a function, 0 if `func` is null
+///
+/// # Safety
+///
+/// `func` must be null or a live function.
+#[no_mangle]
+pub unsafe extern "C" fn wrt_func_result_arity(func: *const wrt_func_t) -> usize {
+    // SAFETY: forwarded from the caller
+    unsafe { ffi::borrow(func) }.map_or(0, |func| func.results.len())
+}
+
+/// Call a function
+///
+/// `args` must hold exactly the parameters of the function, with matching
+/// kinds, and `results` room for exactly its results. A trap is reported as
+/// an error.
+///
+/// # Safety
+///
+/// `store` must be null or a live store not used concurrently, `func` null
+/// or a live function, `args` must point to `nargs` values and `results` to
+/// `nresults` writable values unless the count is 0.
+#[no_mangle]
+pub unsafe extern "C" fn wrt_func_call(
+    store: *mut wrt_store_t,
+    func: *const wrt_func_t,
+    args: *const wrt_val_t,
+    nargs: usize,
+    results: *mut wrt_val_t,
+    nresults: usize,
+) -> *mut wrt_error_t {
+    into_raw((|| {
+        // SAFETY: forwarded from the caller
+        let store = unsafe { ffi::borrow_mut(store) }?;
+        // SAFETY: forwarded from the caller
+        let func = unsafe { ffi::borrow(func) }?;
+        // SAFETY: forwarded from the caller
+        let args = unsafe { ffi::slice(args, nargs) }?;
+        // SAFETY: forwarded from the caller
+        let results = unsafe { ffi::slice_mut(results, nresults) }?;
+        if args.len() != func.params.len() || results.len() != func.results.len() {
+            return Err(Error::runtime_invalid_argument(
+                "Argument or result count does not match the function type",
+            ));
+        }
+
+        let args = args
+            .iter()
+            .zip(&func.params)
+            .map(|(arg, param)| arg.to_value(*param))
+            .collect::<Result<Vec<_>>>()?;
+        let values = store.engine_mut(func.store)?.execute(func.instance, &func.name, &args)?;
+        if values.len() != results.len() {
+            return Err(Error::runtime_type_mismatch(
+                "Function returned an unexpected number of results",
+            ));
+        }
+        for (result, value) in results.iter_mut().zip(&values) {
+            *result = wrt_val_t::from_value(value)?;
+        }
+        Ok(())
+    })())
+}
+
+/// Delete a function handle
+///
+/// # Safety
+///
+/// `func` must be null or a function returned by this API that has not
+/// been deleted.
+#[no_mangle]
+pub unsafe extern "C" fn wrt_func_delete(func: *mut wrt_func_t) {
+    // SAFETY: forwarded from the caller
+    unsafe { ffi::delete(func) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_conversion() {
+        let value = wrt_val_t {
+            kind: WRT_I64,
+            of:   wrt_val_union { i64: -3 },
+        };
+        assert_eq!(value.to_value(ValueType::I64).unwrap(), Value::I64(-3));
+        assert!(value.to_value(ValueType::I32).is_err());
+
+        let converted = wrt_val_t::from_value(&Value::F64(FloatBits64::from_float(0.5))).unwrap();
+        assert_eq!(converted.kind, WRT_F64);
+        assert_eq!(unsafe { converted.of.f64 }, 0.5);
+        assert_eq!(
+            converted.to_value(ValueType::F64).unwrap(),
+            Value::F64(FloatBits64::from_float(0.5))
+        );
+
+        assert!(wrt_val_t::from_value(&Value::FuncRef(None)).is_err());
+    }
+}
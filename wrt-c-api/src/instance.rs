@@ -0,0 +1,184 @@
+//! Modules and instances
+
+use wrt_decoder::decoder::decode_module;
+use wrt_runtime::engine::{
+    CapabilityEngine,
+    InstanceHandle,
+    ModuleHandle,
+};
+
+use crate::{
+    engine::wrt_store_t,
+    error::{
+        into_raw,
+        wrt_error_t,
+    },
+    ffi,
+};
+
+/// A module loaded into a store
+pub struct wrt_module_t {
+    store:  u64,
+    handle: ModuleHandle,
+}
+
+/// An instance of a module
+pub struct wrt_instance_t {
+    pub(crate) store:  u64,
+    pub(crate) handle: InstanceHandle,
+}
+
+/// Check that `binary` is a well-formed module without loading it
+///
+/// # Safety
+///
+/// `binary` must point to `len` readable bytes unless `len` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn wrt_module_validate(binary: *const u8, len: usize) -> *mut wrt_error_t {
+    into_raw((|| {
+        // SAFETY: forwarded from the caller
+        let binary = unsafe { ffi::slice(binary, len) }?;
+        decode_module(binary).map(|_| ())
+    })())
+}
+
+/// Load a module binary into a store
+///
+/// The binary is copied, so it may be released once the function returns.
+/// On success `*out` receives a module that must be deleted with
+/// `wrt_module_delete`.
+///
+/// # Safety
+///
+/// `store` must be null or a live store not used concurrently, `binary`
+/// must point to `len` readable bytes unless `len` is 0, and `out` must be
+/// null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn wrt_module_new(
+    store: *mut wrt_store_t,
+    binary: *const u8,
+    len: usize,
+    out: *mut *mut wrt_module_t,
+) -> *mut wrt_error_t {
+    into_raw((|| {
+        // SAFETY: forwarded from the caller
+        let store = unsafe { ffi::borrow_mut(store) }?;
+        // SAFETY: forwarded from the caller
+        let binary = unsafe { ffi::slice(binary, len) }?;
+        let id = store.id();
+        let handle = store.engine_mut(id)?.load_module(binary)?;
+        // SAFETY: forwarded from the caller
+        unsafe { ffi::write_out(out, wrt_module_t { store: id, handle }) }
+    })())
+}
+
+/// Delete a module handle
+///
+/// Instances of the module stay valid.
+///
+/// # Safety
+///
+/// `module` must be null or a module returned by this API that has not been
+/// deleted.
+#[no_mangle]
+pub unsafe extern "C" fn wrt_module_delete(module: *mut wrt_module_t) {
+    // SAFETY: forwarded from the caller
+    unsafe { ffi::delete(module) }
+}
+
+/// Instantiate a module of `store`
+///
+/// The start function, if any, runs before the function returns. On
+/// success `*out` receives an instance that must be deleted with
+/// `wrt_instance_delete`.
+///
+/// # Safety
+///
+/// `store` must be null or a live store not used concurrently, `module`
+/// null or a live module, and `out` null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn wrt_instance_new(
+    store: *mut wrt_store_t,
+    module: *const wrt_module_t,
+    out: *mut *mut wrt_instance_t,
+) -> *mut wrt_error_t {
+    into_raw((|| {
+        // SAFETY: forwarded from the caller
+        let store = unsafe { ffi::borrow_mut(store) }?;
+        // SAFETY: forwarded from the caller
+        let module = unsafe { ffi::borrow(module) }?;
+        let handle = store.engine_mut(module.store)?.instantiate(module.handle)?;
+        let instance = wrt_instance_t {
+            store: module.store,
+            handle,
+        };
+        // SAFETY: forwarded from the caller
+        unsafe { ffi::write_out(out, instance) }
+    })())
+}
+
+/// Delete an instance handle
+///
+/// # Safety
+///
+/// `instance` must be null or an instance returned by this API that has not
+/// been deleted.
+#[no_mangle]
+pub unsafe extern "C" fn wrt_instance_delete(instance: *mut wrt_instance_t) {
+    // SAFETY: forwarded from the caller
+    unsafe { ffi::delete(instance) }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::ptr;
+
+    use super::*;
+    use crate::{
+        wrt_engine_delete,
+        wrt_engine_new,
+        wrt_error_delete,
+        wrt_store_delete,
+        wrt_store_new,
+    };
+
+    /// `(module (func (export "f")))`
+    const MODULE: &[u8] = &[
+        0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, 0x01, 0x04, 0x01, 0x60, 0x00, 0x00, 0x03,
+        0x02, 0x01, 0x00, 0x07, 0x05, 0x01, 0x01, b'f', 0x00, 0x00, 0x0A, 0x04, 0x01, 0x02, 0x00,
+        0x0B,
+    ];
+
+    #[test]
+    fn test_module_validate() {
+        unsafe {
+            assert!(wrt_module_validate(MODULE.as_ptr(), MODULE.len()).is_null());
+
+            let error = wrt_module_validate(MODULE.as_ptr(), 12);
+            assert!(!error.is_null());
+            wrt_error_delete(error);
+
+            let error = wrt_module_validate(ptr::null(), 8);
+            assert!(!error.is_null());
+            wrt_error_delete(error);
+        }
+    }
+
+    #[test]
+    fn test_malformed_module_is_rejected() {
+        unsafe {
+            let engine = wrt_engine_new();
+            let mut store = ptr::null_mut();
+            assert!(wrt_store_new(engine, &mut store).is_null());
+
+            let mut module = ptr::null_mut();
+            let error = wrt_module_new(store, b"\0asm".as_ptr(), 4, &mut module);
+            assert!(!error.is_null());
+            assert!(module.is_null());
+            wrt_error_delete(error);
+
+            wrt_store_delete(store);
+            wrt_engine_delete(engine);
+        }
+    }
+}
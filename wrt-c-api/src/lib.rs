@@ -0,0 +1,103 @@
+//! C API for embedding WRT
+//!
+//! Exposes the runtime to C and C++ hosts through opaque handles modelled on
+//! the [wasm-c-api](https://github.com/WebAssembly/wasm-c-api): an engine
+//! holds the configuration, a store owns the modules and instances created
+//! in it, and functions and memories are looked up by export name. The
+//! header `include/wrt.h` is generated from this crate with cbindgen.
+//!
+//! Conventions shared by all functions:
+//!
+//! - Fallible functions return a `wrt_error_t*`, which is null on success. A
+//!   non-null error must be released with `wrt_error_delete`.
+//! - Objects are returned through out parameters and released with the matching
+//!   `*_delete` function.
+//! - Modules, instances and functions belong to the store they were created in
+//!   and are rejected by any other store.
+//!
+//! ```c
+//! wrt_engine_t *engine = wrt_engine_new();
+//! wrt_store_t *store;
+//! wrt_module_t *module;
+//! wrt_instance_t *instance;
+//! wrt_func_t *add;
+//! wrt_error_t *error = wrt_store_new(engine, &store);
+//! if (!error) error = wrt_module_new(store, binary, binary_len, &module);
+//! if (!error) error = wrt_instance_new(store, module, &instance);
+//! if (!error) error = wrt_instance_export_func(store, instance, "add", &add);
+//! if (!error) {
+//!     wrt_val_t args[2] = {{WRT_I32, {.i32 = 1}}, {WRT_I32, {.i32 = 2}}};
+//!     wrt_val_t result;
+//!     error = wrt_func_call(store, add, args, 2, &result, 1);
+//! }
+//! if (error) {
+//!     fprintf(stderr, "%s\n", wrt_error_message(error));
+//!     wrt_error_delete(error);
+//! }
+//! ```
+
+#![allow(non_camel_case_types)]
+#![deny(unsafe_op_in_unsafe_fn)]
+#![warn(missing_docs)]
+
+mod engine;
+mod error;
+mod ffi;
+mod func;
+mod instance;
+mod memory;
+
+pub use engine::{
+    wrt_engine_delete,
+    wrt_engine_new,
+    wrt_engine_new_with_preset,
+    wrt_engine_t,
+    wrt_preset_t,
+    wrt_store_delete,
+    wrt_store_new,
+    wrt_store_t,
+    WRT_PRESET_ASIL_A,
+    WRT_PRESET_ASIL_B,
+    WRT_PRESET_ASIL_C,
+    WRT_PRESET_ASIL_D,
+    WRT_PRESET_QM,
+};
+pub use error::{
+    wrt_error_code,
+    wrt_error_delete,
+    wrt_error_message,
+    wrt_error_t,
+};
+pub use func::{
+    wrt_func_call,
+    wrt_func_delete,
+    wrt_func_param_arity,
+    wrt_func_result_arity,
+    wrt_func_t,
+    wrt_instance_export_func,
+    wrt_val_t,
+    wrt_val_union,
+    wrt_valkind_t,
+    WRT_F32,
+    WRT_F64,
+    WRT_I32,
+    WRT_I64,
+};
+pub use instance::{
+    wrt_instance_delete,
+    wrt_instance_new,
+    wrt_instance_t,
+    wrt_module_delete,
+    wrt_module_new,
+    wrt_module_t,
+    wrt_module_validate,
+};
+pub use memory::{
+    wrt_instance_export_memory,
+    wrt_memory_data_size,
+    wrt_memory_delete,
+    wrt_memory_read,
+    wrt_memory_size,
+    wrt_memory_t,
+    wrt_memory_write,
+};
@@ -0,0 +1,142 @@
+//! Exported memories
+
+use core::ffi::c_char;
+
+use wrt_error::Error;
+use wrt_runtime::{
+    externs::Extern,
+    module::MemoryWrapper,
+};
+
+use crate::{
+    engine::wrt_store_t,
+    error::{
+        into_raw,
+        wrt_error_t,
+    },
+    ffi,
+    instance::wrt_instance_t,
+};
+
+/// A memory exported by an instance
+///
+/// The memory stays accessible after the store is deleted.
+pub struct wrt_memory_t {
+    memory: MemoryWrapper,
+}
+
+/// Look up the memory `instance` exports as `name`
+///
+/// On success `*out` receives a memory that must be deleted with
+/// `wrt_memory_delete`.
+///
+/// # Safety
+///
+/// `store` and `instance` must be null or live, `name` null or a
+/// NUL-terminated string, and `out` null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn wrt_instance_export_memory(
+    store: *const wrt_store_t,
+    instance: *const wrt_instance_t,
+    name: *const c_char,
+    out: *mut *mut wrt_memory_t,
+) -> *mut wrt_error_t {
+    into_raw((|| {
+        // SAFETY: forwarded from the caller
+        let store = unsafe { ffi::borrow(store) }?;
+        // SAFETY: forwarded from the caller
+        let instance = unsafe { ffi::borrow(instance) }?;
+        // SAFETY: forwarded from the caller
+        let name = unsafe { ffi::c_str(name) }?;
+        let memory = match store.engine(instance.store)?.get_export(instance.handle, name)? {
+            Some(Extern::Memory(export)) => export.memory,
+            _ => {
+                return Err(Error::memory_not_found(
+                    "Instance has no memory export of this name",
+                ))
+            },
+        };
+        // SAFETY: forwarded from the caller
+        unsafe { ffi::write_out(out, wrt_memory_t { memory }) }
+    })())
+}
+
+/// Size of a memory in 64 KiB pages, 0 if `memory` is null
+///
+/// # Safety
+///
+/// `memory` must be null or a live memory.
+#[no_mangle]
+pub unsafe extern "C" fn wrt_memory_size(memory: *const wrt_memory_t) -> u32 {
+    // SAFETY: forwarded from the caller
+    unsafe { ffi::borrow(memory) }.map_or(0, |memory| memory.memory.size())
+}
+
+/// Size of a memory in bytes, 0 if `memory` is null
+///
+/// # Safety
+///
+/// `memory` must be null or a live memory.
+#[no_mangle]
+pub unsafe extern "C" fn wrt_memory_data_size(memory: *const wrt_memory_t) -> usize {
+    // SAFETY: forwarded from the caller
+    unsafe { ffi::borrow(memory) }.map_or(0, |memory| memory.memory.size_in_bytes())
+}
+
+/// Copy `len` bytes starting at `offset` out of a memory
+///
+/// # Safety
+///
+/// `memory` must be null or a live memory and `buffer` must point to `len`
+/// writable bytes unless `len` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn wrt_memory_read(
+    memory: *const wrt_memory_t,
+    offset: u32,
+    buffer: *mut u8,
+    len: usize,
+) -> *mut wrt_error_t {
+    into_raw((|| {
+        // SAFETY: forwarded from the caller
+        let memory = unsafe { ffi::borrow(memory) }?;
+        // SAFETY: forwarded from the caller
+        let buffer = unsafe { ffi::slice_mut(buffer, len) }?;
+        memory.memory.read(offset, buffer)
+    })())
+}
+
+/// Copy `len` bytes into a memory starting at `offset`
+///
+/// Writes go to the memory in place, so instances sharing it see them.
+///
+/// # Safety
+///
+/// `memory` must be null or a live memory and `data` must point to `len`
+/// readable bytes unless `len` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn wrt_memory_write(
+    memory: *mut wrt_memory_t,
+    offset: u32,
+    data: *const u8,
+    len: usize,
+) -> *mut wrt_error_t {
+    into_raw((|| {
+        // SAFETY: forwarded from the caller
+        let memory = unsafe { ffi::borrow_mut(memory) }?;
+        // SAFETY: forwarded from the caller
+        let data = unsafe { ffi::slice(data, len) }?;
+        memory.memory.view_mut(|mut view| view.write_bytes(offset, data))
+    })())
+}
+
+/// Delete a memory handle
+///
+/// # Safety
+///
+/// `memory` must be null or a memory returned by this API that has not been
+/// deleted.
+#[no_mangle]
+pub unsafe extern "C" fn wrt_memory_delete(memory: *mut wrt_memory_t) {
+    // SAFETY: forwarded from the caller
+    unsafe { ffi::delete(memory) }
+}
@@ -0,0 +1,380 @@
+//! Command line parsing
+
+use core::fmt;
+use std::{
+    io,
+    path::PathBuf,
+};
+
+use wrt_runtime::engine::EnginePreset;
+
+/// Usage text printed by `wrt help`
+pub(crate) const USAGE: &str = "\
+WebAssembly runtime command line
+
+Usage:
+  wrt run [OPTIONS] <MODULE> [ARGS...]
+  wrt validate <MODULE>
+  wrt inspect [--size] [--heap <FUNC>] <MODULE> [ARGS...]
+  wrt repl [OPTIONS] <MODULE>
+  wrt component run [OPTIONS] <COMPONENT> [ARGS...]
+  wrt help | --help
+  wrt version | --version
+
+Run options:
+  --invoke <FUNC>    Exported function to call (default: _start)
+  --fuel <AMOUNT>    Fail the run if it consumes more fuel than this
+  --preset <NAME>    Engine preset: qm, asil-a, asil-b, asil-c, asil-d (default: qm)
+  --wasi             Enable WASI
+  --dir <PATH>       Grant read access to a directory (implies --wasi)
+  --env <NAME>       Expose an environment variable (implies --wasi)
+
+The options of `repl` are the run options other than --invoke; type `help`
+at its prompt for the commands.
+
+Inspect options:
+  --size             Print section and function body sizes, import and export
+                     counts and the estimated instantiation memory
+  --heap <FUNC>      Call an exported function with the guest heap profiled
+                     and print the allocations it made through the exported
+                     malloc, calloc, realloc, free or cabi_realloc
+
+Arguments after the module are passed to the invoked function and are
+parsed according to its parameter types.";
+
+/// A parsed command line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Command {
+    /// Run a core module
+    Run(RunOptions),
+    /// Decode a module and check it is accepted by the engine
+    Validate(PathBuf),
+    /// Print the sections, imports and exports of a module
+    Inspect(InspectOptions),
+    /// Instantiate a module and take commands interactively
+    Repl(RunOptions),
+    /// Run a component
+    ComponentRun(RunOptions),
+    /// Print the usage text
+    Help,
+    /// Print the version
+    Version,
+}
+
+/// Options of `wrt run` and `wrt component run`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RunOptions {
+    /// Module or component to run
+    pub path:   PathBuf,
+    /// Exported function to call, `_start` if not given
+    pub invoke: Option<String>,
+    /// Arguments of the function, as written on the command line
+    pub args:   Vec<String>,
+    /// Largest amount of fuel the run may consume
+    pub fuel:   Option<u64>,
+    /// Preset the engine is created with
+    pub preset: EnginePreset,
+    /// WASI access granted to the module
+    pub wasi:   WasiOptions,
+}
+
+/// Options of `wrt inspect`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct InspectOptions {
+    /// Module to inspect
+    pub path: PathBuf,
+    /// Whether the size report of the module is printed
+    pub size: bool,
+    /// Exported function to call with the guest heap profiled
+    pub heap: Option<String>,
+    /// Arguments of the function, as written on the command line
+    pub args: Vec<String>,
+}
+
+/// WASI access granted to a module
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct WasiOptions {
+    /// Whether WASI is enabled at all
+    pub enabled: bool,
+    /// Directories the module may read
+    pub dirs:    Vec<String>,
+    /// Environment variables the module may read
+    pub env:     Vec<String>,
+}
+
+/// Error of a command
+#[derive(Debug)]
+pub(crate) enum CliError {
+    /// The command line is invalid
+    Usage(String),
+    /// An input file could not be read
+    Io {
+        /// Path of the file
+        path:   PathBuf,
+        /// Error reported by the operating system
+        source: io::Error,
+    },
+    /// The run consumed more fuel than allowed
+    FuelExhausted {
+        /// Fuel the run consumed
+        consumed: u64,
+        /// Fuel the run was allowed to consume
+        limit:    u64,
+    },
+    /// The runtime rejected the module or the call failed
+    Runtime(wrt_error::Error),
+}
+
+impl CliError {
+    /// Process exit code for the error
+    pub(crate) fn exit_code(&self) -> u8 {
+        match self {
+            CliError::Usage(_) => 2,
+            _ => 1,
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Usage(message) => write!(f, "{message}\n\nRun `wrt help` for usage"),
+            CliError::Io { path, source } => write!(f, "cannot read {}: {source}", path.display()),
+            CliError::FuelExhausted { consumed, limit } => {
+                write!(f, "fuel exhausted: consumed {consumed} of {limit}")
+            },
+            CliError::Runtime(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl From<wrt_error::Error> for CliError {
+    fn from(error: wrt_error::Error) -> Self {
+        CliError::Runtime(error)
+    }
+}
+
+fn usage(message: impl Into<String>) -> CliError {
+    CliError::Usage(message.into())
+}
+
+/// Parse the command line arguments, without the program name
+pub(crate) fn parse(args: impl IntoIterator<Item = String>) -> Result<Command, CliError> {
+    let mut args = args.into_iter();
+    let Some(command) = args.next() else {
+        return Ok(Command::Help);
+    };
+    match command.as_str() {
+        "run" => parse_run(args).map(Command::Run),
+        "validate" => single_path("validate", args).map(Command::Validate),
+        "inspect" => parse_inspect(args).map(Command::Inspect),
+        "repl" => parse_repl(args).map(Command::Repl),
+        "component" => match args.next().as_deref() {
+            Some("run") => parse_run(args).map(Command::ComponentRun),
+            Some(other) => Err(usage(format!("unknown component command `{other}`"))),
+            None => Err(usage("missing component command")),
+        },
+        "help" | "--help" | "-h" => Ok(Command::Help),
+        "version" | "--version" | "-V" => Ok(Command::Version),
+        other => Err(usage(format!("unknown command `{other}`"))),
+    }
+}
+
+fn single_path(command: &str, mut args: impl Iterator<Item = String>) -> Result<PathBuf, CliError> {
+    let path = args.next().ok_or_else(|| usage(format!("`{command}` needs a module path")))?;
+    if let Some(extra) = args.next() {
+        return Err(usage(format!("unexpected argument `{extra}`")));
+    }
+    Ok(PathBuf::from(path))
+}
+
+/// Parse the options of `run`, which come before the module path; all
+/// arguments after it belong to the invoked function
+fn parse_run(mut args: impl Iterator<Item = String>) -> Result<RunOptions, CliError> {
+    let mut invoke = None;
+    let mut fuel = None;
+    let mut preset = EnginePreset::QM;
+    let mut wasi = WasiOptions::default();
+
+    let path = loop {
+        let arg = args.next().ok_or_else(|| usage("missing module path"))?;
+        let mut value =
+            |option: &str| args.next().ok_or_else(|| usage(format!("`{option}` needs a value")));
+        match arg.as_str() {
+            "--invoke" => invoke = Some(value("--invoke")?),
+            "--fuel" => {
+                let amount = value("--fuel")?;
+                fuel = Some(
+                    amount.parse().map_err(|_| usage(format!("invalid fuel amount `{amount}`")))?,
+                );
+            },
+            "--preset" => preset = parse_preset(&value("--preset")?)?,
+            "--wasi" => wasi.enabled = true,
+            "--dir" => {
+                wasi.dirs.push(value("--dir")?);
+                wasi.enabled = true;
+            },
+            "--env" => {
+                wasi.env.push(value("--env")?);
+                wasi.enabled = true;
+            },
+            "--" => break args.next().ok_or_else(|| usage("missing module path"))?,
+            option if option.starts_with("--") => {
+                return Err(usage(format!("unknown option `{option}`")));
+            },
+            _ => break arg,
+        }
+    };
+
+    Ok(RunOptions {
+        path: PathBuf::from(path),
+        invoke,
+        args: args.collect(),
+        fuel,
+        preset,
+        wasi,
+    })
+}
+
+/// Parse the options of `repl`, the run options without a function to
+/// invoke
+fn parse_repl(args: impl Iterator<Item = String>) -> Result<RunOptions, CliError> {
+    let options = parse_run(args)?;
+    if options.invoke.is_some() {
+        return Err(usage("`repl` takes no `--invoke`, call functions at its prompt"));
+    }
+    if let Some(extra) = options.args.first() {
+        return Err(usage(format!("unexpected argument `{extra}`")));
+    }
+    Ok(options)
+}
+
+/// Parse the options of `inspect`; arguments after the module path belong to
+/// the function called with `--heap`
+fn parse_inspect(mut args: impl Iterator<Item = String>) -> Result<InspectOptions, CliError> {
+    let mut size = false;
+    let mut heap = None;
+    let path = loop {
+        let arg = args.next().ok_or_else(|| usage("`inspect` needs a module path"))?;
+        match arg.as_str() {
+            "--size" => size = true,
+            "--heap" => {
+                heap = Some(args.next().ok_or_else(|| usage("`--heap` needs a value"))?);
+            },
+            option if option.starts_with("--") => {
+                return Err(usage(format!("unknown option `{option}`")));
+            },
+            _ => break arg,
+        }
+    };
+    let args: Vec<String> = args.collect();
+    if let (None, Some(extra)) = (&heap, args.first()) {
+        return Err(usage(format!("unexpected argument `{extra}`")));
+    }
+
+    Ok(InspectOptions {
+        path: PathBuf::from(path),
+        size,
+        heap,
+        args,
+    })
+}
+
+fn parse_preset(name: &str) -> Result<EnginePreset, CliError> {
+    Ok(match name.to_ascii_lowercase().as_str() {
+        "qm" => EnginePreset::QM,
+        "asil-a" => EnginePreset::AsilA,
+        "asil-b" => EnginePreset::AsilB,
+        "asil-c" => EnginePreset::AsilC,
+        "asil-d" => EnginePreset::AsilD,
+        _ => return Err(usage(format!("unknown preset `{name}`"))),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_line(line: &str) -> Result<Command, CliError> {
+        parse(line.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn test_parse_run() {
+        let Command::Run(options) =
+            parse_line("run --invoke add --fuel 1000 --preset asil-b --dir /data m.wasm 1 -2")
+                .unwrap()
+        else {
+            panic!("expected a run command");
+        };
+        assert_eq!(options.path, PathBuf::from("m.wasm"));
+        assert_eq!(options.invoke.as_deref(), Some("add"));
+        assert_eq!(options.args, ["1", "-2"]);
+        assert_eq!(options.fuel, Some(1000));
+        assert_eq!(options.preset, EnginePreset::AsilB);
+        assert!(options.wasi.enabled);
+        assert_eq!(options.wasi.dirs, ["/data"]);
+
+        // Options after the module belong to the function
+        let Command::Run(options) = parse_line("run m.wasm --wasi").unwrap() else {
+            panic!("expected a run command");
+        };
+        assert!(!options.wasi.enabled);
+        assert_eq!(options.args, ["--wasi"]);
+    }
+
+    #[test]
+    fn test_parse_other_commands() {
+        assert_eq!(
+            parse_line("inspect m.wasm").unwrap(),
+            Command::Inspect(InspectOptions {
+                path: PathBuf::from("m.wasm"),
+                size: false,
+                heap: None,
+                args: Vec::new(),
+            })
+        );
+        assert_eq!(
+            parse_line("inspect --size --heap greet m.wasm 7").unwrap(),
+            Command::Inspect(InspectOptions {
+                path: PathBuf::from("m.wasm"),
+                size: true,
+                heap: Some("greet".into()),
+                args: vec!["7".into()],
+            })
+        );
+        assert_eq!(
+            parse_line("validate m.wasm").unwrap(),
+            Command::Validate(PathBuf::from("m.wasm"))
+        );
+        let Command::Repl(options) = parse_line("repl --fuel 10 m.wasm").unwrap() else {
+            panic!("expected a repl command");
+        };
+        assert_eq!(options.fuel, Some(10));
+        assert!(matches!(
+            parse_line("component run c.wasm").unwrap(),
+            Command::ComponentRun(_)
+        ));
+        assert_eq!(parse_line("").unwrap(), Command::Help);
+        assert_eq!(parse_line("--version").unwrap(), Command::Version);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for line in [
+            "frobnicate",
+            "run",
+            "run --fuel lots m.wasm",
+            "run --preset asil-e m.wasm",
+            "run --verbose m.wasm",
+            "inspect a.wasm b.wasm",
+            "inspect --heap",
+            "repl m.wasm 1",
+            "repl --invoke add m.wasm",
+            "component link c.wasm",
+        ] {
+            let error = parse_line(line).unwrap_err();
+            assert_eq!(error.exit_code(), 2, "{line}");
+        }
+    }
+}
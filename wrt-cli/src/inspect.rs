@@ -0,0 +1,321 @@
+//! `wrt inspect` and `wrt validate`
+
+use core::fmt;
+
+use wrt_decoder::{
+    custom_section_handler::extract_custom_section,
+    decoder::decode_module,
+    parallel_decoder::SectionIndex,
+};
+use wrt_error::Result;
+use wrt_format::{
+    binary::CUSTOM_SECTION_ID,
+    module::{
+        ExportKind,
+        ImportDesc,
+        Module as WrtModule,
+    },
+};
+use wrt_foundation::{
+    CleanCoreFuncType,
+    ValueType,
+};
+use wrt_runtime::{
+    engine::{
+        CapabilityAwareEngine,
+        CapabilityEngine,
+        EnginePreset,
+    },
+    heap_profile::ALLOCATOR_EXPORTS,
+    module::Module,
+    module_analysis::SizeReport,
+};
+
+use crate::{
+    cli::CliError,
+    run::parse_args,
+};
+
+/// Call the export `func` of `binary` with `args` and the guest heap
+/// profiled, returning the rendered profile
+///
+/// Only allocations made through the allocator functions the module exports
+/// are seen, so the profile of a module without them notes that it is empty
+/// for that reason.
+pub(crate) fn profile_heap(
+    binary: &[u8],
+    func: &str,
+    args: &[String],
+) -> core::result::Result<String, CliError> {
+    let mut engine = CapabilityAwareEngine::with_preset(EnginePreset::QM)?;
+    engine.set_heap_profiling(true);
+    let module = engine.load_module(binary)?;
+    let instance = engine.instantiate(module)?;
+
+    let (params, _) = engine.get_export_types(instance, func)?;
+    let args = parse_args(func, args, &params)?;
+    engine.execute(instance, func, &args)?;
+    let mut profile = engine.heap_profile(instance).map(ToString::to_string).unwrap_or_default();
+    if !ALLOCATOR_EXPORTS
+        .iter()
+        .any(|name| engine.get_export_types(instance, name).is_ok())
+    {
+        profile.push_str("no allocator exports: the guest's allocations are not visible\n");
+    }
+    Ok(profile)
+}
+
+/// Load `binary` and report its sizes
+pub(crate) fn analyze(binary: &[u8]) -> Result<SizeReport> {
+    Module::new()?.load_from_binary(binary)?.analyze()
+}
+
+/// Check that the engine accepts `binary` with the given preset
+pub(crate) fn validate(binary: &[u8], preset: EnginePreset) -> Result<()> {
+    let mut engine = CapabilityAwareEngine::with_preset(preset)?;
+    engine.load_module(binary).map(|_| ())
+}
+
+/// A section of an inspected module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SectionSummary {
+    /// Section identifier
+    pub id:   u8,
+    /// Name of a custom section
+    pub name: Option<String>,
+    /// Size of the section payload in bytes
+    pub size: usize,
+}
+
+/// An import or export of an inspected module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ItemSummary {
+    /// Name, `module.name` for imports
+    pub name: String,
+    /// Kind and type of the item, such as `func (i32) -> (i32)`
+    pub desc: String,
+}
+
+/// Layout and interface of a module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ModuleSummary {
+    /// Size of the binary in bytes
+    pub size:     usize,
+    /// Sections in binary order
+    pub sections: Vec<SectionSummary>,
+    /// Imports in declaration order
+    pub imports:  Vec<ItemSummary>,
+    /// Exports in declaration order
+    pub exports:  Vec<ItemSummary>,
+}
+
+impl ModuleSummary {
+    /// Summarize `binary`
+    pub(crate) fn new(binary: &[u8]) -> Result<Self> {
+        let mut sections = Vec::new();
+        for entry in SectionIndex::scan(binary)?.entries() {
+            let name = if entry.id == CUSTOM_SECTION_ID {
+                Some(extract_custom_section(&binary[entry.range.clone()])?.0)
+            } else {
+                None
+            };
+            sections.push(SectionSummary {
+                id: entry.id,
+                name,
+                size: entry.range.len(),
+            });
+        }
+
+        let module = decode_module(binary)?;
+        let imports = module
+            .imports
+            .iter()
+            .map(|import| ItemSummary {
+                name: format!("{}.{}", import.module, import.name),
+                desc: match &import.desc {
+                    ImportDesc::Function(type_idx) => {
+                        function_desc(module.types.get(*type_idx as usize))
+                    },
+                    ImportDesc::Table(_) => "table".into(),
+                    ImportDesc::Memory(_) => "memory".into(),
+                    ImportDesc::Global(_) => "global".into(),
+                    ImportDesc::Tag(_) => "tag".into(),
+                },
+            })
+            .collect();
+        let exports = module
+            .exports
+            .iter()
+            .map(|export| ItemSummary {
+                name: export.name.clone(),
+                desc: match export.kind {
+                    ExportKind::Function => function_desc(function_type(&module, export.index)),
+                    ExportKind::Table => "table".into(),
+                    ExportKind::Memory => "memory".into(),
+                    ExportKind::Global => "global".into(),
+                    ExportKind::Tag => "tag".into(),
+                },
+            })
+            .collect();
+
+        Ok(Self {
+            size: binary.len(),
+            sections,
+            imports,
+            exports,
+        })
+    }
+}
+
+impl fmt::Display for ModuleSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "size: {} bytes", self.size)?;
+        writeln!(f, "sections:")?;
+        for section in &self.sections {
+            write!(
+                f,
+                "  {:<10} {:>8} bytes",
+                section_name(section.id),
+                section.size
+            )?;
+            match &section.name {
+                Some(name) => writeln!(f, "  \"{name}\"")?,
+                None => writeln!(f)?,
+            }
+        }
+        for (title, items) in [("imports", &self.imports), ("exports", &self.exports)] {
+            writeln!(f, "{title}:")?;
+            if items.is_empty() {
+                writeln!(f, "  (none)")?;
+            }
+            for item in items {
+                writeln!(f, "  {}: {}", item.name, item.desc)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Type of the function at `func_idx` of the function index space, which
+/// starts with the imported functions
+fn function_type(module: &WrtModule, func_idx: u32) -> Option<&CleanCoreFuncType> {
+    let imported: Vec<u32> = module
+        .imports
+        .iter()
+        .filter_map(|import| match import.desc {
+            ImportDesc::Function(type_idx) => Some(type_idx),
+            _ => None,
+        })
+        .collect();
+    let func_idx = func_idx as usize;
+    let type_idx = match imported.get(func_idx) {
+        Some(type_idx) => *type_idx,
+        None => module.functions.get(func_idx - imported.len())?.type_idx,
+    };
+    module.types.get(type_idx as usize)
+}
+
+fn function_desc(func_type: Option<&CleanCoreFuncType>) -> String {
+    let Some(func_type) = func_type else {
+        return "func (unknown type)".into();
+    };
+    let list = |types: &[ValueType]| types.iter().map(type_name).collect::<Vec<_>>().join(", ");
+    format!(
+        "func ({}) -> ({})",
+        list(&func_type.params),
+        list(&func_type.results)
+    )
+}
+
+/// Name of a value type as written in the text format
+pub(crate) fn type_name(value_type: &ValueType) -> String {
+    match value_type {
+        ValueType::I32 => "i32".into(),
+        ValueType::I64 => "i64".into(),
+        ValueType::F32 => "f32".into(),
+        ValueType::F64 => "f64".into(),
+        ValueType::V128 => "v128".into(),
+        ValueType::FuncRef => "funcref".into(),
+        ValueType::ExternRef => "externref".into(),
+        other => format!("{other:?}").to_lowercase(),
+    }
+}
+
+fn section_name(id: u8) -> &'static str {
+    match id {
+        0 => "custom",
+        1 => "type",
+        2 => "import",
+        3 => "function",
+        4 => "table",
+        5 => "memory",
+        6 => "global",
+        7 => "export",
+        8 => "start",
+        9 => "element",
+        10 => "code",
+        11 => "data",
+        12 => "datacount",
+        13 => "tag",
+        _ => "unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `(module (import "env" "log" (func (param i32)))
+    ///          (func (param i32 i32) (result i32) local.get 0)
+    ///          (export "first" (func 1)))` with a custom section `meta`
+    const MODULE: &[u8] = &[
+        0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, // header
+        0x01, 0x0B, 0x02, // type section, 2 types
+        0x60, 0x01, 0x7F, 0x00, // (i32) -> ()
+        0x60, 0x02, 0x7F, 0x7F, 0x01, 0x7F, // (i32, i32) -> (i32)
+        0x02, 0x0B, 0x01, // import section, 1 import
+        0x03, b'e', b'n', b'v', 0x03, b'l', b'o', b'g', 0x00, 0x00, // env.log, type 0
+        0x03, 0x02, 0x01, 0x01, // function section, type 1
+        0x07, 0x09, 0x01, // export section, 1 export
+        0x05, b'f', b'i', b'r', b's', b't', 0x00, 0x01, // "first", func 1
+        0x0A, 0x06, 0x01, 0x04, 0x00, 0x20, 0x00, 0x0B, // code section
+        0x00, 0x07, 0x04, b'm', b'e', b't', b'a', 0x01, 0x02, // custom section "meta"
+    ];
+
+    #[test]
+    fn test_module_summary() {
+        let summary = ModuleSummary::new(MODULE).unwrap();
+        assert_eq!(summary.size, MODULE.len());
+
+        let ids: Vec<u8> = summary.sections.iter().map(|section| section.id).collect();
+        assert_eq!(ids, [1, 2, 3, 7, 10, 0]);
+        assert_eq!(summary.sections[5].name.as_deref(), Some("meta"));
+        assert_eq!(summary.sections[5].size, 7);
+
+        assert_eq!(
+            summary.imports,
+            [ItemSummary {
+                name: "env.log".into(),
+                desc: "func (i32) -> ()".into(),
+            }]
+        );
+        assert_eq!(
+            summary.exports,
+            [ItemSummary {
+                name: "first".into(),
+                desc: "func (i32, i32) -> (i32)".into(),
+            }]
+        );
+
+        let text = summary.to_string();
+        assert!(text.contains("custom"));
+        assert!(text.contains("\"meta\""));
+        assert!(text.contains("first: func (i32, i32) -> (i32)"));
+    }
+
+    #[test]
+    fn test_malformed_module() {
+        assert!(ModuleSummary::new(&MODULE[..20]).is_err());
+        assert!(ModuleSummary::new(b"not wasm").is_err());
+    }
+}
@@ -0,0 +1,122 @@
+//! WRT command line runner
+//!
+//! Runs, validates and inspects WebAssembly modules without writing an
+//! embedder:
+//!
+//! ```text
+//! wrt run --invoke add module.wasm 1 2
+//! wrt validate module.wasm
+//! wrt inspect module.wasm
+//! wrt inspect --size module.wasm
+//! wrt inspect --heap greet module.wasm 7
+//! wrt repl module.wasm
+//! ```
+//!
+//! Run `wrt help` for all options.
+
+#![forbid(unsafe_code)] // Rule 2
+#![warn(missing_docs)]
+
+extern crate alloc;
+
+mod cli;
+mod inspect;
+mod repl;
+mod run;
+
+use std::{
+    fs,
+    panic,
+    path::Path,
+    process::ExitCode,
+    thread,
+};
+
+use cli::{
+    CliError,
+    Command,
+};
+use inspect::ModuleSummary;
+use wrt_foundation::memory_init::MemoryInitializer;
+use wrt_runtime::engine::EnginePreset;
+
+/// Stack size of the thread commands run on
+///
+/// The engine keeps its bounded collections inline, which takes far more
+/// stack than the main thread has.
+const STACK_SIZE: usize = 1 << 30;
+
+fn main() -> ExitCode {
+    let worker = thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(|| cli::parse(std::env::args().skip(1)).and_then(execute));
+    let result = match worker {
+        Ok(worker) => worker.join().unwrap_or_else(|payload| panic::resume_unwind(payload)),
+        Err(error) => {
+            eprintln!("error: cannot start the runner thread: {error}");
+            return ExitCode::FAILURE;
+        },
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::from(error.exit_code())
+        },
+    }
+}
+
+fn execute(command: Command) -> Result<(), CliError> {
+    MemoryInitializer::ensure_initialized()?;
+    match command {
+        Command::Run(options) => run::run(&options, &read_module(&options.path)?),
+        Command::ComponentRun(options) => {
+            run::run_component(&options, &read_module(&options.path)?)
+        },
+        Command::Repl(options) => repl::run(&options, &read_module(&options.path)?),
+        Command::Validate(path) => {
+            inspect::validate(&read_module(&path)?, EnginePreset::QM)?;
+            println!("{}: valid", path.display());
+            Ok(())
+        },
+        Command::Inspect(options) => {
+            let binary = read_module(&options.path)?;
+            print!("{}", ModuleSummary::new(&binary)?);
+            if options.size {
+                println!("size report:");
+                print!("{}", inspect::analyze(&binary)?);
+            }
+            if let Some(func) = &options.heap {
+                println!("heap profile of {func}:");
+                print!("{}", inspect::profile_heap(&binary, func, &options.args)?);
+            }
+            Ok(())
+        },
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            Ok(())
+        },
+        Command::Version => {
+            println!("wrt {}", env!("CARGO_PKG_VERSION"));
+            Ok(())
+        },
+    }
+}
+
+/// Read a module binary, translating `.wat` files with the `wat` feature
+fn read_module(path: &Path) -> Result<Vec<u8>, CliError> {
+    let bytes = fs::read(path).map_err(|source| CliError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    #[cfg(feature = "wat")]
+    if path.extension().is_some_and(|extension| extension == "wat") {
+        let text = String::from_utf8(bytes)
+            .map_err(|_| CliError::Usage(format!("{} is not UTF-8 text", path.display())))?;
+        return Ok(wrt_runtime::text_format::wat_to_binary(&text)?);
+    }
+
+    Ok(bytes)
+}
@@ -0,0 +1,400 @@
+//! `wrt repl`: interactive mode
+//!
+//! Instantiates a module once and reads commands from standard input, so a
+//! guest can be exercised without writing an embedder: exports are listed
+//! and called with literal arguments, memory is dumped, and the fuel limit
+//! is changed between calls.
+//!
+//! With `trace on`, the engine traces the calls, and the spans it finished
+//! are printed after each command. Lines starting with `#` are comments, so
+//! a script of commands can be piped in.
+//!
+//! Single-stepping a call is not supported: the engine pauses a call only
+//! when it runs out of fuel, at the next yield point, not after every
+//! operation. `step` is rejected with a note saying so.
+
+use alloc::sync::Arc;
+use std::{
+    io::{
+        self,
+        BufRead,
+        Write,
+    },
+    sync::{
+        Mutex,
+        PoisonError,
+    },
+};
+
+use wrt_foundation::values::Value;
+use wrt_intercept::strategies::{
+    SpanData,
+    SpanStatus,
+    Tracer,
+};
+use wrt_runtime::engine::{
+    CapabilityAwareEngine,
+    CapabilityEngine,
+    InstanceHandle,
+};
+
+use crate::{
+    cli::{
+        CliError,
+        RunOptions,
+    },
+    inspect::{
+        ItemSummary,
+        ModuleSummary,
+    },
+    run::{
+        enable_wasi,
+        is_component,
+        parse_args,
+        result_text,
+    },
+};
+
+/// Commands understood by the interactive mode, printed by `help`
+pub(crate) const HELP: &str = "\
+Commands:
+  exports                         List the exports of the module
+  call <FUNC> [ARGS...]           Call an exported function
+  memory <OFFSET> [LEN] [INDEX]   Dump LEN bytes (default 64) of memory INDEX (default 0)
+  fuel [AMOUNT|off]               Show the fuel left, or limit or unlimit it
+  trace on|off                    Print the spans of the calls after each command
+  help                            Show this text
+  quit | exit                     Leave";
+
+/// Bytes `memory` dumps when no length is given
+const DEFAULT_DUMP_LEN: usize = 64;
+
+/// Most bytes a single `memory` command dumps
+const MAX_DUMP_LEN: usize = 64 * 1024;
+
+/// A command of the interactive mode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ReplCommand {
+    /// List the exports
+    Exports,
+    /// Call an export with arguments as written
+    Call {
+        /// Exported function
+        func: String,
+        /// Arguments, parsed according to the parameter types
+        args: Vec<String>,
+    },
+    /// Dump bytes of a memory
+    Memory {
+        /// Memory index
+        index:  u32,
+        /// First byte to dump
+        offset: u32,
+        /// Number of bytes to dump
+        len:    usize,
+    },
+    /// Show the fuel with `None`, otherwise set the limit
+    Fuel(Option<Option<u64>>),
+    /// Print the spans of calls, or stop
+    Trace(bool),
+    /// Show the commands
+    Help,
+    /// Leave the interactive mode
+    Quit,
+}
+
+fn usage(message: impl Into<String>) -> CliError {
+    CliError::Usage(message.into())
+}
+
+/// Parse a line of input; an empty line or a comment is no command
+pub(crate) fn parse_command(line: &str) -> Result<Option<ReplCommand>, CliError> {
+    let mut words = line.split_whitespace();
+    let Some(command) = words.next().filter(|command| !command.starts_with('#')) else {
+        return Ok(None);
+    };
+    let number = |text: &str| {
+        text.strip_prefix("0x")
+            .map_or_else(|| text.parse(), |hex| u64::from_str_radix(hex, 16))
+            .map_err(|_| usage(format!("invalid number `{text}`")))
+    };
+    let command = match command {
+        "exports" | "e" => ReplCommand::Exports,
+        "call" | "c" => {
+            let func = words.next().ok_or_else(|| usage("`call` needs a function"))?;
+            ReplCommand::Call {
+                func: func.into(),
+                args: words.by_ref().map(String::from).collect(),
+            }
+        },
+        "memory" | "mem" | "m" => {
+            let offset = words.next().ok_or_else(|| usage("`memory` needs an offset"))?;
+            let offset = u32::try_from(number(offset)?)
+                .map_err(|_| usage(format!("offset `{offset}` is out of range")))?;
+            let len = match words.next() {
+                Some(len) => usize::try_from(number(len)?)
+                    .ok()
+                    .filter(|len| *len <= MAX_DUMP_LEN)
+                    .ok_or_else(|| usage("`memory` dumps at most 64 KiB"))?,
+                None => DEFAULT_DUMP_LEN,
+            };
+            let index = match words.next() {
+                Some(index) => u32::try_from(number(index)?)
+                    .map_err(|_| usage(format!("memory index `{index}` is out of range")))?,
+                None => 0,
+            };
+            ReplCommand::Memory { index, offset, len }
+        },
+        "fuel" => ReplCommand::Fuel(match words.next() {
+            None => None,
+            Some("off") => Some(None),
+            Some(amount) => Some(Some(number(amount)?)),
+        }),
+        "trace" | "t" => match words.next() {
+            Some("on") => ReplCommand::Trace(true),
+            Some("off") => ReplCommand::Trace(false),
+            _ => return Err(usage("`trace` needs `on` or `off`")),
+        },
+        "help" | "h" | "?" => ReplCommand::Help,
+        "quit" | "exit" | "q" => ReplCommand::Quit,
+        "step" => {
+            return Err(usage(
+                "`step` is not supported, as calls pause only when they run out of fuel",
+            ))
+        },
+        other => return Err(usage(format!("unknown command `{other}`"))),
+    };
+    if let Some(extra) = words.next() {
+        return Err(usage(format!("unexpected argument `{extra}`")));
+    }
+    Ok(Some(command))
+}
+
+/// Render `bytes` read from `offset` as lines of sixteen bytes in hex and
+/// ASCII
+pub(crate) fn hex_dump(offset: u32, bytes: &[u8]) -> String {
+    let mut text = String::new();
+    for (line, chunk) in bytes.chunks(16).enumerate() {
+        let hex: Vec<String> = chunk.iter().map(|byte| format!("{byte:02x}")).collect();
+        let ascii: String = chunk
+            .iter()
+            .map(|&byte| if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' })
+            .collect();
+        text.push_str(&format!(
+            "{:08x}  {:<47}  |{ascii}|\n",
+            u64::from(offset) + line as u64 * 16,
+            hex.join(" ")
+        ));
+    }
+    text
+}
+
+/// An instantiated module taking commands
+pub(crate) struct Session {
+    engine:   CapabilityAwareEngine,
+    instance: InstanceHandle,
+    exports:  Vec<ItemSummary>,
+    /// Spans the engine finished since they were last printed
+    spans:    Arc<Mutex<Vec<SpanData>>>,
+}
+
+impl Session {
+    /// Instantiate `binary` with the engine options of `options`
+    pub(crate) fn new(options: &RunOptions, binary: &[u8]) -> Result<Self, CliError> {
+        if is_component(binary) {
+            return Err(usage(format!(
+                "{} is a component, which the interactive mode cannot run",
+                options.path.display()
+            )));
+        }
+        let exports = ModuleSummary::new(binary)?.exports;
+
+        let mut engine = CapabilityAwareEngine::with_preset(options.preset)?;
+        if options.wasi.enabled {
+            enable_wasi(&mut engine, &options.wasi)?;
+        }
+        engine.set_fuel(options.fuel);
+
+        let module = engine.load_module(binary)?;
+        let instance = engine.instantiate(module)?;
+        Ok(Self {
+            engine,
+            instance,
+            exports,
+            spans: Arc::new(Mutex::new(Vec::new())),
+        })
+    }
+
+    /// Run `command` and return its output
+    pub(crate) fn eval(&mut self, command: ReplCommand) -> Result<String, CliError> {
+        let output = match command {
+            ReplCommand::Exports => self
+                .exports
+                .iter()
+                .map(|item| format!("{}: {}\n", item.name, item.desc))
+                .collect(),
+            ReplCommand::Call { func, args } => {
+                let args = self.parse_args(&func, &args)?;
+                let results = self.engine.execute(self.instance, &func, &args);
+                take_spans(&self.spans) + &results_text(results?)
+            },
+            ReplCommand::Memory { index, offset, len } => hex_dump(
+                offset,
+                &self.engine.read_memory(self.instance, index, offset, len)?,
+            ),
+            ReplCommand::Fuel(None) => match self.engine.fuel() {
+                Some(fuel) => format!("fuel: {fuel} left\n"),
+                None => "fuel: unlimited\n".into(),
+            },
+            ReplCommand::Fuel(Some(fuel)) => {
+                self.engine.set_fuel(fuel);
+                String::new()
+            },
+            ReplCommand::Trace(true) => {
+                let finished = self.spans.clone();
+                self.engine.set_tracer(Some(Arc::new(Tracer::new(Arc::new(move |span| {
+                    finished.lock().unwrap_or_else(PoisonError::into_inner).push(span);
+                })))));
+                "tracing on\n".into()
+            },
+            ReplCommand::Trace(false) => {
+                self.engine.set_tracer(None);
+                "tracing off\n".into()
+            },
+            ReplCommand::Help => format!("{HELP}\n"),
+            ReplCommand::Quit => String::new(),
+        };
+        Ok(output)
+    }
+
+    fn parse_args(&self, func: &str, args: &[String]) -> Result<Vec<Value>, CliError> {
+        let (params, _) = self.engine.get_export_types(self.instance, func)?;
+        parse_args(func, args, &params)
+    }
+}
+
+/// Render and forget the spans finished so far
+fn take_spans(spans: &Mutex<Vec<SpanData>>) -> String {
+    let spans = core::mem::take(&mut *spans.lock().unwrap_or_else(PoisonError::into_inner));
+    spans
+        .iter()
+        .map(|span| {
+            let micros = span.end_unix_nanos.saturating_sub(span.start_unix_nanos) / 1000;
+            let status = match &span.status {
+                SpanStatus::Ok => "ok".into(),
+                SpanStatus::Error(message) => format!("error: {message}"),
+            };
+            format!("trace: {} {status} ({micros} us)\n", span.name)
+        })
+        .collect()
+}
+
+fn results_text(results: Vec<Value>) -> String {
+    results.iter().map(|result| result_text(result) + "\n").collect()
+}
+
+/// Instantiate the module of `options` and take commands from standard input
+/// until `quit` or the end of the input
+pub(crate) fn run(options: &RunOptions, binary: &[u8]) -> Result<(), CliError> {
+    let mut session = Session::new(options, binary)?;
+    println!("{}: type `help` for the commands", options.path.display());
+
+    let mut stdin = io::stdin().lock();
+    let mut line = String::new();
+    loop {
+        print!("wrt> ");
+        let _ = io::stdout().flush();
+        line.clear();
+        match stdin.read_line(&mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {},
+        }
+        let result = parse_command(&line).and_then(|command| match command {
+            Some(ReplCommand::Quit) => Ok(None),
+            Some(command) => session.eval(command).map(Some),
+            None => Ok(Some(String::new())),
+        });
+        match result {
+            Ok(Some(output)) => print!("{output}"),
+            Ok(None) => return Ok(()),
+            Err(CliError::Usage(message)) => eprintln!("error: {message}"),
+            Err(error) => eprintln!("error: {error}"),
+        }
+    }
+    println!();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(parse_command("  ").unwrap(), None);
+        assert_eq!(parse_command("# call add 1 2").unwrap(), None);
+        assert_eq!(
+            parse_command("call add 1 -2").unwrap(),
+            Some(ReplCommand::Call {
+                func: "add".into(),
+                args: vec!["1".into(), "-2".into()],
+            })
+        );
+        assert_eq!(
+            parse_command("mem 0x100").unwrap(),
+            Some(ReplCommand::Memory {
+                index:  0,
+                offset: 0x100,
+                len:    DEFAULT_DUMP_LEN,
+            })
+        );
+        assert_eq!(
+            parse_command("m 0 8 1").unwrap(),
+            Some(ReplCommand::Memory {
+                index:  1,
+                offset: 0,
+                len:    8,
+            })
+        );
+        assert_eq!(
+            parse_command("fuel off").unwrap(),
+            Some(ReplCommand::Fuel(Some(None)))
+        );
+        assert_eq!(
+            parse_command("fuel 500").unwrap(),
+            Some(ReplCommand::Fuel(Some(Some(500))))
+        );
+        assert_eq!(
+            parse_command("trace on").unwrap(),
+            Some(ReplCommand::Trace(true))
+        );
+        assert_eq!(parse_command("q").unwrap(), Some(ReplCommand::Quit));
+
+        for line in [
+            "call",
+            "memory",
+            "memory 0x1_0000_0000",
+            "memory 0 0x10001",
+            "fuel lots",
+            "trace",
+            "step",
+            "exports 1",
+            "run",
+        ] {
+            assert!(parse_command(line).is_err(), "{line}");
+        }
+        let error = parse_command("step add").unwrap_err();
+        assert!(error.to_string().contains("not supported"), "{error}");
+    }
+
+    #[test]
+    fn test_hex_dump() {
+        let bytes: Vec<u8> = (b'A'..=b'R').chain([0, 0xff]).collect();
+        assert_eq!(
+            hex_dump(0x20, &bytes),
+            "00000020  41 42 43 44 45 46 47 48 49 4a 4b 4c 4d 4e 4f 50  \
+             |ABCDEFGHIJKLMNOP|\n00000030  51 52 00 ff                                      \
+             |QR..|\n"
+        );
+    }
+}
@@ -0,0 +1,268 @@
+//! `wrt run` and `wrt component run`
+
+use wrt_format::binary::WASM_MAGIC;
+use wrt_foundation::{
+    operations::{
+        global_fuel_consumed,
+        reset_global_operations,
+    },
+    values::{
+        FloatBits32,
+        FloatBits64,
+        Value,
+    },
+    ValueType,
+};
+use wrt_runtime::engine::{
+    CapabilityAwareEngine,
+    CapabilityEngine,
+};
+
+use crate::{
+    cli::{
+        CliError,
+        RunOptions,
+        WasiOptions,
+    },
+    inspect::type_name,
+};
+
+/// Function called when `--invoke` is not given, the entry point of WASI
+/// commands
+const DEFAULT_FUNCTION: &str = "_start";
+
+/// Run a core module and print the results of the invoked function
+pub(crate) fn run(options: &RunOptions, binary: &[u8]) -> Result<(), CliError> {
+    if is_component(binary) {
+        return Err(CliError::Usage(format!(
+            "{} is a component, run it with `wrt component run`",
+            options.path.display()
+        )));
+    }
+
+    let mut engine = CapabilityAwareEngine::with_preset(options.preset)?;
+    if options.wasi.enabled {
+        enable_wasi(&mut engine, &options.wasi)?;
+    }
+
+    let module = engine.load_module(binary)?;
+    // Fuel spent by the start function counts towards the limit
+    reset_global_operations();
+    let instance = engine.instantiate(module)?;
+
+    let func = options.invoke.as_deref().unwrap_or(DEFAULT_FUNCTION);
+    let (params, _) = engine.get_export_types(instance, func)?;
+    let args = parse_args(func, &options.args, &params)?;
+    let results = engine.execute(instance, func, &args)?;
+
+    if let Some(limit) = options.fuel {
+        let consumed = global_fuel_consumed();
+        if consumed > limit {
+            return Err(CliError::FuelExhausted { consumed, limit });
+        }
+    }
+
+    for result in &results {
+        println!("{}", result_text(result));
+    }
+    Ok(())
+}
+
+/// Text printed for a result of an invoked function
+#[cfg(feature = "component")]
+pub(crate) fn result_text(result: &Value) -> String {
+    use wrt_component::canonical_abi::{
+        ComponentValueFormatter,
+        DisplayLimits,
+    };
+    use wrt_intercept::strategies::ValueFormatter;
+
+    ComponentValueFormatter::new(DisplayLimits::UNLIMITED).format_value(result)
+}
+
+/// Text printed for a result of an invoked function
+#[cfg(not(feature = "component"))]
+pub(crate) fn result_text(result: &Value) -> String {
+    result.to_string()
+}
+
+/// Run a component
+///
+/// Component execution lives in `wrt-component`, which the runner does not
+/// link yet, so components are only recognized.
+pub(crate) fn run_component(options: &RunOptions, binary: &[u8]) -> Result<(), CliError> {
+    if !is_component(binary) {
+        return Err(CliError::Usage(format!(
+            "{} is not a component, run it with `wrt run`",
+            options.path.display()
+        )));
+    }
+    Err(CliError::Runtime(
+        wrt_error::Error::not_supported_unsupported_operation(
+            "Component execution is not supported by this build",
+        ),
+    ))
+}
+
+/// Whether `binary` is a component rather than a core module
+///
+/// Both share the magic number; the layer field in the upper half of the
+/// version is 1 for components and 0 for core modules.
+pub(crate) fn is_component(binary: &[u8]) -> bool {
+    binary.len() >= 8 && binary[..4] == WASM_MAGIC && binary[6..8] == [0x01, 0x00]
+}
+
+/// Grant the module the WASI access described by `options`
+#[cfg(feature = "wasi")]
+pub(crate) fn enable_wasi(engine: &mut CapabilityAwareEngine, options: &WasiOptions) -> Result<(), CliError> {
+    use wrt_wasi::{
+        ComponentModelProvider,
+        WasiCapabilities,
+        WasiFileSystemCapabilities,
+    };
+
+    let mut capabilities = WasiCapabilities::sandboxed()?;
+    // Without --dir the module gets no filesystem access at all
+    if options.dirs.is_empty() {
+        capabilities.filesystem = WasiFileSystemCapabilities::minimal()?;
+    }
+    for dir in &options.dirs {
+        capabilities.filesystem.add_allowed_path(dir)?;
+    }
+    capabilities.environment.environ_access = !options.env.is_empty();
+    for name in &options.env {
+        capabilities.environment.add_allowed_var(name)?;
+    }
+
+    let mut provider = ComponentModelProvider::new(capabilities)?;
+    if let Some(registry) = engine.host_registry_mut() {
+        provider.register_with_registry(registry)?;
+    }
+    engine.enable_wasi()?;
+    Ok(())
+}
+
+#[cfg(not(feature = "wasi"))]
+pub(crate) fn enable_wasi(
+    _engine: &mut CapabilityAwareEngine,
+    _options: &WasiOptions,
+) -> Result<(), CliError> {
+    Err(CliError::Usage(
+        "this build of wrt has no WASI support".into(),
+    ))
+}
+
+/// Parse the command line arguments of `func` according to its parameter
+/// types
+pub(crate) fn parse_args(
+    func: &str,
+    args: &[String],
+    params: &[ValueType],
+) -> Result<Vec<Value>, CliError> {
+    if args.len() != params.len() {
+        return Err(CliError::Usage(format!(
+            "`{func}` takes {} arguments, got {}",
+            params.len(),
+            args.len()
+        )));
+    }
+    args.iter().zip(params).map(|(arg, param)| parse_value(arg, param)).collect()
+}
+
+/// Parse a single argument
+///
+/// Integers are decimal, signed or unsigned, or hexadecimal with a `0x`
+/// prefix. Floats also accept `inf` and `nan`, and references only `null`.
+fn parse_value(text: &str, value_type: &ValueType) -> Result<Value, CliError> {
+    let invalid = || {
+        CliError::Usage(format!(
+            "invalid {} argument `{text}`",
+            type_name(value_type)
+        ))
+    };
+    let hex = text.strip_prefix("0x");
+    Ok(match value_type {
+        ValueType::I32 => Value::I32(
+            text.parse::<i32>()
+                .ok()
+                .or_else(|| text.parse::<u32>().ok().map(|value| value as i32))
+                .or_else(|| {
+                    hex.and_then(|hex| u32::from_str_radix(hex, 16).ok()).map(|value| value as i32)
+                })
+                .ok_or_else(invalid)?,
+        ),
+        ValueType::I64 => Value::I64(
+            text.parse::<i64>()
+                .ok()
+                .or_else(|| text.parse::<u64>().ok().map(|value| value as i64))
+                .or_else(|| {
+                    hex.and_then(|hex| u64::from_str_radix(hex, 16).ok()).map(|value| value as i64)
+                })
+                .ok_or_else(invalid)?,
+        ),
+        ValueType::F32 => Value::F32(FloatBits32::from_float(
+            text.parse().map_err(|_| invalid())?,
+        )),
+        ValueType::F64 => Value::F64(FloatBits64::from_float(
+            text.parse().map_err(|_| invalid())?,
+        )),
+        ValueType::FuncRef if text == "null" => Value::FuncRef(None),
+        ValueType::ExternRef if text == "null" => Value::ExternRef(None),
+        ValueType::FuncRef | ValueType::ExternRef => return Err(invalid()),
+        _ => {
+            return Err(CliError::Usage(format!(
+                "{} arguments cannot be given on the command line",
+                type_name(value_type)
+            )))
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_value() {
+        let parse = |text: &str, value_type| parse_value(text, &value_type).unwrap();
+        assert_eq!(parse("-7", ValueType::I32), Value::I32(-7));
+        assert_eq!(parse("4294967295", ValueType::I32), Value::I32(-1));
+        assert_eq!(parse("0xff", ValueType::I32), Value::I32(255));
+        assert_eq!(parse("0xffffffffffffffff", ValueType::I64), Value::I64(-1));
+        assert_eq!(
+            parse("1.5", ValueType::F32),
+            Value::F32(FloatBits32::from_float(1.5))
+        );
+        assert_eq!(
+            parse("-inf", ValueType::F64),
+            Value::F64(FloatBits64::from_float(f64::NEG_INFINITY))
+        );
+
+        assert!(parse_value("4294967296", &ValueType::I32).is_err());
+        assert!(parse_value("one", &ValueType::I64).is_err());
+        assert_eq!(parse("null", ValueType::ExternRef), Value::ExternRef(None));
+        assert!(parse_value("0", &ValueType::FuncRef).is_err());
+        assert!(parse_value("0", &ValueType::V128).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_checks_arity() {
+        let args = vec!["1".to_string(), "2".to_string()];
+        assert_eq!(
+            parse_args("add", &args, &[ValueType::I32, ValueType::I64]).unwrap(),
+            [Value::I32(1), Value::I64(2)]
+        );
+        let error = parse_args("add", &args, &[ValueType::I32]).unwrap_err();
+        assert_eq!(
+            error.to_string().lines().next(),
+            Some("`add` takes 1 arguments, got 2")
+        );
+    }
+
+    #[test]
+    fn test_is_component() {
+        assert!(is_component(b"\0asm\x0d\0\x01\0"));
+        assert!(!is_component(b"\0asm\x01\0\0\0"));
+        assert!(!is_component(b"\0asm"));
+    }
+}
@@ -0,0 +1,63 @@
+//! End-to-end tests of the `wrt` binary on modules with code
+
+use std::{
+    fs,
+    path::PathBuf,
+    process::{
+        Command,
+        Output,
+    },
+};
+
+/// Module exporting `add`, which adds two i32 parameters
+const ADD_WASM: &[u8] = &[
+    0x00, 0x61, 0x73, 0x6d, // WASM magic
+    0x01, 0x00, 0x00, 0x00, // Version
+    // Type section - (i32, i32) -> i32
+    0x01, 0x07, 0x01, 0x60, 0x02, 0x7f, 0x7f, 0x01, 0x7f, // Function section
+    0x03, 0x02, 0x01, 0x00, // Export section - function 0 as "add"
+    0x07, 0x07, 0x01, 0x03, 0x61, 0x64, 0x64, 0x00, 0x00,
+    // Code section - local.get 0, local.get 1, i32.add
+    0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x6a, 0x0b,
+];
+
+/// Write `binary` to a file of its own and run `wrt` with `args` followed by
+/// its path and `trailing`
+fn wrt(name: &str, binary: &[u8], args: &[&str], trailing: &[&str]) -> Output {
+    let path: PathBuf =
+        std::env::temp_dir().join(format!("wrt-cli-{name}-{}.wasm", std::process::id()));
+    fs::write(&path, binary).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_wrt"))
+        .args(args)
+        .arg(&path)
+        .args(trailing)
+        .output()
+        .unwrap();
+    let _ = fs::remove_file(&path);
+    output
+}
+
+#[test]
+fn test_validate_accepts_module_with_code() {
+    let output = wrt("validate", ADD_WASM, &["validate"], &[]);
+    assert!(output.status.success(), "{output:?}");
+}
+
+#[test]
+fn test_run_invokes_function_with_code() {
+    let output = wrt("run", ADD_WASM, &["run", "--invoke", "add"], &["2", "3"]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "i32:5");
+}
+
+#[test]
+fn test_validate_rejects_invalid_code() {
+    // Replace the local.get operations with nops, leaving i32.add without
+    // operands
+    let mut binary = ADD_WASM.to_vec();
+    let body = binary.len() - 6;
+    binary[body..body + 4].copy_from_slice(&[0x01; 4]);
+    let output = wrt("invalid", &binary, &["validate"], &[]);
+    assert!(!output.status.success(), "{output:?}");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Type mismatch"));
+}
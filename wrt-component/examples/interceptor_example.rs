@@ -1,6 +1,10 @@
 use std::sync::Arc;
 
 use wrt_component::{
+    canonical_abi::{
+        ComponentValueFormatter,
+        DisplayLimits,
+    },
     Component,
     ComponentType,
 };
@@ -37,7 +41,10 @@ fn main() -> Result<()> {
 
     // Create a logging interceptor
     let log_sink = Arc::new(|log_entry: &str| println!("[LOG] {}", log_entry));
-    let logging_strategy = LoggingStrategy::new(log_sink);
+    let logging_strategy = LoggingStrategy::with_formatter(
+        log_sink,
+        ComponentValueFormatter::new(DisplayLimits::COMPACT),
+    );
 
     // Create a firewall interceptor
     let firewall = FirewallBuilder::new(false)
@@ -0,0 +1,948 @@
+//! Built-ins of the component model async ABI
+//!
+//! Components built with the async ABI pass `stream<T>`, `future<T>` and
+//! `error-context` values as handles into a per-instance [`AsyncHandleTable`]
+//! and complete their async exports through `task.return` on an
+//! [`AsyncTask`] instead of returning results. The table implements the
+//! `stream.*`, `future.*`, `error-context.*` and `waitable-set.*` built-ins
+//! on those handles.
+//!
+//! The host takes part through the same ends the guest uses:
+//! [`stream_channel`] and [`future_channel`] create a connected pair, one end
+//! of which is handed to a guest with [`AsyncHandleTable::insert`] while the
+//! host keeps the other. Ends leaving a guest, such as a stream returned by an
+//! export, are taken out of its table with [`AsyncHandleTable::take`] or moved
+//! along with the value carrying them by
+//! [`AsyncHandleTable::transfer_value`].
+//!
+//! None of the built-ins block. Reads, writes and `task.wait` return
+//! [`Poll::Pending`] when they cannot make progress, so that an async engine
+//! mode can suspend the calling task and resume it once `task.wait` reports
+//! an event. Streams and futures without a payload type carry empty tuples.
+
+use core::task::Poll;
+use std::{
+    collections::{
+        HashMap,
+        VecDeque,
+    },
+    string::{
+        String,
+        ToString,
+    },
+    sync::{
+        Arc,
+        Mutex,
+        MutexGuard,
+        PoisonError,
+    },
+    vec::Vec,
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+
+use super::canonical_abi::{
+    ComponentType,
+    ComponentValue,
+};
+use crate::wit::value_conforms;
+
+/// Number of values a stream created by `stream.new` buffers
+pub const DEFAULT_STREAM_CAPACITY: usize = 64;
+
+/// Lock shared channel state
+///
+/// Every update leaves the state consistent, so a panic while it was held
+/// does not invalidate it.
+fn lock<T>(state: &Mutex<T>) -> MutexGuard<'_, T> {
+    state.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn payload_conforms(ty: Option<&ComponentType>, value: &ComponentValue) -> bool {
+    match ty {
+        Some(ty) => value_conforms(ty, value),
+        None => matches!(value, ComponentValue::Tuple(values) if values.is_empty()),
+    }
+}
+
+#[derive(Debug)]
+struct StreamState {
+    element:        Option<ComponentType>,
+    buffer:         VecDeque<ComponentValue>,
+    capacity:       usize,
+    reader_dropped: bool,
+    writer_dropped: bool,
+}
+
+/// Readable end of a stream
+#[derive(Debug)]
+pub struct StreamReader {
+    state: Arc<Mutex<StreamState>>,
+}
+
+/// Writable end of a stream
+#[derive(Debug)]
+pub struct StreamWriter {
+    state: Arc<Mutex<StreamState>>,
+}
+
+/// Create a stream of `element` values buffering up to `capacity` of them
+pub fn stream_channel(
+    element: Option<ComponentType>,
+    capacity: usize,
+) -> Result<(StreamReader, StreamWriter)> {
+    if capacity == 0 {
+        return Err(Error::validation_error("Stream capacity must not be zero"));
+    }
+    let state = Arc::new(Mutex::new(StreamState {
+        element,
+        buffer: VecDeque::new(),
+        capacity,
+        reader_dropped: false,
+        writer_dropped: false,
+    }));
+    Ok((
+        StreamReader {
+            state: state.clone(),
+        },
+        StreamWriter { state },
+    ))
+}
+
+impl StreamReader {
+    /// Element type of the stream
+    pub fn element_type(&self) -> Option<ComponentType> {
+        lock(&self.state).element.clone()
+    }
+
+    /// Read up to `max` values
+    ///
+    /// Returns `None` once the writer is dropped and every value was read.
+    pub fn read(&self, max: usize) -> Poll<Option<Vec<ComponentValue>>> {
+        let mut state = lock(&self.state);
+        if !state.buffer.is_empty() {
+            let count = max.min(state.buffer.len());
+            Poll::Ready(Some(state.buffer.drain(..count).collect()))
+        } else if state.writer_dropped {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+
+    /// Whether a read would not be pending
+    pub fn is_ready(&self) -> bool {
+        let state = lock(&self.state);
+        !state.buffer.is_empty() || state.writer_dropped
+    }
+}
+
+impl Drop for StreamReader {
+    fn drop(&mut self) {
+        let mut state = lock(&self.state);
+        state.reader_dropped = true;
+        state.buffer.clear();
+    }
+}
+
+impl StreamWriter {
+    /// Element type of the stream
+    pub fn element_type(&self) -> Option<ComponentType> {
+        lock(&self.state).element.clone()
+    }
+
+    /// Write as many of `values` as the stream has room for
+    ///
+    /// Returns the number of values written, or `None` if the reader was
+    /// dropped. Values not written are left to the caller to retry.
+    pub fn write(&self, values: &[ComponentValue]) -> Result<Poll<Option<usize>>> {
+        let mut state = lock(&self.state);
+        if !values.iter().all(|value| payload_conforms(state.element.as_ref(), value)) {
+            return Err(Error::runtime_type_mismatch(
+                "Stream value does not match the element type",
+            ));
+        }
+        if state.reader_dropped {
+            return Ok(Poll::Ready(None));
+        }
+
+        let room = state.capacity - state.buffer.len();
+        if room == 0 && !values.is_empty() {
+            return Ok(Poll::Pending);
+        }
+        let count = room.min(values.len());
+        state.buffer.extend(values[..count].iter().cloned());
+        Ok(Poll::Ready(Some(count)))
+    }
+
+    /// Whether a write would not be pending
+    pub fn is_ready(&self) -> bool {
+        let state = lock(&self.state);
+        state.buffer.len() < state.capacity || state.reader_dropped
+    }
+}
+
+impl Drop for StreamWriter {
+    fn drop(&mut self) {
+        lock(&self.state).writer_dropped = true;
+    }
+}
+
+#[derive(Debug)]
+struct FutureState {
+    ty:             Option<ComponentType>,
+    value:          Option<ComponentValue>,
+    written:        bool,
+    read:           bool,
+    reader_dropped: bool,
+    writer_dropped: bool,
+}
+
+/// Readable end of a future
+#[derive(Debug)]
+pub struct FutureReader {
+    state: Arc<Mutex<FutureState>>,
+}
+
+/// Writable end of a future
+#[derive(Debug)]
+pub struct FutureWriter {
+    state: Arc<Mutex<FutureState>>,
+}
+
+/// Create a future of a `ty` value
+pub fn future_channel(ty: Option<ComponentType>) -> (FutureReader, FutureWriter) {
+    let state = Arc::new(Mutex::new(FutureState {
+        ty,
+        value: None,
+        written: false,
+        read: false,
+        reader_dropped: false,
+        writer_dropped: false,
+    }));
+    (
+        FutureReader {
+            state: state.clone(),
+        },
+        FutureWriter { state },
+    )
+}
+
+impl FutureReader {
+    /// Value type of the future
+    pub fn value_type(&self) -> Option<ComponentType> {
+        lock(&self.state).ty.clone()
+    }
+
+    /// Read the value of the future
+    ///
+    /// Returns `None` if the writer was dropped without writing a value.
+    /// The value can be read only once.
+    pub fn read(&self) -> Result<Poll<Option<ComponentValue>>> {
+        let mut state = lock(&self.state);
+        if state.read {
+            return Err(Error::runtime_trap("Future value already read"));
+        }
+        if let Some(value) = state.value.take() {
+            state.read = true;
+            Ok(Poll::Ready(Some(value)))
+        } else if state.writer_dropped {
+            Ok(Poll::Ready(None))
+        } else {
+            Ok(Poll::Pending)
+        }
+    }
+
+    /// Whether a read would not be pending
+    pub fn is_ready(&self) -> bool {
+        let state = lock(&self.state);
+        state.value.is_some() || state.writer_dropped
+    }
+}
+
+impl Drop for FutureReader {
+    fn drop(&mut self) {
+        let mut state = lock(&self.state);
+        state.reader_dropped = true;
+        state.value = None;
+    }
+}
+
+impl FutureWriter {
+    /// Value type of the future
+    pub fn value_type(&self) -> Option<ComponentType> {
+        lock(&self.state).ty.clone()
+    }
+
+    /// Complete the future with `value`
+    ///
+    /// Returns whether the reader still exists to receive the value. A
+    /// future can be written only once.
+    pub fn write(&self, value: ComponentValue) -> Result<bool> {
+        let mut state = lock(&self.state);
+        if state.written {
+            return Err(Error::runtime_trap("Future value already written"));
+        }
+        if !payload_conforms(state.ty.as_ref(), &value) {
+            return Err(Error::runtime_type_mismatch(
+                "Future value does not match the value type",
+            ));
+        }
+        state.written = true;
+        if state.reader_dropped {
+            return Ok(false);
+        }
+        state.value = Some(value);
+        Ok(true)
+    }
+
+    /// Whether the future still awaits its value
+    pub fn is_ready(&self) -> bool {
+        let state = lock(&self.state);
+        !state.written || state.reader_dropped
+    }
+}
+
+impl Drop for FutureWriter {
+    fn drop(&mut self) {
+        lock(&self.state).writer_dropped = true;
+    }
+}
+
+#[derive(Debug)]
+struct TaskState {
+    result_types: Vec<ComponentType>,
+    results:      Option<Vec<ComponentValue>>,
+    returned:     bool,
+}
+
+/// An async call, shared between its caller and the callee
+///
+/// The callee completes the call with [`task_return`](Self::task_return),
+/// after which the caller collects the results with
+/// [`poll_results`](Self::poll_results).
+#[derive(Debug, Clone)]
+pub struct AsyncTask {
+    state: Arc<Mutex<TaskState>>,
+}
+
+impl AsyncTask {
+    /// Create a task for a call returning values of `result_types`
+    pub fn new(result_types: Vec<ComponentType>) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(TaskState {
+                result_types,
+                results: None,
+                returned: false,
+            })),
+        }
+    }
+
+    /// `task.return`: complete the call with its results
+    pub fn task_return(&self, values: Vec<ComponentValue>) -> Result<()> {
+        let mut state = lock(&self.state);
+        if state.returned {
+            return Err(Error::runtime_trap("task.return called more than once"));
+        }
+        if values.len() != state.result_types.len()
+            || !state
+                .result_types
+                .iter()
+                .zip(&values)
+                .all(|(ty, value)| value_conforms(ty, value))
+        {
+            return Err(Error::runtime_type_mismatch(
+                "task.return values do not match the result types",
+            ));
+        }
+        state.returned = true;
+        state.results = Some(values);
+        Ok(())
+    }
+
+    /// Whether the callee called `task.return`
+    pub fn is_returned(&self) -> bool {
+        lock(&self.state).returned
+    }
+
+    /// Take the results of the call once it returned
+    pub fn poll_results(&self) -> Result<Poll<Vec<ComponentValue>>> {
+        let mut state = lock(&self.state);
+        match state.results.take() {
+            Some(results) => Ok(Poll::Ready(results)),
+            None if state.returned => {
+                Err(Error::runtime_invalid_state("Task results already taken"))
+            },
+            None => Ok(Poll::Pending),
+        }
+    }
+}
+
+/// An entry of an [`AsyncHandleTable`]
+#[derive(Debug)]
+pub enum AsyncHandle {
+    /// Readable end of a stream
+    StreamReader(StreamReader),
+    /// Writable end of a stream
+    StreamWriter(StreamWriter),
+    /// Readable end of a future
+    FutureReader(FutureReader),
+    /// Writable end of a future
+    FutureWriter(FutureWriter),
+    /// Error context with its debug message
+    ErrorContext(Arc<str>),
+    /// Async call made by the component
+    Subtask(AsyncTask),
+    /// Waitable set with its members in join order
+    WaitableSet(Vec<u32>),
+}
+
+impl AsyncHandle {
+    fn is_waitable(&self) -> bool {
+        !matches!(self, Self::ErrorContext(_) | Self::WaitableSet(_))
+    }
+
+    /// Event the handle is ready to deliver, if any
+    fn event(&self) -> Option<AsyncEventKind> {
+        match self {
+            Self::StreamReader(end) if end.is_ready() => Some(AsyncEventKind::StreamRead),
+            Self::StreamWriter(end) if end.is_ready() => Some(AsyncEventKind::StreamWrite),
+            Self::FutureReader(end) if end.is_ready() => Some(AsyncEventKind::FutureRead),
+            Self::FutureWriter(end) if end.is_ready() => Some(AsyncEventKind::FutureWrite),
+            Self::Subtask(task) if task.is_returned() => Some(AsyncEventKind::Subtask),
+            _ => None,
+        }
+    }
+}
+
+/// Kind of progress reported by `task.wait`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsyncEventKind {
+    /// A stream can be read or its writer was dropped
+    StreamRead,
+    /// A stream can be written or its reader was dropped
+    StreamWrite,
+    /// A future has its value or its writer was dropped
+    FutureRead,
+    /// A future can be written or its reader was dropped
+    FutureWrite,
+    /// A subtask called `task.return`
+    Subtask,
+}
+
+/// Event reported by `task.wait`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AsyncEvent {
+    /// What made progress
+    pub kind:   AsyncEventKind,
+    /// Handle of the waitable that made progress
+    pub handle: u32,
+}
+
+/// Handles of the async values owned by a component instance
+#[derive(Debug)]
+pub struct AsyncHandleTable {
+    entries:         HashMap<u32, AsyncHandle>,
+    next_handle:     u32,
+    stream_capacity: usize,
+}
+
+impl Default for AsyncHandleTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AsyncHandleTable {
+    /// Create an empty table
+    pub fn new() -> Self {
+        Self {
+            entries:         HashMap::new(),
+            // Handle 0 is reserved as the null handle
+            next_handle:     1,
+            stream_capacity: DEFAULT_STREAM_CAPACITY,
+        }
+    }
+
+    /// Set the number of values buffered by streams created by `stream.new`
+    pub fn with_stream_capacity(mut self, capacity: usize) -> Self {
+        self.stream_capacity = capacity;
+        self
+    }
+
+    /// Number of live handles
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the table holds no handles
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Add an entry, returning its handle
+    pub fn insert(&mut self, entry: AsyncHandle) -> Result<u32> {
+        let handle = self.next_handle;
+        self.next_handle = handle
+            .checked_add(1)
+            .ok_or_else(|| Error::resource_exhausted("Async handle table exhausted"))?;
+        self.entries.insert(handle, entry);
+        Ok(handle)
+    }
+
+    /// Entry of a handle
+    pub fn get(&self, handle: u32) -> Result<&AsyncHandle> {
+        self.entries
+            .get(&handle)
+            .ok_or_else(|| Error::resource_invalid_handle("Invalid async handle"))
+    }
+
+    /// Remove an entry, for example to pass it to another component
+    ///
+    /// The handle leaves the waitable set it was joined to.
+    pub fn take(&mut self, handle: u32) -> Result<AsyncHandle> {
+        let entry = self
+            .entries
+            .remove(&handle)
+            .ok_or_else(|| Error::resource_invalid_handle("Invalid async handle"))?;
+        for entry in self.entries.values_mut() {
+            if let AsyncHandle::WaitableSet(members) = entry {
+                members.retain(|member| *member != handle);
+            }
+        }
+        Ok(entry)
+    }
+
+    /// Move the async handles carried by `value` into `target`
+    ///
+    /// Stream and future ends change owner, error contexts are copied.
+    /// Returns the value with its handles renumbered for `target`.
+    pub fn transfer_value(
+        &mut self,
+        value: ComponentValue,
+        target: &mut AsyncHandleTable,
+    ) -> Result<ComponentValue> {
+        Ok(match value {
+            ComponentValue::Stream(handle) => match self.take(handle)? {
+                entry @ (AsyncHandle::StreamReader(_) | AsyncHandle::StreamWriter(_)) => {
+                    ComponentValue::Stream(target.insert(entry)?)
+                },
+                entry => return Err(self.restore(handle, entry)),
+            },
+            ComponentValue::Future(handle) => match self.take(handle)? {
+                entry @ (AsyncHandle::FutureReader(_) | AsyncHandle::FutureWriter(_)) => {
+                    ComponentValue::Future(target.insert(entry)?)
+                },
+                entry => return Err(self.restore(handle, entry)),
+            },
+            ComponentValue::ErrorContext(handle) => {
+                let message = self.error_context(handle)?;
+                ComponentValue::ErrorContext(target.insert(AsyncHandle::ErrorContext(message))?)
+            },
+            ComponentValue::List(values) => {
+                ComponentValue::List(self.transfer_all(values, target)?)
+            },
+            ComponentValue::Tuple(values) => {
+                ComponentValue::Tuple(self.transfer_all(values, target)?)
+            },
+            ComponentValue::Record(fields) => {
+                let (names, values): (Vec<_>, Vec<_>) = fields.into_iter().unzip();
+                ComponentValue::Record(
+                    names.into_iter().zip(self.transfer_all(values, target)?).collect(),
+                )
+            },
+            ComponentValue::Variant(case, payload) => {
+                ComponentValue::Variant(case, self.transfer_payload(payload, target)?)
+            },
+            ComponentValue::Option(payload) => {
+                ComponentValue::Option(self.transfer_payload(payload, target)?)
+            },
+            ComponentValue::Result(Ok(payload)) => {
+                ComponentValue::Result(Ok(self.transfer_payload(payload, target)?))
+            },
+            ComponentValue::Result(Err(payload)) => {
+                ComponentValue::Result(Err(self.transfer_payload(payload, target)?))
+            },
+            value => value,
+        })
+    }
+
+    fn transfer_all(
+        &mut self,
+        values: Vec<ComponentValue>,
+        target: &mut AsyncHandleTable,
+    ) -> Result<Vec<ComponentValue>> {
+        values.into_iter().map(|value| self.transfer_value(value, target)).collect()
+    }
+
+    fn transfer_payload(
+        &mut self,
+        payload: Option<Box<ComponentValue>>,
+        target: &mut AsyncHandleTable,
+    ) -> Result<Option<Box<ComponentValue>>> {
+        payload
+            .map(|value| Ok(Box::new(self.transfer_value(*value, target)?)))
+            .transpose()
+    }
+
+    /// Put back an entry taken for a handle of the wrong kind
+    fn restore(&mut self, handle: u32, entry: AsyncHandle) -> Error {
+        self.entries.insert(handle, entry);
+        Error::runtime_type_mismatch("Async handle has the wrong kind")
+    }
+
+    fn drop_entry(&mut self, handle: u32, kind: fn(&AsyncHandle) -> bool) -> Result<AsyncHandle> {
+        if !kind(self.get(handle)?) {
+            return Err(Error::runtime_type_mismatch(
+                "Async handle has the wrong kind",
+            ));
+        }
+        self.take(handle)
+    }
+
+    /// `stream.new`: create a stream, returning its readable and writable
+    /// ends
+    pub fn stream_new(&mut self, element: Option<ComponentType>) -> Result<(u32, u32)> {
+        let (reader, writer) = stream_channel(element, self.stream_capacity)?;
+        let reader = self.insert(AsyncHandle::StreamReader(reader))?;
+        let writer = self.insert(AsyncHandle::StreamWriter(writer))?;
+        Ok((reader, writer))
+    }
+
+    /// `stream.read`: read up to `max` values from a readable end
+    pub fn stream_read(
+        &mut self,
+        handle: u32,
+        max: usize,
+    ) -> Result<Poll<Option<Vec<ComponentValue>>>> {
+        match self.get(handle)? {
+            AsyncHandle::StreamReader(reader) => Ok(reader.read(max)),
+            _ => Err(Error::runtime_type_mismatch(
+                "Handle is not a readable stream end",
+            )),
+        }
+    }
+
+    /// `stream.write`: write values to a writable end
+    pub fn stream_write(
+        &mut self,
+        handle: u32,
+        values: &[ComponentValue],
+    ) -> Result<Poll<Option<usize>>> {
+        match self.get(handle)? {
+            AsyncHandle::StreamWriter(writer) => writer.write(values),
+            _ => Err(Error::runtime_type_mismatch(
+                "Handle is not a writable stream end",
+            )),
+        }
+    }
+
+    /// `stream.drop-readable`
+    pub fn stream_drop_readable(&mut self, handle: u32) -> Result<()> {
+        self.drop_entry(handle, |entry| {
+            matches!(entry, AsyncHandle::StreamReader(_))
+        })
+        .map(drop)
+    }
+
+    /// `stream.drop-writable`
+    pub fn stream_drop_writable(&mut self, handle: u32) -> Result<()> {
+        self.drop_entry(handle, |entry| {
+            matches!(entry, AsyncHandle::StreamWriter(_))
+        })
+        .map(drop)
+    }
+
+    /// `future.new`: create a future, returning its readable and writable
+    /// ends
+    pub fn future_new(&mut self, ty: Option<ComponentType>) -> Result<(u32, u32)> {
+        let (reader, writer) = future_channel(ty);
+        let reader = self.insert(AsyncHandle::FutureReader(reader))?;
+        let writer = self.insert(AsyncHandle::FutureWriter(writer))?;
+        Ok((reader, writer))
+    }
+
+    /// `future.read`: read the value from a readable end
+    pub fn future_read(&mut self, handle: u32) -> Result<Poll<Option<ComponentValue>>> {
+        match self.get(handle)? {
+            AsyncHandle::FutureReader(reader) => reader.read(),
+            _ => Err(Error::runtime_type_mismatch(
+                "Handle is not a readable future end",
+            )),
+        }
+    }
+
+    /// `future.write`: write the value to a writable end
+    pub fn future_write(&mut self, handle: u32, value: ComponentValue) -> Result<bool> {
+        match self.get(handle)? {
+            AsyncHandle::FutureWriter(writer) => writer.write(value),
+            _ => Err(Error::runtime_type_mismatch(
+                "Handle is not a writable future end",
+            )),
+        }
+    }
+
+    /// `future.drop-readable`
+    pub fn future_drop_readable(&mut self, handle: u32) -> Result<()> {
+        self.drop_entry(handle, |entry| {
+            matches!(entry, AsyncHandle::FutureReader(_))
+        })
+        .map(drop)
+    }
+
+    /// `future.drop-writable`
+    pub fn future_drop_writable(&mut self, handle: u32) -> Result<()> {
+        self.drop_entry(handle, |entry| {
+            matches!(entry, AsyncHandle::FutureWriter(_))
+        })
+        .map(drop)
+    }
+
+    /// `error-context.new`
+    pub fn error_context_new(&mut self, message: &str) -> Result<u32> {
+        self.insert(AsyncHandle::ErrorContext(Arc::from(message)))
+    }
+
+    /// Debug message of an error context
+    pub fn error_context(&self, handle: u32) -> Result<Arc<str>> {
+        match self.get(handle)? {
+            AsyncHandle::ErrorContext(message) => Ok(message.clone()),
+            _ => Err(Error::runtime_type_mismatch(
+                "Handle is not an error context",
+            )),
+        }
+    }
+
+    /// `error-context.debug-message`
+    pub fn error_context_debug_message(&self, handle: u32) -> Result<String> {
+        Ok(self.error_context(handle)?.to_string())
+    }
+
+    /// `error-context.drop`
+    pub fn error_context_drop(&mut self, handle: u32) -> Result<()> {
+        self.drop_entry(handle, |entry| {
+            matches!(entry, AsyncHandle::ErrorContext(_))
+        })
+        .map(drop)
+    }
+
+    /// Add an async call made by the component, returning its subtask handle
+    pub fn subtask_new(&mut self, task: AsyncTask) -> Result<u32> {
+        self.insert(AsyncHandle::Subtask(task))
+    }
+
+    /// `subtask.drop`: release a subtask that returned
+    pub fn subtask_drop(&mut self, handle: u32) -> Result<()> {
+        match self.get(handle)? {
+            AsyncHandle::Subtask(task) if task.is_returned() => self.take(handle).map(drop),
+            AsyncHandle::Subtask(_) => {
+                Err(Error::runtime_trap("Subtask dropped before it returned"))
+            },
+            _ => Err(Error::runtime_type_mismatch("Handle is not a subtask")),
+        }
+    }
+
+    /// `waitable-set.new`
+    pub fn waitable_set_new(&mut self) -> Result<u32> {
+        self.insert(AsyncHandle::WaitableSet(Vec::new()))
+    }
+
+    /// `waitable-set.drop`
+    pub fn waitable_set_drop(&mut self, set: u32) -> Result<()> {
+        self.drop_entry(set, |entry| matches!(entry, AsyncHandle::WaitableSet(_)))
+            .map(drop)
+    }
+
+    /// `waitable.join`: move a waitable into `set`, or out of any set for
+    /// `None`
+    pub fn waitable_join(&mut self, waitable: u32, set: Option<u32>) -> Result<()> {
+        if !self.get(waitable)?.is_waitable() {
+            return Err(Error::runtime_type_mismatch("Handle is not waitable"));
+        }
+        if let Some(set) = set {
+            if !matches!(self.get(set)?, AsyncHandle::WaitableSet(_)) {
+                return Err(Error::runtime_type_mismatch("Handle is not a waitable set"));
+            }
+        }
+
+        for (handle, entry) in &mut self.entries {
+            if let AsyncHandle::WaitableSet(members) = entry {
+                members.retain(|member| *member != waitable);
+                if Some(*handle) == set {
+                    members.push(waitable);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// `task.wait`: the first member of `set` able to make progress
+    ///
+    /// Members are checked in join order. Returns [`Poll::Pending`] while none
+    /// of them can, in which case the calling task waits for the host or
+    /// another component to act on the other ends.
+    pub fn task_wait(&self, set: u32) -> Result<Poll<AsyncEvent>> {
+        let AsyncHandle::WaitableSet(members) = self.get(set)? else {
+            return Err(Error::runtime_type_mismatch("Handle is not a waitable set"));
+        };
+        for &handle in members {
+            if let Some(kind) = self.get(handle)?.event() {
+                return Ok(Poll::Ready(AsyncEvent { kind, handle }));
+            }
+        }
+        Ok(Poll::Pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes(values: &[u8]) -> Vec<ComponentValue> {
+        values.iter().copied().map(ComponentValue::U8).collect()
+    }
+
+    #[test]
+    fn test_host_stream_to_guest() {
+        let mut guest = AsyncHandleTable::new();
+        let (reader, writer) = stream_channel(Some(ComponentType::U8), 2).unwrap();
+        let handle = guest.insert(AsyncHandle::StreamReader(reader)).unwrap();
+        let set = guest.waitable_set_new().unwrap();
+        guest.waitable_join(handle, Some(set)).unwrap();
+
+        assert_eq!(guest.task_wait(set).unwrap(), Poll::Pending);
+        assert_eq!(guest.stream_read(handle, 8).unwrap(), Poll::Pending);
+
+        // Backpressure once the buffer is full
+        assert_eq!(
+            writer.write(&bytes(&[1, 2, 3])).unwrap(),
+            Poll::Ready(Some(2))
+        );
+        assert_eq!(writer.write(&bytes(&[3])).unwrap(), Poll::Pending);
+        assert!(writer.write(&[ComponentValue::S8(3)]).is_err());
+
+        assert_eq!(
+            guest.task_wait(set).unwrap(),
+            Poll::Ready(AsyncEvent {
+                kind: AsyncEventKind::StreamRead,
+                handle,
+            })
+        );
+        assert_eq!(
+            guest.stream_read(handle, 1).unwrap(),
+            Poll::Ready(Some(bytes(&[1])))
+        );
+        assert_eq!(writer.write(&bytes(&[3])).unwrap(), Poll::Ready(Some(1)));
+        drop(writer);
+        assert_eq!(
+            guest.stream_read(handle, 8).unwrap(),
+            Poll::Ready(Some(bytes(&[2, 3])))
+        );
+        assert_eq!(guest.stream_read(handle, 8).unwrap(), Poll::Ready(None));
+
+        guest.stream_drop_readable(handle).unwrap();
+        assert!(guest.stream_drop_readable(handle).is_err());
+        guest.waitable_set_drop(set).unwrap();
+        assert!(guest.is_empty());
+    }
+
+    #[test]
+    fn test_guest_stream_to_host() {
+        let mut guest = AsyncHandleTable::new().with_stream_capacity(4);
+        let (reader, writer) = guest.stream_new(Some(ComponentType::String)).unwrap();
+        assert!(guest.stream_write(reader, &[]).is_err());
+
+        // The export returns the readable end to the host
+        let AsyncHandle::StreamReader(host) = guest.take(reader).unwrap() else {
+            panic!("expected a readable stream end");
+        };
+        let hello = vec![ComponentValue::String("hello".into())];
+        assert_eq!(
+            guest.stream_write(writer, &hello).unwrap(),
+            Poll::Ready(Some(1))
+        );
+        assert_eq!(host.read(4), Poll::Ready(Some(hello.clone())));
+
+        drop(host);
+        assert_eq!(
+            guest.stream_write(writer, &hello).unwrap(),
+            Poll::Ready(None)
+        );
+    }
+
+    #[test]
+    fn test_future_and_subtask() {
+        let mut caller = AsyncHandleTable::new();
+        let mut callee = AsyncHandleTable::new();
+
+        let task = AsyncTask::new(vec![ComponentType::Future(Some(Box::new(
+            ComponentType::U32,
+        )))]);
+        let subtask = caller.subtask_new(task.clone()).unwrap();
+        let set = caller.waitable_set_new().unwrap();
+        caller.waitable_join(subtask, Some(set)).unwrap();
+        assert!(caller.subtask_drop(subtask).is_err());
+
+        // The callee returns the readable end of a future it completes later
+        let (reader, writer) = callee.future_new(Some(ComponentType::U32)).unwrap();
+        let result = callee.transfer_value(ComponentValue::Future(reader), &mut caller).unwrap();
+        assert!(task.task_return(vec![ComponentValue::U32(1)]).is_err());
+        task.task_return(vec![result.clone()]).unwrap();
+        assert!(task.task_return(vec![result.clone()]).is_err());
+
+        assert_eq!(
+            caller.task_wait(set).unwrap(),
+            Poll::Ready(AsyncEvent {
+                kind:   AsyncEventKind::Subtask,
+                handle: subtask,
+            })
+        );
+        assert_eq!(
+            task.poll_results().unwrap(),
+            Poll::Ready(vec![result.clone()])
+        );
+        caller.subtask_drop(subtask).unwrap();
+
+        let ComponentValue::Future(future) = result else {
+            panic!("expected a future");
+        };
+        caller.waitable_join(future, Some(set)).unwrap();
+        assert_eq!(caller.task_wait(set).unwrap(), Poll::Pending);
+        assert!(callee.future_write(writer, ComponentValue::S32(7)).is_err());
+        assert!(callee.future_write(writer, ComponentValue::U32(7)).unwrap());
+        assert_eq!(
+            caller.task_wait(set).unwrap().map(|event| event.kind),
+            Poll::Ready(AsyncEventKind::FutureRead)
+        );
+        assert_eq!(
+            caller.future_read(future).unwrap(),
+            Poll::Ready(Some(ComponentValue::U32(7)))
+        );
+        assert!(caller.future_read(future).is_err());
+    }
+
+    #[test]
+    fn test_error_context_is_copied() {
+        let mut from = AsyncHandleTable::new();
+        let mut to = AsyncHandleTable::new();
+        let error = from.error_context_new("disk full").unwrap();
+
+        let value =
+            ComponentValue::Result(Err(Some(Box::new(ComponentValue::ErrorContext(error)))));
+        let ComponentValue::Result(Err(Some(moved))) = from.transfer_value(value, &mut to).unwrap()
+        else {
+            panic!("expected an error result");
+        };
+        let ComponentValue::ErrorContext(copy) = *moved else {
+            panic!("expected an error context");
+        };
+        assert_eq!(to.error_context_debug_message(copy).unwrap(), "disk full");
+        from.error_context_drop(error).unwrap();
+        assert_eq!(to.error_context_debug_message(copy).unwrap(), "disk full");
+        assert!(to.transfer_value(ComponentValue::Stream(copy), &mut from).is_err());
+        assert_eq!(to.len(), 1);
+    }
+}
@@ -89,7 +89,7 @@ pub struct CanonicalABI {
     /// Verification level for canonical operations
     verification_level: VerificationLevel,
     /// Optional interceptor for canonical operations
-    interceptor:        Option<u32>, // Placeholder for LinkInterceptor
+    interceptor:        Option<Arc<LinkInterceptor>>,
     /// Metrics for canonical operations
     metrics:            CanonicalMetrics,
     /// String encoding options
@@ -181,18 +181,10 @@ pub fn lift(
         // Update metrics
         self.metrics.lift_count += 1;
 
-        // Intercept if necessary
-        if let Some(interceptor) = &self.interceptor {
-            for strategy in &interceptor.strategies {
-                if strategy.should_intercept_canonical() {
-                    if let Some(value) = strategy.intercept_lift(ty, addr, memory_bytes)? {
-                        // Convert the strategy's result into a Value
-                        // This is a placeholder - actual implementation would depend on the return
-                        // format
-                        return Ok(value); // Placeholder
-                    }
-                }
-            }
+        // Give interception strategies the first chance to provide the value
+        #[cfg(feature = "std")]
+        if let Some(data) = self.intercept_lift(ty, addr, memory_bytes)? {
+            return self.lift_intercepted(ty, addr, &data, resource_table, memory_bytes);
         }
 
         // Perform the lift operation
@@ -213,22 +205,56 @@ pub fn lower(
         // Update metrics
         self.metrics.lower_count += 1;
 
+        // Isolating strategies lower into a scratch copy and only commit it
+        // once the whole value has been written, so failures never leave a
+        // partially lowered value in guest memory
+        match memory_strategy {
+            MemoryStrategy::Isolated | MemoryStrategy::FullIsolation => {
+                let mut scratch = memory_bytes.to_vec();
+                self.lower_scalar(value, addr, &mut scratch)?;
+                memory_bytes.copy_from_slice(&scratch);
+                Ok(())
+            },
+            _ => self.lower_scalar(value, addr, memory_bytes),
+        }
+    }
+
+    /// Lower a scalar value, offering the result to interception strategies
+    fn lower_scalar(
+        &self,
+        value: &wrt_foundation::values::Value,
+        addr: u32,
+        memory_bytes: &mut [u8],
+    ) -> Result<()> {
         // Perform lower operation based on value type
-        if let Some(b) = value.as_bool() {
-            self.lower_bool(b, addr, memory_bytes)
+        let (ty, size) = if let Some(b) = value.as_bool() {
+            self.lower_bool(b, addr, memory_bytes)?;
+            (ValType::Bool, 1)
         } else if let Some(v) = value.as_i32() {
-            self.lower_s32(v, addr, memory_bytes)
+            self.lower_s32(v, addr, memory_bytes)?;
+            (ValType::S32, 4)
         } else if let Some(v) = value.as_i64() {
-            self.lower_s64(v, addr, memory_bytes)
+            self.lower_s64(v, addr, memory_bytes)?;
+            (ValType::S64, 8)
         } else if let Some(v) = value.as_f32() {
-            self.lower_f32(v, addr, memory_bytes)
+            self.lower_f32(v, addr, memory_bytes)?;
+            (ValType::F32, 4)
         } else if let Some(v) = value.as_f64() {
-            self.lower_f64(v, addr, memory_bytes)
+            self.lower_f64(v, addr, memory_bytes)?;
+            (ValType::F64, 8)
         } else {
             // For now, return a "not implemented" error
             // This simplified implementation focuses on basic types
-            Err(Error::unimplemented("Expected i32 for bool"))
+            return Err(Error::unimplemented("Expected i32 for bool"));
+        };
+
+        // Scalars are offered to strategies as their little-endian bytes
+        #[cfg(feature = "std")]
+        {
+            let data = memory_bytes[addr as usize..addr as usize + size].to_vec();
+            self.intercept_lower(&ty, &data, addr, memory_bytes)?;
         }
+        Ok(())
     }
 
     fn lift_value(
@@ -898,7 +924,23 @@ fn lower_char(&self, value: char, addr: u32, memory_bytes: &mut [u8]) -> Result<
 
     fn lower_string(&self, value: &str, addr: u32, memory_bytes: &mut [u8]) -> Result<()> {
         // Use the string encoding support
-        lower_string_with_options(value, addr, memory_bytes, &self.string_options)
+        lower_string_with_options(value, addr, memory_bytes, &self.string_options)?;
+
+        // Offer the encoded contents, which follow the length prefix, to
+        // interception strategies
+        #[cfg(feature = "std")]
+        {
+            let len = u32::from_le_bytes([
+                memory_bytes[addr as usize],
+                memory_bytes[addr as usize + 1],
+                memory_bytes[addr as usize + 2],
+                memory_bytes[addr as usize + 3],
+            ]) as usize;
+            let contents_addr = addr + 4;
+            let data = memory_bytes[contents_addr as usize..contents_addr as usize + len].to_vec();
+            self.intercept_lower(&ValType::String, &data, contents_addr, memory_bytes)?;
+        }
+        Ok(())
     }
 
     fn lower_list(
@@ -943,6 +985,14 @@ fn lower_list(
             current_addr += element_size;
         }
 
+        // Offer the flat element data to interception strategies
+        #[cfg(feature = "std")]
+        {
+            let list_ty = ValType::List(Box::new(inner_ty.clone()));
+            let data = memory_bytes[data_ptr as usize..(data_ptr + total_size) as usize].to_vec();
+            self.intercept_lower(&list_ty, &data, data_ptr, memory_bytes)?;
+        }
+
         // Update metrics
         self.metrics.lower_bytes += 8 + total_size as u64;
         self.metrics.max_lower_bytes = self.metrics.max_lower_bytes.max(8 + total_size as u64);
@@ -1115,11 +1165,120 @@ fn check_bounds(&self, addr: u32, size: u32, memory_bytes: &[u8]) -> Result<()>
     }
 
     /// Get strategy from interceptor
+    ///
+    /// The first strategy that expresses a preference for canonical
+    /// operations (handle 0) wins; otherwise the configured strategy is used.
     fn get_strategy_from_interceptor(&self) -> MemoryStrategy {
-        // Return default strategy for now
+        #[cfg(feature = "std")]
+        if let Some(interceptor) = &self.interceptor {
+            if let Some(strategy) = interceptor
+                .strategies
+                .iter()
+                .find_map(|strategy| strategy.get_memory_strategy(0))
+                .and_then(MemoryStrategy::from_u8)
+            {
+                return strategy;
+            }
+        }
         self.memory_strategy
     }
 
+    /// Ask interception strategies for replacement data of a lift
+    ///
+    /// Returns the data of the first strategy that handles the operation.
+    #[cfg(feature = "std")]
+    fn intercept_lift(
+        &self,
+        ty: &ValType,
+        addr: u32,
+        memory_bytes: &[u8],
+    ) -> Result<Option<Vec<u8>>> {
+        let Some(interceptor) = &self.interceptor else {
+            return Ok(None);
+        };
+        let mut intercept_ty = None;
+        for strategy in &interceptor.strategies {
+            if !strategy.should_intercept_canonical() {
+                continue;
+            }
+            if intercept_ty.is_none() {
+                let mut types = Vec::new();
+                intercept_ty = Some(interception_type(ty, &mut types)?.map(|ty| (ty, types)));
+            }
+            // Types strategies cannot be told about are not intercepted
+            let Some(Some((intercepted, types))) = &intercept_ty else {
+                return Ok(None);
+            };
+            if let Some(data) =
+                strategy.intercept_lift_with_types(intercepted, types, addr, memory_bytes)?
+            {
+                return Ok(Some(data));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Offer lowered data to interception strategies
+    ///
+    /// Returns true if a strategy handled the operation by writing its own
+    /// data at `addr`.
+    #[cfg(feature = "std")]
+    fn intercept_lower(
+        &self,
+        ty: &ValType,
+        data: &[u8],
+        addr: u32,
+        memory_bytes: &mut [u8],
+    ) -> Result<bool> {
+        let Some(interceptor) = &self.interceptor else {
+            return Ok(false);
+        };
+        let mut intercept_ty = None;
+        for strategy in &interceptor.strategies {
+            if !strategy.should_intercept_canonical() {
+                continue;
+            }
+            if intercept_ty.is_none() {
+                let mut types = Vec::new();
+                intercept_ty = Some(interception_type(ty, &mut types)?.map(|ty| (ty, types)));
+            }
+            // Types strategies cannot be told about are not intercepted
+            let Some(Some((intercepted, types))) = &intercept_ty else {
+                return Ok(false);
+            };
+            if strategy.intercept_lower_with_types(intercepted, types, data, addr, memory_bytes)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Turn data returned by an interception strategy into a value
+    ///
+    /// Strings are returned as their encoded contents. All other types are
+    /// returned as their flat representation, which replaces the original
+    /// bytes at `addr` in a private copy of memory before lifting, so that
+    /// nested pointers still resolve.
+    #[cfg(feature = "std")]
+    fn lift_intercepted(
+        &self,
+        ty: &ValType,
+        addr: u32,
+        data: &[u8],
+        resource_table: &ResourceTable,
+        memory_bytes: &[u8],
+    ) -> Result<Value> {
+        if let ValType::String = ty {
+            let string = crate::string_encoding::decode_string(data, self.string_options.encoding)?;
+            return Ok(Value::String(string));
+        }
+
+        self.check_bounds(addr, data.len() as u32, memory_bytes)?;
+        let mut overlay = memory_bytes.to_vec();
+        overlay[addr as usize..addr as usize + data.len()].copy_from_slice(data);
+        self.lift_value(ty, addr, resource_table, &overlay)
+    }
+
     // SIMD-Optimized Bulk Operations for Performance Enhancement
 
     /// Bulk lower operation for arrays of i32 values using SIMD when available
@@ -1313,6 +1472,101 @@ pub fn update_bulk_metrics(
     }
 }
 
+/// Value type in the representation used by interception strategies
+#[cfg(feature = "std")]
+type InterceptionType = FoundationValType<wrt_foundation::safe_memory::NoStdProvider<64>>;
+
+/// Convert a component value type into the type representation used by
+/// interception strategies
+///
+/// The element, member and payload types are appended to `types`, which the
+/// type references of the converted type index. Returns `None` for types the
+/// representation cannot hold: records, variants, enums and flags, whose
+/// names do not fit it, tuples of more than 16 members, and streams and
+/// futures.
+#[cfg(feature = "std")]
+fn interception_type(
+    ty: &ValType,
+    types: &mut Vec<InterceptionType>,
+) -> Result<Option<InterceptionType>> {
+    use wrt_foundation::{
+        bounded::BoundedVec,
+        budget_aware_provider::CrateId as BudgetCrateId,
+        safe_managed_alloc,
+    };
+
+    Ok(Some(match ty {
+        ValType::Bool => FoundationValType::Bool,
+        ValType::S8 => FoundationValType::S8,
+        ValType::U8 => FoundationValType::U8,
+        ValType::S16 => FoundationValType::S16,
+        ValType::U16 => FoundationValType::U16,
+        ValType::S32 => FoundationValType::S32,
+        ValType::U32 => FoundationValType::U32,
+        ValType::S64 => FoundationValType::S64,
+        ValType::U64 => FoundationValType::U64,
+        ValType::F32 => FoundationValType::F32,
+        ValType::F64 => FoundationValType::F64,
+        ValType::Char => FoundationValType::Char,
+        ValType::String => FoundationValType::String,
+        ValType::List(element) => match interception_type_ref(element, types)? {
+            Some(element) => FoundationValType::List(element),
+            None => return Ok(None),
+        },
+        ValType::Option(payload) => match interception_type_ref(payload, types)? {
+            Some(payload) => FoundationValType::Option(payload),
+            None => return Ok(None),
+        },
+        ValType::Result(result) => {
+            let mut payload = |ty: &Option<Box<ValType>>| match ty {
+                Some(ty) => interception_type_ref(ty, types).map(|r| r.map(Some)),
+                None => Ok(Some(None)),
+            };
+            let (Some(ok), Some(err)) = (payload(&result.ok)?, payload(&result.err)?) else {
+                return Ok(None);
+            };
+            FoundationValType::Result { ok, err }
+        },
+        ValType::Tuple(tuple) => {
+            let provider = safe_managed_alloc!(64, BudgetCrateId::Component)?;
+            let mut members = BoundedVec::new(provider)?;
+            for member in &tuple.types {
+                let Some(member) = interception_type_ref(member, types)? else {
+                    return Ok(None);
+                };
+                if members.push(member).is_err() {
+                    return Ok(None);
+                }
+            }
+            FoundationValType::Tuple(members)
+        },
+        ValType::Own(idx) => FoundationValType::Own(*idx),
+        ValType::Borrow(idx) => FoundationValType::Borrow(*idx),
+        ValType::Record(_)
+        | ValType::Variant(_)
+        | ValType::Enum(_)
+        | ValType::Flags(_)
+        | ValType::Stream(_)
+        | ValType::Future(_) => return Ok(None),
+    }))
+}
+
+/// Convert a type nested in another one with [`interception_type`] and
+/// append it to `types`, returning its reference
+#[cfg(feature = "std")]
+fn interception_type_ref(
+    ty: &ValType,
+    types: &mut Vec<InterceptionType>,
+) -> Result<Option<wrt_foundation::component_value::ValTypeRef>> {
+    let Some(nested) = interception_type(ty, types)? else {
+        return Ok(None);
+    };
+    let index = u32::try_from(types.len())
+        .map_err(|_| Error::capacity_limit_exceeded("Type too deeply nested for interception"))?;
+    types.push(nested);
+    Ok(Some(wrt_foundation::component_value::ValTypeRef(index)))
+}
+
 /// Comprehensive Value handling for canonical ABI compatibility
 ///
 /// This function ensures proper conversion between the different Value
@@ -1805,3 +2059,253 @@ pub fn convert_value_for_type(
         _ => Ok(value.clone()),
     }
 }
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use wrt_foundation::component_value::ValTypeRef;
+    use wrt_intercept::LinkInterceptorStrategy;
+
+    use super::*;
+    use crate::types::{
+        Field,
+        Record,
+        Tuple,
+    };
+
+    /// Type, nested types and data of an operation offered to [`Recorder`]
+    type Offered = (InterceptionType, Vec<InterceptionType>, Vec<u8>);
+
+    /// Canonical strategy recording the operations offered to it, which
+    /// replaces lifted values with fixed data
+    struct Recorder {
+        replacement: Option<Vec<u8>>,
+        offered:     Arc<Mutex<Vec<Offered>>>,
+    }
+
+    impl LinkInterceptorStrategy for Recorder {
+        fn before_call(
+            &self,
+            _source: &str,
+            _target: &str,
+            _function: &str,
+            args: &[Value],
+        ) -> Result<Vec<Value>> {
+            Ok(args.to_vec())
+        }
+
+        fn after_call(
+            &self,
+            _source: &str,
+            _target: &str,
+            _function: &str,
+            _args: &[Value],
+            result: Result<Vec<Value>>,
+        ) -> Result<Vec<Value>> {
+            result
+        }
+
+        fn should_intercept_canonical(&self) -> bool {
+            true
+        }
+
+        fn intercept_lift_with_types(
+            &self,
+            ty: &InterceptionType,
+            types: &[InterceptionType],
+            _addr: u32,
+            _memory_bytes: &[u8],
+        ) -> Result<Option<Vec<u8>>> {
+            self.offered.lock().unwrap().push((ty.clone(), types.to_vec(), Vec::new()));
+            Ok(self.replacement.clone())
+        }
+
+        fn intercept_lower_with_types(
+            &self,
+            value_type: &InterceptionType,
+            types: &[InterceptionType],
+            value_data: &[u8],
+            _addr: u32,
+            _memory_bytes: &mut [u8],
+        ) -> Result<bool> {
+            self.offered.lock().unwrap().push((
+                value_type.clone(),
+                types.to_vec(),
+                value_data.to_vec(),
+            ));
+            Ok(false)
+        }
+
+        fn clone_strategy(&self) -> Arc<dyn LinkInterceptorStrategy> {
+            Arc::new(Self {
+                replacement: self.replacement.clone(),
+                offered:     self.offered.clone(),
+            })
+        }
+    }
+
+    /// A canonical ABI intercepted by a [`Recorder`], and the operations
+    /// offered to it
+    fn intercepted(replacement: Option<Vec<u8>>) -> (CanonicalABI, Arc<Mutex<Vec<Offered>>>) {
+        let offered = Arc::new(Mutex::new(Vec::new()));
+        let mut interceptor = LinkInterceptor::new("recorder");
+        interceptor.add_strategy(Arc::new(Recorder {
+            replacement,
+            offered: offered.clone(),
+        }));
+        (
+            CanonicalABI::new().with_interceptor(Arc::new(interceptor)),
+            offered,
+        )
+    }
+
+    fn write_u32(memory: &mut [u8], addr: usize, value: u32) {
+        memory[addr..addr + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    #[test]
+    fn test_intercepts_string() -> Result<()> {
+        let table = ResourceTable::new(16);
+        let mut memory = vec![0u8; 64];
+        write_u32(&mut memory, 0, 5);
+        memory[4..9].copy_from_slice(b"hello");
+
+        let (abi, offered) = intercepted(Some(b"howdy".to_vec()));
+        let lifted = abi.lift(&ValType::String, 0, &table, &memory)?;
+        assert!(matches!(lifted, Value::String(ref s) if s == "howdy"));
+
+        abi.lower_value(
+            &Value::String("hi".to_string()),
+            &ValType::String,
+            16,
+            &table,
+            &mut memory,
+        )?;
+        let offered = offered.lock().unwrap();
+        assert_eq!(
+            *offered,
+            vec![
+                (FoundationValType::String, Vec::new(), Vec::new()),
+                (FoundationValType::String, Vec::new(), b"hi".to_vec()),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_intercepts_list_with_element_type() -> Result<()> {
+        let table = ResourceTable::new(16);
+        let list = ValType::List(Box::new(ValType::U32));
+        let mut memory = vec![0u8; 64];
+        write_u32(&mut memory, 0, 8);
+        write_u32(&mut memory, 4, 2);
+        write_u32(&mut memory, 8, 1);
+        write_u32(&mut memory, 12, 2);
+        write_u32(&mut memory, 16, 7);
+
+        // The replacement (ptr, len) pair selects the last element only
+        let replacement = [16u32.to_le_bytes(), 1u32.to_le_bytes()].concat();
+        let (abi, offered) = intercepted(Some(replacement.clone()));
+        let lifted = abi.lift(&list, 0, &table, &memory)?;
+
+        let mut replaced = memory.clone();
+        replaced[..8].copy_from_slice(&replacement);
+        assert_eq!(
+            lifted,
+            CanonicalABI::new().lift(&list, 0, &table, &replaced)?
+        );
+
+        let bytes = ValType::List(Box::new(ValType::U8));
+        let values = vec![Box::new(Value::I32(1)), Box::new(Value::I32(2))];
+        abi.lower_value(&Value::List(values), &bytes, 32, &table, &mut memory)?;
+
+        let offered = offered.lock().unwrap();
+        assert_eq!(
+            *offered,
+            vec![
+                (
+                    FoundationValType::List(ValTypeRef(0)),
+                    vec![FoundationValType::U32],
+                    Vec::new()
+                ),
+                (
+                    FoundationValType::List(ValTypeRef(0)),
+                    vec![FoundationValType::U8],
+                    vec![1, 2]
+                ),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_intercepts_tuple_with_member_types() -> Result<()> {
+        let table = ResourceTable::new(16);
+        let tuple = ValType::Tuple(Tuple {
+            types: vec![ValType::U8, ValType::List(Box::new(ValType::U32))],
+        });
+        let mut memory = vec![0u8; 64];
+        memory[0] = 5;
+        write_u32(&mut memory, 1, 16);
+        write_u32(&mut memory, 5, 1);
+        write_u32(&mut memory, 16, 9);
+
+        let mut replacement = vec![6];
+        replacement.extend_from_slice(&16u32.to_le_bytes());
+        replacement.extend_from_slice(&0u32.to_le_bytes());
+        let (abi, offered) = intercepted(Some(replacement.clone()));
+        let lifted = abi.lift(&tuple, 0, &table, &memory)?;
+
+        let mut replaced = memory.clone();
+        replaced[..replacement.len()].copy_from_slice(&replacement);
+        assert_eq!(
+            lifted,
+            CanonicalABI::new().lift(&tuple, 0, &table, &replaced)?
+        );
+        assert_ne!(
+            lifted,
+            CanonicalABI::new().lift(&tuple, 0, &table, &memory)?
+        );
+
+        let offered = offered.lock().unwrap();
+        assert_eq!(offered.len(), 1);
+        let (FoundationValType::Tuple(members), types, _) = &offered[0] else {
+            panic!("tuple offered as {:?}", offered[0].0);
+        };
+        assert_eq!(
+            members.iter().collect::<Vec<_>>(),
+            vec![ValTypeRef(0), ValTypeRef(2)]
+        );
+        assert_eq!(
+            *types,
+            vec![
+                FoundationValType::U8,
+                FoundationValType::U32,
+                FoundationValType::List(ValTypeRef(1)),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_record_is_not_intercepted() -> Result<()> {
+        let table = ResourceTable::new(16);
+        let record = ValType::Record(Record {
+            fields: vec![Field {
+                name: "id".to_string(),
+                ty:   ValType::U32,
+            }],
+        });
+        let mut memory = vec![0u8; 16];
+        write_u32(&mut memory, 0, 42);
+
+        // Record field names do not fit the interception type representation,
+        // so the record lifts normally even though the strategy would replace it
+        let (abi, offered) = intercepted(Some(vec![0; 4]));
+        assert_eq!(
+            abi.lift(&record, 0, &table, &memory)?,
+            CanonicalABI::new().lift(&record, 0, &table, &memory)?
+        );
+        assert!(offered.lock().unwrap().is_empty());
+        Ok(())
+    }
+}
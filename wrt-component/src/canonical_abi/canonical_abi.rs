@@ -8,12 +8,17 @@
 //! # Features
 //!
 //! - **Complete Type Support**: All Canonical ABI types including primitives,
-//!   strings, lists, records, variants, options, results, and flags
+//!   strings, lists, records, tuples, variants, enums, options, results, flags
+//!   and resource handles
+//! - **String Encodings**: UTF-8, UTF-16 and latin1+utf16 as selected by the
+//!   `string-encoding` canonical option
+//! - **Guest Allocation**: Strings and lists are placed in linear memory
+//!   through the component's `realloc` function ([`CanonicalRealloc`])
+//! - **Flat Values**: Lifting from and lowering to core WebAssembly values,
+//!   including spilling to memory beyond the flat parameter limit
 //! - **Cross-Environment Compatibility**: Works in std, no_std+alloc, and pure
 //!   no_std
-//! - **Memory Safety**: Comprehensive bounds checking and validation
-//! - **Performance Optimized**: Efficient lifting and lowering operations
-//! - **Error Handling**: Detailed error reporting for invalid operations
+//! - **Memory Safety**: Comprehensive bounds, alignment and length checks
 //!
 //! # Core Operations
 //!
@@ -22,6 +27,9 @@
 //! - **Lifting**: Convert core WebAssembly values to component model values
 //! - **Lowering**: Convert component model values to core WebAssembly values
 //!
+//! Lowering is type-directed: the [`ComponentType`] decides the discriminant
+//! of a variant, the encoding of a string and the layout of every aggregate.
+//!
 //! # Example
 //!
 //! ```no_run
@@ -29,16 +37,24 @@
 //!     CanonicalABI,
 //!     ComponentType,
 //!     ComponentValue,
+//!     SimpleMemory,
 //! };
 //!
-//! // Create a canonical ABI instance
 //! let abi = CanonicalABI::new();
+//! let mut memory = SimpleMemory::new(65536);
 //!
-//! // Lift an i32 from memory
-//! let value = abi.lift_i32(&memory, 0)?;
+//! // A bump allocator standing in for the guest's `cabi_realloc`
+//! let mut next: u32 = 1024;
+//! let mut realloc = |_old_ptr, _old_size, align: u32, size: u32| {
+//!     let ptr = next.div_ceil(align) * align;
+//!     next = ptr + size;
+//!     Ok(ptr)
+//! };
 //!
-//! // Lower a string to memory
-//! abi.lower_string(&mut memory, 100, "hello")?;
+//! let value = ComponentValue::String("hello".into());
+//! abi.lower(&mut memory, &mut realloc, &ComponentType::String, &value, 0)?;
+//! assert_eq!(abi.lift(&memory, &ComponentType::String, 0)?, value);
+//! # Ok::<(), wrt_error::Error>(())
 //! ```
 
 // Cross-environment imports
@@ -47,15 +63,14 @@
 
 #[cfg(all(not(feature = "std")))]
 use alloc::{
-    collections::BTreeMap as HashMap,
     string::String,
     vec,
     vec::Vec,
 };
 #[cfg(feature = "std")]
 use std::{
-    collections::HashMap,
     string::String,
+    sync::Arc,
     vec::Vec,
 };
 
@@ -63,37 +78,53 @@
 // #[cfg(not(any(feature = "std", )))]
 // use wrt_foundation::{BoundedString, BoundedVec, BoundedMap as HashMap};
 use wrt_error::{
-    codes,
     Error,
-    ErrorCategory,
     Result,
 };
-#[cfg(not(feature = "std"))]
-use wrt_foundation::safe_memory::NoStdProvider;
+use wrt_foundation::{
+    float_repr::{
+        FloatBits32,
+        FloatBits64,
+    },
+    types::ValueType as CoreType,
+    values::Value as CoreValue,
+};
+#[cfg(feature = "std")]
+use wrt_intercept::LinkInterceptor;
 
 // Import prelude for consistent type access
 use crate::prelude::*;
+#[cfg(feature = "std")]
+use crate::resources::MemoryStrategy;
 
 /// Maximum string length for safety (4MB)
-const MAX_STRING_LENGTH: usize = 4 * 1024 * 1024;
+pub(crate) const MAX_STRING_LENGTH: usize = 4 * 1024 * 1024;
 
-/// Maximum list length for safety  
-const MAX_LIST_LENGTH: usize = 1024 * 1024;
+/// Maximum list length for safety
+pub(crate) const MAX_LIST_LENGTH: usize = 1024 * 1024;
 
 /// Maximum record field count
 const MAX_RECORD_FIELDS: usize = 1024;
 
-/// Page size constant (64KB)
-const PAGE_SIZE: usize = 65536;
+/// Maximum number of flat core values passed as parameters before the
+/// parameters are spilled to linear memory
+pub const MAX_FLAT_PARAMS: usize = 16;
+
+/// Maximum number of flat core values returned before the results are
+/// spilled to linear memory
+pub const MAX_FLAT_RESULTS: usize = 1;
+
+/// Length bit marking a latin1+utf16 string as UTF-16 encoded
+const UTF16_TAG: u32 = 1 << 31;
 
 /// Component model value types as defined in the Canonical ABI
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ComponentType {
     /// Boolean type
     Bool,
     /// Signed 8-bit integer
     S8,
-    /// Unsigned 8-bit integer  
+    /// Unsigned 8-bit integer
     U8,
     /// Signed 16-bit integer
     S16,
@@ -131,6 +162,70 @@ pub enum ComponentType {
     Result(Option<Box<ComponentType>>, Option<Box<ComponentType>>),
     /// Flags (bitset)
     Flags(Vec<String>),
+    /// Owned handle to a resource of the given resource type
+    Own(u32),
+    /// Borrowed handle to a resource of the given resource type
+    Borrow(u32),
+    /// Handle to an end of a stream of the given element type
+    Stream(Option<Box<ComponentType>>),
+    /// Handle to an end of a future of the given value type
+    Future(Option<Box<ComponentType>>),
+    /// Handle to an error context
+    ErrorContext,
+}
+
+impl ComponentType {
+    /// The zero value of the type
+    ///
+    /// Numbers are zero, strings, lists and flags empty, options `none`,
+    /// results `ok` and variants and enums their first case, with payloads
+    /// and fields built the same way. Handles have no zero value, so types
+    /// containing one return `None`, as do variants and enums without cases.
+    pub fn default_value(&self) -> Option<ComponentValue> {
+        let payload = |ty: Option<&ComponentType>| match ty {
+            Some(ty) => ty.default_value().map(|value| Some(Box::new(value))),
+            None => Some(None),
+        };
+
+        Some(match self {
+            Self::Bool => ComponentValue::Bool(false),
+            Self::S8 => ComponentValue::S8(0),
+            Self::U8 => ComponentValue::U8(0),
+            Self::S16 => ComponentValue::S16(0),
+            Self::U16 => ComponentValue::U16(0),
+            Self::S32 => ComponentValue::S32(0),
+            Self::U32 => ComponentValue::U32(0),
+            Self::S64 => ComponentValue::S64(0),
+            Self::U64 => ComponentValue::U64(0),
+            Self::F32 => ComponentValue::F32(0.0),
+            Self::F64 => ComponentValue::F64(0.0),
+            Self::Char => ComponentValue::Char('\0'),
+            Self::String => ComponentValue::String(String::new()),
+            Self::List(_) => ComponentValue::List(Vec::new()),
+            Self::Record(fields) => ComponentValue::Record(
+                fields
+                    .iter()
+                    .map(|(name, ty)| Some((name.clone(), ty.default_value()?)))
+                    .collect::<Option<_>>()?,
+            ),
+            Self::Tuple(types) => ComponentValue::Tuple(
+                types.iter().map(ComponentType::default_value).collect::<Option<_>>()?,
+            ),
+            Self::Variant(cases) => {
+                let (name, ty) = cases.first()?;
+                ComponentValue::Variant(name.clone(), payload(ty.as_ref())?)
+            },
+            Self::Enum(cases) => ComponentValue::Enum(cases.first()?.clone()),
+            Self::Option(_) => ComponentValue::Option(None),
+            Self::Result(ok, _) => ComponentValue::Result(Ok(payload(ok.as_deref())?)),
+            Self::Flags(_) => ComponentValue::Flags(Vec::new()),
+            Self::Own(_)
+            | Self::Borrow(_)
+            | Self::Stream(_)
+            | Self::Future(_)
+            | Self::ErrorContext => return None,
+        })
+    }
 }
 
 /// Component model values as defined in the Canonical ABI
@@ -174,10 +269,20 @@ pub enum ComponentValue {
     Enum(String),
     /// Optional value
     Option(Option<Box<ComponentValue>>),
-    /// Result value  
+    /// Result value
     Result(core::result::Result<Option<Box<ComponentValue>>, Option<Box<ComponentValue>>>),
     /// Flags (bitset)
     Flags(Vec<String>),
+    /// Owned resource handle (index into the resource table)
+    Own(u32),
+    /// Borrowed resource handle (index into the resource table)
+    Borrow(u32),
+    /// Stream end handle (index into the async handle table)
+    Stream(u32),
+    /// Future end handle (index into the async handle table)
+    Future(u32),
+    /// Error context handle (index into the async handle table)
+    ErrorContext(u32),
 }
 
 /// Memory interface for canonical ABI operations
@@ -295,23 +400,134 @@ fn size(&self) -> u32 {
     }
 }
 
+/// Memory view that buffers writes until they are committed
+///
+/// Used by isolating memory strategies so that a failed lowering never
+/// leaves a partially written value in guest memory.
+#[cfg(feature = "std")]
+struct StagedMemory<'a, M: CanonicalMemory> {
+    memory:  &'a mut M,
+    pending: Vec<(u32, Vec<u8>)>,
+}
+
+#[cfg(feature = "std")]
+impl<'a, M: CanonicalMemory> StagedMemory<'a, M> {
+    fn new(memory: &'a mut M) -> Self {
+        Self {
+            memory,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Apply all buffered writes to the underlying memory
+    fn commit(self) -> Result<()> {
+        for (offset, data) in &self.pending {
+            self.memory.write_bytes(*offset, data)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<M: CanonicalMemory> CanonicalMemory for StagedMemory<'_, M> {
+    fn read_bytes(&self, offset: u32, len: u32) -> Result<Vec<u8>> {
+        let mut bytes = self.memory.read_bytes(offset, len)?;
+        let start = u64::from(offset);
+        let end = start + u64::from(len);
+        for (write_offset, data) in &self.pending {
+            let write_start = u64::from(*write_offset);
+            let write_end = write_start + data.len() as u64;
+            for pos in start.max(write_start)..end.min(write_end) {
+                bytes[(pos - start) as usize] = data[(pos - write_start) as usize];
+            }
+        }
+        Ok(bytes)
+    }
+
+    fn write_bytes(&mut self, offset: u32, data: &[u8]) -> Result<()> {
+        if u64::from(offset) + data.len() as u64 > u64::from(self.memory.size()) {
+            return Err(Error::memory_out_of_bounds("Memory write out of bounds"));
+        }
+        self.pending.push((offset, data.to_vec()));
+        Ok(())
+    }
+
+    fn size(&self) -> u32 {
+        self.memory.size()
+    }
+}
+
+/// Guest allocator used to place strings and lists in linear memory
+///
+/// Implementations call the `realloc` function named in the canonical
+/// options. Any `FnMut(old_ptr, old_size, align, new_size) -> Result<u32>`
+/// closure can be used directly.
+pub trait CanonicalRealloc {
+    /// Reallocate `old_size` bytes at `old_ptr` to `new_size` bytes with the
+    /// given alignment, returning the new pointer
+    fn realloc(&mut self, old_ptr: u32, old_size: u32, align: u32, new_size: u32) -> Result<u32>;
+}
+
+impl<F> CanonicalRealloc for F
+where
+    F: FnMut(u32, u32, u32, u32) -> Result<u32>,
+{
+    fn realloc(&mut self, old_ptr: u32, old_size: u32, align: u32, new_size: u32) -> Result<u32> {
+        self(old_ptr, old_size, align, new_size)
+    }
+}
+
+/// Allocator for canonical options without a `realloc` function
+///
+/// Lowering a string or list with it fails, as required by the
+/// specification.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoRealloc;
+
+impl CanonicalRealloc for NoRealloc {
+    fn realloc(&mut self, _: u32, _: u32, _: u32, _: u32) -> Result<u32> {
+        Err(Error::validation_error(
+            "Lowering strings and lists requires a realloc function",
+        ))
+    }
+}
+
 /// Canonical ABI implementation
-#[derive(Debug)]
 pub struct CanonicalABI {
-    /// String encoding (always UTF-8 for now)
-    string_encoding: StringEncoding,
+    /// String encoding of the canonical options
+    pub(crate) string_encoding: StringEncoding,
     /// Binary std/no_std choice
-    alignment:       u32,
+    pub(crate) alignment:       u32,
+    /// Memory strategy used when no interceptor expresses a preference
+    #[cfg(feature = "std")]
+    memory_strategy:            MemoryStrategy,
+    /// Optional interceptor consulted for the memory strategy
+    #[cfg(feature = "std")]
+    interceptor:                Option<Arc<LinkInterceptor>>,
+}
+
+impl core::fmt::Debug for CanonicalABI {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut debug = f.debug_struct("CanonicalABI");
+        debug.field("string_encoding", &self.string_encoding);
+        debug.field("alignment", &self.alignment);
+        #[cfg(feature = "std")]
+        debug
+            .field("memory_strategy", &self.memory_strategy)
+            .field("interceptor", &self.interceptor.is_some());
+        debug.finish()
+    }
 }
 
 /// String encoding options
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StringEncoding {
     /// UTF-8 encoding (default)
     Utf8,
-    /// UTF-16 encoding
+    /// UTF-16 (little-endian) encoding, length counted in code units
     Utf16,
-    /// Latin-1 encoding
+    /// The specification's `latin1+utf16` encoding: Latin-1 when every
+    /// character fits, otherwise UTF-16 with the high length bit set
     Latin1,
 }
 
@@ -327,12 +543,29 @@ fn default() -> Self {
     }
 }
 
+/// Memory layout of a variant-like type
+#[derive(Debug, Clone, Copy)]
+struct VariantLayout {
+    /// Size of the discriminant in bytes
+    discriminant_size: u32,
+    /// Offset of the payload from the start of the value
+    payload_offset:    u32,
+    /// Alignment of the whole value
+    alignment:         u32,
+    /// Size of the whole value
+    size:              u32,
+}
+
 impl CanonicalABI {
     /// Create a new Canonical ABI instance
     pub fn new() -> Self {
         Self {
             string_encoding: StringEncoding::Utf8,
-            alignment:       1,
+            alignment: 1,
+            #[cfg(feature = "std")]
+            memory_strategy: MemoryStrategy::default(),
+            #[cfg(feature = "std")]
+            interceptor: None,
         }
     }
 
@@ -348,55 +581,61 @@ pub fn with_alignment(mut self, alignment: u32) -> Self {
         self
     }
 
+    /// Set the memory strategy used when no interceptor expresses a preference
+    #[cfg(feature = "std")]
+    pub fn with_memory_strategy(mut self, strategy: MemoryStrategy) -> Self {
+        self.memory_strategy = strategy;
+        self
+    }
+
+    /// Set the interceptor consulted for the memory strategy
+    #[cfg(feature = "std")]
+    pub fn with_interceptor(mut self, interceptor: Arc<LinkInterceptor>) -> Self {
+        self.interceptor = Some(interceptor);
+        self
+    }
+
+    /// Get the memory strategy for canonical operations
+    ///
+    /// The first interception strategy that expresses a preference for
+    /// canonical operations (handle 0) wins; otherwise the configured
+    /// strategy is used.
+    #[cfg(feature = "std")]
+    pub fn memory_strategy(&self) -> MemoryStrategy {
+        self.interceptor
+            .as_ref()
+            .and_then(|interceptor| {
+                interceptor
+                    .strategies
+                    .iter()
+                    .find_map(|strategy| strategy.get_memory_strategy(0))
+            })
+            .and_then(MemoryStrategy::from_u8)
+            .unwrap_or(self.memory_strategy)
+    }
+
     /// Calculate the size of a type in memory
     pub fn size_of(&self, ty: &ComponentType) -> Result<u32> {
         match ty {
             ComponentType::Bool | ComponentType::S8 | ComponentType::U8 => Ok(1),
             ComponentType::S16 | ComponentType::U16 => Ok(2),
-            ComponentType::S32 | ComponentType::U32 | ComponentType::F32 | ComponentType::Char => {
-                Ok(4)
-            },
+            ComponentType::S32
+            | ComponentType::U32
+            | ComponentType::F32
+            | ComponentType::Char
+            | ComponentType::Own(_)
+            | ComponentType::Borrow(_)
+            | ComponentType::Stream(_)
+            | ComponentType::Future(_)
+            | ComponentType::ErrorContext => Ok(4),
             ComponentType::S64 | ComponentType::U64 | ComponentType::F64 => Ok(8),
             ComponentType::String | ComponentType::List(_) => Ok(8), // ptr + len
-            ComponentType::Option(inner) => {
-                let inner_size = self.size_of(inner)?;
-                Ok(inner_size + 1) // discriminant + optional value
-            },
-            ComponentType::Result(ok, err) => {
-                let ok_size = if let Some(ok_ty) = ok { self.size_of(ok_ty)? } else { 0 };
-                let err_size = if let Some(err_ty) = err { self.size_of(err_ty)? } else { 0 };
-                Ok(4 + ok_size.max(err_size)) // discriminant + max(ok, err)
-            },
             ComponentType::Record(fields) => {
-                let mut total_size = 0;
-                for (_, field_ty) in fields {
-                    total_size += self.size_of(field_ty)?;
-                }
-                Ok(total_size)
-            },
-            ComponentType::Tuple(types) => {
-                let mut total_size = 0;
-                for ty in types {
-                    total_size += self.size_of(ty)?;
-                }
-                Ok(total_size)
-            },
-            ComponentType::Variant(cases) => {
-                let mut max_payload_size = 0;
-                for (_, payload_ty) in cases {
-                    if let Some(ty) = payload_ty {
-                        max_payload_size = max_payload_size.max(self.size_of(ty)?);
-                    }
-                }
-                Ok(4 + max_payload_size) // discriminant + max payload
-            },
-            ComponentType::Enum(_) => Ok(4), // discriminant only
-            ComponentType::Flags(flags) => {
-                // Each flag is 1 bit, round up to byte boundary
-                let bit_count = flags.len();
-                let byte_count = (bit_count + 7) / 8;
-                Ok(byte_count as u32)
+                self.fields_layout(fields.iter().map(|(_, ty)| ty)).map(|(size, _)| size)
             },
+            ComponentType::Tuple(types) => self.fields_layout(types.iter()).map(|(size, _)| size),
+            ComponentType::Flags(flags) => Ok(flags_size(flags.len())),
+            _ => self.variant_layout(ty).map(|layout| layout.size),
         }
     }
 
@@ -405,36 +644,73 @@ pub fn align_of(&self, ty: &ComponentType) -> Result<u32> {
         match ty {
             ComponentType::Bool | ComponentType::S8 | ComponentType::U8 => Ok(1),
             ComponentType::S16 | ComponentType::U16 => Ok(2),
-            ComponentType::S32 | ComponentType::U32 | ComponentType::F32 | ComponentType::Char => {
-                Ok(4)
-            },
+            ComponentType::S32
+            | ComponentType::U32
+            | ComponentType::F32
+            | ComponentType::Char
+            | ComponentType::Own(_)
+            | ComponentType::Borrow(_)
+            | ComponentType::Stream(_)
+            | ComponentType::Future(_)
+            | ComponentType::ErrorContext => Ok(4),
             ComponentType::S64 | ComponentType::U64 | ComponentType::F64 => Ok(8),
             ComponentType::String | ComponentType::List(_) => Ok(4), // pointer alignment
-            ComponentType::Option(inner) => self.align_of(inner),
-            ComponentType::Result(ok, err) => {
-                let ok_align = if let Some(ok_ty) = ok { self.align_of(ok_ty)? } else { 1 };
-                let err_align = if let Some(err_ty) = err { self.align_of(err_ty)? } else { 1 };
-                Ok(4.max(ok_align).max(err_align))
-            },
             ComponentType::Record(fields) => {
-                let mut max_align = 1;
-                for (_, field_ty) in fields {
-                    max_align = max_align.max(self.align_of(field_ty)?);
-                }
-                Ok(max_align)
-            },
-            ComponentType::Tuple(types) => {
-                let mut max_align = 1;
-                for ty in types {
-                    max_align = max_align.max(self.align_of(ty)?);
-                }
-                Ok(max_align)
+                self.fields_layout(fields.iter().map(|(_, ty)| ty)).map(|(_, align)| align)
             },
-            ComponentType::Variant(_) | ComponentType::Enum(_) => Ok(4),
-            ComponentType::Flags(_) => Ok(1),
+            ComponentType::Tuple(types) => self.fields_layout(types.iter()).map(|(_, align)| align),
+            ComponentType::Flags(flags) => Ok(flags_size(flags.len()).clamp(1, 4)),
+            _ => self.variant_layout(ty).map(|layout| layout.alignment),
         }
     }
 
+    /// Size and alignment of a sequence of fields laid out in order
+    fn fields_layout<'t>(
+        &self,
+        types: impl Iterator<Item = &'t ComponentType>,
+    ) -> Result<(u32, u32)> {
+        let mut size = 0;
+        let mut alignment = 1;
+        for ty in types {
+            let field_align = self.align_of(ty)?;
+            size = align_to(size, field_align)
+                .checked_add(self.size_of(ty)?)
+                .ok_or_else(|| Error::validation_error("Component type too large"))?;
+            alignment = alignment.max(field_align);
+        }
+        Ok((align_to(size, alignment), alignment))
+    }
+
+    /// Layout of a variant, enum, option or result type
+    fn variant_layout(&self, ty: &ComponentType) -> Result<VariantLayout> {
+        self.case_layout(&case_payloads(ty)?)
+    }
+
+    /// Layout of a variant-like type given the payload type of each case
+    fn case_layout(&self, payloads: &[Option<&ComponentType>]) -> Result<VariantLayout> {
+        let mut payload_size = 0;
+        let mut payload_align = 1;
+        for payload in payloads.iter().flatten() {
+            payload_size = payload_size.max(self.size_of(payload)?);
+            payload_align = payload_align.max(self.align_of(payload)?);
+        }
+
+        let discriminant_size = discriminant_size(payloads.len());
+        let alignment = discriminant_size.max(payload_align);
+        let payload_offset = align_to(discriminant_size, payload_align);
+        let size = payload_offset
+            .checked_add(payload_size)
+            .map(|size| align_to(size, alignment))
+            .ok_or_else(|| Error::validation_error("Component type too large"))?;
+
+        Ok(VariantLayout {
+            discriminant_size,
+            payload_offset,
+            alignment,
+            size,
+        })
+    }
+
     // ==== LIFTING OPERATIONS ====
 
     /// Lift a value from memory
@@ -466,6 +742,13 @@ pub fn lift<M: CanonicalMemory>(
             ComponentType::Option(inner_ty) => self.lift_option(memory, inner_ty, offset),
             ComponentType::Result(ok_ty, err_ty) => self.lift_result(memory, ok_ty, err_ty, offset),
             ComponentType::Flags(flags) => self.lift_flags(memory, flags, offset),
+            ComponentType::Own(_) => Ok(ComponentValue::Own(memory.read_u32_le(offset)?)),
+            ComponentType::Borrow(_) => Ok(ComponentValue::Borrow(memory.read_u32_le(offset)?)),
+            ComponentType::Stream(_) => Ok(ComponentValue::Stream(memory.read_u32_le(offset)?)),
+            ComponentType::Future(_) => Ok(ComponentValue::Future(memory.read_u32_le(offset)?)),
+            ComponentType::ErrorContext => {
+                Ok(ComponentValue::ErrorContext(memory.read_u32_le(offset)?))
+            },
         }
     }
 
@@ -523,17 +806,17 @@ pub fn lift_u64<M: CanonicalMemory>(&self, memory: &M, offset: u32) -> Result<Co
         Ok(ComponentValue::U64(value))
     }
 
-    /// Lift an f32 value
+    /// Lift an f32 value, canonicalizing `NaN`s
     pub fn lift_f32<M: CanonicalMemory>(&self, memory: &M, offset: u32) -> Result<ComponentValue> {
         let bits = memory.read_u32_le(offset)?;
-        let value = f32::from_bits(bits);
+        let value = FloatBits32::from_bits(bits).canonicalize_nan().value();
         Ok(ComponentValue::F32(value))
     }
 
-    /// Lift an f64 value
+    /// Lift an f64 value, canonicalizing `NaN`s
     pub fn lift_f64<M: CanonicalMemory>(&self, memory: &M, offset: u32) -> Result<ComponentValue> {
         let bits = memory.read_u64_le(offset)?;
-        let value = f64::from_bits(bits);
+        let value = FloatBits64::from_bits(bits).canonicalize_nan().value();
         Ok(ComponentValue::F64(value))
     }
 
@@ -554,60 +837,52 @@ pub fn lift_string<M: CanonicalMemory>(
         // String is stored as (ptr: u32, len: u32)
         let ptr = memory.read_u32_le(offset)?;
         let len = memory.read_u32_le(offset + 4)?;
+        self.load_string(memory, ptr, len).map(ComponentValue::String)
+    }
+
+    /// Decode a string from its pointer and encoded length
+    fn load_string<M: CanonicalMemory>(&self, memory: &M, ptr: u32, len: u32) -> Result<String> {
+        let (utf16, units) = match self.string_encoding {
+            StringEncoding::Utf8 => (false, len),
+            StringEncoding::Utf16 => (true, len),
+            StringEncoding::Latin1 => (len & UTF16_TAG != 0, len & !UTF16_TAG),
+        };
 
         // Safety check
-        if len > MAX_STRING_LENGTH as u32 {
+        if units as usize > MAX_STRING_LENGTH {
             return Err(Error::validation_error("Error occurred: String too long"));
         }
+        if ptr % self.string_alignment() != 0 {
+            return Err(Error::validation_error(
+                "Error occurred: Misaligned string pointer",
+            ));
+        }
 
-        // Read string data
-        let bytes = memory.read_bytes(ptr, len)?;
+        let byte_len = if utf16 { units * 2 } else { units };
+        let bytes = memory.read_bytes(ptr, byte_len)?;
 
-        // Decode based on encoding
-        let string = match self.string_encoding {
+        match self.string_encoding {
             StringEncoding::Utf8 => String::from_utf8(bytes)
-                .map_err(|_| Error::validation_error("Error occurred: Invalid UTF-8 string"))?,
-            StringEncoding::Utf16Le => {
-                if bytes.len() % 2 != 0 {
-                    return Err(Error::validation_error(
-                        "Error occurred: UTF-16 byte sequence must have even length",
-                    ));
-                }
-
-                let mut code_units = Vec::new();
-                for chunk in bytes.chunks_exact(2) {
-                    let code_unit = u16::from_le_bytes([chunk[0], chunk[1]]);
-                    code_units.push(code_unit);
-                }
-
-                String::from_utf16(&code_units).map_err(|_| {
-                    Error::validation_error("Error occurred: Invalid UTF-16 sequence")
-                })?
+                .map_err(|_| Error::validation_error("Error occurred: Invalid UTF-8 string")),
+            _ if utf16 => {
+                let code_units: Vec<u16> = bytes
+                    .chunks_exact(2)
+                    .map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]))
+                    .collect();
+                String::from_utf16(&code_units)
+                    .map_err(|_| Error::validation_error("Error occurred: Invalid UTF-16 sequence"))
             },
-            StringEncoding::Utf16Be => {
-                if bytes.len() % 2 != 0 {
-                    return Err(Error::validation_error(
-                        "Error occurred: UTF-16 byte sequence must have even length",
-                    ));
-                }
-
-                let mut code_units = Vec::new();
-                for chunk in bytes.chunks_exact(2) {
-                    let code_unit = u16::from_be_bytes([chunk[0], chunk[1]]);
-                    code_units.push(code_unit);
-                }
-
-                String::from_utf16(&code_units).map_err(|_| {
-                    Error::validation_error("Error occurred: Invalid UTF-16 sequence")
-                })?
-            },
-            StringEncoding::Latin1 => {
-                // Latin-1 is a direct mapping from bytes to Unicode code points 0x00-0xFF
-                bytes.iter().map(|&b| b as char).collect()
-            },
-        };
+            // Latin-1 is a direct mapping from bytes to Unicode code points 0x00-0xFF
+            _ => Ok(bytes.iter().map(|&b| b as char).collect()),
+        }
+    }
 
-        Ok(ComponentValue::String(string))
+    /// Alignment of string data for the configured encoding
+    fn string_alignment(&self) -> u32 {
+        match self.string_encoding {
+            StringEncoding::Utf8 => 1,
+            StringEncoding::Utf16 | StringEncoding::Latin1 => 2,
+        }
     }
 
     /// Lift a list value
@@ -620,19 +895,37 @@ pub fn lift_list<M: CanonicalMemory>(
         // List is stored as (ptr: u32, len: u32)
         let ptr = memory.read_u32_le(offset)?;
         let len = memory.read_u32_le(offset + 4)?;
+        self.load_list(memory, element_ty, ptr, len)
+    }
 
+    /// Lift the elements of a list from its pointer and length
+    fn load_list<M: CanonicalMemory>(
+        &self,
+        memory: &M,
+        element_ty: &ComponentType,
+        ptr: u32,
+        len: u32,
+    ) -> Result<ComponentValue> {
         // Safety check
-        if len > MAX_LIST_LENGTH as u32 {
+        if len as usize > MAX_LIST_LENGTH {
             return Err(Error::validation_error("Error occurred: List too long"));
         }
+        if ptr % self.align_of(element_ty)? != 0 {
+            return Err(Error::validation_error(
+                "Error occurred: Misaligned list pointer",
+            ));
+        }
 
         let element_size = self.size_of(element_ty)?;
-        let mut values = Vec::new();
+        let in_bounds =
+            u64::from(ptr) + u64::from(len) * u64::from(element_size) <= u64::from(memory.size());
+        if !in_bounds {
+            return Err(Error::memory_out_of_bounds("List extends beyond memory"));
+        }
 
+        let mut values = Vec::with_capacity(len as usize);
         for i in 0..len {
-            let element_offset = ptr + i * element_size;
-            let value = self.lift(memory, element_ty, element_offset)?;
-            values.push(value);
+            values.push(self.lift(memory, element_ty, ptr + i * element_size)?);
         }
 
         Ok(ComponentValue::List(values))
@@ -645,10 +938,17 @@ pub fn lift_record<M: CanonicalMemory>(
         fields: &[(String, ComponentType)],
         offset: u32,
     ) -> Result<ComponentValue> {
-        let mut field_values = Vec::new();
+        if fields.len() > MAX_RECORD_FIELDS {
+            return Err(Error::validation_error(
+                "Error occurred: Too many record fields",
+            ));
+        }
+
+        let mut field_values = Vec::with_capacity(fields.len());
         let mut current_offset = offset;
 
         for (field_name, field_ty) in fields {
+            current_offset = align_to(current_offset, self.align_of(field_ty)?);
             let value = self.lift(memory, field_ty, current_offset)?;
             field_values.push((field_name.clone(), value));
             current_offset += self.size_of(field_ty)?;
@@ -664,18 +964,41 @@ pub fn lift_tuple<M: CanonicalMemory>(
         types: &[ComponentType],
         offset: u32,
     ) -> Result<ComponentValue> {
-        let mut values = Vec::new();
+        let mut values = Vec::with_capacity(types.len());
         let mut current_offset = offset;
 
         for ty in types {
-            let value = self.lift(memory, ty, current_offset)?;
-            values.push(value);
+            current_offset = align_to(current_offset, self.align_of(ty)?);
+            values.push(self.lift(memory, ty, current_offset)?);
             current_offset += self.size_of(ty)?;
         }
 
         Ok(ComponentValue::Tuple(values))
     }
 
+    /// Read the discriminant of a variant-like value and lift its payload
+    fn lift_case<M: CanonicalMemory>(
+        &self,
+        memory: &M,
+        payloads: &[Option<&ComponentType>],
+        offset: u32,
+    ) -> Result<(usize, Option<Box<ComponentValue>>)> {
+        let layout = self.case_layout(payloads)?;
+        let case = read_discriminant(memory, layout.discriminant_size, offset)? as usize;
+
+        let payload_ty = payloads.get(case).ok_or_else(|| {
+            Error::validation_error("Error occurred: Invalid variant discriminant")
+        })?;
+        let payload = match payload_ty {
+            Some(payload_ty) => {
+                let value = self.lift(memory, payload_ty, offset + layout.payload_offset)?;
+                Some(Box::new(value))
+            },
+            None => None,
+        };
+        Ok((case, payload))
+    }
+
     /// Lift a variant value
     pub fn lift_variant<M: CanonicalMemory>(
         &self,
@@ -683,25 +1006,9 @@ pub fn lift_variant<M: CanonicalMemory>(
         cases: &[(String, Option<ComponentType>)],
         offset: u32,
     ) -> Result<ComponentValue> {
-        let discriminant = memory.read_u32_le(offset)?;
-
-        if discriminant as usize >= cases.len() {
-            return Err(Error::validation_error(
-                "Error occurred: Invalid variant discriminant",
-            ));
-        }
-
-        let (case_name, payload_ty) = &cases[discriminant as usize];
-
-        if let Some(ty) = payload_ty {
-            let payload_value = self.lift(memory, ty, offset + 4)?;
-            Ok(ComponentValue::Variant(
-                case_name.clone(),
-                Some(Box::new(payload_value)),
-            ))
-        } else {
-            Ok(ComponentValue::Variant(case_name.clone(), None))
-        }
+        let payloads: Vec<_> = cases.iter().map(|(_, ty)| ty.as_ref()).collect();
+        let (case, payload) = self.lift_case(memory, &payloads, offset)?;
+        Ok(ComponentValue::Variant(cases[case].0.clone(), payload))
     }
 
     /// Lift an enum value
@@ -711,15 +1018,12 @@ pub fn lift_enum<M: CanonicalMemory>(
         cases: &[String],
         offset: u32,
     ) -> Result<ComponentValue> {
-        let discriminant = memory.read_u32_le(offset)?;
-
-        if discriminant as usize >= cases.len() {
-            return Err(Error::validation_error(
-                "Error occurred: Invalid enum discriminant",
-            ));
-        }
+        let discriminant = read_discriminant(memory, discriminant_size(cases.len()), offset)?;
 
-        Ok(ComponentValue::Enum(cases[discriminant as usize].clone()))
+        let case = cases
+            .get(discriminant as usize)
+            .ok_or_else(|| Error::validation_error("Error occurred: Invalid enum discriminant"))?;
+        Ok(ComponentValue::Enum(case.clone()))
     }
 
     /// Lift an option value
@@ -729,14 +1033,8 @@ pub fn lift_option<M: CanonicalMemory>(
         inner_ty: &ComponentType,
         offset: u32,
     ) -> Result<ComponentValue> {
-        let discriminant = memory.read_u8(offset)?;
-
-        if discriminant == 0 {
-            Ok(ComponentValue::Option(None))
-        } else {
-            let value = self.lift(memory, inner_ty, offset + 1)?;
-            Ok(ComponentValue::Option(Some(Box::new(value))))
-        }
+        let (_, payload) = self.lift_case(memory, &[None, Some(inner_ty)], offset)?;
+        Ok(ComponentValue::Option(payload))
     }
 
     /// Lift a result value
@@ -747,30 +1045,10 @@ pub fn lift_result<M: CanonicalMemory>(
         err_ty: &Option<Box<ComponentType>>,
         offset: u32,
     ) -> Result<ComponentValue> {
-        let discriminant = memory.read_u32_le(offset)?;
-
-        match discriminant {
-            0 => {
-                // Ok case
-                if let Some(ty) = ok_ty {
-                    let value = self.lift(memory, ty, offset + 4)?;
-                    Ok(ComponentValue::Result(Ok(Some(Box::new(value)))))
-                } else {
-                    Ok(ComponentValue::Result(Ok(None)))
-                }
-            },
-            1 => {
-                // Err case
-                if let Some(ty) = err_ty {
-                    let value = self.lift(memory, ty, offset + 4)?;
-                    Ok(ComponentValue::Result(Err(Some(Box::new(value)))))
-                } else {
-                    Ok(ComponentValue::Result(Err(None)))
-                }
-            },
-            _ => Err(Error::validation_error(
-                "Error occurred: Invalid result discriminant",
-            )),
+        let payloads = [ok_ty.as_deref(), err_ty.as_deref()];
+        match self.lift_case(memory, &payloads, offset)? {
+            (0, payload) => Ok(ComponentValue::Result(Ok(payload))),
+            (_, payload) => Ok(ComponentValue::Result(Err(payload))),
         }
     }
 
@@ -781,56 +1059,111 @@ pub fn lift_flags<M: CanonicalMemory>(
         flags: &[String],
         offset: u32,
     ) -> Result<ComponentValue> {
-        let byte_count = (flags.len() + 7) / 8;
-        let bytes = memory.read_bytes(offset, byte_count as u32)?;
+        let bytes = memory.read_bytes(offset, flags_size(flags.len()))?;
 
-        let mut active_flags = Vec::new();
+        // Little-endian storage makes bit `i` live in byte `i / 8` for every
+        // flags representation (u8, u16 and sequences of u32)
+        let active_flags = flags
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| bytes[i / 8] & (1 << (i % 8)) != 0)
+            .map(|(_, flag_name)| flag_name.clone())
+            .collect();
 
-        for (i, flag_name) in flags.iter().enumerate() {
-            let byte_index = i / 8;
-            let bit_index = i % 8;
+        Ok(ComponentValue::Flags(active_flags))
+    }
 
-            if byte_index < bytes.len() && (bytes[byte_index] & (1 << bit_index)) != 0 {
-                active_flags.push(flag_name.clone());
-            }
+    // ==== LOWERING OPERATIONS ====
+
+    /// Lower a value of the given type to memory
+    ///
+    /// Strings and lists are allocated through `realloc`. With an isolating
+    /// memory strategy nothing is written unless the whole value lowers
+    /// successfully.
+    pub fn lower<M: CanonicalMemory, R: CanonicalRealloc>(
+        &self,
+        memory: &mut M,
+        realloc: &mut R,
+        ty: &ComponentType,
+        value: &ComponentValue,
+        offset: u32,
+    ) -> Result<()> {
+        #[cfg(feature = "std")]
+        if self.isolates_writes() {
+            let mut staged = StagedMemory::new(memory);
+            self.lower_value(&mut staged, realloc, ty, value, offset)?;
+            return staged.commit();
         }
 
-        Ok(ComponentValue::Flags(active_flags))
+        self.lower_value(memory, realloc, ty, value, offset)
     }
 
-    // ==== LOWERING OPERATIONS ====
+    /// Whether the selected memory strategy requires staged writes
+    #[cfg(feature = "std")]
+    fn isolates_writes(&self) -> bool {
+        matches!(
+            self.memory_strategy(),
+            MemoryStrategy::Isolated | MemoryStrategy::FullIsolation
+        )
+    }
 
-    /// Lower a value to memory
-    pub fn lower<M: CanonicalMemory>(
+    /// Type-directed lowering of a single value
+    fn lower_value<M: CanonicalMemory, R: CanonicalRealloc>(
         &self,
         memory: &mut M,
+        realloc: &mut R,
+        ty: &ComponentType,
         value: &ComponentValue,
         offset: u32,
     ) -> Result<()> {
-        match value {
-            ComponentValue::Bool(v) => self.lower_bool(memory, *v, offset),
-            ComponentValue::S8(v) => self.lower_s8(memory, *v, offset),
-            ComponentValue::U8(v) => self.lower_u8(memory, *v, offset),
-            ComponentValue::S16(v) => self.lower_s16(memory, *v, offset),
-            ComponentValue::U16(v) => self.lower_u16(memory, *v, offset),
-            ComponentValue::S32(v) => self.lower_s32(memory, *v, offset),
-            ComponentValue::U32(v) => self.lower_u32(memory, *v, offset),
-            ComponentValue::S64(v) => self.lower_s64(memory, *v, offset),
-            ComponentValue::U64(v) => self.lower_u64(memory, *v, offset),
-            ComponentValue::F32(v) => self.lower_f32(memory, *v, offset),
-            ComponentValue::F64(v) => self.lower_f64(memory, *v, offset),
-            ComponentValue::Char(v) => self.lower_char(memory, *v, offset),
-            ComponentValue::String(v) => self.lower_string(memory, v, offset),
-            ComponentValue::List(v) => self.lower_list(memory, v, offset),
-            ComponentValue::Record(v) => self.lower_record(memory, v, offset),
-            ComponentValue::Tuple(v) => self.lower_tuple(memory, v, offset),
-            ComponentValue::Variant(name, payload) => {
-                self.lower_variant(memory, name, payload, offset)
+        match (ty, value) {
+            (ComponentType::Bool, ComponentValue::Bool(v)) => self.lower_bool(memory, *v, offset),
+            (ComponentType::S8, ComponentValue::S8(v)) => self.lower_s8(memory, *v, offset),
+            (ComponentType::U8, ComponentValue::U8(v)) => self.lower_u8(memory, *v, offset),
+            (ComponentType::S16, ComponentValue::S16(v)) => self.lower_s16(memory, *v, offset),
+            (ComponentType::U16, ComponentValue::U16(v)) => self.lower_u16(memory, *v, offset),
+            (ComponentType::S32, ComponentValue::S32(v)) => self.lower_s32(memory, *v, offset),
+            (ComponentType::U32, ComponentValue::U32(v)) => self.lower_u32(memory, *v, offset),
+            (ComponentType::S64, ComponentValue::S64(v)) => self.lower_s64(memory, *v, offset),
+            (ComponentType::U64, ComponentValue::U64(v)) => self.lower_u64(memory, *v, offset),
+            (ComponentType::F32, ComponentValue::F32(v)) => self.lower_f32(memory, *v, offset),
+            (ComponentType::F64, ComponentValue::F64(v)) => self.lower_f64(memory, *v, offset),
+            (ComponentType::Char, ComponentValue::Char(v)) => self.lower_char(memory, *v, offset),
+            (ComponentType::String, ComponentValue::String(v)) => {
+                self.lower_string(memory, realloc, v, offset)
+            },
+            (ComponentType::List(element_ty), ComponentValue::List(v)) => {
+                self.lower_list(memory, realloc, element_ty, v, offset)
+            },
+            (ComponentType::Record(fields), ComponentValue::Record(v)) => {
+                self.lower_record(memory, realloc, fields, v, offset)
+            },
+            (ComponentType::Tuple(types), ComponentValue::Tuple(v)) => {
+                self.lower_tuple(memory, realloc, types, v, offset)
+            },
+            (ComponentType::Variant(cases), ComponentValue::Variant(name, payload)) => {
+                self.lower_variant(memory, realloc, cases, name, payload, offset)
+            },
+            (ComponentType::Enum(cases), ComponentValue::Enum(name)) => {
+                self.lower_enum(memory, cases, name, offset)
+            },
+            (ComponentType::Option(inner_ty), ComponentValue::Option(v)) => {
+                self.lower_option(memory, realloc, inner_ty, v, offset)
             },
-            ComponentValue::Enum(name) => self.lower_enum(memory, name, offset),
-            ComponentValue::Option(v) => self.lower_option(memory, v, offset),
-            ComponentValue::Result(v) => self.lower_result(memory, v, offset),
-            ComponentValue::Flags(v) => self.lower_flags(memory, v, offset),
+            (ComponentType::Result(ok_ty, err_ty), ComponentValue::Result(v)) => {
+                self.lower_result(memory, realloc, ok_ty, err_ty, v, offset)
+            },
+            (ComponentType::Flags(flags), ComponentValue::Flags(v)) => {
+                self.lower_flags(memory, flags, v, offset)
+            },
+            (ComponentType::Own(_), ComponentValue::Own(handle))
+            | (ComponentType::Borrow(_), ComponentValue::Borrow(handle))
+            | (ComponentType::Stream(_), ComponentValue::Stream(handle))
+            | (ComponentType::Future(_), ComponentValue::Future(handle))
+            | (ComponentType::ErrorContext, ComponentValue::ErrorContext(handle)) => {
+                memory.write_u32_le(offset, *handle)
+            },
+            _ => Err(value_type_mismatch()),
         }
     }
 
@@ -954,431 +1287,903 @@ pub fn lower_char<M: CanonicalMemory>(
         memory.write_u32_le(offset, value as u32)
     }
 
-    /// Lower a string value
-    pub fn lower_string<M: CanonicalMemory>(
+    /// Allocate guest memory through `realloc` and validate the result
+    fn allocate<M: CanonicalMemory, R: CanonicalRealloc>(
+        &self,
+        memory: &M,
+        realloc: &mut R,
+        align: u32,
+        size: u32,
+    ) -> Result<u32> {
+        let ptr = realloc.realloc(0, 0, align, size)?;
+        if ptr % align != 0 {
+            return Err(Error::validation_error(
+                "Error occurred: Misaligned realloc result",
+            ));
+        }
+        if u64::from(ptr) + u64::from(size) > u64::from(memory.size()) {
+            return Err(Error::memory_out_of_bounds("Realloc result out of bounds"));
+        }
+        Ok(ptr)
+    }
+
+    /// Lower a string value, allocating its contents through `realloc`
+    pub fn lower_string<M: CanonicalMemory, R: CanonicalRealloc>(
         &self,
         memory: &mut M,
+        realloc: &mut R,
         value: &str,
         offset: u32,
     ) -> Result<()> {
-        // This is a simplified implementation that assumes string data
-        // Binary std/no_std choice
-        // Binary std/no_std choice
+        let (ptr, len) = self.store_string(memory, realloc, value)?;
+        memory.write_u32_le(offset, ptr)?;
+        memory.write_u32_le(offset + 4, len)
+    }
 
-        let bytes = value.as_bytes();
-        let len = bytes.len() as u32;
+    /// Encode and store a string, returning its pointer and encoded length
+    fn store_string<M: CanonicalMemory, R: CanonicalRealloc>(
+        &self,
+        memory: &mut M,
+        realloc: &mut R,
+        value: &str,
+    ) -> Result<(u32, u32)> {
+        let latin1 = self.string_encoding == StringEncoding::Latin1
+            && value.chars().all(|c| u32::from(c) <= 0xFF);
+
+        let (bytes, len) = match self.string_encoding {
+            StringEncoding::Utf8 => (value.as_bytes().to_vec(), value.len()),
+            _ if latin1 => {
+                let bytes: Vec<u8> = value.chars().map(|c| c as u8).collect();
+                let len = bytes.len();
+                (bytes, len)
+            },
+            _ => {
+                let bytes: Vec<u8> = value.encode_utf16().flat_map(u16::to_le_bytes).collect();
+                let len = bytes.len() / 2;
+                (bytes, len)
+            },
+        };
 
         // Safety check
-        if len > MAX_STRING_LENGTH as u32 {
+        if len > MAX_STRING_LENGTH {
             return Err(Error::validation_error("Error occurred: String too long"));
         }
 
-        // For this simplified implementation, we'll assume the string data
-        // is written immediately after the pointer/length pair
-        let data_offset = offset + 8;
-
-        // Write pointer and length
-        memory.write_u32_le(offset, data_offset)?;
-        memory.write_u32_le(offset + 4, len)?;
+        let ptr = self.allocate(memory, realloc, self.string_alignment(), bytes.len() as u32)?;
+        memory.write_bytes(ptr, &bytes)?;
 
-        // Write string data
-        memory.write_bytes(data_offset, bytes)?;
-
-        Ok(())
+        let tagged = self.string_encoding == StringEncoding::Latin1 && !latin1;
+        Ok((
+            ptr,
+            if tagged { len as u32 | UTF16_TAG } else { len as u32 },
+        ))
     }
 
-    /// Lower a list value (simplified implementation)
-    pub fn lower_list<M: CanonicalMemory>(
+    /// Lower a list value, allocating its elements through `realloc`
+    pub fn lower_list<M: CanonicalMemory, R: CanonicalRealloc>(
         &self,
         memory: &mut M,
+        realloc: &mut R,
+        element_ty: &ComponentType,
         values: &[ComponentValue],
         offset: u32,
     ) -> Result<()> {
-        // This is a simplified implementation
-        let len = values.len() as u32;
+        let (ptr, len) = self.store_list(memory, realloc, element_ty, values)?;
+        memory.write_u32_le(offset, ptr)?;
+        memory.write_u32_le(offset + 4, len)
+    }
 
+    /// Store the elements of a list, returning its pointer and length
+    fn store_list<M: CanonicalMemory, R: CanonicalRealloc>(
+        &self,
+        memory: &mut M,
+        realloc: &mut R,
+        element_ty: &ComponentType,
+        values: &[ComponentValue],
+    ) -> Result<(u32, u32)> {
         // Safety check
-        if len > MAX_LIST_LENGTH as u32 {
+        if values.len() > MAX_LIST_LENGTH {
             return Err(Error::validation_error("Error occurred: List too long"));
         }
 
-        // For this simplified implementation, we'll write a basic representation
-        memory.write_u32_le(offset, offset + 8)?; // pointer
-        memory.write_u32_le(offset + 4, len)?; // length
+        let element_size = self.size_of(element_ty)?;
+        let byte_len = element_size
+            .checked_mul(values.len() as u32)
+            .ok_or_else(|| Error::validation_error("Error occurred: List too large"))?;
+        let ptr = self.allocate(memory, realloc, self.align_of(element_ty)?, byte_len)?;
+
+        for (i, value) in values.iter().enumerate() {
+            let element_offset = ptr + i as u32 * element_size;
+            self.lower_value(memory, realloc, element_ty, value, element_offset)?;
+        }
 
-        // This would need proper element size calculation and layout
-        // For now, just return OK as a placeholder
-        Ok(())
+        Ok((ptr, values.len() as u32))
     }
 
-    /// Lower a record value with proper field layout
-    pub fn lower_record<M: CanonicalMemory>(
+    /// Lower a record value, matching fields by name
+    pub fn lower_record<M: CanonicalMemory, R: CanonicalRealloc>(
         &self,
         memory: &mut M,
-        fields: &[(String, ComponentValue)],
+        realloc: &mut R,
+        fields: &[(String, ComponentType)],
+        values: &[(String, ComponentValue)],
         offset: u32,
     ) -> Result<()> {
-        // Calculate field layouts and offsets
-        let mut current_offset = 0;
-
-        for (field_name, field_value) in fields {
-            // Calculate field layout based on value type
-            let field_layout = self.calculate_value_layout(field_value);
-
-            // Align current offset to field's alignment requirement
-            current_offset = align_to(current_offset, field_layout.alignment);
-
-            // Lower the field value at the aligned offset
-            self.lower(memory, field_value, offset + current_offset as u32)?;
+        if values.len() != fields.len() {
+            return Err(value_type_mismatch());
+        }
 
-            // Move to next field position
-            current_offset += field_layout.size;
+        let mut current_offset = offset;
+        for (field_name, field_ty) in fields {
+            let (_, value) = values
+                .iter()
+                .find(|(name, _)| name == field_name)
+                .ok_or_else(value_type_mismatch)?;
+            current_offset = align_to(current_offset, self.align_of(field_ty)?);
+            self.lower_value(memory, realloc, field_ty, value, current_offset)?;
+            current_offset += self.size_of(field_ty)?;
         }
 
         Ok(())
     }
 
-    /// Lower a tuple value with proper element layout
-    pub fn lower_tuple<M: CanonicalMemory>(
+    /// Lower a tuple value
+    pub fn lower_tuple<M: CanonicalMemory, R: CanonicalRealloc>(
         &self,
         memory: &mut M,
+        realloc: &mut R,
+        types: &[ComponentType],
         values: &[ComponentValue],
         offset: u32,
     ) -> Result<()> {
-        // Calculate element layouts and offsets
-        let mut current_offset = 0;
-
-        for value in values {
-            // Calculate element layout based on value type
-            let element_layout = self.calculate_value_layout(value);
-
-            // Align current offset to element's alignment requirement
-            current_offset = align_to(current_offset, element_layout.alignment);
-
-            // Lower the element value at the aligned offset
-            self.lower(memory, value, offset + current_offset as u32)?;
+        if values.len() != types.len() {
+            return Err(value_type_mismatch());
+        }
 
-            // Move to next element position
-            current_offset += element_layout.size;
+        let mut current_offset = offset;
+        for (ty, value) in types.iter().zip(values) {
+            current_offset = align_to(current_offset, self.align_of(ty)?);
+            self.lower_value(memory, realloc, ty, value, current_offset)?;
+            current_offset += self.size_of(ty)?;
         }
 
         Ok(())
     }
 
-    /// Lower a variant value with proper discriminant and payload layout
-    pub fn lower_variant<M: CanonicalMemory>(
+    /// Write the discriminant of a variant-like value and lower its payload
+    fn lower_case<M: CanonicalMemory, R: CanonicalRealloc>(
         &self,
         memory: &mut M,
-        cases: &[(String, Option<ComponentType>)],
-        case_name: &str,
-        payload: &Option<Box<ComponentValue>>,
+        realloc: &mut R,
+        payloads: &[Option<&ComponentType>],
+        case: usize,
+        payload: Option<&ComponentValue>,
         offset: u32,
     ) -> Result<()> {
-        // Find the discriminant for this case
-        let discriminant = cases
-            .iter()
-            .position(|(name, _)| name == case_name)
-            .ok_or_else(|| Error::validation_error("Error occurred: Variant case not found"))?;
-
-        // Calculate discriminant size based on number of cases
-        let discriminant_size = if cases.len() <= 256 {
-            1
-        } else if cases.len() <= 65536 {
-            2
-        } else {
-            4
-        };
+        let layout = self.case_layout(payloads)?;
+        write_discriminant(memory, layout.discriminant_size, offset, case as u32)?;
 
-        // Write discriminant
-        match discriminant_size {
-            1 => memory.write_u8(offset, discriminant as u8)?,
-            2 => memory.write_u16_le(offset, discriminant as u16)?,
-            4 => memory.write_u32_le(offset, discriminant as u32)?,
-            _ => {
-                return Err(Error::validation_error(
-                    "Error occurred: Invalid discriminant size calculated",
-                ))
+        match (payloads[case], payload) {
+            (Some(payload_ty), Some(payload)) => {
+                let payload_offset = offset + layout.payload_offset;
+                self.lower_value(memory, realloc, payload_ty, payload, payload_offset)
             },
+            (None, None) => Ok(()),
+            _ => Err(value_type_mismatch()),
         }
+    }
 
-        // If there's a payload, lower it after the discriminant with proper alignment
-        if let Some(payload_value) = payload {
-            let payload_layout = self.calculate_value_layout(payload_value);
-
-            // Calculate payload offset with proper alignment
-            let payload_offset = align_to(discriminant_size, payload_layout.alignment);
-
-            // Lower the payload
-            self.lower(memory, payload_value, offset + payload_offset as u32)?;
-        }
-
-        Ok(())
+    /// Lower a variant value
+    pub fn lower_variant<M: CanonicalMemory, R: CanonicalRealloc>(
+        &self,
+        memory: &mut M,
+        realloc: &mut R,
+        cases: &[(String, Option<ComponentType>)],
+        name: &str,
+        payload: &Option<Box<ComponentValue>>,
+        offset: u32,
+    ) -> Result<()> {
+        let case = cases
+            .iter()
+            .position(|(case_name, _)| case_name == name)
+            .ok_or_else(|| Error::validation_error("Error occurred: Unknown variant case"))?;
+        let payloads: Vec<_> = cases.iter().map(|(_, ty)| ty.as_ref()).collect();
+        self.lower_case(memory, realloc, &payloads, case, payload.as_deref(), offset)
     }
 
-    /// Lower an enum value with proper discriminant calculation
+    /// Lower an enum value
     pub fn lower_enum<M: CanonicalMemory>(
         &self,
         memory: &mut M,
         cases: &[String],
-        case_name: &str,
+        name: &str,
         offset: u32,
     ) -> Result<()> {
-        // Find the discriminant for this case
-        let discriminant = cases
+        let case = cases
             .iter()
-            .position(|name| name == case_name)
-            .ok_or_else(|| Error::validation_error("Error occurred: Enum case not found"))?;
-
-        // Calculate discriminant size based on number of cases
-        let discriminant_size = if cases.len() <= 256 {
-            1
-        } else if cases.len() <= 65536 {
-            2
-        } else {
-            4
-        };
-
-        // Write discriminant
-        match discriminant_size {
-            1 => memory.write_u8(offset, discriminant as u8),
-            2 => memory.write_u16_le(offset, discriminant as u16),
-            4 => memory.write_u32_le(offset, discriminant as u32),
-            _ => {
-                return Err(Error::validation_error(
-                    "Error occurred: Invalid discriminant size calculated",
-                ))
-            },
-        }
+            .position(|case_name| case_name == name)
+            .ok_or_else(|| Error::validation_error("Error occurred: Unknown enum case"))?;
+        write_discriminant(memory, discriminant_size(cases.len()), offset, case as u32)
     }
 
-    /// Lower an option value with proper layout
-    pub fn lower_option<M: CanonicalMemory>(
+    /// Lower an option value
+    pub fn lower_option<M: CanonicalMemory, R: CanonicalRealloc>(
         &self,
         memory: &mut M,
+        realloc: &mut R,
+        inner_ty: &ComponentType,
         value: &Option<Box<ComponentValue>>,
         offset: u32,
     ) -> Result<()> {
-        match value {
-            Some(inner_value) => {
-                // Write Some discriminant (1)
-                memory.write_u8(offset, 1)?;
-
-                // Calculate layout for the inner value
-                let inner_layout = self.calculate_value_layout(inner_value);
-
-                // Calculate payload offset with proper alignment
-                let payload_offset = align_to(1, inner_layout.alignment);
-
-                // Lower the inner value
-                self.lower(memory, inner_value, offset + payload_offset as u32)?;
-            },
-            None => {
-                // Write None discriminant (0)
-                memory.write_u8(offset, 0)?;
-            },
-        }
-        Ok(())
+        let case = usize::from(value.is_some());
+        let payloads = [None, Some(inner_ty)];
+        self.lower_case(memory, realloc, &payloads, case, value.as_deref(), offset)
     }
 
-    /// Lower a result value with proper layout
-    pub fn lower_result<M: CanonicalMemory>(
+    /// Lower a result value
+    pub fn lower_result<M: CanonicalMemory, R: CanonicalRealloc>(
         &self,
         memory: &mut M,
+        realloc: &mut R,
+        ok_ty: &Option<Box<ComponentType>>,
+        err_ty: &Option<Box<ComponentType>>,
         value: &core::result::Result<Option<Box<ComponentValue>>, Option<Box<ComponentValue>>>,
         offset: u32,
     ) -> Result<()> {
-        match value {
-            Ok(ok_value) => {
-                // Write Ok discriminant (0)
-                memory.write_u8(offset, 0)?;
-
-                // If there's an Ok value, lower it
-                if let Some(inner_value) = ok_value {
-                    let inner_layout = self.calculate_value_layout(inner_value);
-                    let payload_offset = align_to(1, inner_layout.alignment);
-                    self.lower(memory, inner_value, offset + payload_offset as u32)?;
+        let payloads = [ok_ty.as_deref(), err_ty.as_deref()];
+        let (case, payload) = match value {
+            Ok(payload) => (0, payload),
+            Err(payload) => (1, payload),
+        };
+        self.lower_case(memory, realloc, &payloads, case, payload.as_deref(), offset)
+    }
+
+    /// Lower a flags value
+    pub fn lower_flags<M: CanonicalMemory>(
+        &self,
+        memory: &mut M,
+        flags: &[String],
+        active: &[String],
+        offset: u32,
+    ) -> Result<()> {
+        let bytes = flags_to_bytes(flags, active)?;
+        memory.write_bytes(offset, &bytes)
+    }
+
+    // ==== FLAT (CORE VALUE) OPERATIONS ====
+
+    /// Flatten a type into the core value types used to pass it
+    pub fn flatten(&self, ty: &ComponentType) -> Result<Vec<CoreType>> {
+        let mut flat = Vec::new();
+        self.flatten_into(ty, &mut flat)?;
+        Ok(flat)
+    }
+
+    fn flatten_into(&self, ty: &ComponentType, flat: &mut Vec<CoreType>) -> Result<()> {
+        match ty {
+            ComponentType::Bool
+            | ComponentType::S8
+            | ComponentType::U8
+            | ComponentType::S16
+            | ComponentType::U16
+            | ComponentType::S32
+            | ComponentType::U32
+            | ComponentType::Char
+            | ComponentType::Enum(_)
+            | ComponentType::Own(_)
+            | ComponentType::Borrow(_)
+            | ComponentType::Stream(_)
+            | ComponentType::Future(_)
+            | ComponentType::ErrorContext => flat.push(CoreType::I32),
+            ComponentType::S64 | ComponentType::U64 => flat.push(CoreType::I64),
+            ComponentType::F32 => flat.push(CoreType::F32),
+            ComponentType::F64 => flat.push(CoreType::F64),
+            ComponentType::String | ComponentType::List(_) => {
+                flat.extend([CoreType::I32, CoreType::I32]);
+            },
+            ComponentType::Record(fields) => {
+                for (_, field_ty) in fields {
+                    self.flatten_into(field_ty, flat)?;
                 }
             },
-            Err(err_value) => {
-                // Write Err discriminant (1)
-                memory.write_u8(offset, 1)?;
-
-                // If there's an Err value, lower it
-                if let Some(inner_value) = err_value {
-                    let inner_layout = self.calculate_value_layout(inner_value);
-                    let payload_offset = align_to(1, inner_layout.alignment);
-                    self.lower(memory, inner_value, offset + payload_offset as u32)?;
+            ComponentType::Tuple(types) => {
+                for ty in types {
+                    self.flatten_into(ty, flat)?;
                 }
             },
+            ComponentType::Flags(flags) => {
+                flat.extend(core::iter::repeat_n(
+                    CoreType::I32,
+                    flags.len().div_ceil(32),
+                ));
+            },
+            ComponentType::Variant(_) | ComponentType::Option(_) | ComponentType::Result(..) => {
+                flat.push(CoreType::I32);
+                flat.extend(self.flatten_payloads(&case_payloads(ty)?)?);
+            },
         }
         Ok(())
     }
 
-    /// Lower a flags value with proper bit layout
-    pub fn lower_flags<M: CanonicalMemory>(
-        &self,
-        memory: &mut M,
-        flag_definitions: &[String],
-        active_flags: &[String],
-        offset: u32,
-    ) -> Result<()> {
-        // Calculate the number of bytes needed for all flags
-        let num_bytes = (flag_definitions.len() + 7) / 8;
-
-        // Create bit array
-        let mut flag_bytes = vec![0u8; num_bytes];
-
-        // Set bits for active flags
-        for active_flag in active_flags {
-            if let Some(flag_index) = flag_definitions.iter().position(|f| f == active_flag) {
-                let byte_index = flag_index / 8;
-                let bit_index = flag_index % 8;
-                if byte_index < flag_bytes.len() {
-                    flag_bytes[byte_index] |= 1 << bit_index;
+    /// Join the flattened payloads of all cases of a variant-like type
+    fn flatten_payloads(&self, payloads: &[Option<&ComponentType>]) -> Result<Vec<CoreType>> {
+        let mut joined: Vec<CoreType> = Vec::new();
+        for payload in payloads.iter().flatten() {
+            for (i, ty) in self.flatten(payload)?.into_iter().enumerate() {
+                match joined.get_mut(i) {
+                    Some(slot) => *slot = join_flat(*slot, ty),
+                    None => joined.push(ty),
                 }
             }
         }
+        Ok(joined)
+    }
 
-        // Write flag bytes to memory
-        memory.write_bytes(offset, &flag_bytes)
-    }
-
-    /// Calculate memory layout for a ComponentValue
-    fn calculate_value_layout(&self, value: &ComponentValue) -> MemoryLayout {
-        match value {
-            ComponentValue::Bool(_) => MemoryLayout::new(1, 1),
-            ComponentValue::S8(_) | ComponentValue::U8(_) => MemoryLayout::new(1, 1),
-            ComponentValue::S16(_) | ComponentValue::U16(_) => MemoryLayout::new(2, 2),
-            ComponentValue::S32(_) | ComponentValue::U32(_) => MemoryLayout::new(4, 4),
-            ComponentValue::S64(_) | ComponentValue::U64(_) => MemoryLayout::new(8, 8),
-            ComponentValue::F32(_) => MemoryLayout::new(4, 4),
-            ComponentValue::F64(_) => MemoryLayout::new(8, 8),
-            ComponentValue::Char(_) => MemoryLayout::new(4, 4),
-            ComponentValue::String(_) => MemoryLayout::new(8, 4), // ptr + len
-            ComponentValue::List(_) => MemoryLayout::new(8, 4),   // ptr + len
-            ComponentValue::Record(fields) => {
-                // Calculate record layout from fields
-                let mut offset = 0;
-                let mut max_alignment = 1;
-
-                for (_, field_value) in fields {
-                    let field_layout = self.calculate_value_layout(field_value);
-                    offset = align_to(offset, field_layout.alignment);
-                    offset += field_layout.size;
-                    max_alignment = max_alignment.max(field_layout.alignment);
-                }
+    /// Lift a value from flat core values
+    ///
+    /// Strings and lists are read from `memory` through the pointer and
+    /// length found in the flat values.
+    pub fn lift_flat<M: CanonicalMemory>(
+        &self,
+        memory: &M,
+        ty: &ComponentType,
+        values: &[CoreValue],
+    ) -> Result<ComponentValue> {
+        let mut reader = FlatReader::new(values);
+        let value = self.lift_flat_value(memory, ty, &mut reader)?;
+        reader.finish()?;
+        Ok(value)
+    }
 
-                let final_size = align_to(offset, max_alignment);
-                MemoryLayout::new(final_size, max_alignment)
+    fn lift_flat_value<M: CanonicalMemory>(
+        &self,
+        memory: &M,
+        ty: &ComponentType,
+        reader: &mut FlatReader<'_>,
+    ) -> Result<ComponentValue> {
+        Ok(match ty {
+            ComponentType::Bool => ComponentValue::Bool(reader.next_i32()? != 0),
+            ComponentType::S8 => ComponentValue::S8(reader.next_i32()? as i8),
+            ComponentType::U8 => ComponentValue::U8(reader.next_i32()? as u8),
+            ComponentType::S16 => ComponentValue::S16(reader.next_i32()? as i16),
+            ComponentType::U16 => ComponentValue::U16(reader.next_i32()? as u16),
+            ComponentType::S32 => ComponentValue::S32(reader.next_i32()?),
+            ComponentType::U32 => ComponentValue::U32(reader.next_i32()? as u32),
+            ComponentType::S64 => ComponentValue::S64(reader.next_i64()?),
+            ComponentType::U64 => ComponentValue::U64(reader.next_i64()? as u64),
+            ComponentType::F32 => ComponentValue::F32(
+                FloatBits32::from_float(reader.next_f32()?).canonicalize_nan().value(),
+            ),
+            ComponentType::F64 => ComponentValue::F64(
+                FloatBits64::from_float(reader.next_f64()?).canonicalize_nan().value(),
+            ),
+            ComponentType::Char => ComponentValue::Char(
+                char::from_u32(reader.next_i32()? as u32)
+                    .ok_or_else(|| Error::validation_error("Error occurred: Invalid char value"))?,
+            ),
+            ComponentType::String => {
+                let ptr = reader.next_i32()? as u32;
+                let len = reader.next_i32()? as u32;
+                ComponentValue::String(self.load_string(memory, ptr, len)?)
             },
-            ComponentValue::Tuple(values) => {
-                // Calculate tuple layout from values
-                let mut offset = 0;
-                let mut max_alignment = 1;
-
-                for value in values {
-                    let value_layout = self.calculate_value_layout(value);
-                    offset = align_to(offset, value_layout.alignment);
-                    offset += value_layout.size;
-                    max_alignment = max_alignment.max(value_layout.alignment);
+            ComponentType::List(element_ty) => {
+                let ptr = reader.next_i32()? as u32;
+                let len = reader.next_i32()? as u32;
+                self.load_list(memory, element_ty, ptr, len)?
+            },
+            ComponentType::Record(fields) => {
+                let mut field_values = Vec::with_capacity(fields.len());
+                for (field_name, field_ty) in fields {
+                    let value = self.lift_flat_value(memory, field_ty, reader)?;
+                    field_values.push((field_name.clone(), value));
                 }
-
-                let final_size = align_to(offset, max_alignment);
-                MemoryLayout::new(final_size, max_alignment)
+                ComponentValue::Record(field_values)
             },
-            ComponentValue::Option(inner) => {
-                if let Some(inner_value) = inner {
-                    let inner_layout = self.calculate_value_layout(inner_value);
-                    let payload_offset = align_to(1, inner_layout.alignment);
-                    let total_size = payload_offset + inner_layout.size;
-                    let alignment = inner_layout.alignment.max(1);
-                    let final_size = align_to(total_size, alignment);
-                    MemoryLayout::new(final_size, alignment)
-                } else {
-                    MemoryLayout::new(1, 1) // Just discriminant
+            ComponentType::Tuple(types) => {
+                let mut values = Vec::with_capacity(types.len());
+                for ty in types {
+                    values.push(self.lift_flat_value(memory, ty, reader)?);
                 }
+                ComponentValue::Tuple(values)
             },
-            ComponentValue::Result(result) => {
-                let mut max_payload_size = 0;
-                let mut max_payload_alignment = 1;
-
-                match result {
-                    Ok(Some(ok_value)) => {
-                        let layout = self.calculate_value_layout(ok_value);
-                        max_payload_size = layout.size;
-                        max_payload_alignment = layout.alignment;
-                    },
-                    Err(Some(err_value)) => {
-                        let layout = self.calculate_value_layout(err_value);
-                        max_payload_size = layout.size;
-                        max_payload_alignment = layout.alignment;
+            ComponentType::Enum(cases) => {
+                let case = cases.get(reader.next_i32()? as u32 as usize).ok_or_else(|| {
+                    Error::validation_error("Error occurred: Invalid enum discriminant")
+                })?;
+                ComponentValue::Enum(case.clone())
+            },
+            ComponentType::Flags(flags) => {
+                let mut bytes = Vec::with_capacity(flags.len().div_ceil(32) * 4);
+                for _ in 0..flags.len().div_ceil(32) {
+                    bytes.extend_from_slice(&reader.next_i32()?.to_le_bytes());
+                }
+                let active_flags = flags
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| bytes[i / 8] & (1 << (i % 8)) != 0)
+                    .map(|(_, flag_name)| flag_name.clone())
+                    .collect();
+                ComponentValue::Flags(active_flags)
+            },
+            ComponentType::Own(_) => ComponentValue::Own(reader.next_i32()? as u32),
+            ComponentType::Borrow(_) => ComponentValue::Borrow(reader.next_i32()? as u32),
+            ComponentType::Stream(_) => ComponentValue::Stream(reader.next_i32()? as u32),
+            ComponentType::Future(_) => ComponentValue::Future(reader.next_i32()? as u32),
+            ComponentType::ErrorContext => ComponentValue::ErrorContext(reader.next_i32()? as u32),
+            ComponentType::Variant(_) | ComponentType::Option(_) | ComponentType::Result(..) => {
+                let payloads = case_payloads(ty)?;
+                let (case, payload) = self.lift_flat_case(memory, &payloads, reader)?;
+                match ty {
+                    ComponentType::Variant(cases) => {
+                        ComponentValue::Variant(cases[case].0.clone(), payload)
                     },
-                    _ => {}, // No payload
+                    ComponentType::Option(_) => ComponentValue::Option(payload),
+                    _ if case == 0 => ComponentValue::Result(Ok(payload)),
+                    _ => ComponentValue::Result(Err(payload)),
                 }
+            },
+        })
+    }
+
+    /// Lift the discriminant and payload of a flattened variant-like value
+    fn lift_flat_case<M: CanonicalMemory>(
+        &self,
+        memory: &M,
+        payloads: &[Option<&ComponentType>],
+        reader: &mut FlatReader<'_>,
+    ) -> Result<(usize, Option<Box<ComponentValue>>)> {
+        let case = reader.next_i32()? as u32 as usize;
+        let payload_ty = payloads.get(case).ok_or_else(|| {
+            Error::validation_error("Error occurred: Invalid variant discriminant")
+        })?;
+
+        // All cases share the joined slots; every slot is consumed regardless
+        // of the case that was selected
+        let joined = self.flatten_payloads(payloads)?;
+        let mut slots = Vec::with_capacity(joined.len());
+        for slot_ty in &joined {
+            slots.push(reader.next(*slot_ty)?);
+        }
 
-                let payload_offset = align_to(1, max_payload_alignment);
-                let total_size = payload_offset + max_payload_size;
-                let alignment = max_payload_alignment.max(1);
-                let final_size = align_to(total_size, alignment);
-                MemoryLayout::new(final_size, alignment)
+        let Some(payload_ty) = payload_ty else {
+            return Ok((case, None));
+        };
+        let mut coerced = Vec::new();
+        for (slot, want) in slots.iter().zip(self.flatten(payload_ty)?) {
+            coerced.push(narrow_flat(slot, want));
+        }
+        let payload = self.lift_flat(memory, payload_ty, &coerced)?;
+        Ok((case, Some(Box::new(payload))))
+    }
+
+    /// Lower a value to flat core values
+    ///
+    /// Strings and lists are allocated in `memory` through `realloc`.
+    pub fn lower_flat<M: CanonicalMemory, R: CanonicalRealloc>(
+        &self,
+        memory: &mut M,
+        realloc: &mut R,
+        ty: &ComponentType,
+        value: &ComponentValue,
+    ) -> Result<Vec<CoreValue>> {
+        let mut flat = Vec::new();
+        self.lower_flat_value(memory, realloc, ty, value, &mut flat)?;
+        Ok(flat)
+    }
+
+    fn lower_flat_value<M: CanonicalMemory, R: CanonicalRealloc>(
+        &self,
+        memory: &mut M,
+        realloc: &mut R,
+        ty: &ComponentType,
+        value: &ComponentValue,
+        flat: &mut Vec<CoreValue>,
+    ) -> Result<()> {
+        match (ty, value) {
+            (ComponentType::Bool, ComponentValue::Bool(v)) => flat.push(CoreValue::I32(*v as i32)),
+            (ComponentType::S8, ComponentValue::S8(v)) => flat.push(CoreValue::I32(*v as i32)),
+            (ComponentType::U8, ComponentValue::U8(v)) => flat.push(CoreValue::I32(*v as i32)),
+            (ComponentType::S16, ComponentValue::S16(v)) => flat.push(CoreValue::I32(*v as i32)),
+            (ComponentType::U16, ComponentValue::U16(v)) => flat.push(CoreValue::I32(*v as i32)),
+            (ComponentType::S32, ComponentValue::S32(v)) => flat.push(CoreValue::I32(*v)),
+            (ComponentType::U32, ComponentValue::U32(v)) => flat.push(CoreValue::I32(*v as i32)),
+            (ComponentType::S64, ComponentValue::S64(v)) => flat.push(CoreValue::I64(*v)),
+            (ComponentType::U64, ComponentValue::U64(v)) => flat.push(CoreValue::I64(*v as i64)),
+            (ComponentType::F32, ComponentValue::F32(v)) => {
+                flat.push(CoreValue::F32(FloatBits32::from_float(*v)));
+            },
+            (ComponentType::F64, ComponentValue::F64(v)) => {
+                flat.push(CoreValue::F64(FloatBits64::from_float(*v)));
+            },
+            (ComponentType::Char, ComponentValue::Char(v)) => {
+                flat.push(CoreValue::I32(u32::from(*v) as i32));
             },
-            ComponentValue::Variant(_, payload) => {
-                if let Some(payload_value) = payload {
-                    let payload_layout = self.calculate_value_layout(payload_value);
-                    let payload_offset = align_to(4, payload_layout.alignment); // 4-byte discriminant
-                    let total_size = payload_offset + payload_layout.size;
-                    let alignment = payload_layout.alignment.max(4);
-                    let final_size = align_to(total_size, alignment);
-                    MemoryLayout::new(final_size, alignment)
-                } else {
-                    MemoryLayout::new(4, 4) // Just discriminant
+            (ComponentType::String, ComponentValue::String(v)) => {
+                let (ptr, len) = self.store_string(memory, realloc, v)?;
+                flat.extend([CoreValue::I32(ptr as i32), CoreValue::I32(len as i32)]);
+            },
+            (ComponentType::List(element_ty), ComponentValue::List(v)) => {
+                let (ptr, len) = self.store_list(memory, realloc, element_ty, v)?;
+                flat.extend([CoreValue::I32(ptr as i32), CoreValue::I32(len as i32)]);
+            },
+            (ComponentType::Record(fields), ComponentValue::Record(values)) => {
+                if values.len() != fields.len() {
+                    return Err(value_type_mismatch());
+                }
+                for (field_name, field_ty) in fields {
+                    let (_, value) = values
+                        .iter()
+                        .find(|(name, _)| name == field_name)
+                        .ok_or_else(value_type_mismatch)?;
+                    self.lower_flat_value(memory, realloc, field_ty, value, flat)?;
+                }
+            },
+            (ComponentType::Tuple(types), ComponentValue::Tuple(values)) => {
+                if values.len() != types.len() {
+                    return Err(value_type_mismatch());
+                }
+                for (ty, value) in types.iter().zip(values) {
+                    self.lower_flat_value(memory, realloc, ty, value, flat)?;
+                }
+            },
+            (ComponentType::Enum(cases), ComponentValue::Enum(name)) => {
+                let case = cases
+                    .iter()
+                    .position(|case_name| case_name == name)
+                    .ok_or_else(|| Error::validation_error("Error occurred: Unknown enum case"))?;
+                flat.push(CoreValue::I32(case as i32));
+            },
+            (ComponentType::Flags(flags), ComponentValue::Flags(active)) => {
+                let mut bytes = flags_to_bytes(flags, active)?;
+                bytes.resize(flags.len().div_ceil(32) * 4, 0);
+                for chunk in bytes.chunks_exact(4) {
+                    let bits = i32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+                    flat.push(CoreValue::I32(bits));
                 }
             },
-            ComponentValue::Enum(_) => MemoryLayout::new(4, 4), // 4-byte discriminant
-            ComponentValue::Flags(flags) => {
-                let num_bytes = (flags.len() + 7) / 8;
-                let alignment = if num_bytes <= 1 {
-                    1
-                } else if num_bytes <= 2 {
-                    2
-                } else if num_bytes <= 4 {
-                    4
-                } else {
-                    8
+            (ComponentType::Own(_), ComponentValue::Own(handle))
+            | (ComponentType::Borrow(_), ComponentValue::Borrow(handle))
+            | (ComponentType::Stream(_), ComponentValue::Stream(handle))
+            | (ComponentType::Future(_), ComponentValue::Future(handle))
+            | (ComponentType::ErrorContext, ComponentValue::ErrorContext(handle)) => {
+                flat.push(CoreValue::I32(*handle as i32));
+            },
+            (ComponentType::Variant(cases), ComponentValue::Variant(name, payload)) => {
+                let case =
+                    cases.iter().position(|(case_name, _)| case_name == name).ok_or_else(|| {
+                        Error::validation_error("Error occurred: Unknown variant case")
+                    })?;
+                let payloads = case_payloads(ty)?;
+                self.lower_flat_case(memory, realloc, &payloads, case, payload.as_deref(), flat)?;
+            },
+            (ComponentType::Option(_), ComponentValue::Option(payload)) => {
+                let payloads = case_payloads(ty)?;
+                let case = usize::from(payload.is_some());
+                self.lower_flat_case(memory, realloc, &payloads, case, payload.as_deref(), flat)?;
+            },
+            (ComponentType::Result(..), ComponentValue::Result(result)) => {
+                let payloads = case_payloads(ty)?;
+                let (case, payload) = match result {
+                    Ok(payload) => (0, payload),
+                    Err(payload) => (1, payload),
                 };
-                let size = align_to(num_bytes, alignment);
-                MemoryLayout::new(size, alignment)
+                self.lower_flat_case(memory, realloc, &payloads, case, payload.as_deref(), flat)?;
             },
-            _ => MemoryLayout::new(0, 1), // Unknown types
+            _ => return Err(value_type_mismatch()),
         }
+        Ok(())
+    }
+
+    /// Lower the discriminant and payload of a variant-like value, widening
+    /// the payload to the joined slots of all cases
+    fn lower_flat_case<M: CanonicalMemory, R: CanonicalRealloc>(
+        &self,
+        memory: &mut M,
+        realloc: &mut R,
+        payloads: &[Option<&ComponentType>],
+        case: usize,
+        payload: Option<&ComponentValue>,
+        flat: &mut Vec<CoreValue>,
+    ) -> Result<()> {
+        flat.push(CoreValue::I32(case as i32));
+
+        let payload_flat = match (payloads[case], payload) {
+            (Some(payload_ty), Some(payload)) => {
+                self.lower_flat(memory, realloc, payload_ty, payload)?
+            },
+            (None, None) => Vec::new(),
+            _ => return Err(value_type_mismatch()),
+        };
+
+        for (i, slot_ty) in self.flatten_payloads(payloads)?.into_iter().enumerate() {
+            flat.push(match payload_flat.get(i) {
+                Some(value) => widen_flat(value, slot_ty),
+                None => zero_flat(slot_ty),
+            });
+        }
+        Ok(())
+    }
+
+    /// Lift a sequence of values passed as core values
+    ///
+    /// When the flattened types exceed `max_flat` core values, `values` holds
+    /// a single pointer to the values stored in memory as a tuple.
+    pub fn lift_flat_values<M: CanonicalMemory>(
+        &self,
+        memory: &M,
+        types: &[ComponentType],
+        values: &[CoreValue],
+        max_flat: usize,
+    ) -> Result<Vec<ComponentValue>> {
+        let tuple = ComponentType::Tuple(types.to_vec());
+        if self.flatten(&tuple)?.len() > max_flat {
+            let mut reader = FlatReader::new(values);
+            let ptr = reader.next_i32()? as u32;
+            reader.finish()?;
+            if ptr % self.align_of(&tuple)? != 0 {
+                return Err(Error::validation_error(
+                    "Error occurred: Misaligned spill pointer",
+                ));
+            }
+            return match self.lift_tuple(memory, types, ptr)? {
+                ComponentValue::Tuple(values) => Ok(values),
+                _ => Err(value_type_mismatch()),
+            };
+        }
+
+        match self.lift_flat(memory, &tuple, values)? {
+            ComponentValue::Tuple(values) => Ok(values),
+            _ => Err(value_type_mismatch()),
+        }
+    }
+
+    /// Lower a sequence of values to core values
+    ///
+    /// When the flattened types exceed `max_flat` core values, the values are
+    /// stored as a tuple in memory allocated through `realloc` and a single
+    /// pointer is returned instead.
+    pub fn lower_flat_values<M: CanonicalMemory, R: CanonicalRealloc>(
+        &self,
+        memory: &mut M,
+        realloc: &mut R,
+        types: &[ComponentType],
+        values: &[ComponentValue],
+        max_flat: usize,
+    ) -> Result<Vec<CoreValue>> {
+        #[cfg(feature = "std")]
+        if self.isolates_writes() {
+            let mut staged = StagedMemory::new(memory);
+            let flat = self.lower_values_flat(&mut staged, realloc, types, values, max_flat)?;
+            staged.commit()?;
+            return Ok(flat);
+        }
+
+        self.lower_values_flat(memory, realloc, types, values, max_flat)
+    }
+
+    fn lower_values_flat<M: CanonicalMemory, R: CanonicalRealloc>(
+        &self,
+        memory: &mut M,
+        realloc: &mut R,
+        types: &[ComponentType],
+        values: &[ComponentValue],
+        max_flat: usize,
+    ) -> Result<Vec<CoreValue>> {
+        let tuple = ComponentType::Tuple(types.to_vec());
+        let value = ComponentValue::Tuple(values.to_vec());
+        if self.flatten(&tuple)?.len() > max_flat {
+            let size = self.size_of(&tuple)?;
+            let ptr = self.allocate(memory, realloc, self.align_of(&tuple)?, size)?;
+            self.lower_value(memory, realloc, &tuple, &value, ptr)?;
+            return Ok(vec![CoreValue::I32(ptr as i32)]);
+        }
+
+        self.lower_flat(memory, realloc, &tuple, &value)
     }
 }
 
-/// Memory layout information for values
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct MemoryLayout {
-    /// Size in bytes
-    size:      usize,
-    /// Alignment requirement in bytes
-    alignment: usize,
+/// Align `value` up to the next multiple of `alignment`
+fn align_to(value: u32, alignment: u32) -> u32 {
+    value.div_ceil(alignment) * alignment
+}
+
+/// Size in bytes of the discriminant of a type with `case_count` cases
+fn discriminant_size(case_count: usize) -> u32 {
+    match case_count {
+        0..=0x100 => 1,
+        0x101..=0x1_0000 => 2,
+        _ => 4,
+    }
+}
+
+/// Size in bytes of a flags value with `flag_count` flags
+fn flags_size(flag_count: usize) -> u32 {
+    match flag_count {
+        0 => 0,
+        1..=8 => 1,
+        9..=16 => 2,
+        _ => 4 * flag_count.div_ceil(32) as u32,
+    }
+}
+
+/// Payload types of the cases of a variant-like type
+fn case_payloads(ty: &ComponentType) -> Result<Vec<Option<&ComponentType>>> {
+    match ty {
+        ComponentType::Variant(cases) => Ok(cases.iter().map(|(_, ty)| ty.as_ref()).collect()),
+        ComponentType::Enum(cases) => Ok(vec![None; cases.len()]),
+        ComponentType::Option(inner) => Ok(vec![None, Some(inner.as_ref())]),
+        ComponentType::Result(ok, err) => Ok(vec![ok.as_deref(), err.as_deref()]),
+        _ => Err(Error::type_mismatch_error("Type is not a variant")),
+    }
+}
+
+fn read_discriminant<M: CanonicalMemory>(memory: &M, size: u32, offset: u32) -> Result<u32> {
+    match size {
+        1 => memory.read_u8(offset).map(u32::from),
+        2 => memory.read_u16_le(offset).map(u32::from),
+        _ => memory.read_u32_le(offset),
+    }
+}
+
+fn write_discriminant<M: CanonicalMemory>(
+    memory: &mut M,
+    size: u32,
+    offset: u32,
+    case: u32,
+) -> Result<()> {
+    match size {
+        1 => memory.write_u8(offset, case as u8),
+        2 => memory.write_u16_le(offset, case as u16),
+        _ => memory.write_u32_le(offset, case),
+    }
+}
+
+/// Encode the active flags into their little-endian memory representation
+fn flags_to_bytes(flags: &[String], active: &[String]) -> Result<Vec<u8>> {
+    let mut bytes = vec![0u8; flags_size(flags.len()) as usize];
+    for name in active {
+        let index = flags
+            .iter()
+            .position(|flag_name| flag_name == name)
+            .ok_or_else(|| Error::validation_error("Error occurred: Unknown flag"))?;
+        bytes[index / 8] |= 1 << (index % 8);
+    }
+    Ok(bytes)
+}
+
+fn value_type_mismatch() -> Error {
+    Error::type_mismatch_error("Component value does not match its type")
+}
+
+/// Join the flat types of two variant cases sharing a slot
+fn join_flat(a: CoreType, b: CoreType) -> CoreType {
+    match (a, b) {
+        (a, b) if a == b => a,
+        (CoreType::I32, CoreType::F32) | (CoreType::F32, CoreType::I32) => CoreType::I32,
+        _ => CoreType::I64,
+    }
 }
 
-impl MemoryLayout {
-    /// Create a new memory layout
-    fn new(size: usize, alignment: usize) -> Self {
-        Self { size, alignment }
+/// Convert a joined slot value back to the flat type of the case payload
+fn narrow_flat(slot: &CoreValue, want: CoreType) -> CoreValue {
+    match (slot, want) {
+        (CoreValue::I32(v), CoreType::F32) => CoreValue::F32(FloatBits32::from_bits(*v as u32)),
+        (CoreValue::I64(v), CoreType::I32) => CoreValue::I32(*v as i32),
+        (CoreValue::I64(v), CoreType::F32) => CoreValue::F32(FloatBits32::from_bits(*v as u32)),
+        (CoreValue::I64(v), CoreType::F64) => CoreValue::F64(FloatBits64::from_bits(*v as u64)),
+        _ => slot.clone(),
     }
 }
 
-/// Align a value to the specified alignment
-fn align_to(value: usize, alignment: usize) -> usize {
-    (value + alignment - 1) & !(alignment - 1)
+/// Convert a case payload value to the joined flat type of its slot
+fn widen_flat(value: &CoreValue, slot: CoreType) -> CoreValue {
+    match (value, slot) {
+        (CoreValue::F32(v), CoreType::I32) => CoreValue::I32(v.to_bits() as i32),
+        (CoreValue::I32(v), CoreType::I64) => CoreValue::I64(i64::from(*v as u32)),
+        (CoreValue::F32(v), CoreType::I64) => CoreValue::I64(i64::from(v.to_bits())),
+        (CoreValue::F64(v), CoreType::I64) => CoreValue::I64(v.to_bits() as i64),
+        _ => value.clone(),
+    }
+}
+
+/// Zero value of a flat type, used to fill slots unused by a variant case
+fn zero_flat(ty: CoreType) -> CoreValue {
+    match ty {
+        CoreType::I64 => CoreValue::I64(0),
+        CoreType::F32 => CoreValue::F32(FloatBits32::from_bits(0)),
+        CoreType::F64 => CoreValue::F64(FloatBits64::from_bits(0)),
+        _ => CoreValue::I32(0),
+    }
+}
+
+/// Cursor over flat core values being lifted
+struct FlatReader<'a> {
+    values: &'a [CoreValue],
+    pos:    usize,
+}
+
+impl<'a> FlatReader<'a> {
+    fn new(values: &'a [CoreValue]) -> Self {
+        Self { values, pos: 0 }
+    }
+
+    /// Take the next value, which must have the given type
+    fn next(&mut self, ty: CoreType) -> Result<CoreValue> {
+        let value = self
+            .values
+            .get(self.pos)
+            .ok_or_else(|| Error::runtime_invalid_argument("Too few flat values"))?;
+        if value.value_type() != ty {
+            return Err(Error::type_mismatch_error("Flat value has unexpected type"));
+        }
+        self.pos += 1;
+        Ok(value.clone())
+    }
+
+    fn next_i32(&mut self) -> Result<i32> {
+        match self.next(CoreType::I32)? {
+            CoreValue::I32(v) => Ok(v),
+            _ => Err(Error::type_mismatch_error("Flat value has unexpected type")),
+        }
+    }
+
+    fn next_i64(&mut self) -> Result<i64> {
+        match self.next(CoreType::I64)? {
+            CoreValue::I64(v) => Ok(v),
+            _ => Err(Error::type_mismatch_error("Flat value has unexpected type")),
+        }
+    }
+
+    fn next_f32(&mut self) -> Result<f32> {
+        match self.next(CoreType::F32)? {
+            CoreValue::F32(v) => Ok(v.value()),
+            _ => Err(Error::type_mismatch_error("Flat value has unexpected type")),
+        }
+    }
+
+    fn next_f64(&mut self) -> Result<f64> {
+        match self.next(CoreType::F64)? {
+            CoreValue::F64(v) => Ok(v.value()),
+            _ => Err(Error::type_mismatch_error("Flat value has unexpected type")),
+        }
+    }
+
+    /// Check that every value was consumed
+    fn finish(&self) -> Result<()> {
+        if self.pos != self.values.len() {
+            return Err(Error::runtime_invalid_argument("Too many flat values"));
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Bump allocator standing in for a guest `cabi_realloc`
+    fn bump(start: u32) -> impl FnMut(u32, u32, u32, u32) -> Result<u32> {
+        let mut next = start;
+        move |_, _, align, size| {
+            let ptr = align_to(next, align);
+            next = ptr + size;
+            Ok(ptr)
+        }
+    }
+
+    fn round_trip(abi: &CanonicalABI, ty: &ComponentType, value: &ComponentValue) {
+        let mut memory = SimpleMemory::new(4096);
+        abi.lower(&mut memory, &mut bump(1024), ty, value, 0).unwrap();
+        assert_eq!(&abi.lift(&memory, ty, 0).unwrap(), value);
+
+        let flat = abi.lower_flat(&mut memory, &mut bump(2048), ty, value).unwrap();
+        assert_eq!(
+            flat.iter().map(CoreValue::value_type).collect::<Vec<_>>(),
+            abi.flatten(ty).unwrap()
+        );
+        assert_eq!(&abi.lift_flat(&memory, ty, &flat).unwrap(), value);
+    }
+
     #[test]
     fn test_simple_memory() {
         let mut memory = SimpleMemory::new(1024);
@@ -1424,11 +2229,107 @@ fn test_canonical_abi_string() {
         let mut memory = SimpleMemory::new(1024);
 
         // Lower a string
-        abi.lower_string(&mut memory, "hello", 0).unwrap();
+        abi.lower_string(&mut memory, &mut bump(100), "hello", 0).unwrap();
+        assert_eq!(memory.read_u32_le(0).unwrap(), 100);
+        assert_eq!(memory.read_u32_le(4).unwrap(), 5);
 
         // Lift it back
         let value = abi.lift_string(&memory, 0).unwrap();
         assert_eq!(value, ComponentValue::String("hello".to_string()));
+
+        // Strings cannot be lowered without a realloc function
+        assert!(abi.lower_string(&mut memory, &mut NoRealloc, "hello", 0).is_err());
+    }
+
+    #[test]
+    fn test_string_encodings() {
+        let value = ComponentValue::String("h\u{e9}llo \u{1F600}".to_string());
+        let ascii = ComponentValue::String("caf\u{e9}".to_string());
+
+        for encoding in [
+            StringEncoding::Utf8,
+            StringEncoding::Utf16,
+            StringEncoding::Latin1,
+        ] {
+            let abi = CanonicalABI::new().with_string_encoding(encoding);
+            round_trip(&abi, &ComponentType::String, &value);
+            round_trip(&abi, &ComponentType::String, &ascii);
+        }
+
+        // Latin-1 content is stored one byte per character, anything else as
+        // UTF-16 with the tag bit set in the length
+        let abi = CanonicalABI::new().with_string_encoding(StringEncoding::Latin1);
+        let mut memory = SimpleMemory::new(1024);
+        abi.lower(
+            &mut memory,
+            &mut bump(64),
+            &ComponentType::String,
+            &ascii,
+            0,
+        )
+        .unwrap();
+        assert_eq!(memory.read_u32_le(4).unwrap(), 4);
+        abi.lower(
+            &mut memory,
+            &mut bump(64),
+            &ComponentType::String,
+            &value,
+            0,
+        )
+        .unwrap();
+        assert_eq!(memory.read_u32_le(4).unwrap(), 8 | UTF16_TAG);
+
+        let abi = CanonicalABI::new().with_string_encoding(StringEncoding::Utf16);
+        abi.lower(
+            &mut memory,
+            &mut bump(64),
+            &ComponentType::String,
+            &value,
+            0,
+        )
+        .unwrap();
+        assert_eq!(memory.read_u32_le(4).unwrap(), 8);
+    }
+
+    #[test]
+    fn test_lifting_rejects_invalid_scalars() {
+        let abi = CanonicalABI::new();
+        let mut memory = SimpleMemory::new(1024);
+
+        // NaN payloads are not observable across the component boundary
+        memory.write_u64_le(0, 0xfff8_0000_0000_0001).unwrap();
+        let ComponentValue::F64(value) = abi.lift_f64(&memory, 0).unwrap() else {
+            panic!("expected f64");
+        };
+        assert_eq!(value.to_bits(), FloatBits64::NAN.to_bits());
+        let lifted = abi
+            .lift_flat(
+                &memory,
+                &ComponentType::F32,
+                &[CoreValue::F32(FloatBits32::from_bits(0x7fc0_0001))],
+            )
+            .unwrap();
+        let ComponentValue::F32(value) = lifted else {
+            panic!("expected f32");
+        };
+        assert_eq!(value.to_bits(), FloatBits32::NAN.to_bits());
+
+        // Surrogates and code points past U+10FFFF are not chars
+        memory.write_u32_le(8, 0xD800).unwrap();
+        assert!(abi.lift_char(&memory, 8).is_err());
+        assert!(abi
+            .lift_flat(&memory, &ComponentType::Char, &[CoreValue::I32(0x11_0000)])
+            .is_err());
+
+        // Neither are lone surrogates in UTF-16 nor malformed UTF-8 strings
+        memory.write_bytes(64, &0xD800u16.to_le_bytes()).unwrap();
+        memory.write_u32_le(16, 64).unwrap();
+        memory.write_u32_le(20, 1).unwrap();
+        let utf16 = CanonicalABI::new().with_string_encoding(StringEncoding::Utf16);
+        assert!(utf16.lift_string(&memory, 16).is_err());
+        memory.write_bytes(64, &[0xC3, 0x28]).unwrap();
+        memory.write_u32_le(20, 2).unwrap();
+        assert!(abi.lift_string(&memory, 16).is_err());
     }
 
     #[test]
@@ -1439,6 +2340,17 @@ fn test_size_calculation() {
         assert_eq!(abi.size_of(&ComponentType::S32).unwrap(), 4);
         assert_eq!(abi.size_of(&ComponentType::F64).unwrap(), 8);
         assert_eq!(abi.size_of(&ComponentType::String).unwrap(), 8); // ptr + len
+        assert_eq!(abi.size_of(&ComponentType::Own(0)).unwrap(), 4);
+
+        // Aggregates are padded to the alignment of their largest member
+        let tuple = ComponentType::Tuple(vec![ComponentType::U8, ComponentType::U64]);
+        assert_eq!(abi.size_of(&tuple).unwrap(), 16);
+        let option = ComponentType::Option(Box::new(ComponentType::U16));
+        assert_eq!(abi.size_of(&option).unwrap(), 4);
+        let flags = ComponentType::Flags((0..40).map(|i| i.to_string()).collect());
+        assert_eq!(abi.size_of(&flags).unwrap(), 8);
+        let cases = ComponentType::Enum((0..300).map(|i| i.to_string()).collect());
+        assert_eq!(abi.size_of(&cases).unwrap(), 2);
     }
 
     #[test]
@@ -1448,24 +2360,200 @@ fn test_alignment_calculation() {
         assert_eq!(abi.align_of(&ComponentType::Bool).unwrap(), 1);
         assert_eq!(abi.align_of(&ComponentType::S32).unwrap(), 4);
         assert_eq!(abi.align_of(&ComponentType::F64).unwrap(), 8);
+        let result = ComponentType::Result(Some(Box::new(ComponentType::F64)), None);
+        assert_eq!(abi.align_of(&result).unwrap(), 8);
     }
 
     #[test]
     fn test_option_value() {
+        let abi = CanonicalABI::new();
+        let ty = ComponentType::Option(Box::new(ComponentType::S32));
+
+        round_trip(&abi, &ty, &ComponentValue::Option(None));
+        round_trip(
+            &abi,
+            &ty,
+            &ComponentValue::Option(Some(Box::new(ComponentValue::S32(42)))),
+        );
+
+        // Discriminants other than 0 and 1 are rejected
+        let mut memory = SimpleMemory::new(64);
+        memory.write_u8(0, 2).unwrap();
+        assert!(abi.lift(&memory, &ty, 0).is_err());
+    }
+
+    #[test]
+    fn test_compound_round_trips() {
+        let abi = CanonicalABI::new();
+        let point = ComponentType::Record(vec![
+            ("x".to_string(), ComponentType::U8),
+            ("y".to_string(), ComponentType::F64),
+        ]);
+        let shape = ComponentType::Variant(vec![
+            ("none".to_string(), None),
+            ("circle".to_string(), Some(ComponentType::F32)),
+            ("named".to_string(), Some(ComponentType::String)),
+        ]);
+        let ty = ComponentType::Tuple(vec![
+            ComponentType::List(Box::new(point.clone())),
+            shape.clone(),
+            ComponentType::Enum(vec!["a".to_string(), "b".to_string()]),
+            ComponentType::Flags(vec!["r".to_string(), "w".to_string(), "x".to_string()]),
+            ComponentType::Result(Some(Box::new(ComponentType::U64)), None),
+            ComponentType::Own(1),
+            ComponentType::Borrow(1),
+        ]);
+        let value = ComponentValue::Tuple(vec![
+            ComponentValue::List(vec![
+                ComponentValue::Record(vec![
+                    ("x".to_string(), ComponentValue::U8(1)),
+                    ("y".to_string(), ComponentValue::F64(2.5)),
+                ]),
+                ComponentValue::Record(vec![
+                    ("x".to_string(), ComponentValue::U8(3)),
+                    ("y".to_string(), ComponentValue::F64(-4.0)),
+                ]),
+            ]),
+            ComponentValue::Variant(
+                "circle".to_string(),
+                Some(Box::new(ComponentValue::F32(1.5))),
+            ),
+            ComponentValue::Enum("b".to_string()),
+            ComponentValue::Flags(vec!["r".to_string(), "x".to_string()]),
+            ComponentValue::Result(Ok(Some(Box::new(ComponentValue::U64(u64::MAX))))),
+            ComponentValue::Own(7),
+            ComponentValue::Borrow(8),
+        ]);
+        round_trip(&abi, &ty, &value);
+
+        // Payloads sharing a joined flat slot are coerced back and forth
+        let named = ComponentValue::Variant(
+            "named".to_string(),
+            Some(Box::new(ComponentValue::String("disc".to_string()))),
+        );
+        round_trip(&abi, &shape, &named);
+        assert_eq!(
+            abi.flatten(&shape).unwrap(),
+            vec![CoreType::I32, CoreType::I32, CoreType::I32]
+        );
+    }
+
+    #[test]
+    fn test_lower_type_mismatch() {
+        let abi = CanonicalABI::new();
+        let mut memory = SimpleMemory::new(64);
+        let mut realloc = bump(32);
+
+        let err = abi
+            .lower(
+                &mut memory,
+                &mut realloc,
+                &ComponentType::U32,
+                &ComponentValue::S32(1),
+                0,
+            )
+            .unwrap_err();
+        assert_eq!(err.code, value_type_mismatch().code);
+
+        let flags = ComponentType::Flags(vec!["a".to_string()]);
+        let unknown = ComponentValue::Flags(vec!["b".to_string()]);
+        assert!(abi.lower(&mut memory, &mut realloc, &flags, &unknown, 0).is_err());
+    }
+
+    #[test]
+    fn test_flat_values_spill() {
         let abi = CanonicalABI::new();
         let mut memory = SimpleMemory::new(1024);
+        let types = vec![ComponentType::U64; MAX_FLAT_PARAMS + 1];
+        let values: Vec<_> = (0..=MAX_FLAT_PARAMS as u64).map(ComponentValue::U64).collect();
+
+        let flat = abi
+            .lower_flat_values(
+                &mut memory,
+                &mut bump(100),
+                &types,
+                &values,
+                MAX_FLAT_PARAMS,
+            )
+            .unwrap();
+        // Spilled into an 8-byte aligned tuple
+        assert_eq!(flat, vec![CoreValue::I32(104)]);
+        assert_eq!(memory.read_u64_le(104 + 8).unwrap(), 1);
+        assert_eq!(
+            abi.lift_flat_values(&memory, &types, &flat, MAX_FLAT_PARAMS).unwrap(),
+            values
+        );
+
+        let results = [ComponentValue::U64(9)];
+        let flat = abi
+            .lower_flat_values(
+                &mut memory,
+                &mut NoRealloc,
+                &types[..1],
+                &results,
+                MAX_FLAT_RESULTS,
+            )
+            .unwrap();
+        assert_eq!(flat, vec![CoreValue::I64(9)]);
+    }
 
-        // Test None option
-        abi.lower_option(&mut memory, &None, 0).unwrap();
-        let value = abi.lift_option(&memory, &ComponentType::S32, 0).unwrap();
-        assert_eq!(value, ComponentValue::Option(None));
+    #[test]
+    fn test_isolated_lowering_is_atomic() {
+        let abi = CanonicalABI::new().with_memory_strategy(MemoryStrategy::Isolated);
+        let mut memory = SimpleMemory::new(64);
+        let ty = ComponentType::Tuple(vec![ComponentType::U32, ComponentType::String]);
+        let value = ComponentValue::Tuple(vec![
+            ComponentValue::U32(0xAABBCCDD),
+            ComponentValue::String("x".to_string()),
+        ]);
+
+        // The string cannot be allocated, so the first field is not written
+        assert!(abi.lower(&mut memory, &mut NoRealloc, &ty, &value, 0).is_err());
+        assert_eq!(memory.read_u32_le(0).unwrap(), 0);
 
-        // Test Some option
-        let some_value = Some(Box::new(ComponentValue::S32(42)));
-        abi.lower_option(&mut memory, &some_value, 10).unwrap();
-        // Note: This test is simplified and doesn't actually verify the full
-        // lifting because the lowering implementation is also
-        // simplified
+        abi.lower(&mut memory, &mut bump(32), &ty, &value, 0).unwrap();
+        assert_eq!(abi.lift(&memory, &ty, 0).unwrap(), value);
+    }
+
+    #[test]
+    fn test_default_values() {
+        let ty = ComponentType::Record(vec![
+            ("id".to_string(), ComponentType::U64),
+            (
+                "state".to_string(),
+                ComponentType::Variant(vec![
+                    ("idle".to_string(), None),
+                    ("busy".to_string(), Some(ComponentType::U32)),
+                ]),
+            ),
+            (
+                "reply".to_string(),
+                ComponentType::Result(Some(Box::new(ComponentType::String)), None),
+            ),
+        ]);
+        assert_eq!(
+            ty.default_value(),
+            Some(ComponentValue::Record(vec![
+                ("id".to_string(), ComponentValue::U64(0)),
+                (
+                    "state".to_string(),
+                    ComponentValue::Variant("idle".to_string(), None)
+                ),
+                (
+                    "reply".to_string(),
+                    ComponentValue::Result(Ok(Some(Box::new(ComponentValue::String(
+                        String::new()
+                    )))))
+                ),
+            ]))
+        );
+
+        // Handles have no zero value
+        assert_eq!(
+            ComponentType::Tuple(vec![ComponentType::Own(0)]).default_value(),
+            None
+        );
+        assert_eq!(ComponentType::Enum(Vec::new()).default_value(), None);
     }
 
     #[test]
@@ -1551,6 +2639,27 @@ fn update_checksum(&self, checksum: &mut wrt_foundation::traits::Checksum) {
                 20u8.update_checksum(checksum);
                 flags.len().update_checksum(checksum);
             },
+            ComponentType::Own(resource) => {
+                21u8.update_checksum(checksum);
+                resource.update_checksum(checksum);
+            },
+            ComponentType::Borrow(resource) => {
+                22u8.update_checksum(checksum);
+                resource.update_checksum(checksum);
+            },
+            ComponentType::Stream(element) => {
+                23u8.update_checksum(checksum);
+                if let Some(element) = element {
+                    element.update_checksum(checksum);
+                }
+            },
+            ComponentType::Future(value) => {
+                24u8.update_checksum(checksum);
+                if let Some(value) = value {
+                    value.update_checksum(checksum);
+                }
+            },
+            ComponentType::ErrorContext => 25u8.update_checksum(checksum),
         }
     }
 }
@@ -1608,6 +2717,11 @@ fn update_checksum(&self, checksum: &mut wrt_foundation::traits::Checksum) {
             ComponentValue::Option(_) => 18u8.update_checksum(checksum),
             ComponentValue::Result { .. } => 19u8.update_checksum(checksum),
             ComponentValue::Flags(_) => 20u8.update_checksum(checksum),
+            ComponentValue::Own(_) => 21u8.update_checksum(checksum),
+            ComponentValue::Borrow(_) => 22u8.update_checksum(checksum),
+            ComponentValue::Stream(_) => 23u8.update_checksum(checksum),
+            ComponentValue::Future(_) => 24u8.update_checksum(checksum),
+            ComponentValue::ErrorContext(_) => 25u8.update_checksum(checksum),
         }
     }
 }
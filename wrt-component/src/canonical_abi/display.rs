@@ -0,0 +1,682 @@
+//! Human-readable rendering of component values and types
+//!
+//! [`ComponentValue`] implements [`Display`](fmt::Display) in the WebAssembly
+//! Value Encoding (WAVE), the text syntax of `wasm-tools` and `wasmtime` for
+//! component values:
+//!
+//! ```text
+//! {name: "wrt", tags: ["a", "b"], size: some(42), mode: %none, flags: {read, write}}
+//! ```
+//!
+//! Handles, which WAVE does not cover, are written as `own#3`, `borrow#3`,
+//! `stream#3`, `future#3` and `error-context#3`. The alternate flag (`{:#}`)
+//! puts the fields of records and the items of lists and tuples on indented
+//! lines, and [`ComponentValue::display_with`] elides what lies beyond
+//! [`DisplayLimits`] so that values a guest controls keep log lines and error
+//! messages short.
+//!
+//! [`ComponentValue::json`] renders the same value as JSON for structured
+//! logs, and [`ComponentType`] displays in WIT syntax. Neither allocates, so
+//! values can be logged on targets without `std`.
+//!
+//! With `std`, [`ComponentValueFormatter`] lets a
+//! [`LoggingStrategy`](wrt_intercept::strategies::LoggingStrategy) write the
+//! values of the calls it logs this way.
+
+use core::fmt;
+
+#[cfg(feature = "std")]
+use wrt_foundation::values::Value;
+#[cfg(feature = "std")]
+use wrt_intercept::strategies::ValueFormatter;
+
+use super::canonical_abi::{
+    ComponentType,
+    ComponentValue,
+};
+
+/// Labels that must be escaped with `%` to not read as WAVE keywords
+const WAVE_KEYWORDS: [&str; 8] = ["true", "false", "some", "none", "ok", "err", "inf", "nan"];
+
+fn write_label(f: &mut fmt::Formatter<'_>, label: &str) -> fmt::Result {
+    if WAVE_KEYWORDS.contains(&label) {
+        f.write_str("%")?;
+    }
+    f.write_str(label)
+}
+
+/// Write `text` between `quote`s, escaping as both WAVE and JSON expect
+fn write_quoted(f: &mut fmt::Formatter<'_>, text: &str, quote: char, json: bool) -> fmt::Result {
+    use fmt::Write;
+
+    f.write_char(quote)?;
+    for c in text.chars() {
+        match c {
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            '"' | '\'' if c == quote => {
+                f.write_char('\\')?;
+                f.write_char(c)?;
+            },
+            c if c.is_control() && json => write!(f, "\\u{:04x}", c as u32)?,
+            c if c.is_control() => write!(f, "\\u{{{:x}}}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char(quote)
+}
+
+/// Write `items` separated by commas between `open` and `close`
+fn write_list<T>(
+    f: &mut fmt::Formatter<'_>,
+    open: &str,
+    items: impl IntoIterator<Item = T>,
+    close: &str,
+    mut item: impl FnMut(&mut fmt::Formatter<'_>, T) -> fmt::Result,
+) -> fmt::Result {
+    f.write_str(open)?;
+    for (index, value) in items.into_iter().enumerate() {
+        if index > 0 {
+            f.write_str(", ")?;
+        }
+        item(f, value)?;
+    }
+    f.write_str(close)
+}
+
+fn write_wave_float(f: &mut fmt::Formatter<'_>, value: f64) -> fmt::Result {
+    if value.is_nan() {
+        f.write_str("nan")
+    } else if value.is_infinite() {
+        f.write_str(if value > 0.0 { "inf" } else { "-inf" })
+    } else {
+        write!(f, "{value}")
+    }
+}
+
+/// How much of a [`ComponentValue`] is rendered
+///
+/// Contents of lists, tuples, records and case payloads nested deeper than
+/// `max_depth`, items of a list, tuple, record or flags beyond `max_items` and
+/// characters of a string beyond `max_string_chars` are elided as `...`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplayLimits {
+    /// Levels of nested values shown
+    pub max_depth:        usize,
+    /// Items shown per list, tuple, record or flags
+    pub max_items:        usize,
+    /// Characters shown per string
+    pub max_string_chars: usize,
+}
+
+impl DisplayLimits {
+    /// Limits suited to a log line or an error message
+    pub const COMPACT: Self = Self {
+        max_depth:        4,
+        max_items:        8,
+        max_string_chars: 64,
+    };
+    /// Render values in full
+    pub const UNLIMITED: Self = Self {
+        max_depth:        usize::MAX,
+        max_items:        usize::MAX,
+        max_string_chars: usize::MAX,
+    };
+}
+
+impl Default for DisplayLimits {
+    fn default() -> Self {
+        Self::UNLIMITED
+    }
+}
+
+/// WAVE writer applying [`DisplayLimits`], indenting nested values when
+/// `pretty`
+struct WaveWriter<'f, 'a> {
+    f:      &'f mut fmt::Formatter<'a>,
+    limits: DisplayLimits,
+    pretty: bool,
+}
+
+impl WaveWriter<'_, '_> {
+    fn value(&mut self, value: &ComponentValue, depth: usize) -> fmt::Result {
+        type V = ComponentValue;
+
+        match value {
+            V::Bool(value) => write!(self.f, "{value}"),
+            V::S8(value) => write!(self.f, "{value}"),
+            V::U8(value) => write!(self.f, "{value}"),
+            V::S16(value) => write!(self.f, "{value}"),
+            V::U16(value) => write!(self.f, "{value}"),
+            V::S32(value) => write!(self.f, "{value}"),
+            V::U32(value) => write!(self.f, "{value}"),
+            V::S64(value) => write!(self.f, "{value}"),
+            V::U64(value) => write!(self.f, "{value}"),
+            V::F32(value) => write_wave_float(self.f, f64::from(*value)),
+            V::F64(value) => write_wave_float(self.f, *value),
+            V::Char(value) => {
+                let mut buffer = [0; 4];
+                write_quoted(self.f, value.encode_utf8(&mut buffer), '\'', false)
+            },
+            V::String(value) => self.string(value),
+            V::List(values) => self.items("[", values, "]", depth, self.pretty, |w, v, depth| {
+                w.value(v, depth)
+            }),
+            V::Tuple(values) => self.items("(", values, ")", depth, self.pretty, |w, v, depth| {
+                w.value(v, depth)
+            }),
+            V::Record(fields) => self.items(
+                "{",
+                fields,
+                "}",
+                depth,
+                self.pretty,
+                |w, (name, value), depth| {
+                    write_label(w.f, name)?;
+                    w.f.write_str(": ")?;
+                    w.value(value, depth)
+                },
+            ),
+            V::Variant(case, payload) => {
+                write_label(self.f, case)?;
+                self.payload(payload.as_deref(), depth)
+            },
+            V::Enum(case) => write_label(self.f, case),
+            V::Option(None) => self.f.write_str("none"),
+            V::Option(Some(value)) => {
+                self.f.write_str("some")?;
+                self.payload(Some(value.as_ref()), depth)
+            },
+            V::Result(Ok(payload)) => {
+                self.f.write_str("ok")?;
+                self.payload(payload.as_deref(), depth)
+            },
+            V::Result(Err(payload)) => {
+                self.f.write_str("err")?;
+                self.payload(payload.as_deref(), depth)
+            },
+            // Flags stay on one line, they are labels only
+            V::Flags(flags) => self.items("{", flags, "}", depth, false, |w, flag, _| {
+                write_label(w.f, flag)
+            }),
+            V::Own(handle) => write!(self.f, "own#{handle}"),
+            V::Borrow(handle) => write!(self.f, "borrow#{handle}"),
+            V::Stream(handle) => write!(self.f, "stream#{handle}"),
+            V::Future(handle) => write!(self.f, "future#{handle}"),
+            V::ErrorContext(handle) => write!(self.f, "error-context#{handle}"),
+        }
+    }
+
+    fn string(&mut self, text: &str) -> fmt::Result {
+        match text.char_indices().nth(self.limits.max_string_chars) {
+            Some((end, _)) => {
+                write_quoted(self.f, &text[..end], '"', false)?;
+                self.f.write_str("...")
+            },
+            None => write_quoted(self.f, text, '"', false),
+        }
+    }
+
+    /// Write the optional payload of a case as `(payload)`
+    fn payload(&mut self, payload: Option<&ComponentValue>, depth: usize) -> fmt::Result {
+        match payload {
+            Some(_) if depth >= self.limits.max_depth => self.f.write_str("(...)"),
+            Some(payload) => {
+                self.f.write_str("(")?;
+                self.value(payload, depth + 1)?;
+                self.f.write_str(")")
+            },
+            None => Ok(()),
+        }
+    }
+
+    /// Write `items` between `open` and `close`, one per line when
+    /// `break_lines`
+    fn items<T>(
+        &mut self,
+        open: &str,
+        items: &[T],
+        close: &str,
+        depth: usize,
+        break_lines: bool,
+        mut item: impl FnMut(&mut Self, &T, usize) -> fmt::Result,
+    ) -> fmt::Result {
+        self.f.write_str(open)?;
+        if items.is_empty() {
+            return self.f.write_str(close);
+        }
+        if depth >= self.limits.max_depth {
+            self.f.write_str("...")?;
+            return self.f.write_str(close);
+        }
+
+        let shown = items.len().min(self.limits.max_items);
+        for (index, value) in items[..shown].iter().enumerate() {
+            self.separator(index, depth + 1, break_lines)?;
+            item(self, value, depth + 1)?;
+        }
+        if shown < items.len() {
+            self.separator(shown, depth + 1, break_lines)?;
+            self.f.write_str("...")?;
+        }
+        if break_lines {
+            self.new_line(depth)?;
+        }
+        self.f.write_str(close)
+    }
+
+    fn separator(&mut self, index: usize, depth: usize, break_lines: bool) -> fmt::Result {
+        if index > 0 {
+            self.f.write_str(if break_lines { "," } else { ", " })?;
+        }
+        if break_lines {
+            self.new_line(depth)?;
+        }
+        Ok(())
+    }
+
+    fn new_line(&mut self, depth: usize) -> fmt::Result {
+        self.f.write_str("\n")?;
+        for _ in 0..depth {
+            self.f.write_str("  ")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ComponentValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.display_with(DisplayLimits::UNLIMITED).fmt(f)
+    }
+}
+
+impl ComponentValue {
+    /// Render the value in WAVE, eliding what lies beyond `limits`
+    ///
+    /// ```text
+    /// {name: "a very long na"..., tags: ["a", "b", ...], nested: {inner: [...]}}
+    /// ```
+    pub fn display_with(&self, limits: DisplayLimits) -> ComponentValueDisplay<'_> {
+        ComponentValueDisplay {
+            value: self,
+            limits,
+        }
+    }
+
+    /// Render the value as JSON
+    ///
+    /// Lists, tuples and flags become arrays, records objects, and options
+    /// their payload or `null`. Variants and results become an object with
+    /// the case name as the only key, enums the case name. Floats that JSON
+    /// cannot represent become the strings `"NaN"`, `"Infinity"` and
+    /// `"-Infinity"`, and handles an object such as `{"own": 3}`.
+    pub fn json(&self) -> ComponentValueJson<'_> {
+        ComponentValueJson(self)
+    }
+}
+
+/// WAVE rendering of a [`ComponentValue`] within [`DisplayLimits`], see
+/// [`ComponentValue::display_with`]
+#[derive(Debug, Clone, Copy)]
+pub struct ComponentValueDisplay<'a> {
+    value:  &'a ComponentValue,
+    limits: DisplayLimits,
+}
+
+impl fmt::Display for ComponentValueDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pretty = f.alternate();
+        WaveWriter {
+            f,
+            limits: self.limits,
+            pretty,
+        }
+        .value(self.value, 0)
+    }
+}
+
+/// [`ValueFormatter`] writing the core values of logged calls in WAVE within
+/// [`DisplayLimits`]
+///
+/// Integers and floats are written as the `s32`, `s64`, `f32` and `f64`
+/// component values they carry, other values as they display.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ComponentValueFormatter {
+    limits: DisplayLimits,
+}
+
+#[cfg(feature = "std")]
+impl ComponentValueFormatter {
+    /// Create a formatter eliding what lies beyond `limits`
+    pub const fn new(limits: DisplayLimits) -> Self {
+        Self { limits }
+    }
+}
+
+#[cfg(feature = "std")]
+impl ValueFormatter for ComponentValueFormatter {
+    fn format_value(&self, value: &Value) -> String {
+        let value = match value {
+            Value::I32(value) => ComponentValue::S32(*value),
+            Value::I64(value) => ComponentValue::S64(*value),
+            Value::F32(value) => ComponentValue::F32(value.value()),
+            Value::F64(value) => ComponentValue::F64(value.value()),
+            value => return value.to_string(),
+        };
+        value.display_with(self.limits).to_string()
+    }
+}
+
+/// JSON rendering of a [`ComponentValue`], see [`ComponentValue::json`]
+#[derive(Debug, Clone, Copy)]
+pub struct ComponentValueJson<'a>(&'a ComponentValue);
+
+fn write_json_float(f: &mut fmt::Formatter<'_>, value: f64) -> fmt::Result {
+    if value.is_nan() {
+        f.write_str("\"NaN\"")
+    } else if value.is_infinite() {
+        f.write_str(if value > 0.0 { "\"Infinity\"" } else { "\"-Infinity\"" })
+    } else {
+        write!(f, "{value}")
+    }
+}
+
+fn write_json_case(
+    f: &mut fmt::Formatter<'_>,
+    case: &str,
+    payload: Option<&ComponentValue>,
+) -> fmt::Result {
+    f.write_str("{")?;
+    write_quoted(f, case, '"', true)?;
+    match payload {
+        Some(payload) => write!(f, ": {}}}", payload.json()),
+        None => f.write_str(": null}"),
+    }
+}
+
+impl fmt::Display for ComponentValueJson<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        type V = ComponentValue;
+
+        match self.0 {
+            V::Bool(_)
+            | V::S8(_)
+            | V::U8(_)
+            | V::S16(_)
+            | V::U16(_)
+            | V::S32(_)
+            | V::U32(_)
+            | V::S64(_)
+            | V::U64(_) => write!(f, "{}", self.0),
+            V::F32(value) => write_json_float(f, f64::from(*value)),
+            V::F64(value) => write_json_float(f, *value),
+            V::Char(value) => {
+                let mut buffer = [0; 4];
+                write_quoted(f, value.encode_utf8(&mut buffer), '"', true)
+            },
+            V::String(value) | V::Enum(value) => write_quoted(f, value, '"', true),
+            V::List(values) | V::Tuple(values) => {
+                write_list(f, "[", values, "]", |f, v| write!(f, "{}", v.json()))
+            },
+            V::Record(fields) => write_list(f, "{", fields, "}", |f, (name, value)| {
+                write_quoted(f, name, '"', true)?;
+                write!(f, ": {}", value.json())
+            }),
+            V::Variant(case, payload) => write_json_case(f, case, payload.as_deref()),
+            V::Option(None) => f.write_str("null"),
+            V::Option(Some(value)) => write!(f, "{}", value.json()),
+            V::Result(Ok(payload)) => write_json_case(f, "ok", payload.as_deref()),
+            V::Result(Err(payload)) => write_json_case(f, "err", payload.as_deref()),
+            V::Flags(flags) => write_list(f, "[", flags, "]", |f, flag| {
+                write_quoted(f, flag, '"', true)
+            }),
+            V::Own(handle) => write!(f, "{{\"own\": {handle}}}"),
+            V::Borrow(handle) => write!(f, "{{\"borrow\": {handle}}}"),
+            V::Stream(handle) => write!(f, "{{\"stream\": {handle}}}"),
+            V::Future(handle) => write!(f, "{{\"future\": {handle}}}"),
+            V::ErrorContext(handle) => write!(f, "{{\"error-context\": {handle}}}"),
+        }
+    }
+}
+
+impl fmt::Display for ComponentType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let optional = |f: &mut fmt::Formatter<'_>, ty: Option<&ComponentType>| match ty {
+            Some(ty) => write!(f, "{ty}"),
+            None => f.write_str("_"),
+        };
+
+        match self {
+            Self::Bool => f.write_str("bool"),
+            Self::S8 => f.write_str("s8"),
+            Self::U8 => f.write_str("u8"),
+            Self::S16 => f.write_str("s16"),
+            Self::U16 => f.write_str("u16"),
+            Self::S32 => f.write_str("s32"),
+            Self::U32 => f.write_str("u32"),
+            Self::S64 => f.write_str("s64"),
+            Self::U64 => f.write_str("u64"),
+            Self::F32 => f.write_str("f32"),
+            Self::F64 => f.write_str("f64"),
+            Self::Char => f.write_str("char"),
+            Self::String => f.write_str("string"),
+            Self::List(element) => write!(f, "list<{element}>"),
+            Self::Record(fields) => write_list(f, "record { ", fields, " }", |f, (name, ty)| {
+                write!(f, "{name}: {ty}")
+            }),
+            Self::Tuple(types) => write_list(f, "tuple<", types, ">", |f, ty| write!(f, "{ty}")),
+            Self::Variant(cases) => {
+                write_list(f, "variant { ", cases, " }", |f, (name, ty)| match ty {
+                    Some(ty) => write!(f, "{name}({ty})"),
+                    None => f.write_str(name),
+                })
+            },
+            Self::Enum(cases) => write_list(f, "enum { ", cases, " }", |f, case| f.write_str(case)),
+            Self::Option(inner) => write!(f, "option<{inner}>"),
+            Self::Result(None, None) => f.write_str("result"),
+            Self::Result(ok, None) => {
+                f.write_str("result<")?;
+                optional(f, ok.as_deref())?;
+                f.write_str(">")
+            },
+            Self::Result(ok, err) => {
+                f.write_str("result<")?;
+                optional(f, ok.as_deref())?;
+                f.write_str(", ")?;
+                optional(f, err.as_deref())?;
+                f.write_str(">")
+            },
+            Self::Flags(flags) => {
+                write_list(f, "flags { ", flags, " }", |f, flag| f.write_str(flag))
+            },
+            Self::Own(resource) => write!(f, "own<{resource}>"),
+            Self::Borrow(resource) => write!(f, "borrow<{resource}>"),
+            Self::Stream(None) => f.write_str("stream"),
+            Self::Stream(Some(element)) => write!(f, "stream<{element}>"),
+            Self::Future(None) => f.write_str("future"),
+            Self::Future(Some(value)) => write!(f, "future<{value}>"),
+            Self::ErrorContext => f.write_str("error-context"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> ComponentValue {
+        ComponentValue::Record(vec![
+            ("name".into(), ComponentValue::String("a \"b\"\n".into())),
+            (
+                "tags".into(),
+                ComponentValue::List(vec![ComponentValue::Char('\''), ComponentValue::U8(7)]),
+            ),
+            (
+                "size".into(),
+                ComponentValue::Option(Some(Box::new(ComponentValue::F32(f32::NAN)))),
+            ),
+            ("mode".into(), ComponentValue::Enum("none".into())),
+            (
+                "flags".into(),
+                ComponentValue::Flags(vec!["read".into(), "write".into()]),
+            ),
+            (
+                "shape".into(),
+                ComponentValue::Variant(
+                    "circle".into(),
+                    Some(Box::new(ComponentValue::Tuple(vec![
+                        ComponentValue::S32(-1),
+                        ComponentValue::F64(2.5),
+                    ]))),
+                ),
+            ),
+            ("done".into(), ComponentValue::Result(Ok(None))),
+            ("file".into(), ComponentValue::Own(3)),
+        ])
+    }
+
+    #[test]
+    fn test_wave_display() {
+        assert_eq!(
+            sample().to_string(),
+            concat!(
+                r#"{name: "a \"b\"\n", tags: ['\'', 7], size: some(nan), mode: %none, "#,
+                r#"flags: {read, write}, shape: circle((-1, 2.5)), done: ok, file: own#3}"#
+            )
+        );
+        assert_eq!(
+            ComponentValue::Result(Err(Some(Box::new(ComponentValue::Bool(false))))).to_string(),
+            "err(false)"
+        );
+        assert_eq!(
+            ComponentValue::String("\u{7}".into()).to_string(),
+            r#""\u{7}""#
+        );
+    }
+
+    #[test]
+    fn test_limited_display() {
+        let limits = DisplayLimits {
+            max_depth:        2,
+            max_items:        2,
+            max_string_chars: 3,
+        };
+        assert_eq!(
+            sample().display_with(limits).to_string(),
+            r#"{name: "a \""..., tags: ['\'', 7], ...}"#
+        );
+
+        let nested = ComponentValue::List(vec![ComponentValue::Option(Some(Box::new(
+            ComponentValue::List(vec![ComponentValue::U8(1)]),
+        )))]);
+        assert_eq!(nested.display_with(limits).to_string(), "[some([...])]");
+        assert_eq!(
+            ComponentValue::List(Vec::new()).display_with(limits).to_string(),
+            "[]"
+        );
+        // Characters, not bytes, are counted
+        assert_eq!(
+            ComponentValue::String("äöüß".into()).display_with(limits).to_string(),
+            r#""äöü"..."#
+        );
+        assert_eq!(
+            sample().display_with(DisplayLimits::UNLIMITED).to_string(),
+            sample().to_string()
+        );
+    }
+
+    #[test]
+    fn test_pretty_display() {
+        let value = ComponentValue::Record(vec![
+            (
+                "point".into(),
+                ComponentValue::Tuple(vec![ComponentValue::S32(1), ComponentValue::S32(2)]),
+            ),
+            (
+                "flags".into(),
+                ComponentValue::Flags(vec!["read".into(), "write".into()]),
+            ),
+            ("empty".into(), ComponentValue::List(Vec::new())),
+        ]);
+        assert_eq!(
+            format!("{value:#}"),
+            "{\n  point: (\n    1,\n    2\n  ),\n  flags: {read, write},\n  empty: []\n}"
+        );
+
+        let limits = DisplayLimits {
+            max_items: 1,
+            ..DisplayLimits::UNLIMITED
+        };
+        assert_eq!(
+            format!("{:#}", value.display_with(limits)),
+            "{\n  point: (\n    1,\n    ...\n  ),\n  ...\n}"
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_value_formatter() {
+        use wrt_foundation::values::{
+            FloatBits64,
+            Value,
+        };
+
+        let formatter = ComponentValueFormatter::new(DisplayLimits::COMPACT);
+        assert_eq!(formatter.format_value(&Value::I32(-7)), "-7");
+        assert_eq!(
+            formatter.format_value(&Value::F64(FloatBits64::from_float(f64::NAN))),
+            "nan"
+        );
+        assert_eq!(
+            formatter.format_value(&Value::FuncRef(None)),
+            "funcref:null"
+        );
+    }
+
+    #[test]
+    fn test_json_display() {
+        assert_eq!(
+            sample().json().to_string(),
+            concat!(
+                r#"{"name": "a \"b\"\n", "tags": ["'", 7], "size": "NaN", "mode": "none", "#,
+                r#""flags": ["read", "write"], "shape": {"circle": [-1, 2.5]}, "#,
+                r#""done": {"ok": null}, "file": {"own": 3}}"#
+            )
+        );
+        assert_eq!(ComponentValue::Option(None).json().to_string(), "null");
+        assert_eq!(
+            ComponentValue::String("\u{7}".into()).json().to_string(),
+            r#""\u0007""#
+        );
+    }
+
+    #[test]
+    fn test_type_display() {
+        let ty = ComponentType::Record(vec![
+            (
+                "items".into(),
+                ComponentType::List(Box::new(ComponentType::String)),
+            ),
+            (
+                "status".into(),
+                ComponentType::Result(None, Some(Box::new(ComponentType::ErrorContext))),
+            ),
+            (
+                "events".into(),
+                ComponentType::Stream(Some(Box::new(ComponentType::U8))),
+            ),
+        ]);
+        assert_eq!(
+            ty.to_string(),
+            "record { items: list<string>, status: result<_, error-context>, events: stream<u8> }"
+        );
+        assert_eq!(ComponentType::Result(None, None).to_string(), "result");
+        assert_eq!(
+            ComponentType::Result(Some(Box::new(ComponentType::U32)), None).to_string(),
+            "result<u32>"
+        );
+    }
+}
@@ -0,0 +1,748 @@
+//! Build-time composition of components
+//!
+//! [`ComponentComposer`] takes several parsed components and a wiring
+//! description and produces a single component embedding them as nested
+//! components, so a deployment can be assembled once at build time and handed
+//! to the [`ComponentLinker`](super::ComponentLinker) as one unit.
+//!
+//! Each import of a part is satisfied, in order of preference, by
+//!
+//! 1. an explicit [`CompositionWire`] to an export of another part,
+//! 2. the only other part exporting a compatible item of the same name, or
+//! 3. an import of the composed component with the same name and type, shared
+//!    by all parts importing it.
+//!
+//! The parts are instantiated in dependency order, each instantiation
+//! preceded by the aliases of the provider exports it takes as arguments.
+//! Index references of the composed component follow that definition order:
+//! imports first, then the aliases and instance of every part, then the
+//! aliases of the re-exported items.
+
+use std::{
+    boxed::Box,
+    collections::HashMap,
+    string::String,
+    vec::Vec,
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_format::component::{
+    Alias,
+    AliasTarget,
+    Component as WrtComponent,
+    ComponentTypeDefinition,
+    Export,
+    ExportName,
+    ExternType,
+    FormatValType,
+    Import,
+    Instance,
+    InstanceExpr,
+    InstantiateArgReference,
+    Sort,
+};
+
+use super::component_linker::{
+    flatten_extern,
+    MAX_TYPE_DEPTH,
+};
+
+/// Connection of an import of one part to an export of another
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompositionWire {
+    /// Name of the importing part
+    pub importer: String,
+    /// Name of the import
+    pub import:   String,
+    /// Name of the providing part
+    pub provider: String,
+    /// Name of the export satisfying the import
+    pub export:   String,
+}
+
+/// Export of a part re-exported by the composed component
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompositionExport {
+    /// Name of the export of the composed component
+    pub name:      String,
+    /// Name of the exporting part
+    pub component: String,
+    /// Name of the export of the part
+    pub export:    String,
+}
+
+/// Where the argument for an import of a part comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ImportSource {
+    /// An export of another part
+    Part { provider: usize, export: usize },
+    /// An import of the composed component
+    Outer,
+}
+
+/// Next free index of each index space of the composed component
+#[derive(Debug, Default)]
+struct IndexSpaces {
+    functions: u32,
+    instances: u32,
+}
+
+impl IndexSpaces {
+    fn allocate(&mut self, sort: Sort) -> Result<u32> {
+        let next = match sort {
+            Sort::Function => &mut self.functions,
+            Sort::Instance => &mut self.instances,
+            _ => {
+                return Err(Error::runtime_not_implemented(
+                    "Only functions and instances can be composed",
+                ))
+            },
+        };
+        let index = *next;
+        *next += 1;
+        Ok(index)
+    }
+}
+
+/// Composes parsed components into a single component
+#[derive(Debug, Clone, Default)]
+pub struct ComponentComposer {
+    /// Parts in the order they were added, with their names
+    parts:   Vec<(String, WrtComponent)>,
+    wires:   Vec<CompositionWire>,
+    exports: Vec<CompositionExport>,
+}
+
+impl ComponentComposer {
+    /// Create a composer without any parts
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a part under a unique name
+    pub fn add_component(&mut self, name: &str, component: WrtComponent) -> Result<()> {
+        if self.part_index(name).is_ok() {
+            return Err(Error::validation_error(
+                "Component name already used in composition",
+            ));
+        }
+        self.parts.push((name.to_string(), component));
+        Ok(())
+    }
+
+    /// Satisfy the import `import` of `importer` with the export `export` of
+    /// `provider`
+    pub fn wire(
+        &mut self,
+        importer: &str,
+        import: &str,
+        provider: &str,
+        export: &str,
+    ) -> Result<()> {
+        let importer_index = self.part_index(importer)?;
+        let provider_index = self.part_index(provider)?;
+        if importer_index == provider_index {
+            return Err(Error::component_linking_error(
+                "A component cannot satisfy its own import",
+            ));
+        }
+        self.import_index(importer_index, import)?;
+        self.export_index(provider_index, export)?;
+        if self.wires.iter().any(|wire| wire.importer == importer && wire.import == import) {
+            return Err(Error::validation_error("Import is already wired"));
+        }
+
+        self.wires.push(CompositionWire {
+            importer: importer.to_string(),
+            import:   import.to_string(),
+            provider: provider.to_string(),
+            export:   export.to_string(),
+        });
+        Ok(())
+    }
+
+    /// Re-export the export `export` of `component` as `name`
+    pub fn export(&mut self, name: &str, component: &str, export: &str) -> Result<()> {
+        let index = self.part_index(component)?;
+        self.export_index(index, export)?;
+        if self.exports.iter().any(|existing| existing.name == name) {
+            return Err(Error::validation_error("Composed export name already used"));
+        }
+
+        self.exports.push(CompositionExport {
+            name:      name.to_string(),
+            component: component.to_string(),
+            export:    export.to_string(),
+        });
+        Ok(())
+    }
+
+    /// Explicit wires of the composition
+    pub fn wires(&self) -> &[CompositionWire] {
+        &self.wires
+    }
+
+    /// Build the composed component
+    pub fn compose(&self) -> Result<WrtComponent> {
+        let sources = self.resolve_sources()?;
+        let order = self.instantiation_order(&sources)?;
+
+        let mut composed = WrtComponent::new();
+        let mut spaces = IndexSpaces::default();
+
+        // Imports shared by all parts importing the same name
+        let mut outer_imports: HashMap<&str, (usize, Sort, u32)> = HashMap::new();
+        for (part_index, (_, part)) in self.parts.iter().enumerate() {
+            for (import_index, import) in part.imports.iter().enumerate() {
+                if sources[part_index][import_index] != ImportSource::Outer {
+                    continue;
+                }
+
+                let name = import.name.name.as_str();
+                if let Some(&(first, _, _)) = outer_imports.get(name) {
+                    let first_import =
+                        &self.parts[first].1.imports[self.import_index(first, name)?];
+                    if !provides(&self.parts[first].1, &first_import.ty, part, &import.ty)?
+                        || !provides(part, &import.ty, &self.parts[first].1, &first_import.ty)?
+                    {
+                        return Err(Error::component_linking_error(
+                            "Components import the same name with different types",
+                        ));
+                    }
+                    continue;
+                }
+
+                let ty = inline_extern(part, &import.ty, 0)?;
+                let sort = extern_sort(&ty)?;
+                let index = spaces.allocate(sort)?;
+                outer_imports.insert(name, (part_index, sort, index));
+                composed.imports.push(Import {
+                    name: import.name.clone(),
+                    ty,
+                });
+            }
+        }
+
+        let mut instance_indices = vec![None; self.parts.len()];
+        let mut aliases: HashMap<(usize, usize), (Sort, u32)> = HashMap::new();
+        for &part_index in &order {
+            let part = &self.parts[part_index].1;
+            let mut arg_refs = Vec::with_capacity(part.imports.len());
+            for (import_index, import) in part.imports.iter().enumerate() {
+                let name = import.name.name.as_str();
+                let (sort, idx) = match sources[part_index][import_index] {
+                    ImportSource::Outer => {
+                        let (_, sort, idx) = outer_imports[name];
+                        (sort, idx)
+                    },
+                    ImportSource::Part { provider, export } => self.alias_export(
+                        &mut composed,
+                        &mut spaces,
+                        &mut aliases,
+                        &instance_indices,
+                        provider,
+                        export,
+                    )?,
+                };
+                arg_refs.push(InstantiateArgReference {
+                    name: name.to_string(),
+                    sort,
+                    idx,
+                });
+            }
+
+            composed.instances.push(Instance {
+                instance_expr: InstanceExpr::ComponentReference {
+                    component_idx: part_index as u32,
+                    arg_refs,
+                },
+            });
+            instance_indices[part_index] = Some(spaces.allocate(Sort::Instance)?);
+        }
+
+        for export in &self.exports {
+            let part_index = self.part_index(&export.component)?;
+            let export_index = self.export_index(part_index, &export.export)?;
+            let ty =
+                self.parts[part_index].1.exports[export_index].ty.as_ref().ok_or_else(|| {
+                    Error::validation_error("Composed exports need a type ascription")
+                })?;
+            let ty = inline_extern(&self.parts[part_index].1, ty, 0)?;
+            let (sort, idx) = self.alias_export(
+                &mut composed,
+                &mut spaces,
+                &mut aliases,
+                &instance_indices,
+                part_index,
+                export_index,
+            )?;
+            composed.exports.push(Export {
+                name: ExportName::new(export.name.clone()),
+                sort,
+                idx,
+                ty: Some(ty),
+            });
+        }
+
+        composed.components = self.parts.iter().map(|(_, part)| part.clone()).collect();
+        Ok(composed)
+    }
+
+    fn part_index(&self, name: &str) -> Result<usize> {
+        self.parts
+            .iter()
+            .position(|(part, _)| part == name)
+            .ok_or_else(|| Error::component_not_found("Component not part of the composition"))
+    }
+
+    fn import_index(&self, part: usize, name: &str) -> Result<usize> {
+        self.parts[part]
+            .1
+            .imports
+            .iter()
+            .position(|import| import.name.name == name)
+            .ok_or_else(|| Error::component_not_found("Component has no import of that name"))
+    }
+
+    fn export_index(&self, part: usize, name: &str) -> Result<usize> {
+        self.parts[part]
+            .1
+            .exports
+            .iter()
+            .position(|export| export.name.name == name)
+            .ok_or_else(|| Error::component_not_found("Component has no export of that name"))
+    }
+
+    /// Decide where every import of every part gets its argument from
+    fn resolve_sources(&self) -> Result<Vec<Vec<ImportSource>>> {
+        let mut sources = Vec::with_capacity(self.parts.len());
+        for (part_index, (name, part)) in self.parts.iter().enumerate() {
+            let mut part_sources = Vec::with_capacity(part.imports.len());
+            for import in &part.imports {
+                let wire = self
+                    .wires
+                    .iter()
+                    .find(|wire| &wire.importer == name && wire.import == import.name.name);
+
+                let source = if let Some(wire) = wire {
+                    let provider = self.part_index(&wire.provider)?;
+                    let export = self.export_index(provider, &wire.export)?;
+                    let export_ty = self.parts[provider].1.exports[export].ty.as_ref();
+                    match export_ty {
+                        Some(ty) if provides(&self.parts[provider].1, ty, part, &import.ty)? => {},
+                        _ => {
+                            return Err(Error::component_linking_error(
+                                "Wired export does not provide the import's type",
+                            ))
+                        },
+                    }
+                    ImportSource::Part { provider, export }
+                } else {
+                    self.implicit_source(part_index, &import.name.name, &import.ty)?
+                };
+                part_sources.push(source);
+            }
+            sources.push(part_sources);
+        }
+        Ok(sources)
+    }
+
+    /// Source of an unwired import: the only other part exporting a
+    /// compatible item of the same name, or else an outer import
+    fn implicit_source(
+        &self,
+        importer: usize,
+        name: &str,
+        ty: &ExternType,
+    ) -> Result<ImportSource> {
+        let mut source = ImportSource::Outer;
+        for (provider, (_, part)) in self.parts.iter().enumerate() {
+            if provider == importer {
+                continue;
+            }
+            for (export, candidate) in part.exports.iter().enumerate() {
+                let Some(export_ty) = &candidate.ty else {
+                    continue;
+                };
+                if candidate.name.name != name
+                    || !provides(part, export_ty, &self.parts[importer].1, ty)?
+                {
+                    continue;
+                }
+                if source != ImportSource::Outer {
+                    return Err(Error::component_linking_error(
+                        "Several components could satisfy an unwired import",
+                    ));
+                }
+                source = ImportSource::Part { provider, export };
+            }
+        }
+        Ok(source)
+    }
+
+    /// Order the parts so that every provider is instantiated before the
+    /// parts importing from it, keeping the order they were added otherwise
+    fn instantiation_order(&self, sources: &[Vec<ImportSource>]) -> Result<Vec<usize>> {
+        let mut order = Vec::with_capacity(self.parts.len());
+        let mut placed = vec![false; self.parts.len()];
+        while order.len() < self.parts.len() {
+            let next = (0..self.parts.len()).find(|&part| {
+                !placed[part]
+                    && sources[part].iter().all(|source| match source {
+                        ImportSource::Part { provider, .. } => placed[*provider],
+                        ImportSource::Outer => true,
+                    })
+            });
+            let Some(next) = next else {
+                return Err(Error::component_linking_error(
+                    "Composition wiring contains a cycle",
+                ));
+            };
+            placed[next] = true;
+            order.push(next);
+        }
+        Ok(order)
+    }
+
+    /// Alias an export of an instantiated part, reusing an earlier alias of
+    /// the same export
+    fn alias_export(
+        &self,
+        composed: &mut WrtComponent,
+        spaces: &mut IndexSpaces,
+        aliases: &mut HashMap<(usize, usize), (Sort, u32)>,
+        instance_indices: &[Option<u32>],
+        part: usize,
+        export: usize,
+    ) -> Result<(Sort, u32)> {
+        if let Some(&alias) = aliases.get(&(part, export)) {
+            return Ok(alias);
+        }
+
+        let instance_idx = instance_indices[part].ok_or_else(|| {
+            Error::runtime_invalid_state("Provider is not instantiated before its importer")
+        })?;
+        let item = &self.parts[part].1.exports[export];
+        let sort = item.sort;
+        let idx = spaces.allocate(sort)?;
+        composed.aliases.push(Alias {
+            target: AliasTarget::InstanceExport {
+                instance_idx,
+                name: item.name.name.clone(),
+                kind: sort,
+            },
+        });
+        aliases.insert((part, export), (sort, idx));
+        Ok((sort, idx))
+    }
+}
+
+/// Whether `export` of `provider` provides every function `import` of
+/// `importer` requires, with identical signatures
+fn provides(
+    provider: &WrtComponent,
+    export: &ExternType,
+    importer: &WrtComponent,
+    import: &ExternType,
+) -> Result<bool> {
+    let mut offered = Vec::new();
+    let mut required = Vec::new();
+    flatten_extern(provider, "", "", export, &mut offered)?;
+    flatten_extern(importer, "", "", import, &mut required)?;
+
+    Ok(required.iter().all(|(_, name, signature)| {
+        offered.iter().any(|(_, offered_name, offered_signature)| {
+            offered_name == name
+                && offered_signature.params == signature.params
+                && offered_signature.returns == signature.returns
+        })
+    }))
+}
+
+/// Sort of the items of an extern type
+fn extern_sort(ty: &ExternType) -> Result<Sort> {
+    match ty {
+        ExternType::Function { .. } => Ok(Sort::Function),
+        ExternType::Instance { .. } => Ok(Sort::Instance),
+        _ => Err(Error::runtime_not_implemented(
+            "Only functions and instances can be composed",
+        )),
+    }
+}
+
+/// Copy an extern type of a part into the composed component, replacing
+/// references into the part's type index space by the types they refer to
+fn inline_extern(part: &WrtComponent, ty: &ExternType, depth: usize) -> Result<ExternType> {
+    if depth > MAX_TYPE_DEPTH {
+        return Err(Error::validation_error("Component type nesting too deep"));
+    }
+
+    Ok(match ty {
+        ExternType::Function { params, results } => ExternType::Function {
+            params:  params
+                .iter()
+                .map(|(name, ty)| Ok((name.clone(), inline_val(part, ty, depth + 1)?)))
+                .collect::<Result<_>>()?,
+            results: results
+                .iter()
+                .map(|ty| inline_val(part, ty, depth + 1))
+                .collect::<Result<_>>()?,
+        },
+        ExternType::Instance { exports } => ExternType::Instance {
+            exports: exports
+                .iter()
+                .map(|(name, ty)| Ok((name.clone(), inline_extern(part, ty, depth + 1)?)))
+                .collect::<Result<_>>()?,
+        },
+        ExternType::Type(idx) => {
+            let definition = &part
+                .types
+                .get(*idx as usize)
+                .ok_or_else(|| Error::validation_error("Type index out of bounds"))?
+                .definition;
+            let ty = match definition {
+                ComponentTypeDefinition::Function { params, results } => ExternType::Function {
+                    params:  params.clone(),
+                    results: results.clone(),
+                },
+                ComponentTypeDefinition::Instance { exports } => ExternType::Instance {
+                    exports: exports.clone(),
+                },
+                _ => {
+                    return Err(Error::runtime_not_implemented(
+                        "Only function and instance types can cross a composition boundary",
+                    ))
+                },
+            };
+            inline_extern(part, &ty, depth + 1)?
+        },
+        ExternType::Value(_) | ExternType::Component { .. } => {
+            return Err(Error::runtime_not_implemented(
+                "Only functions and instances can be composed",
+            ))
+        },
+    })
+}
+
+/// Copy a value type of a part, replacing type references by their
+/// definitions
+fn inline_val(part: &WrtComponent, ty: &FormatValType, depth: usize) -> Result<FormatValType> {
+    if depth > MAX_TYPE_DEPTH {
+        return Err(Error::validation_error("Component type nesting too deep"));
+    }
+    let inline = |ty: &FormatValType| inline_val(part, ty, depth + 1);
+
+    Ok(match ty {
+        FormatValType::Ref(idx) => match part.types.get(*idx as usize).map(|ty| &ty.definition) {
+            Some(ComponentTypeDefinition::Value(ty)) => inline(ty)?,
+            Some(_) => {
+                return Err(Error::type_mismatch_error(
+                    "Type reference is not a value type",
+                ))
+            },
+            None => return Err(Error::validation_error("Type reference out of bounds")),
+        },
+        FormatValType::Record(fields) => FormatValType::Record(
+            fields
+                .iter()
+                .map(|(name, ty)| Ok((name.clone(), inline(ty)?)))
+                .collect::<Result<_>>()?,
+        ),
+        FormatValType::Variant(cases) => FormatValType::Variant(
+            cases
+                .iter()
+                .map(|(name, ty)| Ok((name.clone(), ty.as_ref().map(inline).transpose()?)))
+                .collect::<Result<_>>()?,
+        ),
+        FormatValType::List(ty) => FormatValType::List(Box::new(inline(ty)?)),
+        FormatValType::FixedList(ty, len) => FormatValType::FixedList(Box::new(inline(ty)?), *len),
+        FormatValType::Tuple(types) => {
+            FormatValType::Tuple(types.iter().map(inline).collect::<Result<_>>()?)
+        },
+        FormatValType::Option(ty) => FormatValType::Option(Box::new(inline(ty)?)),
+        FormatValType::Result(ty) => FormatValType::Result(Box::new(inline(ty)?)),
+        FormatValType::Own(_) | FormatValType::Borrow(_) => {
+            return Err(Error::runtime_not_implemented(
+                "Resource handles cannot cross a composition boundary",
+            ))
+        },
+        other => other.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use wrt_format::component::{
+        ComponentType as WrtComponentType,
+        ImportName,
+    };
+
+    use super::*;
+    use crate::components::component_linker::ComponentLinker;
+
+    fn add_type() -> ExternType {
+        ExternType::Function {
+            params:  vec![
+                ("a".to_string(), FormatValType::S32),
+                ("b".to_string(), FormatValType::S32),
+            ],
+            results: vec![FormatValType::S32],
+        }
+    }
+
+    fn import(name: &str, ty: ExternType) -> Import {
+        Import {
+            name: ImportName::new(String::new(), name.to_string()),
+            ty,
+        }
+    }
+
+    fn export(name: &str, sort: Sort, ty: ExternType) -> Export {
+        Export {
+            name: ExportName::new(name.to_string()),
+            sort,
+            idx: 0,
+            ty: Some(ty),
+        }
+    }
+
+    /// Exports the instance `math` with `add`, its type given by reference
+    fn math() -> WrtComponent {
+        let mut component = WrtComponent::new();
+        component.types.push(WrtComponentType {
+            definition: ComponentTypeDefinition::Instance {
+                exports: vec![("add".to_string(), add_type())],
+            },
+        });
+        component.exports.push(export("math", Sort::Instance, ExternType::Type(0)));
+        component
+    }
+
+    /// Imports `math` and `log`, exports `run`
+    fn app() -> WrtComponent {
+        let mut component = WrtComponent::new();
+        component.imports.push(import(
+            "math",
+            ExternType::Instance {
+                exports: vec![("add".to_string(), add_type())],
+            },
+        ));
+        component.imports.push(import(
+            "log",
+            ExternType::Function {
+                params:  vec![("message".to_string(), FormatValType::String)],
+                results: Vec::new(),
+            },
+        ));
+        component.exports.push(export(
+            "run",
+            Sort::Function,
+            ExternType::Function {
+                params:  Vec::new(),
+                results: vec![FormatValType::S32],
+            },
+        ));
+        component
+    }
+
+    #[test]
+    fn test_compose_in_dependency_order() {
+        let mut composer = ComponentComposer::new();
+        composer.add_component("app", app()).unwrap();
+        composer.add_component("math", math()).unwrap();
+        composer.export("run", "app", "run").unwrap();
+        composer.export("math", "math", "math").unwrap();
+
+        let composed = composer.compose().unwrap();
+        assert_eq!(composed.components.len(), 2);
+
+        // `log` has no provider and becomes an import of the composition
+        assert_eq!(composed.imports.len(), 1);
+        assert_eq!(composed.imports[0].name.name, "log");
+
+        // `math` is instantiated first and its export aliased for `app`
+        let instance_parts: Vec<_> = composed
+            .instances
+            .iter()
+            .map(|instance| match &instance.instance_expr {
+                InstanceExpr::ComponentReference {
+                    component_idx,
+                    arg_refs,
+                } => (*component_idx, arg_refs.clone()),
+                InstanceExpr::InlineExports(_) => panic!("unexpected inline exports"),
+            })
+            .collect();
+        assert_eq!(instance_parts[0].0, 1);
+        assert_eq!(instance_parts[1].0, 0);
+        let args = &instance_parts[1].1;
+        assert_eq!(
+            (args[0].name.as_str(), args[0].sort, args[0].idx),
+            ("math", Sort::Instance, 1)
+        );
+        assert_eq!(
+            (args[1].name.as_str(), args[1].sort, args[1].idx),
+            ("log", Sort::Function, 0)
+        );
+
+        // The `math` alias is shared by the argument and the re-export
+        assert_eq!(composed.aliases.len(), 2);
+        assert_eq!(composed.exports[0].sort, Sort::Function);
+        assert_eq!(composed.exports[0].idx, 1);
+        assert_eq!(composed.exports[1].idx, 1);
+        assert!(matches!(
+            composed.exports[1].ty,
+            Some(ExternType::Instance { .. })
+        ));
+
+        // The composition links as a single component
+        let mut linker = ComponentLinker::new();
+        linker.add_parsed_component("composed".to_string(), &composed).unwrap();
+    }
+
+    #[test]
+    fn test_composition_errors() {
+        let mut composer = ComponentComposer::new();
+        composer.add_component("app", app()).unwrap();
+        assert!(composer.add_component("app", app()).is_err());
+        composer.add_component("math", math()).unwrap();
+
+        assert!(composer.wire("app", "math", "app", "run").is_err());
+        assert!(composer.wire("app", "missing", "math", "math").is_err());
+        assert!(composer.export("run", "app", "missing").is_err());
+
+        // Wiring an export of the wrong type is rejected when composing
+        composer.wire("app", "log", "app2", "run").unwrap_err();
+        composer.add_component("app2", app()).unwrap();
+        composer.wire("app", "log", "app2", "run").unwrap();
+        assert!(composer.compose().is_err());
+
+        // Two candidates for an unwired import are ambiguous
+        let mut composer = ComponentComposer::new();
+        composer.add_component("app", app()).unwrap();
+        composer.add_component("math", math()).unwrap();
+        composer.add_component("math2", math()).unwrap();
+        assert!(composer.compose().is_err());
+        composer.wire("app", "math", "math2", "math").unwrap();
+        assert!(composer.compose().is_ok());
+
+        // Parts providing each other's imports cannot be ordered
+        let function = || ExternType::Function {
+            params:  Vec::new(),
+            results: Vec::new(),
+        };
+        let mut first = WrtComponent::new();
+        first.imports.push(import("ping", function()));
+        first.exports.push(export("pong", Sort::Function, function()));
+        let mut second = WrtComponent::new();
+        second.imports.push(import("pong", function()));
+        second.exports.push(export("ping", Sort::Function, function()));
+
+        let mut composer = ComponentComposer::new();
+        composer.add_component("first", first).unwrap();
+        composer.add_component("second", second).unwrap();
+        assert!(composer.compose().is_err());
+    }
+}
@@ -42,6 +42,10 @@
 //! ```
 
 // Cross-environment imports
+use core::hash::{
+    Hash,
+    Hasher,
+};
 #[cfg(feature = "std")]
 use std::{
     boxed::Box,
@@ -51,6 +55,7 @@
     vec::Vec,
 };
 
+use wrt_foundation::TypeHasher;
 #[cfg(not(feature = "std"))]
 use wrt_foundation::{
     bounded::{
@@ -343,6 +348,75 @@ fn default() -> Self {
     }
 }
 
+impl FunctionSignature {
+    /// Stable structural hash of the parameter and result types
+    ///
+    /// The function name is not hashed, so signatures that link have equal
+    /// hashes. See [`TypeHasher`] for the guarantees of the hash.
+    pub fn type_hash(&self) -> u64 {
+        let mut hasher = TypeHasher::new();
+        self.hash_types(&mut hasher);
+        hasher.finish()
+    }
+
+    fn hash_types(&self, hasher: &mut TypeHasher) {
+        hasher.write_usize(self.params.len());
+        for param in self.params.iter() {
+            param.hash(hasher);
+        }
+        hasher.write_usize(self.returns.len());
+        for result in self.returns.iter() {
+            result.hash(hasher);
+        }
+    }
+}
+
+/// Hash the kind of an import or export and the types it must match exactly
+fn extern_type_hash(kind: u8, hash_types: impl FnOnce(&mut TypeHasher)) -> u64 {
+    let mut hasher = TypeHasher::new();
+    hasher.write_u8(kind);
+    hash_types(&mut hasher);
+    hasher.finish()
+}
+
+impl ExportType {
+    /// Stable structural hash, equal to the hash of every [`ImportType`] this
+    /// export can satisfy
+    ///
+    /// Memory and table limits are matched by range, so they are not hashed.
+    pub fn type_hash(&self) -> u64 {
+        match self {
+            Self::Function(signature) => extern_type_hash(0, |h| signature.hash_types(h)),
+            Self::Memory(_) => extern_type_hash(1, |_| {}),
+            Self::Table { element_type, .. } => extern_type_hash(2, |h| element_type.hash(h)),
+            Self::Global {
+                value_type,
+                mutable,
+            } => extern_type_hash(3, |h| (value_type, mutable).hash(h)),
+            Self::Type(ty) => extern_type_hash(4, |h| ty.hash(h)),
+        }
+    }
+}
+
+impl ImportType {
+    /// Stable structural hash, equal to the hash of every [`ExportType`] that
+    /// can satisfy this import
+    ///
+    /// Memory and table limits are matched by range, so they are not hashed.
+    pub fn type_hash(&self) -> u64 {
+        match self {
+            Self::Function(signature) => extern_type_hash(0, |h| signature.hash_types(h)),
+            Self::Memory(_) => extern_type_hash(1, |_| {}),
+            Self::Table { element_type, .. } => extern_type_hash(2, |h| element_type.hash(h)),
+            Self::Global {
+                value_type,
+                mutable,
+            } => extern_type_hash(3, |h| (value_type, mutable).hash(h)),
+            Self::Type(ty) => extern_type_hash(4, |h| ty.hash(h)),
+        }
+    }
+}
+
 impl Default for InstanceMetadata {
     fn default() -> Self {
         Self {
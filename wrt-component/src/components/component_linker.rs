@@ -1,4 +1,15 @@
 //! Component Linker and Import/Export Resolution System
+//!
+//! The linker takes parsed components, wires every component import either
+//! to a compatible export of another component instance or to a host
+//! implementation, and instantiates the components in dependency order.
+//!
+//! Imports and exports are flattened to functions: a function imported
+//! through an instance import `a` is named `a#f`, which is also the name
+//! under which an instance export `a` of a providing component exposes `f`.
+//!
+//! [`ComponentLinker::dependency_graph_dot`] renders how the components,
+//! their instances and the host are wired as a GraphViz graph.
 
 // Cross-environment imports
 #[cfg(not(feature = "std"))]
@@ -9,6 +20,7 @@
     collections::HashMap,
     format,
     string::String,
+    sync::Arc,
     vec::Vec,
 };
 
@@ -40,9 +52,34 @@
 type HashMap<K, V> =
     wrt_foundation::bounded_collections::BoundedMap<K, V, 64, NoStdProvider<65536>>;
 
+#[cfg(feature = "std")]
+use wrt_format::component::{
+    Component as WrtComponent,
+    ComponentTypeDefinition,
+    CoreInstanceExpr,
+    ExternType,
+    FormatValType,
+    Import as WrtImport,
+    InstanceExpr,
+    Sort,
+};
+#[cfg(feature = "std")]
+use wrt_foundation::verification::Checksum;
+#[cfg(feature = "std")]
+use wrt_intercept::{
+    ImportBinding,
+    InstanceLifecycle,
+    LinkInterceptor,
+};
+
+use crate::canonical_abi::{
+    ComponentType,
+    ComponentValue,
+};
 use crate::components::component_instantiation::{
     create_component_export,
     create_component_import,
+    create_function_signature,
     ComponentExport,
     ComponentImport,
     ComponentInstance,
@@ -57,9 +94,45 @@
 /// Maximum number of components in linker
 const MAX_LINKED_COMPONENTS: usize = 256;
 
+/// Maximum depth of type references followed while converting types
+pub(crate) const MAX_TYPE_DEPTH: usize = 32;
+
+/// Maximum nesting depth of component definitions
+const MAX_NESTING_DEPTH: usize = 16;
+
+/// Separator between an instance name and one of its function names
+const INSTANCE_SEPARATOR: char = '#';
+
+/// Provider ID of imports satisfied by a host implementation
+pub const HOST_INSTANCE_ID: InstanceId = 0;
+
 /// Component identifier in the linker
 pub type ComponentId = String;
 
+/// Host implementation of an imported component function
+#[cfg(feature = "std")]
+pub type HostFunction =
+    Arc<dyn Fn(&[ComponentValue]) -> Result<Vec<ComponentValue>> + Send + Sync>;
+
+/// Host function registered with the linker
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct HostImplementation {
+    /// Signature imports must match to be satisfied by this function
+    pub signature: FunctionSignature,
+    /// The implementation
+    pub function:  HostFunction,
+}
+
+#[cfg(feature = "std")]
+impl core::fmt::Debug for HostImplementation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("HostImplementation")
+            .field("signature", &self.signature)
+            .finish_non_exhaustive()
+    }
+}
+
 /// Component linker for managing multiple components and their dependencies
 #[derive(Debug)]
 pub struct ComponentLinker {
@@ -67,6 +140,17 @@ pub struct ComponentLinker {
     components:       HashMap<ComponentId, ComponentDefinition>,
     /// Active component instances
     instances:        HashMap<InstanceId, ComponentInstance>,
+    /// Most recent instance of each component, used to satisfy imports
+    providers:        HashMap<ComponentId, InstanceId>,
+    /// Host implementations keyed by flattened import name
+    #[cfg(feature = "std")]
+    host_functions:   HashMap<String, HostImplementation>,
+    /// Keys of the host functions generated to stub unresolved imports
+    #[cfg(feature = "std")]
+    stubbed_imports:  Vec<String>,
+    /// Interceptors attached to the import boundary of components
+    #[cfg(feature = "std")]
+    interceptors:     HashMap<ComponentId, Arc<LinkInterceptor>>,
     /// Dependency graph
     link_graph:       LinkGraph,
     /// Next available instance ID
@@ -81,15 +165,67 @@ pub struct ComponentLinker {
 #[derive(Debug, Clone)]
 pub struct ComponentDefinition {
     /// Component ID
-    pub id:       ComponentId,
+    pub id:            ComponentId,
     /// Component binary (simplified as bytes)
-    pub binary:   BoundedVec<u8, 1048576, NoStdProvider<65536>>, // 1MB max binary size
+    pub binary:        BoundedVec<u8, 1048576, NoStdProvider<65536>>, // 1MB max binary size
+    /// Checksum of `binary`, reported to lifecycle hooks
+    pub digest:        u32,
     /// Parsed exports
-    pub exports:  BoundedVec<ComponentExport, 64, NoStdProvider<65536>>,
+    pub exports:       BoundedVec<ComponentExport, 64, NoStdProvider<65536>>,
     /// Parsed imports
-    pub imports:  BoundedVec<ComponentImport, 64, NoStdProvider<65536>>,
+    pub imports:       BoundedVec<ComponentImport, 64, NoStdProvider<65536>>,
+    /// Structural type hashes of `exports`, by index
+    pub export_hashes: Vec<u64>,
+    /// Structural type hashes of `imports`, by index
+    pub import_hashes: Vec<u64>,
     /// Component metadata
-    pub metadata: ComponentMetadata,
+    pub metadata:      ComponentMetadata,
+    /// Core and nested component instances declared by the component
+    #[cfg(feature = "std")]
+    pub instance_plan: InstancePlan,
+}
+
+/// Core and nested component instances declared by a component, in
+/// definition order, with every index reference checked
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InstancePlan {
+    /// Core instances, indexed by core instance index
+    pub core_instances:      Vec<CoreInstancePlan>,
+    /// Component instances defined by the component
+    pub component_instances: Vec<ComponentInstancePlan>,
+}
+
+/// How a core instance is created
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CoreInstancePlan {
+    /// Instantiate an embedded core module
+    Instantiate {
+        /// Index of the core module
+        module_index: u32,
+        /// Import module names paired with the core instance providing them
+        arguments:    Vec<(String, u32)>,
+    },
+    /// Bundle of exports of items already defined
+    Exports(Vec<String>),
+}
+
+/// How a component instance is created
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ComponentInstancePlan {
+    /// Instantiate a nested component
+    Instantiate {
+        /// Index of the nested component
+        component_index: u32,
+        /// Names of the imports supplied to the nested component
+        arguments:       Vec<String>,
+        /// Instances declared by the nested component itself
+        nested:          Box<InstancePlan>,
+    },
+    /// Bundle of exports of items already defined
+    Exports(Vec<String>),
 }
 
 /// Component metadata for introspection
@@ -157,6 +293,8 @@ pub struct LinkerConfig {
     pub validate_dependencies:    bool,
     /// Circular dependency handling
     pub circular_dependency_mode: CircularDependencyMode,
+    /// Handling of function imports nothing else satisfies
+    pub import_stubs:             ImportStubMode,
 }
 
 /// Circular dependency handling modes
@@ -170,6 +308,23 @@ pub enum CircularDependencyMode {
     Warn,
 }
 
+/// Stubs generated for function imports no component export or host
+/// function satisfies
+///
+/// Stubs let partially ported components be instantiated and exercised
+/// before every import is implemented. They are registered as host functions
+/// and replaced when the embedder defines the real implementation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportStubMode {
+    /// Unresolved imports fail instantiation
+    Disabled,
+    /// Calling an unresolved import traps
+    Trap,
+    /// Calling an unresolved import returns the zero value of each result,
+    /// or traps if a result type has none
+    DefaultValues,
+}
+
 /// Linking statistics
 #[derive(Debug, Clone, Default)]
 pub struct LinkingStats {
@@ -181,6 +336,8 @@ pub struct LinkingStats {
     pub links_resolved:        u32,
     /// Resolution failures
     pub resolution_failures:   u32,
+    /// Imports satisfied by generated stubs
+    pub imports_stubbed:       u32,
     /// Last resolution time (microseconds)
     pub last_resolution_time:  u64,
 }
@@ -193,6 +350,7 @@ fn default() -> Self {
             max_instance_memory:      64 * 1024 * 1024, // 64MB
             validate_dependencies:    true,
             circular_dependency_mode: CircularDependencyMode::Reject,
+            import_stubs:             ImportStubMode::Disabled,
         }
     }
 }
@@ -220,6 +378,13 @@ pub fn with_config(config: LinkerConfig) -> Self {
         Self {
             components: HashMap::new(),
             instances: HashMap::new(),
+            providers: HashMap::new(),
+            #[cfg(feature = "std")]
+            host_functions: HashMap::new(),
+            #[cfg(feature = "std")]
+            stubbed_imports: Vec::new(),
+            #[cfg(feature = "std")]
+            interceptors: HashMap::new(),
             link_graph: LinkGraph::new(),
             next_instance_id: 1,
             config,
@@ -227,37 +392,91 @@ pub fn with_config(config: LinkerConfig) -> Self {
         }
     }
 
-    /// Add a component to the linker
+    /// Add a component to the linker, decoding its binary
+    #[cfg(all(feature = "std", feature = "decoder"))]
     pub fn add_component(&mut self, id: ComponentId, binary: &[u8]) -> Result<()> {
-        if self.components.len() >= MAX_LINKED_COMPONENTS {
-            return Err(Error::resource_exhausted(
-                "Maximum number of components reached",
-            ));
+        if binary.is_empty() {
+            return Err(Error::validation_error("Empty component binary"));
         }
 
-        // Parse component binary (simplified)
-        let (exports, imports, metadata) = self.parse_component_binary(binary)?;
+        let component = wrt_decoder::component::parse_component_binary(binary)?;
+        self.register_component(id, binary, &component)
+    }
 
-        let definition = ComponentDefinition {
-            id: id.clone(),
-            binary: binary.to_vec(),
-            exports,
-            imports,
-            metadata,
-        };
+    /// Add a component to the linker, decoding its binary
+    #[cfg(not(all(feature = "std", feature = "decoder")))]
+    pub fn add_component(&mut self, _id: ComponentId, _binary: &[u8]) -> Result<()> {
+        Err(Error::runtime_not_implemented(
+            "Decoding components requires the std and decoder features",
+        ))
+    }
 
-        // Add to components map
-        self.components.insert(id.clone(), definition);
+    /// Add an already parsed component to the linker
+    #[cfg(feature = "std")]
+    pub fn add_parsed_component(&mut self, id: ComponentId, component: &WrtComponent) -> Result<()> {
+        let binary = component.binary.clone().unwrap_or_default();
+        self.register_component(id, &binary, component)
+    }
 
-        // Update dependency graph
-        self.link_graph.add_component(id)?;
+    /// Define a host implementation for the import `name` of instance
+    /// `module`; use an empty `module` for plain function imports
+    ///
+    /// An implementation replaces the stub generated for the import, also
+    /// for instances created before.
+    #[cfg(feature = "std")]
+    pub fn define_host_function<F>(
+        &mut self,
+        module: &str,
+        name: &str,
+        signature: FunctionSignature,
+        function: F,
+    ) -> Result<()>
+    where
+        F: Fn(&[ComponentValue]) -> Result<Vec<ComponentValue>> + Send + Sync + 'static,
+    {
+        let key = qualified_name(module, name);
+        if let Some(position) = self.stubbed_imports.iter().position(|stub| *stub == key) {
+            self.stubbed_imports.remove(position);
+        } else if self.host_functions.contains_key(&key) {
+            return Err(Error::validation_error("Host function already defined"));
+        }
 
-        // Update statistics
-        self.stats.components_registered += 1;
+        self.host_functions.insert(
+            key,
+            HostImplementation {
+                signature,
+                function: Arc::new(function),
+            },
+        );
+        Ok(())
+    }
 
+    /// Attach an interceptor to the calls a component makes through its
+    /// imports
+    ///
+    /// Embedders hand it to the canonical ABI converting values at that
+    /// boundary through [`CanonicalABI::with_interceptor`].
+    ///
+    /// [`CanonicalABI::with_interceptor`]: crate::canonical_abi::CanonicalABI::with_interceptor
+    #[cfg(feature = "std")]
+    pub fn set_interceptor(
+        &mut self,
+        component_id: &ComponentId,
+        interceptor: Arc<LinkInterceptor>,
+    ) -> Result<()> {
+        if !self.components.contains_key(component_id) {
+            return Err(Error::component_not_found("Component not found"));
+        }
+        self.interceptors.insert(component_id.clone(), interceptor);
         Ok(())
     }
 
+    /// Interceptor attached to the import boundary of a component
+    #[cfg(feature = "std")]
+    pub fn interceptor(&self, component_id: &ComponentId) -> Option<&Arc<LinkInterceptor>> {
+        self.interceptors.get(component_id)
+    }
+
     /// Remove a component from the linker
     pub fn remove_component(&mut self, id: &ComponentId) -> Result<()> {
         // Check if component exists
@@ -281,12 +500,20 @@ pub fn remove_component(&mut self, id: &ComponentId) -> Result<()> {
 
         // Remove from components and graph
         self.components.remove(id);
+        self.providers.remove(id);
+        #[cfg(feature = "std")]
+        self.interceptors.remove(id);
         self.link_graph.remove_component(id)?;
 
         Ok(())
     }
 
     /// Instantiate a component with dependency resolution
+    ///
+    /// Every import is wired to an instance of another component that
+    /// exports a compatible function, or else to a host implementation.
+    /// The interceptor of the component is then told about the instance and
+    /// its import bindings, and may veto its creation.
     pub fn instantiate(
         &mut self,
         component_id: &ComponentId,
@@ -297,9 +524,24 @@ pub fn instantiate(
             .components
             .get(component_id)
             .ok_or_else(|| Error::component_not_found("Component not found"))?;
+        let exports = component.exports.clone();
+        let imports = component.imports.clone();
+        let import_hashes = component.import_hashes.clone();
+        #[cfg(feature = "std")]
+        let digest = component.digest;
 
         // Resolve dependencies
-        let resolved_imports = self.resolve_imports(component_id, &component.imports)?;
+        let resolved_imports = self.resolve_imports(component_id, &imports, &import_hashes)?;
+
+        #[cfg(feature = "std")]
+        if let Some(interceptor) = self.interceptors.get(component_id) {
+            interceptor.on_instantiate(&InstanceLifecycle {
+                component: component_id,
+                instance_id: self.next_instance_id,
+                digest,
+                bindings: &self.import_bindings(&resolved_imports),
+            })?;
+        }
 
         // Create instance
         let instance_id = self.next_instance_id;
@@ -311,8 +553,8 @@ pub fn instantiate(
             instance_id,
             component_id.clone(),
             instance_config,
-            component.exports.clone(),
-            component.imports.clone(),
+            exports,
+            imports,
         )?;
 
         // Add resolved imports
@@ -325,6 +567,7 @@ pub fn instantiate(
 
         // Add to instances map
         self.instances.insert(instance_id, instance);
+        self.providers.insert(component_id.clone(), instance_id);
 
         // Update statistics
         self.stats.instances_created += 1;
@@ -332,10 +575,41 @@ pub fn instantiate(
         Ok(instance_id)
     }
 
+    /// Destroy an instance and release its resources
+    ///
+    /// The interceptor of its component is notified. Instances importing
+    /// from the destroyed instance fail when calling those imports, and later
+    /// instances no longer resolve imports against it.
+    pub fn destroy_instance(&mut self, instance_id: InstanceId) -> Result<()> {
+        let mut instance = self
+            .instances
+            .remove(&instance_id)
+            .ok_or_else(|| Error::component_not_found("Component instance not found"))?;
+        if self.providers.get(&instance.name) == Some(&instance_id) {
+            self.providers.remove(&instance.name);
+        }
+
+        #[cfg(feature = "std")]
+        if let Some(interceptor) = self.interceptors.get(&instance.name) {
+            interceptor.on_destroy(&InstanceLifecycle {
+                component: &instance.name,
+                instance_id,
+                digest: self.components.get(&instance.name).map_or(0, |c| c.digest),
+                bindings: &self.import_bindings(&instance.imports),
+            });
+        }
+
+        instance.terminate();
+        Ok(())
+    }
+
     /// Link all components and create instances
     pub fn link_all(&mut self) -> Result<Vec<InstanceId>> {
         let mut instance_ids = Vec::new();
 
+        // Record which component provides each import
+        self.rebuild_link_graph()?;
+
         // Topological sort to determine instantiation order
         let sorted_components = self.link_graph.topological_sort()?;
 
@@ -348,6 +622,46 @@ pub fn link_all(&mut self) -> Result<Vec<InstanceId>> {
         Ok(instance_ids)
     }
 
+    /// Call the import `import_name` of an instance, dispatching to the
+    /// provider it was wired to at instantiation
+    #[cfg(feature = "std")]
+    pub fn invoke_import(
+        &mut self,
+        instance_id: InstanceId,
+        import_name: &str,
+        args: &[ComponentValue],
+    ) -> Result<Vec<ComponentValue>> {
+        let instance = self
+            .instances
+            .get(&instance_id)
+            .ok_or_else(|| Error::component_not_found("Component instance not found"))?;
+        let resolved = instance
+            .imports
+            .iter()
+            .find(|resolved| import_key(&resolved.import) == import_name)
+            .cloned()
+            .ok_or_else(|| Error::runtime_function_not_found("Import not found"))?;
+
+        if resolved.provider_id == HOST_INSTANCE_ID {
+            let host = self
+                .host_functions
+                .get(&resolved.provider_export)
+                .ok_or_else(|| Error::runtime_function_not_found("Host function not found"))?;
+            return (host.function)(args);
+        }
+
+        self.instances
+            .get_mut(&resolved.provider_id)
+            .ok_or_else(|| Error::component_not_found("Provider instance not found"))?
+            .call_function(&resolved.provider_export, args)
+    }
+
+    /// Get the instances declared by a registered component
+    #[cfg(feature = "std")]
+    pub fn instance_plan(&self, component_id: &ComponentId) -> Option<&InstancePlan> {
+        self.components.get(component_id).map(|component| &component.instance_plan)
+    }
+
     /// Get a component instance by ID
     pub fn get_instance(&self, instance_id: InstanceId) -> Option<&ComponentInstance> {
         self.instances.get(&instance_id)
@@ -358,93 +672,210 @@ pub fn get_instance_mut(&mut self, instance_id: InstanceId) -> Option<&mut Compo
         self.instances.get_mut(&instance_id)
     }
 
+    /// Flattened names of the imports currently satisfied by generated stubs
+    #[cfg(feature = "std")]
+    pub fn stubbed_imports(&self) -> &[String] {
+        &self.stubbed_imports
+    }
+
     /// Get linking statistics
     pub fn get_stats(&self) -> &LinkingStats {
         &self.stats
     }
 
+    /// Render the wiring of the registered components as a GraphViz DOT graph
+    ///
+    /// Components are boxes with a dashed edge to the component providing
+    /// each of their imports. Instances are ellipses attached to their
+    /// component, with an edge per resolved import to the providing instance
+    /// or to the host. Components with an interceptor have a double border,
+    /// and the import edges of their instances are red and name the
+    /// interceptor.
+    #[cfg(feature = "std")]
+    pub fn dependency_graph_dot(&self) -> String {
+        let mut dot = String::from("digraph composition {\n    rankdir=LR;\n");
+        let interceptor_name =
+            |id: &ComponentId| self.interceptors.get(id).map(|interceptor| interceptor.name());
+
+        if !self.host_functions.is_empty() {
+            dot.push_str("    \"host\" [shape=diamond];\n");
+        }
+
+        for node in &self.link_graph.nodes {
+            let id = &node.component_id;
+            let node_id = dot_escape(&format!("component:{id}"));
+            let label = dot_escape(id);
+            let attributes = match interceptor_name(id) {
+                Some(name) => format!(
+                    "shape=box, peripheries=2, label=\"{label}\\ninterceptor: {}\"",
+                    dot_escape(name)
+                ),
+                None => format!("shape=box, label=\"{label}\""),
+            };
+            dot.push_str(&format!("    \"{node_id}\" [{attributes}];\n"));
+
+            let component = &self.components[id];
+            for (import, &import_hash) in component.imports.iter().zip(&component.import_hashes) {
+                if let Some((provider_id, _)) = self.find_provider(id, import, import_hash) {
+                    dot.push_str(&format!(
+                        "    \"{node_id}\" -> \"{}\" [style=dashed, label=\"{}\"];\n",
+                        dot_escape(&format!("component:{provider_id}")),
+                        dot_escape(&import_key(import)),
+                    ));
+                }
+            }
+        }
+
+        let mut instances: Vec<_> = self.instances.values().collect();
+        instances.sort_by_key(|instance| instance.id);
+        for instance in instances {
+            let node_id = format!("instance:{}", instance.id);
+            dot.push_str(&format!(
+                "    \"{node_id}\" [shape=ellipse, label=\"{} #{}\"];\n",
+                dot_escape(&instance.name),
+                instance.id,
+            ));
+            dot.push_str(&format!(
+                "    \"{node_id}\" -> \"{}\" [style=dotted, arrowhead=none];\n",
+                dot_escape(&format!("component:{}", instance.name)),
+            ));
+
+            for resolved in &instance.imports {
+                let target = match resolved.provider_id {
+                    HOST_INSTANCE_ID => String::from("host"),
+                    provider_id => format!("instance:{provider_id}"),
+                };
+                let label = dot_escape(&import_key(&resolved.import));
+                let attributes = match interceptor_name(&instance.name) {
+                    Some(name) => format!("color=red, label=\"{label}\\n[{}]\"", dot_escape(name)),
+                    None => format!("label=\"{label}\""),
+                };
+                dot.push_str(&format!(
+                    "    \"{node_id}\" -> \"{target}\" [{attributes}];\n"
+                ));
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
     // Private helper methods
 
-    fn parse_component_binary(
-        &self,
+    #[cfg(feature = "std")]
+    fn register_component(
+        &mut self,
+        id: ComponentId,
         binary: &[u8],
-    ) -> core::result::Result<(
-        Vec<ComponentExport>,
-        Vec<ComponentImport>,
-        ComponentMetadata,
-    )> {
-        // Simplified component parsing
-        if binary.is_empty() {
-            return Err(Error::runtime_execution_error("Empty component binary"));
+        component: &WrtComponent,
+    ) -> Result<()> {
+        if self.components.len() >= MAX_LINKED_COMPONENTS {
+            return Err(Error::resource_exhausted(
+                "Maximum number of components reached",
+            ));
         }
 
-        // Create some example exports and imports based on binary content
-        #[cfg(feature = "std")]
-        let exports = vec![create_component_export(
-            "main".to_string(),
-            ExportType::Function(crate::component_instantiation::create_function_signature(
-                "main".to_string(),
-                vec![],
-                vec![crate::canonical_abi::ComponentType::S32],
-            )),
-        )];
-
-        #[cfg(not(feature = "std"))]
-        let exports = {
-            let mut exports = Vec::new();
-            let mut params = Vec::new();
-            let mut results = Vec::new();
-            results.push(crate::canonical_abi::ComponentType::S32).map_err(|_| {
-                Error::platform_memory_allocation_failed("Memory allocation failed")
-            })?;
+        if self.components.contains_key(&id) {
+            return Err(Error::validation_error("Component already registered"));
+        }
 
-            let signature = crate::component_instantiation::create_function_signature(
-                String::new_from_str("main").map_err(|_| {
-                    Error::platform_memory_allocation_failed("Memory allocation failed")
-                })?,
-                params,
-                results,
-            );
-
-            exports
-                .push(create_component_export(
-                    String::new_from_str("main").map_err(|_| {
-                        Error::platform_memory_allocation_failed("Memory allocation failed")
-                    })?,
-                    ExportType::Function(signature),
-                ))
-                .map_err(|_| {
-                    Error::platform_memory_allocation_failed("Memory allocation failed")
-                })?;
-            exports
+        let exports = convert_exports(component)?;
+        let imports = convert_imports(component)?;
+        let definition = ComponentDefinition {
+            id: id.clone(),
+            binary: binary.to_vec(),
+            digest: Checksum::compute(binary).value(),
+            export_hashes: exports.iter().map(|export| export.export_type.type_hash()).collect(),
+            import_hashes: imports.iter().map(|import| import.import_type.type_hash()).collect(),
+            exports,
+            imports,
+            metadata: ComponentMetadata {
+                name: component.name.clone().unwrap_or_default(),
+                ..ComponentMetadata::default()
+            },
+            instance_plan: plan_instances(component, 0)?,
         };
 
-        #[cfg(feature = "std")]
-        let imports = vec![create_component_import(
-            "log".to_string(),
-            "env".to_string(),
-            ImportType::Function(crate::component_instantiation::create_function_signature(
-                "log".to_string(),
-                vec![crate::canonical_abi::ComponentType::String],
-                vec![],
-            )),
-        )];
+        // Update dependency graph
+        self.link_graph.add_component(id.clone())?;
+
+        // Add to components map
+        self.components.insert(id, definition);
+
+        // Update statistics
+        self.stats.components_registered += 1;
 
-        let metadata = ComponentMetadata::default();
+        Ok(())
+    }
+
+    fn rebuild_link_graph(&mut self) -> Result<()> {
+        self.link_graph.clear_dependencies();
+
+        let mut dependencies = Vec::new();
+        for node in &self.link_graph.nodes {
+            let importer_id = &node.component_id;
+            let importer = &self.components[importer_id];
+            for (import, &import_hash) in importer.imports.iter().zip(&importer.import_hashes) {
+                if let Some((provider_id, export)) =
+                    self.find_provider(importer_id, import, import_hash)
+                {
+                    dependencies.push((
+                        importer_id.clone(),
+                        provider_id.clone(),
+                        import.clone(),
+                        export.clone(),
+                    ));
+                }
+            }
+        }
 
-        Ok((exports, imports, metadata))
+        for (importer_id, provider_id, import, export) in dependencies {
+            self.link_graph.add_dependency(&importer_id, &provider_id, import, export)?;
+        }
+
+        Ok(())
+    }
+
+    /// First other component, in registration order, exporting a match for
+    /// `import`
+    fn find_provider(
+        &self,
+        importer_id: &ComponentId,
+        import: &ComponentImport,
+        import_hash: u64,
+    ) -> Option<(&ComponentId, &ComponentExport)> {
+        self.link_graph
+            .nodes
+            .iter()
+            .map(|node| &node.component_id)
+            .filter(|id| *id != importer_id)
+            .find_map(|id| {
+                self.find_compatible_export(&self.components[id], import, import_hash)
+                    .map(|export| (id, export))
+            })
     }
 
     fn resolve_imports(
         &mut self,
         component_id: &ComponentId,
         imports: &[ComponentImport],
+        import_hashes: &[u64],
     ) -> Result<Vec<ResolvedImport>> {
         let mut resolved = Vec::new();
 
-        for import in imports {
-            let resolution = self.resolve_single_import(component_id, import)?;
-            resolved.push(resolution);
+        for (import, &import_hash) in imports.iter().zip(import_hashes) {
+            let resolution = self.resolve_single_import(component_id, import, import_hash);
+            #[cfg(feature = "std")]
+            let resolution = resolution.or_else(|| self.stub_import(import));
+            match resolution {
+                Some(resolution) => resolved.push(resolution),
+                None => {
+                    self.stats.resolution_failures += 1;
+                    return Err(Error::component_linking_error(
+                        "No component export or host function satisfies import",
+                    ));
+                },
+            }
         }
 
         self.stats.links_resolved += resolved.len() as u32;
@@ -453,44 +884,154 @@ fn resolve_imports(
 
     fn resolve_single_import(
         &self,
-        _component_id: &ComponentId,
+        component_id: &ComponentId,
         import: &ComponentImport,
-    ) -> Result<ResolvedImport> {
-        // Find a component that exports what we need
-        for (provider_id, component) in &self.components {
-            for export in &component.exports {
-                if self.is_compatible_import_export(import, export)? {
-                    return Ok(ResolvedImport {
+        import_hash: u64,
+    ) -> Option<ResolvedImport> {
+        // Prefer instantiated components, in registration order
+        for node in &self.link_graph.nodes {
+            if &node.component_id == component_id {
+                continue;
+            }
+            let Some(&provider_id) = self.providers.get(&node.component_id) else {
+                continue;
+            };
+
+            let component = &self.components[&node.component_id];
+            if let Some(export) = self.find_compatible_export(component, import, import_hash) {
+                return Some(ResolvedImport {
+                    import: import.clone(),
+                    provider_id,
+                    provider_export: export.name.clone(),
+                });
+            }
+        }
+
+        // Fall back to host implementations
+        #[cfg(feature = "std")]
+        if let ImportType::Function(signature) = &import.import_type {
+            let key = import_key(import);
+            if let Some(host) = self.host_functions.get(&key) {
+                if self.is_compatible_function_signature(signature, &host.signature) {
+                    return Some(ResolvedImport {
                         import:          import.clone(),
-                        provider_id:     1, // Simplified - would map component ID to instance ID
-                        provider_export: export.name.clone(),
+                        provider_id:     HOST_INSTANCE_ID,
+                        provider_export: key,
                     });
                 }
             }
         }
 
-        Err(Error::component_not_found("Component not found"))
+        None
+    }
+
+    /// What each of `imports` is bound to, as reported to lifecycle hooks
+    #[cfg(feature = "std")]
+    fn import_bindings(&self, imports: &[ResolvedImport]) -> Vec<ImportBinding> {
+        imports
+            .iter()
+            .map(|resolved| ImportBinding {
+                import:   import_key(&resolved.import),
+                provider: self
+                    .instances
+                    .get(&resolved.provider_id)
+                    .map(|instance| instance.name.clone()),
+                export:   resolved.provider_export.clone(),
+            })
+            .collect()
+    }
+
+    /// Satisfy the function import `import` with a stub, as configured by
+    /// [`LinkerConfig::import_stubs`]
+    ///
+    /// Imports with a host function of another signature are not stubbed.
+    #[cfg(feature = "std")]
+    fn stub_import(&mut self, import: &ComponentImport) -> Option<ResolvedImport> {
+        let ImportType::Function(signature) = &import.import_type else {
+            return None;
+        };
+        let key = import_key(import);
+        if self.host_functions.contains_key(&key) {
+            return None;
+        }
+
+        let defaults = match self.config.import_stubs {
+            ImportStubMode::Disabled => return None,
+            ImportStubMode::Trap => None,
+            ImportStubMode::DefaultValues => signature
+                .returns
+                .iter()
+                .map(ComponentType::default_value)
+                .collect::<Option<Vec<_>>>(),
+        };
+        let function: HostFunction = match defaults {
+            Some(values) => Arc::new(move |_: &[ComponentValue]| Ok(values.clone())),
+            None => Arc::new(|_: &[ComponentValue]| {
+                Err(Error::runtime_trap(
+                    "Called an unresolved import stubbed by the linker",
+                ))
+            }),
+        };
+
+        self.host_functions.insert(
+            key.clone(),
+            HostImplementation {
+                signature: signature.clone(),
+                function,
+            },
+        );
+        self.stubbed_imports.push(key.clone());
+        self.stats.imports_stubbed += 1;
+
+        Some(ResolvedImport {
+            import:          import.clone(),
+            provider_id:     HOST_INSTANCE_ID,
+            provider_export: key,
+        })
+    }
+
+    /// First export of `component` compatible with `import`
+    ///
+    /// With strict typing, exports whose cached type hash differs from
+    /// `import_hash` are skipped without comparing types; equal hashes are
+    /// confirmed structurally, so a hash collision never links mismatched
+    /// types.
+    fn find_compatible_export<'c>(
+        &self,
+        component: &'c ComponentDefinition,
+        import: &ComponentImport,
+        import_hash: u64,
+    ) -> Option<&'c ComponentExport> {
+        component
+            .exports
+            .iter()
+            .zip(&component.export_hashes)
+            .find(|(export, &export_hash)| {
+                (!self.config.strict_typing || export_hash == import_hash)
+                    && self.is_compatible_import_export(import, export)
+            })
+            .map(|(export, _)| export)
     }
 
     fn is_compatible_import_export(
         &self,
         import: &ComponentImport,
         export: &ComponentExport,
-    ) -> Result<bool> {
+    ) -> bool {
         // Check name compatibility
-        if import.name != export.name {
-            return Ok(false);
+        if import_key(import) != export.name {
+            return false;
         }
 
         // Check type compatibility
         match (&import.import_type, &export.export_type) {
             (ImportType::Function(import_sig), ExportType::Function(export_sig)) => {
-                Ok(self.is_compatible_function_signature(import_sig, export_sig))
+                self.is_compatible_function_signature(import_sig, export_sig)
             },
             (ImportType::Memory(import_mem), ExportType::Memory(export_mem)) => {
-                Ok(self.is_compatible_memory_config(import_mem, export_mem))
+                self.is_compatible_memory_config(import_mem, export_mem)
             },
-            _ => Ok(false), // Other type combinations
+            _ => false, // Other type combinations
         }
     }
 
@@ -499,20 +1040,312 @@ fn is_compatible_function_signature(
         import_sig: &FunctionSignature,
         export_sig: &FunctionSignature,
     ) -> bool {
-        // Simplified compatibility check
-        import_sig.params == export_sig.params && import_sig.returns == export_sig.returns
+        !self.config.strict_typing
+            || (import_sig.params == export_sig.params && import_sig.returns == export_sig.returns)
     }
 
     fn is_compatible_memory_config(
         &self,
-        _import_mem: &crate::component_instantiation::MemoryConfig,
-        _export_mem: &crate::component_instantiation::MemoryConfig,
+        import_mem: &crate::component_instantiation::MemoryConfig,
+        export_mem: &crate::component_instantiation::MemoryConfig,
     ) -> bool {
-        // Simplified compatibility check
-        true
+        // The exported memory must satisfy the imported limits
+        export_mem.initial_pages >= import_mem.initial_pages
+            && match (import_mem.max_pages, export_mem.max_pages) {
+                (Some(import_max), Some(export_max)) => export_max <= import_max,
+                (Some(_), None) => false,
+                (None, _) => true,
+            }
+    }
+}
+
+/// Join an instance name and a function name into a flattened name
+pub(crate) fn qualified_name(instance: &str, name: &str) -> String {
+    if instance.is_empty() {
+        name.to_string()
+    } else {
+        format!("{instance}{INSTANCE_SEPARATOR}{name}")
+    }
+}
+
+/// Escape text for a quoted GraphViz identifier or label
+#[cfg(feature = "std")]
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Flattened name an import is matched against exports with
+pub(crate) fn import_key(import: &ComponentImport) -> String {
+    qualified_name(&import.module, &import.name)
+}
+
+/// Convert a component value type, following type references
+#[cfg(feature = "std")]
+fn convert_val_type(
+    component: &WrtComponent,
+    ty: &FormatValType,
+    depth: usize,
+) -> Result<ComponentType> {
+    if depth > MAX_TYPE_DEPTH {
+        return Err(Error::validation_error("Component type nesting too deep"));
+    }
+    let convert = |ty: &FormatValType| convert_val_type(component, ty, depth + 1);
+
+    Ok(match ty {
+        FormatValType::Bool => ComponentType::Bool,
+        FormatValType::S8 => ComponentType::S8,
+        FormatValType::U8 => ComponentType::U8,
+        FormatValType::S16 => ComponentType::S16,
+        FormatValType::U16 => ComponentType::U16,
+        FormatValType::S32 => ComponentType::S32,
+        FormatValType::U32 => ComponentType::U32,
+        FormatValType::S64 => ComponentType::S64,
+        FormatValType::U64 => ComponentType::U64,
+        FormatValType::F32 => ComponentType::F32,
+        FormatValType::F64 => ComponentType::F64,
+        FormatValType::Char => ComponentType::Char,
+        FormatValType::String => ComponentType::String,
+        FormatValType::Ref(idx) => match component.types.get(*idx as usize) {
+            Some(ty) => match &ty.definition {
+                ComponentTypeDefinition::Value(ty) => convert(ty)?,
+                _ => return Err(Error::type_mismatch_error("Type reference is not a value type")),
+            },
+            None => return Err(Error::validation_error("Type reference out of bounds")),
+        },
+        FormatValType::Record(fields) => ComponentType::Record(
+            fields
+                .iter()
+                .map(|(name, ty)| Ok((name.clone(), convert(ty)?)))
+                .collect::<Result<_>>()?,
+        ),
+        FormatValType::Variant(cases) => ComponentType::Variant(
+            cases
+                .iter()
+                .map(|(name, ty)| Ok((name.clone(), ty.as_ref().map(convert).transpose()?)))
+                .collect::<Result<_>>()?,
+        ),
+        FormatValType::List(ty) | FormatValType::FixedList(ty, _) => {
+            ComponentType::List(Box::new(convert(ty)?))
+        },
+        FormatValType::Tuple(types) => {
+            ComponentType::Tuple(types.iter().map(convert).collect::<Result<_>>()?)
+        },
+        FormatValType::Flags(names) => ComponentType::Flags(names.clone()),
+        FormatValType::Enum(names) => ComponentType::Enum(names.clone()),
+        FormatValType::Option(ty) => ComponentType::Option(Box::new(convert(ty)?)),
+        FormatValType::Result(ty) => ComponentType::Result(Some(Box::new(convert(ty)?)), None),
+        FormatValType::Own(idx) => ComponentType::Own(*idx),
+        FormatValType::Borrow(idx) => ComponentType::Borrow(*idx),
+        FormatValType::ErrorContext => ComponentType::ErrorContext,
+        FormatValType::Void => ComponentType::Tuple(Vec::new()),
+    })
+}
+
+/// Build the signature of a function named `name` from its parameter and
+/// result types
+#[cfg(feature = "std")]
+fn function_signature(
+    component: &WrtComponent,
+    name: String,
+    params: &[(String, FormatValType)],
+    results: &[FormatValType],
+) -> Result<FunctionSignature> {
+    Ok(create_function_signature(
+        name,
+        params
+            .iter()
+            .map(|(_, ty)| convert_val_type(component, ty, 0))
+            .collect::<Result<_>>()?,
+        results
+            .iter()
+            .map(|ty| convert_val_type(component, ty, 0))
+            .collect::<Result<_>>()?,
+    ))
+}
+
+/// Flatten an extern type into `(instance, name, signature)` functions.
+///
+/// Returns `Ok(false)` for items that need no runtime wiring (types).
+#[cfg(feature = "std")]
+pub(crate) fn flatten_extern(
+    component: &WrtComponent,
+    instance: &str,
+    name: &str,
+    ty: &ExternType,
+    functions: &mut Vec<(String, String, FunctionSignature)>,
+) -> Result<bool> {
+    match ty {
+        ExternType::Function { params, results } => {
+            let signature = function_signature(
+                component,
+                qualified_name(instance, name),
+                params,
+                results,
+            )?;
+            functions.push((instance.to_string(), name.to_string(), signature));
+            Ok(true)
+        },
+        ExternType::Instance { exports } if instance.is_empty() => {
+            for (export_name, export_ty) in exports {
+                flatten_extern(component, name, export_name, export_ty, functions)?;
+            }
+            Ok(true)
+        },
+        ExternType::Type(idx) => match component.types.get(*idx as usize) {
+            Some(ty) => match &ty.definition {
+                ComponentTypeDefinition::Function { params, results } => flatten_extern(
+                    component,
+                    instance,
+                    name,
+                    &ExternType::Function {
+                        params:  params.clone(),
+                        results: results.clone(),
+                    },
+                    functions,
+                ),
+                ComponentTypeDefinition::Instance { exports } => flatten_extern(
+                    component,
+                    instance,
+                    name,
+                    &ExternType::Instance {
+                        exports: exports.clone(),
+                    },
+                    functions,
+                ),
+                // Type imports and exports are resolved structurally
+                _ => Ok(false),
+            },
+            None => Err(Error::validation_error("Type index out of bounds")),
+        },
+        _ => Err(Error::runtime_not_implemented(
+            "Only function and instance imports and exports can be linked",
+        )),
     }
 }
 
+/// Convert the imports of a parsed component into flattened function imports
+#[cfg(feature = "std")]
+pub(crate) fn convert_imports(component: &WrtComponent) -> Result<Vec<ComponentImport>> {
+    let mut functions = Vec::new();
+    for WrtImport { name, ty } in &component.imports {
+        flatten_extern(component, "", &name.name, ty, &mut functions)?;
+    }
+
+    Ok(functions
+        .into_iter()
+        .map(|(instance, name, signature)| {
+            create_component_import(name, instance, ImportType::Function(signature))
+        })
+        .collect())
+}
+
+/// Convert the exports of a parsed component into flattened function exports
+#[cfg(feature = "std")]
+pub(crate) fn convert_exports(component: &WrtComponent) -> Result<Vec<ComponentExport>> {
+    let mut functions = Vec::new();
+    for export in &component.exports {
+        match (&export.sort, &export.ty) {
+            (Sort::Function | Sort::Instance, Some(ty)) => {
+                flatten_extern(component, "", &export.name.name, ty, &mut functions)?;
+            },
+            (Sort::Function | Sort::Instance, None) => {
+                return Err(Error::validation_error(
+                    "Linked function and instance exports need a type ascription",
+                ))
+            },
+            _ => {},
+        }
+    }
+
+    Ok(functions
+        .into_iter()
+        .map(|(instance, name, signature)| {
+            create_component_export(
+                qualified_name(&instance, &name),
+                ExportType::Function(signature),
+            )
+        })
+        .collect())
+}
+
+/// Resolve the core and nested component instances of a component,
+/// checking every index they reference
+#[cfg(feature = "std")]
+fn plan_instances(component: &WrtComponent, depth: usize) -> Result<InstancePlan> {
+    if depth > MAX_NESTING_DEPTH {
+        return Err(Error::validation_error("Component nesting too deep"));
+    }
+
+    let mut plan = InstancePlan::default();
+
+    for (index, core_instance) in component.core_instances.iter().enumerate() {
+        let entry = match &core_instance.instance_expr {
+            CoreInstanceExpr::ModuleReference {
+                module_idx,
+                arg_refs,
+            } => {
+                if *module_idx as usize >= component.modules.len() {
+                    return Err(Error::validation_error("Core module index out of bounds"));
+                }
+                // Arguments may only refer to core instances defined earlier
+                if arg_refs.iter().any(|arg| arg.instance_idx as usize >= index) {
+                    return Err(Error::validation_error(
+                        "Core instance argument refers to an undefined instance",
+                    ));
+                }
+                CoreInstancePlan::Instantiate {
+                    module_index: *module_idx,
+                    arguments:    arg_refs
+                        .iter()
+                        .map(|arg| (arg.name.clone(), arg.instance_idx))
+                        .collect(),
+                }
+            },
+            CoreInstanceExpr::InlineExports(exports) => {
+                CoreInstancePlan::Exports(exports.iter().map(|e| e.name.clone()).collect())
+            },
+        };
+        plan.core_instances.push(entry);
+    }
+
+    for instance in &component.instances {
+        let entry = match &instance.instance_expr {
+            InstanceExpr::ComponentReference {
+                component_idx,
+                arg_refs,
+            } => {
+                let nested = component
+                    .components
+                    .get(*component_idx as usize)
+                    .ok_or_else(|| Error::validation_error("Component index out of bounds"))?;
+
+                // Every import of the nested component must be supplied
+                if nested
+                    .imports
+                    .iter()
+                    .any(|import| !arg_refs.iter().any(|arg| arg.name == import.name.name))
+                {
+                    return Err(Error::component_linking_error(
+                        "Nested component instantiation is missing an import argument",
+                    ));
+                }
+
+                ComponentInstancePlan::Instantiate {
+                    component_index: *component_idx,
+                    arguments:       arg_refs.iter().map(|arg| arg.name.clone()).collect(),
+                    nested:          Box::new(plan_instances(nested, depth + 1)?),
+                }
+            },
+            InstanceExpr::InlineExports(exports) => ComponentInstancePlan::Exports(
+                exports.iter().map(|e| e.name.clone()).collect(),
+            ),
+        };
+        plan.component_instances.push(entry);
+    }
+
+    Ok(plan)
+}
+
 impl LinkGraph {
     /// Create a new empty link graph
     pub fn new() -> Self {
@@ -542,6 +1375,48 @@ pub fn add_component(&mut self, component_id: ComponentId) -> Result<()> {
         Ok(())
     }
 
+    /// Record that `from` imports `import` satisfied by `export` of `to`
+    pub fn add_dependency(
+        &mut self,
+        from: &ComponentId,
+        to: &ComponentId,
+        import: ComponentImport,
+        export: ComponentExport,
+    ) -> Result<()> {
+        let (from, to) = match (self.find_node_index(from), self.find_node_index(to)) {
+            (Some(from), Some(to)) => (from, to),
+            _ => return Err(Error::component_not_found("Component not found in graph")),
+        };
+
+        if !self.nodes[from].dependencies.contains(&to) {
+            self.nodes[from].dependencies.push(to);
+            self.nodes[to].dependents.push(from);
+        }
+
+        self.edges.push(GraphEdge {
+            from,
+            to,
+            import,
+            export,
+            weight: 1,
+        });
+        Ok(())
+    }
+
+    /// Remove all dependencies, keeping the components
+    pub fn clear_dependencies(&mut self) {
+        self.edges.clear();
+        for node in &mut self.nodes {
+            node.dependencies.clear();
+            node.dependents.clear();
+        }
+    }
+
+    /// Get the dependency edges
+    pub fn edges(&self) -> &[GraphEdge] {
+        &self.edges
+    }
+
     /// Remove a component from the graph
     pub fn remove_component(&mut self, component_id: &ComponentId) -> Result<()> {
         let node_index = self.find_node_index(component_id).ok_or_else(|| {
@@ -563,6 +1438,15 @@ pub fn remove_component(&mut self, component_id: &ComponentId) -> Result<()> {
             node.index -= 1;
         }
 
+        for node in &mut self.nodes {
+            for links in [&mut node.dependencies, &mut node.dependents] {
+                links.retain(|&index| index != node_index);
+                for index in links.iter_mut().filter(|index| **index > node_index) {
+                    *index -= 1;
+                }
+            }
+        }
+
         for edge in &mut self.edges {
             if edge.from > node_index {
                 edge.from -= 1;
@@ -576,6 +1460,8 @@ pub fn remove_component(&mut self, component_id: &ComponentId) -> Result<()> {
     }
 
     /// Perform topological sort to determine instantiation order
+    ///
+    /// Every component comes after the components it depends on.
     pub fn topological_sort(&self) -> Result<Vec<ComponentId>> {
         #[cfg(feature = "std")]
         {
@@ -589,7 +1475,6 @@ pub fn topological_sort(&self) -> Result<Vec<ComponentId>> {
                 }
             }
 
-            result.reverse();
             Ok(result)
         }
         #[cfg(not(feature = "std"))]
@@ -621,7 +1506,6 @@ pub fn topological_sort(&self) -> Result<Vec<ComponentId>> {
                 }
             }
 
-            result.reverse();
             Ok(result)
         }
     }
@@ -638,7 +1522,7 @@ fn topological_sort_visit(
         }
 
         if visited[node_index] {
-            return Ok();
+            return Ok(());
         }
 
         temp_visited[node_index] = true;
@@ -671,8 +1555,78 @@ fn default() -> Self {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Mutex;
+
+    use wrt_format::component::{
+        CoreInstance,
+        Export as WrtExport,
+        ExportName,
+        ImportName,
+    };
+    use wrt_foundation::values::Value;
+    use wrt_intercept::LinkInterceptorStrategy;
+
     use super::*;
 
+    fn function_type(params: &[FormatValType], results: &[FormatValType]) -> ExternType {
+        ExternType::Function {
+            params:  params.iter().map(|ty| (String::new(), ty.clone())).collect(),
+            results: results.to_vec(),
+        }
+    }
+
+    fn import(name: &str, ty: ExternType) -> WrtImport {
+        WrtImport {
+            name: ImportName {
+                namespace: String::new(),
+                name:      name.to_string(),
+                nested:    Vec::new(),
+                package:   None,
+            },
+            ty,
+        }
+    }
+
+    fn export(name: &str, sort: Sort, ty: ExternType) -> WrtExport {
+        WrtExport {
+            name: ExportName {
+                name:        name.to_string(),
+                is_resource: false,
+                semver:      None,
+                integrity:   None,
+                nested:      Vec::new(),
+            },
+            sort,
+            idx: 0,
+            ty: Some(ty),
+        }
+    }
+
+    /// Instance type exporting `add: func(s32, s32) -> s32`
+    fn math_instance() -> ExternType {
+        ExternType::Instance {
+            exports: vec![(
+                "add".to_string(),
+                function_type(
+                    &[FormatValType::S32, FormatValType::S32],
+                    &[FormatValType::S32],
+                ),
+            )],
+        }
+    }
+
+    fn provider() -> WrtComponent {
+        let mut component = WrtComponent::new();
+        component.exports.push(export("math", Sort::Instance, math_instance()));
+        component
+    }
+
+    fn consumer() -> WrtComponent {
+        let mut component = WrtComponent::new();
+        component.imports.push(import("math", math_instance()));
+        component
+    }
+
     #[test]
     fn test_linker_creation() {
         let linker = ComponentLinker::new();
@@ -684,20 +1638,22 @@ fn test_linker_creation() {
     #[test]
     fn test_add_component() {
         let mut linker = ComponentLinker::new();
-        let binary = vec![0x00, 0x61, 0x73, 0x6d]; // "wasm" magic
 
-        let result = linker.add_component("test_component".to_string(), &binary);
+        let result = linker.add_parsed_component("test_component".to_string(), &provider());
         assert!(result.is_ok());
         assert_eq!(linker.components.len(), 1);
         assert_eq!(linker.stats.components_registered, 1);
+
+        let definition = &linker.components["test_component"];
+        assert_eq!(definition.exports[0].name, "math#add");
+        assert!(linker.add_parsed_component("test_component".to_string(), &provider()).is_err());
     }
 
     #[test]
     fn test_remove_component() {
         let mut linker = ComponentLinker::new();
-        let binary = vec![0x00, 0x61, 0x73, 0x6d];
 
-        linker.add_component("test_component".to_string(), &binary).unwrap();
+        linker.add_parsed_component("test_component".to_string(), &provider()).unwrap();
         assert_eq!(linker.components.len(), 1);
 
         let result = linker.remove_component(&"test_component".to_string());
@@ -705,6 +1661,309 @@ fn test_remove_component() {
         assert_eq!(linker.components.len(), 0);
     }
 
+    #[test]
+    fn test_link_components_in_dependency_order() {
+        let mut linker = ComponentLinker::new();
+        // Register the consumer first so that ordering has to come from the graph
+        linker.add_parsed_component("app".to_string(), &consumer()).unwrap();
+        linker.add_parsed_component("math".to_string(), &provider()).unwrap();
+
+        let instances = linker.link_all().unwrap();
+        assert_eq!(instances.len(), 2);
+        assert_eq!(linker.link_graph.edges().len(), 1);
+
+        let math = instances[0];
+        let app = linker.get_instance(instances[1]).unwrap();
+        assert_eq!(app.imports.len(), 1);
+        assert_eq!(app.imports[0].provider_id, math);
+        assert_eq!(app.imports[0].provider_export, "math#add");
+        assert_eq!(linker.get_stats().links_resolved, 1);
+    }
+
+    #[test]
+    fn test_type_hashes_gate_linking() {
+        let mut mismatched = WrtComponent::new();
+        let add_s64 = ExternType::Instance {
+            exports: vec![(
+                "add".to_string(),
+                function_type(&[FormatValType::S64], &[FormatValType::S64]),
+            )],
+        };
+        mismatched.exports.push(export("math", Sort::Instance, add_s64));
+
+        let mut linker = ComponentLinker::new();
+        linker.add_parsed_component("app".to_string(), &consumer()).unwrap();
+        linker.add_parsed_component("math".to_string(), &provider()).unwrap();
+        linker.add_parsed_component("wide".to_string(), &mismatched).unwrap();
+
+        let import_hash = linker.components["app"].import_hashes[0];
+        assert_eq!(linker.components["math"].export_hashes[0], import_hash);
+        assert_ne!(linker.components["wide"].export_hashes[0], import_hash);
+
+        // A colliding hash is caught by the structural comparison
+        linker.components.get_mut("wide").unwrap().export_hashes[0] = import_hash;
+        linker.remove_component(&"math".to_string()).unwrap();
+        linker.rebuild_link_graph().unwrap();
+        assert!(linker.link_graph.edges().is_empty());
+        assert!(linker.instantiate(&"app".to_string(), None).is_err());
+    }
+
+    #[test]
+    fn test_host_function_import() {
+        let mut linker = ComponentLinker::new();
+        let mut component = WrtComponent::new();
+        component.imports.push(import(
+            "double",
+            function_type(&[FormatValType::S32], &[FormatValType::S32]),
+        ));
+        linker.add_parsed_component("app".to_string(), &component).unwrap();
+
+        // Unresolved imports fail instantiation
+        assert!(linker.instantiate(&"app".to_string(), None).is_err());
+        assert_eq!(linker.get_stats().resolution_failures, 1);
+
+        linker
+            .define_host_function(
+                "",
+                "double",
+                create_function_signature(
+                    "double".to_string(),
+                    vec![ComponentType::S32],
+                    vec![ComponentType::S32],
+                ),
+                |args: &[ComponentValue]| match args {
+                    [ComponentValue::S32(v)] => Ok(vec![ComponentValue::S32(v * 2)]),
+                    _ => Err(Error::runtime_type_mismatch("Expected one s32 argument")),
+                },
+            )
+            .unwrap();
+
+        let instance = linker.instantiate(&"app".to_string(), None).unwrap();
+        assert_eq!(linker.get_instance(instance).unwrap().imports[0].provider_id, HOST_INSTANCE_ID);
+        assert_eq!(
+            linker.invoke_import(instance, "double", &[ComponentValue::S32(21)]).unwrap(),
+            vec![ComponentValue::S32(42)]
+        );
+    }
+
+    #[test]
+    fn test_import_stubs() {
+        let mut component = WrtComponent::new();
+        component.imports.push(import(
+            "lookup",
+            function_type(
+                &[FormatValType::String],
+                &[
+                    FormatValType::S32,
+                    FormatValType::Option(Box::new(FormatValType::String)),
+                ],
+            ),
+        ));
+        component
+            .imports
+            .push(import("log", function_type(&[FormatValType::String], &[])));
+
+        let mut trapping = ComponentLinker::with_config(LinkerConfig {
+            import_stubs: ImportStubMode::Trap,
+            ..LinkerConfig::default()
+        });
+        trapping.add_parsed_component("app".to_string(), &component).unwrap();
+        let instance = trapping.instantiate(&"app".to_string(), None).unwrap();
+        assert_eq!(trapping.stubbed_imports(), ["lookup", "log"]);
+        assert_eq!(trapping.get_stats().imports_stubbed, 2);
+        assert!(trapping.invoke_import(instance, "log", &[]).is_err());
+
+        let mut defaults = ComponentLinker::with_config(LinkerConfig {
+            import_stubs: ImportStubMode::DefaultValues,
+            ..LinkerConfig::default()
+        });
+        defaults.add_parsed_component("app".to_string(), &component).unwrap();
+        let instance = defaults.instantiate(&"app".to_string(), None).unwrap();
+        assert_eq!(
+            defaults.invoke_import(instance, "lookup", &[]).unwrap(),
+            vec![ComponentValue::S32(0), ComponentValue::Option(None)]
+        );
+
+        // Defining the import replaces its stub
+        defaults
+            .define_host_function(
+                "",
+                "lookup",
+                create_function_signature(
+                    "lookup".to_string(),
+                    vec![ComponentType::String],
+                    vec![
+                        ComponentType::S32,
+                        ComponentType::Option(Box::new(ComponentType::String)),
+                    ],
+                ),
+                |_: &[ComponentValue]| {
+                    Ok(vec![ComponentValue::S32(7), ComponentValue::Option(None)])
+                },
+            )
+            .unwrap();
+        assert_eq!(defaults.stubbed_imports(), ["log"]);
+        assert_eq!(
+            defaults.invoke_import(instance, "lookup", &[]).unwrap()[0],
+            ComponentValue::S32(7)
+        );
+    }
+
+    /// Records instances with their bindings and vetoes those bound to the
+    /// host
+    struct BindingMonitor(Arc<Mutex<Vec<String>>>);
+
+    impl LinkInterceptorStrategy for BindingMonitor {
+        fn before_call(
+            &self,
+            _source: &str,
+            _target: &str,
+            _function: &str,
+            args: &[Value],
+        ) -> Result<Vec<Value>> {
+            Ok(args.to_vec())
+        }
+
+        fn after_call(
+            &self,
+            _source: &str,
+            _target: &str,
+            _function: &str,
+            _args: &[Value],
+            result: Result<Vec<Value>>,
+        ) -> Result<Vec<Value>> {
+            result
+        }
+
+        fn on_instantiate(&self, instance: &InstanceLifecycle<'_>) -> Result<()> {
+            for binding in instance.bindings {
+                let Some(provider) = &binding.provider else {
+                    return Err(Error::component_linking_error(
+                        "Host imports are not allowed",
+                    ));
+                };
+                self.0.lock().unwrap().push(format!(
+                    "{} {}: {} <- {}.{}",
+                    instance.component,
+                    instance.instance_id,
+                    binding.import,
+                    provider,
+                    binding.export
+                ));
+            }
+            Ok(())
+        }
+
+        fn on_destroy(&self, instance: &InstanceLifecycle<'_>) {
+            self.0.lock().unwrap().push(format!(
+                "{} {}: destroyed",
+                instance.component, instance.instance_id
+            ));
+        }
+
+        fn clone_strategy(&self) -> Arc<dyn LinkInterceptorStrategy> {
+            Arc::new(Self(self.0.clone()))
+        }
+    }
+
+    #[test]
+    fn test_lifecycle_hooks() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut monitor = LinkInterceptor::new("monitor");
+        monitor.add_strategy(Arc::new(BindingMonitor(events.clone())));
+        let monitor = Arc::new(monitor);
+
+        let mut linker = ComponentLinker::new();
+        linker.add_parsed_component("math".to_string(), &provider()).unwrap();
+        linker.add_parsed_component("app".to_string(), &consumer()).unwrap();
+        linker.set_interceptor(&"app".to_string(), monitor.clone()).unwrap();
+
+        let instances = linker.link_all().unwrap();
+        linker.destroy_instance(instances[1]).unwrap();
+        assert!(linker.get_instance(instances[1]).is_none());
+        assert!(linker.destroy_instance(instances[1]).is_err());
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                format!("app {}: math#add <- math.math#add", instances[1]),
+                format!("app {}: destroyed", instances[1]),
+            ]
+        );
+
+        // Imports no longer resolve against a destroyed provider
+        linker.destroy_instance(instances[0]).unwrap();
+        assert!(linker.instantiate(&"app".to_string(), None).is_err());
+
+        // Bindings to the host are vetoed
+        let mut component = WrtComponent::new();
+        component.imports.push(import("log", function_type(&[], &[])));
+        linker.add_parsed_component("logger".to_string(), &component).unwrap();
+        linker.set_interceptor(&"logger".to_string(), monitor).unwrap();
+        linker
+            .define_host_function(
+                "",
+                "log",
+                create_function_signature("log".to_string(), vec![], vec![]),
+                |_: &[ComponentValue]| Ok(vec![]),
+            )
+            .unwrap();
+        assert!(linker.instantiate(&"logger".to_string(), None).is_err());
+        assert_eq!(linker.get_stats().instances_created, 2);
+    }
+
+    #[test]
+    fn test_dependency_graph_dot() {
+        let mut linker = ComponentLinker::new();
+        linker.add_parsed_component("app".to_string(), &consumer()).unwrap();
+        linker.add_parsed_component("math".to_string(), &provider()).unwrap();
+        let audit = Arc::new(LinkInterceptor::new("audit"));
+        linker.set_interceptor(&"app".to_string(), audit.clone()).unwrap();
+        assert!(linker.set_interceptor(&"missing".to_string(), audit).is_err());
+
+        // Component dependencies are shown before anything is instantiated
+        let dot = linker.dependency_graph_dot();
+        assert!(dot.starts_with("digraph composition {"));
+        assert!(dot.contains(
+            r#""component:app" [shape=box, peripheries=2, label="app\ninterceptor: audit"];"#
+        ));
+        assert!(dot
+            .contains(r#""component:app" -> "component:math" [style=dashed, label="math#add"];"#));
+        assert!(!dot.contains("instance:"));
+        assert!(!dot.contains(r#""host""#));
+
+        let instances = linker.link_all().unwrap();
+        let dot = linker.dependency_graph_dot();
+        assert!(dot.contains(&format!(
+            r#""instance:{}" -> "instance:{}" [color=red, label="math#add\n[audit]"];"#,
+            instances[1], instances[0]
+        )));
+        assert!(dot.contains(&format!(
+            r#""instance:{}" -> "component:math" [style=dotted, arrowhead=none];"#,
+            instances[0]
+        )));
+        assert!(dot.ends_with("}\n"));
+        assert_eq!(dot_escape("a\"b\\"), r#"a\"b\\"#);
+    }
+
+    #[test]
+    fn test_instance_plan_validation() {
+        let mut component = WrtComponent::new();
+        component.core_instances.push(CoreInstance {
+            instance_expr: CoreInstanceExpr::InlineExports(Vec::new()),
+        });
+        let plan = plan_instances(&component, 0).unwrap();
+        assert_eq!(plan.core_instances, vec![CoreInstancePlan::Exports(Vec::new())]);
+
+        // No core module 0 to instantiate
+        component.core_instances.push(CoreInstance {
+            instance_expr: CoreInstanceExpr::ModuleReference {
+                module_idx: 0,
+                arg_refs:   Vec::new(),
+            },
+        });
+        assert!(plan_instances(&component, 0).is_err());
+    }
+
     #[test]
     fn test_link_graph_operations() {
         let mut graph = LinkGraph::new();
@@ -736,6 +1995,19 @@ fn test_topological_sort_single() {
         assert_eq!(result, vec!["comp1".to_string()]);
     }
 
+    #[test]
+    fn test_topological_sort_cycle() {
+        let mut graph = LinkGraph::new();
+        let (a, b) = ("a".to_string(), "b".to_string());
+        graph.add_component(a.clone()).unwrap();
+        graph.add_component(b.clone()).unwrap();
+        graph.add_dependency(&a, &b, ComponentImport::default(), ComponentExport::default()).unwrap();
+        assert_eq!(graph.topological_sort().unwrap(), vec![b.clone(), a.clone()]);
+
+        graph.add_dependency(&b, &a, ComponentImport::default(), ComponentExport::default()).unwrap();
+        assert!(graph.topological_sort().is_err());
+    }
+
     #[test]
     fn test_linker_config_default() {
         let config = LinkerConfig::default();
@@ -747,14 +2019,14 @@ fn test_linker_config_default() {
             config.circular_dependency_mode,
             CircularDependencyMode::Reject
         );
+        assert_eq!(config.import_stubs, ImportStubMode::Disabled);
     }
 
     #[test]
     fn test_linking_stats() {
         let mut linker = ComponentLinker::new();
-        let binary = vec![0x00, 0x61, 0x73, 0x6d];
 
-        linker.add_component("test".to_string(), &binary).unwrap();
+        linker.add_parsed_component("test".to_string(), &provider()).unwrap();
 
         let stats = linker.get_stats();
         assert_eq!(stats.components_registered, 1);
@@ -5,6 +5,8 @@
 
 pub mod component;
 pub mod component_communication;
+#[cfg(feature = "std")]
+pub mod component_composer;
 pub mod component_instantiation;
 pub mod component_linker;
 pub mod component_no_std;
@@ -14,6 +16,8 @@
 
 pub use component::*;
 pub use component_communication::*;
+#[cfg(feature = "std")]
+pub use component_composer::*;
 pub use component_instantiation::*;
 pub use component_linker::*;
 pub use component_no_std::*;
@@ -85,6 +85,8 @@ macro_rules! debug_println {
 pub mod unified_execution_agent_stubs;
 pub mod values;
 pub mod virtualization;
+#[cfg(feature = "std")]
+pub mod wit;
 
 // Module aliases for commonly expected imports
 pub use memory_layout as memory;
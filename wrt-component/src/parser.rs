@@ -9,6 +9,7 @@
 use std::collections::HashSet;
 
 use wrt_error::kinds::DecodingError;
+use wrt_foundation::leb128;
 
 use crate::{
     builtins::BuiltinType,
@@ -111,13 +112,13 @@ fn parse_builtins_from_import_section(data: &[u8]) -> Result<Vec<String>> {
     let mut offset = 0;
 
     // Read import count
-    let (count, bytes_read) = read_leb128_u32(data, offset)?;
+    let (count, bytes_read) = leb128::read_u32(data, offset)?;
     offset += bytes_read;
 
     // Parse each import
     for _ in 0..count {
         // Read module name
-        let (module_len, bytes_read) = read_leb128_u32(data, offset)?;
+        let (module_len, bytes_read) = leb128::read_u32(data, offset)?;
         offset += bytes_read;
 
         if offset + module_len as usize > data.len() {
@@ -129,7 +130,7 @@ fn parse_builtins_from_import_section(data: &[u8]) -> Result<Vec<String>> {
         offset += module_len as usize;
 
         // Read import name
-        let (name_len, bytes_read) = read_leb128_u32(data, offset)?;
+        let (name_len, bytes_read) = leb128::read_u32(data, offset)?;
         offset += bytes_read;
 
         if offset + name_len as usize > data.len() {
@@ -158,38 +159,6 @@ fn parse_builtins_from_import_section(data: &[u8]) -> Result<Vec<String>> {
     Ok(builtin_names)
 }
 
-/// Helper function to read LEB128 unsigned 32-bit integer
-fn read_leb128_u32(data: &[u8], offset: usize) -> Result<(u32, usize)> {
-    let mut result = 0u32;
-    let mut shift = 0;
-    let mut bytes_read = 0;
-
-    for i in 0..5 {
-        // Max 5 bytes for u32
-        if offset + i >= data.len() {
-            return Err(Error::parse_error(
-                "Unexpected end of data while reading LEB128",
-            ));
-        }
-
-        let byte = data[offset + i];
-        bytes_read += 1;
-
-        result |= ((byte & 0x7F) as u32) << shift;
-
-        if byte & 0x80 == 0 {
-            break;
-        }
-
-        shift += 7;
-        if shift >= 32 {
-            return Err(Error::parse_error("LEB128 value too large for u32"));
-        }
-    }
-
-    Ok((result, bytes_read))
-}
-
 /// Scan a WebAssembly binary for built-in imports and map them to built-in
 /// types
 ///
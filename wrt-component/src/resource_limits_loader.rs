@@ -26,7 +26,10 @@
     Error,
     ErrorCategory,
 };
-use wrt_foundation::NoStdProvider;
+use wrt_foundation::{
+    leb128,
+    NoStdProvider,
+};
 
 use crate::{
     async_::fuel_async_executor::{
@@ -91,7 +94,7 @@ fn find_custom_section(wasm_bytes: &[u8], section_name: &str) -> Result<Option<V
         offset += 1;
 
         // Read section size (LEB128)
-        let (section_size, new_offset) = read_leb128_u32(&wasm_bytes[offset..])?;
+        let (section_size, new_offset) = leb128::read_u32(wasm_bytes, offset)?;
         offset += new_offset;
 
         let section_end = offset + section_size as usize;
@@ -102,7 +105,7 @@ fn find_custom_section(wasm_bytes: &[u8], section_name: &str) -> Result<Option<V
         // Check if this is a custom section (type 0)
         if section_type == 0 {
             // Read name length and name
-            let (name_len, name_offset) = read_leb128_u32(&wasm_bytes[offset..])?;
+            let (name_len, name_offset) = leb128::read_u32(wasm_bytes, offset)?;
             let name_start = offset + name_offset;
             let name_end = name_start + name_len as usize;
 
@@ -129,36 +132,6 @@ fn find_custom_section(wasm_bytes: &[u8], section_name: &str) -> Result<Option<V
     Ok(None)
 }
 
-/// Read LEB128 encoded u32
-fn read_leb128_u32(bytes: &[u8]) -> Result<(u32, usize), Error> {
-    let mut result = 0u32;
-    let mut shift = 0;
-    let mut offset = 0;
-
-    loop {
-        if offset >= bytes.len() {
-            return Err(Error::parse_error("Unexpected end of LEB128"));
-        }
-
-        let byte = bytes[offset];
-        offset += 1;
-
-        if shift >= 32 {
-            return Err(Error::parse_error("LEB128 too large for u32"));
-        }
-
-        result |= ((byte & 0x7F) as u32) << shift;
-
-        if byte & 0x80 == 0 {
-            break;
-        }
-
-        shift += 7;
-    }
-
-    Ok((result, offset))
-}
-
 /// Convert ResourceLimitsSection to ASILExecutionConfig
 fn convert_to_asil_config(
     limits: &ResourceLimitsSection<NoStdProvider<4096>>,
@@ -264,9 +237,12 @@ fn test_find_custom_section() {
     #[test]
     fn test_leb128_parsing() {
         // Test simple values
-        assert_eq!(read_leb128_u32(&[0x00]).unwrap(), (0, 1));
-        assert_eq!(read_leb128_u32(&[0x7F]).unwrap(), (127, 1));
-        assert_eq!(read_leb128_u32(&[0x80, 0x01]).unwrap(), (128, 2));
-        assert_eq!(read_leb128_u32(&[0x80, 0x80, 0x01]).unwrap(), (16384, 3));
+        assert_eq!(leb128::read_u32(&[0x00], 0).unwrap(), (0, 1));
+        assert_eq!(leb128::read_u32(&[0x7F], 0).unwrap(), (127, 1));
+        assert_eq!(leb128::read_u32(&[0x80, 0x01], 0).unwrap(), (128, 2));
+        assert_eq!(
+            leb128::read_u32(&[0x80, 0x80, 0x01], 0).unwrap(),
+            (16384, 3)
+        );
     }
 }
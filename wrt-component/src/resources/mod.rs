@@ -78,7 +78,10 @@
     ResourceTableBuilder,
 };
 // Export ResourceInterceptor
-pub use resource_interceptor::ResourceInterceptor;
+pub use resource_interceptor::{
+    ResourceInterceptor,
+    ResourceLifecycleEvent,
+};
 // Export ResourceId and ResourceManager based on feature flags
 #[cfg(feature = "std")]
 pub use resource_manager::{
@@ -104,6 +107,7 @@
 #[cfg(feature = "std")]
 pub use resource_table::{
     BufferPoolTrait,
+    HandleKind,
     MemoryStrategy,
     Resource,
     ResourceTable,
@@ -132,30 +136,31 @@
 };
 
 /// Timestamp implementation for no_std
+///
+/// Reads the clock installed through [`wrt_foundation::platform_hooks`];
+/// without one, all instants are equal and durations are zero.
 #[derive(Debug, Clone, Copy)]
 pub struct Instant {
-    // Store a monotonic counter for elapsed time simulation
-    dummy: u64,
+    /// Monotonic time of the platform clock in nanoseconds
+    nanos: u64,
 }
 
 impl Instant {
-    // Create a new instant at the current monotonic time
+    /// Create a new instant at the current monotonic time
     pub fn now() -> Self {
-        // In a real implementation, we might read from a hardware timer
-        // Here we just use a placeholder value for no_std compatibility
-        Self { dummy: 0 }
+        Self {
+            nanos: wrt_foundation::platform_hooks::monotonic_ns().unwrap_or(0),
+        }
     }
 
-    // Get the elapsed time since this instant was created
-    pub fn elapsed(&self) -> Duration {
-        // In a real implementation, we'd compare with the current monotonic time
-        // Here we just return zero for no_std compatibility
-        Duration::from_secs(0)
+    /// Get the elapsed time since this instant was created
+    pub fn elapsed(&self) -> core::time::Duration {
+        Self::now().duration_since(self)
     }
 
-    // Calculate the duration between two instants
-    pub fn duration_since(&self, earlier: &Self) -> Duration {
-        // Just a placeholder implementation for no_std
-        Duration::from_secs(0)
+    /// Calculate the duration between two instants, zero if `earlier` is
+    /// later
+    pub fn duration_since(&self, earlier: &Self) -> core::time::Duration {
+        core::time::Duration::from_nanos(self.nanos.saturating_sub(earlier.nanos))
     }
 }
@@ -3,12 +3,24 @@
 // Licensed under the MIT license.
 // SPDX-License-Identifier: MIT
 
+use wrt_error::Result;
 #[cfg(feature = "std")]
 use wrt_foundation::resource::ResourceOperation as FormatResourceOperation;
 
 #[cfg(feature = "std")]
 use super::Resource;
 
+/// Identity of a resource handle reported to lifecycle hooks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResourceLifecycleEvent {
+    /// Handle of the resource in the owning table
+    pub handle:   u32,
+    /// Type index of the resource
+    pub type_idx: u32,
+    /// Component instance owning the handle, if the table has an owner
+    pub owner:    Option<u32>,
+}
+
 /// Trait for intercepting resource operations
 #[cfg(feature = "std")]
 pub trait ResourceInterceptor: Send + Sync {
@@ -41,6 +53,31 @@ fn intercept_resource_operation(
     ) -> Result<Option<Vec<u8>>> {
         Ok(None)
     }
+
+    /// Called once a new resource has been assigned its handle.
+    ///
+    /// Returning an error rejects the creation, which lets hosts bound the
+    /// number of live resources per component.
+    fn on_resource_created(&self, event: &ResourceLifecycleEvent) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called before a resource is removed from its table
+    fn on_resource_dropped(&self, event: &ResourceLifecycleEvent) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called before ownership of a resource moves to another table.
+    ///
+    /// `from` describes the handle being given up and `to` the handle it will
+    /// have in the receiving table. Returning an error rejects the transfer.
+    fn on_resource_transferred(
+        &self,
+        from: &ResourceLifecycleEvent,
+        to: &ResourceLifecycleEvent,
+    ) -> Result<()> {
+        Ok(())
+    }
 }
 
 /// Trait for intercepting resource operations (no_std compatible)
@@ -59,4 +96,23 @@ pub trait ResourceInterceptor {
     fn get_memory_strategy(&self, handle: u32) -> Option<u8> {
         None
     }
+
+    /// Called once a new resource has been assigned its handle
+    fn on_resource_created(&mut self, event: &ResourceLifecycleEvent) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called before a resource is removed from its table
+    fn on_resource_dropped(&mut self, event: &ResourceLifecycleEvent) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called before ownership of a resource moves to another table
+    fn on_resource_transferred(
+        &mut self,
+        from: &ResourceLifecycleEvent,
+        to: &ResourceLifecycleEvent,
+    ) -> Result<()> {
+        Ok(())
+    }
 }
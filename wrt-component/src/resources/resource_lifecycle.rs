@@ -556,10 +556,9 @@ pub fn set_hooks(&mut self, hooks: LifecycleHooks) {
         self.hooks = hooks;
     }
 
-    /// Get current timestamp (mock implementation)
+    /// Current time of the platform clock in nanoseconds, 0 without one
     fn get_timestamp(&self) -> u64 {
-        // In a real implementation, this would use platform-specific time
-        0
+        wrt_foundation::platform_hooks::monotonic_ns().unwrap_or(0)
     }
 }
 
@@ -3,6 +3,8 @@
 // This module provides resource management functionality for the Component
 // Model, including resource creation, access control, and lifetime management.
 
+use std::sync::MutexGuard;
+
 use wrt_error::kinds::PoisonedLockError;
 use wrt_foundation::{
     component_value::ComponentValue,
@@ -187,6 +189,34 @@ pub fn borrow_resource(&self, handle: u32) -> Result<u32> {
         table.borrow_resource(handle)
     }
 
+    /// Lend a resource to another component instance for a call into it
+    pub fn lend_resource(&self, handle: u32, target: &ResourceManager) -> Result<u32> {
+        let (mut table, mut target) = self.lock_pair(target)?;
+        table.lend_resource(handle, &mut target)
+    }
+
+    /// Transfer ownership of a resource to another component instance
+    pub fn transfer_resource(&self, handle: u32, target: &ResourceManager) -> Result<u32> {
+        let (mut table, mut target) = self.lock_pair(target)?;
+        table.transfer_resource(handle, &mut target)
+    }
+
+    /// Enter a call into this component instance
+    pub fn enter_call(&self) -> Result<()> {
+        let mut table = self
+            .table
+            .lock()
+            .map_err(|_| Error::runtime_poisoned_lock("Failed to acquire resource table lock"))?;
+        table.enter_call()
+    }
+
+    /// Exit a call into this component instance, moving the returned owned
+    /// handles to `caller`
+    pub fn exit_call(&self, returned: &[u32], caller: &ResourceManager) -> Result<Vec<u32>> {
+        let (mut table, mut caller) = self.lock_pair(caller)?;
+        table.exit_call(returned, &mut caller)
+    }
+
     /// Get a host resource by ID and type (legacy API)
     pub fn get_host_resource<T: 'static + Send + Sync>(
         &self,
@@ -344,6 +374,24 @@ pub fn create_named_arena(&self, name: &str) -> ResourceArena {
     pub fn uses_optimized_memory(&self) -> bool {
         self.use_optimized_memory
     }
+
+    /// Lock the tables of this manager and another one
+    fn lock_pair<'a>(
+        &'a self,
+        other: &'a ResourceManager,
+    ) -> Result<(MutexGuard<'a, ResourceTable>, MutexGuard<'a, ResourceTable>)> {
+        if Arc::ptr_eq(&self.table, &other.table) {
+            return Err(Error::runtime_invalid_state(
+                "Resources cannot move within the same table",
+            ));
+        }
+        let lock = |table: &'a Arc<Mutex<ResourceTable>>| {
+            table
+                .lock()
+                .map_err(|_| Error::runtime_poisoned_lock("Failed to acquire resource table lock"))
+        };
+        Ok((lock(&self.table)?, lock(&other.table)?))
+    }
 }
 
 impl fmt::Debug for ResourceManager {
@@ -467,4 +515,24 @@ fn test_borrow_resource() {
 
         assert_eq!(*data1, *data2);
     }
+
+    #[test]
+    fn test_cross_component_call() {
+        let caller = ResourceManager::new_with_id("caller");
+        let callee = ResourceManager::new_with_id("callee");
+        let handle = caller.create_resource(1, Arc::new(42i32)).unwrap();
+
+        callee.enter_call().unwrap();
+        let borrowed = caller.lend_resource(handle, &callee).unwrap();
+        assert!(caller.drop_resource(handle).is_err());
+        callee.drop_resource(borrowed).unwrap();
+
+        let result = callee.create_resource(2, Arc::new(7i32)).unwrap();
+        let returned = callee.exit_call(&[result], &caller).unwrap();
+        assert!(caller.has_resource(ResourceId(returned[0])).unwrap());
+        assert!(!callee.has_resource(ResourceId(result)).unwrap());
+
+        // Moving within one table is rejected instead of deadlocking
+        assert!(caller.transfer_resource(handle, &caller).is_err());
+    }
 }
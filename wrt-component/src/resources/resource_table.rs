@@ -1,6 +1,10 @@
 // Safety-critical imports for WRT allocator
 #[cfg(not(feature = "std"))]
 use alloc::collections::BTreeMap as HashMap;
+use core::sync::atomic::{
+    AtomicU32,
+    Ordering,
+};
 #[cfg(all(feature = "std", not(feature = "safety-critical")))]
 use std::collections::HashMap;
 use std::{
@@ -31,6 +35,7 @@
 
 use super::{
     buffer_pool::BufferPool,
+    resource_interceptor::ResourceLifecycleEvent,
     resource_operation::{
         from_format_resource_operation,
         to_format_resource_operation,
@@ -179,11 +184,27 @@ pub fn to_u8(self) -> u8 {
     }
 }
 
+/// Whether a handle owns its resource or borrows it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HandleKind {
+    /// Owned handle, which may be transferred or dropped
+    Own,
+    /// Borrowed handle, valid until the end of the call it was created in
+    Borrow {
+        /// Call depth of the table when the borrow was created
+        scope: u32,
+    },
+}
+
 /// Resource entry in the resource table (budget-aware)
 #[derive(Clone)]
 struct ResourceEntry {
     /// The resource instance
     resource:           Arc<Mutex<Resource>>,
+    /// Whether the handle owns or borrows the resource
+    kind:               HandleKind,
+    /// Outstanding borrows of the owning handle, shared with every borrow
+    lends:              Arc<AtomicU32>,
     /// Weak references to borrowed resources (budget-aware)
     #[cfg(all(feature = "std", feature = "safety-critical"))]
     borrows:            WrtVec<Weak<Mutex<Resource>>, { CrateId::Component as u8 }, 32>,
@@ -315,6 +336,10 @@ pub struct ResourceTable {
     default_memory_strategy:    MemoryStrategy,
     /// Default verification level
     default_verification_level: VerificationLevel,
+    /// Component instance owning the handles in this table
+    owner:                      Option<u32>,
+    /// Number of calls into the owning instance currently in progress
+    call_depth:                 u32,
     /// Buffer pool for bounded copy operations
     buffer_pool:                Arc<Mutex<dyn BufferPoolTrait + Send + Sync>>,
     /// Interceptors for resource operations (budget-aware)
@@ -340,6 +365,8 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
                 "default_verification_level",
                 &self.default_verification_level,
             )
+            .field("owner", &self.owner)
+            .field("call_depth", &self.call_depth)
             .field("interceptor_count", &self.interceptors.len())
             .finish()
     }
@@ -371,6 +398,8 @@ pub fn new() -> WrtResult<Self> {
             max_resources: MAX_RESOURCES,
             default_memory_strategy: MemoryStrategy::default(),
             default_verification_level: VerificationLevel::default(),
+            owner: None,
+            call_depth: 0,
             buffer_pool: Arc::new(Mutex::new(BufferPool::new(4096)))
                 as Arc<Mutex<dyn BufferPoolTrait + Send + Sync>>,
             #[cfg(all(feature = "std", feature = "safety-critical"))]
@@ -413,6 +442,8 @@ pub fn new_with_optimized_memory() -> WrtResult<Self> {
             max_resources: MAX_RESOURCES,
             default_memory_strategy: MemoryStrategy::default(),
             default_verification_level: VerificationLevel::default(),
+            owner: None,
+            call_depth: 0,
             buffer_pool: Arc::new(Mutex::new(SizeClassBufferPool::new()))
                 as Arc<Mutex<dyn BufferPoolTrait + Send + Sync>>,
             #[cfg(all(feature = "std", feature = "safety-critical"))]
@@ -441,6 +472,8 @@ pub fn new_with_config(
             max_resources,
             default_memory_strategy: memory_strategy,
             default_verification_level: verification_level,
+            owner: None,
+            call_depth: 0,
             buffer_pool: Arc::new(Mutex::new(BufferPool::new(4096)))
                 as Arc<Mutex<dyn BufferPoolTrait + Send + Sync>>,
             #[cfg(feature = "safety-critical")]
@@ -465,6 +498,8 @@ pub fn new_with_config_and_optimized_memory(
             max_resources,
             default_memory_strategy: memory_strategy,
             default_verification_level: verification_level,
+            owner: None,
+            call_depth: 0,
             buffer_pool: Arc::new(Mutex::new(SizeClassBufferPool::new()))
                 as Arc<Mutex<dyn BufferPoolTrait + Send + Sync>>,
             #[cfg(feature = "safety-critical")]
@@ -489,6 +524,16 @@ pub fn add_interceptor(&mut self, interceptor: Arc<dyn ResourceInterceptor>) ->
         }
     }
 
+    /// Set the component instance owning the handles in this table
+    pub fn set_owner(&mut self, owner: u32) {
+        self.owner = Some(owner);
+    }
+
+    /// Get the component instance owning the handles in this table
+    pub fn owner(&self) -> Option<u32> {
+        self.owner
+    }
+
     /// Create a new resource
     pub fn create_resource(
         &mut self,
@@ -514,8 +559,20 @@ pub fn create_resource(
         let handle = self.next_handle;
         self.next_handle += 1;
 
+        // Report the new handle; interceptors may reject the creation
+        let event = ResourceLifecycleEvent {
+            handle,
+            type_idx,
+            owner: self.owner,
+        };
+        for interceptor in &self.interceptors {
+            interceptor.on_resource_created(&event)?;
+        }
+
         let entry = ResourceEntry {
             resource: Arc::new(Mutex::new(resource)),
+            kind: HandleKind::Own,
+            lends: Arc::new(AtomicU32::new(0)),
             #[cfg(feature = "safety-critical")]
             borrows: WrtVec::new(),
             #[cfg(not(feature = "safety-critical"))]
@@ -541,92 +598,160 @@ pub fn create_resource(
     }
 
     /// Create a borrowed reference to a resource
+    ///
+    /// The borrow is scoped to the call in progress, see
+    /// [`ResourceTable::enter_call`].
     pub fn borrow_resource(&mut self, handle: u32) -> Result<u32> {
-        // Check if the resource exists
-        let resource_opt = self.resources.get(&handle).map(|entry| entry.resource.clone());
+        let (resource, lends) = self.lend(handle)?;
+        self.insert_borrow(resource, lends)
+    }
 
-        let resource = match resource_opt {
-            Some(r) => r,
-            None => {
-                return Err(Error::resource_error("Resource not found"));
-            },
-        };
+    /// Lend a resource to the instance owning `target` for a call into it
+    ///
+    /// This lowers a `borrow<T>` argument: the returned handle is valid in
+    /// `target` until the call in progress there exits, and the lent resource
+    /// can be neither dropped nor transferred before the borrow ends.
+    pub fn lend_resource(&mut self, handle: u32, target: &mut ResourceTable) -> Result<u32> {
+        if target.call_depth == 0 {
+            return Err(Error::runtime_invalid_state(
+                "Resources can only be lent to a call in progress",
+            ));
+        }
+        let (resource, lends) = self.lend(handle)?;
+        target.insert_borrow(resource, lends)
+    }
 
-        // Notify interceptors about resource borrowing
+    /// Drop a resource
+    ///
+    /// Dropping an owned handle traps while borrows of it are outstanding.
+    pub fn drop_resource(&mut self, handle: u32) -> Result<()> {
+        self.check_not_lent(handle)?;
+
+        // Notify interceptors about resource dropping
         for interceptor in &self.interceptors {
-            interceptor.on_resource_borrow(handle)?;
+            interceptor.on_resource_drop(handle)?;
         }
+        self.notify_dropped(handle)?;
 
-        // Create a new handle for the borrowed resource
-        let borrow_handle = self.next_handle;
-        self.next_handle += 1;
+        // Remove the resource
+        self.remove_entry(handle);
 
-        // Store the weak reference in the original resource
-        let weak_ref = Arc::downgrade(&resource);
-        if let Some(entry) = self.resources.get_mut(&handle) {
-            #[cfg(feature = "safety-critical")]
-            {
-                entry.borrows.push(weak_ref).map_err(|_| {
-                    Error::resource_exhausted("Failed to add resource borrow: capacity exceeded")
-                })?;
-            }
-            #[cfg(not(feature = "safety-critical"))]
-            {
-                entry.borrows.push(weak_ref);
-            }
-        }
+        Ok(())
+    }
 
-        // Store the borrowed resource
-        #[cfg(feature = "safety-critical")]
-        {
-            self.resources
-                .insert(
-                    borrow_handle,
-                    ResourceEntry {
-                        resource,
-                        borrows: WrtVec::new(),
-                        memory_strategy: self.default_memory_strategy,
-                        verification_level: self.default_verification_level,
-                    },
-                )
-                .map_err(|_| {
-                    Error::resource_exhausted(
-                        "Failed to store borrowed resource: capacity exceeded",
-                    )
-                })?;
+    /// Enter a call into the instance owning this table
+    ///
+    /// Borrows created until the matching [`ResourceTable::exit_call`],
+    /// including those lent by the caller, are scoped to this call.
+    pub fn enter_call(&mut self) -> Result<()> {
+        self.call_depth = self
+            .call_depth
+            .checked_add(1)
+            .ok_or_else(|| Error::runtime_trap("Call depth overflow"))?;
+        Ok(())
+    }
+
+    /// Exit the innermost call, moving the owned handles it returns to
+    /// `caller`
+    ///
+    /// Traps if a borrow scoped to the call is still live, as the callee must
+    /// drop every borrow before returning. Otherwise each handle in
+    /// `returned` is transferred to `caller` and the new handles are returned
+    /// in order.
+    pub fn exit_call(&mut self, returned: &[u32], caller: &mut ResourceTable) -> Result<Vec<u32>> {
+        let scope = self.call_depth;
+        if scope == 0 {
+            return Err(Error::runtime_invalid_state("No call in progress"));
         }
-        #[cfg(not(feature = "safety-critical"))]
+        if self
+            .resources
+            .iter()
+            .any(|(_, entry)| entry.kind == HandleKind::Borrow { scope })
         {
-            self.resources.insert(
-                borrow_handle,
-                ResourceEntry {
-                    resource,
-                    borrows: Vec::new(),
-                    memory_strategy: self.default_memory_strategy,
-                    verification_level: self.default_verification_level,
-                },
-            );
+            return Err(Error::runtime_trap("Borrowed handle outlives the call"));
         }
+        self.call_depth -= 1;
 
-        Ok(borrow_handle)
+        returned.iter().map(|handle| self.transfer_resource(*handle, caller)).collect()
     }
 
-    /// Drop a resource
-    pub fn drop_resource(&mut self, handle: u32) -> Result<()> {
-        // Check if the resource exists
-        if !self.resources.contains_key(&handle) {
-            return Err(Error::resource_error("Resource not found"));
+    /// Number of calls into the owning instance currently in progress
+    pub fn call_depth(&self) -> u32 {
+        self.call_depth
+    }
+
+    /// Whether a handle owns or borrows its resource
+    pub fn handle_kind(&self, handle: u32) -> Result<HandleKind> {
+        self.resources
+            .get(&handle)
+            .map(|entry| entry.kind)
+            .ok_or_else(|| Error::resource_error("Resource not found"))
+    }
+
+    /// Number of outstanding borrows of the resource behind a handle
+    pub fn borrow_count(&self, handle: u32) -> Result<u32> {
+        self.resources
+            .get(&handle)
+            .map(|entry| entry.lends.load(Ordering::Acquire))
+            .ok_or_else(|| Error::resource_error("Resource not found"))
+    }
+
+    /// Transfer ownership of a resource to another table
+    ///
+    /// The resource is removed from this table and inserted into `target`
+    /// under a new handle, which is returned. Interceptors of both tables are
+    /// notified and may reject the transfer.
+    ///
+    /// Only owned handles without outstanding borrows can be transferred.
+    pub fn transfer_resource(&mut self, handle: u32, target: &mut ResourceTable) -> Result<u32> {
+        if let HandleKind::Borrow { .. } = self.handle_kind(handle)? {
+            return Err(Error::resource_invalid_handle(
+                "Borrowed handles cannot be transferred",
+            ));
         }
+        self.check_not_lent(handle)?;
+        let from = self.lifecycle_event(handle)?;
 
-        // Notify interceptors about resource dropping
+        if target.resources.len() >= target.max_resources {
+            return Err(Error::resource_exhausted(
+                "Maximum number of resources reached in target table",
+            ));
+        }
+
+        let to = ResourceLifecycleEvent {
+            handle:   target.next_handle,
+            type_idx: from.type_idx,
+            owner:    target.owner,
+        };
+
+        // Notify each distinct interceptor once, even if it watches both tables
         for interceptor in &self.interceptors {
-            interceptor.on_resource_drop(handle)?;
+            interceptor.on_resource_transferred(&from, &to)?;
+        }
+        for interceptor in &target.interceptors {
+            if !self.interceptors.iter().any(|seen| Arc::ptr_eq(seen, interceptor)) {
+                interceptor.on_resource_transferred(&from, &to)?;
+            }
         }
 
-        // Remove the resource
-        self.resources.remove(&handle);
+        let entry = self
+            .resources
+            .remove(&handle)
+            .ok_or_else(|| Error::resource_error("Resource not found"))?;
+        target.next_handle += 1;
 
-        Ok(())
+        #[cfg(feature = "safety-critical")]
+        {
+            target.resources.insert(to.handle, entry).map_err(|_| {
+                Error::resource_exhausted("Failed to insert resource: capacity exceeded")
+            })?;
+        }
+        #[cfg(not(feature = "safety-critical"))]
+        {
+            target.resources.insert(to.handle, entry);
+        }
+
+        Ok(to.handle)
     }
 
     /// Get a resource by handle
@@ -686,12 +811,16 @@ pub fn apply_operation(
             },
             FormatResourceOperation::Drop(drop) => {
                 // Drop operation - remove the resource from the table
-                let resource = self.resources.remove(&handle).unwrap();
+                self.check_not_lent(handle)?;
+                self.notify_dropped(handle)?;
+                let resource = self.remove_entry(handle).unwrap();
                 Ok(ComponentValue::Void)
             },
             FormatResourceOperation::Destroy(destroy) => {
                 // Destroy operation - similar to drop but may perform cleanup
-                let resource = self.resources.remove(&handle).unwrap();
+                self.check_not_lent(handle)?;
+                self.notify_dropped(handle)?;
+                let resource = self.remove_entry(handle).unwrap();
                 // Run any destroy callbacks here
                 Ok(ComponentValue::Void)
             },
@@ -753,7 +882,7 @@ pub fn cleanup_unused_resources(&mut self) -> usize {
             .collect();
 
         for handle in &handles_to_remove {
-            self.resources.remove(handle);
+            self.remove_entry(*handle);
         }
 
         handles_to_remove.len()
@@ -774,6 +903,127 @@ pub fn reset_buffer_pool(&mut self) {
         self.buffer_pool.lock().unwrap().reset()
     }
 
+    /// Record a new borrow of `handle`, returning the borrowed resource and
+    /// the borrow counter of its owning handle
+    fn lend(&mut self, handle: u32) -> Result<(Arc<Mutex<Resource>>, Arc<AtomicU32>)> {
+        let (resource, lends) = self
+            .resources
+            .get(&handle)
+            .map(|entry| (entry.resource.clone(), entry.lends.clone()))
+            .ok_or_else(|| Error::resource_error("Resource not found"))?;
+
+        // Notify interceptors about resource borrowing
+        for interceptor in &self.interceptors {
+            interceptor.on_resource_borrow(handle)?;
+        }
+
+        // Store the weak reference in the original resource
+        let weak_ref = Arc::downgrade(&resource);
+        if let Some(entry) = self.resources.get_mut(&handle) {
+            #[cfg(feature = "safety-critical")]
+            {
+                entry.borrows.push(weak_ref).map_err(|_| {
+                    Error::resource_exhausted("Failed to add resource borrow: capacity exceeded")
+                })?;
+            }
+            #[cfg(not(feature = "safety-critical"))]
+            {
+                entry.borrows.push(weak_ref);
+            }
+        }
+
+        Ok((resource, lends))
+    }
+
+    /// Store a borrowed resource under a new handle scoped to the current call
+    fn insert_borrow(
+        &mut self,
+        resource: Arc<Mutex<Resource>>,
+        lends: Arc<AtomicU32>,
+    ) -> Result<u32> {
+        if self.resources.len() >= self.max_resources {
+            return Err(Error::resource_exhausted(
+                "Maximum number of resources reached",
+            ));
+        }
+
+        let borrow_handle = self.next_handle;
+        self.next_handle += 1;
+
+        let entry = ResourceEntry {
+            resource,
+            kind: HandleKind::Borrow {
+                scope: self.call_depth,
+            },
+            lends: lends.clone(),
+            #[cfg(feature = "safety-critical")]
+            borrows: WrtVec::new(),
+            #[cfg(not(feature = "safety-critical"))]
+            borrows: Vec::new(),
+            memory_strategy: self.default_memory_strategy,
+            verification_level: self.default_verification_level,
+        };
+
+        #[cfg(feature = "safety-critical")]
+        {
+            self.resources.insert(borrow_handle, entry).map_err(|_| {
+                Error::resource_exhausted("Failed to store borrowed resource: capacity exceeded")
+            })?;
+        }
+        #[cfg(not(feature = "safety-critical"))]
+        {
+            self.resources.insert(borrow_handle, entry);
+        }
+
+        lends.fetch_add(1, Ordering::AcqRel);
+        Ok(borrow_handle)
+    }
+
+    /// Trap if `handle` owns a resource that is still borrowed
+    fn check_not_lent(&self, handle: u32) -> Result<()> {
+        if self.handle_kind(handle)? == HandleKind::Own && self.borrow_count(handle)? > 0 {
+            return Err(Error::runtime_trap("Resource released while borrowed"));
+        }
+        Ok(())
+    }
+
+    /// Remove a handle from the table, ending the borrow it represents
+    fn remove_entry(&mut self, handle: u32) -> Option<ResourceEntry> {
+        let entry = self.resources.remove(&handle)?;
+        if let HandleKind::Borrow { .. } = entry.kind {
+            entry.lends.fetch_sub(1, Ordering::AcqRel);
+        }
+        Some(entry)
+    }
+
+    /// Describe a live handle for lifecycle hooks
+    fn lifecycle_event(&self, handle: u32) -> Result<ResourceLifecycleEvent> {
+        let entry = self
+            .resources
+            .get(&handle)
+            .ok_or_else(|| Error::resource_error("Resource not found"))?;
+        let type_idx = entry
+            .resource
+            .lock()
+            .map_err(|_| Error::poisoned_lock("Resource lock poisoned"))?
+            .type_idx;
+
+        Ok(ResourceLifecycleEvent {
+            handle,
+            type_idx,
+            owner: self.owner,
+        })
+    }
+
+    /// Notify interceptors that a handle is about to be dropped
+    fn notify_dropped(&self, handle: u32) -> Result<()> {
+        let event = self.lifecycle_event(handle)?;
+        for interceptor in &self.interceptors {
+            interceptor.on_resource_dropped(&event)?;
+        }
+        Ok(())
+    }
+
     /// Get memory strategy from interceptors
     pub fn get_strategy_from_interceptors(&self, handle: u32) -> Option<MemoryStrategy> {
         for interceptor in &self.interceptors {
@@ -863,6 +1113,34 @@ fn intercept_resource_operation(
                 Ok(None)
             }
         }
+
+        fn on_resource_created(&self, event: &ResourceLifecycleEvent) -> Result<()> {
+            self.operations.lock().unwrap().push(format!(
+                "created_{}_{}_{:?}",
+                event.handle, event.type_idx, event.owner
+            ));
+            Ok(())
+        }
+
+        fn on_resource_dropped(&self, event: &ResourceLifecycleEvent) -> Result<()> {
+            self.operations.lock().unwrap().push(format!(
+                "dropped_{}_{}_{:?}",
+                event.handle, event.type_idx, event.owner
+            ));
+            Ok(())
+        }
+
+        fn on_resource_transferred(
+            &self,
+            from: &ResourceLifecycleEvent,
+            to: &ResourceLifecycleEvent,
+        ) -> Result<()> {
+            self.operations.lock().unwrap().push(format!(
+                "transferred_{}_{:?}_{}_{:?}",
+                from.handle, from.owner, to.handle, to.owner
+            ));
+            Ok(())
+        }
     }
 
     #[test]
@@ -994,6 +1272,8 @@ fn test_resource_interception() {
             42,
             ResourceEntry {
                 resource: Arc::new(Mutex::new(Resource::new(1, data))),
+                kind: HandleKind::Own,
+                lends: Arc::default(),
                 #[cfg(feature = "safety-critical")]
                 borrows: WrtVec::new(),
                 #[cfg(not(feature = "safety-critical"))]
@@ -1037,6 +1317,8 @@ fn test_memory_strategy_selection() {
                     1,
                     Arc::new(TestData { value: 2 }),
                 ))),
+                kind: HandleKind::Own,
+                lends: Arc::default(),
                 #[cfg(feature = "safety-critical")]
                 borrows: WrtVec::new(),
                 #[cfg(not(feature = "safety-critical"))]
@@ -1053,6 +1335,8 @@ fn test_memory_strategy_selection() {
                     1,
                     Arc::new(TestData { value: 3 }),
                 ))),
+                kind: HandleKind::Own,
+                lends: Arc::default(),
                 #[cfg(feature = "safety-critical")]
                 borrows: WrtVec::new(),
                 #[cfg(not(feature = "safety-critical"))]
@@ -1070,4 +1354,127 @@ fn test_memory_strategy_selection() {
         let odd_strategy = table.get_strategy_from_interceptors(odd_handle);
         assert_eq!(odd_strategy, None);
     }
+
+    #[test]
+    fn test_resource_lifecycle_events() {
+        let interceptor = Arc::new(TestInterceptor::new());
+
+        let mut source = ResourceTable::new().unwrap();
+        source.set_owner(1);
+        source.add_interceptor(interceptor.clone()).unwrap();
+        let mut target = ResourceTable::new().unwrap();
+        target.set_owner(2);
+        target.add_interceptor(interceptor.clone()).unwrap();
+
+        let handle = source.create_resource(7, Arc::new(TestData { value: 1 })).unwrap();
+        let moved = source.transfer_resource(handle, &mut target).unwrap();
+        assert_eq!(source.resource_count(), 0);
+        assert_eq!(target.get_resource(moved).unwrap().lock().unwrap().type_idx, 7);
+        target.drop_resource(moved).unwrap();
+
+        let lifecycle: Vec<String> = interceptor
+            .get_operations()
+            .into_iter()
+            .filter(|op| {
+                op.starts_with("created_")
+                    || op.starts_with("transferred_")
+                    || op.starts_with("dropped_")
+            })
+            .collect();
+        assert_eq!(
+            lifecycle,
+            vec![
+                format!("created_{}_7_Some(1)", handle),
+                format!("transferred_{}_Some(1)_{}_Some(2)", handle, moved),
+                format!("dropped_{}_7_Some(2)", moved),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cross_component_borrow() {
+        let mut caller = ResourceTable::new().unwrap();
+        let mut callee = ResourceTable::new().unwrap();
+        let mut other = ResourceTable::new().unwrap();
+        let handle = caller.create_resource(1, Arc::new(TestData { value: 7 })).unwrap();
+
+        // Borrows are only lent for the duration of a call
+        assert!(caller.lend_resource(handle, &mut callee).is_err());
+
+        callee.enter_call().unwrap();
+        let borrowed = caller.lend_resource(handle, &mut callee).unwrap();
+        assert_eq!(
+            callee.handle_kind(borrowed).unwrap(),
+            HandleKind::Borrow { scope: 1 }
+        );
+        assert_eq!(caller.borrow_count(handle).unwrap(), 1);
+
+        let resource = callee.get_resource(borrowed).unwrap();
+        assert_eq!(
+            resource.lock().unwrap().data.downcast_ref::<TestData>().unwrap().value,
+            7
+        );
+
+        // Neither side can release the resource while it is borrowed
+        assert!(caller.drop_resource(handle).is_err());
+        assert!(caller.transfer_resource(handle, &mut other).is_err());
+        assert!(callee.transfer_resource(borrowed, &mut other).is_err());
+
+        callee.drop_resource(borrowed).unwrap();
+        assert!(callee.exit_call(&[], &mut caller).unwrap().is_empty());
+        assert_eq!(caller.borrow_count(handle).unwrap(), 0);
+        caller.drop_resource(handle).unwrap();
+    }
+
+    #[test]
+    fn test_dangling_borrow_traps() {
+        let mut caller = ResourceTable::new().unwrap();
+        let mut callee = ResourceTable::new().unwrap();
+        let handle = caller.create_resource(1, Arc::new(TestData { value: 7 })).unwrap();
+
+        callee.enter_call().unwrap();
+        let borrowed = caller.lend_resource(handle, &mut callee).unwrap();
+
+        // Nested calls do not end the borrows of the outer call
+        callee.enter_call().unwrap();
+        callee.exit_call(&[], &mut caller).unwrap();
+        assert!(callee.get_resource(borrowed).is_ok());
+
+        let error = callee.exit_call(&[], &mut caller).unwrap_err();
+        assert_eq!(error.message, "Borrowed handle outlives the call");
+        assert_eq!(callee.call_depth(), 1);
+    }
+
+    #[test]
+    fn test_transfer_on_return() {
+        let mut caller = ResourceTable::new().unwrap();
+        caller.set_owner(1);
+        let mut callee = ResourceTable::new().unwrap();
+        callee.set_owner(2);
+
+        // Owned arguments move into the callee
+        let argument = caller.create_resource(1, Arc::new(TestData { value: 1 })).unwrap();
+        callee.enter_call().unwrap();
+        let received = caller.transfer_resource(argument, &mut callee).unwrap();
+
+        // Owned results move back to the caller when the call exits
+        let created = callee.create_resource(2, Arc::new(TestData { value: 2 })).unwrap();
+        let returned = callee.exit_call(&[created, received], &mut caller).unwrap();
+
+        assert_eq!(callee.resource_count(), 0);
+        assert_eq!(caller.resource_count(), 2);
+        assert_eq!(caller.handle_kind(returned[0]).unwrap(), HandleKind::Own);
+        let resource = caller.get_resource(returned[0]).unwrap();
+        assert_eq!(
+            resource.lock().unwrap().data.downcast_ref::<TestData>().unwrap().value,
+            2
+        );
+        assert_eq!(
+            caller.get_resource(returned[1]).unwrap().lock().unwrap().type_idx,
+            1
+        );
+
+        // No call is in progress any more
+        assert!(callee.exit_call(&[], &mut caller).is_err());
+    }
 }
@@ -12,6 +12,11 @@ pub mod kani_verification {
     use kani;
 
     use super::*;
+    #[cfg(feature = "std")]
+    use crate::canonical_abi::canonical_abi::{
+        CanonicalABI,
+        ComponentType,
+    };
 
     // --- Component Type Safety ---
 
@@ -283,6 +288,80 @@ pub fn verify_component_instance_safety() {
             }
         }
     }
+
+    // --- Canonical ABI Layout ---
+
+    /// Arbitrary component type without type parameters
+    #[cfg(feature = "std")]
+    fn any_scalar_type() -> ComponentType {
+        match kani::any::<u8>() % 14 {
+            0 => ComponentType::Bool,
+            1 => ComponentType::S8,
+            2 => ComponentType::U16,
+            3 => ComponentType::S32,
+            4 => ComponentType::U64,
+            5 => ComponentType::F32,
+            6 => ComponentType::F64,
+            7 => ComponentType::Char,
+            8 => ComponentType::String,
+            9 => ComponentType::Own(kani::any()),
+            10 => ComponentType::Borrow(kani::any()),
+            11 => ComponentType::ErrorContext,
+            12 => ComponentType::Enum(vec![String::new(); 3]),
+            _ => ComponentType::List(Box::new(ComponentType::U8)),
+        }
+    }
+
+    /// Verify that the canonical ABI gives every type a power-of-two
+    /// alignment, a size that is a multiple of it, and a size that holds all
+    /// of its fields or its payload and discriminant
+    #[cfg_attr(kani, kani::proof)]
+    #[cfg_attr(kani, kani::unwind(66))]
+    pub fn verify_canonical_abi_layout() {
+        #[cfg(feature = "std")]
+        {
+            let abi = CanonicalABI::new();
+            let first = any_scalar_type();
+            let second = any_scalar_type();
+            let first_size = abi.size_of(&first).expect("Scalar types have a size");
+            let second_size = abi.size_of(&second).expect("Scalar types have a size");
+
+            // Smallest size the type must have
+            let (ty, min_size) = match kani::any::<u8>() % 6 {
+                0 => (first, first_size),
+                1 => (
+                    ComponentType::Tuple(vec![first, second]),
+                    first_size + second_size,
+                ),
+                2 => (
+                    ComponentType::Record(vec![
+                        ("first".to_string(), first),
+                        ("second".to_string(), second),
+                    ]),
+                    first_size + second_size,
+                ),
+                3 => (ComponentType::Option(Box::new(first)), first_size + 1),
+                4 => (
+                    ComponentType::Result(Some(Box::new(first)), Some(Box::new(second))),
+                    first_size.max(second_size) + 1,
+                ),
+                _ => {
+                    let flag_count: usize = kani::any();
+                    kani::assume(flag_count <= 64);
+                    (
+                        ComponentType::Flags(vec![String::new(); flag_count]),
+                        flag_count.div_ceil(8) as u32,
+                    )
+                },
+            };
+
+            let size = abi.size_of(&ty).expect("Type has a size");
+            let align = abi.align_of(&ty).expect("Type has an alignment");
+            assert!(align.is_power_of_two() && align <= 8);
+            assert_eq!(size % align, 0, "Size must be a multiple of the alignment");
+            assert!(size >= min_size, "Type must hold its fields");
+        }
+    }
 }
 
 // Expose verification module in docs but not for normal compilation
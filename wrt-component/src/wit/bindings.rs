@@ -0,0 +1,329 @@
+//! Typed host bindings generated from a resolved world
+//!
+//! [`HostBindings`] lists the functions a world imports so that an embedder
+//! can supply an implementation for each of them. Installed implementations
+//! only ever see arguments that conform to the WIT types of the import, and
+//! their results are checked the same way before they reach the guest.
+
+use std::{
+    sync::Arc,
+    vec::Vec,
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+
+use super::world::{
+    WitFunctionType,
+    WitWorldType,
+};
+use crate::{
+    canonical_abi::{
+        ComponentType,
+        ComponentValue,
+    },
+    components::{
+        component_instantiation::ComponentInstance,
+        component_linker::{
+            ComponentLinker,
+            HostFunction,
+        },
+    },
+};
+
+/// Host implementations for the imports of a world
+pub struct HostBindings {
+    imports: Vec<(WitFunctionType, Option<HostFunction>)>,
+}
+
+impl core::fmt::Debug for HostBindings {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("HostBindings")
+            .field(
+                "imports",
+                &self.imports.iter().map(|(ty, _)| ty.qualified_name()).collect::<Vec<_>>(),
+            )
+            .finish_non_exhaustive()
+    }
+}
+
+impl HostBindings {
+    /// Create bindings for every import of a world
+    pub fn new(world: &WitWorldType) -> Self {
+        Self {
+            imports: world.imports.iter().map(|ty| (ty.clone(), None)).collect(),
+        }
+    }
+
+    /// Create bindings for a list of imported functions
+    pub fn from_functions(functions: impl IntoIterator<Item = WitFunctionType>) -> Self {
+        Self {
+            imports: functions.into_iter().map(|ty| (ty, None)).collect(),
+        }
+    }
+
+    /// Functions the world imports
+    pub fn functions(&self) -> impl Iterator<Item = &WitFunctionType> {
+        self.imports.iter().map(|(ty, _)| ty)
+    }
+
+    /// Imports that have no implementation yet
+    pub fn unimplemented(&self) -> impl Iterator<Item = &WitFunctionType> {
+        self.imports.iter().filter(|(_, function)| function.is_none()).map(|(ty, _)| ty)
+    }
+
+    /// Implement the import `name` of instance `instance`; use an empty
+    /// `instance` for freestanding function imports
+    pub fn implement<F>(&mut self, instance: &str, name: &str, function: F) -> Result<()>
+    where
+        F: Fn(&[ComponentValue]) -> Result<Vec<ComponentValue>> + Send + Sync + 'static,
+    {
+        let (_, slot) = self
+            .imports
+            .iter_mut()
+            .find(|(ty, _)| ty.instance == instance && ty.name == name)
+            .ok_or_else(|| Error::resource_not_found("World does not import this function"))?;
+        if slot.is_some() {
+            return Err(Error::validation_error("World import already implemented"));
+        }
+
+        *slot = Some(Arc::new(function));
+        Ok(())
+    }
+
+    /// Define every implementation as a host function of the linker
+    ///
+    /// Fails without defining anything if an import is unimplemented.
+    pub fn install(self, linker: &mut ComponentLinker) -> Result<()> {
+        if self.unimplemented().next().is_some() {
+            return Err(Error::component_linking_error(
+                "World import has no host implementation",
+            ));
+        }
+
+        for (ty, function) in self.imports {
+            let Some(function) = function else {
+                continue;
+            };
+            let (instance, name, signature) =
+                (ty.instance.clone(), ty.name.clone(), ty.signature());
+            linker.define_host_function(&instance, &name, signature, move |args| {
+                ty.check_arguments(args)?;
+                let results = function(args)?;
+                ty.check_results(&results)?;
+                Ok(results)
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+impl WitFunctionType {
+    /// Check that arguments conform to the parameter types
+    pub fn check_arguments(&self, args: &[ComponentValue]) -> Result<()> {
+        if args.len() != self.params.len()
+            || !self.params.iter().zip(args).all(|((_, ty), value)| value_conforms(ty, value))
+        {
+            return Err(Error::runtime_type_mismatch(
+                "Arguments do not match the WIT parameter types",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check that results conform to the result types
+    pub fn check_results(&self, results: &[ComponentValue]) -> Result<()> {
+        if results.len() != self.results.len()
+            || !self.results.iter().zip(results).all(|(ty, value)| value_conforms(ty, value))
+        {
+            return Err(Error::runtime_type_mismatch(
+                "Results do not match the WIT result types",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Call this exported function on a component instance, checking
+    /// arguments and results against the world
+    pub fn call(
+        &self,
+        instance: &mut ComponentInstance,
+        args: &[ComponentValue],
+    ) -> Result<Vec<ComponentValue>> {
+        self.check_arguments(args)?;
+        let results = instance.call_function(&self.qualified_name(), args)?;
+        self.check_results(&results)?;
+        Ok(results)
+    }
+}
+
+/// Whether a value is an inhabitant of a component type
+pub fn value_conforms(ty: &ComponentType, value: &ComponentValue) -> bool {
+    use ComponentType as T;
+    use ComponentValue as V;
+
+    let payload_conforms = |ty: Option<&T>, value: Option<&V>| match (ty, value) {
+        (Some(ty), Some(value)) => value_conforms(ty, value),
+        (None, None) => true,
+        _ => false,
+    };
+
+    match (ty, value) {
+        (T::Bool, V::Bool(_))
+        | (T::S8, V::S8(_))
+        | (T::U8, V::U8(_))
+        | (T::S16, V::S16(_))
+        | (T::U16, V::U16(_))
+        | (T::S32, V::S32(_))
+        | (T::U32, V::U32(_))
+        | (T::S64, V::S64(_))
+        | (T::U64, V::U64(_))
+        | (T::F32, V::F32(_))
+        | (T::F64, V::F64(_))
+        | (T::Char, V::Char(_))
+        | (T::String, V::String(_))
+        | (T::Own(_), V::Own(_))
+        | (T::Borrow(_), V::Borrow(_))
+        | (T::Stream(_), V::Stream(_))
+        | (T::Future(_), V::Future(_))
+        | (T::ErrorContext, V::ErrorContext(_)) => true,
+        (T::List(element), V::List(values)) => values.iter().all(|v| value_conforms(element, v)),
+        (T::Record(fields), V::Record(values)) => {
+            fields.len() == values.len()
+                && fields.iter().all(|(name, ty)| {
+                    values.iter().any(|(field, value)| field == name && value_conforms(ty, value))
+                })
+        },
+        (T::Tuple(types), V::Tuple(values)) => {
+            types.len() == values.len()
+                && types.iter().zip(values).all(|(ty, value)| value_conforms(ty, value))
+        },
+        (T::Variant(cases), V::Variant(name, payload)) => cases
+            .iter()
+            .any(|(case, ty)| case == name && payload_conforms(ty.as_ref(), payload.as_deref())),
+        (T::Enum(cases), V::Enum(name)) => cases.contains(name),
+        (T::Option(inner), V::Option(value)) => {
+            value.as_deref().is_none_or(|value| value_conforms(inner, value))
+        },
+        (T::Result(ok, err), V::Result(value)) => match value {
+            Ok(payload) => payload_conforms(ok.as_deref(), payload.as_deref()),
+            Err(payload) => payload_conforms(err.as_deref(), payload.as_deref()),
+        },
+        (T::Flags(flags), V::Flags(active)) => active.iter().all(|flag| flags.contains(flag)),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        components::component_linker::HOST_INSTANCE_ID,
+        wit::parse_wit,
+    };
+
+    const WORLD: &str = r#"
+        package example:greeter;
+
+        interface names {
+            record person { name: string, age: u8 }
+            greet: func(who: person) -> string;
+        }
+
+        world greeter {
+            import names;
+            import log: func(message: string);
+        }
+    "#;
+
+    fn bindings() -> HostBindings {
+        HostBindings::new(&parse_wit(WORLD).unwrap().resolve_world("greeter").unwrap())
+    }
+
+    fn person(age: ComponentValue) -> ComponentValue {
+        ComponentValue::Record(vec![
+            ("age".to_string(), age),
+            (
+                "name".to_string(),
+                ComponentValue::String("Ada".to_string()),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_value_conforms() {
+        let ty = ComponentType::Record(vec![
+            ("name".to_string(), ComponentType::String),
+            ("age".to_string(), ComponentType::U8),
+        ]);
+        assert!(value_conforms(&ty, &person(ComponentValue::U8(36))));
+        assert!(!value_conforms(&ty, &person(ComponentValue::U32(36))));
+
+        let ty = ComponentType::Result(None, Some(Box::new(ComponentType::String)));
+        assert!(value_conforms(&ty, &ComponentValue::Result(Ok(None))));
+        assert!(!value_conforms(
+            &ty,
+            &ComponentValue::Result(Ok(Some(Box::new(ComponentValue::U8(1)))))
+        ));
+    }
+
+    #[test]
+    fn test_implement_and_install() {
+        let mut bindings = bindings();
+        assert_eq!(bindings.functions().count(), 2);
+
+        bindings
+            .implement("example:greeter/names", "greet", |_| {
+                Ok(vec![ComponentValue::String("Hello".to_string())])
+            })
+            .unwrap();
+        assert!(bindings.implement("example:greeter/names", "greet", |_| Ok(vec![])).is_err());
+        assert!(bindings.implement("", "missing", |_| Ok(vec![])).is_err());
+        assert_eq!(bindings.unimplemented().count(), 1);
+
+        // The log implementation returns a value the world does not declare
+        bindings.implement("", "log", |_| Ok(vec![ComponentValue::Bool(true)])).unwrap();
+        let mut linker = ComponentLinker::new();
+        bindings.install(&mut linker).unwrap();
+
+        // Host functions check their arguments and results against the world
+        let mut component = wrt_format::component::Component::new();
+        component.imports.push(wrt_format::component::Import {
+            name: wrt_format::component::ImportName {
+                namespace: String::new(),
+                name:      "log".to_string(),
+                nested:    Vec::new(),
+                package:   None,
+            },
+            ty:   wrt_format::component::ExternType::Function {
+                params:  vec![(
+                    "message".to_string(),
+                    wrt_format::component::FormatValType::String,
+                )],
+                results: Vec::new(),
+            },
+        });
+        linker.add_parsed_component("app".to_string(), &component).unwrap();
+        let instance = linker.instantiate(&"app".to_string(), None).unwrap();
+        assert_eq!(
+            linker.get_instance(instance).unwrap().imports[0].provider_id,
+            HOST_INSTANCE_ID
+        );
+
+        // Wrong argument type
+        assert!(linker.invoke_import(instance, "log", &[ComponentValue::U8(1)]).is_err());
+        // Result not declared by the world
+        assert!(linker
+            .invoke_import(instance, "log", &[ComponentValue::String("hi".to_string())])
+            .is_err());
+    }
+
+    #[test]
+    fn test_install_requires_every_import() {
+        let mut linker = ComponentLinker::new();
+        assert!(bindings().install(&mut linker).is_err());
+    }
+}
@@ -0,0 +1,78 @@
+//! WIT support for the Component Model
+//!
+//! Embedders can load a `.wit` world at runtime without external tooling:
+//!
+//! - [`parse_wit`] parses WIT text into a [`WitDocument`]
+//! - [`WitDocument::resolve_world`] flattens a world into typed function
+//!   imports and exports ([`WitWorldType`])
+//! - [`WitWorldType::check_component`] type-checks a parsed component against
+//!   the world
+//! - [`HostBindings`] collects host implementations of the world's imports and
+//!   installs them into a [`ComponentLinker`], checking every call against the
+//!   WIT types
+//!
+//! The `wrt-bindgen` crate generates typed Rust bindings for the interfaces of
+//! a WIT document at compile time; [`typed`] holds the conversions between
+//! Rust types and component values those bindings use.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use wrt_component::{
+//!     canonical_abi::ComponentValue,
+//!     components::component_linker::ComponentLinker,
+//!     wit::{
+//!         parse_wit,
+//!         HostBindings,
+//!     },
+//! };
+//!
+//! let document = parse_wit(
+//!     "package example:app;
+//!      world app {
+//!          import log: func(message: string);
+//!          export run: func() -> s32;
+//!      }",
+//! )?;
+//! let world = document.resolve_world("app")?;
+//!
+//! let mut bindings = HostBindings::new(&world);
+//! bindings.implement("", "log", |args: &[ComponentValue]| {
+//!     println!("{args:?}");
+//!     Ok(Vec::new())
+//! })?;
+//!
+//! let mut linker = ComponentLinker::new();
+//! bindings.install(&mut linker)?;
+//! # Ok::<(), wrt_error::Error>(())
+//! ```
+//!
+//! [`ComponentLinker`]: crate::components::component_linker::ComponentLinker
+
+pub mod bindings;
+pub mod parser;
+pub mod typed;
+pub mod world;
+
+pub use bindings::{
+    value_conforms,
+    HostBindings,
+};
+pub use parser::{
+    parse_wit,
+    WitDocument,
+    WitParser,
+};
+pub use typed::{
+    Borrowed,
+    WitErrorContext,
+    WitFuture,
+    WitResource,
+    WitStream,
+    WitValue,
+};
+pub use world::{
+    WitFunctionType,
+    WitWorldType,
+    WorldMismatch,
+};
@@ -0,0 +1,1049 @@
+//! WIT text parser
+//!
+//! Parses the WebAssembly Interface Type (WIT) text format into a
+//! [`WitDocument`]. The parser covers a single package per document:
+//! interfaces with type definitions, resources and functions, `use`
+//! statements, and worlds with imports, exports, `use` and `include`.
+//! Feature gates (`@since`, `@unstable`, `@deprecated`) are accepted and
+//! ignored.
+
+use std::{
+    boxed::Box,
+    string::{
+        String,
+        ToString,
+    },
+    vec::Vec,
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+
+/// Maximum nesting depth of types in a WIT document
+const MAX_TYPE_NESTING: usize = 32;
+
+/// A parsed WIT document
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WitDocument {
+    /// Package declared by the document, if any
+    pub package:    Option<WitPackageName>,
+    /// Interfaces defined by the document, in source order
+    pub interfaces: Vec<WitInterface>,
+    /// Worlds defined by the document, in source order
+    pub worlds:     Vec<WitWorld>,
+}
+
+/// Name of a WIT package, such as `wasi:cli@0.2.0`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WitPackageName {
+    /// Package namespace
+    pub namespace: String,
+    /// Package name
+    pub name:      String,
+    /// Semantic version, if any
+    pub version:   Option<String>,
+}
+
+impl WitPackageName {
+    /// Fully qualified name of an interface of this package, as used for
+    /// component imports and exports (`namespace:name/interface@version`)
+    pub fn interface_id(&self, interface: &str) -> String {
+        match &self.version {
+            Some(version) => {
+                format!("{}:{}/{}@{}", self.namespace, self.name, interface, version)
+            },
+            None => format!("{}:{}/{}", self.namespace, self.name, interface),
+        }
+    }
+}
+
+/// A WIT interface
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WitInterface {
+    /// Interface name (empty for inline interfaces of a world)
+    pub name:      String,
+    /// Types brought into scope from other interfaces
+    pub uses:      Vec<WitUse>,
+    /// Type definitions
+    pub types:     Vec<WitTypeDef>,
+    /// Freestanding functions
+    pub functions: Vec<WitFunction>,
+}
+
+/// A `use` statement bringing types of another interface into scope
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WitUse {
+    /// Interface the types come from
+    pub interface: WitInterfacePath,
+    /// Imported type names with their local alias, if renamed
+    pub names:     Vec<(String, Option<String>)>,
+}
+
+/// Reference to an interface or world
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WitInterfacePath {
+    /// Item of the current package
+    Local(String),
+    /// Item of another package, such as `wasi:io/streams@0.2.0`
+    Package {
+        /// Package the item belongs to
+        package: WitPackageName,
+        /// Item name
+        name:    String,
+    },
+}
+
+/// A named type definition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WitTypeDef {
+    /// Type name
+    pub name: String,
+    /// Definition
+    pub kind: WitTypeDefKind,
+}
+
+/// Kinds of WIT type definitions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WitTypeDefKind {
+    /// `type name = ty;`
+    Alias(WitType),
+    /// `record name { field: ty, ... }`
+    Record(Vec<(String, WitType)>),
+    /// `variant name { case, case(ty), ... }`
+    Variant(Vec<(String, Option<WitType>)>),
+    /// `enum name { case, ... }`
+    Enum(Vec<String>),
+    /// `flags name { flag, ... }`
+    Flags(Vec<String>),
+    /// `resource name { ... }` with its constructor, methods and static
+    /// functions
+    Resource(Vec<WitResourceFunction>),
+}
+
+/// A function declared inside a resource
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WitResourceFunction {
+    /// How the function relates to the resource
+    pub kind:     WitResourceFunctionKind,
+    /// Declared signature, without the implicit `self` parameter
+    pub function: WitFunction,
+}
+
+/// Kinds of resource functions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WitResourceFunctionKind {
+    /// `constructor(params)`
+    Constructor,
+    /// `name: func(...)`, taking a borrowed `self`
+    Method,
+    /// `name: static func(...)`
+    Static,
+}
+
+/// A WIT function signature
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WitFunction {
+    /// Function name
+    pub name:     String,
+    /// Named parameters
+    pub params:   Vec<(String, WitType)>,
+    /// Result types
+    pub results:  Vec<WitType>,
+    /// Whether the function is declared `async`
+    pub is_async: bool,
+}
+
+/// A WIT type expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WitType {
+    /// `bool`
+    Bool,
+    /// `s8`
+    S8,
+    /// `u8`
+    U8,
+    /// `s16`
+    S16,
+    /// `u16`
+    U16,
+    /// `s32`
+    S32,
+    /// `u32`
+    U32,
+    /// `s64`
+    S64,
+    /// `u64`
+    U64,
+    /// `f32`
+    F32,
+    /// `f64`
+    F64,
+    /// `char`
+    Char,
+    /// `string`
+    String,
+    /// `list<ty>`
+    List(Box<WitType>),
+    /// `option<ty>`
+    Option(Box<WitType>),
+    /// `result`, `result<ok>`, `result<_, err>` or `result<ok, err>`
+    Result(Option<Box<WitType>>, Option<Box<WitType>>),
+    /// `tuple<ty, ...>`
+    Tuple(Vec<WitType>),
+    /// `borrow<resource>`
+    Borrow(String),
+    /// `stream` or `stream<ty>`
+    Stream(Option<Box<WitType>>),
+    /// `future` or `future<ty>`
+    Future(Option<Box<WitType>>),
+    /// `error-context`
+    ErrorContext,
+    /// Reference to a named type; an owned handle for resources
+    Named(String),
+}
+
+/// A WIT world
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WitWorld {
+    /// World name
+    pub name:  String,
+    /// World items in source order
+    pub items: Vec<WitWorldItem>,
+}
+
+/// Items of a world
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WitWorldItem {
+    /// `import ...;`
+    Import(WitExtern),
+    /// `export ...;`
+    Export(WitExtern),
+    /// `include world;`
+    Include(WitInterfacePath),
+    /// `use interface.{...};`
+    Use(WitUse),
+    /// Type definition local to the world
+    Type(WitTypeDef),
+}
+
+/// An item imported or exported by a world
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WitExtern {
+    /// `name: func(...)`
+    Function(WitFunction),
+    /// `name: interface { ... }`
+    Interface(WitInterface),
+    /// A named interface, such as `logging` or `wasi:cli/stdout@0.2.0`
+    Path(WitInterfacePath),
+}
+
+/// Parse a WIT document
+pub fn parse_wit(source: &str) -> Result<WitDocument> {
+    WitParser::new(source).parse()
+}
+
+/// Recursive descent parser for WIT text
+///
+/// On failure [`WitParser::offset`] reports the byte offset at which parsing
+/// stopped.
+#[derive(Debug)]
+pub struct WitParser<'a> {
+    source: &'a str,
+    pos:    usize,
+}
+
+impl<'a> WitParser<'a> {
+    /// Create a parser over WIT source text
+    pub fn new(source: &'a str) -> Self {
+        Self { source, pos: 0 }
+    }
+
+    /// Current byte offset into the source
+    pub fn offset(&self) -> usize {
+        self.pos
+    }
+
+    /// Line and column (both 1-based) of the current offset
+    pub fn line_column(&self) -> (usize, usize) {
+        let consumed = &self.source[..self.pos];
+        let line = consumed.matches('\n').count() + 1;
+        let column = consumed.rfind('\n').map_or(self.pos, |nl| self.pos - nl - 1) + 1;
+        (line, column)
+    }
+
+    /// Parse the whole source as a document
+    pub fn parse(&mut self) -> Result<WitDocument> {
+        let mut document = WitDocument::default();
+
+        self.skip_trivia()?;
+        if self.peek_keyword("package") {
+            self.keyword("package")?;
+            document.package = Some(self.package_name()?);
+            self.expect(';')?;
+        }
+
+        loop {
+            self.skip_trivia()?;
+            if self.at_end() {
+                break;
+            }
+            self.skip_gates()?;
+
+            if self.peek_keyword("interface") {
+                self.keyword("interface")?;
+                let name = self.identifier()?;
+                document.interfaces.push(self.interface_body(name)?);
+            } else if self.peek_keyword("world") {
+                self.keyword("world")?;
+                let name = self.identifier()?;
+                document.worlds.push(self.world_body(name)?);
+            } else {
+                return Err(Error::parse_error("Expected 'interface' or 'world'"));
+            }
+        }
+
+        Ok(document)
+    }
+
+    fn package_name(&mut self) -> Result<WitPackageName> {
+        let namespace = self.identifier()?;
+        self.expect(':')?;
+        let name = self.identifier()?;
+        let version = self.optional_version()?;
+        Ok(WitPackageName {
+            namespace,
+            name,
+            version,
+        })
+    }
+
+    fn optional_version(&mut self) -> Result<Option<String>> {
+        if !self.eat('@')? {
+            return Ok(None);
+        }
+
+        let start = self.pos;
+        while let Some(c) = self.peek_char() {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+') {
+                self.pos += c.len_utf8();
+            } else {
+                break;
+            }
+        }
+        if self.pos == start {
+            return Err(Error::parse_error("Expected a version after '@'"));
+        }
+        Ok(Some(self.source[start..self.pos].to_string()))
+    }
+
+    /// Parse `name`, `name@version` or `namespace:package/name@version`
+    fn interface_path(&mut self) -> Result<WitInterfacePath> {
+        let first = self.identifier()?;
+        if !self.eat(':')? {
+            return Ok(WitInterfacePath::Local(first));
+        }
+
+        let package = self.identifier()?;
+        self.expect('/')?;
+        let name = self.identifier()?;
+        let version = self.optional_version()?;
+        Ok(WitInterfacePath::Package {
+            package: WitPackageName {
+                namespace: first,
+                name: package,
+                version,
+            },
+            name,
+        })
+    }
+
+    fn interface_body(&mut self, name: String) -> Result<WitInterface> {
+        let mut interface = WitInterface {
+            name,
+            ..WitInterface::default()
+        };
+
+        self.expect('{')?;
+        loop {
+            if self.eat('}')? {
+                return Ok(interface);
+            }
+            self.skip_gates()?;
+
+            if self.peek_keyword("use") {
+                interface.uses.push(self.use_statement()?);
+            } else if let Some(def) = self.type_def()? {
+                interface.types.push(def);
+            } else {
+                interface.functions.push(self.named_function()?);
+            }
+        }
+    }
+
+    fn world_body(&mut self, name: String) -> Result<WitWorld> {
+        let mut world = WitWorld {
+            name,
+            items: Vec::new(),
+        };
+
+        self.expect('{')?;
+        loop {
+            if self.eat('}')? {
+                return Ok(world);
+            }
+            self.skip_gates()?;
+
+            let item = if self.peek_keyword("import") {
+                self.keyword("import")?;
+                WitWorldItem::Import(self.world_extern()?)
+            } else if self.peek_keyword("export") {
+                self.keyword("export")?;
+                WitWorldItem::Export(self.world_extern()?)
+            } else if self.peek_keyword("include") {
+                self.keyword("include")?;
+                let path = self.interface_path()?;
+                self.expect(';')?;
+                WitWorldItem::Include(path)
+            } else if self.peek_keyword("use") {
+                WitWorldItem::Use(self.use_statement()?)
+            } else if let Some(def) = self.type_def()? {
+                WitWorldItem::Type(def)
+            } else {
+                return Err(Error::parse_error("Expected a world item"));
+            };
+            world.items.push(item);
+        }
+    }
+
+    fn world_extern(&mut self) -> Result<WitExtern> {
+        let start = self.pos;
+        let name = self.identifier()?;
+
+        // `name: func(...)` and `name: interface { ... }`, as opposed to a
+        // package path such as `wasi:io/streams`
+        if self.eat(':')? {
+            if self.peek_keyword("interface") {
+                self.keyword("interface")?;
+                return Ok(WitExtern::Interface(self.interface_body(name)?));
+            }
+            if self.peek_keyword("func") || self.peek_keyword("async") {
+                self.pos = start;
+                return Ok(WitExtern::Function(self.named_function()?));
+            }
+        }
+
+        self.pos = start;
+        let path = self.interface_path()?;
+        self.expect(';')?;
+        Ok(WitExtern::Path(path))
+    }
+
+    fn use_statement(&mut self) -> Result<WitUse> {
+        self.keyword("use")?;
+        let interface = self.interface_path()?;
+        self.expect('.')?;
+        self.expect('{')?;
+
+        let mut names = Vec::new();
+        loop {
+            let name = self.identifier()?;
+            let alias = if self.peek_keyword("as") {
+                self.keyword("as")?;
+                Some(self.identifier()?)
+            } else {
+                None
+            };
+            names.push((name, alias));
+
+            if !self.eat(',')? || self.peek_is('}')? {
+                break;
+            }
+        }
+        self.expect('}')?;
+        self.expect(';')?;
+
+        Ok(WitUse { interface, names })
+    }
+
+    /// Parse a type definition if one starts at the current position
+    fn type_def(&mut self) -> Result<Option<WitTypeDef>> {
+        let kind = if self.peek_keyword("type") {
+            self.keyword("type")?;
+            let name = self.identifier()?;
+            self.expect('=')?;
+            let ty = self.ty(0)?;
+            self.expect(';')?;
+            return Ok(Some(WitTypeDef {
+                name,
+                kind: WitTypeDefKind::Alias(ty),
+            }));
+        } else if self.peek_keyword("record") {
+            "record"
+        } else if self.peek_keyword("variant") {
+            "variant"
+        } else if self.peek_keyword("enum") {
+            "enum"
+        } else if self.peek_keyword("flags") {
+            "flags"
+        } else if self.peek_keyword("resource") {
+            "resource"
+        } else {
+            return Ok(None);
+        };
+
+        self.keyword(kind)?;
+        let name = self.identifier()?;
+        let kind = match kind {
+            "record" => WitTypeDefKind::Record(self.braced_list(|p| {
+                let field = p.identifier()?;
+                p.expect(':')?;
+                Ok((field, p.ty(0)?))
+            })?),
+            "variant" => WitTypeDefKind::Variant(self.braced_list(|p| {
+                let case = p.identifier()?;
+                let payload = if p.eat('(')? {
+                    let ty = p.ty(0)?;
+                    p.expect(')')?;
+                    Some(ty)
+                } else {
+                    None
+                };
+                Ok((case, payload))
+            })?),
+            "enum" => WitTypeDefKind::Enum(self.braced_list(Self::identifier)?),
+            "flags" => WitTypeDefKind::Flags(self.braced_list(Self::identifier)?),
+            _ => WitTypeDefKind::Resource(self.resource_body()?),
+        };
+
+        Ok(Some(WitTypeDef { name, kind }))
+    }
+
+    /// Parse `{ item, item, ... }` allowing a trailing comma
+    fn braced_list<T>(&mut self, mut item: impl FnMut(&mut Self) -> Result<T>) -> Result<Vec<T>> {
+        self.expect('{')?;
+        let mut items = Vec::new();
+        while !self.eat('}')? {
+            self.skip_gates()?;
+            items.push(item(self)?);
+            if !self.eat(',')? {
+                self.expect('}')?;
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    fn resource_body(&mut self) -> Result<Vec<WitResourceFunction>> {
+        let mut functions = Vec::new();
+        if self.eat(';')? {
+            return Ok(functions);
+        }
+
+        self.expect('{')?;
+        while !self.eat('}')? {
+            self.skip_gates()?;
+
+            if self.peek_keyword("constructor") {
+                self.keyword("constructor")?;
+                let params = self.params()?;
+                self.expect(';')?;
+                functions.push(WitResourceFunction {
+                    kind:     WitResourceFunctionKind::Constructor,
+                    function: WitFunction {
+                        name: "constructor".to_string(),
+                        params,
+                        results: Vec::new(),
+                        is_async: false,
+                    },
+                });
+                continue;
+            }
+
+            let name = self.identifier()?;
+            self.expect(':')?;
+            let kind = if self.peek_keyword("static") {
+                self.keyword("static")?;
+                WitResourceFunctionKind::Static
+            } else {
+                WitResourceFunctionKind::Method
+            };
+            let function = self.function_signature(name)?;
+            functions.push(WitResourceFunction { kind, function });
+        }
+
+        Ok(functions)
+    }
+
+    /// Parse `name: func(...) -> ...;`
+    fn named_function(&mut self) -> Result<WitFunction> {
+        let name = self.identifier()?;
+        self.expect(':')?;
+        self.function_signature(name)
+    }
+
+    /// Parse `func(...) -> ...;` or `async func(...) -> ...;` after the
+    /// function name
+    fn function_signature(&mut self, name: String) -> Result<WitFunction> {
+        let is_async = self.peek_keyword("async");
+        if is_async {
+            self.keyword("async")?;
+        }
+        self.keyword("func")?;
+        let params = self.params()?;
+
+        let results = if self.eat_str("->")? {
+            if self.peek_is('(')? {
+                // Named results: `-> (a: ty, b: ty)`
+                self.params()?.into_iter().map(|(_, ty)| ty).collect()
+            } else {
+                vec![self.ty(0)?]
+            }
+        } else {
+            Vec::new()
+        };
+        self.expect(';')?;
+
+        Ok(WitFunction {
+            name,
+            params,
+            results,
+            is_async,
+        })
+    }
+
+    fn params(&mut self) -> Result<Vec<(String, WitType)>> {
+        self.expect('(')?;
+        let mut params = Vec::new();
+        while !self.eat(')')? {
+            let name = self.identifier()?;
+            self.expect(':')?;
+            params.push((name, self.ty(0)?));
+            if !self.eat(',')? {
+                self.expect(')')?;
+                break;
+            }
+        }
+        Ok(params)
+    }
+
+    fn ty(&mut self, depth: usize) -> Result<WitType> {
+        if depth > MAX_TYPE_NESTING {
+            return Err(Error::parse_error("WIT type nesting too deep"));
+        }
+
+        let escaped = self.peek_is('%')?;
+        let name = self.identifier()?;
+        if escaped {
+            return Ok(WitType::Named(name));
+        }
+
+        Ok(match name.as_str() {
+            "bool" => WitType::Bool,
+            "s8" => WitType::S8,
+            "u8" => WitType::U8,
+            "s16" => WitType::S16,
+            "u16" => WitType::U16,
+            "s32" => WitType::S32,
+            "u32" => WitType::U32,
+            "s64" => WitType::S64,
+            "u64" => WitType::U64,
+            "f32" | "float32" => WitType::F32,
+            "f64" | "float64" => WitType::F64,
+            "char" => WitType::Char,
+            "string" => WitType::String,
+            "list" => {
+                self.expect('<')?;
+                let element = self.ty(depth + 1)?;
+                // Fixed-length lists are lifted like lists
+                if self.eat(',')? {
+                    self.integer()?;
+                }
+                self.expect('>')?;
+                WitType::List(Box::new(element))
+            },
+            "option" => {
+                self.expect('<')?;
+                let inner = self.ty(depth + 1)?;
+                self.expect('>')?;
+                WitType::Option(Box::new(inner))
+            },
+            "result" => {
+                if !self.eat('<')? {
+                    return Ok(WitType::Result(None, None));
+                }
+                let ok = if self.eat('_')? { None } else { Some(Box::new(self.ty(depth + 1)?)) };
+                let err = if self.eat(',')? { Some(Box::new(self.ty(depth + 1)?)) } else { None };
+                self.expect('>')?;
+                WitType::Result(ok, err)
+            },
+            "tuple" => {
+                self.expect('<')?;
+                let mut types = Vec::new();
+                while !self.eat('>')? {
+                    types.push(self.ty(depth + 1)?);
+                    if !self.eat(',')? {
+                        self.expect('>')?;
+                        break;
+                    }
+                }
+                WitType::Tuple(types)
+            },
+            "borrow" => {
+                self.expect('<')?;
+                let resource = self.identifier()?;
+                self.expect('>')?;
+                WitType::Borrow(resource)
+            },
+            "stream" | "future" => {
+                let payload = if self.eat('<')? {
+                    let payload = self.ty(depth + 1)?;
+                    self.expect('>')?;
+                    Some(Box::new(payload))
+                } else {
+                    None
+                };
+                if name == "stream" {
+                    WitType::Stream(payload)
+                } else {
+                    WitType::Future(payload)
+                }
+            },
+            "error-context" => WitType::ErrorContext,
+            _ => WitType::Named(name),
+        })
+    }
+
+    fn integer(&mut self) -> Result<u32> {
+        self.skip_trivia()?;
+        let start = self.pos;
+        while self.peek_char().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        self.source[start..self.pos]
+            .parse()
+            .map_err(|_| Error::parse_error("Expected an integer"))
+    }
+
+    /// Parse an identifier, stripping the `%` escape of keywords
+    fn identifier(&mut self) -> Result<String> {
+        self.skip_trivia()?;
+        if self.peek_char() == Some('%') {
+            self.pos += 1;
+        }
+
+        let start = self.pos;
+        while let Some(c) = self.peek_char() {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+
+        let ident = &self.source[start..self.pos];
+        if ident.is_empty() || ident.starts_with('-') || ident.ends_with('-') {
+            self.pos = start;
+            return Err(Error::parse_error("Expected an identifier"));
+        }
+        Ok(ident.to_string())
+    }
+
+    /// Skip `@since(...)`, `@unstable(...)` and `@deprecated(...)` gates
+    fn skip_gates(&mut self) -> Result<()> {
+        while self.eat('@')? {
+            self.identifier()?;
+            self.expect('(')?;
+            while let Some(c) = self.peek_char() {
+                self.pos += c.len_utf8();
+                if c == ')' {
+                    break;
+                }
+            }
+            self.skip_trivia()?;
+        }
+        Ok(())
+    }
+
+    fn keyword(&mut self, keyword: &'static str) -> Result<()> {
+        if !self.peek_keyword(keyword) {
+            return Err(Error::parse_error("Expected a WIT keyword"));
+        }
+        self.pos += keyword.len();
+        Ok(())
+    }
+
+    fn peek_keyword(&mut self, keyword: &str) -> bool {
+        if self.skip_trivia().is_err() {
+            return false;
+        }
+        let rest = &self.source[self.pos..];
+        rest.starts_with(keyword)
+            && !rest[keyword.len()..]
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_alphanumeric() || c == '-')
+    }
+
+    fn peek_is(&mut self, c: char) -> Result<bool> {
+        self.skip_trivia()?;
+        Ok(self.peek_char() == Some(c))
+    }
+
+    fn eat(&mut self, c: char) -> Result<bool> {
+        if self.peek_is(c)? {
+            self.pos += c.len_utf8();
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn eat_str(&mut self, s: &str) -> Result<bool> {
+        self.skip_trivia()?;
+        if self.source[self.pos..].starts_with(s) {
+            self.pos += s.len();
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<()> {
+        if self.eat(c)? {
+            Ok(())
+        } else {
+            Err(Error::parse_error("Unexpected token in WIT source"))
+        }
+    }
+
+    fn peek_char(&self) -> Option<char> {
+        self.source[self.pos..].chars().next()
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos >= self.source.len()
+    }
+
+    /// Skip whitespace, line comments and (nested) block comments
+    fn skip_trivia(&mut self) -> Result<()> {
+        loop {
+            let rest = &self.source[self.pos..];
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+
+            if trimmed.starts_with("//") {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if trimmed.starts_with("/*") {
+                let mut depth = 0usize;
+                let mut chars = trimmed.char_indices().peekable();
+                let mut end = None;
+                while let Some((i, c)) = chars.next() {
+                    match (c, chars.peek().map(|&(_, next)| next)) {
+                        ('/', Some('*')) => {
+                            depth += 1;
+                            chars.next();
+                        },
+                        ('*', Some('/')) => {
+                            depth -= 1;
+                            chars.next();
+                            if depth == 0 {
+                                end = Some(i + 2);
+                                break;
+                            }
+                        },
+                        _ => {},
+                    }
+                }
+                match end {
+                    Some(end) => self.pos += end,
+                    None => return Err(Error::parse_error("Unterminated block comment")),
+                }
+            } else {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOGGER: &str = r#"
+        package example:logger@1.0.0;
+
+        /// Structured logging
+        interface logging {
+            enum level { debug, info, warn, error }
+
+            record entry {
+                level: level,
+                message: string,
+                fields: list<tuple<string, string>>,
+            }
+
+            log: func(entry: entry);
+            flush: func() -> result<u32, string>;
+        }
+
+        world app {
+            import logging;
+            import clock: interface {
+                now: func() -> u64;
+            }
+            export run: func(args: list<string>) -> s32;
+        }
+    "#;
+
+    #[test]
+    fn test_parse_package_interface_and_world() {
+        let doc = parse_wit(LOGGER).unwrap();
+
+        let package = doc.package.as_ref().unwrap();
+        assert_eq!(
+            package.interface_id("logging"),
+            "example:logger/logging@1.0.0"
+        );
+
+        let logging = &doc.interfaces[0];
+        assert_eq!(logging.name, "logging");
+        assert_eq!(logging.types.len(), 2);
+        assert_eq!(
+            logging.types[0].kind,
+            WitTypeDefKind::Enum(vec![
+                "debug".to_string(),
+                "info".to_string(),
+                "warn".to_string(),
+                "error".to_string(),
+            ])
+        );
+        assert_eq!(logging.functions[1].name, "flush");
+        assert_eq!(
+            logging.functions[1].results,
+            vec![WitType::Result(
+                Some(Box::new(WitType::U32)),
+                Some(Box::new(WitType::String))
+            )]
+        );
+
+        let world = &doc.worlds[0];
+        assert_eq!(world.name, "app");
+        assert_eq!(
+            world.items[0],
+            WitWorldItem::Import(WitExtern::Path(WitInterfacePath::Local(
+                "logging".to_string()
+            )))
+        );
+        let WitWorldItem::Import(WitExtern::Interface(clock)) = &world.items[1] else {
+            panic!("expected an inline interface import");
+        };
+        assert_eq!(clock.name, "clock");
+        let WitWorldItem::Export(WitExtern::Function(run)) = &world.items[2] else {
+            panic!("expected a function export");
+        };
+        assert_eq!(run.name, "run");
+    }
+
+    #[test]
+    fn test_parse_resources_uses_and_gates() {
+        let doc = parse_wit(
+            r#"
+            interface types {
+                resource file {
+                    constructor(path: string);
+                    read: func(len: u32) -> list<u8>;
+                    open: static func(path: string) -> result<file>;
+                }
+                flags mode { read, write }
+            }
+            interface fs {
+                use types.{file, mode as access};
+                @since(version = 0.2.0)
+                copy: func(from: borrow<file>, to: borrow<file>) -> result<_, %string>;
+            }
+            /* block /* nested */ comment */
+            world host {
+                import wasi:io/streams@0.2.0;
+                include base;
+            }
+            "#,
+        )
+        .unwrap();
+
+        let WitTypeDefKind::Resource(functions) = &doc.interfaces[0].types[0].kind else {
+            panic!("expected a resource");
+        };
+        assert_eq!(functions.len(), 3);
+        assert_eq!(functions[0].kind, WitResourceFunctionKind::Constructor);
+        assert_eq!(functions[1].kind, WitResourceFunctionKind::Method);
+        assert_eq!(functions[2].kind, WitResourceFunctionKind::Static);
+
+        let fs = &doc.interfaces[1];
+        assert_eq!(
+            fs.uses[0].names,
+            vec![
+                ("file".to_string(), None),
+                ("mode".to_string(), Some("access".to_string()))
+            ]
+        );
+        assert_eq!(
+            fs.functions[0].params[0].1,
+            WitType::Borrow("file".to_string())
+        );
+        // An escaped keyword is a type reference, not the builtin
+        assert_eq!(
+            fs.functions[0].results[0],
+            WitType::Result(None, Some(Box::new(WitType::Named("string".to_string()))))
+        );
+
+        let WitWorldItem::Import(WitExtern::Path(WitInterfacePath::Package { package, name })) =
+            &doc.worlds[0].items[0]
+        else {
+            panic!("expected a package import");
+        };
+        assert_eq!(package.interface_id(name), "wasi:io/streams@0.2.0");
+    }
+
+    #[test]
+    fn test_parse_async_functions() {
+        let doc = parse_wit(
+            r#"
+            interface pipe {
+                copy: async func(input: stream<u8>) -> future<result<_, error-context>>;
+                tick: func() -> stream;
+            }
+            "#,
+        )
+        .unwrap();
+
+        let copy = &doc.interfaces[0].functions[0];
+        assert!(copy.is_async);
+        assert_eq!(
+            copy.params[0].1,
+            WitType::Stream(Some(Box::new(WitType::U8)))
+        );
+        assert_eq!(
+            copy.results[0],
+            WitType::Future(Some(Box::new(WitType::Result(
+                None,
+                Some(Box::new(WitType::ErrorContext))
+            ))))
+        );
+        let tick = &doc.interfaces[0].functions[1];
+        assert!(!tick.is_async);
+        assert_eq!(tick.results[0], WitType::Stream(None));
+    }
+
+    #[test]
+    fn test_parse_error_offset() {
+        let source = "interface broken {\n    f: func(a: u32 -> u32;\n}";
+        let mut parser = WitParser::new(source);
+        assert!(parser.parse().is_err());
+        assert_eq!(parser.line_column().0, 2);
+
+        assert!(parse_wit("world w { import }").is_err());
+        assert!(parse_wit("interface i { /* unterminated }").is_err());
+    }
+}
@@ -0,0 +1,422 @@
+//! Conversions between Rust types and component values
+//!
+//! Bindings generated by `wrt-bindgen` map every WIT type to a Rust type
+//! implementing [`WitValue`], so host implementations and guest calls work
+//! with plain Rust values instead of [`ComponentValue`]s. Implementations are
+//! provided here for the primitive types, `String`, `Vec<T>`, `Option<T>`,
+//! `Result<T, E>` and tuples, and typed handles stand in for streams, futures
+//! and error contexts; records, variants, enums, flags and resources get
+//! theirs from the generated code.
+
+use std::{
+    boxed::Box,
+    marker::PhantomData,
+    string::String,
+    vec::Vec,
+};
+
+pub use wrt_error::{
+    Error,
+    Result,
+};
+
+use crate::canonical_abi::{
+    ComponentType,
+    ComponentValue,
+};
+
+/// A Rust type with a component model representation
+pub trait WitValue: Sized {
+    /// Whether this is the unit type, which stands for an absent payload
+    const IS_UNIT: bool = false;
+
+    /// Component type of the values
+    fn component_type() -> ComponentType;
+
+    /// Convert into a component value
+    fn into_value(self) -> ComponentValue;
+
+    /// Convert from a component value, failing on a type mismatch
+    fn from_value(value: ComponentValue) -> Result<Self>;
+}
+
+/// A resource type generated from a WIT `resource`
+pub trait WitResource {
+    /// Create a handle to an owned resource
+    fn from_handle(handle: u32) -> Self;
+
+    /// Handle of the resource
+    fn handle(&self) -> u32;
+}
+
+/// A borrowed handle to a resource of type `T`
+#[derive(Debug)]
+pub struct Borrowed<T> {
+    handle:  u32,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Borrowed<T> {
+    /// Borrow the resource with the given handle
+    pub fn new(handle: u32) -> Self {
+        Self {
+            handle,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Handle of the borrowed resource
+    pub fn handle(&self) -> u32 {
+        self.handle
+    }
+}
+
+impl<T> Clone for Borrowed<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Borrowed<T> {}
+
+impl<T: WitResource> Borrowed<T> {
+    /// Borrow an owned resource
+    pub fn of(resource: &T) -> Self {
+        Self::new(resource.handle())
+    }
+}
+
+impl<T: WitResource> WitValue for Borrowed<T> {
+    fn component_type() -> ComponentType {
+        ComponentType::Borrow(0)
+    }
+
+    fn into_value(self) -> ComponentValue {
+        ComponentValue::Borrow(self.handle)
+    }
+
+    fn from_value(value: ComponentValue) -> Result<Self> {
+        match value {
+            ComponentValue::Borrow(handle) => Ok(Self::new(handle)),
+            _ => Err(mismatch()),
+        }
+    }
+}
+
+macro_rules! async_handle {
+    ($(#[$doc:meta])* $name:ident, $variant:ident) => {
+        $(#[$doc])*
+        #[derive(Debug)]
+        pub struct $name<T> {
+            handle:  u32,
+            _marker: PhantomData<fn() -> T>,
+        }
+
+        impl<T> $name<T> {
+            /// Wrap a handle into the async handle table
+            pub fn new(handle: u32) -> Self {
+                Self {
+                    handle,
+                    _marker: PhantomData,
+                }
+            }
+
+            /// Handle into the async handle table
+            pub fn handle(&self) -> u32 {
+                self.handle
+            }
+        }
+
+        impl<T: WitValue> WitValue for $name<T> {
+            fn component_type() -> ComponentType {
+                ComponentType::$variant(payload_type::<T>())
+            }
+
+            fn into_value(self) -> ComponentValue {
+                ComponentValue::$variant(self.handle)
+            }
+
+            fn from_value(value: ComponentValue) -> Result<Self> {
+                match value {
+                    ComponentValue::$variant(handle) => Ok(Self::new(handle)),
+                    _ => Err(mismatch()),
+                }
+            }
+        }
+    };
+}
+
+async_handle!(
+    /// Handle to an end of a `stream<T>`, or of a `stream` for `T = ()`
+    WitStream,
+    Stream
+);
+async_handle!(
+    /// Handle to an end of a `future<T>`, or of a `future` for `T = ()`
+    WitFuture,
+    Future
+);
+
+/// Handle to an `error-context`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WitErrorContext(pub u32);
+
+impl WitValue for WitErrorContext {
+    fn component_type() -> ComponentType {
+        ComponentType::ErrorContext
+    }
+
+    fn into_value(self) -> ComponentValue {
+        ComponentValue::ErrorContext(self.0)
+    }
+
+    fn from_value(value: ComponentValue) -> Result<Self> {
+        match value {
+            ComponentValue::ErrorContext(handle) => Ok(Self(handle)),
+            _ => Err(mismatch()),
+        }
+    }
+}
+
+/// Error for a component value of an unexpected type
+pub fn mismatch() -> Error {
+    Error::runtime_type_mismatch("Component value does not match the WIT type")
+}
+
+/// Convert the argument at `index` of a call
+pub fn lift_argument<T: WitValue>(args: &[ComponentValue], index: usize) -> Result<T> {
+    let value = args
+        .get(index)
+        .cloned()
+        .ok_or_else(|| Error::runtime_type_mismatch("Missing argument for WIT parameter"))?;
+    T::from_value(value)
+}
+
+/// Convert the single result of a call
+pub fn lift_result<T: WitValue>(results: Vec<ComponentValue>) -> Result<T> {
+    let mut results = results.into_iter();
+    match (results.next(), results.next()) {
+        (Some(value), None) => T::from_value(value),
+        _ => Err(Error::runtime_type_mismatch("Expected exactly one result")),
+    }
+}
+
+/// Take a named field out of a record value
+pub fn take_field<T: WitValue>(
+    fields: &mut Vec<(String, ComponentValue)>,
+    name: &str,
+) -> Result<T> {
+    let index = fields
+        .iter()
+        .position(|(field, _)| field == name)
+        .ok_or_else(|| Error::runtime_type_mismatch("Record value lacks a WIT field"))?;
+    T::from_value(fields.swap_remove(index).1)
+}
+
+/// Component type of an optional payload
+pub fn payload_type<T: WitValue>() -> Option<Box<ComponentType>> {
+    (!T::IS_UNIT).then(|| Box::new(T::component_type()))
+}
+
+/// Convert an optional payload into a component value
+pub fn lower_payload<T: WitValue>(value: T) -> Option<Box<ComponentValue>> {
+    (!T::IS_UNIT).then(|| Box::new(value.into_value()))
+}
+
+/// Convert an optional payload from a component value
+pub fn lift_payload<T: WitValue>(value: Option<Box<ComponentValue>>) -> Result<T> {
+    match value {
+        Some(value) if !T::IS_UNIT => T::from_value(*value),
+        None if T::IS_UNIT => T::from_value(ComponentValue::Tuple(Vec::new())),
+        _ => Err(mismatch()),
+    }
+}
+
+macro_rules! primitive {
+    ($($ty:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl WitValue for $ty {
+                fn component_type() -> ComponentType {
+                    ComponentType::$variant
+                }
+
+                fn into_value(self) -> ComponentValue {
+                    ComponentValue::$variant(self)
+                }
+
+                fn from_value(value: ComponentValue) -> Result<Self> {
+                    match value {
+                        ComponentValue::$variant(value) => Ok(value),
+                        _ => Err(mismatch()),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+primitive! {
+    bool => Bool,
+    i8 => S8,
+    u8 => U8,
+    i16 => S16,
+    u16 => U16,
+    i32 => S32,
+    u32 => U32,
+    i64 => S64,
+    u64 => U64,
+    f32 => F32,
+    f64 => F64,
+    char => Char,
+    String => String,
+}
+
+impl<T: WitValue> WitValue for Vec<T> {
+    fn component_type() -> ComponentType {
+        ComponentType::List(Box::new(T::component_type()))
+    }
+
+    fn into_value(self) -> ComponentValue {
+        ComponentValue::List(self.into_iter().map(WitValue::into_value).collect())
+    }
+
+    fn from_value(value: ComponentValue) -> Result<Self> {
+        match value {
+            ComponentValue::List(values) => values.into_iter().map(T::from_value).collect(),
+            _ => Err(mismatch()),
+        }
+    }
+}
+
+impl<T: WitValue> WitValue for Option<T> {
+    fn component_type() -> ComponentType {
+        ComponentType::Option(Box::new(T::component_type()))
+    }
+
+    fn into_value(self) -> ComponentValue {
+        ComponentValue::Option(self.map(|value| Box::new(value.into_value())))
+    }
+
+    fn from_value(value: ComponentValue) -> Result<Self> {
+        match value {
+            ComponentValue::Option(value) => value.map(|value| T::from_value(*value)).transpose(),
+            _ => Err(mismatch()),
+        }
+    }
+}
+
+impl<T: WitValue, E: WitValue> WitValue for core::result::Result<T, E> {
+    fn component_type() -> ComponentType {
+        ComponentType::Result(payload_type::<T>(), payload_type::<E>())
+    }
+
+    fn into_value(self) -> ComponentValue {
+        ComponentValue::Result(self.map(lower_payload).map_err(lower_payload))
+    }
+
+    fn from_value(value: ComponentValue) -> Result<Self> {
+        match value {
+            ComponentValue::Result(Ok(value)) => Ok(Ok(lift_payload(value)?)),
+            ComponentValue::Result(Err(value)) => Ok(Err(lift_payload(value)?)),
+            _ => Err(mismatch()),
+        }
+    }
+}
+
+macro_rules! tuple {
+    ($(($($name:ident),*)),* $(,)?) => {
+        $(
+            impl<$($name: WitValue),*> WitValue for ($($name,)*) {
+                const IS_UNIT: bool = tuple!(@unit $($name)*);
+
+                fn component_type() -> ComponentType {
+                    ComponentType::Tuple(vec![$($name::component_type()),*])
+                }
+
+                #[allow(non_snake_case)]
+                fn into_value(self) -> ComponentValue {
+                    let ($($name,)*) = self;
+                    ComponentValue::Tuple(vec![$($name.into_value()),*])
+                }
+
+                fn from_value(value: ComponentValue) -> Result<Self> {
+                    match value {
+                        ComponentValue::Tuple(values) => {
+                            let mut values = values.into_iter();
+                            let tuple = (
+                                $($name::from_value(values.next().ok_or_else(mismatch)?)?,)*
+                            );
+                            match values.next() {
+                                Some(_) => Err(mismatch()),
+                                None => Ok(tuple),
+                            }
+                        },
+                        _ => Err(mismatch()),
+                    }
+                }
+            }
+        )*
+    };
+    (@unit) => { true };
+    (@unit $($name:ident)+) => { false };
+}
+
+tuple! {
+    (),
+    (A),
+    (A, B),
+    (A, B, C),
+    (A, B, C, D),
+    (A, B, C, D, E),
+    (A, B, C, D, E, F),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<T: WitValue + Clone + PartialEq + core::fmt::Debug>(value: T) {
+        let lowered = value.clone().into_value();
+        assert!(crate::wit::value_conforms(&T::component_type(), &lowered));
+        assert_eq!(T::from_value(lowered).unwrap(), value);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        round_trip(true);
+        round_trip(-5i64);
+        round_trip('x');
+        round_trip(String::from("text"));
+        round_trip(vec![Some(1u8), None]);
+        round_trip((1u32, String::from("a"), 2.5f64));
+        round_trip(Ok::<(), String>(()));
+        round_trip(Err::<u16, ()>(()));
+        round_trip(WitErrorContext(3));
+
+        let stream = WitStream::<u8>::new(4).into_value();
+        assert!(crate::wit::value_conforms(
+            &WitStream::<u8>::component_type(),
+            &stream
+        ));
+        assert_eq!(WitStream::<u8>::from_value(stream).unwrap().handle(), 4);
+        assert_eq!(
+            WitFuture::<()>::component_type(),
+            ComponentType::Future(None)
+        );
+    }
+
+    #[test]
+    fn test_mismatch() {
+        assert!(u32::from_value(ComponentValue::S32(1)).is_err());
+        assert!(
+            <(u8, u8)>::from_value(ComponentValue::Tuple(vec![ComponentValue::U8(1)])).is_err()
+        );
+        assert!(lift_result::<u8>(Vec::new()).is_err());
+        assert!(
+            <core::result::Result<(), u8>>::from_value(ComponentValue::Result(Ok(Some(Box::new(
+                ComponentValue::U8(1)
+            )))))
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,774 @@
+//! Resolution of WIT worlds and type checking of components against them
+//!
+//! A world is resolved into the functions it imports and exports, with every
+//! type reference replaced by its [`ComponentType`]. Functions are named the
+//! way the component linker flattens them: interface functions belong to an
+//! instance named by the interface (`namespace:package/interface@version`),
+//! and resource functions carry the component model's `[constructor]`,
+//! `[method]` and `[static]` prefixes.
+
+use std::{
+    format,
+    string::{
+        String,
+        ToString,
+    },
+    vec::Vec,
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_format::component::Component as WrtComponent;
+
+use super::parser::{
+    WitDocument,
+    WitExtern,
+    WitFunction,
+    WitInterface,
+    WitInterfacePath,
+    WitResourceFunctionKind,
+    WitType,
+    WitTypeDef,
+    WitTypeDefKind,
+    WitUse,
+    WitWorld,
+    WitWorldItem,
+};
+use crate::{
+    canonical_abi::ComponentType,
+    components::{
+        component_instantiation::{
+            create_function_signature,
+            ExportType,
+            FunctionSignature,
+            ImportType,
+        },
+        component_linker::{
+            convert_exports,
+            convert_imports,
+            import_key,
+            qualified_name,
+        },
+    },
+};
+
+/// Maximum depth of type references and world includes followed
+const MAX_RESOLVE_DEPTH: usize = 32;
+
+/// A function imported or exported by a world, with resolved types
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WitFunctionType {
+    /// Instance the function belongs to; empty for freestanding functions
+    pub instance: String,
+    /// Function name
+    pub name:     String,
+    /// Named parameters
+    pub params:   Vec<(String, ComponentType)>,
+    /// Result types
+    pub results:  Vec<ComponentType>,
+    /// Whether the function uses the async ABI, completing through
+    /// `task.return`
+    pub is_async: bool,
+}
+
+impl WitFunctionType {
+    /// Name under which the linker matches this function
+    pub fn qualified_name(&self) -> String {
+        qualified_name(&self.instance, &self.name)
+    }
+
+    /// Signature of this function as used by the linker
+    pub fn signature(&self) -> FunctionSignature {
+        create_function_signature(
+            self.qualified_name(),
+            self.params.iter().map(|(_, ty)| ty.clone()).collect(),
+            self.results.clone(),
+        )
+    }
+
+    fn is_compatible(&self, signature: &FunctionSignature) -> bool {
+        self.params.len() == signature.params.len()
+            && self
+                .params
+                .iter()
+                .zip(signature.params.iter())
+                .all(|((_, world), component)| is_compatible(world, component))
+            && all_compatible(&self.results, &signature.returns)
+    }
+}
+
+/// A world with its imports and exports flattened to functions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WitWorldType {
+    /// World name
+    pub name:      String,
+    /// Imported functions
+    pub imports:   Vec<WitFunctionType>,
+    /// Exported functions
+    pub exports:   Vec<WitFunctionType>,
+    /// Resources referenced by handle types, as `owner.resource`, indexed by
+    /// the type index of [`ComponentType::Own`] and [`ComponentType::Borrow`]
+    pub resources: Vec<String>,
+}
+
+/// Ways in which a component fails to match a world
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorldMismatch {
+    /// The component imports a function the world does not declare
+    UndeclaredImport(String),
+    /// An import of the component has a different type than in the world
+    ImportType(String),
+    /// The component lacks an export the world requires
+    MissingExport(String),
+    /// An export of the component has a different type than in the world
+    ExportType(String),
+}
+
+impl WitWorldType {
+    /// Find an import by its qualified name
+    pub fn import(&self, qualified_name: &str) -> Option<&WitFunctionType> {
+        self.imports.iter().find(|f| f.qualified_name() == qualified_name)
+    }
+
+    /// Find an export by its qualified name
+    pub fn export(&self, qualified_name: &str) -> Option<&WitFunctionType> {
+        self.exports.iter().find(|f| f.qualified_name() == qualified_name)
+    }
+
+    /// Compare a parsed component against this world
+    ///
+    /// The component may import any subset of the world's imports, but must
+    /// provide every export. Resource handle types match any handle of the
+    /// same kind, since type indices are local to the component.
+    pub fn mismatches(&self, component: &WrtComponent) -> Result<Vec<WorldMismatch>> {
+        let mut mismatches = Vec::new();
+
+        for import in convert_imports(component)? {
+            let name = import_key(&import);
+            let ImportType::Function(signature) = &import.import_type else {
+                continue;
+            };
+            match self.import(&name) {
+                None => mismatches.push(WorldMismatch::UndeclaredImport(name)),
+                Some(expected) if !expected.is_compatible(signature) => {
+                    mismatches.push(WorldMismatch::ImportType(name))
+                },
+                Some(_) => {},
+            }
+        }
+
+        let exports = convert_exports(component)?;
+        for expected in &self.exports {
+            let name = expected.qualified_name();
+            match exports.iter().find(|export| export.name == name) {
+                None => mismatches.push(WorldMismatch::MissingExport(name)),
+                Some(export) => match &export.export_type {
+                    ExportType::Function(signature) if expected.is_compatible(signature) => {},
+                    _ => mismatches.push(WorldMismatch::ExportType(name)),
+                },
+            }
+        }
+
+        Ok(mismatches)
+    }
+
+    /// Check that a parsed component conforms to this world
+    pub fn check_component(&self, component: &WrtComponent) -> Result<()> {
+        match self.mismatches(component)?.first() {
+            None => Ok(()),
+            Some(WorldMismatch::UndeclaredImport(_)) => Err(Error::component_linking_error(
+                "Component imports a function the world does not declare",
+            )),
+            Some(WorldMismatch::MissingExport(_)) => Err(Error::component_linking_error(
+                "Component lacks an export required by the world",
+            )),
+            Some(_) => Err(Error::type_mismatch_error(
+                "Component function type does not match the world",
+            )),
+        }
+    }
+}
+
+impl WitDocument {
+    /// Resolve the world named `name`
+    pub fn resolve_world(&self, name: &str) -> Result<WitWorldType> {
+        let world = self
+            .worlds
+            .iter()
+            .find(|world| world.name == name)
+            .ok_or_else(|| Error::resource_not_found("World not found in WIT document"))?;
+
+        let mut resolver = Resolver {
+            document:  self,
+            resources: Vec::new(),
+        };
+        let mut resolved = WitWorldType {
+            name:      world.name.clone(),
+            imports:   Vec::new(),
+            exports:   Vec::new(),
+            resources: Vec::new(),
+        };
+        resolver.world(world, &mut resolved, 0)?;
+        resolved.resources = resolver.resources;
+
+        Ok(resolved)
+    }
+}
+
+/// Types visible from an interface or world
+#[derive(Clone, Copy)]
+struct Scope<'a> {
+    /// Name resources defined in this scope are qualified with
+    owner: &'a str,
+    types: &'a [WitTypeDef],
+    uses:  &'a [WitUse],
+}
+
+impl<'a> Scope<'a> {
+    fn interface(owner: &'a str, interface: &'a WitInterface) -> Self {
+        Self {
+            owner,
+            types: &interface.types,
+            uses: &interface.uses,
+        }
+    }
+}
+
+struct Resolver<'a> {
+    document:  &'a WitDocument,
+    resources: Vec<String>,
+}
+
+impl<'a> Resolver<'a> {
+    fn world(&mut self, world: &'a WitWorld, out: &mut WitWorldType, depth: usize) -> Result<()> {
+        if depth > MAX_RESOLVE_DEPTH {
+            return Err(Error::validation_error(
+                "WIT world includes nested too deeply",
+            ));
+        }
+
+        let mut types = Vec::new();
+        let mut uses = Vec::new();
+        for item in &world.items {
+            match item {
+                WitWorldItem::Type(def) => types.push(def.clone()),
+                WitWorldItem::Use(item) => uses.push(item.clone()),
+                _ => {},
+            }
+        }
+        let scope = Scope {
+            owner: &world.name,
+            types: &types,
+            uses:  &uses,
+        };
+
+        for item in &world.items {
+            match item {
+                WitWorldItem::Import(item) => {
+                    self.world_extern(&world.name, scope, item, &mut out.imports)?
+                },
+                WitWorldItem::Export(item) => {
+                    self.world_extern(&world.name, scope, item, &mut out.exports)?
+                },
+                WitWorldItem::Include(path) => {
+                    let included = self.lookup(path, &self.document.worlds, |w| &w.name)?;
+                    self.world(included, out, depth + 1)?;
+                },
+                WitWorldItem::Use(_) | WitWorldItem::Type(_) => {},
+            }
+        }
+
+        Ok(())
+    }
+
+    fn world_extern(
+        &mut self,
+        world: &str,
+        scope: Scope<'_>,
+        item: &'a WitExtern,
+        out: &mut Vec<WitFunctionType>,
+    ) -> Result<()> {
+        match item {
+            WitExtern::Function(function) => {
+                let resolved = self.function(scope, "", function.name.clone(), function, None)?;
+                push_unique(out, resolved);
+            },
+            WitExtern::Interface(interface) => {
+                let owner = format!("{world}/{}", interface.name);
+                let scope = Scope::interface(&owner, interface);
+                self.interface(&interface.name, scope, interface, out)?;
+            },
+            WitExtern::Path(path) => {
+                let interface = self.lookup(path, &self.document.interfaces, |i| &i.name)?;
+                let instance = match (path, &self.document.package) {
+                    (WitInterfacePath::Local(name), Some(package)) => package.interface_id(name),
+                    (WitInterfacePath::Local(name), None) => name.clone(),
+                    (WitInterfacePath::Package { package, name }, _) => package.interface_id(name),
+                };
+                let scope = Scope::interface(&interface.name, interface);
+                self.interface(&instance, scope, interface, out)?;
+            },
+        }
+        Ok(())
+    }
+
+    /// Flatten the functions of an interface, including resource functions
+    fn interface(
+        &mut self,
+        instance: &str,
+        scope: Scope<'_>,
+        interface: &WitInterface,
+        out: &mut Vec<WitFunctionType>,
+    ) -> Result<()> {
+        for function in &interface.functions {
+            let resolved = self.function(scope, instance, function.name.clone(), function, None)?;
+            push_unique(out, resolved);
+        }
+
+        for def in &interface.types {
+            let WitTypeDefKind::Resource(functions) = &def.kind else {
+                continue;
+            };
+            let handle = self.resource_index(scope.owner, &def.name);
+
+            for resource_function in functions {
+                let function = &resource_function.function;
+                let mut resolved = match resource_function.kind {
+                    WitResourceFunctionKind::Constructor => {
+                        let name = format!("[constructor]{}", def.name);
+                        let mut resolved = self.function(scope, instance, name, function, None)?;
+                        resolved.results = vec![ComponentType::Own(handle)];
+                        resolved
+                    },
+                    WitResourceFunctionKind::Method => {
+                        let name = format!("[method]{}.{}", def.name, function.name);
+                        let receiver = ComponentType::Borrow(handle);
+                        self.function(scope, instance, name, function, Some(receiver))?
+                    },
+                    WitResourceFunctionKind::Static => {
+                        let name = format!("[static]{}.{}", def.name, function.name);
+                        self.function(scope, instance, name, function, None)?
+                    },
+                };
+                resolved.instance = instance.to_string();
+                push_unique(out, resolved);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn function(
+        &mut self,
+        scope: Scope<'_>,
+        instance: &str,
+        name: String,
+        function: &WitFunction,
+        receiver: Option<ComponentType>,
+    ) -> Result<WitFunctionType> {
+        let mut params = Vec::new();
+        if let Some(receiver) = receiver {
+            params.push(("self".to_string(), receiver));
+        }
+        for (param, ty) in &function.params {
+            params.push((param.clone(), self.ty(scope, ty, 0)?));
+        }
+
+        Ok(WitFunctionType {
+            instance: instance.to_string(),
+            name,
+            params,
+            results: function
+                .results
+                .iter()
+                .map(|ty| self.ty(scope, ty, 0))
+                .collect::<Result<_>>()?,
+            is_async: function.is_async,
+        })
+    }
+
+    fn ty(&mut self, scope: Scope<'_>, ty: &WitType, depth: usize) -> Result<ComponentType> {
+        if depth > MAX_RESOLVE_DEPTH {
+            return Err(Error::validation_error("WIT type nesting too deep"));
+        }
+        let mut boxed = |ty: &WitType| Ok::<_, Error>(Box::new(self.ty(scope, ty, depth + 1)?));
+
+        Ok(match ty {
+            WitType::Bool => ComponentType::Bool,
+            WitType::S8 => ComponentType::S8,
+            WitType::U8 => ComponentType::U8,
+            WitType::S16 => ComponentType::S16,
+            WitType::U16 => ComponentType::U16,
+            WitType::S32 => ComponentType::S32,
+            WitType::U32 => ComponentType::U32,
+            WitType::S64 => ComponentType::S64,
+            WitType::U64 => ComponentType::U64,
+            WitType::F32 => ComponentType::F32,
+            WitType::F64 => ComponentType::F64,
+            WitType::Char => ComponentType::Char,
+            WitType::String => ComponentType::String,
+            WitType::List(element) => ComponentType::List(boxed(element)?),
+            WitType::Option(inner) => ComponentType::Option(boxed(inner)?),
+            WitType::Result(ok, err) => ComponentType::Result(
+                ok.as_deref().map(&mut boxed).transpose()?,
+                err.as_deref().map(&mut boxed).transpose()?,
+            ),
+            WitType::Tuple(types) => ComponentType::Tuple(
+                types.iter().map(|ty| self.ty(scope, ty, depth + 1)).collect::<Result<_>>()?,
+            ),
+            WitType::Borrow(resource) => match self.named(scope, resource, depth + 1)? {
+                ComponentType::Own(handle) => ComponentType::Borrow(handle),
+                _ => {
+                    return Err(Error::type_mismatch_error(
+                        "Borrowed type is not a resource",
+                    ))
+                },
+            },
+            WitType::Stream(element) => {
+                ComponentType::Stream(element.as_deref().map(&mut boxed).transpose()?)
+            },
+            WitType::Future(value) => {
+                ComponentType::Future(value.as_deref().map(&mut boxed).transpose()?)
+            },
+            WitType::ErrorContext => ComponentType::ErrorContext,
+            WitType::Named(name) => self.named(scope, name, depth + 1)?,
+        })
+    }
+
+    /// Resolve a type name defined in or brought into `scope`
+    fn named(&mut self, scope: Scope<'_>, name: &str, depth: usize) -> Result<ComponentType> {
+        if depth > MAX_RESOLVE_DEPTH {
+            return Err(Error::validation_error("WIT type nesting too deep"));
+        }
+
+        if let Some(def) = scope.types.iter().find(|def| def.name == name) {
+            let convert_all = |resolver: &mut Self, types: &[(String, WitType)]| {
+                types
+                    .iter()
+                    .map(|(name, ty)| Ok((name.clone(), resolver.ty(scope, ty, depth + 1)?)))
+                    .collect::<Result<Vec<_>>>()
+            };
+
+            return Ok(match &def.kind {
+                WitTypeDefKind::Alias(ty) => self.ty(scope, ty, depth + 1)?,
+                WitTypeDefKind::Record(fields) => ComponentType::Record(convert_all(self, fields)?),
+                WitTypeDefKind::Variant(cases) => ComponentType::Variant(
+                    cases
+                        .iter()
+                        .map(|(case, ty)| {
+                            let payload =
+                                ty.as_ref().map(|ty| self.ty(scope, ty, depth + 1)).transpose()?;
+                            Ok((case.clone(), payload))
+                        })
+                        .collect::<Result<_>>()?,
+                ),
+                WitTypeDefKind::Enum(cases) => ComponentType::Enum(cases.clone()),
+                WitTypeDefKind::Flags(flags) => ComponentType::Flags(flags.clone()),
+                WitTypeDefKind::Resource(_) => {
+                    ComponentType::Own(self.resource_index(scope.owner, &def.name))
+                },
+            });
+        }
+
+        for item in scope.uses {
+            for (original, alias) in &item.names {
+                if alias.as_deref().unwrap_or(original) != name {
+                    continue;
+                }
+                let interface =
+                    self.lookup(&item.interface, &self.document.interfaces, |i| &i.name)?;
+                let used = Scope::interface(&interface.name, interface);
+                return self.named(used, original, depth + 1);
+            }
+        }
+
+        Err(Error::validation_error("Unknown type in WIT document"))
+    }
+
+    /// Find an interface or world of the document by path
+    fn lookup<T>(
+        &self,
+        path: &WitInterfacePath,
+        items: &'a [T],
+        name_of: impl Fn(&T) -> &String,
+    ) -> Result<&'a T> {
+        let name = match path {
+            WitInterfacePath::Local(name) => name,
+            WitInterfacePath::Package { package, name } => {
+                let local = self.document.package.as_ref().is_some_and(|own| {
+                    own.namespace == package.namespace
+                        && own.name == package.name
+                        && (package.version.is_none() || own.version == package.version)
+                });
+                if !local {
+                    return Err(Error::runtime_not_implemented(
+                        "WIT items of other packages cannot be resolved",
+                    ));
+                }
+                name
+            },
+        };
+
+        items.iter().find(|item| name_of(item) == name).ok_or_else(|| {
+            Error::resource_not_found("Interface or world not found in WIT document")
+        })
+    }
+
+    fn resource_index(&mut self, owner: &str, name: &str) -> u32 {
+        let qualified = format!("{owner}.{name}");
+        match self.resources.iter().position(|resource| *resource == qualified) {
+            Some(index) => index as u32,
+            None => {
+                self.resources.push(qualified);
+                (self.resources.len() - 1) as u32
+            },
+        }
+    }
+}
+
+fn push_unique(out: &mut Vec<WitFunctionType>, function: WitFunctionType) {
+    if !out.iter().any(|f| f.instance == function.instance && f.name == function.name) {
+        out.push(function);
+    }
+}
+
+fn all_compatible(world: &[ComponentType], component: &[ComponentType]) -> bool {
+    world.len() == component.len()
+        && world
+            .iter()
+            .zip(component)
+            .all(|(world, component)| is_compatible(world, component))
+}
+
+fn payload_compatible(world: Option<&ComponentType>, component: Option<&ComponentType>) -> bool {
+    match (world, component) {
+        (Some(world), Some(component)) => is_compatible(world, component),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Structural type compatibility between a world type and a component type
+fn is_compatible(world: &ComponentType, component: &ComponentType) -> bool {
+    use ComponentType as T;
+
+    match (world, component) {
+        // Handle type indices are local to the WIT document and the component
+        (T::Own(_), T::Own(_))
+        | (T::Borrow(_), T::Borrow(_))
+        | (T::ErrorContext, T::ErrorContext) => true,
+        (T::Stream(world), T::Stream(component)) | (T::Future(world), T::Future(component)) => {
+            payload_compatible(world.as_deref(), component.as_deref())
+        },
+        (T::List(world), T::List(component)) | (T::Option(world), T::Option(component)) => {
+            is_compatible(world, component)
+        },
+        (T::Tuple(world), T::Tuple(component)) => all_compatible(world, component),
+        (T::Record(world), T::Record(component)) => {
+            world.len() == component.len()
+                && world
+                    .iter()
+                    .zip(component)
+                    .all(|((wn, wt), (cn, ct))| wn == cn && is_compatible(wt, ct))
+        },
+        (T::Variant(world), T::Variant(component)) => {
+            world.len() == component.len()
+                && world.iter().zip(component).all(|((wn, wt), (cn, ct))| {
+                    wn == cn && payload_compatible(wt.as_ref(), ct.as_ref())
+                })
+        },
+        // Parsed components only record the ok payload of a result
+        (T::Result(world_ok, world_err), T::Result(ok, err)) => {
+            payload_compatible(world_ok.as_deref(), ok.as_deref())
+                && (err.is_none() || payload_compatible(world_err.as_deref(), err.as_deref()))
+        },
+        _ => world == component,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wrt_format::component::{
+        Export as WrtExport,
+        ExportName,
+        ExternType,
+        FormatValType,
+        Import as WrtImport,
+        ImportName,
+        Sort,
+    };
+
+    use super::*;
+    use crate::wit::parse_wit;
+
+    const WORLD: &str = r#"
+        package example:fs@0.1.0;
+
+        interface types {
+            resource file {
+                constructor(path: string);
+                read: func(len: u32) -> list<u8>;
+            }
+            type size = u64;
+        }
+
+        interface host {
+            use types.{file, size};
+            stat: func(f: borrow<file>) -> size;
+        }
+
+        world base {
+            import log: func(message: string);
+        }
+
+        world tool {
+            include base;
+            import host;
+            record options { verbose: bool }
+            export run: func(opts: options) -> result<s32, string>;
+        }
+
+        world service {
+            export types;
+        }
+    "#;
+
+    fn tool() -> WitWorldType {
+        parse_wit(WORLD).unwrap().resolve_world("tool").unwrap()
+    }
+
+    fn import(name: &str, ty: ExternType) -> WrtImport {
+        WrtImport {
+            name: ImportName {
+                namespace: String::new(),
+                name:      name.to_string(),
+                nested:    Vec::new(),
+                package:   None,
+            },
+            ty,
+        }
+    }
+
+    fn run_export(result: FormatValType) -> WrtExport {
+        WrtExport {
+            name: ExportName {
+                name:        "run".to_string(),
+                is_resource: false,
+                semver:      None,
+                integrity:   None,
+                nested:      Vec::new(),
+            },
+            sort: Sort::Function,
+            idx:  0,
+            ty:   Some(ExternType::Function {
+                params:  vec![(
+                    "opts".to_string(),
+                    FormatValType::Record(vec![("verbose".to_string(), FormatValType::Bool)]),
+                )],
+                results: vec![result],
+            }),
+        }
+    }
+
+    #[test]
+    fn test_resolve_world() {
+        let world = tool();
+
+        let names: Vec<String> =
+            world.imports.iter().map(WitFunctionType::qualified_name).collect();
+        assert_eq!(names, vec!["log", "example:fs/host@0.1.0#stat"]);
+
+        let stat = world.import("example:fs/host@0.1.0#stat").unwrap();
+        assert_eq!(stat.params[0].1, ComponentType::Borrow(0));
+        assert_eq!(stat.results, vec![ComponentType::U64]);
+        assert_eq!(world.resources, vec!["types.file".to_string()]);
+
+        let run = world.export("run").unwrap();
+        assert_eq!(
+            run.results,
+            vec![ComponentType::Result(
+                Some(Box::new(ComponentType::S32)),
+                Some(Box::new(ComponentType::String))
+            )]
+        );
+    }
+
+    #[test]
+    fn test_resource_functions() {
+        let world = parse_wit(WORLD).unwrap().resolve_world("service").unwrap();
+
+        let constructor = world.export("example:fs/types@0.1.0#[constructor]file").unwrap();
+        assert_eq!(
+            constructor.params[0],
+            ("path".to_string(), ComponentType::String)
+        );
+        assert_eq!(constructor.results, vec![ComponentType::Own(0)]);
+
+        let read = world.export("example:fs/types@0.1.0#[method]file.read").unwrap();
+        assert_eq!(
+            read.params[0],
+            ("self".to_string(), ComponentType::Borrow(0))
+        );
+        assert_eq!(
+            read.results,
+            vec![ComponentType::List(Box::new(ComponentType::U8))]
+        );
+    }
+
+    #[test]
+    fn test_unknown_items() {
+        let doc = parse_wit("world w { import missing; }").unwrap();
+        assert!(doc.resolve_world("w").is_err());
+        assert!(doc.resolve_world("other").is_err());
+
+        let doc = parse_wit("world w { export f: func() -> unknown; }").unwrap();
+        assert!(doc.resolve_world("w").is_err());
+
+        let doc = parse_wit("world w { import wasi:io/streams; }").unwrap();
+        assert!(doc.resolve_world("w").is_err());
+    }
+
+    #[test]
+    fn test_check_component() {
+        let world = tool();
+
+        let mut component = WrtComponent::new();
+        component.imports.push(import(
+            "log",
+            ExternType::Function {
+                params:  vec![("message".to_string(), FormatValType::String)],
+                results: Vec::new(),
+            },
+        ));
+        component.exports.push(run_export(FormatValType::Result(Box::new(
+            FormatValType::S32,
+        ))));
+        assert!(world.check_component(&component).is_ok());
+
+        // Wrong result type of the export and an import the world lacks
+        let mut component = WrtComponent::new();
+        component.imports.push(import(
+            "exit",
+            ExternType::Function {
+                params:  vec![("code".to_string(), FormatValType::S32)],
+                results: Vec::new(),
+            },
+        ));
+        component.exports.push(run_export(FormatValType::S32));
+        assert_eq!(
+            world.mismatches(&component).unwrap(),
+            vec![
+                WorldMismatch::UndeclaredImport("exit".to_string()),
+                WorldMismatch::ExportType("run".to_string()),
+            ]
+        );
+        assert!(world.check_component(&component).is_err());
+
+        assert_eq!(
+            world.mismatches(&WrtComponent::new()).unwrap(),
+            vec![WorldMismatch::MissingExport("run".to_string())]
+        );
+    }
+}
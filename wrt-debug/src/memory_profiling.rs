@@ -724,13 +724,18 @@ fn capture_call_stack(
 
     /// Get timestamp relative to profiler start
     fn get_relative_timestamp(&self) -> u64 {
-        Self::get_timestamp() - self.start_time
+        Self::get_timestamp().saturating_sub(self.start_time)
     }
 
     /// Get current timestamp in microseconds
+    ///
+    /// Reads the platform clock installed through
+    /// `wrt_foundation::platform_hooks`. Without one, a counter advancing by
+    /// 1000 per reading keeps the events in order.
     fn get_timestamp() -> u64 {
-        // In no_std, this would use a platform-specific timer
-        // For now, use a simple counter
+        if let Some(nanos) = wrt_foundation::platform_hooks::monotonic_ns() {
+            return nanos / 1_000;
+        }
         static COUNTER: AtomicU32 = AtomicU32::new(0);
         COUNTER.fetch_add(1000, Ordering::SeqCst) as u64
     }
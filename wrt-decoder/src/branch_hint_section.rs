@@ -13,9 +13,12 @@
 //! ```text
 //! branch_hint_section ::= func_count:u32 func_hint*
 //! func_hint ::= func_idx:u32 hint_count:u32 branch_hint*
-//! branch_hint ::= instruction_offset:u32 hint_value:u8
+//! branch_hint ::= instruction_offset:u32 hint_size:u32 hint_value:u8
 //! ```
 //!
+//! The instruction offset counts from the first byte of the function body,
+//! the local declarations included, and the hint size is always 1.
+//!
 //! Where hint_value is:
 //! - 0x00: likely_false (branch is unlikely to be taken)
 //! - 0x01: likely_true (branch is likely to be taken)
@@ -304,9 +307,16 @@ pub fn parse_branch_hint_section(data: &[u8]) -> Result<BranchHintSection> {
             let (instruction_offset, consumed) = read_leb128_u32(data, offset)?;
             offset += consumed;
 
-            // Read hint value
-            let (hint_byte, consumed) = read_u8(data, offset)?;
+            // Read hint size, which is 1 for the single hint byte
+            let (hint_size, consumed) = read_leb128_u32(data, offset)?;
             offset += consumed;
+            if hint_size != 1 {
+                return Err(Error::parse_error("Invalid branch hint size"));
+            }
+
+            // Read hint value
+            let (hint_byte, next) = read_u8(data, offset)?;
+            offset = next;
 
             let hint_value = BranchHintValue::from_byte(hint_byte)?;
             function_hints.add_hint(instruction_offset, hint_value)?;
@@ -336,6 +346,7 @@ pub fn encode_branch_hint_section(section: &BranchHintSection) -> Result<Vec<u8>
 
         for (offset, hint) in hints.iter() {
             data.extend_from_slice(&format_write_leb128_u32(*offset));
+            data.push(0x01);
             data.push(hint.to_byte());
         }
     }
@@ -346,6 +357,16 @@ pub fn encode_branch_hint_section(section: &BranchHintSection) -> Result<Vec<u8>
 /// Branch hint section name constant
 pub const BRANCH_HINT_SECTION_NAME: &str = "metadata.code.branch_hint";
 
+/// Read the branch hint section of a module binary
+///
+/// Returns `None` for modules without a branch hint section.
+#[cfg(feature = "std")]
+pub fn read_branch_hint_section(binary: &[u8]) -> Result<Option<BranchHintSection>> {
+    crate::custom_section_handler::find_custom_section(binary, BRANCH_HINT_SECTION_NAME)?
+        .map(parse_branch_hint_section)
+        .transpose()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -476,6 +497,7 @@ fn test_parse_malformed_data() {
             0x00, // function index = 0
             0x01, // hint count = 1
             0x05, // instruction offset = 5
+            0x01, // hint size = 1
             0x02, // invalid hint value
         ];
         assert!(parse_branch_hint_section(data).is_err());
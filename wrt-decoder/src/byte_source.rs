@@ -0,0 +1,236 @@
+//! Decoding modules from non-contiguous storage
+//!
+//! On bare-metal targets a module often lives in flash or ROM that is not
+//! mapped into the address space as one slice, or arrives in a chain of
+//! fixed-size buffers. A [`ByteSource`] gives the decoder random access to
+//! such storage, and [`decode_module_from_source`] decodes a module from it
+//! through [`StreamingDecoder::push`], so that only the section being decoded
+//! is ever copied into RAM instead of the whole binary.
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+use wrt_format::module::Module as WrtModule;
+#[cfg(not(feature = "std"))]
+use wrt_foundation::safe_memory::NoStdProvider;
+
+use crate::{
+    prelude::*,
+    streaming_decoder::StreamingDecoder,
+    streaming_validator::ComprehensivePlatformLimits,
+};
+
+/// Size of the buffer a source is read through while decoding
+const READ_CHUNK_SIZE: usize = 256;
+
+/// Random-access storage holding a module binary
+pub trait ByteSource {
+    /// Size of the binary in bytes
+    fn len(&self) -> usize;
+
+    /// Fill `buf` with the bytes starting at `offset`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the range lies beyond [`len`](Self::len) or the
+    /// storage cannot be read.
+    fn read_at(&self, offset: usize, buf: &mut [u8]) -> Result<()>;
+
+    /// Whether the binary is empty
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Read the byte at `offset`
+    fn read_u8_at(&self, offset: usize) -> Result<u8> {
+        let mut byte = [0];
+        self.read_at(offset, &mut byte)?;
+        Ok(byte[0])
+    }
+
+    /// Read the unsigned LEB128 value at `offset`, returning it and the
+    /// number of bytes it takes
+    fn read_leb128_u32_at(&self, offset: usize) -> Result<(u32, usize)> {
+        let available = self.len().saturating_sub(offset).min(5);
+        let mut bytes = [0; 5];
+        self.read_at(offset, &mut bytes[..available])?;
+        read_leb128_u32(&bytes[..available], 0)
+    }
+}
+
+impl ByteSource for [u8] {
+    fn len(&self) -> usize {
+        <[u8]>::len(self)
+    }
+
+    fn read_at(&self, offset: usize, buf: &mut [u8]) -> Result<()> {
+        let bytes = offset
+            .checked_add(buf.len())
+            .and_then(|end| self.get(offset..end))
+            .ok_or_else(|| Error::parse_error("Read beyond end of byte source"))?;
+        buf.copy_from_slice(bytes);
+        Ok(())
+    }
+}
+
+impl<S: ByteSource + ?Sized> ByteSource for &S {
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+
+    fn read_at(&self, offset: usize, buf: &mut [u8]) -> Result<()> {
+        (**self).read_at(offset, buf)
+    }
+}
+
+/// Binary split across several buffers, read as if they were concatenated
+#[derive(Debug, Clone, Copy)]
+pub struct SegmentedSource<'a> {
+    segments: &'a [&'a [u8]],
+}
+
+impl<'a> SegmentedSource<'a> {
+    /// Source of the concatenation of `segments`
+    pub fn new(segments: &'a [&'a [u8]]) -> Self {
+        Self { segments }
+    }
+}
+
+impl ByteSource for SegmentedSource<'_> {
+    fn len(&self) -> usize {
+        self.segments.iter().map(|segment| segment.len()).sum()
+    }
+
+    fn read_at(&self, offset: usize, buf: &mut [u8]) -> Result<()> {
+        let mut skip = offset;
+        let mut filled = 0;
+        for segment in self.segments {
+            if filled == buf.len() {
+                break;
+            }
+            if skip >= segment.len() {
+                skip -= segment.len();
+                continue;
+            }
+            let count = (segment.len() - skip).min(buf.len() - filled);
+            buf[filled..filled + count].copy_from_slice(&segment[skip..skip + count]);
+            filled += count;
+            skip = 0;
+        }
+
+        if filled == buf.len() {
+            Ok(())
+        } else {
+            Err(Error::parse_error("Read beyond end of byte source"))
+        }
+    }
+}
+
+/// Push the whole of `source` into `decoder`
+fn push_source<S: ByteSource + ?Sized>(
+    source: &S,
+    decoder: &mut StreamingDecoder<'_>,
+) -> Result<()> {
+    let mut chunk = [0; READ_CHUNK_SIZE];
+    let mut offset = 0;
+    while offset < source.len() {
+        let count = (source.len() - offset).min(READ_CHUNK_SIZE);
+        source.read_at(offset, &mut chunk[..count])?;
+        decoder.push(&chunk[..count])?;
+        offset += count;
+    }
+    Ok(())
+}
+
+/// Decode and validate a module stored in `source` (std version)
+#[cfg(feature = "std")]
+pub fn decode_module_from_source<S: ByteSource + ?Sized>(source: &S) -> Result<WrtModule> {
+    decode_module_from_source_with_limits(source, ComprehensivePlatformLimits::default())
+}
+
+/// Decode and validate a module stored in `source` against `limits` (std
+/// version)
+#[cfg(feature = "std")]
+pub fn decode_module_from_source_with_limits<S: ByteSource + ?Sized>(
+    source: &S,
+    limits: ComprehensivePlatformLimits,
+) -> Result<WrtModule> {
+    let mut decoder = StreamingDecoder::incremental_with_limits(limits)?;
+    push_source(source, &mut decoder)?;
+    decoder.finish()
+}
+
+/// Decode and validate a module stored in `source` (no_std version)
+#[cfg(not(feature = "std"))]
+pub fn decode_module_from_source<S: ByteSource + ?Sized>(
+    source: &S,
+) -> Result<WrtModule<NoStdProvider<8192>>> {
+    decode_module_from_source_with_limits(source, ComprehensivePlatformLimits::default())
+}
+
+/// Decode and validate a module stored in `source` against `limits` (no_std
+/// version)
+#[cfg(not(feature = "std"))]
+pub fn decode_module_from_source_with_limits<S: ByteSource + ?Sized>(
+    source: &S,
+    limits: ComprehensivePlatformLimits,
+) -> Result<WrtModule<NoStdProvider<8192>>> {
+    let mut decoder = StreamingDecoder::incremental_with_limits(limits)?;
+    push_source(source, &mut decoder)?;
+    decoder.finish()
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::{
+        streaming_decoder::decode_module_streaming,
+        test_fixtures::ModuleBinary,
+    };
+
+    /// Module with a function returning 7, exported as "run"
+    fn sample_module() -> Vec<u8> {
+        let mut binary = ModuleBinary::new();
+        binary.section(1, &[0x01, 0x60, 0x00, 0x01, 0x7F]);
+        binary.section(3, &[0x01, 0x00]);
+        binary.section(7, &[0x01, 0x03, b'r', b'u', b'n', 0x00, 0x00]);
+        binary.section(10, &[0x01, 0x04, 0x00, 0x41, 0x07, 0x0B]);
+        binary.build()
+    }
+
+    #[test]
+    fn test_segmented_source_reads() {
+        let segments: [&[u8]; 4] = [b"ab", b"", b"cde", b"f"];
+        let source = SegmentedSource::new(&segments);
+        assert_eq!(source.len(), 6);
+
+        let mut buf = [0; 4];
+        source.read_at(1, &mut buf).unwrap();
+        assert_eq!(&buf, b"bcde");
+        assert_eq!(source.read_u8_at(5).unwrap(), b'f');
+        assert!(source.read_at(3, &mut buf).is_err());
+
+        let segments: [&[u8]; 2] = [&[0xE5], &[0x8E, 0x26, 0x00]];
+        let source = SegmentedSource::new(&segments);
+        assert_eq!(source.read_leb128_u32_at(0).unwrap(), (624_485, 3));
+        assert!(source.read_leb128_u32_at(3).is_ok());
+    }
+
+    #[test]
+    fn test_decode_from_segments() {
+        let binary = sample_module();
+        let expected = decode_module_streaming(&binary).unwrap();
+
+        let (head, tail) = binary.split_at(11);
+        let (middle, tail) = tail.split_at(9);
+        let segments: [&[u8]; 3] = [head, middle, tail];
+        let module = decode_module_from_source(&SegmentedSource::new(&segments)).unwrap();
+        assert_eq!(module.functions[0].code, expected.functions[0].code);
+        assert_eq!(module.exports[0].name, "run");
+
+        let module = decode_module_from_source(binary.as_slice()).unwrap();
+        assert_eq!(module.functions.len(), 1);
+
+        assert!(decode_module_from_source(&binary[..binary.len() - 1]).is_err());
+    }
+}
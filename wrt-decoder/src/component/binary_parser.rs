@@ -23,22 +23,43 @@ mod component_binary_parser {
         Result,
     };
     use wrt_format::{
+        binary,
         component::Component,
         Validatable,
     };
     // Import ValidationLevel from foundation if available, otherwise define locally
     pub use wrt_foundation::VerificationLevel as ValidationLevel;
 
-    use crate::prelude::*;
+    use crate::{
+        component::parse::{
+            parse_alias_section,
+            parse_canon_section,
+            parse_component_type_section,
+            parse_core_instance_section,
+            parse_core_type_section,
+            parse_export_section,
+            parse_import_section,
+            parse_instance_section,
+            parse_start_section,
+            parse_value_section,
+        },
+        prelude::*,
+    };
 
     /// Component Magic Number: "\0asm" (same as modules)
     const COMPONENT_MAGIC: [u8; 4] = [0x00, 0x61, 0x73, 0x6D];
 
-    /// Component Version (1)
-    const COMPONENT_VERSION: u32 = 1;
+    /// Component Version (0x0d, the version emitted by current toolchains)
+    const COMPONENT_VERSION: u16 = 0x0d;
+
+    /// Legacy Component Version (1, as written by `wrt_format::binary`)
+    const LEGACY_COMPONENT_VERSION: u16 = 1;
 
     /// Component Layer (1, distinguishes from modules which use layer 0)
-    const COMPONENT_LAYER: u32 = 1;
+    const COMPONENT_LAYER: u16 = 1;
+
+    /// Size of the preamble: magic (4 bytes), version (2 bytes), layer (2 bytes)
+    const HEADER_SIZE: usize = 8;
 
     /// Component Binary Parser
     ///
@@ -143,10 +164,10 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     pub struct ComponentHeader {
         /// Magic number (must be COMPONENT_MAGIC)
         pub magic:   [u8; 4],
-        /// Version (must be COMPONENT_VERSION)
-        pub version: u32,
-        /// Layer (must be COMPONENT_LAYER for components)  
-        pub layer:   u32,
+        /// Version (COMPONENT_VERSION or LEGACY_COMPONENT_VERSION)
+        pub version: u16,
+        /// Layer (must be COMPONENT_LAYER for components)
+        pub layer:   u16,
     }
 
     impl ComponentHeader {
@@ -156,7 +177,7 @@ pub fn validate(&self) -> Result<()> {
                 return Err(Error::parse_error("Invalid component magic number"));
             }
 
-            if self.version != COMPONENT_VERSION {
+            if self.version != COMPONENT_VERSION && self.version != LEGACY_COMPONENT_VERSION {
                 return Err(Error::parse_error("Unsupported component version"));
             }
 
@@ -187,6 +208,11 @@ pub fn with_validation_level(validation_level: ValidationLevel) -> Self {
             }
         }
 
+        /// Level of validation the parser performs
+        pub fn validation_level(&self) -> ValidationLevel {
+            self.validation_level
+        }
+
         /// Parse a WebAssembly Component Model binary
         ///
         /// # Arguments
@@ -200,9 +226,9 @@ pub fn parse(&mut self, bytes: &[u8]) -> Result<Component> {
             self.size = bytes.len();
 
             // Validate minimum size
-            if bytes.len() < 12 {
+            if bytes.len() < HEADER_SIZE {
                 return Err(Error::parse_error(
-                    "Component binary too small (minimum 12 bytes required)",
+                    "Component binary too small (minimum 8 bytes required)",
                 ));
             }
 
@@ -228,7 +254,7 @@ pub fn parse(&mut self, bytes: &[u8]) -> Result<Component> {
 
         /// Parse the component header (magic, version, layer)
         fn parse_header(&mut self, bytes: &[u8]) -> Result<ComponentHeader> {
-            if self.offset + 12 > bytes.len() {
+            if self.offset + HEADER_SIZE > bytes.len() {
                 return Err(Error::parse_error(
                     "Insufficient bytes for component header",
                 ));
@@ -239,23 +265,13 @@ fn parse_header(&mut self, bytes: &[u8]) -> Result<ComponentHeader> {
             magic.copy_from_slice(&bytes[self.offset..self.offset + 4]);
             self.offset += 4;
 
-            // Parse version (4 bytes, little-endian)
-            let version = u32::from_le_bytes([
-                bytes[self.offset],
-                bytes[self.offset + 1],
-                bytes[self.offset + 2],
-                bytes[self.offset + 3],
-            ]);
-            self.offset += 4;
+            // Parse version (2 bytes, little-endian)
+            let version = u16::from_le_bytes([bytes[self.offset], bytes[self.offset + 1]]);
+            self.offset += 2;
 
-            // Parse layer (4 bytes, little-endian)
-            let layer = u32::from_le_bytes([
-                bytes[self.offset],
-                bytes[self.offset + 1],
-                bytes[self.offset + 2],
-                bytes[self.offset + 3],
-            ]);
-            self.offset += 4;
+            // Parse layer (2 bytes, little-endian)
+            let layer = u16::from_le_bytes([bytes[self.offset], bytes[self.offset + 1]]);
+            self.offset += 2;
 
             Ok(ComponentHeader {
                 magic,
@@ -283,7 +299,8 @@ fn parse_section(&mut self, bytes: &[u8], component: &mut Component) -> Result<(
                 .ok_or_else(|| Error::parse_error("Unknown component section ID"))?;
 
             // Read section size (LEB128)
-            let (section_size, _size_bytes) = self.read_leb128_u32(bytes)?;
+            let (section_size, size_bytes) = self.read_leb128_u32(bytes)?;
+            self.offset += size_bytes;
 
             // Validate section size
             if self.offset + section_size as usize > self.size {
@@ -348,224 +365,184 @@ fn parse_section(&mut self, bytes: &[u8], component: &mut Component) -> Result<(
 
         /// Read a LEB128 unsigned 32-bit integer
         fn read_leb128_u32(&mut self, bytes: &[u8]) -> Result<(u32, usize)> {
-            let mut result = 0u32;
-            let mut shift = 0;
-            let mut bytes_read = 0;
-            let start_offset = self.offset;
-
-            loop {
-                if self.offset >= self.size {
-                    return Err(Error::parse_error(
-                        "Unexpected end of binary while reading LEB128",
-                    ));
-                }
-
-                let byte = bytes[self.offset];
-                self.offset += 1;
-                bytes_read += 1;
-
-                result |= ((byte & 0x7F) as u32) << shift;
-
-                if (byte & 0x80) == 0 {
-                    break;
-                }
-
-                shift += 7;
-                if shift >= 32 {
-                    return Err(Error::parse_error("LEB128 value too large for u32"));
-                }
+            binary::read_leb128_u32(&bytes[..self.size], self.offset)
+        }
 
-                if bytes_read > 5 {
-                    return Err(Error::parse_error("LEB128 encoding too long"));
+        /// Parse custom section, picking up the component name if present
+        fn parse_custom_section(&mut self, data: &[u8], component: &mut Component) -> Result<()> {
+            // Custom sections are application-specific; malformed ones are ignored
+            let Ok((name, name_offset)) = binary::read_string(data, 0) else {
+                return Ok(());
+            };
+            if name == b"name" {
+                if let Ok(name_section) =
+                    crate::component::name_section::parse_component_name_section(
+                        &data[name_offset..],
+                    )
+                {
+                    if let Some(component_name) = name_section.component_name {
+                        component.name = Some(component_name);
+                    }
                 }
             }
-
-            // Reset offset to where it was before this call
-            self.offset = start_offset;
-            Ok((result, bytes_read))
-        }
-
-        /// Parse custom section (placeholder implementation)
-        fn parse_custom_section(&mut self, _data: &[u8], _component: &mut Component) -> Result<()> {
-            // Custom sections are application-specific and can be safely ignored
-            // In a full implementation, this would handle name sections, debug info, etc.
             Ok(())
         }
 
-        /// Parse core module section using streaming parser
+        /// Parse core module section, which embeds exactly one core module
         fn parse_core_module_section(
             &mut self,
             data: &[u8],
             component: &mut Component,
         ) -> Result<()> {
-            use crate::component::streaming_core_module_parser::StreamingCoreModuleParser;
-
-            // Create unified streaming parser (works for all ASIL levels)
-            let mut parser = StreamingCoreModuleParser::new(data, self.validation_level)?;
-
-            // Parse the core module section
-            let core_module_section = parser.parse()?;
+            let module = binary::parse_binary(data)?;
 
-            // Store parsed modules in component
-            for (i, module) in core_module_section.iter_modules().enumerate() {
-                // Validate module at the requested verification level
-                if self.validation_level >= ValidationLevel::Standard {
-                    module.validate()?;
-                }
-
-                // Add to component's modules collection
-                // Note: This is a simplified integration - in practice you might
-                // want to store modules differently based on your component model
-                self.record_core_module_parsed(component, i, module)?;
+            // Validate module at the requested verification level
+            if self.validation_level >= ValidationLevel::Standard {
+                module.validate()?;
             }
 
-            // Update our parsing offset
-            self.offset += core_module_section.bytes_consumed;
-
-            Ok(())
-        }
-
-        /// Record that a core module has been parsed (helper method)
-        fn record_core_module_parsed(
-            &self,
-            _component: &mut Component,
-            _index: usize,
-            _module: &wrt_format::module::Module,
-        ) -> Result<()> {
-            // For now, just validate that parsing succeeded
-            // In a full implementation, you would integrate this with
-            // your component's module storage system
+            component.modules.push(module);
             Ok(())
         }
 
-        /// Parse core instance section (placeholder implementation)
+        /// Parse core instance section
         fn parse_core_instance_section(
             &mut self,
-            _data: &[u8],
-            _component: &mut Component,
+            data: &[u8],
+            component: &mut Component,
         ) -> Result<()> {
-            // This would parse core module instantiations
+            let (instances, consumed) = parse_core_instance_section(data)?;
+            Self::expect_section_end(data, consumed)?;
+            component.core_instances.extend(instances);
             Ok(())
         }
 
-        /// Parse core type section (placeholder implementation)
+        /// Parse core type section
         fn parse_core_type_section(
             &mut self,
-            _data: &[u8],
-            _component: &mut Component,
+            data: &[u8],
+            component: &mut Component,
         ) -> Result<()> {
-            // This would parse core WebAssembly types (function signatures, etc.)
+            let (types, consumed) = parse_core_type_section(data)?;
+            Self::expect_section_end(data, consumed)?;
+            component.core_types.extend(types);
             Ok(())
         }
 
-        /// Parse component section (placeholder implementation)
+        /// Parse component section, which embeds exactly one nested component
         fn parse_component_section(
             &mut self,
-            _data: &[u8],
-            _component: &mut Component,
+            data: &[u8],
+            component: &mut Component,
         ) -> Result<()> {
-            // This would parse nested component definitions
+            let nested = ComponentBinaryParser::with_validation_level(self.validation_level)
+                .parse(data)?;
+            component.components.push(nested);
             Ok(())
         }
 
-        /// Parse instance section (placeholder implementation)
+        /// Parse instance section
         fn parse_instance_section(
             &mut self,
-            _data: &[u8],
-            _component: &mut Component,
+            data: &[u8],
+            component: &mut Component,
         ) -> Result<()> {
-            // This would parse component instantiations
+            let (instances, consumed) = parse_instance_section(data)?;
+            Self::expect_section_end(data, consumed)?;
+            component.instances.extend(instances);
             Ok(())
         }
 
-        /// Parse alias section (placeholder implementation)
-        fn parse_alias_section(&mut self, _data: &[u8], _component: &mut Component) -> Result<()> {
-            // This would parse type and instance aliases
+        /// Parse alias section
+        fn parse_alias_section(&mut self, data: &[u8], component: &mut Component) -> Result<()> {
+            let (aliases, consumed) = parse_alias_section(data)?;
+            Self::expect_section_end(data, consumed)?;
+            component.aliases.extend(aliases);
             Ok(())
         }
 
-        /// Parse type section using streaming parser
+        /// Parse component type section
         fn parse_type_section(&mut self, data: &[u8], component: &mut Component) -> Result<()> {
-            use crate::component::streaming_type_parser::StreamingTypeParser;
-
-            // Create unified streaming parser (works for all ASIL levels)
-            let mut parser = StreamingTypeParser::new(data, self.validation_level)?;
-
-            // Parse the component type section
-            let type_section = parser.parse()?;
-
-            // Store parsed types in component
-            for (i, comp_type) in type_section.iter_types().enumerate() {
-                // Validate type at the requested verification level
-                if self.validation_level >= ValidationLevel::Standard {
-                    comp_type.validate()?;
-                }
-
-                // Add to component's types collection
-                // Convert placeholder type to wrt_format type - temporary stub
-                let dummy_comp_type = wrt_format::component::ComponentType {
-                    definition: wrt_format::component::ComponentTypeDefinition::Function {
-                        params:  Vec::new(),
-                        results: Vec::new(),
-                    },
-                };
-                self.record_component_type_parsed(component, i, &dummy_comp_type)?;
-            }
-
-            // Update our parsing offset is handled by the caller
-
+            let (types, consumed) = parse_component_type_section(data)?;
+            Self::expect_section_end(data, consumed)?;
+            component.types.extend(types);
             Ok(())
         }
 
-        /// Record that a component type has been parsed (helper method)
-        fn record_component_type_parsed(
-            &self,
-            component: &mut Component,
-            _index: usize,
-            comp_type: &wrt_format::component::ComponentType,
-        ) -> Result<()> {
-            // Store the type in the component's types vector
-            component.types.push(comp_type.clone());
+        /// Parse canon section
+        fn parse_canon_section(&mut self, data: &[u8], component: &mut Component) -> Result<()> {
+            let (canons, consumed) = parse_canon_section(data)?;
+            Self::expect_section_end(data, consumed)?;
+            component.canonicals.extend(canons);
             Ok(())
         }
 
-        /// Parse canon section (placeholder implementation)
-        fn parse_canon_section(&mut self, _data: &[u8], _component: &mut Component) -> Result<()> {
-            // This would parse canonical function adapters
+        /// Parse start section
+        fn parse_start_section(&mut self, data: &[u8], component: &mut Component) -> Result<()> {
+            if component.start.is_some() {
+                return Err(Error::parse_error("Duplicate component start section"));
+            }
+            let (start, consumed) = parse_start_section(data)?;
+            Self::expect_section_end(data, consumed)?;
+            component.start = Some(start);
             Ok(())
         }
 
-        /// Parse start section (placeholder implementation)
-        fn parse_start_section(&mut self, _data: &[u8], _component: &mut Component) -> Result<()> {
-            // This would parse the component start function
+        /// Parse import section
+        fn parse_import_section(&mut self, data: &[u8], component: &mut Component) -> Result<()> {
+            let (imports, consumed) = parse_import_section(data)?;
+            Self::expect_section_end(data, consumed)?;
+            component.imports.extend(imports);
             Ok(())
         }
 
-        /// Parse import section (placeholder implementation)
-        fn parse_import_section(&mut self, _data: &[u8], _component: &mut Component) -> Result<()> {
-            // This would parse component imports
+        /// Parse export section
+        fn parse_export_section(&mut self, data: &[u8], component: &mut Component) -> Result<()> {
+            let (exports, consumed) = parse_export_section(data)?;
+            Self::expect_section_end(data, consumed)?;
+            component.exports.extend(exports);
             Ok(())
         }
 
-        /// Parse export section (placeholder implementation)
-        fn parse_export_section(&mut self, _data: &[u8], _component: &mut Component) -> Result<()> {
-            // This would parse component exports
+        /// Parse value section
+        fn parse_value_section(&mut self, data: &[u8], component: &mut Component) -> Result<()> {
+            let (values, consumed) = parse_value_section(data)?;
+            Self::expect_section_end(data, consumed)?;
+            component.values.extend(values);
             Ok(())
         }
 
-        /// Parse value section (placeholder implementation)
-        fn parse_value_section(&mut self, _data: &[u8], _component: &mut Component) -> Result<()> {
-            // This would parse component values
+        /// Ensure a section parser consumed the whole section payload
+        fn expect_section_end(data: &[u8], consumed: usize) -> Result<()> {
+            if consumed != data.len() {
+                return Err(Error::parse_error(
+                    "Component section size does not match its contents",
+                ));
+            }
             Ok(())
         }
 
         /// Validate the complete component (strict mode only)
-        fn validate_component(&self, _component: &Component) -> Result<()> {
-            // This would perform comprehensive validation:
-            // - Check all type references are valid
-            // - Verify import/export consistency
-            // - Validate resource lifecycle
-            // - Check alias resolution
+        fn validate_component(&self, component: &Component) -> Result<()> {
+            // Import and export names must be unique within a component
+            let mut seen = std::collections::HashSet::new();
+            for import in component.imports.iter() {
+                let key = (&import.name.namespace, &import.name.name, &import.name.nested);
+                if !seen.insert(key) {
+                    return Err(Error::validation_error("Duplicate component import name"));
+                }
+            }
+
+            let mut seen = std::collections::HashSet::new();
+            for export in component.exports.iter() {
+                if !seen.insert((&export.name.name, &export.name.nested)) {
+                    return Err(Error::validation_error("Duplicate component export name"));
+                }
+            }
+
+            for nested in component.components.iter() {
+                self.validate_component(nested)?;
+            }
             Ok(())
         }
     }
@@ -720,12 +697,81 @@ fn test_convenience_functions() {
             assert!(result1.is_ok());
 
             // Test parsing with validation level
-            let result2 = parse_component_binary_with_validation(&binary, ValidationLevel::Minimal);
+            let result2 = parse_component_binary_with_validation(&binary, ValidationLevel::Basic);
             assert!(result2.is_ok());
 
             let result3 = parse_component_binary_with_validation(&binary, ValidationLevel::Full);
             assert!(result3.is_ok());
         }
+
+        fn header() -> Vec<u8> {
+            let mut binary = Vec::new();
+            binary.extend_from_slice(&COMPONENT_MAGIC);
+            binary.extend_from_slice(&COMPONENT_VERSION.to_le_bytes());
+            binary.extend_from_slice(&COMPONENT_LAYER.to_le_bytes());
+            binary
+        }
+
+        fn push_section(binary: &mut Vec<u8>, id: ComponentSectionId, payload: &[u8]) {
+            binary.push(id as u8);
+            binary.push(payload.len() as u8);
+            binary.extend_from_slice(payload);
+        }
+
+        /// Export section payload exporting component functions by name
+        fn export_section(names: &[&str]) -> Vec<u8> {
+            let mut payload = vec![names.len() as u8];
+            for (idx, name) in names.iter().enumerate() {
+                payload.push(name.len() as u8);
+                payload.extend_from_slice(name.as_bytes());
+                payload.push(0); // No flags
+                payload.push(binary::COMPONENT_SORT_FUNC);
+                payload.push(idx as u8);
+                payload.push(0); // No declared type
+            }
+            payload
+        }
+
+        #[test]
+        fn test_parse_sections_into_component() {
+            let mut core_module = Vec::new();
+            core_module.extend_from_slice(&binary::WASM_MAGIC);
+            core_module.extend_from_slice(&binary::WASM_VERSION);
+
+            let mut binary = header();
+            push_section(&mut binary, ComponentSectionId::CoreModule, &core_module);
+            push_section(&mut binary, ComponentSectionId::Component, &header());
+            push_section(&mut binary, ComponentSectionId::Export, &export_section(&["run"]));
+            push_section(&mut binary, ComponentSectionId::Start, &[0, 0, 0]);
+
+            let component = parse_component_binary(&binary).unwrap();
+            assert_eq!(component.modules.len(), 1);
+            assert_eq!(component.components.len(), 1);
+            assert_eq!(component.exports.len(), 1);
+            assert_eq!(component.exports[0].name.name, "run");
+            assert_eq!(component.start.as_ref().map(|s| s.func_idx), Some(0));
+        }
+
+        #[test]
+        fn test_section_size_must_match_contents() {
+            let mut binary = header();
+            push_section(&mut binary, ComponentSectionId::Start, &[0, 0, 0, 0]);
+            assert!(parse_component_binary(&binary).is_err());
+
+            let mut binary = header();
+            push_section(&mut binary, ComponentSectionId::Start, &[0, 0, 0]);
+            push_section(&mut binary, ComponentSectionId::Start, &[0, 0, 0]);
+            assert!(parse_component_binary(&binary).is_err());
+        }
+
+        #[test]
+        fn test_full_validation_rejects_duplicate_exports() {
+            let mut binary = header();
+            push_section(&mut binary, ComponentSectionId::Export, &export_section(&["a", "a"]));
+
+            assert!(parse_component_binary_with_validation(&binary, ValidationLevel::Full).is_err());
+            assert!(parse_component_binary_with_validation(&binary, ValidationLevel::Basic).is_ok());
+        }
     }
 } // end of component_binary_parser module
 
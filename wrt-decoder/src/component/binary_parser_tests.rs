@@ -19,18 +19,18 @@ fn create_minimal_component_binary() -> Vec<u8> {
         // Add component magic
         binary.extend_from_slice(&[0x00, 0x61, 0x73, 0x6D]); // Component magic
 
-        // Add version (1 in little-endian)
-        binary.extend_from_slice(&[0x01, 0x00, 0x00, 0x00]); // Version 1
+        // Add version (0x0d in little-endian)
+        binary.extend_from_slice(&[0x0d, 0x00]); // Version 0x0d
 
         // Add layer (1 in little-endian)
-        binary.extend_from_slice(&[0x01, 0x00, 0x00, 0x00]); // Layer 1
+        binary.extend_from_slice(&[0x01, 0x00]); // Layer 1
 
         binary
     }
 
     /// Create a component binary with a custom section
     fn create_component_with_custom_section() -> Vec<u8> {
-        let binary = create_minimal_component_binary();
+        let mut binary = create_minimal_component_binary();
 
         // Add custom section
         binary.push(0); // Custom section ID
@@ -49,8 +49,8 @@ fn create_invalid_magic_binary() -> Vec<u8> {
 
         // Add invalid magic
         binary.extend_from_slice(&[0xFF, 0xFF, 0xFF, 0xFF]); // Invalid magic
-        binary.extend_from_slice(&[0x01, 0x00, 0x00, 0x00]); // Version 1
-        binary.extend_from_slice(&[0x01, 0x00, 0x00, 0x00]); // Layer 1
+        binary.extend_from_slice(&[0x0d, 0x00]); // Version 0x0d
+        binary.extend_from_slice(&[0x01, 0x00]); // Layer 1
 
         binary
     }
@@ -60,8 +60,8 @@ fn create_invalid_version_binary() -> Vec<u8> {
         let mut binary = Vec::new();
 
         binary.extend_from_slice(&[0x00, 0x61, 0x73, 0x6D]); // Valid magic
-        binary.extend_from_slice(&[0xFF, 0xFF, 0xFF, 0xFF]); // Invalid version
-        binary.extend_from_slice(&[0x01, 0x00, 0x00, 0x00]); // Layer 1
+        binary.extend_from_slice(&[0xFF, 0xFF]); // Invalid version
+        binary.extend_from_slice(&[0x01, 0x00]); // Layer 1
 
         binary
     }
@@ -71,8 +71,8 @@ fn create_invalid_layer_binary() -> Vec<u8> {
         let mut binary = Vec::new();
 
         binary.extend_from_slice(&[0x00, 0x61, 0x73, 0x6D]); // Valid magic
-        binary.extend_from_slice(&[0x01, 0x00, 0x00, 0x00]); // Version 1
-        binary.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]); // Invalid layer (0)
+        binary.extend_from_slice(&[0x0d, 0x00]); // Version 0x0d
+        binary.extend_from_slice(&[0x00, 0x00]); // Invalid layer (0)
 
         binary
     }
@@ -82,13 +82,13 @@ fn create_invalid_layer_binary() -> Vec<u8> {
     #[test]
     fn test_parser_creation() {
         let parser = ComponentBinaryParser::new();
-        assert_eq!(parser.validation_level, ValidationLevel::Standard);
+        assert_eq!(parser.validation_level(), ValidationLevel::Standard);
 
-        let minimal_parser = ComponentBinaryParser::with_validation_level(ValidationLevel::Minimal);
-        assert_eq!(minimal_parser.validation_level, ValidationLevel::Minimal);
+        let minimal_parser = ComponentBinaryParser::with_validation_level(ValidationLevel::Basic);
+        assert_eq!(minimal_parser.validation_level(), ValidationLevel::Basic);
 
-        let strict_parser = ComponentBinaryParser::with_validation_level(ValidationLevel::Strict);
-        assert_eq!(strict_parser.validation_level, ValidationLevel::Strict);
+        let strict_parser = ComponentBinaryParser::with_validation_level(ValidationLevel::Full);
+        assert_eq!(strict_parser.validation_level(), ValidationLevel::Full);
     }
 
     #[test]
@@ -126,20 +126,20 @@ fn test_parse_empty_binary() {
 
         assert!(result.is_err());
         let error = result.unwrap_err();
-        assert_eq!(error.category(), ErrorCategory::Parse);
+        assert_eq!(error.category, ErrorCategory::Parse);
     }
 
     #[test]
     fn test_parse_too_small_binary() {
         let mut parser = ComponentBinaryParser::new();
 
-        // Binary smaller than minimum header size (12 bytes)
+        // Binary smaller than minimum header size (8 bytes)
         let small_binary = vec![0x00, 0x61, 0x73, 0x6D, 0x01, 0x00];
         let result = parser.parse(&small_binary);
 
         assert!(result.is_err());
         let error = result.unwrap_err();
-        assert_eq!(error.category(), ErrorCategory::Parse);
+        assert_eq!(error.category, ErrorCategory::Parse);
     }
 
     #[test]
@@ -151,8 +151,8 @@ fn test_parse_invalid_magic() {
         assert!(result.is_err());
 
         let error = result.unwrap_err();
-        assert_eq!(error.category(), ErrorCategory::Parse);
-        assert!(error.message().contains("magic"));
+        assert_eq!(error.category, ErrorCategory::Parse);
+        assert!(error.message.contains("magic"));
     }
 
     #[test]
@@ -165,8 +165,8 @@ fn test_parse_invalid_version() {
         assert!(result.is_err());
 
         let error = result.unwrap_err();
-        assert_eq!(error.category(), ErrorCategory::Parse);
-        assert!(error.message().contains("version"));
+        assert_eq!(error.category, ErrorCategory::Parse);
+        assert!(error.message.contains("version"));
     }
 
     #[test]
@@ -179,8 +179,8 @@ fn test_parse_invalid_layer() {
         assert!(result.is_err());
 
         let error = result.unwrap_err();
-        assert_eq!(error.category(), ErrorCategory::Parse);
-        assert!(error.message().contains("layer"));
+        assert_eq!(error.category, ErrorCategory::Parse);
+        assert!(error.message.contains("layer"));
     }
 
     // Validation level tests
@@ -191,7 +191,7 @@ fn test_validation_levels() {
 
         // Test minimal validation
         let mut minimal_parser =
-            ComponentBinaryParser::with_validation_level(ValidationLevel::Minimal);
+            ComponentBinaryParser::with_validation_level(ValidationLevel::Basic);
         let result1 = minimal_parser.parse(&binary);
         assert!(result1.is_ok());
 
@@ -202,8 +202,7 @@ fn test_validation_levels() {
         assert!(result2.is_ok());
 
         // Test strict validation
-        let mut strict_parser =
-            ComponentBinaryParser::with_validation_level(ValidationLevel::Strict);
+        let mut strict_parser = ComponentBinaryParser::with_validation_level(ValidationLevel::Full);
         let result3 = strict_parser.parse(&binary);
         assert!(result3.is_ok());
     }
@@ -219,13 +218,13 @@ fn test_convenience_functions() {
         assert!(result1.is_ok());
 
         // Test parsing with different validation levels
-        let result2 = parse_component_binary_with_validation(&binary, ValidationLevel::Minimal);
+        let result2 = parse_component_binary_with_validation(&binary, ValidationLevel::Basic);
         assert!(result2.is_ok());
 
         let result3 = parse_component_binary_with_validation(&binary, ValidationLevel::Standard);
         assert!(result3.is_ok());
 
-        let result4 = parse_component_binary_with_validation(&binary, ValidationLevel::Strict);
+        let result4 = parse_component_binary_with_validation(&binary, ValidationLevel::Full);
         assert!(result4.is_ok());
     }
 
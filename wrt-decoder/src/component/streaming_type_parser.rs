@@ -631,6 +631,63 @@ fn create_decoder_provider<const N: usize>() -> wrt_error::Result<wrt_foundation
 /// Decoder provider type for consistent allocation
 type DecoderProvider = NoStdProvider<65536>;
 
+/// A composite value type whose element types are still being parsed
+#[derive(Debug, Clone, Copy)]
+enum PendingValType {
+    /// Record with `remaining` fields left
+    Record { remaining: u32 },
+    /// Variant with `remaining` cases left
+    Variant { remaining: u32 },
+    /// Tuple with `remaining` elements left
+    Tuple { remaining: u32 },
+    /// List waiting for its element type
+    #[cfg(feature = "std")]
+    List,
+}
+
+/// Start of a value type
+enum ValTypeHead {
+    /// A value type without nested value types
+    Complete(FormatValType),
+    /// A composite value type whose element types follow
+    Composite(PendingValType),
+}
+
+/// Work stack of composite value types being parsed
+struct PendingValTypes {
+    entries: [PendingValType; MAX_TYPE_RECURSION_DEPTH],
+    len:     usize,
+}
+
+impl PendingValTypes {
+    fn new() -> Self {
+        Self {
+            entries: [PendingValType::Tuple { remaining: 0 }; MAX_TYPE_RECURSION_DEPTH],
+            len:     0,
+        }
+    }
+
+    fn push(&mut self, composite: PendingValType) -> Result<()> {
+        // ASIL constraint: Check nesting depth
+        let slot = self
+            .entries
+            .get_mut(self.len)
+            .ok_or_else(|| Error::validation_error("Type recursion depth exceeded "))?;
+        *slot = composite;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn last_mut(&mut self) -> Option<&mut PendingValType> {
+        self.len.checked_sub(1).map(|last| &mut self.entries[last])
+    }
+
+    fn pop(&mut self) -> PendingValType {
+        self.len -= 1;
+        self.entries[self.len]
+    }
+}
+
 /// Component Type Section streaming parser
 ///
 /// This parser processes Component Type sections within Component binaries
@@ -1019,7 +1076,10 @@ fn parse_extern_type(&mut self) -> Result<ExternType> {
                 Ok(ExternType::Type(type_idx))
             },
             0x03 => {
-                // Instance type - recursive parse
+                // Instance type - recursive parse, bounded like nested type definitions
+                if self.recursion_depth >= MAX_TYPE_RECURSION_DEPTH {
+                    return Err(Error::validation_error("Type recursion depth exceeded "));
+                }
                 self.recursion_depth += 1;
                 let instance_def = self.parse_instance_type_definition()?;
                 self.recursion_depth -= 1;
@@ -1031,7 +1091,10 @@ fn parse_extern_type(&mut self) -> Result<ExternType> {
                 }
             },
             0x04 => {
-                // Component type - recursive parse
+                // Component type - recursive parse, bounded like nested type definitions
+                if self.recursion_depth >= MAX_TYPE_RECURSION_DEPTH {
+                    return Err(Error::validation_error("Type recursion depth exceeded "));
+                }
                 self.recursion_depth += 1;
                 let component_def = self.parse_component_type_definition()?;
                 self.recursion_depth -= 1;
@@ -1047,7 +1110,46 @@ fn parse_extern_type(&mut self) -> Result<ExternType> {
     }
 
     /// Parse component value type
+    ///
+    /// Nested value types are parsed with an explicit work stack of at most
+    /// [`MAX_TYPE_RECURSION_DEPTH`] entries rather than by recursion, so the
+    /// native stack used does not depend on the input.
     fn parse_value_type(&mut self) -> Result<FormatValType> {
+        let mut pending = PendingValTypes::new();
+        loop {
+            let mut value = match self.parse_value_type_head()? {
+                ValTypeHead::Complete(value) => Some(value),
+                ValTypeHead::Composite(composite) => {
+                    pending.push(composite)?;
+                    None
+                },
+            };
+
+            // Complete the composite types whose elements have all been parsed
+            loop {
+                let Some(composite) = pending.last_mut() else {
+                    return value.ok_or_else(|| Error::parse_error("Missing value type"));
+                };
+
+                #[cfg(feature = "std")]
+                if let (PendingValType::List, Some(element)) = (*composite, value.take()) {
+                    pending.pop();
+                    value = Some(FormatValType::List(Box::new(element)));
+                    continue;
+                }
+
+                // Element types of the other composite types are skipped
+                if self.advance_composite(composite)? {
+                    break;
+                }
+                let completed = pending.pop();
+                value = Some(self.finish_composite(completed)?);
+            }
+        }
+    }
+
+    /// Parse a value type up to its first nested value type
+    fn parse_value_type_head(&mut self) -> Result<ValTypeHead> {
         if self.offset >= self.data.len() {
             return Err(Error::parse_error(
                 "Unexpected end while reading value type ",
@@ -1057,119 +1159,129 @@ fn parse_value_type(&mut self) -> Result<FormatValType> {
         let val_form = self.data[self.offset];
         self.offset += 1;
 
-        match val_form {
-            0x7F => Ok(FormatValType::Bool),
-            0x7E => Ok(FormatValType::S8),
-            0x7D => Ok(FormatValType::U8),
-            0x7C => Ok(FormatValType::S16),
-            0x7B => Ok(FormatValType::U16),
-            0x7A => Ok(FormatValType::S32),
-            0x79 => Ok(FormatValType::U32),
-            0x78 => Ok(FormatValType::S64),
-            0x77 => Ok(FormatValType::U64),
-            0x76 => Ok(FormatValType::F32),
-            0x75 => Ok(FormatValType::F64),
-            0x74 => Ok(FormatValType::Char),
-            0x73 => Ok(FormatValType::String),
+        let value = match val_form {
+            0x7F => FormatValType::Bool,
+            0x7E => FormatValType::S8,
+            0x7D => FormatValType::U8,
+            0x7C => FormatValType::S16,
+            0x7B => FormatValType::U16,
+            0x7A => FormatValType::S32,
+            0x79 => FormatValType::U32,
+            0x78 => FormatValType::S64,
+            0x77 => FormatValType::U64,
+            0x76 => FormatValType::F32,
+            0x75 => FormatValType::F64,
+            0x74 => FormatValType::Char,
+            0x73 => FormatValType::String,
             0x72 => {
-                // Record type - simplified for streaming
-                let (field_count, bytes_read) = read_leb128_u32(self.data, self.offset)?;
+                // Record type - field types are skipped for streaming
+                let (remaining, bytes_read) = read_leb128_u32(self.data, self.offset)?;
                 self.offset += bytes_read;
-
-                // Skip field definitions for now (would need full recursive parsing)
-                for _ in 0..field_count {
-                    let _name = self.read_string()?;
-                    let _field_type = self.parse_value_type()?;
-                }
-
-                // Use bounded vec for empty record - allocation will be handled by capability
-                // system
-                #[cfg(not(feature = "std"))]
-                let empty_fields = {
-                    let provider = create_decoder_provider::<4096>()?;
-                    DecoderVec::new(provider)?
-                };
-                #[cfg(feature = "std")]
-                let empty_fields = DecoderVec::new();
-                return Ok(FormatValType::Record(empty_fields));
+                return Ok(ValTypeHead::Composite(PendingValType::Record { remaining }));
             },
             0x71 => {
-                // Variant type - simplified for streaming
-                let (case_count, bytes_read) = read_leb128_u32(self.data, self.offset)?;
+                // Variant type - case types are skipped for streaming
+                let (remaining, bytes_read) = read_leb128_u32(self.data, self.offset)?;
                 self.offset += bytes_read;
-
-                // Skip case definitions for now
-                for _ in 0..case_count {
-                    let _name = self.read_string()?;
-                    // Optional case type
-                    if self.offset < self.data.len() && self.data[self.offset] == 1 {
-                        self.offset += 1;
-                        let _case_type = self.parse_value_type()?;
-                    } else if self.offset < self.data.len() {
-                        self.offset += 1; // Skip the 0 byte
-                    }
-                }
-
-                // Use bounded vec for empty variant - allocation will be handled by capability
-                // system
-                #[cfg(not(feature = "std"))]
-                let empty_cases = {
-                    let provider = create_decoder_provider::<4096>()?;
-                    DecoderVec::new(provider)?
-                };
-                #[cfg(feature = "std")]
-                let empty_cases = DecoderVec::new();
-                return Ok(FormatValType::Variant(empty_cases));
+                return Ok(ValTypeHead::Composite(PendingValType::Variant {
+                    remaining,
+                }));
             },
             0x70 => {
                 // List type
                 #[cfg(feature = "std")]
-                {
-                    let element_type = self.parse_value_type()?;
-                    // For std, we use Box<FormatValType>
-                    Ok(FormatValType::List(Box::new(element_type)))
-                }
+                return Ok(ValTypeHead::Composite(PendingValType::List));
                 #[cfg(not(feature = "std"))]
                 {
-                    let element_type_ref = self.parse_type_ref()?;
                     // For no_std placeholder, we use u32 type reference
-                    Ok(FormatValType::List(element_type_ref))
+                    FormatValType::List(self.parse_type_ref()?)
                 }
             },
             0x6F => {
-                // Tuple type - simplified for streaming
-                let (element_count, bytes_read) = read_leb128_u32(self.data, self.offset)?;
+                // Tuple type - element types are skipped for streaming
+                let (remaining, bytes_read) = read_leb128_u32(self.data, self.offset)?;
                 self.offset += bytes_read;
-
-                // Skip element types for now
-                for _ in 0..element_count {
-                    let _element_type = self.parse_value_type()?;
-                }
-
-                // Use bounded vec for empty tuple - allocation will be handled by capability
-                // system
-                #[cfg(not(feature = "std"))]
-                let empty_elements = {
-                    let provider = create_decoder_provider::<4096>()?;
-                    DecoderVec::new(provider)?
-                };
-                #[cfg(feature = "std")]
-                let empty_elements = DecoderVec::new();
-                return Ok(FormatValType::Tuple(empty_elements));
+                return Ok(ValTypeHead::Composite(PendingValType::Tuple { remaining }));
             },
             0x6E => {
                 // Own resource
                 let (resource_idx, bytes_read) = read_leb128_u32(self.data, self.offset)?;
                 self.offset += bytes_read;
-                Ok(FormatValType::Own(resource_idx))
+                FormatValType::Own(resource_idx)
             },
             0x6D => {
                 // Borrow resource
                 let (resource_idx, bytes_read) = read_leb128_u32(self.data, self.offset)?;
                 self.offset += bytes_read;
-                Ok(FormatValType::Borrow(resource_idx))
+                FormatValType::Borrow(resource_idx)
+            },
+            _ => return Err(Error::parse_error("Unknown value type form ")),
+        };
+        Ok(ValTypeHead::Complete(value))
+    }
+
+    /// Consume the input up to the next element type of `composite`
+    ///
+    /// Returns `false` when the composite type has no more element types.
+    fn advance_composite(&mut self, composite: &mut PendingValType) -> Result<bool> {
+        match composite {
+            PendingValType::Record { remaining } => {
+                if *remaining == 0 {
+                    return Ok(false);
+                }
+                *remaining -= 1;
+                let _name = self.read_string()?;
+                Ok(true)
+            },
+            PendingValType::Variant { remaining } => {
+                while *remaining > 0 {
+                    *remaining -= 1;
+                    let _name = self.read_string()?;
+                    // Optional case type
+                    if self.offset < self.data.len() && self.data[self.offset] == 1 {
+                        self.offset += 1;
+                        return Ok(true);
+                    } else if self.offset < self.data.len() {
+                        self.offset += 1; // Skip the 0 byte
+                    }
+                }
+                Ok(false)
             },
-            _ => Err(Error::parse_error("Unknown value type form ")),
+            PendingValType::Tuple { remaining } => {
+                if *remaining == 0 {
+                    return Ok(false);
+                }
+                *remaining -= 1;
+                Ok(true)
+            },
+            #[cfg(feature = "std")]
+            PendingValType::List => Ok(true),
+        }
+    }
+
+    /// Build the value type of a composite type whose elements were skipped
+    fn finish_composite(&self, composite: PendingValType) -> Result<FormatValType> {
+        // Use bounded vecs for the skipped elements - allocation will be handled by
+        // capability system
+        #[cfg(not(feature = "std"))]
+        let provider = create_decoder_provider::<4096>()?;
+        match composite {
+            #[cfg(not(feature = "std"))]
+            PendingValType::Record { .. } => Ok(FormatValType::Record(DecoderVec::new(provider)?)),
+            #[cfg(feature = "std")]
+            PendingValType::Record { .. } => Ok(FormatValType::Record(DecoderVec::new())),
+            #[cfg(not(feature = "std"))]
+            PendingValType::Variant { .. } => {
+                Ok(FormatValType::Variant(DecoderVec::new(provider)?))
+            },
+            #[cfg(feature = "std")]
+            PendingValType::Variant { .. } => Ok(FormatValType::Variant(DecoderVec::new())),
+            #[cfg(not(feature = "std"))]
+            PendingValType::Tuple { .. } => Ok(FormatValType::Tuple(DecoderVec::new(provider)?)),
+            #[cfg(feature = "std")]
+            PendingValType::Tuple { .. } => Ok(FormatValType::Tuple(DecoderVec::new())),
+            #[cfg(feature = "std")]
+            PendingValType::List => Err(Error::parse_error("List type without element type")),
         }
     }
 
@@ -1352,6 +1464,33 @@ fn test_recursion_depth_protection() {
         assert!(parser.parse().is_ok());
     }
 
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_nested_value_types_are_bounded() {
+        // One value type definition: list<list<...<string>>>
+        let nested = |depth: usize| {
+            let mut data = vec![1, 0x43];
+            data.extend(core::iter::repeat(0x70).take(depth));
+            data.push(0x73);
+            data
+        };
+
+        let data = nested(MAX_TYPE_RECURSION_DEPTH);
+        let mut parser = StreamingTypeParser::new(&data, VerificationLevel::Standard).unwrap();
+        assert_eq!(parser.parse().unwrap().bytes_consumed(), data.len());
+
+        // Far deeper than the work stack, must fail without exhausting the
+        // native stack
+        let data = nested(60_000);
+        let mut parser = StreamingTypeParser::new(&data, VerificationLevel::Standard).unwrap();
+        assert!(parser.parse().is_err());
+
+        // record { a: bool, b: list<u32> } skips its field types
+        let data = [1, 0x43, 0x72, 2, 1, b'a', 0x7F, 1, b'b', 0x70, 0x79];
+        let mut parser = StreamingTypeParser::new(&data, VerificationLevel::Standard).unwrap();
+        assert_eq!(parser.parse().unwrap().bytes_consumed(), data.len());
+    }
+
     #[test]
     fn test_parser_offset_tracking() {
         let data = &[0u8]; // Zero types
@@ -4,13 +4,19 @@
 //! including automatic recognition and parsing of well-known sections like
 //! branch hints, name sections, and others.
 
-#[cfg(not(feature = "std"))]
-use alloc::collections::BTreeMap;
+use alloc::{
+    collections::BTreeMap,
+    sync::Arc,
+};
 #[cfg(not(feature = "std"))]
 use alloc::{
     string::String,
     vec::Vec,
 };
+use core::{
+    any::Any,
+    fmt,
+};
 #[cfg(all(feature = "std", not(feature = "safety-critical")))]
 use std::collections::HashMap;
 #[cfg(feature = "std")]
@@ -38,10 +44,30 @@
         BranchHintSection,
         BRANCH_HINT_SECTION_NAME,
     },
+    dylink_section::{
+        parse_dylink_section,
+        DylinkSection,
+        DYLINK_SECTION_NAME,
+    },
+    name_section::{
+        parse_name_section,
+        NameMap,
+        NAME_SECTION_NAME,
+    },
+    producers_section::{
+        parse_producers_section,
+        ProducersSection,
+        PRODUCERS_SECTION_NAME,
+    },
     resource_limits_section::{
         ResourceLimitsSection,
         RESOURCE_LIMITS_SECTION_NAME,
     },
+    target_features_section::{
+        parse_target_features_section,
+        TargetFeaturesSection,
+        TARGET_FEATURES_SECTION_NAME,
+    },
 };
 
 /// Represents a parsed custom section
@@ -52,18 +78,14 @@ pub enum CustomSection {
     BranchHint(BranchHintSection),
     /// Resource limits section for execution constraints
     ResourceLimits(ResourceLimitsSection),
+    /// Dynamic linking section of a side module
+    Dylink(DylinkSection),
     /// Name section for debugging information
-    Name {
-        /// Module name
-        module_name:    Option<String>,
-        /// Function names
-        #[cfg(all(feature = "std", feature = "safety-critical"))]
-        function_names: WrtHashMap<u32, String, { CrateId::Decoder as u8 }, 256>,
-        #[cfg(all(feature = "std", not(feature = "safety-critical")))]
-        function_names: HashMap<u32, String>,
-        #[cfg(not(feature = "std"))]
-        function_names: BTreeMap<u32, String>,
-    },
+    Name(NameMap),
+    /// Producers section recording the toolchain
+    Producers(ProducersSection),
+    /// Target features section listing the features the code uses
+    TargetFeatures(TargetFeaturesSection),
     /// Unknown custom section (raw data preserved)
     Unknown {
         /// Section name
@@ -116,9 +138,11 @@ pub fn add_section(&mut self, name: &str, data: &[u8]) -> Result<()> {
 
                 CustomSection::ResourceLimits(resource_limits)
             },
-            "name" => {
-                let name_section = parse_name_section(data)?;
-                name_section
+            DYLINK_SECTION_NAME => CustomSection::Dylink(parse_dylink_section(data)?),
+            NAME_SECTION_NAME => CustomSection::Name(parse_name_section(data)?),
+            PRODUCERS_SECTION_NAME => CustomSection::Producers(parse_producers_section(data)?),
+            TARGET_FEATURES_SECTION_NAME => {
+                CustomSection::TargetFeatures(parse_target_features_section(data)?)
             },
             _ => {
                 // Unknown section - preserve raw data
@@ -165,6 +189,15 @@ pub fn get_resource_limits(&self) -> Option<&ResourceLimitsSection> {
         }
     }
 
+    /// Get dynamic linking section if present
+    pub fn get_dylink(&self) -> Option<&DylinkSection> {
+        if let Some(CustomSection::Dylink(dylink)) = self.sections.get(DYLINK_SECTION_NAME) {
+            Some(dylink)
+        } else {
+            None
+        }
+    }
+
     /// Get a specific branch hint
     pub fn get_branch_hint(
         &self,
@@ -175,24 +208,46 @@ pub fn get_branch_hint(
             .and_then(|hints| hints.get_hint(function_index, instruction_offset))
     }
 
-    /// Get name section information
-    pub fn get_function_name(&self, function_index: u32) -> Option<&str> {
-        if let Some(CustomSection::Name { function_names, .. }) = self.sections.get("name") {
-            function_names.get(&function_index).map(|s| s.as_str())
+    /// Get name section if present
+    pub fn get_names(&self) -> Option<&NameMap> {
+        if let Some(CustomSection::Name(names)) = self.sections.get(NAME_SECTION_NAME) {
+            Some(names)
         } else {
             None
         }
     }
 
-    /// Get module name if present
-    pub fn get_module_name(&self) -> Option<&str> {
-        if let Some(CustomSection::Name { module_name, .. }) = self.sections.get("name") {
-            module_name.as_ref().map(|s| s.as_str())
+    /// Get producers section if present
+    pub fn get_producers(&self) -> Option<&ProducersSection> {
+        if let Some(CustomSection::Producers(producers)) = self.sections.get(PRODUCERS_SECTION_NAME)
+        {
+            Some(producers)
+        } else {
+            None
+        }
+    }
+
+    /// Get target features section if present
+    pub fn get_target_features(&self) -> Option<&TargetFeaturesSection> {
+        if let Some(CustomSection::TargetFeatures(features)) =
+            self.sections.get(TARGET_FEATURES_SECTION_NAME)
+        {
+            Some(features)
         } else {
             None
         }
     }
 
+    /// Get name section information
+    pub fn get_function_name(&self, function_index: u32) -> Option<&str> {
+        self.get_names().and_then(|names| names.function_name(function_index))
+    }
+
+    /// Get module name if present
+    pub fn get_module_name(&self) -> Option<&str> {
+        self.get_names().and_then(|names| names.module_name.as_deref())
+    }
+
     /// Check if branch hints are available
     pub fn has_branch_hints(&self) -> bool {
         self.get_branch_hints().is_some()
@@ -220,21 +275,6 @@ fn default() -> Self {
     }
 }
 
-/// Parse a WebAssembly name section
-fn parse_name_section(_data: &[u8]) -> Result<CustomSection> {
-    // Simplified name section parsing - normally this would be more complex
-    // For now, just create an empty name section
-    Ok(CustomSection::Name {
-        module_name: None,
-        #[cfg(all(feature = "std", feature = "safety-critical"))]
-        function_names: WrtHashMap::with_capacity(0),
-        #[cfg(all(feature = "std", not(feature = "safety-critical")))]
-        function_names: HashMap::with_capacity(0),
-        #[cfg(not(feature = "std"))]
-        function_names: BTreeMap::new(),
-    })
-}
-
 impl Default for CustomSection {
     fn default() -> Self {
         CustomSection::Unknown {
@@ -244,6 +284,147 @@ fn default() -> Self {
     }
 }
 
+/// Deserializer of the payload of a custom section, as registered with a
+/// [`CustomSectionRegistry`]
+type SectionDeserializer = Arc<dyn Fn(&[u8]) -> Result<Arc<dyn Any + Send + Sync>> + Send + Sync>;
+
+/// Deserializers for custom sections, keyed by section name
+///
+/// Plugins register a handler for the sections they understand and get the
+/// typed values it produces back from the [`CustomSectionValues`] of each
+/// module, without the decoder having to know the section format.
+#[derive(Clone, Default)]
+pub struct CustomSectionRegistry {
+    handlers: BTreeMap<String, SectionDeserializer>,
+}
+
+impl CustomSectionRegistry {
+    /// Create a registry without handlers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Deserialize sections named `name` with `handler`, replacing any
+    /// handler registered for them before
+    pub fn register<T, F>(&mut self, name: &str, handler: F)
+    where
+        T: Any + Send + Sync,
+        F: Fn(&[u8]) -> Result<T> + Send + Sync + 'static,
+    {
+        let handler: SectionDeserializer =
+            Arc::new(move |data| Ok(Arc::new(handler(data)?) as Arc<dyn Any + Send + Sync>));
+        self.handlers.insert(name.to_string(), handler);
+    }
+
+    /// Remove the handler of sections named `name`, returning whether there
+    /// was one
+    pub fn unregister(&mut self, name: &str) -> bool {
+        self.handlers.remove(name).is_some()
+    }
+
+    /// Whether a handler is registered for sections named `name`
+    pub fn is_registered(&self, name: &str) -> bool {
+        self.handlers.contains_key(name)
+    }
+
+    /// Whether no handlers are registered
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+
+    /// Deserialize the custom sections of a module binary that have a handler
+    ///
+    /// Only the first section of each name is deserialized, and sections
+    /// without a handler are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the binary is malformed or a handler fails.
+    pub fn deserialize(&self, binary: &[u8]) -> Result<CustomSectionValues> {
+        let mut values = CustomSectionValues::default();
+        if self.handlers.is_empty() {
+            return Ok(values);
+        }
+        visit_custom_sections(binary, |name, data| {
+            if let Some(handler) = self.handlers.get(name) {
+                if !values.values.contains_key(name) {
+                    values.values.insert(name.to_string(), handler(data)?);
+                }
+            }
+            Ok(false)
+        })?;
+        Ok(values)
+    }
+}
+
+impl fmt::Debug for CustomSectionRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.handlers.keys()).finish()
+    }
+}
+
+/// Typed values deserialized from the custom sections of a module by a
+/// [`CustomSectionRegistry`]
+///
+/// Clones share the values, and two sets of values are equal when they share
+/// the values of the same sections.
+#[derive(Clone, Default)]
+pub struct CustomSectionValues {
+    values: BTreeMap<String, Arc<dyn Any + Send + Sync>>,
+}
+
+impl CustomSectionValues {
+    /// Value deserialized from the section named `name`, if it is a `T`
+    pub fn get<T: Any>(&self, name: &str) -> Option<&T> {
+        self.values.get(name)?.downcast_ref()
+    }
+
+    /// Shared value deserialized from the section named `name`, if it is a
+    /// `T`
+    pub fn get_shared<T: Any + Send + Sync>(&self, name: &str) -> Option<Arc<T>> {
+        self.values.get(name)?.clone().downcast().ok()
+    }
+
+    /// Whether a value was deserialized from a section named `name`
+    pub fn contains(&self, name: &str) -> bool {
+        self.values.contains_key(name)
+    }
+
+    /// Names of the sections values were deserialized from
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.values.keys().map(String::as_str)
+    }
+
+    /// Number of values
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Whether no values were deserialized
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl fmt::Debug for CustomSectionValues {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.values.keys()).finish()
+    }
+}
+
+impl PartialEq for CustomSectionValues {
+    fn eq(&self, other: &Self) -> bool {
+        self.values.len() == other.values.len()
+            && self
+                .values
+                .iter()
+                .zip(&other.values)
+                .all(|((a_name, a), (b_name, b))| a_name == b_name && Arc::ptr_eq(a, b))
+    }
+}
+
+impl Eq for CustomSectionValues {}
+
 /// Utility function to extract custom section name and data from a complete
 /// custom section
 pub fn extract_custom_section(section_data: &[u8]) -> Result<(String, &[u8])> {
@@ -269,6 +450,60 @@ pub fn extract_custom_section(section_data: &[u8]) -> Result<(String, &[u8])> {
     Ok((name, &section_data[offset..]))
 }
 
+/// Find the contents of the first custom section named `name` in a module
+/// binary
+///
+/// Returns `None` if the module has no such section.
+pub fn find_custom_section<'a>(binary: &'a [u8], name: &str) -> Result<Option<&'a [u8]>> {
+    let mut found = None;
+    visit_custom_sections(binary, |section_name, data| {
+        if section_name == name {
+            found = Some(data);
+        }
+        Ok(found.is_some())
+    })?;
+    Ok(found)
+}
+
+/// Call `visit` with the name and contents of each custom section of a module
+/// binary, in order, until it returns `true`
+fn visit_custom_sections<'a>(
+    binary: &'a [u8],
+    mut visit: impl FnMut(&str, &'a [u8]) -> Result<bool>,
+) -> Result<()> {
+    use wrt_format::binary::{
+        read_leb128_u32,
+        CUSTOM_SECTION_ID,
+        WASM_MAGIC,
+    };
+    const HEADER_SIZE: usize = 8;
+
+    if binary.len() < HEADER_SIZE || binary[..4] != WASM_MAGIC {
+        return Err(Error::parse_error("Invalid WebAssembly module header"));
+    }
+
+    let mut offset = HEADER_SIZE;
+    while offset < binary.len() {
+        let id = binary[offset];
+        let (size, consumed) = read_leb128_u32(binary, offset + 1)?;
+        let start = offset + 1 + consumed;
+        let end = start
+            .checked_add(size as usize)
+            .filter(|end| *end <= binary.len())
+            .ok_or_else(|| Error::parse_error("Section exceeds module size"))?;
+
+        if id == CUSTOM_SECTION_ID {
+            let (section_name, data) = extract_custom_section(&binary[start..end])?;
+            if visit(&section_name, data)? {
+                break;
+            }
+        }
+        offset = end;
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -335,4 +570,32 @@ fn test_extract_custom_section_invalid() {
         let section_data = &[2, 0xFF, 0xFE]; // invalid UTF-8 bytes
         assert!(extract_custom_section(section_data).is_err());
     }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_registry_deserializes_registered_sections() {
+        // Module with custom sections "count" [3], "skip" [1] and "count" [9]
+        let mut binary = vec![0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00];
+        for (name, data) in [("count", 3u8), ("skip", 1), ("count", 9)] {
+            binary.extend_from_slice(&[0, name.len() as u8 + 2, name.len() as u8]);
+            binary.extend_from_slice(name.as_bytes());
+            binary.push(data);
+        }
+
+        let mut registry = CustomSectionRegistry::new();
+        registry.register("count", |data: &[u8]| Ok(u32::from(data[0])));
+        let values = registry.deserialize(&binary).unwrap();
+        assert_eq!(values.get::<u32>("count"), Some(&3));
+        assert_eq!(values.get::<u8>("count"), None);
+        assert!(!values.contains("skip"));
+        assert_eq!(values.len(), 1);
+        assert_eq!(values.clone(), values);
+
+        registry.register("skip", |_: &[u8]| -> Result<()> {
+            Err(Error::parse_error("Malformed section"))
+        });
+        assert!(registry.deserialize(&binary).is_err());
+        assert!(registry.unregister("skip"));
+        assert!(!registry.is_registered("skip"));
+    }
 }
@@ -0,0 +1,188 @@
+//! Decode errors located in the module binary
+//!
+//! The section decoders attribute a failure to the section, the index of the
+//! item within the section and the byte offset the decoding stopped at, in
+//! the context chain of the returned [`Error`]. A [`DecodeError`] extracts
+//! that location so tools can report it, and renders the bytes around it:
+//!
+//! ```
+//! use wrt_decoder::{
+//!     decode_error::DecodeError,
+//!     streaming_decoder::decode_module_streaming,
+//! };
+//!
+//! // Memory section whose only memory has invalid limits flags
+//! let binary = b"\0asm\x01\0\0\0\x05\x03\x01\x07\x01";
+//! let error = DecodeError::from(decode_module_streaming(binary).unwrap_err());
+//! assert_eq!(error.section_id(), Some(5));
+//! assert_eq!(error.item_index(), Some(0));
+//! assert_eq!(error.offset(), Some(11));
+//! println!("{}", error.render_with_context(binary));
+//! ```
+
+use core::fmt;
+
+use crate::{
+    prelude::*,
+    streaming_decoder::section_of_operation,
+};
+
+/// Number of bytes per line of [`DecodeError::render_with_context`]
+const BYTES_PER_LINE: usize = 16;
+
+/// Decoding error with its location in the module binary
+#[derive(Debug, Clone, Copy)]
+pub struct DecodeError {
+    error:      Error,
+    section_id: Option<u8>,
+    offset:     Option<usize>,
+    item_index: Option<u32>,
+}
+
+impl DecodeError {
+    /// The underlying error
+    pub fn error(&self) -> Error {
+        self.error
+    }
+
+    /// Id of the section being decoded, if the error is attributed to one
+    pub fn section_id(&self) -> Option<u8> {
+        self.section_id
+    }
+
+    /// Offset in the binary the decoding failed at, if known
+    ///
+    /// This is the offset of the innermost part that could not be decoded,
+    /// for example a LEB128 value or a flags byte, or the start of the
+    /// section when the error is not attributed to any item.
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    /// Index of the item within its section, such as the import or function
+    /// body, that failed to decode
+    pub fn item_index(&self) -> Option<u32> {
+        self.item_index
+    }
+
+    /// Render the error followed by a hex dump of `binary` around the
+    /// failing offset, with the failing byte marked
+    ///
+    /// `binary` has to be the binary the error was returned for.
+    #[cfg(feature = "std")]
+    pub fn render_with_context(&self, binary: &[u8]) -> String {
+        use core::fmt::Write;
+
+        let mut rendered = String::new();
+        // Writing to a `String` cannot fail
+        let _ = write!(rendered, "{self}");
+        let Some(offset) = self.offset else {
+            return rendered;
+        };
+
+        let line_of_offset = offset / BYTES_PER_LINE * BYTES_PER_LINE;
+        let start = line_of_offset.saturating_sub(BYTES_PER_LINE);
+        let end = binary.len().min(line_of_offset + 2 * BYTES_PER_LINE);
+        for line in (start..end.max(line_of_offset + 1)).step_by(BYTES_PER_LINE) {
+            let bytes = binary.get(line..end.min(line + BYTES_PER_LINE)).unwrap_or(&[]);
+            let _ = write!(rendered, "\n{line:08x} ");
+            for byte in bytes {
+                let _ = write!(rendered, " {byte:02x}");
+            }
+            if line == line_of_offset {
+                // Past the last byte for a binary that ended too early
+                let column = offset - line_of_offset;
+                let _ = write!(rendered, "\n{:width$}^^", "", width = 10 + 3 * column);
+            }
+        }
+        rendered
+    }
+}
+
+impl From<Error> for DecodeError {
+    fn from(error: Error) -> Self {
+        let frames = error.context();
+        Self {
+            error,
+            section_id: frames.iter().find_map(|frame| section_of_operation(frame.operation())),
+            offset: frames.iter().find_map(|frame| frame.offset()).map(|offset| offset as usize),
+            item_index: frames.iter().find_map(|frame| frame.item()),
+        }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::{
+        parallel_decoder::{
+            decode_module_parallel_with,
+            ParallelDecodeConfig,
+        },
+        sliced_validation::SlicedValidator,
+        streaming_decoder::decode_module_streaming,
+    };
+
+    /// Module whose second import has an invalid kind at offset 0x1f
+    fn module_with_bad_import() -> Vec<u8> {
+        let mut binary = b"\0asm\x01\0\0\0".to_vec();
+        binary.extend_from_slice(&[0x01, 0x04, 0x01, 0x60, 0x00, 0x00]);
+        let imports = [
+            0x02, 0x03, b'e', b'n', b'v', 0x01, b'f', 0x00, 0x00, 0x03, b'e', b'n', b'v', 0x01,
+            b'g', 0x09, 0x00,
+        ];
+        binary.extend_from_slice(&[0x02, imports.len() as u8]);
+        binary.extend_from_slice(&imports);
+        binary
+    }
+
+    #[test]
+    fn test_error_is_located() {
+        let binary = module_with_bad_import();
+        let error = DecodeError::from(decode_module_streaming(&binary).unwrap_err());
+
+        assert_eq!(error.section_id(), Some(2));
+        assert_eq!(error.item_index(), Some(1));
+        assert_eq!(error.offset(), Some(0x1f));
+        assert_eq!(binary[0x1f], 0x09);
+        assert!(error.to_string().contains("while decoding import 1 at offset 0x1f"));
+
+        let rendered = error.render_with_context(&binary);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[1], format!("00000000  {}", hex(&binary[..16])));
+        assert_eq!(lines[2], format!("00000010  {}", hex(&binary[16..32])));
+        assert_eq!(lines[3], format!("{}^^", " ".repeat(10 + 3 * 0xf)));
+        assert_eq!(lines[4], format!("00000020  {}", hex(&binary[32..])));
+    }
+
+    #[test]
+    fn test_decoders_agree_on_location() {
+        let binary = module_with_bad_import();
+        let streaming = location(decode_module_streaming(&binary).unwrap_err());
+
+        let config = ParallelDecodeConfig {
+            min_section_size: 0,
+        };
+        let parallel = decode_module_parallel_with(&binary, &config).unwrap_err();
+        assert_eq!(location(parallel), streaming);
+
+        let mut validator = SlicedValidator::new(&binary).unwrap();
+        let sliced = validator.step(usize::MAX).unwrap_err();
+        assert_eq!(location(sliced), streaming);
+    }
+
+    fn location(error: Error) -> (Option<u8>, Option<u32>, Option<usize>) {
+        let error = DecodeError::from(error);
+        (error.section_id(), error.item_index(), error.offset())
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{byte:02x}")).collect::<Vec<_>>().join(" ")
+    }
+}
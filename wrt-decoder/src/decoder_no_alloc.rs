@@ -41,7 +41,10 @@
     Result,
 };
 use wrt_foundation::{
-    safe_memory::NoStdProvider,
+    safe_memory::{
+        NoStdProvider,
+        SliceView,
+    },
     verification::VerificationLevel,
 };
 
@@ -381,6 +384,235 @@ pub fn find_custom_section<'a>(&self, bytes: &'a [u8], name: &str) -> Option<(us
         }
         None
     }
+
+    /// Returns a view of the contents of a specific section type, borrowed
+    /// from `bytes`, if it exists
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The WebAssembly binary data the header was decoded from
+    /// * `id` - The section ID to find
+    pub fn section_view<'a>(
+        &self,
+        bytes: &'a [u8],
+        id: SectionId,
+    ) -> Result<Option<SliceView<'a>>> {
+        match self.find_section(id) {
+            Some((offset, size)) => Ok(Some(
+                SliceView::with_verification_level(bytes, self.verification_level)?
+                    .view(offset, size as usize)?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns a view of the data of a custom section with a specific name,
+    /// borrowed from `bytes`, if it exists
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The WebAssembly binary data the header was decoded from
+    /// * `name` - The custom section name to find
+    pub fn custom_section_view<'a>(
+        &self,
+        bytes: &'a [u8],
+        name: &str,
+    ) -> Result<Option<SliceView<'a>>> {
+        match self.find_custom_section(bytes, name) {
+            Some((offset, size)) => Ok(Some(
+                SliceView::with_verification_level(bytes, self.verification_level)?
+                    .view(offset, size as usize)?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the bodies of the functions in the code section as views
+    /// borrowed from `bytes`, for decoding them lazily
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The WebAssembly binary data the header was decoded from
+    pub fn function_bodies<'a>(&self, bytes: &'a [u8]) -> Result<FunctionBodies<'a>> {
+        Ok(FunctionBodies(VecReader::new(
+            self.section_view(bytes, SectionId::Code)?,
+        )?))
+    }
+
+    /// Returns the data segments of the data section, their contents as
+    /// views borrowed from `bytes`
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The WebAssembly binary data the header was decoded from
+    pub fn data_segments<'a>(&self, bytes: &'a [u8]) -> Result<DataSegments<'a>> {
+        Ok(DataSegments(VecReader::new(
+            self.section_view(bytes, SectionId::Data)?,
+        )?))
+    }
+}
+
+/// Reader of the entries of a section holding a vector
+#[derive(Debug)]
+struct VecReader<'a> {
+    section:   Option<SliceView<'a>>,
+    bytes:     &'a [u8],
+    offset:    usize,
+    remaining: u32,
+}
+
+impl<'a> VecReader<'a> {
+    fn new(section: Option<SliceView<'a>>) -> Result<Self> {
+        let bytes = match &section {
+            Some(section) => section.data()?,
+            None => &[],
+        };
+        let (remaining, offset) = match section {
+            Some(_) => read_leb128_u32(bytes, 0)?,
+            None => (0, 0),
+        };
+        Ok(Self {
+            section,
+            bytes,
+            offset,
+            remaining,
+        })
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        let (value, len) = read_leb128_u32(self.bytes, self.offset)?;
+        self.offset += len;
+        Ok(value)
+    }
+
+    /// View of the `len` bytes at the current offset, moving past them
+    fn take(&mut self, len: usize) -> Result<SliceView<'a>> {
+        let section = self.section.as_ref().ok_or_else(|| {
+            create_error(NoAllocErrorCode::BoundsCheckFailed, "Section is missing")
+        })?;
+        let view = section.view(self.offset, len)?;
+        self.offset += len;
+        Ok(view)
+    }
+
+    /// View of the constant expression at the current offset, moving past it
+    fn take_const_expr(&mut self) -> Result<SliceView<'a>> {
+        let len = const_expr_len(&self.bytes[self.offset..])?;
+        self.take(len)
+    }
+
+    /// Count down the entries, failing the iteration for good on an error
+    fn next_entry<T>(&mut self, read: impl FnOnce(&mut Self) -> Result<T>) -> Option<Result<T>> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let entry = read(self);
+        if entry.is_err() {
+            self.remaining = 0;
+        }
+        Some(entry)
+    }
+}
+
+/// Iterator over the function bodies of a module, see
+/// [`WasmModuleHeader::function_bodies`]
+///
+/// Each body, locals included, is a view of the module bytes.
+#[derive(Debug)]
+pub struct FunctionBodies<'a>(VecReader<'a>);
+
+impl<'a> Iterator for FunctionBodies<'a> {
+    type Item = Result<SliceView<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next_entry(|reader| {
+            let size = reader.read_u32()?;
+            reader.take(size as usize)
+        })
+    }
+}
+
+/// A data segment whose contents are borrowed from the module bytes
+#[derive(Debug, Clone, Copy)]
+pub struct DataSegmentView<'a> {
+    /// Memory index and offset expression of an active segment, `None` for a
+    /// passive one
+    pub active: Option<(u32, SliceView<'a>)>,
+    /// Bytes the segment initializes memory with
+    pub init:   SliceView<'a>,
+}
+
+/// Iterator over the data segments of a module, see
+/// [`WasmModuleHeader::data_segments`]
+#[derive(Debug)]
+pub struct DataSegments<'a>(VecReader<'a>);
+
+impl<'a> Iterator for DataSegments<'a> {
+    type Item = Result<DataSegmentView<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next_entry(|reader| {
+            let active = match reader.read_u32()? {
+                0 => Some((0, reader.take_const_expr()?)),
+                1 => None,
+                2 => {
+                    let memory = reader.read_u32()?;
+                    Some((memory, reader.take_const_expr()?))
+                },
+                _ => {
+                    return Err(create_error(
+                        NoAllocErrorCode::ValidationError,
+                        "Invalid data segment flags",
+                    ))
+                },
+            };
+            let size = reader.read_u32()?;
+            Ok(DataSegmentView {
+                active,
+                init: reader.take(size as usize)?,
+            })
+        })
+    }
+}
+
+/// Length of the constant expression at the start of `bytes`, including its
+/// `end`
+fn const_expr_len(bytes: &[u8]) -> Result<usize> {
+    let truncated = || {
+        create_error(
+            NoAllocErrorCode::BoundsCheckFailed,
+            "Truncated constant expression",
+        )
+    };
+    let skip_leb = |offset: usize| -> Result<usize> {
+        let len = bytes[offset..].iter().position(|byte| byte & 0x80 == 0).ok_or_else(truncated)?;
+        Ok(offset + len + 1)
+    };
+    let mut offset = 0;
+    loop {
+        let opcode = *bytes.get(offset).ok_or_else(truncated)?;
+        offset += 1;
+        offset = match opcode {
+            // end
+            0x0B => return Ok(offset),
+            // i32.const, i64.const, global.get, ref.null, ref.func
+            0x41 | 0x42 | 0x23 | 0xD0 | 0xD2 => skip_leb(offset)?,
+            // f32.const, f64.const
+            0x43 => offset + 4,
+            0x44 => offset + 8,
+            // Extended constant arithmetic
+            0x6A | 0x6B | 0x6C | 0x7C | 0x7D | 0x7E => offset,
+            // v128.const
+            0xFD => skip_leb(offset)? + 16,
+            _ => {
+                return Err(create_error(
+                    NoAllocErrorCode::ValidationError,
+                    "Unsupported constant expression",
+                ))
+            },
+        };
+    }
 }
 
 impl Default for WasmModuleHeader {
@@ -763,4 +995,36 @@ fn test_validate_module_no_alloc() {
         let result = validate_module_no_alloc(&MINIMAL_MODULE, ValidatorType::Basic);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_section_views_borrow_module_bytes() {
+        #[rustfmt::skip]
+        let module = [
+            0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00,
+            // Code section: one body returning nothing but an i32.const
+            0x0A, 0x06, 0x01, 0x04, 0x00, 0x41, 0x00, 0x0B,
+            // Data section: an active and a passive segment
+            0x0B, 0x0B, 0x02,
+            0x00, 0x41, 0x08, 0x0B, 0x02, 0xAA, 0xBB,
+            0x01, 0x01, 0xCC,
+        ];
+        let header = decode_module_header_simple(&module).unwrap();
+
+        let bodies: Vec<_> = header.function_bodies(&module).unwrap().collect();
+        assert_eq!(bodies.len(), 1);
+        let body = bodies[0].as_ref().unwrap();
+        assert_eq!(body.offset(), 12);
+        assert_eq!(body.data().unwrap(), &[0x00, 0x41, 0x00, 0x0B]);
+
+        let mut segments = header.data_segments(&module).unwrap();
+        let active = segments.next().unwrap().unwrap();
+        let (memory, offset) = active.active.unwrap();
+        assert_eq!(memory, 0);
+        assert_eq!(offset.data().unwrap(), &[0x41, 0x08, 0x0B]);
+        assert_eq!(active.init.data().unwrap(), &[0xAA, 0xBB]);
+        let passive = segments.next().unwrap().unwrap();
+        assert!(passive.active.is_none());
+        assert_eq!(passive.init.data().unwrap(), &[0xCC]);
+        assert!(segments.next().is_none());
+    }
 }
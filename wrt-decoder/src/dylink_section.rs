@@ -0,0 +1,346 @@
+//! WebAssembly Dynamic Linking Custom Section Parser
+//!
+//! This module requires the `std` feature.
+//!
+//! This module implements parsing for the "dylink.0" custom section emitted by
+//! Emscripten and `clang -shared` for side modules, as described by the
+//! WebAssembly tool conventions for dynamic linking. The section must be the
+//! first section of a side module and describes how much linear memory and
+//! table space the module needs and which other modules it depends on.
+//!
+//! # Custom Section Format
+//!
+//! The section is a sequence of subsections:
+//! ```text
+//! dylink_section ::= subsection*
+//! subsection ::= type:u8 payload_len:u32 payload
+//! mem_info (1) ::= memory_size:u32 memory_align:u32 table_size:u32 table_align:u32
+//! needed (2) ::= count:u32 name*
+//! export_info (3) ::= count:u32 (name flags:u32)*
+//! import_info (4) ::= count:u32 (module:name field:name flags:u32)*
+//! runtime_path (5) ::= count:u32 name*
+//! ```
+//!
+//! Alignments are stored as powers of two. Unknown subsections are skipped.
+
+use std::{
+    string::String,
+    vec::Vec,
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_format::binary::{
+    read_leb128_u32,
+    read_string,
+    read_u8,
+};
+
+/// Dynamic linking section name constant
+pub const DYLINK_SECTION_NAME: &str = "dylink.0";
+
+/// Subsection holding memory and table requirements
+pub const WASM_DYLINK_MEM_INFO: u8 = 0x1;
+/// Subsection listing the modules this module depends on
+pub const WASM_DYLINK_NEEDED: u8 = 0x2;
+/// Subsection holding additional symbol information for exports
+pub const WASM_DYLINK_EXPORT_INFO: u8 = 0x3;
+/// Subsection holding additional symbol information for imports
+pub const WASM_DYLINK_IMPORT_INFO: u8 = 0x4;
+/// Subsection listing search paths for needed modules
+pub const WASM_DYLINK_RUNTIME_PATH: u8 = 0x5;
+
+/// Symbol flag marking a weak binding
+pub const WASM_SYMBOL_BINDING_WEAK: u32 = 0x1;
+/// Symbol flag marking a thread-local symbol
+pub const WASM_SYMBOL_TLS: u32 = 0x100;
+
+/// Memory and table requirements of a side module
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DylinkMemInfo {
+    /// Bytes of linear memory the module's data needs
+    pub memory_size:  u32,
+    /// Required alignment of the memory region, as a power of two
+    pub memory_align: u32,
+    /// Number of table slots the module's element segments need
+    pub table_size:   u32,
+    /// Required alignment of the table region, as a power of two
+    pub table_align:  u32,
+}
+
+impl DylinkMemInfo {
+    /// Required memory alignment in bytes
+    pub fn memory_alignment(&self) -> Result<u32> {
+        alignment(self.memory_align)
+    }
+
+    /// Required table alignment in slots
+    pub fn table_alignment(&self) -> Result<u32> {
+        alignment(self.table_align)
+    }
+}
+
+/// Additional information about an exported symbol
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DylinkExportInfo {
+    /// Export name
+    pub name:  String,
+    /// Symbol flags (`WASM_SYMBOL_*`)
+    pub flags: u32,
+}
+
+/// Additional information about an imported symbol
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DylinkImportInfo {
+    /// Import module name
+    pub module: String,
+    /// Import field name
+    pub field:  String,
+    /// Symbol flags (`WASM_SYMBOL_*`)
+    pub flags:  u32,
+}
+
+impl DylinkImportInfo {
+    /// Whether the import may remain unresolved
+    pub fn is_weak(&self) -> bool {
+        self.flags & WASM_SYMBOL_BINDING_WEAK != 0
+    }
+}
+
+/// Parsed "dylink.0" section
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DylinkSection {
+    /// Memory and table requirements
+    pub mem_info:      DylinkMemInfo,
+    /// Modules that must be loaded before this one
+    pub needed:        Vec<String>,
+    /// Symbol information for exports
+    pub export_info:   Vec<DylinkExportInfo>,
+    /// Symbol information for imports
+    pub import_info:   Vec<DylinkImportInfo>,
+    /// Search paths for needed modules
+    pub runtime_paths: Vec<String>,
+}
+
+impl DylinkSection {
+    /// Whether the import `module`.`field` is declared weak
+    pub fn is_weak_import(&self, module: &str, field: &str) -> bool {
+        self.import_info
+            .iter()
+            .any(|info| info.module == module && info.field == field && info.is_weak())
+    }
+
+    /// Whether the export `name` is a thread-local symbol
+    pub fn is_tls_export(&self, name: &str) -> bool {
+        self.export_info
+            .iter()
+            .any(|info| info.name == name && info.flags & WASM_SYMBOL_TLS != 0)
+    }
+}
+
+fn alignment(log2: u32) -> Result<u32> {
+    1u32.checked_shl(log2)
+        .ok_or_else(|| Error::parse_error("Dylink alignment exceeds 32 bits"))
+}
+
+fn read_name(data: &[u8], offset: &mut usize) -> Result<String> {
+    let (bytes, consumed) = read_string(data, *offset)?;
+    *offset += consumed;
+    core::str::from_utf8(bytes)
+        .map(String::from)
+        .map_err(|_| Error::parse_error("Invalid UTF-8 in dylink section name"))
+}
+
+fn read_u32(data: &[u8], offset: &mut usize) -> Result<u32> {
+    let (value, consumed) = read_leb128_u32(data, *offset)?;
+    *offset += consumed;
+    Ok(value)
+}
+
+fn read_names(data: &[u8], offset: &mut usize) -> Result<Vec<String>> {
+    let count = read_u32(data, offset)?;
+    (0..count).map(|_| read_name(data, offset)).collect()
+}
+
+/// Parse the dynamic linking custom section from binary data
+pub fn parse_dylink_section(data: &[u8]) -> Result<DylinkSection> {
+    let mut offset = 0;
+    let mut section = DylinkSection::default();
+
+    while offset < data.len() {
+        let (kind, next) = read_u8(data, offset)?;
+        offset = next;
+        let payload_len = read_u32(data, &mut offset)? as usize;
+        let end = offset
+            .checked_add(payload_len)
+            .filter(|end| *end <= data.len())
+            .ok_or_else(|| Error::parse_error("Dylink subsection exceeds section size"))?;
+        let payload = &data[..end];
+
+        match kind {
+            WASM_DYLINK_MEM_INFO => {
+                section.mem_info = DylinkMemInfo {
+                    memory_size:  read_u32(payload, &mut offset)?,
+                    memory_align: read_u32(payload, &mut offset)?,
+                    table_size:   read_u32(payload, &mut offset)?,
+                    table_align:  read_u32(payload, &mut offset)?,
+                };
+            },
+            WASM_DYLINK_NEEDED => section.needed = read_names(payload, &mut offset)?,
+            WASM_DYLINK_EXPORT_INFO => {
+                let count = read_u32(payload, &mut offset)?;
+                for _ in 0..count {
+                    let name = read_name(payload, &mut offset)?;
+                    let flags = read_u32(payload, &mut offset)?;
+                    section.export_info.push(DylinkExportInfo { name, flags });
+                }
+            },
+            WASM_DYLINK_IMPORT_INFO => {
+                let count = read_u32(payload, &mut offset)?;
+                for _ in 0..count {
+                    let module = read_name(payload, &mut offset)?;
+                    let field = read_name(payload, &mut offset)?;
+                    let flags = read_u32(payload, &mut offset)?;
+                    section.import_info.push(DylinkImportInfo {
+                        module,
+                        field,
+                        flags,
+                    });
+                }
+            },
+            WASM_DYLINK_RUNTIME_PATH => section.runtime_paths = read_names(payload, &mut offset)?,
+            // Subsections added by later revisions of the convention
+            _ => offset = end,
+        }
+
+        if offset != end {
+            return Err(Error::parse_error("Dylink subsection size mismatch"));
+        }
+    }
+
+    Ok(section)
+}
+
+/// Read the dynamic linking section of a module binary
+///
+/// The convention requires "dylink.0" to be the first section of a side
+/// module, so only that section is inspected. Returns `None` for modules that
+/// are not side modules.
+pub fn read_dylink_section(binary: &[u8]) -> Result<Option<DylinkSection>> {
+    const HEADER_SIZE: usize = 8;
+
+    if binary.len() < HEADER_SIZE || binary[..4] != wrt_format::binary::WASM_MAGIC {
+        return Err(Error::parse_error("Invalid WebAssembly module header"));
+    }
+
+    let mut offset = HEADER_SIZE;
+    if offset == binary.len() || binary[offset] != wrt_format::binary::CUSTOM_SECTION_ID {
+        return Ok(None);
+    }
+    offset += 1;
+
+    let size = read_u32(binary, &mut offset)? as usize;
+    let end = offset
+        .checked_add(size)
+        .filter(|end| *end <= binary.len())
+        .ok_or_else(|| Error::parse_error("Custom section exceeds module size"))?;
+    let contents = &binary[..end];
+
+    if read_name(contents, &mut offset)? != DYLINK_SECTION_NAME {
+        return Ok(None);
+    }
+
+    parse_dylink_section(&contents[offset..]).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn name(value: &str) -> Vec<u8> {
+        let mut bytes = vec![value.len() as u8];
+        bytes.extend_from_slice(value.as_bytes());
+        bytes
+    }
+
+    fn subsection(kind: u8, payload: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![kind, payload.len() as u8];
+        bytes.extend_from_slice(payload);
+        bytes
+    }
+
+    fn side_module_section() -> Vec<u8> {
+        let mut data = subsection(WASM_DYLINK_MEM_INFO, &[0x90, 0x01, 0x03, 0x02, 0x00]);
+
+        let mut needed = vec![0x01];
+        needed.extend(name("libc.so"));
+        data.extend(subsection(WASM_DYLINK_NEEDED, &needed));
+
+        let mut imports = vec![0x01];
+        imports.extend(name("GOT.mem"));
+        imports.extend(name("errno"));
+        imports.push(WASM_SYMBOL_BINDING_WEAK as u8);
+        data.extend(subsection(WASM_DYLINK_IMPORT_INFO, &imports));
+
+        // Unknown subsection
+        data.extend(subsection(0x7f, &[1, 2, 3]));
+        data
+    }
+
+    #[test]
+    fn test_parse_dylink_section() {
+        let section = parse_dylink_section(&side_module_section()).unwrap();
+
+        assert_eq!(
+            section.mem_info,
+            DylinkMemInfo {
+                memory_size:  144,
+                memory_align: 3,
+                table_size:   2,
+                table_align:  0,
+            }
+        );
+        assert_eq!(section.mem_info.memory_alignment().unwrap(), 8);
+        assert_eq!(section.mem_info.table_alignment().unwrap(), 1);
+        assert_eq!(section.needed, vec!["libc.so".to_string()]);
+        assert!(section.is_weak_import("GOT.mem", "errno"));
+        assert!(!section.is_weak_import("GOT.func", "errno"));
+        assert!(section.export_info.is_empty());
+    }
+
+    #[test]
+    fn test_parse_malformed_data() {
+        // Payload length beyond the end of the section
+        assert!(parse_dylink_section(&[WASM_DYLINK_MEM_INFO, 0x08, 0x00]).is_err());
+
+        // Payload length shorter than its contents
+        assert!(parse_dylink_section(&subsection(WASM_DYLINK_MEM_INFO, &[0x10, 0x00])).is_err());
+
+        // Alignment that does not fit
+        let mem_info = DylinkMemInfo {
+            memory_align: 40,
+            ..DylinkMemInfo::default()
+        };
+        assert!(mem_info.memory_alignment().is_err());
+    }
+
+    #[test]
+    fn test_read_dylink_section() {
+        let mut contents = name(DYLINK_SECTION_NAME);
+        contents.extend(side_module_section());
+
+        let mut binary = vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x00];
+        binary.push(contents.len() as u8);
+        binary.extend(contents);
+
+        let section = read_dylink_section(&binary).unwrap().unwrap();
+        assert_eq!(section.mem_info.memory_size, 144);
+
+        // Main modules have no dylink section
+        let binary = [0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+        assert_eq!(read_dylink_section(&binary).unwrap(), None);
+        assert!(read_dylink_section(&binary[..4]).is_err());
+    }
+}
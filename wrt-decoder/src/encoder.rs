@@ -0,0 +1,567 @@
+//! Encoding modules back to the binary format
+//!
+//! This module requires the `std` feature.
+//!
+//! [`encode_module`] writes a module as produced by the decoder back to a
+//! WebAssembly binary. Sections are written in the order the specification
+//! requires and empty sections are omitted. The module does not record where
+//! its custom sections stood, so they all follow the core sections.
+//!
+//! Function bodies decoded from a binary keep their local declarations in
+//! `code` and have no `locals`; they are written unchanged. For a function
+//! with `locals`, `code` holds only the expression and the locals are
+//! declared ahead of it.
+//!
+//! A data count section is written whenever the module has data segments,
+//! so that `memory.init` and `data.drop` stay valid. Element and data
+//! segments are written in the shortest of their equivalent encodings, so a
+//! binary using those encodings and a data count section decodes and encodes
+//! back to the same bytes.
+
+use std::vec::Vec;
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_format::{
+    binary::{
+        CODE_SECTION_ID,
+        CUSTOM_SECTION_ID,
+        DATA_COUNT_SECTION_ID,
+        DATA_SECTION_ID,
+        ELEMENT_SECTION_ID,
+        EXPORT_SECTION_ID,
+        FUNCTION_SECTION_ID,
+        GLOBAL_SECTION_ID,
+        IMPORT_SECTION_ID,
+        MEMORY_SECTION_ID,
+        START_SECTION_ID,
+        TABLE_SECTION_ID,
+        TYPE_SECTION_ID,
+        WASM_MAGIC,
+        WASM_VERSION,
+    },
+    module::{
+        ExportKind,
+        Function,
+        ImportDesc,
+        Module as WrtModule,
+    },
+    pure_format_types::{
+        PureDataMode,
+        PureDataSegment,
+        PureElementInit,
+        PureElementMode,
+        PureElementSegment,
+    },
+    types::FormatGlobalType,
+    write_leb128_u32,
+    write_string,
+};
+use wrt_foundation::{
+    types::{
+        Limits,
+        MemoryType,
+        RefType,
+        TableType,
+    },
+    ValueType,
+};
+
+fn length(len: usize) -> Result<u32> {
+    u32::try_from(len).map_err(|_| Error::validation_error("Module entry too large to encode"))
+}
+
+fn write_vec_len(data: &mut Vec<u8>, len: usize) -> Result<()> {
+    data.extend(write_leb128_u32(length(len)?));
+    Ok(())
+}
+
+fn write_section(binary: &mut Vec<u8>, id: u8, payload: &[u8]) -> Result<()> {
+    binary.push(id);
+    write_vec_len(binary, payload.len())?;
+    binary.extend_from_slice(payload);
+    Ok(())
+}
+
+fn value_type(value_type: ValueType) -> Result<u8> {
+    match value_type {
+        ValueType::StructRef(_) | ValueType::ArrayRef(_) => Err(Error::validation_error(
+            "Cannot encode references to GC types",
+        )),
+        _ => Ok(value_type.to_binary()),
+    }
+}
+
+fn ref_type(ref_type: RefType) -> u8 {
+    match ref_type {
+        RefType::Funcref => 0x70,
+        RefType::Externref => 0x6F,
+    }
+}
+
+fn write_value_types(data: &mut Vec<u8>, types: &[ValueType]) -> Result<()> {
+    write_vec_len(data, types.len())?;
+    for ty in types {
+        data.push(value_type(*ty)?);
+    }
+    Ok(())
+}
+
+fn write_limits(data: &mut Vec<u8>, limits: &Limits, shared: bool) {
+    let flags = u8::from(limits.max.is_some()) | (u8::from(shared) << 1);
+    data.push(flags);
+    data.extend(write_leb128_u32(limits.min));
+    if let Some(max) = limits.max {
+        data.extend(write_leb128_u32(max));
+    }
+}
+
+fn write_table_type(data: &mut Vec<u8>, table: &TableType) {
+    data.push(ref_type(table.element_type));
+    write_limits(data, &table.limits, false);
+}
+
+fn write_memory_type(data: &mut Vec<u8>, memory: &MemoryType) {
+    write_limits(data, &memory.limits, memory.shared);
+}
+
+fn write_global_type(data: &mut Vec<u8>, global_type: &FormatGlobalType) -> Result<()> {
+    data.push(value_type(global_type.value_type)?);
+    data.push(u8::from(global_type.mutable));
+    Ok(())
+}
+
+/// Write a constant expression, which must end with `end`
+fn write_const_expr(data: &mut Vec<u8>, expr: &[u8]) -> Result<()> {
+    if expr.last() != Some(&0x0B) {
+        return Err(Error::validation_error(
+            "Constant expression does not end with end",
+        ));
+    }
+    data.extend_from_slice(expr);
+    Ok(())
+}
+
+fn write_function_indices(data: &mut Vec<u8>, indices: &[u32]) -> Result<()> {
+    write_vec_len(data, indices.len())?;
+    for index in indices {
+        data.extend(write_leb128_u32(*index));
+    }
+    Ok(())
+}
+
+fn encode_type_section(module: &WrtModule) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    write_vec_len(&mut data, module.types.len())?;
+    for func_type in &module.types {
+        data.push(0x60);
+        write_value_types(&mut data, &func_type.params)?;
+        write_value_types(&mut data, &func_type.results)?;
+    }
+    Ok(data)
+}
+
+fn encode_import_section(module: &WrtModule) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    write_vec_len(&mut data, module.imports.len())?;
+    for import in &module.imports {
+        data.extend(write_string(&import.module));
+        data.extend(write_string(&import.name));
+        match &import.desc {
+            ImportDesc::Function(type_idx) => {
+                data.push(0x00);
+                data.extend(write_leb128_u32(*type_idx));
+            },
+            ImportDesc::Table(table) => {
+                data.push(0x01);
+                write_table_type(&mut data, table);
+            },
+            ImportDesc::Memory(memory) => {
+                data.push(0x02);
+                write_memory_type(&mut data, memory);
+            },
+            ImportDesc::Global(global_type) => {
+                data.push(0x03);
+                write_global_type(&mut data, global_type)?;
+            },
+            ImportDesc::Tag(type_idx) => {
+                data.extend([0x04, 0x00]);
+                data.extend(write_leb128_u32(*type_idx));
+            },
+        }
+    }
+    Ok(data)
+}
+
+fn encode_function_section(module: &WrtModule) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    write_vec_len(&mut data, module.functions.len())?;
+    for function in &module.functions {
+        data.extend(write_leb128_u32(function.type_idx));
+    }
+    Ok(data)
+}
+
+fn encode_table_section(module: &WrtModule) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    write_vec_len(&mut data, module.tables.len())?;
+    for table in &module.tables {
+        write_table_type(&mut data, table);
+    }
+    Ok(data)
+}
+
+fn encode_memory_section(module: &WrtModule) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    write_vec_len(&mut data, module.memories.len())?;
+    for memory in &module.memories {
+        write_memory_type(&mut data, memory);
+    }
+    Ok(data)
+}
+
+fn encode_global_section(module: &WrtModule) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    write_vec_len(&mut data, module.globals.len())?;
+    for global in &module.globals {
+        write_global_type(&mut data, &global.global_type)?;
+        write_const_expr(&mut data, &global.init)?;
+    }
+    Ok(data)
+}
+
+fn encode_export_section(module: &WrtModule) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    write_vec_len(&mut data, module.exports.len())?;
+    for export in &module.exports {
+        data.extend(write_string(&export.name));
+        data.push(match export.kind {
+            ExportKind::Function => 0x00,
+            ExportKind::Table => 0x01,
+            ExportKind::Memory => 0x02,
+            ExportKind::Global => 0x03,
+            ExportKind::Tag => 0x04,
+        });
+        data.extend(write_leb128_u32(export.index));
+    }
+    Ok(data)
+}
+
+fn encode_element_segment(data: &mut Vec<u8>, element: &PureElementSegment) -> Result<()> {
+    let expressions = matches!(element.init_data, PureElementInit::ExpressionBytes(_));
+    let funcref = element.element_type == RefType::Funcref;
+    if !expressions && !funcref {
+        return Err(Error::validation_error(
+            "Element segment of function indices must hold function references",
+        ));
+    }
+
+    // Flags 0 and 4 leave out both the table index and the element type
+    let flags = match element.mode {
+        PureElementMode::Active { table_index, .. } => {
+            if table_index == 0 && funcref {
+                0x00
+            } else {
+                0x02
+            }
+        },
+        PureElementMode::Passive => 0x01,
+        PureElementMode::Declared => 0x03,
+    } | if expressions { 0x04 } else { 0x00 };
+    data.extend(write_leb128_u32(flags));
+
+    if let PureElementMode::Active { table_index, .. } = element.mode {
+        if flags & 0x02 != 0 {
+            data.extend(write_leb128_u32(table_index));
+        }
+        write_const_expr(data, &element.offset_expr_bytes)?;
+    }
+    if flags & 0x03 != 0 {
+        // Element kind 0x00 stands for function references
+        data.push(if expressions { ref_type(element.element_type) } else { 0x00 });
+    }
+
+    match &element.init_data {
+        PureElementInit::FunctionIndices(indices) => write_function_indices(data, indices)?,
+        PureElementInit::ExpressionBytes(exprs) => {
+            write_vec_len(data, exprs.len())?;
+            for expr in exprs {
+                write_const_expr(data, expr)?;
+            }
+        },
+    }
+    Ok(())
+}
+
+fn encode_element_section(module: &WrtModule) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    write_vec_len(&mut data, module.elements.len())?;
+    for element in &module.elements {
+        encode_element_segment(&mut data, element)?;
+    }
+    Ok(data)
+}
+
+/// Write the local declarations of `function`, grouping runs of equal types
+fn write_locals(data: &mut Vec<u8>, function: &Function) -> Result<()> {
+    let mut groups: Vec<(u32, ValueType)> = Vec::new();
+    for local in &function.locals {
+        match groups.last_mut() {
+            Some((count, ty)) if ty == local => *count += 1,
+            _ => groups.push((1, *local)),
+        }
+    }
+
+    write_vec_len(data, groups.len())?;
+    for (count, ty) in groups {
+        data.extend(write_leb128_u32(count));
+        data.push(value_type(ty)?);
+    }
+    Ok(())
+}
+
+fn encode_code_section(module: &WrtModule) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    write_vec_len(&mut data, module.functions.len())?;
+    for function in &module.functions {
+        if function.locals.is_empty() {
+            write_vec_len(&mut data, function.code.len())?;
+            data.extend_from_slice(&function.code);
+        } else {
+            let mut body = Vec::new();
+            write_locals(&mut body, function)?;
+            body.extend_from_slice(&function.code);
+            write_vec_len(&mut data, body.len())?;
+            data.extend(body);
+        }
+    }
+    Ok(data)
+}
+
+fn encode_data_segment(data: &mut Vec<u8>, segment: &PureDataSegment) -> Result<()> {
+    match segment.mode {
+        PureDataMode::Active { memory_index, .. } => {
+            if memory_index == 0 {
+                data.push(0x00);
+            } else {
+                data.push(0x02);
+                data.extend(write_leb128_u32(memory_index));
+            }
+            write_const_expr(data, &segment.offset_expr_bytes)?;
+        },
+        PureDataMode::Passive => data.push(0x01),
+    }
+    write_vec_len(data, segment.data_bytes.len())?;
+    data.extend_from_slice(&segment.data_bytes);
+    Ok(())
+}
+
+fn encode_data_section(module: &WrtModule) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    write_vec_len(&mut data, module.data.len())?;
+    for segment in &module.data {
+        encode_data_segment(&mut data, segment)?;
+    }
+    Ok(data)
+}
+
+/// Encode a module as a WebAssembly binary
+///
+/// # Errors
+///
+/// Returns an error if the module holds something the binary format cannot
+/// express, such as a constant expression without `end` or an element
+/// segment of function indices for a table of external references.
+pub fn encode_module(module: &WrtModule) -> Result<Vec<u8>> {
+    let mut binary = Vec::new();
+    binary.extend_from_slice(&WASM_MAGIC);
+    binary.extend_from_slice(&WASM_VERSION);
+
+    if !module.types.is_empty() {
+        write_section(&mut binary, TYPE_SECTION_ID, &encode_type_section(module)?)?;
+    }
+    if !module.imports.is_empty() {
+        write_section(
+            &mut binary,
+            IMPORT_SECTION_ID,
+            &encode_import_section(module)?,
+        )?;
+    }
+    if !module.functions.is_empty() {
+        write_section(
+            &mut binary,
+            FUNCTION_SECTION_ID,
+            &encode_function_section(module)?,
+        )?;
+    }
+    if !module.tables.is_empty() {
+        write_section(
+            &mut binary,
+            TABLE_SECTION_ID,
+            &encode_table_section(module)?,
+        )?;
+    }
+    if !module.memories.is_empty() {
+        write_section(
+            &mut binary,
+            MEMORY_SECTION_ID,
+            &encode_memory_section(module)?,
+        )?;
+    }
+    if !module.globals.is_empty() {
+        write_section(
+            &mut binary,
+            GLOBAL_SECTION_ID,
+            &encode_global_section(module)?,
+        )?;
+    }
+    if !module.exports.is_empty() {
+        write_section(
+            &mut binary,
+            EXPORT_SECTION_ID,
+            &encode_export_section(module)?,
+        )?;
+    }
+    if let Some(start) = module.start {
+        write_section(&mut binary, START_SECTION_ID, &write_leb128_u32(start))?;
+    }
+    if !module.elements.is_empty() {
+        write_section(
+            &mut binary,
+            ELEMENT_SECTION_ID,
+            &encode_element_section(module)?,
+        )?;
+    }
+    if !module.data.is_empty() {
+        let count = write_leb128_u32(length(module.data.len())?);
+        write_section(&mut binary, DATA_COUNT_SECTION_ID, &count)?;
+    }
+    if !module.functions.is_empty() {
+        write_section(&mut binary, CODE_SECTION_ID, &encode_code_section(module)?)?;
+    }
+    if !module.data.is_empty() {
+        write_section(&mut binary, DATA_SECTION_ID, &encode_data_section(module)?)?;
+    }
+
+    for custom in &module.custom_sections {
+        let mut payload = write_string(&custom.name);
+        payload.extend_from_slice(&custom.data);
+        write_section(&mut binary, CUSTOM_SECTION_ID, &payload)?;
+    }
+
+    Ok(binary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        streaming_decoder::decode_module_streaming,
+        test_fixtures::ModuleBinary,
+    };
+
+    /// Module using every core section, in the encodings the encoder picks
+    fn sample_module() -> Vec<u8> {
+        let mut binary = ModuleBinary::new();
+        // (func (param i32) (result i32)), (func)
+        binary.section(1, &[0x02, 0x60, 0x01, 0x7F, 0x01, 0x7F, 0x60, 0x00, 0x00]);
+        let mut imports = vec![0x02];
+        imports.extend(write_string("env"));
+        imports.extend(write_string("log"));
+        imports.extend([0x00, 0x01]);
+        imports.extend(write_string("env"));
+        imports.extend(write_string("mem"));
+        imports.extend([0x02, 0x03, 0x01, 0x02]);
+        binary.section(2, &imports);
+        binary.section(3, &[0x02, 0x00, 0x01]);
+        binary.section(4, &[0x02, 0x70, 0x00, 0x02, 0x6F, 0x01, 0x00, 0x08]);
+        binary.section(5, &[0x01, 0x01, 0x01, 0x04]);
+        binary.section(
+            6,
+            &[
+                0x02, 0x7F, 0x01, 0x41, 0x2A, 0x0B, 0x7E, 0x00, 0x42, 0x7F, 0x0B,
+            ],
+        );
+        let mut exports = vec![0x02];
+        exports.extend(write_string("run"));
+        exports.extend([0x00, 0x01]);
+        exports.extend(write_string("memory"));
+        exports.extend([0x02, 0x01]);
+        binary.section(7, &exports);
+        binary.section(8, &[0x02]);
+        binary.section(
+            9,
+            &[
+                0x04, // four segments
+                0x00, 0x41, 0x00, 0x0B, 0x02, 0x01, 0x02, // active, table 0
+                0x01, 0x00, 0x01, 0x01, // passive
+                0x06, 0x01, 0x41, 0x01, 0x0B, 0x6F, 0x01, 0xD0, 0x6F, 0x0B, // table 1
+                0x03, 0x00, 0x01, 0x02, // declared
+            ],
+        );
+        binary.section(12, &[0x02]);
+        binary.section(
+            10,
+            &[
+                0x02, 0x06, 0x01, 0x01, 0x7F, 0x20, 0x00, 0x0B, 0x03, 0x00, 0x01, 0x0B,
+            ],
+        );
+        binary.section(
+            11,
+            &[
+                0x02, 0x00, 0x41, 0x10, 0x0B, 0x02, b'h', b'i', 0x01, 0x01, 0xFF,
+            ],
+        );
+        binary.custom("note", &[0x2A]);
+        binary.build()
+    }
+
+    #[test]
+    fn test_roundtrip_preserves_bytes() {
+        let binary = sample_module();
+        let module = decode_module_streaming(&binary).unwrap();
+        assert_eq!(module.tables.len(), 2);
+        assert_eq!(module.memories.len(), 1);
+        assert_eq!(module.globals[1].init, [0x42, 0x7F, 0x0B]);
+        assert_eq!(module.elements.len(), 4);
+        assert_eq!(module.data[1].data_bytes, [0xFF]);
+        assert_eq!(module.custom_sections[0].name, "note");
+
+        assert_eq!(encode_module(&module).unwrap(), binary);
+    }
+
+    #[test]
+    fn test_roundtrip_of_built_module() {
+        let mut module = decode_module_streaming(&sample_module()).unwrap();
+        module.functions[0].locals = vec![ValueType::I32, ValueType::I32, ValueType::F64];
+        module.functions[0].code = vec![0x20, 0x00, 0x0B];
+        // Externref segment for table 0 needs the explicit table index
+        module.elements[2].mode = PureElementMode::Active {
+            table_index:     0,
+            offset_expr_len: 3,
+        };
+
+        let encoded = encode_module(&module).unwrap();
+        let decoded = decode_module_streaming(&encoded).unwrap();
+        assert_eq!(
+            decoded.functions[0].code,
+            [0x02, 0x02, 0x7F, 0x01, 0x7C, 0x20, 0x00, 0x0B]
+        );
+        assert_eq!(decoded.elements, module.elements);
+        assert_eq!(decoded.data, module.data);
+        assert_eq!(encode_module(&decoded).unwrap(), encoded);
+    }
+
+    #[test]
+    fn test_encode_rejects_inexpressible_modules() {
+        let mut module = decode_module_streaming(&sample_module()).unwrap();
+        module.globals[0].init.pop();
+        assert!(encode_module(&module).is_err());
+
+        let mut module = decode_module_streaming(&sample_module()).unwrap();
+        module.elements[1].element_type = RefType::Externref;
+        assert!(encode_module(&module).is_err());
+    }
+}
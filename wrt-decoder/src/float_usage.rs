@@ -0,0 +1,454 @@
+//! Detection of floating-point use in module binaries
+//!
+//! Targets without an FPU run `f32` and `f64` code only through the
+//! `soft-float` build of the runtime. Embedders that would rather not run
+//! such code at all can refuse modules that use floating-point before
+//! instantiating them: [`uses_floats`] looks for float value types in the
+//! types, imports, globals and locals of a module binary, and for scalar and
+//! SIMD instructions operating on floats in its global initializers and
+//! function bodies.
+//!
+//! The scan works on the binary itself and needs no allocation, so it is
+//! available in every configuration.
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_format::binary::{
+    read_leb128_i64,
+    read_leb128_u32,
+    read_leb128_u64,
+};
+
+const TYPE_SECTION: u8 = 1;
+const IMPORT_SECTION: u8 = 2;
+const GLOBAL_SECTION: u8 = 6;
+const CODE_SECTION: u8 = 10;
+
+/// Whether the module `binary` uses `f32` or `f64` values
+///
+/// # Errors
+///
+/// Returns an error if the binary is malformed or contains instructions the
+/// scan does not know, in which case the absence of floats cannot be shown.
+pub fn uses_floats(binary: &[u8]) -> Result<bool> {
+    if binary.len() < 8 || binary[..4] != *b"\0asm" {
+        return Err(Error::parse_error("Not a WebAssembly module"));
+    }
+
+    let mut offset = 8;
+    while offset < binary.len() {
+        let id = binary[offset];
+        let (size, bytes_read) = read_leb128_u32(binary, offset + 1)?;
+        let start = offset + 1 + bytes_read;
+        let end = start
+            .checked_add(size as usize)
+            .filter(|end| *end <= binary.len())
+            .ok_or_else(|| Error::parse_error("Section extends beyond module"))?;
+        let section = &binary[start..end];
+
+        let floats = match id {
+            TYPE_SECTION => type_section_uses_floats(section)?,
+            IMPORT_SECTION => import_section_uses_floats(section)?,
+            GLOBAL_SECTION => global_section_uses_floats(section)?,
+            CODE_SECTION => code_section_uses_floats(section)?,
+            _ => false,
+        };
+        if floats {
+            return Ok(true);
+        }
+        offset = end;
+    }
+    Ok(false)
+}
+
+fn read_byte(data: &[u8], offset: usize) -> Result<u8> {
+    data.get(offset)
+        .copied()
+        .ok_or_else(|| Error::parse_error("Unexpected end of section"))
+}
+
+fn is_float_type(byte: u8) -> bool {
+    matches!(byte, 0x7D | 0x7C)
+}
+
+/// Skip a value type, returning whether it is a float type
+fn value_type(data: &[u8], offset: &mut usize) -> Result<bool> {
+    let byte = read_byte(data, *offset)?;
+    *offset += 1;
+    if matches!(byte, 0x63 | 0x64) {
+        // Typed reference, followed by its heap type
+        *offset += read_leb128_i64(data, *offset)?.1;
+    }
+    Ok(is_float_type(byte))
+}
+
+/// Skip a vector of value types, returning whether any is a float type
+fn value_types(data: &[u8], offset: &mut usize) -> Result<bool> {
+    let (count, bytes_read) = read_leb128_u32(data, *offset)?;
+    *offset += bytes_read;
+    let mut floats = false;
+    for _ in 0..count {
+        floats |= value_type(data, offset)?;
+    }
+    Ok(floats)
+}
+
+fn skip_u32(data: &[u8], offset: &mut usize) -> Result<()> {
+    *offset += read_leb128_u32(data, *offset)?.1;
+    Ok(())
+}
+
+fn skip_name(data: &[u8], offset: &mut usize) -> Result<()> {
+    let (len, bytes_read) = read_leb128_u32(data, *offset)?;
+    *offset += bytes_read + len as usize;
+    Ok(())
+}
+
+fn skip_limits(data: &[u8], offset: &mut usize) -> Result<()> {
+    let flags = read_byte(data, *offset)?;
+    *offset += 1;
+    *offset += read_leb128_u64(data, *offset)?.1;
+    if flags & 0x01 != 0 {
+        *offset += read_leb128_u64(data, *offset)?.1;
+    }
+    Ok(())
+}
+
+fn type_section_uses_floats(data: &[u8]) -> Result<bool> {
+    let (count, mut offset) = read_leb128_u32(data, 0)?;
+    for _ in 0..count {
+        if read_byte(data, offset)? != 0x60 {
+            return Err(Error::parse_error("Unsupported type form"));
+        }
+        offset += 1;
+        if value_types(data, &mut offset)? | value_types(data, &mut offset)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn import_section_uses_floats(data: &[u8]) -> Result<bool> {
+    let (count, mut offset) = read_leb128_u32(data, 0)?;
+    for _ in 0..count {
+        skip_name(data, &mut offset)?;
+        skip_name(data, &mut offset)?;
+        let kind = read_byte(data, offset)?;
+        offset += 1;
+        match kind {
+            0x00 => skip_u32(data, &mut offset)?,
+            0x01 => {
+                value_type(data, &mut offset)?;
+                skip_limits(data, &mut offset)?;
+            },
+            0x02 => skip_limits(data, &mut offset)?,
+            0x03 => {
+                if value_type(data, &mut offset)? {
+                    return Ok(true);
+                }
+                offset += 1;
+            },
+            0x04 => {
+                offset += 1;
+                skip_u32(data, &mut offset)?;
+            },
+            _ => return Err(Error::parse_error("Invalid import kind")),
+        }
+    }
+    Ok(false)
+}
+
+fn global_section_uses_floats(data: &[u8]) -> Result<bool> {
+    let (count, mut offset) = read_leb128_u32(data, 0)?;
+    for _ in 0..count {
+        if value_type(data, &mut offset)? {
+            return Ok(true);
+        }
+        offset += 1;
+        match scan_expr(data, offset)? {
+            Scan::Float => return Ok(true),
+            Scan::End(end) => offset = end,
+        }
+    }
+    Ok(false)
+}
+
+fn code_section_uses_floats(data: &[u8]) -> Result<bool> {
+    let (count, mut offset) = read_leb128_u32(data, 0)?;
+    for _ in 0..count {
+        let (size, bytes_read) = read_leb128_u32(data, offset)?;
+        let body_start = offset + bytes_read;
+        let body = data
+            .get(body_start..body_start + size as usize)
+            .ok_or_else(|| Error::parse_error("Function body extends beyond section"))?;
+
+        let (groups, mut body_offset) = read_leb128_u32(body, 0)?;
+        for _ in 0..groups {
+            skip_u32(body, &mut body_offset)?;
+            if value_type(body, &mut body_offset)? {
+                return Ok(true);
+            }
+        }
+        if let Scan::Float = scan_expr(body, body_offset)? {
+            return Ok(true);
+        }
+        offset = body_start + size as usize;
+    }
+    Ok(false)
+}
+
+/// Outcome of scanning an expression
+enum Scan {
+    /// An instruction operates on floats
+    Float,
+    /// The expression is float-free and ends before the given offset
+    End(usize),
+}
+
+fn skip_memarg(data: &[u8], offset: &mut usize) -> Result<()> {
+    let (align, bytes_read) = read_leb128_u32(data, *offset)?;
+    *offset += bytes_read;
+    if align & 0x40 != 0 {
+        // Multi-memory: explicit memory index
+        skip_u32(data, offset)?;
+    }
+    *offset += read_leb128_u64(data, *offset)?.1;
+    Ok(())
+}
+
+/// Whether a single-byte opcode operates on floats
+fn is_float_opcode(opcode: u8) -> bool {
+    matches!(
+        opcode,
+        // Loads, stores and constants
+        0x2A | 0x2B | 0x38 | 0x39 | 0x43 | 0x44
+        // Comparisons
+        | 0x5B..=0x66
+        // Arithmetic
+        | 0x8B..=0xA6
+        // Conversions from and to floats
+        | 0xA8..=0xAB
+        | 0xAE..=0xBF
+    )
+}
+
+/// Whether an `0xFD`-prefixed SIMD instruction operates on float lanes
+fn is_float_simd_opcode(opcode: u32) -> bool {
+    matches!(
+        opcode,
+        // Splats and lane accesses
+        0x13 | 0x14 | 0x1F..=0x22
+        // Comparisons
+        | 0x41..=0x4C
+        // Demotion, promotion and rounding
+        | 0x5E | 0x5F | 0x67..=0x6A | 0x74 | 0x75 | 0x7A | 0x94
+        // Arithmetic and conversions
+        | 0xE0..=0xFF
+        // Relaxed truncation, multiply-add, min and max
+        | 0x101..=0x108 | 0x10D..=0x110
+    )
+}
+
+/// Scan the instructions of an expression starting at `offset`
+fn scan_expr(data: &[u8], mut offset: usize) -> Result<Scan> {
+    let mut depth = 0u32;
+    loop {
+        let opcode = read_byte(data, offset)?;
+        offset += 1;
+        if is_float_opcode(opcode) {
+            return Ok(Scan::Float);
+        }
+
+        match opcode {
+            // Blocks with a block type
+            0x02 | 0x03 | 0x04 | 0x06 => {
+                if is_float_type(read_byte(data, offset)?) {
+                    return Ok(Scan::Float);
+                }
+                offset += read_leb128_i64(data, offset)?.1;
+                depth += 1;
+            },
+            // try_table with its catch clauses
+            0x1F => {
+                if is_float_type(read_byte(data, offset)?) {
+                    return Ok(Scan::Float);
+                }
+                offset += read_leb128_i64(data, offset)?.1;
+                let (count, bytes_read) = read_leb128_u32(data, offset)?;
+                offset += bytes_read;
+                for _ in 0..count {
+                    let kind = read_byte(data, offset)?;
+                    offset += 1;
+                    if kind < 0x02 {
+                        skip_u32(data, &mut offset)?;
+                    }
+                    skip_u32(data, &mut offset)?;
+                }
+                depth += 1;
+            },
+            0x0B | 0x18 => {
+                if opcode == 0x18 {
+                    // delegate closes its try block
+                    skip_u32(data, &mut offset)?;
+                }
+                if depth == 0 {
+                    return Ok(Scan::End(offset));
+                }
+                depth -= 1;
+            },
+            0x00 | 0x01 | 0x05 | 0x0A | 0x0F | 0x19 | 0x1A | 0x1B | 0xD1 | 0xD3 | 0xD4 => {},
+            0x45..=0xC4 => {},
+            0x07..=0x09 | 0x0C | 0x0D | 0x10 | 0x12 | 0x14 | 0x15 | 0x20..=0x26 | 0x3F | 0x40 => {
+                skip_u32(data, &mut offset)?;
+            },
+            0xD2 | 0xD5 | 0xD6 => skip_u32(data, &mut offset)?,
+            0x11 | 0x13 => {
+                skip_u32(data, &mut offset)?;
+                skip_u32(data, &mut offset)?;
+            },
+            0x0E => {
+                let (count, bytes_read) = read_leb128_u32(data, offset)?;
+                offset += bytes_read;
+                for _ in 0..=count {
+                    skip_u32(data, &mut offset)?;
+                }
+            },
+            0x1C => {
+                if value_types(data, &mut offset)? {
+                    return Ok(Scan::Float);
+                }
+            },
+            0x28..=0x3E => skip_memarg(data, &mut offset)?,
+            0x41 | 0x42 | 0xD0 => offset += read_leb128_i64(data, offset)?.1,
+            0xFC => {
+                let (sub, bytes_read) = read_leb128_u32(data, offset)?;
+                offset += bytes_read;
+                match sub {
+                    // Saturating truncation of floats
+                    0..=7 => return Ok(Scan::Float),
+                    8 | 10 | 12 | 14 => {
+                        skip_u32(data, &mut offset)?;
+                        skip_u32(data, &mut offset)?;
+                    },
+                    9 | 11 | 13 | 15..=17 => skip_u32(data, &mut offset)?,
+                    _ => return Err(Error::parse_error("Unknown 0xFC instruction")),
+                }
+            },
+            0xFD => {
+                let (sub, bytes_read) = read_leb128_u32(data, offset)?;
+                offset += bytes_read;
+                if is_float_simd_opcode(sub) {
+                    return Ok(Scan::Float);
+                }
+                match sub {
+                    0x00..=0x0B | 0x5C | 0x5D => skip_memarg(data, &mut offset)?,
+                    0x0C | 0x0D => offset += 16,
+                    0x15..=0x1E => offset += 1,
+                    0x54..=0x5B => {
+                        skip_memarg(data, &mut offset)?;
+                        offset += 1;
+                    },
+                    // The remaining instructions, relaxed SIMD included, take no
+                    // immediates
+                    0x0E..=0x113 => {},
+                    _ => return Err(Error::parse_error("Unknown SIMD instruction")),
+                }
+            },
+            0xFE => {
+                let (sub, bytes_read) = read_leb128_u32(data, offset)?;
+                offset += bytes_read;
+                if sub == 0x03 {
+                    // atomic.fence
+                    offset += 1;
+                } else {
+                    skip_memarg(data, &mut offset)?;
+                }
+            },
+            _ => return Err(Error::parse_error("Unknown opcode")),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    fn module(sections: &[(u8, &[u8])]) -> Vec<u8> {
+        let mut binary = b"\0asm\x01\0\0\0".to_vec();
+        for (id, payload) in sections {
+            binary.push(*id);
+            binary.push(payload.len() as u8);
+            binary.extend_from_slice(payload);
+        }
+        binary
+    }
+
+    /// Type section with a single `[] -> [result]` function type
+    fn types(result: u8) -> [u8; 5] {
+        [1, 0x60, 0, 1, result]
+    }
+
+    /// Code section with a single body of `locals` and `instructions`
+    fn code(locals: &[u8], instructions: &[u8]) -> Vec<u8> {
+        let mut body = locals.to_vec();
+        body.extend_from_slice(instructions);
+        let mut section = vec![1, body.len() as u8];
+        section.extend_from_slice(&body);
+        section
+    }
+
+    #[test]
+    fn test_integer_module_is_float_free() {
+        // i32.const 1; i32.const 2; i32.add; block; i64.const -1; drop; end
+        let body = code(
+            &[1, 1, 0x7F],
+            &[
+                0x41, 1, 0x41, 2, 0x6A, 0x02, 0x40, 0x42, 0x7F, 0x1A, 0x0B, 0x0B,
+            ],
+        );
+        let binary = module(&[(1, &types(0x7F)), (3, &[1, 0]), (10, &body)]);
+        assert!(!uses_floats(&binary).unwrap());
+    }
+
+    #[test]
+    fn test_float_uses_are_found() {
+        // f32 result type
+        let binary = module(&[(1, &types(0x7D))]);
+        assert!(uses_floats(&binary).unwrap());
+
+        // f64 local
+        let body = code(&[1, 1, 0x7C], &[0x41, 0, 0x0B]);
+        assert!(uses_floats(&module(&[(10, &body)])).unwrap());
+
+        // f32.const inside a block, after an immediate that contains 0x43
+        let body = code(
+            &[0],
+            &[
+                0x02, 0x40, 0x41, 0x43, 0x1A, 0x43, 0, 0, 0x80, 0x3F, 0x1A, 0x0B, 0x0B,
+            ],
+        );
+        assert!(uses_floats(&module(&[(10, &body)])).unwrap());
+
+        // i32.trunc_sat_f32_s
+        let body = code(&[0], &[0x41, 0, 0xFC, 0, 0x1A, 0x0B]);
+        assert!(uses_floats(&module(&[(10, &body)])).unwrap());
+
+        // f64 global import
+        let import = [1, 1, b'm', 1, b'g', 0x03, 0x7C, 0];
+        assert!(uses_floats(&module(&[(2, &import)])).unwrap());
+    }
+
+    #[test]
+    fn test_immediates_are_skipped() {
+        // i32.const 0x43 must not be read as f32.const
+        let body = code(&[0], &[0x41, 0x43, 0x1A, 0x0B]);
+        assert!(!uses_floats(&module(&[(10, &body)])).unwrap());
+
+        // Unknown opcodes cannot be shown to be float-free
+        let body = code(&[0], &[0xFB, 0, 0x0B]);
+        assert!(uses_floats(&module(&[(10, &body)])).is_err());
+    }
+}
@@ -563,10 +563,11 @@ mod tests {
     fn test_minimal_binaries() {
         let core = FormatTestData::minimal_core_module().unwrap();
         assert!(core.len() >= 8);
-        assert_eq!(&core[0..4], &[0x00, 0x61, 0x73, 0x6d]);
+        assert_eq!(&core.to_vec().unwrap()[0..4], &[0x00, 0x61, 0x73, 0x6d]);
 
         let component = FormatTestData::minimal_component().unwrap();
         assert!(component.len() >= 8);
+        let component = component.to_vec().unwrap();
         assert_eq!(&component[0..4], &[0x00, 0x61, 0x73, 0x6d]);
         assert_eq!(component[4], 0x0a); // Component version
     }
@@ -55,13 +55,17 @@
 
 // Module exports
 // Core memory optimization modules (always available)
+pub mod byte_source;
+pub mod decode_error;
 pub mod decoder;
+pub mod float_usage;
 pub mod format_detection_tests;
 pub mod lazy_detection;
 pub mod memory_optimized;
 pub mod optimized_string;
 pub mod prelude;
 pub mod shared_cache;
+pub mod sliced_validation;
 pub mod streaming_decoder;
 pub mod streaming_validation;
 pub mod streaming_validator;
@@ -71,6 +75,10 @@
 #[cfg(not(feature = "std"))]
 pub mod bounded_decoder_infra;
 
+// Parallel decoding of independent sections (std only)
+#[cfg(feature = "std")]
+pub mod parallel_decoder;
+
 // Section parsing - use bounded version in no_std
 #[cfg(feature = "std")]
 pub mod sections;
@@ -96,24 +104,50 @@
 pub mod branch_hint_section;
 #[cfg(feature = "std")]
 pub mod custom_section_handler;
+#[cfg(feature = "std")]
+pub mod dylink_section;
+#[cfg(feature = "std")]
+pub mod encoder;
+#[cfg(feature = "std")]
+pub mod module_editor;
+#[cfg(feature = "std")]
+pub mod name_section;
+#[cfg(feature = "std")]
+pub mod producers_section;
 
 // Resource limits section - now ASIL-D compatible (no external dependencies)
 pub mod resource_limits_section;
 
+#[cfg(feature = "std")]
+pub mod target_features_section;
+
 // TOML configuration parser for resource limits (std only for tooling)
 #[cfg(feature = "std")]
 pub mod toml_config;
 
+// Module binaries shared by the unit tests
+#[cfg(all(test, feature = "std"))]
+mod test_fixtures;
+
 // Most re-exports temporarily disabled for demo - keep only essential ones
+pub use byte_source::{
+    decode_module_from_source,
+    ByteSource,
+    SegmentedSource,
+};
 // Component functionality (std only)
 #[cfg(feature = "std")]
 pub use component::decode_no_alloc;
+pub use decode_error::DecodeError;
 pub use decoder_no_alloc::{
     create_memory_provider,
     decode_module_header,
     extract_section_info,
     validate_module_no_alloc,
     verify_wasm_header,
+    DataSegmentView,
+    DataSegments,
+    FunctionBodies,
     SectionId,
     SectionInfo,
     ValidatorType,
@@ -137,6 +171,8 @@
     DecodedCache,
     SectionData,
 };
+// Sliced validation exports
+pub use sliced_validation::SlicedValidator;
 // Streaming validator exports
 pub use streaming_validator::{
     CodeSection,
@@ -0,0 +1,402 @@
+//! Section-level editing of module binaries
+//!
+//! This module requires the `std` feature.
+//!
+//! A [`ModuleEditor`] splits a binary into its sections and edits them
+//! without decoding the others: custom sections are removed or added,
+//! exports renamed and the module names of imports rewritten, while code,
+//! data and all other sections are copied byte for byte. This is meant for
+//! deployment-time hygiene such as stripping debug information before a
+//! module is flashed, or pointing imports at the host module of a target.
+
+use std::{
+    string::String,
+    vec::Vec,
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_format::{
+    binary::{
+        read_leb128_u32,
+        read_leb128_u64,
+        read_string,
+        read_u8,
+        CUSTOM_SECTION_ID,
+        EXPORT_SECTION_ID,
+        IMPORT_SECTION_ID,
+        WASM_MAGIC,
+        WASM_VERSION,
+    },
+    write_leb128_u32,
+    write_string,
+};
+
+use crate::{
+    custom_section_handler::extract_custom_section,
+    name_section::NAME_SECTION_NAME,
+};
+
+/// Size of the module header (magic number and version)
+const HEADER_SIZE: usize = 8;
+
+/// Names of custom sections holding debug information besides the
+/// `.debug_*` DWARF sections
+const DEBUG_SECTION_NAMES: [&str; 3] =
+    [NAME_SECTION_NAME, "sourceMappingURL", "external_debug_info"];
+
+/// A section of the edited module
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RawSection {
+    /// Section identifier
+    id:      u8,
+    /// Name of a custom section
+    name:    Option<String>,
+    /// Section contents, without identifier and size
+    payload: Vec<u8>,
+}
+
+/// Editor of the sections of a module binary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleEditor {
+    sections: Vec<RawSection>,
+}
+
+impl ModuleEditor {
+    /// Split `binary` into its sections
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the header is invalid or a section exceeds the
+    /// binary. Sections are not decoded, so other malformations are kept.
+    pub fn new(binary: &[u8]) -> Result<Self> {
+        if binary.len() < HEADER_SIZE
+            || binary[..4] != WASM_MAGIC
+            || binary[4..HEADER_SIZE] != WASM_VERSION
+        {
+            return Err(Error::parse_error("Invalid WebAssembly module header"));
+        }
+
+        let mut sections = Vec::new();
+        let mut offset = HEADER_SIZE;
+        while offset < binary.len() {
+            let id = binary[offset];
+            let (size, consumed) = read_leb128_u32(binary, offset + 1)?;
+            let start = offset + 1 + consumed;
+            let end = start
+                .checked_add(size as usize)
+                .filter(|end| *end <= binary.len())
+                .ok_or_else(|| Error::parse_error("Section exceeds module size"))?;
+
+            let payload = &binary[start..end];
+            let name = if id == CUSTOM_SECTION_ID {
+                Some(extract_custom_section(payload)?.0)
+            } else {
+                None
+            };
+            sections.push(RawSection {
+                id,
+                name,
+                payload: payload.to_vec(),
+            });
+            offset = end;
+        }
+
+        Ok(Self { sections })
+    }
+
+    /// Names of the custom sections in module order
+    pub fn custom_section_names(&self) -> impl Iterator<Item = &str> {
+        self.sections.iter().filter_map(|section| section.name.as_deref())
+    }
+
+    /// Remove the custom sections for whose name `remove` returns true,
+    /// returning how many were removed
+    pub fn remove_custom_sections(&mut self, mut remove: impl FnMut(&str) -> bool) -> usize {
+        let before = self.sections.len();
+        self.sections
+            .retain(|section| !section.name.as_deref().is_some_and(&mut remove));
+        before - self.sections.len()
+    }
+
+    /// Remove the custom sections named `name`, returning how many were
+    /// removed
+    pub fn remove_custom_section(&mut self, name: &str) -> usize {
+        self.remove_custom_sections(|section_name| section_name == name)
+    }
+
+    /// Remove the name section, DWARF sections and source map references,
+    /// returning how many sections were removed
+    pub fn strip_debug_info(&mut self) -> usize {
+        self.remove_custom_sections(|name| {
+            name.starts_with(".debug_") || DEBUG_SECTION_NAMES.contains(&name)
+        })
+    }
+
+    /// Append a custom section named `name` holding `data`
+    pub fn inject_custom_section(&mut self, name: &str, data: &[u8]) {
+        let mut payload = write_string(name);
+        payload.extend_from_slice(data);
+        self.sections.push(RawSection {
+            id: CUSTOM_SECTION_ID,
+            name: Some(String::from(name)),
+            payload,
+        });
+    }
+
+    /// Rename the export `from` to `to`, returning whether the module has an
+    /// export named `from`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the module already exports a different item as
+    /// `to`, or the export section is malformed.
+    pub fn rename_export(&mut self, from: &str, to: &str) -> Result<bool> {
+        let Some(section) = self.section_mut(EXPORT_SECTION_ID) else {
+            return Ok(false);
+        };
+
+        let (count, mut offset) = read_leb128_u32(&section.payload, 0)?;
+        let mut payload = write_leb128_u32(count);
+        let mut renamed = false;
+        let mut conflict = false;
+        for _ in 0..count {
+            let name = read_name(&section.payload, &mut offset)?;
+            // Kind and index
+            let start = offset;
+            offset += 1;
+            offset += read_leb128_u32(&section.payload, offset)?.1;
+            let rest = section
+                .payload
+                .get(start..offset)
+                .ok_or_else(|| Error::parse_error("Unexpected end of export section"))?;
+
+            if name == to && from != to {
+                conflict = true;
+            }
+            if name == from {
+                payload.extend(write_string(to));
+                renamed = true;
+            } else {
+                payload.extend(write_string(name));
+            }
+            payload.extend_from_slice(rest);
+        }
+
+        if renamed && conflict {
+            return Err(Error::validation_error(
+                "Module already has an export of that name",
+            ));
+        }
+        finish_rewrite(section, payload, offset)?;
+        Ok(renamed)
+    }
+
+    /// Make the imports from module `from` import from module `to`,
+    /// returning how many imports were changed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the import section is malformed.
+    pub fn rename_import_module(&mut self, from: &str, to: &str) -> Result<usize> {
+        let Some(section) = self.section_mut(IMPORT_SECTION_ID) else {
+            return Ok(0);
+        };
+
+        let (count, mut offset) = read_leb128_u32(&section.payload, 0)?;
+        let mut payload = write_leb128_u32(count);
+        let mut renamed = 0;
+        for _ in 0..count {
+            let module = read_name(&section.payload, &mut offset)?;
+            let start = offset;
+            read_name(&section.payload, &mut offset)?;
+            offset = skip_import_desc(&section.payload, offset)?;
+
+            if module == from {
+                payload.extend(write_string(to));
+                renamed += 1;
+            } else {
+                payload.extend(write_string(module));
+            }
+            payload.extend_from_slice(&section.payload[start..offset]);
+        }
+
+        finish_rewrite(section, payload, offset)?;
+        Ok(renamed)
+    }
+
+    /// Binary of the edited module
+    pub fn to_binary(&self) -> Result<Vec<u8>> {
+        let mut binary = Vec::with_capacity(
+            HEADER_SIZE
+                + self.sections.iter().map(|section| section.payload.len() + 6).sum::<usize>(),
+        );
+        binary.extend_from_slice(&WASM_MAGIC);
+        binary.extend_from_slice(&WASM_VERSION);
+        for section in &self.sections {
+            let size = u32::try_from(section.payload.len())
+                .map_err(|_| Error::validation_error("Section too large to encode"))?;
+            binary.push(section.id);
+            binary.extend(write_leb128_u32(size));
+            binary.extend_from_slice(&section.payload);
+        }
+        Ok(binary)
+    }
+
+    fn section_mut(&mut self, id: u8) -> Option<&mut RawSection> {
+        self.sections.iter_mut().find(|section| section.id == id)
+    }
+}
+
+/// Read a name, advancing `offset` past it
+fn read_name<'a>(data: &'a [u8], offset: &mut usize) -> Result<&'a str> {
+    let (bytes, consumed) = read_string(data, *offset)?;
+    *offset += consumed;
+    core::str::from_utf8(bytes).map_err(|_| Error::parse_error("Invalid UTF-8 in name"))
+}
+
+/// Skip limits, returning the offset after them
+fn skip_limits(data: &[u8], offset: usize) -> Result<usize> {
+    let (flags, mut offset) = read_u8(data, offset)?;
+    // Bit 2 marks 64-bit limits
+    let read_bound = |offset: usize| -> Result<usize> {
+        Ok(if flags & 0x04 != 0 {
+            offset + read_leb128_u64(data, offset)?.1
+        } else {
+            offset + read_leb128_u32(data, offset)?.1
+        })
+    };
+    offset = read_bound(offset)?;
+    if flags & 0x01 != 0 {
+        offset = read_bound(offset)?;
+    }
+    Ok(offset)
+}
+
+/// Skip an import descriptor, returning the offset after it
+fn skip_import_desc(data: &[u8], offset: usize) -> Result<usize> {
+    let (kind, offset) = read_u8(data, offset)?;
+    match kind {
+        // Function: type index
+        0x00 => Ok(offset + read_leb128_u32(data, offset)?.1),
+        // Table: element type and limits
+        0x01 => skip_limits(data, read_u8(data, offset)?.1),
+        // Memory: limits
+        0x02 => skip_limits(data, offset),
+        // Global: value type and mutability
+        0x03 => Ok(read_u8(data, read_u8(data, offset)?.1)?.1),
+        // Tag: attribute and type index
+        0x04 => {
+            let offset = read_u8(data, offset)?.1;
+            Ok(offset + read_leb128_u32(data, offset)?.1)
+        },
+        _ => Err(Error::parse_error("Invalid import kind")),
+    }
+}
+
+/// Replace the payload of a rewritten section, checking that the old one
+/// ended after `offset` bytes
+fn finish_rewrite(section: &mut RawSection, payload: Vec<u8>, offset: usize) -> Result<()> {
+    if offset != section.payload.len() {
+        return Err(Error::parse_error("Section size mismatch"));
+    }
+    section.payload = payload;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        streaming_decoder::decode_module_streaming,
+        test_fixtures::ModuleBinary,
+    };
+
+    /// Module importing a function and a memory from "env", exporting a
+    /// function, with debug sections
+    fn sample_module() -> Vec<u8> {
+        let mut binary = ModuleBinary::new();
+        binary.section(1, &[0x01, 0x60, 0x00, 0x00]);
+        let mut imports = vec![0x03];
+        for (module, name) in [("env", "log"), ("wasi", "exit"), ("env", "mem")] {
+            imports.extend(write_string(module));
+            imports.extend(write_string(name));
+            if name == "mem" {
+                imports.extend([0x02, 0x01, 0x01, 0x02]);
+            } else {
+                imports.extend([0x00, 0x00]);
+            }
+        }
+        binary.section(2, &imports);
+        binary.section(3, &[0x01, 0x00]);
+        let mut exports = vec![0x01];
+        exports.extend(write_string("run"));
+        exports.extend([0x00, 0x02]);
+        binary.section(7, &exports);
+        binary.custom(".debug_info", &[1, 2, 3]);
+        binary.section(10, &[0x01, 0x02, 0x00, 0x0B]);
+        binary.custom("name", &[0x00, 0x02, 0x01, b'm']);
+        binary.custom("producers", &[0x00]);
+        binary.build()
+    }
+
+    #[test]
+    fn test_unedited_module_is_unchanged() {
+        let binary = sample_module();
+        let editor = ModuleEditor::new(&binary).unwrap();
+        assert_eq!(editor.to_binary().unwrap(), binary);
+        assert_eq!(
+            editor.custom_section_names().collect::<Vec<_>>(),
+            [".debug_info", "name", "producers"]
+        );
+        assert!(ModuleEditor::new(&binary[..binary.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_strip_and_inject_custom_sections() {
+        let mut editor = ModuleEditor::new(&sample_module()).unwrap();
+        assert_eq!(editor.strip_debug_info(), 2);
+        editor.inject_custom_section("build-id", &[0xAB; 4]);
+        assert_eq!(editor.remove_custom_section("producers"), 1);
+
+        let binary = editor.to_binary().unwrap();
+        let module = decode_module_streaming(&binary).unwrap();
+        assert_eq!(module.custom_sections.len(), 1);
+        assert_eq!(module.custom_sections[0].name, "build-id");
+        assert_eq!(module.custom_sections[0].data, [0xAB; 4]);
+        assert_eq!(module.functions[0].code, [0x00, 0x0B]);
+    }
+
+    #[test]
+    fn test_rename_exports_and_import_modules() {
+        let mut editor = ModuleEditor::new(&sample_module()).unwrap();
+        assert!(editor.rename_export("run", "_start").unwrap());
+        assert!(!editor.rename_export("missing", "other").unwrap());
+        assert_eq!(editor.rename_import_module("env", "host").unwrap(), 2);
+
+        let module = decode_module_streaming(&editor.to_binary().unwrap()).unwrap();
+        assert_eq!(module.exports[0].name, "_start");
+        let imports: Vec<_> = module
+            .imports
+            .iter()
+            .map(|import| (import.module.as_str(), import.name.as_str()))
+            .collect();
+        assert_eq!(
+            imports,
+            [("host", "log"), ("wasi", "exit"), ("host", "mem")]
+        );
+
+        let mut exports = vec![0x02];
+        for name in ["a", "b"] {
+            exports.extend(write_string(name));
+            exports.extend([0x00, 0x00]);
+        }
+        let mut binary = ModuleBinary::new();
+        binary.section(7, &exports);
+        let mut editor = ModuleEditor::new(&binary.build()).unwrap();
+        assert!(editor.rename_export("a", "b").is_err());
+        assert!(!editor.rename_export("c", "b").unwrap());
+    }
+}
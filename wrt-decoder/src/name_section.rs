@@ -0,0 +1,322 @@
+//! WebAssembly Name Custom Section Parser
+//!
+//! This module requires the `std` feature.
+//!
+//! This module implements parsing for the "name" custom section defined in
+//! the appendix of the WebAssembly core specification. Toolchains emit it to
+//! name the module, its functions and their locals, so that diagnostics can
+//! show `parse_header` instead of function 42.
+//!
+//! # Custom Section Format
+//!
+//! The section is a sequence of subsections, each present at most once and in
+//! order of their identifiers:
+//! ```text
+//! name_section ::= subsection*
+//! subsection ::= id:u8 size:u32 payload
+//! module (0) ::= name
+//! function (1) ::= name_map
+//! local (2) ::= count:u32 (func_idx:u32 name_map)*
+//! name_map ::= count:u32 (idx:u32 name)*
+//! ```
+//!
+//! Indices of a name map are strictly increasing. Subsections of the extended
+//! name section proposal (labels, types, tables, ...) are skipped.
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    string::String,
+    vec::Vec,
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_format::{
+    binary::{
+        read_leb128_u32,
+        read_string,
+        read_u8,
+    },
+    write_leb128_u32,
+    write_string,
+};
+
+use crate::custom_section_handler::find_custom_section;
+
+/// Name section name constant
+pub const NAME_SECTION_NAME: &str = "name";
+
+/// Subsection holding the module name
+pub const NAME_SUBSECTION_MODULE: u8 = 0;
+/// Subsection holding function names
+pub const NAME_SUBSECTION_FUNCTION: u8 = 1;
+/// Subsection holding local names per function
+pub const NAME_SUBSECTION_LOCAL: u8 = 2;
+
+/// Names parsed from the "name" section of a module
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct NameMap {
+    /// Name of the module
+    pub module_name:    Option<String>,
+    /// Function names by function index
+    pub function_names: BTreeMap<u32, String>,
+    /// Local names by function index and local index
+    pub local_names:    BTreeMap<u32, BTreeMap<u32, String>>,
+}
+
+impl NameMap {
+    /// Name of the function at `function_index`
+    pub fn function_name(&self, function_index: u32) -> Option<&str> {
+        self.function_names.get(&function_index).map(String::as_str)
+    }
+
+    /// Name of local `local_index` of the function at `function_index`
+    pub fn local_name(&self, function_index: u32, local_index: u32) -> Option<&str> {
+        self.local_names
+            .get(&function_index)
+            .and_then(|locals| locals.get(&local_index))
+            .map(String::as_str)
+    }
+
+    /// Symbol for the function at `function_index`, named if possible
+    pub fn symbolicate(&self, function_index: u32) -> FunctionSymbol<'_> {
+        FunctionSymbol {
+            index: function_index,
+            name:  self.function_name(function_index),
+        }
+    }
+
+    /// Whether the section names nothing
+    pub fn is_empty(&self) -> bool {
+        self.module_name.is_none() && self.function_names.is_empty() && self.local_names.is_empty()
+    }
+}
+
+/// A function as shown in diagnostics
+///
+/// Displays as the function name, or as `func[index]` for unnamed functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FunctionSymbol<'a> {
+    /// Function index
+    pub index: u32,
+    /// Function name, if the name section has one
+    pub name:  Option<&'a str>,
+}
+
+impl fmt::Display for FunctionSymbol<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name {
+            Some(name) => f.write_str(name),
+            None => write!(f, "func[{}]", self.index),
+        }
+    }
+}
+
+fn read_u32(data: &[u8], offset: &mut usize) -> Result<u32> {
+    let (value, consumed) = read_leb128_u32(data, *offset)?;
+    *offset += consumed;
+    Ok(value)
+}
+
+fn read_name(data: &[u8], offset: &mut usize) -> Result<String> {
+    let (bytes, consumed) = read_string(data, *offset)?;
+    *offset += consumed;
+    core::str::from_utf8(bytes)
+        .map(String::from)
+        .map_err(|_| Error::parse_error("Invalid UTF-8 in name section"))
+}
+
+fn read_name_map(data: &[u8], offset: &mut usize) -> Result<BTreeMap<u32, String>> {
+    let count = read_u32(data, offset)?;
+    let mut names = BTreeMap::new();
+    let mut previous = None;
+    for _ in 0..count {
+        let index = read_u32(data, offset)?;
+        if previous.is_some_and(|previous| index <= previous) {
+            return Err(Error::parse_error(
+                "Name map indices not in increasing order",
+            ));
+        }
+        previous = Some(index);
+        names.insert(index, read_name(data, offset)?);
+    }
+    Ok(names)
+}
+
+/// Parse the name custom section from binary data
+pub fn parse_name_section(data: &[u8]) -> Result<NameMap> {
+    let mut offset = 0;
+    let mut names = NameMap::default();
+    let mut previous_id = None;
+
+    while offset < data.len() {
+        let (id, next) = read_u8(data, offset)?;
+        offset = next;
+        let size = read_u32(data, &mut offset)? as usize;
+        let end = offset
+            .checked_add(size)
+            .filter(|end| *end <= data.len())
+            .ok_or_else(|| Error::parse_error("Name subsection exceeds section size"))?;
+        let payload = &data[..end];
+
+        if previous_id.is_some_and(|previous| id <= previous) {
+            return Err(Error::parse_error("Name subsections out of order"));
+        }
+        previous_id = Some(id);
+
+        match id {
+            NAME_SUBSECTION_MODULE => names.module_name = Some(read_name(payload, &mut offset)?),
+            NAME_SUBSECTION_FUNCTION => names.function_names = read_name_map(payload, &mut offset)?,
+            NAME_SUBSECTION_LOCAL => {
+                let count = read_u32(payload, &mut offset)?;
+                let mut previous = None;
+                for _ in 0..count {
+                    let function_index = read_u32(payload, &mut offset)?;
+                    if previous.is_some_and(|previous| function_index <= previous) {
+                        return Err(Error::parse_error(
+                            "Local names not in increasing function order",
+                        ));
+                    }
+                    previous = Some(function_index);
+                    let locals = read_name_map(payload, &mut offset)?;
+                    names.local_names.insert(function_index, locals);
+                }
+            },
+            // Subsections of the extended name section
+            _ => offset = end,
+        }
+
+        if offset != end {
+            return Err(Error::parse_error("Name subsection size mismatch"));
+        }
+    }
+
+    Ok(names)
+}
+
+fn encode_name_map(names: &BTreeMap<u32, String>, data: &mut Vec<u8>) -> Result<()> {
+    data.extend(write_leb128_u32(length(names.len())?));
+    for (index, name) in names {
+        data.extend(write_leb128_u32(*index));
+        data.extend(write_string(name));
+    }
+    Ok(())
+}
+
+fn length(len: usize) -> Result<u32> {
+    u32::try_from(len).map_err(|_| Error::parse_error("Name section entry too large"))
+}
+
+/// Encode a name section, omitting empty subsections
+pub fn encode_name_section(names: &NameMap) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    let mut subsection = |id: u8, payload: Vec<u8>| -> Result<()> {
+        data.push(id);
+        data.extend(write_leb128_u32(length(payload.len())?));
+        data.extend(payload);
+        Ok(())
+    };
+
+    if let Some(module_name) = &names.module_name {
+        subsection(NAME_SUBSECTION_MODULE, write_string(module_name))?;
+    }
+    if !names.function_names.is_empty() {
+        let mut payload = Vec::new();
+        encode_name_map(&names.function_names, &mut payload)?;
+        subsection(NAME_SUBSECTION_FUNCTION, payload)?;
+    }
+    if !names.local_names.is_empty() {
+        let mut payload = write_leb128_u32(length(names.local_names.len())?);
+        for (function_index, locals) in &names.local_names {
+            payload.extend(write_leb128_u32(*function_index));
+            encode_name_map(locals, &mut payload)?;
+        }
+        subsection(NAME_SUBSECTION_LOCAL, payload)?;
+    }
+
+    Ok(data)
+}
+
+/// Read the name section of a module binary
+///
+/// Returns `None` for modules without a name section.
+pub fn read_name_section(binary: &[u8]) -> Result<Option<NameMap>> {
+    find_custom_section(binary, NAME_SECTION_NAME)?
+        .map(parse_name_section)
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names() -> NameMap {
+        let mut names = NameMap {
+            module_name: Some("demo".into()),
+            ..NameMap::default()
+        };
+        names.function_names.insert(0, "main".into());
+        names.function_names.insert(3, "parse_header".into());
+        names
+            .local_names
+            .insert(3, BTreeMap::from([(0, "input".into()), (2, "len".into())]));
+        names
+    }
+
+    #[test]
+    fn test_name_section_roundtrip() {
+        let names = names();
+        let encoded = encode_name_section(&names).unwrap();
+        let parsed = parse_name_section(&encoded).unwrap();
+        assert_eq!(parsed, names);
+
+        assert_eq!(parsed.function_name(3), Some("parse_header"));
+        assert_eq!(parsed.local_name(3, 2), Some("len"));
+        assert_eq!(parsed.local_name(0, 0), None);
+        assert_eq!(parsed.symbolicate(3).to_string(), "parse_header");
+        assert_eq!(parsed.symbolicate(7).to_string(), "func[7]");
+        assert!(parse_name_section(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_malformed_name_section() {
+        // Subsection size beyond the end of the section
+        assert!(parse_name_section(&[NAME_SUBSECTION_FUNCTION, 0x08, 0x00]).is_err());
+
+        // Function indices out of order
+        let data = [NAME_SUBSECTION_FUNCTION, 7, 2, 1, 1, b'a', 0, 1, b'b'];
+        assert!(parse_name_section(&data).is_err());
+
+        // Subsections out of order
+        let data = [NAME_SUBSECTION_FUNCTION, 1, 0, NAME_SUBSECTION_MODULE, 1, 0];
+        assert!(parse_name_section(&data).is_err());
+
+        // Extended name subsections are skipped
+        let data = [NAME_SUBSECTION_MODULE, 2, 1, b'm', 4, 3, 1, 2, 3];
+        assert_eq!(
+            parse_name_section(&data).unwrap().module_name.as_deref(),
+            Some("m")
+        );
+    }
+
+    #[test]
+    fn test_read_name_section() {
+        let mut contents = write_string(NAME_SECTION_NAME);
+        contents.extend(encode_name_section(&names()).unwrap());
+
+        // Type section with no types, then the name section
+        let mut binary = vec![
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x01, 0x00,
+        ];
+        binary.push(0x00);
+        binary.extend(write_leb128_u32(contents.len() as u32));
+        binary.extend(contents);
+
+        assert_eq!(read_name_section(&binary).unwrap(), Some(names()));
+        assert_eq!(read_name_section(&binary[..11]).unwrap(), None);
+        assert!(read_name_section(&binary[..4]).is_err());
+    }
+}
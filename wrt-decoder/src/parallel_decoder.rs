@@ -0,0 +1,239 @@
+//! Parallel decoding of independent module sections
+//!
+//! Large modules spend most of their load time in the type, import, function
+//! and code sections. None of these depend on the module being built, so once
+//! the section index is known they are decoded on scoped worker threads. The
+//! results are then merged in section order through the same
+//! [`StreamingDecoder`] used for sequential decoding, which makes the decoded
+//! module (and the first error reported) identical to
+//! [`decode_module_streaming`](crate::streaming_decoder::decode_module_streaming).
+
+use core::ops::Range;
+use std::thread;
+
+use wrt_format::module::Module as WrtModule;
+
+use crate::{
+    prelude::*,
+    streaming_decoder::{
+        decode_independent_section,
+        is_independent_section,
+        section_error,
+        DecodedSection,
+        StreamingDecoder,
+    },
+};
+
+/// Configuration for [`decode_module_parallel_with`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParallelDecodeConfig {
+    /// Sections smaller than this many bytes are decoded on the calling
+    /// thread, as spawning a worker would cost more than it saves
+    pub min_section_size: usize,
+}
+
+impl Default for ParallelDecodeConfig {
+    fn default() -> Self {
+        Self {
+            min_section_size: 64 * 1024,
+        }
+    }
+}
+
+/// Location of a single section within a module binary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionEntry {
+    /// Section identifier
+    pub id:     u8,
+    /// Offset of the section identifier byte
+    pub offset: usize,
+    /// Byte range of the section payload
+    pub range:  Range<usize>,
+}
+
+/// Index of all sections of a module binary, in binary order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SectionIndex {
+    entries: Vec<SectionEntry>,
+}
+
+impl SectionIndex {
+    /// Scan the section headers of `binary` without decoding any payload
+    pub fn scan(binary: &[u8]) -> Result<Self> {
+        // Validates the module header
+        StreamingDecoder::new(binary)?.decode_header()?;
+
+        let mut entries = Vec::new();
+        let mut offset = 8;
+        while offset < binary.len() {
+            let id = binary[offset];
+            let (size, bytes_read) = read_leb128_u32(binary, offset + 1)?;
+            let start = offset + 1 + bytes_read;
+            let end = start
+                .checked_add(size as usize)
+                .filter(|end| *end <= binary.len())
+                .ok_or_else(|| Error::parse_error("Section extends beyond binary"))?;
+
+            entries.push(SectionEntry {
+                id,
+                offset,
+                range: start..end,
+            });
+            offset = end;
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Get the indexed sections
+    pub fn entries(&self) -> &[SectionEntry] {
+        &self.entries
+    }
+}
+
+/// Decode a WebAssembly module, decoding independent sections in parallel
+pub fn decode_module_parallel(binary: &[u8]) -> Result<WrtModule> {
+    decode_module_parallel_with(binary, &ParallelDecodeConfig::default())
+}
+
+/// Decode a WebAssembly module with an explicit parallel decode configuration
+pub fn decode_module_parallel_with(
+    binary: &[u8],
+    config: &ParallelDecodeConfig,
+) -> Result<WrtModule> {
+    let index = SectionIndex::scan(binary)?;
+    let decoded = decode_sections(binary, &index, config);
+
+    let mut decoder = StreamingDecoder::new(binary)?;
+    decoder.decode_header()?;
+
+    for (entry, section) in index.entries().iter().zip(decoded) {
+        section
+            .and_then(|section| match section {
+                Some(section) => decoder.apply_decoded_section(section),
+                None => decoder.process_section(entry.id, &binary[entry.range.clone()]),
+            })
+            .map_err(|error| section_error(error, entry.id, entry.offset, entry.range.start))?;
+    }
+
+    decoder.finish()
+}
+
+/// Decode the independent sections of `index`, one result per entry
+fn decode_sections(
+    binary: &[u8],
+    index: &SectionIndex,
+    config: &ParallelDecodeConfig,
+) -> Vec<Result<Option<DecodedSection>>> {
+    thread::scope(|scope| {
+        let pending: Vec<_> = index
+            .entries()
+            .iter()
+            .map(|entry| {
+                let data = &binary[entry.range.clone()];
+                if !is_independent_section(entry.id) {
+                    Err(Ok(None))
+                } else if data.len() < config.min_section_size {
+                    Err(decode_independent_section(entry.id, data))
+                } else {
+                    let id = entry.id;
+                    Ok(scope.spawn(move || decode_independent_section(id, data)))
+                }
+            })
+            .collect();
+
+        pending
+            .into_iter()
+            .map(|pending| match pending {
+                Ok(worker) => worker.join().unwrap_or_else(|_| {
+                    Err(Error::runtime_execution_error("Section decoder thread panicked"))
+                }),
+                Err(result) => result,
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        streaming_decoder::decode_module_streaming,
+        test_fixtures::ModuleBinary,
+    };
+
+    /// Build a module with imports, two functions and an export
+    fn sample_module() -> Vec<u8> {
+        let mut binary = ModuleBinary::new();
+        // (func (param i32) (result i64)), (func)
+        binary.section(1, &[0x02, 0x60, 0x01, 0x7F, 0x01, 0x7E, 0x60, 0x00, 0x00]);
+        // (import "env" "f" (func (type 1))), (import "env" "m" (memory 1 2))
+        binary.section(
+            2,
+            &[
+                0x02, 0x03, b'e', b'n', b'v', 0x01, b'f', 0x00, 0x01, 0x03, b'e', b'n', b'v', 0x01,
+                b'm', 0x02, 0x01, 0x01, 0x02,
+            ],
+        );
+        binary.section(3, &[0x02, 0x00, 0x01]);
+        binary.section(7, &[0x01, 0x03, b'r', b'u', b'n', 0x00, 0x01]);
+        binary.section(10, &[0x02, 0x04, 0x00, 0x42, 0x00, 0x0B, 0x02, 0x00, 0x0B]);
+        binary.build()
+    }
+
+    #[test]
+    fn test_section_index() {
+        let binary = sample_module();
+        let index = SectionIndex::scan(&binary).unwrap();
+        let ids: Vec<u8> = index.entries().iter().map(|entry| entry.id).collect();
+        assert_eq!(ids, vec![1, 2, 3, 7, 10]);
+        assert_eq!(index.entries()[0].range, 10..19);
+
+        let mut truncated = binary.clone();
+        truncated.truncate(binary.len() - 1);
+        assert!(SectionIndex::scan(&truncated).is_err());
+    }
+
+    #[test]
+    fn test_parallel_matches_streaming() {
+        let binary = sample_module();
+        let sequential = decode_module_streaming(&binary).unwrap();
+
+        // Force every independent section onto a worker thread
+        let config = ParallelDecodeConfig {
+            min_section_size: 0,
+        };
+        let parallel = decode_module_parallel_with(&binary, &config).unwrap();
+
+        assert_eq!(parallel.types, sequential.types);
+        assert_eq!(parallel.types[0].params, vec![ValueType::I32]);
+        assert_eq!(parallel.imports.len(), 2);
+        assert_eq!(parallel.imports[1].name, "m");
+        assert_eq!(
+            format!("{:?}", parallel.imports),
+            format!("{:?}", sequential.imports)
+        );
+        assert_eq!(parallel.functions.len(), 2);
+        for (p, s) in parallel.functions.iter().zip(sequential.functions.iter()) {
+            assert_eq!((p.type_idx, &p.code), (s.type_idx, &s.code));
+        }
+        assert_eq!(parallel.functions[0].code, vec![0x00, 0x42, 0x00, 0x0B]);
+        assert_eq!(parallel.exports.len(), 1);
+    }
+
+    #[test]
+    fn test_first_error_in_section_order() {
+        let mut binary = ModuleBinary::new();
+        // Invalid value type in the type section, truncated code body later
+        binary.section(1, &[0x01, 0x60, 0x01, 0x55, 0x00]);
+        binary.section(10, &[0x01, 0x10, 0x00]);
+        let binary = binary.build();
+
+        let config = ParallelDecodeConfig {
+            min_section_size: 0,
+        };
+        let err = decode_module_parallel_with(&binary, &config).unwrap_err();
+        let expected = decode_module_streaming(&binary).unwrap_err();
+        assert_eq!((err.code, err.message), (expected.code, expected.message));
+    }
+}
@@ -353,31 +353,7 @@ pub fn write_string(_s: &str) -> BoundedVec<u8, 256, NoStdProvider<512>> {
 
     /// Read LEB128 u32 from data with offset
     pub fn read_leb_u32(data: &[u8], offset: usize) -> wrt_error::Result<(u32, usize)> {
-        // Simple implementation for no_std - just read from beginning
-        if offset >= data.len() {
-            return Err(wrt_error::Error::parse_error("Offset out of bounds"));
-        }
-        // For simplicity, just parse from the offset
-        let mut value = 0u32;
-        let mut shift = 0;
-        let mut bytes_read = 0;
-
-        for &byte in &data[offset..] {
-            if bytes_read >= 5 {
-                return Err(wrt_error::Error::parse_error("LEB128 too long"));
-            }
-
-            value |= ((byte & 0x7F) as u32) << shift;
-            bytes_read += 1;
-
-            if (byte & 0x80) == 0 {
-                return Ok((value, bytes_read));
-            }
-
-            shift += 7;
-        }
-
-        Err(wrt_error::Error::parse_error("Incomplete LEB128"))
+        wrt_format::binary::read_leb128_u32(data, offset)
     }
 
     /// Read name from binary data in no_std mode
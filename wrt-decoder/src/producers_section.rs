@@ -0,0 +1,275 @@
+//! WebAssembly Producers Custom Section Parser
+//!
+//! This module requires the `std` feature.
+//!
+//! This module implements parsing for the "producers" custom section described
+//! by the WebAssembly tool conventions. Compilers and post-processing tools
+//! record themselves in it, which lets hosts log where a module came from.
+//!
+//! # Custom Section Format
+//!
+//! ```text
+//! producers_section ::= field_count:u32 field*
+//! field ::= field_name:name value_count:u32 (name:name version:name)*
+//! ```
+//!
+//! The known fields are [`FIELD_LANGUAGE`], [`FIELD_PROCESSED_BY`] and
+//! [`FIELD_SDK`]. Field names and the value names within a field are unique.
+
+use std::{
+    fmt,
+    string::String,
+    vec::Vec,
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_format::{
+    binary::{
+        read_leb128_u32,
+        read_string,
+    },
+    write_leb128_u32,
+    write_string,
+};
+
+use crate::custom_section_handler::find_custom_section;
+
+/// Producers section name constant
+pub const PRODUCERS_SECTION_NAME: &str = "producers";
+
+/// Field listing the source languages
+pub const FIELD_LANGUAGE: &str = "language";
+/// Field listing the tools that produced or transformed the module
+pub const FIELD_PROCESSED_BY: &str = "processed-by";
+/// Field listing the SDKs the module was built with
+pub const FIELD_SDK: &str = "sdk";
+
+/// A producer and its version
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProducerValue {
+    /// Name of the language, tool or SDK
+    pub name:    String,
+    /// Version, possibly empty
+    pub version: String,
+}
+
+/// A field of the producers section
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProducersField {
+    /// Field name, such as [`FIELD_PROCESSED_BY`]
+    pub name:   String,
+    /// Producers listed in the field
+    pub values: Vec<ProducerValue>,
+}
+
+/// Parsed "producers" section
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ProducersSection {
+    /// Fields in section order
+    pub fields: Vec<ProducersField>,
+}
+
+impl ProducersSection {
+    /// Producers listed in the field `name`
+    pub fn field(&self, name: &str) -> &[ProducerValue] {
+        self.fields
+            .iter()
+            .find(|field| field.name == name)
+            .map_or(&[], |field| field.values.as_slice())
+    }
+
+    /// Source languages of the module
+    pub fn languages(&self) -> &[ProducerValue] {
+        self.field(FIELD_LANGUAGE)
+    }
+
+    /// Tools that produced or transformed the module
+    pub fn processed_by(&self) -> &[ProducerValue] {
+        self.field(FIELD_PROCESSED_BY)
+    }
+
+    /// SDKs the module was built with
+    pub fn sdks(&self) -> &[ProducerValue] {
+        self.field(FIELD_SDK)
+    }
+
+    /// Version of the producer `name` listed in any field
+    pub fn version_of(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .flat_map(|field| &field.values)
+            .find(|value| value.name == name)
+            .map(|value| value.version.as_str())
+    }
+}
+
+/// Formats the section on one line for logs, such as
+/// `language: Rust; processed-by: rustc 1.75.0`
+impl fmt::Display for ProducersSection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (field_index, field) in self.fields.iter().enumerate() {
+            if field_index > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{}: ", field.name)?;
+            for (value_index, value) in field.values.iter().enumerate() {
+                if value_index > 0 {
+                    f.write_str(", ")?;
+                }
+                f.write_str(&value.name)?;
+                if !value.version.is_empty() {
+                    write!(f, " {}", value.version)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn read_u32(data: &[u8], offset: &mut usize) -> Result<u32> {
+    let (value, consumed) = read_leb128_u32(data, *offset)?;
+    *offset += consumed;
+    Ok(value)
+}
+
+fn read_name(data: &[u8], offset: &mut usize) -> Result<String> {
+    let (bytes, consumed) = read_string(data, *offset)?;
+    *offset += consumed;
+    core::str::from_utf8(bytes)
+        .map(String::from)
+        .map_err(|_| Error::parse_error("Invalid UTF-8 in producers section"))
+}
+
+/// Parse the producers custom section from binary data
+pub fn parse_producers_section(data: &[u8]) -> Result<ProducersSection> {
+    let mut offset = 0;
+    let mut section = ProducersSection::default();
+
+    let field_count = read_u32(data, &mut offset)?;
+    for _ in 0..field_count {
+        let name = read_name(data, &mut offset)?;
+        if section.fields.iter().any(|field| field.name == name) {
+            return Err(Error::parse_error("Duplicate field in producers section"));
+        }
+
+        let value_count = read_u32(data, &mut offset)?;
+        let mut values: Vec<ProducerValue> = Vec::new();
+        for _ in 0..value_count {
+            let value = ProducerValue {
+                name:    read_name(data, &mut offset)?,
+                version: read_name(data, &mut offset)?,
+            };
+            if values.iter().any(|existing| existing.name == value.name) {
+                return Err(Error::parse_error("Duplicate value in producers field"));
+            }
+            values.push(value);
+        }
+        section.fields.push(ProducersField { name, values });
+    }
+
+    if offset != data.len() {
+        return Err(Error::parse_error("Trailing data in producers section"));
+    }
+
+    Ok(section)
+}
+
+fn length(len: usize) -> Result<u32> {
+    u32::try_from(len).map_err(|_| Error::parse_error("Producers section entry too large"))
+}
+
+/// Encode a producers section
+pub fn encode_producers_section(section: &ProducersSection) -> Result<Vec<u8>> {
+    let mut data = write_leb128_u32(length(section.fields.len())?);
+    for field in &section.fields {
+        data.extend(write_string(&field.name));
+        data.extend(write_leb128_u32(length(field.values.len())?));
+        for value in &field.values {
+            data.extend(write_string(&value.name));
+            data.extend(write_string(&value.version));
+        }
+    }
+    Ok(data)
+}
+
+/// Read the producers section of a module binary
+///
+/// Returns `None` for modules without a producers section.
+pub fn read_producers_section(binary: &[u8]) -> Result<Option<ProducersSection>> {
+    find_custom_section(binary, PRODUCERS_SECTION_NAME)?
+        .map(parse_producers_section)
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(name: &str, version: &str) -> ProducerValue {
+        ProducerValue {
+            name:    name.into(),
+            version: version.into(),
+        }
+    }
+
+    fn section() -> ProducersSection {
+        ProducersSection {
+            fields: vec![
+                ProducersField {
+                    name:   FIELD_LANGUAGE.into(),
+                    values: vec![value("Rust", "")],
+                },
+                ProducersField {
+                    name:   FIELD_PROCESSED_BY.into(),
+                    values: vec![value("rustc", "1.75.0"), value("wasm-opt", "116")],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_producers_roundtrip() {
+        let encoded = encode_producers_section(&section()).unwrap();
+        let parsed = parse_producers_section(&encoded).unwrap();
+        assert_eq!(parsed, section());
+
+        assert_eq!(parsed.languages(), &[value("Rust", "")]);
+        assert_eq!(parsed.processed_by().len(), 2);
+        assert!(parsed.sdks().is_empty());
+        assert_eq!(parsed.version_of("wasm-opt"), Some("116"));
+        assert_eq!(parsed.version_of("clang"), None);
+        assert_eq!(
+            parsed.to_string(),
+            "language: Rust; processed-by: rustc 1.75.0, wasm-opt 116"
+        );
+    }
+
+    #[test]
+    fn test_parse_malformed_producers() {
+        let mut duplicate = section();
+        duplicate.fields[1].values.push(value("rustc", "1.76.0"));
+        let encoded = encode_producers_section(&duplicate).unwrap();
+        assert!(parse_producers_section(&encoded).is_err());
+
+        let mut encoded = encode_producers_section(&section()).unwrap();
+        assert!(parse_producers_section(&encoded[..encoded.len() - 1]).is_err());
+        encoded.push(0);
+        assert!(parse_producers_section(&encoded).is_err());
+    }
+
+    #[test]
+    fn test_read_producers_section() {
+        let mut contents = write_string(PRODUCERS_SECTION_NAME);
+        contents.extend(encode_producers_section(&section()).unwrap());
+
+        let mut binary = vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x00];
+        binary.extend(write_leb128_u32(contents.len() as u32));
+        binary.extend(contents);
+
+        assert_eq!(read_producers_section(&binary).unwrap(), Some(section()));
+        assert_eq!(read_producers_section(&binary[..8]).unwrap(), None);
+    }
+}
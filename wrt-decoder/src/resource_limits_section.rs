@@ -29,8 +29,6 @@
     Error,
     ErrorCategory,
 };
-#[cfg(test)]
-use wrt_foundation::safe_memory::NoStdProvider;
 use wrt_foundation::{
     safe_managed_alloc,
     traits::{
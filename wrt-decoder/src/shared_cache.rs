@@ -425,38 +425,6 @@ fn parse_exports_from_binary(binary: &[u8]) -> Result<Vec<ExportInfo>> {
     Ok(exports)
 }
 
-/// Helper function to read LEB128 unsigned 32-bit integer
-fn read_leb128_u32(data: &[u8], offset: usize) -> Result<(u32, usize)> {
-    let mut result = 0u32;
-    let mut shift = 0;
-    let mut bytes_read = 0;
-
-    for i in 0..5 {
-        // Max 5 bytes for u32
-        if offset + i >= data.len() {
-            return Err(Error::parse_error(
-                "Unexpected end of data while reading LEB128",
-            ));
-        }
-
-        let byte = data[offset + i];
-        bytes_read += 1;
-
-        result |= ((byte & 0x7F) as u32) << shift;
-
-        if byte & 0x80 == 0 {
-            break;
-        }
-
-        shift += 7;
-        if shift >= 32 {
-            return Err(Error::parse_error("LEB128 value too large for u32"));
-        }
-    }
-
-    Ok((result, bytes_read))
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,275 @@
+//! Time-sliced validation of a module binary
+//!
+//! Hosts that hot-swap modules while serving requests cannot afford to stall
+//! their loop while a large replacement module is validated. A
+//! [`SlicedValidator`] instead decodes and validates the module in slices of
+//! bounded work, each started by the host with [`SlicedValidator::step`], and
+//! produces the same module as
+//! [`decode_module_streaming`](crate::streaming_decoder::decode_module_streaming)
+//! once all slices are done.
+//!
+//! A slice handles whole sections, except for the code section, whose
+//! function bodies are handled one at a time. The budget of a slice is the
+//! number of binary bytes it may handle; a slice always makes progress, so a
+//! single section or body larger than the budget is handled in one slice.
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+use wrt_format::module::Module as WrtModule;
+#[cfg(not(feature = "std"))]
+use wrt_foundation::safe_memory::NoStdProvider;
+
+use crate::{
+    prelude::*,
+    streaming_decoder::{
+        decode_function_body,
+        item_context,
+        section_error,
+        DecodedSection,
+        StreamingDecoder,
+    },
+};
+
+/// Code section whose function bodies are split across slices
+#[derive(Debug)]
+struct PendingCode {
+    /// Offset of the code section in the binary
+    section:   usize,
+    /// Offset of the code section payload in the binary
+    payload:   usize,
+    /// End of the code section in the binary
+    end:       usize,
+    /// Number of bodies in the section
+    count:     u32,
+    /// Bodies still to be read
+    remaining: u32,
+    /// Bodies read so far
+    bodies:    alloc::vec::Vec<alloc::vec::Vec<u8>>,
+}
+
+/// Validator of a module binary doing bounded work per call
+pub struct SlicedValidator<'a> {
+    binary:  &'a [u8],
+    decoder: StreamingDecoder<'a>,
+    /// Next byte of the binary to be handled
+    offset:  usize,
+    code:    Option<PendingCode>,
+    /// Error of the slice that failed, reported by every later slice
+    error:   Option<Error>,
+}
+
+impl<'a> SlicedValidator<'a> {
+    /// Start validating `binary`, checking only its header
+    pub fn new(binary: &'a [u8]) -> Result<Self> {
+        let mut decoder = StreamingDecoder::new(binary)?;
+        decoder.decode_header()?;
+
+        Ok(Self {
+            binary,
+            decoder,
+            offset: 8,
+            code: None,
+            error: None,
+        })
+    }
+
+    /// Handle up to `budget` bytes of the binary
+    ///
+    /// Returns `true` once the whole binary is validated.
+    ///
+    /// # Errors
+    ///
+    /// Returns the validation error of the module, on this and every later
+    /// call.
+    pub fn step(&mut self, budget: usize) -> Result<bool> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        let start = self.offset;
+        while !self.is_complete() {
+            if let Err(error) = self.advance() {
+                self.error = Some(error);
+                return Err(error);
+            }
+            if self.offset - start >= budget {
+                break;
+            }
+        }
+        Ok(self.is_complete())
+    }
+
+    /// Whether the whole binary is validated
+    pub fn is_complete(&self) -> bool {
+        self.code.is_none() && self.offset >= self.binary.len()
+    }
+
+    /// Number of bytes validated and total size of the binary
+    pub fn progress(&self) -> (usize, usize) {
+        (self.offset, self.binary.len())
+    }
+
+    /// Finish validation and return the decoded module (std version)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if validation failed or is not yet complete.
+    #[cfg(feature = "std")]
+    pub fn finish(self) -> Result<WrtModule> {
+        self.check_finished()?;
+        self.decoder.finish()
+    }
+
+    /// Finish validation and return the decoded module (no_std version)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if validation failed or is not yet complete.
+    #[cfg(not(feature = "std"))]
+    pub fn finish(self) -> Result<WrtModule<NoStdProvider<8192>>> {
+        self.check_finished()?;
+        self.decoder.finish()
+    }
+
+    fn check_finished(&self) -> Result<()> {
+        match self.error {
+            Some(error) => Err(error),
+            None if !self.is_complete() => Err(Error::runtime_invalid_state(
+                "Module validation is not complete",
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Handle the next section, or the next function body of the code
+    /// section
+    fn advance(&mut self) -> Result<()> {
+        if let Some(code) = &mut self.code {
+            if code.remaining == 0 {
+                let bodies = core::mem::take(&mut code.bodies);
+                self.offset = code.end;
+                self.code = None;
+                return self.decoder.apply_decoded_section(DecodedSection::Code(bodies));
+            }
+
+            let index = code.count - code.remaining;
+            let mut offset = self.offset;
+            let body =
+                decode_function_body(&self.binary[..code.end], &mut offset).map_err(|error| {
+                    let frame =
+                        item_context("decoding function body", index, offset - code.payload);
+                    section_error(error.with_context(frame), 10, code.section, code.payload)
+                })?;
+
+            code.bodies.push(body);
+            code.remaining -= 1;
+            self.offset = offset;
+            return Ok(());
+        }
+
+        let section_id = self.binary[self.offset];
+        let (section_size, bytes_read) = read_leb128_u32(self.binary, self.offset + 1)?;
+        let start = self.offset + 1 + bytes_read;
+        let end = start
+            .checked_add(section_size as usize)
+            .filter(|end| *end <= self.binary.len())
+            .ok_or_else(|| Error::parse_error("Section extends beyond binary"))?;
+
+        if section_id == 10 {
+            let (count, bytes_read) = read_leb128_u32(&self.binary[..end], start)?;
+            self.code = Some(PendingCode {
+                section: self.offset,
+                payload: start,
+                end,
+                count,
+                remaining: count,
+                bodies: alloc::vec::Vec::new(),
+            });
+            self.offset = start + bytes_read;
+            return Ok(());
+        }
+
+        self.decoder
+            .process_section(section_id, &self.binary[start..end])
+            .map_err(|error| section_error(error, section_id, self.offset, start))?;
+        self.offset = end;
+        Ok(())
+    }
+}
+
+impl core::fmt::Debug for SlicedValidator<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SlicedValidator")
+            .field("offset", &self.offset)
+            .field("len", &self.binary.len())
+            .field("error", &self.error)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::{
+        streaming_decoder::decode_module_streaming,
+        test_fixtures::{
+            two_function_module,
+            ModuleBinary,
+        },
+    };
+
+    /// Module with two functions and an export
+    fn sample_module() -> Vec<u8> {
+        two_function_module().build()
+    }
+
+    #[test]
+    fn test_slices_match_streaming_decode() {
+        let binary = sample_module();
+        let expected = decode_module_streaming(&binary).unwrap();
+
+        let mut validator = SlicedValidator::new(&binary).unwrap();
+        let mut slices = 0;
+        while !validator.step(1).unwrap() {
+            slices += 1;
+            assert!(!validator.is_complete());
+        }
+        // Three sections, the code section header and two bodies take a slice
+        // each, merging the bodies completes the last one
+        assert_eq!(slices, 6);
+        assert_eq!(validator.progress(), (binary.len(), binary.len()));
+
+        let module = validator.finish().unwrap();
+        assert_eq!(module.types, expected.types);
+        assert_eq!(module.functions.len(), 2);
+        for (sliced, streamed) in module.functions.iter().zip(expected.functions.iter()) {
+            assert_eq!(
+                (sliced.type_idx, &sliced.code),
+                (streamed.type_idx, &streamed.code)
+            );
+        }
+        assert_eq!(module.exports.len(), 1);
+
+        // A large budget validates everything in one slice
+        let mut validator = SlicedValidator::new(&binary).unwrap();
+        assert!(validator.step(usize::MAX).unwrap());
+    }
+
+    #[test]
+    fn test_errors_are_sticky() {
+        // The second body claims more bytes than the section holds
+        let binary = ModuleBinary::new().section(10, &[0x02, 0x02, 0x00, 0x0B, 0x10, 0x00]).build();
+
+        let mut validator = SlicedValidator::new(&binary).unwrap();
+        assert!(!validator.step(1).unwrap());
+        assert!(!validator.step(1).unwrap());
+        let error = validator.step(1).unwrap_err();
+        let expected = decode_module_streaming(&binary).unwrap_err();
+        assert_eq!(error.message, expected.message);
+        assert_eq!(validator.step(1).unwrap_err().message, error.message);
+        assert!(validator.finish().is_err());
+
+        assert!(SlicedValidator::new(b"\0asm").is_err());
+    }
+}
@@ -3,14 +3,58 @@
 //! This module provides a streaming API for decoding WebAssembly modules
 //! that processes sections one at a time without loading the entire binary
 //! into memory.
+//!
+//! A decoder created with [`StreamingDecoder::incremental`] does not need the
+//! binary up front: bytes are handed to [`StreamingDecoder::push`] in chunks
+//! of any size as they arrive, for example from the network or while paging
+//! through flash. Each section is decoded and validated against the platform
+//! limits as soon as its last byte arrives, so only the bytes of the section
+//! in progress are buffered and validation is complete when the last chunk
+//! is pushed.
 
 #[cfg(not(feature = "std"))]
 extern crate alloc;
 
+use wrt_error::{
+    ContextFrame,
+    ResultExt,
+};
 use wrt_format::module::{
     Function,
     Module as WrtModule,
 };
+#[cfg(feature = "std")]
+use wrt_format::{
+    binary::{
+        read_leb128_i32,
+        read_leb128_i64,
+    },
+    conversion::parse_value_type,
+    module::{
+        Global,
+        Import,
+        ImportDesc,
+    },
+    pure_format_types::{
+        PureDataMode,
+        PureDataSegment,
+        PureElementInit,
+        PureElementMode,
+        PureElementSegment,
+    },
+    section::CustomSection,
+    types::FormatGlobalType,
+};
+#[cfg(feature = "std")]
+use wrt_foundation::{
+    types::{
+        Limits,
+        MemoryType,
+        RefType,
+        TableType,
+    },
+    CleanCoreFuncType,
+};
 use wrt_foundation::{
     bounded::BoundedVec,
     safe_memory::NoStdProvider,
@@ -24,6 +68,67 @@
     },
 };
 
+/// Size of the module header (magic number and version)
+const HEADER_SIZE: usize = 8;
+
+/// Context operation of decoding each section, indexed by section id
+const SECTION_OPERATIONS: [&str; 13] = [
+    "decoding custom section",
+    "decoding type section",
+    "decoding import section",
+    "decoding function section",
+    "decoding table section",
+    "decoding memory section",
+    "decoding global section",
+    "decoding export section",
+    "decoding start section",
+    "decoding element section",
+    "decoding code section",
+    "decoding data section",
+    "decoding data count section",
+];
+
+/// Offset as stored in a [`ContextFrame`]
+fn frame_offset(offset: usize) -> u32 {
+    u32::try_from(offset).unwrap_or(u32::MAX)
+}
+
+/// Id of the section whose decoding a context frame with `operation` names
+pub(crate) fn section_of_operation(operation: &str) -> Option<u8> {
+    let id = SECTION_OPERATIONS.iter().position(|name| *name == operation)?;
+    u8::try_from(id).ok()
+}
+
+/// Attribute `error`, raised while decoding the payload at `payload_offset`
+/// of the section whose id byte is at `section_offset`
+///
+/// Frames added for the items of a section carry offsets relative to its
+/// payload; they are made offsets in the module here.
+pub(crate) fn section_error(
+    error: Error,
+    section_id: u8,
+    section_offset: usize,
+    payload_offset: usize,
+) -> Error {
+    let mut located = Error::new(error.category, error.code, error.message);
+    for frame in error.context().iter() {
+        located = located.with_context(match frame.offset() {
+            Some(offset) => frame.with_offset(offset.saturating_add(frame_offset(payload_offset))),
+            None => frame,
+        });
+    }
+    let operation = SECTION_OPERATIONS
+        .get(usize::from(section_id))
+        .unwrap_or(&SECTION_OPERATIONS[0]);
+    located.with_context(ContextFrame::new(operation).with_offset(frame_offset(section_offset)))
+}
+
+/// Error context frame for decoding item `index` of a section, which failed
+/// at `offset` within the section payload
+pub(crate) fn item_context(operation: &'static str, index: u32, offset: usize) -> ContextFrame {
+    ContextFrame::new(operation).with_item(index).with_offset(frame_offset(offset))
+}
+
 /// Streaming decoder that processes WebAssembly modules section by section
 pub struct StreamingDecoder<'a> {
     /// The WebAssembly binary data
@@ -32,6 +137,13 @@ pub struct StreamingDecoder<'a> {
     offset:          usize,
     /// Platform limits for validation
     platform_limits: ComprehensivePlatformLimits,
+    /// Validator of a binary pushed in chunks, `None` for a binary given as
+    /// a whole
+    validator:       Option<StreamingWasmValidator>,
+    /// Pushed bytes of the header or section not yet complete
+    pending:         alloc::vec::Vec<u8>,
+    /// Error of the pushed binary, reported by every later call
+    error:           Option<Error>,
     /// The module being built (std version)
     #[cfg(feature = "std")]
     module:          WrtModule,
@@ -40,6 +152,14 @@ pub struct StreamingDecoder<'a> {
     module:          WrtModule<NoStdProvider<8192>>,
 }
 
+/// Export of the module being built
+#[cfg(feature = "std")]
+type ModuleExport = wrt_format::module::Export;
+
+/// Export of the module being built
+#[cfg(not(feature = "std"))]
+type ModuleExport = wrt_format::module::Export<NoStdProvider<8192>>;
+
 impl<'a> StreamingDecoder<'a> {
     /// Create a new streaming decoder (std version)
     #[cfg(feature = "std")]
@@ -50,6 +170,9 @@ pub fn new(binary: &'a [u8]) -> Result<Self> {
             binary,
             offset: 0,
             platform_limits: ComprehensivePlatformLimits::default(),
+            validator: None,
+            pending: alloc::vec::Vec::new(),
+            error: None,
             module,
         })
     }
@@ -67,30 +190,162 @@ pub fn new(binary: &'a [u8]) -> Result<Self> {
             binary,
             offset: 0,
             platform_limits: ComprehensivePlatformLimits::default(),
+            validator: None,
+            pending: alloc::vec::Vec::new(),
+            error: None,
             module,
         })
     }
 
-    /// Decode the module header
-    pub fn decode_header(&mut self) -> Result<()> {
-        // Validate magic number and version
-        if self.binary.len() < 8 {
-            return Err(Error::parse_error(
-                "Binary too small for WebAssembly header",
+    /// Create a decoder for a binary handed to [`push`](Self::push) in
+    /// chunks, validated against the default platform limits
+    pub fn incremental() -> Result<StreamingDecoder<'static>> {
+        StreamingDecoder::incremental_with_limits(ComprehensivePlatformLimits::default())
+    }
+
+    /// Create a decoder for a binary handed to [`push`](Self::push) in
+    /// chunks, validated against `limits`
+    pub fn incremental_with_limits(
+        limits: ComprehensivePlatformLimits,
+    ) -> Result<StreamingDecoder<'static>> {
+        let mut decoder = StreamingDecoder::new(&[])?;
+        decoder.validator = Some(StreamingWasmValidator::new(limits));
+        Ok(decoder)
+    }
+
+    /// Decode and validate the sections completed by the next `chunk` of
+    /// the binary
+    ///
+    /// Bytes of a section that is not complete yet are kept until the rest
+    /// of it is pushed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the decoder was not created with
+    /// [`incremental`](Self::incremental), or if a completed part of the
+    /// binary is malformed or exceeds the platform limits.
+    pub fn push(&mut self, chunk: &[u8]) -> Result<()> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        let Some(mut validator) = self.validator.take() else {
+            return Err(Error::runtime_invalid_state(
+                "Decoder does not accept pushed bytes",
             ));
+        };
+        self.pending.extend_from_slice(chunk);
+        let pending = core::mem::take(&mut self.pending);
+
+        let result = self.consume_pending(&pending, &mut validator);
+        self.validator = Some(validator);
+        let consumed = result.map_err(|error| *self.error.insert(error))?;
+
+        self.offset += consumed;
+        self.pending = pending;
+        self.pending.drain(..consumed);
+        Ok(())
+    }
+
+    /// Decode the complete header and sections at the start of `pending`,
+    /// returning the number of bytes they take
+    fn consume_pending(
+        &mut self,
+        pending: &[u8],
+        validator: &mut StreamingWasmValidator,
+    ) -> Result<usize> {
+        let mut consumed = 0;
+        if self.offset == 0 {
+            if pending.len() < HEADER_SIZE {
+                return Ok(0);
+            }
+            Self::check_header(&pending[..HEADER_SIZE])?;
+            validator.begin(&pending[..HEADER_SIZE])?;
+            consumed = HEADER_SIZE;
+        }
+
+        while let Some((&section_id, rest)) = pending[consumed..].split_first() {
+            let (section_size, bytes_read) = match read_leb128_u32(rest, 0) {
+                Ok(size) => size,
+                // The size is still arriving
+                Err(_) if rest.len() < 5 && rest.iter().all(|byte| byte & 0x80 != 0) => break,
+                Err(error) => return Err(error),
+            };
+            let Some(section_data) = rest.get(bytes_read..bytes_read + section_size as usize)
+            else {
+                break;
+            };
+
+            let section_offset = self.offset + consumed;
+            self.process_section(section_id, section_data)
+                .and_then(|()| validator.validate_section_data(section_id, section_data))
+                .map_err(|error| {
+                    let payload_offset = section_offset + 1 + bytes_read;
+                    section_error(error, section_id, section_offset, payload_offset)
+                })?;
+            consumed += 1 + bytes_read + section_data.len();
+        }
+
+        Ok(consumed)
+    }
+
+    /// Number of pushed bytes buffered until their section is complete
+    pub fn buffered_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Number of pushed bytes decoded so far
+    pub fn decoded_len(&self) -> usize {
+        self.offset
+    }
+
+    /// Validator of a binary pushed in chunks
+    pub fn validator(&self) -> Option<&StreamingWasmValidator> {
+        self.validator.as_ref()
+    }
+
+    /// Check that a pushed binary ended after a complete section and finish
+    /// its validation
+    fn complete_input(&mut self) -> Result<()> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        let Some(validator) = &mut self.validator else {
+            return Ok(());
+        };
+        if self.offset < HEADER_SIZE || !self.pending.is_empty() {
+            return Err(Error::parse_error("Module binary ends within a section"));
         }
+        validator.complete()?;
+        Ok(())
+    }
 
+    /// Check the magic number and version of a module header
+    fn check_header(header: &[u8]) -> Result<()> {
         // Check magic number
-        if &self.binary[0..4] != b"\0asm" {
+        if &header[0..4] != b"\0asm" {
             return Err(Error::parse_error("Invalid WebAssembly magic number"));
         }
 
         // Check version
-        if &self.binary[4..8] != &[0x01, 0x00, 0x00, 0x00] {
+        if header[4..8] != [0x01, 0x00, 0x00, 0x00] {
             return Err(Error::parse_error("Unsupported WebAssembly version"));
         }
 
-        self.offset = 8;
+        Ok(())
+    }
+
+    /// Decode the module header
+    pub fn decode_header(&mut self) -> Result<()> {
+        // Validate magic number and version
+        if self.binary.len() < 8 {
+            return Err(Error::parse_error(
+                "Binary too small for WebAssembly header",
+            ));
+        }
+
+        Self::check_header(&self.binary[..HEADER_SIZE])?;
+
+        self.offset = HEADER_SIZE;
         Ok(())
     }
 
@@ -101,6 +356,7 @@ pub fn process_next_section(&mut self) -> Result<bool> {
         }
 
         // Read section ID
+        let section_offset = self.offset;
         let section_id = self.binary[self.offset];
         self.offset += 1;
 
@@ -115,14 +371,15 @@ pub fn process_next_section(&mut self) -> Result<bool> {
 
         // Process section data without loading it all into memory
         let section_data = &self.binary[self.offset..section_end];
-        self.process_section(section_id, section_data)?;
+        self.process_section(section_id, section_data)
+            .map_err(|error| section_error(error, section_id, section_offset, self.offset))?;
 
         self.offset = section_end;
         Ok(true)
     }
 
     /// Process a specific section
-    fn process_section(&mut self, section_id: u8, data: &[u8]) -> Result<()> {
+    pub(crate) fn process_section(&mut self, section_id: u8, data: &[u8]) -> Result<()> {
         match section_id {
             1 => self.process_type_section(data),
             2 => self.process_import_section(data),
@@ -141,6 +398,13 @@ fn process_section(&mut self, section_id: u8, data: &[u8]) -> Result<()> {
     }
 
     /// Process type section
+    #[cfg(feature = "std")]
+    fn process_type_section(&mut self, data: &[u8]) -> Result<()> {
+        self.apply_decoded_section(DecodedSection::Types(decode_type_section(data)?))
+    }
+
+    /// Process type section
+    #[cfg(not(feature = "std"))]
     fn process_type_section(&mut self, data: &[u8]) -> Result<()> {
         let mut offset = 0;
         let (count, bytes_read) = read_leb128_u32(data, offset)?;
@@ -156,6 +420,13 @@ fn process_type_section(&mut self, data: &[u8]) -> Result<()> {
     }
 
     /// Process import section
+    #[cfg(feature = "std")]
+    fn process_import_section(&mut self, data: &[u8]) -> Result<()> {
+        self.apply_decoded_section(DecodedSection::Imports(decode_import_section(data)?))
+    }
+
+    /// Process import section
+    #[cfg(not(feature = "std"))]
     fn process_import_section(&mut self, data: &[u8]) -> Result<()> {
         let mut offset = 0;
         let (count, bytes_read) = read_leb128_u32(data, offset)?;
@@ -171,29 +442,18 @@ fn process_import_section(&mut self, data: &[u8]) -> Result<()> {
 
     /// Process function section
     fn process_function_section(&mut self, data: &[u8]) -> Result<()> {
-        let mut offset = 0;
-        let (count, bytes_read) = read_leb128_u32(data, offset)?;
-        offset += bytes_read;
-
-        // Reserve space for functions
-        for _ in 0..count {
-            let (type_idx, bytes_read) = read_leb128_u32(data, offset)?;
-            offset += bytes_read;
-
-            // Create function with empty body for now
-            let func = Function {
-                type_idx,
-                locals: alloc::vec::Vec::new(),
-                code: alloc::vec::Vec::new(),
-            };
-
-            let _ = self.module.functions.push(func);
-        }
+        self.apply_decoded_section(DecodedSection::Functions(decode_function_section(data)?))
+    }
 
+    /// Process table section
+    #[cfg(feature = "std")]
+    fn process_table_section(&mut self, data: &[u8]) -> Result<()> {
+        self.module.tables.extend(decode_table_section(data)?);
         Ok(())
     }
 
     /// Process table section
+    #[cfg(not(feature = "std"))]
     fn process_table_section(&mut self, data: &[u8]) -> Result<()> {
         // Parse tables one at a time
         Ok(())
@@ -213,14 +473,26 @@ fn process_memory_section(&mut self, data: &[u8]) -> Result<()> {
         }
 
         // Process each memory one at a time
-        for _ in 0..count {
-            // Parse memory type and validate against platform limits
+        #[cfg(feature = "std")]
+        for index in 0..count {
+            let (limits, shared, next) = decode_limits(data, offset)
+                .with_context(|| item_context("decoding memory", index, offset))?;
+            offset = next;
+            self.module.memories.push(MemoryType { limits, shared });
         }
 
         Ok(())
     }
 
     /// Process global section
+    #[cfg(feature = "std")]
+    fn process_global_section(&mut self, data: &[u8]) -> Result<()> {
+        self.module.globals.extend(decode_global_section(data)?);
+        Ok(())
+    }
+
+    /// Process global section
+    #[cfg(not(feature = "std"))]
     fn process_global_section(&mut self, data: &[u8]) -> Result<()> {
         // Parse globals one at a time
         Ok(())
@@ -228,39 +500,49 @@ fn process_global_section(&mut self, data: &[u8]) -> Result<()> {
 
     /// Process export section
     fn process_export_section(&mut self, data: &[u8]) -> Result<()> {
-        use wrt_format::binary::read_leb128_u32;
-
-        use crate::optimized_string::parse_utf8_string_inplace;
-
         let (count, mut offset) = read_leb128_u32(data, 0)?;
 
-        for _ in 0..count {
-            // Parse export name
-            let (export_name, new_offset) = parse_utf8_string_inplace(data, offset)?;
-            offset = new_offset;
+        for index in 0..count {
+            let export = Self::decode_export(data, &mut offset)
+                .with_context(|| item_context("decoding export", index, offset))?;
+            self.module.exports.push(export);
+        }
 
-            if offset >= data.len() {
-                return Err(Error::parse_error("Unexpected end of export kind"));
-            }
+        Ok(())
+    }
 
-            // Parse export kind
-            let kind_byte = data[offset];
-            offset += 1;
+    /// Decode the export at `offset`, advancing it past the export
+    fn decode_export(data: &[u8], offset: &mut usize) -> Result<ModuleExport> {
+        use crate::optimized_string::parse_utf8_string_inplace;
 
-            let kind = match kind_byte {
-                0x00 => wrt_format::module::ExportKind::Function,
-                0x01 => wrt_format::module::ExportKind::Table,
-                0x02 => wrt_format::module::ExportKind::Memory,
-                0x03 => wrt_format::module::ExportKind::Global,
-                _ => return Err(Error::parse_error("Invalid export kind")),
-            };
+        // Parse export name
+        let (export_name, bytes_read) = parse_utf8_string_inplace(data, *offset)?;
+        *offset += bytes_read;
 
-            // Parse export index
-            let (index, new_offset) = read_leb128_u32(data, offset)?;
-            offset = new_offset;
+        if *offset >= data.len() {
+            return Err(Error::parse_error("Unexpected end of export kind"));
+        }
 
-            // Add export to module
-            // Convert the smaller BoundedString to the larger one expected by Export
+        // Parse export kind
+        let kind = match data[*offset] {
+            0x00 => wrt_format::module::ExportKind::Function,
+            0x01 => wrt_format::module::ExportKind::Table,
+            0x02 => wrt_format::module::ExportKind::Memory,
+            0x03 => wrt_format::module::ExportKind::Global,
+            _ => return Err(Error::parse_error("Invalid export kind")),
+        };
+        *offset += 1;
+
+        // Parse export index
+        let (index, bytes_read) = read_leb128_u32(data, *offset)?;
+        *offset += bytes_read;
+
+        #[cfg(feature = "std")]
+        let name = export_name;
+
+        // Convert the smaller BoundedString to the larger one expected by Export
+        #[cfg(not(feature = "std"))]
+        let name = {
             let name_str = export_name.as_str();
             let provider = wrt_foundation::safe_managed_alloc!(
                 8192,
@@ -270,18 +552,13 @@ fn process_export_section(&mut self, data: &[u8]) -> Result<()> {
                 256,
                 wrt_foundation::NoStdProvider<8192>,
             > = wrt_foundation::BoundedString::from_str(name_str, provider)?;
+            export_name_large
+                .as_str()
+                .map_err(|_| Error::parse_error("Invalid export name"))?
+                .to_string()
+        };
 
-            self.module.exports.push(wrt_format::module::Export {
-                name: export_name_large
-                    .as_str()
-                    .map_err(|_| Error::parse_error("Invalid export name"))?
-                    .to_string(),
-                kind,
-                index,
-            });
-        }
-
-        Ok(())
+        Ok(ModuleExport { name, kind, index })
     }
 
     /// Process start section
@@ -292,6 +569,14 @@ fn process_start_section(&mut self, data: &[u8]) -> Result<()> {
     }
 
     /// Process element section
+    #[cfg(feature = "std")]
+    fn process_element_section(&mut self, data: &[u8]) -> Result<()> {
+        self.module.elements.extend(decode_element_section(data)?);
+        Ok(())
+    }
+
+    /// Process element section
+    #[cfg(not(feature = "std"))]
     fn process_element_section(&mut self, data: &[u8]) -> Result<()> {
         // Parse elements one at a time
         Ok(())
@@ -299,35 +584,49 @@ fn process_element_section(&mut self, data: &[u8]) -> Result<()> {
 
     /// Process code section
     fn process_code_section(&mut self, data: &[u8]) -> Result<()> {
-        let mut offset = 0;
-        let (count, bytes_read) = read_leb128_u32(data, offset)?;
-        offset += bytes_read;
-
-        // Process each function body one at a time
-        for i in 0..count {
-            let (body_size, bytes_read) = read_leb128_u32(data, offset)?;
-            offset += bytes_read;
-
-            // Instead of loading entire body, we could process instructions
-            // one at a time for even lower memory usage
-            let body_end = offset + body_size as usize;
-            if body_end > data.len() {
-                return Err(Error::parse_error("Function body extends beyond section"));
-            }
-
-            // For now, copy the body - but this could be optimized further
-            if let Some(func) = self.module.functions.get_mut(i as usize) {
-                let body_data = &data[offset..body_end];
-                func.code.extend_from_slice(body_data);
-            }
+        self.apply_decoded_section(DecodedSection::Code(decode_code_section(data)?))
+    }
 
-            offset = body_end;
+    /// Merge a section decoded by [`decode_independent_section`] into the
+    /// module being built
+    pub(crate) fn apply_decoded_section(&mut self, section: DecodedSection) -> Result<()> {
+        match section {
+            #[cfg(feature = "std")]
+            DecodedSection::Types(types) => self.module.types.extend(types),
+            #[cfg(feature = "std")]
+            DecodedSection::Imports(imports) => self.module.imports.extend(imports),
+            DecodedSection::Functions(type_indices) => {
+                for type_idx in type_indices {
+                    // Bodies are attached when the code section is applied
+                    let func = Function {
+                        type_idx,
+                        locals: alloc::vec::Vec::new(),
+                        code: alloc::vec::Vec::new(),
+                    };
+
+                    let _ = self.module.functions.push(func);
+                }
+            },
+            DecodedSection::Code(bodies) => {
+                for (i, body) in bodies.into_iter().enumerate() {
+                    if let Some(func) = self.module.functions.get_mut(i) {
+                        func.code = body;
+                    }
+                }
+            },
         }
+        Ok(())
+    }
 
+    /// Process data section
+    #[cfg(feature = "std")]
+    fn process_data_section(&mut self, data: &[u8]) -> Result<()> {
+        self.module.data.extend(decode_data_section(data)?);
         Ok(())
     }
 
     /// Process data section
+    #[cfg(not(feature = "std"))]
     fn process_data_section(&mut self, data: &[u8]) -> Result<()> {
         // Parse data segments one at a time
         Ok(())
@@ -340,6 +639,18 @@ fn process_data_count_section(&mut self, _data: &[u8]) -> Result<()> {
     }
 
     /// Process custom section
+    #[cfg(feature = "std")]
+    fn process_custom_section(&mut self, data: &[u8]) -> Result<()> {
+        let (name, offset) = decode_name(data, 0)?;
+        self.module.custom_sections.push(CustomSection {
+            name,
+            data: data[offset..].to_vec(),
+        });
+        Ok(())
+    }
+
+    /// Process custom section
+    #[cfg(not(feature = "std"))]
     fn process_custom_section(&mut self, _data: &[u8]) -> Result<()> {
         // Skip custom sections or process specific ones
         Ok(())
@@ -347,18 +658,516 @@ fn process_custom_section(&mut self, _data: &[u8]) -> Result<()> {
 
     /// Finish decoding and return the module
     /// Finish decoding and return the module (std version)
+    ///
+    /// For a binary pushed in chunks, this fails if the binary ended within
+    /// a section or the module exceeds the platform limits.
     #[cfg(feature = "std")]
-    pub fn finish(self) -> Result<WrtModule> {
+    pub fn finish(mut self) -> Result<WrtModule> {
+        self.complete_input()?;
         Ok(self.module)
     }
 
     /// Finish decoding and return the module (no_std version)
+    ///
+    /// For a binary pushed in chunks, this fails if the binary ended within
+    /// a section or the module exceeds the platform limits.
     #[cfg(not(feature = "std"))]
-    pub fn finish(self) -> Result<WrtModule<NoStdProvider<8192>>> {
+    pub fn finish(mut self) -> Result<WrtModule<NoStdProvider<8192>>> {
+        self.complete_input()?;
         Ok(self.module)
     }
 }
 
+/// Section payload decoded without access to the module being built
+///
+/// Sections of these kinds only depend on their own bytes, so they can be
+/// decoded in any order (or concurrently) and merged into the module with
+/// [`StreamingDecoder::apply_decoded_section`] afterwards.
+#[derive(Debug)]
+pub(crate) enum DecodedSection {
+    /// Function types of the type section
+    #[cfg(feature = "std")]
+    Types(Vec<CleanCoreFuncType>),
+    /// Entries of the import section
+    #[cfg(feature = "std")]
+    Imports(Vec<Import>),
+    /// Type indices of the function section
+    Functions(alloc::vec::Vec<u32>),
+    /// Function bodies of the code section
+    Code(alloc::vec::Vec<alloc::vec::Vec<u8>>),
+}
+
+/// Check whether a section can be decoded independently of the others
+pub(crate) fn is_independent_section(section_id: u8) -> bool {
+    match section_id {
+        #[cfg(feature = "std")]
+        1 | 2 => true,
+        3 | 10 => true,
+        _ => false,
+    }
+}
+
+/// Decode a section that does not depend on the module being built.
+///
+/// Returns `None` for sections that have to be processed in order.
+pub(crate) fn decode_independent_section(
+    section_id: u8,
+    data: &[u8],
+) -> Result<Option<DecodedSection>> {
+    let section = match section_id {
+        #[cfg(feature = "std")]
+        1 => DecodedSection::Types(decode_type_section(data)?),
+        #[cfg(feature = "std")]
+        2 => DecodedSection::Imports(decode_import_section(data)?),
+        3 => DecodedSection::Functions(decode_function_section(data)?),
+        10 => DecodedSection::Code(decode_code_section(data)?),
+        _ => return Ok(None),
+    };
+    Ok(Some(section))
+}
+
+/// Upper bound for pre-allocation driven by untrusted element counts
+const MAX_PREALLOCATED_ENTRIES: usize = 1024;
+
+/// Read a byte, failing if the section ends early
+fn read_byte(data: &[u8], offset: usize) -> Result<u8> {
+    data.get(offset)
+        .copied()
+        .ok_or_else(|| Error::parse_error("Unexpected end of section"))
+}
+
+/// Decode the vector of items making up a section with `decode_item`
+///
+/// `decode_item` advances the offset past each part of the item it decodes,
+/// so a failure is attributed to the index of the item and the offset of
+/// the part that could not be decoded.
+fn decode_items<T>(
+    data: &[u8],
+    operation: &'static str,
+    mut decode_item: impl FnMut(&[u8], &mut usize) -> Result<T>,
+) -> Result<alloc::vec::Vec<T>> {
+    let (count, mut offset) = read_leb128_u32(data, 0)?;
+    let mut items = alloc::vec::Vec::with_capacity((count as usize).min(MAX_PREALLOCATED_ENTRIES));
+
+    for index in 0..count {
+        let item = decode_item(data, &mut offset)
+            .with_context(|| item_context(operation, index, offset))?;
+        items.push(item);
+    }
+
+    Ok(items)
+}
+
+/// Decode the function section into the type index of every function
+fn decode_function_section(data: &[u8]) -> Result<alloc::vec::Vec<u32>> {
+    decode_items(data, "decoding function", |data, offset| {
+        let (type_idx, bytes_read) = read_leb128_u32(data, *offset)?;
+        *offset += bytes_read;
+        Ok(type_idx)
+    })
+}
+
+/// Decode the code section into the raw body of every function
+fn decode_code_section(data: &[u8]) -> Result<alloc::vec::Vec<alloc::vec::Vec<u8>>> {
+    decode_items(data, "decoding function body", decode_function_body)
+}
+
+/// Decode the raw function body at `offset`, advancing it past the body
+pub(crate) fn decode_function_body(data: &[u8], offset: &mut usize) -> Result<alloc::vec::Vec<u8>> {
+    let (body_size, bytes_read) = read_leb128_u32(data, *offset)?;
+    *offset += bytes_read;
+
+    let body_end = offset
+        .checked_add(body_size as usize)
+        .filter(|end| *end <= data.len())
+        .ok_or_else(|| Error::parse_error("Function body extends beyond section"))?;
+
+    let body = data[*offset..body_end].to_vec();
+    *offset = body_end;
+    Ok(body)
+}
+
+/// Decode a vector of value types, returning the types and the new offset
+#[cfg(feature = "std")]
+fn decode_value_types(data: &[u8], offset: usize) -> Result<(Vec<ValueType>, usize)> {
+    let (count, bytes_read) = read_leb128_u32(data, offset)?;
+    let mut offset = offset + bytes_read;
+    let mut types = Vec::with_capacity((count as usize).min(MAX_PREALLOCATED_ENTRIES));
+
+    for _ in 0..count {
+        types.push(parse_value_type(read_byte(data, offset)?)?);
+        offset += 1;
+    }
+
+    Ok((types, offset))
+}
+
+/// Decode the type section into function types
+#[cfg(feature = "std")]
+fn decode_type_section(data: &[u8]) -> Result<Vec<CleanCoreFuncType>> {
+    decode_items(data, "decoding type", |data, offset| {
+        if read_byte(data, *offset)? != 0x60 {
+            return Err(Error::parse_error("Expected function type marker (0x60)"));
+        }
+        *offset += 1;
+
+        let (params, next) = decode_value_types(data, *offset)?;
+        *offset = next;
+        let (results, next) = decode_value_types(data, *offset)?;
+        *offset = next;
+
+        Ok(CleanCoreFuncType { params, results })
+    })
+}
+
+/// Decode a length-prefixed UTF-8 name, returning it and the new offset
+#[cfg(feature = "std")]
+fn decode_name(data: &[u8], offset: usize) -> Result<(String, usize)> {
+    let (len, bytes_read) = read_leb128_u32(data, offset)?;
+    let start = offset + bytes_read;
+    let end = start
+        .checked_add(len as usize)
+        .filter(|end| *end <= data.len())
+        .ok_or_else(|| Error::parse_error("Name extends beyond section"))?;
+
+    let name = core::str::from_utf8(&data[start..end])
+        .map_err(|_| Error::parse_error("Invalid UTF-8 in name"))?;
+    Ok((name.to_string(), end))
+}
+
+/// Decode table or memory limits, returning them, the shared flag and the
+/// new offset
+#[cfg(feature = "std")]
+fn decode_limits(data: &[u8], offset: usize) -> Result<(Limits, bool, usize)> {
+    let flags = read_byte(data, offset)?;
+    if flags > 0x03 {
+        return Err(Error::parse_error("Invalid limits flags"));
+    }
+
+    let (min, bytes_read) = read_leb128_u32(data, offset + 1)?;
+    let mut offset = offset + 1 + bytes_read;
+
+    let max = if flags & 0x01 != 0 {
+        let (max, bytes_read) = read_leb128_u32(data, offset)?;
+        offset += bytes_read;
+        Some(max)
+    } else {
+        None
+    };
+
+    Ok((Limits { min, max }, flags & 0x02 != 0, offset))
+}
+
+/// Decode the import section
+#[cfg(feature = "std")]
+fn decode_import_section(data: &[u8]) -> Result<Vec<Import>> {
+    decode_items(data, "decoding import", decode_import)
+}
+
+/// Decode the import at `offset`, advancing it past each part of the import
+#[cfg(feature = "std")]
+fn decode_import(data: &[u8], offset: &mut usize) -> Result<Import> {
+    let (module, next) = decode_name(data, *offset)?;
+    *offset = next;
+    let (name, next) = decode_name(data, *offset)?;
+    *offset = next;
+
+    // The offset stays at the kind byte until the descriptor is decoded
+    let desc = match read_byte(data, *offset)? {
+        0x00 => {
+            let (type_idx, bytes_read) = read_leb128_u32(data, *offset + 1)?;
+            *offset += 1 + bytes_read;
+            ImportDesc::Function(type_idx)
+        },
+        0x01 => {
+            let (table, next) = decode_table_type(data, *offset + 1)?;
+            *offset = next;
+            ImportDesc::Table(table)
+        },
+        0x02 => {
+            let (limits, shared, next) = decode_limits(data, *offset + 1)?;
+            *offset = next;
+            ImportDesc::Memory(MemoryType { limits, shared })
+        },
+        0x03 => {
+            let (global_type, next) = decode_global_type(data, *offset + 1)?;
+            *offset = next;
+            ImportDesc::Global(global_type)
+        },
+        0x04 => {
+            if read_byte(data, *offset + 1)? != 0x00 {
+                return Err(Error::parse_error("Invalid tag attribute"));
+            }
+            let (type_idx, bytes_read) = read_leb128_u32(data, *offset + 2)?;
+            *offset += 2 + bytes_read;
+            ImportDesc::Tag(type_idx)
+        },
+        _ => return Err(Error::parse_error("Invalid import kind")),
+    };
+
+    Ok(Import { module, name, desc })
+}
+
+/// Decode a global type, returning it and the new offset
+#[cfg(feature = "std")]
+fn decode_global_type(data: &[u8], offset: usize) -> Result<(FormatGlobalType, usize)> {
+    let value_type = parse_value_type(read_byte(data, offset)?)?;
+    let mutable = match read_byte(data, offset + 1)? {
+        0x00 => false,
+        0x01 => true,
+        _ => return Err(Error::parse_error("Invalid global mutability")),
+    };
+    Ok((
+        FormatGlobalType {
+            value_type,
+            mutable,
+        },
+        offset + 2,
+    ))
+}
+
+/// Decode a table type, returning it and the new offset
+#[cfg(feature = "std")]
+fn decode_table_type(data: &[u8], offset: usize) -> Result<(TableType, usize)> {
+    let element_type = match read_byte(data, offset)? {
+        0x70 => RefType::Funcref,
+        0x6F => RefType::Externref,
+        _ => return Err(Error::parse_error("Invalid table element type")),
+    };
+    let (limits, _, next) = decode_limits(data, offset + 1)?;
+    Ok((
+        TableType {
+            element_type,
+            limits,
+        },
+        next,
+    ))
+}
+
+/// Decode the table section
+#[cfg(feature = "std")]
+fn decode_table_section(data: &[u8]) -> Result<Vec<TableType>> {
+    decode_items(data, "decoding table", |data, offset| {
+        let (table, next) = decode_table_type(data, *offset)?;
+        *offset = next;
+        Ok(table)
+    })
+}
+
+/// Decode a constant expression, returning its bytes including the final
+/// `end` and the new offset
+#[cfg(feature = "std")]
+fn decode_const_expr(data: &[u8], offset: usize) -> Result<(Vec<u8>, usize)> {
+    let start = offset;
+    let mut offset = offset;
+
+    loop {
+        let opcode = read_byte(data, offset)?;
+        offset += 1;
+        match opcode {
+            // end
+            0x0B => break,
+            // i32.const
+            0x41 => offset += read_leb128_i32(data, offset)?.1,
+            // i64.const
+            0x42 => offset += read_leb128_i64(data, offset)?.1,
+            // f32.const, f64.const
+            0x43 => offset += 4,
+            0x44 => offset += 8,
+            // global.get, ref.func
+            0x23 | 0xD2 => offset += read_leb128_u32(data, offset)?.1,
+            // ref.null
+            0xD0 => offset += 1,
+            // Extended constant expressions: i32/i64 add, sub and mul
+            0x6A | 0x6B | 0x6C | 0x7C | 0x7D | 0x7E => {},
+            // v128.const
+            0xFD => {
+                let (opcode, bytes_read) = read_leb128_u32(data, offset)?;
+                if opcode != 12 {
+                    return Err(Error::parse_error(
+                        "Invalid instruction in constant expression",
+                    ));
+                }
+                offset += bytes_read + 16;
+            },
+            _ => {
+                return Err(Error::parse_error(
+                    "Invalid instruction in constant expression",
+                ))
+            },
+        }
+        if offset > data.len() {
+            return Err(Error::parse_error(
+                "Constant expression extends beyond section",
+            ));
+        }
+    }
+
+    Ok((data[start..offset].to_vec(), offset))
+}
+
+/// Decode the global section
+#[cfg(feature = "std")]
+fn decode_global_section(data: &[u8]) -> Result<Vec<Global>> {
+    decode_items(data, "decoding global", |data, offset| {
+        let (global_type, next) = decode_global_type(data, *offset)?;
+        *offset = next;
+        let (init, next) = decode_const_expr(data, *offset)?;
+        *offset = next;
+
+        Ok(Global { global_type, init })
+    })
+}
+
+/// Decode a vector of function indices, returning them and the new offset
+#[cfg(feature = "std")]
+fn decode_function_indices(data: &[u8], offset: usize) -> Result<(Vec<u32>, usize)> {
+    let (count, bytes_read) = read_leb128_u32(data, offset)?;
+    let mut offset = offset + bytes_read;
+    let mut indices = Vec::with_capacity((count as usize).min(MAX_PREALLOCATED_ENTRIES));
+
+    for _ in 0..count {
+        let (index, bytes_read) = read_leb128_u32(data, offset)?;
+        offset += bytes_read;
+        indices.push(index);
+    }
+
+    Ok((indices, offset))
+}
+
+/// Decode the element section
+///
+/// Bit 0 of the segment flags marks a passive or declared segment, bit 1 an
+/// explicit table index (active) or a declared segment (otherwise), and bit 2
+/// initialization by expressions instead of function indices.
+#[cfg(feature = "std")]
+fn decode_element_section(data: &[u8]) -> Result<Vec<PureElementSegment>> {
+    decode_items(data, "decoding element segment", decode_element_segment)
+}
+
+/// Decode the element segment at `offset`, advancing it past each part of the
+/// segment
+#[cfg(feature = "std")]
+fn decode_element_segment(data: &[u8], offset: &mut usize) -> Result<PureElementSegment> {
+    let (flags, bytes_read) = read_leb128_u32(data, *offset)?;
+    if flags > 0x07 {
+        return Err(Error::parse_error("Invalid element segment flags"));
+    }
+    *offset += bytes_read;
+
+    let mut offset_expr_bytes = Vec::new();
+    let mode = if flags & 0x01 == 0 {
+        let table_index = if flags & 0x02 != 0 {
+            let (table_index, bytes_read) = read_leb128_u32(data, *offset)?;
+            *offset += bytes_read;
+            table_index
+        } else {
+            0
+        };
+        let (expr, next) = decode_const_expr(data, *offset)?;
+        *offset = next;
+        offset_expr_bytes = expr;
+        PureElementMode::Active {
+            table_index,
+            offset_expr_len: offset_expr_bytes.len() as u32,
+        }
+    } else if flags & 0x02 == 0 {
+        PureElementMode::Passive
+    } else {
+        PureElementMode::Declared
+    };
+
+    // Segments without explicit table index or type hold function
+    // references
+    let element_type = if flags & 0x03 == 0 {
+        RefType::Funcref
+    } else {
+        let byte = read_byte(data, *offset)?;
+        *offset += 1;
+        match (flags & 0x04 != 0, byte) {
+            (false, 0x00) | (true, 0x70) => RefType::Funcref,
+            (true, 0x6F) => RefType::Externref,
+            _ => return Err(Error::parse_error("Invalid element segment type")),
+        }
+    };
+
+    let init_data = if flags & 0x04 == 0 {
+        let (indices, next) = decode_function_indices(data, *offset)?;
+        *offset = next;
+        PureElementInit::FunctionIndices(indices)
+    } else {
+        let (count, bytes_read) = read_leb128_u32(data, *offset)?;
+        *offset += bytes_read;
+        let mut exprs = Vec::with_capacity((count as usize).min(MAX_PREALLOCATED_ENTRIES));
+        for _ in 0..count {
+            let (expr, next) = decode_const_expr(data, *offset)?;
+            *offset = next;
+            exprs.push(expr);
+        }
+        PureElementInit::ExpressionBytes(exprs)
+    };
+
+    Ok(PureElementSegment {
+        mode,
+        element_type,
+        offset_expr_bytes,
+        init_data,
+    })
+}
+
+/// Decode the data section
+#[cfg(feature = "std")]
+fn decode_data_section(data: &[u8]) -> Result<Vec<PureDataSegment>> {
+    decode_items(data, "decoding data segment", decode_data_segment)
+}
+
+/// Decode the data segment at `offset`, advancing it past each part of the
+/// segment
+#[cfg(feature = "std")]
+fn decode_data_segment(data: &[u8], offset: &mut usize) -> Result<PureDataSegment> {
+    let (flags, bytes_read) = read_leb128_u32(data, *offset)?;
+    if flags > 0x02 {
+        return Err(Error::parse_error("Invalid data segment flags"));
+    }
+    *offset += bytes_read;
+
+    let mut offset_expr_bytes = Vec::new();
+    let mode = if flags == 0x01 {
+        PureDataMode::Passive
+    } else {
+        let memory_index = if flags == 0x02 {
+            let (memory_index, bytes_read) = read_leb128_u32(data, *offset)?;
+            *offset += bytes_read;
+            memory_index
+        } else {
+            0
+        };
+        let (expr, next) = decode_const_expr(data, *offset)?;
+        *offset = next;
+        offset_expr_bytes = expr;
+        PureDataMode::Active {
+            memory_index,
+            offset_expr_len: offset_expr_bytes.len() as u32,
+        }
+    };
+
+    let (len, bytes_read) = read_leb128_u32(data, *offset)?;
+    *offset += bytes_read;
+    let end = offset
+        .checked_add(len as usize)
+        .filter(|end| *end <= data.len())
+        .ok_or_else(|| Error::parse_error("Data segment extends beyond section"))?;
+
+    let data_bytes = data[*offset..end].to_vec();
+    *offset = end;
+
+    Ok(PureDataSegment {
+        mode,
+        offset_expr_bytes,
+        data_bytes,
+    })
+}
+
 /// Decode a WebAssembly module using streaming processing (std version)
 #[cfg(feature = "std")]
 pub fn decode_module_streaming(binary: &[u8]) -> Result<WrtModule> {
@@ -389,3 +1198,87 @@ pub fn decode_module_streaming(binary: &[u8]) -> Result<WrtModule<NoStdProvider<
     // Return the completed module
     decoder.finish()
 }
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{
+        two_function_module,
+        ModuleBinary,
+    };
+
+    /// Module with two functions, an export and a custom section
+    fn sample_module() -> Vec<u8> {
+        two_function_module().custom("note", &[0x2A]).build()
+    }
+
+    #[test]
+    fn test_push_matches_whole_binary() {
+        let binary = sample_module();
+        let expected = decode_module_streaming(&binary).unwrap();
+
+        for chunk_size in [1, 3, 7, binary.len()] {
+            let mut decoder = StreamingDecoder::incremental().unwrap();
+            for chunk in binary.chunks(chunk_size) {
+                decoder.push(chunk).unwrap();
+                assert!(decoder.buffered_len() <= 16);
+            }
+            assert_eq!(decoder.decoded_len(), binary.len());
+
+            let module = decoder.finish().unwrap();
+            assert_eq!(module.functions.len(), expected.functions.len());
+            assert_eq!(module.functions[1].code, expected.functions[1].code);
+            assert_eq!(module.exports[0].name, "run");
+        }
+    }
+
+    #[test]
+    fn test_push_rejects_incomplete_and_malformed_binaries() {
+        let binary = sample_module();
+
+        // Binary ending within a section
+        let mut decoder = StreamingDecoder::incremental().unwrap();
+        decoder.push(&binary[..binary.len() - 2]).unwrap();
+        assert!(decoder.finish().is_err());
+
+        // Binary ending within the header
+        let mut decoder = StreamingDecoder::incremental().unwrap();
+        decoder.push(&binary[..5]).unwrap();
+        assert!(decoder.finish().is_err());
+
+        // Errors are reported by every later call
+        let mut decoder = StreamingDecoder::incremental().unwrap();
+        assert!(decoder.push(b"\0asm\x02\0\0\0").is_err());
+        assert!(decoder.push(&binary[8..]).is_err());
+        assert!(decoder.finish().is_err());
+
+        // Memory beyond the platform limit
+        let mut decoder = StreamingDecoder::incremental().unwrap();
+        decoder.push(&binary[..8]).unwrap();
+        assert!(decoder.push(&[0x05, 0x04, 0x01, 0x00, 0x80, 0x40]).is_err());
+
+        // A decoder of a whole binary does not take chunks
+        let mut decoder = StreamingDecoder::new(&binary).unwrap();
+        assert!(decoder.push(&binary).is_err());
+    }
+
+    #[test]
+    fn test_errors_name_the_failing_section() {
+        // Memory section at offset 8 whose limits flag is invalid
+        let binary = ModuleBinary::new().section(5, &[0x01, 0x07, 0x01]).build();
+
+        let error = decode_module_streaming(&binary).unwrap_err();
+        let mut frames = error.context().iter();
+        let item = frames.next().unwrap();
+        assert_eq!(item.operation(), "decoding memory");
+        assert_eq!(item.item(), Some(0));
+        assert_eq!(item.offset(), Some(11));
+        let section = frames.next().unwrap();
+        assert_eq!(section.operation(), "decoding memory section");
+        assert_eq!(section.offset(), Some(8));
+
+        let mut decoder = StreamingDecoder::incremental().unwrap();
+        let pushed = decoder.push(&binary).unwrap_err();
+        assert_eq!(pushed.context(), error.context());
+    }
+}
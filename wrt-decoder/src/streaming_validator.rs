@@ -110,6 +110,35 @@ pub struct WasmConfiguration {
 };
 pub use runtime_stubs::WasmConfiguration;
 
+#[cfg(feature = "platform-limits")]
+impl From<wrt_platform::PlatformId> for PlatformId {
+    fn from(platform_id: wrt_platform::PlatformId) -> Self {
+        match platform_id {
+            wrt_platform::PlatformId::Linux => Self::Linux,
+            wrt_platform::PlatformId::QNX => Self::QNX,
+            wrt_platform::PlatformId::MacOS => Self::MacOS,
+            wrt_platform::PlatformId::VxWorks => Self::VxWorks,
+            wrt_platform::PlatformId::Zephyr => Self::Zephyr,
+            wrt_platform::PlatformId::Tock => Self::Tock,
+            wrt_platform::PlatformId::Embedded => Self::Embedded,
+            wrt_platform::PlatformId::Unknown => Self::Unknown,
+        }
+    }
+}
+
+#[cfg(feature = "platform-limits")]
+impl From<wrt_platform::ComprehensivePlatformLimits> for ComprehensivePlatformLimits {
+    fn from(limits: wrt_platform::ComprehensivePlatformLimits) -> Self {
+        Self {
+            max_total_memory:       limits.max_total_memory,
+            max_wasm_linear_memory: limits.max_wasm_linear_memory,
+            max_stack_bytes:        limits.max_stack_bytes,
+            max_components:         limits.max_components,
+            platform_id:            limits.platform_id.into(),
+        }
+    }
+}
+
 /// WASM section types for validation
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Section {
@@ -351,8 +380,52 @@ pub fn validate_single_pass(&mut self, wasm_bytes: &[u8]) -> Result<WasmConfigur
             self.validate_section(&section)?;
         }
 
+        self.complete()
+    }
+
+    /// Start validating a module whose bytes arrive section by section,
+    /// checking its 8-byte header
+    pub fn begin(&mut self, header: &[u8]) -> Result<(), Error> {
+        self.requirements = WasmRequirements::default();
+        self.state = ValidationState::Header;
+        let result = self.validate_header(header);
+        self.state = match result {
+            Ok(()) => ValidationState::Sections,
+            Err(_) => ValidationState::Failed,
+        };
+        result
+    }
+
+    /// Validate the next section of a module started with
+    /// [`begin`](Self::begin)
+    pub fn validate_section_data(&mut self, section_id: u8, data: &[u8]) -> Result<(), Error> {
+        if self.state != ValidationState::Sections {
+            return Err(Error::runtime_invalid_state(
+                "Validator is not expecting sections",
+            ));
+        }
+        let result = self
+            .parse_section_type(section_id, data)
+            .and_then(|section| self.validate_section(&section));
+        if result.is_err() {
+            self.state = ValidationState::Failed;
+        }
+        result
+    }
+
+    /// Finish validation once all sections are validated
+    pub fn complete(&mut self) -> Result<WasmConfiguration, Error> {
+        if self.state != ValidationState::Sections {
+            return Err(Error::runtime_invalid_state(
+                "Validator is not expecting sections",
+            ));
+        }
+
         // Final validation against platform limits
-        self.validate_final_requirements()?;
+        if let Err(error) = self.validate_final_requirements() {
+            self.state = ValidationState::Failed;
+            return Err(error);
+        }
 
         self.state = ValidationState::Complete;
 
@@ -443,6 +516,8 @@ fn parse_section_type(&self, section_id: u8, section_data: &[u8]) -> Result<Sect
             9 => Ok(Section::Element),
             10 => self.parse_code_section(section_data),
             11 => Ok(Section::Data),
+            // The data count only restates the number of data segments
+            12 => Ok(Section::Data),
             _ => Err(Error::parse_error("Unknown section type ")),
         }
     }
@@ -568,26 +643,7 @@ fn validate_final_requirements(&self) -> Result<(), Error> {
 
     /// Read LEB128 unsigned 32-bit integer
     fn read_leb128_u32(&self, data: &[u8]) -> Result<(u32, usize), Error> {
-        let mut result = 0u32;
-        let mut shift = 0;
-        let mut bytes_read = 0;
-
-        for &byte in data.iter().take(5) {
-            // Max 5 bytes for u32
-            bytes_read += 1;
-            result |= ((byte & 0x7F) as u32) << shift;
-
-            if byte & 0x80 == 0 {
-                return Ok((result, bytes_read));
-            }
-
-            shift += 7;
-            if shift >= 32 {
-                return Err(Error::parse_error("LEB128 value too large"));
-            }
-        }
-
-        Err(Error::parse_error("Truncated LEB128 value "))
+        wrt_format::binary::read_leb128_u32(data, 0)
     }
 
     /// Get current validation state
@@ -606,12 +662,29 @@ pub fn requirements(&self) -> &WasmRequirements {
 
 impl PlatformWasmValidatorFactory {
     /// Create validator for current platform
+    ///
+    /// With the `platform-limits` feature the limits are discovered by
+    /// `wrt-platform`, otherwise the defaults apply.
     pub fn create_for_platform() -> Result<StreamingWasmValidator, Error> {
-        // In a real implementation, this would detect the current platform
+        #[cfg(feature = "platform-limits")]
+        let limits = wrt_platform::PlatformLimitDiscoverer::new().discover()?.into();
+        #[cfg(not(feature = "platform-limits"))]
         let limits = ComprehensivePlatformLimits::default();
         Ok(StreamingWasmValidator::new(limits))
     }
 
+    /// Create validator for the RTOS target described by `descriptor`
+    #[cfg(feature = "platform-limits")]
+    pub fn create_for_descriptor(
+        descriptor: &wrt_platform::RtosDescriptor,
+    ) -> Result<StreamingWasmValidator, Error> {
+        use wrt_platform::ComprehensiveLimitProvider;
+
+        Ok(StreamingWasmValidator::new(
+            descriptor.discover_limits()?.into(),
+        ))
+    }
+
     /// Create validator with specific limits
     pub fn create_with_limits(limits: ComprehensivePlatformLimits) -> StreamingWasmValidator {
         StreamingWasmValidator::new(limits)
@@ -708,6 +781,21 @@ fn test_factory_methods() {
         );
     }
 
+    #[cfg(feature = "platform-limits")]
+    #[test]
+    fn test_descriptor_limits() {
+        let descriptor =
+            wrt_platform::RtosDescriptor::new(wrt_platform::PlatformId::Zephyr, 256 * 1024, 1_000)
+                .with_pool_bytes(128 * 1024);
+        let validator = PlatformWasmValidatorFactory::create_for_descriptor(&descriptor).unwrap();
+
+        let limits = &validator.platform_limits;
+        assert_eq!(limits.platform_id, PlatformId::Zephyr);
+        assert_eq!(limits.max_total_memory, 256 * 1024);
+        assert_eq!(limits.max_wasm_linear_memory, 128 * 1024);
+        assert_eq!(limits.max_stack_bytes, 16 * 1024);
+    }
+
     #[test]
     fn test_requirements_validation() {
         let mut limits = ComprehensivePlatformLimits::default();
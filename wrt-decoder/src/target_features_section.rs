@@ -0,0 +1,237 @@
+//! WebAssembly Target Features Custom Section Parser
+//!
+//! This module requires the `std` feature.
+//!
+//! This module implements parsing for the "target_features" custom section
+//! described by the WebAssembly tool conventions. The linker records in it
+//! which WebAssembly features the code of a module was compiled to use, such
+//! as `simd128` or `bulk-memory`, so that a host can refuse a module needing
+//! a feature it has not enabled before instantiating it.
+//!
+//! # Custom Section Format
+//!
+//! ```text
+//! target_features_section ::= feature_count:u32 (prefix:u8 feature:name)*
+//! ```
+//!
+//! The prefix is `+` for a feature the module uses, `-` for a feature it
+//! must not be combined with, and `=` for a feature every module linked with
+//! it must use, which older toolchains emit.
+
+use std::{
+    string::String,
+    vec::Vec,
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_format::{
+    binary::{
+        read_leb128_u32,
+        read_string,
+        read_u8,
+    },
+    write_leb128_u32,
+    write_string,
+};
+
+use crate::custom_section_handler::find_custom_section;
+
+/// Target features section name constant
+pub const TARGET_FEATURES_SECTION_NAME: &str = "target_features";
+
+/// How a module relates to a target feature
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeaturePolicy {
+    /// The module uses the feature (`+`)
+    Used,
+    /// The module must not be combined with code using the feature (`-`)
+    Disallowed,
+    /// All code linked with the module must use the feature (`=`)
+    Required,
+}
+
+impl FeaturePolicy {
+    /// Parse a policy from its prefix byte
+    pub fn from_prefix(prefix: u8) -> Result<Self> {
+        match prefix {
+            b'+' => Ok(FeaturePolicy::Used),
+            b'-' => Ok(FeaturePolicy::Disallowed),
+            b'=' => Ok(FeaturePolicy::Required),
+            _ => Err(Error::parse_error("Invalid target feature prefix")),
+        }
+    }
+
+    /// Prefix byte of the policy
+    pub fn prefix(self) -> u8 {
+        match self {
+            FeaturePolicy::Used => b'+',
+            FeaturePolicy::Disallowed => b'-',
+            FeaturePolicy::Required => b'=',
+        }
+    }
+
+    /// Whether the code of the module depends on the feature
+    pub fn needs_feature(self) -> bool {
+        matches!(self, FeaturePolicy::Used | FeaturePolicy::Required)
+    }
+}
+
+/// A target feature entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetFeature {
+    /// How the module relates to the feature
+    pub policy: FeaturePolicy,
+    /// Feature name, such as `simd128`
+    pub name:   String,
+}
+
+/// Parsed "target_features" section
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TargetFeaturesSection {
+    /// Features in section order
+    pub features: Vec<TargetFeature>,
+}
+
+impl TargetFeaturesSection {
+    /// Policy of the module for the feature `name`, if the section lists it
+    pub fn policy(&self, name: &str) -> Option<FeaturePolicy> {
+        self.features
+            .iter()
+            .find(|feature| feature.name == name)
+            .map(|feature| feature.policy)
+    }
+
+    /// Whether the code of the module depends on the feature `name`
+    pub fn needs(&self, name: &str) -> bool {
+        self.policy(name).is_some_and(FeaturePolicy::needs_feature)
+    }
+
+    /// Names of the features the code of the module depends on
+    pub fn needed(&self) -> impl Iterator<Item = &str> {
+        self.features
+            .iter()
+            .filter(|feature| feature.policy.needs_feature())
+            .map(|feature| feature.name.as_str())
+    }
+
+    /// Names of the needed features for which `enabled` returns false
+    pub fn missing<'a>(&'a self, enabled: impl Fn(&str) -> bool + 'a) -> Vec<&'a str> {
+        self.needed().filter(|name| !enabled(name)).collect()
+    }
+}
+
+/// Parse the target features custom section from binary data
+pub fn parse_target_features_section(data: &[u8]) -> Result<TargetFeaturesSection> {
+    let mut offset = 0;
+    let mut section = TargetFeaturesSection::default();
+
+    let (count, consumed) = read_leb128_u32(data, offset)?;
+    offset += consumed;
+    for _ in 0..count {
+        let (prefix, next) = read_u8(data, offset)?;
+        offset = next;
+        let (bytes, consumed) = read_string(data, offset)?;
+        offset += consumed;
+        let name = core::str::from_utf8(bytes)
+            .map_err(|_| Error::parse_error("Invalid UTF-8 in target feature name"))?;
+        section.features.push(TargetFeature {
+            policy: FeaturePolicy::from_prefix(prefix)?,
+            name:   String::from(name),
+        });
+    }
+
+    if offset != data.len() {
+        return Err(Error::parse_error(
+            "Trailing data in target features section",
+        ));
+    }
+
+    Ok(section)
+}
+
+/// Encode a target features section
+pub fn encode_target_features_section(section: &TargetFeaturesSection) -> Result<Vec<u8>> {
+    let count = u32::try_from(section.features.len())
+        .map_err(|_| Error::parse_error("Too many target features"))?;
+    let mut data = write_leb128_u32(count);
+    for feature in &section.features {
+        data.push(feature.policy.prefix());
+        data.extend(write_string(&feature.name));
+    }
+    Ok(data)
+}
+
+/// Read the target features section of a module binary
+///
+/// Returns `None` for modules without a target features section.
+pub fn read_target_features_section(binary: &[u8]) -> Result<Option<TargetFeaturesSection>> {
+    find_custom_section(binary, TARGET_FEATURES_SECTION_NAME)?
+        .map(parse_target_features_section)
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn section() -> TargetFeaturesSection {
+        let feature = |policy, name: &str| TargetFeature {
+            policy,
+            name: name.into(),
+        };
+        TargetFeaturesSection {
+            features: vec![
+                feature(FeaturePolicy::Used, "simd128"),
+                feature(FeaturePolicy::Disallowed, "atomics"),
+                feature(FeaturePolicy::Required, "mutable-globals"),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_target_features_roundtrip() {
+        let encoded = encode_target_features_section(&section()).unwrap();
+        assert_eq!(encoded[1], b'+');
+        let parsed = parse_target_features_section(&encoded).unwrap();
+        assert_eq!(parsed, section());
+
+        assert!(parsed.needs("simd128"));
+        assert!(!parsed.needs("atomics"));
+        assert_eq!(parsed.policy("atomics"), Some(FeaturePolicy::Disallowed));
+        assert_eq!(parsed.policy("tail-call"), None);
+        assert_eq!(
+            parsed.needed().collect::<Vec<_>>(),
+            ["simd128", "mutable-globals"]
+        );
+        assert_eq!(
+            parsed.missing(|name| name == "mutable-globals"),
+            ["simd128"]
+        );
+    }
+
+    #[test]
+    fn test_parse_malformed_target_features() {
+        assert!(parse_target_features_section(&[1, b'?', 1, b'a']).is_err());
+        assert!(parse_target_features_section(&[2, b'+', 1, b'a']).is_err());
+        assert!(parse_target_features_section(&[1, b'+', 1, b'a', 0]).is_err());
+    }
+
+    #[test]
+    fn test_read_target_features_section() {
+        let mut contents = write_string(TARGET_FEATURES_SECTION_NAME);
+        contents.extend(encode_target_features_section(&section()).unwrap());
+
+        let mut binary = vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x00];
+        binary.extend(write_leb128_u32(contents.len() as u32));
+        binary.extend(contents);
+
+        assert_eq!(
+            read_target_features_section(&binary).unwrap(),
+            Some(section())
+        );
+        assert_eq!(read_target_features_section(&binary[..8]).unwrap(), None);
+    }
+}
@@ -0,0 +1,59 @@
+//! Module binaries shared by the unit tests of this crate
+
+use wrt_format::{
+    binary::CUSTOM_SECTION_ID,
+    write_leb128_u32,
+    write_string,
+};
+
+/// Builder of WebAssembly module binaries, one section at a time
+#[derive(Debug, Clone)]
+pub(crate) struct ModuleBinary {
+    binary: Vec<u8>,
+}
+
+impl ModuleBinary {
+    /// Start a module with the magic number and version 1
+    pub(crate) fn new() -> Self {
+        Self {
+            binary: b"\0asm\x01\0\0\0".to_vec(),
+        }
+    }
+
+    /// Append a section with the given id and payload
+    pub(crate) fn section(&mut self, id: u8, payload: &[u8]) -> &mut Self {
+        self.binary.push(id);
+        self.binary.extend(write_leb128_u32(payload.len() as u32));
+        self.binary.extend_from_slice(payload);
+        self
+    }
+
+    /// Append a custom section with the given name and data
+    pub(crate) fn custom(&mut self, name: &str, data: &[u8]) -> &mut Self {
+        let mut payload = write_string(name);
+        payload.extend_from_slice(data);
+        self.section(CUSTOM_SECTION_ID, &payload)
+    }
+
+    /// The binary built so far
+    pub(crate) fn build(&self) -> Vec<u8> {
+        self.binary.clone()
+    }
+}
+
+/// Module with two `() -> i32` functions returning 1 and 2, exporting the
+/// second as "run"
+pub(crate) fn two_function_module() -> ModuleBinary {
+    let mut binary = ModuleBinary::new();
+    binary
+        .section(1, &[0x01, 0x60, 0x00, 0x01, 0x7F])
+        .section(3, &[0x02, 0x00, 0x00])
+        .section(7, &[0x01, 0x03, b'r', b'u', b'n', 0x00, 0x01])
+        .section(
+            10,
+            &[
+                0x02, 0x04, 0x00, 0x41, 0x01, 0x0B, 0x04, 0x00, 0x41, 0x02, 0x0B,
+            ],
+        );
+    binary
+}
@@ -235,6 +235,8 @@
 pub const ASIL_LEVEL_MISMATCH: u16 = 7008;
 /// Safety monitor timeout error
 pub const SAFETY_MONITOR_TIMEOUT: u16 = 7009;
+/// Memory page no longer matches its checksum
+pub const MEMORY_PAGE_CORRUPTED: u16 = 7011;
 
 // Unified types error codes (8000-8999)
 /// Unified type configuration error
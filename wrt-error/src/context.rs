@@ -7,13 +7,353 @@
 // Licensed under the MIT license.
 // SPDX-License-Identifier: MIT
 
-//! Error context utilities.
+//! Error context chain.
 //!
-//! This module is currently a placeholder. Its original content, which provided
-//! context-aware error handling (e.g., `ResultExt`), was removed due to
-//! dependencies on `alloc`. Future work may reintroduce `no_std` and `no_alloc`
-//! compatible context mechanisms here.
-
-// Binary std/no_std choice
-// Binary std/no_std choice
-// mechanisms.
+//! An [`Error`](crate::Error) carries a [`ContextChain`]: the operations it
+//! propagated through, each optionally with the module byte offset, the
+//! function index and the index of the item within its section it concerned.
+//! Frames are added with [`ResultExt`] as the error travels outwards, for
+//! example from the decoder through the engine, so a failure can be located
+//! from its message alone.
+//!
+//! The chain is stored inline in the error and keeps the innermost frame and
+//! the most recent outer frames, so it needs neither `alloc` nor unbounded
+//! storage:
+//!
+//! ```
+//! use wrt_error::{
+//!     context::ContextFrame,
+//!     Error,
+//!     ResultExt,
+//! };
+//!
+//! fn decode() -> wrt_error::Result<()> {
+//!     Err(Error::parse_error("Invalid LEB128 encoding"))
+//!         .context(ContextFrame::new("decoding code section").with_offset(0x2a))
+//! }
+//!
+//! let error = decode().context("loading module").unwrap_err();
+//! assert_eq!(error.context().len(), 2);
+//! assert_eq!(error.context().innermost().unwrap().offset(), Some(0x2a));
+//! ```
+
+use core::fmt;
+
+/// Number of frames a [`ContextChain`] holds
+pub const MAX_CONTEXT_FRAMES: usize = 3;
+
+/// Marker for an absent offset or index
+const ABSENT: u32 = u32::MAX;
+
+/// One step an error propagated through
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextFrame {
+    operation: &'static str,
+    offset:    u32,
+    function:  u32,
+    item:      u32,
+}
+
+impl ContextFrame {
+    /// Create a frame for `operation`
+    #[must_use]
+    pub const fn new(operation: &'static str) -> Self {
+        Self {
+            operation,
+            offset: ABSENT,
+            function: ABSENT,
+            item: ABSENT,
+        }
+    }
+
+    /// Attach the byte offset in the module the operation was at
+    #[must_use]
+    pub const fn with_offset(mut self, offset: u32) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Attach the index of the function the operation concerned
+    #[must_use]
+    pub const fn with_function(mut self, index: u32) -> Self {
+        self.function = index;
+        self
+    }
+
+    /// Attach the index of the item, such as an import or data segment,
+    /// the operation concerned within its section
+    #[must_use]
+    pub const fn with_item(mut self, index: u32) -> Self {
+        self.item = index;
+        self
+    }
+
+    /// The operation
+    #[must_use]
+    pub const fn operation(&self) -> &'static str {
+        self.operation
+    }
+
+    /// Byte offset in the module, if known
+    #[must_use]
+    pub const fn offset(&self) -> Option<u32> {
+        present(self.offset)
+    }
+
+    /// Function index, if known
+    #[must_use]
+    pub const fn function(&self) -> Option<u32> {
+        present(self.function)
+    }
+
+    /// Index of the item within its section, if known
+    #[must_use]
+    pub const fn item(&self) -> Option<u32> {
+        present(self.item)
+    }
+}
+
+const fn present(value: u32) -> Option<u32> {
+    if value == ABSENT {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+impl From<&'static str> for ContextFrame {
+    fn from(operation: &'static str) -> Self {
+        Self::new(operation)
+    }
+}
+
+impl fmt::Display for ContextFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.operation)?;
+        if let Some(item) = self.item() {
+            write!(f, " {item}")?;
+        }
+        if let Some(function) = self.function() {
+            write!(f, " in function {function}")?;
+        }
+        if let Some(offset) = self.offset() {
+            write!(f, " at offset {offset:#x}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Bounded chain of [`ContextFrame`]s, innermost first
+///
+/// Once full, the innermost frame is kept and the outer frames form a ring
+/// of the most recent ones; [`ContextChain::omitted`] counts the frames
+/// overwritten in between.
+///
+/// The fields of the frames are stored column by column, which keeps the
+/// chain, and with it every `Result` carrying an [`Error`](crate::Error),
+/// free of per-frame padding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextChain {
+    operations: [&'static str; MAX_CONTEXT_FRAMES],
+    offsets:    [u32; MAX_CONTEXT_FRAMES],
+    functions:  [u32; MAX_CONTEXT_FRAMES],
+    items:      [u32; MAX_CONTEXT_FRAMES],
+    /// Number of frames ever pushed
+    pushed:     u16,
+}
+
+impl ContextChain {
+    /// An empty chain
+    pub const EMPTY: Self = Self {
+        operations: [""; MAX_CONTEXT_FRAMES],
+        offsets:    [ABSENT; MAX_CONTEXT_FRAMES],
+        functions:  [ABSENT; MAX_CONTEXT_FRAMES],
+        items:      [ABSENT; MAX_CONTEXT_FRAMES],
+        pushed:     0,
+    };
+
+    /// Add `frame` as the outermost frame
+    #[must_use]
+    pub const fn pushed(mut self, frame: ContextFrame) -> Self {
+        let pushed = self.pushed as usize;
+        // The outer frames form a ring over all slots but the innermost one
+        let slot = if pushed == 0 { 0 } else { 1 + (pushed - 1) % (MAX_CONTEXT_FRAMES - 1) };
+        self.operations[slot] = frame.operation;
+        self.offsets[slot] = frame.offset;
+        self.functions[slot] = frame.function;
+        self.items[slot] = frame.item;
+        self.pushed = self.pushed.saturating_add(1);
+        self
+    }
+
+    /// Number of frames held
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        let pushed = self.pushed as usize;
+        if pushed < MAX_CONTEXT_FRAMES {
+            pushed
+        } else {
+            MAX_CONTEXT_FRAMES
+        }
+    }
+
+    /// Whether the chain holds no frames
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.pushed == 0
+    }
+
+    /// Number of frames that were pushed but are no longer held
+    #[must_use]
+    pub const fn omitted(&self) -> usize {
+        self.pushed as usize - self.len()
+    }
+
+    /// The frame closest to where the error was raised
+    #[must_use]
+    pub const fn innermost(&self) -> Option<ContextFrame> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.frame(0))
+        }
+    }
+
+    const fn frame(&self, slot: usize) -> ContextFrame {
+        ContextFrame {
+            operation: self.operations[slot],
+            offset:    self.offsets[slot],
+            function:  self.functions[slot],
+            item:      self.items[slot],
+        }
+    }
+
+    /// The frames held, innermost first
+    pub fn iter(&self) -> impl Iterator<Item = ContextFrame> + '_ {
+        let len = self.len();
+        let pushed = self.pushed as usize;
+        // Position of the oldest frame in the ring of outer frames
+        let ring = MAX_CONTEXT_FRAMES - 1;
+        let oldest = if pushed <= MAX_CONTEXT_FRAMES { 0 } else { (pushed - 1) % ring };
+        (0..len).map(
+            move |i| {
+                if i == 0 {
+                    self.frame(0)
+                } else {
+                    self.frame(1 + (oldest + i - 1) % ring)
+                }
+            },
+        )
+    }
+}
+
+impl Default for ContextChain {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+impl fmt::Display for ContextChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, frame) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            if i == 1 && self.omitted() > 0 {
+                write!(f, "... {} more, ", self.omitted())?;
+            }
+            write!(f, "while {frame}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Adds context frames to the error of a `Result`
+pub trait ResultExt<T> {
+    /// Add `frame` to the error's context chain
+    ///
+    /// # Errors
+    ///
+    /// Returns the original error with `frame` added.
+    fn context(self, frame: impl Into<ContextFrame>) -> crate::Result<T>;
+
+    /// Add the frame built by `frame` to the error's context chain, building
+    /// it only on error
+    ///
+    /// # Errors
+    ///
+    /// Returns the original error with the frame added.
+    fn with_context<F: FnOnce() -> ContextFrame>(self, frame: F) -> crate::Result<T>;
+}
+
+impl<T> ResultExt<T> for crate::Result<T> {
+    fn context(self, frame: impl Into<ContextFrame>) -> Self {
+        self.map_err(|error| error.with_context(frame.into()))
+    }
+
+    fn with_context<F: FnOnce() -> ContextFrame>(self, frame: F) -> Self {
+        self.map_err(|error| error.with_context(frame()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(not(feature = "std"))]
+    use alloc::string::ToString;
+
+    use super::*;
+    use crate::Error;
+
+    #[test]
+    fn test_frames_are_kept_innermost_first() {
+        let chain = ContextChain::EMPTY
+            .pushed(ContextFrame::new("reading LEB128").with_offset(0x10))
+            .pushed(ContextFrame::new("decoding function").with_function(3));
+
+        assert_eq!(chain.len(), 2);
+        assert_eq!(chain.omitted(), 0);
+        let operations: [&str; 2] = {
+            let mut frames = chain.iter().map(|frame| frame.operation());
+            [frames.next().unwrap(), frames.next().unwrap()]
+        };
+        assert_eq!(operations, ["reading LEB128", "decoding function"]);
+        assert_eq!(chain.innermost().unwrap().offset(), Some(0x10));
+        assert_eq!(chain.innermost().unwrap().function(), None);
+    }
+
+    #[test]
+    fn test_full_chain_keeps_innermost_and_latest() {
+        let operations = ["a", "b", "c", "d", "e", "f"];
+        let chain = operations
+            .iter()
+            .fold(ContextChain::EMPTY, |chain, &op| chain.pushed(op.into()));
+
+        assert_eq!(chain.len(), MAX_CONTEXT_FRAMES);
+        assert_eq!(chain.omitted(), operations.len() - MAX_CONTEXT_FRAMES);
+        let mut frames = chain.iter().map(|frame| frame.operation());
+        assert_eq!(frames.next(), Some("a"));
+        assert_eq!(frames.next(), Some("e"));
+        assert_eq!(frames.next(), Some("f"));
+        assert_eq!(frames.next(), None);
+    }
+
+    #[test]
+    fn test_result_ext_and_display() {
+        let result: crate::Result<()> = Err(Error::parse_error("Unexpected end"));
+        let error = result
+            .with_context(|| ContextFrame::new("decoding import").with_item(2).with_offset(0x2a))
+            .context(ContextFrame::new("calling export").with_function(7))
+            .unwrap_err();
+
+        assert_eq!(error.context().len(), 2);
+        assert_eq!(error.context().innermost().unwrap().item(), Some(2));
+        assert_eq!(
+            error.context().to_string(),
+            "while decoding import 2 at offset 0x2a, while calling export in function 7"
+        );
+        assert!(error.to_string().contains(&error.context().to_string()));
+
+        // Context must not make `Result`s noticeably larger
+        assert!(core::mem::size_of::<Error>() <= 128);
+    }
+}
@@ -16,6 +16,10 @@
 
 use crate::{
     codes,
+    context::{
+        ContextChain,
+        ContextFrame,
+    },
     kinds,
     prelude::{
         str,
@@ -84,6 +88,8 @@ pub enum ErrorCategory {
     InvalidState      = 29,
     /// Not implemented errors
     NotImplemented    = 30,
+    /// Memory contents corrupted since they were written (bit-flips)
+    MemoryCorruption  = 31,
 }
 
 /// Base trait for all error types - `no_std` version
@@ -110,6 +116,8 @@ pub struct Error {
     pub code:     u16,
     /// `Error` message
     pub message:  &'static str,
+    /// Operations the error propagated through
+    context:      ContextChain,
 }
 
 impl Error {
@@ -185,9 +193,23 @@ pub const fn new(category: ErrorCategory, code: u16, message: &'static str) -> S
             category,
             code,
             message,
+            context: ContextChain::EMPTY,
         }
     }
 
+    /// Add `frame` as the outermost step of the error's context chain
+    #[must_use]
+    pub const fn with_context(mut self, frame: ContextFrame) -> Self {
+        self.context = self.context.pushed(frame);
+        self
+    }
+
+    /// Operations the error propagated through, innermost first
+    #[must_use]
+    pub const fn context(&self) -> &ContextChain {
+        &self.context
+    }
+
     /// Create a component error with dynamic context (using static fallback)
     #[must_use]
     pub const fn component_error(_message: &'static str) -> Self {
@@ -307,12 +329,20 @@ pub fn is_async_runtime_error(&self) -> bool {
         self.category == ErrorCategory::AsyncRuntime
     }
 
+    /// Check if this is a memory corruption error
+    #[must_use]
+    pub fn is_memory_corruption_error(&self) -> bool {
+        self.category == ErrorCategory::MemoryCorruption
+    }
+
     /// Get the ASIL level of this error (ASIL-B and above)
     #[cfg(any(feature = "asil-b", feature = "asil-c", feature = "asil-d"))]
     #[must_use]
     pub const fn asil_level(&self) -> &'static str {
         match self.category {
-            ErrorCategory::Safety | ErrorCategory::FoundationRuntime => "ASIL-D", /* Safety and foundation errors require highest level */
+            ErrorCategory::Safety
+            | ErrorCategory::FoundationRuntime
+            | ErrorCategory::MemoryCorruption => "ASIL-D", /* Safety and foundation errors require highest level */
             ErrorCategory::Memory
             | ErrorCategory::RuntimeTrap
             | ErrorCategory::ComponentRuntime => "ASIL-C", /* Memory/trap/component runtime */
@@ -338,6 +368,7 @@ pub const fn requires_safe_state(&self) -> bool {
                 | ErrorCategory::RuntimeTrap
                 | ErrorCategory::ComponentRuntime
                 | ErrorCategory::FoundationRuntime
+                | ErrorCategory::MemoryCorruption
         )
     }
 
@@ -353,7 +384,9 @@ pub const fn validate_integrity(&self) -> bool {
             ErrorCategory::Memory => self.code >= 4000 && self.code < 5000,
             ErrorCategory::Validation => self.code >= 5000 && self.code < 6000,
             ErrorCategory::Type => self.code >= 6000 && self.code < 7000,
-            ErrorCategory::Runtime | ErrorCategory::Safety => self.code >= 7000 && self.code < 8000,
+            ErrorCategory::Runtime | ErrorCategory::Safety | ErrorCategory::MemoryCorruption => {
+                self.code >= 7000 && self.code < 8000
+            },
             ErrorCategory::System => self.code >= 8000 && self.code < 9000,
             ErrorCategory::ComponentRuntime => self.code >= 24000 && self.code < 25000,
             ErrorCategory::PlatformRuntime => self.code >= 25000 && self.code < 26000,
@@ -1180,6 +1213,12 @@ pub const fn execution_timeout(message: &'static str) -> Self {
         Self::new(ErrorCategory::Runtime, codes::EXECUTION_TIMEOUT, message)
     }
 
+    /// Create a fuel exhausted error
+    #[must_use]
+    pub const fn fuel_exhausted(message: &'static str) -> Self {
+        Self::new(ErrorCategory::Runtime, codes::FUEL_EXHAUSTED, message)
+    }
+
     /// Create a type mismatch error
     #[must_use]
     pub const fn type_mismatch_error(message: &'static str) -> Self {
@@ -1480,6 +1519,16 @@ pub const fn memory_corruption_detected(message: &'static str) -> Self {
         )
     }
 
+    /// Create a memory page corrupted error
+    #[must_use]
+    pub const fn memory_page_corrupted(message: &'static str) -> Self {
+        Self::new(
+            ErrorCategory::MemoryCorruption,
+            codes::MEMORY_PAGE_CORRUPTED,
+            message,
+        )
+    }
+
     // Async Runtime Error Factory Methods
 
     /// Create an async task spawn failed error
@@ -1685,7 +1734,7 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
                 self.code,
                 self.asil_level(),
                 self.message
-            )
+            )?;
         }
         #[cfg(not(any(feature = "asil-c", feature = "asil-d")))]
         {
@@ -1693,8 +1742,12 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
                 f,
                 "[{:?}][E{:04X}] {}",
                 self.category, self.code, self.message
-            )
+            )?;
+        }
+        if !self.context.is_empty() {
+            write!(f, " ({})", self.context)?;
         }
+        Ok(())
     }
 }
 
@@ -112,6 +112,11 @@
 pub mod verify;
 
 // Re-export key types
+pub use context::{
+    ContextChain,
+    ContextFrame,
+    ResultExt,
+};
 pub use errors::{
     Error,
     ErrorCategory,
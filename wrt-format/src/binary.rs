@@ -570,145 +570,32 @@ pub fn parse_binary(bytes: &[u8]) -> Result<Module> {
     Ok(module)
 }
 
-/// Binary std/no_std choice
-/// needed)
+/// Read a LEB128 unsigned integer from a byte array
+///
+/// Encodings are checked as described in [`wrt_foundation::leb128`].
 pub fn read_leb128_u32(bytes: &[u8], pos: usize) -> wrt_error::Result<(u32, usize)> {
-    let mut result = 0u32;
-    let mut shift = 0;
-    let mut offset = 0;
-
-    loop {
-        if pos + offset >= bytes.len() {
-            return Err(parse_error("LEB128 exceeds buffer bounds"));
-        }
-
-        let byte = bytes[pos + offset];
-        offset += 1;
-
-        result |= ((byte & 0x7F) as u32) << shift;
-
-        // Check for continuation bit
-        if (byte & 0x80) == 0 {
-            break;
-        }
-
-        shift += 7;
-
-        // Prevent overflow
-        if shift >= 32 {
-            return Err(parse_error("LEB128 overflow"));
-        }
-    }
-
-    Ok((result, offset))
+    wrt_foundation::leb128::read_u32(bytes, pos)
 }
 
 /// Read a LEB128 signed integer from a byte array
 pub fn read_leb128_i32(bytes: &[u8], pos: usize) -> wrt_error::Result<(i32, usize)> {
-    let mut result = 0i32;
-    let mut shift = 0;
-    let mut offset = 0;
-    let mut byte;
-
-    loop {
-        if pos + offset >= bytes.len() {
-            return Err(parse_error("Truncated LEB128 integer"));
-        }
-
-        byte = bytes[pos + offset];
-        offset += 1;
-
-        // Apply 7 bits from this byte
-        result |= ((byte & 0x7F) as i32) << shift;
-        shift += 7;
-
-        // Check for continuation bit
-        if byte & 0x80 == 0 {
-            break;
-        }
-
-        // Guard against malformed/malicious LEB128
-        if shift >= 32 {
-            return Err(parse_error("LEB128 integer too large"));
-        }
-    }
-
-    // Sign-extend if needed
-    if shift < 32 && (byte & 0x40) != 0 {
-        result |= !0 << shift;
-    }
+    wrt_foundation::leb128::read_i32(bytes, pos)
+}
 
-    Ok((result, offset))
+/// Read a LEB128 signed 33-bit integer, as used by block types, from a byte
+/// array
+pub fn read_leb128_i33(bytes: &[u8], pos: usize) -> wrt_error::Result<(i64, usize)> {
+    wrt_foundation::leb128::read_i33(bytes, pos)
 }
 
 /// Read a LEB128 signed 64-bit integer from a byte array
 pub fn read_leb128_i64(bytes: &[u8], pos: usize) -> wrt_error::Result<(i64, usize)> {
-    let mut result = 0i64;
-    let mut shift = 0;
-    let mut offset = 0;
-    let mut byte;
-
-    loop {
-        if pos + offset >= bytes.len() {
-            return Err(parse_error("Truncated LEB128 integer"));
-        }
-
-        byte = bytes[pos + offset];
-        offset += 1;
-
-        // Apply 7 bits from this byte
-        result |= ((byte & 0x7F) as i64) << shift;
-        shift += 7;
-
-        // Check for continuation bit
-        if byte & 0x80 == 0 {
-            break;
-        }
-
-        // Guard against malformed/malicious LEB128
-        if shift >= 64 {
-            return Err(parse_error("LEB128 integer too large"));
-        }
-    }
-
-    // Sign-extend if needed
-    if shift < 64 && (byte & 0x40) != 0 {
-        result |= !0 << shift;
-    }
-
-    Ok((result, offset))
+    wrt_foundation::leb128::read_i64(bytes, pos)
 }
 
 /// Read a LEB128 unsigned 64-bit integer from a byte array
 pub fn read_leb128_u64(bytes: &[u8], pos: usize) -> wrt_error::Result<(u64, usize)> {
-    let mut result = 0u64;
-    let mut shift = 0;
-    let mut offset = 0;
-
-    loop {
-        if pos + offset >= bytes.len() {
-            return Err(parse_error("Truncated LEB128 integer"));
-        }
-
-        let byte = bytes[pos + offset];
-        offset += 1;
-
-        // Apply 7 bits from this byte
-        result |= ((byte & 0x7F) as u64) << shift;
-        shift += 7;
-
-        // Check for continuation bit
-        if byte & 0x80 == 0 {
-            break;
-        }
-
-        // Guard against malformed/malicious LEB128
-        if shift >= 64 {
-            return Err(parse_error("LEB128 integer too large"));
-        }
-    }
-
-    Ok((result, offset))
+    wrt_foundation::leb128::read_u64(bytes, pos)
 }
 
 /// Read a single byte from the byte array
@@ -805,116 +692,21 @@ pub fn generate_binary(module: &Module) -> Result<Vec<u8>> {
     ///
     /// This function will be used when implementing the full binary parser.
     pub fn read_leb128_u32(bytes: &[u8], pos: usize) -> Result<(u32, usize)> {
-        let mut result = 0u32;
-        let mut shift = 0;
-        let mut offset = 0;
-
-        loop {
-            if pos + offset >= bytes.len() {
-                return Err(parse_error("Truncated LEB128 integer"));
-            }
-
-            let byte = bytes[pos + offset];
-            offset += 1;
-
-            // Apply 7 bits from this byte
-            result |= ((byte & 0x7F) as u32) << shift;
-            shift += 7;
-
-            // Check for continuation bit
-            if byte & 0x80 == 0 {
-                break;
-            }
-
-            // Guard against malformed/malicious LEB128
-            if shift >= 32 {
-                return Err(parse_error("LEB128 integer too large"));
-            }
-        }
-
-        Ok((result, offset))
+        wrt_foundation::leb128::read_u32(bytes, pos)
     }
 
     /// Read a LEB128 signed integer from a byte array
     ///
     /// This function will be used when implementing the full binary parser.
     pub fn read_leb128_i32(bytes: &[u8], pos: usize) -> Result<(i32, usize)> {
-        let mut result = 0i32;
-        let mut shift = 0;
-        let mut offset = 0;
-        let mut byte;
-
-        loop {
-            if pos + offset >= bytes.len() {
-                return Err(parse_error("Truncated LEB128 integer"));
-            }
-
-            byte = bytes[pos + offset];
-            offset += 1;
-
-            // Apply 7 bits from this byte
-            result |= ((byte & 0x7F) as i32) << shift;
-            shift += 7;
-
-            // Check for continuation bit
-            if byte & 0x80 == 0 {
-                break;
-            }
-
-            // Guard against malformed/malicious LEB128
-            if shift >= 32 {
-                return Err(parse_error("LEB128 integer too large"));
-            }
-        }
-
-        // Sign extend if needed
-        if shift < 32 && (byte & 0x40) != 0 {
-            // The result is negative, sign extend it
-            result |= !0 << shift;
-        }
-
-        Ok((result, offset))
+        wrt_foundation::leb128::read_i32(bytes, pos)
     }
 
     /// Read a LEB128 signed 64-bit integer from a byte array
     ///
     /// This function will be used when implementing the full binary parser.
     pub fn read_leb128_i64(bytes: &[u8], pos: usize) -> Result<(i64, usize)> {
-        let mut result = 0i64;
-        let mut shift = 0;
-        let mut offset = 0;
-        let mut byte;
-
-        loop {
-            if pos + offset >= bytes.len() {
-                return Err(parse_error("Truncated LEB128 integer"));
-            }
-
-            byte = bytes[pos + offset];
-            offset += 1;
-
-            // Apply 7 bits from this byte
-            result |= ((byte & 0x7F) as i64) << shift;
-            shift += 7;
-
-            // Check for continuation bit
-            if byte & 0x80 == 0 {
-                break;
-            }
-
-            // Guard against malformed/malicious LEB128
-            if shift >= 64 {
-                return Err(parse_error("LEB128 integer too large"));
-            }
-        }
-
-        // Sign extend if needed
-        if shift < 64 && (byte & 0x40) != 0 {
-            // The result is negative, sign extend it
-            result |= !0 << shift;
-        }
-
-        Ok((result, offset))
+        wrt_foundation::leb128::read_i64(bytes, pos)
     }
 
     /// Write a LEB128 unsigned integer to a byte array
@@ -1037,40 +829,7 @@ pub fn is_valid_wasm_header(bytes: &[u8]) -> bool {
 
     /// Read a LEB128 unsigned 64-bit integer from a byte array
     pub fn read_leb128_u64(bytes: &[u8], pos: usize) -> Result<(u64, usize)> {
-        let mut result: u64 = 0;
-        let mut shift: u32 = 0;
-        let mut offset = pos;
-        let mut byte;
-
-        loop {
-            if offset >= bytes.len() {
-                return Err(parse_error("Unexpected end of LEB128 sequence"));
-            }
-
-            byte = bytes[offset];
-            offset += 1;
-
-            // Apply the 7 bits from the current byte
-            result |= ((byte & 0x7F) as u64) << shift;
-
-            // If the high bit is not set, we're done
-            if byte & 0x80 == 0 {
-                break;
-            }
-
-            // Otherwise, shift for the next 7 bits
-            shift += 7;
-
-            // Ensure we don't exceed 64 bits (10 bytes)
-            if shift >= 64 {
-                if byte & 0x7F != 0 {
-                    return Err(parse_error("LEB128 sequence exceeds maximum u64 value"));
-                }
-                break;
-            }
-        }
-
-        Ok((result, offset - pos))
+        wrt_foundation::leb128::read_u64(bytes, pos)
     }
 
     /// Write a LEB128 unsigned 64-bit integer to a byte array
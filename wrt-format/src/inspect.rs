@@ -0,0 +1,358 @@
+//! Inspection of the imports and resource needs of modules and components
+//!
+//! Embedders use these descriptors before instantiation to check that every
+//! import can be satisfied, and the [`ResourceRequirements`] report to
+//! pre-provision the memories and tables an instance will need.
+
+use std::{
+    string::String,
+    vec::Vec,
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_foundation::CleanCoreFuncType;
+
+use crate::{
+    component::{
+        Component,
+        CoreInstanceExpr,
+        ExternType,
+        InstanceExpr,
+    },
+    module::{
+        ImportDesc,
+        Memory,
+        Module,
+        Table,
+    },
+    types::FormatGlobalType,
+};
+
+/// Kind and full type of a module import
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportType {
+    /// Function with its signature
+    Function(CleanCoreFuncType),
+    /// Table with its element type and limits
+    Table(Table),
+    /// Memory with its limits
+    Memory(Memory),
+    /// Global with its value type and mutability
+    Global(FormatGlobalType),
+    /// Exception tag with the signature of its payload
+    Tag(CleanCoreFuncType),
+}
+
+/// Import of a module, with its type resolved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportDescriptor {
+    /// Module name the import is requested from
+    pub module: String,
+    /// Name of the item within the module
+    pub name:   String,
+    /// Kind and type of the item
+    pub ty:     ImportType,
+}
+
+/// Import of a component, with its name joined into a single path
+#[derive(Debug, Clone)]
+pub struct ComponentImportDescriptor {
+    /// Namespace, name and nested names of the import, joined with `.`
+    pub path: String,
+    /// Kind and type of the import
+    pub ty:   ExternType,
+}
+
+/// Imports and resources an instance needs
+///
+/// Memory and table sizes add up the minimum of every memory and table the
+/// instance imports or defines, so they are what an embedder has to provide
+/// before instantiation. A maximum is `None` as soon as one of the memories
+/// or tables is unbounded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceRequirements {
+    /// Number of imported functions
+    pub imported_functions: usize,
+    /// Number of imported globals
+    pub imported_globals:   usize,
+    /// Number of imported memories
+    pub imported_memories:  usize,
+    /// Number of imported tables
+    pub imported_tables:    usize,
+    /// Pages of all memories at instantiation
+    pub memory_pages:       u64,
+    /// Pages all memories may grow to
+    pub max_memory_pages:   Option<u64>,
+    /// Whether any memory is shared between threads
+    pub shared_memory:      bool,
+    /// Elements of all tables at instantiation
+    pub table_elements:     u64,
+    /// Elements all tables may grow to
+    pub max_table_elements: Option<u64>,
+}
+
+impl ResourceRequirements {
+    fn add_memory(&mut self, memory: &Memory) {
+        let limits = memory.limits;
+        self.memory_pages = self.memory_pages.saturating_add(u64::from(limits.min));
+        self.max_memory_pages = self
+            .max_memory_pages
+            .zip(limits.max)
+            .map(|(total, max)| total.saturating_add(u64::from(max)));
+        self.shared_memory |= memory.shared;
+    }
+
+    fn add_table(&mut self, table: &Table) {
+        let limits = table.limits;
+        self.table_elements = self.table_elements.saturating_add(u64::from(limits.min));
+        self.max_table_elements = self
+            .max_table_elements
+            .zip(limits.max)
+            .map(|(total, max)| total.saturating_add(u64::from(max)));
+    }
+}
+
+impl Default for ResourceRequirements {
+    fn default() -> Self {
+        Self {
+            imported_functions: 0,
+            imported_globals:   0,
+            imported_memories:  0,
+            imported_tables:    0,
+            memory_pages:       0,
+            max_memory_pages:   Some(0),
+            shared_memory:      false,
+            table_elements:     0,
+            max_table_elements: Some(0),
+        }
+    }
+}
+
+impl Module {
+    /// Imports of the module in declaration order, with their types
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an import refers to a type the module does not
+    /// define.
+    pub fn import_descriptors(&self) -> Result<Vec<ImportDescriptor>> {
+        self.imports
+            .iter()
+            .map(|import| {
+                let ty = match &import.desc {
+                    ImportDesc::Function(type_idx) => {
+                        ImportType::Function(self.func_type(*type_idx)?)
+                    },
+                    ImportDesc::Table(table) => ImportType::Table(table.clone()),
+                    ImportDesc::Memory(memory) => ImportType::Memory(*memory),
+                    ImportDesc::Global(global) => ImportType::Global(*global),
+                    ImportDesc::Tag(type_idx) => ImportType::Tag(self.func_type(*type_idx)?),
+                };
+                Ok(ImportDescriptor {
+                    module: import.module.clone(),
+                    name: import.name.clone(),
+                    ty,
+                })
+            })
+            .collect()
+    }
+
+    /// Imports and resources an instance of the module needs
+    pub fn requirements(&self) -> ResourceRequirements {
+        let mut requirements = ResourceRequirements::default();
+        for import in &self.imports {
+            match &import.desc {
+                ImportDesc::Function(_) => requirements.imported_functions += 1,
+                ImportDesc::Global(_) => requirements.imported_globals += 1,
+                ImportDesc::Memory(memory) => {
+                    requirements.imported_memories += 1;
+                    requirements.add_memory(memory);
+                },
+                ImportDesc::Table(table) => {
+                    requirements.imported_tables += 1;
+                    requirements.add_table(table);
+                },
+                ImportDesc::Tag(_) => {},
+            }
+        }
+        self.add_defined_resources(&mut requirements);
+        requirements
+    }
+
+    fn add_defined_resources(&self, requirements: &mut ResourceRequirements) {
+        for memory in &self.memories {
+            requirements.add_memory(memory);
+        }
+        for table in &self.tables {
+            requirements.add_table(table);
+        }
+    }
+
+    fn func_type(&self, type_idx: u32) -> Result<CleanCoreFuncType> {
+        self.types
+            .get(type_idx as usize)
+            .cloned()
+            .ok_or_else(|| Error::validation_error("Import refers to an undefined type"))
+    }
+}
+
+impl Component {
+    /// Imports of the component in declaration order
+    pub fn import_descriptors(&self) -> Vec<ComponentImportDescriptor> {
+        self.imports
+            .iter()
+            .map(|import| ComponentImportDescriptor {
+                path: import.name.full_path(),
+                ty:   import.ty.clone(),
+            })
+            .collect()
+    }
+
+    /// Imports and resources an instance of the component needs
+    ///
+    /// Imported functions count the function imports of the component
+    /// itself. Memories and tables are those defined by every core module
+    /// the component, or a component nested in it, instantiates; memories
+    /// and tables core modules import are provided by other core instances.
+    pub fn requirements(&self) -> ResourceRequirements {
+        let mut requirements = ResourceRequirements::default();
+        requirements.imported_functions = self
+            .imports
+            .iter()
+            .filter(|import| matches!(import.ty, ExternType::Function { .. }))
+            .count();
+        self.add_instantiated_resources(&mut requirements);
+        requirements
+    }
+
+    fn add_instantiated_resources(&self, requirements: &mut ResourceRequirements) {
+        for instance in &self.core_instances {
+            if let CoreInstanceExpr::ModuleReference { module_idx, .. } = &instance.instance_expr {
+                if let Some(module) = self.modules.get(*module_idx as usize) {
+                    module.add_defined_resources(requirements);
+                }
+            }
+        }
+        for instance in &self.instances {
+            if let InstanceExpr::ComponentReference { component_idx, .. } = &instance.instance_expr
+            {
+                if let Some(component) = self.components.get(*component_idx as usize) {
+                    component.add_instantiated_resources(requirements);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wrt_foundation::{
+        types::Limits,
+        ValueType,
+    };
+
+    use super::*;
+    use crate::{
+        component::{
+            CoreInstance,
+            Import,
+            ImportName,
+            Instance,
+        },
+        module::Import as ModuleImport,
+    };
+
+    fn memory(min: u32, max: Option<u32>) -> Memory {
+        Memory {
+            limits: Limits::new(min, max),
+            shared: false,
+        }
+    }
+
+    fn module_import(name: &str, desc: ImportDesc) -> ModuleImport {
+        ModuleImport {
+            module: "env".to_string(),
+            name: name.to_string(),
+            desc,
+        }
+    }
+
+    #[test]
+    fn test_module_imports_and_requirements() {
+        let mut module = Module::new();
+        let log = CleanCoreFuncType {
+            params:  vec![ValueType::I32],
+            results: vec![],
+        };
+        module.types.push(log.clone());
+        module.imports.push(module_import("log", ImportDesc::Function(0)));
+        module.imports.push(module_import(
+            "memory",
+            ImportDesc::Memory(memory(2, Some(4))),
+        ));
+        module.memories.push(memory(1, None));
+
+        let imports = module.import_descriptors().unwrap();
+        assert_eq!(imports.len(), 2);
+        assert_eq!(imports[0].name, "log");
+        assert_eq!(imports[0].ty, ImportType::Function(log));
+        assert_eq!(imports[1].ty, ImportType::Memory(memory(2, Some(4))));
+
+        let requirements = module.requirements();
+        assert_eq!(requirements.imported_functions, 1);
+        assert_eq!(requirements.imported_memories, 1);
+        assert_eq!(requirements.memory_pages, 3);
+        // The defined memory is unbounded
+        assert_eq!(requirements.max_memory_pages, None);
+        assert_eq!(requirements.max_table_elements, Some(0));
+
+        module.imports.push(module_import("bad", ImportDesc::Function(7)));
+        assert!(module.import_descriptors().is_err());
+    }
+
+    #[test]
+    fn test_component_requirements_follow_instantiations() {
+        let mut core = Module::new();
+        core.memories.push(memory(3, Some(8)));
+
+        let mut nested = Component::new();
+        nested.modules.push(core);
+        nested.core_instances.push(CoreInstance {
+            instance_expr: CoreInstanceExpr::ModuleReference {
+                module_idx: 0,
+                arg_refs:   Vec::new(),
+            },
+        });
+
+        let mut component = Component::new();
+        component.imports.push(Import {
+            name: ImportName::new("wasi".to_string(), "clock".to_string()),
+            ty:   ExternType::Function {
+                params:  Vec::new(),
+                results: Vec::new(),
+            },
+        });
+        // Instantiated twice, so its memory is needed twice
+        component.components.push(nested);
+        for _ in 0..2 {
+            component.instances.push(Instance {
+                instance_expr: InstanceExpr::ComponentReference {
+                    component_idx: 0,
+                    arg_refs:      Vec::new(),
+                },
+            });
+        }
+
+        let imports = component.import_descriptors();
+        assert_eq!(imports.len(), 1);
+        assert_eq!(imports[0].path, "wasi.clock");
+
+        let requirements = component.requirements();
+        assert_eq!(requirements.imported_functions, 1);
+        assert_eq!(requirements.memory_pages, 6);
+        assert_eq!(requirements.max_memory_pages, Some(16));
+    }
+}
@@ -239,6 +239,9 @@ macro_rules! format {
 /// Incremental parser for efficient WIT re-parsing
 #[cfg(feature = "std")]
 pub mod incremental_parser;
+/// Inspection of module and component imports and resource needs
+#[cfg(feature = "std")]
+pub mod inspect;
 /// Interface demonstration (clean separation)
 pub mod interface_demo;
 /// Basic LSP (Language Server Protocol) infrastructure
@@ -332,6 +335,7 @@ macro_rules! format {
 // Core parsing functions available in all configurations
 pub use binary::{
     read_leb128_i32,
+    read_leb128_i33,
     read_leb128_i64,
     read_leb128_u32,
     read_leb128_u64,
@@ -112,7 +112,7 @@
 
 /// Default maximum size for an item to be serialized onto a stack buffer within
 /// `BoundedVec`/`BoundedStack`.
-const MAX_ITEM_SERIALIZED_SIZE: usize = 256;
+const MAX_ITEM_SERIALIZED_SIZE: usize = 512;
 
 /// Size of the checksum in bytes, typically the size of a u32.
 pub const CHECKSUM_SIZE: usize = core::mem::size_of::<u32>();
@@ -677,7 +677,7 @@ pub fn push(&mut self, item: T) -> core::result::Result<(), BoundedError> {
             return Ok(());
         }
 
-        let bytes_written = {
+        {
             let buffer_slice = SliceMut::new(&mut item_bytes_buffer[..item_size])
                 .map_err(|_| BoundedError::runtime_execution_error("Operation failed"))?;
             let mut write_stream = WriteStream::new(buffer_slice);
@@ -685,11 +685,10 @@ pub fn push(&mut self, item: T) -> core::result::Result<(), BoundedError> {
                 .map_err(|_| {
                     BoundedError::new(BoundedErrorKind::ConversionError, "Operation failed")
                 })?;
-            write_stream.position()
-        };
+        }
 
         self.handler
-            .write_data(offset, &item_bytes_buffer[..bytes_written])
+            .write_data(offset, &item_bytes_buffer[..item_size])
             .map_err(|e| BoundedError::runtime_execution_error("Operation failed"))?;
 
         self.length += 1;
@@ -1120,7 +1119,9 @@ pub fn push(&mut self, item: T) -> core::result::Result<(), BoundedError> {
             return Ok(());
         }
 
-        let bytes_written = {
+        // The whole slot is written, zero padded, so that reading it back
+        // never touches uninitialized bytes
+        {
             let buffer_slice =
                 SliceMut::new(&mut item_bytes_buffer[..item_size]).map_err(|_| {
                     BoundedError::new(BoundedErrorKind::ConversionError, "Failed to create slice")
@@ -1128,11 +1129,10 @@ pub fn push(&mut self, item: T) -> core::result::Result<(), BoundedError> {
             let mut write_stream = WriteStream::new(buffer_slice);
             item.to_bytes_with_provider(&mut write_stream, &self.provider)
                 .map_err(|_| BoundedError::runtime_execution_error("Failed to serialize item"))?;
-            write_stream.position()
-        };
+        }
 
         self.provider
-            .write_data(offset, &item_bytes_buffer[..bytes_written])
+            .write_data(offset, &item_bytes_buffer[..item_size])
             .map_err(|e| {
                 BoundedError::new(BoundedErrorKind::SliceError, "Slice operation failed")
             })?;
@@ -1231,38 +1231,9 @@ pub fn get(&self, index: usize) -> Result<T> {
                 let mut read_stream = ReadStream::new(slice_view);
                 // Deserialize T using FromBytes trait
                 match T::from_bytes_with_provider(&mut read_stream, &self.provider) {
-                    Ok(item) => {
-                        // Optional: Verify checksum if not ZST and verification is enabled
-                        if CHECKSUM_SIZE > 0 && self.item_serialized_size > 0 {
-                            let checksum_offset = offset + self.item_serialized_size;
-                            if let Ok(checksum_slice) =
-                                self.provider.borrow_slice(checksum_offset, CHECKSUM_SIZE)
-                            {
-                                let mut cs_stream = ReadStream::new(checksum_slice);
-                                if let Ok(stored_checksum) = Checksum::from_bytes_with_provider(
-                                    &mut cs_stream,
-                                    &self.provider,
-                                ) {
-                                    let mut current_checksum = Checksum::new();
-                                    item.update_checksum(&mut current_checksum);
-                                    if current_checksum != stored_checksum {
-                                        return Err(crate::Error::validation_error(
-                                            "Checksum mismatch on BoundedVec::get",
-                                        ));
-                                    }
-                                } else {
-                                    return Err(crate::Error::deserialization_error(
-                                        "Failed to read stored checksum on BoundedVec::get",
-                                    ));
-                                }
-                            } else {
-                                return Err(crate::Error::memory_error(
-                                    "Failed to get checksum slice on BoundedVec::get",
-                                ));
-                            }
-                        }
-                        Ok(item)
-                    },
+                    // Items are stored back to back without per-item
+                    // checksums; the collection checksum covers them
+                    Ok(item) => Ok(item),
                     Err(e) => Err(crate::Error::deserialization_error(
                         "Failed to deserialize item from BoundedVec",
                     )),
@@ -1592,19 +1563,18 @@ pub fn set(&mut self, index: usize, value: T) -> core::result::Result<T, Bounded
             ));
         }
 
-        let bytes_written = {
+        {
             let buffer_slice = SliceMut::new(&mut item_bytes_buffer[..item_size])
                 .map_err(|_| BoundedError::runtime_execution_error("Operation failed"))?;
             let mut write_stream = WriteStream::new(buffer_slice);
             value.to_bytes_with_provider(&mut write_stream, &self.provider).map_err(|_| {
                 BoundedError::new(BoundedErrorKind::ConversionError, "Operation failed")
             })?;
-            write_stream.position()
-        };
+        }
 
         // Write new value to memory
         self.provider
-            .write_data(offset, &item_bytes_buffer[..bytes_written])
+            .write_data(offset, &item_bytes_buffer[..item_size])
             .map_err(|e| BoundedError::runtime_execution_error("Operation failed"))?;
 
         // Update checksum if needed
@@ -1696,18 +1666,17 @@ pub fn insert(&mut self, index: usize, value: T) -> core::result::Result<(), Bou
                 ));
             }
 
-            let bytes_written = {
+            {
                 let buffer_slice = SliceMut::new(&mut item_bytes_buffer[..item_size])
                     .map_err(|_| BoundedError::runtime_execution_error("Operation failed"))?;
                 let mut write_stream = WriteStream::new(buffer_slice);
                 current_item.to_bytes_with_provider(&mut write_stream, &self.provider).map_err(
                     |_| BoundedError::new(BoundedErrorKind::ConversionError, "Operation failed"),
                 )?;
-                write_stream.position()
-            };
+            }
 
             self.provider
-                .write_data(dest_offset, &item_bytes_buffer[..bytes_written])
+                .write_data(dest_offset, &item_bytes_buffer[..item_size])
                 .map_err(|e| BoundedError::runtime_execution_error("Operation failed"))?;
         }
 
@@ -1723,18 +1692,17 @@ pub fn insert(&mut self, index: usize, value: T) -> core::result::Result<(), Bou
             ));
         }
 
-        let bytes_written = {
+        {
             let buffer_slice = SliceMut::new(&mut item_bytes_buffer[..item_size])
                 .map_err(|_| BoundedError::runtime_execution_error("Operation failed"))?;
             let mut write_stream = WriteStream::new(buffer_slice);
             value.to_bytes_with_provider(&mut write_stream, &self.provider).map_err(|_| {
                 BoundedError::new(BoundedErrorKind::ConversionError, "Operation failed")
             })?;
-            write_stream.position()
-        };
+        }
 
         self.provider
-            .write_data(offset, &item_bytes_buffer[..bytes_written])
+            .write_data(offset, &item_bytes_buffer[..item_size])
             .map_err(|e| BoundedError::runtime_execution_error("Operation failed"))?;
 
         // Update length
@@ -1832,18 +1800,17 @@ pub fn remove(&mut self, index: usize) -> core::result::Result<T, BoundedError>
                 ));
             }
 
-            let bytes_written = {
+            {
                 let buffer_slice = SliceMut::new(&mut item_bytes_buffer[..item_size])
                     .map_err(|_| BoundedError::runtime_execution_error("Operation failed"))?;
                 let mut write_stream = WriteStream::new(buffer_slice);
                 next_item.to_bytes_with_provider(&mut write_stream, &self.provider).map_err(
                     |_| BoundedError::new(BoundedErrorKind::ConversionError, "Operation failed"),
                 )?;
-                write_stream.position()
-            };
+            }
 
             self.provider
-                .write_data(dest_offset, &item_bytes_buffer[..bytes_written])
+                .write_data(dest_offset, &item_bytes_buffer[..item_size])
                 .map_err(|e| BoundedError::runtime_execution_error("Operation failed"))?;
         }
 
@@ -3153,8 +3120,8 @@ fn from_bytes_with_provider<'a, PStream: crate::MemoryProvider>(
     ) -> Result<Self> {
         // Read length
         let count = reader.read_u32_le()? as usize;
-        // Read checksum
-        let checksum = Checksum::from_bytes_with_provider(reader, stream_provider)?;
+        // Read checksum, which pushing the items recomputes
+        let _checksum = Checksum::from_bytes_with_provider(reader, stream_provider)?;
 
         if count > N_ELEMENTS {
             return Err(crate::Error::from(SerializationError::Custom(
@@ -3165,8 +3132,6 @@ fn from_bytes_with_provider<'a, PStream: crate::MemoryProvider>(
         // Note: This should be instantiated with proper allocation macro in real usage
         // For deserialization, using default provider
         let mut vec = BoundedVec::<T, N_ELEMENTS, P>::new(P::default())?;
-        vec.checksum = checksum;
-        vec.length = count;
 
         for _ in 0..count {
             // T::from_bytes_with_provider might need its own provider if T is also generic
@@ -3210,12 +3175,30 @@ pub fn as_internal_slice(&self) -> core::result::Result<Slice<'_>, crate::Error>
     }
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Default)]
 pub struct BoundedString<const N_BYTES: usize, P: MemoryProvider + Default + Clone + PartialEq + Eq>
 {
     bytes: BoundedVec<u8, N_BYTES, P>,
 }
 
+// Strings are equal when their bytes are, whichever provider holds them, so
+// that a string read back from a collection matches the one looked up with
+impl<const N_BYTES: usize, P: MemoryProvider + Default + Clone + PartialEq + Eq> PartialEq
+    for BoundedString<N_BYTES, P>
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && (0..self.len()).all(
+                |i| matches!((self.bytes.get(i), other.bytes.get(i)), (Ok(a), Ok(b)) if a == b),
+            )
+    }
+}
+
+impl<const N_BYTES: usize, P: MemoryProvider + Default + Clone + PartialEq + Eq> Eq
+    for BoundedString<N_BYTES, P>
+{
+}
+
 // Implement Ord specifically for BoundedString to support HashMap keys in
 // no_std (BTreeMap)
 impl<
@@ -3262,15 +3245,27 @@ fn cmp(&self, other: &Self) -> core::cmp::Ordering {
     }
 }
 
+// Strings serialize to a fixed size, their bytes padded with zeros up to the
+// capacity, so that they fit the equally sized slots of bounded collections
+// whatever their length.
 impl<const N_BYTES: usize, P: MemoryProvider + Default + Clone + PartialEq + Eq> ToBytes
     for BoundedString<N_BYTES, P>
 {
+    fn serialized_size(&self) -> usize {
+        // Length (u32) + checksum + bytes up to the capacity
+        4 + CHECKSUM_SIZE + N_BYTES
+    }
+
     fn to_bytes_with_provider<'a, PStream: crate::MemoryProvider>(
         &self,
         writer: &mut WriteStream<'a>,
         stream_provider: &PStream,
     ) -> Result<()> {
-        self.bytes.to_bytes_with_provider(writer, stream_provider)
+        self.bytes.to_bytes_with_provider(writer, stream_provider)?;
+        for _ in self.bytes.len()..N_BYTES {
+            writer.write_u8(0)?;
+        }
+        Ok(())
     }
 
     #[cfg(feature = "default-provider")]
@@ -3287,9 +3282,12 @@ fn from_bytes_with_provider<'a, PStream: crate::MemoryProvider>(
         reader: &mut ReadStream<'a>,
         stream_provider: &PStream,
     ) -> Result<Self> {
-        Ok(Self {
-            bytes: BoundedVec::<u8, N_BYTES, P>::from_bytes_with_provider(reader, stream_provider)?,
-        })
+        let bytes =
+            BoundedVec::<u8, N_BYTES, P>::from_bytes_with_provider(reader, stream_provider)?;
+        for _ in bytes.len()..N_BYTES {
+            reader.read_u8()?;
+        }
+        Ok(Self { bytes })
     }
 
     #[cfg(feature = "default-provider")]
@@ -3312,7 +3310,10 @@ impl<const N_BYTES: usize, P: MemoryProvider + Default + Clone + PartialEq + Eq>
     for BoundedString<N_BYTES, P>
 {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.bytes.hash(state);
+        self.len().hash(state);
+        for byte in self.bytes.iter() {
+            byte.hash(state);
+        }
     }
 }
 
@@ -3486,12 +3487,20 @@ pub fn from_str(s: &str, provider: P) -> core::result::Result<Self, Serializatio
 
     /// Returns the string as a slice.
     ///
-    /// This will panic if the internal bytes are not valid UTF-8.
-    /// For a non-panicking version, use `try_as_str`.
+    /// Returns an error if the internal bytes are not valid UTF-8 or if there's
+    /// a problem accessing the underlying storage.
     pub fn as_str(&self) -> core::result::Result<&str, BoundedError> {
-        // This is temporarily disabled due to lifetime issues in no_std mode
-        // TODO: Implement proper lifetime management or alternative API
-        Err(BoundedError::runtime_execution_error("Operation failed"))
+        if self.bytes.is_empty() {
+            return Ok("");
+        }
+        let bytes = self.bytes.as_raw_slice()?.data().map_err(|_| {
+            BoundedError::new(
+                BoundedErrorKind::SliceError,
+                "Failed to access string bytes",
+            )
+        })?;
+        core::str::from_utf8(bytes)
+            .map_err(|_| BoundedError::new(BoundedErrorKind::Utf8Error, "Invalid UTF-8 in string"))
     }
 
     /// Tries to return the string as a slice.
@@ -3893,6 +3902,10 @@ fn update_checksum(&self, checksum: &mut crate::verification::Checksum) {
 impl<const N_BYTES: usize, P: MemoryProvider + Default + Clone + PartialEq + Eq> ToBytes
     for WasmName<N_BYTES, P>
 {
+    fn serialized_size(&self) -> usize {
+        self.inner.serialized_size()
+    }
+
     fn to_bytes_with_provider<'a, PStream: crate::MemoryProvider>(
         &self,
         writer: &mut WriteStream<'a>,
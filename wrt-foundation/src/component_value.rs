@@ -95,6 +95,10 @@ fn update_checksum(&self, checksum: &mut Checksum) {
 }
 
 impl ToBytes for ValTypeRef {
+    fn serialized_size(&self) -> usize {
+        4
+    }
+
     fn to_bytes_with_provider<'a, PStream: crate::MemoryProvider>(
         &self,
         writer: &mut WriteStream<'a>,
@@ -530,6 +534,80 @@ fn default() -> Self {
     }
 }
 
+impl<P: MemoryProvider + Default + Clone + PartialEq + Eq> ComponentValue<P> {
+    /// Lift a scalar of type `ty` from the core value it is passed as
+    ///
+    /// Follows the canonical ABI: integers narrower than 32 bits are
+    /// truncated, `NaN`s are canonicalized and chars must be Unicode scalar
+    /// values, so the lifted value is always valid for `ty`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `ty` is not a scalar type, `value` is not of the
+    /// core type `ty` is passed as, or a char is not a Unicode scalar value.
+    pub fn lift_scalar(ty: &ValType<P>, value: &Value) -> Result<Self> {
+        Ok(match (ty, value) {
+            (ValType::Bool, Value::I32(v)) => ComponentValue::Bool(*v != 0),
+            (ValType::S8, Value::I32(v)) => ComponentValue::S8(*v as i8),
+            (ValType::U8, Value::I32(v)) => ComponentValue::U8(*v as u8),
+            (ValType::S16, Value::I32(v)) => ComponentValue::S16(*v as i16),
+            (ValType::U16, Value::I32(v)) => ComponentValue::U16(*v as u16),
+            (ValType::S32, Value::I32(v)) => ComponentValue::S32(*v),
+            (ValType::U32, Value::I32(v)) => ComponentValue::U32(*v as u32),
+            (ValType::S64, Value::I64(v)) => ComponentValue::S64(*v),
+            (ValType::U64, Value::I64(v)) => ComponentValue::U64(*v as u64),
+            (ValType::F32, Value::F32(v)) => ComponentValue::F32(v.canonicalize_nan()),
+            (ValType::F64, Value::F64(v)) => ComponentValue::F64(v.canonicalize_nan()),
+            (ValType::Char, Value::I32(v)) => {
+                ComponentValue::Char(char_from_code_point(*v as u32)?)
+            },
+            _ => {
+                return Err(Error::runtime_type_mismatch(
+                    "Core value does not lift to a component scalar of this type",
+                ))
+            },
+        })
+    }
+
+    /// Lower a scalar to the core value it is passed as
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if this is not a scalar value.
+    pub fn lower_scalar(&self) -> Result<Value> {
+        Ok(match self {
+            ComponentValue::Bool(v) => Value::I32(i32::from(*v)),
+            ComponentValue::S8(v) => Value::I32(i32::from(*v)),
+            ComponentValue::U8(v) => Value::I32(i32::from(*v)),
+            ComponentValue::S16(v) => Value::I32(i32::from(*v)),
+            ComponentValue::U16(v) => Value::I32(i32::from(*v)),
+            ComponentValue::S32(v) => Value::I32(*v),
+            ComponentValue::U32(v) => Value::I32(*v as i32),
+            ComponentValue::S64(v) => Value::I64(*v),
+            ComponentValue::U64(v) => Value::I64(*v as i64),
+            ComponentValue::F32(v) => Value::F32(v.canonicalize_nan()),
+            ComponentValue::F64(v) => Value::F64(v.canonicalize_nan()),
+            ComponentValue::Char(v) => Value::I32(u32::from(*v) as i32),
+            _ => {
+                return Err(Error::runtime_type_mismatch(
+                    "Component value is not a scalar",
+                ))
+            },
+        })
+    }
+}
+
+/// Char for a code point lifted from core code
+///
+/// # Errors
+///
+/// Returns an error for surrogates and code points above `U+10FFFF`, which
+/// are not Unicode scalar values.
+pub fn char_from_code_point(code_point: u32) -> Result<char> {
+    char::from_u32(code_point)
+        .ok_or_else(|| Error::validation_error("Code point is not a Unicode scalar value"))
+}
+
 impl<P: MemoryProvider + Default + Clone + PartialEq + Eq> Checksummable for ComponentValue<P> {
     fn update_checksum(&self, checksum: &mut Checksum) {
         // Manually write a discriminant byte then checksum the inner value
@@ -1141,4 +1219,34 @@ fn test_primitive_value_type_matching() {
         // assert!(int_value.matches_type(&ValType::S32, &store);
         // assert!(!int_value.matches_type(&ValType::Bool, &store);
     }
+
+    #[test]
+    fn test_scalar_lifting_never_yields_invalid_values() {
+        type Cv = ComponentValue<crate::safe_memory::NoStdProvider<64>>;
+
+        assert_eq!(
+            Cv::lift_scalar(&ValType::U8, &Value::I32(0x1ff)).unwrap(),
+            Cv::U8(0xff)
+        );
+        assert_eq!(
+            Cv::lift_scalar(&ValType::U64, &Value::I64(-1)).unwrap(),
+            Cv::U64(u64::MAX)
+        );
+        assert_eq!(
+            Cv::lift_scalar(&ValType::Char, &Value::I32(0x1F600)).unwrap(),
+            Cv::Char('\u{1F600}')
+        );
+        // Surrogates and code points past U+10FFFF are not chars
+        assert!(Cv::lift_scalar(&ValType::Char, &Value::I32(0xD800)).is_err());
+        assert!(Cv::lift_scalar(&ValType::Char, &Value::I32(0x11_0000)).is_err());
+        assert!(Cv::lift_scalar(&ValType::S64, &Value::I32(1)).is_err());
+
+        let nan = Value::F64(FloatBits64::from_bits(0xfff8_0000_0000_0001));
+        assert_eq!(
+            Cv::lift_scalar(&ValType::F64, &nan).unwrap(),
+            Cv::F64(FloatBits64::NAN)
+        );
+        assert_eq!(Cv::U64(u64::MAX).lower_scalar().unwrap(), Value::I64(-1));
+        assert!(Cv::Void.lower_scalar().is_err());
+    }
 }
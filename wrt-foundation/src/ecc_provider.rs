@@ -0,0 +1,398 @@
+//! Memory provider guarding its contents with per-page checksums
+//!
+//! A bit-flip in the RAM holding guest linear memory changes what the guest
+//! reads without anyone noticing. [`EccProvider`] wraps another provider and
+//! keeps a checksum of every [`ECC_PAGE_SIZE`] bytes page it holds:
+//!
+//! - writes verify the pages they touch first, so a flip is not sealed into the
+//!   new checksum, and reseal them afterwards
+//! - reads verify the pages they touch, as often as the verification level of
+//!   the provider asks for
+//! - [`EccProvider::scrub`] verifies a few pages at a time, round robin, so
+//!   that an embedder can walk all of memory from an idle task or a timer
+//!   without waiting for the guest to read it
+//!
+//! A page that no longer matches its checksum is reported as an error of the
+//! [`ErrorCategory::MemoryCorruption`](wrt_error::ErrorCategory) category.
+//!
+//! Pages handed out through [`Provider::get_slice_mut`] may change without
+//! the provider seeing it; they are not verified until the next mutating call
+//! or scrub reseals them.
+
+use core::fmt;
+
+use crate::{
+    safe_memory::{
+        Provider,
+        SafeMemoryHandler,
+        Slice,
+        SliceMut,
+        Stats,
+    },
+    verification::{
+        Checksum,
+        VerificationLevel,
+    },
+    Error,
+    Result,
+};
+
+/// Bytes covered by one checksum
+pub const ECC_PAGE_SIZE: usize = 4096;
+
+/// Importance of a read for the verification level of the provider
+const READ_IMPORTANCE: u8 = 128;
+
+/// Provider wrapping `P` with a checksum over each of its first `PAGES` pages
+///
+/// Only the bytes of those pages are accessible: the capacity of the wrapper
+/// is that of `P`, capped at `PAGES * ECC_PAGE_SIZE`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct EccProvider<P: Provider, const PAGES: usize> {
+    inner:        P,
+    checksums:    [u32; PAGES],
+    /// Pages lent out mutably, whose checksums are stale
+    unsealed:     [bool; PAGES],
+    any_unsealed: bool,
+    /// Next page to scrub
+    scrub_cursor: usize,
+}
+
+impl<P: Provider, const PAGES: usize> EccProvider<P, PAGES> {
+    /// Wrap `inner`, checksumming what it already holds
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the contents of `inner` cannot be read.
+    pub fn new(inner: P) -> Result<Self> {
+        let mut provider = Self {
+            inner,
+            checksums: [0; PAGES],
+            unsealed: [false; PAGES],
+            any_unsealed: false,
+            scrub_cursor: 0,
+        };
+        provider.reseal_range(0, provider.capacity())?;
+        Ok(provider)
+    }
+
+    /// The wrapped provider
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    /// Mutable access to the wrapped provider, bypassing the checksums
+    ///
+    /// Writes made through it are reported as corruption unless
+    /// [`Self::reseal`] is called afterwards.
+    pub fn inner_mut(&mut self) -> &mut P {
+        &mut self.inner
+    }
+
+    /// Number of pages covering the capacity of the provider
+    pub fn page_count(&self) -> usize {
+        self.capacity().div_ceil(ECC_PAGE_SIZE)
+    }
+
+    /// Recompute the checksums of all pages, accepting their current contents
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the contents of the provider cannot be read.
+    pub fn reseal(&mut self) -> Result<()> {
+        self.reseal_range(0, self.capacity())
+    }
+
+    /// Verify the next `pages` pages, continuing where the previous scrub
+    /// stopped and wrapping around at the end of the provider
+    ///
+    /// Pages lent out mutably since they were last sealed are resealed
+    /// first. Call this from an idle task or timer to find corruption in
+    /// pages the guest does not read.
+    ///
+    /// # Errors
+    ///
+    /// Returns a memory corruption error for the first scrubbed page that no
+    /// longer matches its checksum; the next scrub starts after it.
+    pub fn scrub(&mut self, pages: usize) -> Result<()> {
+        self.reseal_unsealed()?;
+        let page_count = self.page_count();
+        if page_count == 0 {
+            return Ok(());
+        }
+        for _ in 0..pages.min(page_count) {
+            let page = self.scrub_cursor % page_count;
+            self.scrub_cursor = (page + 1) % page_count;
+            self.verify_page(page)?;
+        }
+        Ok(())
+    }
+
+    /// The first page that no longer matches its checksum, if any
+    pub fn first_corrupted_page(&self) -> Option<usize> {
+        (0..self.page_count()).find(|&page| self.verify_page(page).is_err())
+    }
+
+    fn page_checksum(&self, page: usize) -> Result<u32> {
+        let start = page * ECC_PAGE_SIZE;
+        let end = (start + ECC_PAGE_SIZE).min(self.inner.size());
+        if end <= start {
+            return Ok(Checksum::new().value());
+        }
+        let bytes = self.inner.borrow_slice(start, end - start)?;
+        Ok(Checksum::compute(bytes.data()?).value())
+    }
+
+    fn verify_page(&self, page: usize) -> Result<()> {
+        if self.unsealed[page] {
+            return Ok(());
+        }
+        if self.page_checksum(page)? != self.checksums[page] {
+            return Err(Error::memory_page_corrupted(
+                "Memory page does not match its checksum",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Pages overlapping the bytes `start..end`
+    fn pages_of(&self, start: usize, end: usize) -> core::ops::Range<usize> {
+        if end <= start {
+            return 0..0;
+        }
+        let last = end.div_ceil(ECC_PAGE_SIZE).min(self.page_count());
+        (start / ECC_PAGE_SIZE).min(last)..last
+    }
+
+    fn verify_range(&self, start: usize, end: usize) -> Result<()> {
+        self.pages_of(start, end).try_for_each(|page| self.verify_page(page))
+    }
+
+    fn reseal_range(&mut self, start: usize, end: usize) -> Result<()> {
+        for page in self.pages_of(start, end) {
+            self.checksums[page] = self.page_checksum(page)?;
+            self.unsealed[page] = false;
+        }
+        Ok(())
+    }
+
+    fn reseal_unsealed(&mut self) -> Result<()> {
+        if !self.any_unsealed {
+            return Ok(());
+        }
+        for page in 0..PAGES {
+            if self.unsealed[page] {
+                self.checksums[page] = self.page_checksum(page)?;
+                self.unsealed[page] = false;
+            }
+        }
+        self.any_unsealed = false;
+        Ok(())
+    }
+
+    /// Run `write` over the bytes `start..end`, verifying the pages it
+    /// touches before and resealing them after
+    ///
+    /// Pages up to the used size of the provider are included, since growing
+    /// it changes the bytes their checksums cover.
+    fn guarded_write<T>(
+        &mut self,
+        start: usize,
+        end: usize,
+        write: impl FnOnce(&mut P) -> Result<T>,
+    ) -> Result<T> {
+        self.reseal_unsealed()?;
+        let old_size = self.inner.size();
+        let start = start.min(old_size);
+        self.verify_range(start, end)?;
+        let result = write(&mut self.inner)?;
+        self.reseal_range(start, end.max(self.inner.size()))?;
+        Ok(result)
+    }
+}
+
+impl<P: Provider, const PAGES: usize> Default for EccProvider<P, PAGES> {
+    fn default() -> Self {
+        let inner = P::default();
+        let checksums = [Checksum::new().value(); PAGES];
+        let mut provider = Self {
+            inner,
+            checksums,
+            unsealed: [false; PAGES],
+            any_unsealed: false,
+            scrub_cursor: 0,
+        };
+        // A default provider is normally empty; anything it does hold is
+        // accepted as it is
+        if provider.reseal().is_err() {
+            provider.unsealed = [true; PAGES];
+            provider.any_unsealed = true;
+        }
+        provider
+    }
+}
+
+impl<P: Provider, const PAGES: usize> fmt::Debug for EccProvider<P, PAGES> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EccProvider")
+            .field("inner", &self.inner)
+            .field("pages", &PAGES)
+            .field("any_unsealed", &self.any_unsealed)
+            .field("scrub_cursor", &self.scrub_cursor)
+            .finish()
+    }
+}
+
+impl<P: Provider, const PAGES: usize> Provider for EccProvider<P, PAGES> {
+    type Allocator = P::Allocator;
+
+    fn borrow_slice(&self, offset: usize, len: usize) -> Result<Slice<'_>> {
+        self.verify_access(offset, len)?;
+        if self.verification_level().should_verify(READ_IMPORTANCE) {
+            self.verify_range(offset, offset + len)?;
+        }
+        self.inner.borrow_slice(offset, len)
+    }
+
+    fn write_data(&mut self, offset: usize, data: &[u8]) -> Result<()> {
+        self.verify_access(offset, data.len())?;
+        self.guarded_write(offset, offset + data.len(), |inner| {
+            inner.write_data(offset, data)
+        })
+    }
+
+    fn verify_access(&self, offset: usize, len: usize) -> Result<()> {
+        let end = offset
+            .checked_add(len)
+            .ok_or_else(|| Error::memory_out_of_bounds("Memory access overflows"))?;
+        if end > self.capacity() {
+            return Err(Error::memory_out_of_bounds(
+                "Memory access beyond the pages covered by checksums",
+            ));
+        }
+        self.inner.verify_access(offset, len)
+    }
+
+    fn size(&self) -> usize {
+        self.inner.size().min(self.capacity())
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.capacity().min(PAGES * ECC_PAGE_SIZE)
+    }
+
+    fn verify_integrity(&self) -> Result<()> {
+        self.verify_range(0, self.capacity())?;
+        self.inner.verify_integrity()
+    }
+
+    fn set_verification_level(&mut self, level: VerificationLevel) {
+        self.inner.set_verification_level(level);
+    }
+
+    fn verification_level(&self) -> VerificationLevel {
+        self.inner.verification_level()
+    }
+
+    fn memory_stats(&self) -> Stats {
+        self.inner.memory_stats()
+    }
+
+    fn get_slice_mut(&mut self, offset: usize, len: usize) -> Result<SliceMut<'_>> {
+        self.verify_access(offset, len)?;
+        self.reseal_unsealed()?;
+        self.verify_range(offset, offset + len)?;
+        for page in self.pages_of(offset, offset + len) {
+            self.unsealed[page] = true;
+            self.any_unsealed = true;
+        }
+        self.inner.get_slice_mut(offset, len)
+    }
+
+    fn copy_within(&mut self, src_offset: usize, dst_offset: usize, len: usize) -> Result<()> {
+        self.verify_access(src_offset, len)?;
+        self.verify_access(dst_offset, len)?;
+        self.verify_range(src_offset, src_offset + len)?;
+        self.guarded_write(dst_offset, dst_offset + len, |inner| {
+            inner.copy_within(src_offset, dst_offset, len)
+        })
+    }
+
+    fn ensure_used_up_to(&mut self, byte_offset: usize) -> Result<()> {
+        if byte_offset > self.capacity() {
+            return Err(Error::memory_out_of_bounds(
+                "Used size beyond the pages covered by checksums",
+            ));
+        }
+        self.guarded_write(byte_offset, byte_offset, |inner| {
+            inner.ensure_used_up_to(byte_offset)
+        })
+    }
+
+    fn acquire_memory(&self, layout: core::alloc::Layout) -> Result<*mut u8> {
+        self.inner.acquire_memory(layout)
+    }
+
+    fn release_memory(&self, ptr: *mut u8, layout: core::alloc::Layout) -> Result<()> {
+        self.inner.release_memory(ptr, layout)
+    }
+
+    fn get_allocator(&self) -> &Self::Allocator {
+        self.inner.get_allocator()
+    }
+
+    fn new_handler(&self) -> Result<SafeMemoryHandler<Self>>
+    where
+        Self: Sized + Clone,
+    {
+        Ok(SafeMemoryHandler::new(self.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::safe_memory::NoStdProvider;
+
+    type TestProvider = EccProvider<NoStdProvider<{ 4 * ECC_PAGE_SIZE }>, 4>;
+
+    fn provider_with_data() -> TestProvider {
+        let mut provider = TestProvider::new(NoStdProvider::default()).unwrap();
+        provider.ensure_used_up_to(4 * ECC_PAGE_SIZE).unwrap();
+        provider.write_data(0, &[7; 2 * ECC_PAGE_SIZE]).unwrap();
+        provider
+    }
+
+    #[test]
+    fn test_writes_keep_checksums_current() {
+        let mut provider = provider_with_data();
+        provider.write_data(ECC_PAGE_SIZE + 10, &[1, 2, 3]).unwrap();
+        provider.copy_within(0, 3 * ECC_PAGE_SIZE, 16).unwrap();
+        provider.get_slice_mut(20, 4).unwrap().data_mut().unwrap()[0] = 9;
+
+        assert!(provider.verify_integrity().is_ok());
+        assert!(provider.scrub(4).is_ok());
+        assert_eq!(provider.borrow_slice(20, 1).unwrap().data().unwrap(), &[9]);
+        assert_eq!(provider.first_corrupted_page(), None);
+    }
+
+    #[test]
+    fn test_bit_flip_is_reported_as_corruption() {
+        let mut provider = provider_with_data();
+        provider.inner_mut().write_data(ECC_PAGE_SIZE + 100, &[6]).unwrap();
+
+        assert_eq!(provider.first_corrupted_page(), Some(1));
+        let error = provider.borrow_slice(ECC_PAGE_SIZE, 4).unwrap_err();
+        assert!(error.is_memory_corruption_error());
+        assert!(provider.borrow_slice(0, 4).is_ok());
+        // A write to the page does not seal the flip into its checksum
+        assert!(provider.write_data(ECC_PAGE_SIZE, &[1]).is_err());
+
+        // Scrubbing finds it on the second page it visits
+        assert!(provider.scrub(1).is_ok());
+        assert!(provider.scrub(1).is_err());
+
+        provider.reseal().unwrap();
+        assert!(provider.verify_integrity().is_ok());
+    }
+}
@@ -64,6 +64,19 @@ pub const fn to_bits(self) -> u32 {
     pub const fn from_bits(bits: u32) -> Self {
         Self(bits)
     }
+
+    /// Returns [`Self::NAN`] if this is any `NaN`, this value otherwise.
+    ///
+    /// The Component Model only exposes the canonical `NaN`, so floats lifted
+    /// from core values are passed through this.
+    #[must_use]
+    pub const fn canonicalize_nan(self) -> Self {
+        if self.0 & 0x7fff_ffff > 0x7f80_0000 {
+            Self::NAN
+        } else {
+            self
+        }
+    }
 }
 
 impl Hash for FloatBits32 {
@@ -133,6 +146,19 @@ pub const fn to_bits(self) -> u64 {
     pub const fn from_bits(bits: u64) -> Self {
         Self(bits)
     }
+
+    /// Returns [`Self::NAN`] if this is any `NaN`, this value otherwise.
+    ///
+    /// The Component Model only exposes the canonical `NaN`, so floats lifted
+    /// from core values are passed through this.
+    #[must_use]
+    pub const fn canonicalize_nan(self) -> Self {
+        if self.0 & 0x7fff_ffff_ffff_ffff > 0x7ff0_0000_0000_0000 {
+            Self::NAN
+        } else {
+            self
+        }
+    }
 }
 
 impl Hash for FloatBits64 {
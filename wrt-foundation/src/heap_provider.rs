@@ -168,8 +168,12 @@ fn copy_within(&mut self, src_offset: usize, dst_offset: usize, len: usize) -> R
     }
 
     fn ensure_used_up_to(&mut self, byte_offset: usize) -> Result<()> {
+        // The buffer lives on the heap, so it grows rather than failing
         if byte_offset > self.data.len() {
-            return Err(Error::memory_error("Offset exceeds capacity"));
+            self.data
+                .try_reserve_exact(byte_offset - self.data.len())
+                .map_err(|_| Error::memory_error("Failed to allocate heap memory"))?;
+            self.data.resize(byte_offset, 0);
         }
         self.used = core::cmp::max(self.used, byte_offset);
         Ok(())
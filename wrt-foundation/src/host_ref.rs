@@ -0,0 +1,301 @@
+//! Host data carried by `externref` values
+//!
+//! An [`ExternRef`] is opaque to the guest: it can pass it around, store it in
+//! tables and hand it back to the host, but never look inside. A
+//! [`HostRefTable`] gives hosts the other half: it stores arbitrary host
+//! objects and hands out the [`ExternRef`]s standing for them, so that a host
+//! function receiving one of those references gets the object back with
+//! [`HostRefTable::get`].
+//!
+//! Objects are reference counted. An object starts with one reference, hosts
+//! add more with [`HostRefTable::retain`] and drop them with
+//! [`HostRefTable::release`]; the object is removed, and its finalizer run,
+//! when the last one is released or the table is dropped. Hosts that do not
+//! share objects simply release each once.
+//!
+//! The index of a reference combines the slot of the object with a
+//! generation, so a stale reference to a removed object is rejected instead
+//! of resolving to the object that took its slot.
+
+use alloc::{
+    boxed::Box,
+    vec::Vec,
+};
+use core::{
+    any::Any,
+    fmt,
+};
+
+use crate::{
+    values::ExternRef,
+    Error,
+    Result,
+};
+
+/// Bits of a reference index selecting the slot
+const SLOT_BITS: u32 = 24;
+const SLOT_MASK: u32 = (1 << SLOT_BITS) - 1;
+
+/// Object stored for the host
+type HostObject = Box<dyn Any + Send + Sync>;
+
+/// Finalizer run with the object when it is removed
+type Finalizer = Box<dyn FnOnce(HostObject) + Send + Sync>;
+
+struct Entry {
+    object:    HostObject,
+    refs:      u32,
+    finalizer: Option<Finalizer>,
+}
+
+struct Slot {
+    generation: u8,
+    entry:      Option<Entry>,
+}
+
+/// Host objects referenced by `externref` values
+#[derive(Default)]
+pub struct HostRefTable {
+    slots: Vec<Slot>,
+    /// Slots without an object, reused first
+    free:  Vec<u32>,
+}
+
+impl HostRefTable {
+    /// Create an empty table
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of objects in the table
+    pub fn len(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+
+    /// Whether the table holds no objects
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Store `object`, returning the reference standing for it
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table has no room for another object.
+    pub fn insert<T: Any + Send + Sync>(&mut self, object: T) -> Result<ExternRef> {
+        self.insert_entry(Entry {
+            object:    Box::new(object),
+            refs:      1,
+            finalizer: None,
+        })
+    }
+
+    /// Store `object` as by [`Self::insert`], running `finalizer` with it
+    /// once it is removed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table has no room for another object.
+    pub fn insert_with_finalizer<T, F>(&mut self, object: T, finalizer: F) -> Result<ExternRef>
+    where
+        T: Any + Send + Sync,
+        F: FnOnce(T) + Send + Sync + 'static,
+    {
+        self.insert_entry(Entry {
+            object:    Box::new(object),
+            refs:      1,
+            finalizer: Some(Box::new(move |object: HostObject| {
+                if let Ok(object) = object.downcast::<T>() {
+                    finalizer(*object);
+                }
+            })),
+        })
+    }
+
+    fn insert_entry(&mut self, entry: Entry) -> Result<ExternRef> {
+        let slot = match self.free.pop() {
+            Some(slot) => slot,
+            None => {
+                let slot = u32::try_from(self.slots.len())
+                    .ok()
+                    .filter(|&slot| slot <= SLOT_MASK)
+                    .ok_or_else(|| Error::resource_exhausted("Too many host references"))?;
+                self.slots.push(Slot {
+                    generation: 0,
+                    entry:      None,
+                });
+                slot
+            },
+        };
+        let slot_state = &mut self.slots[slot as usize];
+        slot_state.entry = Some(entry);
+        Ok(ExternRef {
+            index: slot | (u32::from(slot_state.generation) << SLOT_BITS),
+        })
+    }
+
+    fn entry(&self, reference: &ExternRef) -> Option<&Entry> {
+        let slot = self.slots.get((reference.index & SLOT_MASK) as usize)?;
+        if u32::from(slot.generation) != reference.index >> SLOT_BITS {
+            return None;
+        }
+        slot.entry.as_ref()
+    }
+
+    fn entry_mut(&mut self, reference: &ExternRef) -> Result<&mut Entry> {
+        let slot = self
+            .slots
+            .get_mut((reference.index & SLOT_MASK) as usize)
+            .filter(|slot| u32::from(slot.generation) == reference.index >> SLOT_BITS)
+            .ok_or_else(|| Error::resource_invalid_handle("Unknown host reference"))?;
+        slot.entry
+            .as_mut()
+            .ok_or_else(|| Error::resource_invalid_handle("Unknown host reference"))
+    }
+
+    /// Whether `reference` stands for an object of the table
+    pub fn contains(&self, reference: &ExternRef) -> bool {
+        self.entry(reference).is_some()
+    }
+
+    /// Object `reference` stands for, if it is a `T`
+    pub fn get<T: Any>(&self, reference: &ExternRef) -> Option<&T> {
+        self.entry(reference)?.object.downcast_ref()
+    }
+
+    /// Object `reference` stands for, mutably, if it is a `T`
+    pub fn get_mut<T: Any>(&mut self, reference: &ExternRef) -> Option<&mut T> {
+        self.entry_mut(reference).ok()?.object.downcast_mut()
+    }
+
+    /// Add a reference to the object `reference` stands for
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `reference` does not stand for an object of the
+    /// table.
+    pub fn retain(&mut self, reference: &ExternRef) -> Result<()> {
+        let entry = self.entry_mut(reference)?;
+        entry.refs = entry.refs.saturating_add(1);
+        Ok(())
+    }
+
+    /// Drop a reference to the object `reference` stands for, removing the
+    /// object and running its finalizer if it was the last
+    ///
+    /// Returns whether the object was removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `reference` does not stand for an object of the
+    /// table.
+    pub fn release(&mut self, reference: &ExternRef) -> Result<bool> {
+        let entry = self.entry_mut(reference)?;
+        entry.refs -= 1;
+        if entry.refs > 0 {
+            return Ok(false);
+        }
+        let slot = reference.index & SLOT_MASK;
+        if let Some(entry) = self.remove_slot(slot) {
+            finalize(entry);
+        }
+        Ok(true)
+    }
+
+    /// Remove the object `reference` stands for regardless of its references
+    /// and return it, without running its finalizer
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `reference` does not stand for an object of the
+    /// table or the object is not a `T`, in which case it stays in the table.
+    pub fn take<T: Any>(&mut self, reference: &ExternRef) -> Result<T> {
+        if !self.entry_mut(reference)?.object.is::<T>() {
+            return Err(Error::runtime_type_mismatch(
+                "Host reference has another type",
+            ));
+        }
+        self.remove_slot(reference.index & SLOT_MASK)
+            .and_then(|entry| entry.object.downcast().ok())
+            .map(|object| *object)
+            .ok_or_else(|| Error::resource_invalid_handle("Unknown host reference"))
+    }
+
+    fn remove_slot(&mut self, slot: u32) -> Option<Entry> {
+        let slot_state = &mut self.slots[slot as usize];
+        let entry = slot_state.entry.take()?;
+        // References to the removed object no longer match the slot
+        slot_state.generation = slot_state.generation.wrapping_add(1);
+        self.free.push(slot);
+        Some(entry)
+    }
+}
+
+fn finalize(entry: Entry) {
+    if let Some(finalizer) = entry.finalizer {
+        finalizer(entry.object);
+    }
+}
+
+impl Drop for HostRefTable {
+    fn drop(&mut self) {
+        for slot in &mut self.slots {
+            if let Some(entry) = slot.entry.take() {
+                finalize(entry);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for HostRefTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HostRefTable").field("len", &self.len()).finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::sync::Arc;
+    use core::sync::atomic::{
+        AtomicU32,
+        Ordering,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_get_returns_host_object() {
+        let mut table = HostRefTable::new();
+        let reference = table.insert(42u64).unwrap();
+        assert_eq!(table.get::<u64>(&reference), Some(&42));
+        assert_eq!(table.get::<u32>(&reference), None);
+        *table.get_mut::<u64>(&reference).unwrap() += 1;
+        assert!(table.take::<u32>(&reference).is_err());
+        assert_eq!(table.take::<u64>(&reference).unwrap(), 43);
+        assert!(table.is_empty());
+    }
+
+    #[test]
+    fn test_last_release_runs_finalizer() {
+        let finalized = Arc::new(AtomicU32::new(0));
+        let mut table = HostRefTable::new();
+        let counter = finalized.clone();
+        let reference = table
+            .insert_with_finalizer(7u32, move |value| {
+                counter.fetch_add(value, Ordering::SeqCst);
+            })
+            .unwrap();
+
+        table.retain(&reference).unwrap();
+        assert!(!table.release(&reference).unwrap());
+        assert_eq!(finalized.load(Ordering::SeqCst), 0);
+        assert!(table.release(&reference).unwrap());
+        assert_eq!(finalized.load(Ordering::SeqCst), 7);
+
+        // The stale reference does not resolve to the slot's next object
+        let next = table.insert(1u32).unwrap();
+        assert!(!table.contains(&reference));
+        assert!(table.release(&reference).is_err());
+        assert!(table.contains(&next));
+    }
+}
@@ -0,0 +1,412 @@
+//! Collections bounded by a capacity chosen at runtime
+//!
+//! A [`BoundedVec`] reserves the memory for `N` elements up front, so a
+//! collection sized for the worst case costs that RAM even when it holds a
+//! handful of elements. [`HybridVec`] keeps the bounded, checked semantics
+//! but takes its capacity from the caller at runtime, up to the compile-time
+//! maximum `N`:
+//!
+//! - with `alloc`, elements are stored on the heap, which grows lazily as
+//!   elements are pushed and never past the capacity
+//! - without `alloc`, elements are stored in a [`BoundedVec`] with room for `N`
+//!   elements, as before
+//!
+//! Either way a push beyond the capacity fails, the collection maintains a
+//! checksum according to its [`VerificationLevel`], and it serializes to the
+//! same bytes as a [`BoundedVec`] with the same elements.
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+use alloc::vec::Vec;
+#[cfg(any(feature = "std", feature = "alloc"))]
+use core::marker::PhantomData;
+
+use crate::{
+    bounded::{
+        BoundedError,
+        BoundedErrorKind,
+        BoundedVec,
+    },
+    operations::{
+        record_global_operation,
+        Type as OperationType,
+    },
+    traits::{
+        importance,
+        BoundedCapacity,
+        Checksummable,
+        Checksummed,
+        FromBytes,
+        ReadStream,
+        ToBytes,
+        WriteStream,
+    },
+    verification::{
+        Checksum,
+        VerificationLevel,
+    },
+    MemoryProvider,
+    Result,
+};
+
+/// Vector of at most a runtime-chosen number of elements
+///
+/// `N` is the largest capacity any instance may be given, and the number of
+/// elements the provider `P` is sized for without `alloc`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HybridVec<T, const N: usize, P>
+where
+    T: Sized + Checksummable + ToBytes + FromBytes + Default + Clone + PartialEq + Eq,
+    P: MemoryProvider + Default + Clone + PartialEq + Eq,
+{
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    items:              Vec<T>,
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    _provider:          PhantomData<P>,
+    #[cfg(not(any(feature = "std", feature = "alloc")))]
+    items:              BoundedVec<T, N, P>,
+    cap:                usize,
+    checksum:           Checksum,
+    verification_level: VerificationLevel,
+}
+
+impl<T, const N: usize, P> HybridVec<T, N, P>
+where
+    T: Sized + Checksummable + ToBytes + FromBytes + Default + Clone + PartialEq + Eq,
+    P: MemoryProvider + Default + Clone + PartialEq + Eq,
+{
+    /// Create an empty vector holding at most `cap` elements, capped at `N`
+    ///
+    /// Without `alloc` the elements are stored in `provider`, which must have
+    /// room for `N` of them; with `alloc` it is not used.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bounded storage cannot be created.
+    pub fn new(provider: P, cap: usize) -> Result<Self> {
+        Self::with_verification_level(provider, cap, VerificationLevel::default())
+    }
+
+    /// Create an empty vector as by [`Self::new`] with a specific
+    /// verification level
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bounded storage cannot be created.
+    pub fn with_verification_level(
+        provider: P,
+        cap: usize,
+        verification_level: VerificationLevel,
+    ) -> Result<Self> {
+        record_global_operation(OperationType::CollectionCreate, verification_level);
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        let items = {
+            drop(provider);
+            Vec::new()
+        };
+        #[cfg(not(any(feature = "std", feature = "alloc")))]
+        let items = BoundedVec::with_verification_level(provider, verification_level)?;
+        Ok(Self {
+            items,
+            #[cfg(any(feature = "std", feature = "alloc"))]
+            _provider: PhantomData,
+            cap: cap.min(N),
+            checksum: Checksum::new(),
+            verification_level,
+        })
+    }
+
+    /// Verification level of the vector
+    pub fn verification_level(&self) -> VerificationLevel {
+        self.verification_level
+    }
+
+    /// Number of elements the storage currently has room for
+    ///
+    /// With `alloc` this grows with the vector up to [`Self::capacity`];
+    /// without it, it is always `N`.
+    pub fn reserved(&self) -> usize {
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        return self.items.capacity();
+        #[cfg(not(any(feature = "std", feature = "alloc")))]
+        return N;
+    }
+
+    /// Append `item`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the vector is full or the storage cannot grow.
+    #[allow(clippy::needless_pass_by_value)] // The item is moved into the storage
+    pub fn push(&mut self, item: T) -> core::result::Result<(), BoundedError> {
+        if self.is_full() {
+            return Err(BoundedError::capacity_exceeded());
+        }
+        if self.verification_level >= VerificationLevel::Full {
+            item.update_checksum(&mut self.checksum);
+        }
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        {
+            if self.items.len() == self.items.capacity() {
+                // Grow geometrically, but never reserve past the capacity
+                let len = self.items.len();
+                let additional = len.max(4).min(self.cap - len);
+                self.items.try_reserve_exact(additional).map_err(|_| {
+                    BoundedError::new(BoundedErrorKind::CapacityExceeded, "Allocation failed")
+                })?;
+            }
+            self.items.push(item);
+        }
+        #[cfg(not(any(feature = "std", feature = "alloc")))]
+        self.items.push(item)?;
+        record_global_operation(OperationType::CollectionPush, self.verification_level);
+        Ok(())
+    }
+
+    /// Remove and return the last element, or `None` if the vector is empty
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the element cannot be read from the storage.
+    pub fn pop(&mut self) -> core::result::Result<Option<T>, BoundedError> {
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        let item = self.items.pop();
+        #[cfg(not(any(feature = "std", feature = "alloc")))]
+        let item = self.items.pop()?;
+        if item.is_some() {
+            record_global_operation(OperationType::CollectionWrite, self.verification_level);
+            if self.verification_level >= VerificationLevel::Full {
+                self.recalculate_checksum();
+            }
+        }
+        Ok(item)
+    }
+
+    /// Element at `index`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `index` is out of bounds or the element cannot be
+    /// read from the storage.
+    pub fn get(&self, index: usize) -> Result<T> {
+        record_global_operation(OperationType::CollectionRead, self.verification_level);
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        return self
+            .items
+            .get(index)
+            .cloned()
+            .ok_or_else(|| crate::Error::index_out_of_bounds("Index out of bounds"));
+        #[cfg(not(any(feature = "std", feature = "alloc")))]
+        return self.items.get(index);
+    }
+
+    /// Replace the element at `index` with `value`, returning the old one
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `index` is out of bounds or the storage cannot be
+    /// written.
+    pub fn set(&mut self, index: usize, value: T) -> core::result::Result<T, BoundedError> {
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        let old = {
+            let len = self.items.len();
+            let slot = self
+                .items
+                .get_mut(index)
+                .ok_or_else(|| BoundedError::index_out_of_bounds(index, len))?;
+            core::mem::replace(slot, value)
+        };
+        #[cfg(not(any(feature = "std", feature = "alloc")))]
+        let old = self.items.set(index, value)?;
+        record_global_operation(OperationType::CollectionWrite, self.verification_level);
+        if self.verification_level >= VerificationLevel::Full {
+            self.recalculate_checksum();
+        }
+        Ok(old)
+    }
+
+    /// Remove all elements, releasing the heap storage with `alloc`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bounded storage cannot be cleared.
+    pub fn clear(&mut self) -> core::result::Result<(), BoundedError> {
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        {
+            self.items = Vec::new();
+        }
+        #[cfg(not(any(feature = "std", feature = "alloc")))]
+        self.items.clear()?;
+        self.checksum = Checksum::new();
+        record_global_operation(OperationType::CollectionWrite, self.verification_level);
+        Ok(())
+    }
+
+    /// Iterate over copies of the elements
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        return self.items.iter().cloned();
+        #[cfg(not(any(feature = "std", feature = "alloc")))]
+        return self.items.iter();
+    }
+}
+
+impl<T, const N: usize, P> BoundedCapacity for HybridVec<T, N, P>
+where
+    T: Sized + Checksummable + ToBytes + FromBytes + Default + Clone + PartialEq + Eq,
+    P: MemoryProvider + Default + Clone + PartialEq + Eq,
+{
+    fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    fn len(&self) -> usize {
+        self.items.len()
+    }
+}
+
+impl<T, const N: usize, P> Checksummable for HybridVec<T, N, P>
+where
+    T: Sized + Checksummable + ToBytes + FromBytes + Default + Clone + PartialEq + Eq,
+    P: MemoryProvider + Default + Clone + PartialEq + Eq,
+{
+    fn update_checksum(&self, checksum: &mut Checksum) {
+        (self.len() as u32).update_checksum(checksum);
+        for item in self.iter() {
+            item.update_checksum(checksum);
+        }
+    }
+}
+
+impl<T, const N: usize, P> Checksummed for HybridVec<T, N, P>
+where
+    T: Sized + Checksummable + ToBytes + FromBytes + Default + Clone + PartialEq + Eq,
+    P: MemoryProvider + Default + Clone + PartialEq + Eq,
+{
+    fn checksum(&self) -> Checksum {
+        self.checksum
+    }
+
+    fn recalculate_checksum(&mut self) {
+        let mut checksum = Checksum::new();
+        for item in self.iter() {
+            item.update_checksum(&mut checksum);
+        }
+        self.checksum = checksum;
+    }
+
+    fn verify_checksum(&self) -> bool {
+        record_global_operation(OperationType::CollectionValidate, self.verification_level);
+        if !self.verification_level.should_verify(importance::CRITICAL) {
+            return true;
+        }
+        let mut checksum = Checksum::new();
+        for item in self.iter() {
+            item.update_checksum(&mut checksum);
+        }
+        checksum == self.checksum
+    }
+}
+
+impl<T, const N: usize, P> ToBytes for HybridVec<T, N, P>
+where
+    T: Sized + Checksummable + ToBytes + FromBytes + Default + Clone + PartialEq + Eq,
+    P: MemoryProvider + Default + Clone + PartialEq + Eq,
+{
+    fn serialized_size(&self) -> usize {
+        // Length (u32) + checksum + items, as for a BoundedVec
+        4 + self.checksum.serialized_size() + self.len() * T::default().serialized_size()
+    }
+
+    fn to_bytes_with_provider<'a, PStream: MemoryProvider>(
+        &self,
+        writer: &mut WriteStream<'a>,
+        stream_provider: &PStream,
+    ) -> Result<()> {
+        writer.write_u32_le(self.len() as u32)?;
+        self.checksum.to_bytes_with_provider(writer, stream_provider)?;
+        for item in self.iter() {
+            item.to_bytes_with_provider(writer, stream_provider)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T, const N: usize, P> FromBytes for HybridVec<T, N, P>
+where
+    T: Sized + Checksummable + ToBytes + FromBytes + Default + Clone + PartialEq + Eq,
+    P: MemoryProvider + Default + Clone + PartialEq + Eq,
+{
+    /// Read a vector with capacity `N`
+    fn from_bytes_with_provider<'a, PStream: MemoryProvider>(
+        reader: &mut ReadStream<'a>,
+        stream_provider: &PStream,
+    ) -> Result<Self> {
+        let count = reader.read_u32_le()? as usize;
+        let checksum = Checksum::from_bytes_with_provider(reader, stream_provider)?;
+        if count > N {
+            return Err(crate::Error::foundation_bounded_capacity_exceeded(
+                "Decoded vector length exceeds capacity",
+            ));
+        }
+        let mut vec = Self::new(P::default(), N)?;
+        for _ in 0..count {
+            vec.push(T::from_bytes_with_provider(reader, stream_provider)?)?;
+        }
+        vec.checksum = checksum;
+        Ok(vec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::safe_memory::NoStdProvider;
+
+    type Vec16 = HybridVec<u32, 16, NoStdProvider<256>>;
+
+    #[test]
+    fn test_capacity_is_capped() {
+        let mut vec = Vec16::new(NoStdProvider::default(), 3).unwrap();
+        assert_eq!(vec.capacity(), 3);
+        for value in 0..3 {
+            vec.push(value).unwrap();
+        }
+        assert!(vec.push(3).is_err());
+        assert_eq!(vec.get(2).unwrap(), 2);
+        assert!(vec.get(3).is_err());
+
+        assert_eq!(
+            Vec16::new(NoStdProvider::default(), 100).unwrap().capacity(),
+            16
+        );
+    }
+
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    #[test]
+    fn test_storage_grows_lazily() {
+        let mut vec = Vec16::new(NoStdProvider::default(), 10).unwrap();
+        assert_eq!(vec.reserved(), 0);
+        vec.push(1).unwrap();
+        assert_eq!(vec.reserved(), 4);
+        for value in 0..9 {
+            let _ = vec.push(value);
+        }
+        assert_eq!(vec.reserved(), 10);
+        vec.clear().unwrap();
+        assert_eq!(vec.reserved(), 0);
+    }
+
+    #[test]
+    fn test_checksum_tracks_contents() {
+        let mut vec =
+            Vec16::with_verification_level(NoStdProvider::default(), 8, VerificationLevel::Full)
+                .unwrap();
+        vec.push(7).unwrap();
+        vec.push(9).unwrap();
+        assert!(vec.verify_checksum());
+        assert_eq!(vec.set(0, 8).unwrap(), 7);
+        assert!(vec.verify_checksum());
+        assert_eq!(vec.pop().unwrap(), Some(9));
+        assert!(vec.verify_checksum());
+    }
+}
@@ -0,0 +1,232 @@
+//! Interned names
+//!
+//! Export, import and filter lookups compare the same handful of names over
+//! and over. A [`NamePool`] stores every distinct name once, in a fixed-size
+//! byte arena, and hands out a [`NameId`] for it. Comparing two ids is a
+//! single integer comparison, and a name is interned without allocating.
+//!
+//! The pool is bounded both in the number of names and in their total length,
+//! and keeps a checksum of its arena so that corruption of the stored names
+//! can be detected with [`Checksummed::verify_checksum`].
+
+use core::fmt;
+
+use crate::{
+    operations::{
+        record_global_operation,
+        Type as OperationType,
+    },
+    traits::Checksummed,
+    verification::{
+        Checksum,
+        VerificationLevel,
+    },
+    Error,
+    Result,
+};
+
+/// Handle to a name interned in a [`NamePool`]
+///
+/// Ids are only meaningful for the pool that issued them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct NameId(u32);
+
+impl NameId {
+    /// Position of the name in its pool, in the order names were interned
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Entry {
+    hash:  u32,
+    start: u32,
+    len:   u32,
+}
+
+impl Entry {
+    const EMPTY: Self = Self {
+        hash:  0,
+        start: 0,
+        len:   0,
+    };
+}
+
+/// 32-bit FNV-1a, cheap enough to compute on every lookup
+fn hash(name: &[u8]) -> u32 {
+    name.iter().fold(0x811c_9dc5, |hash, &byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// Pool of up to `NAMES` distinct names of `BYTES` bytes in total
+#[derive(Clone, PartialEq, Eq)]
+pub struct NamePool<const NAMES: usize, const BYTES: usize> {
+    bytes:    [u8; BYTES],
+    used:     usize,
+    entries:  [Entry; NAMES],
+    len:      usize,
+    checksum: Checksum,
+}
+
+impl<const NAMES: usize, const BYTES: usize> NamePool<NAMES, BYTES> {
+    /// Create an empty pool
+    pub const fn new() -> Self {
+        Self {
+            bytes:    [0; BYTES],
+            used:     0,
+            entries:  [Entry::EMPTY; NAMES],
+            len:      0,
+            checksum: Checksum::new(),
+        }
+    }
+
+    /// Number of names in the pool
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the pool holds no names
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Bytes taken by the names in the pool
+    pub fn bytes_used(&self) -> usize {
+        self.used
+    }
+
+    fn find(&self, name: &[u8], hash: u32) -> Option<NameId> {
+        self.entries[..self.len]
+            .iter()
+            .position(|entry| {
+                entry.hash == hash
+                    && self.bytes[entry.start as usize..][..entry.len as usize] == *name
+            })
+            .map(|index| NameId(index as u32))
+    }
+
+    /// Id of `name`, if it has been interned
+    pub fn get(&self, name: &str) -> Option<NameId> {
+        record_global_operation(OperationType::CollectionRead, VerificationLevel::default());
+        self.find(name.as_bytes(), hash(name.as_bytes()))
+    }
+
+    /// Id of `name`, adding it to the pool if it is not there yet
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pool has no room for another name of this
+    /// length.
+    pub fn intern(&mut self, name: &str) -> Result<NameId> {
+        let name = name.as_bytes();
+        let hash = hash(name);
+        if let Some(id) = self.find(name, hash) {
+            return Ok(id);
+        }
+        if self.len == NAMES || name.len() > BYTES - self.used {
+            return Err(Error::foundation_bounded_capacity_exceeded(
+                "Name pool is full",
+            ));
+        }
+        self.bytes[self.used..][..name.len()].copy_from_slice(name);
+        self.entries[self.len] = Entry {
+            hash,
+            start: self.used as u32,
+            len: name.len() as u32,
+        };
+        self.used += name.len();
+        self.checksum.update_slice(name);
+        self.len += 1;
+        record_global_operation(OperationType::CollectionPush, VerificationLevel::default());
+        Ok(NameId(self.len as u32 - 1))
+    }
+
+    /// Name `id` stands for, or `None` if the pool did not issue it
+    pub fn resolve(&self, id: NameId) -> Option<&str> {
+        let entry = self.entries[..self.len].get(id.index())?;
+        // Only whole `str`s are copied into the arena
+        core::str::from_utf8(&self.bytes[entry.start as usize..][..entry.len as usize]).ok()
+    }
+
+    /// Remove all names, invalidating the ids issued so far
+    pub fn clear(&mut self) {
+        self.used = 0;
+        self.len = 0;
+        self.checksum = Checksum::new();
+    }
+}
+
+impl<const NAMES: usize, const BYTES: usize> Default for NamePool<NAMES, BYTES> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const NAMES: usize, const BYTES: usize> fmt::Debug for NamePool<NAMES, BYTES> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries((0..self.len).filter_map(|index| self.resolve(NameId(index as u32))))
+            .finish()
+    }
+}
+
+impl<const NAMES: usize, const BYTES: usize> Checksummed for NamePool<NAMES, BYTES> {
+    fn checksum(&self) -> Checksum {
+        self.checksum
+    }
+
+    fn recalculate_checksum(&mut self) {
+        self.checksum = Checksum::compute(&self.bytes[..self.used]);
+    }
+
+    fn verify_checksum(&self) -> bool {
+        record_global_operation(
+            OperationType::CollectionValidate,
+            VerificationLevel::default(),
+        );
+        Checksum::compute(&self.bytes[..self.used]) == self.checksum
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern_deduplicates() {
+        let mut pool = NamePool::<4, 16>::new();
+        let run = pool.intern("run").unwrap();
+        let init = pool.intern("init").unwrap();
+        assert_ne!(run, init);
+        assert_eq!(pool.intern("run").unwrap(), run);
+        assert_eq!(pool.get("init"), Some(init));
+        assert_eq!(pool.get("stop"), None);
+        assert_eq!(pool.resolve(init), Some("init"));
+        assert_eq!(pool.len(), 2);
+        assert_eq!(pool.bytes_used(), 7);
+    }
+
+    #[test]
+    fn test_pool_is_bounded() {
+        let mut pool = NamePool::<2, 8>::new();
+        assert!(pool.intern("abcdefghi").is_err());
+        pool.intern("abcd").unwrap();
+        pool.intern("efgh").unwrap();
+        assert!(pool.intern("i").is_err());
+        // Names already present are still found when the pool is full
+        assert!(pool.intern("abcd").is_ok());
+    }
+
+    #[test]
+    fn test_checksum_detects_corruption() {
+        let mut pool = NamePool::<4, 16>::new();
+        pool.intern("memory").unwrap();
+        assert!(pool.verify_checksum());
+        pool.bytes[0] ^= 1;
+        assert!(!pool.verify_checksum());
+        pool.recalculate_checksum();
+        assert!(pool.verify_checksum());
+    }
+}
@@ -0,0 +1,278 @@
+// WRT - wrt-foundation
+// Copyright (c) 2025 Ralf Anton Beier
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+//! LEB128 decoding for the WebAssembly binary format.
+//!
+//! The binary format encodes an N-bit integer in at most `ceil(N / 7)`
+//! LEB128 bytes, and the unused bits of the last byte have to be zero for
+//! unsigned integers and copies of the sign bit for signed ones. Every reader
+//! in this module enforces both rules.
+//!
+//! The format still allows redundant encodings, such as `0x80 0x00` for zero.
+//! [`Leb128Mode::Strict`] rejects those, so that each value has exactly one
+//! encoding as some certification profiles require. The free functions use
+//! [`Leb128Mode::DEFAULT`], which is strict with the `strict-leb128` feature.
+//!
+//! All readers take the position to read at and return the value together
+//! with the number of bytes it occupies.
+
+use crate::prelude::{
+    codes,
+    Error,
+    ErrorCategory,
+    Result,
+};
+
+/// How strictly LEB128 encodings are checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Leb128Mode {
+    /// Accept every encoding the WebAssembly specification allows
+    Spec,
+    /// Additionally reject encodings with redundant trailing bytes
+    Strict,
+}
+
+impl Leb128Mode {
+    /// Mode of the free functions of this module
+    #[cfg(not(feature = "strict-leb128"))]
+    pub const DEFAULT: Self = Self::Spec;
+    /// Mode of the free functions of this module
+    #[cfg(feature = "strict-leb128")]
+    pub const DEFAULT: Self = Self::Strict;
+
+    /// Read an unsigned 32-bit integer
+    pub fn read_u32(self, bytes: &[u8], pos: usize) -> Result<(u32, usize)> {
+        let (value, len) = read_unsigned(bytes, pos, 32, self)?;
+        // The length check keeps the value within 32 bits
+        Ok((value as u32, len))
+    }
+
+    /// Read an unsigned 64-bit integer
+    pub fn read_u64(self, bytes: &[u8], pos: usize) -> Result<(u64, usize)> {
+        read_unsigned(bytes, pos, 64, self)
+    }
+
+    /// Read a signed 32-bit integer
+    pub fn read_i32(self, bytes: &[u8], pos: usize) -> Result<(i32, usize)> {
+        let (value, len) = read_signed(bytes, pos, 32, self)?;
+        // The length check keeps the value within 32 bits
+        Ok((value as i32, len))
+    }
+
+    /// Read a signed 33-bit integer, as used by block types
+    pub fn read_i33(self, bytes: &[u8], pos: usize) -> Result<(i64, usize)> {
+        read_signed(bytes, pos, 33, self)
+    }
+
+    /// Read a signed 64-bit integer
+    pub fn read_i64(self, bytes: &[u8], pos: usize) -> Result<(i64, usize)> {
+        read_signed(bytes, pos, 64, self)
+    }
+}
+
+impl Default for Leb128Mode {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Read an unsigned 32-bit integer in the default mode
+pub fn read_u32(bytes: &[u8], pos: usize) -> Result<(u32, usize)> {
+    Leb128Mode::DEFAULT.read_u32(bytes, pos)
+}
+
+/// Read an unsigned 64-bit integer in the default mode
+pub fn read_u64(bytes: &[u8], pos: usize) -> Result<(u64, usize)> {
+    Leb128Mode::DEFAULT.read_u64(bytes, pos)
+}
+
+/// Read a signed 32-bit integer in the default mode
+pub fn read_i32(bytes: &[u8], pos: usize) -> Result<(i32, usize)> {
+    Leb128Mode::DEFAULT.read_i32(bytes, pos)
+}
+
+/// Read a signed 33-bit integer in the default mode
+pub fn read_i33(bytes: &[u8], pos: usize) -> Result<(i64, usize)> {
+    Leb128Mode::DEFAULT.read_i33(bytes, pos)
+}
+
+/// Read a signed 64-bit integer in the default mode
+pub fn read_i64(bytes: &[u8], pos: usize) -> Result<(i64, usize)> {
+    Leb128Mode::DEFAULT.read_i64(bytes, pos)
+}
+
+fn leb128_error(message: &'static str) -> Error {
+    Error::new(
+        ErrorCategory::Parse,
+        codes::PARSE_INVALID_LEB128_ENCODING,
+        message,
+    )
+}
+
+/// Byte `index` of the encoding starting at `pos`
+fn byte_at(bytes: &[u8], pos: usize, index: usize) -> Result<u8> {
+    pos.checked_add(index)
+        .and_then(|offset| bytes.get(offset))
+        .copied()
+        .ok_or_else(|| leb128_error("Truncated LEB128 integer"))
+}
+
+fn read_unsigned(bytes: &[u8], pos: usize, bits: u32, mode: Leb128Mode) -> Result<(u64, usize)> {
+    let max_len = bits.div_ceil(7) as usize;
+    let mut result = 0u64;
+    let mut shift = 0u32;
+    let mut len = 0;
+
+    loop {
+        let byte = byte_at(bytes, pos, len)?;
+        len += 1;
+        let payload = u64::from(byte & 0x7F);
+
+        // The last byte allowed may neither continue nor carry bits beyond
+        // the width of the integer
+        if len == max_len && (byte & 0x80 != 0 || payload >> (bits - shift) != 0) {
+            return Err(leb128_error("LEB128 integer too large"));
+        }
+
+        result |= payload << shift;
+        if byte & 0x80 == 0 {
+            if mode == Leb128Mode::Strict && len > 1 && byte == 0 {
+                return Err(leb128_error("Redundant LEB128 encoding"));
+            }
+            return Ok((result, len));
+        }
+        shift += 7;
+    }
+}
+
+fn read_signed(bytes: &[u8], pos: usize, bits: u32, mode: Leb128Mode) -> Result<(i64, usize)> {
+    let max_len = bits.div_ceil(7) as usize;
+    let mut result = 0i64;
+    let mut shift = 0u32;
+    let mut len = 0;
+
+    loop {
+        let byte = byte_at(bytes, pos, len)?;
+        len += 1;
+
+        if len == max_len {
+            // The sign bit and the unused bits above it must all be equal
+            let sign_and_unused = (byte & 0x7F) >> (bits - shift - 1);
+            let all_set = 0x7F >> (bits - shift - 1);
+            if byte & 0x80 != 0 || (sign_and_unused != 0 && sign_and_unused != all_set) {
+                return Err(leb128_error("LEB128 integer too large"));
+            }
+        }
+
+        result |= i64::from(byte & 0x7F) << shift;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            if mode == Leb128Mode::Strict && len > 1 {
+                // A final byte that only repeats the sign of the previous one
+                // is redundant
+                let previous_sign = byte_at(bytes, pos, len - 2)? & 0x40 != 0;
+                if (byte == 0x00 && !previous_sign) || (byte == 0x7F && previous_sign) {
+                    return Err(leb128_error("Redundant LEB128 encoding"));
+                }
+            }
+            if shift < 64 && byte & 0x40 != 0 {
+                result |= !0 << shift;
+            }
+            return Ok((result, len));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_unsigned() {
+        assert_eq!(read_u32(&[0x00], 0).unwrap(), (0, 1));
+        assert_eq!(read_u32(&[0xE5, 0x8E, 0x26], 0).unwrap(), (624_485, 3));
+        assert_eq!(
+            read_u32(&[0xFF, 0xFF, 0xFF, 0xFF, 0x0F], 0).unwrap(),
+            (u32::MAX, 5)
+        );
+        assert_eq!(read_u32(&[0xAA, 0x01], 1).unwrap(), (1, 1));
+        assert_eq!(
+            read_u64(
+                &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01],
+                0
+            )
+            .unwrap(),
+            (u64::MAX, 10)
+        );
+
+        // Bits beyond 32 in the fifth byte
+        assert!(read_u32(&[0xFF, 0xFF, 0xFF, 0xFF, 0x1F], 0).is_err());
+        // More bytes than a 32-bit integer needs
+        assert!(read_u32(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x00], 0).is_err());
+        assert!(read_u64(
+            &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x02],
+            0
+        )
+        .is_err());
+        assert!(read_u32(&[0x80, 0x80], 0).is_err());
+        assert!(read_u32(&[], 0).is_err());
+    }
+
+    #[test]
+    fn test_read_signed() {
+        assert_eq!(read_i32(&[0x7F], 0).unwrap(), (-1, 1));
+        assert_eq!(read_i32(&[0xC0, 0xBB, 0x78], 0).unwrap(), (-123_456, 3));
+        assert_eq!(
+            read_i32(&[0x80, 0x80, 0x80, 0x80, 0x78], 0).unwrap(),
+            (i32::MIN, 5)
+        );
+        assert_eq!(
+            read_i32(&[0xFF, 0xFF, 0xFF, 0xFF, 0x07], 0).unwrap(),
+            (i32::MAX, 5)
+        );
+        assert_eq!(read_i33(&[0x40], 0).unwrap(), (-64, 1));
+        assert_eq!(
+            read_i33(&[0xFF, 0xFF, 0xFF, 0xFF, 0x0F], 0).unwrap(),
+            (0xFFFF_FFFF, 5)
+        );
+        assert_eq!(
+            read_i64(
+                &[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x7F],
+                0
+            )
+            .unwrap(),
+            (i64::MIN, 10)
+        );
+
+        // Unused bits of the fifth byte that do not match the sign
+        assert!(read_i32(&[0xFF, 0xFF, 0xFF, 0xFF, 0x0F], 0).is_err());
+        assert!(read_i32(&[0x80, 0x80, 0x80, 0x80, 0x70], 0).is_err());
+        assert!(read_i33(&[0xFF, 0xFF, 0xFF, 0xFF, 0x1F], 0).is_err());
+        assert!(read_i32(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x00], 0).is_err());
+        assert!(read_i64(&[0xFF; 11], 0).is_err());
+    }
+
+    #[test]
+    fn test_strict_mode() {
+        let spec = Leb128Mode::Spec;
+        let strict = Leb128Mode::Strict;
+
+        assert_eq!(spec.read_u32(&[0x80, 0x00], 0).unwrap(), (0, 2));
+        assert!(strict.read_u32(&[0x80, 0x00], 0).is_err());
+        assert!(strict.read_u64(&[0x81, 0x80, 0x00], 0).is_err());
+        assert_eq!(strict.read_u32(&[0x80, 0x01], 0).unwrap(), (128, 2));
+
+        assert_eq!(spec.read_i32(&[0xFF, 0x7F], 0).unwrap(), (-1, 2));
+        assert!(strict.read_i32(&[0xFF, 0x7F], 0).is_err());
+        assert!(strict.read_i64(&[0x80, 0x00], 0).is_err());
+        // The final byte carries the sign the previous one lacks
+        assert_eq!(strict.read_i32(&[0xC0, 0x00], 0).unwrap(), (64, 2));
+        assert_eq!(strict.read_i32(&[0x80, 0x7F], 0).unwrap(), (-128, 2));
+        assert_eq!(
+            strict.read_i33(&[0xFF, 0xFF, 0xFF, 0xFF, 0x0F], 0).unwrap(),
+            (0xFFFF_FFFF, 5)
+        );
+    }
+}
@@ -197,6 +197,12 @@ macro_rules! safe_managed_alloc {
 pub mod conversion;
 /// Float representation utilities
 pub mod float_repr;
+/// Collections bounded by a capacity chosen at runtime
+pub mod hybrid;
+/// Interned names with cheap comparable handles
+pub mod interned;
+/// LEB128 decoding with canonical-encoding enforcement
+pub mod leb128;
 /// Operation tracking and fuel metering
 pub mod operations;
 /// Resource management
@@ -251,6 +257,8 @@ macro_rules! safe_managed_alloc {
 pub mod memory_sizing;
 /// Memory system monitoring and telemetry
 pub mod monitoring;
+/// Statically sized providers and build-time RAM budgets
+pub mod static_memory;
 
 // Clean Architecture - Provider-Free Types
 pub mod clean_core_types;
@@ -278,6 +286,8 @@ macro_rules! safe_managed_alloc {
 /// Platform Abstraction Interface (PAI) for cross-platform safety-critical
 /// runtime
 pub mod platform_abstraction;
+/// Clock and entropy hooks installed by bare-metal integrators
+pub mod platform_hooks;
 /// ASIL-aware safety primitives for safety-critical applications
 pub mod safety_system;
 /// Unified type system with platform-configurable bounded collections
@@ -296,9 +306,14 @@ macro_rules! safe_managed_alloc {
 pub mod safety_monitor;
 // Production telemetry and logging infrastructure (ASIL-A)
 pub mod telemetry;
+// Memory provider guarding its pages with checksums
+pub mod ecc_provider;
 // Heap-based memory provider to avoid stack overflow
 #[cfg(any(feature = "std", feature = "alloc"))]
 pub mod heap_provider;
+// Host objects referenced by externref values
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub mod host_ref;
 
 // Binary std/no_std choice
 #[cfg(feature = "std")]
@@ -421,6 +436,7 @@ macro_rules! safe_managed_alloc {
     SafeMemoryHandler,
     Slice as SafeSlice,
     SliceMut as SafeSliceMut,
+    SliceView as SafeSliceView,
     Stats as MemoryStats,
 };
 
@@ -532,6 +548,10 @@ macro_rules! safe_managed_alloc {
     PlatformServices,
     TimeProvider,
 };
+pub use platform_hooks::{
+    PlatformClock,
+    PlatformEntropy,
+};
 // Re-export safety system types
 pub use safety_system::{
     AgricultureLevel,
@@ -554,6 +574,10 @@ macro_rules! safe_managed_alloc {
     SilLevel,
     UniversalSafetyContext,
 };
+pub use static_memory::{
+    BudgetReport,
+    StaticMemoryProvider,
+};
 pub use traits::{
     BoundedCapacity,
     Checksummed,
@@ -587,6 +611,7 @@ macro_rules! safe_managed_alloc {
     MemoryType,
     RefType,
     TableType,
+    TypeHasher,
     ValueType,
 };
 // Re-export unified types for backward compatibility and new functionality
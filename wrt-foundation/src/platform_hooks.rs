@@ -0,0 +1,202 @@
+//! Clock and entropy hooks for bare-metal integrators
+//!
+//! Runtime crates that need the time or random bytes ask the hooks installed
+//! here before falling back to what the target provides on its own, so a
+//! bare-metal build reports real timings and real randomness instead of
+//! placeholder values. The integrator implements [`PlatformClock`] for its
+//! timer and [`PlatformEntropy`] for its random number generator and
+//! installs both once at startup:
+//!
+//! ```no_run
+//! use wrt_foundation::platform_hooks::{
+//!     install_clock,
+//!     install_entropy,
+//!     PlatformEntropy,
+//!     TickClock,
+//! };
+//!
+//! /// Cortex-M DWT cycle counter, extended to 64 bits by the application
+//! fn read_cycles() -> u64 {
+//!     // SAFETY: DWT_CYCCNT is a read-only counter register
+//!     u64::from(unsafe { core::ptr::read_volatile(0xE000_1004 as *const u32) })
+//! }
+//!
+//! struct Trng;
+//!
+//! impl PlatformEntropy for Trng {
+//!     fn fill_bytes(&self, buffer: &mut [u8]) -> wrt_error::Result<()> {
+//!         for chunk in buffer.chunks_mut(4) {
+//!             // SAFETY: RNG_DR of the STM32 TRNG peripheral
+//!             let word = unsafe { core::ptr::read_volatile(0x5006_0808 as *const u32) };
+//!             chunk.copy_from_slice(&word.to_le_bytes()[..chunk.len()]);
+//!         }
+//!         Ok(())
+//!     }
+//! }
+//!
+//! static CLOCK: TickClock = TickClock::new(read_cycles, 80_000_000);
+//! static TRNG: Trng = Trng;
+//!
+//! install_clock(&CLOCK)?;
+//! install_entropy(&TRNG)?;
+//! # Ok::<(), wrt_error::Error>(())
+//! ```
+//!
+//! Each hook can be installed once per process; installing the same hook
+//! again succeeds, installing a different one fails.
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_sync::WrtOnce;
+
+const NANOS_PER_SECOND: u128 = 1_000_000_000;
+
+/// Time source of the platform
+pub trait PlatformClock: Send + Sync {
+    /// Nanoseconds since an arbitrary origin, never decreasing
+    fn monotonic_ns(&self) -> u64;
+
+    /// Nanoseconds since the Unix epoch, or `None` without a real-time clock
+    fn wall_clock_ns(&self) -> Option<u64> {
+        None
+    }
+
+    /// Resolution of [`monotonic_ns`](Self::monotonic_ns) in nanoseconds
+    fn resolution_ns(&self) -> u64 {
+        1
+    }
+}
+
+/// Source of cryptographically secure random bytes
+pub trait PlatformEntropy: Send + Sync {
+    /// Fill `buffer` with random bytes
+    fn fill_bytes(&self, buffer: &mut [u8]) -> Result<()>;
+}
+
+/// Monotonic clock over a free-running counter, such as a cycle counter or
+/// the tick counter of an RTOS
+///
+/// Counters narrower than 64 bits should be extended by the read function.
+#[derive(Debug, Clone, Copy)]
+pub struct TickClock {
+    read:         fn() -> u64,
+    frequency_hz: u64,
+}
+
+impl TickClock {
+    /// Create a clock reading `read`, which advances `frequency_hz` times
+    /// per second
+    pub const fn new(read: fn() -> u64, frequency_hz: u64) -> Self {
+        Self { read, frequency_hz }
+    }
+}
+
+impl PlatformClock for TickClock {
+    fn monotonic_ns(&self) -> u64 {
+        let nanos =
+            u128::from((self.read)()) * NANOS_PER_SECOND / u128::from(self.frequency_hz.max(1));
+        u64::try_from(nanos).unwrap_or(u64::MAX)
+    }
+
+    fn resolution_ns(&self) -> u64 {
+        u64::try_from(NANOS_PER_SECOND / u128::from(self.frequency_hz.max(1)))
+            .unwrap_or(u64::MAX)
+            .max(1)
+    }
+}
+
+static CLOCK: WrtOnce<&'static dyn PlatformClock> = WrtOnce::new();
+static ENTROPY: WrtOnce<&'static dyn PlatformEntropy> = WrtOnce::new();
+
+/// Whether two hooks are the same object
+fn same_hook<T: ?Sized>(a: &T, b: &T) -> bool {
+    core::ptr::eq((a as *const T).cast::<u8>(), (b as *const T).cast::<u8>())
+}
+
+/// Install the platform clock
+pub fn install_clock(clock: &'static dyn PlatformClock) -> Result<()> {
+    if same_hook(*CLOCK.get_or_init(|| clock), clock) {
+        Ok(())
+    } else {
+        Err(Error::runtime_invalid_state(
+            "A different platform clock is already installed",
+        ))
+    }
+}
+
+/// Install the platform entropy source
+pub fn install_entropy(entropy: &'static dyn PlatformEntropy) -> Result<()> {
+    if same_hook(*ENTROPY.get_or_init(|| entropy), entropy) {
+        Ok(())
+    } else {
+        Err(Error::runtime_invalid_state(
+            "A different platform entropy source is already installed",
+        ))
+    }
+}
+
+/// The installed platform clock, if any
+pub fn clock() -> Option<&'static dyn PlatformClock> {
+    CLOCK.get().copied()
+}
+
+/// The installed platform entropy source, if any
+pub fn entropy() -> Option<&'static dyn PlatformEntropy> {
+    ENTROPY.get().copied()
+}
+
+/// Monotonic time of the installed clock in nanoseconds, if one is installed
+pub fn monotonic_ns() -> Option<u64> {
+    clock().map(PlatformClock::monotonic_ns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed_ticks() -> u64 {
+        32_768
+    }
+
+    struct Pattern;
+
+    impl PlatformEntropy for Pattern {
+        fn fill_bytes(&self, buffer: &mut [u8]) -> Result<()> {
+            for (i, byte) in buffer.iter_mut().enumerate() {
+                *byte = i as u8;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_tick_clock() {
+        let clock = TickClock::new(fixed_ticks, 32_768);
+        assert_eq!(clock.monotonic_ns(), 1_000_000_000);
+        assert_eq!(clock.resolution_ns(), 30_517);
+        assert_eq!(clock.wall_clock_ns(), None);
+        assert_eq!(
+            TickClock::new(fixed_ticks, 0).resolution_ns(),
+            1_000_000_000
+        );
+    }
+
+    #[test]
+    fn test_install_once() {
+        static CLOCK: TickClock = TickClock::new(fixed_ticks, 1_000);
+        static OTHER: TickClock = TickClock::new(fixed_ticks, 1_000);
+        static ENTROPY: Pattern = Pattern;
+
+        install_clock(&CLOCK).unwrap();
+        install_clock(&CLOCK).unwrap();
+        assert!(install_clock(&OTHER).is_err());
+        assert_eq!(monotonic_ns(), Some(32_768_000_000));
+
+        install_entropy(&ENTROPY).unwrap();
+        let mut buffer = [0xFF; 4];
+        entropy().unwrap().fill_bytes(&mut buffer).unwrap();
+        assert_eq!(buffer, [0, 1, 2, 3]);
+    }
+}
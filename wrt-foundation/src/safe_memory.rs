@@ -282,6 +282,212 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     }
 }
 
+/// A checked view of a range of borrowed input, such as the bytes of a
+/// module being decoded
+///
+/// Unlike a [`Slice`], a view remembers where it lies in its source, and
+/// narrowing it with [`SliceView::view`] only checksums the narrower range.
+/// Large payloads like data segments, custom sections and function bodies can
+/// so be handed out without copying them, and the borrow keeps the source
+/// alive for as long as any view of it is used.
+#[derive(Clone, Copy)]
+pub struct SliceView<'a> {
+    /// The whole input the view is part of
+    source:             &'a [u8],
+    /// Start of the view in the source
+    start:              usize,
+    /// Length of the view for redundant verification
+    length:             usize,
+    /// Checksum of the viewed bytes, unless verification is off
+    checksum:           Checksum,
+    /// Verification level for this view
+    verification_level: VerificationLevel,
+}
+
+impl<'a> SliceView<'a> {
+    /// Create a view of all of `source`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the initial integrity verification fails.
+    pub fn new(source: &'a [u8]) -> Result<Self> {
+        Self::with_verification_level(source, VerificationLevel::default())
+    }
+
+    /// Create a view of all of `source` with a specific verification level
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the initial integrity verification fails.
+    pub fn with_verification_level(source: &'a [u8], level: VerificationLevel) -> Result<Self> {
+        Self::of_range(source, 0, source.len(), level)
+    }
+
+    fn of_range(
+        source: &'a [u8],
+        start: usize,
+        length: usize,
+        level: VerificationLevel,
+    ) -> Result<Self> {
+        let bytes = start
+            .checked_add(length)
+            .and_then(|end| source.get(start..end))
+            .ok_or_else(|| Error::memory_error("Invalid view range"))?;
+        let checksum = if level == VerificationLevel::Off {
+            Checksum::new()
+        } else {
+            record_global_operation(OperationType::ChecksumCalculation, level);
+            Checksum::compute(bytes)
+        };
+        Ok(Self {
+            source,
+            start,
+            length,
+            checksum,
+            verification_level: level,
+        })
+    }
+
+    /// Get the viewed bytes, borrowed from the source
+    ///
+    /// This performs an integrity check before returning the data.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the integrity check fails.
+    pub fn data(&self) -> Result<&'a [u8]> {
+        record_global_operation(OperationType::MemoryRead, self.verification_level);
+        self.verify_integrity_with_importance(128)?;
+        self.bytes()
+    }
+
+    fn bytes(&self) -> Result<&'a [u8]> {
+        self.source
+            .get(self.start..self.start + self.length)
+            .ok_or_else(|| Error::validation_error("Memory corruption: view out of its source"))
+    }
+
+    /// Offset of the view in its source
+    #[must_use]
+    pub fn offset(&self) -> usize {
+        self.start
+    }
+
+    /// Get length of the view
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Check if the view is empty
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Get the current verification level
+    #[must_use]
+    pub fn verification_level(&self) -> VerificationLevel {
+        self.verification_level
+    }
+
+    /// Verify data integrity using the stored checksum
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the integrity check fails based on the current
+    /// verification level and default importance (128).
+    pub fn verify_integrity(&self) -> Result<()> {
+        record_global_operation(OperationType::CollectionValidate, self.verification_level);
+        self.verify_integrity_with_importance(128)
+    }
+
+    /// Verify data integrity with specified operation importance
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the integrity check fails based on the current
+    /// verification level and the provided importance.
+    pub fn verify_integrity_with_importance(&self, importance: u8) -> Result<()> {
+        if !self.verification_level.should_verify(importance) {
+            return Ok(());
+        }
+        let bytes = self.bytes()?;
+
+        #[cfg(not(feature = "optimize"))]
+        if importance >= 200 || self.verification_level.should_verify_redundant() {
+            record_global_operation(OperationType::ChecksumCalculation, self.verification_level);
+            if Checksum::compute(bytes) != self.checksum {
+                return Err(Error::validation_error(
+                    "Memory corruption: checksum mismatch on read",
+                ));
+            }
+        }
+        #[cfg(feature = "optimize")]
+        let _ = bytes;
+
+        Ok(())
+    }
+
+    /// Create a view of `len` bytes from `start` on within this view
+    ///
+    /// Only the narrower range is checksummed; the bytes of this view are not
+    /// verified again.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the range does not lie within this view.
+    pub fn view(&self, start: usize, len: usize) -> Result<SliceView<'a>> {
+        record_global_operation(OperationType::MemoryRead, self.verification_level);
+        let Some(end) = start.checked_add(len) else {
+            return Err(Error::memory_error("Sub-view range calculation overflow"));
+        };
+        if end > self.length {
+            return Err(Error::memory_error("Invalid sub-view range"));
+        }
+        Self::of_range(
+            self.source,
+            self.start + start,
+            len,
+            self.verification_level,
+        )
+    }
+
+    /// Create a view of the bytes from `start` on within this view
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `start` lies beyond the end of this view.
+    pub fn view_from(&self, start: usize) -> Result<SliceView<'a>> {
+        let len = self
+            .length
+            .checked_sub(start)
+            .ok_or_else(|| Error::memory_error("Invalid sub-view range"))?;
+        self.view(start, len)
+    }
+
+    /// Convert into a [`Slice`] of the viewed bytes
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the integrity check of the view or of the new
+    /// slice fails.
+    pub fn to_slice(&self) -> Result<Slice<'a>> {
+        Slice::with_verification_level(self.data()?, self.verification_level)
+    }
+}
+
+impl fmt::Debug for SliceView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SliceView")
+            .field("offset", &self.start)
+            .field("length", &self.length)
+            .field("checksum", &self.checksum)
+            .field("verification_level", &self.verification_level)
+            .finish()
+    }
+}
+
 /// A safe mutable slice with integrated checksum for data integrity
 /// verification
 pub struct SliceMut<'a> {
@@ -1312,6 +1518,19 @@ fn new_internal(level: VerificationLevel) -> Self {
         }
     }
 
+    /// Internal constructor for a provider whose whole capacity is usable,
+    /// as handed out by the budget-aware allocation macros
+    pub(crate) const fn new_full_internal(level: VerificationLevel) -> Self {
+        Self {
+            data:               [0; N],
+            used:               N,
+            access_count:       AtomicUsize::new(0),
+            last_access_offset: AtomicUsize::new(0),
+            last_access_length: AtomicUsize::new(0),
+            verification_level: level,
+        }
+    }
+
     /// Create a new memory provider with specified size and verification level
     ///
     /// # Deprecated
@@ -1464,10 +1683,16 @@ fn borrow_slice(&self, offset: usize, len: usize) -> Result<Slice<'_>> {
     }
 
     fn write_data(&mut self, offset: usize, data_to_write: &[u8]) -> Result<()> {
-        self.verify_access(offset, data_to_write.len())?;
-        if offset + data_to_write.len() > N {
+        // A write initializes what it covers, so unlike reads it may extend
+        // past the used region, up to the capacity
+        if offset.checked_add(data_to_write.len()).map_or(true, |end| end > N) {
             return Err(Error::memory_out_of_bounds("Write data overflows capacity"));
         }
+        if self.verification_level.should_track_stats() {
+            self.access_count.fetch_add(1, Ordering::Relaxed);
+            self.last_access_offset.store(offset, Ordering::Relaxed);
+            self.last_access_length.store(data_to_write.len(), Ordering::Relaxed);
+        }
         self.data[offset..offset + data_to_write.len()].copy_from_slice(data_to_write);
         self.used = core::cmp::max(self.used, offset + data_to_write.len());
         // TODO: Consider if checksum/integrity of Slice/SliceMut needs update here if
@@ -1947,6 +2172,18 @@ pub fn verify_integrity(&self) -> Result<()> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_slice_view_narrows_without_copying() {
+        let source = [0u8, 1, 2, 3, 4, 5, 6, 7];
+        let view = SliceView::new(&source).unwrap();
+        let inner = view.view(2, 4).unwrap().view_from(1).unwrap();
+        assert_eq!(inner.offset(), 3);
+        assert_eq!(inner.data().unwrap(), &[3, 4, 5]);
+        assert!(core::ptr::eq(inner.data().unwrap(), &source[3..6]));
+        assert!(view.view(6, 3).is_err());
+        assert!(inner.view_from(4).is_err());
+    }
+
     #[test]
     fn test_safe_memory_handler_copy_within() {
         // Create a NoStdProvider with capacity 50
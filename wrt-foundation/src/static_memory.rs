@@ -0,0 +1,278 @@
+//! Statically sized memory and build-time RAM budgets
+//!
+//! On microcontrollers without a heap the RAM used by the runtime has to be
+//! known before the firmware runs. [`StaticMemoryProvider`] is a memory
+//! provider whose storage size is part of its type, and [`BudgetReport`]
+//! adds up the worst-case sizes of such storage in a `const` context, so
+//! an integrator can let the build fail when the runtime would not fit:
+//!
+//! ```
+//! use wrt_foundation::static_memory::{
+//!     BudgetReport,
+//!     StaticMemoryProvider,
+//! };
+//!
+//! const RAM_FOR_WASM: usize = 96 * 1024;
+//! const BUDGET: BudgetReport = BudgetReport::new()
+//!     .with::<StaticMemoryProvider<32_768>>("operand pool")
+//!     .with_each::<StaticMemoryProvider<4_096>>("call frames", 8);
+//! const _: () = assert!(BUDGET.total_bytes() <= RAM_FOR_WASM);
+//! ```
+//!
+//! Memory held in a `StaticMemoryProvider` is accounted for by the report
+//! rather than by the runtime crate budgets.
+
+use core::{
+    fmt,
+    mem::size_of,
+};
+
+use crate::{
+    prelude::*,
+    safe_memory::{
+        NoStdProvider,
+        Provider,
+        SafeMemoryHandler,
+        Slice,
+        SliceMut,
+        Stats,
+    },
+    verification::VerificationLevel,
+};
+
+/// Number of entries a [`BudgetReport`] can hold
+pub const MAX_BUDGET_ENTRIES: usize = 24;
+
+/// Memory provider with `N` bytes of storage fixed at compile time
+///
+/// The storage is held inline, so a provider placed in a `static` ends up in
+/// `.bss` and shows up in the linker map. `N` must not be zero.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaticMemoryProvider<const N: usize> {
+    inner: NoStdProvider<N>,
+}
+
+impl<const N: usize> StaticMemoryProvider<N> {
+    /// Worst-case RAM used by a provider of this size
+    pub const FOOTPRINT: usize = size_of::<Self>();
+    const NON_EMPTY: () = assert!(N > 0, "StaticMemoryProvider needs at least one byte");
+
+    /// Create a provider with all `N` bytes zeroed and usable
+    pub const fn new(level: VerificationLevel) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::NON_EMPTY;
+        Self {
+            inner: NoStdProvider::new_full_internal(level),
+        }
+    }
+}
+
+impl<const N: usize> Default for StaticMemoryProvider<N> {
+    fn default() -> Self {
+        Self::new(VerificationLevel::Standard)
+    }
+}
+
+impl<const N: usize> Provider for StaticMemoryProvider<N> {
+    type Allocator = NoStdProvider<N>;
+
+    fn borrow_slice(&self, offset: usize, len: usize) -> Result<Slice<'_>> {
+        self.inner.borrow_slice(offset, len)
+    }
+
+    fn write_data(&mut self, offset: usize, data: &[u8]) -> Result<()> {
+        self.inner.write_data(offset, data)
+    }
+
+    fn verify_access(&self, offset: usize, len: usize) -> Result<()> {
+        self.inner.verify_access(offset, len)
+    }
+
+    fn size(&self) -> usize {
+        Provider::size(&self.inner)
+    }
+
+    fn capacity(&self) -> usize {
+        N
+    }
+
+    fn verify_integrity(&self) -> Result<()> {
+        Provider::verify_integrity(&self.inner)
+    }
+
+    fn set_verification_level(&mut self, level: VerificationLevel) {
+        self.inner.set_verification_level(level);
+    }
+
+    fn verification_level(&self) -> VerificationLevel {
+        self.inner.verification_level()
+    }
+
+    fn memory_stats(&self) -> Stats {
+        Provider::memory_stats(&self.inner)
+    }
+
+    fn get_slice_mut(&mut self, offset: usize, len: usize) -> Result<SliceMut<'_>> {
+        self.inner.get_slice_mut(offset, len)
+    }
+
+    fn copy_within(&mut self, src_offset: usize, dst_offset: usize, len: usize) -> Result<()> {
+        self.inner.copy_within(src_offset, dst_offset, len)
+    }
+
+    fn ensure_used_up_to(&mut self, byte_offset: usize) -> Result<()> {
+        self.inner.ensure_used_up_to(byte_offset)
+    }
+
+    fn acquire_memory(&self, layout: core::alloc::Layout) -> Result<*mut u8> {
+        self.inner.acquire_memory(layout)
+    }
+
+    fn release_memory(&self, ptr: *mut u8, layout: core::alloc::Layout) -> Result<()> {
+        self.inner.release_memory(ptr, layout)
+    }
+
+    fn get_allocator(&self) -> &Self::Allocator {
+        &self.inner
+    }
+
+    fn new_handler(&self) -> Result<SafeMemoryHandler<Self>>
+    where
+        Self: Sized + Clone,
+    {
+        Ok(SafeMemoryHandler::new(self.clone()))
+    }
+}
+
+/// One line of a [`BudgetReport`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BudgetEntry {
+    /// What the memory is used for
+    pub name:  &'static str,
+    /// Worst-case size in bytes
+    pub bytes: usize,
+}
+
+/// Worst-case RAM consumption, itemized and summed at compile time
+///
+/// Built with `const` methods so the total can be checked by a `const`
+/// assertion. Adding more than [`MAX_BUDGET_ENTRIES`] entries fails the
+/// build when done in a `const` context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BudgetReport {
+    entries: [BudgetEntry; MAX_BUDGET_ENTRIES],
+    len:     usize,
+}
+
+impl BudgetReport {
+    /// Create an empty report
+    pub const fn new() -> Self {
+        Self {
+            entries: [BudgetEntry {
+                name:  "",
+                bytes: 0,
+            }; MAX_BUDGET_ENTRIES],
+            len:     0,
+        }
+    }
+
+    /// Add `bytes` used for `name`
+    pub const fn with_bytes(mut self, name: &'static str, bytes: usize) -> Self {
+        assert!(self.len < MAX_BUDGET_ENTRIES, "BudgetReport is full");
+        self.entries[self.len] = BudgetEntry { name, bytes };
+        self.len += 1;
+        self
+    }
+
+    /// Add one value of type `T` used for `name`
+    pub const fn with<T>(self, name: &'static str) -> Self {
+        self.with_bytes(name, size_of::<T>())
+    }
+
+    /// Add `count` values of type `T` used for `name`
+    pub const fn with_each<T>(self, name: &'static str, count: usize) -> Self {
+        self.with_bytes(name, size_of::<T>().saturating_mul(count))
+    }
+
+    /// Add every entry of `other`
+    pub const fn with_report(mut self, other: &BudgetReport) -> Self {
+        let mut i = 0;
+        while i < other.len {
+            self = self.with_bytes(other.entries[i].name, other.entries[i].bytes);
+            i += 1;
+        }
+        self
+    }
+
+    /// Sum of all entries in bytes
+    pub const fn total_bytes(&self) -> usize {
+        let mut total = 0usize;
+        let mut i = 0;
+        while i < self.len {
+            total = total.saturating_add(self.entries[i].bytes);
+            i += 1;
+        }
+        total
+    }
+
+    /// The entries in the order they were added
+    pub fn entries(&self) -> &[BudgetEntry] {
+        &self.entries[..self.len]
+    }
+}
+
+impl Default for BudgetReport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for BudgetReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in self.entries() {
+            writeln!(f, "{:<32} {:>10} B", entry.name, entry.bytes)?;
+        }
+        write!(f, "{:<32} {:>10} B", "total", self.total_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bounded::BoundedVec;
+
+    #[test]
+    fn test_static_provider_backs_collections() {
+        let mut vec: BoundedVec<u32, 16, StaticMemoryProvider<256>> =
+            BoundedVec::new(StaticMemoryProvider::default()).unwrap();
+        for i in 0..16 {
+            vec.push(i).unwrap();
+        }
+        assert!(vec.push(16).is_err());
+        assert_eq!(vec.pop().unwrap(), Some(15));
+        assert!(StaticMemoryProvider::<256>::FOOTPRINT >= 256);
+    }
+
+    #[test]
+    fn test_budget_report() {
+        const POOLS: BudgetReport = BudgetReport::new()
+            .with::<StaticMemoryProvider<1024>>("pool")
+            .with_each::<u64>("slots", 4);
+        const REPORT: BudgetReport =
+            BudgetReport::new().with_bytes("stack", 512).with_report(&POOLS);
+        const _: () = assert!(REPORT.total_bytes() >= 512 + 1024 + 32);
+
+        assert_eq!(REPORT.entries().len(), 3);
+        assert_eq!(
+            REPORT.entries()[2],
+            BudgetEntry {
+                name:  "slots",
+                bytes: 32,
+            }
+        );
+        assert_eq!(
+            REPORT.total_bytes(),
+            512 + StaticMemoryProvider::<1024>::FOOTPRINT + 32
+        );
+    }
+}
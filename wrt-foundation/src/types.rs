@@ -150,34 +150,89 @@
 /// Index for an exception tag.
 pub type TagIdx = u32;
 
-/// Internal hasher for `FuncType`, may be removed or replaced.
-#[derive(Default)] // Simplified Debug for Hasher
-struct Hasher {
-    hash: u32,
+/// FNV-1a 64-bit offset basis
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+/// FNV-1a 64-bit prime
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Stable hasher for structural type hashes.
+///
+/// The hash depends only on the hashed data: integers are hashed as
+/// fixed-width little-endian bytes and no per-process seed is used, so a hash
+/// computed when an artifact is built stays valid wherever it is loaded.
+/// Equal hashes do not imply equal types; linkers compare hashes first and
+/// compare the types structurally only when the hashes are equal.
+#[derive(Debug, Clone, Copy)]
+pub struct TypeHasher {
+    hash: u64,
 }
 
-impl core::fmt::Debug for Hasher {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("Hasher").field("hash", &self.hash).finish()
+impl Default for TypeHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TypeHasher {
+    /// Creates a hasher with the FNV-1a initial state.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            hash: FNV_OFFSET_BASIS,
+        }
     }
 }
 
-#[allow(dead_code)]
-impl Hasher {
-    fn new() -> Self {
-        Self { hash: 0x811c_9dc5 } // FNV-1a offset basis for 32-bit
+impl CoreHasher for TypeHasher {
+    fn finish(&self) -> u64 {
+        self.hash
     }
 
-    fn update(&mut self, bytes: &[u8]) {
+    fn write(&mut self, bytes: &[u8]) {
         for &byte in bytes {
-            self.hash ^= u32::from(byte);
-            self.hash = self.hash.wrapping_mul(0x0100_0193); // FNV prime for
-                                                             // 32-bit
+            self.hash ^= u64::from(byte);
+            self.hash = self.hash.wrapping_mul(FNV_PRIME);
         }
     }
 
-    fn finalize(&self) -> u32 {
-        self.hash
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_i16(&mut self, i: i16) {
+        self.write_u16(i as u16);
+    }
+
+    fn write_i32(&mut self, i: i32) {
+        self.write_u32(i as u32);
+    }
+
+    fn write_i64(&mut self, i: i64) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_i128(&mut self, i: i128) {
+        self.write_u128(i as u128);
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.write_u64(i as i64 as u64);
     }
 }
 
@@ -497,6 +552,25 @@ pub fn new(
         Ok(Self { params, results })
     }
 
+    /// Stable structural hash of the function type.
+    ///
+    /// Hashes the binary encoding of the type, so equal types have equal
+    /// hashes regardless of their memory provider. See [`TypeHasher`].
+    #[must_use]
+    pub fn type_hash(&self) -> u64 {
+        let mut hasher = TypeHasher::new();
+        hasher.write_u8(0x60);
+        hasher.write_u32(self.params.len() as u32);
+        for param in self.params.iter() {
+            hasher.write_u8(param.to_binary());
+        }
+        hasher.write_u32(self.results.len() as u32);
+        for result in self.results.iter() {
+            hasher.write_u8(result.to_binary());
+        }
+        hasher.finish()
+    }
+
     /// Verifies the function type.
     /// Placeholder implementation.
     pub fn verify(&self) -> wrt_error::Result<()> {
@@ -538,9 +612,20 @@ fn update_checksum(&self, checksum: &mut Checksum) {
     }
 }
 
+/// Bytes a function type serializes to: its prefix, then its parameter and
+/// result types with their lengths and checksums, padded up to the capacities
+const FUNC_TYPE_SERIALIZED_SIZE: usize =
+    1 + 2 * (4 + crate::bounded::CHECKSUM_SIZE) + MAX_FUNC_TYPE_PARAMS + MAX_FUNC_TYPE_RESULTS;
+
+// Function types serialize to a fixed size, so that they fit the equally
+// sized slots of bounded collections whatever their arity.
 impl<PFunc: MemoryProvider + Default + Clone + core::fmt::Debug + PartialEq + Eq> ToBytes
     for FuncType<PFunc>
 {
+    fn serialized_size(&self) -> usize {
+        FUNC_TYPE_SERIALIZED_SIZE
+    }
+
     fn to_bytes_with_provider<'a, PStream: crate::MemoryProvider>(
         &self,
         writer: &mut WriteStream<'a>,
@@ -549,6 +634,11 @@ fn to_bytes_with_provider<'a, PStream: crate::MemoryProvider>(
         writer.write_u8(0x60)?; // FuncType prefix
         self.params.to_bytes_with_provider(writer, stream_provider)?;
         self.results.to_bytes_with_provider(writer, stream_provider)?;
+        for _ in
+            self.params.len() + self.results.len()..MAX_FUNC_TYPE_PARAMS + MAX_FUNC_TYPE_RESULTS
+        {
+            writer.write_u8(0)?;
+        }
         Ok(())
     }
 
@@ -585,6 +675,9 @@ fn from_bytes_with_provider<'a, PStream: crate::MemoryProvider>(
                 reader,
                 stream_provider,
             )?;
+        for _ in params.len() + results.len()..MAX_FUNC_TYPE_PARAMS + MAX_FUNC_TYPE_RESULTS {
+            reader.read_u8()?;
+        }
 
         Ok(FuncType { params, results })
     }
@@ -3212,6 +3305,14 @@ fn from_bytes_with_provider<'a, PStream: crate::MemoryProvider>(
 }
 
 /// Represents a WebAssembly Module structure.
+///
+/// Deprecated: the decoder produces `wrt_format::module::Module`, which the
+/// runtime lowers into its own `wrt_runtime::module::Module`, and only the
+/// no_std `wrt_runtime::module::Module::from_wrt_foundation_module` still
+/// takes this type. It is built with the `legacy-module` feature, which is on
+/// by default for this release and goes away with this type in the next.
+#[cfg(feature = "legacy-module")]
+#[deprecated(note = "use `wrt_format::module::Module`, the module produced by the decoder")]
 #[derive(Debug, Clone, PartialEq, Hash)] // Module itself cannot be Eq easily due to provider. P must be Eq for fields.
 pub struct Module<P: MemoryProvider + Default + Clone + core::fmt::Debug + PartialEq + Eq> {
     /// Types section: A list of function types defined in the module.
@@ -3247,6 +3348,8 @@ pub struct Module<P: MemoryProvider + Default + Clone + core::fmt::Debug + Parti
     provider:            P,
 }
 
+#[cfg(feature = "legacy-module")]
+#[allow(deprecated)]
 impl<P: MemoryProvider + Default + Clone + core::fmt::Debug + PartialEq + Eq> Module<P> {
     /// Creates a new, empty `Module` with the given memory provider.
     pub fn new(provider: P) -> Self {
@@ -3278,6 +3381,8 @@ pub fn provider(&self) -> P {
 }
 
 // If P: Default is available, we can provide a Default impl for Module.
+#[cfg(feature = "legacy-module")]
+#[allow(deprecated)]
 impl<P: MemoryProvider + Default + Clone + core::fmt::Debug + PartialEq + Eq> Default
     for Module<P>
 {
@@ -3287,6 +3392,8 @@ fn default() -> Self {
     }
 }
 
+#[cfg(feature = "legacy-module")]
+#[allow(deprecated)]
 impl<P: MemoryProvider + Default + Clone + core::fmt::Debug + PartialEq + Eq> Checksummable
     for Module<P>
 {
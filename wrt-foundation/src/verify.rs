@@ -23,10 +23,13 @@ pub mod kani_verification {
             BoundedError,
             BoundedVec,
         },
+        leb128::Leb128Mode,
         safe_memory::{
             DefaultNoStdProvider,
             SafeMemoryHandler,
         },
+        static_memory::StaticMemoryProvider,
+        traits::BoundedCapacity,
         types::ValueType,
     };
 
@@ -413,6 +416,136 @@ pub fn verify_bounds_checking() {
         let invalid_access = memory_provider.verify_access(invalid_index, invalid_len);
         assert!(invalid_access.is_err(), "Invalid access should fail");
     }
+
+    // --- Bounded Collection Invariants ---
+
+    /// Verify that `BoundedVec` matches a stack model under any sequence of
+    /// pushes and pops, and never holds more than its capacity
+    #[cfg_attr(kani, kani::proof)]
+    #[cfg_attr(kani, kani::unwind(7))]
+    pub fn verify_bounded_vec_invariants() {
+        const CAPACITY: usize = 4;
+        let provider = StaticMemoryProvider::<64>::default();
+        let mut vec = BoundedVec::<u32, CAPACITY, StaticMemoryProvider<64>>::new(provider)
+            .expect("BoundedVec creation should succeed");
+
+        let mut model = [0u32; CAPACITY];
+        let mut model_len = 0;
+        for _ in 0..6 {
+            if kani::any() {
+                let value: u32 = kani::any();
+                let pushed = vec.push(value);
+                if model_len < CAPACITY {
+                    assert!(pushed.is_ok(), "Push should succeed below capacity");
+                    model[model_len] = value;
+                    model_len += 1;
+                } else {
+                    assert!(pushed.is_err(), "Push should fail at capacity");
+                }
+            } else {
+                let popped = vec.pop().expect("Pop should not fail");
+                if model_len > 0 {
+                    model_len -= 1;
+                    assert_eq!(popped, Some(model[model_len]));
+                } else {
+                    assert_eq!(popped, None);
+                }
+            }
+
+            assert_eq!(vec.len(), model_len);
+            assert!(vec.len() <= vec.capacity());
+            assert_eq!(vec.is_empty(), model_len == 0);
+            assert_eq!(vec.is_full(), model_len == CAPACITY);
+        }
+
+        for (index, expected) in model[..model_len].iter().enumerate() {
+            assert_eq!(vec.get(index).ok(), Some(*expected));
+        }
+    }
+
+    // --- LEB128 Decoding ---
+
+    /// Verify that LEB128 decoding never reads outside of its input and
+    /// reports a length within the limit for the integer width
+    #[cfg_attr(kani, kani::proof)]
+    #[cfg_attr(kani, kani::unwind(7))]
+    pub fn verify_leb128_decoding_stays_in_bounds() {
+        let bytes: [u8; 6] = kani::any();
+        let input_len: usize = kani::any();
+        kani::assume(input_len <= bytes.len());
+        let input = &bytes[..input_len];
+        let pos: usize = kani::any();
+        let mode = if kani::any() { Leb128Mode::Spec } else { Leb128Mode::Strict };
+
+        if let Ok((_, len)) = mode.read_u32(input, pos) {
+            assert!(len >= 1 && len <= 5);
+            assert!(pos < input.len() && len <= input.len() - pos);
+        }
+        if let Ok((_, len)) = mode.read_i32(input, pos) {
+            assert!(len >= 1 && len <= 5);
+            assert!(pos < input.len() && len <= input.len() - pos);
+        }
+    }
+
+    /// Verify that the minimal encoding of every 32-bit integer decodes to
+    /// the integer in both modes
+    #[cfg_attr(kani, kani::proof)]
+    #[cfg_attr(kani, kani::unwind(7))]
+    pub fn verify_leb128_round_trip() {
+        let unsigned: u32 = kani::any();
+        let mut bytes = [0u8; 5];
+        let mut len = 0;
+        let mut remaining = unsigned;
+        loop {
+            let byte = (remaining & 0x7F) as u8;
+            remaining >>= 7;
+            if remaining == 0 {
+                bytes[len] = byte;
+                len += 1;
+                break;
+            }
+            bytes[len] = byte | 0x80;
+            len += 1;
+        }
+        for mode in [Leb128Mode::Spec, Leb128Mode::Strict] {
+            assert_eq!(mode.read_u32(&bytes[..len], 0).ok(), Some((unsigned, len)));
+        }
+
+        let signed: i32 = kani::any();
+        let mut bytes = [0u8; 5];
+        let mut len = 0;
+        let mut remaining = signed;
+        loop {
+            let byte = (remaining & 0x7F) as u8;
+            remaining >>= 7;
+            let sign = byte & 0x40 != 0;
+            if (remaining == 0 && !sign) || (remaining == -1 && sign) {
+                bytes[len] = byte;
+                len += 1;
+                break;
+            }
+            bytes[len] = byte | 0x80;
+            len += 1;
+        }
+        for mode in [Leb128Mode::Spec, Leb128Mode::Strict] {
+            assert_eq!(mode.read_i32(&bytes[..len], 0).ok(), Some((signed, len)));
+        }
+    }
+
+    /// Verify that strict mode only rejects encodings and never decodes one
+    /// differently from spec mode
+    #[cfg_attr(kani, kani::proof)]
+    #[cfg_attr(kani, kani::unwind(7))]
+    pub fn verify_leb128_strict_mode_refines_spec() {
+        let bytes: [u8; 5] = kani::any();
+
+        if let Ok(decoded) = Leb128Mode::Strict.read_u32(&bytes, 0) {
+            assert_eq!(Leb128Mode::Spec.read_u32(&bytes, 0).ok(), Some(decoded));
+        }
+        if let Ok(decoded) = Leb128Mode::Strict.read_i32(&bytes, 0) {
+            assert_eq!(Leb128Mode::Spec.read_i32(&bytes, 0).ok(), Some(decoded));
+        }
+    }
 }
 
 // Expose verification module in docs but not for normal compilation
@@ -0,0 +1,518 @@
+//! Differential execution against a reference interpreter
+//!
+//! [`diff_execution`] runs the same module and invocations in two
+//! [`Executor`]s, usually [`WrtExecutor`] and a reference such as the spec
+//! interpreter, and reports the first point where they disagree: whether the
+//! module instantiates, the results or trap of each call, and the contents of
+//! the exported memories after it. State diverges for good after the first
+//! difference, so comparison stops there.
+//!
+//! NaN results compare equal regardless of payload, since the specification
+//! leaves the payload of most NaNs nondeterministic. Traps compare equal
+//! regardless of message, as engines word them differently.
+
+use core::fmt;
+
+use arbitrary::{
+    Arbitrary,
+    Unstructured,
+};
+use wrt_decoder::decoder::decode_module;
+use wrt_error::Result;
+use wrt_format::module::ExportKind;
+use wrt_foundation::{
+    memory_init::MemoryInitializer,
+    values::Value,
+};
+use wrt_runtime::{
+    engine::{
+        is_trap,
+        CapabilityAwareEngine,
+        CapabilityEngine,
+        EnginePreset,
+        InstanceHandle,
+    },
+    externs::Extern,
+};
+
+use crate::{
+    generator::ArbitraryModule,
+    targets::arbitrary_value,
+};
+
+/// Outcome of invoking an exported function
+#[derive(Debug, Clone)]
+pub enum Outcome {
+    /// The function returned these values
+    Returned(Vec<Value>),
+    /// The function trapped, with an engine-specific message
+    Trapped(String),
+}
+
+impl Outcome {
+    /// Check whether two outcomes agree, treating all NaNs and all traps as
+    /// equal
+    pub fn matches(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Returned(a), Self::Returned(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| values_match(a, b))
+            },
+            (Self::Trapped(_), Self::Trapped(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+fn values_match(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::F32(a), Value::F32(b)) => {
+            a.0 == b.0 || (f32::from_bits(a.0).is_nan() && f32::from_bits(b.0).is_nan())
+        },
+        (Value::F64(a), Value::F64(b)) => {
+            a.0 == b.0 || (f64::from_bits(a.0).is_nan() && f64::from_bits(b.0).is_nan())
+        },
+        _ => a == b,
+    }
+}
+
+/// An engine that can take part in differential execution
+pub trait Executor {
+    /// Name of the engine, for reports
+    fn name(&self) -> &str;
+
+    /// Instantiate `binary`, replacing any previous instance
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the module fails to load or instantiate.
+    fn instantiate(&mut self, binary: &[u8]) -> Result<()>;
+
+    /// Invoke the exported function `func` of the current instance
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the call could not be made at all, such as for a
+    /// missing export. Traps are an [`Outcome`], not an error.
+    fn invoke(&mut self, func: &str, args: &[Value]) -> Result<Outcome>;
+
+    /// Contents of the memory the current instance exports as `name`
+    fn memory(&self, name: &str) -> Option<Vec<u8>>;
+}
+
+/// WRT as an [`Executor`]
+pub struct WrtExecutor {
+    preset:   EnginePreset,
+    engine:   Option<CapabilityAwareEngine>,
+    instance: Option<InstanceHandle>,
+}
+
+impl WrtExecutor {
+    /// Create an executor that runs modules in engines of `preset`
+    pub fn new(preset: EnginePreset) -> Self {
+        Self {
+            preset,
+            engine: None,
+            instance: None,
+        }
+    }
+}
+
+impl Default for WrtExecutor {
+    fn default() -> Self {
+        Self::new(EnginePreset::QM)
+    }
+}
+
+impl Executor for WrtExecutor {
+    fn name(&self) -> &str {
+        "wrt"
+    }
+
+    fn instantiate(&mut self, binary: &[u8]) -> Result<()> {
+        self.engine = None;
+        self.instance = None;
+
+        MemoryInitializer::ensure_initialized()?;
+        let mut engine = CapabilityAwareEngine::with_preset(self.preset)?;
+        let module = engine.load_module(binary)?;
+        self.instance = Some(engine.instantiate(module)?);
+        self.engine = Some(engine);
+        Ok(())
+    }
+
+    fn invoke(&mut self, func: &str, args: &[Value]) -> Result<Outcome> {
+        let (Some(engine), Some(instance)) = (self.engine.as_mut(), self.instance) else {
+            return Err(wrt_error::Error::runtime_error("No module instantiated"));
+        };
+        match engine.execute(instance, func, args) {
+            Ok(results) => Ok(Outcome::Returned(results)),
+            Err(error) if is_trap(&error) => Ok(Outcome::Trapped(error.message.to_string())),
+            Err(error) => Err(error),
+        }
+    }
+
+    fn memory(&self, name: &str) -> Option<Vec<u8>> {
+        let engine = self.engine.as_ref()?;
+        match engine.get_export(self.instance?, name).ok()?? {
+            Extern::Memory(export) => export.memory.0.buffer().ok(),
+            _ => None,
+        }
+    }
+}
+
+/// A call to an exported function
+#[derive(Debug, Clone)]
+pub struct Invocation {
+    /// Name of the exported function
+    pub func: String,
+    /// Arguments of the call
+    pub args: Vec<Value>,
+}
+
+/// What two executors disagreed on
+#[derive(Debug, Clone)]
+pub enum DivergenceKind {
+    /// One executor instantiated the module and the other did not
+    Instantiation {
+        /// Error of the subject
+        subject:   Option<String>,
+        /// Error of the reference
+        reference: Option<String>,
+    },
+    /// An invocation failed to run in one of the executors
+    Invocation {
+        /// The error
+        error: String,
+    },
+    /// An invocation returned different values or trapped in only one
+    /// executor
+    Outcome {
+        /// Outcome in the subject
+        subject:   Outcome,
+        /// Outcome in the reference
+        reference: Outcome,
+    },
+    /// An exported memory differs after an invocation
+    Memory {
+        /// Name of the memory export
+        name:      String,
+        /// Size of the memory in the subject, in bytes
+        subject:   Option<usize>,
+        /// Size of the memory in the reference, in bytes
+        reference: Option<usize>,
+        /// Offset of the first differing byte, if both sides have the
+        /// memory
+        offset:    Option<usize>,
+    },
+}
+
+/// First disagreement between two executors
+#[derive(Debug, Clone)]
+pub struct Divergence {
+    /// Index of the invocation that diverged, `None` for instantiation
+    pub invocation: Option<usize>,
+    /// What differed
+    pub kind:       DivergenceKind,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.invocation {
+            Some(index) => write!(f, "invocation {index}: ")?,
+            None => write!(f, "instantiation: ")?,
+        }
+        match &self.kind {
+            DivergenceKind::Instantiation { subject, reference } => write!(
+                f,
+                "subject {}, reference {}",
+                subject.as_deref().unwrap_or("succeeded"),
+                reference.as_deref().unwrap_or("succeeded")
+            ),
+            DivergenceKind::Invocation { error } => write!(f, "could not run: {error}"),
+            DivergenceKind::Outcome { subject, reference } => {
+                write!(f, "subject {subject:?}, reference {reference:?}")
+            },
+            DivergenceKind::Memory {
+                name,
+                subject,
+                reference,
+                offset,
+            } => {
+                write!(
+                    f,
+                    "memory {name:?} differs (sizes {subject:?} and {reference:?}"
+                )?;
+                match offset {
+                    Some(offset) => write!(f, ", first at byte {offset})"),
+                    None => write!(f, ")"),
+                }
+            },
+        }
+    }
+}
+
+/// Run `binary` and `invocations` in `subject` and `reference` and return
+/// the first divergence, if any
+///
+/// Modules that neither executor can instantiate agree trivially.
+///
+/// # Errors
+///
+/// Returns an error if `binary` cannot be decoded to find its exported
+/// memories, unless neither executor instantiates it.
+pub fn diff_execution(
+    binary: &[u8],
+    invocations: &[Invocation],
+    subject: &mut dyn Executor,
+    reference: &mut dyn Executor,
+) -> Result<Option<Divergence>> {
+    match (subject.instantiate(binary), reference.instantiate(binary)) {
+        (Ok(()), Ok(())) => {},
+        (Err(_), Err(_)) => return Ok(None),
+        (subject, reference) => {
+            return Ok(Some(Divergence {
+                invocation: None,
+                kind:       DivergenceKind::Instantiation {
+                    subject:   subject.err().map(|error| error.to_string()),
+                    reference: reference.err().map(|error| error.to_string()),
+                },
+            }));
+        },
+    }
+
+    let memories: Vec<String> = decode_module(binary)?
+        .exports
+        .into_iter()
+        .filter(|export| export.kind == ExportKind::Memory)
+        .map(|export| export.name)
+        .collect();
+
+    for (index, invocation) in invocations.iter().enumerate() {
+        let diverged = |kind| {
+            Ok(Some(Divergence {
+                invocation: Some(index),
+                kind,
+            }))
+        };
+
+        let outcomes = (
+            subject.invoke(&invocation.func, &invocation.args),
+            reference.invoke(&invocation.func, &invocation.args),
+        );
+        let (subject_outcome, reference_outcome) = match outcomes {
+            (Ok(subject), Ok(reference)) => (subject, reference),
+            (Err(error), _) | (_, Err(error)) => {
+                return diverged(DivergenceKind::Invocation {
+                    error: error.to_string(),
+                });
+            },
+        };
+        if !subject_outcome.matches(&reference_outcome) {
+            return diverged(DivergenceKind::Outcome {
+                subject:   subject_outcome,
+                reference: reference_outcome,
+            });
+        }
+
+        for name in &memories {
+            let (a, b) = (subject.memory(name), reference.memory(name));
+            if a != b {
+                let offset = a
+                    .as_ref()
+                    .zip(b.as_ref())
+                    .map(|(a, b)| a.iter().zip(b).take_while(|(a, b)| a == b).count());
+                return diverged(DivergenceKind::Memory {
+                    name: name.clone(),
+                    subject: a.map(|memory| memory.len()),
+                    reference: b.map(|memory| memory.len()),
+                    offset,
+                });
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Fuzz target comparing WRT with `reference` on a module generated from
+/// the fuzzer input, calling every exported function once
+///
+/// # Panics
+///
+/// Panics with the divergence if the executors disagree.
+pub fn differential_fuzz(data: &[u8], reference: &mut dyn Executor) {
+    let mut u = Unstructured::new(data);
+    let Ok(module) = ArbitraryModule::arbitrary(&mut u) else {
+        return;
+    };
+    let binary = module.to_binary().expect("generated module failed to encode");
+
+    let mut invocations = Vec::new();
+    for (name, func_type) in module.exported_functions() {
+        let Ok(args) = func_type
+            .params
+            .iter()
+            .map(|param| arbitrary_value(&mut u, *param))
+            .collect::<arbitrary::Result<Vec<_>>>()
+        else {
+            break;
+        };
+        invocations.push(Invocation {
+            func: name.into(),
+            args,
+        });
+    }
+
+    let mut subject = WrtExecutor::default();
+    let divergence = diff_execution(&binary, &invocations, &mut subject, reference)
+        .expect("generated module failed to decode");
+    if let Some(divergence) = divergence {
+        panic!(
+            "{} and {} diverge at {divergence}",
+            subject.name(),
+            reference.name()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wrt_foundation::values::FloatBits32;
+
+    use super::*;
+
+    /// Executor replaying recorded outcomes and memory contents
+    struct Recorded {
+        instantiates: bool,
+        outcomes:     Vec<Outcome>,
+        memory:       Vec<u8>,
+        calls:        usize,
+    }
+
+    impl Recorded {
+        fn new(outcomes: Vec<Outcome>, memory: &[u8]) -> Self {
+            Self {
+                instantiates: true,
+                outcomes,
+                memory: memory.to_vec(),
+                calls: 0,
+            }
+        }
+    }
+
+    impl Executor for Recorded {
+        fn name(&self) -> &str {
+            "recorded"
+        }
+
+        fn instantiate(&mut self, _binary: &[u8]) -> Result<()> {
+            if self.instantiates {
+                Ok(())
+            } else {
+                Err(wrt_error::Error::validation_error("rejected"))
+            }
+        }
+
+        fn invoke(&mut self, _func: &str, _args: &[Value]) -> Result<Outcome> {
+            self.calls += 1;
+            Ok(self.outcomes[self.calls - 1].clone())
+        }
+
+        fn memory(&self, name: &str) -> Option<Vec<u8>> {
+            (name == "mem").then(|| self.memory.clone())
+        }
+    }
+
+    /// Module exporting a one-page memory as "mem"
+    const MODULE: &[u8] = &[
+        0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, // header
+        0x05, 0x03, 0x01, 0x00, 0x01, // memory section
+        0x07, 0x07, 0x01, 0x03, b'm', b'e', b'm', 0x02, 0x00, // export section
+    ];
+
+    fn calls(count: usize) -> Vec<Invocation> {
+        (0..count)
+            .map(|_| Invocation {
+                func: "f".into(),
+                args: Vec::new(),
+            })
+            .collect()
+    }
+
+    fn f32_nan(bits: u32) -> Outcome {
+        Outcome::Returned(vec![Value::F32(FloatBits32(bits))])
+    }
+
+    #[test]
+    fn test_agreement() {
+        let mut subject = Recorded::new(
+            vec![
+                Outcome::Returned(vec![Value::I32(7)]),
+                f32_nan(0x7FC0_0000),
+                Outcome::Trapped("unreachable".into()),
+            ],
+            &[1, 2, 3],
+        );
+        let mut reference = Recorded::new(
+            vec![
+                Outcome::Returned(vec![Value::I32(7)]),
+                f32_nan(0xFFC0_0001),
+                Outcome::Trapped("integer divide by zero".into()),
+            ],
+            &[1, 2, 3],
+        );
+
+        let divergence = diff_execution(MODULE, &calls(3), &mut subject, &mut reference).unwrap();
+        assert!(divergence.is_none());
+    }
+
+    #[test]
+    fn test_outcome_divergence() {
+        let mut subject = Recorded::new(
+            vec![
+                Outcome::Returned(vec![]),
+                Outcome::Returned(vec![Value::I32(1)]),
+            ],
+            &[],
+        );
+        let mut reference = Recorded::new(
+            vec![Outcome::Returned(vec![]), Outcome::Trapped("trap".into())],
+            &[],
+        );
+
+        let divergence = diff_execution(MODULE, &calls(2), &mut subject, &mut reference)
+            .unwrap()
+            .unwrap();
+        assert_eq!(divergence.invocation, Some(1));
+        assert!(matches!(divergence.kind, DivergenceKind::Outcome { .. }));
+    }
+
+    #[test]
+    fn test_memory_divergence() {
+        let mut subject = Recorded::new(vec![Outcome::Returned(vec![])], &[0, 1, 2, 3]);
+        let mut reference = Recorded::new(vec![Outcome::Returned(vec![])], &[0, 1, 9, 3]);
+
+        let divergence = diff_execution(MODULE, &calls(1), &mut subject, &mut reference)
+            .unwrap()
+            .unwrap();
+        assert!(matches!(
+            divergence.kind,
+            DivergenceKind::Memory { ref name, offset: Some(2), .. } if name == "mem"
+        ));
+        assert!(divergence.to_string().contains("first at byte 2"));
+    }
+
+    #[test]
+    fn test_instantiation_divergence() {
+        let mut subject = Recorded::new(Vec::new(), &[]);
+        let mut reference = Recorded::new(Vec::new(), &[]);
+        reference.instantiates = false;
+
+        let divergence =
+            diff_execution(MODULE, &[], &mut subject, &mut reference).unwrap().unwrap();
+        assert_eq!(divergence.invocation, None);
+
+        subject.instantiates = false;
+        assert!(diff_execution(MODULE, &[], &mut subject, &mut reference).unwrap().is_none());
+    }
+}
@@ -0,0 +1,564 @@
+//! Structured generation of valid modules from fuzzer input
+//!
+//! Mutating raw bytes rarely gets past the decoder, so [`ArbitraryModule`]
+//! instead reads its choices from the fuzzer input and builds a module that
+//! validates by construction. Function bodies are generated as typed
+//! expression trees over the numeric types, with globals, an optional memory
+//! and calls to functions of lower index. The generator emits no loops and
+//! no recursion, so every generated function terminates, though it may trap.
+
+use arbitrary::{
+    Arbitrary,
+    Result,
+    Unstructured,
+};
+use wrt_decoder::encoder::encode_module;
+use wrt_format::{
+    module::{
+        Export,
+        ExportKind,
+        Function,
+        Global,
+        Module as WrtModule,
+    },
+    types::FormatGlobalType,
+    write_leb128_i32,
+    write_leb128_i64,
+    write_leb128_u32,
+};
+use wrt_foundation::{
+    types::{
+        Limits,
+        MemoryType,
+    },
+    CleanCoreFuncType,
+    ValueType,
+};
+
+/// Numeric value types the generator uses
+const VALUE_TYPES: [ValueType; 4] = [
+    ValueType::I32,
+    ValueType::I64,
+    ValueType::F32,
+    ValueType::F64,
+];
+
+/// Limits on the size of generated modules
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GeneratorConfig {
+    /// Maximum number of functions
+    pub max_functions:    usize,
+    /// Maximum number of parameters of a function
+    pub max_params:       usize,
+    /// Maximum number of results of a function
+    pub max_results:      usize,
+    /// Maximum number of locals of a function, besides its parameters
+    pub max_locals:       usize,
+    /// Maximum number of globals
+    pub max_globals:      usize,
+    /// Maximum number of statements in a block
+    pub max_statements:   usize,
+    /// Maximum nesting depth of expressions and blocks
+    pub max_depth:        usize,
+    /// Maximum initial size of the memory in pages; no memory is generated
+    /// when this is `None`
+    pub max_memory_pages: Option<u32>,
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        Self {
+            max_functions:    8,
+            max_params:       4,
+            max_results:      2,
+            max_locals:       4,
+            max_globals:      4,
+            max_statements:   8,
+            max_depth:        5,
+            max_memory_pages: Some(2),
+        }
+    }
+}
+
+/// A valid module generated from fuzzer input
+///
+/// Every function is exported as `f<index>`, and the memory, if any, as
+/// `memory`.
+#[derive(Debug, Clone)]
+pub struct ArbitraryModule {
+    module: WrtModule,
+}
+
+impl ArbitraryModule {
+    /// Generate a module within the limits of `config`
+    pub fn generate(u: &mut Unstructured<'_>, config: &GeneratorConfig) -> Result<Self> {
+        let mut module = WrtModule::new();
+
+        let memory_pages = match config.max_memory_pages {
+            Some(max_pages) if u.arbitrary()? => Some(u.int_in_range(0..=max_pages)?),
+            _ => None,
+        };
+        if let Some(min) = memory_pages {
+            let max = if u.arbitrary()? {
+                Some(min.saturating_add(u.int_in_range(0..=4)?))
+            } else {
+                None
+            };
+            module.memories.push(MemoryType::new(Limits::new(min, max), false));
+            module.exports.push(Export {
+                name:  "memory".into(),
+                kind:  ExportKind::Memory,
+                index: 0,
+            });
+        }
+
+        for _ in 0..u.int_in_range(0..=config.max_globals)? {
+            let value_type = *u.choose(&VALUE_TYPES)?;
+            let mut init = Vec::new();
+            push_const(u, value_type, &mut init)?;
+            init.push(0x0B);
+            module.globals.push(Global {
+                global_type: FormatGlobalType {
+                    value_type,
+                    mutable: u.arbitrary()?,
+                },
+                init,
+            });
+        }
+
+        for _ in 0..u.int_in_range(1..=config.max_functions.max(1))? {
+            let func_type = CleanCoreFuncType {
+                params:  value_types(u, config.max_params)?,
+                results: value_types(u, config.max_results)?,
+            };
+            let locals = value_types(u, config.max_locals)?;
+
+            let index = module.functions.len() as u32;
+            let mut body = BodyBuilder {
+                u,
+                config,
+                module: &module,
+                locals: func_type.params.iter().chain(&locals).copied().collect(),
+                depth: 0,
+                code: Vec::new(),
+            };
+            body.statements()?;
+            for result in &func_type.results {
+                body.expression(*result)?;
+            }
+            let mut code = body.code;
+            code.push(0x0B);
+
+            module.types.push(func_type);
+            module.functions.push(Function {
+                type_idx: index,
+                locals,
+                code,
+            });
+            module.exports.push(Export {
+                name: format!("f{index}"),
+                kind: ExportKind::Function,
+                index,
+            });
+        }
+
+        Ok(Self { module })
+    }
+
+    /// The generated module
+    pub fn module(&self) -> &WrtModule {
+        &self.module
+    }
+
+    /// Signatures of the exported functions, by export name
+    pub fn exported_functions(&self) -> impl Iterator<Item = (&str, &CleanCoreFuncType)> + '_ {
+        self.module
+            .exports
+            .iter()
+            .filter(|export| export.kind == ExportKind::Function)
+            .map(|export| {
+                let function = &self.module.functions[export.index as usize];
+                (
+                    export.name.as_str(),
+                    &self.module.types[function.type_idx as usize],
+                )
+            })
+    }
+
+    /// Encode the module to a WebAssembly binary
+    pub fn to_binary(&self) -> wrt_error::Result<Vec<u8>> {
+        encode_module(&self.module)
+    }
+}
+
+impl<'a> Arbitrary<'a> for ArbitraryModule {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Self::generate(u, &GeneratorConfig::default())
+    }
+}
+
+fn value_types(u: &mut Unstructured<'_>, max: usize) -> Result<Vec<ValueType>> {
+    (0..u.int_in_range(0..=max)?).map(|_| u.choose(&VALUE_TYPES).copied()).collect()
+}
+
+/// Append a `*.const` instruction with an arbitrary value of `value_type`
+fn push_const(u: &mut Unstructured<'_>, value_type: ValueType, code: &mut Vec<u8>) -> Result<()> {
+    match value_type {
+        ValueType::I32 => {
+            code.push(0x41);
+            code.extend(write_leb128_i32(u.arbitrary()?));
+        },
+        ValueType::I64 => {
+            code.push(0x42);
+            code.extend(write_leb128_i64(u.arbitrary()?));
+        },
+        ValueType::F32 => {
+            code.push(0x43);
+            code.extend(u.arbitrary::<u32>()?.to_le_bytes());
+        },
+        _ => {
+            code.push(0x44);
+            code.extend(u.arbitrary::<u64>()?.to_le_bytes());
+        },
+    }
+    Ok(())
+}
+
+/// Encoding of a numeric value type
+fn type_byte(value_type: ValueType) -> u8 {
+    match value_type {
+        ValueType::I32 => 0x7F,
+        ValueType::I64 => 0x7E,
+        ValueType::F32 => 0x7D,
+        _ => 0x7C,
+    }
+}
+
+/// Index of a numeric value type in [`VALUE_TYPES`]
+fn type_index(value_type: ValueType) -> usize {
+    match value_type {
+        ValueType::I32 => 0,
+        ValueType::I64 => 1,
+        ValueType::F32 => 2,
+        _ => 3,
+    }
+}
+
+/// Binary operators by operand type, all producing their operand type
+const BINARY_OPS: [&[u8]; 4] = [
+    &[
+        0x6A, 0x6B, 0x6C, 0x6D, 0x6E, 0x6F, 0x70, 0x71, 0x72, 0x73, 0x74, 0x75, 0x76, 0x77, 0x78,
+    ],
+    &[
+        0x7C, 0x7D, 0x7E, 0x7F, 0x80, 0x81, 0x82, 0x83, 0x84, 0x85, 0x86, 0x87, 0x88, 0x89, 0x8A,
+    ],
+    &[0x92, 0x93, 0x94, 0x95, 0x96, 0x97, 0x98],
+    &[0xA0, 0xA1, 0xA2, 0xA3, 0xA4, 0xA5, 0xA6],
+];
+
+/// Unary operators by operand type, all producing their operand type
+const UNARY_OPS: [&[u8]; 4] = [
+    &[0x67, 0x68, 0x69],
+    &[0x79, 0x7A, 0x7B],
+    &[0x8B, 0x8C, 0x8D, 0x8E, 0x8F, 0x90, 0x91],
+    &[0x99, 0x9A, 0x9B, 0x9C, 0x9D, 0x9E, 0x9F],
+];
+
+/// Comparisons producing an i32, by operand type
+const COMPARISONS: [&[u8]; 4] = [
+    &[0x46, 0x47, 0x48, 0x49, 0x4A, 0x4B, 0x4C, 0x4D, 0x4E, 0x4F],
+    &[0x51, 0x52, 0x53, 0x54, 0x55, 0x56, 0x57, 0x58, 0x59, 0x5A],
+    &[0x5B, 0x5C, 0x5D, 0x5E, 0x5F, 0x60],
+    &[0x61, 0x62, 0x63, 0x64, 0x65, 0x66],
+];
+
+/// Conversions as (opcode, operand type, result type)
+const CONVERSIONS: [(u8, ValueType, ValueType); 25] = [
+    (0x45, ValueType::I32, ValueType::I32),
+    (0x50, ValueType::I64, ValueType::I32),
+    (0xA7, ValueType::I64, ValueType::I32),
+    (0xA8, ValueType::F32, ValueType::I32),
+    (0xA9, ValueType::F32, ValueType::I32),
+    (0xAA, ValueType::F64, ValueType::I32),
+    (0xAB, ValueType::F64, ValueType::I32),
+    (0xAC, ValueType::I32, ValueType::I64),
+    (0xAD, ValueType::I32, ValueType::I64),
+    (0xAE, ValueType::F32, ValueType::I64),
+    (0xAF, ValueType::F32, ValueType::I64),
+    (0xB0, ValueType::F64, ValueType::I64),
+    (0xB1, ValueType::F64, ValueType::I64),
+    (0xB2, ValueType::I32, ValueType::F32),
+    (0xB3, ValueType::I32, ValueType::F32),
+    (0xB4, ValueType::I64, ValueType::F32),
+    (0xB5, ValueType::I64, ValueType::F32),
+    (0xB6, ValueType::F64, ValueType::F32),
+    (0xB7, ValueType::I32, ValueType::F64),
+    (0xB8, ValueType::I32, ValueType::F64),
+    (0xB9, ValueType::I64, ValueType::F64),
+    (0xBA, ValueType::I64, ValueType::F64),
+    (0xBB, ValueType::F32, ValueType::F64),
+    (0xBC, ValueType::F32, ValueType::I32),
+    (0xBF, ValueType::I64, ValueType::F64),
+];
+
+/// Natural alignment exponents of loads and stores, by value type
+const NATURAL_ALIGN: [u32; 4] = [2, 3, 2, 3];
+
+/// Generator of a single function body
+struct BodyBuilder<'u, 'a, 'm> {
+    u:      &'u mut Unstructured<'a>,
+    config: &'m GeneratorConfig,
+    module: &'m WrtModule,
+    locals: Vec<ValueType>,
+    depth:  usize,
+    code:   Vec<u8>,
+}
+
+impl BodyBuilder<'_, '_, '_> {
+    fn has_memory(&self) -> bool {
+        !self.module.memories.is_empty()
+    }
+
+    /// Generate a sequence of statements, leaving the stack unchanged
+    fn statements(&mut self) -> Result<()> {
+        for _ in 0..self.u.int_in_range(0..=self.config.max_statements)? {
+            self.statement()?;
+        }
+        Ok(())
+    }
+
+    fn statement(&mut self) -> Result<()> {
+        let nested = self.depth < self.config.max_depth;
+        match self.u.int_in_range(0..=8)? {
+            0 if !self.locals.is_empty() => {
+                let local = self.u.choose_index(self.locals.len())?;
+                self.expression(self.locals[local])?;
+                self.code.push(0x21);
+                self.code.extend(write_leb128_u32(local as u32));
+            },
+            1 => {
+                let mutable: Vec<_> = (0..self.module.globals.len())
+                    .filter(|global| self.module.globals[*global].global_type.mutable)
+                    .collect();
+                if let Some(global) = mutable.get(self.u.choose_index(mutable.len().max(1))?) {
+                    self.expression(self.module.globals[*global].global_type.value_type)?;
+                    self.code.push(0x24);
+                    self.code.extend(write_leb128_u32(*global as u32));
+                }
+            },
+            2 if self.has_memory() => {
+                let value_type = *self.u.choose(&VALUE_TYPES)?;
+                self.expression(ValueType::I32)?;
+                self.expression(value_type)?;
+                self.code.push(0x36 + type_index(value_type) as u8);
+                self.memarg(value_type)?;
+            },
+            3 if nested => {
+                // if <cond> <statements> else <statements> end
+                self.expression(ValueType::I32)?;
+                self.code.extend([0x04, 0x40]);
+                self.depth += 1;
+                self.statements()?;
+                self.code.push(0x05);
+                self.statements()?;
+                self.depth -= 1;
+                self.code.push(0x0B);
+            },
+            4 if nested => {
+                // block <statements> <cond> br_if 0 <statements> end
+                self.code.extend([0x02, 0x40]);
+                self.depth += 1;
+                self.statements()?;
+                self.expression(ValueType::I32)?;
+                self.code.extend([0x0D, 0x00]);
+                self.statements()?;
+                self.depth -= 1;
+                self.code.push(0x0B);
+            },
+            5 if self.u.ratio(1, 8)? => self.code.push(0x00),
+            6 => {
+                let value_type = *self.u.choose(&VALUE_TYPES)?;
+                self.expression(value_type)?;
+                self.code.push(0x1A);
+            },
+            _ => self.code.push(0x01),
+        }
+        Ok(())
+    }
+
+    /// Generate an expression leaving one value of `value_type`
+    fn expression(&mut self, value_type: ValueType) -> Result<()> {
+        if self.depth >= self.config.max_depth || self.u.is_empty() {
+            return self.leaf(value_type);
+        }
+
+        self.depth += 1;
+        let index = type_index(value_type);
+        match self.u.int_in_range(0..=9)? {
+            0 => {
+                self.expression(value_type)?;
+                self.expression(value_type)?;
+                self.code.push(*self.u.choose(BINARY_OPS[index])?);
+            },
+            1 => {
+                self.expression(value_type)?;
+                self.code.push(*self.u.choose(UNARY_OPS[index])?);
+            },
+            2 if value_type == ValueType::I32 => {
+                let operand = *self.u.choose(&VALUE_TYPES)?;
+                self.expression(operand)?;
+                self.expression(operand)?;
+                self.code.push(*self.u.choose(COMPARISONS[type_index(operand)])?);
+            },
+            3 => {
+                let candidates: Vec<_> =
+                    CONVERSIONS.iter().filter(|(_, _, result)| *result == value_type).collect();
+                let (opcode, operand, _) = *self.u.choose(&candidates)?;
+                self.expression(*operand)?;
+                self.code.push(*opcode);
+            },
+            4 => {
+                self.expression(value_type)?;
+                self.expression(value_type)?;
+                self.expression(ValueType::I32)?;
+                self.code.push(0x1B);
+            },
+            5 => {
+                // if (result t) <expr> else <expr> end
+                self.expression(ValueType::I32)?;
+                self.code.extend([0x04, type_byte(value_type)]);
+                self.expression(value_type)?;
+                self.code.push(0x05);
+                self.expression(value_type)?;
+                self.code.push(0x0B);
+            },
+            6 if self.has_memory() => {
+                self.expression(ValueType::I32)?;
+                self.code.push(0x28 + index as u8);
+                self.memarg(value_type)?;
+            },
+            7 if self.has_memory() && value_type == ValueType::I32 => {
+                if self.u.arbitrary()? {
+                    self.code.extend([0x3F, 0x00]);
+                } else {
+                    self.expression(ValueType::I32)?;
+                    self.code.extend([0x40, 0x00]);
+                }
+            },
+            8 => self.call(value_type)?,
+            9 if !self.locals.is_empty() => {
+                // local.tee of a local of the same type
+                let local = self.u.choose_index(self.locals.len())?;
+                if self.locals[local] == value_type {
+                    self.expression(value_type)?;
+                    self.code.push(0x22);
+                    self.code.extend(write_leb128_u32(local as u32));
+                } else {
+                    self.leaf(value_type)?;
+                }
+            },
+            _ => self.leaf(value_type)?,
+        }
+        self.depth -= 1;
+        Ok(())
+    }
+
+    /// Call a previously generated function whose only result is of
+    /// `value_type`
+    fn call(&mut self, value_type: ValueType) -> Result<()> {
+        let callees: Vec<_> = (0..self.module.functions.len())
+            .filter(|callee| {
+                self.module.types[self.module.functions[*callee].type_idx as usize].results
+                    == [value_type]
+            })
+            .collect();
+        let Some(&callee) = callees.get(self.u.choose_index(callees.len().max(1))?) else {
+            return self.leaf(value_type);
+        };
+
+        let module = self.module;
+        let func_type = &module.types[module.functions[callee].type_idx as usize];
+        for param in &func_type.params {
+            self.expression(*param)?;
+        }
+        self.code.push(0x10);
+        self.code.extend(write_leb128_u32(callee as u32));
+        Ok(())
+    }
+
+    /// Generate a constant, or a read of a local or global of `value_type`
+    fn leaf(&mut self, value_type: ValueType) -> Result<()> {
+        let locals: Vec<_> = (0..self.locals.len())
+            .filter(|local| self.locals[*local] == value_type)
+            .collect();
+        let globals: Vec<_> = (0..self.module.globals.len())
+            .filter(|global| self.module.globals[*global].global_type.value_type == value_type)
+            .collect();
+
+        match self.u.int_in_range(0..=2)? {
+            0 if !locals.is_empty() => {
+                self.code.push(0x20);
+                self.code.extend(write_leb128_u32(*self.u.choose(&locals)? as u32));
+            },
+            1 if !globals.is_empty() => {
+                self.code.push(0x23);
+                self.code.extend(write_leb128_u32(*self.u.choose(&globals)? as u32));
+            },
+            _ => push_const(self.u, value_type, &mut self.code)?,
+        }
+        Ok(())
+    }
+
+    /// Append the alignment and offset of a load or store of `value_type`
+    fn memarg(&mut self, value_type: ValueType) -> Result<()> {
+        let align = self.u.int_in_range(0..=NATURAL_ALIGN[type_index(value_type)])?;
+        let offset = if self.u.ratio(1, 4)? {
+            self.u.arbitrary()?
+        } else {
+            self.u.int_in_range(0..=64)?
+        };
+        self.code.extend(write_leb128_u32(align));
+        self.code.extend(write_leb128_u32(offset));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wrt_decoder::decoder::decode_module;
+
+    use super::*;
+
+    /// Deterministic pseudo-random bytes standing in for fuzzer input
+    fn input(seed: u64, len: usize) -> Vec<u8> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6_364_136_223_846_793_005)
+                    .wrapping_add(1_442_695_040_888_963_407);
+                (state >> 56) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_generated_modules_decode() {
+        for seed in 0..64 {
+            let data = input(seed, 4096);
+            let module = ArbitraryModule::arbitrary(&mut Unstructured::new(&data)).unwrap();
+            let binary = module.to_binary().unwrap();
+
+            let decoded = decode_module(&binary).unwrap();
+            assert_eq!(decoded.functions.len(), module.module().functions.len());
+            assert_eq!(module.exported_functions().count(), decoded.functions.len());
+        }
+    }
+
+    #[test]
+    fn test_generation_from_empty_input() {
+        let module = ArbitraryModule::arbitrary(&mut Unstructured::new(&[])).unwrap();
+        assert_eq!(module.module().functions.len(), 1);
+        assert!(decode_module(&module.to_binary().unwrap()).is_ok());
+    }
+}
@@ -0,0 +1,60 @@
+//! # WRT Fuzz Support
+//!
+//! Building blocks for fuzzing the WebAssembly Runtime (WRT) with
+//! `cargo-fuzz` or any other libFuzzer-style driver.
+//!
+//! - [`targets`] provides ready-made fuzz targets for the decoder and the
+//!   interpreter.
+//! - [`generator`] turns fuzzer input into modules that are valid by
+//!   construction, so that campaigns get past the decoder and exercise
+//!   execution.
+//! - [`differential`] runs modules in WRT and a reference interpreter and
+//!   reports where they disagree.
+//! - [`triage`] reproduces crashes and minimizes the inputs that cause them.
+//!
+//! A `cargo-fuzz` target only has to forward its input:
+//!
+//! ```ignore
+//! #![no_main]
+//! libfuzzer_sys::fuzz_target!(|data: &[u8]| {
+//!     wrt_fuzz_support::decode_module_fuzz(data);
+//! });
+//! ```
+
+// WRT - wrt-fuzz-support
+// Module: Fuzzing Support
+//
+// Copyright (c) 2025 Ralf Anton Beier
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+#![forbid(unsafe_code)] // Rule 2
+#![warn(missing_docs)]
+
+pub mod differential;
+pub mod generator;
+pub mod targets;
+pub mod triage;
+
+pub use differential::{
+    diff_execution,
+    differential_fuzz,
+    Divergence,
+    Executor,
+    Invocation,
+    Outcome,
+    WrtExecutor,
+};
+pub use generator::{
+    ArbitraryModule,
+    GeneratorConfig,
+};
+pub use targets::{
+    decode_module_fuzz,
+    execute_fuzz,
+};
+pub use triage::{
+    minimize,
+    minimize_crash,
+    panics,
+};
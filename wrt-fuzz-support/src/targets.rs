@@ -0,0 +1,129 @@
+//! Fuzz targets for the decoder and the interpreter
+//!
+//! Each target takes the raw fuzzer input and panics only when it finds a
+//! bug. Errors returned for malformed or trapping input are expected and
+//! ignored.
+
+use arbitrary::{
+    Arbitrary,
+    Unstructured,
+};
+use wrt_decoder::{
+    decoder::decode_module,
+    encoder::encode_module,
+};
+use wrt_foundation::{
+    memory_init::MemoryInitializer,
+    values::{
+        FloatBits32,
+        FloatBits64,
+        Value,
+    },
+    ValueType,
+};
+use wrt_runtime::engine::{
+    CapabilityAwareEngine,
+    CapabilityEngine,
+    EnginePreset,
+};
+
+use crate::generator::ArbitraryModule;
+
+/// Decode arbitrary bytes as a module
+///
+/// Whatever decodes has to survive a round trip through the encoder: the
+/// encoding must decode again and encode to the same bytes.
+///
+/// # Panics
+///
+/// Panics if a decoded module does not round-trip.
+pub fn decode_module_fuzz(data: &[u8]) {
+    let Ok(module) = decode_module(data) else {
+        return;
+    };
+    // Some decodable modules cannot be expressed by the encoder
+    let Ok(encoded) = encode_module(&module) else {
+        return;
+    };
+
+    let decoded = decode_module(&encoded).expect("encoded module failed to decode");
+    let reencoded = encode_module(&decoded).expect("decoded module failed to encode");
+    assert_eq!(
+        encoded, reencoded,
+        "module encoding is not stable across a round trip"
+    );
+}
+
+/// Generate a module from the fuzzer input, then call each of its exported
+/// functions with arguments taken from the rest of the input
+///
+/// Load, instantiation and execution errors, including traps, are expected;
+/// any panic is a bug.
+pub fn execute_fuzz(data: &[u8]) {
+    let mut u = Unstructured::new(data);
+    let Ok(module) = ArbitraryModule::arbitrary(&mut u) else {
+        return;
+    };
+    let binary = module.to_binary().expect("generated module failed to encode");
+
+    // Engine construction allocates from the memory system
+    MemoryInitializer::ensure_initialized().expect("memory system failed to initialize");
+    let Ok(mut engine) = CapabilityAwareEngine::with_preset(EnginePreset::QM) else {
+        return;
+    };
+    let Ok(handle) = engine.load_module(&binary) else {
+        return;
+    };
+    let Ok(instance) = engine.instantiate(handle) else {
+        return;
+    };
+
+    for (name, func_type) in module.exported_functions() {
+        let Ok(args) = func_type
+            .params
+            .iter()
+            .map(|param| arbitrary_value(&mut u, *param))
+            .collect::<arbitrary::Result<Vec<_>>>()
+        else {
+            return;
+        };
+        let _ = engine.execute(instance, name, &args);
+    }
+}
+
+/// Take an arbitrary value of a numeric type from the input
+pub(crate) fn arbitrary_value(
+    u: &mut Unstructured<'_>,
+    value_type: ValueType,
+) -> arbitrary::Result<Value> {
+    Ok(match value_type {
+        ValueType::I32 => Value::I32(u.arbitrary()?),
+        ValueType::I64 => Value::I64(u.arbitrary()?),
+        ValueType::F32 => Value::F32(FloatBits32::from_bits(u.arbitrary()?)),
+        ValueType::F64 => Value::F64(FloatBits64::from_bits(u.arbitrary()?)),
+        _ => return Err(arbitrary::Error::IncorrectFormat),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_target_on_generated_modules() {
+        for seed in 0..32u8 {
+            let data: Vec<u8> =
+                (0..2048u32).map(|i| (i as u8).wrapping_mul(seed | 1) ^ seed).collect();
+            let module = ArbitraryModule::arbitrary(&mut Unstructured::new(&data)).unwrap();
+            decode_module_fuzz(&module.to_binary().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_targets_accept_malformed_input() {
+        for data in [&[][..], b"\0asm", b"\0asm\x01\0\0\0\x01\xFF", &[0xFF; 64]] {
+            decode_module_fuzz(data);
+            execute_fuzz(data);
+        }
+    }
+}
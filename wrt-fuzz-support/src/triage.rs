@@ -0,0 +1,198 @@
+//! Crash triage: reproducing and minimizing failing inputs
+//!
+//! [`minimize`] shrinks an input while a predicate keeps failing on it. It
+//! first drops whole sections of a module binary, then shrinks the payload of
+//! each remaining section while keeping its size field consistent, and
+//! finally removes arbitrary byte ranges with delta debugging. The section
+//! passes let it cut large modules quickly; the byte pass makes it work on
+//! inputs that are not modules at all.
+//!
+//! [`panics`] and [`minimize_crash`] catch panics, so they need the input to
+//! be replayed in a build that unwinds on panic, such as a test. Fuzzing
+//! builds usually abort on panic instead.
+
+use core::panic::AssertUnwindSafe;
+use std::panic;
+
+use wrt_decoder::parallel_decoder::SectionIndex;
+use wrt_format::write_leb128_u32;
+
+/// Length of the magic number and version that start a module binary
+const HEADER_LEN: usize = 8;
+
+/// Check whether `target` panics on `input`
+pub fn panics(target: impl Fn(&[u8]), input: &[u8]) -> bool {
+    panic::catch_unwind(AssertUnwindSafe(|| target(input))).is_err()
+}
+
+/// Minimize a crashing input of a fuzz target
+///
+/// The panic hook is silenced while the target is replayed, so that only
+/// the minimized input is left for the caller to report. Returns `input`
+/// unchanged if the target does not panic on it.
+pub fn minimize_crash(target: fn(&[u8]), input: &[u8]) -> Vec<u8> {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let minimized = minimize(input, |candidate| panics(target, candidate));
+    panic::set_hook(hook);
+    minimized
+}
+
+/// Shrink `input` while `failing` keeps returning true for it
+///
+/// The result is 1-minimal with respect to single bytes: removing any one
+/// more byte makes `failing` return false. Returns `input` unchanged if
+/// `failing` does not hold for it.
+pub fn minimize(input: &[u8], mut failing: impl FnMut(&[u8]) -> bool) -> Vec<u8> {
+    let mut current = input.to_vec();
+    if !failing(&current) {
+        return current;
+    }
+
+    remove_sections(&mut current, &mut failing);
+    shrink_section_payloads(&mut current, &mut failing);
+    current = ddmin(current, &mut failing);
+    current
+}
+
+/// Byte ranges of the sections of `binary`, each including its id and size
+fn sections(binary: &[u8]) -> Option<Vec<(usize, usize)>> {
+    let index = SectionIndex::scan(binary).ok()?;
+    let mut start = HEADER_LEN;
+    Some(
+        index
+            .entries()
+            .iter()
+            .map(|entry| {
+                let section = (start, entry.range.end);
+                start = entry.range.end;
+                section
+            })
+            .collect(),
+    )
+}
+
+/// Drop whole sections, last first, while the input keeps failing
+fn remove_sections(current: &mut Vec<u8>, failing: &mut impl FnMut(&[u8]) -> bool) {
+    let Some(sections) = sections(current) else {
+        return;
+    };
+    for (start, end) in sections.into_iter().rev() {
+        let mut candidate = current[..start].to_vec();
+        candidate.extend_from_slice(&current[end..]);
+        if failing(&candidate) {
+            *current = candidate;
+        }
+    }
+}
+
+/// Shrink the payload of each section, rewriting its size field to match
+fn shrink_section_payloads(current: &mut Vec<u8>, failing: &mut impl FnMut(&[u8]) -> bool) {
+    let Some(count) = sections(current).map(|sections| sections.len()) else {
+        return;
+    };
+
+    for section in 0..count {
+        let Some((start, end)) =
+            sections(current).and_then(|sections| sections.get(section).copied())
+        else {
+            return;
+        };
+        let id = current[start];
+        let (_, size_len) = match wrt_format::read_leb128_u32(current, start + 1) {
+            Ok(read) => read,
+            Err(_) => return,
+        };
+        let prefix = current[..start].to_vec();
+        let suffix = current[end..].to_vec();
+        let rebuild = |payload: &[u8]| {
+            let mut binary = prefix.clone();
+            binary.push(id);
+            binary.extend(write_leb128_u32(payload.len() as u32));
+            binary.extend_from_slice(payload);
+            binary.extend_from_slice(&suffix);
+            binary
+        };
+
+        let payload = current[start + 1 + size_len..end].to_vec();
+        let payload = ddmin(payload, &mut |payload: &[u8]| failing(&rebuild(payload)));
+        *current = rebuild(&payload);
+    }
+}
+
+/// Delta debugging over the bytes of `input`, which must be failing
+fn ddmin(mut input: Vec<u8>, failing: &mut impl FnMut(&[u8]) -> bool) -> Vec<u8> {
+    let mut chunks = 2;
+    while !input.is_empty() {
+        let chunk_len = input.len().div_ceil(chunks);
+        let mut reduced = false;
+
+        let mut start = 0;
+        while start < input.len() {
+            let end = (start + chunk_len).min(input.len());
+            let mut candidate = input[..start].to_vec();
+            candidate.extend_from_slice(&input[end..]);
+            if failing(&candidate) {
+                input = candidate;
+                reduced = true;
+            } else {
+                start = end;
+            }
+        }
+
+        if reduced {
+            chunks = chunks.saturating_sub(1).max(2);
+        } else if chunk_len == 1 {
+            break;
+        } else {
+            chunks = (chunks * 2).min(input.len());
+        }
+    }
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A module with an empty type section, a custom section and an empty
+    /// function section
+    const MODULE: &[u8] = &[
+        0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, // header
+        0x01, 0x01, 0x00, // type section
+        0x00, 0x05, 0x01, b'x', 0xDE, 0xAD, 0x42, // custom section "x"
+        0x03, 0x01, 0x00, // function section
+    ];
+
+    #[test]
+    fn test_minimize_bytes() {
+        let input: Vec<u8> = (0..=255).collect();
+        let minimized = minimize(&input, |candidate| {
+            candidate.contains(&7) && candidate.contains(&200)
+        });
+        assert_eq!(minimized, [7, 200]);
+    }
+
+    #[test]
+    fn test_minimize_keeps_section_sizes_consistent() {
+        let minimized = minimize(MODULE, |candidate| {
+            // Fails on well-formed modules that still contain 0x42
+            SectionIndex::scan(candidate).is_ok() && candidate.contains(&0x42)
+        });
+        assert_eq!(
+            minimized,
+            [0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, 0x00, 0x01, 0x42]
+        );
+    }
+
+    #[test]
+    fn test_minimize_crash() {
+        fn target(data: &[u8]) {
+            assert!(!data.windows(2).any(|pair| pair == [0xDE, 0xAD]));
+        }
+
+        assert!(panics(target, MODULE));
+        assert_eq!(minimize_crash(target, MODULE), [0xDE, 0xAD]);
+        assert_eq!(minimize_crash(target, &[1, 2, 3]), [1, 2, 3]);
+    }
+}
@@ -64,6 +64,9 @@
 pub mod function;
 pub mod host;
 pub mod prelude;
+/// Assertion intrinsics for guest-side tests
+#[cfg(feature = "std")]
+pub mod test_intrinsics;
 
 // Agent C deliverables - Enhanced Host Integration
 /// Bounded host integration with memory constraints
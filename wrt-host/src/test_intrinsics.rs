@@ -0,0 +1,366 @@
+//! Assertion intrinsics for guest-side tests.
+//!
+//! A test harness registers these host functions so test modules can state
+//! expectations inside the guest and have the engine report them as
+//! structured results. They are imported from `wrt:test`:
+//!
+//! ```text
+//! (func $assert_eq_i32 (param $id i32) (param $expected i32) (param $actual i32))
+//! (func $assert_trap_next (param $id i32))
+//! ```
+//!
+//! `id` is chosen by the guest, typically the source line, and identifies the
+//! assertion in the report. A failing `assert_eq_i32` traps to end the test.
+//! `assert_trap_next` declares that the running test must trap after this
+//! point; the test then passes if it traps and fails if it returns.
+//!
+//! The harness runs every export whose name starts with
+//! [`TEST_EXPORT_PREFIX`] between [`TestHarness::begin_test`] and
+//! [`TestHarness::finish_test`].
+
+use alloc::sync::Arc;
+use core::{
+    any::Any,
+    fmt,
+};
+use std::sync::Mutex;
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_foundation::values::Value;
+
+use crate::{
+    callback::CallbackRegistry,
+    function::CloneableFn,
+};
+
+/// Module name of the test intrinsics
+pub const TEST_MODULE: &str = "wrt:test";
+
+/// Function name of the `i32` equality assertion
+pub const ASSERT_EQ_I32: &str = "assert_eq_i32";
+
+/// Function name of the trap expectation
+pub const ASSERT_TRAP_NEXT: &str = "assert_trap_next";
+
+/// Exports with this name prefix are run as tests
+pub const TEST_EXPORT_PREFIX: &str = "test_";
+
+/// Whether an export is a guest test
+#[must_use]
+pub fn is_test_export(name: &str) -> bool {
+    name.starts_with(TEST_EXPORT_PREFIX)
+}
+
+/// Reason a guest test failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestFailure {
+    /// An `assert_eq_i32` compared unequal values
+    NotEqual {
+        /// Guest identifier of the assertion
+        id:       u32,
+        /// Expected value
+        expected: i32,
+        /// Actual value
+        actual:   i32,
+    },
+    /// The test returned after an `assert_trap_next`
+    MissingTrap {
+        /// Guest identifier of the assertion
+        id: u32,
+    },
+    /// The test trapped without expecting it, with the trap message
+    UnexpectedTrap(&'static str),
+}
+
+impl fmt::Display for TestFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotEqual {
+                id,
+                expected,
+                actual,
+            } => write!(f, "assertion {id}: expected {expected}, got {actual}"),
+            Self::MissingTrap { id } => write!(f, "assertion {id}: expected a trap"),
+            Self::UnexpectedTrap(message) => write!(f, "unexpected trap: {message}"),
+        }
+    }
+}
+
+/// Result of a single guest test
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestReport {
+    /// Export name of the test
+    pub name:       String,
+    /// Number of assertions the test made
+    pub assertions: u32,
+    /// Why the test failed, `None` if it passed
+    pub failure:    Option<TestFailure>,
+}
+
+impl TestReport {
+    /// Whether the test passed
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+/// State of the test that is currently running
+#[derive(Debug)]
+struct RunningTest {
+    name:        String,
+    assertions:  u32,
+    failure:     Option<TestFailure>,
+    /// Identifier of a pending `assert_trap_next`
+    expect_trap: Option<u32>,
+}
+
+#[derive(Debug, Default)]
+struct HarnessState {
+    running: Option<RunningTest>,
+    reports: Vec<TestReport>,
+}
+
+/// Host side of the test intrinsics, collecting the results of guest tests
+///
+/// Clones share their state.
+#[derive(Debug, Clone, Default)]
+pub struct TestHarness {
+    state: Arc<Mutex<HarnessState>>,
+}
+
+impl TestHarness {
+    /// Create a harness without any results
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the `wrt:test` host functions
+    pub fn register(&self, registry: &mut CallbackRegistry) {
+        let harness = self.clone();
+        registry.register_host_function(
+            TEST_MODULE,
+            ASSERT_EQ_I32,
+            CloneableFn::new_with_args(move |_: &mut dyn Any, args: Vec<Value>| {
+                let [Value::I32(id), Value::I32(expected), Value::I32(actual)] = *args else {
+                    return Err(Error::runtime_type_mismatch(
+                        "assert_eq_i32 expects (i32, i32, i32) arguments",
+                    ));
+                };
+                harness.assert_eq_i32(id as u32, expected, actual)?;
+                Ok(Vec::new())
+            }),
+        );
+
+        let harness = self.clone();
+        registry.register_host_function(
+            TEST_MODULE,
+            ASSERT_TRAP_NEXT,
+            CloneableFn::new_with_args(move |_: &mut dyn Any, args: Vec<Value>| {
+                let [Value::I32(id)] = *args else {
+                    return Err(Error::runtime_type_mismatch(
+                        "assert_trap_next expects an i32 argument",
+                    ));
+                };
+                harness.assert_trap_next(id as u32)?;
+                Ok(Vec::new())
+            }),
+        );
+    }
+
+    fn with_running<T>(&self, f: impl FnOnce(&mut RunningTest) -> Result<T>) -> Result<T> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| Error::poisoned_lock("Test harness lock poisoned"))?;
+        let test = state
+            .running
+            .as_mut()
+            .ok_or_else(|| Error::runtime_invalid_state("No guest test is running"))?;
+        f(test)
+    }
+
+    /// Start recording the assertions of a test
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if another test is still running.
+    pub fn begin_test(&self, name: &str) -> Result<()> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| Error::poisoned_lock("Test harness lock poisoned"))?;
+        if state.running.is_some() {
+            return Err(Error::runtime_invalid_state(
+                "A guest test is already running",
+            ));
+        }
+        state.running = Some(RunningTest {
+            name:        name.to_string(),
+            assertions:  0,
+            failure:     None,
+            expect_trap: None,
+        });
+        Ok(())
+    }
+
+    /// Check that two `i32` values are equal
+    ///
+    /// # Errors
+    ///
+    /// Returns a trap if the values differ, or an error if no test is running.
+    pub fn assert_eq_i32(&self, id: u32, expected: i32, actual: i32) -> Result<()> {
+        self.with_running(|test| {
+            test.assertions = test.assertions.saturating_add(1);
+            if expected == actual {
+                return Ok(());
+            }
+            test.failure.get_or_insert(TestFailure::NotEqual {
+                id,
+                expected,
+                actual,
+            });
+            Err(Error::runtime_trap("Guest assertion failed"))
+        })
+    }
+
+    /// Expect the running test to trap after this point
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no test is running.
+    pub fn assert_trap_next(&self, id: u32) -> Result<()> {
+        self.with_running(|test| {
+            test.assertions = test.assertions.saturating_add(1);
+            test.expect_trap = Some(id);
+            Ok(())
+        })
+    }
+
+    /// Finish the running test with the outcome of its export call
+    ///
+    /// A failed assertion fails the test regardless of the outcome. Otherwise
+    /// the test passes if it trapped exactly when a trap was expected.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no test is running.
+    pub fn finish_test(&self, outcome: &Result<Vec<Value>>) -> Result<TestReport> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| Error::poisoned_lock("Test harness lock poisoned"))?;
+        let test = state
+            .running
+            .take()
+            .ok_or_else(|| Error::runtime_invalid_state("No guest test is running"))?;
+
+        let failure = test.failure.or(match (test.expect_trap, outcome) {
+            (Some(id), Ok(_)) => Some(TestFailure::MissingTrap { id }),
+            (None, Err(error)) => Some(TestFailure::UnexpectedTrap(error.message)),
+            _ => None,
+        });
+        let report = TestReport {
+            name: test.name,
+            assertions: test.assertions,
+            failure,
+        };
+        state.reports.push(report.clone());
+        Ok(report)
+    }
+
+    /// Reports of all finished tests, in the order they ran
+    #[must_use]
+    pub fn reports(&self) -> Vec<TestReport> {
+        self.state.lock().map(|state| state.reports.clone()).unwrap_or_default()
+    }
+
+    /// Number of passed and failed tests
+    #[must_use]
+    pub fn summary(&self) -> (usize, usize) {
+        let reports = self.reports();
+        let passed = reports.iter().filter(|report| report.passed()).count();
+        (passed, reports.len() - passed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(registry: &CallbackRegistry, function: &str, args: Vec<Value>) -> Result<Vec<Value>> {
+        let mut engine = ();
+        registry.call_host_function(&mut engine, TEST_MODULE, function, args)
+    }
+
+    #[test]
+    fn test_assertions_through_registry() {
+        let harness = TestHarness::new();
+        let mut registry = CallbackRegistry::new();
+        harness.register(&mut registry);
+        assert!(registry.has_host_function(TEST_MODULE, ASSERT_EQ_I32));
+        assert!(registry.has_host_function(TEST_MODULE, ASSERT_TRAP_NEXT));
+
+        // Assertions outside of a test are rejected
+        let equal = vec![Value::I32(1), Value::I32(5), Value::I32(5)];
+        assert!(call(&registry, ASSERT_EQ_I32, equal.clone()).is_err());
+
+        harness.begin_test("test_add").unwrap();
+        assert!(harness.begin_test("test_other").is_err());
+        call(&registry, ASSERT_EQ_I32, equal).unwrap();
+        assert!(call(&registry, ASSERT_EQ_I32, vec![Value::I32(1)]).is_err());
+        let outcome = call(
+            &registry,
+            ASSERT_EQ_I32,
+            vec![Value::I32(9), Value::I32(5), Value::I32(4)],
+        );
+        assert!(outcome.is_err());
+
+        let report = harness.finish_test(&outcome).unwrap();
+        assert_eq!(report.name, "test_add");
+        assert_eq!(report.assertions, 2);
+        assert_eq!(
+            report.failure,
+            Some(TestFailure::NotEqual {
+                id:       9,
+                expected: 5,
+                actual:   4,
+            })
+        );
+        assert_eq!(
+            report.failure.unwrap().to_string(),
+            "assertion 9: expected 5, got 4"
+        );
+    }
+
+    #[test]
+    fn test_trap_expectations() {
+        let harness = TestHarness::new();
+        let trap = Err(Error::runtime_trap("unreachable executed"));
+
+        harness.begin_test("test_traps").unwrap();
+        harness.assert_trap_next(3).unwrap();
+        assert!(harness.finish_test(&trap).unwrap().passed());
+
+        harness.begin_test("test_returns").unwrap();
+        harness.assert_trap_next(4).unwrap();
+        let report = harness.finish_test(&Ok(Vec::new())).unwrap();
+        assert_eq!(report.failure, Some(TestFailure::MissingTrap { id: 4 }));
+
+        harness.begin_test("test_unexpected").unwrap();
+        let report = harness.finish_test(&trap).unwrap();
+        assert_eq!(
+            report.failure,
+            Some(TestFailure::UnexpectedTrap("unreachable executed"))
+        );
+
+        assert!(harness.finish_test(&Ok(Vec::new())).is_err());
+        assert_eq!(harness.summary(), (1, 2));
+        assert!(is_test_export("test_traps"));
+        assert!(!is_test_export("run"));
+    }
+}
@@ -78,6 +78,10 @@
 // WebAssembly 3.0 Branch Hinting operations
 pub mod branch_hinting;
 
+// Include verification module conditionally, but exclude during coverage builds
+#[cfg(all(not(coverage), any(doc, feature = "kani")))]
+pub mod verify;
+
 // Re-export commonly used types
 pub use control_ops::BranchTarget;
 // Test module for arithmetic operations
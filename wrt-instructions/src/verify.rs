@@ -0,0 +1,142 @@
+//! Verification module for the Kani model checker
+//!
+//! This module contains proofs that the memory load and store instructions
+//! compute their effective address without wrapping and only access memory
+//! within its bounds.
+
+#[cfg(all(kani, feature = "std"))]
+pub mod proofs {
+    use core::cell::Cell;
+
+    use crate::{
+        memory_ops::{
+            MemoryLoad,
+            MemoryOperations,
+            MemoryStore,
+        },
+        prelude::*,
+    };
+
+    /// Size of the memory the proofs run against
+    const MEMORY_SIZE: usize = 16;
+
+    /// Memory of [`MEMORY_SIZE`] bytes that records the accesses made to it
+    struct RecordingMemory {
+        bytes:       [u8; MEMORY_SIZE],
+        last_access: Cell<Option<(u32, u32)>>,
+    }
+
+    impl RecordingMemory {
+        fn new() -> Self {
+            Self {
+                bytes:       kani::any(),
+                last_access: Cell::new(None),
+            }
+        }
+
+        fn range(&self, offset: u32, len: u32) -> Result<core::ops::Range<usize>> {
+            self.last_access.set(Some((offset, len)));
+            let start = offset as usize;
+            match start.checked_add(len as usize) {
+                Some(end) if end <= MEMORY_SIZE => Ok(start..end),
+                _ => Err(Error::memory_out_of_bounds("Memory access out of bounds")),
+            }
+        }
+    }
+
+    impl MemoryOperations for RecordingMemory {
+        fn read_bytes(&self, offset: u32, len: u32) -> Result<Vec<u8>> {
+            let range = self.range(offset, len)?;
+            Ok(self.bytes[range].to_vec())
+        }
+
+        fn write_bytes(&mut self, offset: u32, bytes: &[u8]) -> Result<()> {
+            let range = self.range(offset, bytes.len() as u32)?;
+            self.bytes[range].copy_from_slice(bytes);
+            Ok(())
+        }
+
+        fn size_in_bytes(&self) -> Result<usize> {
+            Ok(MEMORY_SIZE)
+        }
+
+        fn grow(&mut self, _bytes: usize) -> Result<()> {
+            Err(Error::memory_error("Memory cannot grow"))
+        }
+
+        fn fill(&mut self, offset: u32, value: u8, size: u32) -> Result<()> {
+            let range = self.range(offset, size)?;
+            self.bytes[range].fill(value);
+            Ok(())
+        }
+
+        fn copy(&mut self, dest: u32, src: u32, size: u32) -> Result<()> {
+            let src = self.range(src, size)?;
+            let dest = self.range(dest, size)?;
+            self.bytes.copy_within(src, dest.start);
+            Ok(())
+        }
+    }
+
+    /// Verify that a load accesses exactly the bytes at its effective address
+    /// and fails instead of wrapping the address around
+    #[cfg_attr(kani, kani::proof)]
+    #[cfg_attr(kani, kani::unwind(9))]
+    pub fn verify_load_effective_address() {
+        let memory = RecordingMemory::new();
+        let base: i32 = kani::any();
+        let offset: u32 = kani::any();
+        let align: u32 = kani::any();
+        let load = if kani::any() {
+            MemoryLoad::i32_legacy(offset, align)
+        } else {
+            MemoryLoad::i64_load16(offset, align, kani::any())
+        };
+
+        let result = load.execute(&memory, &Value::I32(base));
+        let effective = u64::from(base as u32) + u64::from(offset);
+        if effective > u64::from(u32::MAX) {
+            assert!(result.is_err(), "Overflowing address must trap");
+            assert!(memory.last_access.get().is_none());
+        }
+        if result.is_ok() {
+            let (address, len) = memory.last_access.get().expect("Load must read memory");
+            assert_eq!(u64::from(address), effective);
+            assert_eq!(len, load.width / 8);
+            assert!(address as usize + len as usize <= MEMORY_SIZE);
+            assert!(align <= 1 || address % align == 0);
+        }
+    }
+
+    /// Verify that a store only writes within the memory, at its effective
+    /// address, and that loading from there returns the stored value
+    #[cfg_attr(kani, kani::proof)]
+    #[cfg_attr(kani, kani::unwind(9))]
+    pub fn verify_store_stays_in_bounds() {
+        let mut memory = RecordingMemory::new();
+        let before = memory.bytes;
+        let base: i32 = kani::any();
+        let offset: u32 = kani::any();
+        let value: i32 = kani::any();
+
+        let store = MemoryStore::i32(offset, 1);
+        let result = store.execute(&mut memory, &Value::I32(base), &Value::I32(value));
+        let effective = u64::from(base as u32) + u64::from(offset);
+        match result {
+            Ok(()) => {
+                let (address, len) = memory.last_access.get().expect("Store must write memory");
+                assert_eq!(u64::from(address), effective);
+                assert_eq!(len, 4);
+                for index in 0..MEMORY_SIZE {
+                    let written = index >= address as usize && index < address as usize + 4;
+                    assert!(written || memory.bytes[index] == before[index]);
+                }
+
+                let load = MemoryLoad::i32_legacy(offset, 1);
+                let loaded = load.execute(&memory, &Value::I32(base));
+                assert!(matches!(loaded, Ok(Value::I32(read)) if read == value));
+            },
+            Err(_) => assert_eq!(memory.bytes, before, "Failed store must not write"),
+        }
+    }
+}
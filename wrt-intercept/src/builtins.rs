@@ -369,11 +369,16 @@ fn test_intercept_context() {
     #[cfg(feature = "std")]
     #[test]
     fn test_builtin_serialization() {
+        use wrt_foundation::float_repr::{
+            FloatBits32,
+            FloatBits64,
+        };
+
         let values = vec![
             ComponentValue::S32(123),
             ComponentValue::S64(456),
-            ComponentValue::F32(1.23),
-            ComponentValue::F64(4.56),
+            ComponentValue::F32(FloatBits32::from_float(1.23)),
+            ComponentValue::F64(FloatBits64::from_float(4.56)),
         ];
 
         let serialized_bytes = BuiltinSerialization::serialize(&values).unwrap();
@@ -390,14 +395,14 @@ fn test_builtin_serialization() {
         if let (ComponentValue::F32(a), ComponentValue::F32(b)) =
             (&deserialized_values[2], &values[2])
         {
-            assert!((a - b).abs() < f32::EPSILON);
+            assert!((a.value() - b.value()).abs() < f32::EPSILON);
         } else {
             panic!("Expected F32 values");
         }
         if let (ComponentValue::F64(a), ComponentValue::F64(b)) =
             (&deserialized_values[3], &values[3])
         {
-            assert!((a - b).abs() < f64::EPSILON);
+            assert!((a.value() - b.value()).abs() < f64::EPSILON);
         } else {
             panic!("Expected F64 values");
         }
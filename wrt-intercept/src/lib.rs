@@ -154,6 +154,24 @@ fn should_bypass(&self) -> bool {
         false
     }
 
+    /// Determines if the normal execution of a particular call should be
+    /// bypassed
+    ///
+    /// Strategies that serve some functions themselves override this to
+    /// decide per call; it defaults to [`Self::should_bypass`].
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - Identifier of the target component or host
+    /// * `function` - Name of the function being called
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether to bypass the call
+    fn should_bypass_call(&self, _target: &str, _function: &str) -> bool {
+        self.should_bypass()
+    }
+
     /// Determines if the strategy should intercept canonical ABI operations
     ///
     /// # Returns
@@ -165,10 +183,15 @@ fn should_intercept_canonical(&self) -> bool {
 
     /// Intercepts a lift operation in the canonical ABI
     ///
+    /// Serialized values use the canonical ABI representation: scalars are
+    /// their little-endian bytes, strings are their encoded contents, and all
+    /// other types are the flat bytes of the value as laid out in memory.
+    ///
     /// # Arguments
     ///
     /// * `ty` - The value type being lifted
-    /// * `addr` - The memory address from which to lift
+    /// * `addr` - The memory address from which to lift (for strings, the
+    ///   address of the `(ptr, len)` pair)
     /// * `memory_bytes` - The memory bytes to read from
     ///
     /// # Returns
@@ -187,11 +210,15 @@ fn intercept_lift(
 
     /// Intercepts a lower operation in the canonical ABI
     ///
+    /// `value_data` uses the same serialization as
+    /// [`intercept_lift`](Self::intercept_lift).
+    ///
     /// # Arguments
     ///
     /// * `value_type` - The type of the value being lowered
     /// * `value_data` - The serialized value being lowered
-    /// * `addr` - The memory address to which to lower
+    /// * `addr` - The memory address to which to lower (for strings, the
+    ///   already allocated destination of the encoded contents)
     /// * `memory_bytes` - The memory bytes to write to
     ///
     /// # Returns
@@ -209,6 +236,68 @@ fn intercept_lower(
         Ok(false)
     }
 
+    /// Intercepts a lift operation in the canonical ABI, given the types
+    /// nested in the lifted type
+    ///
+    /// The type references in `ty`, such as the element type of a list, and
+    /// in the types of `types` are indices into `types`. The default
+    /// implementation ignores the nested types and calls
+    /// [`intercept_lift`](Self::intercept_lift).
+    ///
+    /// # Arguments
+    ///
+    /// * `ty` - The value type being lifted
+    /// * `types` - The types nested in `ty`
+    /// * `addr` - The memory address from which to lift
+    /// * `memory_bytes` - The memory bytes to read from
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<Vec<u8>>>` - Serialized value if lifting was handled,
+    ///   None if it should proceed normally
+    #[cfg(feature = "std")]
+    fn intercept_lift_with_types(
+        &self,
+        ty: &ValType<wrt_foundation::NoStdProvider<64>>,
+        _types: &[ValType<wrt_foundation::NoStdProvider<64>>],
+        addr: u32,
+        memory_bytes: &[u8],
+    ) -> Result<Option<Vec<u8>>> {
+        self.intercept_lift(ty, addr, memory_bytes)
+    }
+
+    /// Intercepts a lower operation in the canonical ABI, given the types
+    /// nested in the lowered type
+    ///
+    /// `types` is interpreted as for
+    /// [`intercept_lift_with_types`](Self::intercept_lift_with_types). The
+    /// default implementation ignores the nested types and calls
+    /// [`intercept_lower`](Self::intercept_lower).
+    ///
+    /// # Arguments
+    ///
+    /// * `value_type` - The type of the value being lowered
+    /// * `types` - The types nested in `value_type`
+    /// * `value_data` - The serialized value being lowered
+    /// * `addr` - The memory address to which to lower
+    /// * `memory_bytes` - The memory bytes to write to
+    ///
+    /// # Returns
+    ///
+    /// * `Result<bool>` - True if the lowering was handled, false if it should
+    ///   proceed normally
+    #[cfg(feature = "std")]
+    fn intercept_lower_with_types(
+        &self,
+        value_type: &ValType<wrt_foundation::NoStdProvider<64>>,
+        _types: &[ValType<wrt_foundation::NoStdProvider<64>>],
+        value_data: &[u8],
+        addr: u32,
+        memory_bytes: &mut [u8],
+    ) -> Result<bool> {
+        self.intercept_lower(value_type, value_data, addr, memory_bytes)
+    }
+
     /// Determines if the strategy should intercept component function calls
     ///
     /// # Returns
@@ -333,6 +422,30 @@ fn after_start(
         Ok(None)
     }
 
+    /// Called before an instance is created, once its imports are resolved
+    ///
+    /// # Arguments
+    ///
+    /// * `instance` - The component, its digest and the import bindings of the
+    ///   instance
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - An error vetoes the instantiation
+    #[cfg(feature = "std")]
+    fn on_instantiate(&self, _instance: &InstanceLifecycle<'_>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called when an instance is destroyed
+    ///
+    /// # Arguments
+    ///
+    /// * `instance` - The component, its digest and the import bindings the
+    ///   instance had
+    #[cfg(feature = "std")]
+    fn on_destroy(&self, _instance: &InstanceLifecycle<'_>) {}
+
     /// Clones this strategy
     ///
     /// # Returns
@@ -505,7 +618,7 @@ pub fn intercept_call<F>(
             modified_args = strategy.before_call(&self.name, target, function, &modified_args)?;
 
             // Early return if strategy bypasses execution
-            if strategy.should_bypass() {
+            if strategy.should_bypass_call(target, function) {
                 return Ok(modified_args);
             }
         }
@@ -521,6 +634,37 @@ pub fn intercept_call<F>(
         result
     }
 
+    /// Notifies all strategies of an instance about to be created
+    ///
+    /// Strategies are notified in order, and the first to return an error
+    /// vetoes the instantiation; later strategies are not notified.
+    ///
+    /// # Arguments
+    ///
+    /// * `instance` - The instance about to be created
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - The error of the vetoing strategy, if any
+    #[cfg(feature = "std")]
+    pub fn on_instantiate(&self, instance: &InstanceLifecycle<'_>) -> Result<()> {
+        self.strategies
+            .iter()
+            .try_for_each(|strategy| strategy.on_instantiate(instance))
+    }
+
+    /// Notifies all strategies, in reverse order, of a destroyed instance
+    ///
+    /// # Arguments
+    ///
+    /// * `instance` - The destroyed instance
+    #[cfg(feature = "std")]
+    pub fn on_destroy(&self, instance: &InstanceLifecycle<'_>) {
+        for strategy in self.strategies.iter().rev() {
+            strategy.on_destroy(instance);
+        }
+    }
+
     /// Gets the name of this interceptor
     ///
     /// # Returns
@@ -654,6 +798,32 @@ pub fn apply_modifications(
     }
 }
 
+/// Import of an instance and what it is bound to
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportBinding {
+    /// Flattened name of the import
+    pub import:   String,
+    /// Component whose instance provides the import, or `None` for the host
+    pub provider: Option<String>,
+    /// Export or host function the import is bound to
+    pub export:   String,
+}
+
+/// Instance passed to the lifecycle hooks of a strategy
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy)]
+pub struct InstanceLifecycle<'a> {
+    /// Component the instance is created from
+    pub component:   &'a str,
+    /// Identifier of the instance
+    pub instance_id: u32,
+    /// Checksum of the component binary
+    pub digest:      u32,
+    /// Binding of every import of the instance
+    pub bindings:    &'a [ImportBinding],
+}
+
 /// Result of an interception operation
 #[derive(Debug, Clone)]
 pub struct InterceptionResult {
@@ -1063,8 +1233,8 @@ fn process_results(
             &self,
             _component_name: &str,
             _func_name: &str,
-            _args: &[ComponentValue],
-            _results: &[ComponentValue],
+            _args: &[ComponentValue<wrt_foundation::NoStdProvider<64>>],
+            _results: &[ComponentValue<wrt_foundation::NoStdProvider<64>>],
         ) -> Result<Option<Vec<Modification>>> {
             if self.modify_result {
                 Ok(Some(vec![Modification::Replace {
@@ -1077,6 +1247,95 @@ fn process_results(
         }
     }
 
+    /// Records lifecycle events and vetoes instances of one component
+    struct LifecycleMonitor {
+        vetoed: &'static str,
+        events: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl LinkInterceptorStrategy for LifecycleMonitor {
+        fn before_call(
+            &self,
+            _source: &str,
+            _target: &str,
+            _function: &str,
+            args: &[Value],
+        ) -> Result<Vec<Value>> {
+            Ok(args.to_vec())
+        }
+
+        fn after_call(
+            &self,
+            _source: &str,
+            _target: &str,
+            _function: &str,
+            _args: &[Value],
+            result: Result<Vec<Value>>,
+        ) -> Result<Vec<Value>> {
+            result
+        }
+
+        fn on_instantiate(&self, instance: &InstanceLifecycle<'_>) -> Result<()> {
+            self.events.lock().unwrap().push(format!(
+                "create {}#{} {}",
+                instance.component,
+                instance.instance_id,
+                instance.bindings.len()
+            ));
+            if instance.component == self.vetoed {
+                return Err(Error::runtime_execution_error("Instantiation vetoed"));
+            }
+            Ok(())
+        }
+
+        fn on_destroy(&self, instance: &InstanceLifecycle<'_>) {
+            self.events.lock().unwrap().push(format!(
+                "destroy {}#{}",
+                instance.component, instance.instance_id
+            ));
+        }
+
+        fn clone_strategy(&self) -> Arc<dyn LinkInterceptorStrategy> {
+            Arc::new(Self {
+                vetoed: self.vetoed,
+                events: self.events.clone(),
+            })
+        }
+    }
+
+    #[test]
+    fn test_lifecycle_hooks() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut interceptor = LinkInterceptor::new("monitor");
+        interceptor.add_strategy(Arc::new(LifecycleMonitor {
+            vetoed: "untrusted",
+            events: events.clone(),
+        }));
+
+        let bindings = [ImportBinding {
+            import:   "log".to_string(),
+            provider: None,
+            export:   "log".to_string(),
+        }];
+        let mut instance = InstanceLifecycle {
+            component:   "app",
+            instance_id: 1,
+            digest:      0x1234,
+            bindings:    &bindings,
+        };
+        interceptor.on_instantiate(&instance).unwrap();
+        interceptor.on_destroy(&instance);
+
+        instance.component = "untrusted";
+        instance.instance_id = 2;
+        assert!(interceptor.on_instantiate(&instance).is_err());
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            ["create app#1 1", "destroy app#1", "create untrusted#2 1"]
+        );
+    }
+
     #[test]
     fn test_interceptor_passthrough() {
         let strategy = Arc::new(TestStrategy {
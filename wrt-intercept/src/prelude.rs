@@ -108,7 +108,12 @@
 };
 // Conditional imports
 #[cfg(feature = "std")]
-pub use crate::strategies::StatisticsStrategy;
+pub use crate::strategies::{
+    FaultInjectionStrategy,
+    RedactionStrategy,
+    StatisticsStrategy,
+    VirtualClockStrategy,
+};
 // Re-export from this crate
 pub use crate::{
     // Builtin interceptors
@@ -0,0 +1,488 @@
+//! Fault-injection strategy for intercepting component function calls
+//!
+//! This strategy deliberately fails, delays, or corrupts matching function
+//! calls so that the error handling paths of components can be exercised
+//! without modifying guest code. Faults are triggered either on specific
+//! invocations or with a configurable probability driven by a seeded,
+//! deterministic pseudo-random generator, which keeps test runs reproducible.
+//!
+//! Note: This strategy requires the `std` feature.
+
+#[cfg(feature = "std")]
+use std::{
+    sync::{
+        Arc,
+        Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+#[cfg(feature = "std")]
+use wrt_error::{
+    Error,
+    Result,
+};
+#[cfg(feature = "std")]
+use wrt_foundation::values::{
+    FloatBits32,
+    FloatBits64,
+};
+
+#[cfg(feature = "std")]
+use crate::{
+    prelude::{
+        str,
+        Debug,
+        Value,
+    },
+    LinkInterceptorStrategy,
+};
+
+/// Selects the function calls a fault rule applies to
+///
+/// A `None` field matches any value.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FaultMatcher {
+    /// Identifier of the calling component
+    pub source:   Option<String>,
+    /// Identifier of the target component or host
+    pub target:   Option<String>,
+    /// Name of the function being called
+    pub function: Option<String>,
+}
+
+#[cfg(feature = "std")]
+impl FaultMatcher {
+    /// Create a matcher that matches every call
+    #[must_use]
+    pub fn any() -> Self {
+        Self::default()
+    }
+
+    /// Create a matcher for a function on a specific target
+    #[must_use]
+    pub fn function(target: &str, function: &str) -> Self {
+        Self {
+            source:   None,
+            target:   Some(target.to_string()),
+            function: Some(function.to_string()),
+        }
+    }
+
+    /// Check whether a call matches this matcher
+    #[must_use]
+    pub fn matches(&self, source: &str, target: &str, function: &str) -> bool {
+        self.source.as_deref().map_or(true, |s| s == source)
+            && self.target.as_deref().map_or(true, |t| t == target)
+            && self.function.as_deref().map_or(true, |f| f == function)
+    }
+}
+
+/// Determines on which matching invocations a fault fires
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultTrigger {
+    /// Fire on every matching call
+    Always,
+    /// Fire only on the Nth matching call (1-based)
+    OnNthCall(u64),
+    /// Fire on every Nth matching call (N, 2N, 3N, ...)
+    EveryNthCall(u64),
+    /// Fire with a probability of `numerator / denominator`
+    Probability {
+        /// Numerator of the firing probability
+        numerator:   u32,
+        /// Denominator of the firing probability
+        denominator: u32,
+    },
+}
+
+/// How the results of a call are corrupted
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Corruption {
+    /// Flip the lowest bit of every numeric result value
+    FlipLowBit,
+    /// Replace every numeric result value with zero
+    Zero,
+    /// Drop all result values
+    Truncate,
+    /// Replace the results with the given values
+    Replace(Vec<Value>),
+}
+
+/// The fault to inject when a rule fires
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub enum FaultAction {
+    /// Fail the call with the given error before it reaches the target
+    Fail(Error),
+    /// Delay the call by the given duration before it reaches the target
+    Delay(Duration),
+    /// Corrupt the results returned by the target
+    Corrupt(Corruption),
+}
+
+/// A single fault-injection rule
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct FaultRule {
+    /// Calls the rule applies to
+    pub matcher: FaultMatcher,
+    /// When the rule fires
+    pub trigger: FaultTrigger,
+    /// What happens when the rule fires
+    pub action:  FaultAction,
+}
+
+#[cfg(feature = "std")]
+impl FaultRule {
+    /// Create a new fault rule
+    #[must_use]
+    pub fn new(matcher: FaultMatcher, trigger: FaultTrigger, action: FaultAction) -> Self {
+        Self {
+            matcher,
+            trigger,
+            action,
+        }
+    }
+
+    /// Whether the rule is evaluated before the call (fail/delay) or after it
+    /// (corrupt)
+    fn applies_before_call(&self) -> bool {
+        !matches!(self.action, FaultAction::Corrupt(_))
+    }
+}
+
+/// Configuration for the fault-injection strategy
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct FaultInjectionConfig {
+    /// Whether fault injection is active
+    pub enabled: bool,
+    /// Rules to evaluate, in order
+    pub rules:   Vec<FaultRule>,
+    /// Seed for the pseudo-random generator used by probabilistic triggers
+    pub seed:    u64,
+}
+
+#[cfg(feature = "std")]
+impl Default for FaultInjectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            rules:   Vec::new(),
+            seed:    0x2545_F491_4F6C_DD1D,
+        }
+    }
+}
+
+/// Mutable bookkeeping shared by all rules
+#[cfg(feature = "std")]
+#[derive(Debug)]
+struct FaultState {
+    /// Number of matching calls seen by each rule
+    match_counts:    Vec<u64>,
+    /// Number of times each rule fired
+    injected_counts: Vec<u64>,
+    /// State of the xorshift pseudo-random generator
+    rng:             u64,
+}
+
+#[cfg(feature = "std")]
+impl FaultState {
+    fn new(rule_count: usize, seed: u64) -> Self {
+        Self {
+            match_counts:    vec![0; rule_count],
+            injected_counts: vec![0; rule_count],
+            // xorshift must never be seeded with zero
+            rng:             if seed == 0 { 1 } else { seed },
+        }
+    }
+
+    fn next_random(&mut self) -> u64 {
+        let mut x = self.rng;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.rng = x;
+        x
+    }
+
+    /// Record a matching call for a rule and decide whether it fires
+    fn should_fire(&mut self, index: usize, trigger: FaultTrigger) -> bool {
+        self.match_counts[index] += 1;
+        let count = self.match_counts[index];
+
+        let fire = match trigger {
+            FaultTrigger::Always => true,
+            FaultTrigger::OnNthCall(n) => count == n,
+            FaultTrigger::EveryNthCall(n) => n != 0 && count % n == 0,
+            FaultTrigger::Probability {
+                numerator,
+                denominator,
+            } => {
+                denominator != 0
+                    && self.next_random() % u64::from(denominator) < u64::from(numerator)
+            },
+        };
+
+        if fire {
+            self.injected_counts[index] += 1;
+        }
+        fire
+    }
+}
+
+/// A strategy that injects faults into matching function calls
+#[cfg(feature = "std")]
+pub struct FaultInjectionStrategy {
+    /// Configuration for this strategy
+    config: FaultInjectionConfig,
+    /// Per-rule counters and generator state
+    state:  Mutex<FaultState>,
+}
+
+#[cfg(feature = "std")]
+impl FaultInjectionStrategy {
+    /// Create a new fault-injection strategy with the given configuration
+    #[must_use]
+    pub fn new(config: FaultInjectionConfig) -> Self {
+        let state = FaultState::new(config.rules.len(), config.seed);
+        Self {
+            config,
+            state: Mutex::new(state),
+        }
+    }
+
+    /// Number of times the rule at `index` has injected a fault
+    #[must_use]
+    pub fn injected_count(&self, index: usize) -> u64 {
+        self.state
+            .lock()
+            .ok()
+            .and_then(|state| state.injected_counts.get(index).copied())
+            .unwrap_or(0)
+    }
+
+    /// Total number of faults injected across all rules
+    #[must_use]
+    pub fn total_injected(&self) -> u64 {
+        self.state.lock().map_or(0, |state| state.injected_counts.iter().sum())
+    }
+
+    /// Reset all counters and reseed the pseudo-random generator
+    pub fn reset(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            *state = FaultState::new(self.config.rules.len(), self.config.seed);
+        }
+    }
+
+    /// Collect the actions of all rules in the given phase that fire for this
+    /// call
+    fn fired_actions(
+        &self,
+        before_call: bool,
+        source: &str,
+        target: &str,
+        function: &str,
+    ) -> Result<Vec<FaultAction>> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| Error::poisoned_lock("Fault injection state lock poisoned"))?;
+
+        let mut fired = Vec::new();
+        for (index, rule) in self.config.rules.iter().enumerate() {
+            if rule.applies_before_call() != before_call
+                || !rule.matcher.matches(source, target, function)
+            {
+                continue;
+            }
+            if state.should_fire(index, rule.trigger) {
+                fired.push(rule.action.clone());
+            }
+        }
+        Ok(fired)
+    }
+
+    /// Apply a corruption to a list of result values
+    fn corrupt(values: Vec<Value>, corruption: &Corruption) -> Vec<Value> {
+        match corruption {
+            Corruption::FlipLowBit => values.into_iter().map(flip_low_bit).collect(),
+            Corruption::Zero => values.into_iter().map(zero_value).collect(),
+            Corruption::Truncate => Vec::new(),
+            Corruption::Replace(replacement) => replacement.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+fn flip_low_bit(value: Value) -> Value {
+    match value {
+        Value::I32(v) => Value::I32(v ^ 1),
+        Value::I64(v) => Value::I64(v ^ 1),
+        Value::F32(v) => Value::F32(FloatBits32(v.0 ^ 1)),
+        Value::F64(v) => Value::F64(FloatBits64(v.0 ^ 1)),
+        Value::Ref(v) => Value::Ref(v ^ 1),
+        other => other,
+    }
+}
+
+#[cfg(feature = "std")]
+fn zero_value(value: Value) -> Value {
+    match value {
+        Value::I32(_) => Value::I32(0),
+        Value::I64(_) => Value::I64(0),
+        Value::F32(_) => Value::F32(FloatBits32(0)),
+        Value::F64(_) => Value::F64(FloatBits64(0)),
+        other => other,
+    }
+}
+
+#[cfg(feature = "std")]
+impl LinkInterceptorStrategy for FaultInjectionStrategy {
+    fn before_call(
+        &self,
+        source: &str,
+        target: &str,
+        function: &str,
+        args: &[Value],
+    ) -> Result<Vec<Value>> {
+        if self.config.enabled {
+            for action in self.fired_actions(true, source, target, function)? {
+                match action {
+                    FaultAction::Fail(error) => return Err(error),
+                    FaultAction::Delay(duration) => thread::sleep(duration),
+                    FaultAction::Corrupt(_) => {},
+                }
+            }
+        }
+
+        // Return unmodified arguments
+        Ok(args.to_vec())
+    }
+
+    fn after_call(
+        &self,
+        source: &str,
+        target: &str,
+        function: &str,
+        _args: &[Value],
+        result: Result<Vec<Value>>,
+    ) -> Result<Vec<Value>> {
+        if !self.config.enabled {
+            return result;
+        }
+
+        // Errors from the target are passed through untouched
+        let mut values = result?;
+        for action in self.fired_actions(false, source, target, function)? {
+            if let FaultAction::Corrupt(corruption) = action {
+                values = Self::corrupt(values, &corruption);
+            }
+        }
+        Ok(values)
+    }
+
+    fn clone_strategy(&self) -> Arc<dyn LinkInterceptorStrategy> {
+        Arc::new(Self::new(self.config.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strategy_with(rule: FaultRule) -> FaultInjectionStrategy {
+        FaultInjectionStrategy::new(FaultInjectionConfig {
+            rules: vec![rule],
+            ..FaultInjectionConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_fail_on_nth_call() {
+        let strategy = strategy_with(FaultRule::new(
+            FaultMatcher::function("target", "func"),
+            FaultTrigger::OnNthCall(2),
+            FaultAction::Fail(Error::runtime_error("Injected fault")),
+        ));
+
+        assert!(strategy.before_call("source", "target", "func", &[]).is_ok());
+        assert!(strategy.before_call("source", "target", "func", &[]).is_err());
+        assert!(strategy.before_call("source", "target", "func", &[]).is_ok());
+        // Non-matching calls are neither counted nor failed
+        assert!(strategy.before_call("source", "target", "other", &[]).is_ok());
+        assert_eq!(strategy.injected_count(0), 1);
+    }
+
+    #[test]
+    fn test_every_nth_call() {
+        let strategy = strategy_with(FaultRule::new(
+            FaultMatcher::any(),
+            FaultTrigger::EveryNthCall(3),
+            FaultAction::Fail(Error::runtime_error("Injected fault")),
+        ));
+
+        let failures = (0..9)
+            .filter(|_| strategy.before_call("source", "target", "func", &[]).is_err())
+            .count();
+        assert_eq!(failures, 3);
+    }
+
+    #[test]
+    fn test_corrupt_results() {
+        let strategy = strategy_with(FaultRule::new(
+            FaultMatcher::any(),
+            FaultTrigger::Always,
+            FaultAction::Corrupt(Corruption::FlipLowBit),
+        ));
+
+        let result = strategy
+            .after_call("source", "target", "func", &[], Ok(vec![Value::I32(4), Value::I64(7)]))
+            .unwrap();
+        assert_eq!(result, vec![Value::I32(5), Value::I64(6)]);
+    }
+
+    #[test]
+    fn test_probability_is_deterministic() {
+        let rule = FaultRule::new(
+            FaultMatcher::any(),
+            FaultTrigger::Probability {
+                numerator:   1,
+                denominator: 2,
+            },
+            FaultAction::Fail(Error::runtime_error("Injected fault")),
+        );
+        let first = strategy_with(rule.clone());
+        let second = strategy_with(rule);
+
+        for _ in 0..32 {
+            assert_eq!(
+                first.before_call("source", "target", "func", &[]).is_err(),
+                second.before_call("source", "target", "func", &[]).is_err()
+            );
+        }
+        assert!(first.total_injected() > 0);
+        assert!(first.total_injected() < 32);
+    }
+
+    #[test]
+    fn test_disabled_injects_nothing() {
+        let strategy = FaultInjectionStrategy::new(FaultInjectionConfig {
+            enabled: false,
+            rules: vec![FaultRule::new(
+                FaultMatcher::any(),
+                FaultTrigger::Always,
+                FaultAction::Fail(Error::runtime_error("Injected fault")),
+            )],
+            ..FaultInjectionConfig::default()
+        });
+
+        assert!(strategy.before_call("source", "target", "func", &[]).is_ok());
+        assert_eq!(strategy.total_injected(), 0);
+    }
+}
@@ -5,7 +5,7 @@
 
 #[cfg(feature = "std")]
 use std::{
-    collections::HashSet,
+    collections::HashMap,
     sync::{
         Arc,
         RwLock,
@@ -16,6 +16,11 @@
     Error,
     Result,
 };
+#[cfg(feature = "std")]
+use wrt_foundation::interned::{
+    NameId,
+    NamePool,
+};
 
 use crate::{
     prelude::{
@@ -76,17 +81,45 @@ pub struct FirewallConfig {
     pub check_parameters: bool,
 }
 
+/// Verdicts of the function calls seen so far, keyed by the interned
+/// source, target and function names
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+struct VerdictCache {
+    names:    Box<NamePool<256, 8192>>,
+    verdicts: HashMap<(NameId, NameId, NameId), bool>,
+}
+
+#[cfg(feature = "std")]
+impl VerdictCache {
+    fn get(&self, source: &str, target: &str, function: &str) -> Option<bool> {
+        let key = (
+            self.names.get(source)?,
+            self.names.get(target)?,
+            self.names.get(function)?,
+        );
+        self.verdicts.get(&key).copied()
+    }
+
+    fn insert(&mut self, source: &str, target: &str, function: &str, allowed: bool) {
+        // Calls whose names no longer fit are decided by the rules every time
+        if let (Ok(source), Ok(target), Ok(function)) = (
+            self.names.intern(source),
+            self.names.intern(target),
+            self.names.intern(function),
+        ) {
+            self.verdicts.insert((source, target, function), allowed);
+        }
+    }
+}
+
 /// A strategy that enforces security rules on function calls
 #[cfg(feature = "std")]
 pub struct FirewallStrategy {
     /// Configuration for this strategy
-    config:            FirewallConfig,
-    /// Cache of allowed function calls for performance
-    #[cfg(feature = "std")]
-    allowed_functions: RwLock<HashSet<String>>,
-    /// Cache of denied function calls for performance
-    #[cfg(feature = "std")]
-    denied_functions:  RwLock<HashSet<String>>,
+    config: FirewallConfig,
+    /// Cache of the verdicts on function calls for performance
+    cache:  RwLock<VerdictCache>,
 }
 
 /// A strategy that enforces security rules on function calls (`no_std` version)
@@ -103,33 +136,17 @@ pub fn new(config: FirewallConfig) -> Self {
         Self {
             config,
             #[cfg(feature = "std")]
-            allowed_functions: RwLock::new(HashSet::new()),
-            #[cfg(feature = "std")]
-            denied_functions: RwLock::new(HashSet::new()),
+            cache: RwLock::default(),
         }
     }
 
-    /// Helper function to generate a unique key for a function call
-    #[cfg(feature = "std")]
-    fn function_key(source: &str, target: &str, function: &str) -> String {
-        format!("{}->{}::{}", source, target, function)
-    }
-
     /// Check if a function call is allowed
     #[cfg(feature = "std")]
     fn is_allowed(&self, source: &str, target: &str, function: &str) -> bool {
-        let key = Self::function_key(source, target, function);
-
         // Check cache first
-        if let Ok(allowed) = self.allowed_functions.read() {
-            if allowed.contains(&key) {
-                return true;
-            }
-        }
-
-        if let Ok(denied) = self.denied_functions.read() {
-            if denied.contains(&key) {
-                return false;
+        if let Ok(cache) = self.cache.read() {
+            if let Some(allowed) = cache.get(source, target, function) {
+                return allowed;
             }
         }
 
@@ -137,12 +154,8 @@ fn is_allowed(&self, source: &str, target: &str, function: &str) -> bool {
         let allowed = self.apply_rules(source, target, function);
 
         // Update cache
-        if allowed {
-            if let Ok(mut allowed_cache) = self.allowed_functions.write() {
-                allowed_cache.insert(key);
-            }
-        } else if let Ok(mut denied_cache) = self.denied_functions.write() {
-            denied_cache.insert(key);
+        if let Ok(mut cache) = self.cache.write() {
+            cache.insert(source, target, function, allowed);
         }
 
         allowed
@@ -248,10 +261,7 @@ fn after_call(
     fn clone_strategy(&self) -> Arc<dyn LinkInterceptorStrategy> {
         Arc::new(Self {
             config: self.config.clone(),
-            #[cfg(feature = "std")]
-            allowed_functions: RwLock::new(HashSet::new()),
-            #[cfg(feature = "std")]
-            denied_functions: RwLock::new(HashSet::new()),
+            cache:  RwLock::default(),
         })
     }
 }
@@ -4,16 +4,47 @@
 //! that can be used out of the box or as examples for creating custom
 //! strategies.
 
+mod fault_injection;
 mod firewall;
 mod logging;
+mod redaction;
 mod stats;
+mod tracing;
+mod virtual_clock;
 
+#[cfg(feature = "std")]
+pub use fault_injection::{
+    Corruption,
+    FaultAction,
+    FaultInjectionConfig,
+    FaultInjectionStrategy,
+    FaultMatcher,
+    FaultRule,
+    FaultTrigger,
+};
 pub use firewall::{
     FirewallConfig,
     FirewallRule,
     FirewallStrategy,
 };
-pub use logging::LoggingStrategy;
+#[cfg(feature = "std")]
+pub use logging::{
+    DefaultValueFormatter,
+    LogSink,
+    ValueFormatter,
+};
+pub use logging::{
+    LoggingConfig,
+    LoggingStrategy,
+};
+#[cfg(feature = "std")]
+pub use redaction::{
+    FieldKind,
+    FieldLayout,
+    RedactionConfig,
+    RedactionRule,
+    RedactionStrategy,
+};
 #[cfg(not(feature = "std"))]
 pub use stats::FunctionStats;
 #[cfg(feature = "std")]
@@ -21,3 +52,24 @@
     FunctionStats,
     StatisticsStrategy,
 };
+#[cfg(feature = "std")]
+pub use tracing::{
+    AttributeValue,
+    SpanData,
+    SpanExporter,
+    SpanId,
+    SpanKind,
+    SpanStatus,
+    TraceContext,
+    TraceId,
+    Tracer,
+    TracingStrategy,
+};
+#[cfg(feature = "std")]
+pub use virtual_clock::{
+    ClockBinding,
+    ClockKind,
+    ClockMode,
+    VirtualClockConfig,
+    VirtualClockStrategy,
+};
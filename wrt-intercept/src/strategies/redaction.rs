@@ -0,0 +1,549 @@
+//! Redaction strategy for intercepting canonical ABI lift/lower operations
+//!
+//! This strategy masks sensitive data when values cross a component
+//! boundary, for example to strip personally identifiable information before
+//! it reaches a logging component. Redaction is driven by declarative rules:
+//!
+//! - String patterns are masked byte-for-byte in every lifted or lowered string
+//!   with an ASCII mask, which keeps the encoded length (and UTF-8 validity)
+//!   unchanged.
+//! - Record rules identify a record type by the names and types of its fields,
+//!   describe its layout by their kinds and name the fields to redact. Scalar
+//!   fields are zeroed, string and list fields are replaced with empty values.
+//!
+//! Serialized values exchanged with the canonical ABI hooks follow the
+//! convention documented on [`LinkInterceptorStrategy::intercept_lift`]:
+//! strings are their encoded contents, records are their flat canonical ABI
+//! bytes.
+//!
+//! Note: This strategy requires the `std` feature.
+
+#[cfg(feature = "std")]
+use std::sync::{
+    atomic::{
+        AtomicU64,
+        Ordering,
+    },
+    Arc,
+};
+
+#[cfg(feature = "std")]
+use wrt_error::{
+    Error,
+    Result,
+};
+#[cfg(feature = "std")]
+use wrt_foundation::{
+    component_value::ValTypeRef,
+    NoStdProvider,
+};
+
+#[cfg(feature = "std")]
+use crate::{
+    prelude::{
+        Debug,
+        ValType,
+        Value,
+    },
+    LinkInterceptorStrategy,
+};
+
+/// Canonical ABI kind of a record field, used to compute the record layout
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    /// `bool`, `s8` or `u8`
+    Byte,
+    /// `s16` or `u16`
+    Half,
+    /// `s32`, `u32`, `f32`, `char`, or a resource handle
+    Word,
+    /// `s64`, `u64` or `f64`
+    DoubleWord,
+    /// `string`, stored as a `(ptr, len)` pair
+    String,
+    /// `list<T>`, stored as a `(ptr, len)` pair
+    List,
+}
+
+#[cfg(feature = "std")]
+impl FieldKind {
+    /// Size of the field in bytes
+    #[must_use]
+    pub fn size(self) -> usize {
+        match self {
+            FieldKind::Byte => 1,
+            FieldKind::Half => 2,
+            FieldKind::Word => 4,
+            FieldKind::DoubleWord | FieldKind::String | FieldKind::List => 8,
+        }
+    }
+
+    /// Alignment of the field in bytes
+    #[must_use]
+    pub fn alignment(self) -> usize {
+        match self {
+            FieldKind::Byte => 1,
+            FieldKind::Half => 2,
+            FieldKind::Word | FieldKind::String | FieldKind::List => 4,
+            FieldKind::DoubleWord => 8,
+        }
+    }
+}
+
+/// Name, type and kind of a record field
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldLayout {
+    /// Name of the field
+    pub name: String,
+    /// Type of the field, as referenced by the record type
+    pub ty:   ValTypeRef,
+    /// Canonical ABI kind of the field
+    pub kind: FieldKind,
+}
+
+#[cfg(feature = "std")]
+impl FieldLayout {
+    /// Create the layout of a field
+    #[must_use]
+    pub fn new(name: &str, ty: ValTypeRef, kind: FieldKind) -> Self {
+        Self {
+            name: name.to_string(),
+            ty,
+            kind,
+        }
+    }
+}
+
+/// A declarative redaction rule
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RedactionRule {
+    /// Mask every occurrence of the pattern in strings crossing the boundary
+    StringPattern(String),
+    /// Redact fields of a record type
+    Record {
+        /// Fields of the record type, in declaration order. The rule applies
+        /// to records whose field names and types are exactly these
+        fields: Vec<FieldLayout>,
+        /// Names of the fields to redact
+        redact: Vec<String>,
+    },
+}
+
+/// Configuration for the redaction strategy
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct RedactionConfig {
+    /// Rules to apply
+    pub rules: Vec<RedactionRule>,
+    /// Byte used to mask matched string contents, which must be ASCII
+    pub mask:  u8,
+}
+
+#[cfg(feature = "std")]
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            rules: Vec::new(),
+            mask:  b'*',
+        }
+    }
+}
+
+/// A strategy that redacts values crossing a component boundary
+#[cfg(feature = "std")]
+pub struct RedactionStrategy {
+    /// Configuration for this strategy
+    config:         RedactionConfig,
+    /// Number of values that were redacted
+    redacted_count: AtomicU64,
+}
+
+#[cfg(feature = "std")]
+impl RedactionStrategy {
+    /// Create a new redaction strategy with the given configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the mask is not ASCII, as masking with it would
+    /// break the UTF-8 encoding of strings.
+    pub fn new(config: RedactionConfig) -> Result<Self> {
+        if !config.mask.is_ascii() {
+            return Err(Error::validation_error("Redaction mask must be ASCII"));
+        }
+        Ok(Self {
+            config,
+            redacted_count: AtomicU64::new(0),
+        })
+    }
+
+    /// Number of values redacted so far
+    #[must_use]
+    pub fn redacted_count(&self) -> u64 {
+        self.redacted_count.load(Ordering::Relaxed)
+    }
+
+    /// Mask all configured string patterns in `bytes`
+    ///
+    /// Returns `true` if anything was masked.
+    pub fn redact_string_bytes(&self, bytes: &mut [u8]) -> bool {
+        let mut redacted = false;
+        for rule in &self.config.rules {
+            let RedactionRule::StringPattern(pattern) = rule else {
+                continue;
+            };
+            let pattern = pattern.as_bytes();
+            if pattern.is_empty() || pattern.len() > bytes.len() {
+                continue;
+            }
+
+            let mut start = 0;
+            while start + pattern.len() <= bytes.len() {
+                if &bytes[start..start + pattern.len()] == pattern {
+                    bytes[start..start + pattern.len()].fill(self.config.mask);
+                    start += pattern.len();
+                    redacted = true;
+                } else {
+                    start += 1;
+                }
+            }
+        }
+        redacted
+    }
+
+    /// Redact the flat canonical ABI bytes of a record with the given field
+    /// names and types
+    ///
+    /// Returns `Ok(None)` if no record rule matches the fields.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is shorter than the declared record layout.
+    pub fn redact_record_bytes(
+        &self,
+        record: &[(String, ValTypeRef)],
+        bytes: &[u8],
+    ) -> Result<Option<Vec<u8>>> {
+        let Some((fields, redact)) = self.record_rule(record) else {
+            return Ok(None);
+        };
+
+        let size = record_size(fields);
+        if bytes.len() < size {
+            return Err(Error::runtime_out_of_bounds(
+                "Record data shorter than its declared layout",
+            ));
+        }
+
+        let mut redacted = bytes[..size].to_vec();
+        let mut offset = 0;
+        for FieldLayout { name, kind, .. } in fields {
+            offset = align_to(offset, kind.alignment());
+            if redact.contains(name) {
+                match kind {
+                    // Keep the pointer, clear the length
+                    FieldKind::String | FieldKind::List => {
+                        redacted[offset + 4..offset + 8].fill(0);
+                    },
+                    _ => redacted[offset..offset + kind.size()].fill(0),
+                }
+            }
+            offset += kind.size();
+        }
+        Ok(Some(redacted))
+    }
+
+    /// Find the record rule whose declared fields are those of `record`
+    fn record_rule(&self, record: &[(String, ValTypeRef)]) -> Option<(&[FieldLayout], &[String])> {
+        self.config.rules.iter().find_map(|rule| match rule {
+            RedactionRule::Record { fields, redact }
+                if fields.len() == record.len()
+                    && fields
+                        .iter()
+                        .zip(record)
+                        .all(|(field, (name, ty))| field.name == *name && field.ty == *ty) =>
+            {
+                Some((fields.as_slice(), redact.as_slice()))
+            },
+            _ => None,
+        })
+    }
+
+    fn record_redacted(&self) {
+        self.redacted_count.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Round `offset` up to the next multiple of `alignment`
+#[cfg(feature = "std")]
+fn align_to(offset: usize, alignment: usize) -> usize {
+    offset.div_ceil(alignment) * alignment
+}
+
+/// Size of a record with the given fields according to the canonical ABI
+#[cfg(feature = "std")]
+fn record_size(fields: &[FieldLayout]) -> usize {
+    let mut size = 0;
+    let mut max_alignment = 1;
+    for FieldLayout { kind, .. } in fields {
+        size = align_to(size, kind.alignment()) + kind.size();
+        max_alignment = max_alignment.max(kind.alignment());
+    }
+    align_to(size, max_alignment)
+}
+
+/// Field names and types of a record type, or `None` if the type is not a
+/// record
+#[cfg(feature = "std")]
+fn record_fields(ty: &ValType<NoStdProvider<64>>) -> Option<Vec<(String, ValTypeRef)>> {
+    let ValType::Record(fields) = ty else {
+        return None;
+    };
+    Some(
+        fields
+            .iter()
+            .map(|(name, ty)| {
+                (
+                    name.as_str().map(ToString::to_string).unwrap_or_default(),
+                    ty,
+                )
+            })
+            .collect(),
+    )
+}
+
+/// Range of `len` bytes at `offset`, or `None` if it would overflow
+#[cfg(feature = "std")]
+fn byte_range(offset: usize, len: usize) -> Option<core::ops::Range<usize>> {
+    Some(offset..offset.checked_add(len)?)
+}
+
+/// Read a little-endian `u32` from memory
+#[cfg(feature = "std")]
+fn read_u32(memory: &[u8], offset: usize) -> Result<u32> {
+    byte_range(offset, 4)
+        .and_then(|range| memory.get(range))
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u32::from_le_bytes)
+        .ok_or_else(|| Error::runtime_out_of_bounds("Canonical value out of memory bounds"))
+}
+
+#[cfg(feature = "std")]
+impl LinkInterceptorStrategy for RedactionStrategy {
+    fn before_call(
+        &self,
+        _source: &str,
+        _target: &str,
+        _function: &str,
+        args: &[Value],
+    ) -> Result<Vec<Value>> {
+        Ok(args.to_vec())
+    }
+
+    fn after_call(
+        &self,
+        _source: &str,
+        _target: &str,
+        _function: &str,
+        _args: &[Value],
+        result: Result<Vec<Value>>,
+    ) -> Result<Vec<Value>> {
+        result
+    }
+
+    fn should_intercept_canonical(&self) -> bool {
+        !self.config.rules.is_empty()
+    }
+
+    fn intercept_lift(
+        &self,
+        ty: &ValType<NoStdProvider<64>>,
+        addr: u32,
+        memory_bytes: &[u8],
+    ) -> Result<Option<Vec<u8>>> {
+        let addr = addr as usize;
+
+        if matches!(ty, ValType::String) {
+            let len_offset = addr
+                .checked_add(4)
+                .ok_or_else(|| Error::runtime_out_of_bounds("String out of memory bounds"))?;
+            let ptr = read_u32(memory_bytes, addr)? as usize;
+            let len = read_u32(memory_bytes, len_offset)? as usize;
+            let mut contents = byte_range(ptr, len)
+                .and_then(|range| memory_bytes.get(range))
+                .ok_or_else(|| Error::runtime_out_of_bounds("String out of memory bounds"))?
+                .to_vec();
+            if self.redact_string_bytes(&mut contents) {
+                self.record_redacted();
+                return Ok(Some(contents));
+            }
+            return Ok(None);
+        }
+
+        if let Some(fields) = record_fields(ty) {
+            let data = memory_bytes
+                .get(addr..)
+                .ok_or_else(|| Error::runtime_out_of_bounds("Record out of memory bounds"))?;
+            let redacted = self.redact_record_bytes(&fields, data)?;
+            if redacted.is_some() {
+                self.record_redacted();
+            }
+            return Ok(redacted);
+        }
+
+        Ok(None)
+    }
+
+    fn intercept_lower(
+        &self,
+        value_type: &ValType<NoStdProvider<64>>,
+        value_data: &[u8],
+        addr: u32,
+        memory_bytes: &mut [u8],
+    ) -> Result<bool> {
+        let redacted = if matches!(value_type, ValType::String) {
+            let mut contents = value_data.to_vec();
+            self.redact_string_bytes(&mut contents).then_some(contents)
+        } else if let Some(fields) = record_fields(value_type) {
+            self.redact_record_bytes(&fields, value_data)?
+        } else {
+            None
+        };
+
+        let Some(redacted) = redacted else {
+            return Ok(false);
+        };
+
+        byte_range(addr as usize, redacted.len())
+            .and_then(|range| memory_bytes.get_mut(range))
+            .ok_or_else(|| Error::runtime_out_of_bounds("Lowered value out of memory bounds"))?
+            .copy_from_slice(&redacted);
+        self.record_redacted();
+        Ok(true)
+    }
+
+    fn clone_strategy(&self) -> Arc<dyn LinkInterceptorStrategy> {
+        Arc::new(Self {
+            config:         self.config.clone(),
+            redacted_count: AtomicU64::new(0),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern_strategy(pattern: &str) -> RedactionStrategy {
+        RedactionStrategy::new(RedactionConfig {
+            rules: vec![RedactionRule::StringPattern(pattern.to_string())],
+            ..RedactionConfig::default()
+        })
+        .unwrap()
+    }
+
+    fn fields_of(fields: &[(&str, u32)]) -> Vec<(String, ValTypeRef)> {
+        fields.iter().map(|&(name, ty)| (name.to_string(), ValTypeRef(ty))).collect()
+    }
+
+    #[test]
+    fn test_rejects_non_ascii_mask() {
+        let config = RedactionConfig {
+            mask: 0xC3,
+            ..RedactionConfig::default()
+        };
+        assert!(RedactionStrategy::new(config).is_err());
+    }
+
+    #[test]
+    fn test_lift_masks_string_pattern() {
+        let strategy = pattern_strategy("secret");
+
+        // (ptr, len) pair at 0, string contents at 8
+        let text = b"my secret value";
+        let mut memory = vec![0u8; 8];
+        memory[0..4].copy_from_slice(&8u32.to_le_bytes());
+        memory[4..8].copy_from_slice(&(text.len() as u32).to_le_bytes());
+        memory.extend_from_slice(text);
+
+        let lifted = strategy.intercept_lift(&ValType::String, 0, &memory).unwrap();
+        assert_eq!(lifted.as_deref(), Some(&b"my ****** value"[..]));
+        assert_eq!(strategy.redacted_count(), 1);
+    }
+
+    #[test]
+    fn test_lift_without_match_proceeds_normally() {
+        let strategy = pattern_strategy("secret");
+
+        let text = b"public";
+        let mut memory = vec![0u8; 8];
+        memory[0..4].copy_from_slice(&8u32.to_le_bytes());
+        memory[4..8].copy_from_slice(&(text.len() as u32).to_le_bytes());
+        memory.extend_from_slice(text);
+
+        assert!(strategy.intercept_lift(&ValType::String, 0, &memory).unwrap().is_none());
+        assert_eq!(strategy.redacted_count(), 0);
+    }
+
+    #[test]
+    fn test_lift_rejects_overflowing_string() {
+        let strategy = pattern_strategy("secret");
+
+        let mut memory = vec![0u8; 8];
+        memory[0..4].copy_from_slice(&u32::MAX.to_le_bytes());
+        memory[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        assert!(strategy.intercept_lift(&ValType::String, 0, &memory).is_err());
+        assert!(strategy.intercept_lift(&ValType::String, u32::MAX, &memory).is_err());
+    }
+
+    #[test]
+    fn test_lower_writes_masked_string() {
+        let strategy = pattern_strategy("4111");
+        let mut memory = vec![0u8; 16];
+
+        let handled =
+            strategy.intercept_lower(&ValType::String, b"card 4111", 4, &mut memory).unwrap();
+        assert!(handled);
+        assert_eq!(&memory[4..13], b"card ****");
+    }
+
+    #[test]
+    fn test_record_field_redaction() {
+        let strategy = RedactionStrategy::new(RedactionConfig {
+            rules: vec![RedactionRule::Record {
+                fields: vec![
+                    FieldLayout::new("id", ValTypeRef(0), FieldKind::Word),
+                    FieldLayout::new("email", ValTypeRef(1), FieldKind::String),
+                    FieldLayout::new("age", ValTypeRef(2), FieldKind::Byte),
+                ],
+                redact: vec!["email".to_string(), "age".to_string()],
+            }],
+            ..RedactionConfig::default()
+        })
+        .unwrap();
+
+        // id = 7, email = (ptr 64, len 12), age = 42, padded to 16 bytes
+        let mut record = Vec::new();
+        record.extend_from_slice(&7u32.to_le_bytes());
+        record.extend_from_slice(&64u32.to_le_bytes());
+        record.extend_from_slice(&12u32.to_le_bytes());
+        record.extend_from_slice(&[42, 0, 0, 0]);
+
+        let user = fields_of(&[("id", 0), ("email", 1), ("age", 2)]);
+        let redacted = strategy.redact_record_bytes(&user, &record).unwrap().unwrap();
+        assert_eq!(&redacted[0..4], &7u32.to_le_bytes());
+        assert_eq!(&redacted[4..8], &64u32.to_le_bytes());
+        assert_eq!(&redacted[8..12], &0u32.to_le_bytes());
+        assert_eq!(redacted[12], 0);
+
+        // Records with a different shape, or the same field names with other
+        // types, are left alone
+        let other = fields_of(&[("id", 0)]);
+        assert!(strategy.redact_record_bytes(&other, &record).unwrap().is_none());
+        let retyped = fields_of(&[("id", 0), ("email", 3), ("age", 2)]);
+        assert!(strategy.redact_record_bytes(&retyped, &record).unwrap().is_none());
+    }
+}
@@ -0,0 +1,507 @@
+//! Tracing strategy emitting OpenTelemetry-compatible spans
+//!
+//! A [`Tracer`] records spans for calls across WebAssembly boundaries: the
+//! [`TracingStrategy`] opens a span for every intercepted call between
+//! components or to the host, and the engine opens one for every export it
+//! executes when it is given the tracer. Spans nest: a span opened while
+//! another is open becomes its child, and the outermost span is a child of
+//! the context the host supplied with [`Tracer::set_parent`], typically taken
+//! from the `traceparent` header of the request being served. A distributed
+//! trace therefore continues through the guest and the components it calls.
+//!
+//! Trace and span identifiers and the `traceparent` format follow the W3C
+//! Trace Context recommendation, and finished spans carry the data of an
+//! OpenTelemetry span: name, kind, parent, start and end time, attributes and
+//! status. They are handed to a [`SpanExporter`], which forwards them to an
+//! OpenTelemetry SDK or collector.
+//!
+//! Note: This strategy requires the `std` feature.
+
+#[cfg(feature = "std")]
+use core::{
+    fmt,
+    sync::atomic::{
+        AtomicU64,
+        Ordering,
+    },
+};
+#[cfg(feature = "std")]
+use std::{
+    sync::{
+        Arc,
+        Mutex,
+        MutexGuard,
+        PoisonError,
+    },
+    time::{
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+
+#[cfg(feature = "std")]
+use wrt_error::{
+    Error,
+    Result,
+};
+
+#[cfg(feature = "std")]
+use crate::{
+    prelude::{
+        str,
+        Debug,
+        Value,
+    },
+    LinkInterceptorStrategy,
+};
+
+/// Identifier of a trace
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TraceId(pub [u8; 16]);
+
+/// Identifier of a span within a trace
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SpanId(pub [u8; 8]);
+
+#[cfg(feature = "std")]
+fn write_hex(f: &mut fmt::Formatter<'_>, bytes: &[u8]) -> fmt::Result {
+    bytes.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for TraceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_hex(f, &self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for SpanId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_hex(f, &self.0)
+    }
+}
+
+/// Parse `N` bytes from exactly `2 * N` lowercase hex digits
+#[cfg(feature = "std")]
+fn parse_hex<const N: usize>(hex: &str) -> Option<[u8; N]> {
+    if hex.len() != 2 * N || hex.bytes().any(|c| !matches!(c, b'0'..=b'9' | b'a'..=b'f')) {
+        return None;
+    }
+    let mut bytes = [0; N];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).ok()?;
+    }
+    Some(bytes)
+}
+
+/// Parse an identifier, which must not be all zeros
+#[cfg(feature = "std")]
+fn parse_id<const N: usize>(hex: &str) -> Option<[u8; N]> {
+    parse_hex(hex).filter(|bytes: &[u8; N]| bytes.iter().any(|&byte| byte != 0))
+}
+
+/// Position of a span in a trace, propagated across process and wasm
+/// boundaries
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceContext {
+    /// Trace the span belongs to
+    pub trace_id: TraceId,
+    /// The span
+    pub span_id:  SpanId,
+    /// Whether the trace is sampled
+    pub sampled:  bool,
+}
+
+#[cfg(feature = "std")]
+impl TraceContext {
+    /// Parse a W3C `traceparent` header value,
+    /// `00-<trace id>-<span id>-<flags>`
+    ///
+    /// # Errors
+    ///
+    /// Returns a parse error if the value is malformed or an identifier is
+    /// all zeros.
+    pub fn from_traceparent(header: &str) -> Result<Self> {
+        let invalid = || Error::parse_error("Invalid traceparent header");
+        let mut fields = header.trim().split('-');
+        let (Some(version), Some(trace_id), Some(span_id), Some(flags)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            return Err(invalid());
+        };
+        // Later versions may append fields; version 00 has exactly four
+        let version = parse_hex::<1>(version).ok_or_else(invalid)?[0];
+        if version == 0xff || (version == 0 && fields.next().is_some()) {
+            return Err(invalid());
+        }
+        let flags = parse_hex::<1>(flags).ok_or_else(invalid)?[0];
+        Ok(Self {
+            trace_id: TraceId(parse_id(trace_id).ok_or_else(invalid)?),
+            span_id:  SpanId(parse_id(span_id).ok_or_else(invalid)?),
+            sampled:  flags & 1 != 0,
+        })
+    }
+
+    /// The W3C `traceparent` header value of the context
+    pub fn traceparent(&self) -> String {
+        format!(
+            "00-{}-{}-{:02x}",
+            self.trace_id,
+            self.span_id,
+            u8::from(self.sampled)
+        )
+    }
+}
+
+/// Role of a span in a call
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpanKind {
+    /// An operation within the guest
+    Internal,
+    /// A call the guest makes to another component or the host
+    Client,
+    /// A call the host makes into the guest
+    Server,
+}
+
+/// Outcome of a span
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpanStatus {
+    /// The call succeeded
+    Ok,
+    /// The call failed with the given description
+    Error(String),
+}
+
+/// Value of a span attribute
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttributeValue {
+    /// A string
+    String(String),
+    /// An integer
+    Int(i64),
+    /// A boolean
+    Bool(bool),
+}
+
+#[cfg(feature = "std")]
+impl From<&str> for AttributeValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.into())
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<i64> for AttributeValue {
+    fn from(value: i64) -> Self {
+        Self::Int(value)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<bool> for AttributeValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+/// A finished span
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpanData {
+    /// Name of the span, the called function
+    pub name:             String,
+    /// Context of the span
+    pub context:          TraceContext,
+    /// Span the span is a child of, if any
+    pub parent_span_id:   Option<SpanId>,
+    /// Role of the span in the call
+    pub kind:             SpanKind,
+    /// Start time in nanoseconds since the Unix epoch
+    pub start_unix_nanos: u64,
+    /// End time in nanoseconds since the Unix epoch
+    pub end_unix_nanos:   u64,
+    /// Attributes describing the call
+    pub attributes:       Vec<(String, AttributeValue)>,
+    /// Outcome of the call
+    pub status:           SpanStatus,
+}
+
+/// Destination of finished spans
+///
+/// Any `Fn(SpanData)` closure that is `Send + Sync` can be used directly.
+#[cfg(feature = "std")]
+pub trait SpanExporter: Send + Sync {
+    /// Export a finished span
+    fn export(&self, span: SpanData);
+}
+
+#[cfg(feature = "std")]
+impl<F> SpanExporter for F
+where
+    F: Fn(SpanData) + Send + Sync,
+{
+    fn export(&self, span: SpanData) {
+        self(span);
+    }
+}
+
+#[cfg(feature = "std")]
+fn unix_nanos() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| {
+        u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX)
+    })
+}
+
+/// Records nested spans and hands them to an exporter when they end
+#[cfg(feature = "std")]
+pub struct Tracer {
+    exporter: Arc<dyn SpanExporter>,
+    parent:   Mutex<Option<TraceContext>>,
+    /// Spans opened and not ended yet, innermost last
+    active:   Mutex<Vec<SpanData>>,
+    /// State of the identifier generator
+    seed:     AtomicU64,
+}
+
+#[cfg(feature = "std")]
+impl Debug for Tracer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tracer")
+            .field("parent", &*lock(&self.parent))
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "std")]
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(feature = "std")]
+impl Tracer {
+    /// Tracer handing finished spans to `exporter`
+    pub fn new(exporter: Arc<dyn SpanExporter>) -> Self {
+        Self {
+            exporter,
+            parent: Mutex::new(None),
+            active: Mutex::new(Vec::new()),
+            seed: AtomicU64::new(unix_nanos()),
+        }
+    }
+
+    /// Make the outermost spans children of `parent`, or roots of new traces
+    /// with `None`
+    pub fn set_parent(&self, parent: Option<TraceContext>) {
+        *lock(&self.parent) = parent;
+    }
+
+    /// Context of the innermost open span, or the parent supplied by the host
+    /// if no span is open, for propagating the trace to outgoing calls
+    pub fn current(&self) -> Option<TraceContext> {
+        lock(&self.active)
+            .last()
+            .map(|span| span.context)
+            .or_else(|| *lock(&self.parent))
+    }
+
+    /// Open a span named `name`, as a child of the innermost open span
+    pub fn start_span(
+        &self,
+        name: &str,
+        kind: SpanKind,
+        attributes: Vec<(String, AttributeValue)>,
+    ) -> TraceContext {
+        let parent = self.current();
+        let context = TraceContext {
+            trace_id: parent.map_or_else(|| TraceId(self.random_id()), |parent| parent.trace_id),
+            span_id:  SpanId(self.random_id()),
+            sampled:  parent.map_or(true, |parent| parent.sampled),
+        };
+        lock(&self.active).push(SpanData {
+            name: name.into(),
+            context,
+            parent_span_id: parent.map(|parent| parent.span_id),
+            kind,
+            start_unix_nanos: unix_nanos(),
+            end_unix_nanos: 0,
+            attributes,
+            status: SpanStatus::Ok,
+        });
+        context
+    }
+
+    /// End the innermost open span with `status` and export it if its trace
+    /// is sampled
+    pub fn end_span(&self, status: SpanStatus) {
+        let Some(mut span) = lock(&self.active).pop() else {
+            return;
+        };
+        span.end_unix_nanos = unix_nanos();
+        span.status = status;
+        if span.context.sampled {
+            self.exporter.export(span);
+        }
+    }
+
+    /// A non-zero identifier of `N` bytes
+    ///
+    /// Identifiers only need to be unique, not unpredictable, so they are
+    /// drawn from a SplitMix64 sequence seeded with the time.
+    fn random_id<const N: usize>(&self) -> [u8; N] {
+        let mut id = [0; N];
+        for chunk in id.chunks_mut(8) {
+            let mut z = self.seed.fetch_add(0x9E37_79B9_7F4A_7C15, Ordering::Relaxed);
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^= z >> 31;
+            chunk.copy_from_slice(&z.to_le_bytes()[..chunk.len()]);
+        }
+        if id.iter().all(|&byte| byte == 0) {
+            id[N - 1] = 1;
+        }
+        id
+    }
+}
+
+/// A strategy opening a span for every intercepted call
+///
+/// Spans are named `target.function` and carry the attributes
+/// `wasm.component.source`, `wasm.component.target` and `wasm.function`.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct TracingStrategy {
+    tracer: Arc<Tracer>,
+}
+
+#[cfg(feature = "std")]
+impl TracingStrategy {
+    /// Strategy recording spans with `tracer`
+    pub fn new(tracer: Arc<Tracer>) -> Self {
+        Self { tracer }
+    }
+
+    /// The tracer of the strategy
+    pub fn tracer(&self) -> &Arc<Tracer> {
+        &self.tracer
+    }
+}
+
+#[cfg(feature = "std")]
+impl LinkInterceptorStrategy for TracingStrategy {
+    fn before_call(
+        &self,
+        source: &str,
+        target: &str,
+        function: &str,
+        args: &[Value],
+    ) -> Result<Vec<Value>> {
+        self.tracer.start_span(
+            &format!("{target}.{function}"),
+            SpanKind::Client,
+            vec![
+                ("wasm.component.source".into(), source.into()),
+                ("wasm.component.target".into(), target.into()),
+                ("wasm.function".into(), function.into()),
+            ],
+        );
+        Ok(args.to_vec())
+    }
+
+    fn after_call(
+        &self,
+        _source: &str,
+        _target: &str,
+        _function: &str,
+        _args: &[Value],
+        result: Result<Vec<Value>>,
+    ) -> Result<Vec<Value>> {
+        self.tracer.end_span(match &result {
+            Ok(_) => SpanStatus::Ok,
+            Err(error) => SpanStatus::Error(error.to_string()),
+        });
+        result
+    }
+
+    fn clone_strategy(&self) -> Arc<dyn LinkInterceptorStrategy> {
+        // Clones share the tracer, so their spans nest with the original's
+        Arc::new(self.clone())
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    fn collecting_tracer() -> (Arc<Tracer>, Arc<Mutex<Vec<SpanData>>>) {
+        let spans = Arc::new(Mutex::new(Vec::new()));
+        let exporter = {
+            let spans = spans.clone();
+            move |span| lock(&spans).push(span)
+        };
+        (Arc::new(Tracer::new(Arc::new(exporter))), spans)
+    }
+
+    #[test]
+    fn test_traceparent_round_trip() {
+        let header = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let context = TraceContext::from_traceparent(header).unwrap();
+        assert!(context.sampled);
+        assert_eq!(context.traceparent(), header);
+
+        for invalid in [
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-1",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        ] {
+            assert!(
+                TraceContext::from_traceparent(invalid).is_err(),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_intercepted_calls_continue_host_trace() {
+        let (tracer, spans) = collecting_tracer();
+        let parent = TraceContext::from_traceparent(
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        )
+        .unwrap();
+        tracer.set_parent(Some(parent));
+
+        let strategy = TracingStrategy::new(tracer.clone());
+        let outer = tracer.start_span("handle", SpanKind::Server, Vec::new());
+        strategy.before_call("app", "kv", "get", &[]).unwrap();
+        let _ = strategy.after_call(
+            "app",
+            "kv",
+            "get",
+            &[],
+            Err(Error::runtime_trap_error("Unreachable")),
+        );
+        tracer.end_span(SpanStatus::Ok);
+
+        let spans = lock(&spans);
+        assert_eq!(spans.len(), 2);
+        let (call, handle) = (&spans[0], &spans[1]);
+        assert_eq!(call.name, "kv.get");
+        assert_eq!(call.kind, SpanKind::Client);
+        assert_eq!(call.context.trace_id, parent.trace_id);
+        assert_eq!(call.parent_span_id, Some(outer.span_id));
+        assert!(matches!(call.status, SpanStatus::Error(_)));
+        assert!(call.attributes.contains(&("wasm.component.target".into(), "kv".into())));
+        assert_eq!(handle.parent_span_id, Some(parent.span_id));
+        assert!(handle.end_unix_nanos >= handle.start_unix_nanos);
+        assert_eq!(tracer.current(), Some(parent));
+    }
+}
@@ -0,0 +1,459 @@
+//! Virtual-time strategy for intercepting clock and timer host functions
+//!
+//! This strategy serves the results of clock host functions from a
+//! controllable virtual clock instead of the host clock, so that guests that
+//! depend on time behave reproducibly in tests and simulations. The virtual
+//! clock either only moves when advanced explicitly (manual mode) or follows
+//! the host clock at a configurable rate (scaled mode).
+//!
+//! Intercepted clock reads still invoke the host function; only their results
+//! are replaced. Clock reads are side-effect free, so this keeps the strategy
+//! composable with other strategies on the same interceptor. Sleep-like host
+//! functions are bypassed instead: the virtual clock advances by the
+//! requested duration and the call returns at once, so guests never block on
+//! the host and scaled time does not count the sleep twice.
+//!
+//! Note: This strategy requires the `std` feature.
+
+#[cfg(feature = "std")]
+use std::{
+    sync::{
+        Arc,
+        Mutex,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+#[cfg(feature = "std")]
+use wrt_error::{
+    Error,
+    Result,
+};
+
+#[cfg(feature = "std")]
+use crate::{
+    prelude::{
+        str,
+        Debug,
+        Value,
+    },
+    LinkInterceptorStrategy,
+};
+
+/// Kind of time-related host function
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockKind {
+    /// Returns the monotonic time as nanoseconds (`u64`)
+    MonotonicNow,
+    /// Returns the monotonic clock resolution as nanoseconds (`u64`)
+    MonotonicResolution,
+    /// Returns the wall-clock time as seconds (`u64`) and nanoseconds (`u32`)
+    WallNow,
+    /// Returns the wall-clock resolution as seconds (`u64`) and nanoseconds
+    /// (`u32`)
+    WallResolution,
+    /// Sleeps for the duration given in nanoseconds (`u64`) by the first
+    /// argument
+    Sleep,
+}
+
+/// Associates a host function with the kind of clock it implements
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClockBinding {
+    /// Target interface; matches any target starting with this string, so
+    /// that versioned interface names are covered
+    pub interface: String,
+    /// Name of the function
+    pub function:  String,
+    /// Kind of clock function
+    pub kind:      ClockKind,
+}
+
+#[cfg(feature = "std")]
+impl ClockBinding {
+    /// Create a new clock binding
+    #[must_use]
+    pub fn new(interface: &str, function: &str, kind: ClockKind) -> Self {
+        Self {
+            interface: interface.to_string(),
+            function: function.to_string(),
+            kind,
+        }
+    }
+
+    /// Bindings for the `wasi:clocks` interfaces
+    #[must_use]
+    pub fn wasi_clocks() -> Vec<Self> {
+        vec![
+            Self::new(
+                "wasi:clocks/monotonic-clock",
+                "now",
+                ClockKind::MonotonicNow,
+            ),
+            Self::new(
+                "wasi:clocks/monotonic-clock",
+                "resolution",
+                ClockKind::MonotonicResolution,
+            ),
+            Self::new("wasi:clocks/wall-clock", "now", ClockKind::WallNow),
+            Self::new(
+                "wasi:clocks/wall-clock",
+                "resolution",
+                ClockKind::WallResolution,
+            ),
+        ]
+    }
+
+    /// Check whether a call matches this binding
+    ///
+    /// Calls routed through the host callback registry use the target `host`
+    /// and a `module::function` key, which is matched as well.
+    fn matches(&self, target: &str, function: &str) -> bool {
+        if target.starts_with(self.interface.as_str()) && self.function == function {
+            return true;
+        }
+        function.rsplit_once("::").is_some_and(|(module, name)| {
+            module.starts_with(self.interface.as_str()) && self.function == name
+        })
+    }
+}
+
+/// How the virtual clock advances
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockMode {
+    /// Time only advances through [`VirtualClockStrategy::advance`],
+    /// [`VirtualClockStrategy::set`] and sleep functions
+    Manual,
+    /// Time follows the host clock scaled by `numerator / denominator`, in
+    /// addition to explicit advances
+    Scaled {
+        /// Numerator of the rate
+        numerator:   u32,
+        /// Denominator of the rate
+        denominator: u32,
+    },
+}
+
+/// Configuration for the virtual clock strategy
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VirtualClockConfig {
+    /// How the clock advances
+    pub mode:       ClockMode,
+    /// Monotonic time reported when the strategy is created
+    pub start:      Duration,
+    /// Wall-clock time (since the Unix epoch) at monotonic time zero
+    pub wall_epoch: Duration,
+    /// Resolution reported by resolution functions
+    pub resolution: Duration,
+    /// Host functions served from the virtual clock
+    pub bindings:   Vec<ClockBinding>,
+}
+
+#[cfg(feature = "std")]
+impl Default for VirtualClockConfig {
+    fn default() -> Self {
+        Self {
+            mode:       ClockMode::Manual,
+            start:      Duration::ZERO,
+            wall_epoch: Duration::ZERO,
+            resolution: Duration::from_nanos(1),
+            bindings:   ClockBinding::wasi_clocks(),
+        }
+    }
+}
+
+/// Mutable clock state
+#[cfg(feature = "std")]
+#[derive(Debug)]
+struct ClockState {
+    /// Virtual time accumulated through explicit advances
+    offset: Duration,
+    /// Host instant that scaled time is measured from
+    origin: Instant,
+}
+
+/// Strategy that serves clock host functions from a virtual clock
+#[cfg(feature = "std")]
+pub struct VirtualClockStrategy {
+    /// Configuration
+    config: VirtualClockConfig,
+    /// Clock state
+    state:  Mutex<ClockState>,
+}
+
+#[cfg(feature = "std")]
+impl VirtualClockStrategy {
+    /// Create a new virtual clock strategy
+    #[must_use]
+    pub fn new(config: VirtualClockConfig) -> Self {
+        let state = ClockState {
+            offset: config.start,
+            origin: Instant::now(),
+        };
+        Self {
+            config,
+            state: Mutex::new(state),
+        }
+    }
+
+    /// Get the current virtual monotonic time
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the clock state lock is poisoned.
+    pub fn now(&self) -> Result<Duration> {
+        let state = self.lock_state()?;
+        Ok(self.now_locked(&state))
+    }
+
+    /// Get the current virtual wall-clock time since the Unix epoch
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the clock state lock is poisoned.
+    pub fn wall_now(&self) -> Result<Duration> {
+        Ok(self.config.wall_epoch.saturating_add(self.now()?))
+    }
+
+    /// Advance the virtual clock by `delta`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the clock state lock is poisoned.
+    pub fn advance(&self, delta: Duration) -> Result<()> {
+        let mut state = self.lock_state()?;
+        state.offset = state.offset.saturating_add(delta);
+        Ok(())
+    }
+
+    /// Set the virtual monotonic time
+    ///
+    /// Moving the clock backwards violates monotonicity as observed by the
+    /// guest, so it is rejected.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `time` lies before the current virtual time or the
+    /// clock state lock is poisoned.
+    pub fn set(&self, time: Duration) -> Result<()> {
+        let mut state = self.lock_state()?;
+        if time < self.now_locked(&state) {
+            return Err(Error::runtime_invalid_argument(
+                "Virtual monotonic clock cannot move backwards",
+            ));
+        }
+        state.offset = time;
+        state.origin = Instant::now();
+        Ok(())
+    }
+
+    fn lock_state(&self) -> Result<std::sync::MutexGuard<'_, ClockState>> {
+        self.state
+            .lock()
+            .map_err(|_| Error::poisoned_lock("Virtual clock state lock poisoned"))
+    }
+
+    fn now_locked(&self, state: &ClockState) -> Duration {
+        match self.config.mode {
+            ClockMode::Manual => state.offset,
+            ClockMode::Scaled {
+                numerator,
+                denominator,
+            } => {
+                let real = state.origin.elapsed().as_nanos();
+                let scaled = real
+                    .saturating_mul(u128::from(numerator))
+                    .checked_div(u128::from(denominator))
+                    .unwrap_or(0);
+                let scaled = Duration::from_nanos(u64::try_from(scaled).unwrap_or(u64::MAX));
+                state.offset.saturating_add(scaled)
+            },
+        }
+    }
+
+    fn binding(&self, target: &str, function: &str) -> Option<ClockKind> {
+        self.config
+            .bindings
+            .iter()
+            .find(|binding| binding.matches(target, function))
+            .map(|binding| binding.kind)
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    fn nanos_value(duration: Duration) -> Value {
+        Value::I64(u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX) as i64)
+    }
+
+    #[allow(clippy::cast_possible_wrap)]
+    fn datetime_values(duration: Duration) -> Vec<Value> {
+        vec![
+            Value::I64(duration.as_secs() as i64),
+            Value::I32(duration.subsec_nanos() as i32),
+        ]
+    }
+}
+
+#[cfg(feature = "std")]
+impl LinkInterceptorStrategy for VirtualClockStrategy {
+    fn before_call(
+        &self,
+        _source: &str,
+        target: &str,
+        function: &str,
+        args: &[Value],
+    ) -> Result<Vec<Value>> {
+        if self.binding(target, function) == Some(ClockKind::Sleep) {
+            #[allow(clippy::cast_sign_loss)]
+            let nanos = match args.first() {
+                Some(Value::I64(nanos)) => *nanos as u64,
+                _ => {
+                    return Err(Error::runtime_type_mismatch(
+                        "Sleep function expects a u64 nanosecond duration",
+                    ))
+                },
+            };
+            self.advance(Duration::from_nanos(nanos))?;
+            // Results of the bypassed call
+            return Ok(Vec::new());
+        }
+
+        Ok(args.to_vec())
+    }
+
+    fn should_bypass_call(&self, target: &str, function: &str) -> bool {
+        self.binding(target, function) == Some(ClockKind::Sleep)
+    }
+
+    fn after_call(
+        &self,
+        _source: &str,
+        target: &str,
+        function: &str,
+        _args: &[Value],
+        result: Result<Vec<Value>>,
+    ) -> Result<Vec<Value>> {
+        let Some(kind) = self.binding(target, function) else {
+            return result;
+        };
+
+        match kind {
+            ClockKind::MonotonicNow => Ok(vec![Self::nanos_value(self.now()?)]),
+            ClockKind::MonotonicResolution => Ok(vec![Self::nanos_value(self.config.resolution)]),
+            ClockKind::WallNow => Ok(Self::datetime_values(self.wall_now()?)),
+            ClockKind::WallResolution => Ok(Self::datetime_values(self.config.resolution)),
+            ClockKind::Sleep => result,
+        }
+    }
+
+    fn clone_strategy(&self) -> Arc<dyn LinkInterceptorStrategy> {
+        Arc::new(Self::new(self.config.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONOTONIC: &str = "wasi:clocks/monotonic-clock@0.2.0";
+    const WALL: &str = "wasi:clocks/wall-clock@0.2.0";
+
+    fn host_result() -> Result<Vec<Value>> {
+        Ok(vec![Value::I64(123_456_789)])
+    }
+
+    #[test]
+    fn test_manual_clock_is_deterministic() {
+        let strategy = VirtualClockStrategy::new(VirtualClockConfig {
+            start: Duration::from_secs(5),
+            ..VirtualClockConfig::default()
+        });
+
+        let first = strategy.after_call("guest", MONOTONIC, "now", &[], host_result()).unwrap();
+        let second = strategy.after_call("guest", MONOTONIC, "now", &[], host_result()).unwrap();
+        assert_eq!(first, vec![Value::I64(5_000_000_000)]);
+        assert_eq!(first, second);
+
+        strategy.advance(Duration::from_millis(250)).unwrap();
+        let advanced = strategy.after_call("guest", MONOTONIC, "now", &[], host_result()).unwrap();
+        assert_eq!(advanced, vec![Value::I64(5_250_000_000)]);
+
+        // Calls through the host callback registry are matched by key
+        let keyed = strategy
+            .after_call(
+                "guest",
+                "host",
+                "wasi:clocks/monotonic-clock@0.2.0::now",
+                &[],
+                host_result(),
+            )
+            .unwrap();
+        assert_eq!(keyed, advanced);
+
+        // Unbound functions are passed through
+        let other = strategy.after_call("guest", "other", "now", &[], host_result()).unwrap();
+        assert_eq!(other, vec![Value::I64(123_456_789)]);
+    }
+
+    #[test]
+    fn test_wall_clock_and_set() {
+        let strategy = VirtualClockStrategy::new(VirtualClockConfig {
+            wall_epoch: Duration::from_secs(1_700_000_000),
+            ..VirtualClockConfig::default()
+        });
+
+        strategy.set(Duration::from_nanos(1_500_000_000)).unwrap();
+        let wall = strategy.after_call("guest", WALL, "now", &[], host_result()).unwrap();
+        assert_eq!(
+            wall,
+            vec![Value::I64(1_700_000_001), Value::I32(500_000_000)]
+        );
+
+        assert!(strategy.set(Duration::from_secs(1)).is_err());
+    }
+
+    #[test]
+    fn test_sleep_advances_clock_without_host_sleep() {
+        let mut config = VirtualClockConfig::default();
+        config.bindings.push(ClockBinding::new("host:timer", "sleep", ClockKind::Sleep));
+        let strategy = Arc::new(VirtualClockStrategy::new(config));
+        let mut interceptor = crate::LinkInterceptor::new("guest");
+        interceptor.add_strategy(strategy.clone());
+
+        let results = interceptor
+            .intercept_call(
+                "host:timer",
+                "sleep",
+                vec![Value::I64(3_600_000_000_000)],
+                |_| panic!("host sleep must be bypassed"),
+            )
+            .unwrap();
+        assert!(results.is_empty());
+        assert_eq!(strategy.now().unwrap(), Duration::from_secs(3_600));
+
+        // Clock reads still reach the host
+        let now = interceptor.intercept_call(MONOTONIC, "now", Vec::new(), |_| host_result());
+        assert_eq!(now.unwrap(), vec![Value::I64(3_600_000_000_000)]);
+        assert!(strategy.before_call("guest", "host:timer", "sleep", &[Value::I32(1)]).is_err());
+    }
+
+    #[test]
+    fn test_scaled_clock_advances_with_host_time() {
+        let strategy = VirtualClockStrategy::new(VirtualClockConfig {
+            mode: ClockMode::Scaled {
+                numerator:   1000,
+                denominator: 1,
+            },
+            ..VirtualClockConfig::default()
+        });
+
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(strategy.now().unwrap() >= Duration::from_secs(2));
+    }
+}
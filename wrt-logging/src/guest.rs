@@ -0,0 +1,418 @@
+//! Guest-to-host logging interface.
+//!
+//! This module defines the host function through which WebAssembly guests log
+//! messages, together with a built-in host implementation. The interface is
+//! imported as `wrt:logging` `log` with the core signature
+//!
+//! ```text
+//! (func (param $level i32) (param $target_ptr i32) (param $target_len i32)
+//!       (param $msg_ptr i32) (param $msg_len i32))
+//! ```
+//!
+//! where `level` is 0 (trace) to 5 (critical) and `target`/`msg` are UTF-8
+//! strings in the guest's linear memory. The built-in implementation applies a
+//! minimum level, truncates overlong messages, enforces a per-instance rate
+//! limit and forwards accepted messages to the `log` facade, so every embedder
+//! gets guest logs through the same path as its own.
+
+use alloc::sync::Arc;
+use core::{
+    any::Any,
+    time::Duration,
+};
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::Instant,
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_foundation::values::Value;
+use wrt_host::{
+    function::CloneableFn,
+    CallbackRegistry,
+};
+
+use crate::level::LogLevel;
+
+/// Module name of the guest logging interface
+pub const GUEST_LOG_MODULE: &str = "wrt:logging";
+
+/// Function name of the guest logging interface
+pub const GUEST_LOG_FUNCTION: &str = "log";
+
+/// Log target used when the guest passes an empty target
+pub const DEFAULT_GUEST_TARGET: &str = "wasm";
+
+/// Access to the calling instance, implemented by the engine passed to host
+/// functions
+pub trait GuestLogContext {
+    /// Identifier of the instance that is currently executing
+    fn instance_id(&self) -> u32;
+
+    /// Read `len` bytes at `offset` from the instance's default memory
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the range is out of bounds or the instance has no
+    /// memory.
+    fn read_guest_memory(&self, offset: u32, len: u32) -> Result<Vec<u8>>;
+}
+
+/// Configuration of the built-in guest logger
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GuestLogConfig {
+    /// Messages below this level are discarded
+    pub min_level:           LogLevel,
+    /// Maximum number of messages an instance may log per window
+    pub messages_per_window: u32,
+    /// Length of a rate-limit window
+    pub window:              Duration,
+    /// Messages longer than this many bytes are truncated
+    pub max_message_len:     u32,
+    /// Targets longer than this many bytes are rejected
+    pub max_target_len:      u32,
+}
+
+impl Default for GuestLogConfig {
+    fn default() -> Self {
+        Self {
+            min_level:           LogLevel::Trace,
+            messages_per_window: 100,
+            window:              Duration::from_secs(1),
+            max_message_len:     4096,
+            max_target_len:      128,
+        }
+    }
+}
+
+/// Outcome of a guest log call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuestLogOutcome {
+    /// The message was forwarded to the host logger
+    Logged,
+    /// The message was below the configured minimum level
+    Filtered,
+    /// The instance exceeded its rate limit
+    RateLimited,
+}
+
+/// Per-instance rate-limit state
+#[derive(Debug)]
+struct RateWindow {
+    /// Start of the current window
+    start:         Instant,
+    /// Messages accepted in the current window
+    logged:        u32,
+    /// Messages dropped in the current window
+    dropped:       u32,
+    /// Messages dropped over the lifetime of the instance
+    total_dropped: u64,
+}
+
+/// Built-in host implementation of the guest logging interface
+///
+/// Clones share their rate-limit state.
+#[derive(Debug, Clone)]
+pub struct GuestLogger {
+    config:  GuestLogConfig,
+    windows: Arc<Mutex<HashMap<u32, RateWindow>>>,
+}
+
+impl GuestLogger {
+    /// Create a new guest logger
+    #[must_use]
+    pub fn new(config: GuestLogConfig) -> Self {
+        Self {
+            config,
+            windows: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Get the configuration
+    #[must_use]
+    pub fn config(&self) -> &GuestLogConfig {
+        &self.config
+    }
+
+    /// Register the `wrt:logging` `log` host function.
+    ///
+    /// The engine passed to host functions must be of type `E`.
+    pub fn register<E>(&self, registry: &mut CallbackRegistry)
+    where
+        E: GuestLogContext + 'static,
+    {
+        let logger = self.clone();
+        registry.register_host_function(
+            GUEST_LOG_MODULE,
+            GUEST_LOG_FUNCTION,
+            CloneableFn::new_with_args(move |target: &mut dyn Any, args: Vec<Value>| {
+                let context = target.downcast_ref::<E>().ok_or_else(|| {
+                    Error::runtime_invalid_argument("Engine does not provide a guest log context")
+                })?;
+                logger.handle_call(context, &args)?;
+                Ok(Vec::new())
+            }),
+        );
+    }
+
+    /// Handle a call of the logging interface with raw guest arguments
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the arguments do not match the interface signature,
+    /// the level is unknown, the strings are out of bounds, or the target is
+    /// too long.
+    pub fn handle_call(
+        &self,
+        context: &dyn GuestLogContext,
+        args: &[Value],
+    ) -> Result<GuestLogOutcome> {
+        let [
+            Value::I32(level),
+            Value::I32(target_ptr),
+            Value::I32(target_len),
+            Value::I32(msg_ptr),
+            Value::I32(msg_len),
+        ] = *args
+        else {
+            return Err(Error::runtime_type_mismatch(
+                "Guest log expects (i32, i32, i32, i32, i32) arguments",
+            ));
+        };
+
+        let level = level_from_guest(level)?;
+        if level < self.config.min_level {
+            return Ok(GuestLogOutcome::Filtered);
+        }
+
+        let target_len = target_len as u32;
+        if target_len > self.config.max_target_len {
+            return Err(Error::runtime_invalid_argument("Guest log target too long"));
+        }
+        let target = context.read_guest_memory(target_ptr as u32, target_len)?;
+
+        // Only read as much of the message as will be logged
+        let msg_len = (msg_len as u32).min(self.config.max_message_len);
+        let message = context.read_guest_memory(msg_ptr as u32, msg_len)?;
+
+        let target = String::from_utf8_lossy(&target);
+        let target = if target.is_empty() { DEFAULT_GUEST_TARGET } else { &target };
+        let message = String::from_utf8_lossy(&message);
+
+        self.log(context.instance_id(), level, target, &message)
+    }
+
+    /// Log a message on behalf of an instance, honoring the configured level
+    /// and rate limit
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the rate-limit state lock is poisoned.
+    pub fn log(
+        &self,
+        instance_id: u32,
+        level: LogLevel,
+        target: &str,
+        message: &str,
+    ) -> Result<GuestLogOutcome> {
+        if level < self.config.min_level {
+            return Ok(GuestLogOutcome::Filtered);
+        }
+
+        let mut windows = self
+            .windows
+            .lock()
+            .map_err(|_| Error::poisoned_lock("Guest logger state lock poisoned"))?;
+        let now = Instant::now();
+        let window = windows.entry(instance_id).or_insert(RateWindow {
+            start:         now,
+            logged:        0,
+            dropped:       0,
+            total_dropped: 0,
+        });
+
+        if now.duration_since(window.start) >= self.config.window {
+            if window.dropped > 0 {
+                log::warn!(
+                    target: target,
+                    "[instance {}] {} guest log messages dropped by rate limit",
+                    instance_id,
+                    window.dropped
+                );
+            }
+            window.start = now;
+            window.logged = 0;
+            window.dropped = 0;
+        }
+
+        if window.logged >= self.config.messages_per_window {
+            window.dropped = window.dropped.saturating_add(1);
+            window.total_dropped = window.total_dropped.saturating_add(1);
+            return Ok(GuestLogOutcome::RateLimited);
+        }
+        window.logged += 1;
+        drop(windows);
+
+        log::log!(target: target, to_log_level(level), "[instance {}] {}", instance_id, message);
+        Ok(GuestLogOutcome::Logged)
+    }
+
+    /// Number of messages of an instance dropped by the rate limit
+    #[must_use]
+    pub fn dropped_count(&self, instance_id: u32) -> u64 {
+        self.windows
+            .lock()
+            .ok()
+            .and_then(|windows| windows.get(&instance_id).map(|w| w.total_dropped))
+            .unwrap_or(0)
+    }
+
+    /// Forget the rate-limit state of an instance, e.g. when it is dropped
+    pub fn remove_instance(&self, instance_id: u32) {
+        if let Ok(mut windows) = self.windows.lock() {
+            windows.remove(&instance_id);
+        }
+    }
+}
+
+impl Default for GuestLogger {
+    fn default() -> Self {
+        Self::new(GuestLogConfig::default())
+    }
+}
+
+/// Decode a guest log level
+fn level_from_guest(level: i32) -> Result<LogLevel> {
+    match level {
+        0 => Ok(LogLevel::Trace),
+        1 => Ok(LogLevel::Debug),
+        2 => Ok(LogLevel::Info),
+        3 => Ok(LogLevel::Warn),
+        4 => Ok(LogLevel::Error),
+        5 => Ok(LogLevel::Critical),
+        _ => Err(Error::runtime_invalid_argument("Unknown guest log level")),
+    }
+}
+
+/// Map a log level onto the `log` facade
+fn to_log_level(level: LogLevel) -> log::Level {
+    match level {
+        LogLevel::Trace => log::Level::Trace,
+        LogLevel::Debug => log::Level::Debug,
+        LogLevel::Info => log::Level::Info,
+        LogLevel::Warn => log::Level::Warn,
+        LogLevel::Error | LogLevel::Critical => log::Level::Error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestInstance {
+        id:     u32,
+        memory: Vec<u8>,
+    }
+
+    impl GuestLogContext for TestInstance {
+        fn instance_id(&self) -> u32 {
+            self.id
+        }
+
+        fn read_guest_memory(&self, offset: u32, len: u32) -> Result<Vec<u8>> {
+            let start = offset as usize;
+            let end = start
+                .checked_add(len as usize)
+                .ok_or_else(|| Error::runtime_out_of_bounds("Guest memory access overflow"))?;
+            self.memory
+                .get(start..end)
+                .map(<[u8]>::to_vec)
+                .ok_or_else(|| Error::runtime_out_of_bounds("Guest memory access out of bounds"))
+        }
+    }
+
+    fn instance(id: u32) -> TestInstance {
+        let mut memory = vec![0u8; 64];
+        memory[..3].copy_from_slice(b"app");
+        memory[16..21].copy_from_slice(b"hello");
+        TestInstance { id, memory }
+    }
+
+    fn args(level: i32, msg_len: i32) -> Vec<Value> {
+        vec![
+            Value::I32(level),
+            Value::I32(0),
+            Value::I32(3),
+            Value::I32(16),
+            Value::I32(msg_len),
+        ]
+    }
+
+    #[test]
+    fn test_level_filter_and_validation() {
+        let logger = GuestLogger::new(GuestLogConfig {
+            min_level: LogLevel::Warn,
+            ..GuestLogConfig::default()
+        });
+        let guest = instance(1);
+
+        assert_eq!(logger.handle_call(&guest, &args(2, 5)).unwrap(), GuestLogOutcome::Filtered);
+        assert_eq!(logger.handle_call(&guest, &args(4, 5)).unwrap(), GuestLogOutcome::Logged);
+        assert!(logger.handle_call(&guest, &args(9, 5)).is_err());
+        assert!(logger.handle_call(&guest, &[Value::I32(4)]).is_err());
+
+        // Out-of-bounds messages trap instead of being logged
+        let mut out_of_bounds = args(4, 5);
+        out_of_bounds[3] = Value::I32(62);
+        assert!(logger.handle_call(&guest, &out_of_bounds).is_err());
+    }
+
+    #[test]
+    fn test_rate_limit_is_per_instance() {
+        let logger = GuestLogger::new(GuestLogConfig {
+            messages_per_window: 2,
+            window: Duration::from_secs(3600),
+            ..GuestLogConfig::default()
+        });
+        let first = instance(1);
+        let second = instance(2);
+
+        for _ in 0..2 {
+            assert_eq!(logger.handle_call(&first, &args(2, 5)).unwrap(), GuestLogOutcome::Logged);
+        }
+        assert_eq!(
+            logger.handle_call(&first, &args(2, 5)).unwrap(),
+            GuestLogOutcome::RateLimited
+        );
+        assert_eq!(logger.handle_call(&second, &args(2, 5)).unwrap(), GuestLogOutcome::Logged);
+
+        assert_eq!(logger.dropped_count(1), 1);
+        assert_eq!(logger.dropped_count(2), 0);
+
+        logger.remove_instance(1);
+        assert_eq!(logger.handle_call(&first, &args(2, 5)).unwrap(), GuestLogOutcome::Logged);
+    }
+
+    #[test]
+    fn test_registered_host_function() {
+        let mut registry = CallbackRegistry::new();
+        GuestLogger::default().register::<TestInstance>(&mut registry);
+        assert!(registry.has_host_function(GUEST_LOG_MODULE, GUEST_LOG_FUNCTION));
+
+        let mut guest = instance(7);
+        let result = registry
+            .call_host_function(&mut guest, GUEST_LOG_MODULE, GUEST_LOG_FUNCTION, args(2, 5))
+            .unwrap();
+        assert!(result.is_empty());
+
+        // Engines that do not provide a log context are rejected
+        let mut other = 0u32;
+        assert!(registry
+            .call_host_function(&mut other, GUEST_LOG_MODULE, GUEST_LOG_FUNCTION, args(2, 5))
+            .is_err());
+    }
+}
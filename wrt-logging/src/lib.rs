@@ -61,6 +61,14 @@
 /// Binary std/no_std choice
 pub mod minimal_handler;
 
+/// Guest-to-host logging interface with rate limiting.
+///
+/// This module defines the `wrt:logging` host function that guests use to log
+/// and the built-in host implementation that routes into the `log` facade.
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod guest;
+
 /// Bounded logging infrastructure with configurable limits (Agent C).
 ///
 /// This module provides enhanced logging functionality with bounded buffers
@@ -80,6 +88,16 @@
     LogMetadata,
     LoggerId,
 };
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use guest::{
+    GuestLogConfig,
+    GuestLogContext,
+    GuestLogOutcome,
+    GuestLogger,
+    GUEST_LOG_FUNCTION,
+    GUEST_LOG_MODULE,
+};
 pub use handler::{
     LogHandler,
     LoggingExt,
@@ -36,6 +36,7 @@
 pub mod ops;
 pub mod prelude;
 pub mod safety;
+pub mod soft_float;
 pub mod traits;
 
 // SIMD operations module (requires platform feature)
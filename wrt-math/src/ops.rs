@@ -11,6 +11,8 @@
 //! returning `Result` with `TrapCode` for Wasm-defined trapping behavior.
 
 // Conditionally import std or core items for f32/f64
+#[cfg(feature = "soft-float")]
+use core::cmp::Ordering;
 #[cfg(not(feature = "std"))]
 use core::{
     f32,
@@ -29,12 +31,14 @@
 };
 
 // Import necessary items from this crate's prelude and wrt-error
+// Result, TrapCode, WrtError should come via wrt_error directly or be aliased
+// in prelude
 use crate::prelude::{
     FloatBits32,
     FloatBits64,
-}; // Result, TrapCode, WrtError should come
-   // via wrt_error directly or be aliased in
-   // prelude
+};
+#[cfg(feature = "soft-float")]
+use crate::soft_float;
 
 // Module-level constants for Wasm conversion boundaries
 const I64_MAX_AS_F32: f32 = 9_223_372_036_854_775_808.0_f32;
@@ -529,6 +533,19 @@ fn trap(code: TrapCode) -> WrtError {
     code.into() // Relies on the From<TrapCode> for WrtError impl
 }
 
+/// Trapping truncation of a soft-float integer value to `T`
+#[cfg(feature = "soft-float")]
+fn soft_trunc<T: TryFrom<i128>>(value: Option<i128>) -> Result<T> {
+    let value = value.ok_or_else(|| trap(TrapCode::InvalidConversionToInteger))?;
+    T::try_from(value).map_err(|_| trap(TrapCode::IntegerOverflow))
+}
+
+/// Saturating truncation of a soft-float integer value to `T`
+#[cfg(feature = "soft-float")]
+fn soft_trunc_sat<T: TryFrom<i128>>(value: i128, min: T, max: T) -> T {
+    T::try_from(value).unwrap_or(if value < 0 { min } else { max })
+}
+
 // --- I32 Operations ---
 
 /// i32.add: Add two i32 values.
@@ -1266,7 +1283,14 @@ pub fn i64_ge_u(lhs: u64, rhs: u64) -> Result<i32> {
 /// This function does not currently return an error.
 #[inline]
 pub fn f32_add(lhs: FloatBits32, rhs: FloatBits32) -> Result<FloatBits32> {
-    Ok(FloatBits32::from_float(lhs.value() + rhs.value()))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f32_add(lhs, rhs))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        Ok(FloatBits32::from_float(lhs.value() + rhs.value()))
+    }
 }
 
 /// f32.sub: Subtract two f32 values.
@@ -1276,7 +1300,14 @@ pub fn f32_add(lhs: FloatBits32, rhs: FloatBits32) -> Result<FloatBits32> {
 /// This function does not currently return an error.
 #[inline]
 pub fn f32_sub(lhs: FloatBits32, rhs: FloatBits32) -> Result<FloatBits32> {
-    Ok(FloatBits32::from_float(lhs.value() - rhs.value()))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f32_sub(lhs, rhs))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        Ok(FloatBits32::from_float(lhs.value() - rhs.value()))
+    }
 }
 
 /// f32.mul: Multiply two f32 values.
@@ -1286,7 +1317,14 @@ pub fn f32_sub(lhs: FloatBits32, rhs: FloatBits32) -> Result<FloatBits32> {
 /// This function does not currently return an error.
 #[inline]
 pub fn f32_mul(lhs: FloatBits32, rhs: FloatBits32) -> Result<FloatBits32> {
-    Ok(FloatBits32::from_float(lhs.value() * rhs.value()))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f32_mul(lhs, rhs))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        Ok(FloatBits32::from_float(lhs.value() * rhs.value()))
+    }
 }
 
 /// f32.div: Divide two f32 values.
@@ -1297,7 +1335,14 @@ pub fn f32_mul(lhs: FloatBits32, rhs: FloatBits32) -> Result<FloatBits32> {
 /// This function does not currently return an error.
 #[inline]
 pub fn f32_div(lhs: FloatBits32, rhs: FloatBits32) -> Result<FloatBits32> {
-    Ok(FloatBits32::from_float(lhs.value() / rhs.value()))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f32_div(lhs, rhs))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        Ok(FloatBits32::from_float(lhs.value() / rhs.value()))
+    }
 }
 
 /// f32.abs: Absolute value of an f32.
@@ -1349,7 +1394,14 @@ pub fn wasm_f32_copysign(lhs: FloatBits32, rhs: FloatBits32) -> Result<FloatBits
 /// This function does not currently return an error.
 #[inline]
 pub fn wasm_f32_ceil(val: FloatBits32) -> Result<FloatBits32> {
-    Ok(FloatBits32::from_float(f32_ceil_compat(val.value())))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f32_ceil(val))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        Ok(FloatBits32::from_float(f32_ceil_compat(val.value())))
+    }
 }
 
 /// f32.floor: Floor of an f32 value.
@@ -1359,7 +1411,14 @@ pub fn wasm_f32_ceil(val: FloatBits32) -> Result<FloatBits32> {
 /// This function does not currently return an error.
 #[inline]
 pub fn wasm_f32_floor(val: FloatBits32) -> Result<FloatBits32> {
-    Ok(FloatBits32::from_float(f32_floor_compat(val.value())))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f32_floor(val))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        Ok(FloatBits32::from_float(f32_floor_compat(val.value())))
+    }
 }
 
 /// f32.trunc: Truncate f32 value (to integer towards zero).
@@ -1369,11 +1428,18 @@ pub fn wasm_f32_floor(val: FloatBits32) -> Result<FloatBits32> {
 /// This function does not currently return an error.
 #[inline]
 pub fn wasm_f32_trunc(val: FloatBits32) -> Result<FloatBits32> {
-    // Note: f32_trunc_compat returns f32, we need to ensure bit patterns are
-    // preserved if that's critical. .to_bits().from_bits() ensures this.
-    Ok(FloatBits32::from_bits(
-        f32_trunc_compat(val.value()).to_bits(),
-    ))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f32_trunc(val))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        // Note: f32_trunc_compat returns f32, we need to ensure bit patterns are
+        // preserved if that's critical. .to_bits().from_bits() ensures this.
+        Ok(FloatBits32::from_bits(
+            f32_trunc_compat(val.value()).to_bits(),
+        ))
+    }
 }
 
 /// f32.nearest: Round f32 to nearest integer (ties to even).
@@ -1383,9 +1449,16 @@ pub fn wasm_f32_trunc(val: FloatBits32) -> Result<FloatBits32> {
 /// This function does not currently return an error.
 #[inline]
 pub fn wasm_f32_nearest(val: FloatBits32) -> Result<FloatBits32> {
-    Ok(FloatBits32::from_float(f32_round_ties_to_even_compat(
-        val.value(),
-    )))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f32_nearest(val))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        Ok(FloatBits32::from_float(f32_round_ties_to_even_compat(
+            val.value(),
+        )))
+    }
 }
 
 /// f32.sqrt: Square root of an f32 value.
@@ -1395,7 +1468,14 @@ pub fn wasm_f32_nearest(val: FloatBits32) -> Result<FloatBits32> {
 /// This function does not currently return an error.
 #[inline]
 pub fn wasm_f32_sqrt(val: FloatBits32) -> Result<FloatBits32> {
-    Ok(FloatBits32::from_float(f32_sqrt_compat(val.value())))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f32_sqrt(val))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        Ok(FloatBits32::from_float(f32_sqrt_compat(val.value())))
+    }
 }
 
 /// f32.min: Minimum of two f32 values (WASM semantics).
@@ -1407,24 +1487,31 @@ pub fn wasm_f32_sqrt(val: FloatBits32) -> Result<FloatBits32> {
 /// This function does not currently return an error.
 #[inline]
 pub fn wasm_f32_min(lhs: FloatBits32, rhs: FloatBits32) -> Result<FloatBits32> {
-    let l = lhs.value();
-    let r = rhs.value();
-
-    if l.is_nan() || r.is_nan() {
-        Ok(FloatBits32::NAN)
-    } else if l == r && l == 0.0 {
-        // Special handling for +0.0 and -0.0
-        // Wasm: min(-0.0, +0.0) is -0.0. min(+0.0, -0.0) is -0.0.
-        // If l is -0.0 (negative sign bit), it's smaller or equal.
-        if l.is_sign_negative() {
-            Ok(lhs)
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f32_min(lhs, rhs))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        let l = lhs.value();
+        let r = rhs.value();
+
+        if l.is_nan() || r.is_nan() {
+            Ok(FloatBits32::NAN)
+        } else if l == r && l == 0.0 {
+            // Special handling for +0.0 and -0.0
+            // Wasm: min(-0.0, +0.0) is -0.0. min(+0.0, -0.0) is -0.0.
+            // If l is -0.0 (negative sign bit), it's smaller or equal.
+            if l.is_sign_negative() {
+                Ok(lhs)
+            } else {
+                Ok(rhs)
+            } // If l is +0.0, r must be -0.0 or +0.0
         } else {
-            Ok(rhs)
-        } // If l is +0.0, r must be -0.0 or +0.0
-    } else {
-        // Standard comparison for non-NaN, non-zero cases.
-        // Rust's f32::min behaves correctly for Wasm's non-NaN requirements.
-        Ok(FloatBits32::from_float(l.min(r)))
+            // Standard comparison for non-NaN, non-zero cases.
+            // Rust's f32::min behaves correctly for Wasm's non-NaN requirements.
+            Ok(FloatBits32::from_float(l.min(r)))
+        }
     }
 }
 
@@ -1437,23 +1524,30 @@ pub fn wasm_f32_min(lhs: FloatBits32, rhs: FloatBits32) -> Result<FloatBits32> {
 /// This function does not currently return an error.
 #[inline]
 pub fn wasm_f32_max(lhs: FloatBits32, rhs: FloatBits32) -> Result<FloatBits32> {
-    let l = lhs.value();
-    let r = rhs.value();
-
-    if l.is_nan() || r.is_nan() {
-        Ok(FloatBits32::NAN)
-    } else if l == r && l == 0.0 {
-        // Special handling for +0.0 and -0.0
-        // Wasm: max(-0.0, +0.0) is +0.0. max(+0.0, -0.0) is +0.0.
-        // If l is +0.0 (positive sign bit), it's greater or equal.
-        if l.is_sign_positive() {
-            Ok(lhs)
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f32_max(lhs, rhs))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        let l = lhs.value();
+        let r = rhs.value();
+
+        if l.is_nan() || r.is_nan() {
+            Ok(FloatBits32::NAN)
+        } else if l == r && l == 0.0 {
+            // Special handling for +0.0 and -0.0
+            // Wasm: max(-0.0, +0.0) is +0.0. max(+0.0, -0.0) is +0.0.
+            // If l is +0.0 (positive sign bit), it's greater or equal.
+            if l.is_sign_positive() {
+                Ok(lhs)
+            } else {
+                Ok(rhs)
+            } // If l is -0.0, r must be +0.0 or -0.0
         } else {
-            Ok(rhs)
-        } // If l is -0.0, r must be +0.0 or -0.0
-    } else {
-        // Rust's f32::max behaves correctly for Wasm's non-NaN requirements.
-        Ok(FloatBits32::from_float(l.max(r)))
+            // Rust's f32::max behaves correctly for Wasm's non-NaN requirements.
+            Ok(FloatBits32::from_float(l.max(r)))
+        }
     }
 }
 
@@ -1462,25 +1556,53 @@ pub fn wasm_f32_max(lhs: FloatBits32, rhs: FloatBits32) -> Result<FloatBits32> {
 /// f64.add: Add two f64 values.
 #[inline]
 pub fn f64_add(lhs: FloatBits64, rhs: FloatBits64) -> Result<FloatBits64> {
-    Ok(FloatBits64::from_float(lhs.value() + rhs.value()))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f64_add(lhs, rhs))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        Ok(FloatBits64::from_float(lhs.value() + rhs.value()))
+    }
 }
 
 /// f64.sub: Subtract two f64 values.
 #[inline]
 pub fn f64_sub(lhs: FloatBits64, rhs: FloatBits64) -> Result<FloatBits64> {
-    Ok(FloatBits64::from_float(lhs.value() - rhs.value()))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f64_sub(lhs, rhs))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        Ok(FloatBits64::from_float(lhs.value() - rhs.value()))
+    }
 }
 
 /// f64.mul: Multiply two f64 values.
 #[inline]
 pub fn f64_mul(lhs: FloatBits64, rhs: FloatBits64) -> Result<FloatBits64> {
-    Ok(FloatBits64::from_float(lhs.value() * rhs.value()))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f64_mul(lhs, rhs))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        Ok(FloatBits64::from_float(lhs.value() * rhs.value()))
+    }
 }
 
 /// f64.div: Divide two f64 values.
 #[inline]
 pub fn f64_div(lhs: FloatBits64, rhs: FloatBits64) -> Result<FloatBits64> {
-    Ok(FloatBits64::from_float(lhs.value() / rhs.value()))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f64_div(lhs, rhs))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        Ok(FloatBits64::from_float(lhs.value() / rhs.value()))
+    }
 }
 
 /// f64.abs: Absolute value of an f64.
@@ -1511,78 +1633,127 @@ pub fn wasm_f64_copysign(lhs: FloatBits64, rhs: FloatBits64) -> Result<FloatBits
 /// f64.ceil: Ceiling of an f64 value.
 #[inline]
 pub fn wasm_f64_ceil(val: FloatBits64) -> Result<FloatBits64> {
-    Ok(FloatBits64::from_float(f64_ceil_compat(val.value())))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f64_ceil(val))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        Ok(FloatBits64::from_float(f64_ceil_compat(val.value())))
+    }
 }
 
 /// f64.floor: Floor of an f64 value.
 #[inline]
 pub fn wasm_f64_floor(val: FloatBits64) -> Result<FloatBits64> {
-    Ok(FloatBits64::from_float(f64_floor_compat(val.value())))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f64_floor(val))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        Ok(FloatBits64::from_float(f64_floor_compat(val.value())))
+    }
 }
 
 /// f64.trunc: Truncate f64 value (to integer towards zero).
 #[inline]
 pub fn wasm_f64_trunc(val: FloatBits64) -> Result<FloatBits64> {
-    Ok(FloatBits64::from_bits(
-        f64_trunc_compat(val.value()).to_bits(),
-    ))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f64_trunc(val))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        Ok(FloatBits64::from_bits(
+            f64_trunc_compat(val.value()).to_bits(),
+        ))
+    }
 }
 
 /// f64.nearest: Round to nearest integer, ties to even.
 /// Follows IEEE 754-2008 `roundToIntegralTiesToEven`.
 #[inline]
 pub fn wasm_f64_nearest(val: FloatBits64) -> Result<FloatBits64> {
-    let x = val.value();
-    // Wasm spec: NaN -> canonical NaN; +/-Inf -> +/-Inf; +/-0 -> +/-0
-    if x.is_nan() {
-        return Ok(FloatBits64::NAN);
-    }
-    if x.is_infinite() || x == 0.0 {
-        return Ok(val);
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f64_nearest(val))
     }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        let x = val.value();
+        // Wasm spec: NaN -> canonical NaN; +/-Inf -> +/-Inf; +/-0 -> +/-0
+        if x.is_nan() {
+            return Ok(FloatBits64::NAN);
+        }
+        if x.is_infinite() || x == 0.0 {
+            return Ok(val);
+        }
 
-    Ok(FloatBits64::from_float(f64_round_ties_to_even_compat(x)))
+        Ok(FloatBits64::from_float(f64_round_ties_to_even_compat(x)))
+    }
 }
 
 /// f64.sqrt: Square root of an f64 value.
 #[inline]
 pub fn wasm_f64_sqrt(val: FloatBits64) -> Result<FloatBits64> {
-    Ok(FloatBits64::from_float(f64_sqrt_compat(val.value())))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f64_sqrt(val))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        Ok(FloatBits64::from_float(f64_sqrt_compat(val.value())))
+    }
 }
 
 /// f64.min: Minimum of two f64 values (WASM semantics).
 #[inline]
 pub fn wasm_f64_min(lhs: FloatBits64, rhs: FloatBits64) -> Result<FloatBits64> {
-    let l = lhs.value();
-    let r = rhs.value();
-    if l.is_nan() || r.is_nan() {
-        Ok(FloatBits64::NAN)
-    } else if l == r && l == 0.0 {
-        if l.is_sign_negative() {
-            Ok(lhs)
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f64_min(lhs, rhs))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        let l = lhs.value();
+        let r = rhs.value();
+        if l.is_nan() || r.is_nan() {
+            Ok(FloatBits64::NAN)
+        } else if l == r && l == 0.0 {
+            if l.is_sign_negative() {
+                Ok(lhs)
+            } else {
+                Ok(rhs)
+            }
         } else {
-            Ok(rhs)
+            Ok(FloatBits64::from_float(l.min(r)))
         }
-    } else {
-        Ok(FloatBits64::from_float(l.min(r)))
     }
 }
 
 /// f64.max: Maximum of two f64 values (WASM semantics).
 #[inline]
 pub fn wasm_f64_max(lhs: FloatBits64, rhs: FloatBits64) -> Result<FloatBits64> {
-    let l = lhs.value();
-    let r = rhs.value();
-    if l.is_nan() || r.is_nan() {
-        Ok(FloatBits64::NAN)
-    } else if l == r && l == 0.0 {
-        if l.is_sign_positive() {
-            Ok(lhs)
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f64_max(lhs, rhs))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        let l = lhs.value();
+        let r = rhs.value();
+        if l.is_nan() || r.is_nan() {
+            Ok(FloatBits64::NAN)
+        } else if l == r && l == 0.0 {
+            if l.is_sign_positive() {
+                Ok(lhs)
+            } else {
+                Ok(rhs)
+            }
         } else {
-            Ok(rhs)
+            Ok(FloatBits64::from_float(l.max(r)))
         }
-    } else {
-        Ok(FloatBits64::from_float(l.max(r)))
     }
 }
 
@@ -1592,105 +1763,217 @@ pub fn wasm_f64_max(lhs: FloatBits64, rhs: FloatBits64) -> Result<FloatBits64> {
 /// WebAssembly f32.eq operation.
 #[inline]
 pub fn f32_eq(lhs: FloatBits32, rhs: FloatBits32) -> Result<i32> {
-    let l = lhs.value();
-    let r = rhs.value();
-    // Wasm: NaN == X is false. NaN == NaN is false.
-    // Standard Rust `l == r` handles this correctly for non-NaNs.
-    // If either is NaN, `l == r` is false.
-    Ok(i32::from(l == r))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(i32::from(
+            soft_float::f32_compare(lhs, rhs) == Some(Ordering::Equal),
+        ))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        let l = lhs.value();
+        let r = rhs.value();
+        // Wasm: NaN == X is false. NaN == NaN is false.
+        // Standard Rust `l == r` handles this correctly for non-NaNs.
+        // If either is NaN, `l == r` is false.
+        Ok(i32::from(l == r))
+    }
 }
 
 /// WebAssembly f32.ne operation.
 #[inline]
 pub fn f32_ne(lhs: FloatBits32, rhs: FloatBits32) -> Result<i32> {
-    let l = lhs.value();
-    let r = rhs.value();
-    // Wasm: NaN != X is true. NaN != NaN is true.
-    // Standard Rust `l != r` handles this correctly.
-    Ok(i32::from(l != r))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(i32::from(
+            soft_float::f32_compare(lhs, rhs) != Some(Ordering::Equal),
+        ))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        let l = lhs.value();
+        let r = rhs.value();
+        // Wasm: NaN != X is true. NaN != NaN is true.
+        // Standard Rust `l != r` handles this correctly.
+        Ok(i32::from(l != r))
+    }
 }
 
 /// WebAssembly f32.lt operation.
 #[inline]
 pub fn f32_lt(lhs: FloatBits32, rhs: FloatBits32) -> Result<i32> {
-    let l = lhs.value();
-    let r = rhs.value();
-    // Wasm: if either is NaN, result is false.
-    // Rust `l < r` is false if either is NaN.
-    Ok(i32::from(l < r))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(i32::from(
+            soft_float::f32_compare(lhs, rhs) == Some(Ordering::Less),
+        ))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        let l = lhs.value();
+        let r = rhs.value();
+        // Wasm: if either is NaN, result is false.
+        // Rust `l < r` is false if either is NaN.
+        Ok(i32::from(l < r))
+    }
 }
 
 /// WebAssembly f32.gt operation.
 #[inline]
 pub fn f32_gt(lhs: FloatBits32, rhs: FloatBits32) -> Result<i32> {
-    let l = lhs.value();
-    let r = rhs.value();
-    Ok(i32::from(l > r))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(i32::from(
+            soft_float::f32_compare(lhs, rhs) == Some(Ordering::Greater),
+        ))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        let l = lhs.value();
+        let r = rhs.value();
+        Ok(i32::from(l > r))
+    }
 }
 
 /// WebAssembly f32.le operation.
 #[inline]
 pub fn f32_le(lhs: FloatBits32, rhs: FloatBits32) -> Result<i32> {
-    let l = lhs.value();
-    let r = rhs.value();
-    Ok(i32::from(l <= r))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(i32::from(matches!(
+            soft_float::f32_compare(lhs, rhs),
+            Some(Ordering::Less | Ordering::Equal)
+        )))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        let l = lhs.value();
+        let r = rhs.value();
+        Ok(i32::from(l <= r))
+    }
 }
 
 /// WebAssembly f32.ge operation.
 #[inline]
 pub fn f32_ge(lhs: FloatBits32, rhs: FloatBits32) -> Result<i32> {
-    let l = lhs.value();
-    let r = rhs.value();
-    Ok(i32::from(l >= r))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(i32::from(matches!(
+            soft_float::f32_compare(lhs, rhs),
+            Some(Ordering::Greater | Ordering::Equal)
+        )))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        let l = lhs.value();
+        let r = rhs.value();
+        Ok(i32::from(l >= r))
+    }
 }
 
 // F64 Comparisons
 /// WebAssembly f64.eq operation.
 #[inline]
 pub fn f64_eq(lhs: FloatBits64, rhs: FloatBits64) -> Result<i32> {
-    let l = lhs.value();
-    let r = rhs.value();
-    Ok(i32::from(l == r))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(i32::from(
+            soft_float::f64_compare(lhs, rhs) == Some(Ordering::Equal),
+        ))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        let l = lhs.value();
+        let r = rhs.value();
+        Ok(i32::from(l == r))
+    }
 }
 
 /// WebAssembly f64.ne operation.
 #[inline]
 pub fn f64_ne(lhs: FloatBits64, rhs: FloatBits64) -> Result<i32> {
-    let l = lhs.value();
-    let r = rhs.value();
-    Ok(i32::from(l != r))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(i32::from(
+            soft_float::f64_compare(lhs, rhs) != Some(Ordering::Equal),
+        ))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        let l = lhs.value();
+        let r = rhs.value();
+        Ok(i32::from(l != r))
+    }
 }
 
 /// WebAssembly f64.lt operation.
 #[inline]
 pub fn f64_lt(lhs: FloatBits64, rhs: FloatBits64) -> Result<i32> {
-    let l = lhs.value();
-    let r = rhs.value();
-    Ok(i32::from(l < r))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(i32::from(
+            soft_float::f64_compare(lhs, rhs) == Some(Ordering::Less),
+        ))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        let l = lhs.value();
+        let r = rhs.value();
+        Ok(i32::from(l < r))
+    }
 }
 
 /// WebAssembly f64.gt operation.
 #[inline]
 pub fn f64_gt(lhs: FloatBits64, rhs: FloatBits64) -> Result<i32> {
-    let l = lhs.value();
-    let r = rhs.value();
-    Ok(i32::from(l > r))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(i32::from(
+            soft_float::f64_compare(lhs, rhs) == Some(Ordering::Greater),
+        ))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        let l = lhs.value();
+        let r = rhs.value();
+        Ok(i32::from(l > r))
+    }
 }
 
 /// WebAssembly f64.le operation.
 #[inline]
 pub fn f64_le(lhs: FloatBits64, rhs: FloatBits64) -> Result<i32> {
-    let l = lhs.value();
-    let r = rhs.value();
-    Ok(i32::from(l <= r))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(i32::from(matches!(
+            soft_float::f64_compare(lhs, rhs),
+            Some(Ordering::Less | Ordering::Equal)
+        )))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        let l = lhs.value();
+        let r = rhs.value();
+        Ok(i32::from(l <= r))
+    }
 }
 
 /// WebAssembly f64.ge operation.
 #[inline]
 pub fn f64_ge(lhs: FloatBits64, rhs: FloatBits64) -> Result<i32> {
-    let l = lhs.value();
-    let r = rhs.value();
-    Ok(i32::from(l >= r))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(i32::from(matches!(
+            soft_float::f64_compare(lhs, rhs),
+            Some(Ordering::Greater | Ordering::Equal)
+        )))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        let l = lhs.value();
+        let r = rhs.value();
+        Ok(i32::from(l >= r))
+    }
 }
 
 // --- Saturating Float to Integer Conversions ---
@@ -1703,42 +1986,49 @@ pub fn f64_ge(lhs: FloatBits64, rhs: FloatBits64) -> Result<i32> {
 #[inline]
 #[must_use = "Saturating conversion result must be used"]
 pub fn i32_trunc_sat_f32_s(val: FloatBits32) -> i32 {
-    let f = val.value();
-    if f.is_nan() {
-        0
-    } else if f.is_infinite() {
-        if f.is_sign_positive() {
-            i32::MAX
-        } else {
-            i32::MIN
-        }
-    } else {
-        let trunc = f32_trunc_compat(f);
-        // Check against the valid range for i32 represented as f32
-        // i32::MIN as f32 is -2147483600.0 (approx)
-        // i32::MAX as f32 is 2147483600.0 (approx)
-        // A more precise range check:
-        if trunc >= (i32::MIN as f32) && trunc <= (i32::MAX as f32) {
-            // Check if it's precisely representable or within the range for direct cast
-            if (-2_147_483_648.0_f32..2_147_483_648.0_f32).contains(&trunc) {
-                // Wasm spec Table 15
-                trunc as i32
-            } else if trunc == -2_147_483_648.0_f32 {
-                // exactly i32::MIN
+    #[cfg(feature = "soft-float")]
+    {
+        soft_trunc_sat(soft_float::f32_to_int_sat(val), i32::MIN, i32::MAX)
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        let f = val.value();
+        if f.is_nan() {
+            0
+        } else if f.is_infinite() {
+            if f.is_sign_positive() {
+                i32::MAX
+            } else {
                 i32::MIN
+            }
+        } else {
+            let trunc = f32_trunc_compat(f);
+            // Check against the valid range for i32 represented as f32
+            // i32::MIN as f32 is -2147483600.0 (approx)
+            // i32::MAX as f32 is 2147483600.0 (approx)
+            // A more precise range check:
+            if trunc >= (i32::MIN as f32) && trunc <= (i32::MAX as f32) {
+                // Check if it's precisely representable or within the range for direct cast
+                if (-2_147_483_648.0_f32..2_147_483_648.0_f32).contains(&trunc) {
+                    // Wasm spec Table 15
+                    trunc as i32
+                } else if trunc == -2_147_483_648.0_f32 {
+                    // exactly i32::MIN
+                    i32::MIN
+                } else if f.is_sign_positive() {
+                    // Positive out of precise range
+                    i32::MAX
+                } else {
+                    // Negative out of precise range
+                    i32::MIN
+                }
             } else if f.is_sign_positive() {
-                // Positive out of precise range
+                // Positive out of representable f32 range for i32
                 i32::MAX
             } else {
-                // Negative out of precise range
+                // Negative out of representable f32 range for i32
                 i32::MIN
             }
-        } else if f.is_sign_positive() {
-            // Positive out of representable f32 range for i32
-            i32::MAX
-        } else {
-            // Negative out of representable f32 range for i32
-            i32::MIN
         }
     }
 }
@@ -1750,39 +2040,46 @@ pub fn i32_trunc_sat_f32_s(val: FloatBits32) -> i32 {
 #[inline]
 #[must_use = "Saturating conversion result must be used"]
 pub fn i32_trunc_sat_f32_u(val: FloatBits32) -> i32 {
-    // Wasm returns i32
-    let f = val.value();
-    if f.is_nan() {
-        0
-    } else if f.is_infinite() {
-        if f.is_sign_positive() {
-            u32::MAX as i32
-        } else {
+    #[cfg(feature = "soft-float")]
+    {
+        soft_trunc_sat(soft_float::f32_to_int_sat(val), 0, u32::MAX) as i32
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        // Wasm returns i32
+        let f = val.value();
+        if f.is_nan() {
             0
-        } // Negative infinity saturates to 0 for unsigned
-    } else {
-        let trunc = f32_trunc_compat(f);
-        // Check against valid range for u32 represented as f32
-        // u32::MIN (0) as f32 is 0.0
-        // u32::MAX as f32 is 4294967300.0 (approx)
-        if trunc > (u32::MIN as f32) && trunc < (u32::MAX as f32) {
-            // Wasm spec Table 15 range
-            // Further check based on spec: (0.0, 4294967296.0)
-            if trunc > 0.0_f32 && trunc < 4_294_967_296.0_f32 {
-                trunc as u32 as i32
-            } else if trunc <= 0.0_f32 {
-                // Includes -0.0
+        } else if f.is_infinite() {
+            if f.is_sign_positive() {
+                u32::MAX as i32
+            } else {
+                0
+            } // Negative infinity saturates to 0 for unsigned
+        } else {
+            let trunc = f32_trunc_compat(f);
+            // Check against valid range for u32 represented as f32
+            // u32::MIN (0) as f32 is 0.0
+            // u32::MAX as f32 is 4294967300.0 (approx)
+            if trunc > (u32::MIN as f32) && trunc < (u32::MAX as f32) {
+                // Wasm spec Table 15 range
+                // Further check based on spec: (0.0, 4294967296.0)
+                if trunc > 0.0_f32 && trunc < 4_294_967_296.0_f32 {
+                    trunc as u32 as i32
+                } else if trunc <= 0.0_f32 {
+                    // Includes -0.0
+                    0
+                } else {
+                    // >= 2^32
+                    u32::MAX as i32
+                }
+            } else if trunc <= (u32::MIN as f32) {
+                // Includes negative numbers and -0.0
                 0
             } else {
-                // >= 2^32
+                // Greater than or equal to u32::MAX as f32
                 u32::MAX as i32
             }
-        } else if trunc <= (u32::MIN as f32) {
-            // Includes negative numbers and -0.0
-            0
-        } else {
-            // Greater than or equal to u32::MAX as f32
-            u32::MAX as i32
         }
     }
 }
@@ -1792,34 +2089,41 @@ pub fn i32_trunc_sat_f32_u(val: FloatBits32) -> i32 {
 #[inline]
 #[must_use = "Saturating conversion result must be used"]
 pub fn i64_trunc_sat_f32_s(val: FloatBits32) -> i64 {
-    // Wasm returns i64
-    let f = val.value();
-    if f.is_nan() {
-        0
-    } else if f.is_infinite() {
-        if f.is_sign_positive() {
-            i64::MAX
-        } else {
-            i64::MIN
-        }
-    } else {
-        let trunc = f32_trunc_compat(f);
-        // Wasm spec Table 15 range: (-9223372036854775808.0, 9223372036854775808.0)
-        // f32 cannot represent these bounds exactly.
-        // If f > i64::MAX as f32 (approx 9.223372E18), saturate to i64::MAX.
-        // If f < i64::MIN as f32 (approx -9.223372E18), saturate to i64::MIN.
-
-        if (I64_MIN_AS_F32..I64_MAX_AS_F32).contains(&trunc) {
-            trunc as i64
-        } else if trunc == I64_MIN_AS_F32 {
-            // Check exact bound for MIN
-            i64::MIN
-        } else if trunc >= I64_MAX_AS_F32 {
-            // Handles values >= MAX bound
-            i64::MAX
+    #[cfg(feature = "soft-float")]
+    {
+        soft_trunc_sat(soft_float::f32_to_int_sat(val), i64::MIN, i64::MAX)
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        // Wasm returns i64
+        let f = val.value();
+        if f.is_nan() {
+            0
+        } else if f.is_infinite() {
+            if f.is_sign_positive() {
+                i64::MAX
+            } else {
+                i64::MIN
+            }
         } else {
-            // Handles values < MIN bound
-            i64::MIN
+            let trunc = f32_trunc_compat(f);
+            // Wasm spec Table 15 range: (-9223372036854775808.0, 9223372036854775808.0)
+            // f32 cannot represent these bounds exactly.
+            // If f > i64::MAX as f32 (approx 9.223372E18), saturate to i64::MAX.
+            // If f < i64::MIN as f32 (approx -9.223372E18), saturate to i64::MIN.
+
+            if (I64_MIN_AS_F32..I64_MAX_AS_F32).contains(&trunc) {
+                trunc as i64
+            } else if trunc == I64_MIN_AS_F32 {
+                // Check exact bound for MIN
+                i64::MIN
+            } else if trunc >= I64_MAX_AS_F32 {
+                // Handles values >= MAX bound
+                i64::MAX
+            } else {
+                // Handles values < MIN bound
+                i64::MIN
+            }
         }
     }
 }
@@ -1829,29 +2133,36 @@ pub fn i64_trunc_sat_f32_s(val: FloatBits32) -> i64 {
 #[inline]
 #[must_use = "Saturating conversion result must be used"]
 pub fn i64_trunc_sat_f32_u(val: FloatBits32) -> i64 {
-    // Wasm returns i64
-    let f = val.value();
-    if f.is_nan() {
-        0
-    } else if f.is_infinite() {
-        if f.is_sign_positive() {
-            u64::MAX as i64
-        } else {
-            0
-        }
-    } else {
-        let trunc = f32_trunc_compat(f);
-        // Wasm spec Table 15 range: (0.0, 18446744073709551616.0)
-        const U64_MAX_AS_F32: f32 = 18_446_744_073_709_551_616.0_f32; // u64::MAX (2^64 - 1)
-
-        if trunc > 0.0_f32 && trunc < U64_MAX_AS_F32 {
-            trunc as u64 as i64
-        } else if trunc <= 0.0_f32 {
-            // Includes negative numbers and -0.0
+    #[cfg(feature = "soft-float")]
+    {
+        soft_trunc_sat(soft_float::f32_to_int_sat(val), 0, u64::MAX) as i64
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        // Wasm returns i64
+        let f = val.value();
+        if f.is_nan() {
             0
+        } else if f.is_infinite() {
+            if f.is_sign_positive() {
+                u64::MAX as i64
+            } else {
+                0
+            }
         } else {
-            // >= U64_MAX_AS_F32
-            u64::MAX as i64
+            let trunc = f32_trunc_compat(f);
+            // Wasm spec Table 15 range: (0.0, 18446744073709551616.0)
+            const U64_MAX_AS_F32: f32 = 18_446_744_073_709_551_616.0_f32; // u64::MAX (2^64 - 1)
+
+            if trunc > 0.0_f32 && trunc < U64_MAX_AS_F32 {
+                trunc as u64 as i64
+            } else if trunc <= 0.0_f32 {
+                // Includes negative numbers and -0.0
+                0
+            } else {
+                // >= U64_MAX_AS_F32
+                u64::MAX as i64
+            }
         }
     }
 }
@@ -1861,28 +2172,35 @@ pub fn i64_trunc_sat_f32_u(val: FloatBits32) -> i64 {
 #[inline]
 #[must_use = "Saturating conversion result must be used"]
 pub fn i32_trunc_sat_f64_s(val: FloatBits64) -> i32 {
-    let f = val.value();
-    if f.is_nan() {
-        0
-    } else if f.is_infinite() {
-        if f.is_sign_positive() {
-            i32::MAX
-        } else {
-            i32::MIN
-        }
-    } else {
-        let trunc = f64_trunc_compat(f);
-        // Wasm spec Table 15 range: (-2147483648.0, 2147483648.0)
-        if (-2_147_483_648.0_f64..2_147_483_648.0_f64).contains(&trunc) {
-            trunc as i32
-        } else if trunc == -2_147_483_648.0_f64 {
-            // Exactly i32::MIN
-            i32::MIN
-        } else if trunc >= 2_147_483_648.0_f64 {
-            i32::MAX
+    #[cfg(feature = "soft-float")]
+    {
+        soft_trunc_sat(soft_float::f64_to_int_sat(val), i32::MIN, i32::MAX)
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        let f = val.value();
+        if f.is_nan() {
+            0
+        } else if f.is_infinite() {
+            if f.is_sign_positive() {
+                i32::MAX
+            } else {
+                i32::MIN
+            }
         } else {
-            // trunc < -2147483648.0
-            i32::MIN
+            let trunc = f64_trunc_compat(f);
+            // Wasm spec Table 15 range: (-2147483648.0, 2147483648.0)
+            if (-2_147_483_648.0_f64..2_147_483_648.0_f64).contains(&trunc) {
+                trunc as i32
+            } else if trunc == -2_147_483_648.0_f64 {
+                // Exactly i32::MIN
+                i32::MIN
+            } else if trunc >= 2_147_483_648.0_f64 {
+                i32::MAX
+            } else {
+                // trunc < -2147483648.0
+                i32::MIN
+            }
         }
     }
 }
@@ -1892,26 +2210,33 @@ pub fn i32_trunc_sat_f64_s(val: FloatBits64) -> i32 {
 #[inline]
 #[must_use = "Saturating conversion result must be used"]
 pub fn i32_trunc_sat_f64_u(val: FloatBits64) -> i32 {
-    // Wasm returns i32
-    let f = val.value();
-    if f.is_nan() {
-        0
-    } else if f.is_infinite() {
-        if f.is_sign_positive() {
-            u32::MAX as i32
-        } else {
-            0
-        }
-    } else {
-        let trunc = f64_trunc_compat(f);
-        // Wasm spec Table 15 range: (0.0, 4294967296.0)
-        if trunc > 0.0_f64 && trunc < 4_294_967_296.0_f64 {
-            trunc as u32 as i32
-        } else if trunc <= 0.0_f64 {
+    #[cfg(feature = "soft-float")]
+    {
+        soft_trunc_sat(soft_float::f64_to_int_sat(val), 0, u32::MAX) as i32
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        // Wasm returns i32
+        let f = val.value();
+        if f.is_nan() {
             0
+        } else if f.is_infinite() {
+            if f.is_sign_positive() {
+                u32::MAX as i32
+            } else {
+                0
+            }
         } else {
-            // >= 2^32
-            u32::MAX as i32
+            let trunc = f64_trunc_compat(f);
+            // Wasm spec Table 15 range: (0.0, 4294967296.0)
+            if trunc > 0.0_f64 && trunc < 4_294_967_296.0_f64 {
+                trunc as u32 as i32
+            } else if trunc <= 0.0_f64 {
+                0
+            } else {
+                // >= 2^32
+                u32::MAX as i32
+            }
         }
     }
 }
@@ -1921,39 +2246,46 @@ pub fn i32_trunc_sat_f64_u(val: FloatBits64) -> i32 {
 #[inline]
 #[must_use = "Saturating conversion result must be used"]
 pub fn i64_trunc_sat_f64_s(val: FloatBits64) -> i64 {
-    let f = val.value();
-    if f.is_nan() {
-        0
-    } else if f.is_infinite() {
-        if f.is_sign_positive() {
-            i64::MAX
-        } else {
-            i64::MIN
-        }
-    } else {
-        let trunc = f64_trunc_compat(f);
-        // Wasm spec Table 15 range: (-9223372036854775808.0, 9223372036854775808.0)
-        // i64::MIN is -2^63, i64::MAX is 2^63 - 1
-        // For f64, these are precisely representable.
-        if trunc >= (i64::MIN as f64) && trunc < (i64::MAX as f64) {
-            // Max is exclusive here due to how f64 to i64 cast might handle edge.
-            // If trunc is exactly i64::MAX as f64, it should cast to i64::MAX.
-            // If trunc is slightly less than i64::MIN as f64, it should cast to i64::MIN.
-            if trunc == (i64::MAX as f64) {
-                // Check for exact MAX value
+    #[cfg(feature = "soft-float")]
+    {
+        soft_trunc_sat(soft_float::f64_to_int_sat(val), i64::MIN, i64::MAX)
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        let f = val.value();
+        if f.is_nan() {
+            0
+        } else if f.is_infinite() {
+            if f.is_sign_positive() {
                 i64::MAX
             } else {
-                trunc as i64
+                i64::MIN
             }
-        } else if trunc == (i64::MIN as f64) {
-            // Simplified boolean expression
-            // Ensure exact i64::MIN
-            i64::MIN
-        } else if trunc >= (i64::MAX as f64) {
-            i64::MAX
         } else {
-            // trunc < (i64::MIN as f64)
-            i64::MIN
+            let trunc = f64_trunc_compat(f);
+            // Wasm spec Table 15 range: (-9223372036854775808.0, 9223372036854775808.0)
+            // i64::MIN is -2^63, i64::MAX is 2^63 - 1
+            // For f64, these are precisely representable.
+            if trunc >= (i64::MIN as f64) && trunc < (i64::MAX as f64) {
+                // Max is exclusive here due to how f64 to i64 cast might handle edge.
+                // If trunc is exactly i64::MAX as f64, it should cast to i64::MAX.
+                // If trunc is slightly less than i64::MIN as f64, it should cast to i64::MIN.
+                if trunc == (i64::MAX as f64) {
+                    // Check for exact MAX value
+                    i64::MAX
+                } else {
+                    trunc as i64
+                }
+            } else if trunc == (i64::MIN as f64) {
+                // Simplified boolean expression
+                // Ensure exact i64::MIN
+                i64::MIN
+            } else if trunc >= (i64::MAX as f64) {
+                i64::MAX
+            } else {
+                // trunc < (i64::MIN as f64)
+                i64::MIN
+            }
         }
     }
 }
@@ -1963,37 +2295,44 @@ pub fn i64_trunc_sat_f64_s(val: FloatBits64) -> i64 {
 #[inline]
 #[must_use = "Saturating conversion result must be used"]
 pub fn i64_trunc_sat_f64_u(val: FloatBits64) -> i64 {
-    // Wasm returns i64
-    let f = val.value();
-    if f.is_nan() {
-        0
-    } else if f.is_infinite() {
-        if f.is_sign_positive() {
-            u64::MAX as i64
-        } else {
+    #[cfg(feature = "soft-float")]
+    {
+        soft_trunc_sat(soft_float::f64_to_int_sat(val), 0, u64::MAX) as i64
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        // Wasm returns i64
+        let f = val.value();
+        if f.is_nan() {
             0
-        }
-    } else {
-        let trunc = f64_trunc_compat(f);
-        // Wasm spec Table 15 range: (0.0, 18446744073709551616.0) which is 2^64
-        // u64::MAX is 2^64 - 1
-        if trunc > 0.0_f64 && trunc < (u64::MAX as f64) {
-            // If trunc < u64::MAX precisely
-            // If trunc is precisely u64::MAX, then (trunc as u64) is u64::MAX
-            // If trunc is slightly less than 2^64 but rounds to 2^64 for f64
-            // then (trunc as u64) will be u64::MAX.
-            // The spec range is (0, 2^64).
-            if trunc >= 18_446_744_073_709_551_616.0_f64 {
-                // 2^64
-                u64::MAX as i64 // Saturate
+        } else if f.is_infinite() {
+            if f.is_sign_positive() {
+                u64::MAX as i64
             } else {
-                trunc as u64 as i64
+                0
             }
-        } else if trunc <= 0.0_f64 {
-            0
         } else {
-            // >= u64::MAX as f64, or >= 2^64
-            u64::MAX as i64
+            let trunc = f64_trunc_compat(f);
+            // Wasm spec Table 15 range: (0.0, 18446744073709551616.0) which is 2^64
+            // u64::MAX is 2^64 - 1
+            if trunc > 0.0_f64 && trunc < (u64::MAX as f64) {
+                // If trunc < u64::MAX precisely
+                // If trunc is precisely u64::MAX, then (trunc as u64) is u64::MAX
+                // If trunc is slightly less than 2^64 but rounds to 2^64 for f64
+                // then (trunc as u64) will be u64::MAX.
+                // The spec range is (0, 2^64).
+                if trunc >= 18_446_744_073_709_551_616.0_f64 {
+                    // 2^64
+                    u64::MAX as i64 // Saturate
+                } else {
+                    trunc as u64 as i64
+                }
+            } else if trunc <= 0.0_f64 {
+                0
+            } else {
+                // >= u64::MAX as f64, or >= 2^64
+                u64::MAX as i64
+            }
         }
     }
 }
@@ -2009,29 +2348,36 @@ pub fn i64_trunc_sat_f64_u(val: FloatBits64) -> i64 {
 ///   range.
 #[inline]
 pub fn i32_trunc_f32_s(val: FloatBits32) -> Result<i32> {
-    let f = val.value();
-    if f.is_nan() || f.is_infinite() {
-        return Err(trap(TrapCode::InvalidConversionToInteger));
-    }
-    let f_trunc = f32_trunc_compat(f);
-    // Wasm spec: "if trunc_s(z) is out of range of T, then trap"
-    // Range for i32 is [-2147483648, 2147483647]
-    // Check against f32 representations of these bounds.
-    // f_trunc must be >= -2147483648.0 and <= 2147483647.0 (approx)
-    // More precisely, use the spec's table range:
-    // not in (-2147483648.0, 2147483648.0) then trap for f32->i32
-    // This means f_trunc must be >= -2147483648.0 AND < 2147483648.0
-    // Or more simply, if f_trunc < i32::MIN as f32 or f_trunc > i32::MAX as f32
-    // (roughly)
-    if !(-2_147_483_648.0_f32..2_147_483_648.0_f32).contains(&f_trunc) {
-        // Special case for i32::MIN: -2147483648.0f32 as i32 is i32::MIN
-        if f_trunc == -2_147_483_648.0_f32 {
-            // This is valid
-        } else {
-            return Err(trap(TrapCode::IntegerOverflow));
+    #[cfg(feature = "soft-float")]
+    {
+        soft_trunc(soft_float::f32_to_int(val))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        let f = val.value();
+        if f.is_nan() || f.is_infinite() {
+            return Err(trap(TrapCode::InvalidConversionToInteger));
         }
+        let f_trunc = f32_trunc_compat(f);
+        // Wasm spec: "if trunc_s(z) is out of range of T, then trap"
+        // Range for i32 is [-2147483648, 2147483647]
+        // Check against f32 representations of these bounds.
+        // f_trunc must be >= -2147483648.0 and <= 2147483647.0 (approx)
+        // More precisely, use the spec's table range:
+        // not in (-2147483648.0, 2147483648.0) then trap for f32->i32
+        // This means f_trunc must be >= -2147483648.0 AND < 2147483648.0
+        // Or more simply, if f_trunc < i32::MIN as f32 or f_trunc > i32::MAX as f32
+        // (roughly)
+        if !(-2_147_483_648.0_f32..2_147_483_648.0_f32).contains(&f_trunc) {
+            // Special case for i32::MIN: -2147483648.0f32 as i32 is i32::MIN
+            if f_trunc == -2_147_483_648.0_f32 {
+                // This is valid
+            } else {
+                return Err(trap(TrapCode::IntegerOverflow));
+            }
+        }
+        Ok(f_trunc as i32)
     }
-    Ok(f_trunc as i32)
 }
 
 /// `i32.trunc_f32_u`: Truncate f32 to unsigned i32 (u32), trapping on invalid
@@ -2043,24 +2389,31 @@ pub fn i32_trunc_f32_s(val: FloatBits32) -> Result<i32> {
 ///   range.
 #[inline]
 pub fn i32_trunc_f32_u(val: FloatBits32) -> Result<u32> {
-    let f = val.value();
-    if f.is_nan() || f.is_infinite() {
-        return Err(trap(TrapCode::InvalidConversionToInteger));
-    }
-    let f_trunc = f32_trunc_compat(f);
-    // Range for u32 is [0, 4294967295]
-    // Wasm spec table range for f32->u32: not in (0.0, 4294967296.0) then trap
-    // This means f_trunc must be >= 0.0 AND < 4294967296.0
-    // (Note: -0.0 should also be handled as in range, converting to 0)
-    if !(0.0_f32..4_294_967_296.0_f32).contains(&f_trunc) {
-        // Check for -0.0 case explicitly, as (f_trunc < 0.0) would be true
-        if f_trunc == 0.0 && f_trunc.is_sign_negative() {
-            // -0.0 is fine, becomes 0u32
-        } else {
-            return Err(trap(TrapCode::IntegerOverflow));
+    #[cfg(feature = "soft-float")]
+    {
+        soft_trunc(soft_float::f32_to_int(val))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        let f = val.value();
+        if f.is_nan() || f.is_infinite() {
+            return Err(trap(TrapCode::InvalidConversionToInteger));
+        }
+        let f_trunc = f32_trunc_compat(f);
+        // Range for u32 is [0, 4294967295]
+        // Wasm spec table range for f32->u32: not in (0.0, 4294967296.0) then trap
+        // This means f_trunc must be >= 0.0 AND < 4294967296.0
+        // (Note: -0.0 should also be handled as in range, converting to 0)
+        if !(0.0_f32..4_294_967_296.0_f32).contains(&f_trunc) {
+            // Check for -0.0 case explicitly, as (f_trunc < 0.0) would be true
+            if f_trunc == 0.0 && f_trunc.is_sign_negative() {
+                // -0.0 is fine, becomes 0u32
+            } else {
+                return Err(trap(TrapCode::IntegerOverflow));
+            }
         }
+        Ok(f_trunc as u32)
     }
-    Ok(f_trunc as u32)
 }
 
 /// `i32.trunc_f64_s`: Truncate f64 to signed i32, trapping on invalid input.
@@ -2071,20 +2424,27 @@ pub fn i32_trunc_f32_u(val: FloatBits32) -> Result<u32> {
 ///   range.
 #[inline]
 pub fn i32_trunc_f64_s(val: FloatBits64) -> Result<i32> {
-    let d = val.value();
-    if d.is_nan() || d.is_infinite() {
-        return Err(trap(TrapCode::InvalidConversionToInteger));
-    }
-    let d_trunc = f64_trunc_compat(d);
-    // Wasm spec table range: not in (-2147483648.0, 2147483648.0) for f64->i32
-    if !(-2_147_483_648.0_f64..2_147_483_648.0_f64).contains(&d_trunc) {
-        if d_trunc == -2_147_483_648.0_f64 {
-            // valid
-        } else {
-            return Err(trap(TrapCode::IntegerOverflow));
+    #[cfg(feature = "soft-float")]
+    {
+        soft_trunc(soft_float::f64_to_int(val))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        let d = val.value();
+        if d.is_nan() || d.is_infinite() {
+            return Err(trap(TrapCode::InvalidConversionToInteger));
         }
+        let d_trunc = f64_trunc_compat(d);
+        // Wasm spec table range: not in (-2147483648.0, 2147483648.0) for f64->i32
+        if !(-2_147_483_648.0_f64..2_147_483_648.0_f64).contains(&d_trunc) {
+            if d_trunc == -2_147_483_648.0_f64 {
+                // valid
+            } else {
+                return Err(trap(TrapCode::IntegerOverflow));
+            }
+        }
+        Ok(d_trunc as i32)
     }
-    Ok(d_trunc as i32)
 }
 
 /// `i32.trunc_f64_u`: Truncate f64 to unsigned i32 (u32), trapping on invalid
@@ -2096,20 +2456,27 @@ pub fn i32_trunc_f64_s(val: FloatBits64) -> Result<i32> {
 ///   range.
 #[inline]
 pub fn i32_trunc_f64_u(val: FloatBits64) -> Result<u32> {
-    let d = val.value();
-    if d.is_nan() || d.is_infinite() {
-        return Err(trap(TrapCode::InvalidConversionToInteger));
-    }
-    let d_trunc = f64_trunc_compat(d);
-    // Wasm spec table range: not in (0.0, 4294967296.0) for f64->u32
-    if !(0.0_f64..4_294_967_296.0_f64).contains(&d_trunc) {
-        if d_trunc == 0.0 && d_trunc.is_sign_negative() {
-            // -0.0 is fine
-        } else {
-            return Err(trap(TrapCode::IntegerOverflow));
+    #[cfg(feature = "soft-float")]
+    {
+        soft_trunc(soft_float::f64_to_int(val))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        let d = val.value();
+        if d.is_nan() || d.is_infinite() {
+            return Err(trap(TrapCode::InvalidConversionToInteger));
+        }
+        let d_trunc = f64_trunc_compat(d);
+        // Wasm spec table range: not in (0.0, 4294967296.0) for f64->u32
+        if !(0.0_f64..4_294_967_296.0_f64).contains(&d_trunc) {
+            if d_trunc == 0.0 && d_trunc.is_sign_negative() {
+                // -0.0 is fine
+            } else {
+                return Err(trap(TrapCode::IntegerOverflow));
+            }
         }
+        Ok(d_trunc as u32)
     }
-    Ok(d_trunc as u32)
 }
 
 /// `i64.trunc_f32_s`: Truncate f32 to signed i64, trapping on invalid input.
@@ -2120,25 +2487,32 @@ pub fn i32_trunc_f64_u(val: FloatBits64) -> Result<u32> {
 ///   range.
 #[inline]
 pub fn i64_trunc_f32_s(val: FloatBits32) -> Result<i64> {
-    let f = val.value();
-    if f.is_nan() || f.is_infinite() {
-        return Err(trap(TrapCode::InvalidConversionToInteger));
-    }
-    let f_trunc = f32_trunc_compat(f);
-    // Wasm spec table range: not in (-9223372036854775808.0, 9223372036854775808.0)
-    // for f32->i64 These bounds are not precisely representable by f32.
-    // Check if f_trunc is outside the representable range of i64.
-    // i64::MIN as f32 is approx -9.223372E18
-    // i64::MAX as f32 is approx  9.223372E18
-
-    if !(I64_MIN_AS_F32..I64_MAX_AS_F32).contains(&f_trunc) {
-        if f_trunc == I64_MIN_AS_F32 { // Check for exact lower bound
-             // This is okay, will become i64::MIN
-        } else {
-            return Err(trap(TrapCode::IntegerOverflow));
+    #[cfg(feature = "soft-float")]
+    {
+        soft_trunc(soft_float::f32_to_int(val))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        let f = val.value();
+        if f.is_nan() || f.is_infinite() {
+            return Err(trap(TrapCode::InvalidConversionToInteger));
         }
+        let f_trunc = f32_trunc_compat(f);
+        // Wasm spec table range: not in (-9223372036854775808.0, 9223372036854775808.0)
+        // for f32->i64 These bounds are not precisely representable by f32.
+        // Check if f_trunc is outside the representable range of i64.
+        // i64::MIN as f32 is approx -9.223372E18
+        // i64::MAX as f32 is approx  9.223372E18
+
+        if !(I64_MIN_AS_F32..I64_MAX_AS_F32).contains(&f_trunc) {
+            if f_trunc == I64_MIN_AS_F32 { // Check for exact lower bound
+                 // This is okay, will become i64::MIN
+            } else {
+                return Err(trap(TrapCode::IntegerOverflow));
+            }
+        }
+        Ok(f_trunc as i64)
     }
-    Ok(f_trunc as i64)
 }
 
 /// `i64.trunc_f32_u`: Truncate f32 to unsigned i64 (u64), trapping on invalid
@@ -2150,21 +2524,28 @@ pub fn i64_trunc_f32_s(val: FloatBits32) -> Result<i64> {
 ///   range.
 #[inline]
 pub fn i64_trunc_f32_u(val: FloatBits32) -> Result<u64> {
-    let f = val.value();
-    if f.is_nan() || f.is_infinite() {
-        return Err(trap(TrapCode::InvalidConversionToInteger));
-    }
-    let f_trunc = f32_trunc_compat(f);
-    // Wasm spec table range: not in (0.0, 18446744073709551616.0) for f32->u64
-    const U64_MAX_AS_F32: f32 = 18_446_744_073_709_551_616.0_f32; // 2^64
-    if !(0.0_f32..U64_MAX_AS_F32).contains(&f_trunc) {
-        if f_trunc == 0.0 && f_trunc.is_sign_negative() {
-            // -0.0 is fine
-        } else {
-            return Err(trap(TrapCode::IntegerOverflow));
+    #[cfg(feature = "soft-float")]
+    {
+        soft_trunc(soft_float::f32_to_int(val))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        let f = val.value();
+        if f.is_nan() || f.is_infinite() {
+            return Err(trap(TrapCode::InvalidConversionToInteger));
+        }
+        let f_trunc = f32_trunc_compat(f);
+        // Wasm spec table range: not in (0.0, 18446744073709551616.0) for f32->u64
+        const U64_MAX_AS_F32: f32 = 18_446_744_073_709_551_616.0_f32; // 2^64
+        if !(0.0_f32..U64_MAX_AS_F32).contains(&f_trunc) {
+            if f_trunc == 0.0 && f_trunc.is_sign_negative() {
+                // -0.0 is fine
+            } else {
+                return Err(trap(TrapCode::IntegerOverflow));
+            }
         }
+        Ok(f_trunc as u64)
     }
-    Ok(f_trunc as u64)
 }
 
 /// `i64.trunc_f64_s`: Truncate f64 to signed i64, trapping on invalid input.
@@ -2175,28 +2556,37 @@ pub fn i64_trunc_f32_u(val: FloatBits32) -> Result<u64> {
 ///   range.
 #[inline]
 pub fn i64_trunc_f64_s(val: FloatBits64) -> Result<i64> {
-    let d = val.value();
-    if d.is_nan() || d.is_infinite() {
-        return Err(trap(TrapCode::InvalidConversionToInteger));
-    }
-    let d_trunc = f64_trunc_compat(d);
-    // Wasm spec table range: not in (-9223372036854775808.0, 9223372036854775808.0)
-    // for f64->i64 These are i64::MIN and (i64::MAX + 1) as f64.
-    if d_trunc < (i64::MIN as f64) || d_trunc >= ((i64::MAX as f64) + 1.0) {
-        // Check carefully against exact f64 repr of i64 bounds
-        // Test: (i64::MIN as f64) is -9223372036854776000.0
-        // Test: (i64::MAX as f64) is  9223372036854776000.0
-        // The spec implies the range of representable values, not the casted bounds.
-        // Let's use the numeric literals from spec's Table 15 for bounds check.
-        if !(-9_223_372_036_854_775_808.0_f64..9_223_372_036_854_775_808.0_f64).contains(&d_trunc) {
-            if d_trunc == -9_223_372_036_854_775_808.0_f64 { // i64::MIN
-                 // okay
-            } else {
-                return Err(trap(TrapCode::IntegerOverflow));
+    #[cfg(feature = "soft-float")]
+    {
+        soft_trunc(soft_float::f64_to_int(val))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        let d = val.value();
+        if d.is_nan() || d.is_infinite() {
+            return Err(trap(TrapCode::InvalidConversionToInteger));
+        }
+        let d_trunc = f64_trunc_compat(d);
+        // Wasm spec table range: not in (-9223372036854775808.0, 9223372036854775808.0)
+        // for f64->i64 These are i64::MIN and (i64::MAX + 1) as f64.
+        if d_trunc < (i64::MIN as f64) || d_trunc >= ((i64::MAX as f64) + 1.0) {
+            // Check carefully against exact f64 repr of i64 bounds
+            // Test: (i64::MIN as f64) is -9223372036854776000.0
+            // Test: (i64::MAX as f64) is  9223372036854776000.0
+            // The spec implies the range of representable values, not the casted bounds.
+            // Let's use the numeric literals from spec's Table 15 for bounds check.
+            if !(-9_223_372_036_854_775_808.0_f64..9_223_372_036_854_775_808.0_f64)
+                .contains(&d_trunc)
+            {
+                if d_trunc == -9_223_372_036_854_775_808.0_f64 { // i64::MIN
+                     // okay
+                } else {
+                    return Err(trap(TrapCode::IntegerOverflow));
+                }
             }
         }
+        Ok(d_trunc as i64)
     }
-    Ok(d_trunc as i64)
 }
 
 /// `i64.trunc_f64_u`: Truncate f64 to unsigned i64 (u64), trapping on invalid
@@ -2208,21 +2598,28 @@ pub fn i64_trunc_f64_s(val: FloatBits64) -> Result<i64> {
 ///   range.
 #[inline]
 pub fn i64_trunc_f64_u(val: FloatBits64) -> Result<u64> {
-    let d = val.value();
-    if d.is_nan() || d.is_infinite() {
-        return Err(trap(TrapCode::InvalidConversionToInteger));
-    }
-    let d_trunc = f64_trunc_compat(d);
-    // Wasm spec table range: not in (0.0, 18446744073709551616.0) for f64->u64
-    // This is (0, 2^64).
-    if !(0.0_f64..18_446_744_073_709_551_616.0_f64).contains(&d_trunc) {
-        if d_trunc == 0.0 && d_trunc.is_sign_negative() {
-            // -0.0 is fine
-        } else {
-            return Err(trap(TrapCode::IntegerOverflow));
+    #[cfg(feature = "soft-float")]
+    {
+        soft_trunc(soft_float::f64_to_int(val))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        let d = val.value();
+        if d.is_nan() || d.is_infinite() {
+            return Err(trap(TrapCode::InvalidConversionToInteger));
         }
+        let d_trunc = f64_trunc_compat(d);
+        // Wasm spec table range: not in (0.0, 18446744073709551616.0) for f64->u64
+        // This is (0, 2^64).
+        if !(0.0_f64..18_446_744_073_709_551_616.0_f64).contains(&d_trunc) {
+            if d_trunc == 0.0 && d_trunc.is_sign_negative() {
+                // -0.0 is fine
+            } else {
+                return Err(trap(TrapCode::IntegerOverflow));
+            }
+        }
+        Ok(d_trunc as u64)
     }
-    Ok(d_trunc as u64)
 }
 
 // --- Type Conversion Operations ---
@@ -2236,7 +2633,14 @@ pub fn i64_trunc_f64_u(val: FloatBits64) -> Result<u64> {
 /// This function does not return an error.
 #[inline]
 pub fn f32_convert_i32_s(val: i32) -> Result<FloatBits32> {
-    Ok(FloatBits32::from_float(val as f32))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f32_from_i64(val.into()))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        Ok(FloatBits32::from_float(val as f32))
+    }
 }
 
 /// f32.convert_i32_u: Convert unsigned i32 to f32.
@@ -2246,7 +2650,14 @@ pub fn f32_convert_i32_s(val: i32) -> Result<FloatBits32> {
 /// This function does not return an error.
 #[inline]
 pub fn f32_convert_i32_u(val: u32) -> Result<FloatBits32> {
-    Ok(FloatBits32::from_float(val as f32))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f32_from_u64(val.into()))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        Ok(FloatBits32::from_float(val as f32))
+    }
 }
 
 /// f32.convert_i64_s: Convert signed i64 to f32.
@@ -2256,7 +2667,14 @@ pub fn f32_convert_i32_u(val: u32) -> Result<FloatBits32> {
 /// This function does not return an error.
 #[inline]
 pub fn f32_convert_i64_s(val: i64) -> Result<FloatBits32> {
-    Ok(FloatBits32::from_float(val as f32))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f32_from_i64(val))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        Ok(FloatBits32::from_float(val as f32))
+    }
 }
 
 /// f32.convert_i64_u: Convert unsigned i64 to f32.
@@ -2266,7 +2684,14 @@ pub fn f32_convert_i64_s(val: i64) -> Result<FloatBits32> {
 /// This function does not return an error.
 #[inline]
 pub fn f32_convert_i64_u(val: u64) -> Result<FloatBits32> {
-    Ok(FloatBits32::from_float(val as f32))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f32_from_u64(val))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        Ok(FloatBits32::from_float(val as f32))
+    }
 }
 
 /// f64.convert_i32_s: Convert signed i32 to f64.
@@ -2276,7 +2701,14 @@ pub fn f32_convert_i64_u(val: u64) -> Result<FloatBits32> {
 /// This function does not return an error.
 #[inline]
 pub fn f64_convert_i32_s(val: i32) -> Result<FloatBits64> {
-    Ok(FloatBits64::from_float(f64::from(val)))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f64_from_i64(val.into()))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        Ok(FloatBits64::from_float(f64::from(val)))
+    }
 }
 
 /// f64.convert_i32_u: Convert unsigned i32 to f64.
@@ -2286,7 +2718,14 @@ pub fn f64_convert_i32_s(val: i32) -> Result<FloatBits64> {
 /// This function does not return an error.
 #[inline]
 pub fn f64_convert_i32_u(val: u32) -> Result<FloatBits64> {
-    Ok(FloatBits64::from_float(f64::from(val)))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f64_from_u64(val.into()))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        Ok(FloatBits64::from_float(f64::from(val)))
+    }
 }
 
 /// f64.convert_i64_s: Convert signed i64 to f64.
@@ -2296,7 +2735,14 @@ pub fn f64_convert_i32_u(val: u32) -> Result<FloatBits64> {
 /// This function does not return an error.
 #[inline]
 pub fn f64_convert_i64_s(val: i64) -> Result<FloatBits64> {
-    Ok(FloatBits64::from_float(val as f64))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f64_from_i64(val))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        Ok(FloatBits64::from_float(val as f64))
+    }
 }
 
 /// f64.convert_i64_u: Convert unsigned i64 to f64.
@@ -2306,7 +2752,14 @@ pub fn f64_convert_i64_s(val: i64) -> Result<FloatBits64> {
 /// This function does not return an error.
 #[inline]
 pub fn f64_convert_i64_u(val: u64) -> Result<FloatBits64> {
-    Ok(FloatBits64::from_float(val as f64))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f64_from_u64(val))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        Ok(FloatBits64::from_float(val as f64))
+    }
 }
 
 // Float to Float Conversions
@@ -2319,7 +2772,14 @@ pub fn f64_convert_i64_u(val: u64) -> Result<FloatBits64> {
 /// This function does not return an error.
 #[inline]
 pub fn f32_demote_f64(val: FloatBits64) -> Result<FloatBits32> {
-    Ok(FloatBits32::from_float(val.value() as f32))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f32_demote_f64(val))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        Ok(FloatBits32::from_float(val.value() as f32))
+    }
 }
 
 /// f64.promote_f32: Promote f32 to f64.
@@ -2330,7 +2790,14 @@ pub fn f32_demote_f64(val: FloatBits64) -> Result<FloatBits32> {
 /// This function does not return an error.
 #[inline]
 pub fn f64_promote_f32(val: FloatBits32) -> Result<FloatBits64> {
-    Ok(FloatBits64::from_float(f64::from(val.value())))
+    #[cfg(feature = "soft-float")]
+    {
+        Ok(soft_float::f64_promote_f32(val))
+    }
+    #[cfg(not(feature = "soft-float"))]
+    {
+        Ok(FloatBits64::from_float(f64::from(val.value())))
+    }
 }
 
 // Reinterpret (bit casting) Operations
@@ -0,0 +1,687 @@
+// WRT - wrt-math
+// Module: Soft-float Operations
+// SW-REQ-ID: REQ_018 (Wasm numeric operations)
+//
+// Copyright (c) 2025 R T
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+//! Integer-only IEEE 754 arithmetic for targets without an FPU.
+//!
+//! The functions of this module compute f32 and f64 operations from the bit
+//! patterns of their operands using integer arithmetic only, so their results
+//! do not depend on the floating-point unit, compiler intrinsics or `libm` of
+//! the target. Results are rounded to nearest, ties to even, as WebAssembly
+//! requires, and every NaN result is the canonical NaN.
+//!
+//! With the `soft-float` feature, the f32 and f64 operations of
+//! [`crate::ops`] are routed through this module. Sign manipulation (`abs`,
+//! `neg`, `copysign`) and reinterpretation never round and are not affected.
+
+use core::cmp::Ordering;
+
+use crate::float_bits::{
+    FloatBits32,
+    FloatBits64,
+};
+
+/// Layout of an IEEE 754 binary format
+#[derive(Clone, Copy)]
+struct Format {
+    exp_bits: u32,
+    man_bits: u32,
+}
+
+const BINARY32: Format = Format {
+    exp_bits: 8,
+    man_bits: 23,
+};
+
+const BINARY64: Format = Format {
+    exp_bits: 11,
+    man_bits: 52,
+};
+
+/// Bits below the significand of the larger addend that keep the sum exact
+/// enough to round correctly
+const ADD_GUARD_BITS: i32 = 64;
+
+/// Position of the leading bit of intermediate significands of `div` and
+/// `sqrt`, leaving well over the needed precision below the rounding point
+const WIDE_MSB: u32 = 120;
+
+impl Format {
+    const fn bias(self) -> i32 {
+        (1 << (self.exp_bits - 1)) - 1
+    }
+
+    const fn max_field(self) -> u64 {
+        (1 << self.exp_bits) - 1
+    }
+
+    const fn sign_bit(self) -> u64 {
+        1 << (self.exp_bits + self.man_bits)
+    }
+
+    const fn infinity(self) -> u64 {
+        self.max_field() << self.man_bits
+    }
+
+    const fn nan(self) -> u64 {
+        self.infinity() | 1 << (self.man_bits - 1)
+    }
+
+    /// Exponent of the least significant bit of subnormals
+    const fn min_exp(self) -> i32 {
+        1 - self.bias() - self.man_bits as i32
+    }
+
+    const fn signed_zero(self, negative: bool) -> u64 {
+        if negative {
+            self.sign_bit()
+        } else {
+            0
+        }
+    }
+}
+
+/// Value of a bit pattern, finite values being `sig * 2^exp`
+#[derive(Clone, Copy)]
+enum Class {
+    Nan,
+    Infinity,
+    Zero,
+    Finite { exp: i32, sig: u128 },
+}
+
+fn unpack(fmt: Format, bits: u64) -> (bool, Class) {
+    let negative = bits & fmt.sign_bit() != 0;
+    let field = (bits >> fmt.man_bits) & fmt.max_field();
+    let fraction = bits & ((1 << fmt.man_bits) - 1);
+    let class = if field == fmt.max_field() {
+        if fraction == 0 {
+            Class::Infinity
+        } else {
+            Class::Nan
+        }
+    } else if field == 0 {
+        if fraction == 0 {
+            Class::Zero
+        } else {
+            Class::Finite {
+                exp: fmt.min_exp(),
+                sig: u128::from(fraction),
+            }
+        }
+    } else {
+        Class::Finite {
+            exp: field as i32 - fmt.bias() - fmt.man_bits as i32,
+            sig: u128::from(fraction | 1 << fmt.man_bits),
+        }
+    };
+    (negative, class)
+}
+
+/// Round `sig * 2^exp` to nearest, ties to even, and encode it
+///
+/// Callers fold bits lost while computing `sig` into its least significant
+/// bit, which must lie at least two bits below the rounding point.
+fn round_pack(fmt: Format, negative: bool, exp: i32, sig: u128) -> u64 {
+    let sign = fmt.signed_zero(negative);
+    if sig == 0 {
+        return sign;
+    }
+    let msb = sig.ilog2() as i32;
+    let lsb_exp = (exp + msb - fmt.man_bits as i32).max(fmt.min_exp());
+    let shift = lsb_exp - exp;
+    if shift > msb + 1 {
+        // Below half of the smallest subnormal
+        return sign;
+    }
+
+    let kept = if shift <= 0 {
+        sig << -shift
+    } else {
+        let kept = sig >> shift;
+        let rem = sig & ((1 << shift) - 1);
+        let half = 1 << (shift - 1);
+        if rem > half || (rem == half && kept & 1 == 1) {
+            kept + 1
+        } else {
+            kept
+        }
+    };
+
+    // A carry out of the significand moves into the exponent field, and a
+    // subnormal lands on field zero with its leading bit still clear
+    let field = i64::from(lsb_exp) + i64::from(fmt.man_bits) + i64::from(fmt.bias()) - 1;
+    if field >= fmt.max_field() as i64 {
+        return sign | fmt.infinity();
+    }
+    let bits = ((field as u64) << fmt.man_bits) + kept as u64;
+    sign | bits.min(fmt.infinity())
+}
+
+fn add(fmt: Format, a: u64, b: u64) -> u64 {
+    let (an, ac) = unpack(fmt, a);
+    let (bn, bc) = unpack(fmt, b);
+    match (ac, bc) {
+        (Class::Nan, _) | (_, Class::Nan) => fmt.nan(),
+        (Class::Infinity, Class::Infinity) => {
+            if an == bn {
+                a
+            } else {
+                fmt.nan()
+            }
+        },
+        (Class::Zero, Class::Zero) => fmt.signed_zero(an && bn),
+        (Class::Infinity, _) | (_, Class::Zero) => a,
+        (_, Class::Infinity) | (Class::Zero, _) => b,
+        (Class::Finite { exp: ae, sig: asig }, Class::Finite { exp: be, sig: bsig }) => {
+            let ((an, ae, asig), (bn, be, bsig)) = if ae >= be {
+                ((an, ae, asig), (bn, be, bsig))
+            } else {
+                ((bn, be, bsig), (an, ae, asig))
+            };
+            let asig = asig << ADD_GUARD_BITS;
+            let bsig = bsig << ADD_GUARD_BITS;
+            let distance = (ae - be) as u32;
+            let bsig = if distance >= 128 {
+                1
+            } else {
+                let aligned = bsig >> distance;
+                aligned | u128::from(aligned << distance != bsig)
+            };
+            let exp = ae - ADD_GUARD_BITS;
+
+            if an == bn {
+                round_pack(fmt, an, exp, asig + bsig)
+            } else {
+                match asig.cmp(&bsig) {
+                    Ordering::Greater => round_pack(fmt, an, exp, asig - bsig),
+                    Ordering::Less => round_pack(fmt, bn, exp, bsig - asig),
+                    Ordering::Equal => 0,
+                }
+            }
+        },
+    }
+}
+
+fn mul(fmt: Format, a: u64, b: u64) -> u64 {
+    let negative = (a ^ b) & fmt.sign_bit() != 0;
+    match (unpack(fmt, a).1, unpack(fmt, b).1) {
+        (Class::Nan, _)
+        | (_, Class::Nan)
+        | (Class::Infinity, Class::Zero)
+        | (Class::Zero, Class::Infinity) => fmt.nan(),
+        (Class::Infinity, _) | (_, Class::Infinity) => fmt.signed_zero(negative) | fmt.infinity(),
+        (Class::Zero, _) | (_, Class::Zero) => fmt.signed_zero(negative),
+        (Class::Finite { exp: ae, sig: asig }, Class::Finite { exp: be, sig: bsig }) => {
+            round_pack(fmt, negative, ae + be, asig * bsig)
+        },
+    }
+}
+
+fn div(fmt: Format, a: u64, b: u64) -> u64 {
+    let negative = (a ^ b) & fmt.sign_bit() != 0;
+    match (unpack(fmt, a).1, unpack(fmt, b).1) {
+        (Class::Nan, _)
+        | (_, Class::Nan)
+        | (Class::Infinity, Class::Infinity)
+        | (Class::Zero, Class::Zero) => fmt.nan(),
+        (Class::Infinity, _) | (_, Class::Zero) => fmt.signed_zero(negative) | fmt.infinity(),
+        (_, Class::Infinity) | (Class::Zero, _) => fmt.signed_zero(negative),
+        (Class::Finite { exp: ae, sig: asig }, Class::Finite { exp: be, sig: bsig }) => {
+            let shift = WIDE_MSB - asig.ilog2();
+            let numerator = asig << shift;
+            let quotient = numerator / bsig;
+            let inexact = numerator % bsig != 0;
+            round_pack(
+                fmt,
+                negative,
+                ae - shift as i32 - be,
+                quotient | u128::from(inexact),
+            )
+        },
+    }
+}
+
+fn sqrt(fmt: Format, a: u64) -> u64 {
+    match unpack(fmt, a) {
+        (_, Class::Nan) | (true, Class::Infinity | Class::Finite { .. }) => fmt.nan(),
+        (_, Class::Zero) | (false, Class::Infinity) => a,
+        (false, Class::Finite { exp, sig }) => {
+            // Keep the exponent even so that it halves exactly
+            let mut shift = (WIDE_MSB - sig.ilog2()) as i32;
+            if (exp - shift) % 2 != 0 {
+                shift -= 1;
+            }
+            let radicand = sig << shift;
+            let root = isqrt(radicand);
+            let inexact = root * root != radicand;
+            round_pack(fmt, false, (exp - shift) / 2, root | u128::from(inexact))
+        },
+    }
+}
+
+fn isqrt(n: u128) -> u128 {
+    let mut rem = n;
+    let mut root = 0;
+    let mut bit = 1 << 126;
+    while bit > n {
+        bit >>= 2;
+    }
+    while bit != 0 {
+        if rem >= root + bit {
+            rem -= root + bit;
+            root = (root >> 1) + bit;
+        } else {
+            root >>= 1;
+        }
+        bit >>= 2;
+    }
+    root
+}
+
+/// Direction of rounding to an integral value
+#[derive(Clone, Copy)]
+enum Rounding {
+    Nearest,
+    TowardZero,
+    Up,
+    Down,
+}
+
+fn round_integral(fmt: Format, a: u64, rounding: Rounding) -> u64 {
+    match unpack(fmt, a) {
+        (_, Class::Nan) => fmt.nan(),
+        (_, Class::Infinity | Class::Zero) => a,
+        (_, Class::Finite { exp, .. }) if exp >= 0 => a,
+        (negative, Class::Finite { exp, sig }) => {
+            // Larger shifts only push the value further below one half
+            let shift = exp.unsigned_abs().min(100);
+            let kept = sig >> shift;
+            let rem = sig & ((1 << shift) - 1);
+            let half = 1 << (shift - 1);
+            let round_up = match rounding {
+                Rounding::Nearest => rem > half || (rem == half && kept & 1 == 1),
+                Rounding::TowardZero => false,
+                Rounding::Up => !negative && rem != 0,
+                Rounding::Down => negative && rem != 0,
+            };
+            round_pack(fmt, negative, 0, kept + u128::from(round_up))
+        },
+    }
+}
+
+fn compare(fmt: Format, a: u64, b: u64) -> Option<Ordering> {
+    let key = |bits: u64| -> Option<i64> {
+        match unpack(fmt, bits) {
+            (_, Class::Nan) => None,
+            (negative, _) => {
+                let magnitude = (bits & !fmt.sign_bit()) as i64;
+                Some(if negative { -magnitude } else { magnitude })
+            },
+        }
+    };
+    Some(key(a)?.cmp(&key(b)?))
+}
+
+fn min(fmt: Format, a: u64, b: u64) -> u64 {
+    match compare(fmt, a, b) {
+        None => fmt.nan(),
+        Some(Ordering::Less) => a,
+        Some(Ordering::Greater) => b,
+        // Equal values differ at most in the sign of zero
+        Some(Ordering::Equal) => a | b,
+    }
+}
+
+fn max(fmt: Format, a: u64, b: u64) -> u64 {
+    match compare(fmt, a, b) {
+        None => fmt.nan(),
+        Some(Ordering::Less) => b,
+        Some(Ordering::Greater) => a,
+        Some(Ordering::Equal) => a & b,
+    }
+}
+
+fn from_int(fmt: Format, negative: bool, magnitude: u64) -> u64 {
+    round_pack(fmt, negative, 0, u128::from(magnitude))
+}
+
+fn convert(from: Format, to: Format, a: u64) -> u64 {
+    match unpack(from, a) {
+        (_, Class::Nan) => to.nan(),
+        (negative, Class::Infinity) => to.signed_zero(negative) | to.infinity(),
+        (negative, Class::Zero) => to.signed_zero(negative),
+        (negative, Class::Finite { exp, sig }) => round_pack(to, negative, exp, sig),
+    }
+}
+
+/// Smallest magnitude, in bits, clamped values keep outside every 64-bit
+/// integer range
+const CLAMP_BITS: i32 = 70;
+
+fn to_int(fmt: Format, a: u64) -> Option<i128> {
+    match unpack(fmt, a) {
+        (_, Class::Nan | Class::Infinity) => None,
+        (_, Class::Zero) => Some(0),
+        (negative, Class::Finite { exp, sig }) => {
+            let magnitude = if exp >= 0 {
+                sig << exp.min(CLAMP_BITS)
+            } else {
+                sig >> exp.unsigned_abs().min(127)
+            };
+            let magnitude = magnitude as i128;
+            Some(if negative { -magnitude } else { magnitude })
+        },
+    }
+}
+
+fn to_int_sat(fmt: Format, a: u64) -> i128 {
+    match unpack(fmt, a) {
+        (_, Class::Nan) => 0,
+        (negative, Class::Infinity) => {
+            let magnitude = 1_i128 << CLAMP_BITS;
+            if negative {
+                -magnitude
+            } else {
+                magnitude
+            }
+        },
+        _ => to_int(fmt, a).unwrap_or(0),
+    }
+}
+
+macro_rules! soft_float_ops {
+    ($bits:ident, $fmt:ident, $raw:ty,
+     $add:ident, $sub:ident, $mul:ident, $div:ident, $sqrt:ident,
+     $min:ident, $max:ident, $ceil:ident, $floor:ident, $trunc:ident, $nearest:ident,
+     $compare:ident, $from_i64:ident, $from_u64:ident, $to_int:ident, $to_int_sat:ident,
+     $name:literal) => {
+        #[doc = concat!("Add two ", $name, " values")]
+        #[must_use]
+        pub fn $add(lhs: $bits, rhs: $bits) -> $bits {
+            $bits::from_bits(add($fmt, lhs.to_bits().into(), rhs.to_bits().into()) as $raw)
+        }
+
+        #[doc = concat!("Subtract two ", $name, " values")]
+        #[must_use]
+        pub fn $sub(lhs: $bits, rhs: $bits) -> $bits {
+            let rhs = u64::from(rhs.to_bits()) ^ $fmt.sign_bit();
+            $bits::from_bits(add($fmt, lhs.to_bits().into(), rhs) as $raw)
+        }
+
+        #[doc = concat!("Multiply two ", $name, " values")]
+        #[must_use]
+        pub fn $mul(lhs: $bits, rhs: $bits) -> $bits {
+            $bits::from_bits(mul($fmt, lhs.to_bits().into(), rhs.to_bits().into()) as $raw)
+        }
+
+        #[doc = concat!("Divide two ", $name, " values")]
+        #[must_use]
+        pub fn $div(lhs: $bits, rhs: $bits) -> $bits {
+            $bits::from_bits(div($fmt, lhs.to_bits().into(), rhs.to_bits().into()) as $raw)
+        }
+
+        #[doc = concat!("Square root of an ", $name, " value")]
+        #[must_use]
+        pub fn $sqrt(val: $bits) -> $bits {
+            $bits::from_bits(sqrt($fmt, val.to_bits().into()) as $raw)
+        }
+
+        #[doc = concat!("Minimum of two ", $name, " values, -0 being less than +0")]
+        #[must_use]
+        pub fn $min(lhs: $bits, rhs: $bits) -> $bits {
+            $bits::from_bits(min($fmt, lhs.to_bits().into(), rhs.to_bits().into()) as $raw)
+        }
+
+        #[doc = concat!("Maximum of two ", $name, " values, +0 being greater than -0")]
+        #[must_use]
+        pub fn $max(lhs: $bits, rhs: $bits) -> $bits {
+            $bits::from_bits(max($fmt, lhs.to_bits().into(), rhs.to_bits().into()) as $raw)
+        }
+
+        #[doc = concat!("Round an ", $name, " value up to an integral value")]
+        #[must_use]
+        pub fn $ceil(val: $bits) -> $bits {
+            $bits::from_bits(round_integral($fmt, val.to_bits().into(), Rounding::Up) as $raw)
+        }
+
+        #[doc = concat!("Round an ", $name, " value down to an integral value")]
+        #[must_use]
+        pub fn $floor(val: $bits) -> $bits {
+            $bits::from_bits(round_integral($fmt, val.to_bits().into(), Rounding::Down) as $raw)
+        }
+
+        #[doc = concat!("Round an ", $name, " value toward zero to an integral value")]
+        #[must_use]
+        pub fn $trunc(val: $bits) -> $bits {
+            $bits::from_bits(
+                round_integral($fmt, val.to_bits().into(), Rounding::TowardZero) as $raw,
+            )
+        }
+
+        #[doc = concat!("Round an ", $name, " value to the nearest integral value, ties to even")]
+        #[must_use]
+        pub fn $nearest(val: $bits) -> $bits {
+            $bits::from_bits(
+                round_integral($fmt, val.to_bits().into(), Rounding::Nearest) as $raw,
+            )
+        }
+
+        #[doc = concat!("Compare two ", $name, " values, `None` if either is NaN")]
+        #[must_use]
+        pub fn $compare(lhs: $bits, rhs: $bits) -> Option<Ordering> {
+            compare($fmt, lhs.to_bits().into(), rhs.to_bits().into())
+        }
+
+        #[doc = concat!("Convert a signed integer to the nearest ", $name, " value")]
+        #[must_use]
+        pub fn $from_i64(val: i64) -> $bits {
+            $bits::from_bits(from_int($fmt, val < 0, val.unsigned_abs()) as $raw)
+        }
+
+        #[doc = concat!("Convert an unsigned integer to the nearest ", $name, " value")]
+        #[must_use]
+        pub fn $from_u64(val: u64) -> $bits {
+            $bits::from_bits(from_int($fmt, false, val) as $raw)
+        }
+
+        #[doc = concat!("Truncate an ", $name, " value to an integer, `None` for NaN and infinities")]
+        ///
+        /// Magnitudes beyond the range of 64-bit integers are clamped to a
+        /// value that is still outside of it.
+        #[must_use]
+        pub fn $to_int(val: $bits) -> Option<i128> {
+            to_int($fmt, val.to_bits().into())
+        }
+
+        #[doc = concat!("Truncate an ", $name, " value to an integer, NaN giving zero")]
+        ///
+        /// Infinities and magnitudes beyond the range of 64-bit integers are
+        /// clamped to a value that is still outside of it.
+        #[must_use]
+        pub fn $to_int_sat(val: $bits) -> i128 {
+            to_int_sat($fmt, val.to_bits().into())
+        }
+    };
+}
+
+soft_float_ops! {
+    FloatBits32, BINARY32, u32, f32_add, f32_sub, f32_mul, f32_div, f32_sqrt, f32_min, f32_max,
+    f32_ceil, f32_floor, f32_trunc, f32_nearest, f32_compare, f32_from_i64, f32_from_u64,
+    f32_to_int, f32_to_int_sat, "f32"
+}
+
+soft_float_ops! {
+    FloatBits64, BINARY64, u64, f64_add, f64_sub, f64_mul, f64_div, f64_sqrt, f64_min, f64_max,
+    f64_ceil, f64_floor, f64_trunc, f64_nearest, f64_compare, f64_from_i64, f64_from_u64,
+    f64_to_int, f64_to_int_sat, "f64"
+}
+
+/// Round an f64 value to the nearest f32 value
+#[must_use]
+pub fn f32_demote_f64(val: FloatBits64) -> FloatBits32 {
+    FloatBits32::from_bits(convert(BINARY64, BINARY32, val.to_bits()) as u32)
+}
+
+/// Convert an f32 value to f64, which is exact
+#[must_use]
+pub fn f64_promote_f32(val: FloatBits32) -> FloatBits64 {
+    FloatBits64::from_bits(convert(BINARY32, BINARY64, val.to_bits().into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Operands covering zeros, subnormals, boundaries, ties and specials
+    const F64_SAMPLES: [f64; 24] = [
+        0.0,
+        -0.0,
+        1.0,
+        -1.0,
+        0.5,
+        1.5,
+        2.5,
+        -2.5,
+        0.1,
+        3.0,
+        1e-310,
+        -4.9e-324,
+        f64::MIN_POSITIVE,
+        f64::MAX,
+        -f64::MAX,
+        f64::EPSILON,
+        1.0 + f64::EPSILON,
+        123_456_789.123,
+        -0.3,
+        9_007_199_254_740_993.0,
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+        f64::NAN,
+        7.0e300,
+    ];
+
+    fn f32_samples() -> impl Iterator<Item = f32> {
+        F64_SAMPLES.iter().map(|&x| x as f32).chain([
+            1e-45,
+            1.1e-38,
+            16_777_217.0,
+            3.402_823_4e38,
+            0.1,
+        ])
+    }
+
+    fn same32(soft: FloatBits32, native: f32) -> bool {
+        if native.is_nan() {
+            soft.to_bits() == FloatBits32::NAN.to_bits()
+        } else {
+            soft.to_bits() == native.to_bits()
+        }
+    }
+
+    fn same64(soft: FloatBits64, native: f64) -> bool {
+        if native.is_nan() {
+            soft.to_bits() == FloatBits64::NAN.to_bits()
+        } else {
+            soft.to_bits() == native.to_bits()
+        }
+    }
+
+    #[test]
+    fn test_f64_arithmetic_matches_native() {
+        for &a in &F64_SAMPLES {
+            let fa = FloatBits64::from_float(a);
+            assert!(same64(f64_sqrt(fa), a.sqrt()), "sqrt {a:e}");
+            assert!(same64(f64_ceil(fa), a.ceil()), "ceil {a:e}");
+            assert!(same64(f64_floor(fa), a.floor()), "floor {a:e}");
+            assert!(same64(f64_trunc(fa), a.trunc()), "trunc {a:e}");
+            assert!(same32(f32_demote_f64(fa), a as f32), "demote {a:e}");
+            for &b in &F64_SAMPLES {
+                let fb = FloatBits64::from_float(b);
+                assert!(same64(f64_add(fa, fb), a + b), "{a:e} + {b:e}");
+                assert!(same64(f64_sub(fa, fb), a - b), "{a:e} - {b:e}");
+                assert!(same64(f64_mul(fa, fb), a * b), "{a:e} * {b:e}");
+                assert!(same64(f64_div(fa, fb), a / b), "{a:e} / {b:e}");
+                assert_eq!(f64_compare(fa, fb), a.partial_cmp(&b), "{a:e} <=> {b:e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_f32_arithmetic_matches_native() {
+        for a in f32_samples() {
+            let fa = FloatBits32::from_float(a);
+            assert!(same32(f32_sqrt(fa), a.sqrt()), "sqrt {a:e}");
+            assert!(same64(f64_promote_f32(fa), f64::from(a)), "promote {a:e}");
+            for b in f32_samples() {
+                let fb = FloatBits32::from_float(b);
+                assert!(same32(f32_add(fa, fb), a + b), "{a:e} + {b:e}");
+                assert!(same32(f32_sub(fa, fb), a - b), "{a:e} - {b:e}");
+                assert!(same32(f32_mul(fa, fb), a * b), "{a:e} * {b:e}");
+                assert!(same32(f32_div(fa, fb), a / b), "{a:e} / {b:e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_integer_conversions() {
+        for val in [
+            0,
+            1,
+            -1,
+            i64::MAX,
+            i64::MIN,
+            (1 << 53) + 1,
+            (1 << 24) + 1,
+            -12_345_678_901,
+        ] {
+            assert!(same32(f32_from_i64(val), val as f32), "{val}");
+            assert!(same64(f64_from_i64(val), val as f64), "{val}");
+        }
+        assert!(same32(f32_from_u64(u64::MAX), u64::MAX as f32));
+        assert!(same64(f64_from_u64(u64::MAX), u64::MAX as f64));
+
+        assert_eq!(f64_to_int(FloatBits64::from_float(-2.9)), Some(-2));
+        assert_eq!(f32_to_int(FloatBits32::from_float(f32::INFINITY)), None);
+        assert_eq!(f32_to_int_sat(FloatBits32::NAN), 0);
+        assert!(f64_to_int_sat(FloatBits64::from_float(1e300)) > i128::from(u64::MAX));
+        assert!(f64_to_int_sat(FloatBits64::from_float(f64::NEG_INFINITY)) < i128::from(i64::MIN));
+    }
+
+    #[test]
+    fn test_nearest_ties_to_even() {
+        for (val, nearest) in [
+            (0.5, 0.0),
+            (1.5, 2.0),
+            (2.5, 2.0),
+            (-2.5, -2.0),
+            (-0.4, -0.0),
+            (3.7, 4.0),
+        ] {
+            let soft = f32_nearest(FloatBits32::from_float(val));
+            assert_eq!(soft.to_bits(), f32::to_bits(nearest), "nearest {val}");
+            let soft = f64_nearest(FloatBits64::from_float(f64::from(val)));
+            assert_eq!(
+                soft.to_bits(),
+                f64::from(nearest).to_bits(),
+                "nearest {val}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_min_max_signed_zero() {
+        let pos = FloatBits64::from_float(0.0);
+        let neg = FloatBits64::from_float(-0.0);
+        assert_eq!(f64_min(pos, neg).to_bits(), neg.to_bits());
+        assert_eq!(f64_max(neg, pos).to_bits(), pos.to_bits());
+        assert_eq!(
+            f64_min(pos, FloatBits64::NAN).to_bits(),
+            FloatBits64::NAN.to_bits()
+        );
+    }
+}
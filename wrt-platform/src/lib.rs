@@ -90,8 +90,10 @@
 pub mod platform_abstraction;
 pub mod prelude;
 pub mod random;
+pub mod rtos;
 pub mod runtime_detection;
 pub mod simd;
+pub mod static_pool_memory;
 pub mod sync;
 pub mod time;
 
@@ -186,6 +188,14 @@
 pub mod qnx_sync;
 
 // Linux-specific modules
+#[cfg(all(
+    feature = "platform-linux",
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+pub mod linux_cow_memory;
+#[cfg(all(feature = "platform-linux", target_os = "linux", target_arch = "x86_64"))]
+pub mod linux_guarded_memory;
 #[cfg(all(feature = "platform-linux", target_os = "linux"))]
 pub mod linux_memory;
 #[cfg(all(
@@ -200,6 +210,8 @@
 
 // Zephyr-specific modules
 #[cfg(feature = "platform-zephyr")]
+pub mod zephyr_kernel;
+#[cfg(feature = "platform-zephyr")]
 pub mod zephyr_memory;
 #[cfg(feature = "platform-zephyr")]
 pub mod zephyr_sync;
@@ -284,6 +296,17 @@
     LinuxAllocator,
     LinuxAllocatorBuilder,
 };
+#[cfg(all(
+    feature = "platform-linux",
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+pub use linux_cow_memory::{
+    CowMemoryImage,
+    LinuxCowAllocator,
+};
+#[cfg(all(feature = "platform-linux", target_os = "linux", target_arch = "x86_64"))]
+pub use linux_guarded_memory::GuardedLinearMemory;
 #[cfg(all(
     feature = "platform-linux",
     feature = "linux-mte",
@@ -365,6 +388,12 @@
     QnxSyncPriority,
 };
 // Export runtime detection
+pub use rtos::{
+    RtosDescriptor,
+    RtosKernel,
+    RtosMutex,
+    RtosMutexGuard,
+};
 pub use runtime_detection::{
     MemoryCapabilities,
     PlatformCapabilities,
@@ -391,6 +420,7 @@
     SimdLevel,
     SimdProvider,
 };
+pub use static_pool_memory::StaticPoolAllocator;
 pub use sync::{
     FutexLike,
     SpinFutex,
@@ -432,6 +462,8 @@
 pub use wrt_error::Error;
 // Export Zephyr specific implementations if enabled
 #[cfg(feature = "platform-zephyr")]
+pub use zephyr_kernel::ZephyrKernel;
+#[cfg(feature = "platform-zephyr")]
 pub use zephyr_memory::{
     ZephyrAllocator,
     ZephyrAllocatorBuilder,
@@ -684,15 +716,17 @@ fn test_vxworks_thread_builder() {
 }
 
 // Global allocator for no_std builds - panic on allocation attempts
-// This catches inadvertent allocation attempts in no_std mode
-#[cfg(all(not(feature = "std"), not(test)))]
+// This catches inadvertent allocation attempts in no_std mode. Bare-metal
+// integrators providing their own `#[global_allocator]` enable the
+// `external-allocator` feature to leave it out.
+#[cfg(all(not(feature = "std"), not(test), not(feature = "external-allocator")))]
 #[global_allocator]
 static GLOBAL: PanicAllocator = PanicAllocator;
 
-#[cfg(all(not(feature = "std"), not(test)))]
+#[cfg(all(not(feature = "std"), not(test), not(feature = "external-allocator")))]
 struct PanicAllocator;
 
-#[cfg(all(not(feature = "std"), not(test)))]
+#[cfg(all(not(feature = "std"), not(test), not(feature = "external-allocator")))]
 unsafe impl core::alloc::GlobalAlloc for PanicAllocator {
     #[allow(clippy::panic)] // Intentional panic to prevent allocation in no_std
     unsafe fn alloc(&self, _layout: core::alloc::Layout) -> *mut u8 {
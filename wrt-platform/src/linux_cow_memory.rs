@@ -0,0 +1,495 @@
+#![allow(unsafe_code)]
+// WRT - wrt-platform
+// Module: Linux Copy-on-Write Memory Images
+// SW-REQ-ID: REQ_PLATFORM_001, REQ_MEMORY_001
+//
+// Copyright (c) 2025 The WRT Project Developers
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+//! Copy-on-write initialization of linear memories on Linux.
+//!
+//! Instantiating a module copies its active data segments into every new
+//! linear memory. A [`CowMemoryImage`] holds the initialized contents of a
+//! memory once, in an anonymous memory file, and every
+//! [`LinuxCowAllocator`] created from it maps that file privately at the
+//! start of its memory. The kernel then shares the initialized pages
+//! between all instances, faults them in on first access and copies a page
+//! only when an instance first writes to it.
+//!
+//! The image file is sparse: pages no data segment touches take no space
+//! and read as zero, like the rest of a fresh linear memory.
+
+use core::ptr::{
+    self,
+    NonNull,
+};
+
+use wrt_error::{
+    Error,
+    ErrorCategory,
+    Result,
+};
+
+use crate::memory::{
+    PageAllocator,
+    WASM_PAGE_SIZE,
+};
+
+/// Linux syscall numbers for x86_64
+#[cfg(target_arch = "x86_64")]
+mod syscalls {
+    pub const PWRITE64: usize = 18;
+    pub const MMAP: usize = 9;
+    pub const MUNMAP: usize = 11;
+    pub const CLOSE: usize = 3;
+    pub const DUP: usize = 32;
+    pub const FTRUNCATE: usize = 77;
+    pub const MEMFD_CREATE: usize = 319;
+}
+
+/// Linux syscall numbers for aarch64 (ARM64)
+#[cfg(target_arch = "aarch64")]
+mod syscalls {
+    pub const PWRITE64: usize = 68;
+    pub const MMAP: usize = 222;
+    pub const MUNMAP: usize = 215;
+    pub const CLOSE: usize = 57;
+    pub const DUP: usize = 23;
+    pub const FTRUNCATE: usize = 46;
+    pub const MEMFD_CREATE: usize = 279;
+}
+
+/// Protection flags for memory mapping
+const PROT_READ: usize = 0x1;
+const PROT_WRITE: usize = 0x2;
+
+/// Mapping flags
+const MAP_PRIVATE: usize = 0x02;
+const MAP_FIXED: usize = 0x10;
+const MAP_ANONYMOUS: usize = 0x20;
+
+/// Close the memory file on exec
+const MFD_CLOEXEC: usize = 0x1;
+
+/// NUL-terminated name of the memory file, shown in `/proc/<pid>/maps`
+const IMAGE_NAME: &[u8] = b"wrt-memory-image\0";
+
+/// Performs a syscall directly without libc
+///
+/// Returns the raw result, a negative errno on failure.
+unsafe fn syscall(nr: usize, args: [usize; 6]) -> isize {
+    let result: isize;
+
+    #[cfg(target_arch = "x86_64")]
+    core::arch::asm!(
+        "syscall",
+        inout("rax") nr as isize => result,
+        in("rdi") args[0],
+        in("rsi") args[1],
+        in("rdx") args[2],
+        in("r10") args[3],
+        in("r8") args[4],
+        in("r9") args[5],
+        out("rcx") _,
+        out("r11") _,
+    );
+
+    #[cfg(target_arch = "aarch64")]
+    core::arch::asm!(
+        "svc #0",
+        in("x8") nr,
+        inout("x0") args[0] as isize => result,
+        in("x1") args[1],
+        in("x2") args[2],
+        in("x3") args[3],
+        in("x4") args[4],
+        in("x5") args[5],
+    );
+
+    result
+}
+
+/// Whether a raw syscall result is an error
+fn failed(result: isize) -> bool {
+    (-4095..0).contains(&result)
+}
+
+fn pages_to_bytes(pages: u32) -> Result<usize> {
+    (pages as usize)
+        .checked_mul(WASM_PAGE_SIZE)
+        .ok_or_else(|| Error::memory_error("Page count results in byte overflow"))
+}
+
+/// Initialized contents of a linear memory, shared copy-on-write
+#[derive(Debug)]
+pub struct CowMemoryImage {
+    fd:    i32,
+    /// Size of the image in bytes, a whole number of Wasm pages
+    bytes: usize,
+}
+
+impl CowMemoryImage {
+    /// Creates an empty image.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the kernel cannot create the memory file.
+    pub fn new() -> Result<Self> {
+        // SAFETY: IMAGE_NAME is NUL-terminated and outlives the call.
+        let fd = unsafe {
+            syscall(
+                syscalls::MEMFD_CREATE,
+                [IMAGE_NAME.as_ptr() as usize, MFD_CLOEXEC, 0, 0, 0, 0],
+            )
+        };
+        if failed(fd) {
+            return Err(Error::runtime_execution_error(
+                "Failed to create memory image file",
+            ));
+        }
+        Ok(Self {
+            fd:    fd as i32,
+            bytes: 0,
+        })
+    }
+
+    /// Writes `data` at byte `offset` of the image, growing it to cover the
+    /// written range.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the range overflows or the kernel rejects the
+    /// write.
+    pub fn write(&mut self, offset: usize, data: &[u8]) -> Result<()> {
+        let end = offset
+            .checked_add(data.len())
+            .ok_or_else(|| Error::memory_error("Memory image write overflows"))?;
+        if end > self.bytes {
+            let pages = end.div_ceil(WASM_PAGE_SIZE);
+            let bytes = pages
+                .checked_mul(WASM_PAGE_SIZE)
+                .ok_or_else(|| Error::memory_error("Memory image size overflows"))?;
+            // SAFETY: ftruncate only resizes the file we own.
+            let result =
+                unsafe { syscall(syscalls::FTRUNCATE, [self.fd as usize, bytes, 0, 0, 0, 0]) };
+            if failed(result) {
+                return Err(Error::runtime_execution_error(
+                    "Failed to resize memory image file",
+                ));
+            }
+            self.bytes = bytes;
+        }
+
+        let mut written = 0;
+        while written < data.len() {
+            let rest = &data[written..];
+            // SAFETY: `rest` is a valid buffer of `rest.len()` bytes for the
+            // duration of the call.
+            let result = unsafe {
+                syscall(
+                    syscalls::PWRITE64,
+                    [
+                        self.fd as usize,
+                        rest.as_ptr() as usize,
+                        rest.len(),
+                        offset + written,
+                        0,
+                        0,
+                    ],
+                )
+            };
+            if failed(result) || result == 0 {
+                return Err(Error::runtime_execution_error(
+                    "Failed to write memory image file",
+                ));
+            }
+            written += result as usize;
+        }
+        Ok(())
+    }
+
+    /// Number of Wasm pages covered by the image.
+    pub fn pages(&self) -> u32 {
+        (self.bytes / WASM_PAGE_SIZE) as u32
+    }
+
+    /// Creates an allocator whose memory starts out with the contents of the
+    /// image.
+    ///
+    /// The allocator keeps its own handle to the image, so the image may be
+    /// dropped while memories created from it are still alive.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the kernel cannot duplicate the file handle.
+    pub fn allocator(&self, maximum_pages: Option<u32>) -> Result<LinuxCowAllocator> {
+        // SAFETY: dup only duplicates the file descriptor we own.
+        let fd = unsafe { syscall(syscalls::DUP, [self.fd as usize, 0, 0, 0, 0, 0]) };
+        if failed(fd) {
+            return Err(Error::runtime_execution_error(
+                "Failed to duplicate memory image handle",
+            ));
+        }
+        let max_pages = maximum_pages.unwrap_or(LinuxCowAllocator::DEFAULT_MAX_PAGES);
+        Ok(LinuxCowAllocator {
+            fd: fd as i32,
+            image_bytes: self.bytes,
+            base_ptr: None,
+            total_reserved_bytes: 0,
+            current_committed_bytes: 0,
+            max_capacity_bytes: pages_to_bytes(max_pages)?,
+        })
+    }
+}
+
+impl Drop for CowMemoryImage {
+    fn drop(&mut self) {
+        // SAFETY: the descriptor is owned by this image and closed only here.
+        unsafe {
+            syscall(syscalls::CLOSE, [self.fd as usize, 0, 0, 0, 0, 0]);
+        }
+    }
+}
+
+/// A `PageAllocator` whose memory is initialized copy-on-write from a
+/// [`CowMemoryImage`].
+#[derive(Debug)]
+pub struct LinuxCowAllocator {
+    fd: i32,
+    image_bytes: usize,
+    base_ptr: Option<NonNull<u8>>,
+    total_reserved_bytes: usize,
+    current_committed_bytes: usize,
+    max_capacity_bytes: usize,
+}
+
+// Safety: NonNull<u8> is safe to send between threads as it's just a pointer
+// wrapper
+unsafe impl Send for LinuxCowAllocator {}
+unsafe impl Sync for LinuxCowAllocator {}
+
+impl LinuxCowAllocator {
+    // Corresponds to 4GiB, a common Wasm limit
+    const DEFAULT_MAX_PAGES: u32 = 65536;
+
+    unsafe fn mmap(
+        addr: *mut u8,
+        len: usize,
+        prot: usize,
+        flags: usize,
+        fd: i32,
+    ) -> Result<*mut u8> {
+        let result = syscall(
+            syscalls::MMAP,
+            [addr as usize, len, prot, flags, fd as usize, 0],
+        );
+        if failed(result) {
+            return Err(Error::runtime_execution_error(
+                "Failed to map memory using mmap syscall",
+            ));
+        }
+        Ok(result as *mut u8)
+    }
+
+    unsafe fn munmap(addr: *mut u8, len: usize) -> isize {
+        syscall(syscalls::MUNMAP, [addr as usize, len, 0, 0, 0, 0])
+    }
+}
+
+impl PageAllocator for LinuxCowAllocator {
+    fn allocate(
+        &mut self,
+        initial_pages: u32,
+        maximum_pages: Option<u32>,
+    ) -> Result<(NonNull<u8>, usize)> {
+        if self.base_ptr.is_some() {
+            return Err(Error::new(
+                ErrorCategory::System,
+                1,
+                "Memory allocator already initialized with allocated memory",
+            ));
+        }
+
+        if initial_pages == 0 {
+            return Err(Error::memory_error("Initial pages cannot be zero"));
+        }
+
+        let initial_bytes = pages_to_bytes(initial_pages)?;
+        if self.image_bytes > initial_bytes {
+            return Err(Error::memory_error(
+                "Memory image is larger than the initial memory",
+            ));
+        }
+        let max_pages_hint = maximum_pages.unwrap_or(initial_pages).max(initial_pages);
+        let reserve_bytes = pages_to_bytes(max_pages_hint)?;
+        if reserve_bytes > self.max_capacity_bytes {
+            return Err(Error::memory_error(
+                "Requested reservation size exceeds allocator's maximum capacity",
+            ));
+        }
+
+        // SAFETY: an anonymous private mapping of a fresh address range.
+        let ptr = unsafe {
+            Self::mmap(
+                ptr::null_mut(),
+                reserve_bytes,
+                PROT_READ | PROT_WRITE,
+                MAP_PRIVATE | MAP_ANONYMOUS,
+                -1,
+            )?
+        };
+
+        if self.image_bytes > 0 {
+            // SAFETY: the image range lies within the reservation mapped
+            // above, which is replaced in place by a private mapping of the
+            // image file.
+            let mapped = unsafe {
+                Self::mmap(
+                    ptr,
+                    self.image_bytes,
+                    PROT_READ | PROT_WRITE,
+                    MAP_PRIVATE | MAP_FIXED,
+                    self.fd,
+                )
+            };
+            if let Err(error) = mapped {
+                // SAFETY: unmaps the reservation mapped above.
+                unsafe {
+                    Self::munmap(ptr, reserve_bytes);
+                }
+                return Err(error);
+            }
+        }
+
+        let base_ptr = NonNull::new(ptr)
+            .ok_or_else(|| Error::new(ErrorCategory::System, 1, "mmap returned null pointer"))?;
+        self.base_ptr = Some(base_ptr);
+        self.total_reserved_bytes = reserve_bytes;
+        self.current_committed_bytes = initial_bytes;
+
+        Ok((base_ptr, initial_bytes))
+    }
+
+    fn grow(&mut self, current_pages: u32, additional_pages: u32) -> Result<()> {
+        if self.base_ptr.is_none() {
+            return Err(Error::runtime_execution_error(
+                "Cannot grow memory: no memory has been allocated",
+            ));
+        }
+
+        if additional_pages == 0 {
+            return Ok(());
+        }
+
+        if pages_to_bytes(current_pages)? != self.current_committed_bytes {
+            return Err(Error::memory_error(
+                "Current page count does not match internal state",
+            ));
+        }
+
+        let new_total_pages = current_pages
+            .checked_add(additional_pages)
+            .ok_or_else(|| Error::memory_error("Page count overflow during grow"))?;
+        let new_committed_bytes = pages_to_bytes(new_total_pages)?;
+        if new_committed_bytes > self.total_reserved_bytes {
+            return Err(Error::memory_error(
+                "Grow request exceeds total reserved memory space",
+            ));
+        }
+
+        // The whole reservation is mapped read/write, pages beyond the image
+        // are anonymous and zero-filled
+        self.current_committed_bytes = new_committed_bytes;
+        Ok(())
+    }
+
+    unsafe fn deallocate(&mut self, ptr: NonNull<u8>, size: usize) -> Result<()> {
+        let Some(base_ptr) = self.base_ptr.take() else {
+            return Err(Error::memory_error("No memory allocated to deallocate"));
+        };
+
+        if ptr.as_ptr() != base_ptr.as_ptr() {
+            self.base_ptr = Some(base_ptr);
+            return Err(Error::memory_error(
+                "Attempted to deallocate with mismatched pointer",
+            ));
+        }
+
+        // SAFETY: ptr was obtained from our mmap call and `size` is the size
+        // we reserved; private copies of image pages are released with it.
+        if Self::munmap(ptr.as_ptr(), size) != 0 {
+            self.base_ptr = Some(base_ptr);
+            return Err(Error::runtime_execution_error(
+                "Memory unmapping failed due to OS error",
+            ));
+        }
+
+        self.total_reserved_bytes = 0;
+        self.current_committed_bytes = 0;
+        Ok(())
+    }
+}
+
+impl Drop for LinuxCowAllocator {
+    fn drop(&mut self) {
+        // SAFETY: the descriptor is owned by this allocator and closed only
+        // here. A mapping that was not deallocated keeps the file alive.
+        unsafe {
+            syscall(syscalls::CLOSE, [self.fd as usize, 0, 0, 0, 0, 0]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory<'a>(base: NonNull<u8>, bytes: usize) -> &'a mut [u8] {
+        // SAFETY: the test keeps the mapping alive while using the slice.
+        unsafe { core::slice::from_raw_parts_mut(base.as_ptr(), bytes) }
+    }
+
+    #[test]
+    fn test_instances_share_image_until_written() {
+        let mut image = CowMemoryImage::new().unwrap();
+        image.write(16, b"hello").unwrap();
+        image.write(WASM_PAGE_SIZE + 1, &[7; 3]).unwrap();
+        assert_eq!(image.pages(), 2);
+
+        let mut first = image.allocator(None).unwrap();
+        let mut second = image.allocator(None).unwrap();
+        drop(image);
+
+        let (a, a_bytes) = first.allocate(3, Some(4)).unwrap();
+        let (b, b_bytes) = second.allocate(2, None).unwrap();
+        let a_mem = memory(a, a_bytes);
+        let b_mem = memory(b, b_bytes);
+
+        assert_eq!(&a_mem[16..21], b"hello");
+        assert_eq!(&b_mem[WASM_PAGE_SIZE..WASM_PAGE_SIZE + 4], &[0, 7, 7, 7]);
+        assert_eq!(a_mem[0], 0);
+        assert_eq!(a_mem[2 * WASM_PAGE_SIZE], 0);
+
+        a_mem[16] = b'j';
+        assert_eq!(&a_mem[16..21], b"jello");
+        assert_eq!(&b_mem[16..21], b"hello");
+
+        first.grow(3, 1).unwrap();
+        assert!(first.grow(4, 1).is_err());
+
+        unsafe {
+            first.deallocate(a, 4 * WASM_PAGE_SIZE).unwrap();
+            second.deallocate(b, b_bytes).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_image_must_fit_initial_memory() {
+        let mut image = CowMemoryImage::new().unwrap();
+        image.write(WASM_PAGE_SIZE, &[1]).unwrap();
+        let mut allocator = image.allocator(None).unwrap();
+        assert!(allocator.allocate(1, None).is_err());
+        assert!(image.write(usize::MAX, &[1]).is_err());
+    }
+}
@@ -0,0 +1,659 @@
+#![allow(unsafe_code)]
+// WRT - wrt-platform
+// Module: Linux Guard-Page Bounds Checked Linear Memory
+// SW-REQ-ID: REQ_PLATFORM_001, REQ_MEMORY_001
+//
+// Copyright (c) 2025 The WRT Project Developers
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+//! Linear memory bounds checked by guard pages on Linux x86_64.
+//!
+//! A [`GuardedLinearMemory`] reserves address space for the largest 32-bit
+//! memory followed by a guard region of the same size, and makes only the
+//! current pages of the memory accessible. Every effective address of a
+//! 32-bit access, `address + offset`, therefore lies within the
+//! reservation, and an access beyond the current size hits an inaccessible
+//! page instead of unrelated memory, so loads and stores need no explicit
+//! bounds check.
+//!
+//! Accesses go through small assembly routines. A `SIGSEGV` handler,
+//! installed when the first guarded memory is created, recognizes a fault
+//! raised by one of these routines and resumes execution at a landing pad
+//! that makes the routine report the fault, which surfaces as an
+//! out-of-bounds error. Faults anywhere else are passed on to the handler
+//! that was installed before.
+
+use core::{
+    ptr::{
+        self,
+        NonNull,
+    },
+    sync::atomic::{
+        AtomicU8,
+        Ordering,
+    },
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+
+use crate::memory::WASM_PAGE_SIZE;
+
+/// Linux syscall numbers for x86_64
+mod syscalls {
+    pub const MMAP: usize = 9;
+    pub const MPROTECT: usize = 10;
+    pub const MUNMAP: usize = 11;
+    pub const RT_SIGACTION: usize = 13;
+    pub const RT_SIGRETURN: usize = 15;
+}
+
+/// Protection flags for memory mapping
+const PROT_NONE: usize = 0x0;
+const PROT_READ: usize = 0x1;
+const PROT_WRITE: usize = 0x2;
+
+/// Mapping flags
+const MAP_PRIVATE: usize = 0x02;
+const MAP_ANONYMOUS: usize = 0x20;
+const MAP_NORESERVE: usize = 0x4000;
+
+/// Signal handling
+const SIGSEGV: usize = 11;
+const SA_SIGINFO: u64 = 0x4;
+const SA_ONSTACK: u64 = 0x0800_0000;
+const SA_RESTORER: u64 = 0x0400_0000;
+const SA_NODEFER: u64 = 0x4000_0000;
+const SIG_DFL: usize = 0;
+const SIG_IGN: usize = 1;
+
+/// Offset of the saved `rip` in the kernel's `ucontext`
+const UCONTEXT_RIP: usize = 168;
+
+/// Largest number of pages of a 32-bit memory
+const MAX_PAGES: u32 = 65536;
+
+/// Bytes addressable by a 32-bit memory, and size of the guard region
+/// behind it, which covers every `address + offset` of 32-bit values
+const ADDRESSABLE_BYTES: usize = 1 << 32;
+
+/// Size of the reservation of each memory
+const RESERVATION_BYTES: usize = 2 * ADDRESSABLE_BYTES;
+
+// Access routines. Each loads or stores through the pointer in `rdi`; the
+// instruction that may fault is labelled `*_pc`. Loads return the value in
+// `rax` and a fault flag in `rdx`, stores return the fault flag in `rax`.
+core::arch::global_asm!(
+    ".pushsection .text.wrt_guarded_memory,\"ax\",@progbits",
+    ".p2align 4",
+    "wrt_guarded_load8:",
+    "xor edx, edx",
+    "wrt_guarded_load8_pc:",
+    "movzx eax, byte ptr [rdi]",
+    "ret",
+    "wrt_guarded_load16:",
+    "xor edx, edx",
+    "wrt_guarded_load16_pc:",
+    "movzx eax, word ptr [rdi]",
+    "ret",
+    "wrt_guarded_load32:",
+    "xor edx, edx",
+    "wrt_guarded_load32_pc:",
+    "mov eax, dword ptr [rdi]",
+    "ret",
+    "wrt_guarded_load64:",
+    "xor edx, edx",
+    "wrt_guarded_load64_pc:",
+    "mov rax, qword ptr [rdi]",
+    "ret",
+    "wrt_guarded_load_fault:",
+    "xor eax, eax",
+    "mov edx, 1",
+    "ret",
+    "wrt_guarded_store8:",
+    "wrt_guarded_store8_pc:",
+    "mov byte ptr [rdi], sil",
+    "xor eax, eax",
+    "ret",
+    "wrt_guarded_store16:",
+    "wrt_guarded_store16_pc:",
+    "mov word ptr [rdi], si",
+    "xor eax, eax",
+    "ret",
+    "wrt_guarded_store32:",
+    "wrt_guarded_store32_pc:",
+    "mov dword ptr [rdi], esi",
+    "xor eax, eax",
+    "ret",
+    "wrt_guarded_store64:",
+    "wrt_guarded_store64_pc:",
+    "mov qword ptr [rdi], rsi",
+    "xor eax, eax",
+    "ret",
+    "wrt_guarded_store_fault:",
+    "mov eax, 1",
+    "ret",
+    "wrt_guarded_sigreturn:",
+    "mov eax, {sigreturn}",
+    "syscall",
+    ".popsection",
+    sigreturn = const syscalls::RT_SIGRETURN,
+);
+
+/// Result of a guarded load, returned in `rax` and `rdx`
+#[repr(C)]
+struct Loaded {
+    value: u64,
+    fault: u64,
+}
+
+extern "C" {
+    fn wrt_guarded_load8(ptr: *const u8) -> Loaded;
+    fn wrt_guarded_load16(ptr: *const u8) -> Loaded;
+    fn wrt_guarded_load32(ptr: *const u8) -> Loaded;
+    fn wrt_guarded_load64(ptr: *const u8) -> Loaded;
+    fn wrt_guarded_store8(ptr: *mut u8, value: u64) -> u64;
+    fn wrt_guarded_store16(ptr: *mut u8, value: u64) -> u64;
+    fn wrt_guarded_store32(ptr: *mut u8, value: u64) -> u64;
+    fn wrt_guarded_store64(ptr: *mut u8, value: u64) -> u64;
+
+    static wrt_guarded_load8_pc: u8;
+    static wrt_guarded_load16_pc: u8;
+    static wrt_guarded_load32_pc: u8;
+    static wrt_guarded_load64_pc: u8;
+    static wrt_guarded_load_fault: u8;
+    static wrt_guarded_store8_pc: u8;
+    static wrt_guarded_store16_pc: u8;
+    static wrt_guarded_store32_pc: u8;
+    static wrt_guarded_store64_pc: u8;
+    static wrt_guarded_store_fault: u8;
+    static wrt_guarded_sigreturn: u8;
+}
+
+/// Kernel `sigaction` structure for `rt_sigaction` on x86_64
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct SigAction {
+    handler:  usize,
+    flags:    u64,
+    restorer: usize,
+    mask:     u64,
+}
+
+/// Handler that was installed before the guard fault handler
+static mut PREVIOUS_ACTION: SigAction = SigAction {
+    handler:  SIG_DFL,
+    flags:    0,
+    restorer: 0,
+    mask:     0,
+};
+
+/// Installation state of the guard fault handler
+static HANDLER_STATE: AtomicU8 = AtomicU8::new(HANDLER_UNINSTALLED);
+const HANDLER_UNINSTALLED: u8 = 0;
+const HANDLER_INSTALLING: u8 = 1;
+const HANDLER_INSTALLED: u8 = 2;
+
+/// Performs a syscall directly without libc
+unsafe fn syscall(nr: usize, args: [usize; 6]) -> isize {
+    let result: isize;
+    core::arch::asm!(
+        "syscall",
+        inout("rax") nr as isize => result,
+        in("rdi") args[0],
+        in("rsi") args[1],
+        in("rdx") args[2],
+        in("r10") args[3],
+        in("r8") args[4],
+        in("r9") args[5],
+        out("rcx") _,
+        out("r11") _,
+    );
+    result
+}
+
+/// Whether a raw syscall result is an error
+fn failed(result: isize) -> bool {
+    (-4095..0).contains(&result)
+}
+
+/// Landing pad for a fault at `pc`, if `pc` is a guarded access
+// Taking the address of an extern static needs `unsafe` before Rust 1.82
+#[allow(unused_unsafe)]
+fn landing_pad(pc: usize) -> Option<usize> {
+    // SAFETY: only the addresses of the assembly labels are taken.
+    unsafe {
+        let loads = [
+            ptr::addr_of!(wrt_guarded_load8_pc),
+            ptr::addr_of!(wrt_guarded_load16_pc),
+            ptr::addr_of!(wrt_guarded_load32_pc),
+            ptr::addr_of!(wrt_guarded_load64_pc),
+        ];
+        let stores = [
+            ptr::addr_of!(wrt_guarded_store8_pc),
+            ptr::addr_of!(wrt_guarded_store16_pc),
+            ptr::addr_of!(wrt_guarded_store32_pc),
+            ptr::addr_of!(wrt_guarded_store64_pc),
+        ];
+        if loads.iter().any(|label| *label as usize == pc) {
+            Some(ptr::addr_of!(wrt_guarded_load_fault) as usize)
+        } else if stores.iter().any(|label| *label as usize == pc) {
+            Some(ptr::addr_of!(wrt_guarded_store_fault) as usize)
+        } else {
+            None
+        }
+    }
+}
+
+/// `SIGSEGV` handler resuming guarded accesses at their landing pad
+unsafe extern "C" fn handle_guard_fault(signal: i32, info: *mut u8, context: *mut u8) {
+    let rip = context.add(UCONTEXT_RIP).cast::<usize>();
+    if let Some(pad) = landing_pad(rip.read_unaligned()) {
+        rip.write_unaligned(pad);
+        return;
+    }
+
+    let previous = ptr::addr_of!(PREVIOUS_ACTION).read();
+    match previous.handler {
+        SIG_DFL | SIG_IGN => {
+            // Restore the default action; returning re-executes the faulting
+            // instruction, which then terminates the process as before
+            let default = SigAction {
+                handler: SIG_DFL,
+                ..previous
+            };
+            syscall(
+                syscalls::RT_SIGACTION,
+                [SIGSEGV, ptr::addr_of!(default) as usize, 0, 8, 0, 0],
+            );
+        },
+        handler if previous.flags & SA_SIGINFO != 0 => {
+            let handler: unsafe extern "C" fn(i32, *mut u8, *mut u8) =
+                core::mem::transmute(handler);
+            handler(signal, info, context);
+        },
+        handler => {
+            let handler: unsafe extern "C" fn(i32) = core::mem::transmute(handler);
+            handler(signal);
+        },
+    }
+}
+
+/// Install the guard fault handler once per process
+#[allow(unused_unsafe)]
+fn install_fault_handler() -> Result<()> {
+    loop {
+        match HANDLER_STATE.compare_exchange(
+            HANDLER_UNINSTALLED,
+            HANDLER_INSTALLING,
+            Ordering::Acquire,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => break,
+            Err(HANDLER_INSTALLED) => return Ok(()),
+            Err(_) => core::hint::spin_loop(),
+        }
+    }
+
+    let action = SigAction {
+        handler:  handle_guard_fault as *const () as usize,
+        flags:    SA_SIGINFO | SA_ONSTACK | SA_RESTORER | SA_NODEFER,
+        // SAFETY: only the address of the label is taken.
+        restorer: unsafe { ptr::addr_of!(wrt_guarded_sigreturn) as usize },
+        mask:     0,
+    };
+    // SAFETY: PREVIOUS_ACTION is written only here, before the handler that
+    // reads it is installed, and both structures match the kernel layout.
+    let result = unsafe {
+        syscall(
+            syscalls::RT_SIGACTION,
+            [
+                SIGSEGV,
+                ptr::addr_of!(action) as usize,
+                ptr::addr_of_mut!(PREVIOUS_ACTION) as usize,
+                8,
+                0,
+                0,
+            ],
+        )
+    };
+    if failed(result) {
+        HANDLER_STATE.store(HANDLER_UNINSTALLED, Ordering::Release);
+        return Err(Error::runtime_execution_error(
+            "Failed to install guard page fault handler",
+        ));
+    }
+    HANDLER_STATE.store(HANDLER_INSTALLED, Ordering::Release);
+    Ok(())
+}
+
+fn out_of_bounds() -> Error {
+    Error::memory_out_of_bounds("out of bounds memory access")
+}
+
+/// A 32-bit linear memory whose bounds are enforced by guard pages
+#[derive(Debug)]
+pub struct GuardedLinearMemory {
+    base:      NonNull<u8>,
+    pages:     u32,
+    max_pages: u32,
+}
+
+// Safety: the memory is only accessed through raw pointers to the
+// reservation owned by this value
+unsafe impl Send for GuardedLinearMemory {}
+unsafe impl Sync for GuardedLinearMemory {}
+
+impl GuardedLinearMemory {
+    /// Reserves a guarded memory of `initial_pages` pages that can grow to
+    /// `maximum_pages`, or to the 32-bit limit without a maximum.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the page counts exceed the 32-bit limit, or if
+    /// the address space cannot be reserved or the fault handler cannot be
+    /// installed.
+    pub fn new(initial_pages: u32, maximum_pages: Option<u32>) -> Result<Self> {
+        let max_pages = maximum_pages.unwrap_or(MAX_PAGES);
+        if initial_pages > max_pages || max_pages > MAX_PAGES {
+            return Err(Error::memory_error(
+                "Guarded memory exceeds the 32-bit page limit",
+            ));
+        }
+        install_fault_handler()?;
+
+        // SAFETY: an inaccessible anonymous mapping of a fresh address range.
+        let ptr = unsafe {
+            syscall(
+                syscalls::MMAP,
+                [
+                    0,
+                    RESERVATION_BYTES,
+                    PROT_NONE,
+                    MAP_PRIVATE | MAP_ANONYMOUS | MAP_NORESERVE,
+                    usize::MAX,
+                    0,
+                ],
+            )
+        };
+        if failed(ptr) {
+            return Err(Error::runtime_execution_error(
+                "Failed to reserve guarded memory",
+            ));
+        }
+        let base = NonNull::new(ptr as *mut u8)
+            .ok_or_else(|| Error::runtime_execution_error("mmap returned null pointer"))?;
+
+        let mut memory = Self {
+            base,
+            pages: 0,
+            max_pages,
+        };
+        memory.grow(initial_pages)?;
+        Ok(memory)
+    }
+
+    /// Current size in pages
+    pub fn pages(&self) -> u32 {
+        self.pages
+    }
+
+    /// Current size in bytes
+    pub fn size_in_bytes(&self) -> usize {
+        self.pages as usize * WASM_PAGE_SIZE
+    }
+
+    /// Makes `delta` more pages accessible and returns the previous size in
+    /// pages.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the memory would exceed its maximum or the pages
+    /// cannot be made accessible.
+    pub fn grow(&mut self, delta: u32) -> Result<u32> {
+        let old_pages = self.pages;
+        let new_pages = old_pages
+            .checked_add(delta)
+            .filter(|pages| *pages <= self.max_pages)
+            .ok_or_else(|| Error::memory_error("Guarded memory grow exceeds maximum"))?;
+        if delta > 0 {
+            // SAFETY: the range lies within the reservation owned by self.
+            let result = unsafe {
+                syscall(
+                    syscalls::MPROTECT,
+                    [
+                        self.base.as_ptr() as usize + self.size_in_bytes(),
+                        delta as usize * WASM_PAGE_SIZE,
+                        PROT_READ | PROT_WRITE,
+                        0,
+                        0,
+                        0,
+                    ],
+                )
+            };
+            if failed(result) {
+                return Err(Error::runtime_execution_error(
+                    "Failed to commit guarded memory pages",
+                ));
+            }
+        }
+        self.pages = new_pages;
+        Ok(old_pages)
+    }
+
+    /// Address of `address + offset` within the reservation
+    fn effective(&self, address: u32, offset: u32) -> *mut u8 {
+        // Stays within the reservation: both operands are below 2^32
+        self.base.as_ptr().wrapping_add(address as usize + offset as usize)
+    }
+
+    fn load(
+        &self,
+        address: u32,
+        offset: u32,
+        access: unsafe extern "C" fn(*const u8) -> Loaded,
+    ) -> Result<u64> {
+        // SAFETY: the address lies within the reservation; inaccessible pages
+        // fault into the landing pad of the routine.
+        let loaded = unsafe { access(self.effective(address, offset)) };
+        if loaded.fault != 0 {
+            return Err(out_of_bounds());
+        }
+        Ok(loaded.value)
+    }
+
+    fn store(
+        &mut self,
+        address: u32,
+        offset: u32,
+        value: u64,
+        access: unsafe extern "C" fn(*mut u8, u64) -> u64,
+    ) -> Result<()> {
+        // SAFETY: the address lies within the reservation; inaccessible pages
+        // fault into the landing pad of the routine.
+        if unsafe { access(self.effective(address, offset), value) } != 0 {
+            return Err(out_of_bounds());
+        }
+        Ok(())
+    }
+
+    /// Loads the byte at `address + offset`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the byte lies beyond the memory.
+    pub fn load_u8(&self, address: u32, offset: u32) -> Result<u8> {
+        Ok(self.load(address, offset, wrt_guarded_load8)? as u8)
+    }
+
+    /// Loads the little-endian `u16` at `address + offset`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value extends beyond the memory.
+    pub fn load_u16(&self, address: u32, offset: u32) -> Result<u16> {
+        Ok(self.load(address, offset, wrt_guarded_load16)? as u16)
+    }
+
+    /// Loads the little-endian `u32` at `address + offset`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value extends beyond the memory.
+    pub fn load_u32(&self, address: u32, offset: u32) -> Result<u32> {
+        Ok(self.load(address, offset, wrt_guarded_load32)? as u32)
+    }
+
+    /// Loads the little-endian `u64` at `address + offset`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value extends beyond the memory.
+    pub fn load_u64(&self, address: u32, offset: u32) -> Result<u64> {
+        self.load(address, offset, wrt_guarded_load64)
+    }
+
+    /// Stores `value` at `address + offset`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the byte lies beyond the memory.
+    pub fn store_u8(&mut self, address: u32, offset: u32, value: u8) -> Result<()> {
+        self.store(address, offset, value.into(), wrt_guarded_store8)
+    }
+
+    /// Stores `value` little-endian at `address + offset`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value extends beyond the memory; a value
+    /// straddling the end of the memory is not written.
+    pub fn store_u16(&mut self, address: u32, offset: u32, value: u16) -> Result<()> {
+        self.store(address, offset, value.into(), wrt_guarded_store16)
+    }
+
+    /// Stores `value` little-endian at `address + offset`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value extends beyond the memory; a value
+    /// straddling the end of the memory is not written.
+    pub fn store_u32(&mut self, address: u32, offset: u32, value: u32) -> Result<()> {
+        self.store(address, offset, value.into(), wrt_guarded_store32)
+    }
+
+    /// Stores `value` little-endian at `address + offset`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value extends beyond the memory; a value
+    /// straddling the end of the memory is not written.
+    pub fn store_u64(&mut self, address: u32, offset: u32, value: u64) -> Result<()> {
+        self.store(address, offset, value, wrt_guarded_store64)
+    }
+}
+
+impl Drop for GuardedLinearMemory {
+    fn drop(&mut self) {
+        // SAFETY: the reservation is owned by self and no longer accessed.
+        unsafe {
+            syscall(
+                syscalls::MUNMAP,
+                [self.base.as_ptr() as usize, RESERVATION_BYTES, 0, 0, 0, 0],
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guarded_access() {
+        let mut memory = GuardedLinearMemory::new(1, Some(2)).unwrap();
+        memory.store_u32(8, 4, 0xDEAD_BEEF).unwrap();
+        assert_eq!(memory.load_u32(12, 0).unwrap(), 0xDEAD_BEEF);
+        assert_eq!(memory.load_u16(12, 0).unwrap(), 0xBEEF);
+        assert_eq!(memory.load_u8(15, 0).unwrap(), 0xDE);
+
+        let end = memory.size_in_bytes() as u32;
+        assert_eq!(memory.load_u64(end - 8, 0).unwrap(), 0);
+        assert!(memory.load_u64(end - 4, 0).is_err());
+        assert!(memory.load_u8(end, 0).is_err());
+        assert!(memory.load_u32(u32::MAX, u32::MAX).is_err());
+        assert!(memory.store_u16(end - 1, 0, 0xFFFF).is_err());
+        assert_eq!(memory.load_u8(end - 1, 0).unwrap(), 0);
+
+        assert_eq!(memory.grow(1).unwrap(), 1);
+        memory.store_u64(end, 0, u64::MAX).unwrap();
+        assert_eq!(memory.load_u64(end, 0).unwrap(), u64::MAX);
+        assert!(memory.grow(1).is_err());
+        assert!(GuardedLinearMemory::new(2, Some(1)).is_err());
+    }
+
+    #[test]
+    fn test_out_of_bounds_access() {
+        let mut memory = GuardedLinearMemory::new(1, Some(1)).unwrap();
+        let end = memory.size_in_bytes() as u32;
+        memory.store_u64(end - 8, 0, 0x0102_0304_0506_0708).unwrap();
+
+        // Every access reaching past the end fails, whichever of its bytes
+        // do
+        for straddle in 1..8 {
+            let address = end - 8 + straddle;
+            assert!(memory.load_u64(address, 0).is_err());
+            assert!(memory.store_u64(address, 0, u64::MAX).is_err());
+        }
+        for straddle in 1..4 {
+            assert!(memory.load_u32(end - 4 + straddle, 0).is_err());
+            assert!(memory.store_u32(end - 4, straddle, u32::MAX).is_err());
+        }
+        assert!(memory.load_u16(end - 1, 0).is_err());
+        assert!(memory.store_u8(end, 0, 0xFF).is_err());
+
+        // Failed stores leave the memory as it was
+        assert_eq!(memory.load_u64(end - 8, 0).unwrap(), 0x0102_0304_0506_0708);
+
+        // The offset is added without wrapping, past the 32-bit range
+        assert!(memory.load_u8(0, u32::MAX).is_err());
+        assert!(memory.load_u64(u32::MAX - 3, 4).is_err());
+        assert!(memory.store_u32(u32::MAX, 1, 0).is_err());
+
+        let error = memory.load_u32(end, 0).unwrap_err();
+        assert_eq!(error.category, wrt_error::ErrorCategory::Memory);
+        assert_eq!(error.code, wrt_error::codes::MEMORY_OUT_OF_BOUNDS);
+    }
+
+    #[test]
+    fn test_guard_hits() {
+        // Pages up to the maximum are reserved but stay inaccessible until
+        // the memory grows over them
+        let mut memory = GuardedLinearMemory::new(1, Some(4)).unwrap();
+        let page = WASM_PAGE_SIZE as u32;
+        assert!(memory.load_u8(2 * page, 0).is_err());
+        assert!(memory.store_u32(3 * page, 16, 1).is_err());
+
+        assert_eq!(memory.grow(2).unwrap(), 1);
+        assert_eq!(memory.load_u8(2 * page, 0).unwrap(), 0);
+        memory.store_u32(2 * page, 16, 7).unwrap();
+        assert_eq!(memory.load_u32(2 * page, 16).unwrap(), 7);
+        assert!(memory.store_u32(3 * page, 16, 1).is_err());
+
+        // Guard hits keep being recovered from, by every memory, including
+        // ones without a maximum and ones created after another was dropped
+        drop(memory);
+        let mut unbounded = GuardedLinearMemory::new(0, None).unwrap();
+        for address in [0, page, u32::MAX - 7] {
+            assert!(unbounded.load_u64(address, 0).is_err());
+            assert!(unbounded.store_u64(address, 0, 1).is_err());
+        }
+        assert_eq!(unbounded.grow(1).unwrap(), 0);
+        unbounded.store_u8(page - 1, 0, 9).unwrap();
+        assert_eq!(unbounded.load_u8(page - 1, 0).unwrap(), 9);
+        assert!(unbounded.load_u8(page, 0).is_err());
+    }
+}
@@ -201,7 +201,7 @@ unsafe fn mprotect(addr: *mut u8, len: usize, prot: usize) -> i32 {
     /// Binary std/no_std choice
     unsafe fn setup_guard_pages(&self, base_ptr: *mut u8, total_size: usize) -> Result<()> {
         if !self.use_guard_pages {
-            return Ok();
+            return Ok(());
         }
 
         // Binary std/no_std choice
@@ -339,7 +339,7 @@ fn grow(&mut self, current_pages: u32, additional_pages: u32) -> Result<()> {
         };
 
         if additional_pages == 0 {
-            return Ok();
+            return Ok(());
         }
 
         let current_bytes_from_arg = Self::pages_to_bytes(current_pages)?;
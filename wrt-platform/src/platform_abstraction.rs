@@ -169,15 +169,15 @@ impl PlatformAbstraction<paradigm::Posix> for UnifiedPlatform<paradigm::Posix> {
 
         #[inline(always)] // Zero-cost: compiles to direct constructor call
         fn create_allocator(config: &Self::Config) -> Result<Self::Allocator, Error> {
-            crate::LinuxAllocatorBuilder::new()
+            Ok(crate::LinuxAllocatorBuilder::new()
                 .with_maximum_pages(config.max_pages)
                 .with_guard_pages(config.guard_pages)
-                .build()
+                .build())
         }
 
         #[inline(always)] // Zero-cost: compiles to direct constructor call
         fn create_synchronizer(_config: &Self::Config) -> Result<Self::Synchronizer, Error> {
-            crate::LinuxFutexBuilder::new().build()
+            Ok(crate::LinuxFutexBuilder::new().build())
         }
     }
 
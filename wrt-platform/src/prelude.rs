@@ -18,6 +18,20 @@
 };
 
 // Platform-specific re-exports based on features and targets
+#[cfg(all(
+    feature = "platform-linux",
+    target_os = "linux",
+    not(all(feature = "linux-mte", target_arch = "aarch64"))
+))]
+pub use crate::linux_memory::{
+    LinuxAllocator,
+    LinuxAllocatorBuilder,
+};
+#[cfg(all(feature = "platform-linux", target_os = "linux"))]
+pub use crate::linux_sync::{
+    LinuxFutex,
+    LinuxFutexBuilder,
+};
 #[cfg(all(feature = "platform-macos", target_os = "macos"))]
 pub use crate::macos_memory::{
     MacOsAllocator,
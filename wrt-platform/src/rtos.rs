@@ -0,0 +1,381 @@
+//! Generic RTOS integration layer
+//!
+//! Describes an RTOS target through a [`RtosDescriptor`], from which the
+//! validation limits of the runtime are derived, and binds the few kernel
+//! services the runtime needs through the [`RtosKernel`] trait: the tick
+//! counter, yielding and sleeping, and the identity of the running thread.
+//! [`RtosMutex`] builds a mutex on top of those services, so a new RTOS only
+//! has to implement the trait to get locking.
+//!
+//! Linear memories on RTOS targets are usually carved out of statically
+//! reserved RAM; see [`StaticPoolAllocator`](crate::StaticPoolAllocator).
+
+use core::{
+    cell::UnsafeCell,
+    fmt,
+    ops::{
+        Deref,
+        DerefMut,
+    },
+    sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    },
+    time::Duration,
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+
+use crate::comprehensive_limits::{
+    AsilLevel,
+    ComprehensiveLimitProvider,
+    ComprehensivePlatformLimits,
+    PlatformId,
+};
+
+/// Number of times a contended [`RtosMutex`] yields before it sleeps
+const YIELDS_BEFORE_SLEEP: u32 = 8;
+
+/// Resources of an RTOS target
+///
+/// Built by the integrator from the board and kernel configuration, for
+/// example the RAM size from the devicetree and the tick rate from
+/// `CONFIG_SYS_CLOCK_TICKS_PER_SEC` on Zephyr.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RtosDescriptor {
+    /// Platform the descriptor belongs to
+    pub platform_id:  PlatformId,
+    /// RAM available to the runtime in bytes
+    pub ram_bytes:    usize,
+    /// Part of the RAM reserved as static pool for linear memories
+    pub pool_bytes:   usize,
+    /// Stack size of the thread executing WebAssembly
+    pub stack_bytes:  usize,
+    /// Threads the runtime may use
+    pub max_threads:  usize,
+    /// Kernel ticks per second
+    pub tick_rate_hz: u32,
+    /// Safety integrity level the target is qualified for
+    pub asil_level:   AsilLevel,
+}
+
+impl RtosDescriptor {
+    /// Describe a target with `ram_bytes` of RAM and a kernel ticking
+    /// `tick_rate_hz` times per second
+    ///
+    /// Two thirds of the RAM become the memory pool and a sixteenth the
+    /// stack, matching the split of the generic embedded limits.
+    pub const fn new(platform_id: PlatformId, ram_bytes: usize, tick_rate_hz: u32) -> Self {
+        Self {
+            platform_id,
+            ram_bytes,
+            pool_bytes: ram_bytes / 3 * 2,
+            stack_bytes: ram_bytes / 16,
+            max_threads: 1,
+            tick_rate_hz,
+            asil_level: AsilLevel::QM,
+        }
+    }
+
+    /// Reserve `pool_bytes` for linear memories
+    pub const fn with_pool_bytes(mut self, pool_bytes: usize) -> Self {
+        self.pool_bytes = pool_bytes;
+        self
+    }
+
+    /// Run WebAssembly on stacks of `stack_bytes`
+    pub const fn with_stack_bytes(mut self, stack_bytes: usize) -> Self {
+        self.stack_bytes = stack_bytes;
+        self
+    }
+
+    /// Allow the runtime to use `max_threads` threads
+    pub const fn with_max_threads(mut self, max_threads: usize) -> Self {
+        self.max_threads = max_threads;
+        self
+    }
+
+    /// Qualify the target for `asil_level`
+    pub const fn with_asil_level(mut self, asil_level: AsilLevel) -> Self {
+        self.asil_level = asil_level;
+        self
+    }
+
+    /// Platform limits implied by the descriptor
+    ///
+    /// Every thread may instantiate one component, and the memory not taken
+    /// by the pool is what debug support may use.
+    pub fn limits(&self) -> ComprehensivePlatformLimits {
+        let pool_bytes = self.pool_bytes.min(self.ram_bytes);
+        ComprehensivePlatformLimits {
+            platform_id:            self.platform_id,
+            max_total_memory:       self.ram_bytes,
+            max_wasm_linear_memory: pool_bytes,
+            max_stack_bytes:        self.stack_bytes,
+            max_components:         self.max_threads.max(1),
+            max_debug_overhead:     self.ram_bytes - pool_bytes,
+            asil_level:             self.asil_level,
+        }
+    }
+}
+
+impl ComprehensiveLimitProvider for RtosDescriptor {
+    fn discover_limits(&self) -> Result<ComprehensivePlatformLimits> {
+        if self.ram_bytes == 0 || self.tick_rate_hz == 0 {
+            return Err(Error::validation_invalid_input(
+                "RTOS descriptor needs RAM and a tick rate",
+            ));
+        }
+        Ok(self.limits())
+    }
+
+    fn platform_id(&self) -> PlatformId {
+        self.platform_id
+    }
+}
+
+/// Kernel services the runtime uses on an RTOS
+///
+/// Implemented by a zero-sized type per kernel, such as
+/// [`ZephyrKernel`](crate::ZephyrKernel), whose functions forward to the
+/// kernel API.
+pub trait RtosKernel: Send + Sync + 'static {
+    /// Ticks since boot
+    fn uptime_ticks() -> u64;
+
+    /// Ticks per second
+    fn tick_rate_hz() -> u32;
+
+    /// Let other ready threads of the same priority run
+    fn yield_now();
+
+    /// Suspend the calling thread for at least `ticks` ticks
+    fn sleep_ticks(ticks: u64);
+
+    /// Identifier of the calling thread, never zero
+    fn current_thread() -> usize;
+
+    /// Nanoseconds since boot at tick resolution
+    fn monotonic_ns() -> u64 {
+        let nanos = u128::from(Self::uptime_ticks()) * 1_000_000_000
+            / u128::from(Self::tick_rate_hz().max(1));
+        u64::try_from(nanos).unwrap_or(u64::MAX)
+    }
+
+    /// Suspend the calling thread for at least `duration`, rounded up to
+    /// whole ticks
+    fn sleep(duration: Duration) {
+        let rate = u128::from(Self::tick_rate_hz().max(1));
+        let ticks = (duration.as_nanos() * rate).div_ceil(1_000_000_000);
+        Self::sleep_ticks(u64::try_from(ticks).unwrap_or(u64::MAX));
+    }
+}
+
+/// Mutex built on the services of an [`RtosKernel`]
+///
+/// A contended lock yields a few times and then sleeps a tick at a time, so
+/// a holder of lower priority gets to run and release it. Locking a mutex
+/// the calling thread already holds fails instead of deadlocking.
+pub struct RtosMutex<K: RtosKernel, T> {
+    owner:   AtomicUsize,
+    data:    UnsafeCell<T>,
+    _kernel: core::marker::PhantomData<K>,
+}
+
+// SAFETY: access to `data` is serialized through `owner`
+unsafe impl<K: RtosKernel, T: Send> Send for RtosMutex<K, T> {}
+// SAFETY: access to `data` is serialized through `owner`
+unsafe impl<K: RtosKernel, T: Send> Sync for RtosMutex<K, T> {}
+
+impl<K: RtosKernel, T> RtosMutex<K, T> {
+    /// Create an unlocked mutex protecting `data`
+    pub const fn new(data: T) -> Self {
+        Self {
+            owner:   AtomicUsize::new(0),
+            data:    UnsafeCell::new(data),
+            _kernel: core::marker::PhantomData,
+        }
+    }
+
+    /// Take the lock if no thread holds it
+    pub fn try_lock(&self) -> Option<RtosMutexGuard<'_, K, T>> {
+        self.owner
+            .compare_exchange(0, K::current_thread(), Ordering::Acquire, Ordering::Relaxed)
+            .ok()
+            .map(|_| RtosMutexGuard { mutex: self })
+    }
+
+    /// Take the lock, waiting until the holder releases it
+    pub fn lock(&self) -> Result<RtosMutexGuard<'_, K, T>> {
+        let thread = K::current_thread();
+        let mut attempts = 0u32;
+        loop {
+            match self
+                .owner
+                .compare_exchange_weak(0, thread, Ordering::Acquire, Ordering::Relaxed)
+            {
+                Ok(_) => return Ok(RtosMutexGuard { mutex: self }),
+                Err(owner) if owner == thread => {
+                    return Err(Error::runtime_invalid_state(
+                        "RTOS mutex is already held by the calling thread",
+                    ))
+                },
+                Err(0) => {},
+                Err(_) if attempts < YIELDS_BEFORE_SLEEP => {
+                    attempts += 1;
+                    K::yield_now();
+                },
+                Err(_) => K::sleep_ticks(1),
+            }
+        }
+    }
+
+    /// Consume the mutex and return the protected data
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+impl<K: RtosKernel, T> fmt::Debug for RtosMutex<K, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RtosMutex")
+            .field("owner", &self.owner.load(Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+/// Lock on an [`RtosMutex`], released when dropped
+pub struct RtosMutexGuard<'a, K: RtosKernel, T> {
+    mutex: &'a RtosMutex<K, T>,
+}
+
+impl<K: RtosKernel, T> Deref for RtosMutexGuard<'_, K, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: the guard proves the calling thread holds the lock
+        unsafe { &*self.mutex.data.get() }
+    }
+}
+
+impl<K: RtosKernel, T> DerefMut for RtosMutexGuard<'_, K, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: the guard proves the calling thread holds the lock
+        unsafe { &mut *self.mutex.data.get() }
+    }
+}
+
+impl<K: RtosKernel, T> Drop for RtosMutexGuard<'_, K, T> {
+    fn drop(&mut self) {
+        self.mutex.owner.store(0, Ordering::Release);
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    extern crate alloc;
+
+    use alloc::{
+        sync::Arc,
+        vec::Vec,
+    };
+    use std::{
+        thread,
+        time::Instant,
+    };
+
+    use super::*;
+
+    /// Kernel backed by the host threads, ticking every millisecond
+    struct HostKernel;
+
+    std::thread_local! {
+        static THREAD_ID: usize = {
+            static NEXT: AtomicUsize = AtomicUsize::new(1);
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        };
+    }
+
+    impl RtosKernel for HostKernel {
+        fn uptime_ticks() -> u64 {
+            std::thread_local!(static START: Instant = Instant::now());
+            START.with(|start| start.elapsed().as_millis() as u64)
+        }
+
+        fn tick_rate_hz() -> u32 {
+            1_000
+        }
+
+        fn yield_now() {
+            thread::yield_now();
+        }
+
+        fn sleep_ticks(ticks: u64) {
+            thread::sleep(Duration::from_millis(ticks));
+        }
+
+        fn current_thread() -> usize {
+            THREAD_ID.with(|id| *id)
+        }
+    }
+
+    #[test]
+    fn test_descriptor_limits() {
+        let descriptor = RtosDescriptor::new(PlatformId::Zephyr, 192 * 1024, 10_000)
+            .with_pool_bytes(128 * 1024)
+            .with_stack_bytes(8 * 1024)
+            .with_max_threads(2)
+            .with_asil_level(AsilLevel::AsilB);
+
+        let limits = descriptor.discover_limits().unwrap();
+        assert_eq!(limits.platform_id, PlatformId::Zephyr);
+        assert_eq!(limits.max_total_memory, 192 * 1024);
+        assert_eq!(limits.max_wasm_linear_memory, 128 * 1024);
+        assert_eq!(limits.max_stack_bytes, 8 * 1024);
+        assert_eq!(limits.max_components, 2);
+        assert_eq!(limits.max_debug_overhead, 64 * 1024);
+        assert_eq!(limits.asil_level, AsilLevel::AsilB);
+
+        let oversized = descriptor.with_pool_bytes(1024 * 1024).limits();
+        assert_eq!(oversized.max_wasm_linear_memory, 192 * 1024);
+        assert_eq!(oversized.max_debug_overhead, 0);
+
+        assert!(RtosDescriptor::new(PlatformId::Zephyr, 0, 100).discover_limits().is_err());
+    }
+
+    #[test]
+    fn test_kernel_time() {
+        assert_eq!(HostKernel::monotonic_ns() % 1_000_000, 0);
+        let before = HostKernel::uptime_ticks();
+        HostKernel::sleep(Duration::from_micros(1_500));
+        assert!(HostKernel::uptime_ticks() >= before + 2);
+    }
+
+    #[test]
+    fn test_mutex() {
+        let mutex = Arc::new(RtosMutex::<HostKernel, u32>::new(0));
+
+        let guard = mutex.lock().unwrap();
+        assert!(mutex.lock().is_err());
+        assert!(mutex.try_lock().is_none());
+        drop(guard);
+
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                let mutex = Arc::clone(&mutex);
+                thread::spawn(move || {
+                    for _ in 0..1_000 {
+                        *mutex.lock().unwrap() += 1;
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+        assert_eq!(*mutex.lock().unwrap(), 4_000);
+    }
+}
@@ -0,0 +1,179 @@
+//! `PageAllocator` over a statically reserved memory pool
+//!
+//! RTOS and bare-metal targets usually cannot map memory on demand, so the
+//! linear memory of a module lives in a region reserved at link time, such
+//! as a `static` array or a linker section. [`StaticPoolAllocator`] hands
+//! that region out as one linear memory at a time and zeroes the pages it
+//! commits, so a pool can be reused after the memory is deallocated.
+
+use core::ptr::NonNull;
+
+use wrt_error::{
+    Error,
+    Result,
+};
+
+use crate::memory::{
+    PageAllocator,
+    WASM_PAGE_SIZE,
+};
+
+/// Alignment of the start of the linear memory within the pool
+const POOL_ALIGNMENT: usize = 16;
+
+/// A `PageAllocator` backed by a fixed pool of memory
+#[derive(Debug)]
+pub struct StaticPoolAllocator {
+    base:            NonNull<u8>,
+    capacity_pages:  u32,
+    committed_bytes: usize,
+    allocated:       bool,
+}
+
+// SAFETY: the allocator has exclusive access to the pool it was created from
+unsafe impl Send for StaticPoolAllocator {}
+// SAFETY: shared references do not access the pool
+unsafe impl Sync for StaticPoolAllocator {}
+
+impl StaticPoolAllocator {
+    /// Create an allocator handing out `pool`
+    ///
+    /// The start of the pool is aligned to 16 bytes and its usable size is
+    /// rounded down to whole Wasm pages.
+    pub fn new(pool: &'static mut [u8]) -> Self {
+        let offset = pool.as_ptr().align_offset(POOL_ALIGNMENT).min(pool.len());
+        let usable = &mut pool[offset..];
+        let capacity_pages = u32::try_from(usable.len() / WASM_PAGE_SIZE).unwrap_or(u32::MAX);
+        Self {
+            base: NonNull::from(usable).cast(),
+            capacity_pages,
+            committed_bytes: 0,
+            allocated: false,
+        }
+    }
+
+    /// Number of Wasm pages the pool can hold
+    pub fn capacity_pages(&self) -> u32 {
+        self.capacity_pages
+    }
+
+    /// Zero `len` bytes of the pool starting at `offset`
+    fn zero(&mut self, offset: usize, len: usize) {
+        // SAFETY: callers stay within the capacity of the pool, which the
+        // allocator borrowed exclusively for `'static`
+        unsafe { core::ptr::write_bytes(self.base.as_ptr().add(offset), 0, len) };
+    }
+}
+
+impl PageAllocator for StaticPoolAllocator {
+    fn allocate(
+        &mut self,
+        initial_pages: u32,
+        _maximum_pages: Option<u32>,
+    ) -> Result<(NonNull<u8>, usize)> {
+        if self.allocated {
+            return Err(Error::runtime_invalid_state(
+                "Static memory pool is already in use",
+            ));
+        }
+        if initial_pages > self.capacity_pages {
+            return Err(Error::resource_exhausted(
+                "Initial memory exceeds the static memory pool",
+            ));
+        }
+
+        let initial_bytes = initial_pages as usize * WASM_PAGE_SIZE;
+        self.zero(0, initial_bytes);
+        self.allocated = true;
+        self.committed_bytes = initial_bytes;
+        Ok((self.base, initial_bytes))
+    }
+
+    fn grow(&mut self, current_pages: u32, additional_pages: u32) -> Result<()> {
+        if !self.allocated {
+            return Err(Error::runtime_invalid_state(
+                "Static memory pool is not allocated",
+            ));
+        }
+        if current_pages as usize * WASM_PAGE_SIZE != self.committed_bytes {
+            return Err(Error::memory_error(
+                "Current page count does not match internal state",
+            ));
+        }
+        let new_pages = current_pages
+            .checked_add(additional_pages)
+            .filter(|&pages| pages <= self.capacity_pages)
+            .ok_or(Error::resource_exhausted(
+                "Memory growth exceeds the static memory pool",
+            ))?;
+
+        let new_bytes = new_pages as usize * WASM_PAGE_SIZE;
+        self.zero(self.committed_bytes, new_bytes - self.committed_bytes);
+        self.committed_bytes = new_bytes;
+        Ok(())
+    }
+
+    unsafe fn deallocate(&mut self, ptr: NonNull<u8>, _size: usize) -> Result<()> {
+        if !self.allocated || ptr != self.base {
+            return Err(Error::memory_error(
+                "Attempted to deallocate with mismatched pointer",
+            ));
+        }
+        self.allocated = false;
+        self.committed_bytes = 0;
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    extern crate alloc;
+
+    use alloc::{
+        boxed::Box,
+        vec,
+    };
+
+    use super::*;
+
+    fn pool(pages: usize) -> &'static mut [u8] {
+        Box::leak(vec![0xA5; pages * WASM_PAGE_SIZE + POOL_ALIGNMENT].into_boxed_slice())
+    }
+
+    #[test]
+    fn test_allocate_and_grow() {
+        let mut allocator = StaticPoolAllocator::new(pool(3));
+        assert_eq!(allocator.capacity_pages(), 3);
+
+        let (ptr, size) = allocator.allocate(1, Some(3)).unwrap();
+        assert_eq!(size, WASM_PAGE_SIZE);
+        assert_eq!(ptr.as_ptr() as usize % POOL_ALIGNMENT, 0);
+        assert!(allocator.allocate(1, None).is_err());
+
+        allocator.grow(1, 2).unwrap();
+        assert!(allocator.grow(3, 1).is_err());
+        assert!(allocator.grow(2, 1).is_err());
+
+        // SAFETY: three pages were committed above
+        let memory = unsafe { core::slice::from_raw_parts(ptr.as_ptr(), 3 * WASM_PAGE_SIZE) };
+        assert!(memory.iter().all(|&byte| byte == 0));
+    }
+
+    #[test]
+    fn test_reuse_after_deallocate() {
+        let mut allocator = StaticPoolAllocator::new(pool(1));
+        assert!(allocator.allocate(2, None).is_err());
+
+        let (ptr, size) = allocator.allocate(1, None).unwrap();
+        // SAFETY: one page was committed above
+        unsafe { ptr.as_ptr().write(0xFF) };
+        // SAFETY: `ptr` and `size` come from the allocation above
+        unsafe { allocator.deallocate(ptr, size).unwrap() };
+        // SAFETY: the pool is no longer allocated
+        assert!(unsafe { allocator.deallocate(ptr, size) }.is_err());
+
+        let (ptr, _) = allocator.allocate(1, None).unwrap();
+        // SAFETY: one page was committed above
+        assert_eq!(unsafe { ptr.as_ptr().read() }, 0);
+    }
+}
@@ -0,0 +1,98 @@
+#![allow(unsafe_code)]
+// Allow unsafe FFI calls to Zephyr kernel
+// WRT - wrt-platform
+// Module: Zephyr Kernel Services
+// SW-REQ-ID: REQ_PLATFORM_001
+//
+// Copyright (c) 2025 The WRT Project Developers
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+//! Zephyr RTOS binding of the `RtosKernel` trait.
+//!
+//! Forwards the tick counter, yielding, sleeping and thread identity to the
+//! Zephyr kernel, which gives the runtime an [`RtosMutex`](crate::RtosMutex)
+//! and a tick based clock on Zephyr. The clock can be installed as the
+//! platform clock of the runtime with
+//! `TickClock::new(<ZephyrKernel as RtosKernel>::uptime_ticks, rate)`.
+
+use core::ffi::c_void;
+
+use crate::{
+    comprehensive_limits::PlatformId,
+    rtos::{
+        RtosDescriptor,
+        RtosKernel,
+    },
+};
+
+/// Zephyr `k_timeout_t` with 64-bit ticks
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct ZephyrTimeout {
+    ticks: i64,
+}
+
+// FFI declarations for Zephyr kernel APIs
+extern "C" {
+    /// Get the system uptime in ticks
+    fn k_uptime_ticks() -> i64;
+
+    /// Convert milliseconds to ticks
+    fn k_ms_to_ticks_ceil32(ms: u32) -> u32;
+
+    /// Yield the current thread
+    fn k_yield();
+
+    /// Put the current thread to sleep
+    fn k_sleep(timeout: ZephyrTimeout) -> i32;
+
+    /// Get the thread control block of the current thread
+    fn k_current_get() -> *mut c_void;
+}
+
+/// Zephyr kernel services
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ZephyrKernel;
+
+impl ZephyrKernel {
+    /// Describe a Zephyr target giving the runtime `ram_bytes` of RAM, ticking
+    /// at the configured `CONFIG_SYS_CLOCK_TICKS_PER_SEC`
+    pub fn descriptor(ram_bytes: usize) -> RtosDescriptor {
+        RtosDescriptor::new(PlatformId::Zephyr, ram_bytes, Self::tick_rate_hz())
+    }
+}
+
+impl RtosKernel for ZephyrKernel {
+    fn uptime_ticks() -> u64 {
+        // SAFETY: k_uptime_ticks has no preconditions
+        u64::try_from(unsafe { k_uptime_ticks() }).unwrap_or(0)
+    }
+
+    fn tick_rate_hz() -> u32 {
+        // SAFETY: k_ms_to_ticks_ceil32 is a pure conversion
+        unsafe { k_ms_to_ticks_ceil32(1_000) }
+    }
+
+    fn yield_now() {
+        // SAFETY: k_yield may be called from any thread context
+        unsafe { k_yield() }
+    }
+
+    fn sleep_ticks(ticks: u64) {
+        let timeout = ZephyrTimeout {
+            ticks: i64::try_from(ticks).unwrap_or(i64::MAX),
+        };
+        // SAFETY: k_sleep may be called from any thread context; waking up
+        // early only shortens a sleep the callers already retry
+        unsafe {
+            k_sleep(timeout);
+        }
+    }
+
+    fn current_thread() -> usize {
+        // SAFETY: k_current_get has no preconditions and never returns null
+        // in thread context
+        unsafe { k_current_get() as usize }
+    }
+}
@@ -0,0 +1,243 @@
+//! Engines, modules and instances
+
+use alloc::sync::Arc;
+
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{
+        PyBytes,
+        PyTuple,
+    },
+};
+use wrt_decoder::decoder::decode_module;
+use wrt_error::Error;
+use wrt_foundation::memory_init::MemoryInitializer;
+use wrt_intercept::LinkInterceptor;
+use wrt_runtime::{
+    engine::{
+        CapabilityAwareEngine,
+        CapabilityEngine,
+        EnginePreset,
+        InstanceHandle,
+        ModuleHandle,
+    },
+    externs::Extern,
+    module::MemoryWrapper,
+};
+
+use crate::{
+    interceptor::{
+        PendingError,
+        PyInterceptor,
+    },
+    to_py_err,
+    values,
+};
+
+/// Name under which `invoke` reports calls to interceptors as their target
+const GUEST: &str = "guest";
+
+/// An execution engine configured with a safety preset
+///
+/// `Engine(preset="qm")`, where the preset is one of `qm`, `asil-a`,
+/// `asil-b`, `asil-c` and `asil-d`.
+#[pyclass(module = "wrt_py")]
+pub struct Engine {
+    engine:      CapabilityAwareEngine,
+    interceptor: LinkInterceptor,
+    pending:     PendingError,
+}
+
+#[pymethods]
+impl Engine {
+    #[new]
+    #[pyo3(signature = (preset = "qm"))]
+    fn new(preset: &str) -> PyResult<Self> {
+        let preset = match preset {
+            "qm" => EnginePreset::QM,
+            "asil-a" => EnginePreset::AsilA,
+            "asil-b" => EnginePreset::AsilB,
+            "asil-c" => EnginePreset::AsilC,
+            "asil-d" => EnginePreset::AsilD,
+            _ => return Err(PyValueError::new_err(format!("unknown preset `{preset}`"))),
+        };
+        MemoryInitializer::ensure_initialized().map_err(to_py_err)?;
+        Ok(Self {
+            engine:      CapabilityAwareEngine::with_preset(preset).map_err(to_py_err)?,
+            interceptor: LinkInterceptor::new("host"),
+            pending:     PendingError::default(),
+        })
+    }
+
+    /// Register an interceptor for the calls made through `invoke`
+    ///
+    /// The interceptor may define `before_call(function, args)` and
+    /// `after_call(function, args, results)`. Returning a list replaces the
+    /// arguments or results, returning `None` keeps them, and raising
+    /// aborts the call with that exception. Interceptors run in the order
+    /// they were added before the call and in reverse order after it.
+    fn add_interceptor(&mut self, interceptor: PyObject) {
+        self.interceptor.add_strategy(Arc::new(PyInterceptor::new(
+            interceptor,
+            self.pending.clone(),
+        )));
+    }
+}
+
+/// A module loaded into an engine
+///
+/// `Module(engine, binary)` decodes and validates the binary.
+#[pyclass(module = "wrt_py")]
+pub struct Module {
+    engine: Py<Engine>,
+    handle: ModuleHandle,
+}
+
+#[pymethods]
+impl Module {
+    #[new]
+    fn new(py: Python<'_>, engine: Py<Engine>, binary: &[u8]) -> PyResult<Self> {
+        let handle = engine.borrow_mut(py).engine.load_module(binary).map_err(to_py_err)?;
+        Ok(Self { engine, handle })
+    }
+
+    /// Check that `binary` is a well-formed module without loading it
+    #[staticmethod]
+    fn validate(binary: &[u8]) -> PyResult<()> {
+        decode_module(binary).map(|_| ()).map_err(to_py_err)
+    }
+
+    /// Instantiate the module, running its start function if it has one
+    fn instantiate(&self, py: Python<'_>) -> PyResult<Instance> {
+        let handle =
+            self.engine.borrow_mut(py).engine.instantiate(self.handle).map_err(to_py_err)?;
+        Ok(Instance {
+            engine: self.engine.clone_ref(py),
+            handle,
+        })
+    }
+}
+
+/// An instance of a module
+#[pyclass(module = "wrt_py")]
+pub struct Instance {
+    engine: Py<Engine>,
+    handle: InstanceHandle,
+}
+
+impl Instance {
+    /// Look up the memory exported as `name`
+    fn memory(&self, py: Python<'_>, name: &str) -> PyResult<MemoryWrapper> {
+        match self.engine.borrow(py).engine.get_export(self.handle, name).map_err(to_py_err)? {
+            Some(Extern::Memory(export)) => Ok(export.memory),
+            _ => Err(to_py_err(Error::memory_not_found(
+                "Instance has no memory export of this name",
+            ))),
+        }
+    }
+}
+
+#[pymethods]
+impl Instance {
+    /// Names of the exported functions
+    #[getter]
+    fn functions(&self, py: Python<'_>) -> PyResult<Vec<String>> {
+        self.engine
+            .borrow(py)
+            .engine
+            .get_exported_functions(self.handle)
+            .map_err(to_py_err)
+    }
+
+    /// Call the function exported as `name`
+    ///
+    /// Arguments are converted to the parameter types of the function.
+    /// Returns `None` for a function without results, the result for a
+    /// function with one and a tuple otherwise.
+    #[pyo3(signature = (name, *args))]
+    fn invoke(&self, py: Python<'_>, name: &str, args: &Bound<'_, PyTuple>) -> PyResult<PyObject> {
+        let mut engine = self.engine.borrow_mut(py);
+        let Engine {
+            engine,
+            interceptor,
+            pending,
+        } = &mut *engine;
+
+        let (params, _) = engine.get_export_types(self.handle, name).map_err(to_py_err)?;
+        let args = values::from_python_all(args.iter(), &params)?;
+        let results = interceptor.intercept_call(GUEST, name, args, |args| {
+            engine.execute(self.handle, name, &args)
+        });
+        match results {
+            Ok(results) => values::to_return(py, &results),
+            Err(error) => Err(pending.take().unwrap_or_else(|| to_py_err(error))),
+        }
+    }
+
+    /// Size in 64 KiB pages of the memory exported as `name`
+    #[pyo3(signature = (name = "memory"))]
+    fn memory_size(&self, py: Python<'_>, name: &str) -> PyResult<u32> {
+        Ok(self.memory(py, name)?.size())
+    }
+
+    /// Copy `length` bytes starting at `offset` out of the memory exported
+    /// as `name`
+    #[pyo3(signature = (offset, length, name = "memory"))]
+    fn read_memory<'py>(
+        &self,
+        py: Python<'py>,
+        offset: u32,
+        length: usize,
+        name: &str,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let memory = self.memory(py, name)?;
+        let mut buffer = vec![0; length];
+        memory.read(offset, &mut buffer).map_err(to_py_err)?;
+        Ok(PyBytes::new(py, &buffer))
+    }
+
+    /// Copy `data` into the memory exported as `name` starting at `offset`
+    ///
+    /// The write goes to the instance's memory in place.
+    #[pyo3(signature = (offset, data, name = "memory"))]
+    fn write_memory(&self, py: Python<'_>, offset: u32, data: &[u8], name: &str) -> PyResult<()> {
+        let memory = self.memory(py, name)?;
+        memory.view_mut(|mut view| view.write_bytes(offset, data)).map_err(to_py_err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WrtError;
+
+    /// `(module (func (export "f")))`
+    const MODULE: &[u8] = &[
+        0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, 0x01, 0x04, 0x01, 0x60, 0x00, 0x00, 0x03,
+        0x02, 0x01, 0x00, 0x07, 0x05, 0x01, 0x01, b'f', 0x00, 0x00, 0x0A, 0x04, 0x01, 0x02, 0x00,
+        0x0B,
+    ];
+
+    #[test]
+    fn test_engine_presets() {
+        pyo3::prepare_freethreaded_python();
+        assert!(Engine::new("asil-d").is_ok());
+        let error = Engine::new("asil-e").err().unwrap();
+        Python::with_gil(|py| assert!(error.is_instance_of::<PyValueError>(py)));
+    }
+
+    #[test]
+    fn test_module_validation() {
+        pyo3::prepare_freethreaded_python();
+        assert!(Module::validate(MODULE).is_ok());
+        Python::with_gil(|py| {
+            let error = Module::validate(&MODULE[..12]).unwrap_err();
+            assert!(error.is_instance_of::<WrtError>(py));
+
+            let engine = Py::new(py, Engine::new("qm").unwrap()).unwrap();
+            let error = Module::new(py, engine, b"\0asm").err().unwrap();
+            assert!(error.is_instance_of::<WrtError>(py));
+        });
+    }
+}
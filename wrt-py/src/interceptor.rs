@@ -0,0 +1,181 @@
+//! Interceptors implemented in Python
+
+use alloc::sync::Arc;
+use std::sync::Mutex;
+
+use pyo3::prelude::*;
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_foundation::{
+    values::Value,
+    ValueType,
+};
+use wrt_intercept::LinkInterceptorStrategy;
+
+use crate::values;
+
+/// Exception raised by an interceptor, kept until the call it aborted
+/// returns so it can be re-raised unchanged
+#[derive(Clone, Default)]
+pub(crate) struct PendingError(Arc<Mutex<Option<PyErr>>>);
+
+impl PendingError {
+    /// Record `error` and return the runtime error that aborts the call
+    fn set(&self, error: PyErr) -> Error {
+        if let Ok(mut pending) = self.0.lock() {
+            *pending = Some(error);
+        }
+        Error::runtime_execution_error("Python interceptor raised an exception")
+    }
+
+    /// Take the recorded exception, if any
+    pub(crate) fn take(&self) -> Option<PyErr> {
+        self.0.lock().ok().and_then(|mut pending| pending.take())
+    }
+}
+
+/// Strategy calling the optional `before_call(function, args)` and
+/// `after_call(function, args, results)` methods of a Python object
+///
+/// A hook returning `None` leaves the values unchanged; otherwise it must
+/// return a list of the same length, whose elements replace them.
+pub(crate) struct PyInterceptor {
+    hooks:   PyObject,
+    pending: PendingError,
+}
+
+impl PyInterceptor {
+    pub(crate) fn new(hooks: PyObject, pending: PendingError) -> Self {
+        Self { hooks, pending }
+    }
+
+    /// Call the hook `name` with `function`, `args` and `extra` and apply
+    /// its return value to `current`
+    fn call_hook(
+        &self,
+        name: &str,
+        function: &str,
+        args: &[Value],
+        extra: Option<&[Value]>,
+        current: &[Value],
+    ) -> Result<Vec<Value>> {
+        Python::with_gil(|py| {
+            let hooks = self.hooks.bind(py);
+            let result = (|| {
+                if !hooks.hasattr(name)? {
+                    return Ok(current.to_vec());
+                }
+                let args = values::to_list(py, args)?;
+                let returned = match extra {
+                    Some(extra) => {
+                        hooks.call_method1(name, (function, args, values::to_list(py, extra)?))?
+                    },
+                    None => hooks.call_method1(name, (function, args))?,
+                };
+                if returned.is_none() {
+                    return Ok(current.to_vec());
+                }
+                let types: Vec<ValueType> = current.iter().map(Value::value_type).collect();
+                values::from_python_all(
+                    returned.try_iter()?.collect::<PyResult<Vec<_>>>()?.into_iter(),
+                    &types,
+                )
+            })();
+            result.map_err(|error| self.pending.set(error))
+        })
+    }
+}
+
+impl LinkInterceptorStrategy for PyInterceptor {
+    fn before_call(
+        &self,
+        _source: &str,
+        _target: &str,
+        function: &str,
+        args: &[Value],
+    ) -> Result<Vec<Value>> {
+        self.call_hook("before_call", function, args, None, args)
+    }
+
+    fn after_call(
+        &self,
+        _source: &str,
+        _target: &str,
+        function: &str,
+        args: &[Value],
+        result: Result<Vec<Value>>,
+    ) -> Result<Vec<Value>> {
+        let results = result?;
+        self.call_hook("after_call", function, args, Some(&results), &results)
+    }
+
+    fn clone_strategy(&self) -> Arc<dyn LinkInterceptorStrategy> {
+        Python::with_gil(|py| Arc::new(Self::new(self.hooks.clone_ref(py), self.pending.clone())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use pyo3::ffi::c_str;
+
+    use super::*;
+
+    /// Instantiate the Python class `Hooks` defined by `source`
+    fn hooks(py: Python<'_>, source: &str) -> PyObject {
+        let source = CString::new(source).unwrap();
+        let module = PyModule::from_code(py, &source, c_str!("hooks.py"), c_str!("hooks")).unwrap();
+        module.getattr("Hooks").unwrap().call0().unwrap().unbind()
+    }
+
+    #[test]
+    fn test_hooks_rewrite_values() {
+        pyo3::prepare_freethreaded_python();
+        let hooks = Python::with_gil(|py| {
+            hooks(
+                py,
+                "class Hooks:\n    def before_call(self, function, args):\n        return [a * 2 \
+                 for a in args]\n    def after_call(self, function, args, results):\n        \
+                 return None\n",
+            )
+        });
+        let interceptor = PyInterceptor::new(hooks, PendingError::default());
+
+        let args = interceptor.before_call("host", "guest", "f", &[Value::I32(3)]).unwrap();
+        assert_eq!(args, vec![Value::I32(6)]);
+        let results = interceptor
+            .after_call("host", "guest", "f", &args, Ok(vec![Value::I64(1)]))
+            .unwrap();
+        assert_eq!(results, vec![Value::I64(1)]);
+    }
+
+    #[test]
+    fn test_exception_is_kept_for_the_caller() {
+        pyo3::prepare_freethreaded_python();
+        let hooks = Python::with_gil(|py| {
+            hooks(
+                py,
+                "class Hooks:\n    def before_call(self, function, args):\n        raise \
+                 KeyError(function)\n",
+            )
+        });
+        let pending = PendingError::default();
+        let interceptor = PyInterceptor::new(hooks, pending.clone());
+
+        assert!(interceptor.before_call("host", "guest", "f", &[]).is_err());
+        let error = pending.take().unwrap();
+        Python::with_gil(|py| {
+            assert!(error.is_instance_of::<pyo3::exceptions::PyKeyError>(py));
+        });
+        assert!(pending.take().is_none());
+
+        // Missing hooks leave the values alone
+        let results = interceptor
+            .after_call("host", "guest", "f", &[], Ok(vec![Value::I32(1)]))
+            .unwrap();
+        assert_eq!(results, vec![Value::I32(1)]);
+    }
+}
@@ -0,0 +1,68 @@
+//! Python bindings for WRT
+//!
+//! Exposes the runtime to Python for test automation, hardware-in-the-loop
+//! benches and prototyping. An `Engine` is created with one of the safety
+//! presets, modules are loaded into it and instantiated, and instances are
+//! driven through `invoke` and the memory accessors. Interceptors registered
+//! on the engine observe and may rewrite the arguments and results of every
+//! call made through `invoke`.
+//!
+//! ```python
+//! import wrt_py
+//!
+//! class Trace:
+//!     def before_call(self, function, args):
+//!         print("->", function, args)
+//!
+//!     def after_call(self, function, args, results):
+//!         print("<-", function, results)
+//!
+//! engine = wrt_py.Engine("asil-b")
+//! engine.add_interceptor(Trace())
+//! instance = wrt_py.Module(engine, open("add.wasm", "rb").read()).instantiate()
+//! assert instance.invoke("add", 1, 2) == 3
+//! ```
+//!
+//! Runtime failures are raised as `wrt_py.WrtError`.
+
+#![warn(missing_docs)]
+
+extern crate alloc;
+
+mod engine;
+mod interceptor;
+mod values;
+
+use pyo3::{
+    create_exception,
+    exceptions::PyException,
+    prelude::*,
+};
+
+pub use crate::engine::{
+    Engine,
+    Instance,
+    Module,
+};
+
+create_exception!(
+    wrt_py,
+    WrtError,
+    PyException,
+    "Error reported by the runtime, such as a malformed module or a trap."
+);
+
+/// Raise a runtime error as a `WrtError`
+pub(crate) fn to_py_err(error: wrt_error::Error) -> PyErr {
+    WrtError::new_err(error.message)
+}
+
+/// The `wrt_py` Python module
+#[pymodule]
+fn wrt_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Engine>()?;
+    m.add_class::<Module>()?;
+    m.add_class::<Instance>()?;
+    m.add("WrtError", m.py().get_type::<WrtError>())?;
+    Ok(())
+}
@@ -0,0 +1,127 @@
+//! Conversion between Python objects and WebAssembly values
+
+use pyo3::{
+    exceptions::{
+        PyOverflowError,
+        PyTypeError,
+        PyValueError,
+    },
+    prelude::*,
+    types::{
+        PyList,
+        PyTuple,
+    },
+    IntoPyObjectExt,
+};
+use wrt_foundation::{
+    values::{
+        FloatBits32,
+        FloatBits64,
+        Value,
+    },
+    ValueType,
+};
+
+/// Convert a Python object to a value of type `ty`
+///
+/// Integers for `i32` and `i64` parameters may be given signed or unsigned,
+/// so `0xFFFF_FFFF` and `-1` both denote the same `i32`.
+pub(crate) fn from_python(object: &Bound<'_, PyAny>, ty: ValueType) -> PyResult<Value> {
+    match ty {
+        ValueType::I32 => {
+            let value: i64 = object.extract()?;
+            if value < i64::from(i32::MIN) || value > i64::from(u32::MAX) {
+                return Err(PyOverflowError::new_err(format!(
+                    "{value} does not fit in an i32"
+                )));
+            }
+            Ok(Value::I32(value as i32))
+        },
+        ValueType::I64 => match object.extract::<i64>() {
+            Ok(value) => Ok(Value::I64(value)),
+            Err(_) => Ok(Value::I64(object.extract::<u64>()? as i64)),
+        },
+        ValueType::F32 => Ok(Value::F32(FloatBits32::from_float(
+            object.extract::<f64>()? as f32,
+        ))),
+        ValueType::F64 => Ok(Value::F64(FloatBits64::from_float(object.extract()?))),
+        _ => Err(PyTypeError::new_err(format!(
+            "{ty:?} values are not supported by the Python bindings"
+        ))),
+    }
+}
+
+/// Convert Python objects to values of the given types
+pub(crate) fn from_python_all<'py>(
+    objects: impl ExactSizeIterator<Item = Bound<'py, PyAny>>,
+    types: &[ValueType],
+) -> PyResult<Vec<Value>> {
+    if objects.len() != types.len() {
+        return Err(PyValueError::new_err(format!(
+            "expected {} values, got {}",
+            types.len(),
+            objects.len()
+        )));
+    }
+    objects.zip(types).map(|(object, ty)| from_python(&object, *ty)).collect()
+}
+
+/// Convert a value to a Python `int` or `float`
+pub(crate) fn to_python(py: Python<'_>, value: &Value) -> PyResult<PyObject> {
+    match value {
+        Value::I32(value) => value.into_py_any(py),
+        Value::I64(value) => value.into_py_any(py),
+        Value::F32(value) => f64::from(value.value()).into_py_any(py),
+        Value::F64(value) => value.value().into_py_any(py),
+        _ => Err(PyTypeError::new_err(format!(
+            "{:?} values are not supported by the Python bindings",
+            value.value_type()
+        ))),
+    }
+}
+
+/// Convert values to a Python list
+pub(crate) fn to_list<'py>(py: Python<'py>, values: &[Value]) -> PyResult<Bound<'py, PyList>> {
+    let objects = values.iter().map(|value| to_python(py, value)).collect::<PyResult<Vec<_>>>()?;
+    PyList::new(py, objects)
+}
+
+/// Convert the results of a call to what `invoke` returns: `None` for no
+/// results, the value itself for one and a tuple for several
+pub(crate) fn to_return(py: Python<'_>, values: &[Value]) -> PyResult<PyObject> {
+    match values {
+        [] => Ok(py.None()),
+        [value] => to_python(py, value),
+        _ => {
+            let objects =
+                values.iter().map(|value| to_python(py, value)).collect::<PyResult<Vec<_>>>()?;
+            PyTuple::new(py, objects)?.into_py_any(py)
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let object = 0xFFFF_FFFFu32.into_py_any(py).unwrap();
+            assert_eq!(
+                from_python(object.bind(py), ValueType::I32).unwrap(),
+                Value::I32(-1)
+            );
+            let object = (1i64 << 40).into_py_any(py).unwrap();
+            assert!(from_python(object.bind(py), ValueType::I32).is_err());
+
+            let object = to_python(py, &Value::F64(FloatBits64::from_float(1.5))).unwrap();
+            assert_eq!(object.extract::<f64>(py).unwrap(), 1.5);
+
+            assert!(to_return(py, &[]).unwrap().is_none(py));
+            let pair = to_return(py, &[Value::I32(1), Value::I64(2)]).unwrap();
+            assert_eq!(pair.extract::<(i32, i64)>(py).unwrap(), (1, 2));
+        });
+    }
+}
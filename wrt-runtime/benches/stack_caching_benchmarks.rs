@@ -0,0 +1,88 @@
+#![allow(missing_docs)] // Allow missing docs for benchmark harness code
+//! Benchmarks of the interpreter on arithmetic-heavy guests
+//!
+//! Each guest is a loop of integer arithmetic on locals and the operand
+//! stack, the code top-of-stack caching in the interpreter's dispatch loop
+//! speeds up. The guests are loaded into an engine once and their exports
+//! called with the iteration count as argument.
+
+use criterion::{
+    black_box,
+    criterion_group,
+    criterion_main,
+    BenchmarkId,
+    Criterion,
+};
+use wrt_foundation::{
+    memory_init::MemoryInitializer,
+    values::Value,
+};
+use wrt_runtime::{
+    engine::{
+        CapabilityAwareEngine,
+        CapabilityEngine,
+        EnginePreset,
+        InstanceHandle,
+    },
+    text_format::wat_to_binary,
+};
+
+/// Guests, each exporting `run` taking the iteration count
+const GUESTS: &[(&str, &str)] = &[
+    (
+        // Horner's method: one multiplication and addition per iteration
+        "horner",
+        r#"(module
+            (func (export "run") (param $n i32) (result i32) (local $acc i32)
+              (loop $next
+                (local.set $acc
+                  (i32.add (i32.mul (local.get $acc) (i32.const 31)) (local.get $n)))
+                (br_if $next (local.tee $n (i32.sub (local.get $n) (i32.const 1)))))
+              (local.get $acc)))"#,
+    ),
+    (
+        // A xorshift generator folded into a checksum: deeper expressions
+        "xorshift",
+        r#"(module
+            (func (export "run") (param $n i32) (result i32) (local $x i32) (local $sum i32)
+              (local.set $x (i32.const 2463534242))
+              (loop $next
+                (local.set $x (i32.xor (local.get $x) (i32.shl (local.get $x) (i32.const 13))))
+                (local.set $x (i32.xor (local.get $x) (i32.shr_u (local.get $x) (i32.const 17))))
+                (local.set $x (i32.xor (local.get $x) (i32.shl (local.get $x) (i32.const 5))))
+                (local.set $sum
+                  (i32.add (local.get $sum)
+                    (i32.and (i32.add (local.get $x) (i32.rotl (local.get $sum) (i32.const 7)))
+                             (i32.const 65535))))
+                (br_if $next (local.tee $n (i32.sub (local.get $n) (i32.const 1)))))
+              (local.get $sum)))"#,
+    ),
+];
+
+/// Engine with `guest` instantiated
+fn instantiate(guest: &str) -> (CapabilityAwareEngine, InstanceHandle) {
+    let binary = wat_to_binary(guest).unwrap();
+    let mut engine = CapabilityAwareEngine::with_preset(EnginePreset::QM).unwrap();
+    let module = engine.load_module(&binary).unwrap();
+    let instance = engine.instantiate(module).unwrap();
+    (engine, instance)
+}
+
+fn benchmark_guests(c: &mut Criterion) {
+    MemoryInitializer::ensure_initialized().unwrap();
+    let mut group = c.benchmark_group("guest");
+
+    for (name, guest) in GUESTS {
+        let (mut engine, instance) = instantiate(guest);
+        for iterations in [1_000, 100_000] {
+            group.bench_with_input(BenchmarkId::new(*name, iterations), &iterations, |b, &n| {
+                b.iter(|| engine.execute(instance, "run", &[Value::I32(black_box(n))]).unwrap())
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_guests);
+criterion_main!(benches);
@@ -0,0 +1,311 @@
+//! Attribution of resource use to tenants
+//!
+//! A host running guests on behalf of several tenants registers an
+//! [`Accounting`] with each engine, naming the [`TenantId`] the engine runs
+//! for. The engine then charges the tenant for what its guests use:
+//!
+//! - [`Metered::Fuel`] and [`Metered::Instructions`] consumed by invocations
+//! - [`Metered::MemoryPages`] and [`Metered::TableEntries`] of the memories and
+//!   tables its instances define, when they are created and when they grow
+//!
+//! Resource tables charge [`Metered::ResourceHandles`] for the owned handles
+//! they hold once they are given a tenant with
+//! [`ResourceTable::set_tenant`](crate::resources::ResourceTable::set_tenant).
+//!
+//! Every tenant may have a [`TenantQuota`]. Memory pages, table entries and
+//! resource handles are reserved before they are used, so a growth or
+//! allocation beyond the quota fails. Fuel is enforced by limiting the fuel
+//! of invocations to what the quota has left, and instructions are counted
+//! after the fact.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        Mutex,
+        MutexGuard,
+        PoisonError,
+    },
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+
+use crate::growth_observer::{
+    GrowthEvent,
+    GrowthKind,
+    MemoryObserver,
+};
+
+/// Identifies the tenant resources are attributed to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TenantId(pub u32);
+
+/// Resource accounted per tenant
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Metered {
+    /// Yield points passed by invocations
+    Fuel,
+    /// Instructions executed by invocations
+    Instructions,
+    /// Pages of linear memories
+    MemoryPages,
+    /// Elements of tables
+    TableEntries,
+    /// Owned handles in resource tables
+    ResourceHandles,
+}
+
+/// Resources used by a tenant
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TenantUsage {
+    /// Fuel consumed
+    pub fuel:             u64,
+    /// Instructions executed
+    pub instructions:     u64,
+    /// Memory pages held
+    pub memory_pages:     u64,
+    /// Table entries held
+    pub table_entries:    u64,
+    /// Resource handles held
+    pub resource_handles: u64,
+}
+
+impl TenantUsage {
+    /// Amount used of `metered`
+    pub fn get(&self, metered: Metered) -> u64 {
+        match metered {
+            Metered::Fuel => self.fuel,
+            Metered::Instructions => self.instructions,
+            Metered::MemoryPages => self.memory_pages,
+            Metered::TableEntries => self.table_entries,
+            Metered::ResourceHandles => self.resource_handles,
+        }
+    }
+
+    fn get_mut(&mut self, metered: Metered) -> &mut u64 {
+        match metered {
+            Metered::Fuel => &mut self.fuel,
+            Metered::Instructions => &mut self.instructions,
+            Metered::MemoryPages => &mut self.memory_pages,
+            Metered::TableEntries => &mut self.table_entries,
+            Metered::ResourceHandles => &mut self.resource_handles,
+        }
+    }
+}
+
+/// Limits of the resources a tenant may use, unlimited by default
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TenantQuota {
+    limits: [Option<u64>; 5],
+}
+
+impl TenantQuota {
+    /// Quota without limits
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    /// Limit `metered` to `limit`
+    pub fn with_limit(mut self, metered: Metered, limit: u64) -> Self {
+        self.limits[metered as usize] = Some(limit);
+        self
+    }
+
+    /// Limit of `metered`, if it has one
+    pub fn limit(&self, metered: Metered) -> Option<u64> {
+        self.limits[metered as usize]
+    }
+}
+
+#[derive(Debug, Default)]
+struct Account {
+    quota: TenantQuota,
+    usage: TenantUsage,
+}
+
+/// Resource use and quotas of tenants
+///
+/// Shared between the engines, memories and resource tables of the tenants,
+/// usually behind an [`Arc`].
+#[derive(Debug, Default)]
+pub struct Accounting {
+    accounts: Mutex<HashMap<TenantId, Account>>,
+}
+
+impl Accounting {
+    /// Accounting without tenants
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn accounts(&self) -> MutexGuard<'_, HashMap<TenantId, Account>> {
+        self.accounts.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Set the quota of `tenant`, keeping what it used so far
+    pub fn set_quota(&self, tenant: TenantId, quota: TenantQuota) {
+        self.accounts().entry(tenant).or_default().quota = quota;
+    }
+
+    /// Quota of `tenant`
+    pub fn quota(&self, tenant: TenantId) -> TenantQuota {
+        self.accounts().get(&tenant).map(|account| account.quota).unwrap_or_default()
+    }
+
+    /// Resources used by `tenant`
+    pub fn usage(&self, tenant: TenantId) -> TenantUsage {
+        self.accounts().get(&tenant).map(|account| account.usage).unwrap_or_default()
+    }
+
+    /// Tenants that have used resources or have a quota
+    pub fn tenants(&self) -> Vec<TenantId> {
+        let mut tenants: Vec<_> = self.accounts().keys().copied().collect();
+        tenants.sort_unstable();
+        tenants
+    }
+
+    /// Amount of `metered` `tenant` may still use, or `None` without a limit
+    pub fn remaining(&self, tenant: TenantId, metered: Metered) -> Option<u64> {
+        let accounts = self.accounts();
+        let account = accounts.get(&tenant)?;
+        let limit = account.quota.limit(metered)?;
+        Some(limit.saturating_sub(account.usage.get(metered)))
+    }
+
+    /// Charge `tenant` for `amount` of `metered`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, without charging anything, if the charge would
+    /// exceed the quota of the tenant.
+    pub fn charge(&self, tenant: TenantId, metered: Metered, amount: u64) -> Result<()> {
+        let mut accounts = self.accounts();
+        let account = accounts.entry(tenant).or_default();
+        let used = account.usage.get(metered).saturating_add(amount);
+        if account.quota.limit(metered).is_some_and(|limit| used > limit) {
+            return Err(Error::resource_limit_exceeded("Tenant quota exceeded"));
+        }
+        *account.usage.get_mut(metered) = used;
+        Ok(())
+    }
+
+    /// Charge `tenant` for `amount` of `metered` already used, even beyond
+    /// its quota
+    pub fn record(&self, tenant: TenantId, metered: Metered, amount: u64) {
+        let mut accounts = self.accounts();
+        let used = accounts.entry(tenant).or_default().usage.get_mut(metered);
+        *used = used.saturating_add(amount);
+    }
+
+    /// Give `amount` of `metered` back to `tenant`
+    pub fn release(&self, tenant: TenantId, metered: Metered, amount: u64) {
+        if let Some(account) = self.accounts().get_mut(&tenant) {
+            let used = account.usage.get_mut(metered);
+            *used = used.saturating_sub(amount);
+        }
+    }
+
+    /// Observer charging `tenant` for the growth of memories and tables,
+    /// passing the growth on to `inner`
+    pub fn growth_observer(
+        self: &Arc<Self>,
+        tenant: TenantId,
+        inner: Option<Arc<dyn MemoryObserver>>,
+    ) -> Arc<dyn MemoryObserver> {
+        Arc::new(TenantObserver {
+            accounting: self.clone(),
+            tenant,
+            inner,
+        })
+    }
+}
+
+/// Charges the growth of memories and tables to a tenant
+struct TenantObserver {
+    accounting: Arc<Accounting>,
+    tenant:     TenantId,
+    inner:      Option<Arc<dyn MemoryObserver>>,
+}
+
+impl MemoryObserver for TenantObserver {
+    fn allow_growth(&self, event: &GrowthEvent) -> bool {
+        let metered = match event.kind {
+            GrowthKind::Memory => Metered::MemoryPages,
+            GrowthKind::Table => Metered::TableEntries,
+        };
+        let delta = u64::from(event.delta());
+        if self.accounting.charge(self.tenant, metered, delta).is_err() {
+            return false;
+        }
+        let allowed = self.inner.as_ref().map_or(true, |inner| inner.allow_growth(event));
+        if !allowed {
+            self.accounting.release(self.tenant, metered, delta);
+        }
+        allowed
+    }
+
+    fn grown(&self, event: &GrowthEvent) {
+        if let Some(inner) = &self.inner {
+            inner.grown(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::growth_observer::{
+        GrowthEvent,
+        GrowthRequester,
+    };
+
+    const TENANT: TenantId = TenantId(7);
+
+    #[test]
+    fn test_charge_respects_quota() {
+        let accounting = Accounting::new();
+        accounting.set_quota(
+            TENANT,
+            TenantQuota::unlimited().with_limit(Metered::ResourceHandles, 2),
+        );
+
+        assert!(accounting.charge(TENANT, Metered::ResourceHandles, 2).is_ok());
+        assert!(accounting.charge(TENANT, Metered::ResourceHandles, 1).is_err());
+        accounting.release(TENANT, Metered::ResourceHandles, 1);
+        assert_eq!(
+            accounting.remaining(TENANT, Metered::ResourceHandles),
+            Some(1)
+        );
+
+        // Consumption measured after the fact is recorded beyond the quota
+        accounting.record(TENANT, Metered::Instructions, 100);
+        assert_eq!(accounting.usage(TENANT).instructions, 100);
+        assert_eq!(accounting.remaining(TENANT, Metered::Instructions), None);
+    }
+
+    #[test]
+    fn test_growth_observer_charges_tenant() {
+        let accounting = Arc::new(Accounting::new());
+        accounting.set_quota(
+            TENANT,
+            TenantQuota::unlimited().with_limit(Metered::MemoryPages, 4),
+        );
+        let observer = accounting.growth_observer(TENANT, None);
+
+        let grow = |old_size, new_size| GrowthEvent {
+            kind: GrowthKind::Memory,
+            requester: GrowthRequester {
+                instance: 0,
+                index:    0,
+            },
+            old_size,
+            new_size,
+        };
+        assert!(observer.allow_growth(&grow(1, 4)));
+        assert!(!observer.allow_growth(&grow(4, 6)));
+        assert_eq!(accounting.usage(TENANT).memory_pages, 3);
+    }
+}
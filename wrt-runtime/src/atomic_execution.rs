@@ -940,20 +940,21 @@ fn test_atomic_execution_stats() {
     #[test]
     fn test_memory_ordering_conversion() {
         assert_eq!(
-            AtomicOrdering::from(MemoryOrdering::Unordered),
+            convert_memory_ordering(MemoryOrdering::Unordered),
             AtomicOrdering::Relaxed
         );
         assert_eq!(
-            AtomicOrdering::from(MemoryOrdering::SeqCst),
+            convert_memory_ordering(MemoryOrdering::SeqCst),
             AtomicOrdering::SeqCst
         );
     }
 
     #[cfg(feature = "std")]
     #[test]
-    fn test_atomic_context_creation() -> Result<(), wrt_error::Error> {
+    fn test_atomic_context_creation() {
+        wrt_foundation::memory_init::MemoryInitializer::ensure_initialized().unwrap();
         let thread_manager = ThreadManager::new(ThreadConfig::default()).unwrap();
-        let mut memory = result_vec![0u8; 1024]?;
+        let mut memory = vec![0u8; 1024];
         let context = AtomicMemoryContext::new(memory.as_mut_ptr(), memory.len(), thread_manager);
         assert!(context.is_ok());
     }
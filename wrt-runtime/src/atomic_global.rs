@@ -0,0 +1,314 @@
+//! Host-shared atomic globals
+//!
+//! This module lets a host designate mutable `i32`/`i64` globals of an
+//! instance as *atomic globals*. A designated global is backed by a single
+//! atomic cell that any number of host threads access, which makes it a cheap
+//! cross-thread flag or counter (e.g. an interrupt request, a progress counter
+//! or a configuration epoch).
+//!
+//! Once designated, every access the runtime makes to the global goes through
+//! the cell: [`ModuleInstance::global_value`] and
+//! [`ModuleInstance::set_global_value`], and with them exported globals read
+//! and written through [`InstanceGlobal`], state snapshots and dynamic
+//! linking.
+//!
+//! The stackless engine does not interpret function bodies yet, so there is
+//! no guest `global.get`/`global.set` to route through the cell: sharing is
+//! between host threads and the accessors above. An interpreter must access
+//! globals through the two [`ModuleInstance`] methods for guest instructions
+//! to observe designated globals.
+//!
+//! # Visibility semantics
+//!
+//! All accesses use [`Ordering::SeqCst`]:
+//!
+//! - A write through one handle or accessor is observed by every read that
+//!   executes after it; no read ever observes a torn value.
+//! - Read-modify-write operations are single atomic steps; a concurrent write
+//!   is ordered either entirely before or entirely after them.
+//!
+//! Non-designated globals are unaffected and keep their plain, single-threaded
+//! semantics.
+//!
+//! [`ModuleInstance`]: crate::module_instance::ModuleInstance
+//! [`ModuleInstance::global_value`]: crate::module_instance::ModuleInstance::global_value
+//! [`ModuleInstance::set_global_value`]: crate::module_instance::ModuleInstance::set_global_value
+//! [`InstanceGlobal`]: crate::externs::InstanceGlobal
+
+use core::sync::atomic::{
+    AtomicI32,
+    Ordering,
+};
+#[cfg(target_has_atomic = "64")]
+use core::sync::atomic::AtomicI64;
+use std::sync::Arc;
+
+use wrt_foundation::{
+    types::ValueType as WrtValueType,
+    values::Value as WrtValue,
+};
+
+use crate::{
+    global::Global,
+    prelude::{
+        Debug,
+        Error,
+        Result,
+    },
+};
+
+/// Ordering used for every access to an atomic global
+const ORDERING: Ordering = Ordering::SeqCst;
+
+/// Storage backing an atomic global
+#[derive(Debug)]
+enum AtomicCell {
+    /// 32-bit integer global
+    I32(AtomicI32),
+    /// 64-bit integer global
+    #[cfg(target_has_atomic = "64")]
+    I64(AtomicI64),
+}
+
+/// Read-modify-write operations supported by atomic globals
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AtomicRmwOp {
+    /// Wrapping addition
+    Add,
+    /// Wrapping subtraction
+    Sub,
+    /// Bitwise and
+    And,
+    /// Bitwise or
+    Or,
+    /// Bitwise exclusive or
+    Xor,
+    /// Unconditional exchange
+    Xchg,
+}
+
+/// Shareable handle to a designated atomic global
+///
+/// Cloning the handle is cheap and every clone refers to the same cell, so a
+/// handle can be moved to other threads while the instance is in use.
+#[derive(Debug, Clone)]
+pub struct AtomicGlobal {
+    cell: Arc<AtomicCell>,
+}
+
+impl AtomicGlobal {
+    /// Create an atomic global from a runtime global, taking over its current
+    /// value.
+    ///
+    /// Only mutable `i32` and `i64` globals can be designated.
+    pub fn from_global(global: &Global) -> Result<Self> {
+        let ty = global.global_type_descriptor();
+        if !ty.mutable {
+            return Err(Error::runtime_invalid_argument(
+                "Only mutable globals can be designated as atomic",
+            ));
+        }
+        Self::new(global.get())
+    }
+
+    /// Create a standalone atomic global holding `initial`
+    pub fn new(initial: &WrtValue) -> Result<Self> {
+        let cell = match initial {
+            WrtValue::I32(v) => AtomicCell::I32(AtomicI32::new(*v)),
+            #[cfg(target_has_atomic = "64")]
+            WrtValue::I64(v) => AtomicCell::I64(AtomicI64::new(*v)),
+            _ => {
+                return Err(Error::runtime_unsupported_operation(
+                    "Atomic globals must be of type i32 or i64",
+                ))
+            },
+        };
+        Ok(Self {
+            cell: Arc::new(cell),
+        })
+    }
+
+    /// Get the value type of this global
+    #[must_use]
+    pub fn value_type(&self) -> WrtValueType {
+        match *self.cell {
+            AtomicCell::I32(_) => WrtValueType::I32,
+            #[cfg(target_has_atomic = "64")]
+            AtomicCell::I64(_) => WrtValueType::I64,
+        }
+    }
+
+    /// Check whether two handles refer to the same global
+    #[must_use]
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.cell, &other.cell)
+    }
+
+    /// Atomically load the current value
+    #[must_use]
+    pub fn load(&self) -> WrtValue {
+        match &*self.cell {
+            AtomicCell::I32(a) => WrtValue::I32(a.load(ORDERING)),
+            #[cfg(target_has_atomic = "64")]
+            AtomicCell::I64(a) => WrtValue::I64(a.load(ORDERING)),
+        }
+    }
+
+    /// Atomically store `value`
+    pub fn store(&self, value: &WrtValue) -> Result<()> {
+        match (&*self.cell, value) {
+            (AtomicCell::I32(a), WrtValue::I32(v)) => a.store(*v, ORDERING),
+            #[cfg(target_has_atomic = "64")]
+            (AtomicCell::I64(a), WrtValue::I64(v)) => a.store(*v, ORDERING),
+            _ => return Err(Self::type_mismatch()),
+        }
+        Ok(())
+    }
+
+    /// Apply a read-modify-write operation and return the previous value
+    pub fn rmw(&self, op: AtomicRmwOp, operand: &WrtValue) -> Result<WrtValue> {
+        match (&*self.cell, operand) {
+            (AtomicCell::I32(a), WrtValue::I32(v)) => Ok(WrtValue::I32(match op {
+                AtomicRmwOp::Add => a.fetch_add(*v, ORDERING),
+                AtomicRmwOp::Sub => a.fetch_sub(*v, ORDERING),
+                AtomicRmwOp::And => a.fetch_and(*v, ORDERING),
+                AtomicRmwOp::Or => a.fetch_or(*v, ORDERING),
+                AtomicRmwOp::Xor => a.fetch_xor(*v, ORDERING),
+                AtomicRmwOp::Xchg => a.swap(*v, ORDERING),
+            })),
+            #[cfg(target_has_atomic = "64")]
+            (AtomicCell::I64(a), WrtValue::I64(v)) => Ok(WrtValue::I64(match op {
+                AtomicRmwOp::Add => a.fetch_add(*v, ORDERING),
+                AtomicRmwOp::Sub => a.fetch_sub(*v, ORDERING),
+                AtomicRmwOp::And => a.fetch_and(*v, ORDERING),
+                AtomicRmwOp::Or => a.fetch_or(*v, ORDERING),
+                AtomicRmwOp::Xor => a.fetch_xor(*v, ORDERING),
+                AtomicRmwOp::Xchg => a.swap(*v, ORDERING),
+            })),
+            _ => Err(Self::type_mismatch()),
+        }
+    }
+
+    /// Atomically add `operand` (wrapping) and return the previous value
+    pub fn fetch_add(&self, operand: &WrtValue) -> Result<WrtValue> {
+        self.rmw(AtomicRmwOp::Add, operand)
+    }
+
+    /// Atomically subtract `operand` (wrapping) and return the previous value
+    pub fn fetch_sub(&self, operand: &WrtValue) -> Result<WrtValue> {
+        self.rmw(AtomicRmwOp::Sub, operand)
+    }
+
+    /// Atomically and with `operand` and return the previous value
+    pub fn fetch_and(&self, operand: &WrtValue) -> Result<WrtValue> {
+        self.rmw(AtomicRmwOp::And, operand)
+    }
+
+    /// Atomically or with `operand` and return the previous value
+    pub fn fetch_or(&self, operand: &WrtValue) -> Result<WrtValue> {
+        self.rmw(AtomicRmwOp::Or, operand)
+    }
+
+    /// Atomically xor with `operand` and return the previous value
+    pub fn fetch_xor(&self, operand: &WrtValue) -> Result<WrtValue> {
+        self.rmw(AtomicRmwOp::Xor, operand)
+    }
+
+    /// Atomically replace the value and return the previous one
+    pub fn swap(&self, value: &WrtValue) -> Result<WrtValue> {
+        self.rmw(AtomicRmwOp::Xchg, value)
+    }
+
+    /// Store `new` if the current value equals `expected`.
+    ///
+    /// Returns `Ok(previous)` on success and `Err(actual)` if the current
+    /// value did not match; the outer result reports type mismatches.
+    pub fn compare_exchange(
+        &self,
+        expected: &WrtValue,
+        new: &WrtValue,
+    ) -> Result<core::result::Result<WrtValue, WrtValue>> {
+        match (&*self.cell, expected, new) {
+            (AtomicCell::I32(a), WrtValue::I32(e), WrtValue::I32(n)) => Ok(a
+                .compare_exchange(*e, *n, ORDERING, ORDERING)
+                .map(WrtValue::I32)
+                .map_err(WrtValue::I32)),
+            #[cfg(target_has_atomic = "64")]
+            (AtomicCell::I64(a), WrtValue::I64(e), WrtValue::I64(n)) => Ok(a
+                .compare_exchange(*e, *n, ORDERING, ORDERING)
+                .map(WrtValue::I64)
+                .map_err(WrtValue::I64)),
+            _ => Err(Self::type_mismatch()),
+        }
+    }
+
+    fn type_mismatch() -> Error {
+        Error::runtime_type_mismatch("Value type does not match atomic global type")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn test_designation_requires_mutable_integer_global() {
+        let immutable = Global::new(WrtValueType::I32, false, WrtValue::I32(1)).unwrap();
+        assert!(AtomicGlobal::from_global(&immutable).is_err());
+
+        let float = Global::new(
+            WrtValueType::F32,
+            true,
+            WrtValue::F32(wrt_foundation::float_repr::FloatBits32::from_float(1.0)),
+        )
+        .unwrap();
+        assert!(AtomicGlobal::from_global(&float).is_err());
+
+        let global = Global::new(WrtValueType::I64, true, WrtValue::I64(7)).unwrap();
+        let atomic = AtomicGlobal::from_global(&global).unwrap();
+        assert_eq!(atomic.value_type(), WrtValueType::I64);
+        assert_eq!(atomic.load(), WrtValue::I64(7));
+    }
+
+    #[test]
+    fn test_rmw_operations() {
+        let atomic = AtomicGlobal::new(&WrtValue::I32(0b1100)).unwrap();
+        assert_eq!(atomic.fetch_add(&WrtValue::I32(1)).unwrap(), WrtValue::I32(0b1100));
+        assert_eq!(atomic.fetch_and(&WrtValue::I32(0b0101)).unwrap(), WrtValue::I32(0b1101));
+        assert_eq!(atomic.fetch_or(&WrtValue::I32(0b1000)).unwrap(), WrtValue::I32(0b0101));
+        assert_eq!(atomic.fetch_xor(&WrtValue::I32(0b0001)).unwrap(), WrtValue::I32(0b1101));
+        assert_eq!(atomic.swap(&WrtValue::I32(3)).unwrap(), WrtValue::I32(0b1100));
+        assert_eq!(
+            atomic.compare_exchange(&WrtValue::I32(4), &WrtValue::I32(5)).unwrap(),
+            Err(WrtValue::I32(3))
+        );
+        assert_eq!(
+            atomic.compare_exchange(&WrtValue::I32(3), &WrtValue::I32(5)).unwrap(),
+            Ok(WrtValue::I32(3))
+        );
+        assert_eq!(atomic.load(), WrtValue::I32(5));
+        assert!(atomic.fetch_add(&WrtValue::I64(1)).is_err());
+        assert!(atomic.store(&WrtValue::I64(1)).is_err());
+    }
+
+    #[test]
+    fn test_cross_thread_updates() {
+        let atomic = AtomicGlobal::new(&WrtValue::I64(0)).unwrap();
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                let handle = atomic.clone();
+                thread::spawn(move || {
+                    for _ in 0..1000 {
+                        handle.fetch_add(&WrtValue::I64(1)).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+        assert_eq!(atomic.load(), WrtValue::I64(4000));
+    }
+}
@@ -69,7 +69,6 @@
 #[cfg(feature = "std")]
 use std::{
     sync::Arc,
-    time::Instant,
     vec::Vec,
 };
 /// WebAssembly atomic memory model implementation
@@ -119,8 +118,7 @@ pub fn execute_atomic_operation(
         self.apply_pre_operation_ordering(&operation)?;
 
         // Record operation timing
-        #[cfg(feature = "std")]
-        let start_time = Instant::now();
+        let start_time = crate::time_source::timestamp_ns();
 
         // Execute the atomic operation
         let result = match &operation {
@@ -186,13 +184,10 @@ pub fn execute_atomic_operation(
         };
 
         // Record operation timing
-        #[cfg(feature = "std")]
-        {
-            let duration = start_time.elapsed();
-            self.model_stats.total_execution_time += duration.as_nanos() as u64;
-            if duration.as_nanos() as u64 > self.model_stats.max_operation_time {
-                self.model_stats.max_operation_time = duration.as_nanos() as u64;
-            }
+        let duration = crate::time_source::timestamp_ns().saturating_sub(start_time);
+        self.model_stats.total_execution_time += duration;
+        if duration > self.model_stats.max_operation_time {
+            self.model_stats.max_operation_time = duration;
         }
 
         // Apply memory ordering constraints after operation
@@ -831,6 +826,7 @@ fn test_memory_model_stats() {
 
     #[test]
     fn test_consistency_validation_result() {
+        wrt_foundation::memory_init::MemoryInitializer::ensure_initialized().unwrap();
         let result = ConsistencyValidationResult::new().unwrap();
         assert!(result.is_consistent);
         assert!(result.data_races.is_empty());
@@ -839,6 +835,7 @@ fn test_consistency_validation_result() {
     #[cfg(feature = "std")]
     #[test]
     fn test_atomic_memory_model_creation() {
+        wrt_foundation::memory_init::MemoryInitializer::ensure_initialized().unwrap();
         let thread_manager = ThreadManager::new(ThreadConfig::default()).unwrap();
         let mut memory = vec![0u8; 1024];
         let model = AtomicMemoryModel::new(
@@ -0,0 +1,223 @@
+//! Branch hints lowered onto function bodies
+//!
+//! The "metadata.code.branch_hint" section of a module states for `if` and
+//! `br_if` instructions whether their branch is likely taken, locating each
+//! instruction by its byte offset in the function body.
+//! [`ModuleBranchHints::lower`] translates the offsets into indices of the
+//! instructions of the function's expression, counted after the local
+//! declarations, so that tools can relate each hint to a parsed
+//! instruction.
+//!
+//! The engine exposes the hints of loaded modules, see
+//! [`CapabilityAwareEngine::branch_hints`](crate::engine::CapabilityAwareEngine::branch_hints),
+//! but does not act on them: the interpreter executes hinted and unhinted
+//! branches alike.
+//!
+//! Hints that do not name the first byte of an `if` or `br_if` are invalid
+//! under the branch hinting proposal and are dropped, as are hints of
+//! functions without a body.
+
+use alloc::collections::BTreeMap;
+
+use wrt_decoder::branch_hint_section::{
+    BranchHintSection,
+    BranchHintValue,
+    FunctionBranchHints,
+};
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_format::{
+    binary::read_leb128_u32,
+    module::{
+        ImportDesc,
+        Module as FormatModule,
+    },
+};
+use wrt_foundation::types::Instruction;
+
+use crate::instruction_parser::parse_instruction;
+
+/// Branch hints of a function, keyed by instruction index
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoweredBranchHints {
+    hints: BTreeMap<u32, BranchHintValue>,
+}
+
+impl LoweredBranchHints {
+    /// Hint for the instruction at `instruction`
+    pub fn hint(&self, instruction: u32) -> Option<BranchHintValue> {
+        self.hints.get(&instruction).copied()
+    }
+
+    /// Whether the branch of the instruction at `instruction` is predicted
+    /// to be taken, or `None` if it has no hint
+    pub fn predicts_taken(&self, instruction: u32) -> Option<bool> {
+        self.hint(instruction).map(BranchHintValue::is_likely_taken)
+    }
+
+    /// Hinted instructions and their hints in instruction order
+    pub fn iter(&self) -> impl Iterator<Item = (u32, BranchHintValue)> + '_ {
+        self.hints.iter().map(|(instruction, hint)| (*instruction, *hint))
+    }
+
+    /// Number of hinted instructions
+    pub fn len(&self) -> usize {
+        self.hints.len()
+    }
+
+    /// Whether no instruction is hinted
+    pub fn is_empty(&self) -> bool {
+        self.hints.is_empty()
+    }
+}
+
+/// Branch hints of the functions of a module
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModuleBranchHints {
+    functions: BTreeMap<u32, LoweredBranchHints>,
+}
+
+impl ModuleBranchHints {
+    /// Lower the hints of `section` onto the function bodies of `module`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the body of a hinted function is malformed.
+    pub fn lower(module: &FormatModule, section: &BranchHintSection) -> Result<Self> {
+        let imported_functions = module
+            .imports
+            .iter()
+            .filter(|import| matches!(import.desc, ImportDesc::Function(_)))
+            .count();
+
+        let mut functions = BTreeMap::new();
+        for (func_idx, hints) in &section.function_hints {
+            let Some(function) = (*func_idx as usize)
+                .checked_sub(imported_functions)
+                .and_then(|defined| module.functions.get(defined))
+            else {
+                continue;
+            };
+
+            let lowered = lower_function_hints(&function.code, hints)?;
+            if !lowered.is_empty() {
+                functions.insert(*func_idx, lowered);
+            }
+        }
+
+        Ok(Self { functions })
+    }
+
+    /// Hints of the function at `func_idx` in the function index space
+    pub fn function(&self, func_idx: u32) -> Option<&LoweredBranchHints> {
+        self.functions.get(&func_idx)
+    }
+
+    /// Hint for instruction `instruction` of the function at `func_idx`
+    pub fn hint(&self, func_idx: u32, instruction: u32) -> Option<BranchHintValue> {
+        self.function(func_idx).and_then(|hints| hints.hint(instruction))
+    }
+
+    /// Whether no function has hints
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty()
+    }
+}
+
+/// Offset of the expression of a function body, after its local
+/// declarations
+fn skip_local_declarations(body: &[u8]) -> Result<usize> {
+    let (count, mut offset) = read_leb128_u32(body, 0)?;
+    for _ in 0..count {
+        offset += read_leb128_u32(body, offset)?.1;
+        // Value type
+        offset += 1;
+    }
+    if offset > body.len() {
+        return Err(Error::parse_error(
+            "Local declarations exceed function body",
+        ));
+    }
+    Ok(offset)
+}
+
+/// Lower the hints of one function, given its body with local declarations
+pub fn lower_function_hints(
+    body: &[u8],
+    hints: &FunctionBranchHints,
+) -> Result<LoweredBranchHints> {
+    let mut lowered = LoweredBranchHints::default();
+    if hints.is_empty() {
+        return Ok(lowered);
+    }
+
+    let mut offset = skip_local_declarations(body)?;
+    let mut index = 0;
+    while offset < body.len() {
+        let (instruction, consumed) = parse_instruction(body, offset)?;
+        if matches!(instruction, Instruction::If { .. } | Instruction::BrIf(_)) {
+            if let Some(hint) = u32::try_from(offset).ok().and_then(|offset| hints.get_hint(offset))
+            {
+                lowered.hints.insert(index, hint);
+            }
+        }
+        offset += consumed;
+        index += 1;
+    }
+
+    Ok(lowered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // One i32 local; then local.get 0, if, nop, end, local.get 0, br_if 0,
+    // end
+    const BODY: [u8; 14] = [
+        0x01, 0x01, 0x7F, 0x20, 0x00, 0x04, 0x40, 0x01, 0x0B, 0x20, 0x00, 0x0D, 0x00, 0x0B,
+    ];
+
+    fn hints(entries: &[(u32, BranchHintValue)]) -> FunctionBranchHints {
+        let mut hints = FunctionBranchHints::new(0);
+        for (offset, hint) in entries {
+            hints.add_hint(*offset, *hint).unwrap();
+        }
+        hints
+    }
+
+    #[test]
+    fn test_lower_function_hints() {
+        let lowered = lower_function_hints(
+            &BODY,
+            &hints(&[
+                (5, BranchHintValue::LikelyFalse),
+                (11, BranchHintValue::LikelyTrue),
+                // local.get is not a branch
+                (3, BranchHintValue::LikelyTrue),
+                // Within the if instruction
+                (6, BranchHintValue::LikelyTrue),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(lowered.len(), 2);
+        assert_eq!(lowered.hint(1), Some(BranchHintValue::LikelyFalse));
+        assert_eq!(lowered.predicts_taken(1), Some(false));
+        assert_eq!(lowered.predicts_taken(5), Some(true));
+        assert_eq!(lowered.predicts_taken(0), None);
+    }
+
+    #[test]
+    fn test_lower_rejects_malformed_locals() {
+        let hints = hints(&[(5, BranchHintValue::LikelyTrue)]);
+        assert!(lower_function_hints(&[0x02, 0x01, 0x7F], &hints).is_err());
+        assert!(
+            lower_function_hints(&[0x02, 0x01, 0x7F], &FunctionBranchHints::new(0))
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -946,34 +946,7 @@ fn handle_potential_cfi_violation(
 
     /// Get current timestamp for temporal validation
     fn get_timestamp(&self) -> u64 {
-        // Platform-specific timestamp implementation
-        #[cfg(target_arch = "aarch64")]
-        {
-            // TODO: Replace with safe hardware timer access
-            // For now, use a simple fallback based on atomic counter
-            use core::sync::atomic::{
-                AtomicU64,
-                Ordering,
-            };
-            static COUNTER: AtomicU64 = AtomicU64::new(0);
-            COUNTER.fetch_add(1, Ordering::Relaxed)
-        }
-        #[cfg(target_arch = "riscv64")]
-        {
-            // TODO: Replace with safe hardware timer access
-            // For now, use a simple fallback based on atomic counter
-            use core::sync::atomic::{
-                AtomicU64,
-                Ordering,
-            };
-            static COUNTER: AtomicU64 = AtomicU64::new(0);
-            COUNTER.fetch_add(1, Ordering::Relaxed)
-        }
-        #[cfg(not(any(target_arch = "aarch64", target_arch = "riscv64")))]
-        {
-            // Software fallback - use a simple counter
-            0
-        }
+        crate::time_source::timestamp_ns()
     }
 
     /// Generate unique call site ID
@@ -315,6 +315,6 @@ fn test_factory_access() {
 
         // Test that we can create bounded strings using the factory
         let bounded_str = factory.create_bounded_string::<64>("test").unwrap();
-        assert_eq!(bounded_str.as_str(), "test");
+        assert_eq!(bounded_str.as_str().unwrap(), "test");
     }
 }
@@ -0,0 +1,261 @@
+//! Integrity of loaded code
+//!
+//! A bit-flip in the RAM holding a loaded module silently changes what the
+//! engine runs. [`CodeChecksums`] records a checksum of every function of a
+//! module, covering its type, locals and compiled body, and one over its
+//! tables and element segments when it is loaded, so that they can be
+//! verified again before the code runs.
+//!
+//! How often the engine verifies them follows its integrity level, see
+//! [`CapabilityAwareEngine::set_code_integrity`](crate::engine::CapabilityAwareEngine::set_code_integrity):
+//!
+//! | Level                 | Verified                                         |
+//! |-----------------------|--------------------------------------------------|
+//! | `Off`                 | never                                            |
+//! | `Basic`, `Standard`   | the whole module every [`CHECK_INTERVAL`] calls  |
+//! | `Sampling`            | the called function on sampled calls             |
+//! | `Full`                | the called function on every call                |
+//! | `Redundant`           | the whole module on every call                   |
+//!
+//! Tables are checked as the module defines them: the contents of the tables
+//! of an instance change as it runs and are not covered.
+
+use core::sync::atomic::{
+    AtomicU32,
+    Ordering,
+};
+
+use wrt_foundation::{
+    traits::Checksummable,
+    verification::{
+        Checksum,
+        VerificationLevel,
+    },
+};
+
+use crate::{
+    module::Module,
+    prelude::*,
+};
+
+/// Calls between verifications of a whole module at the `Basic` and
+/// `Standard` levels
+pub const CHECK_INTERVAL: u32 = 64;
+
+/// Checksums of the code of a module, taken when it was loaded
+#[derive(Debug)]
+pub struct CodeChecksums {
+    functions: Vec<Checksum>,
+    tables:    Checksum,
+    /// Calls since the module was last verified as a whole
+    calls:     AtomicU32,
+}
+
+/// Checksum of function `func_idx` of `module`, including the code the
+/// interpreter runs for it
+fn function_checksum(module: &Module, func_idx: u32) -> Option<Checksum> {
+    let mut checksum = Checksum::new();
+    module.get_function(func_idx)?.update_checksum(&mut checksum);
+    let defined = (func_idx as usize).checked_sub(module.code.imports.len());
+    if let Some(code) = defined.and_then(|index| module.code.functions.get(index)) {
+        code.update_checksum(&mut checksum);
+    }
+    Some(checksum)
+}
+
+fn tables_checksum(module: &Module) -> Checksum {
+    let mut checksum = Checksum::new();
+    module.tables.update_checksum(&mut checksum);
+    module.elements.update_checksum(&mut checksum);
+    checksum
+}
+
+impl CodeChecksums {
+    /// Checksum the functions, tables and element segments of `module`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a function of the module cannot be read.
+    pub fn compute(module: &Module) -> Result<Self> {
+        let functions = (0..module.functions.len() as u32)
+            .map(|func_idx| {
+                function_checksum(module, func_idx).ok_or_else(|| {
+                    Error::runtime_function_not_found("Function index out of bounds")
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            functions,
+            tables: tables_checksum(module),
+            calls: AtomicU32::new(0),
+        })
+    }
+
+    /// Number of functions checksummed
+    pub fn function_count(&self) -> usize {
+        self.functions.len()
+    }
+
+    /// Verify function `func_idx` of `module`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the function no longer matches its checksum or the
+    /// function was not checksummed.
+    pub fn verify_function(&self, module: &Module, func_idx: u32) -> Result<()> {
+        let expected = self
+            .functions
+            .get(func_idx as usize)
+            .ok_or_else(|| Error::runtime_function_not_found("Function was not checksummed"))?;
+        let checksum = function_checksum(module, func_idx)
+            .ok_or_else(|| Error::runtime_function_not_found("Function index out of bounds"))?;
+        if checksum != *expected {
+            return Err(Error::memory_corruption_detected(
+                "Function does not match its checksum",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Verify all functions, tables and element segments of `module`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of them no longer matches its checksum.
+    pub fn verify_module(&self, module: &Module) -> Result<()> {
+        if module.functions.len() != self.functions.len() {
+            return Err(Error::memory_corruption_detected(
+                "Module function count does not match its checksums",
+            ));
+        }
+        for func_idx in 0..self.functions.len() {
+            self.verify_function(module, func_idx as u32)?;
+        }
+        if tables_checksum(module) != self.tables {
+            return Err(Error::memory_corruption_detected(
+                "Module tables do not match their checksum",
+            ));
+        }
+        self.calls.store(0, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Verify what `level` asks for before calling function `func_idx` of
+    /// `module`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the verified code no longer matches its
+    /// checksums.
+    pub fn check_call(
+        &self,
+        level: VerificationLevel,
+        module: &Module,
+        func_idx: u32,
+    ) -> Result<()> {
+        match level {
+            VerificationLevel::Off => Ok(()),
+            VerificationLevel::Basic | VerificationLevel::Standard => {
+                if self.calls.fetch_add(1, Ordering::Relaxed) + 1 >= CHECK_INTERVAL {
+                    self.verify_module(module)
+                } else {
+                    Ok(())
+                }
+            },
+            VerificationLevel::Sampling => {
+                if level.should_verify(128) {
+                    self.verify_function(module, func_idx)
+                } else {
+                    Ok(())
+                }
+            },
+            VerificationLevel::Full => self.verify_function(module, func_idx),
+            VerificationLevel::Redundant => self.verify_module(module),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wrt_foundation::{
+        memory_init::MemoryInitializer,
+        types::ValueType,
+    };
+
+    use super::*;
+    use crate::{
+        module::Function,
+        type_conversion::convert_locals_to_bounded,
+    };
+
+    fn module_with_function(type_idx: u32) -> Module {
+        MemoryInitializer::ensure_initialized().unwrap();
+        let mut module = Module::empty();
+        module
+            .functions
+            .push(Function {
+                type_idx,
+                ..Function::default()
+            })
+            .unwrap();
+        module
+    }
+
+    #[test]
+    fn test_corrupted_function_is_detected() {
+        let module = module_with_function(0);
+        let checksums = CodeChecksums::compute(&module).unwrap();
+        assert_eq!(checksums.function_count(), 1);
+        assert!(checksums.verify_module(&module).is_ok());
+
+        let flipped = module_with_function(4);
+        assert!(checksums.verify_function(&flipped, 0).is_err());
+        assert!(checksums.check_call(VerificationLevel::Full, &flipped, 0).is_err());
+        assert!(checksums.check_call(VerificationLevel::Off, &flipped, 0).is_ok());
+
+        // The periodic levels only catch it once the interval has passed
+        for _ in 1..CHECK_INTERVAL {
+            assert!(checksums.check_call(VerificationLevel::Standard, &flipped, 0).is_ok());
+        }
+        assert!(checksums.check_call(VerificationLevel::Standard, &flipped, 0).is_err());
+    }
+
+    #[test]
+    fn test_function_checksum_covers_locals() {
+        MemoryInitializer::ensure_initialized().unwrap();
+        let checksum = |locals: &[ValueType]| {
+            let mut checksum = Checksum::new();
+            Function {
+                locals: convert_locals_to_bounded(locals).unwrap(),
+                ..Function::default()
+            }
+            .update_checksum(&mut checksum);
+            checksum
+        };
+
+        let original = checksum(&[ValueType::I32]);
+        assert_eq!(checksum(&[ValueType::I32]), original);
+        assert_ne!(
+            checksum(&[ValueType::I32, ValueType::I32, ValueType::I32]),
+            original
+        );
+    }
+
+    #[cfg(feature = "wat")]
+    #[test]
+    fn test_function_checksum_covers_compiled_code() {
+        let mut module = Module::from_wat(
+            r#"(module
+                (import "env" "f" (func))
+                (func (result i32) i32.const 1))"#,
+        )
+        .unwrap();
+        let checksums = CodeChecksums::compute(&module).unwrap();
+        assert_eq!(checksums.function_count(), 2);
+        assert!(checksums.verify_module(&module).is_ok());
+
+        Arc::make_mut(&mut module.code).functions[0].ops[0].a = 2;
+        assert!(checksums.verify_function(&module, 1).is_err());
+        assert!(checksums.verify_function(&module, 0).is_ok());
+    }
+}
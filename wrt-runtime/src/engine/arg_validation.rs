@@ -0,0 +1,222 @@
+//! Argument validation and coercion for exported function calls
+//!
+//! Arguments supplied by the embedder are checked against the parameter types
+//! of the export's function type before execution starts. A mismatch is
+//! reported as an [`ArgumentMismatch`] listing the expected and the provided
+//! types, rather than surfacing later as undefined behaviour inside the
+//! interpreter.
+//!
+//! Lossless coercions can be enabled through [`ArgumentCoercion`]; all of them
+//! are disabled by default so that only exact matches are accepted.
+
+use core::fmt;
+
+use wrt_foundation::{
+    float_repr::FloatBits64,
+    types::ValueType,
+    values::Value,
+};
+
+use crate::prelude::{
+    Error,
+    Vec,
+};
+
+/// Optional coercions applied to arguments of exported function calls
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ArgumentCoercion {
+    /// Sign-extend `i32` arguments passed for `i64` parameters
+    pub widen_i32_to_i64:   bool,
+    /// Promote `f32` arguments passed for `f64` parameters
+    pub promote_f32_to_f64: bool,
+}
+
+impl ArgumentCoercion {
+    /// Accept only arguments whose types match the parameters exactly
+    pub const fn strict() -> Self {
+        Self {
+            widen_i32_to_i64:   false,
+            promote_f32_to_f64: false,
+        }
+    }
+
+    /// Enable every lossless coercion
+    pub const fn lossless() -> Self {
+        Self {
+            widen_i32_to_i64:   true,
+            promote_f32_to_f64: true,
+        }
+    }
+
+    /// Coerce `value` to `expected`, or return `None` if not permitted
+    fn coerce(&self, value: &Value, expected: ValueType) -> Option<Value> {
+        match (value, expected) {
+            (value, expected) if value.value_type() == expected => Some(value.clone()),
+            (Value::I32(v), ValueType::I64) if self.widen_i32_to_i64 => {
+                Some(Value::I64(i64::from(*v)))
+            },
+            (Value::F32(v), ValueType::F64) if self.promote_f32_to_f64 => {
+                Some(Value::F64(FloatBits64::from_float(f64::from(v.value()))))
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Arguments that do not match an exported function's parameters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArgumentMismatch {
+    /// Parameter types of the export
+    pub expected: Vec<ValueType>,
+    /// Types of the supplied arguments
+    pub provided: Vec<ValueType>,
+    /// Index of the first mismatching argument, `None` for an arity mismatch
+    pub position: Option<usize>,
+}
+
+impl ArgumentMismatch {
+    /// Check whether the number of arguments was wrong
+    pub fn is_arity_mismatch(&self) -> bool {
+        self.position.is_none()
+    }
+}
+
+/// Write a parenthesized, comma-separated type list
+fn fmt_types(f: &mut fmt::Formatter<'_>, types: &[ValueType]) -> fmt::Result {
+    f.write_str("(")?;
+    for (i, ty) in types.iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        write!(f, "{ty}")?;
+    }
+    f.write_str(")")
+}
+
+impl fmt::Display for ArgumentMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.position {
+            Some(idx) => write!(
+                f,
+                "argument {idx} has type {}, expected {}: ",
+                self.provided[idx], self.expected[idx]
+            )?,
+            None => write!(
+                f,
+                "expected {} arguments, got {}: ",
+                self.expected.len(),
+                self.provided.len()
+            )?,
+        }
+        f.write_str("expected ")?;
+        fmt_types(f, &self.expected)?;
+        f.write_str(", provided ")?;
+        fmt_types(f, &self.provided)
+    }
+}
+
+impl From<ArgumentMismatch> for Error {
+    fn from(mismatch: ArgumentMismatch) -> Self {
+        if mismatch.is_arity_mismatch() {
+            Error::runtime_invalid_argument("Wrong number of arguments for exported function")
+        } else {
+            Error::runtime_type_mismatch("Argument type does not match exported function parameter")
+        }
+    }
+}
+
+/// Validate `args` against `params`, applying the permitted coercions.
+///
+/// Returns the (possibly coerced) arguments ready for execution.
+pub fn validate_arguments(
+    params: &[ValueType],
+    args: &[Value],
+    coercion: ArgumentCoercion,
+) -> core::result::Result<Vec<Value>, ArgumentMismatch> {
+    let mismatch = |position| ArgumentMismatch {
+        expected: params.to_vec(),
+        provided: args.iter().map(Value::value_type).collect(),
+        position,
+    };
+
+    if params.len() != args.len() {
+        return Err(mismatch(None));
+    }
+
+    let mut coerced = Vec::with_capacity(args.len());
+    for (idx, (arg, param)) in args.iter().zip(params.iter()).enumerate() {
+        match coercion.coerce(arg, *param) {
+            Some(value) => coerced.push(value),
+            None => return Err(mismatch(Some(idx))),
+        }
+    }
+    Ok(coerced)
+}
+
+#[cfg(test)]
+mod tests {
+    use wrt_foundation::float_repr::FloatBits32;
+
+    use super::*;
+
+    #[test]
+    fn test_exact_match_and_arity() {
+        let params = [ValueType::I32, ValueType::F64];
+        let args = [Value::I32(1), Value::F64(FloatBits64::from_float(2.0))];
+        assert_eq!(
+            validate_arguments(&params, &args, ArgumentCoercion::default()).unwrap(),
+            args.to_vec()
+        );
+
+        let err = validate_arguments(&params, &args[..1], ArgumentCoercion::default())
+            .unwrap_err();
+        assert!(err.is_arity_mismatch());
+        assert_eq!(
+            err.to_string(),
+            "expected 2 arguments, got 1: expected (I32, F64), provided (I32)"
+        );
+    }
+
+    #[test]
+    fn test_widening_is_opt_in() {
+        let params = [ValueType::I64];
+        let args = [Value::I32(-3)];
+
+        let err = validate_arguments(&params, &args, ArgumentCoercion::strict()).unwrap_err();
+        assert_eq!(err.position, Some(0));
+        assert_eq!(
+            err.to_string(),
+            "argument 0 has type I32, expected I64: expected (I64), provided (I32)"
+        );
+
+        let coercion = ArgumentCoercion {
+            widen_i32_to_i64: true,
+            ..ArgumentCoercion::default()
+        };
+        assert_eq!(
+            validate_arguments(&params, &args, coercion).unwrap(),
+            vec![Value::I64(-3)]
+        );
+    }
+
+    #[test]
+    fn test_no_narrowing() {
+        let lossless = ArgumentCoercion::lossless();
+        assert!(validate_arguments(&[ValueType::I32], &[Value::I64(1)], lossless).is_err());
+        assert!(validate_arguments(
+            &[ValueType::F32],
+            &[Value::F64(FloatBits64::from_float(1.0))],
+            lossless
+        )
+        .is_err());
+        assert_eq!(
+            validate_arguments(
+                &[ValueType::F64],
+                &[Value::F32(FloatBits32::from_float(1.5))],
+                lossless
+            )
+            .unwrap(),
+            vec![Value::F64(FloatBits64::from_float(1.5))]
+        );
+    }
+}
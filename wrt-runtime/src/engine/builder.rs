@@ -15,6 +15,7 @@
 };
 
 use crate::engine::{
+    ArgumentCoercion,
     CapabilityAwareEngine,
     EnginePreset,
 };
@@ -30,6 +31,8 @@ pub struct EngineBuilder {
     custom_context:  Option<MemoryCapabilityContext>,
     /// Resource limits configuration from binary
     resource_config: Option<ASILExecutionConfig>,
+    /// Coercions permitted for arguments of exported functions
+    arg_coercion:    ArgumentCoercion,
 }
 
 impl EngineBuilder {
@@ -40,6 +43,7 @@ pub fn new() -> Self {
             preset:          None,
             custom_context:  None,
             resource_config: None,
+            arg_coercion:    ArgumentCoercion::default(),
         }
     }
 
@@ -67,6 +71,12 @@ pub fn with_resource_config(mut self, config: ASILExecutionConfig) -> Self {
         self
     }
 
+    /// Set the coercions permitted for arguments of exported functions
+    pub fn with_argument_coercion(mut self, coercion: ArgumentCoercion) -> Self {
+        self.arg_coercion = coercion;
+        self
+    }
+
     /// Create an engine for QM (Quality Management) level
     pub fn qm() -> Self {
         Self::new().with_preset(EnginePreset::QM)
@@ -116,6 +126,13 @@ pub fn from_binary(binary: &[u8]) -> Result<Self> {
 
     /// Build the engine with the configured settings
     pub fn build(self) -> Result<CapabilityAwareEngine> {
+        let arg_coercion = self.arg_coercion;
+        let mut engine = self.build_engine()?;
+        engine.set_argument_coercion(arg_coercion);
+        Ok(engine)
+    }
+
+    fn build_engine(self) -> Result<CapabilityAwareEngine> {
         // Priority order: custom_context > preset > asil_level > default QM
 
         if let Some(context) = self.custom_context {
@@ -152,6 +169,7 @@ fn default() -> Self {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::engine::CapabilityEngine;
 
     #[test]
     fn test_builder_qm() {
@@ -6,21 +6,49 @@
 
 #[cfg(all(feature = "alloc", not(feature = "std")))]
 use alloc::sync::Arc;
-use core::sync::atomic::{
-    AtomicU32,
-    Ordering,
+use core::{
+    sync::atomic::{
+        AtomicU32,
+        Ordering,
+    },
+    time::Duration,
 };
 #[cfg(feature = "std")]
-use std::sync::Arc;
+use std::sync::{
+    Arc,
+    Mutex,
+};
 
 // Import decoder function
-use wrt_decoder::decoder::decode_module;
+use wrt_decoder::{
+    decoder::decode_module,
+    float_usage::uses_floats,
+};
+#[cfg(feature = "std")]
+use wrt_decoder::{
+    branch_hint_section::read_branch_hint_section,
+    custom_section_handler::{
+        CustomSectionRegistry,
+        CustomSectionValues,
+    },
+    name_section::{
+        read_name_section,
+        FunctionSymbol,
+        NameMap,
+    },
+    target_features_section::read_target_features_section,
+};
 // Import execution configuration from wrt-foundation where it belongs
 use wrt_foundation::execution::{
     extract_resource_limits_from_binary,
     ASILExecutionConfig,
     ASILExecutionMode,
 };
+use wrt_error::{
+    codes,
+    ContextFrame,
+    ResultExt,
+};
 use wrt_foundation::{
     bounded_collections::BoundedMap,
     budget_aware_provider::CrateId,
@@ -30,25 +58,139 @@
     },
     safe_managed_alloc,
     safe_memory::NoStdProvider,
+    static_memory::BudgetReport,
     traits::{
         ReadStream,
         WriteStream,
     },
+    types::ValueType,
     values::Value,
 };
+#[cfg(feature = "std")]
+use wrt_foundation::{
+    host_ref::HostRefTable,
+    values::ExternRef,
+    verification::VerificationLevel,
+};
+#[cfg(feature = "std")]
+use wrt_host::CloneableFn;
 use wrt_host::{
     BoundedHostIntegrationManager,
     CallbackRegistry,
     HostBuilder,
     HostIntegrationLimits,
 };
+#[cfg(feature = "std")]
+use wrt_intercept::strategies::{
+    AttributeValue,
+    SpanKind,
+    SpanStatus,
+    Tracer,
+};
+#[cfg(feature = "std")]
+use wrt_logging::GuestLogger;
 
+use super::arg_validation::{
+    validate_arguments,
+    ArgumentCoercion,
+    ArgumentMismatch,
+};
+#[cfg(feature = "std")]
+use super::result_stream::ResultStream;
+use super::safety_report::SafetyReport;
+#[cfg(feature = "std")]
+use super::trap_info::{
+    is_trap,
+    take_trap_message,
+    TrapInfo,
+};
+#[cfg(feature = "std")]
+use crate::accounting::{
+    Accounting,
+    Metered,
+    TenantId,
+};
+#[cfg(feature = "std")]
+use crate::branch_hints::ModuleBranchHints;
+#[cfg(feature = "std")]
+use crate::code_integrity::CodeChecksums;
+#[cfg(feature = "std")]
+use crate::uninit_memory::{
+    InitTracker,
+    UninitReadLog,
+    UninitReadMode,
+};
+#[cfg(feature = "std")]
+use crate::growth_observer::{
+    GrowthRequester,
+    MemoryObserver,
+};
+#[cfg(feature = "std")]
+use crate::event_log::LoggedGrowth;
+#[cfg(not(feature = "std"))]
+use crate::execution_backend::NoHostImports;
+#[cfg(feature = "std")]
+use crate::guest_coverage::GuestCoverage;
+#[cfg(feature = "std")]
+use crate::heap_profile::{
+    AllocatorCall,
+    HeapProfile,
+    ProfiledHeap,
+};
+#[cfg(feature = "std")]
+use crate::host_call::HostCallContext;
+#[cfg(feature = "jit")]
+use crate::jit::{
+    jit_permitted,
+    JitBackend,
+};
+#[cfg(feature = "std")]
+use crate::lowering_cache::LoweringCache;
+#[cfg(feature = "std")]
+use crate::metrics::MetricsSnapshot;
+#[cfg(feature = "std")]
+use crate::module_signature::SignaturePolicy;
+#[cfg(feature = "sampling-profiler")]
+use crate::sampling_profiler::{
+    GuestFrame,
+    ProfiledCall,
+    SamplingProfiler,
+};
+#[cfg(feature = "std")]
+use crate::stackless::interpreter::{
+    initial_global,
+    initialize_memories,
+    initialize_table,
+};
+#[cfg(feature = "std")]
+use crate::state::InstanceSnapshot;
 use crate::{
     bounded_runtime_infra::BaseRuntimeProvider,
-    module::Module,
+    event_log::{
+        Event,
+        EventRecorder,
+    },
+    execution_backend::{
+        ExecutionBackend,
+        HostImports,
+    },
+    externs::{
+        Extern,
+        GlobalValue,
+    },
+    interrupt::InterruptHandle,
+    module::{
+        GlobalWrapper,
+        MemoryWrapper,
+        Module,
+        TableWrapper,
+    },
     module_instance::ModuleInstance,
     prelude::*,
-    stackless::StacklessEngine,
+    stackless::{
+        NativeStackUsage,
+        StacklessEngine,
+    },
 };
 
 /// Handle for a loaded module
@@ -196,9 +338,18 @@ pub struct CapabilityAwareEngine {
     context:           MemoryCapabilityContext,
     /// Engine preset used for resource limit extraction
     preset:            EnginePreset,
-    /// Loaded modules indexed by handle
+    /// Loaded modules indexed by handle, in builds without `std`
+    #[cfg(not(feature = "std"))]
     modules:           BoundedMap<ModuleHandle, Module, MAX_MODULES, BaseRuntimeProvider>,
-    /// Module instances indexed by handle  
+    /// Loaded modules, as they were lowered, indexed by handle
+    ///
+    /// Unlike `modules`, which keeps only a summary of each module, these
+    /// are what instances are created from.
+    #[cfg(feature = "std")]
+    loaded_modules:    HashMap<ModuleHandle, Arc<Module>>,
+    /// Module instances indexed by handle, which builds with `std` look up
+    /// in the inner engine instead
+    #[cfg(not(feature = "std"))]
     instances: BoundedMap<InstanceHandle, ModuleInstance, MAX_INSTANCES, BaseRuntimeProvider>,
     /// Next instance index
     next_instance_idx: usize,
@@ -206,6 +357,185 @@ pub struct CapabilityAwareEngine {
     host_registry:     Option<CallbackRegistry>,
     /// Bounded host integration manager for safety-critical environments
     host_manager:      Option<BoundedHostIntegrationManager>,
+    /// Coercions permitted when validating arguments of exported functions
+    arg_coercion:      ArgumentCoercion,
+    /// Whether modules using floating-point values are refused
+    reject_floats:     bool,
+    /// Target features modules may depend on, or `None` to accept any
+    #[cfg(feature = "std")]
+    target_features:   Option<Vec<String>>,
+    /// Memories defined for import resolution, keyed by module and field name
+    #[cfg(feature = "std")]
+    defined_memories:  HashMap<(String, String), MemoryWrapper>,
+    /// Memory imports of each loaded module, in import order
+    #[cfg(feature = "std")]
+    memory_imports:    HashMap<ModuleHandle, Vec<MemoryImport>>,
+    /// Tables defined for import resolution, keyed by module and field name
+    #[cfg(feature = "std")]
+    defined_tables:    HashMap<(String, String), TableWrapper>,
+    /// Table imports of each loaded module, in import order
+    #[cfg(feature = "std")]
+    table_imports:     HashMap<ModuleHandle, Vec<TableImport>>,
+    /// Globals defined for import resolution, keyed by module and field name
+    #[cfg(feature = "std")]
+    defined_globals:   HashMap<(String, String), GlobalWrapper>,
+    /// Global imports of each loaded module, in import order
+    #[cfg(feature = "std")]
+    global_imports:    HashMap<ModuleHandle, Vec<GlobalImport>>,
+    /// Snapshots of pre-initialized modules their instances start from
+    #[cfg(feature = "std")]
+    snapshots:         HashMap<ModuleHandle, Arc<InstanceSnapshot>>,
+    /// Trap of the most recent call to an exported function
+    #[cfg(feature = "std")]
+    last_trap:         Option<TrapInfo>,
+    /// Observer attached to the memories and tables of new instances
+    #[cfg(feature = "std")]
+    memory_observer:   Option<Arc<dyn MemoryObserver>>,
+    /// Tenant charged for the resources of new instances and invocations
+    #[cfg(feature = "std")]
+    tenant:            Option<(Arc<Accounting>, TenantId)>,
+    /// Name sections of loaded modules
+    #[cfg(feature = "std")]
+    module_names:      HashMap<ModuleHandle, Arc<NameMap>>,
+    /// Name sections of the modules of instances
+    #[cfg(feature = "std")]
+    instance_names:    HashMap<InstanceHandle, Arc<NameMap>>,
+    /// Branch hints of loaded modules, lowered onto their instructions
+    #[cfg(feature = "std")]
+    branch_hints:      HashMap<ModuleHandle, ModuleBranchHints>,
+    /// Whether coverage of guest code is collected for modules loaded
+    #[cfg(feature = "std")]
+    collect_coverage:  bool,
+    /// Guest code coverage of loaded modules
+    #[cfg(feature = "std")]
+    module_coverage:   HashMap<ModuleHandle, Arc<GuestCoverage>>,
+    /// Host objects referenced by externref values
+    #[cfg(feature = "std")]
+    host_refs:         Arc<Mutex<HostRefTable>>,
+    /// Handlers deserializing custom sections of modules loaded
+    #[cfg(feature = "std")]
+    section_registry:  CustomSectionRegistry,
+    /// Values deserialized from custom sections of loaded modules
+    #[cfg(feature = "std")]
+    section_values:    HashMap<ModuleHandle, CustomSectionValues>,
+    /// Signatures modules must carry to be loaded, if any
+    #[cfg(feature = "std")]
+    signature_policy:  Option<SignaturePolicy>,
+    /// How often the code of modules loaded is verified against its
+    /// checksums
+    #[cfg(feature = "std")]
+    code_integrity:    VerificationLevel,
+    /// Checksums of the code of loaded modules
+    #[cfg(feature = "std")]
+    module_code:       HashMap<ModuleHandle, Arc<CodeChecksums>>,
+    /// Checksums of the code of the modules of instances
+    #[cfg(feature = "std")]
+    instance_code:     HashMap<InstanceHandle, Arc<CodeChecksums>>,
+    /// How reads of uninitialized memory of new instances are handled, if
+    /// they are checked
+    #[cfg(feature = "std")]
+    uninit_read_mode:  Option<UninitReadMode>,
+    /// Reads of uninitialized memory recorded so far
+    #[cfg(feature = "std")]
+    uninit_reads:      Arc<UninitReadLog>,
+    /// Whether the guest heap allocations of new instances are profiled
+    #[cfg(feature = "std")]
+    heap_profiling:    bool,
+    /// Heap profiles of instances created with heap profiling enabled
+    #[cfg(feature = "std")]
+    heap_profiles:     HashMap<InstanceHandle, Arc<HeapProfile>>,
+    /// Call site allocator calls are attributed to
+    #[cfg(feature = "std")]
+    allocation_site:   Option<String>,
+    /// Exports executed, successfully or not
+    #[cfg(feature = "std")]
+    invocations:       u64,
+    /// Exports that trapped
+    #[cfg(feature = "std")]
+    traps:             u64,
+    /// Tracer recording a span for every executed export, if any
+    #[cfg(feature = "std")]
+    tracer:            Option<Arc<Tracer>>,
+    /// Profiler sampling the guest call stack, if any
+    #[cfg(feature = "sampling-profiler")]
+    sampling_profiler: Option<Arc<SamplingProfiler>>,
+    /// Log of events for post-mortem diagnosis, if any
+    event_log:         Option<&'static dyn EventRecorder>,
+    /// Cache of lowered modules, if any
+    #[cfg(feature = "std")]
+    lowering_cache:    Option<Arc<LoweringCache>>,
+    /// Backend compiling hot functions, if any
+    #[cfg(feature = "jit")]
+    jit:               Option<Arc<JitBackend>>,
+}
+
+/// Engine state at the start of an invocation metered for a tenant
+#[cfg(feature = "std")]
+struct Metering {
+    /// Fuel of the engine
+    fuel:         Option<u64>,
+    /// Whether the fuel of the invocation is limited by the quota instead
+    capped:       bool,
+    /// Fuel consumed by the engine so far
+    consumed:     u64,
+    /// Instructions executed by the engine so far
+    instructions: u64,
+}
+
+/// A memory import declared by a loaded module
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+struct MemoryImport {
+    module: String,
+    name:   String,
+    ty:     CoreMemoryType,
+}
+
+/// A table import declared by a loaded module
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+struct TableImport {
+    module: String,
+    name:   String,
+    ty:     wrt_foundation::types::TableType,
+}
+
+/// A global import declared by a loaded module
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub(crate) struct GlobalImport {
+    pub(crate) module: String,
+    pub(crate) name:   String,
+    pub(crate) ty:     wrt_foundation::types::GlobalType,
+}
+
+/// Serves the function imports of instances from the host function registry
+#[cfg(feature = "std")]
+struct RegisteredImports<'a> {
+    engine:   &'a StacklessEngine,
+    registry: Option<&'a CallbackRegistry>,
+}
+
+#[cfg(feature = "std")]
+impl HostImports for RegisteredImports<'_> {
+    fn call_import(
+        &mut self,
+        instance_id: usize,
+        module: &str,
+        name: &str,
+        args: Vec<Value>,
+    ) -> Result<Vec<Value>> {
+        let registry = self
+            .registry
+            .filter(|registry| registry.has_host_function(module, name))
+            .ok_or_else(|| Error::resource_not_found("Function import not defined"))?;
+        let caller = self
+            .engine
+            .instance(instance_id)
+            .ok_or_else(|| Error::resource_not_found("Instance not found"))?;
+        let mut context = HostCallContext::new(instance_id as u32, caller);
+        registry.call_host_function(&mut context, module, name, args)
+    }
 }
 
 impl CapabilityAwareEngine {
@@ -232,19 +562,9 @@ pub fn with_context_and_preset(
         context: MemoryCapabilityContext,
         preset: EnginePreset,
     ) -> Result<Self> {
-        // Use simple NoStdProvider directly for internal structures to avoid recursion
-        // These are internal engine data structures and don't need full capability
-        // checking
-        let modules_provider = BaseRuntimeProvider::default();
-        let instances_provider = BaseRuntimeProvider::default();
-
         // Initialize host integration based on preset
         let (host_registry, host_manager) = Self::create_host_integration(&preset)?;
 
-        // Create BoundedMaps for engine internal structures
-        let modules = BoundedMap::new(modules_provider)?;
-        let instances = BoundedMap::new(instances_provider)?;
-
         // Create the inner stackless engine
         let inner_engine = StacklessEngine::new();
 
@@ -252,11 +572,90 @@ pub fn with_context_and_preset(
             inner: inner_engine,
             context,
             preset,
-            modules,
-            instances,
+            // Use simple NoStdProvider directly for internal structures to avoid
+            // recursion. These are internal engine data structures and don't need
+            // full capability checking
+            #[cfg(not(feature = "std"))]
+            modules: BoundedMap::new(BaseRuntimeProvider::default())?,
+            #[cfg(feature = "std")]
+            loaded_modules: HashMap::new(),
+            #[cfg(not(feature = "std"))]
+            instances: BoundedMap::new(BaseRuntimeProvider::default())?,
             next_instance_idx: 0,
             host_registry,
             host_manager,
+            arg_coercion: ArgumentCoercion::default(),
+            reject_floats: false,
+            #[cfg(feature = "std")]
+            target_features: None,
+            #[cfg(feature = "std")]
+            defined_memories: HashMap::new(),
+            #[cfg(feature = "std")]
+            memory_imports: HashMap::new(),
+            #[cfg(feature = "std")]
+            defined_tables: HashMap::new(),
+            #[cfg(feature = "std")]
+            table_imports: HashMap::new(),
+            #[cfg(feature = "std")]
+            defined_globals: HashMap::new(),
+            #[cfg(feature = "std")]
+            global_imports: HashMap::new(),
+            #[cfg(feature = "std")]
+            snapshots: HashMap::new(),
+            #[cfg(feature = "std")]
+            last_trap: None,
+            #[cfg(feature = "std")]
+            memory_observer: None,
+            #[cfg(feature = "std")]
+            tenant: None,
+            #[cfg(feature = "std")]
+            module_names: HashMap::new(),
+            #[cfg(feature = "std")]
+            instance_names: HashMap::new(),
+            #[cfg(feature = "std")]
+            branch_hints: HashMap::new(),
+            #[cfg(feature = "std")]
+            collect_coverage: false,
+            #[cfg(feature = "std")]
+            module_coverage: HashMap::new(),
+            #[cfg(feature = "std")]
+            #[cfg(feature = "std")]
+            host_refs: Arc::new(Mutex::new(HostRefTable::new())),
+            #[cfg(feature = "std")]
+            section_registry: CustomSectionRegistry::new(),
+            #[cfg(feature = "std")]
+            section_values: HashMap::new(),
+            #[cfg(feature = "std")]
+            signature_policy: None,
+            #[cfg(feature = "std")]
+            code_integrity: VerificationLevel::Off,
+            #[cfg(feature = "std")]
+            module_code: HashMap::new(),
+            #[cfg(feature = "std")]
+            instance_code: HashMap::new(),
+            #[cfg(feature = "std")]
+            uninit_read_mode: None,
+            #[cfg(feature = "std")]
+            uninit_reads: Arc::new(UninitReadLog::new()),
+            #[cfg(feature = "std")]
+            heap_profiling: false,
+            #[cfg(feature = "std")]
+            heap_profiles: HashMap::new(),
+            #[cfg(feature = "std")]
+            allocation_site: None,
+            #[cfg(feature = "std")]
+            invocations: 0,
+            #[cfg(feature = "std")]
+            traps: 0,
+            #[cfg(feature = "std")]
+            tracer: None,
+            #[cfg(feature = "sampling-profiler")]
+            sampling_profiler: None,
+            event_log: None,
+            #[cfg(feature = "std")]
+            lowering_cache: None,
+            #[cfg(feature = "jit")]
+            jit: None,
         })
     }
 
@@ -329,11 +728,13 @@ pub fn register_host_function<F>(
     {
         #[cfg(feature = "std")]
         {
-            if let Some(ref _mut_registry) = self.host_registry {
-                // TODO: Implement host function registration when CallbackRegistry API is
-                // available The function signature needs to match what
-                // HostFunctionHandler expects For now, return success as
-                // placeholder
+            if let Some(registry) = self.host_registry.as_mut() {
+                let func = Arc::new(func);
+                registry.register_host_function(
+                    module_name,
+                    func_name,
+                    CloneableFn::new_with_args(move |_, args| func(&args)),
+                );
                 Ok(())
             } else {
                 Err(Error::not_supported_unsupported_operation(
@@ -356,6 +757,67 @@ pub fn register_host_function<F>(
         }
     }
 
+    /// Get the host function registry, if the preset allows dynamic host
+    /// functions
+    ///
+    /// Host providers such as the WASI provider register their functions
+    /// here.
+    pub fn host_registry_mut(&mut self) -> Option<&mut CallbackRegistry> {
+        self.host_registry.as_mut()
+    }
+
+    /// Serve the `wrt:logging` `log` host function with `logger`
+    ///
+    /// Guests log through [`call_host_function`](Self::call_host_function),
+    /// which hands the logger a [`HostCallContext`] reading the target and
+    /// message from the default memory of the calling instance.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the preset does not allow dynamic host functions.
+    #[cfg(feature = "std")]
+    pub fn enable_guest_logging(&mut self, logger: &GuestLogger) -> Result<()> {
+        let registry = self.host_registry.as_mut().ok_or_else(|| {
+            Error::not_supported_unsupported_operation(
+                "Host functions not supported in this configuration",
+            )
+        })?;
+        logger.register::<HostCallContext>(registry);
+        Ok(())
+    }
+
+    /// Call the host function `function` of `module` on behalf of
+    /// `instance`
+    ///
+    /// The host function receives a [`HostCallContext`] of the instance as
+    /// its engine.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the instance does not exist, the preset does not
+    /// allow dynamic host functions, the function is not registered, or the
+    /// function fails.
+    #[cfg(feature = "std")]
+    pub fn call_host_function(
+        &mut self,
+        instance: InstanceHandle,
+        module: &str,
+        function: &str,
+        args: Vec<Value>,
+    ) -> Result<Vec<Value>> {
+        let caller = self
+            .inner
+            .instance(instance.index())
+            .ok_or_else(|| Error::resource_not_found("Instance not found"))?;
+        let registry = self.host_registry.as_ref().ok_or_else(|| {
+            Error::not_supported_unsupported_operation(
+                "Host functions not supported in this configuration",
+            )
+        })?;
+        let mut context = HostCallContext::new(instance.index() as u32, caller);
+        registry.call_host_function(&mut context, module, function, args)
+    }
+
     /// Enable WASI support with the current capability constraints
     pub fn enable_wasi(&mut self) -> Result<()> {
         match self.preset {
@@ -411,6 +873,27 @@ fn load_module(&mut self, binary: &[u8]) -> Result<ModuleHandle> {
         let operation = MemoryOperation::Allocate { size: binary.len() };
         self.context.verify_operation(CrateId::Runtime, &operation)?;
 
+        #[cfg(feature = "std")]
+        if let Some(policy) = &self.signature_policy {
+            policy.verify(binary)?;
+        }
+
+        if self.reject_floats && uses_floats(binary)? {
+            return Err(Error::validation_error("Module uses floating-point values"));
+        }
+
+        #[cfg(feature = "std")]
+        if let Some(enabled) = &self.target_features {
+            if let Some(section) = read_target_features_section(binary)? {
+                if !section.missing(|name| enabled.iter().any(|feature| feature == name)).is_empty()
+                {
+                    return Err(Error::validation_error(
+                        "Module needs a target feature the engine has not enabled",
+                    ));
+                }
+            }
+        }
+
         // Extract resource limits from binary if available
         let asil_mode = self.preset_to_asil_mode();
         let _resource_config =
@@ -420,24 +903,154 @@ fn load_module(&mut self, binary: &[u8]) -> Result<ModuleHandle> {
         // This would integrate with the fuel async executor to enforce limits
 
         // Decode the module using wrt-decoder
-        let decoded = decode_module(binary)?;
+        let decoded = decode_module(binary).context("loading module")?;
 
-        // Convert to runtime module
-        let runtime_module = Module::from_wrt_module(&decoded)?;
+        // Convert to runtime module, unless the cache holds it lowered
+        #[cfg(feature = "std")]
+        let cached = self.lowering_cache.as_ref().and_then(|cache| cache.load(binary));
+        #[cfg(not(feature = "std"))]
+        let cached = None;
+        let runtime_module = match cached {
+            Some(module) => module,
+            None => {
+                let module = Module::from_wrt_module(&decoded)?;
+                // A cache that cannot be written only costs the next load
+                // its speed
+                #[cfg(feature = "std")]
+                if let Some(cache) = &self.lowering_cache {
+                    let _ = cache.store(binary, &module);
+                }
+                module
+            },
+        };
+
+        // Unlike the sections below, sections with a registered handler were
+        // asked for, so a malformed one fails the load
+        #[cfg(feature = "std")]
+        let section_values = self.section_registry.deserialize(binary)?;
+
+        // A pre-initialized module carries the state its instances start from
+        #[cfg(feature = "std")]
+        let snapshot = match InstanceSnapshot::from_binary(binary)? {
+            Some(snapshot) => {
+                snapshot.check_module(&runtime_module)?;
+                Some(Arc::new(snapshot))
+            },
+            None => None,
+        };
 
         // Create and store with unique handle
         let handle = ModuleHandle::new();
+        #[cfg(feature = "std")]
+        self.loaded_modules.insert(handle, Arc::new(runtime_module));
+        #[cfg(not(feature = "std"))]
         self.modules.insert(handle, runtime_module)?;
 
+        // Checksum the module as the engine holds it, which is what its
+        // instances run
+        #[cfg(feature = "std")]
+        if self.code_integrity != VerificationLevel::Off {
+            let stored = self.loaded_module(handle)?;
+            self.module_code.insert(handle, Arc::new(CodeChecksums::compute(&stored)?));
+        }
+
+        // A malformed name section only costs diagnostics their names, so it
+        // does not fail the load
+        #[cfg(feature = "std")]
+        if let Ok(Some(names)) = read_name_section(binary) {
+            self.module_names.insert(handle, Arc::new(names));
+        }
+
+        #[cfg(feature = "std")]
+        if !section_values.is_empty() {
+            self.section_values.insert(handle, section_values);
+        }
+
+        // Branch hints are only exposed, not executed, so invalid ones are
+        // dropped
+        #[cfg(feature = "std")]
+        if let Ok(Some(section)) = read_branch_hint_section(binary) {
+            if let Ok(hints) = ModuleBranchHints::lower(&decoded, &section) {
+                if !hints.is_empty() {
+                    self.branch_hints.insert(handle, hints);
+                }
+            }
+        }
+
+        #[cfg(feature = "std")]
+        if self.collect_coverage {
+            self.module_coverage.insert(
+                handle,
+                Arc::new(GuestCoverage::for_module(&self.loaded_modules[&handle])),
+            );
+        }
+
+        // Remember memory, table and global imports by name so instantiation
+        // can resolve them
+        #[cfg(feature = "std")]
+        {
+            let memory_imports: Vec<MemoryImport> = decoded
+                .imports
+                .iter()
+                .filter_map(|import| match &import.desc {
+                    wrt_format::module::ImportDesc::Memory(ty) => Some(MemoryImport {
+                        module: import.module.clone(),
+                        name:   import.name.clone(),
+                        ty:     CoreMemoryType {
+                            limits: ty.limits,
+                            shared: ty.shared,
+                        },
+                    }),
+                    _ => None,
+                })
+                .collect();
+            if !memory_imports.is_empty() {
+                self.memory_imports.insert(handle, memory_imports);
+            }
+
+            let table_imports: Vec<TableImport> = decoded
+                .imports
+                .iter()
+                .filter_map(|import| match &import.desc {
+                    wrt_format::module::ImportDesc::Table(ty) => Some(TableImport {
+                        module: import.module.clone(),
+                        name:   import.name.clone(),
+                        ty:     ty.clone(),
+                    }),
+                    _ => None,
+                })
+                .collect();
+            if !table_imports.is_empty() {
+                self.table_imports.insert(handle, table_imports);
+            }
+
+            let global_imports: Vec<GlobalImport> = decoded
+                .imports
+                .iter()
+                .filter_map(|import| match &import.desc {
+                    wrt_format::module::ImportDesc::Global(ty) => Some(GlobalImport {
+                        module: import.module.clone(),
+                        name:   import.name.clone(),
+                        ty:     wrt_foundation::types::GlobalType::new(ty.value_type, ty.mutable),
+                    }),
+                    _ => None,
+                })
+                .collect();
+            if !global_imports.is_empty() {
+                self.global_imports.insert(handle, global_imports);
+            }
+
+            if let Some(snapshot) = snapshot {
+                self.snapshots.insert(handle, snapshot);
+            }
+        }
+
         Ok(handle)
     }
 
     fn instantiate(&mut self, module_handle: ModuleHandle) -> Result<InstanceHandle> {
         // Get the module
-        let module = self
-            .modules
-            .get(&module_handle)?
-            .ok_or_else(|| Error::resource_not_found("Module not found"))?;
+        let module = self.loaded_module(module_handle)?;
 
         // Verify capability for instance allocation
         let operation = MemoryOperation::Allocate {
@@ -446,7 +1059,157 @@ fn instantiate(&mut self, module_handle: ModuleHandle) -> Result<InstanceHandle>
         self.context.verify_operation(CrateId::Runtime, &operation)?;
 
         // Create module instance
-        let instance = ModuleInstance::new(module.clone(), self.next_instance_idx)?;
+        let mut instance = ModuleInstance::new(module.clone(), self.next_instance_idx)?;
+
+        // Imported memories, tables and globals come first in their index
+        // spaces, followed by the ones the module defines itself, which start
+        // from the snapshot of a pre-initialized module
+        #[cfg(feature = "std")]
+        let snapshot = self.snapshots.get(&module_handle).cloned();
+        #[cfg(feature = "std")]
+        {
+            // Growth is reported under the handle the instance is registered
+            // with below
+            let observer = match &self.tenant {
+                Some((accounting, tenant)) => {
+                    Some(accounting.growth_observer(*tenant, self.memory_observer.clone()))
+                },
+                None => self.memory_observer.clone(),
+            };
+            let observer = match self.event_log {
+                Some(log) => {
+                    Some(Arc::new(LoggedGrowth::new(log, observer)) as Arc<dyn MemoryObserver>)
+                },
+                None => observer,
+            };
+            let mut pages = 0u64;
+            let mut entries = 0u64;
+            let instance_index = self.inner.next_instance_id();
+            let requester = |index: u32| GrowthRequester {
+                instance: instance_index,
+                index,
+            };
+
+            for import in &module.code.imports {
+                let defined = self.host_registry.as_ref().is_some_and(|registry| {
+                    registry.has_host_function(&import.module, &import.name)
+                });
+                if !defined {
+                    return Err(Error::resource_not_found("Function import not defined"));
+                }
+            }
+            for import in self.memory_imports.get(&module_handle).into_iter().flatten() {
+                let memory = self
+                    .defined_memories
+                    .get(&(import.module.clone(), import.name.clone()))
+                    .ok_or_else(|| Error::resource_not_found("Memory import not defined"))?;
+                memory.lock_read().check_import_compatibility(&import.ty)?;
+                instance.import_memory(memory.clone())?;
+            }
+            let imported_memories = self.memory_imports.get(&module_handle).map_or(0, Vec::len);
+            for (index, ty) in module.memory_types().enumerate() {
+                let mut memory = match &snapshot {
+                    Some(snapshot) => snapshot.restore_memory(index, ty)?,
+                    None => crate::memory::Memory::new(ty)?,
+                };
+                if let Some(observer) = &observer {
+                    let index = (imported_memories + index) as u32;
+                    memory.set_growth_observer(observer.clone(), requester(index));
+                }
+                if let Some(mode) = self.uninit_read_mode {
+                    let index = (imported_memories + index) as u32;
+                    let mut tracker = InitTracker::new(
+                        memory.size_in_bytes(),
+                        mode,
+                        instance_index,
+                        index,
+                        self.uninit_reads.clone(),
+                    );
+                    if snapshot.is_some() {
+                        tracker.mark_written(0, memory.size_in_bytes());
+                    }
+                    for segment in module.data.iter() {
+                        if let wrt_foundation::types::DataMode::Active {
+                            memory_index,
+                            offset,
+                        } = segment.mode
+                        {
+                            if memory_index == index {
+                                tracker.mark_written(offset as usize, segment.init.len());
+                            }
+                        }
+                    }
+                    memory.set_init_tracker(tracker);
+                }
+                pages += u64::from(memory.size());
+                instance.add_memory(memory)?;
+            }
+
+            // Globals come before tables and data, whose offsets may read
+            // them
+            for import in self.global_imports.get(&module_handle).into_iter().flatten() {
+                let global = self
+                    .defined_globals
+                    .get(&(import.module.clone(), import.name.clone()))
+                    .ok_or_else(|| Error::resource_not_found("Global import not defined"))?;
+                global.0.check_import_compatibility(&import.ty)?;
+                instance.import_global(global.clone())?;
+            }
+            let imported_globals = self.global_imports.get(&module_handle).map_or(0, Vec::len);
+            for (index, defined) in module.globals.iter().enumerate() {
+                let global = match &snapshot {
+                    Some(snapshot) => snapshot.restore_global(index, &defined.0)?,
+                    None => initial_global(&instance, imported_globals + index)?
+                        .unwrap_or_else(|| (*defined.0).clone()),
+                };
+                instance.add_global(global)?;
+            }
+
+            for import in self.table_imports.get(&module_handle).into_iter().flatten() {
+                let table = self
+                    .defined_tables
+                    .get(&(import.module.clone(), import.name.clone()))
+                    .ok_or_else(|| Error::resource_not_found("Table import not defined"))?;
+                table.0.check_import_compatibility(&import.ty)?;
+                instance.import_table(table.clone())?;
+            }
+            let imported_tables = self.table_imports.get(&module_handle).map_or(0, Vec::len);
+            if snapshot.is_none() {
+                for index in 0..imported_tables {
+                    initialize_table(&instance, index as u32, None)?;
+                }
+            }
+            for (index, defined) in module.tables.iter().enumerate() {
+                let table_idx = (imported_tables + index) as u32;
+                let mut table = match &snapshot {
+                    Some(snapshot) => snapshot.restore_table(index, defined.0.ty.clone())?,
+                    None => {
+                        let mut table = crate::table::Table::new(defined.0.ty.clone())?;
+                        initialize_table(&instance, table_idx, Some(&mut table))?;
+                        table
+                    },
+                };
+                if let Some(observer) = &observer {
+                    table.set_growth_observer(observer.clone(), requester(table_idx));
+                }
+                entries += u64::from(table.size());
+                instance.add_table(table)?;
+            }
+            if snapshot.is_none() {
+                initialize_memories(&instance)?;
+            }
+
+            // The tenant holds the initial sizes of the memories and tables
+            // for the lifetime of the engine; growth is charged by the
+            // observer
+            if let Some((accounting, tenant)) = &self.tenant {
+                accounting.charge(*tenant, Metered::MemoryPages, pages)?;
+                accounting
+                    .charge(*tenant, Metered::TableEntries, entries)
+                    .inspect_err(|_| accounting.release(*tenant, Metered::MemoryPages, pages))?;
+            }
+        }
+
         let instance_arc = Arc::new(instance.clone());
 
         // Register with inner engine
@@ -455,11 +1218,67 @@ fn instantiate(&mut self, module_handle: ModuleHandle) -> Result<InstanceHandle>
 
         // Store mapping
         let handle = InstanceHandle::from_index(instance_idx as usize);
+        #[cfg(not(feature = "std"))]
         self.instances.insert(handle, instance)?;
+        if let Some(log) = self.event_log {
+            log.record(Event::instantiation(handle.index(), module_handle.0));
+        }
+        #[cfg(feature = "std")]
+        if let Some(names) = self.module_names.get(&module_handle) {
+            self.instance_names.insert(handle, names.clone());
+        }
+        #[cfg(feature = "std")]
+        if let Some(coverage) = self.module_coverage.get(&module_handle) {
+            self.inner.coverage.insert(instance_idx, coverage.clone());
+        }
+        #[cfg(feature = "std")]
+        if let Some(checksums) = self.module_code.get(&module_handle) {
+            self.instance_code.insert(handle, checksums.clone());
+        }
+        #[cfg(feature = "std")]
+        if self.heap_profiling {
+            let profile = Arc::new(HeapProfile::new());
+            let heap = ProfiledHeap::new(profile.clone(), instance.module());
+            self.inner.heap_profiles.insert(instance_idx, Arc::new(heap));
+            self.heap_profiles.insert(handle, profile);
+        }
 
-        // Run start function if present
-        if let Some(start_idx) = module.start {
-            self.inner.execute(instance_idx as usize, start_idx as usize, vec![])?;
+        // Run start function if present; a snapshot already reflects it
+        #[cfg(feature = "std")]
+        let start = module.start.filter(|_| snapshot.is_none());
+        #[cfg(not(feature = "std"))]
+        let start = module.start;
+        if let Some(start_idx) = start {
+            #[cfg(feature = "std")]
+            if let Some(checksums) = self.instance_code.get(&handle) {
+                checksums.check_call(self.code_integrity, &module, start_idx)?;
+            }
+            self.inner.interruption.begin();
+            #[cfg(feature = "sampling-profiler")]
+            let call = self.profile_call(handle, start_idx);
+            #[cfg(feature = "std")]
+            let mut host = RegisteredImports {
+                engine:   &self.inner,
+                registry: self.host_registry.as_ref(),
+            };
+            #[cfg(not(feature = "std"))]
+            let mut host = NoHostImports;
+            let result = self
+                .inner
+                .execute(instance_idx, start_idx as usize, vec![], &mut host)
+                .with_context(|| {
+                    ContextFrame::new("running start function").with_function(start_idx)
+                });
+            #[cfg(feature = "sampling-profiler")]
+            drop(call);
+            self.inner.interruption.end();
+            if let (Some(log), Err(error)) = (self.event_log, &result) {
+                let consumed = self.inner.interruption.fuel_consumed();
+                if let Some(event) = Event::for_failure(handle.index(), start_idx, error, consumed) {
+                    log.record(event);
+                }
+            }
+            result?;
         }
 
         Ok(handle)
@@ -471,47 +1290,884 @@ fn execute(
         func_name: &str,
         args: &[Value],
     ) -> Result<Vec<Value>> {
+        #[cfg(feature = "std")]
+        {
+            self.last_trap = None;
+            self.invocations += 1;
+        }
+
         // Get the instance
-        let instance = self
-            .instances
-            .get(&instance_handle)?
-            .ok_or_else(|| Error::resource_not_found("Instance not found"))?;
+        let instance = self.live_instance(instance_handle)?;
 
         // Find the function by name using the new function resolution
         let func_idx = instance.module().validate_function_call(func_name)?;
 
-        // Set current module for execution
-        self.inner.set_current_module(Arc::new(instance.clone()))?;
+        // Reject arguments that do not match the signature before executing
+        let args = Self::validate_call_arguments(&instance, func_idx, args, self.arg_coercion)?
+            .map_err(Error::from)?;
 
-        // Execute the function
-        let results =
-            self.inner.execute(instance_handle.index(), func_idx as usize, args.to_vec())?;
+        #[cfg(feature = "std")]
+        if let Some(checksums) = self.instance_code.get(&instance_handle) {
+            checksums.check_call(self.code_integrity, instance.module(), func_idx)?;
+        }
+
+        #[cfg(feature = "std")]
+        if let Some(tracer) = &self.tracer {
+            let mut attributes = vec![
+                (
+                    "wasm.instance".into(),
+                    AttributeValue::Int(instance_handle.index() as i64),
+                ),
+                ("wasm.function".into(), func_name.into()),
+                (
+                    "wasm.function.index".into(),
+                    AttributeValue::Int(i64::from(func_idx)),
+                ),
+            ];
+            let module = self
+                .instance_names
+                .get(&instance_handle)
+                .and_then(|names| names.module_name.as_deref());
+            if let Some(module) = module {
+                attributes.push(("wasm.module".into(), module.into()));
+            }
+            tracer.start_span(func_name, SpanKind::Server, attributes);
+        }
 
-        Ok(results)
+        // Keep the arguments of calls the heap profile may need to record
+        #[cfg(feature = "std")]
+        let profiled_args = self.heap_profiles.contains_key(&instance_handle).then(|| args.clone());
+
+        // Execute the function, keeping the context the guest attached to a
+        // trap
+        #[cfg(feature = "std")]
+        let metering = self.begin_metering();
+        self.inner.interruption.begin();
+        #[cfg(feature = "jit")]
+        let tiered = self.active_jit().map(|jit| jit.over(&self.inner));
+        #[cfg(feature = "jit")]
+        let backend: &dyn ExecutionBackend = match &tiered {
+            Some(tiered) => tiered,
+            None => &self.inner,
+        };
+        #[cfg(not(feature = "jit"))]
+        let backend: &dyn ExecutionBackend = &self.inner;
+        #[cfg(feature = "sampling-profiler")]
+        let call = self.profile_call(instance_handle, func_idx);
+        #[cfg(feature = "std")]
+        let mut host = RegisteredImports {
+            engine:   &self.inner,
+            registry: self.host_registry.as_ref(),
+        };
+        #[cfg(not(feature = "std"))]
+        let mut host = NoHostImports;
+        let results = backend
+            .execute(instance_handle.index(), func_idx as usize, args, &mut host)
+            .with_context(|| ContextFrame::new("calling export").with_function(func_idx));
+        #[cfg(feature = "sampling-profiler")]
+        drop(call);
+        self.inner.interruption.end();
+        #[cfg(feature = "std")]
+        let results = self.end_metering(metering, results);
+        #[cfg(feature = "std")]
+        if let Some(tracer) = &self.tracer {
+            tracer.end_span(match &results {
+                Ok(_) => SpanStatus::Ok,
+                Err(error) => SpanStatus::Error(error.to_string()),
+            });
+        }
+        #[cfg(feature = "std")]
+        if let (Some(profile), Some(args), Ok(values)) = (
+            self.heap_profiles.get(&instance_handle),
+            &profiled_args,
+            &results,
+        ) {
+            if let Some(call) = AllocatorCall::recognize(func_name, args, values) {
+                profile.record(call, self.allocation_site.as_deref());
+            }
+        }
+        if let (Some(log), Err(error)) = (self.event_log, &results) {
+            let consumed = self.inner.interruption.fuel_consumed();
+            let event = Event::for_failure(instance_handle.index(), func_idx, error, consumed);
+            if let Some(event) = event {
+                log.record(event);
+            }
+        }
+        #[cfg(feature = "std")]
+        if let Err(error) = &results {
+            if is_trap(error) {
+                self.traps += 1;
+                let symbol = self
+                    .instance_names
+                    .get(&instance_handle)
+                    .and_then(|names| names.function_name(func_idx))
+                    .map(String::from);
+                self.last_trap = Some(TrapInfo {
+                    function: func_name.into(),
+                    function_index: func_idx,
+                    symbol,
+                    error: *error,
+                    message: take_trap_message(&instance),
+                });
+            }
+        }
+
+        results
     }
 }
 
 impl CapabilityAwareEngine {
+    /// Worst-case RAM of an engine with all module and instance slots in use
+    ///
+    /// Counts the engine itself, whose module and instance tables are bounded
+    /// collections stored inline, plus one [`Module`] and one
+    /// [`ModuleInstance`] per slot. Linear memories are allocated by the
+    /// platform and are not included. The report can be checked at build
+    /// time:
+    ///
+    /// ```ignore
+    /// const _: () = assert!(
+    ///     CapabilityAwareEngine::memory_budget().total_bytes() <= 256 * 1024
+    /// );
+    /// ```
+    pub const fn memory_budget() -> BudgetReport {
+        BudgetReport::new()
+            .with::<Self>("engine")
+            .with_each::<Module>("modules", MAX_MODULES)
+            .with_each::<ModuleInstance>("instances", MAX_INSTANCES)
+    }
+
+    /// Native stack usage of the interpreter per guest call depth, for sizing
+    /// the stack of the thread running the engine
+    pub fn native_stack_usage(&self) -> NativeStackUsage {
+        self.inner.native_stack_usage()
+    }
+
+    /// Handle to the epoch of the engine, for advancing it or interrupting
+    /// execution from other threads
+    pub fn interrupt_handle(&self) -> &InterruptHandle {
+        self.inner.interruption.handle()
+    }
+
+    /// Interrupt invocations once the epoch has advanced `ticks` past the
+    /// epoch they started at, or never with `None`, the default
+    ///
+    /// See [`crate::interrupt`].
+    pub fn set_epoch_deadline(&mut self, ticks: Option<u64>) {
+        self.inner.interruption.set_epoch_deadline(ticks);
+    }
+
+    /// Limit execution to `fuel` yield points, or remove the limit with
+    /// `None`, the default
+    ///
+    /// An invocation that runs out of fuel fails with a fuel exhausted error
+    /// and is suspended: repeating it with the same arguments once the engine
+    /// has been given more fuel continues it where it stopped. Any other
+    /// invocation discards it. See [`crate::interrupt`].
+    pub fn set_fuel(&mut self, fuel: Option<u64>) {
+        self.inner.interruption.set_fuel(fuel);
+    }
+
+    /// Fuel left, or `None` without a limit
+    pub fn fuel(&self) -> Option<u64> {
+        self.inner.interruption.fuel()
+    }
+
+    /// Interrupt invocations that run longer than `duration` of wall-clock
+    /// time
+    ///
+    /// `callback` runs on a watchdog thread as soon as an invocation exceeds
+    /// the deadline, and the invocation fails with an execution timeout at
+    /// its next safe point. Replaces the previous invocation deadline.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the watchdog thread cannot be started.
+    #[cfg(feature = "std")]
+    pub fn set_invocation_deadline<F>(&mut self, duration: Duration, callback: F) -> Result<()>
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.inner
+            .interruption
+            .set_invocation_deadline(Some((duration, Arc::new(callback))))
+    }
+
+    /// Interrupt invocations that run longer than `duration` as measured by
+    /// the [engine clock](crate::time_source)
+    ///
+    /// The deadline is checked at the safe points of the interpreter, where
+    /// `callback` runs once an invocation has exceeded it and the invocation
+    /// fails with an execution timeout. Replaces the previous invocation
+    /// deadline.
+    #[cfg(not(feature = "std"))]
+    pub fn set_invocation_deadline(&mut self, duration: Duration, callback: fn()) -> Result<()> {
+        self.inner.interruption.set_invocation_deadline(Some((duration, callback)))
+    }
+
+    /// Remove the invocation deadline
+    pub fn clear_invocation_deadline(&mut self) {
+        // Removing a deadline does not start anything that could fail
+        let _ = self.inner.interruption.set_invocation_deadline(None);
+    }
+
+    /// Set the coercions permitted for arguments of exported functions
+    pub fn set_argument_coercion(&mut self, coercion: ArgumentCoercion) {
+        self.arg_coercion = coercion;
+    }
+
+    /// Get the coercions permitted for arguments of exported functions
+    pub fn argument_coercion(&self) -> ArgumentCoercion {
+        self.arg_coercion
+    }
+
+    /// Refuse to load modules that use floating-point values
+    ///
+    /// For targets without an FPU whose runtime is not built with the
+    /// `soft-float` feature; see [`wrt_decoder::float_usage`].
+    pub fn set_reject_float_modules(&mut self, reject: bool) {
+        self.reject_floats = reject;
+    }
+
+    /// Whether modules using floating-point values are refused
+    pub fn rejects_float_modules(&self) -> bool {
+        self.reject_floats
+    }
+
+    /// Refuse to load modules whose "target_features" section lists a
+    /// feature their code depends on that is not in `features`
+    ///
+    /// `None`, the default, accepts modules regardless of the features they
+    /// declare; modules without the section are always accepted. See
+    /// [`wrt_decoder::target_features_section`].
+    #[cfg(feature = "std")]
+    pub fn set_target_features(&mut self, features: Option<Vec<String>>) {
+        self.target_features = features;
+    }
+
+    /// Target features modules may depend on, if restricted
+    #[cfg(feature = "std")]
+    pub fn target_features(&self) -> Option<&[String]> {
+        self.target_features.as_deref()
+    }
+
+    /// Collect coverage of guest code for modules loaded from now on
+    ///
+    /// Calls of functions and entries into their basic blocks are counted
+    /// per module; see [`crate::guest_coverage`].
+    #[cfg(feature = "std")]
+    pub fn set_guest_coverage(&mut self, enabled: bool) {
+        self.collect_coverage = enabled;
+    }
+
+    /// Verify the code of modules loaded from now on against checksums taken
+    /// when they are loaded, as often as `level` asks for
+    ///
+    /// A call fails with a memory corruption error if the code it verifies
+    /// changed since; see [`crate::code_integrity`] for what each level
+    /// verifies. Modules loaded with the level `Off` are never verified.
+    #[cfg(feature = "std")]
+    pub fn set_code_integrity(&mut self, level: VerificationLevel) {
+        self.code_integrity = level;
+    }
+
+    /// Guest code coverage of a loaded module, if it was loaded with
+    /// coverage enabled
+    #[cfg(feature = "std")]
+    pub fn guest_coverage(&self, module: ModuleHandle) -> Option<&GuestCoverage> {
+        self.module_coverage.get(&module).map(|coverage| &**coverage)
+    }
+
+    /// Validate arguments for an exported function without executing it.
+    ///
+    /// The outer result reports lookup failures; the inner result carries
+    /// either the coerced arguments or an [`ArgumentMismatch`] describing the
+    /// expected and provided types.
+    pub fn check_export_arguments(
+        &self,
+        instance_handle: InstanceHandle,
+        func_name: &str,
+        args: &[Value],
+    ) -> Result<core::result::Result<Vec<Value>, ArgumentMismatch>> {
+        let instance = self.live_instance(instance_handle)?;
+
+        let func_idx = instance.module().validate_function_call(func_name)?;
+        Self::validate_call_arguments(&instance, func_idx, args, self.arg_coercion)
+    }
+
+    fn validate_call_arguments(
+        instance: &ModuleInstance,
+        func_idx: u32,
+        args: &[Value],
+        coercion: ArgumentCoercion,
+    ) -> Result<core::result::Result<Vec<Value>, ArgumentMismatch>> {
+        let func_type = instance
+            .module()
+            .get_function_signature(func_idx)
+            .ok_or_else(|| Error::runtime_function_not_found("Function type not found"))?;
+        let params: Vec<ValueType> = func_type.params.iter().collect();
+        Ok(validate_arguments(&params, args, coercion))
+    }
+
+    /// Get the names and kinds of all exports of an instance, in module order
+    pub fn get_exports(
+        &self,
+        instance_handle: InstanceHandle,
+    ) -> Result<Vec<(String, crate::module::ExportKind)>> {
+        let instance = self.live_instance(instance_handle)?;
+
+        instance
+            .module()
+            .exports
+            .values()
+            .map(|export| {
+                let name = export
+                    .name
+                    .as_str()
+                    .map_err(|_| Error::runtime_error("Invalid export name"))?;
+                Ok((name.to_string(), export.kind))
+            })
+            .collect()
+    }
+
+    /// The module loaded as `module_handle`, as it was lowered
+    fn loaded_module(&self, module_handle: ModuleHandle) -> Result<Module> {
+        #[cfg(feature = "std")]
+        let module = self.loaded_modules.get(&module_handle).map(|module| (**module).clone());
+        #[cfg(not(feature = "std"))]
+        let module = self.modules.get(&module_handle)?;
+        module.ok_or_else(|| Error::resource_not_found("Module not found"))
+    }
+
+    /// The instance registered as `instance_handle`, with its items
+    ///
+    /// Unlike the inner engine, `instances` keeps only a summary of each
+    /// instance, without its module, memories, tables and globals, and is
+    /// only kept in builds without `std`.
+    fn live_instance(&self, instance_handle: InstanceHandle) -> Result<Arc<ModuleInstance>> {
+        #[cfg(feature = "std")]
+        let instance = self.inner.instance(instance_handle.index());
+        #[cfg(not(feature = "std"))]
+        let instance = self.instances.get(&instance_handle)?.map(Arc::new);
+        instance.ok_or_else(|| Error::resource_not_found("Instance not found"))
+    }
+
+    /// Get all exports of an instance with their full types, in module order
+    pub fn exports(&self, instance_handle: InstanceHandle) -> Result<Vec<(String, Extern)>> {
+        let instance = self.live_instance(instance_handle)?;
+
+        instance
+            .module()
+            .exports
+            .values()
+            .map(|export| {
+                let name = export
+                    .name
+                    .as_str()
+                    .map_err(|_| Error::runtime_error("Invalid export name"))?;
+                Ok((name.to_string(), instance.resolve_export(&export)?))
+            })
+            .collect()
+    }
+
+    /// Look up an export of an instance by name, with its full type
+    pub fn get_export(
+        &self,
+        instance_handle: InstanceHandle,
+        name: &str,
+    ) -> Result<Option<Extern>> {
+        let instance = self.live_instance(instance_handle)?;
+
+        instance.get_export(name)
+    }
+
+    /// Read the global an instance exports as `name`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no such global or it does not hold a
+    /// `T`.
+    pub fn get_global<T: GlobalValue>(
+        &self,
+        instance_handle: InstanceHandle,
+        name: &str,
+    ) -> Result<T> {
+        let instance = self.live_instance(instance_handle)?;
+
+        instance
+            .get_global(name)?
+            .ok_or_else(|| Error::resource_global_not_found("Global export not found"))?
+            .get()
+    }
+
+    /// Write the global an instance exports as `name`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no such global, it is immutable, or it
+    /// does not hold a `T`.
+    pub fn set_global<T: GlobalValue>(
+        &self,
+        instance_handle: InstanceHandle,
+        name: &str,
+        value: T,
+    ) -> Result<()> {
+        let instance = self.live_instance(instance_handle)?;
+
+        instance
+            .get_global(name)?
+            .ok_or_else(|| Error::resource_global_not_found("Global export not found"))?
+            .set(value)
+    }
+
     /// Get the list of exported functions from an instance
     pub fn get_exported_functions(&self, instance_handle: InstanceHandle) -> Result<Vec<String>> {
-        let instance = self
-            .instances
-            .get(&instance_handle)?
-            .ok_or_else(|| Error::resource_not_found("Instance not found"))?;
+        Ok(self
+            .get_exports(instance_handle)?
+            .into_iter()
+            .filter(|(_, kind)| *kind == crate::module::ExportKind::Function)
+            .map(|(name, _)| name)
+            .collect())
+    }
+
+    /// Get the parameter and result types of an exported function
+    pub fn get_export_types(
+        &self,
+        instance_handle: InstanceHandle,
+        func_name: &str,
+    ) -> Result<(Vec<ValueType>, Vec<ValueType>)> {
+        let instance = self.live_instance(instance_handle)?;
+
+        let func_idx = instance.module().validate_function_call(func_name)?;
+        let func_type = instance
+            .module()
+            .get_function_signature(func_idx)
+            .ok_or_else(|| Error::runtime_function_not_found("Function type not found"))?;
+        Ok((func_type.params.iter().collect(), func_type.results.iter().collect()))
+    }
+
+    /// Copy `len` bytes starting at `offset` out of a memory of an instance
+    pub fn read_memory(
+        &self,
+        instance_handle: InstanceHandle,
+        memory_idx: u32,
+        offset: u32,
+        len: usize,
+    ) -> Result<Vec<u8>> {
+        let mut buffer = vec![0u8; len];
+        self.memory(instance_handle, memory_idx)?.read(offset, &mut buffer)?;
+        Ok(buffer)
+    }
+
+    /// Copy `bytes` into a memory of an instance starting at `offset`
+    pub fn write_memory(
+        &self,
+        instance_handle: InstanceHandle,
+        memory_idx: u32,
+        offset: u32,
+        bytes: &[u8],
+    ) -> Result<()> {
+        self.memory(instance_handle, memory_idx)?.write(offset, bytes)
+    }
+
+    /// Handle to a memory of an instance, whose views read and write it in
+    /// place
+    pub fn memory(
+        &self,
+        instance_handle: InstanceHandle,
+        memory_idx: u32,
+    ) -> Result<MemoryWrapper> {
+        self.live_instance(instance_handle)?.memory(memory_idx)
+    }
+
+    /// Call an exported function whose result is a buffer in guest memory
+    /// and stream that buffer in chunks
+    ///
+    /// The function must return the location of the buffer in its first
+    /// memory as described in [`result_stream`](super::result_stream).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the call fails, if the function does not return
+    /// a buffer location or if the buffer does not lie within the memory.
+    #[cfg(feature = "std")]
+    pub fn execute_streaming(
+        &mut self,
+        instance_handle: InstanceHandle,
+        func_name: &str,
+        args: &[Value],
+    ) -> Result<ResultStream> {
+        let results = self.execute(instance_handle, func_name, args)?;
+        let instance = self.live_instance(instance_handle)?;
+        ResultStream::from_results(instance.memory(0)?, &results)
+    }
+
+    /// Observe the growth of the memories and tables of instances created
+    /// from now on
+    ///
+    /// Growth is reported with the [`InstanceHandle::index`] of the instance
+    /// and the index of the memory or table within the instance, and fails
+    /// if the observer vetoes it. See
+    /// [`growth_observer`](crate::growth_observer).
+    #[cfg(feature = "std")]
+    pub fn set_memory_observer(&mut self, observer: Arc<dyn MemoryObserver>) {
+        self.memory_observer = Some(observer);
+    }
+
+    /// Check reads of linear memory of instances created from now on for
+    /// bytes that were never written, or stop checking with `None`
+    ///
+    /// Bytes count as written once the guest or host wrote them or an active
+    /// data segment initializes them. A read of other bytes is recorded in
+    /// [`Self::uninit_reads`] and traps in [`UninitReadMode::Trap`]. See
+    /// [`uninit_memory`](crate::uninit_memory).
+    #[cfg(feature = "std")]
+    pub fn set_uninit_read_check(&mut self, mode: Option<UninitReadMode>) {
+        self.uninit_read_mode = mode;
+    }
+
+    /// Reads of uninitialized memory recorded by the instances created while
+    /// they were checked
+    #[cfg(feature = "std")]
+    pub fn uninit_reads(&self) -> &UninitReadLog {
+        &self.uninit_reads
+    }
+
+    /// Profile the guest heap allocations of the instances created from now
+    /// on
+    ///
+    /// Calls of the allocator exports of an instance, the canonical ABI
+    /// `cabi_realloc` and the C `malloc`, `calloc`, `realloc` and `free`,
+    /// are recorded in its [`HeapProfile`]; see
+    /// [`heap_profile`](crate::heap_profile).
+    #[cfg(feature = "std")]
+    pub fn set_heap_profiling(&mut self, enabled: bool) {
+        self.heap_profiling = enabled;
+    }
+
+    /// Attribute the allocator calls made from now on to the call site
+    /// `site`, such as the function whose arguments are being lowered, or to
+    /// no call site with `None`
+    #[cfg(feature = "std")]
+    pub fn set_allocation_site(&mut self, site: Option<&str>) {
+        self.allocation_site = site.map(String::from);
+    }
+
+    /// Heap profile of an instance, if it was created with heap profiling
+    /// enabled
+    #[cfg(feature = "std")]
+    pub fn heap_profile(&self, instance: InstanceHandle) -> Option<&HeapProfile> {
+        self.heap_profiles.get(&instance).map(|profile| &**profile)
+    }
+
+    /// Record a span for every export executed from now on with `tracer`, or
+    /// stop with `None`
+    ///
+    /// The span is named after the export and carries the attributes
+    /// `wasm.instance`, `wasm.function`, `wasm.function.index` and, if the
+    /// name section names the module, `wasm.module`. Spans of intercepted
+    /// calls made by the guest while it runs become its children when the
+    /// [`TracingStrategy`](wrt_intercept::strategies::TracingStrategy) shares
+    /// the tracer. See [`Tracer::set_parent`] for continuing a trace of the
+    /// host.
+    #[cfg(feature = "std")]
+    pub fn set_tracer(&mut self, tracer: Option<Arc<Tracer>>) {
+        self.tracer = tracer;
+    }
+
+    /// Keep the guest call stack of invocations in `profiler` for sampling,
+    /// or stop with `None`
+    ///
+    /// Functions are named from the name sections of their modules; see
+    /// [`sampling_profiler`](crate::sampling_profiler).
+    #[cfg(feature = "sampling-profiler")]
+    pub fn set_sampling_profiler(&mut self, profiler: Option<Arc<SamplingProfiler>>) {
+        self.sampling_profiler = profiler;
+    }
+
+    /// Record instantiations, traps, fuel exhaustion and, with `std`, the
+    /// growth of memories in `log`, or stop with `None`
+    ///
+    /// The log is usually a `static` [`EventLog`](crate::event_log::EventLog)
+    /// read out after a failure. Memory growth is only recorded for instances
+    /// created after the log is set.
+    pub fn set_event_log(&mut self, log: Option<&'static dyn EventRecorder>) {
+        self.event_log = log;
+    }
+
+    /// Take the lowered form of modules loaded from now on from `cache`, and
+    /// add the modules it does not hold yet, or stop with `None`
+    ///
+    /// Lowering the function bodies is skipped for cached modules; the
+    /// binary is still decoded for the imports, branch hints and coverage
+    /// points. See [`Module::load_cached`] for skipping both.
+    #[cfg(feature = "std")]
+    pub fn set_lowering_cache(&mut self, cache: Option<Arc<LoweringCache>>) {
+        self.lowering_cache = cache;
+    }
+
+    /// Run hot functions of instances from code compiled by `jit`
+    ///
+    /// The backend is only used by engines created with the
+    /// [`EnginePreset::QM`] preset in builds without a safety standard
+    /// feature; others keep running every function on the interpreter, see
+    /// [`jit_permitted`].
+    #[cfg(feature = "jit")]
+    pub fn set_jit_backend(&mut self, jit: Option<Arc<JitBackend>>) {
+        self.jit = jit;
+    }
+
+    /// The JIT backend, if one is set and permitted for the engine
+    #[cfg(feature = "jit")]
+    fn active_jit(&self) -> Option<&JitBackend> {
+        self.jit.as_deref().filter(|_| jit_permitted(self.preset))
+    }
+
+    /// Push a call of `function` onto the stack of the sampling profiler, if
+    /// there is one
+    #[cfg(feature = "sampling-profiler")]
+    fn profile_call(&self, instance: InstanceHandle, function: u32) -> Option<ProfiledCall<'_>> {
+        let frame = GuestFrame {
+            instance: instance.index(),
+            function,
+        };
+        self.sampling_profiler
+            .as_ref()
+            .map(|profiler| profiler.enter(frame, self.instance_names.get(&instance)))
+    }
+
+    /// Charge `tenant` for the resources of the instances created and the
+    /// invocations run from now on
+    ///
+    /// The fuel of invocations is limited to what the quota of the tenant has
+    /// left, and an invocation that exhausts it fails with a resource limit
+    /// error. See [`accounting`](crate::accounting).
+    #[cfg(feature = "std")]
+    pub fn set_tenant(&mut self, accounting: Arc<Accounting>, tenant: TenantId) {
+        self.tenant = Some((accounting, tenant));
+    }
+
+    /// Tenant the engine charges, if any
+    #[cfg(feature = "std")]
+    pub fn tenant(&self) -> Option<TenantId> {
+        self.tenant.as_ref().map(|(_, tenant)| *tenant)
+    }
+
+    /// Start metering an invocation for the tenant of the engine
+    #[cfg(feature = "std")]
+    fn begin_metering(&mut self) -> Option<Metering> {
+        let (accounting, tenant) = self.tenant.as_ref()?;
+        let fuel = self.fuel();
+        let remaining = accounting.remaining(*tenant, Metered::Fuel);
+        let capped = remaining.is_some_and(|remaining| fuel.is_none_or(|fuel| remaining < fuel));
+        let metering = Metering {
+            fuel,
+            capped,
+            consumed: self.inner.interruption.fuel_consumed(),
+            instructions: self.inner.stats.instructions_executed,
+        };
+        if capped {
+            self.set_fuel(remaining);
+        }
+        Some(metering)
+    }
+
+    /// Charge the tenant of the engine for an invocation
+    #[cfg(feature = "std")]
+    fn end_metering(
+        &mut self,
+        metering: Option<Metering>,
+        results: Result<Vec<Value>>,
+    ) -> Result<Vec<Value>> {
+        let (Some(metering), Some((accounting, tenant))) = (metering, self.tenant.clone()) else {
+            return results;
+        };
+        let consumed = self.inner.interruption.fuel_consumed() - metering.consumed;
+        let instructions = self.inner.stats.instructions_executed - metering.instructions;
+        accounting.record(tenant, Metered::Fuel, consumed);
+        accounting.record(tenant, Metered::Instructions, instructions);
+        if !metering.capped {
+            return results;
+        }
+        self.set_fuel(metering.fuel.map(|fuel| fuel - consumed));
+        // Unlike the fuel of the engine, the fuel of the quota is not
+        // refilled, so running out of it is final
+        results.map_err(|error| match error.code {
+            codes::FUEL_EXHAUSTED => Error::resource_limit_exceeded("Tenant fuel quota exhausted"),
+            _ => error,
+        })
+    }
 
-        let mut functions = Vec::new();
-        // TODO: BoundedMap doesn't support iteration, so we can't list all exports
-        // For now, return an empty list as a placeholder
-        // In a real implementation, we'd need an iterator interface on BoundedMap
-        Ok(functions)
+    /// Load a module written in the WebAssembly text format
+    ///
+    /// The text is translated to the binary format and loaded as by
+    /// [`CapabilityEngine::load_module`].
+    #[cfg(feature = "wat")]
+    pub fn load_wat(&mut self, text: &str) -> Result<ModuleHandle> {
+        let binary = crate::text_format::wat_to_binary(text)?;
+        self.load_module(&binary)
+    }
+
+    /// Name section of a loaded module, if it has a valid one
+    #[cfg(feature = "std")]
+    pub fn module_names(&self, module: ModuleHandle) -> Option<&NameMap> {
+        self.module_names.get(&module).map(|names| &**names)
+    }
+
+    /// Counters and gauges of the engine, for export in the OpenMetrics
+    /// format
+    ///
+    /// Linear memory shared between instances is counted once. Execution
+    /// statistics and interceptor telemetry gathered by the embedder can be
+    /// added to the snapshot; see [`metrics`](crate::metrics).
+    #[cfg(feature = "std")]
+    pub fn metrics(&self) -> MetricsSnapshot {
+        let mut memories: Vec<MemoryWrapper> = Vec::new();
+        for instance in self.inner.instances() {
+            let mut idx = 0;
+            while let Ok(memory) = instance.memory(idx) {
+                if !memories.iter().any(|known| known.same_memory(&memory)) {
+                    memories.push(memory);
+                }
+                idx += 1;
+            }
+        }
+        let memory_bytes = memories.iter().map(|memory| memory.size_in_bytes() as u64).sum();
+        let memory_peak_bytes =
+            memories.iter().map(|memory| memory.lock_read().peak_memory() as u64).sum();
+
+        let mut snapshot = MetricsSnapshot::new();
+        snapshot.gauge(
+            "wrt_modules",
+            "Modules loaded",
+            self.loaded_modules.len() as u64,
+        );
+        snapshot.gauge(
+            "wrt_instances",
+            "Live instances",
+            self.inner.instances().count() as u64,
+        );
+        snapshot.counter(
+            "wrt_instantiations",
+            "Instances created",
+            self.next_instance_idx as u64,
+        );
+        snapshot.counter("wrt_invocations", "Exports executed", self.invocations);
+        snapshot.counter("wrt_traps", "Exports that trapped", self.traps);
+        snapshot.counter(
+            "wrt_fuel_consumed",
+            "Fuel consumed",
+            self.inner.interruption.fuel_consumed(),
+        );
+        snapshot.gauge("wrt_memory_bytes", "Bytes of linear memory", memory_bytes);
+        snapshot.gauge(
+            "wrt_memory_peak_bytes",
+            "Largest size of linear memory in bytes",
+            memory_peak_bytes,
+        );
+        snapshot
+    }
+
+    /// Safety guarantees of the current build and engine configuration
+    pub fn safety_report(&self) -> SafetyReport {
+        #[allow(unused_mut)]
+        let mut report = SafetyReport::new(self.preset, self.context.default_verification_level());
+        #[cfg(feature = "std")]
+        {
+            report.code_integrity = self.code_integrity;
+            report.signed_modules_required =
+                self.signature_policy.as_ref().is_some_and(SignaturePolicy::is_strict);
+        }
+        #[cfg(feature = "jit")]
+        {
+            report.write_xor_execute = self.active_jit().is_none();
+        }
+        report
+    }
+
+    /// Check the signatures of modules loaded from now on against `policy`,
+    /// refusing those that do not pass it
+    #[cfg(feature = "std")]
+    pub fn set_signature_policy(&mut self, policy: SignaturePolicy) {
+        self.signature_policy = Some(policy);
+    }
+
+    /// Handlers deserializing the custom sections of modules loaded from now
+    /// on
+    #[cfg(feature = "std")]
+    pub fn custom_section_registry_mut(&mut self) -> &mut CustomSectionRegistry {
+        &mut self.section_registry
+    }
+
+    /// Values the registered handlers deserialized from the custom sections
+    /// of a loaded module, if there are any
+    #[cfg(feature = "std")]
+    pub fn custom_section_values(&self, module: ModuleHandle) -> Option<&CustomSectionValues> {
+        self.section_values.get(&module)
+    }
+
+    /// Branch hints of a loaded module, lowered onto the instructions of its
+    /// functions, if it has any valid ones
+    ///
+    /// The hints are informational: execution does not depend on them.
+    #[cfg(feature = "std")]
+    pub fn branch_hints(&self, module: ModuleHandle) -> Option<&ModuleBranchHints> {
+        self.branch_hints.get(&module)
+    }
+
+    /// Symbol for function `func_idx` of an instance, for diagnostics such as
+    /// traps, profiles and traces
+    ///
+    /// The symbol carries the name the module's name section gives the
+    /// function and displays as `func[index]` without one.
+    #[cfg(feature = "std")]
+    pub fn function_symbol(&self, instance: InstanceHandle, func_idx: u32) -> FunctionSymbol<'_> {
+        match self.instance_names.get(&instance) {
+            Some(names) => names.symbolicate(func_idx),
+            None => FunctionSymbol {
+                index: func_idx,
+                name:  None,
+            },
+        }
+    }
+
+    /// Trap of the most recent call to an exported function, if it trapped
+    ///
+    /// The trap carries the message the guest attached to it through
+    /// [`TRAP_MESSAGE_EXPORT`](super::trap_info::TRAP_MESSAGE_EXPORT).
+    #[cfg(feature = "std")]
+    pub fn last_trap(&self) -> Option<&TrapInfo> {
+        self.last_trap.as_ref()
+    }
+
+    /// Pre-initialize a module by running `init_func` once
+    ///
+    /// The module is instantiated, including its start function, and
+    /// `init_func` is called without arguments. The resulting memories,
+    /// tables and globals the module defines are appended to `binary` as a
+    /// snapshot, and instances of the returned module start from that state
+    /// without running the start function or `init_func` again. Imported
+    /// memories, tables and globals are not part of the snapshot, so state
+    /// the initialization leaves there is not preserved.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the module fails to load, instantiate or
+    /// initialize, if it is already pre-initialized, or if its state refers
+    /// to host references that cannot be stored.
+    #[cfg(feature = "std")]
+    pub fn pre_initialize(&mut self, binary: &[u8], init_func: &str) -> Result<Vec<u8>> {
+        let module = self.load_module(binary)?;
+        let instance_handle = self.instantiate(module)?;
+        self.execute(instance_handle, init_func, &[])?;
+
+        let instance = self.live_instance(instance_handle)?;
+        InstanceSnapshot::capture(&instance)?.append_to_binary(binary)
     }
 
     /// Check if a function exists in an instance
     pub fn has_function(&self, instance_handle: InstanceHandle, func_name: &str) -> Result<bool> {
-        let instance = self
-            .instances
-            .get(&instance_handle)?
-            .ok_or_else(|| Error::resource_not_found("Instance not found"))?;
+        let instance = self.live_instance(instance_handle)?;
 
         Ok(instance.module().find_function_by_name(func_name).is_some())
     }
@@ -528,6 +2184,168 @@ pub fn get_function_signature(
         Ok(None)
     }
 
+    /// Designate an exported mutable `i32`/`i64` global as a host-shared
+    /// atomic global and return a handle usable from other threads
+    #[cfg(feature = "std")]
+    pub fn share_global(
+        &self,
+        instance_handle: InstanceHandle,
+        global_name: &str,
+    ) -> Result<crate::atomic_global::AtomicGlobal> {
+        let instance = self.live_instance(instance_handle)?;
+
+        let export = instance
+            .module()
+            .get_export(global_name)
+            .ok_or_else(|| Error::resource_not_found("Global export not found"))?;
+        if export.kind != crate::module::ExportKind::Global {
+            return Err(Error::runtime_type_mismatch("Export is not a global"));
+        }
+
+        instance.share_global(export.index)
+    }
+
+    /// Define a host-created memory that satisfies imports of
+    /// `module`.`name`
+    ///
+    /// The memory is shared with every instance importing it; limits are
+    /// checked against each import when the importing module is
+    /// instantiated.
+    #[cfg(feature = "std")]
+    pub fn define_memory(&mut self, module: &str, name: &str, memory: MemoryWrapper) {
+        self.defined_memories.insert((module.to_string(), name.to_string()), memory);
+    }
+
+    /// Define the memory exported by an instance as `export_name` to satisfy
+    /// imports of `module`.`name`
+    #[cfg(feature = "std")]
+    pub fn define_memory_from_export(
+        &mut self,
+        module: &str,
+        name: &str,
+        instance_handle: InstanceHandle,
+        export_name: &str,
+    ) -> Result<()> {
+        let instance = self.live_instance(instance_handle)?;
+
+        let export = instance
+            .module()
+            .get_export(export_name)
+            .ok_or_else(|| Error::resource_not_found("Memory export not found"))?;
+        if export.kind != crate::module::ExportKind::Memory {
+            return Err(Error::runtime_type_mismatch("Export is not a memory"));
+        }
+
+        let memory = instance.memory(export.index)?;
+        self.define_memory(module, name, memory);
+        Ok(())
+    }
+
+    /// Define a host-created table that satisfies imports of `module`.`name`
+    ///
+    /// The table is shared with every instance importing it, which lets
+    /// several modules link against one function table. Limits and element
+    /// type are checked against each import when the importing module is
+    /// instantiated.
+    #[cfg(feature = "std")]
+    pub fn define_table(&mut self, module: &str, name: &str, table: TableWrapper) {
+        self.defined_tables.insert((module.to_string(), name.to_string()), table);
+    }
+
+    /// Define the table exported by an instance as `export_name` to satisfy
+    /// imports of `module`.`name`
+    #[cfg(feature = "std")]
+    pub fn define_table_from_export(
+        &mut self,
+        module: &str,
+        name: &str,
+        instance_handle: InstanceHandle,
+        export_name: &str,
+    ) -> Result<()> {
+        let instance = self.live_instance(instance_handle)?;
+
+        let export = instance
+            .module()
+            .get_export(export_name)
+            .ok_or_else(|| Error::resource_not_found("Table export not found"))?;
+        if export.kind != crate::module::ExportKind::Table {
+            return Err(Error::runtime_type_mismatch("Export is not a table"));
+        }
+
+        let table = instance.table(export.index)?;
+        self.define_table(module, name, table);
+        Ok(())
+    }
+
+    /// Define a host-created global that satisfies imports of
+    /// `module`.`name`
+    ///
+    /// Value type and mutability are checked against each import when the
+    /// importing module is instantiated.
+    #[cfg(feature = "std")]
+    pub fn define_global(&mut self, module: &str, name: &str, global: GlobalWrapper) {
+        self.defined_globals.insert((module.to_string(), name.to_string()), global);
+    }
+
+    /// Host objects referenced by externref values
+    ///
+    /// Host functions clone the table to resolve the externrefs they receive
+    /// and to hand new host objects to guests.
+    #[cfg(feature = "std")]
+    pub fn host_refs(&self) -> &Arc<Mutex<HostRefTable>> {
+        &self.host_refs
+    }
+
+    /// Store `object` in the host reference table and define an immutable
+    /// externref global referencing it that satisfies imports of
+    /// `module`.`name`
+    #[cfg(feature = "std")]
+    pub fn define_host_ref<T: core::any::Any + Send + Sync>(
+        &mut self,
+        module: &str,
+        name: &str,
+        object: T,
+    ) -> Result<ExternRef> {
+        let reference = self
+            .host_refs
+            .lock()
+            .map_err(|_| Error::runtime_error("Failed to lock host references"))?
+            .insert(object)?;
+        let global = Global::new(
+            ValueType::ExternRef,
+            false,
+            Value::ExternRef(Some(reference.clone())),
+        )?;
+        self.define_global(module, name, GlobalWrapper::new(global));
+        Ok(reference)
+    }
+
+    /// Global imports declared by a loaded module, in import order
+    #[cfg(feature = "std")]
+    pub(crate) fn global_imports(&self, module_handle: ModuleHandle) -> &[GlobalImport] {
+        self.global_imports.get(&module_handle).map_or(&[], Vec::as_slice)
+    }
+
+    /// Current value of the global exported by an instance as `export_name`
+    #[cfg(feature = "std")]
+    pub(crate) fn exported_global_value(
+        &self,
+        instance_handle: InstanceHandle,
+        export_name: &str,
+    ) -> Result<Value> {
+        let instance = self.live_instance(instance_handle)?;
+
+        let export = instance
+            .module()
+            .get_export(export_name)
+            .ok_or_else(|| Error::resource_not_found("Global export not found"))?;
+        if export.kind != crate::module::ExportKind::Global {
+            return Err(Error::runtime_type_mismatch("Export is not a global"));
+        }
+
+        instance.global_value(export.index)
+    }
+
     /// Execute a function with additional capability validation
     pub fn execute_with_validation(
         &mut self,
@@ -536,10 +2354,7 @@ pub fn execute_with_validation(
         args: &[wrt_foundation::values::Value],
     ) -> Result<Vec<wrt_foundation::values::Value>> {
         // Additional capability-based validation
-        let instance = self
-            .instances
-            .get(&instance_handle)?
-            .ok_or_else(|| Error::resource_not_found("Instance not found"))?;
+        let instance = self.live_instance(instance_handle)?;
 
         // Verify memory capability allows function execution
         // Note: Using read operation as placeholder since Execute variant doesn't exist
@@ -575,12 +2390,253 @@ fn test_instance_handle_conversion() {
     }
 
     #[test]
-    fn test_engine_preset_creation() {
+    fn test_engine_preset_creation() -> Result<()> {
         // Test that each preset can be created
         let _qm = CapabilityAwareEngine::with_preset(EnginePreset::QM)?;
         let _asil_a = CapabilityAwareEngine::with_preset(EnginePreset::AsilA)?;
         let _asil_b = CapabilityAwareEngine::with_preset(EnginePreset::AsilB)?;
         let _asil_c = CapabilityAwareEngine::with_preset(EnginePreset::AsilC)?;
         let _asil_d = CapabilityAwareEngine::with_preset(EnginePreset::AsilD)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_guest_logging_reads_instance_memory() -> Result<()> {
+        use wrt_foundation::{
+            memory_init::MemoryInitializer,
+            types::Limits,
+        };
+        use wrt_logging::{
+            GUEST_LOG_FUNCTION,
+            GUEST_LOG_MODULE,
+        };
+
+        use crate::memory::Memory;
+
+        MemoryInitializer::ensure_initialized()?;
+        let mut engine = CapabilityAwareEngine::with_preset(EnginePreset::QM)?;
+        engine.enable_guest_logging(&GuestLogger::default())?;
+
+        // An instance whose memory holds "app" at 0 and "hello" at 16
+        let mut memory = Memory::new(CoreMemoryType {
+            limits: Limits::new(1, Some(1)),
+            shared: false,
+        })?;
+        memory.write(0, b"app")?;
+        memory.write(16, b"hello")?;
+        let mut instance = ModuleInstance::new(Module::empty(), 0)?;
+        instance.add_memory(memory)?;
+        let instance =
+            InstanceHandle::from_index(engine.inner.set_current_module(Arc::new(instance))?);
+
+        let log = |engine: &mut CapabilityAwareEngine, msg_ptr: i32| {
+            let args = [2, 0, 3, msg_ptr, 5].map(Value::I32).to_vec();
+            engine.call_host_function(instance, GUEST_LOG_MODULE, GUEST_LOG_FUNCTION, args)
+        };
+        assert!(log(&mut engine, 16)?.is_empty());
+        assert!(log(&mut engine, 65534).is_err());
+        assert!(engine
+            .call_host_function(
+                InstanceHandle::from_index(999),
+                GUEST_LOG_MODULE,
+                GUEST_LOG_FUNCTION,
+                vec![]
+            )
+            .is_err());
+        Ok(())
+    }
+
+    #[cfg(feature = "wat")]
+    #[test]
+    fn test_exports_of_an_instance() -> Result<()> {
+        use wrt_foundation::{
+            memory_init::MemoryInitializer,
+            types::{
+                GlobalType,
+                RefType,
+            },
+            values::FloatBits32,
+        };
+
+        MemoryInitializer::ensure_initialized()?;
+        let binary = crate::text_format::wat_to_binary(
+            r#"(module
+                (memory (export "mem") 1)
+                (table (export "tab") 3 externref)
+                (global (export "scale") f32 (f32.const 1.5)))"#,
+        )?;
+        let mut engine = CapabilityAwareEngine::with_preset(EnginePreset::QM)?;
+        let module = engine.load_module(&binary)?;
+        let instance = engine.instantiate(module)?;
+
+        let mut exports = engine.exports(instance)?;
+        exports.sort_by(|a, b| a.0.cmp(&b.0));
+        let names: Vec<_> =
+            exports.iter().map(|(name, export)| (name.as_str(), export.kind())).collect();
+        assert_eq!(
+            names,
+            [
+                ("mem", ExportKind::Memory),
+                ("scale", ExportKind::Global),
+                ("tab", ExportKind::Table),
+            ]
+        );
+
+        let memory = engine.get_export(instance, "mem")?.and_then(Extern::into_memory).unwrap();
+        assert_eq!((memory.ty.limits.min, memory.memory.size()), (1, 1));
+        let table = engine.get_export(instance, "tab")?.and_then(Extern::into_table).unwrap();
+        assert_eq!(table.ty.element_type, RefType::Externref);
+        assert_eq!(table.table.0.size(), 3);
+        let scale = engine.get_export(instance, "scale")?.and_then(Extern::into_global).unwrap();
+        assert_eq!(scale.ty, GlobalType::new(ValueType::F32, false));
+        assert_eq!(
+            scale.global.0.get(),
+            &Value::F32(FloatBits32::from_float(1.5))
+        );
+        assert!(engine.get_export(instance, "missing")?.is_none());
+        Ok(())
+    }
+
+    #[cfg(feature = "wat")]
+    #[test]
+    fn test_reads_memory_of_an_instance() -> Result<()> {
+        use wrt_foundation::memory_init::MemoryInitializer;
+
+        MemoryInitializer::ensure_initialized()?;
+        let binary = crate::text_format::wat_to_binary(r#"(module (memory (export "mem") 1))"#)?;
+        let mut engine = CapabilityAwareEngine::with_preset(EnginePreset::QM)?;
+        let module = engine.load_module(&binary)?;
+        let instance = engine.instantiate(module)?;
+
+        assert_eq!(engine.read_memory(instance, 0, 0x100, 4)?, [0; 4]);
+        assert!(engine.read_memory(instance, 0, 65534, 4).is_err());
+        assert!(engine.read_memory(instance, 1, 0, 4).is_err());
+        Ok(())
+    }
+
+    #[cfg(feature = "wat")]
+    #[test]
+    fn test_writes_memory_of_an_instance() -> Result<()> {
+        use wrt_foundation::memory_init::MemoryInitializer;
+
+        MemoryInitializer::ensure_initialized()?;
+        let binary = crate::text_format::wat_to_binary(r#"(module (memory (export "mem") 1))"#)?;
+        let mut engine = CapabilityAwareEngine::with_preset(EnginePreset::QM)?;
+        let module = engine.load_module(&binary)?;
+        let instance = engine.instantiate(module)?;
+
+        engine.write_memory(instance, 0, 0x100, b"wrt")?;
+        assert_eq!(engine.read_memory(instance, 0, 0x100, 3)?, b"wrt");
+        assert!(engine.write_memory(instance, 0, 65534, b"wrt").is_err());
+
+        let memory = engine.memory(instance, 0)?;
+        memory.view_mut(|mut view| view.write(0x200, 0x1234_5678u32))?;
+        assert_eq!(
+            engine.read_memory(instance, 0, 0x200, 4)?,
+            [0x78, 0x56, 0x34, 0x12]
+        );
+        let export = engine.get_export(instance, "mem")?.and_then(Extern::into_memory).unwrap();
+        assert_eq!(
+            export.memory.view(|view| view.read::<u32>(0x200))?,
+            0x1234_5678
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "wat")]
+    #[test]
+    fn test_copies_buffers_and_structs_into_an_instance() -> Result<()> {
+        use wrt_foundation::memory_init::MemoryInitializer;
+
+        use crate::memory_view::{
+            FieldLayout,
+            GuestStruct,
+            StructFields,
+            StructFieldsMut,
+            StructLayout,
+        };
+
+        #[derive(Debug, PartialEq)]
+        struct Point {
+            x: i32,
+            y: i32,
+        }
+
+        impl GuestStruct for Point {
+            const LAYOUT: StructLayout = StructLayout::new(
+                8,
+                4,
+                &[FieldLayout::of::<i32>(0), FieldLayout::of::<i32>(4)],
+            );
+
+            fn read_fields(fields: &StructFields<'_>) -> Result<Self> {
+                Ok(Self {
+                    x: fields.get(0)?,
+                    y: fields.get(1)?,
+                })
+            }
+
+            fn write_fields(&self, fields: &mut StructFieldsMut<'_>) -> Result<()> {
+                fields.set(0, self.x)?;
+                fields.set(1, self.y)
+            }
+        }
+
+        MemoryInitializer::ensure_initialized()?;
+        let binary = crate::text_format::wat_to_binary(r#"(module (memory (export "mem") 1))"#)?;
+        let mut engine = CapabilityAwareEngine::with_preset(EnginePreset::QM)?;
+        let module = engine.load_module(&binary)?;
+        let instance = engine.instantiate(module)?;
+        let memory = engine.memory(instance, 0)?;
+
+        memory.write_vectored(&[(0x10, b"head"), (0x80, b"tail")])?;
+        assert!(memory.write_vectored(&[(0x20, b"kept"), (65534, b"over")]).is_err());
+        let (mut head, mut tail, mut kept) = ([0; 4], [0; 4], [0; 4]);
+        memory.read_vectored(&mut [(0x10, &mut head), (0x80, &mut tail), (0x20, &mut kept)])?;
+        assert_eq!((&head, &tail, &kept), (b"head", b"tail", &[0; 4]));
+
+        let points = [Point { x: 1, y: -2 }, Point { x: 3, y: -4 }];
+        memory.view_mut(|mut view| view.write_structs(0x100, &points))?;
+        assert_eq!(
+            engine.read_memory(instance, 0, 0x108, 4)?,
+            3i32.to_le_bytes()
+        );
+        let export = engine.get_export(instance, "mem")?.and_then(Extern::into_memory).unwrap();
+        let mut read = [Point { x: 0, y: 0 }, Point { x: 0, y: 0 }];
+        export.memory.view(|view| view.read_structs(0x100, &mut read))?;
+        assert_eq!(read, points);
+        Ok(())
+    }
+
+    #[cfg(feature = "wat")]
+    #[test]
+    fn test_reads_and_writes_exported_globals() -> Result<()> {
+        use wrt_foundation::memory_init::MemoryInitializer;
+
+        MemoryInitializer::ensure_initialized()?;
+        let binary = crate::text_format::wat_to_binary(
+            r#"(module
+                (memory (export "mem") 1)
+                (global (export "count") (mut i64) (i64.const 3))
+                (global (export "ratio") f64 (f64.const 0.25)))"#,
+        )?;
+        let mut engine = CapabilityAwareEngine::with_preset(EnginePreset::QM)?;
+        let module = engine.load_module(&binary)?;
+        let instance = engine.instantiate(module)?;
+
+        assert_eq!(engine.get_global::<i64>(instance, "count")?, 3);
+        engine.set_global(instance, "count", 42i64)?;
+        assert_eq!(engine.get_global::<i64>(instance, "count")?, 42);
+        assert!(engine.set_global(instance, "count", 1i32).is_err());
+        assert!(engine.get_global::<f64>(instance, "count").is_err());
+        assert_eq!(engine.get_global::<i64>(instance, "count")?, 42);
+
+        assert!(engine.set_global(instance, "ratio", 0.5f64).is_err());
+        assert_eq!(engine.get_global::<f64>(instance, "ratio")?, 0.25);
+
+        assert!(engine.get_global::<i32>(instance, "mem").is_err());
+        assert!(engine.get_global::<i32>(instance, "missing").is_err());
+        Ok(())
     }
 }
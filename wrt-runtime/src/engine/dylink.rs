@@ -0,0 +1,328 @@
+//! Dynamic linking of Emscripten-style side modules
+//!
+//! Side modules produced by Emscripten or `clang -shared` carry a "dylink.0"
+//! section describing the linear memory and table space they need. They do
+//! not own a memory or table; instead they import the ones of the main module
+//! and expect the loader to follow these conventions:
+//!
+//! - `env.memory` and `env.__indirect_function_table` are the shared memory and
+//!   table
+//! - `env.__memory_base` and `env.__table_base` are immutable `i32` globals
+//!   holding the start of the region reserved for the module
+//! - `GOT.mem.<symbol>` globals hold the address of a data symbol
+//! - `GOT.func.<symbol>` globals hold the table index of a function symbol
+//!
+//! [`DynamicLinker`] reserves the regions, defines those imports on a
+//! [`CapabilityAwareEngine`] and instantiates the side module. Data symbols
+//! exported by a linked module are relocated by its memory base and become
+//! available to the modules linked after it. Function symbols resolve to the
+//! table slots registered with [`DynamicLinker::define_function_slot`], such
+//! as those of the main module's functions. Other imports of the side
+//! module, such as `env.__stack_pointer`, are resolved through the engine's
+//! regular definitions.
+
+use std::collections::HashMap;
+
+use wrt_decoder::dylink_section::{
+    read_dylink_section,
+    DylinkSection,
+};
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_foundation::{
+    interned::{
+        NameId,
+        NamePool,
+    },
+    types::ValueType,
+    values::Value,
+};
+
+use super::capability_engine::{
+    CapabilityAwareEngine,
+    CapabilityEngine,
+    InstanceHandle,
+};
+use crate::{
+    global::Global,
+    module::{
+        ExportKind,
+        GlobalWrapper,
+        MemoryWrapper,
+        TableWrapper,
+    },
+    prelude::{
+        String,
+        ToString,
+        Vec,
+    },
+};
+
+/// Import module holding the addresses of data symbols
+pub const GOT_MEM_MODULE: &str = "GOT.mem";
+/// Import module holding the table indices of function symbols
+pub const GOT_FUNC_MODULE: &str = "GOT.func";
+
+/// Export run after instantiation to apply data relocations
+const APPLY_DATA_RELOCS: &str = "__wasm_apply_data_relocs";
+/// Export run after relocation to execute static constructors
+const CALL_CTORS: &str = "__wasm_call_ctors";
+
+/// Names of the symbols a linker resolves
+type SymbolNames = NamePool<1024, 32768>;
+
+/// Values of linker symbols, keyed by interned name
+///
+/// Names that no longer fit the linker's pool are kept as owned strings.
+#[derive(Debug, Default)]
+struct SymbolTable {
+    interned: HashMap<NameId, u32>,
+    spilled:  HashMap<String, u32>,
+}
+
+impl SymbolTable {
+    fn insert(&mut self, names: &mut SymbolNames, name: &str, value: u32) {
+        match names.intern(name) {
+            Ok(id) => {
+                self.interned.insert(id, value);
+            },
+            Err(_) => {
+                self.spilled.insert(name.to_string(), value);
+            },
+        }
+    }
+
+    fn get(&self, names: &SymbolNames, name: &str) -> Option<u32> {
+        names
+            .get(name)
+            .and_then(|id| self.interned.get(&id))
+            .or_else(|| self.spilled.get(name))
+            .copied()
+    }
+}
+
+/// A side module linked by a [`DynamicLinker`]
+#[derive(Debug, Clone)]
+pub struct SideModule {
+    /// Name the module was linked under
+    pub name:        String,
+    /// Instance of the module
+    pub instance:    InstanceHandle,
+    /// Start of the module's region in the shared memory
+    pub memory_base: u32,
+    /// Start of the module's region in the shared table
+    pub table_base:  u32,
+    /// Parsed "dylink.0" section of the module
+    pub dylink:      DylinkSection,
+}
+
+/// Loader for side modules sharing a main module's memory and table
+#[derive(Debug)]
+pub struct DynamicLinker {
+    memory:           MemoryWrapper,
+    table:            TableWrapper,
+    next_memory_base: u32,
+    next_table_base:  u32,
+    symbols:          Box<SymbolNames>,
+    data_symbols:     SymbolTable,
+    function_slots:   SymbolTable,
+    modules:          Vec<SideModule>,
+}
+
+impl DynamicLinker {
+    /// Create a linker placing side modules into `memory` from
+    /// `memory_base` on and into `table` from `table_base` on
+    ///
+    /// The regions below the bases are left to the main module.
+    pub fn new(
+        memory: MemoryWrapper,
+        table: TableWrapper,
+        memory_base: u32,
+        table_base: u32,
+    ) -> Self {
+        Self {
+            memory,
+            table,
+            next_memory_base: memory_base,
+            next_table_base: table_base,
+            symbols: Box::default(),
+            data_symbols: SymbolTable::default(),
+            function_slots: SymbolTable::default(),
+            modules: Vec::new(),
+        }
+    }
+
+    /// Define the address of a data symbol for `GOT.mem` imports
+    pub fn define_data_symbol(&mut self, name: &str, address: u32) {
+        self.data_symbols.insert(&mut self.symbols, name, address);
+    }
+
+    /// Define the table index of a function symbol for `GOT.func` imports
+    pub fn define_function_slot(&mut self, name: &str, index: u32) {
+        self.function_slots.insert(&mut self.symbols, name, index);
+    }
+
+    /// Address of a data symbol, if defined
+    pub fn data_symbol(&self, name: &str) -> Option<u32> {
+        self.data_symbols.get(&self.symbols, name)
+    }
+
+    /// Side modules linked so far, in link order
+    pub fn modules(&self) -> &[SideModule] {
+        &self.modules
+    }
+
+    /// Link a side module under `name` and instantiate it
+    ///
+    /// Every module listed as needed by the side module must have been
+    /// linked before. After instantiation the module's data relocations and
+    /// static constructors are run if it exports them.
+    pub fn link(
+        &mut self,
+        engine: &mut CapabilityAwareEngine,
+        name: &str,
+        binary: &[u8],
+    ) -> Result<InstanceHandle> {
+        let dylink = read_dylink_section(binary)?
+            .ok_or_else(|| Error::validation_error("Module has no dylink.0 section"))?;
+
+        if self.modules.iter().any(|module| module.name == name) {
+            return Err(Error::validation_error("Side module already linked"));
+        }
+        if !dylink
+            .needed
+            .iter()
+            .all(|needed| self.modules.iter().any(|m| m.name == *needed))
+        {
+            return Err(Error::resource_not_found("Needed side module not linked"));
+        }
+
+        let (memory_base, memory_end) = reserve(
+            self.next_memory_base,
+            dylink.mem_info.memory_alignment()?,
+            dylink.mem_info.memory_size,
+        )?;
+        if memory_end as usize > self.memory.size_in_bytes() {
+            return Err(Error::capacity_limit_exceeded(
+                "Side module data does not fit into the shared memory",
+            ));
+        }
+
+        let (table_base, table_end) = reserve(
+            self.next_table_base,
+            dylink.mem_info.table_alignment()?,
+            dylink.mem_info.table_size,
+        )?;
+        if table_end > self.table.size() {
+            return Err(Error::capacity_limit_exceeded(
+                "Side module elements do not fit into the shared table",
+            ));
+        }
+
+        let module = engine.load_module(binary)?;
+
+        engine.define_memory("env", "memory", self.memory.clone());
+        engine.define_table("env", "__indirect_function_table", self.table.clone());
+        engine.define_global("env", "__memory_base", i32_global(memory_base, false)?);
+        engine.define_global("env", "__table_base", i32_global(table_base, false)?);
+
+        let got_entries: Vec<(String, String)> = engine
+            .global_imports(module)
+            .iter()
+            .filter(|import| import.module == GOT_MEM_MODULE || import.module == GOT_FUNC_MODULE)
+            .map(|import| (import.module.clone(), import.name.clone()))
+            .collect();
+        for (got, symbol) in got_entries {
+            let symbols =
+                if got == GOT_MEM_MODULE { &self.data_symbols } else { &self.function_slots };
+            let value = match symbols.get(&self.symbols, &symbol) {
+                Some(value) => value,
+                // Unresolved weak symbols are null
+                None if dylink.is_weak_import(&got, &symbol) => 0,
+                None => return Err(Error::resource_not_found("Undefined GOT symbol")),
+            };
+            engine.define_global(&got, &symbol, i32_global(value, true)?);
+        }
+
+        let instance = engine.instantiate(module)?;
+
+        // Data symbols are exported as offsets into the module's region
+        for (export, kind) in engine.get_exports(instance)? {
+            if kind != ExportKind::Global || self.data_symbols.get(&self.symbols, &export).is_some()
+            {
+                continue;
+            }
+            if let Value::I32(offset) = engine.exported_global_value(instance, &export)? {
+                let address = memory_base
+                    .checked_add(offset as u32)
+                    .ok_or_else(|| Error::validation_error("Data symbol address overflows"))?;
+                self.data_symbols.insert(&mut self.symbols, &export, address);
+            }
+        }
+
+        for init in [APPLY_DATA_RELOCS, CALL_CTORS] {
+            if engine.has_function(instance, init)? {
+                engine.execute(instance, init, &[])?;
+            }
+        }
+
+        self.next_memory_base = memory_end;
+        self.next_table_base = table_end;
+        self.modules.push(SideModule {
+            name: name.to_string(),
+            instance,
+            memory_base,
+            table_base,
+            dylink,
+        });
+        Ok(instance)
+    }
+}
+
+/// Align `next` up to `alignment` and reserve `size` units from there,
+/// returning the start and end of the region
+fn reserve(next: u32, alignment: u32, size: u32) -> Result<(u32, u32)> {
+    let overflow = || Error::capacity_limit_exceeded("Side module region exceeds 32 bits");
+    let base = next.div_ceil(alignment).checked_mul(alignment).ok_or_else(overflow)?;
+    let end = base.checked_add(size).ok_or_else(overflow)?;
+    Ok((base, end))
+}
+
+fn i32_global(value: u32, mutable: bool) -> Result<GlobalWrapper> {
+    Ok(GlobalWrapper::new(Global::new(
+        ValueType::I32,
+        mutable,
+        Value::I32(value as i32),
+    )?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reserve_aligns_regions() {
+        assert_eq!(reserve(0, 1, 10).unwrap(), (0, 10));
+        assert_eq!(reserve(1030, 16, 32).unwrap(), (1040, 1072));
+        assert_eq!(reserve(1040, 16, 0).unwrap(), (1040, 1040));
+        assert!(reserve(u32::MAX - 2, 8, 1).is_err());
+        assert!(reserve(u32::MAX - 8, 1, 16).is_err());
+    }
+
+    #[test]
+    fn test_symbols_outlive_a_full_name_pool() {
+        let mut names = Box::<SymbolNames>::default();
+        for index in 0..1024 {
+            names.intern(&format!("sym{index}")).unwrap();
+        }
+        let mut table = SymbolTable::default();
+        table.insert(&mut names, "sym7", 7);
+        table.insert(&mut names, "overflow", 42);
+        assert_eq!(table.get(&names, "sym7"), Some(7));
+        assert_eq!(table.get(&names, "overflow"), Some(42));
+        assert_eq!(table.get(&names, "sym8"), None);
+        assert_eq!(table.get(&names, "missing"), None);
+    }
+}
@@ -3,12 +3,26 @@
 //! This module provides a unified engine abstraction that uses capabilities
 //! to enforce different safety levels (QM, ASIL-A, ASIL-B).
 
+pub mod arg_validation;
 pub mod builder;
 pub mod capability_engine;
+#[cfg(feature = "std")]
+pub mod dylink;
 pub mod presets;
+#[cfg(feature = "std")]
+pub mod result_stream;
+pub mod safety_report;
+pub mod scheduler;
 #[cfg(test)]
 mod test_standalone;
+#[cfg(feature = "std")]
+pub mod trap_info;
 
+pub use arg_validation::{
+    validate_arguments,
+    ArgumentCoercion,
+    ArgumentMismatch,
+};
 pub use builder::EngineBuilder;
 pub use capability_engine::{
     CapabilityAwareEngine,
@@ -17,6 +31,11 @@
     InstanceHandle,
     ModuleHandle,
 };
+#[cfg(feature = "std")]
+pub use dylink::{
+    DynamicLinker,
+    SideModule,
+};
 pub use presets::{
     asil_a,
     asil_b,
@@ -24,3 +43,24 @@
     asil_d,
     qm,
 };
+#[cfg(feature = "std")]
+pub use result_stream::ResultStream;
+pub use safety_report::{
+    AllocationStrategy,
+    BoundsCheckStrategy,
+    SafetyReport,
+    UnsafeCodePolicy,
+};
+pub use scheduler::{
+    Scheduler,
+    SchedulingPolicy,
+    Task,
+    TaskId,
+    TaskStatus,
+};
+#[cfg(feature = "std")]
+pub use trap_info::{
+    is_trap,
+    TrapInfo,
+    TRAP_MESSAGE_EXPORT,
+};
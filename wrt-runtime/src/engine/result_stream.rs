@@ -0,0 +1,217 @@
+//! Chunked retrieval of large results from guest memory
+//!
+//! Exports that produce a large buffer return where it lies in their first
+//! memory instead of the bytes themselves, either as two `i32` results
+//! `(pointer, length)` or as a single `i32` pointing to such a pair stored
+//! as two little-endian `u32` values, the return area layout of the
+//! component model. A [`ResultStream`] reads the buffer from the guest
+//! memory in chunks of the host's choosing, so the result is never copied
+//! into one host allocation as a whole.
+//!
+//! The stream reads the memory as it is at the time of each read; calling
+//! into the instance again before the stream is drained may change the
+//! bytes it yields.
+
+use std::io;
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_foundation::values::Value;
+
+use crate::{
+    memory_view::MemoryView,
+    module::MemoryWrapper,
+};
+
+/// Buffer returned by an export, read from guest memory in chunks
+#[derive(Debug)]
+pub struct ResultStream {
+    memory:   MemoryWrapper,
+    offset:   u32,
+    len:      u32,
+    position: u32,
+}
+
+impl ResultStream {
+    /// Stream the buffer described by the `results` of a call
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the results are not a pointer and length or a
+    /// pointer to them, or if the buffer does not lie within `memory`.
+    pub fn from_results(memory: MemoryWrapper, results: &[Value]) -> Result<Self> {
+        let (offset, len) = match results {
+            [Value::I32(ptr), Value::I32(len)] => (*ptr as u32, *len as u32),
+            [Value::I32(ret_area)] => {
+                let memory_lock = memory.lock_read();
+                let view = MemoryView::new(&memory_lock);
+                let ret_area = *ret_area as u32;
+                let len_field = ret_area
+                    .checked_add(4)
+                    .ok_or_else(|| Error::memory_out_of_bounds("Return area out of bounds"))?;
+                (view.read::<u32>(ret_area)?, view.read::<u32>(len_field)?)
+            },
+            _ => {
+                return Err(Error::type_error(
+                    "Streamed results must be a pointer and length or a return area pointer",
+                ))
+            },
+        };
+        Self::new(memory, offset, len)
+    }
+
+    /// Stream the `len` bytes at `offset` of `memory`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the buffer does not lie within `memory`.
+    pub fn new(memory: MemoryWrapper, offset: u32, len: u32) -> Result<Self> {
+        let end = offset as usize + len as usize;
+        if end > memory.size_in_bytes() {
+            return Err(Error::memory_out_of_bounds(
+                "Streamed result lies beyond memory",
+            ));
+        }
+        Ok(Self {
+            memory,
+            offset,
+            len,
+            position: 0,
+        })
+    }
+
+    /// Total size of the buffer in bytes
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    /// Whether the buffer is empty
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Bytes not yet read
+    pub fn remaining(&self) -> usize {
+        (self.len - self.position) as usize
+    }
+
+    /// Copy the next chunk of the buffer into `chunk`
+    ///
+    /// Returns the number of bytes copied, zero once the buffer is drained.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the memory can no longer be read.
+    pub fn read_chunk(&mut self, chunk: &mut [u8]) -> Result<usize> {
+        let count = chunk.len().min(self.remaining());
+        self.memory.read(self.offset + self.position, &mut chunk[..count])?;
+        self.position += count as u32;
+        Ok(count)
+    }
+
+    /// Pass the next chunk of at most `max_len` bytes to `f` without copying
+    /// it out of guest memory
+    ///
+    /// Returns `None` once the buffer is drained.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the memory fails its integrity check.
+    pub fn with_next_chunk<R>(
+        &mut self,
+        max_len: usize,
+        f: impl FnOnce(&[u8]) -> R,
+    ) -> Result<Option<R>> {
+        let count = max_len.min(self.remaining());
+        if count == 0 {
+            return Ok(None);
+        }
+        let memory = self.memory.lock_read();
+        let view = MemoryView::new(&memory);
+        let window = view.bytes(self.offset + self.position, count)?;
+        let result = f(window.data()?);
+        self.position += count as u32;
+        Ok(Some(result))
+    }
+}
+
+impl io::Read for ResultStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.read_chunk(buf).map_err(|error| io::Error::other(error.message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::Read,
+        sync::Arc,
+    };
+
+    use wrt_foundation::types::Limits;
+
+    use super::*;
+    use crate::{
+        memory::Memory,
+        prelude::CoreMemoryType,
+    };
+
+    fn memory_with(contents: &[(u32, &[u8])]) -> MemoryWrapper {
+        let mut memory = Memory::new(CoreMemoryType {
+            limits: Limits::new(1, Some(1)),
+            shared: false,
+        })
+        .unwrap();
+        for (offset, bytes) in contents {
+            memory.write(*offset, bytes).unwrap();
+        }
+        MemoryWrapper::new(memory)
+    }
+
+    #[test]
+    fn test_stream_in_chunks() {
+        let payload: Vec<u8> = (0..=255).cycle().take(1000).collect();
+        let memory = memory_with(&[(100, &payload)]);
+
+        let mut stream =
+            ResultStream::from_results(memory.clone(), &[Value::I32(100), Value::I32(1000)])
+                .unwrap();
+        assert_eq!(stream.len(), 1000);
+
+        let mut chunk = [0; 300];
+        let mut received = Vec::new();
+        loop {
+            let count = stream.read_chunk(&mut chunk).unwrap();
+            if count == 0 {
+                break;
+            }
+            received.extend_from_slice(&chunk[..count]);
+        }
+        assert_eq!(received, payload);
+        assert_eq!(stream.remaining(), 0);
+
+        let mut stream = ResultStream::new(memory, 100, 1000).unwrap();
+        let mut sizes = Vec::new();
+        while let Some(size) = stream.with_next_chunk(400, <[u8]>::len).unwrap() {
+            sizes.push(size);
+        }
+        assert_eq!(sizes, [400, 400, 200]);
+    }
+
+    #[test]
+    fn test_stream_from_return_area() {
+        let memory = memory_with(&[(0, &[16, 0, 0, 0, 5, 0, 0, 0]), (16, b"hello")]);
+
+        let mut stream = ResultStream::from_results(memory.clone(), &[Value::I32(0)]).unwrap();
+        let mut text = String::new();
+        stream.read_to_string(&mut text).unwrap();
+        assert_eq!(text, "hello");
+
+        let end = memory.size_in_bytes() as u32;
+        assert!(ResultStream::new(memory.clone(), end - 4, 5).is_err());
+        assert!(ResultStream::from_results(memory.clone(), &[Value::I32(end as i32 - 4)]).is_err());
+        assert!(ResultStream::from_results(memory, &[Value::I64(0)]).is_err());
+    }
+}
@@ -0,0 +1,170 @@
+//! Safety guarantees of an engine
+//!
+//! Safety assessments ask for evidence of what the runtime guarantees: which
+//! crates may contain unsafe code, how guest memory accesses are bounds
+//! checked, whether guest code can ever be written and executed, how memory
+//! is allocated and how much is verified at run time. A [`SafetyReport`]
+//! states these for the current build and engine configuration, taken from
+//! [`CapabilityAwareEngine::safety_report`](super::CapabilityAwareEngine::safety_report),
+//! and displays as `key: value` lines that integrators can attach to their
+//! safety case.
+
+use core::fmt;
+
+use wrt_foundation::verification::VerificationLevel;
+
+use super::EnginePreset;
+
+/// Whether a crate may contain unsafe code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnsafeCodePolicy {
+    /// `#![forbid(unsafe_code)]`: no unsafe code at all
+    Forbidden,
+    /// `#![deny(unsafe_code)]`: only in items that explicitly allow it
+    Denied,
+    /// Unsafe code is not restricted by a crate-level lint
+    Allowed,
+}
+
+/// Unsafe code policy of each crate of the runtime, mirroring their
+/// crate-level lint attributes
+pub const UNSAFE_CODE_POLICIES: &[(&str, UnsafeCodePolicy)] = &[
+    ("wrt-error", UnsafeCodePolicy::Forbidden),
+    ("wrt-format", UnsafeCodePolicy::Forbidden),
+    ("wrt-decoder", UnsafeCodePolicy::Forbidden),
+    ("wrt-instructions", UnsafeCodePolicy::Forbidden),
+    ("wrt-intercept", UnsafeCodePolicy::Forbidden),
+    ("wrt-host", UnsafeCodePolicy::Forbidden),
+    ("wrt-foundation", UnsafeCodePolicy::Denied),
+    ("wrt-runtime", UnsafeCodePolicy::Allowed),
+    ("wrt-sync", UnsafeCodePolicy::Allowed),
+    ("wrt-platform", UnsafeCodePolicy::Allowed),
+];
+
+/// How accesses to linear memory are kept in bounds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundsCheckStrategy {
+    /// Every access is compared against the memory size before it is made;
+    /// no guard pages are relied on
+    Explicit,
+}
+
+/// How the engine allocates memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AllocationStrategy {
+    /// Allocations are limited only by the budgets of the capability context
+    Dynamic,
+    /// Allocations come from fixed-capacity providers within per-crate limits
+    Bounded,
+    /// All memory is reserved statically at build time
+    Static,
+}
+
+/// Safety guarantees of the current build and engine configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SafetyReport {
+    /// Preset the engine was created with
+    pub preset:                  EnginePreset,
+    /// Unsafe code policy of each crate of the runtime
+    pub unsafe_code:             &'static [(&'static str, UnsafeCodePolicy)],
+    /// How accesses to linear memory are kept in bounds
+    pub bounds_checks:           BoundsCheckStrategy,
+    /// Whether address arithmetic of memory accesses traps on overflow
+    /// instead of wrapping (the `checked-arithmetic` feature)
+    pub checked_arithmetic:      bool,
+    /// Whether no memory is ever both writable and executable
+    ///
+    /// The engine interprets guest code and generates no machine code,
+    /// unless a JIT backend is active, see the `jit` module.
+    pub write_xor_execute:       bool,
+    /// Whether guest code is kept apart from the data guests can address
+    ///
+    /// Function bodies live in the engine's module structures, never in
+    /// linear memory, so a guest cannot read or modify its own code.
+    pub code_data_separation:    bool,
+    /// How the engine allocates memory
+    pub allocation:              AllocationStrategy,
+    /// Verification level of the capability context
+    pub verification_level:      VerificationLevel,
+    /// How often loaded code is verified against its checksums
+    pub code_integrity:          VerificationLevel,
+    /// Whether modules are only loaded when they carry a trusted signature
+    pub signed_modules_required: bool,
+}
+
+impl SafetyReport {
+    /// Report for an engine created with `preset` and a capability context
+    /// verifying at `verification_level`
+    ///
+    /// The build-dependent guarantees are taken from the enabled features;
+    /// code integrity is off and signatures are not required.
+    pub fn new(preset: EnginePreset, verification_level: VerificationLevel) -> Self {
+        Self {
+            preset,
+            unsafe_code: UNSAFE_CODE_POLICIES,
+            bounds_checks: BoundsCheckStrategy::Explicit,
+            checked_arithmetic: cfg!(feature = "checked-arithmetic"),
+            write_xor_execute: true,
+            code_data_separation: true,
+            allocation: allocation_strategy(preset),
+            verification_level,
+            code_integrity: VerificationLevel::Off,
+            signed_modules_required: false,
+        }
+    }
+
+    /// Whether no crate of the runtime may contain unsafe code
+    pub fn is_free_of_unsafe_code(&self) -> bool {
+        self.unsafe_code
+            .iter()
+            .all(|(_, policy)| *policy == UnsafeCodePolicy::Forbidden)
+    }
+}
+
+fn allocation_strategy(preset: EnginePreset) -> AllocationStrategy {
+    if cfg!(feature = "static-allocation") {
+        AllocationStrategy::Static
+    } else if cfg!(feature = "bounded-allocation") || preset != EnginePreset::QM {
+        AllocationStrategy::Bounded
+    } else {
+        AllocationStrategy::Dynamic
+    }
+}
+
+impl fmt::Display for SafetyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "preset: {:?}", self.preset)?;
+        for (krate, policy) in self.unsafe_code {
+            writeln!(f, "unsafe-code.{krate}: {policy:?}")?;
+        }
+        writeln!(f, "bounds-checks: {:?}", self.bounds_checks)?;
+        writeln!(f, "checked-arithmetic: {}", self.checked_arithmetic)?;
+        writeln!(f, "write-xor-execute: {}", self.write_xor_execute)?;
+        writeln!(f, "code-data-separation: {}", self.code_data_separation)?;
+        writeln!(f, "allocation: {:?}", self.allocation)?;
+        writeln!(f, "verification-level: {:?}", self.verification_level)?;
+        writeln!(f, "code-integrity: {:?}", self.code_integrity)?;
+        writeln!(
+            f,
+            "signed-modules-required: {}",
+            self.signed_modules_required
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_follows_preset() {
+        let report = SafetyReport::new(EnginePreset::AsilD, VerificationLevel::Redundant);
+        assert_ne!(report.allocation, AllocationStrategy::Dynamic);
+        assert!(!report.is_free_of_unsafe_code());
+
+        let text = std::format!("{report}");
+        assert!(text.contains("preset: AsilD\n"));
+        assert!(text.contains("unsafe-code.wrt-decoder: Forbidden\n"));
+        assert!(text.contains("verification-level: Redundant\n"));
+    }
+}
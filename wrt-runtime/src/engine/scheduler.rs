@@ -0,0 +1,315 @@
+//! Cooperative scheduling of guest invocations across engines
+//!
+//! A [`Scheduler`] multiplexes [`Task`]s, each an engine with a pending call
+//! of one of its exports, on the thread that runs it. Every
+//! [`Scheduler::run_slice`] picks the most urgent ready task, gives its
+//! engine the fuel of one slice and lets it run. A task that runs out of fuel
+//! yields at the next yield point of the interpreter, and its call continues
+//! from there when the task is picked again in a later slice; see
+//! [`crate::interrupt`]. The fuel slice bounds how long a task keeps the
+//! others from running, which lets a single-core host run several components
+//! with bounded interference.
+//!
+//! Urgency follows the [`SchedulingPolicy`]: either the fixed priority of the
+//! tasks, or their relative deadline (deadline-monotonic), with the fixed
+//! priority breaking ties. Tasks of equal urgency take turns.
+
+use core::{
+    cmp::Reverse,
+    fmt,
+    time::Duration,
+};
+
+use wrt_error::{
+    codes,
+    Error,
+    Result,
+};
+use wrt_foundation::values::Value;
+
+use super::capability_engine::{
+    CapabilityAwareEngine,
+    CapabilityEngine,
+    InstanceHandle,
+};
+use crate::prelude::*;
+
+/// Fuel of a slice unless the task sets its own
+pub const DEFAULT_FUEL_SLICE: u64 = 1000;
+
+/// Order in which ready tasks run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SchedulingPolicy {
+    /// Highest [`Task::with_priority`] first
+    #[default]
+    FixedPriority,
+    /// Shortest [`Task::with_deadline`] first; tasks without a deadline run
+    /// after those with one
+    DeadlineMonotonic,
+}
+
+/// Identifies a task of a [`Scheduler`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TaskId(usize);
+
+/// State of a task
+#[derive(Debug, Clone)]
+pub enum TaskStatus {
+    /// Waiting for a slice to run or continue its call
+    Ready,
+    /// The call returned these results
+    Finished(Vec<Value>),
+    /// The call failed
+    Failed(Error),
+}
+
+/// An engine with a pending call of one of its exports
+pub struct Task {
+    engine:     Box<CapabilityAwareEngine>,
+    instance:   InstanceHandle,
+    function:   String,
+    args:       Vec<Value>,
+    priority:   u8,
+    deadline:   Option<Duration>,
+    fuel_slice: u64,
+    status:     TaskStatus,
+    /// Slice the task last ran in, for taking turns
+    last_run:   u64,
+}
+
+impl Task {
+    /// Task calling `function` of `instance` with `args`
+    pub fn new(
+        engine: CapabilityAwareEngine,
+        instance: InstanceHandle,
+        function: &str,
+        args: &[Value],
+    ) -> Self {
+        Self {
+            engine: Box::new(engine),
+            instance,
+            function: function.into(),
+            args: args.to_vec(),
+            priority: 0,
+            deadline: None,
+            fuel_slice: DEFAULT_FUEL_SLICE,
+            status: TaskStatus::Ready,
+            last_run: 0,
+        }
+    }
+
+    /// Set the fixed priority, higher running first
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Set the relative deadline used by
+    /// [`SchedulingPolicy::DeadlineMonotonic`]
+    pub fn with_deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Set the fuel of each slice, at least one
+    pub fn with_fuel_slice(mut self, fuel: u64) -> Self {
+        self.fuel_slice = fuel.max(1);
+        self
+    }
+
+    /// State of the task
+    pub fn status(&self) -> &TaskStatus {
+        &self.status
+    }
+
+    /// Engine of the task
+    pub fn engine(&self) -> &CapabilityAwareEngine {
+        &self.engine
+    }
+
+    /// Engine of the task, for reading the state the call left behind
+    pub fn into_engine(self) -> CapabilityAwareEngine {
+        *self.engine
+    }
+
+    /// Urgency under `policy`, the greatest running first
+    fn rank(&self, policy: SchedulingPolicy) -> Rank {
+        Rank {
+            deadline: match policy {
+                SchedulingPolicy::FixedPriority => Reverse(None),
+                SchedulingPolicy::DeadlineMonotonic => {
+                    Reverse(self.deadline.or(Some(Duration::MAX)))
+                },
+            },
+            priority: self.priority,
+            waiting:  Reverse(self.last_run),
+        }
+    }
+
+    fn run(&mut self, slice: u64) {
+        self.last_run = slice;
+        self.engine.set_fuel(Some(self.fuel_slice));
+        let result = self.engine.execute(self.instance, &self.function, &self.args);
+        self.engine.set_fuel(None);
+        self.status = match result {
+            Ok(results) => TaskStatus::Finished(results),
+            Err(error) if error.code == codes::FUEL_EXHAUSTED => TaskStatus::Ready,
+            Err(error) => TaskStatus::Failed(error),
+        };
+    }
+}
+
+impl fmt::Debug for Task {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Task")
+            .field("function", &self.function)
+            .field("priority", &self.priority)
+            .field("deadline", &self.deadline)
+            .field("status", &self.status)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Rank {
+    deadline: Reverse<Option<Duration>>,
+    priority: u8,
+    waiting:  Reverse<u64>,
+}
+
+/// Runs the tasks of several engines in fuel slices
+#[derive(Debug, Default)]
+pub struct Scheduler {
+    policy: SchedulingPolicy,
+    tasks:  Vec<Option<Task>>,
+    slices: u64,
+}
+
+impl Scheduler {
+    /// Scheduler ordering tasks by `policy`
+    pub fn new(policy: SchedulingPolicy) -> Self {
+        Self {
+            policy,
+            tasks: Vec::new(),
+            slices: 0,
+        }
+    }
+
+    /// Add a task, ready to run
+    pub fn spawn(&mut self, task: Task) -> TaskId {
+        let id = match self.tasks.iter().position(Option::is_none) {
+            Some(free) => free,
+            None => {
+                self.tasks.push(None);
+                self.tasks.len() - 1
+            },
+        };
+        self.tasks[id] = Some(task);
+        TaskId(id)
+    }
+
+    /// Task `id`, if it has not been removed
+    pub fn task(&self, id: TaskId) -> Option<&Task> {
+        self.tasks.get(id.0).and_then(Option::as_ref)
+    }
+
+    /// Remove task `id`, returning it with its status and engine
+    pub fn remove(&mut self, id: TaskId) -> Option<Task> {
+        self.tasks.get_mut(id.0).and_then(Option::take)
+    }
+
+    /// Whether a task is ready to run
+    pub fn has_ready(&self) -> bool {
+        self.tasks.iter().flatten().any(|task| matches!(task.status, TaskStatus::Ready))
+    }
+
+    /// Run the most urgent ready task for one slice, returning the task
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no task is ready.
+    pub fn run_slice(&mut self) -> Result<TaskId> {
+        let policy = self.policy;
+        let (id, task) = self
+            .tasks
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(id, task)| task.as_mut().map(|task| (id, task)))
+            .filter(|(_, task)| matches!(task.status, TaskStatus::Ready))
+            .max_by(|(_, a), (_, b)| a.rank(policy).cmp(&b.rank(policy)))
+            .ok_or_else(|| Error::resource_not_found("No task is ready"))?;
+        self.slices += 1;
+        task.run(self.slices);
+        Ok(TaskId(id))
+    }
+
+    /// Run slices until no task is ready
+    pub fn run_until_idle(&mut self) {
+        while self.run_slice().is_ok() {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rank(priority: u8, deadline: Option<u64>, last_run: u64) -> Rank {
+        Rank {
+            deadline: Reverse(deadline.map(Duration::from_millis)),
+            priority,
+            waiting: Reverse(last_run),
+        }
+    }
+
+    #[test]
+    fn test_rank_orders_by_deadline_priority_and_turn() {
+        // A shorter deadline wins over a higher priority
+        assert!(rank(0, Some(5), 0) > rank(9, Some(10), 0));
+        // Equal deadlines fall back to the priority
+        assert!(rank(2, Some(5), 0) > rank(1, Some(5), 0));
+        // Equal urgency takes turns
+        assert!(rank(1, None, 3) > rank(1, None, 4));
+    }
+
+    #[cfg(feature = "wat")]
+    #[test]
+    fn test_call_continues_across_slices() -> Result<()> {
+        use wrt_foundation::memory_init::MemoryInitializer;
+
+        use crate::engine::EnginePreset;
+
+        MemoryInitializer::ensure_initialized()?;
+        let binary = crate::text_format::wat_to_binary(
+            r#"(module
+                (global (export "iterations") (mut i32) (i32.const 0))
+                (func (export "sum") (param $n i32) (result i32) (local $sum i32)
+                  (loop $next
+                    (global.set 0 (i32.add (global.get 0) (i32.const 1)))
+                    (local.set $sum (i32.add (local.get $sum) (local.get $n)))
+                    (br_if $next (local.tee $n (i32.sub (local.get $n) (i32.const 1)))))
+                  (local.get $sum)))"#,
+        )?;
+        let mut engine = CapabilityAwareEngine::with_preset(EnginePreset::QM)?;
+        let module = engine.load_module(&binary)?;
+        let instance = engine.instantiate(module)?;
+
+        let mut scheduler = Scheduler::default();
+        let task = Task::new(engine, instance, "sum", &[Value::I32(100)]).with_fuel_slice(10);
+        let id = scheduler.spawn(task);
+        scheduler.run_until_idle();
+
+        // A slice passes the entry and nine branches back to the loop, and
+        // stops at the tenth; the next slice continues the loop from there
+        assert_eq!(scheduler.slices, 10);
+        let task = scheduler.remove(id).unwrap();
+        assert!(
+            matches!(task.status(), TaskStatus::Finished(results) if results == &[Value::I32(5050)]),
+            "{task:?}"
+        );
+        assert_eq!(
+            task.engine().get_global::<i32>(instance, "iterations")?,
+            100
+        );
+        Ok(())
+    }
+}
@@ -56,7 +56,7 @@ fn test_preset_capability_levels() {
         let asil_b_context = presets::asil_b().expect("ASIL-B preset should work");
         assert_eq!(
             asil_b_context.default_verification_level(),
-            VerificationLevel::Continuous
+            VerificationLevel::Full
         );
     }
 }
@@ -0,0 +1,127 @@
+//! Guest-provided context for traps
+//!
+//! A trap only tells the embedder which condition stopped execution, which
+//! for intentional traps such as failed assertions is no more than an
+//! `unreachable` instruction. Guests can say why they trap by exporting a
+//! mutable `i32` global named [`TRAP_MESSAGE_EXPORT`] and pointing it at a
+//! NUL-terminated UTF-8 string in their first memory before trapping. When
+//! an exported function traps, the engine reads the string into the
+//! [`TrapInfo`] of the call and resets the global to zero, so that a later
+//! trap is not reported with a stale message.
+
+use core::fmt;
+
+use wrt_error::{
+    codes,
+    Error,
+    ErrorCategory,
+};
+
+use crate::{
+    memory_view::MemoryView,
+    module_instance::ModuleInstance,
+    prelude::String,
+};
+
+/// Name of the exported global pointing to the message of a trap
+pub const TRAP_MESSAGE_EXPORT: &str = "__trap_message";
+
+/// Longest trap message read from guest memory, in bytes
+pub const MAX_TRAP_MESSAGE_LEN: usize = 1024;
+
+/// Trap of a call to an exported function
+#[derive(Debug, Clone)]
+pub struct TrapInfo {
+    /// Name of the exported function
+    pub function:       String,
+    /// Index of the exported function
+    pub function_index: u32,
+    /// Name the module's name section gives the exported function
+    pub symbol:         Option<String>,
+    /// The trap
+    pub error:          Error,
+    /// Message the guest attached to the trap
+    pub message:        Option<String>,
+}
+
+impl TrapInfo {
+    /// Message of the guest, or of the trap if the guest gave none
+    pub fn description(&self) -> &str {
+        self.message.as_deref().unwrap_or(self.error.message)
+    }
+}
+
+impl fmt::Display for TrapInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.function)?;
+        if let Some(symbol) = self.symbol.as_deref().filter(|symbol| *symbol != self.function) {
+            write!(f, " ({symbol})")?;
+        }
+        write!(f, " trapped: {}", self.error.message)?;
+        if let Some(message) = &self.message {
+            write!(f, ": {message}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether `error` is a WebAssembly trap rather than an embedding error
+pub fn is_trap(error: &Error) -> bool {
+    error.category == ErrorCategory::RuntimeTrap || error.code == codes::RUNTIME_TRAP_ERROR
+}
+
+/// Read and reset the message `instance` attached to a trap
+///
+/// A missing export, a null pointer or a pointer that does not lead to a
+/// valid string yields `None`; a broken message must not hide the trap.
+pub(crate) fn take_trap_message(instance: &ModuleInstance) -> Option<String> {
+    let global = instance.get_global(TRAP_MESSAGE_EXPORT).ok()??;
+    let ptr = global.get::<i32>().ok()?;
+    if ptr == 0 {
+        return None;
+    }
+    // An immutable global simply keeps its value
+    let _ = global.set(0i32);
+
+    let memory = instance.memory(0).ok()?;
+    memory.view(|view| view.read_c_str(ptr as u32, MAX_TRAP_MESSAGE_LEN).ok().map(Into::into))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trap_description() {
+        let mut trap = TrapInfo {
+            function:       "check".into(),
+            function_index: 2,
+            symbol:         None,
+            error:          Error::runtime_trap_error("unreachable instruction executed"),
+            message:        None,
+        };
+        assert!(is_trap(&trap.error));
+        assert_eq!(trap.description(), "unreachable instruction executed");
+        assert_eq!(
+            trap.to_string(),
+            "check trapped: unreachable instruction executed"
+        );
+
+        trap.message = Some("assertion failed: len > 0".into());
+        assert_eq!(trap.description(), "assertion failed: len > 0");
+        assert_eq!(
+            trap.to_string(),
+            "check trapped: unreachable instruction executed: assertion failed: len > 0"
+        );
+
+        trap.symbol = Some("app::check_input".into());
+        assert_eq!(
+            trap.to_string(),
+            "check (app::check_input) trapped: unreachable instruction executed: assertion \
+             failed: len > 0"
+        );
+
+        assert!(is_trap(&Error::runtime_trap("trap")));
+        assert!(!is_trap(&Error::resource_not_found("Instance not found")));
+    }
+}
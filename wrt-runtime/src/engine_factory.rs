@@ -23,7 +23,13 @@
 };
 use wrt_foundation::CrateId;
 
-use crate::prelude::*;
+use crate::{
+    prelude::*,
+    time_source::{
+        select_time_source,
+        TimeSource,
+    },
+};
 
 /// Available engine types
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -60,6 +66,8 @@ pub struct EngineConfig {
     pub debug_mode:      bool,
     /// Maximum number of function calls
     pub max_call_depth:  Option<u32>,
+    /// Clock for event timestamps, selected when the engine is created
+    pub time_source:     Option<TimeSource>,
 }
 
 impl Default for EngineConfig {
@@ -70,6 +78,7 @@ fn default() -> Self {
             memory_budget:   65536, // 64KB default
             debug_mode:      false,
             max_call_depth:  Some(1024),
+            time_source:     None,
         }
     }
 }
@@ -106,6 +115,15 @@ pub fn with_max_call_depth(mut self, depth: u32) -> Self {
         self.max_call_depth = Some(depth);
         self
     }
+
+    /// Set the clock for event timestamps
+    ///
+    /// The clock is process-wide, see [`select_time_source`]; creating an
+    /// engine fails if a different clock was already selected.
+    pub fn with_time_source(mut self, source: TimeSource) -> Self {
+        self.time_source = Some(source);
+        self
+    }
 }
 
 /// Main engine factory
@@ -114,6 +132,10 @@ pub fn with_max_call_depth(mut self, depth: u32) -> Self {
 impl EngineFactory {
     /// Create an engine with the specified configuration
     pub fn create(config: EngineConfig) -> Result<Box<dyn RuntimeEngine>> {
+        if let Some(source) = config.time_source {
+            select_time_source(source)?;
+        }
+
         // Create memory provider based on configuration
         Self::create_memory_provider(config.memory_provider, config.memory_budget)?;
 
@@ -0,0 +1,432 @@
+//! Fixed-size log of runtime events for post-mortem diagnosis
+//!
+//! Devices in the field rarely have a console attached when something goes
+//! wrong. An [`EventLog`] keeps the most recent traps, instantiations,
+//! memory growths, fuel exhaustions and interceptor denials in a ring buffer
+//! of fixed capacity, each stamped with the platform clock, see
+//! [`time_source`](crate::time_source). The log needs no allocator and can be
+//! a `static`, so it survives the engine and can be read out after the
+//! fact, for example by a diagnostics service or from a retained RAM region.
+//!
+//! An engine given a log, see
+//! [`CapabilityAwareEngine::set_event_log`](crate::engine::CapabilityAwareEngine::set_event_log),
+//! records instantiations, traps and fuel exhaustion itself, and memory
+//! growth when built with `std`. Interceptor denials surface as errors of
+//! [`LinkInterceptor::intercept_call`](wrt_intercept::LinkInterceptor::intercept_call)
+//! and are recorded by the embedder with [`Event::interceptor_denial`].
+//!
+//! Events are extracted in a compact binary form of [`EVENT_SIZE`] bytes
+//! each, see [`EventLog::drain_into`] and [`decode_events`]. When the log is
+//! full the oldest event is overwritten; [`EventLog::overwritten`] counts the
+//! events lost that way.
+
+use wrt_error::{
+    codes,
+    Error,
+    ErrorCategory,
+    Result,
+};
+use wrt_sync::WrtMutex;
+
+use crate::time_source;
+#[cfg(feature = "std")]
+use crate::{
+    growth_observer::{
+        GrowthEvent,
+        GrowthKind,
+        MemoryObserver,
+    },
+    prelude::*,
+};
+
+/// Size of an encoded [`Event`] in bytes
+///
+/// Fields are little-endian: the timestamp (8 bytes), the kind, a reserved
+/// zero byte, the code (2 bytes), the instance, the index and the value (4
+/// bytes each).
+pub const EVENT_SIZE: usize = 24;
+
+/// Kind of a logged event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum EventKind {
+    /// An exported function trapped
+    Trap              = 1,
+    /// A module was instantiated
+    Instantiation     = 2,
+    /// A linear memory grew
+    MemoryGrow        = 3,
+    /// An exported function ran out of fuel
+    FuelExhausted     = 4,
+    /// An interceptor denied a call
+    InterceptorDenial = 5,
+}
+
+impl EventKind {
+    fn from_u8(kind: u8) -> Option<Self> {
+        Some(match kind {
+            1 => Self::Trap,
+            2 => Self::Instantiation,
+            3 => Self::MemoryGrow,
+            4 => Self::FuelExhausted,
+            5 => Self::InterceptorDenial,
+            _ => return None,
+        })
+    }
+}
+
+/// A logged event
+///
+/// The meaning of `index` and `value` depends on the kind, see the
+/// constructors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Event {
+    /// Platform time the event was recorded at, in nanoseconds
+    pub timestamp_ns: u64,
+    /// What happened
+    pub kind:         EventKind,
+    /// Error code of traps and denials, zero otherwise
+    pub code:         u16,
+    /// Index of the instance the event concerns
+    pub instance:     u32,
+    /// Index of the function, memory or module the event concerns
+    pub index:        u32,
+    /// Additional value of the event
+    pub value:        u32,
+}
+
+impl Event {
+    const fn new(kind: EventKind, code: u16, instance: usize, index: u32, value: u32) -> Self {
+        Self {
+            timestamp_ns: 0,
+            kind,
+            code,
+            instance: instance as u32,
+            index,
+            value,
+        }
+    }
+
+    /// Function `function` of `instance` trapped with `error`
+    pub fn trap(instance: usize, function: u32, error: &Error) -> Self {
+        Self::new(EventKind::Trap, error.code, instance, function, 0)
+    }
+
+    /// `instance` was created from the module with index `module`
+    pub fn instantiation(instance: usize, module: u32) -> Self {
+        Self::new(EventKind::Instantiation, 0, instance, module, 0)
+    }
+
+    /// Memory `memory` of `instance` grew to `pages` pages
+    pub fn memory_grow(instance: usize, memory: u32, pages: u32) -> Self {
+        Self::new(EventKind::MemoryGrow, 0, instance, memory, pages)
+    }
+
+    /// Function `function` of `instance` ran out of fuel after consuming
+    /// `consumed`, saturated to `u32::MAX`
+    pub fn fuel_exhausted(instance: usize, function: u32, consumed: u64) -> Self {
+        let consumed = u32::try_from(consumed).unwrap_or(u32::MAX);
+        Self::new(EventKind::FuelExhausted, 0, instance, function, consumed)
+    }
+
+    /// An interceptor denied a call of function `function` of `instance`
+    /// with `error`
+    pub fn interceptor_denial(instance: usize, function: u32, error: &Error) -> Self {
+        Self::new(
+            EventKind::InterceptorDenial,
+            error.code,
+            instance,
+            function,
+            0,
+        )
+    }
+
+    /// Event for function `function` of `instance` failing with `error`, if
+    /// the failure is worth logging
+    ///
+    /// Fuel exhaustion and traps are logged; errors such as a missing export
+    /// or mismatched arguments are not.
+    pub fn for_failure(
+        instance: usize,
+        function: u32,
+        error: &Error,
+        consumed: u64,
+    ) -> Option<Self> {
+        if error.code == codes::FUEL_EXHAUSTED {
+            Some(Self::fuel_exhausted(instance, function, consumed))
+        } else if error.category == ErrorCategory::RuntimeTrap
+            || error.code == codes::RUNTIME_TRAP_ERROR
+        {
+            Some(Self::trap(instance, function, error))
+        } else {
+            None
+        }
+    }
+
+    /// Encode the event
+    pub fn to_bytes(&self) -> [u8; EVENT_SIZE] {
+        let mut bytes = [0; EVENT_SIZE];
+        bytes[0..8].copy_from_slice(&self.timestamp_ns.to_le_bytes());
+        bytes[8] = self.kind as u8;
+        bytes[10..12].copy_from_slice(&self.code.to_le_bytes());
+        bytes[12..16].copy_from_slice(&self.instance.to_le_bytes());
+        bytes[16..20].copy_from_slice(&self.index.to_le_bytes());
+        bytes[20..24].copy_from_slice(&self.value.to_le_bytes());
+        bytes
+    }
+
+    /// Decode an event encoded by [`Self::to_bytes`]
+    ///
+    /// # Errors
+    ///
+    /// Returns a parse error if `bytes` is not [`EVENT_SIZE`] long or names
+    /// an unknown kind.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let bytes: &[u8; EVENT_SIZE] =
+            bytes.try_into().map_err(|_| Error::parse_error("Truncated event log record"))?;
+        let kind = EventKind::from_u8(bytes[8])
+            .ok_or_else(|| Error::parse_error("Unknown event log record kind"))?;
+        let u32_at = |at: usize| {
+            u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+        };
+        Ok(Self {
+            timestamp_ns: u64::from_le_bytes([
+                bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
+            ]),
+            kind,
+            code: u16::from_le_bytes([bytes[10], bytes[11]]),
+            instance: u32_at(12),
+            index: u32_at(16),
+            value: u32_at(20),
+        })
+    }
+}
+
+/// Decode the events extracted with [`EventLog::drain_into`], oldest first
+pub fn decode_events(bytes: &[u8]) -> impl Iterator<Item = Result<Event>> + '_ {
+    bytes.chunks(EVENT_SIZE).map(Event::from_bytes)
+}
+
+/// Destination of the events of an engine
+pub trait EventRecorder: Sync {
+    /// Record `event`, stamping it with the current time
+    fn record(&self, event: Event);
+}
+
+/// Ring buffer of the `N` most recent events
+pub struct EventLog<const N: usize> {
+    ring: WrtMutex<Ring<N>>,
+}
+
+struct Ring<const N: usize> {
+    events:      [Event; N],
+    /// Index of the oldest event
+    start:       usize,
+    len:         usize,
+    overwritten: u64,
+}
+
+impl<const N: usize> Ring<N> {
+    fn get(&self, index: usize) -> Option<Event> {
+        (index < self.len).then(|| self.events[(self.start + index) % N])
+    }
+}
+
+impl<const N: usize> EventLog<N> {
+    /// An empty log
+    pub const fn new() -> Self {
+        Self {
+            ring: WrtMutex::new(Ring {
+                events:      [Event::new(EventKind::Trap, 0, 0, 0, 0); N],
+                start:       0,
+                len:         0,
+                overwritten: 0,
+            }),
+        }
+    }
+
+    /// Number of events the log holds at most
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Number of events in the log
+    pub fn len(&self) -> usize {
+        self.ring.lock().len
+    }
+
+    /// Whether the log holds no events
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of events overwritten because the log was full
+    pub fn overwritten(&self) -> u64 {
+        self.ring.lock().overwritten
+    }
+
+    /// The event `index` places after the oldest one
+    pub fn get(&self, index: usize) -> Option<Event> {
+        self.ring.lock().get(index)
+    }
+
+    /// Call `f` with each event, oldest first
+    pub fn for_each(&self, mut f: impl FnMut(&Event)) {
+        let ring = self.ring.lock();
+        for index in 0..ring.len {
+            if let Some(event) = ring.get(index) {
+                f(&event);
+            }
+        }
+    }
+
+    /// The events in the log, oldest first
+    #[cfg(feature = "std")]
+    pub fn events(&self) -> Vec<Event> {
+        let mut events = Vec::with_capacity(self.len());
+        self.for_each(|event| events.push(*event));
+        events
+    }
+
+    /// Move as many of the oldest events as fit into `out`, encoded, and
+    /// return the number of bytes written
+    pub fn drain_into(&self, out: &mut [u8]) -> usize {
+        let mut ring = self.ring.lock();
+        let count = ring.len.min(out.len() / EVENT_SIZE);
+        for (index, record) in out.chunks_exact_mut(EVENT_SIZE).take(count).enumerate() {
+            if let Some(event) = ring.get(index) {
+                record.copy_from_slice(&event.to_bytes());
+            }
+        }
+        ring.start = (ring.start + count) % N.max(1);
+        ring.len -= count;
+        count * EVENT_SIZE
+    }
+
+    /// Remove all events and reset the overwritten count
+    pub fn clear(&self) {
+        let mut ring = self.ring.lock();
+        ring.start = 0;
+        ring.len = 0;
+        ring.overwritten = 0;
+    }
+}
+
+impl<const N: usize> Default for EventLog<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> EventRecorder for EventLog<N> {
+    fn record(&self, mut event: Event) {
+        if N == 0 {
+            return;
+        }
+        event.timestamp_ns = time_source::timestamp_ns();
+        let mut ring = self.ring.lock();
+        if ring.len == N {
+            ring.start = (ring.start + 1) % N;
+            ring.len -= 1;
+            ring.overwritten += 1;
+        }
+        let end = (ring.start + ring.len) % N;
+        ring.events[end] = event;
+        ring.len += 1;
+    }
+}
+
+impl<const N: usize> core::fmt::Debug for EventLog<N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("EventLog")
+            .field("capacity", &N)
+            .field("len", &self.len())
+            .field("overwritten", &self.overwritten())
+            .finish()
+    }
+}
+
+/// Observer recording the growth of memories in an event log before passing
+/// it on to the observer of the embedder, if any
+#[cfg(feature = "std")]
+pub(crate) struct LoggedGrowth {
+    log:   &'static dyn EventRecorder,
+    inner: Option<Arc<dyn MemoryObserver>>,
+}
+
+#[cfg(feature = "std")]
+impl LoggedGrowth {
+    pub(crate) fn new(
+        log: &'static dyn EventRecorder,
+        inner: Option<Arc<dyn MemoryObserver>>,
+    ) -> Self {
+        Self { log, inner }
+    }
+}
+
+#[cfg(feature = "std")]
+impl MemoryObserver for LoggedGrowth {
+    fn allow_growth(&self, event: &GrowthEvent) -> bool {
+        self.inner.as_ref().is_none_or(|inner| inner.allow_growth(event))
+    }
+
+    fn grown(&self, event: &GrowthEvent) {
+        if event.kind == GrowthKind::Memory {
+            self.log.record(Event::memory_grow(
+                event.requester.instance,
+                event.requester.index,
+                event.new_size,
+            ));
+        }
+        if let Some(inner) = &self.inner {
+            inner.grown(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_overwrites_oldest() {
+        static LOG: EventLog<2> = EventLog::new();
+        LOG.record(Event::instantiation(0, 0));
+        LOG.record(Event::memory_grow(0, 0, 3));
+        LOG.record(Event::trap(0, 4, &Error::runtime_trap_error("unreachable")));
+
+        assert_eq!(LOG.len(), 2);
+        assert_eq!(LOG.overwritten(), 1);
+        assert_eq!(LOG.get(0).unwrap().kind, EventKind::MemoryGrow);
+        assert_eq!(LOG.get(1).unwrap().kind, EventKind::Trap);
+        assert!(LOG.get(0).unwrap().timestamp_ns <= LOG.get(1).unwrap().timestamp_ns);
+    }
+
+    #[test]
+    fn test_drain_round_trips() {
+        let log = EventLog::<4>::new();
+        let exhausted = Error::fuel_exhausted("Execution ran out of fuel");
+        log.record(Event::for_failure(1, 2, &exhausted, u64::MAX).unwrap());
+        log.record(Event::interceptor_denial(
+            1,
+            7,
+            &Error::runtime_error("denied"),
+        ));
+        assert!(Event::for_failure(1, 2, &Error::resource_not_found("missing"), 0).is_none());
+
+        let mut out = [0u8; EVENT_SIZE + 3];
+        assert_eq!(log.drain_into(&mut out), EVENT_SIZE);
+        assert_eq!(log.len(), 1);
+        let event = Event::from_bytes(&out[..EVENT_SIZE]).unwrap();
+        assert_eq!(event.kind, EventKind::FuelExhausted);
+        assert_eq!((event.instance, event.index, event.value), (1, 2, u32::MAX));
+
+        let mut out = [0u8; 4 * EVENT_SIZE];
+        let written = log.drain_into(&mut out);
+        let events: Vec<_> = decode_events(&out[..written]).collect::<Result<_>>().unwrap();
+        assert_eq!(events[0].kind, EventKind::InterceptorDenial);
+        assert_eq!(events[0].code, codes::RUNTIME_ERROR);
+        assert!(log.is_empty());
+        assert!(Event::from_bytes(&[0; EVENT_SIZE]).is_err());
+    }
+}
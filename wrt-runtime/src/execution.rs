@@ -57,6 +57,12 @@ pub struct ExecutionStats {
     pub gas_limit:                u64,
     /// Number of SIMD operations executed
     pub simd_operations_executed: u64,
+    /// Number of times a memory grew
+    pub memory_grows:             u64,
+    /// Number of memory pages added by growth
+    pub pages_grown:              u64,
+    /// Number of times a table grew
+    pub table_grows:              u64,
 }
 
 impl ExecutionStats {
@@ -0,0 +1,112 @@
+//! Backends executing guest functions
+//!
+//! An [`ExecutionBackend`] runs a function of an instance and returns its
+//! results. The [`StacklessEngine`] interpreter is the backend of every build
+//! and the only one in builds without the `jit` feature. With it, the
+//! [`jit`](crate::jit) module adds a backend that runs hot functions from
+//! code compiled at run time and everything else on the interpreter.
+//!
+//! Backends are interchangeable: a function returns the same results, traps
+//! the same way and consumes the same fuel at its entry whichever backend
+//! runs it. Functions the guest imports are called through the
+//! [`HostImports`] the caller passes along.
+
+use wrt_error::Result;
+use wrt_foundation::values::Value;
+
+use crate::{
+    prelude::*,
+    stackless::StacklessEngine,
+};
+
+/// Functions the host provides for the imports of instances
+pub trait HostImports {
+    /// Call the function `name` of `module` imported by the instance
+    /// `instance_id` with `args`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the function is not defined or fails.
+    fn call_import(
+        &mut self,
+        instance_id: usize,
+        module: &str,
+        name: &str,
+        args: Vec<Value>,
+    ) -> Result<Vec<Value>>;
+}
+
+/// Host of instances without function imports
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoHostImports;
+
+impl HostImports for NoHostImports {
+    fn call_import(
+        &mut self,
+        _instance_id: usize,
+        _module: &str,
+        _name: &str,
+        _args: Vec<Value>,
+    ) -> Result<Vec<Value>> {
+        Err(wrt_error::Error::resource_not_found(
+            "Function import not defined",
+        ))
+    }
+}
+
+/// Executes guest functions
+pub trait ExecutionBackend {
+    /// Name of the backend, for diagnostics
+    fn name(&self) -> &'static str;
+
+    /// Whether the backend may generate machine code at run time
+    fn generates_code(&self) -> bool {
+        false
+    }
+
+    /// Execute function `func_idx` of the instance `instance_id` with `args`,
+    /// calling the functions it imports on `host`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the instance or function does not exist or the
+    /// function traps.
+    fn execute(
+        &self,
+        instance_id: usize,
+        func_idx: usize,
+        args: Vec<Value>,
+        host: &mut dyn HostImports,
+    ) -> Result<Vec<Value>>;
+}
+
+impl ExecutionBackend for StacklessEngine {
+    fn name(&self) -> &'static str {
+        "interpreter"
+    }
+
+    fn execute(
+        &self,
+        instance_id: usize,
+        func_idx: usize,
+        args: Vec<Value>,
+        host: &mut dyn HostImports,
+    ) -> Result<Vec<Value>> {
+        StacklessEngine::execute(self, instance_id, func_idx, args, host)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interpreter_is_the_default_backend() {
+        let interpreter = StacklessEngine::new();
+        let backend: &dyn ExecutionBackend = &interpreter;
+
+        assert_eq!(backend.name(), "interpreter");
+        assert!(!backend.generates_code());
+        assert!(backend.execute(1, 0, Vec::new(), &mut NoHostImports).is_err());
+    }
+}
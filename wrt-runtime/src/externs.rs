@@ -0,0 +1,297 @@
+//! Typed handles to the exports of module instances
+//!
+//! Looking up an export yields an [`Extern`] that carries the full type of
+//! the exported item and, for memories, tables and globals, a handle sharing
+//! the instance's storage, so embedders can inspect and use exports without
+//! walking `module.exports` and resolving indices themselves.
+//!
+//! An [`InstanceGlobal`] reads and writes an exported global through its
+//! instance, so that writes are seen by the guest.
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_foundation::{
+    types::{
+        FuncType as WrtFuncType,
+        GlobalType as WrtGlobalType,
+        TableType as WrtTableType,
+        ValueType as WrtValueType,
+    },
+    values::{
+        FloatBits32,
+        FloatBits64,
+        Value as WrtValue,
+    },
+};
+
+use crate::{
+    bounded_runtime_infra::RuntimeProvider,
+    module::{
+        ExportKind,
+        GlobalWrapper,
+        MemoryWrapper,
+        TableWrapper,
+    },
+    module_instance::ModuleInstance,
+    prelude::CoreMemoryType,
+};
+
+/// Exported function
+#[derive(Debug, Clone)]
+pub struct FuncExport {
+    /// Index of the function in the module
+    pub index: u32,
+    /// Parameter and result types
+    pub ty:    WrtFuncType<RuntimeProvider>,
+}
+
+/// Exported memory
+#[derive(Debug, Clone)]
+pub struct MemoryExport {
+    /// Index of the memory in the instance
+    pub index:  u32,
+    /// Limits and sharing of the memory
+    pub ty:     CoreMemoryType,
+    /// The memory itself
+    pub memory: MemoryWrapper,
+}
+
+/// Exported table
+#[derive(Debug, Clone)]
+pub struct TableExport {
+    /// Index of the table in the instance
+    pub index: u32,
+    /// Element type and limits of the table
+    pub ty:    WrtTableType,
+    /// The table itself
+    pub table: TableWrapper,
+}
+
+/// Exported global
+#[derive(Debug, Clone)]
+pub struct GlobalExport {
+    /// Index of the global in the instance
+    pub index:  u32,
+    /// Value type and mutability of the global
+    pub ty:     WrtGlobalType,
+    /// The global itself
+    pub global: GlobalWrapper,
+}
+
+/// Export of an instance with its full type
+#[derive(Debug, Clone)]
+pub enum Extern {
+    /// Function export
+    Func(FuncExport),
+    /// Memory export
+    Memory(MemoryExport),
+    /// Table export
+    Table(TableExport),
+    /// Global export
+    Global(GlobalExport),
+}
+
+impl Extern {
+    /// Kind of the exported item
+    pub fn kind(&self) -> ExportKind {
+        match self {
+            Self::Func(_) => ExportKind::Function,
+            Self::Memory(_) => ExportKind::Memory,
+            Self::Table(_) => ExportKind::Table,
+            Self::Global(_) => ExportKind::Global,
+        }
+    }
+
+    /// The function, if this is a function export
+    pub fn into_func(self) -> Option<FuncExport> {
+        match self {
+            Self::Func(func) => Some(func),
+            _ => None,
+        }
+    }
+
+    /// The memory, if this is a memory export
+    pub fn into_memory(self) -> Option<MemoryExport> {
+        match self {
+            Self::Memory(memory) => Some(memory),
+            _ => None,
+        }
+    }
+
+    /// The table, if this is a table export
+    pub fn into_table(self) -> Option<TableExport> {
+        match self {
+            Self::Table(table) => Some(table),
+            _ => None,
+        }
+    }
+
+    /// The global, if this is a global export
+    pub fn into_global(self) -> Option<GlobalExport> {
+        match self {
+            Self::Global(global) => Some(global),
+            _ => None,
+        }
+    }
+}
+
+/// Rust type a global of the matching value type is read and written as
+pub trait GlobalValue: Sized {
+    /// Value type of globals holding this type
+    const VALUE_TYPE: WrtValueType;
+
+    /// Extract the value, if it has [`Self::VALUE_TYPE`]
+    fn from_value(value: &WrtValue) -> Option<Self>;
+
+    /// Wrap the value
+    fn into_value(self) -> WrtValue;
+}
+
+impl GlobalValue for i32 {
+    const VALUE_TYPE: WrtValueType = WrtValueType::I32;
+
+    fn from_value(value: &WrtValue) -> Option<Self> {
+        match value {
+            WrtValue::I32(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    fn into_value(self) -> WrtValue {
+        WrtValue::I32(self)
+    }
+}
+
+impl GlobalValue for i64 {
+    const VALUE_TYPE: WrtValueType = WrtValueType::I64;
+
+    fn from_value(value: &WrtValue) -> Option<Self> {
+        match value {
+            WrtValue::I64(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    fn into_value(self) -> WrtValue {
+        WrtValue::I64(self)
+    }
+}
+
+impl GlobalValue for f32 {
+    const VALUE_TYPE: WrtValueType = WrtValueType::F32;
+
+    fn from_value(value: &WrtValue) -> Option<Self> {
+        match value {
+            WrtValue::F32(v) => Some(v.value()),
+            _ => None,
+        }
+    }
+
+    fn into_value(self) -> WrtValue {
+        WrtValue::F32(FloatBits32::from_float(self))
+    }
+}
+
+impl GlobalValue for f64 {
+    const VALUE_TYPE: WrtValueType = WrtValueType::F64;
+
+    fn from_value(value: &WrtValue) -> Option<Self> {
+        match value {
+            WrtValue::F64(v) => Some(v.value()),
+            _ => None,
+        }
+    }
+
+    fn into_value(self) -> WrtValue {
+        WrtValue::F64(FloatBits64::from_float(self))
+    }
+}
+
+/// Exported global read and written through its instance
+#[derive(Debug, Clone, Copy)]
+pub struct InstanceGlobal<'a> {
+    instance: &'a ModuleInstance,
+    index:    u32,
+    ty:       WrtGlobalType,
+}
+
+impl<'a> InstanceGlobal<'a> {
+    pub(crate) fn new(instance: &'a ModuleInstance, index: u32, ty: WrtGlobalType) -> Self {
+        Self {
+            instance,
+            index,
+            ty,
+        }
+    }
+
+    /// Index of the global in the instance
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Value type and mutability of the global
+    pub fn ty(&self) -> &WrtGlobalType {
+        &self.ty
+    }
+
+    /// Whether the global can be written
+    pub fn is_mutable(&self) -> bool {
+        self.ty.mutable
+    }
+
+    /// Current value of the global
+    pub fn get_value(&self) -> Result<WrtValue> {
+        self.instance.global_value(self.index)
+    }
+
+    /// Write the global
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the global is immutable or `value` has another
+    /// type.
+    pub fn set_value(&self, value: WrtValue) -> Result<()> {
+        if !self.ty.mutable {
+            return Err(Error::runtime_invalid_argument("Global is immutable"));
+        }
+        if !value.matches_type(&self.ty.value_type) {
+            return Err(Error::runtime_type_mismatch(
+                "Value does not match global type",
+            ));
+        }
+        self.instance.set_global_value(self.index, value)
+    }
+
+    /// Current value of the global as `T`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the global does not hold a `T`.
+    pub fn get<T: GlobalValue>(&self) -> Result<T> {
+        self.check_type::<T>()?;
+        T::from_value(&self.get_value()?)
+            .ok_or_else(|| Error::runtime_type_mismatch("Global value has unexpected type"))
+    }
+
+    /// Write the global from a `T`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the global is immutable or does not hold a `T`.
+    pub fn set<T: GlobalValue>(&self, value: T) -> Result<()> {
+        self.check_type::<T>()?;
+        self.set_value(value.into_value())
+    }
+
+    fn check_type<T: GlobalValue>(&self) -> Result<()> {
+        if self.ty.value_type == T::VALUE_TYPE {
+            Ok(())
+        } else {
+            Err(Error::runtime_type_mismatch(
+                "Global has another value type",
+            ))
+        }
+    }
+}
@@ -89,6 +89,29 @@ pub fn set(&mut self, new_value: &WrtValue) -> Result<()> {
     pub fn global_type_descriptor(&self) -> &WrtGlobalType {
         &self.ty
     }
+
+    /// Checks whether this global can satisfy an import of the given type
+    ///
+    /// Value type and mutability must both match exactly.
+    ///
+    /// # Errors
+    ///
+    /// Returns a type mismatch error describing the first incompatibility
+    pub fn check_import_compatibility(&self, required: &WrtGlobalType) -> Result<()> {
+        if self.ty.value_type != required.value_type {
+            return Err(Error::runtime_type_mismatch(
+                "Imported global value type does not match",
+            ));
+        }
+
+        if self.ty.mutable != required.mutable {
+            return Err(Error::runtime_type_mismatch(
+                "Imported global mutability does not match",
+            ));
+        }
+
+        Ok(())
+    }
 }
 
 impl Default for Global {
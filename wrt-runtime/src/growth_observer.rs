@@ -0,0 +1,238 @@
+//! Notifications about growing memories and tables
+//!
+//! An embedder that accounts for the memory of its guests registers a
+//! [`MemoryObserver`] with the engine. The engine attaches it to every memory
+//! and table an instance defines, and each `grow` of one of them, whether
+//! requested by the guest or by the host, asks the observer to allow the
+//! growth and tells it once the growth happened. Both calls carry a
+//! [`GrowthEvent`] naming the instance and index of the memory or table and
+//! its sizes before and after the growth.
+//!
+//! A vetoed growth fails like a growth beyond the maximum of the memory or
+//! table, so `memory.grow` and `table.grow` return -1 to the guest. Memories
+//! and tables imported from the host are owned by the host and are not
+//! observed; a clone of a memory or table is a separate object and is not
+//! observed either.
+
+use alloc::sync::Arc;
+use core::{
+    fmt,
+    sync::atomic::{
+        AtomicU64,
+        Ordering,
+    },
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+
+use crate::execution::ExecutionStats;
+
+/// Kind of object that grows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GrowthKind {
+    /// A linear memory, sized in pages
+    Memory,
+    /// A table, sized in elements
+    Table,
+}
+
+/// Memory or table a growth was requested for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GrowthRequester {
+    /// Index of the instance that defines the memory or table
+    pub instance: usize,
+    /// Index of the memory or table in the index space of the instance
+    pub index:    u32,
+}
+
+/// A requested growth of a memory or table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GrowthEvent {
+    /// Whether a memory or a table grows
+    pub kind:      GrowthKind,
+    /// The growing memory or table
+    pub requester: GrowthRequester,
+    /// Size before the growth, in pages or elements
+    pub old_size:  u32,
+    /// Size after the growth, in pages or elements
+    pub new_size:  u32,
+}
+
+impl GrowthEvent {
+    /// Number of pages or elements added
+    pub fn delta(&self) -> u32 {
+        self.new_size - self.old_size
+    }
+}
+
+/// Observer of memory and table growth
+pub trait MemoryObserver: Send + Sync {
+    /// Whether the growth described by `event` may happen
+    fn allow_growth(&self, _event: &GrowthEvent) -> bool {
+        true
+    }
+
+    /// Called after the growth described by `event` happened
+    fn grown(&self, _event: &GrowthEvent) {}
+}
+
+/// Observer attached to a memory or table
+#[derive(Clone)]
+pub(crate) struct GrowthHook {
+    observer:  Arc<dyn MemoryObserver>,
+    requester: GrowthRequester,
+}
+
+impl GrowthHook {
+    pub(crate) fn new(observer: Arc<dyn MemoryObserver>, requester: GrowthRequester) -> Self {
+        Self {
+            observer,
+            requester,
+        }
+    }
+
+    /// Event for growing a `kind` from `old_size` to `new_size`
+    pub(crate) fn event(&self, kind: GrowthKind, old_size: u32, new_size: u32) -> GrowthEvent {
+        GrowthEvent {
+            kind,
+            requester: self.requester,
+            old_size,
+            new_size,
+        }
+    }
+
+    /// Ask the observer to allow `event`
+    pub(crate) fn check(&self, event: &GrowthEvent) -> Result<()> {
+        if self.observer.allow_growth(event) {
+            Ok(())
+        } else {
+            Err(Error::resource_limit_exceeded(
+                "Growth vetoed by memory observer",
+            ))
+        }
+    }
+
+    /// Tell the observer that `event` happened
+    pub(crate) fn grown(&self, event: &GrowthEvent) {
+        self.observer.grown(event);
+    }
+}
+
+impl fmt::Debug for GrowthHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GrowthHook")
+            .field("requester", &self.requester)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Observer enforcing a quota on the memory pages all observed memories
+/// may add together
+#[derive(Debug)]
+pub struct PageQuota {
+    limit: u64,
+    used:  AtomicU64,
+}
+
+impl PageQuota {
+    /// Quota allowing `limit` pages to be added by growth
+    pub fn new(limit: u64) -> Self {
+        Self {
+            limit,
+            used: AtomicU64::new(0),
+        }
+    }
+
+    /// Pages added so far
+    pub fn used(&self) -> u64 {
+        self.used.load(Ordering::Acquire)
+    }
+
+    /// Pages that may still be added
+    pub fn remaining(&self) -> u64 {
+        self.limit.saturating_sub(self.used())
+    }
+}
+
+impl MemoryObserver for PageQuota {
+    fn allow_growth(&self, event: &GrowthEvent) -> bool {
+        if event.kind != GrowthKind::Memory {
+            return true;
+        }
+        // Reserve the pages now, so that concurrent growths cannot exceed
+        // the quota together
+        self.used
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |used| {
+                used.checked_add(u64::from(event.delta())).filter(|total| *total <= self.limit)
+            })
+            .is_ok()
+    }
+}
+
+impl ExecutionStats {
+    /// Account for a memory or table growth
+    pub fn record_growth(&mut self, event: &GrowthEvent) {
+        match event.kind {
+            GrowthKind::Memory => {
+                self.memory_grows = self.memory_grows.saturating_add(1);
+                self.pages_grown = self.pages_grown.saturating_add(u64::from(event.delta()));
+                self.update_memory_usage(event.delta() as usize * crate::memory::PAGE_SIZE);
+            },
+            GrowthKind::Table => {
+                self.table_grows = self.table_grows.saturating_add(1);
+            },
+        }
+    }
+}
+
+impl MemoryObserver for std::sync::Mutex<ExecutionStats> {
+    fn grown(&self, event: &GrowthEvent) {
+        if let Ok(mut stats) = self.lock() {
+            stats.record_growth(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(kind: GrowthKind, old_size: u32, new_size: u32) -> GrowthEvent {
+        GrowthEvent {
+            kind,
+            requester: GrowthRequester {
+                instance: 0,
+                index:    0,
+            },
+            old_size,
+            new_size,
+        }
+    }
+
+    #[test]
+    fn test_page_quota() {
+        let quota = PageQuota::new(3);
+        assert!(quota.allow_growth(&event(GrowthKind::Memory, 1, 3)));
+        assert!(!quota.allow_growth(&event(GrowthKind::Memory, 3, 5)));
+        assert!(quota.allow_growth(&event(GrowthKind::Table, 0, 100)));
+        assert!(quota.allow_growth(&event(GrowthKind::Memory, 3, 4)));
+        assert_eq!(quota.used(), 3);
+        assert_eq!(quota.remaining(), 0);
+    }
+
+    #[test]
+    fn test_stats_record_growth() {
+        let stats = std::sync::Mutex::new(ExecutionStats::new());
+        stats.grown(&event(GrowthKind::Memory, 1, 3));
+        stats.grown(&event(GrowthKind::Table, 0, 10));
+
+        let stats = stats.into_inner().unwrap();
+        assert_eq!(stats.memory_grows, 1);
+        assert_eq!(stats.table_grows, 1);
+        assert_eq!(stats.pages_grown, 2);
+        assert_eq!(stats.memory_usage, 2 * crate::memory::PAGE_SIZE);
+    }
+}
@@ -0,0 +1,350 @@
+//! Coverage of guest code
+//!
+//! With coverage enabled, see
+//! [`CapabilityAwareEngine::set_guest_coverage`](crate::engine::CapabilityAwareEngine::set_guest_coverage),
+//! the engine splits the [lowered code](crate::stackless::code) of every
+//! function of a loaded module into basic blocks, and counts how often each
+//! function is called and each block is entered. A block starts at the first
+//! operation of a function, at every operation a branch leads to, and after
+//! every operation that branches, returns or traps. Blocks are identified by
+//! the position of their first operation in the lowered function.
+//!
+//! The interpreter counts the calls and blocks of the functions it runs,
+//! whether the host or another guest function called them. Functions run by a
+//! JIT backend count their calls but not their blocks.
+//!
+//! The counts of a module are shared by all of its instances and can be
+//! written out in an LCOV-like format with [`GuestCoverage::write_lcov`].
+
+use core::{
+    fmt,
+    sync::atomic::{
+        AtomicU64,
+        Ordering,
+    },
+};
+
+use wrt_decoder::name_section::{
+    FunctionSymbol,
+    NameMap,
+};
+
+use crate::{
+    module::Module,
+    prelude::*,
+    stackless::code::{
+        FunctionCode,
+        OpCode,
+    },
+};
+
+/// Marks an operation that does not start a block
+const NO_BLOCK: u32 = u32::MAX;
+
+/// A basic block of a function
+#[derive(Debug)]
+struct Block {
+    op:    u32,
+    count: AtomicU64,
+}
+
+/// Blocks and call count of a function with a body
+#[derive(Debug)]
+struct FunctionBlocks {
+    calls:    AtomicU64,
+    blocks:   Vec<Block>,
+    /// Index in `blocks` of the block starting at each operation, or
+    /// [`NO_BLOCK`]
+    block_at: Vec<u32>,
+}
+
+/// Execution count of a basic block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockCount {
+    /// Position of the first operation of the block in the lowered function
+    pub op:    u32,
+    /// Number of times the block was entered
+    pub count: u64,
+}
+
+/// Call and block counts of the functions of a module
+#[derive(Debug)]
+pub struct GuestCoverage {
+    imported_functions: u32,
+    functions:          Vec<FunctionBlocks>,
+}
+
+impl GuestCoverage {
+    /// Split the functions of `module` into basic blocks, with all counts
+    /// zero
+    pub fn for_module(module: &Module) -> Self {
+        let functions = module
+            .code
+            .functions
+            .iter()
+            .map(|function| {
+                let starts = split_blocks(function);
+                let mut block_at = vec![NO_BLOCK; function.ops.len()];
+                for (block, &op) in starts.iter().enumerate() {
+                    block_at[op as usize] = block as u32;
+                }
+                FunctionBlocks {
+                    calls: AtomicU64::new(0),
+                    blocks: starts
+                        .into_iter()
+                        .map(|op| Block {
+                            op,
+                            count: AtomicU64::new(0),
+                        })
+                        .collect(),
+                    block_at,
+                }
+            })
+            .collect();
+        Self {
+            imported_functions: module.code.imports.len() as u32,
+            functions,
+        }
+    }
+
+    fn function(&self, func_idx: u32) -> Option<&FunctionBlocks> {
+        func_idx
+            .checked_sub(self.imported_functions)
+            .and_then(|defined| self.functions.get(defined as usize))
+    }
+
+    /// Count a call of the function at `func_idx` in the function index
+    /// space
+    ///
+    /// Calls of imported functions are not counted.
+    pub fn record_call(&self, func_idx: u32) {
+        if let Some(function) = self.function(func_idx) {
+            function.calls.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Count reaching operation `op` of the function at `func_idx`, which
+    /// enters a block if one starts there
+    ///
+    /// Operations that do not start a block are ignored, so the interpreter
+    /// can report every operation it executes.
+    pub fn record_block(&self, func_idx: u32, op: u32) {
+        if let Some(function) = self.function(func_idx) {
+            if let Some(&block) = function.block_at.get(op as usize) {
+                if block != NO_BLOCK {
+                    function.blocks[block as usize].count.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+    }
+
+    /// Number of calls of the function at `func_idx`, or `None` if it has no
+    /// body in the module
+    pub fn calls(&self, func_idx: u32) -> Option<u64> {
+        self.function(func_idx).map(|function| function.calls.load(Ordering::Relaxed))
+    }
+
+    /// Execution counts of the blocks of the function at `func_idx`, in
+    /// operation order
+    pub fn blocks(&self, func_idx: u32) -> impl Iterator<Item = BlockCount> + '_ {
+        self.function(func_idx).into_iter().flat_map(|function| {
+            function.blocks.iter().map(|block| BlockCount {
+                op:    block.op,
+                count: block.count.load(Ordering::Relaxed),
+            })
+        })
+    }
+
+    /// Indices of the functions with a body, in the function index space
+    pub fn functions(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..self.functions.len() as u32).map(move |defined| defined + self.imported_functions)
+    }
+
+    /// Reset all counts to zero
+    pub fn reset(&self) {
+        for function in &self.functions {
+            function.calls.store(0, Ordering::Relaxed);
+            for block in &function.blocks {
+                block.count.store(0, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Write the counts in LCOV's tracefile format
+    ///
+    /// Each function becomes a source file `<module>/<function>`, named
+    /// after its symbol as in `names`. The function is declared at line 0,
+    /// and each block is reported as a line numbered by the position of its
+    /// first operation, counting from 1.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `out` fails.
+    pub fn write_lcov(
+        &self,
+        out: &mut impl fmt::Write,
+        module: &str,
+        names: Option<&NameMap>,
+    ) -> fmt::Result {
+        writeln!(out, "TN:")?;
+        for func_idx in self.functions() {
+            let symbol = match names {
+                Some(names) => names.symbolicate(func_idx),
+                None => FunctionSymbol {
+                    index: func_idx,
+                    name:  None,
+                },
+            };
+            let calls = self.calls(func_idx).unwrap_or(0);
+            writeln!(out, "SF:{module}/{symbol}")?;
+            writeln!(out, "FN:0,{symbol}")?;
+            writeln!(out, "FNDA:{calls},{symbol}")?;
+            writeln!(out, "FNF:1")?;
+            writeln!(out, "FNH:{}", u8::from(calls > 0))?;
+            let (mut found, mut hit) = (0, 0);
+            for block in self.blocks(func_idx) {
+                writeln!(out, "DA:{},{}", block.op + 1, block.count)?;
+                found += 1;
+                hit += u32::from(block.count > 0);
+            }
+            writeln!(out, "LF:{found}")?;
+            writeln!(out, "LH:{hit}")?;
+            writeln!(out, "end_of_record")?;
+        }
+        Ok(())
+    }
+}
+
+/// Positions of the first operation of each basic block of a lowered
+/// function, in order
+fn split_blocks(function: &FunctionCode) -> Vec<u32> {
+    let mut starts = vec![0];
+    for (position, op) in function.ops.iter().enumerate() {
+        let next = position as u32 + 1;
+        match op.code {
+            OpCode::Br | OpCode::BrIf => starts.extend([op.target().pc, next]),
+            OpCode::BrTable => {
+                let targets = &function.targets[op.a as usize..(op.a + op.b) as usize];
+                starts.extend(targets.iter().map(|target| target.pc));
+                starts.push(next);
+            },
+            OpCode::If | OpCode::Jump => starts.extend([op.a, next]),
+            OpCode::Return | OpCode::Unreachable => starts.push(next),
+            _ => {},
+        }
+    }
+    starts.retain(|&op| (op as usize) < function.ops.len());
+    starts.sort_unstable();
+    starts.dedup();
+    starts
+}
+
+#[cfg(all(test, feature = "wat"))]
+mod tests {
+    use wrt_decoder::decoder::decode_module;
+
+    use super::*;
+
+    /// Counts the odd numbers from its parameter down to 1, and a function
+    /// that is never called
+    const ODD: &str = r#"(module
+        (func (export "odd") (param $n i32) (result i32) (local $odd i32)
+          (loop $next
+            (if (i32.and (local.get $n) (i32.const 1))
+              (then (local.set $odd (i32.add (local.get $odd) (i32.const 1)))))
+            (br_if $next (local.tee $n (i32.sub (local.get $n) (i32.const 1)))))
+          (local.get $odd))
+        (func (export "unused") (nop)))"#;
+
+    fn module() -> Module {
+        let binary = crate::text_format::wat_to_binary(ODD).unwrap();
+        Module::from_wrt_module(&decode_module(&binary).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_split_blocks() {
+        let module = module();
+        // Loop, then branch of the if, after the if, after the loop
+        let starts = split_blocks(&module.code.functions[0]);
+        assert_eq!(starts.len(), 4, "{starts:?}");
+        assert_eq!(split_blocks(&module.code.functions[1]), [0]);
+    }
+
+    #[test]
+    fn test_counts_and_lcov() {
+        let coverage = GuestCoverage::for_module(&module());
+        let starts: Vec<u32> = coverage.blocks(0).map(|block| block.op).collect();
+
+        coverage.record_call(0);
+        coverage.record_block(0, starts[0]);
+        coverage.record_block(0, starts[2]);
+        // Not the start of a block
+        coverage.record_block(0, starts[0] + 1);
+        // No such function
+        coverage.record_call(2);
+
+        assert_eq!(coverage.calls(0), Some(1));
+        assert_eq!(coverage.calls(1), Some(0));
+        assert_eq!(coverage.calls(2), None);
+        let counts: Vec<u64> = coverage.blocks(0).map(|block| block.count).collect();
+        assert_eq!(counts, [1, 0, 1, 0]);
+
+        let mut lcov = String::new();
+        coverage.write_lcov(&mut lcov, "app.wasm", None).unwrap();
+        let [a, b, c, d] = [0, 1, 2, 3].map(|block| starts[block] + 1);
+        assert_eq!(
+            lcov,
+            format!(
+                concat!(
+                    "TN:\nSF:app.wasm/func[0]\nFN:0,func[0]\nFNDA:1,func[0]\nFNF:1\nFNH:1\n",
+                    "DA:{},1\nDA:{},0\nDA:{},1\nDA:{},0\nLF:4\nLH:2\nend_of_record\n",
+                    "SF:app.wasm/func[1]\nFN:0,func[1]\nFNDA:0,func[1]\nFNF:1\nFNH:0\n",
+                    "DA:1,0\nLF:1\nLH:0\nend_of_record\n",
+                ),
+                a, b, c, d
+            )
+        );
+
+        coverage.reset();
+        assert_eq!(coverage.calls(0), Some(0));
+        assert!(coverage.blocks(0).all(|block| block.count == 0));
+    }
+
+    #[test]
+    fn test_interpreter_counts_calls_and_blocks() -> Result<()> {
+        use wrt_foundation::{
+            memory_init::MemoryInitializer,
+            values::Value,
+        };
+
+        use crate::engine::{
+            CapabilityAwareEngine,
+            CapabilityEngine,
+            EnginePreset,
+        };
+
+        MemoryInitializer::ensure_initialized()?;
+        let binary = crate::text_format::wat_to_binary(&ODD.replace(
+            "(func (export \"unused\") (nop))",
+            "(func (export \"both\") (result i32)
+               (i32.add (call 0 (i32.const 5)) (call 0 (i32.const 2))))",
+        ))?;
+        let mut engine = CapabilityAwareEngine::with_preset(EnginePreset::QM)?;
+        engine.set_guest_coverage(true);
+        let module = engine.load_module(&binary)?;
+        let instance = engine.instantiate(module)?;
+        assert_eq!(engine.execute(instance, "both", &[])?, [Value::I32(4)]);
+
+        let coverage = engine.guest_coverage(module).unwrap();
+        assert_eq!(coverage.calls(0), Some(2));
+        assert_eq!(coverage.calls(1), Some(1));
+        // Seven iterations, four of them odd, over the two calls
+        let counts: Vec<u64> = coverage.blocks(0).map(|block| block.count).collect();
+        assert_eq!(counts, [7, 4, 7, 2]);
+        assert_eq!(
+            coverage.blocks(1).map(|block| block.count).collect::<Vec<_>>(),
+            [1]
+        );
+        Ok(())
+    }
+}
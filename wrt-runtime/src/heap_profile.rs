@@ -0,0 +1,483 @@
+//! Profile of guest heap allocations
+//!
+//! Guests manage their heap themselves, inside linear memory, so the engine
+//! only sees allocations through the allocator functions a guest exports:
+//! the canonical ABI `cabi_realloc` the host calls to place strings and lists
+//! when lowering values, and the C conventions `malloc`, `calloc`, `realloc`
+//! and `free`. With heap profiling enabled, see
+//! [`CapabilityAwareEngine::set_heap_profiling`](crate::engine::CapabilityAwareEngine::set_heap_profiling),
+//! every call of such an export is recognized by [`AllocatorCall::recognize`]
+//! and recorded in the [`HeapProfile`] of the instance, which tracks the live
+//! allocations, the bytes live and their peak. Calls from the host are
+//! recorded by the engine, and calls the guest makes from its own functions
+//! by the interpreter once they return.
+//!
+//! Allocations the guest makes without calling one of these exports are not
+//! seen: allocators that are not exported, or inlined into their callers by
+//! the compiler, as well as functions running from compiled code of a
+//! [JIT backend](crate::jit), leave no trace in the profile.
+//!
+//! A call can be attributed to a call site, such as the component function
+//! whose arguments are being lowered, see
+//! [`CapabilityAwareEngine::set_allocation_site`](crate::engine::CapabilityAwareEngine::set_allocation_site).
+//! Allocations and live bytes are then also counted per site. Calls the guest
+//! makes itself are only counted in the totals.
+
+use core::fmt;
+use std::sync::Mutex;
+
+use wrt_foundation::values::Value;
+
+use crate::{
+    module::{
+        ExportKind,
+        Module,
+    },
+    prelude::*,
+};
+
+/// Largest number of call sites a [`HeapProfile`] counts separately; further
+/// sites are only included in the totals
+pub const MAX_CALL_SITES: usize = 256;
+
+/// A call of a guest allocator function
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AllocatorCall {
+    /// `size` bytes were allocated at `ptr`, null if the allocation failed
+    Allocate {
+        /// Address of the allocation
+        ptr:  u64,
+        /// Size of the allocation in bytes
+        size: u64,
+    },
+    /// The allocation at `old_ptr` was resized to `size` bytes at `new_ptr`,
+    /// null if the reallocation failed
+    Reallocate {
+        /// Address of the allocation before the call
+        old_ptr: u64,
+        /// Address of the allocation after the call
+        new_ptr: u64,
+        /// Size of the allocation after the call in bytes
+        size:    u64,
+    },
+    /// The allocation at `ptr` was freed
+    Free {
+        /// Address of the allocation
+        ptr: u64,
+    },
+}
+
+impl AllocatorCall {
+    /// Recognize a call of the export `name` with `args` returning `results`
+    /// as a call of a guest allocator
+    ///
+    /// Returns `None` if `name` is not an allocator convention or the
+    /// arguments do not match it.
+    pub fn recognize(name: &str, args: &[Value], results: &[Value]) -> Option<Self> {
+        let int = |value: &Value| match value {
+            Value::I32(value) => Some(*value as u32 as u64),
+            Value::I64(value) => Some(*value as u64),
+            _ => None,
+        };
+        let ints = |values: &[Value]| values.iter().map(int).collect::<Option<Vec<u64>>>();
+        let args = ints(args)?;
+        let results = ints(results)?;
+
+        match (name, args.as_slice(), results.as_slice()) {
+            ("cabi_realloc", &[0, _, _, size], &[ptr]) => Some(Self::Allocate { ptr, size }),
+            ("cabi_realloc", &[old_ptr, _, _, size], &[new_ptr])
+            | ("realloc", &[old_ptr, size], &[new_ptr]) => {
+                if old_ptr == 0 {
+                    Some(Self::Allocate { ptr: new_ptr, size })
+                } else {
+                    Some(Self::Reallocate {
+                        old_ptr,
+                        new_ptr,
+                        size,
+                    })
+                }
+            },
+            ("malloc", &[size], &[ptr]) => Some(Self::Allocate { ptr, size }),
+            ("calloc", &[count, size], &[ptr]) => Some(Self::Allocate {
+                ptr,
+                size: count.saturating_mul(size),
+            }),
+            ("free", &[ptr], &[]) => Some(Self::Free { ptr }),
+            _ => None,
+        }
+    }
+}
+
+/// Names of the exports [`AllocatorCall::recognize`] knows
+pub const ALLOCATOR_EXPORTS: [&str; 5] = ["cabi_realloc", "malloc", "calloc", "realloc", "free"];
+
+/// Heap profile of an instance with the allocator functions its module
+/// exports, so the interpreter can record the calls the guest makes itself
+#[derive(Debug)]
+pub(crate) struct ProfiledHeap {
+    pub(crate) profile: Arc<HeapProfile>,
+    /// Exported allocator functions as `(function index, export name)`
+    allocators:         Vec<(u32, &'static str)>,
+}
+
+impl ProfiledHeap {
+    pub(crate) fn new(profile: Arc<HeapProfile>, module: &Module) -> Self {
+        let allocators = ALLOCATOR_EXPORTS
+            .iter()
+            .filter_map(|name| {
+                let export = module.get_export(name)?;
+                (export.kind == ExportKind::Function).then_some((export.index, *name))
+            })
+            .collect();
+        Self {
+            profile,
+            allocators,
+        }
+    }
+
+    /// Export name of the function at `func_idx` if it is an allocator
+    pub(crate) fn allocator(&self, func_idx: u32) -> Option<&'static str> {
+        self.allocators
+            .iter()
+            .find(|(index, _)| *index == func_idx)
+            .map(|(_, name)| *name)
+    }
+}
+
+/// Totals of a [`HeapProfile`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HeapSummary {
+    /// Bytes in live allocations
+    pub live_bytes:       u64,
+    /// Largest number of bytes live at any time
+    pub peak_bytes:       u64,
+    /// Number of live allocations
+    pub live_allocations: u64,
+    /// Allocations made, including reallocations that moved or resized
+    pub allocations:      u64,
+    /// Allocations freed
+    pub frees:            u64,
+    /// Allocations and reallocations that returned null
+    pub failed:           u64,
+    /// Frees and reallocations of addresses not allocated while profiling
+    pub unknown_frees:    u64,
+}
+
+/// Allocation counts of a call site
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallSite {
+    /// Name of the call site
+    pub name:        String,
+    /// Allocations made from the site
+    pub allocations: u64,
+    /// Bytes allocated from the site
+    pub bytes:       u64,
+    /// Bytes of the allocations made from the site that are still live
+    pub live_bytes:  u64,
+}
+
+/// A live allocation
+#[derive(Debug, Clone, Copy)]
+struct Allocation {
+    size: u64,
+    site: Option<usize>,
+}
+
+#[derive(Debug, Default)]
+struct ProfileState {
+    summary: HeapSummary,
+    live:    HashMap<u64, Allocation>,
+    sites:   Vec<CallSite>,
+}
+
+impl ProfileState {
+    fn site(&mut self, name: Option<&str>) -> Option<usize> {
+        let name = name?;
+        if let Some(index) = self.sites.iter().position(|site| site.name == name) {
+            return Some(index);
+        }
+        if self.sites.len() >= MAX_CALL_SITES {
+            return None;
+        }
+        self.sites.push(CallSite {
+            name:        name.into(),
+            allocations: 0,
+            bytes:       0,
+            live_bytes:  0,
+        });
+        Some(self.sites.len() - 1)
+    }
+
+    fn allocate(&mut self, ptr: u64, size: u64, site: Option<usize>) {
+        if ptr == 0 {
+            self.summary.failed += 1;
+            return;
+        }
+        // A guest allocator never hands out a live address twice, so a known
+        // address was freed behind the profiler's back
+        self.release(ptr);
+        self.live.insert(ptr, Allocation { size, site });
+        self.summary.allocations += 1;
+        self.summary.live_allocations += 1;
+        self.summary.live_bytes += size;
+        self.summary.peak_bytes = self.summary.peak_bytes.max(self.summary.live_bytes);
+        if let Some(site) = site.and_then(|site| self.sites.get_mut(site)) {
+            site.allocations += 1;
+            site.bytes += size;
+            site.live_bytes += size;
+        }
+    }
+
+    fn release(&mut self, ptr: u64) -> bool {
+        let Some(allocation) = self.live.remove(&ptr) else {
+            return false;
+        };
+        self.summary.live_allocations -= 1;
+        self.summary.live_bytes -= allocation.size;
+        if let Some(site) = allocation.site.and_then(|site| self.sites.get_mut(site)) {
+            site.live_bytes -= allocation.size;
+        }
+        true
+    }
+}
+
+/// Live allocations and allocation counts of a guest heap
+#[derive(Debug, Default)]
+pub struct HeapProfile {
+    state: Mutex<ProfileState>,
+}
+
+impl HeapProfile {
+    /// An empty profile
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record `call`, made from the call site `site` if it is known
+    pub fn record(&self, call: AllocatorCall, site: Option<&str>) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        let site = state.site(site);
+        match call {
+            AllocatorCall::Allocate { ptr, size } => state.allocate(ptr, size, site),
+            AllocatorCall::Reallocate {
+                old_ptr,
+                new_ptr,
+                size,
+            } => {
+                // A failed reallocation leaves the old allocation in place
+                if new_ptr == 0 {
+                    state.summary.failed += 1;
+                    return;
+                }
+                if !state.release(old_ptr) {
+                    state.summary.unknown_frees += 1;
+                }
+                state.allocate(new_ptr, size, site);
+            },
+            AllocatorCall::Free { ptr: 0 } => {},
+            AllocatorCall::Free { ptr } => {
+                if state.release(ptr) {
+                    state.summary.frees += 1;
+                } else {
+                    state.summary.unknown_frees += 1;
+                }
+            },
+        }
+    }
+
+    /// Totals of the profile
+    pub fn summary(&self) -> HeapSummary {
+        self.state.lock().map(|state| state.summary).unwrap_or_default()
+    }
+
+    /// Call sites with allocations, those with the most bytes allocated
+    /// first
+    pub fn call_sites(&self) -> Vec<CallSite> {
+        let mut sites = self.state.lock().map(|state| state.sites.clone()).unwrap_or_default();
+        sites.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.name.cmp(&b.name)));
+        sites
+    }
+
+    /// Live allocations as `(address, size)`, by address
+    pub fn live_allocations(&self) -> Vec<(u64, u64)> {
+        let mut live: Vec<(u64, u64)> = self
+            .state
+            .lock()
+            .map(|state| {
+                state.live.iter().map(|(ptr, allocation)| (*ptr, allocation.size)).collect()
+            })
+            .unwrap_or_default();
+        live.sort_unstable();
+        live
+    }
+}
+
+impl fmt::Display for HeapProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let summary = self.summary();
+        writeln!(
+            f,
+            "live: {} bytes in {} allocations",
+            summary.live_bytes, summary.live_allocations
+        )?;
+        writeln!(f, "peak: {} bytes", summary.peak_bytes)?;
+        writeln!(
+            f,
+            "allocations: {}, frees: {}, failed: {}, unknown frees: {}",
+            summary.allocations, summary.frees, summary.failed, summary.unknown_frees
+        )?;
+        let sites = self.call_sites();
+        if !sites.is_empty() {
+            writeln!(f, "call sites:")?;
+        }
+        for site in sites {
+            writeln!(
+                f,
+                "  {}: {} allocations, {} bytes, {} bytes live",
+                site.name, site.allocations, site.bytes, site.live_bytes
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recognize_allocator_calls() {
+        let i32s =
+            |values: &[i32]| values.iter().map(|value| Value::I32(*value)).collect::<Vec<_>>();
+        assert_eq!(
+            AllocatorCall::recognize("cabi_realloc", &i32s(&[0, 0, 8, 64]), &i32s(&[1024])),
+            Some(AllocatorCall::Allocate {
+                ptr:  1024,
+                size: 64,
+            })
+        );
+        assert_eq!(
+            AllocatorCall::recognize("realloc", &i32s(&[1024, 128]), &i32s(&[2048])),
+            Some(AllocatorCall::Reallocate {
+                old_ptr: 1024,
+                new_ptr: 2048,
+                size:    128,
+            })
+        );
+        assert_eq!(
+            AllocatorCall::recognize("calloc", &i32s(&[4, 16]), &i32s(&[512])),
+            Some(AllocatorCall::Allocate {
+                ptr:  512,
+                size: 64,
+            })
+        );
+        assert_eq!(
+            AllocatorCall::recognize("free", &i32s(&[512]), &[]),
+            Some(AllocatorCall::Free { ptr: 512 })
+        );
+        assert_eq!(
+            AllocatorCall::recognize("malloc", &i32s(&[1, 2]), &i32s(&[0])),
+            None
+        );
+        assert_eq!(
+            AllocatorCall::recognize("add", &i32s(&[1]), &i32s(&[2])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_profile_tracks_live_and_peak_bytes() {
+        let profile = HeapProfile::new();
+        profile.record(
+            AllocatorCall::Allocate {
+                ptr:  16,
+                size: 100,
+            },
+            Some("greet"),
+        );
+        profile.record(
+            AllocatorCall::Allocate {
+                ptr:  200,
+                size: 50,
+            },
+            None,
+        );
+        profile.record(
+            AllocatorCall::Reallocate {
+                old_ptr: 16,
+                new_ptr: 400,
+                size:    300,
+            },
+            Some("greet"),
+        );
+        profile.record(AllocatorCall::Free { ptr: 200 }, None);
+        profile.record(AllocatorCall::Free { ptr: 999 }, None);
+        profile.record(AllocatorCall::Allocate { ptr: 0, size: 8 }, None);
+
+        let summary = profile.summary();
+        assert_eq!(summary.live_bytes, 300);
+        assert_eq!(summary.peak_bytes, 350);
+        assert_eq!(summary.live_allocations, 1);
+        assert_eq!(summary.allocations, 3);
+        assert_eq!(summary.frees, 1);
+        assert_eq!(summary.failed, 1);
+        assert_eq!(summary.unknown_frees, 1);
+        assert_eq!(profile.live_allocations(), [(400, 300)]);
+
+        let sites = profile.call_sites();
+        assert_eq!(sites.len(), 1);
+        assert_eq!(sites[0].allocations, 2);
+        assert_eq!(sites[0].bytes, 400);
+        assert_eq!(sites[0].live_bytes, 300);
+        assert!(profile.to_string().contains("greet: 2 allocations, 400 bytes, 300 bytes live"));
+    }
+
+    #[cfg(feature = "wat")]
+    #[test]
+    fn test_guest_allocator_calls_are_recorded() -> Result<()> {
+        use wrt_foundation::memory_init::MemoryInitializer;
+
+        use crate::engine::{
+            CapabilityAwareEngine,
+            CapabilityEngine,
+            EnginePreset,
+        };
+
+        MemoryInitializer::ensure_initialized()?;
+        // A bump allocator and a function allocating twice and freeing once
+        let binary = crate::text_format::wat_to_binary(
+            r#"(module
+                (global $next (mut i32) (i32.const 1024))
+                (func $malloc (export "malloc") (param $size i32) (result i32)
+                  (global.get $next)
+                  (global.set $next (i32.add (global.get $next) (local.get $size))))
+                (func $free (export "free") (param i32))
+                (func (export "greet") (result i32) (local $name i32)
+                  (local.set $name (call $malloc (i32.const 32)))
+                  (drop (call $malloc (i32.const 8)))
+                  (call $free (local.get $name))
+                  (local.get $name)))"#,
+        )?;
+        let mut engine = CapabilityAwareEngine::with_preset(EnginePreset::QM)?;
+        engine.set_heap_profiling(true);
+        let module = engine.load_module(&binary)?;
+        let instance = engine.instantiate(module)?;
+
+        assert_eq!(engine.execute(instance, "greet", &[])?, [Value::I32(1024)]);
+        let profile = engine.heap_profile(instance).unwrap();
+        let summary = profile.summary();
+        assert_eq!(summary.allocations, 2);
+        assert_eq!(summary.frees, 1);
+        assert_eq!(summary.peak_bytes, 40);
+        assert_eq!(profile.live_allocations(), [(1056, 8)]);
+
+        // Calls from the host are recorded once
+        engine.execute(instance, "malloc", &[Value::I32(4)])?;
+        let profile = engine.heap_profile(instance).unwrap();
+        assert_eq!(profile.summary().allocations, 3);
+        assert_eq!(profile.live_allocations(), [(1056, 8), (1064, 4)]);
+        Ok(())
+    }
+}
@@ -0,0 +1,133 @@
+//! Context of host function calls
+//!
+//! Host functions registered in the engine's
+//! [`CallbackRegistry`](wrt_host::CallbackRegistry) receive the engine as
+//! `&mut dyn Any`. The
+//! [`CapabilityAwareEngine`](crate::engine::CapabilityAwareEngine)
+//! passes a [`HostCallContext`] for the calling instance, so host functions
+//! can downcast to it and reach the instance's memories. It implements
+//! [`GuestLogContext`], which lets the built-in
+//! [`GuestLogger`](wrt_logging::GuestLogger) serve guests of the engine, see
+//! [`CapabilityAwareEngine::enable_guest_logging`](crate::engine::CapabilityAwareEngine::enable_guest_logging).
+
+use wrt_logging::GuestLogContext;
+
+use crate::{
+    module_instance::ModuleInstance,
+    prelude::*,
+};
+
+/// The instance a host function is called from
+#[derive(Debug, Clone)]
+pub struct HostCallContext {
+    instance_id: u32,
+    instance:    Arc<ModuleInstance>,
+}
+
+impl HostCallContext {
+    /// Create the context of a call from `instance`, identified by
+    /// `instance_id`
+    #[must_use]
+    pub fn new(instance_id: u32, instance: Arc<ModuleInstance>) -> Self {
+        Self {
+            instance_id,
+            instance,
+        }
+    }
+
+    /// Identifier of the calling instance
+    #[must_use]
+    pub fn instance_id(&self) -> u32 {
+        self.instance_id
+    }
+
+    /// The calling instance
+    #[must_use]
+    pub fn instance(&self) -> &ModuleInstance {
+        &self.instance
+    }
+}
+
+impl GuestLogContext for HostCallContext {
+    fn instance_id(&self) -> u32 {
+        self.instance_id
+    }
+
+    fn read_guest_memory(&self, offset: u32, len: u32) -> Result<Vec<u8>> {
+        let memory = self
+            .instance
+            .memory_ref(0)
+            .ok_or_else(|| Error::runtime_execution_error("Instance has no memory"))?;
+        let mut bytes = vec![0u8; len as usize];
+        memory.read(offset, &mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wrt_foundation::{
+        memory_init::MemoryInitializer,
+        types::Limits,
+        values::Value,
+    };
+    use wrt_host::CallbackRegistry;
+    use wrt_logging::{
+        GuestLogger,
+        GUEST_LOG_FUNCTION,
+        GUEST_LOG_MODULE,
+    };
+
+    use super::*;
+    use crate::{
+        memory::Memory,
+        module::Module,
+    };
+
+    fn context(with_memory: bool) -> Result<HostCallContext> {
+        MemoryInitializer::ensure_initialized()?;
+        let mut instance = ModuleInstance::new(Module::empty(), 3)?;
+        if with_memory {
+            let mut memory = Memory::new(CoreMemoryType {
+                limits: Limits::new(1, Some(1)),
+                shared: false,
+            })?;
+            memory.write(16, b"hello")?;
+            instance.add_memory(memory)?;
+        }
+        Ok(HostCallContext::new(3, Arc::new(instance)))
+    }
+
+    #[test]
+    fn test_reads_default_memory() -> Result<()> {
+        let context = context(true)?;
+        assert_eq!(GuestLogContext::instance_id(&context), 3);
+        assert_eq!(context.read_guest_memory(16, 5)?, b"hello");
+        assert!(context.read_guest_memory(65534, 5).is_err());
+        assert!(self::context(false)?.read_guest_memory(0, 1).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_serves_guest_logger() -> Result<()> {
+        let mut registry = CallbackRegistry::new();
+        GuestLogger::default().register::<HostCallContext>(&mut registry);
+
+        let mut context = context(true)?;
+        let args = vec![
+            Value::I32(2),
+            Value::I32(0),
+            Value::I32(0),
+            Value::I32(16),
+            Value::I32(5),
+        ];
+        let result = registry.call_host_function(
+            &mut context,
+            GUEST_LOG_MODULE,
+            GUEST_LOG_FUNCTION,
+            args,
+        )?;
+        assert!(result.is_empty());
+        Ok(())
+    }
+}
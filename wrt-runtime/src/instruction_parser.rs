@@ -9,6 +9,11 @@
     ErrorCategory,
     Result,
 };
+use wrt_format::binary::{
+    read_leb128_i32,
+    read_leb128_i64,
+    read_leb128_u32,
+};
 use wrt_foundation::{
     bounded::BoundedVec,
     budget_aware_provider::CrateId,
@@ -55,7 +60,7 @@ pub fn parse_instructions(bytecode: &[u8]) -> Result<InstructionVec> {
 }
 
 /// Parse a single instruction from bytecode
-fn parse_instruction(
+pub(crate) fn parse_instruction(
     bytecode: &[u8],
     offset: usize,
 ) -> Result<(Instruction<InstructionProvider>, usize)> {
@@ -435,103 +440,6 @@ fn parse_block_type(bytecode: &[u8], offset: usize) -> Result<BlockType> {
     }
 }
 
-/// Read a LEB128 encoded u32
-fn read_leb128_u32(data: &[u8], offset: usize) -> Result<(u32, usize)> {
-    let mut result = 0u32;
-    let mut shift = 0;
-    let mut consumed = 0;
-
-    loop {
-        if offset + consumed >= data.len() {
-            return Err(Error::parse_error(
-                "Unexpected end of data while reading LEB128",
-            ));
-        }
-
-        let byte = data[offset + consumed];
-        consumed += 1;
-
-        result |= ((byte & 0x7F) as u32) << shift;
-
-        if byte & 0x80 == 0 {
-            break;
-        }
-
-        shift += 7;
-        if shift >= 32 {
-            return Err(Error::parse_error("LEB128 value too large for u32"));
-        }
-    }
-
-    Ok((result, consumed))
-}
-
-/// Read a LEB128 encoded i32
-fn read_leb128_i32(data: &[u8], offset: usize) -> Result<(i32, usize)> {
-    let mut result = 0i32;
-    let mut shift = 0;
-    let mut consumed = 0;
-    let mut byte;
-
-    loop {
-        if offset + consumed >= data.len() {
-            return Err(Error::parse_error(
-                "Unexpected end of data while reading LEB128",
-            ));
-        }
-
-        byte = data[offset + consumed];
-        consumed += 1;
-
-        result |= ((byte & 0x7F) as i32) << shift;
-        shift += 7;
-
-        if byte & 0x80 == 0 {
-            break;
-        }
-    }
-
-    // Sign extend
-    if shift < 32 && (byte & 0x40) != 0 {
-        result |= !0 << shift;
-    }
-
-    Ok((result, consumed))
-}
-
-/// Read a LEB128 encoded i64
-fn read_leb128_i64(data: &[u8], offset: usize) -> Result<(i64, usize)> {
-    let mut result = 0i64;
-    let mut shift = 0;
-    let mut consumed = 0;
-    let mut byte;
-
-    loop {
-        if offset + consumed >= data.len() {
-            return Err(Error::parse_error(
-                "Unexpected end of data while reading LEB128",
-            ));
-        }
-
-        byte = data[offset + consumed];
-        consumed += 1;
-
-        result |= ((byte & 0x7F) as i64) << shift;
-        shift += 7;
-
-        if byte & 0x80 == 0 {
-            break;
-        }
-    }
-
-    // Sign extend
-    if shift < 64 && (byte & 0x40) != 0 {
-        result |= !0 << shift;
-    }
-
-    Ok((result, consumed))
-}
-
 /// Convert BlockType to a type index for instruction storage
 fn block_type_to_index(block_type: &BlockType) -> u32 {
     match block_type {
@@ -7,112 +7,32 @@
 mod tests {
     use wrt_foundation::types::Instruction;
 
-    use crate::instruction_parser::{
-        parse_instruction,
-        InstructionContext,
-    };
-
-    // Mock context for testing
-    struct MockContext {
-        bytecode: Vec<u8>,
-        position: usize,
-    }
-
-    impl InstructionContext for MockContext {
-        fn read_u8(&mut self) -> Result<u8, crate::prelude::Error> {
-            if self.position >= self.bytecode.len() {
-                return Err(crate::prelude::Error::runtime_execution_error(
-                    "Unexpected end of bytecode",
-                ));
-            }
-            let byte = self.bytecode[self.position];
-            self.position += 1;
-            Ok(byte)
-        }
-
-        fn read_u32_leb128(&mut self) -> Result<u32, crate::prelude::Error> {
-            // Simplified LEB128 decoding for tests
-            let mut result = 0u32;
-            let mut shift = 0;
-
-            loop {
-                let byte = self.read_u8()?;
-                result |= ((byte & 0x7F) as u32) << shift;
-                if byte & 0x80 == 0 {
-                    break;
-                }
-                shift += 7;
-                if shift >= 32 {
-                    return Err(crate::prelude::Error::new(
-                        crate::prelude::ErrorCategory::Parse,
-                        wrt_error::codes::PARSE_ERROR,
-                        "LEB128 integer too large",
-                    ));
-                }
-            }
-
-            Ok(result)
-        }
-
-        fn read_i32_leb128(&mut self) -> Result<i32, crate::prelude::Error> {
-            // Simplified signed LEB128 decoding for tests
-            let unsigned = self.read_u32_leb128()?;
-            Ok(unsigned as i32)
-        }
-
-        fn read_i64_leb128(&mut self) -> Result<i64, crate::prelude::Error> {
-            // Simplified for tests
-            let unsigned = self.read_u32_leb128()?;
-            Ok(unsigned as i64)
-        }
-
-        fn read_f32(&mut self) -> Result<f32, crate::prelude::Error> {
-            let mut bytes = [0u8; 4];
-            for byte in &mut bytes {
-                *byte = self.read_u8()?;
-            }
-            Ok(f32::from_le_bytes(bytes))
-        }
-
-        fn read_f64(&mut self) -> Result<f64, crate::prelude::Error> {
-            let mut bytes = [0u8; 8];
-            for byte in &mut bytes {
-                *byte = self.read_u8()?;
-            }
-            Ok(f64::from_le_bytes(bytes))
-        }
-    }
+    use crate::instruction_parser::parse_instruction;
 
     #[test]
     fn test_parse_nop() {
-        let mut ctx = MockContext {
-            bytecode: vec![0x01], // nop
-            position: 0,
-        };
+        let bytecode = [0x01]; // nop
 
-        let inst = parse_instruction(&mut ctx).unwrap();
+        let (inst, consumed) = parse_instruction(&bytecode, 0).unwrap();
+        assert_eq!(consumed, bytecode.len());
         assert!(matches!(inst, Instruction::Nop));
     }
 
     #[test]
     fn test_parse_unreachable() {
-        let mut ctx = MockContext {
-            bytecode: vec![0x00], // unreachable
-            position: 0,
-        };
+        let bytecode = [0x00]; // unreachable
 
-        let inst = parse_instruction(&mut ctx).unwrap();
+        let (inst, consumed) = parse_instruction(&bytecode, 0).unwrap();
+        assert_eq!(consumed, bytecode.len());
         assert!(matches!(inst, Instruction::Unreachable));
     }
 
     #[test]
     fn test_parse_i32_const() {
-        let mut ctx = MockContext {
-            bytecode: vec![0x41, 0x7F], // i32.const 127
-            position: 0,
-        };
+        let bytecode = [0x41, 0xFF, 0x00]; // i32.const 127
 
-        let inst = parse_instruction(&mut ctx).unwrap();
+        let (inst, consumed) = parse_instruction(&bytecode, 0).unwrap();
+        assert_eq!(consumed, bytecode.len());
         match inst {
             Instruction::I32Const(val) => assert_eq!(val, 127),
             _ => panic!("Expected I32Const instruction"),
@@ -121,12 +41,10 @@ fn test_parse_i32_const() {
 
     #[test]
     fn test_parse_i64_const() {
-        let mut ctx = MockContext {
-            bytecode: vec![0x42, 0x64], // i64.const 100
-            position: 0,
-        };
+        let bytecode = [0x42, 0xE4, 0x00]; // i64.const 100
 
-        let inst = parse_instruction(&mut ctx).unwrap();
+        let (inst, consumed) = parse_instruction(&bytecode, 0).unwrap();
+        assert_eq!(consumed, bytecode.len());
         match inst {
             Instruction::I64Const(val) => assert_eq!(val, 100),
             _ => panic!("Expected I64Const instruction"),
@@ -135,12 +53,10 @@ fn test_parse_i64_const() {
 
     #[test]
     fn test_parse_local_get() {
-        let mut ctx = MockContext {
-            bytecode: vec![0x20, 0x02], // local.get 2
-            position: 0,
-        };
+        let bytecode = [0x20, 0x02]; // local.get 2
 
-        let inst = parse_instruction(&mut ctx).unwrap();
+        let (inst, consumed) = parse_instruction(&bytecode, 0).unwrap();
+        assert_eq!(consumed, bytecode.len());
         match inst {
             Instruction::LocalGet(idx) => assert_eq!(idx, 2),
             _ => panic!("Expected LocalGet instruction"),
@@ -149,12 +65,10 @@ fn test_parse_local_get() {
 
     #[test]
     fn test_parse_local_set() {
-        let mut ctx = MockContext {
-            bytecode: vec![0x21, 0x03], // local.set 3
-            position: 0,
-        };
+        let bytecode = [0x21, 0x03]; // local.set 3
 
-        let inst = parse_instruction(&mut ctx).unwrap();
+        let (inst, consumed) = parse_instruction(&bytecode, 0).unwrap();
+        assert_eq!(consumed, bytecode.len());
         match inst {
             Instruction::LocalSet(idx) => assert_eq!(idx, 3),
             _ => panic!("Expected LocalSet instruction"),
@@ -163,56 +77,44 @@ fn test_parse_local_set() {
 
     #[test]
     fn test_parse_i32_add() {
-        let mut ctx = MockContext {
-            bytecode: vec![0x6A], // i32.add
-            position: 0,
-        };
+        let bytecode = [0x6A]; // i32.add
 
-        let inst = parse_instruction(&mut ctx).unwrap();
+        let (inst, consumed) = parse_instruction(&bytecode, 0).unwrap();
+        assert_eq!(consumed, bytecode.len());
         assert!(matches!(inst, Instruction::I32Add));
     }
 
     #[test]
     fn test_parse_return() {
-        let mut ctx = MockContext {
-            bytecode: vec![0x0F], // return
-            position: 0,
-        };
+        let bytecode = [0x0F]; // return
 
-        let inst = parse_instruction(&mut ctx).unwrap();
+        let (inst, consumed) = parse_instruction(&bytecode, 0).unwrap();
+        assert_eq!(consumed, bytecode.len());
         assert!(matches!(inst, Instruction::Return));
     }
 
     #[test]
     fn test_parse_end() {
-        let mut ctx = MockContext {
-            bytecode: vec![0x0B], // end
-            position: 0,
-        };
+        let bytecode = [0x0B]; // end
 
-        let inst = parse_instruction(&mut ctx).unwrap();
+        let (inst, consumed) = parse_instruction(&bytecode, 0).unwrap();
+        assert_eq!(consumed, bytecode.len());
         assert!(matches!(inst, Instruction::End));
     }
 
     #[test]
     fn test_parse_unknown_opcode() {
-        let mut ctx = MockContext {
-            bytecode: vec![0xFF], // Invalid opcode
-            position: 0,
-        };
+        let bytecode = [0xFF]; // Invalid opcode
 
-        let result = parse_instruction(&mut ctx);
+        let result = parse_instruction(&bytecode, 0);
         assert!(result.is_err());
     }
 
     #[test]
     fn test_parse_empty_bytecode() {
-        let mut ctx = MockContext {
-            bytecode: vec![],
-            position: 0,
-        };
+        let bytecode: [u8; 0] = [];
 
-        let result = parse_instruction(&mut ctx);
+        let result = parse_instruction(&bytecode, 0);
         assert!(result.is_err());
     }
 }
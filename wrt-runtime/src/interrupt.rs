@@ -0,0 +1,445 @@
+//! Interruption of running guest code
+//!
+//! Guest code is interrupted cooperatively. The interpreter checks the
+//! engine's epoch at safe points, when an invocation is entered and returns,
+//! after every guest call and after every branch back to a loop, and fails
+//! the invocation with an execution timeout once the epoch has reached the
+//! deadline of the invocation.
+//!
+//! The epoch advances only when the embedder calls
+//! [`InterruptHandle::increment_epoch`], for example from a timer thread or
+//! an interrupt handler, so execution can be bounded in ticks of any clock
+//! with
+//! [`CapabilityAwareEngine::set_epoch_deadline`](crate::engine::CapabilityAwareEngine::set_epoch_deadline).
+//! A wall-clock bound per invocation, set with
+//! [`CapabilityAwareEngine::set_invocation_deadline`](crate::engine::CapabilityAwareEngine::set_invocation_deadline),
+//! feeds the same check: once it expires, the deadline of the running
+//! invocation is moved to the current epoch.
+//!
+//! With `std`, a watchdog thread waits for the wall-clock deadline and runs
+//! the callback as soon as it expires, even while the guest has not reached
+//! a safe point yet. Without `std`, the deadline is measured with the
+//! [engine clock](crate::time_source) and checked, and the callback run, at
+//! the next safe point.
+//!
+//! Fuel bounds execution in units of yield points instead, the safe points
+//! other than returns. Each one the interpreter passes consumes one unit of
+//! fuel, and an invocation that reaches a yield point without fuel stops with
+//! a fuel exhausted error. Stopped at its entry, the invocation has not
+//! changed any state yet; stopped later, it is suspended and continues where
+//! it stopped when it is repeated with the same arguments. The
+//! [`Scheduler`](crate::engine::Scheduler) uses fuel to run guests in slices.
+
+use core::{
+    fmt,
+    sync::atomic::{
+        AtomicU64,
+        Ordering,
+    },
+    time::Duration,
+};
+
+use crate::prelude::*;
+#[cfg(not(feature = "std"))]
+use crate::time_source::timestamp_ns;
+
+/// Deadline of an invocation that is not bounded
+const NO_DEADLINE: u64 = u64::MAX;
+
+/// Fuel of an engine that is not bounded
+const UNLIMITED_FUEL: u64 = u64::MAX;
+
+/// Callback run when an invocation deadline expires
+#[cfg(feature = "std")]
+pub(crate) type DeadlineCallback = Arc<dyn Fn() + Send + Sync>;
+
+/// Callback run when an invocation deadline expires
+#[cfg(not(feature = "std"))]
+pub(crate) type DeadlineCallback = fn();
+
+#[derive(Debug)]
+struct EpochState {
+    epoch:    AtomicU64,
+    /// Epoch at which the running invocation is interrupted
+    deadline: AtomicU64,
+}
+
+/// Handle to the epoch of an engine
+///
+/// The handle can be shared with other threads, which advance the epoch or
+/// interrupt the running invocation while the engine executes.
+#[derive(Debug)]
+#[cfg_attr(any(feature = "std", feature = "alloc"), derive(Clone))]
+pub struct InterruptHandle {
+    state: Arc<EpochState>,
+}
+
+impl InterruptHandle {
+    fn new() -> Self {
+        Self {
+            state: Arc::new(EpochState {
+                epoch:    AtomicU64::new(0),
+                deadline: AtomicU64::new(NO_DEADLINE),
+            }),
+        }
+    }
+
+    /// Current epoch
+    pub fn epoch(&self) -> u64 {
+        self.state.epoch.load(Ordering::Acquire)
+    }
+
+    /// Advance the epoch by one tick
+    pub fn increment_epoch(&self) {
+        self.state.epoch.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Interrupt the running invocation at its next safe point
+    ///
+    /// Has no effect on invocations started later.
+    pub fn interrupt(&self) {
+        self.state.deadline.store(self.epoch(), Ordering::Release);
+    }
+
+    fn is_interrupted(&self) -> bool {
+        self.epoch() >= self.state.deadline.load(Ordering::Acquire)
+    }
+}
+
+/// Wall-clock bound of every invocation
+struct InvocationDeadline {
+    duration: Duration,
+    #[cfg(feature = "std")]
+    watchdog: watchdog::Watchdog,
+    #[cfg(not(feature = "std"))]
+    callback: DeadlineCallback,
+}
+
+impl fmt::Debug for InvocationDeadline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InvocationDeadline")
+            .field("duration", &self.duration)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Interruption state of an engine, checked by the interpreter at its safe
+/// points
+#[derive(Debug)]
+pub(crate) struct Interruption {
+    handle:      InterruptHandle,
+    epoch_ticks: Option<u64>,
+    deadline:    Option<InvocationDeadline>,
+    /// Yield points left to pass, or [`UNLIMITED_FUEL`]
+    fuel:        AtomicU64,
+    /// Yield points passed
+    consumed:    AtomicU64,
+    /// Engine timestamp the running invocation expires at
+    #[cfg(not(feature = "std"))]
+    expires_at:  AtomicU64,
+}
+
+impl Interruption {
+    pub(crate) fn new() -> Self {
+        Self {
+            handle:      InterruptHandle::new(),
+            epoch_ticks: None,
+            deadline:    None,
+            fuel:        AtomicU64::new(UNLIMITED_FUEL),
+            consumed:    AtomicU64::new(0),
+            #[cfg(not(feature = "std"))]
+            expires_at:  AtomicU64::new(NO_DEADLINE),
+        }
+    }
+
+    pub(crate) fn handle(&self) -> &InterruptHandle {
+        &self.handle
+    }
+
+    pub(crate) fn set_epoch_deadline(&mut self, ticks: Option<u64>) {
+        self.epoch_ticks = ticks;
+    }
+
+    /// Limit execution to `fuel` yield points, or remove the limit
+    pub(crate) fn set_fuel(&mut self, fuel: Option<u64>) {
+        // A limit of `u64::MAX` is indistinguishable from no limit in practice
+        *self.fuel.get_mut() = fuel.unwrap_or(UNLIMITED_FUEL);
+    }
+
+    /// Fuel left, or `None` without a limit
+    pub(crate) fn fuel(&self) -> Option<u64> {
+        Some(self.fuel.load(Ordering::Acquire)).filter(|&fuel| fuel != UNLIMITED_FUEL)
+    }
+
+    /// Fuel consumed since the engine was created, whether limited or not
+    pub(crate) fn fuel_consumed(&self) -> u64 {
+        self.consumed.load(Ordering::Acquire)
+    }
+
+    /// Bound every invocation to `duration`, running `callback` when one
+    /// exceeds it, or remove the bound
+    pub(crate) fn set_invocation_deadline(
+        &mut self,
+        deadline: Option<(Duration, DeadlineCallback)>,
+    ) -> Result<()> {
+        // Stop the watchdog of the previous deadline first
+        self.deadline = None;
+        if let Some((duration, callback)) = deadline {
+            self.deadline = Some(InvocationDeadline {
+                duration,
+                #[cfg(feature = "std")]
+                watchdog: watchdog::Watchdog::spawn(self.handle.clone(), callback)?,
+                #[cfg(not(feature = "std"))]
+                callback,
+            });
+        }
+        Ok(())
+    }
+
+    /// Start an invocation, arming its deadlines until [`Self::end`]
+    pub(crate) fn begin(&self) {
+        let epoch_deadline = self
+            .epoch_ticks
+            .map_or(NO_DEADLINE, |ticks| self.handle.epoch().saturating_add(ticks));
+        self.handle.state.deadline.store(epoch_deadline, Ordering::Release);
+        if let Some(deadline) = &self.deadline {
+            #[cfg(feature = "std")]
+            deadline.watchdog.arm(deadline.duration);
+            #[cfg(not(feature = "std"))]
+            {
+                let duration = u64::try_from(deadline.duration.as_nanos()).unwrap_or(NO_DEADLINE);
+                self.expires_at
+                    .store(timestamp_ns().saturating_add(duration), Ordering::Release);
+            }
+        }
+    }
+
+    /// End the running invocation, disarming its wall-clock deadline
+    pub(crate) fn end(&self) {
+        #[cfg(feature = "std")]
+        if let Some(deadline) = &self.deadline {
+            deadline.watchdog.disarm();
+        }
+        #[cfg(not(feature = "std"))]
+        self.expires_at.store(NO_DEADLINE, Ordering::Release);
+    }
+
+    /// Fail if the running invocation has been interrupted
+    pub(crate) fn check(&self) -> Result<()> {
+        #[cfg(not(feature = "std"))]
+        if let Some(deadline) = &self.deadline {
+            let expires_at = self.expires_at.load(Ordering::Acquire);
+            if timestamp_ns() >= expires_at
+                && self
+                    .expires_at
+                    .compare_exchange(expires_at, NO_DEADLINE, Ordering::AcqRel, Ordering::Acquire)
+                    .is_ok()
+            {
+                self.handle.interrupt();
+                (deadline.callback)();
+            }
+        }
+        if self.handle.is_interrupted() {
+            Err(Error::execution_timeout("Execution interrupted"))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Safe point at which the running invocation may yield, consuming one
+    /// unit of fuel
+    ///
+    /// Fails like [`Self::check`], or with a fuel exhausted error once the
+    /// fuel has run out. The interpreter passes a yield point only where it
+    /// can suspend the invocation, so an invocation stopped there can be
+    /// repeated once it has been given more fuel.
+    pub(crate) fn yield_point(&self) -> Result<()> {
+        self.check()?;
+        self.fuel
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |fuel| match fuel {
+                UNLIMITED_FUEL => Some(UNLIMITED_FUEL),
+                0 => None,
+                fuel => Some(fuel - 1),
+            })
+            .map_err(|_| Error::fuel_exhausted("Execution ran out of fuel"))?;
+        self.consumed.fetch_add(1, Ordering::AcqRel);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+mod watchdog {
+    use std::{
+        sync::{
+            Condvar,
+            Mutex,
+            MutexGuard,
+            PoisonError,
+        },
+        thread::{
+            self,
+            JoinHandle,
+        },
+        time::Instant,
+    };
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct State {
+        /// Deadline of the running invocation
+        armed:    Option<Instant>,
+        shutdown: bool,
+    }
+
+    #[derive(Debug, Default)]
+    struct Shared {
+        state: Mutex<State>,
+        wake:  Condvar,
+    }
+
+    impl Shared {
+        fn lock(&self) -> MutexGuard<'_, State> {
+            self.state.lock().unwrap_or_else(PoisonError::into_inner)
+        }
+    }
+
+    /// Thread interrupting invocations that exceed their deadline
+    pub(super) struct Watchdog {
+        shared: Arc<Shared>,
+        thread: Option<JoinHandle<()>>,
+    }
+
+    impl Watchdog {
+        pub(super) fn spawn(handle: InterruptHandle, callback: DeadlineCallback) -> Result<Self> {
+            let shared = Arc::new(Shared::default());
+            let thread = thread::Builder::new()
+                .name("wrt-watchdog".into())
+                .spawn({
+                    let shared = shared.clone();
+                    move || run(&shared, &handle, &*callback)
+                })
+                .map_err(|_| Error::platform_error("Failed to start the watchdog thread"))?;
+            Ok(Self {
+                shared,
+                thread: Some(thread),
+            })
+        }
+
+        pub(super) fn arm(&self, duration: Duration) {
+            self.shared.lock().armed = Instant::now().checked_add(duration);
+            self.shared.wake.notify_one();
+        }
+
+        pub(super) fn disarm(&self) {
+            self.shared.lock().armed = None;
+        }
+    }
+
+    impl Drop for Watchdog {
+        fn drop(&mut self) {
+            self.shared.lock().shutdown = true;
+            self.shared.wake.notify_one();
+            if let Some(thread) = self.thread.take() {
+                let _ = thread.join();
+            }
+        }
+    }
+
+    fn run(shared: &Shared, handle: &InterruptHandle, callback: &(dyn Fn() + Send + Sync)) {
+        let mut state = shared.lock();
+        while !state.shutdown {
+            state = match state.armed {
+                None => shared.wake.wait(state).unwrap_or_else(PoisonError::into_inner),
+                Some(deadline) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    if remaining.is_zero() {
+                        state.armed = None;
+                        handle.interrupt();
+                        // The callback may take its time; do not hold up the
+                        // engine arming the next invocation meanwhile
+                        drop(state);
+                        callback();
+                        shared.lock()
+                    } else {
+                        shared
+                            .wake
+                            .wait_timeout(state, remaining)
+                            .unwrap_or_else(PoisonError::into_inner)
+                            .0
+                    }
+                },
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_epoch_deadline() {
+        let mut interruption = Interruption::new();
+        interruption.set_epoch_deadline(Some(2));
+
+        interruption.begin();
+        interruption.handle().increment_epoch();
+        assert!(interruption.check().is_ok());
+        interruption.handle().increment_epoch();
+        assert!(interruption.check().is_err());
+        interruption.end();
+
+        // The next invocation starts from the current epoch
+        interruption.begin();
+        assert!(interruption.check().is_ok());
+        interruption.handle().interrupt();
+        assert!(interruption.check().is_err());
+    }
+
+    #[test]
+    fn test_yield_point_consumes_fuel() {
+        let mut interruption = Interruption::new();
+        assert_eq!(interruption.fuel(), None);
+        assert!(interruption.yield_point().is_ok());
+
+        interruption.set_fuel(Some(1));
+        assert!(interruption.yield_point().is_ok());
+        assert_eq!(interruption.fuel(), Some(0));
+        assert!(interruption.yield_point().is_err());
+        assert_eq!(interruption.fuel_consumed(), 2);
+
+        interruption.set_fuel(None);
+        assert!(interruption.yield_point().is_ok());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_invocation_deadline_runs_callback() {
+        use std::sync::atomic::AtomicUsize;
+
+        let expired = Arc::new(AtomicUsize::new(0));
+        let mut interruption = Interruption::new();
+        let counter = expired.clone();
+        interruption
+            .set_invocation_deadline(Some((
+                Duration::from_millis(10),
+                Arc::new(move || {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                }),
+            )))
+            .unwrap();
+
+        // Returning in time disarms the watchdog
+        interruption.begin();
+        interruption.end();
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(expired.load(Ordering::SeqCst), 0);
+
+        interruption.begin();
+        assert!(interruption.check().is_ok());
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(interruption.check().is_err());
+        assert_eq!(expired.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,369 @@
+//! Tiered execution of hot functions from compiled code
+//!
+//! A [`JitBackend`] counts the calls of every function the engine executes.
+//! Once a function has been called [`JitBackend::threshold`] times, or has
+//! been marked hot by the embedder, for example from the samples of a
+//! sampling profiler, it is handed to the [`CodeGenerator`] the backend was
+//! created with, and later calls run the [`CompiledFunction`] it returns.
+//! Functions the generator declines or fails to compile stay on the
+//! interpreter for the lifetime of the backend, as do all functions that are
+//! not hot.
+//!
+//! The runtime itself carries no code generator: embedders plug in one for
+//! their target. A compiled call consumes fuel and checks for interruption at
+//! its entry like an interpreted one.
+//!
+//! Generated code cannot be traced back to certified source, so a JIT is
+//! never used by safety-certified configurations: builds enabling a safety
+//! standard feature and engines created with a preset other than
+//! [`EnginePreset::QM`] run every function on the interpreter, whether a
+//! backend is set or not, see [`jit_permitted`].
+
+use core::fmt;
+use std::sync::{
+    Mutex,
+    MutexGuard,
+    PoisonError,
+};
+
+use wrt_error::Result;
+use wrt_foundation::values::Value;
+
+use crate::{
+    engine::EnginePreset,
+    execution_backend::{
+        ExecutionBackend,
+        HostImports,
+    },
+    module::Module,
+    module_instance::ModuleInstance,
+    prelude::*,
+    stackless::StacklessEngine,
+};
+
+/// Calls after which a function is compiled by default
+pub const DEFAULT_HOT_THRESHOLD: u64 = 1000;
+
+/// Whether the build enables a safety standard, in which case no JIT is used
+pub const SAFETY_CERTIFIED_BUILD: bool = cfg!(any(
+    feature = "iso-26262",
+    feature = "do-178c",
+    feature = "iec-61508",
+    feature = "iec-62304",
+    feature = "en-50128",
+    feature = "iso-25119"
+));
+
+/// Whether an engine created with `preset` may run compiled code
+pub fn jit_permitted(preset: EnginePreset) -> bool {
+    !SAFETY_CERTIFIED_BUILD && preset == EnginePreset::QM
+}
+
+/// A function compiled by a [`CodeGenerator`]
+pub trait CompiledFunction: Send + Sync {
+    /// Run the function on `instance` with `args`
+    ///
+    /// # Errors
+    ///
+    /// Returns the trap of the function, as the interpreter would.
+    fn call(&self, instance: &ModuleInstance, args: &[Value]) -> Result<Vec<Value>>;
+}
+
+/// Compiles the functions of modules into machine code
+pub trait CodeGenerator: Send + Sync {
+    /// Compile function `function` of `module`
+    ///
+    /// Returns `None` for functions the generator does not support, which
+    /// then run on the interpreter.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if compilation fails; the function then runs on the
+    /// interpreter.
+    fn compile(&self, module: &Module, function: u32) -> Result<Option<Arc<dyn CompiledFunction>>>;
+}
+
+/// Counts of a [`JitBackend`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JitStats {
+    /// Functions compiled
+    pub compiled:          u64,
+    /// Hot functions the generator declined or failed to compile
+    pub rejected:          u64,
+    /// Calls run from compiled code
+    pub compiled_calls:    u64,
+    /// Calls run on the interpreter
+    pub interpreted_calls: u64,
+}
+
+/// Tier of a function
+enum Tier {
+    /// Running on the interpreter, called `calls` times so far
+    Interpreted { calls: u64 },
+    /// Compiled
+    Compiled(Arc<dyn CompiledFunction>),
+    /// Not compilable, running on the interpreter for good
+    Rejected,
+}
+
+#[derive(Default)]
+struct State {
+    tiers: HashMap<(usize, u32), Tier>,
+    stats: JitStats,
+}
+
+/// Execution backend compiling hot functions, see the [module
+/// documentation](self)
+pub struct JitBackend {
+    generator: Arc<dyn CodeGenerator>,
+    threshold: u64,
+    state:     Mutex<State>,
+}
+
+impl JitBackend {
+    /// Backend compiling functions with `generator` once they are called
+    /// [`DEFAULT_HOT_THRESHOLD`] times
+    pub fn new(generator: Arc<dyn CodeGenerator>) -> Self {
+        Self::with_threshold(generator, DEFAULT_HOT_THRESHOLD)
+    }
+
+    /// Backend compiling functions with `generator` once they are called
+    /// `threshold` times
+    pub fn with_threshold(generator: Arc<dyn CodeGenerator>, threshold: u64) -> Self {
+        Self {
+            generator,
+            threshold: threshold.max(1),
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    /// Calls after which a function is compiled
+    pub fn threshold(&self) -> u64 {
+        self.threshold
+    }
+
+    /// Compile function `function` of instance `instance` on its next call
+    pub fn mark_hot(&self, instance: usize, function: u32) {
+        let mut state = self.state();
+        let tier = state
+            .tiers
+            .entry((instance, function))
+            .or_insert(Tier::Interpreted { calls: 0 });
+        if let Tier::Interpreted { calls } = tier {
+            *calls = (*calls).max(self.threshold - 1);
+        }
+    }
+
+    /// Mark the functions on top of at least `min_samples` samples of
+    /// `profiler` hot
+    #[cfg(feature = "sampling-profiler")]
+    pub fn mark_hot_from(
+        &self,
+        profiler: &crate::sampling_profiler::SamplingProfiler,
+        min_samples: u64,
+    ) {
+        let mut samples: HashMap<_, u64> = HashMap::new();
+        for (stack, count) in profiler.stacks() {
+            if let Some(frame) = stack.last() {
+                *samples.entry(*frame).or_default() += count;
+            }
+        }
+        for (frame, count) in samples {
+            if count >= min_samples {
+                self.mark_hot(frame.instance, frame.function);
+            }
+        }
+    }
+
+    /// Whether function `function` of instance `instance` runs from compiled
+    /// code
+    pub fn is_compiled(&self, instance: usize, function: u32) -> bool {
+        matches!(
+            self.state().tiers.get(&(instance, function)),
+            Some(Tier::Compiled(_))
+        )
+    }
+
+    /// Counts of compilations and calls so far
+    pub fn stats(&self) -> JitStats {
+        self.state().stats
+    }
+
+    /// Backend running compiled functions and falling back to `interpreter`
+    pub fn over<'a>(&'a self, interpreter: &'a StacklessEngine) -> TieredBackend<'a> {
+        TieredBackend {
+            jit: self,
+            interpreter,
+        }
+    }
+
+    /// Count a call of `function` of `instance`, compiling it if it became
+    /// hot, and return its compiled code, if any
+    fn enter(
+        &self,
+        instance: usize,
+        function: u32,
+        module: &Module,
+    ) -> Option<Arc<dyn CompiledFunction>> {
+        {
+            let mut state = self.state();
+            let tier = state
+                .tiers
+                .entry((instance, function))
+                .or_insert(Tier::Interpreted { calls: 0 });
+            match tier {
+                Tier::Compiled(code) => {
+                    let code = code.clone();
+                    state.stats.compiled_calls += 1;
+                    return Some(code);
+                },
+                Tier::Rejected => {
+                    state.stats.interpreted_calls += 1;
+                    return None;
+                },
+                Tier::Interpreted { calls } => {
+                    *calls += 1;
+                    if *calls < self.threshold {
+                        state.stats.interpreted_calls += 1;
+                        return None;
+                    }
+                },
+            }
+        }
+
+        // Compile without holding the lock; a function compiled twice by
+        // concurrent calls keeps the first code stored
+        let compiled = self.generator.compile(module, function).ok().flatten();
+        let mut state = self.state();
+        let state = &mut *state;
+        let tier = state.tiers.entry((instance, function)).or_insert(Tier::Rejected);
+        match (&*tier, compiled) {
+            (Tier::Compiled(code), _) => {
+                state.stats.compiled_calls += 1;
+                Some(code.clone())
+            },
+            (_, Some(code)) => {
+                *tier = Tier::Compiled(code.clone());
+                state.stats.compiled += 1;
+                state.stats.compiled_calls += 1;
+                Some(code)
+            },
+            (_, None) => {
+                *tier = Tier::Rejected;
+                state.stats.rejected += 1;
+                state.stats.interpreted_calls += 1;
+                None
+            },
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl fmt::Debug for JitBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JitBackend")
+            .field("threshold", &self.threshold)
+            .field("stats", &self.stats())
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [`JitBackend`] over the interpreter it falls back to
+pub struct TieredBackend<'a> {
+    jit:         &'a JitBackend,
+    interpreter: &'a StacklessEngine,
+}
+
+impl ExecutionBackend for TieredBackend<'_> {
+    fn name(&self) -> &'static str {
+        "jit"
+    }
+
+    fn generates_code(&self) -> bool {
+        true
+    }
+
+    fn execute(
+        &self,
+        instance_id: usize,
+        func_idx: usize,
+        args: Vec<Value>,
+        host: &mut dyn HostImports,
+    ) -> Result<Vec<Value>> {
+        let Some(instance) = self.interpreter.instance(instance_id) else {
+            return self.interpreter.execute(instance_id, func_idx, args, host);
+        };
+        match self.jit.enter(instance_id, func_idx as u32, instance.module()) {
+            Some(code) => {
+                self.interpreter.interruption.yield_point()?;
+                if let Some(coverage) = self.interpreter.coverage.get(&instance_id) {
+                    coverage.record_call(func_idx as u32);
+                }
+                code.call(&instance, &args)
+            },
+            None => self.interpreter.execute(instance_id, func_idx, args, host),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Constant;
+
+    impl CompiledFunction for Constant {
+        fn call(&self, _instance: &ModuleInstance, _args: &[Value]) -> Result<Vec<Value>> {
+            Ok(vec![Value::I32(7)])
+        }
+    }
+
+    /// Compiles even functions only
+    struct EvenFunctions;
+
+    impl CodeGenerator for EvenFunctions {
+        fn compile(
+            &self,
+            _module: &Module,
+            function: u32,
+        ) -> Result<Option<Arc<dyn CompiledFunction>>> {
+            Ok((function % 2 == 0).then(|| Arc::new(Constant) as Arc<dyn CompiledFunction>))
+        }
+    }
+
+    #[test]
+    fn test_hot_functions_are_compiled_or_stay_interpreted() {
+        let jit = JitBackend::with_threshold(Arc::new(EvenFunctions), 3);
+        let module = Module::new().unwrap();
+
+        assert!(jit.enter(0, 2, &module).is_none());
+        assert!(jit.enter(0, 2, &module).is_none());
+        assert!(jit.enter(0, 2, &module).is_some());
+        assert!(jit.is_compiled(0, 2));
+
+        jit.mark_hot(0, 1);
+        assert!(jit.enter(0, 1, &module).is_none());
+        assert!(jit.enter(0, 1, &module).is_none());
+        assert!(!jit.is_compiled(0, 1));
+
+        assert_eq!(
+            jit.stats(),
+            JitStats {
+                compiled:          1,
+                rejected:          1,
+                compiled_calls:    1,
+                interpreted_calls: 4,
+            }
+        );
+    }
+
+    #[test]
+    fn test_jit_is_only_permitted_for_qm() {
+        assert_eq!(jit_permitted(EnginePreset::QM), !SAFETY_CERTIFIED_BUILD);
+        assert!(!jit_permitted(EnginePreset::AsilA));
+        assert!(!jit_permitted(EnginePreset::AsilD));
+    }
+}
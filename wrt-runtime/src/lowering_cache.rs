@@ -0,0 +1,366 @@
+//! Persistent cache of lowered modules
+//!
+//! Decoding a module and lowering its function bodies into runtime
+//! instructions is the bulk of its load time, and it gives the same result
+//! every time the same binary is loaded by the same engine. A
+//! [`LoweringCache`] keeps the lowered module in the serialized form of
+//! [`module_cache`](crate::module_cache) in a directory, so later loads,
+//! see [`Module::load_cached`], skip both steps.
+//!
+//! Entries are kept per platform and per [`BuildId`] of the engine: the
+//! build id covers the runtime version, the serialized module format, the
+//! fuel [`COST_MODEL_VERSION`] and the enabled features that change how code
+//! is lowered or executed. Opening a cache removes the entries of every
+//! other build id for the platform, so a changed engine never runs code
+//! lowered by another one. Each entry records the length and checksum of the
+//! binary it was lowered from and carries the checksum of the serialized
+//! module; an entry failing either check is removed and the module lowered
+//! again.
+
+use core::{
+    fmt,
+    sync::atomic::{
+        AtomicU64,
+        Ordering,
+    },
+};
+use std::{
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_foundation::verification::Checksum;
+
+use crate::{
+    module::Module,
+    module_cache::{
+        cache_key,
+        SERIALIZED_MODULE_VERSION,
+    },
+    prelude::*,
+};
+
+/// Magic bytes of a cache entry
+pub const LOWERING_CACHE_MAGIC: [u8; 4] = *b"WRTL";
+
+/// Version of the rules by which lowered code is charged fuel
+///
+/// Part of the [`BuildId`]; bump it whenever the placement or cost of yield
+/// points changes, so that cached code follows the new rules.
+pub const COST_MODEL_VERSION: u32 = 2;
+
+/// Magic, build id, binary length and binary checksum
+const ENTRY_HEADER_SIZE: usize = 16;
+
+/// Features that change how code is lowered or executed
+const LOWERING_FEATURES: &[(&str, bool)] = &[
+    ("bounded-allocation", cfg!(feature = "bounded-allocation")),
+    ("checked-arithmetic", cfg!(feature = "checked-arithmetic")),
+    ("mcdc-coverage", cfg!(feature = "mcdc-coverage")),
+    ("optimize", cfg!(feature = "optimize")),
+    ("soft-float", cfg!(feature = "soft-float")),
+    ("static-allocation", cfg!(feature = "static-allocation")),
+    ("strict-leb128", cfg!(feature = "strict-leb128")),
+];
+
+/// Identifies the engine builds that lower modules alike
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BuildId(pub u32);
+
+impl BuildId {
+    /// Build id of the running engine
+    pub fn current() -> Self {
+        Self(Checksum::compute(build_fingerprint().as_bytes()).value())
+    }
+}
+
+impl fmt::Display for BuildId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:08x}", self.0)
+    }
+}
+
+/// Description of everything the [`BuildId`] covers
+pub fn build_fingerprint() -> String {
+    let features: Vec<&str> = LOWERING_FEATURES
+        .iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(name, _)| *name)
+        .collect();
+    format!(
+        "wrt-runtime {}; module format {SERIALIZED_MODULE_VERSION}; cost model \
+         {COST_MODEL_VERSION}; features {}",
+        env!("CARGO_PKG_VERSION"),
+        features.join(",")
+    )
+}
+
+/// Name of the platform entries are kept for, such as `x86_64-linux-64-le`
+pub fn platform() -> String {
+    format!(
+        "{}-{}-{}-{}",
+        std::env::consts::ARCH,
+        std::env::consts::OS,
+        usize::BITS,
+        if cfg!(target_endian = "little") { "le" } else { "be" }
+    )
+}
+
+/// Lookups of a [`LoweringCache`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LoweringCacheStats {
+    /// Modules loaded from the cache
+    pub hits:        u64,
+    /// Modules not in the cache
+    pub misses:      u64,
+    /// Entries removed because they failed their integrity checks
+    pub invalidated: u64,
+}
+
+/// Directory of lowered modules for one platform and build id
+#[derive(Debug)]
+pub struct LoweringCache {
+    dir:         PathBuf,
+    build_id:    BuildId,
+    hits:        AtomicU64,
+    misses:      AtomicU64,
+    invalidated: AtomicU64,
+}
+
+fn io_error(_: std::io::Error) -> Error {
+    Error::system_io_error("Failed to access the lowering cache")
+}
+
+impl LoweringCache {
+    /// Open the cache kept under `root` for the running engine, removing the
+    /// entries of other builds
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the cache directory cannot be created or
+    /// stale entries cannot be removed.
+    pub fn open(root: impl AsRef<Path>) -> Result<Self> {
+        Self::open_for(root, BuildId::current())
+    }
+
+    /// Open the cache kept under `root` for engines with `build_id`
+    ///
+    /// # Errors
+    ///
+    /// As [`Self::open`].
+    pub fn open_for(root: impl AsRef<Path>, build_id: BuildId) -> Result<Self> {
+        let platform_dir = root.as_ref().join(platform());
+        let dir = platform_dir.join(build_id.to_string());
+        fs::create_dir_all(&dir).map_err(io_error)?;
+        for entry in fs::read_dir(&platform_dir).map_err(io_error)? {
+            let path = entry.map_err(io_error)?.path();
+            if path != dir && path.is_dir() {
+                fs::remove_dir_all(&path).map_err(io_error)?;
+            }
+        }
+        Ok(Self {
+            dir,
+            build_id,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            invalidated: AtomicU64::new(0),
+        })
+    }
+
+    /// Directory the entries are kept in
+    pub fn directory(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Build id the entries were lowered by
+    pub fn build_id(&self) -> BuildId {
+        self.build_id
+    }
+
+    /// Lookups so far
+    pub fn stats(&self) -> LoweringCacheStats {
+        LoweringCacheStats {
+            hits:        self.hits.load(Ordering::Relaxed),
+            misses:      self.misses.load(Ordering::Relaxed),
+            invalidated: self.invalidated.load(Ordering::Relaxed),
+        }
+    }
+
+    fn entry_path(&self, binary: &[u8]) -> PathBuf {
+        self.dir.join(format!("{:08x}.wrtl", cache_key(binary)))
+    }
+
+    /// The lowered module cached for `binary`, if there is a valid one
+    ///
+    /// An entry that fails its integrity checks is removed.
+    pub fn load(&self, binary: &[u8]) -> Option<Module> {
+        let path = self.entry_path(binary);
+        let Ok(bytes) = fs::read(&path) else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            return None;
+        };
+        match self.check_entry(&bytes, binary).and_then(Module::deserialize) {
+            Ok(module) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                Some(module)
+            },
+            Err(_) => {
+                let _ = fs::remove_file(&path);
+                self.invalidated.fetch_add(1, Ordering::Relaxed);
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            },
+        }
+    }
+
+    /// Cache `module`, lowered from `binary`
+    ///
+    /// The entry is written to a temporary file first, so a concurrent
+    /// [`Self::load`] never sees it half written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the module fails to serialize or the entry cannot
+    /// be written.
+    pub fn store(&self, binary: &[u8], module: &Module) -> Result<()> {
+        let len = u32::try_from(binary.len())
+            .map_err(|_| Error::capacity_limit_exceeded("Module binary is too large to cache"))?;
+        let serialized = module.serialize()?;
+        let mut bytes = Vec::with_capacity(ENTRY_HEADER_SIZE + serialized.len());
+        bytes.extend_from_slice(&LOWERING_CACHE_MAGIC);
+        bytes.extend_from_slice(&self.build_id.0.to_le_bytes());
+        bytes.extend_from_slice(&len.to_le_bytes());
+        bytes.extend_from_slice(&Checksum::compute(binary).value().to_le_bytes());
+        bytes.extend_from_slice(&serialized);
+
+        let path = self.entry_path(binary);
+        let partial = path.with_extension(format!("{}.partial", std::process::id()));
+        fs::write(&partial, &bytes).map_err(io_error)?;
+        fs::rename(&partial, &path).map_err(|error| {
+            let _ = fs::remove_file(&partial);
+            io_error(error)
+        })
+    }
+
+    /// Remove all entries
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if an entry cannot be removed.
+    pub fn clear(&self) -> Result<()> {
+        for entry in fs::read_dir(&self.dir).map_err(io_error)? {
+            fs::remove_file(entry.map_err(io_error)?.path()).map_err(io_error)?;
+        }
+        Ok(())
+    }
+
+    /// Check the header of an entry against `binary` and return the
+    /// serialized module it holds
+    fn check_entry<'a>(&self, bytes: &'a [u8], binary: &[u8]) -> Result<&'a [u8]> {
+        if bytes.len() < ENTRY_HEADER_SIZE || bytes[..4] != LOWERING_CACHE_MAGIC {
+            return Err(Error::validation_parse_error("Not a lowering cache entry"));
+        }
+        let field = |offset: usize| {
+            u32::from_le_bytes([
+                bytes[offset],
+                bytes[offset + 1],
+                bytes[offset + 2],
+                bytes[offset + 3],
+            ])
+        };
+        if field(4) != self.build_id.0 {
+            return Err(Error::validation_parse_error(
+                "Lowering cache entry of another engine build",
+            ));
+        }
+        if field(8) as usize != binary.len() || field(12) != Checksum::compute(binary).value() {
+            return Err(Error::validation_parse_error(
+                "Lowering cache entry of another module",
+            ));
+        }
+        Ok(&bytes[ENTRY_HEADER_SIZE..])
+    }
+}
+
+impl Module {
+    /// Load a module from `binary`, taking the lowered module from `cache`
+    /// if it holds a valid one and adding it to the cache otherwise
+    ///
+    /// A cache that cannot be written does not fail the load.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error as [`Module::load_from_binary`] does.
+    pub fn load_cached(binary: &[u8], cache: &LoweringCache) -> Result<Self> {
+        if let Some(module) = cache.load(binary) {
+            return Ok(module);
+        }
+        let module = Self::new()?.load_from_binary(binary)?;
+        let _ = cache.store(binary, &module);
+        Ok(module)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("wrt-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        root
+    }
+
+    #[test]
+    fn test_entries_are_checked_against_binary_and_build() {
+        let root = temp_root("lowering-cache-checks");
+        let cache = LoweringCache::open_for(&root, BuildId(7)).unwrap();
+        let binary = b"\0asm\x01\0\0\0";
+        let mut entry = Vec::new();
+        entry.extend_from_slice(&LOWERING_CACHE_MAGIC);
+        entry.extend_from_slice(&7u32.to_le_bytes());
+        entry.extend_from_slice(&(binary.len() as u32).to_le_bytes());
+        entry.extend_from_slice(&Checksum::compute(binary).value().to_le_bytes());
+        entry.extend_from_slice(b"module");
+
+        assert_eq!(cache.check_entry(&entry, binary).unwrap(), b"module");
+        assert!(cache.check_entry(&entry, b"\0asm\x01\0\0\x01").is_err());
+        let other = LoweringCache::open_for(&root, BuildId(8)).unwrap();
+        assert!(other.check_entry(&entry, binary).is_err());
+
+        // A corrupted entry is removed and counted
+        fs::write(other.entry_path(binary), &entry).unwrap();
+        assert!(other.load(binary).is_none());
+        assert!(!other.entry_path(binary).exists());
+        assert_eq!(
+            other.stats(),
+            LoweringCacheStats {
+                hits:        0,
+                misses:      1,
+                invalidated: 1,
+            }
+        );
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_other_builds_are_invalidated() {
+        let root = temp_root("lowering-cache-builds");
+        let old = LoweringCache::open_for(&root, BuildId(1)).unwrap();
+        fs::write(old.directory().join("00000000.wrtl"), b"stale").unwrap();
+
+        let new = LoweringCache::open_for(&root, BuildId(2)).unwrap();
+        assert!(!old.directory().exists());
+        assert!(new.directory().ends_with(Path::new(&platform()).join("00000002")));
+        assert_ne!(BuildId::current().to_string(), "");
+        assert!(build_fingerprint().contains("cost model 2"));
+        let _ = fs::remove_dir_all(&root);
+    }
+}
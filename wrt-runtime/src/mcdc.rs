@@ -0,0 +1,417 @@
+//! MC/DC coverage of the runtime's own decisions
+//!
+//! Certification of the runtime requires modified condition/decision
+//! coverage (MC/DC) evidence collected on the target. The checks the runtime
+//! makes while executing a module, such as memory bounds and alignment,
+//! table bounds and call signatures, are evaluated through [`decision!`],
+//! which with the `mcdc-coverage` feature counts every combination of
+//! condition values and outcome observed in a bounded table. Without the
+//! feature it evaluates to the outcome alone and costs nothing.
+//!
+//! The counts are read with [`coverage`] and written out with [`dump`], for
+//! example over a debug UART at the end of a test campaign.
+//! [`DecisionCoverage::condition_shown_independent`] applies masking MC/DC,
+//! so conditions skipped by short-circuit evaluation do not need to be
+//! varied.
+
+use core::{
+    fmt,
+    sync::atomic::{
+        AtomicU32,
+        Ordering,
+    },
+};
+
+/// Largest number of conditions of a decision
+pub const MAX_CONDITIONS: usize = 3;
+
+/// Number of combinations of condition values of a decision, counting a
+/// condition skipped by short-circuit evaluation as a value of its own
+const VECTORS: usize = 27;
+
+/// A decision the runtime makes, outcome `true` meaning the check passed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Decision {
+    /// Memory access: the end address does not overflow, and lies within
+    /// the memory
+    MemoryAccessInBounds,
+    /// Memory access: the address is a multiple of the alignment
+    MemoryAccessAligned,
+    /// Memory growth: the new size is within the declared maximum
+    MemoryGrowWithinMaximum,
+    /// Memory growth: the new size is within the 4 GiB limit
+    MemoryGrowWithinLimit,
+    /// Table access: the index is within the table
+    TableIndexInBounds,
+    /// Indirect tail call: the parameter types match, and the result types
+    /// match
+    TailCallSignatureMatches,
+    /// Function execution: the function index exists in the module
+    FunctionIndexInBounds,
+}
+
+impl Decision {
+    /// All decisions
+    pub const ALL: [Self; 7] = [
+        Self::MemoryAccessInBounds,
+        Self::MemoryAccessAligned,
+        Self::MemoryGrowWithinMaximum,
+        Self::MemoryGrowWithinLimit,
+        Self::TableIndexInBounds,
+        Self::TailCallSignatureMatches,
+        Self::FunctionIndexInBounds,
+    ];
+
+    /// Name of the decision in [`dump`]s
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::MemoryAccessInBounds => "memory_access_in_bounds",
+            Self::MemoryAccessAligned => "memory_access_aligned",
+            Self::MemoryGrowWithinMaximum => "memory_grow_within_maximum",
+            Self::MemoryGrowWithinLimit => "memory_grow_within_limit",
+            Self::TableIndexInBounds => "table_index_in_bounds",
+            Self::TailCallSignatureMatches => "tail_call_signature_matches",
+            Self::FunctionIndexInBounds => "function_index_in_bounds",
+        }
+    }
+
+    /// Number of conditions of the decision
+    pub const fn condition_count(self) -> usize {
+        match self {
+            Self::MemoryAccessInBounds | Self::TailCallSignatureMatches => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// Evaluate a decision, recording its conditions with the `mcdc-coverage`
+/// feature
+///
+/// `decision!(decision, condition)` records a decision of a single
+/// condition. `decision!(decision, [conditions], outcome)` takes the
+/// conditions as `Option<bool>`s in evaluation order, `None` for one that
+/// short-circuit evaluation skipped. Conditions are only evaluated with the
+/// feature, so they must not have side effects.
+macro_rules! decision {
+    ($decision:expr, [$($condition:expr),+ $(,)?], $outcome:expr) => {{
+        let outcome: bool = $outcome;
+        #[cfg(feature = "mcdc-coverage")]
+        $crate::mcdc::record($decision, &[$($condition),+], outcome);
+        outcome
+    }};
+    ($decision:expr, $condition:expr) => {{
+        let outcome: bool = $condition;
+        #[cfg(feature = "mcdc-coverage")]
+        $crate::mcdc::record($decision, &[Some(outcome)], outcome);
+        outcome
+    }};
+}
+pub(crate) use decision;
+
+/// Position of a combination of condition values in a [`DecisionTable`]
+fn vector_index(conditions: &[Option<bool>]) -> usize {
+    conditions.iter().take(MAX_CONDITIONS).rev().fold(0, |index, condition| {
+        index * 3
+            + match condition {
+                None => 0,
+                Some(false) => 1,
+                Some(true) => 2,
+            }
+    })
+}
+
+/// Combination of condition values at `index` of a [`DecisionTable`]
+fn vector_conditions(mut index: usize) -> [Option<bool>; MAX_CONDITIONS] {
+    let mut conditions = [None; MAX_CONDITIONS];
+    for condition in &mut conditions {
+        *condition = match index % 3 {
+            0 => None,
+            1 => Some(false),
+            _ => Some(true),
+        };
+        index /= 3;
+    }
+    conditions
+}
+
+// Only used to initialize the array of counters
+#[allow(clippy::declare_interior_mutable_const)]
+const ZERO: AtomicU32 = AtomicU32::new(0);
+
+/// Bounded table of how often each combination of condition values and
+/// outcome of each [`Decision`] was observed
+#[derive(Debug)]
+pub struct DecisionTable {
+    counts: [AtomicU32; Decision::ALL.len() * VECTORS * 2],
+}
+
+impl DecisionTable {
+    /// An empty table
+    pub const fn new() -> Self {
+        Self {
+            counts: [ZERO; Decision::ALL.len() * VECTORS * 2],
+        }
+    }
+
+    fn slot(decision: Decision, vector: usize, outcome: bool) -> usize {
+        (decision as usize * VECTORS + vector) * 2 + usize::from(outcome)
+    }
+
+    /// Count one evaluation of `decision`
+    ///
+    /// Conditions beyond [`MAX_CONDITIONS`] are ignored. Counts saturate.
+    pub fn record(&self, decision: Decision, conditions: &[Option<bool>], outcome: bool) {
+        let slot = Self::slot(decision, vector_index(conditions), outcome);
+        let _ = self.counts[slot].fetch_update(Ordering::Relaxed, Ordering::Relaxed, |count| {
+            count.checked_add(1)
+        });
+    }
+
+    /// Observations of `decision` so far
+    pub fn coverage(&self, decision: Decision) -> DecisionCoverage {
+        let mut counts = [[0; 2]; VECTORS];
+        for (vector, outcomes) in counts.iter_mut().enumerate() {
+            for (outcome, count) in outcomes.iter_mut().enumerate() {
+                *count =
+                    self.counts[Self::slot(decision, vector, outcome == 1)].load(Ordering::Relaxed);
+            }
+        }
+        DecisionCoverage { decision, counts }
+    }
+
+    /// Forget all observations
+    pub fn reset(&self) {
+        for count in &self.counts {
+            count.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// Write the observations of all decisions to `out`
+    ///
+    /// Each decision is written as a line
+    /// `mcdc <name> <independent conditions>/<conditions> <covered|partial>`
+    /// followed by a line `<conditions> <outcome> <count>`, indented by a
+    /// space, per observed combination. Conditions are written as `T`, `F`,
+    /// or `-` when skipped, and the outcome as `T` or `F`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `out` fails.
+    pub fn dump(&self, out: &mut impl fmt::Write) -> fmt::Result {
+        for decision in Decision::ALL {
+            let coverage = self.coverage(decision);
+            let independent = (0..decision.condition_count())
+                .filter(|&condition| coverage.condition_shown_independent(condition))
+                .count();
+            writeln!(
+                out,
+                "mcdc {} {}/{} {}",
+                decision.name(),
+                independent,
+                decision.condition_count(),
+                if coverage.is_mcdc_covered() { "covered" } else { "partial" }
+            )?;
+            for observation in coverage.observations() {
+                out.write_str(" ")?;
+                for condition in observation.conditions() {
+                    out.write_char(match condition {
+                        None => '-',
+                        Some(false) => 'F',
+                        Some(true) => 'T',
+                    })?;
+                }
+                writeln!(
+                    out,
+                    " {} {}",
+                    if observation.outcome() { 'T' } else { 'F' },
+                    observation.count()
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for DecisionTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One observed combination of condition values and outcome of a decision
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Observation {
+    conditions:      [Option<bool>; MAX_CONDITIONS],
+    condition_count: usize,
+    outcome:         bool,
+    count:           u32,
+}
+
+impl Observation {
+    /// Values of the conditions, `None` for one skipped by short-circuit
+    /// evaluation
+    pub fn conditions(&self) -> &[Option<bool>] {
+        &self.conditions[..self.condition_count]
+    }
+
+    /// Outcome of the decision
+    pub fn outcome(&self) -> bool {
+        self.outcome
+    }
+
+    /// Number of times the combination was observed
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+}
+
+/// Observations of one decision
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecisionCoverage {
+    decision: Decision,
+    counts:   [[u32; 2]; VECTORS],
+}
+
+impl DecisionCoverage {
+    /// The decision observed
+    pub fn decision(&self) -> Decision {
+        self.decision
+    }
+
+    /// Combinations of condition values and outcome observed at least once
+    pub fn observations(&self) -> impl Iterator<Item = Observation> + '_ {
+        let condition_count = self.decision.condition_count();
+        self.counts.iter().enumerate().flat_map(move |(vector, outcomes)| {
+            [false, true].into_iter().filter_map(move |outcome| {
+                let count = outcomes[usize::from(outcome)];
+                (count > 0).then(|| Observation {
+                    conditions: vector_conditions(vector),
+                    condition_count,
+                    outcome,
+                    count,
+                })
+            })
+        })
+    }
+
+    /// Whether the decision was observed with both outcomes
+    pub fn outcomes_covered(&self) -> bool {
+        self.observations().any(|observation| observation.outcome())
+            && self.observations().any(|observation| !observation.outcome())
+    }
+
+    /// Whether `condition` was shown to independently affect the outcome
+    ///
+    /// This needs two observations with different outcomes in which the
+    /// condition was evaluated with different values, while every other
+    /// condition evaluated in both had the same value.
+    pub fn condition_shown_independent(&self, condition: usize) -> bool {
+        self.observations().any(|first| {
+            self.observations().any(|second| {
+                first.outcome() != second.outcome()
+                    && matches!(
+                        (first.conditions.get(condition), second.conditions.get(condition)),
+                        (Some(Some(a)), Some(Some(b))) if a != b
+                    )
+                    && first.conditions().iter().zip(second.conditions()).enumerate().all(
+                        |(other, pair)| match pair {
+                            (Some(a), Some(b)) => other == condition || a == b,
+                            _ => true,
+                        },
+                    )
+            })
+        })
+    }
+
+    /// Whether MC/DC was achieved: both outcomes were observed and every
+    /// condition was shown to independently affect the outcome
+    pub fn is_mcdc_covered(&self) -> bool {
+        self.outcomes_covered()
+            && (0..self.decision.condition_count())
+                .all(|condition| self.condition_shown_independent(condition))
+    }
+}
+
+/// Table the runtime's decisions are recorded in
+#[cfg(feature = "mcdc-coverage")]
+static DECISIONS: DecisionTable = DecisionTable::new();
+
+/// Count one evaluation of `decision` in the runtime's table
+#[cfg(feature = "mcdc-coverage")]
+#[inline]
+pub fn record(decision: Decision, conditions: &[Option<bool>], outcome: bool) {
+    DECISIONS.record(decision, conditions, outcome);
+}
+
+/// Observations of `decision` made by the runtime so far
+#[cfg(feature = "mcdc-coverage")]
+pub fn coverage(decision: Decision) -> DecisionCoverage {
+    DECISIONS.coverage(decision)
+}
+
+/// Forget the observations made by the runtime
+#[cfg(feature = "mcdc-coverage")]
+pub fn reset() {
+    DECISIONS.reset();
+}
+
+/// Write the observations made by the runtime to `out`, in the format of
+/// [`DecisionTable::dump`]
+///
+/// # Errors
+///
+/// Returns an error if writing to `out` fails.
+#[cfg(feature = "mcdc-coverage")]
+pub fn dump(out: &mut impl fmt::Write) -> fmt::Result {
+    DECISIONS.dump(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_condition_needs_both_outcomes() {
+        let table = DecisionTable::new();
+        table.record(Decision::TableIndexInBounds, &[Some(true)], true);
+        table.record(Decision::TableIndexInBounds, &[Some(true)], true);
+
+        let coverage = table.coverage(Decision::TableIndexInBounds);
+        assert!(!coverage.is_mcdc_covered());
+        let observation = coverage.observations().next().unwrap();
+        assert_eq!(observation.conditions(), &[Some(true)]);
+        assert_eq!(observation.count(), 2);
+
+        table.record(Decision::TableIndexInBounds, &[Some(false)], false);
+        assert!(table.coverage(Decision::TableIndexInBounds).is_mcdc_covered());
+        assert!(!table.coverage(Decision::MemoryAccessAligned).outcomes_covered());
+
+        table.reset();
+        assert_eq!(
+            table.coverage(Decision::TableIndexInBounds).observations().count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_short_circuit_conditions_are_masked() {
+        let table = DecisionTable::new();
+        let decision = Decision::MemoryAccessInBounds;
+        table.record(decision, &[Some(true), Some(true)], true);
+        table.record(decision, &[Some(true), Some(false)], false);
+        let coverage = table.coverage(decision);
+        assert!(!coverage.condition_shown_independent(0));
+        assert!(coverage.condition_shown_independent(1));
+
+        // The second condition is not evaluated when the first one fails
+        table.record(decision, &[Some(false), None], false);
+        assert!(table.coverage(decision).is_mcdc_covered());
+
+        let mut dumped = String::new();
+        table.dump(&mut dumped).unwrap();
+        let lines: Vec<&str> = dumped.lines().collect();
+        assert_eq!(lines[0], "mcdc memory_access_in_bounds 2/2 covered");
+        assert_eq!(&lines[1..4], [" F- F 1", " TF F 1", " TT T 1"]);
+        assert_eq!(lines[4], "mcdc memory_access_aligned 0/1 partial");
+    }
+}
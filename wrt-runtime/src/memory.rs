@@ -61,11 +61,10 @@
 //!
 //! # Thread Safety
 //!
-//! Memory operations use appropriate synchronization primitives based on the
-//! environment:
-//!
-//! - In `std` environments, atomic variables are used for metrics
-//! - In `no_std` environments, `RwLock` is used for metrics
+//! Memory metrics are atomic variables on targets with 64-bit atomics, so
+//! recording an access never takes a lock there; other targets keep them
+//! behind an `RwLock`. Mutating the contents or size of a memory requires
+//! `&mut Memory`.
 //!
 //! # Usage
 //!
@@ -108,13 +107,16 @@
 use alloc::vec;
 #[cfg(not(feature = "std"))]
 use core::borrow::BorrowMut;
+#[cfg(target_has_atomic = "64")]
+use core::sync::atomic::{
+    AtomicU64,
+    AtomicUsize,
+};
 use core::{
     alloc::Layout,
     sync::atomic::{
         AtomicBool,
         AtomicU32,
-        AtomicU64,
-        AtomicUsize,
         Ordering,
     },
     time::Duration,
@@ -140,9 +142,28 @@
 use wrt_instructions::atomic_ops::AtomicOperations;
 // Import the MemoryOperations trait from wrt-instructions
 use wrt_instructions::memory_ops::MemoryOperations;
-#[cfg(not(feature = "std"))]
+#[cfg(not(target_has_atomic = "64"))]
 use wrt_sync::WrtRwLock as RwLock;
 
+#[cfg(feature = "std")]
+use crate::growth_observer::{
+    GrowthEvent,
+    GrowthHook,
+    GrowthKind,
+    GrowthRequester,
+    MemoryObserver,
+};
+use crate::{
+    mcdc::{
+        decision,
+        Decision,
+    },
+    memory_arith,
+    memory_view::{
+        MemoryView,
+        MemoryViewMut,
+    },
+};
 #[cfg(not(feature = "std"))]
 use crate::prelude::vec_with_capacity;
 // Internal modules
@@ -163,12 +184,31 @@
     TryFrom,
     VerificationLevel,
 };
+#[cfg(feature = "std")]
+use crate::uninit_memory::InitTracker;
 
-// Platform-aware memory providers for memory operations
+// Platform-aware memory providers for memory operations. Memory data lives on
+// the heap where there is one, as 64MB inline would not fit on a stack
+#[cfg(any(feature = "std", feature = "alloc"))]
+type LargeMemoryProvider = wrt_foundation::heap_provider::HeapProvider;
+#[cfg(not(any(feature = "std", feature = "alloc")))]
 type LargeMemoryProvider = wrt_foundation::safe_memory::NoStdProvider<67108864>; // 64MB for memory data
 type SmallMemoryProvider = wrt_foundation::safe_memory::NoStdProvider<4096>; // 4KB for small objects
 type MediumMemoryProvider = wrt_foundation::safe_memory::NoStdProvider<65536>; // 64KB for medium objects
 
+/// Provider without any bytes yet, for memories to resize to their pages
+fn empty_provider() -> LargeMemoryProvider {
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    {
+        LargeMemoryProvider::new(0).unwrap_or_default()
+    }
+
+    #[cfg(not(any(feature = "std", feature = "alloc")))]
+    {
+        LargeMemoryProvider::default()
+    }
+}
+
 /// WebAssembly page size (64KB)
 pub const PAGE_SIZE: usize = 65536;
 
@@ -231,77 +271,64 @@ fn usize_to_wasm_u32(size: usize) -> Result<u32> {
 #[derive(Debug)]
 pub struct MemoryMetrics {
     /// Peak memory usage in bytes
-    #[cfg(feature = "std")]
+    #[cfg(target_has_atomic = "64")]
     peak_usage:         AtomicUsize,
     /// Memory access counter for profiling
-    #[cfg(feature = "std")]
+    #[cfg(target_has_atomic = "64")]
     access_count:       AtomicU64,
     /// Maximum size of any access
-    #[cfg(feature = "std")]
+    #[cfg(target_has_atomic = "64")]
     max_access_size:    AtomicUsize,
     /// Number of unique regions accessed
-    #[cfg(feature = "std")]
+    #[cfg(target_has_atomic = "64")]
     unique_regions:     AtomicUsize,
     /// Last access offset for validation
-    #[cfg(feature = "std")]
+    #[cfg(target_has_atomic = "64")]
     last_access_offset: AtomicUsize,
     /// Last access length for validation
-    #[cfg(feature = "std")]
+    #[cfg(target_has_atomic = "64")]
     last_access_length: AtomicUsize,
 
-    /// Peak memory usage (`no_std` version)
-    #[cfg(not(feature = "std"))]
+    /// Metrics of targets without 64-bit atomics
+    #[cfg(not(target_has_atomic = "64"))]
+    values: RwLock<MetricValues>,
+}
+
+/// Values of [`MemoryMetrics`], which targets without 64-bit atomics keep
+/// behind a lock
+#[derive(Debug, Clone, Copy)]
+struct MetricValues {
     peak_usage:         usize,
-    /// Memory access counter (`no_std` version)
-    #[cfg(not(feature = "std"))]
     access_count:       u64,
-    /// Maximum size of any access (`no_std` version)
-    #[cfg(not(feature = "std"))]
     max_access_size:    usize,
-    /// Number of unique regions accessed (`no_std` version)
-    #[cfg(not(feature = "std"))]
     unique_regions:     usize,
-    /// Last access offset for validation (`no_std` version)
-    #[cfg(not(feature = "std"))]
     last_access_offset: usize,
-    /// Last access length for validation (`no_std` version)
-    #[cfg(not(feature = "std"))]
     last_access_length: usize,
 }
 
 impl Clone for MemoryMetrics {
+    #[cfg(target_has_atomic = "64")]
     fn clone(&self) -> Self {
-        #[cfg(feature = "std")]
-        {
-            Self {
-                peak_usage:         AtomicUsize::new(self.peak_usage.load(Ordering::Relaxed)),
-                access_count:       AtomicU64::new(self.access_count.load(Ordering::Relaxed)),
-                max_access_size:    AtomicUsize::new(self.max_access_size.load(Ordering::Relaxed)),
-                unique_regions:     AtomicUsize::new(self.unique_regions.load(Ordering::Relaxed)),
-                last_access_offset: AtomicUsize::new(
-                    self.last_access_offset.load(Ordering::Relaxed),
-                ),
-                last_access_length: AtomicUsize::new(
-                    self.last_access_length.load(Ordering::Relaxed),
-                ),
-            }
+        Self {
+            peak_usage:         AtomicUsize::new(self.peak_usage.load(Ordering::Relaxed)),
+            access_count:       AtomicU64::new(self.access_count.load(Ordering::Relaxed)),
+            max_access_size:    AtomicUsize::new(self.max_access_size.load(Ordering::Relaxed)),
+            unique_regions:     AtomicUsize::new(self.unique_regions.load(Ordering::Relaxed)),
+            last_access_offset: AtomicUsize::new(self.last_access_offset.load(Ordering::Relaxed)),
+            last_access_length: AtomicUsize::new(self.last_access_length.load(Ordering::Relaxed)),
         }
-        #[cfg(not(feature = "std"))]
-        {
-            Self {
-                peak_usage:         self.peak_usage,
-                access_count:       self.access_count,
-                max_access_size:    self.max_access_size,
-                unique_regions:     self.unique_regions,
-                last_access_offset: self.last_access_offset,
-                last_access_length: self.last_access_length,
-            }
+    }
+
+    #[cfg(not(target_has_atomic = "64"))]
+    fn clone(&self) -> Self {
+        Self {
+            values: RwLock::new(*self.values.read()),
         }
     }
 }
 
 impl MemoryMetrics {
-    #[cfg(feature = "std")]
+    #[cfg(target_has_atomic = "64")]
     fn new(size: usize) -> Self {
         Self {
             peak_usage:         AtomicUsize::new(size),
@@ -313,16 +340,73 @@ fn new(size: usize) -> Self {
         }
     }
 
-    #[cfg(not(feature = "std"))]
+    #[cfg(not(target_has_atomic = "64"))]
     fn new(size: usize) -> Self {
         Self {
-            peak_usage:         size,
-            access_count:       0,
-            max_access_size:    0,
-            unique_regions:     0,
-            last_access_offset: 0,
-            last_access_length: 0,
+            values: RwLock::new(MetricValues {
+                peak_usage:         size,
+                access_count:       0,
+                max_access_size:    0,
+                unique_regions:     0,
+                last_access_offset: 0,
+                last_access_length: 0,
+            }),
+        }
+    }
+
+    /// Record an access of `len` bytes at `offset`, counting it if `counted`
+    #[cfg(target_has_atomic = "64")]
+    fn record_access(&self, offset: usize, len: usize, counted: bool) {
+        if counted {
+            self.access_count.fetch_add(1, Ordering::Relaxed);
         }
+        self.max_access_size.fetch_max(len, Ordering::Relaxed);
+        self.last_access_offset.store(offset, Ordering::Relaxed);
+        self.last_access_length.store(len, Ordering::Relaxed);
+    }
+
+    /// Record an access of `len` bytes at `offset`, counting it if `counted`
+    #[cfg(not(target_has_atomic = "64"))]
+    fn record_access(&self, offset: usize, len: usize, counted: bool) {
+        let mut values = self.values.write();
+        if counted {
+            values.access_count += 1;
+        }
+        values.max_access_size = values.max_access_size.max(len);
+        values.last_access_offset = offset;
+        values.last_access_length = len;
+    }
+
+    /// Raise the peak usage to `size` bytes if it is below
+    #[cfg(target_has_atomic = "64")]
+    fn record_size(&self, size: usize) {
+        self.peak_usage.fetch_max(size, Ordering::Relaxed);
+    }
+
+    /// Raise the peak usage to `size` bytes if it is below
+    #[cfg(not(target_has_atomic = "64"))]
+    fn record_size(&self, size: usize) {
+        let mut values = self.values.write();
+        values.peak_usage = values.peak_usage.max(size);
+    }
+
+    /// Current values of the metrics
+    #[cfg(target_has_atomic = "64")]
+    fn snapshot(&self) -> MetricValues {
+        MetricValues {
+            peak_usage:         self.peak_usage.load(Ordering::Relaxed),
+            access_count:       self.access_count.load(Ordering::Relaxed),
+            max_access_size:    self.max_access_size.load(Ordering::Relaxed),
+            unique_regions:     self.unique_regions.load(Ordering::Relaxed),
+            last_access_offset: self.last_access_offset.load(Ordering::Relaxed),
+            last_access_length: self.last_access_length.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Current values of the metrics
+    #[cfg(not(target_has_atomic = "64"))]
+    fn snapshot(&self) -> MetricValues {
+        *self.values.read()
     }
 }
 
@@ -338,13 +422,15 @@ pub struct Memory {
     /// Optional name for debugging
     pub debug_name: Option<wrt_foundation::bounded::BoundedString<128, SmallMemoryProvider>>,
     /// Memory metrics for tracking access
-    #[cfg(feature = "std")]
     pub metrics:            MemoryMetrics,
-    /// Memory metrics for tracking access (`RwLock` for `no_std`)
-    #[cfg(not(feature = "std"))]
-    pub metrics:            RwLock<MemoryMetrics>,
     /// Memory verification level
     pub verification_level: VerificationLevel,
+    /// Observer notified about growth
+    #[cfg(feature = "std")]
+    growth_hook:            Option<GrowthHook>,
+    /// Bytes written, when reads of uninitialized bytes are checked
+    #[cfg(feature = "std")]
+    init_tracker:           Option<InitTracker>,
 }
 
 impl Clone for Memory {
@@ -357,11 +443,13 @@ fn clone(&self) -> Self {
 
         // Create new SafeMemoryHandler
         let new_data = {
-            let new_provider = LargeMemoryProvider::default();
-            let mut new_handler = SafeMemoryHandler::new(new_provider);
+            let mut new_handler = SafeMemoryHandler::new(empty_provider());
 
             // Copy the data into the new handler
             if !current_bytes.is_empty() {
+                new_handler.resize(current_bytes.len()).unwrap_or_else(|e| {
+                    panic!("Failed to allocate cloned memory: {}", e);
+                });
                 new_handler.write_data(0, &current_bytes).unwrap_or_else(|e| {
                     panic!("Failed to write cloned data: {}", e); // Safe: memory cloning is infallible after successful read
                 });
@@ -370,30 +458,7 @@ fn clone(&self) -> Self {
             new_handler
         };
 
-        // Clone metrics, handling potential RwLock poisoning for no_std
-        #[cfg(feature = "std")]
-        let cloned_metrics = MemoryMetrics {
-            peak_usage:         AtomicUsize::new(self.metrics.peak_usage.load(Ordering::Relaxed)),
-            access_count:       AtomicU64::new(self.metrics.access_count.load(Ordering::Relaxed)),
-            max_access_size:    AtomicUsize::new(
-                self.metrics.max_access_size.load(Ordering::Relaxed),
-            ),
-            unique_regions:     AtomicUsize::new(
-                self.metrics.unique_regions.load(Ordering::Relaxed),
-            ),
-            last_access_offset: AtomicUsize::new(
-                self.metrics.last_access_offset.load(Ordering::Relaxed),
-            ),
-            last_access_length: AtomicUsize::new(
-                self.metrics.last_access_length.load(Ordering::Relaxed),
-            ),
-        };
-
-        #[cfg(not(feature = "std"))]
-        let cloned_metrics = {
-            let guard = self.metrics.read();
-            RwLock::new((*guard).clone())
-        };
+        let cloned_metrics = self.metrics.clone();
 
         Self {
             ty:                 self.ty,
@@ -402,13 +467,17 @@ fn clone(&self) -> Self {
             debug_name:         self.debug_name.clone(),
             metrics:            cloned_metrics,
             verification_level: self.verification_level,
+            #[cfg(feature = "std")]
+            growth_hook:        None,
+            #[cfg(feature = "std")]
+            init_tracker:       self.init_tracker.clone(),
         }
     }
 }
 
 impl PartialEq for Memory {
     fn eq(&self, other: &Self) -> bool {
-        // Compare memory data by extracting bytes from RwLock
+        // Compare memory data by extracting its bytes
         let self_data = {
             self.data.to_vec().unwrap_or_default() // Safe: memory comparison
                                                    // read is infallible
@@ -555,17 +624,7 @@ pub fn new(ty: CoreMemoryType) -> Result<Self> {
         // Let's try to instantiate the provider directly.
 
         // Create memory provider based on available features
-        #[cfg(feature = "std")]
-        let data_handler = {
-            let provider = LargeMemoryProvider::default();
-            SafeMemoryHandler::new(provider)
-        };
-
-        #[cfg(not(feature = "std"))]
-        let data_handler = {
-            let provider = LargeMemoryProvider::default();
-            SafeMemoryHandler::new(provider)
-        };
+        let mut data_handler = SafeMemoryHandler::new(empty_provider());
 
         // Binary std/no_std choice
         // initial_pages. Wasm spec implies memory is zero-initialized. mmap
@@ -576,21 +635,45 @@ pub fn new(ty: CoreMemoryType) -> Result<Self> {
         // Binary std/no_std choice
         // should provide zeroed memory for the initial pages.
 
-        let current_size_bytes = wasm_offset_to_usize(initial_pages)? * PAGE_SIZE;
+        let current_size_bytes = memory_arith::pages_to_bytes(initial_pages)?;
+
+        // Make the initial pages accessible, as `grow` does for added pages
+        data_handler.resize(current_size_bytes)?;
 
         Ok(Self {
             ty,
             data: data_handler,
             current_pages: core::sync::atomic::AtomicU32::new(initial_pages),
             debug_name: None,
-            #[cfg(feature = "std")]
             metrics: MemoryMetrics::new(current_size_bytes),
-            #[cfg(not(feature = "std"))]
-            metrics: RwLock::new(MemoryMetrics::new(current_size_bytes)),
             verification_level,
+            #[cfg(feature = "std")]
+            growth_hook: None,
+            #[cfg(feature = "std")]
+            init_tracker: None,
         })
     }
 
+    /// Creates the definition of a memory of type `ty`, without allocating
+    /// its pages
+    ///
+    /// Modules hold their memories this way, instances create them with
+    /// [`Memory::new`] from the type.
+    pub(crate) fn definition(ty: CoreMemoryType) -> Self {
+        Self {
+            ty,
+            data: SafeMemoryHandler::new(empty_provider()),
+            current_pages: core::sync::atomic::AtomicU32::new(0),
+            debug_name: None,
+            metrics: MemoryMetrics::new(0),
+            verification_level: VerificationLevel::Standard,
+            #[cfg(feature = "std")]
+            growth_hook: None,
+            #[cfg(feature = "std")]
+            init_tracker: None,
+        }
+    }
+
     /// Creates a new memory instance with a debug name
     ///
     /// # Arguments
@@ -653,7 +736,60 @@ pub fn size(&self) -> u32 {
     #[must_use]
     pub fn size_in_bytes(&self) -> usize {
         let pages = self.current_pages.load(Ordering::Relaxed);
-        wasm_offset_to_usize(pages).unwrap_or(0) * PAGE_SIZE
+        memory_arith::pages_to_bytes(pages).unwrap_or(0)
+    }
+
+    /// Typed, bounds-checked read access for embedders
+    #[must_use]
+    pub fn view(&self) -> MemoryView<'_> {
+        MemoryView::new(self)
+    }
+
+    /// Typed, bounds-checked read and write access for embedders
+    pub fn view_mut(&mut self) -> MemoryViewMut<'_> {
+        MemoryViewMut::new(self)
+    }
+
+    /// Checks whether this memory can satisfy an import of the given type
+    ///
+    /// Follows the import matching rules of the WebAssembly spec: the current
+    /// size must be at least the required minimum, a required maximum must be
+    /// matched by an equal or smaller declared maximum, and sharedness must
+    /// agree.
+    ///
+    /// # Errors
+    ///
+    /// Returns a type mismatch error describing the first incompatibility
+    pub fn check_import_compatibility(&self, required: &CoreMemoryType) -> Result<()> {
+        if self.ty.shared != required.shared {
+            return Err(Error::runtime_type_mismatch(
+                "Imported memory sharedness does not match",
+            ));
+        }
+
+        if self.size() < required.limits.min {
+            return Err(Error::runtime_type_mismatch(
+                "Imported memory is smaller than the required minimum",
+            ));
+        }
+
+        if let Some(required_max) = required.limits.max {
+            match self.ty.limits.max {
+                Some(max) if max <= required_max => {},
+                Some(_) => {
+                    return Err(Error::runtime_type_mismatch(
+                        "Imported memory maximum exceeds the required maximum",
+                    ))
+                },
+                None => {
+                    return Err(Error::runtime_type_mismatch(
+                        "Imported memory has no maximum but one is required",
+                    ))
+                },
+            }
+        }
+
+        Ok(())
     }
 
     /// A reference to the memory data as a `Vec<u8>`
@@ -692,141 +828,42 @@ pub fn buffer(&self) -> Result<std::vec::Vec<u8>> {
 
     /// Returns the peak memory usage in bytes
     pub fn peak_memory(&self) -> usize {
-        #[cfg(feature = "std")]
-        {
-            self.metrics.peak_usage.load(Ordering::Relaxed)
-        }
-
-        #[cfg(not(feature = "std"))]
-        {
-            // Use read() method with WrtRwLock
-            let metrics = self.metrics.read();
-            metrics.peak_usage
-        }
+        self.metrics.snapshot().peak_usage
     }
 
     /// Returns the total number of memory accesses
     pub fn access_count(&self) -> u64 {
-        #[cfg(feature = "std")]
-        {
-            self.metrics.access_count.load(Ordering::Relaxed)
-        }
-
-        #[cfg(not(feature = "std"))]
-        {
-            // Use read() method with WrtRwLock
-            let metrics = self.metrics.read();
-            metrics.access_count
-        }
+        self.metrics.snapshot().access_count
     }
 
     /// Increment the access count for memory operations
     fn increment_access_count(&self, offset: usize, len: usize) {
-        #[cfg(feature = "std")]
-        {
-            self.metrics.access_count.fetch_add(1, Ordering::Relaxed);
-            self.metrics.max_access_size.fetch_max(len, Ordering::Relaxed);
-            self.metrics.last_access_offset.store(offset, Ordering::Relaxed);
-            self.metrics.last_access_length.store(len, Ordering::Relaxed);
-        }
-
-        #[cfg(not(feature = "std"))]
-        {
-            // Use write() method with WrtRwLock
-            let mut metrics = self.metrics.write();
-            metrics.access_count += 1;
-            metrics.max_access_size = metrics.max_access_size.max(len);
-            metrics.last_access_offset = offset;
-            metrics.last_access_length = len;
-        }
+        self.metrics.record_access(offset, len, true);
     }
 
     /// Update the peak memory usage statistic
     fn update_peak_memory(&self) {
-        let current_size = self.size_in_bytes();
-
-        #[cfg(feature = "std")]
-        {
-            let mut current_peak = self.metrics.peak_usage.load(Ordering::Relaxed);
-            while current_size > current_peak {
-                match self.metrics.peak_usage.compare_exchange(
-                    current_peak,
-                    current_size,
-                    Ordering::Relaxed,
-                    Ordering::Relaxed,
-                ) {
-                    Ok(_) => break,
-                    Err(actual) => current_peak = actual,
-                }
-            }
-        }
-
-        #[cfg(not(feature = "std"))]
-        {
-            // Use write() method with WrtRwLock
-            let mut metrics = self.metrics.write();
-            metrics.peak_usage = metrics.peak_usage.max(current_size);
-        }
+        self.metrics.record_size(self.size_in_bytes());
     }
 
     /// Returns the maximum size of any memory access
     pub fn max_access_size(&self) -> usize {
-        #[cfg(feature = "std")]
-        {
-            self.metrics.max_access_size.load(Ordering::Relaxed)
-        }
-
-        #[cfg(not(feature = "std"))]
-        {
-            // Use read() method with WrtRwLock
-            let metrics = self.metrics.read();
-            metrics.max_access_size
-        }
+        self.metrics.snapshot().max_access_size
     }
 
     /// Returns the number of unique memory regions accessed
     pub fn unique_regions(&self) -> usize {
-        #[cfg(feature = "std")]
-        {
-            self.metrics.unique_regions.load(Ordering::Relaxed)
-        }
-
-        #[cfg(not(feature = "std"))]
-        {
-            // Use read() method with WrtRwLock
-            let metrics = self.metrics.read();
-            metrics.unique_regions
-        }
+        self.metrics.snapshot().unique_regions
     }
 
     /// Returns the offset of the most recent memory access
     pub fn last_access_offset(&self) -> usize {
-        #[cfg(feature = "std")]
-        {
-            self.metrics.last_access_offset.load(Ordering::Relaxed)
-        }
-
-        #[cfg(not(feature = "std"))]
-        {
-            // Use read() method with WrtRwLock
-            let metrics = self.metrics.read();
-            metrics.last_access_offset
-        }
+        self.metrics.snapshot().last_access_offset
     }
 
     /// Returns the length of the most recent memory access
     pub fn last_access_length(&self) -> usize {
-        #[cfg(feature = "std")]
-        {
-            self.metrics.last_access_length.load(Ordering::Relaxed)
-        }
-
-        #[cfg(not(feature = "std"))]
-        {
-            // Use read() method with WrtRwLock
-            let metrics = self.metrics.read();
-            metrics.last_access_length
-        }
+        self.metrics.snapshot().last_access_length
     }
 
     /// Grows memory by the given number of pages
@@ -856,19 +893,22 @@ pub fn grow(&mut self, pages: u32) -> Result<u32> {
 
         // Check against the maximum allowed by type
         if let Some(max) = self.ty.limits.max {
-            if new_page_count > max {
+            if !decision!(Decision::MemoryGrowWithinMaximum, new_page_count <= max) {
                 return Err(Error::resource_limit_exceeded("Memory limit exceeded"));
             }
         }
 
         // Check against the absolute maximum (4GB)
-        if new_page_count > MAX_PAGES {
+        if !decision!(Decision::MemoryGrowWithinLimit, new_page_count <= MAX_PAGES) {
             return Err(Error::resource_limit_exceeded("Runtime operation error"));
         }
 
-        // Calculate the new size in bytes and resize through RwLock
+        // Calculate the new size in bytes
         let old_size = { self.data.size() };
-        let new_size = wasm_offset_to_usize(new_page_count)? * PAGE_SIZE;
+        let new_size = memory_arith::pages_to_bytes(new_page_count)?;
+
+        #[cfg(feature = "std")]
+        let event = self.check_growth(current_pages_val, new_page_count)?;
 
         // Resize the underlying data
         self.data.resize(new_size)?;
@@ -879,9 +919,64 @@ pub fn grow(&mut self, pages: u32) -> Result<u32> {
         // Update peak memory usage
         self.update_peak_memory();
 
+        #[cfg(feature = "std")]
+        if let Some(tracker) = &mut self.init_tracker {
+            tracker.grow(new_size);
+        }
+
+        #[cfg(feature = "std")]
+        self.notify_growth(event);
+
         Ok(old_pages)
     }
 
+    /// Observe the growth of the memory with `observer`, reporting it as
+    /// `requester`
+    #[cfg(feature = "std")]
+    pub fn set_growth_observer(
+        &mut self,
+        observer: Arc<dyn MemoryObserver>,
+        requester: GrowthRequester,
+    ) {
+        self.growth_hook = Some(GrowthHook::new(observer, requester));
+    }
+
+    /// Check reads of bytes of the memory that were never written with
+    /// `tracker`, which takes the bytes it records as written as initialized
+    ///
+    /// See [`uninit_memory`](crate::uninit_memory).
+    #[cfg(feature = "std")]
+    pub fn set_init_tracker(&mut self, mut tracker: InitTracker) {
+        tracker.grow(self.size_in_bytes());
+        self.init_tracker = Some(tracker);
+    }
+
+    /// Tracker of the bytes written, if reads of uninitialized bytes are
+    /// checked
+    #[cfg(feature = "std")]
+    pub fn init_tracker(&self) -> Option<&InitTracker> {
+        self.init_tracker.as_ref()
+    }
+
+    /// Ask the growth observer, if any, to allow growing to `new_pages`
+    #[cfg(feature = "std")]
+    fn check_growth(&self, old_pages: u32, new_pages: u32) -> Result<Option<GrowthEvent>> {
+        let Some(hook) = &self.growth_hook else {
+            return Ok(None);
+        };
+        let event = hook.event(GrowthKind::Memory, old_pages, new_pages);
+        hook.check(&event)?;
+        Ok(Some(event))
+    }
+
+    /// Tell the growth observer, if any, that `event` happened
+    #[cfg(feature = "std")]
+    fn notify_growth(&self, event: Option<GrowthEvent>) {
+        if let (Some(hook), Some(event)) = (&self.growth_hook, event) {
+            hook.grown(&event);
+        }
+    }
+
     /// Thread-safe grow operation for shared memory access (works with
     /// Arc<Memory>)
     ///
@@ -913,18 +1008,21 @@ pub fn grow_shared(&mut self, pages: u32) -> Result<u32> {
 
         // Check against the maximum allowed by type
         if let Some(max) = self.ty.limits.max {
-            if new_page_count > max {
+            if !decision!(Decision::MemoryGrowWithinMaximum, new_page_count <= max) {
                 return Err(Error::resource_limit_exceeded("Memory limit exceeded"));
             }
         }
 
         // Check against the absolute maximum (4GB)
-        if new_page_count > MAX_PAGES {
+        if !decision!(Decision::MemoryGrowWithinLimit, new_page_count <= MAX_PAGES) {
             return Err(Error::resource_limit_exceeded("Runtime operation error"));
         }
 
-        // Calculate the new size in bytes and resize through RwLock
-        let new_size = wasm_offset_to_usize(new_page_count)? * PAGE_SIZE;
+        // Calculate the new size in bytes
+        let new_size = memory_arith::pages_to_bytes(new_page_count)?;
+
+        #[cfg(feature = "std")]
+        let event = self.check_growth(current_pages_val, new_page_count)?;
 
         // Resize the underlying data
         self.data.resize(new_size)?;
@@ -935,6 +1033,14 @@ pub fn grow_shared(&mut self, pages: u32) -> Result<u32> {
         // Update peak memory usage
         self.update_peak_memory();
 
+        #[cfg(feature = "std")]
+        if let Some(tracker) = &mut self.init_tracker {
+            tracker.grow(new_size);
+        }
+
+        #[cfg(feature = "std")]
+        self.notify_growth(event);
+
         Ok(old_pages)
     }
 
@@ -965,6 +1071,11 @@ pub fn read(&self, offset: u32, buffer: &mut [u8]) -> Result<()> {
         // Track this access for profiling
         self.increment_access_count(offset_usize, size);
 
+        #[cfg(feature = "std")]
+        if let Some(tracker) = &self.init_tracker {
+            tracker.check_read(offset_usize, size)?;
+        }
+
         // Use safe memory get_slice to get a verified slice
         let safe_slice = self.data.get_slice(offset_usize, size)?;
 
@@ -1012,12 +1123,108 @@ pub fn write(&mut self, offset: u32, buffer: &[u8]) -> Result<()> {
         // Use the SafeMemoryHandler's write_data method for efficient direct writing
         self.data.write_data(offset_usize, buffer)?;
 
+        #[cfg(feature = "std")]
+        if let Some(tracker) = &mut self.init_tracker {
+            tracker.mark_written(offset_usize, size);
+        }
+
         // Update the peak memory usage
         self.update_peak_memory();
 
         Ok(())
     }
 
+    /// Read several ranges of memory, each into its buffer
+    ///
+    /// Every range is checked before any buffer is filled and the access is
+    /// recorded once for all of them, so exchanging many buffers with a guest
+    /// costs one call rather than a [`read`](Self::read) per buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a range extends beyond the memory or reads
+    /// uninitialized bytes; no buffer is filled then.
+    pub fn read_vectored(&self, ranges: &mut [(u32, &mut [u8])]) -> Result<()> {
+        let (offset, size) =
+            self.check_ranges(ranges.iter().map(|(offset, buffer)| (*offset, buffer.len())))?;
+
+        #[cfg(feature = "std")]
+        if let Some(tracker) = &self.init_tracker {
+            for (offset, buffer) in ranges.iter() {
+                if !buffer.is_empty() {
+                    tracker.check_read(*offset as usize, buffer.len())?;
+                }
+            }
+        }
+
+        self.increment_access_count(offset, size);
+
+        for (offset, buffer) in ranges.iter_mut() {
+            if !buffer.is_empty() {
+                let safe_slice = self.data.get_slice(*offset as usize, buffer.len())?;
+                buffer.copy_from_slice(safe_slice.data()?);
+            }
+        }
+        Ok(())
+    }
+
+    /// Write several buffers into memory, each at its offset
+    ///
+    /// Every range is checked before any byte is written and the access is
+    /// recorded once for all of them. Buffers are written in order, so a later
+    /// buffer overwrites an earlier one where their ranges overlap.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a range extends beyond the memory; nothing is
+    /// written then.
+    pub fn write_vectored(&mut self, ranges: &[(u32, &[u8])]) -> Result<()> {
+        let (offset, size) =
+            self.check_ranges(ranges.iter().map(|(offset, buffer)| (*offset, buffer.len())))?;
+
+        self.increment_access_count(offset, size);
+
+        for (offset, buffer) in ranges {
+            if buffer.is_empty() {
+                continue;
+            }
+            let offset = *offset as usize;
+            self.data.write_data(offset, buffer)?;
+
+            #[cfg(feature = "std")]
+            if let Some(tracker) = &mut self.init_tracker {
+                tracker.mark_written(offset, buffer.len());
+            }
+        }
+
+        self.update_peak_memory();
+        Ok(())
+    }
+
+    /// Check that every non-empty `(offset, len)` range lies within the
+    /// memory, returning the offset of the first and the total length
+    fn check_ranges(&self, ranges: impl Iterator<Item = (u32, usize)>) -> Result<(usize, usize)> {
+        let memory_size = self.size_in_bytes();
+        let mut first = None;
+        let mut total = 0usize;
+
+        for (offset, len) in ranges {
+            if len == 0 {
+                continue;
+            }
+            let start = wasm_offset_to_usize(offset)?;
+            let end = start
+                .checked_add(len)
+                .ok_or_else(|| Error::memory_out_of_bounds("Memory access would overflow"))?;
+            if end > memory_size {
+                return Err(Error::memory_out_of_bounds("Vectored access out of bounds"));
+            }
+            first.get_or_insert(start);
+            total = total.saturating_add(len);
+        }
+        Ok((first.unwrap_or(0), total))
+    }
+
     /// Thread-safe write operation for shared memory access (works with
     /// Arc<Memory>)
     ///
@@ -1073,6 +1280,11 @@ pub fn get_byte(&self, offset: u32) -> Result<u8> {
         let offset_usize = wasm_offset_to_usize(offset)?;
         self.increment_access_count(offset_usize, 1);
 
+        #[cfg(feature = "std")]
+        if let Some(tracker) = &self.init_tracker {
+            tracker.check_read(offset_usize, 1)?;
+        }
+
         // Use SafeMemoryHandler to get a safe slice
         let slice = self.data.get_slice(offset_usize, 1)?;
         let data = slice.data()?;
@@ -1139,15 +1351,13 @@ fn verify_bounds(&self, offset: u32, len: u32) -> bool {
 
     /// Check alignment for memory accesses
     pub fn check_alignment(&self, addr: u32, access_size: u32, align: u32) -> Result<()> {
-        if addr % align != 0 {
+        if !decision!(Decision::MemoryAccessAligned, addr % align == 0) {
             return Err(Error::validation_error("Runtime operation error"));
         }
 
         let addr = wasm_offset_to_usize(addr)?;
         let access_size = wasm_offset_to_usize(access_size)?;
-        if addr + access_size > self.data.size() {
-            return Err(Error::validation_error("Memory access out of bounds"));
-        }
+        memory_arith::access_end(addr, access_size, self.data.size())?;
 
         Ok(())
     }
@@ -1272,7 +1482,7 @@ pub fn with_post_grow_hook<F>(&self, hook: F) -> Result<()>
     pub fn verify_integrity(&self) -> Result<()> {
         // Get the expected size
         let pages = self.current_pages.load(Ordering::Relaxed);
-        let expected_size = wasm_offset_to_usize(pages).unwrap_or(0) * PAGE_SIZE;
+        let expected_size = memory_arith::pages_to_bytes(pages)?;
 
         // Verify memory size is consistent
         if self.data.size() != expected_size {
@@ -1340,17 +1550,19 @@ pub fn copy_within_or_between(
         let mut temp_buf = vec_with_capacity::<u8>(size);
         temp_buf.extend_from_slice(src_data);
 
-        // Get destination memory data using provider-aware method
-        let data_size = self.data.size();
-        let dst_slice = self.data.get_slice(0, data_size)?;
-        let mut dst_data = dst_slice.data()?.to_vec();
-
         // Copy from temporary buffer to destination
-        dst_data[dst_addr..dst_addr + size].copy_from_slice(temp_buf.as_slice());
+        self.data.write_data(dst_addr, &temp_buf)?;
 
-        // Update destination memory
-        self.data.clear()?;
-        self.data.add_data(&dst_data)?;
+        // The copied bytes are as initialized as their source
+        #[cfg(feature = "std")]
+        if let Some(tracker) = &mut self.init_tracker {
+            match src_mem.init_tracker() {
+                Some(src_tracker) => {
+                    tracker.set_written_bits(dst_addr, &src_tracker.written_bits(src_addr, size))
+                },
+                None => tracker.mark_written(dst_addr, size),
+            }
+        }
 
         // Update peak memory usage
         self.update_peak_memory();
@@ -1484,7 +1696,7 @@ pub fn init(&mut self, dst: usize, data: &[u8], src: usize, size: usize) -> Resu
         // with acceptable performance for small operations
         if size <= 32 {
             // Create a safe copy of the source data for integrity
-            let src_data = SafeSlice::new(&data[src..src + size])?;
+            let src_data = SafeSlice::new(&data[src..src_end])?;
 
             // Verify the source data integrity
             src_data.verify_integrity()?;
@@ -1493,7 +1705,7 @@ pub fn init(&mut self, dst: usize, data: &[u8], src: usize, size: usize) -> Resu
             let verified_data = src_data.data()?;
 
             for (i, &byte) in verified_data.iter().enumerate().take(size) {
-                self.set_byte((dst + i) as u32, byte)?;
+                self.set_byte(memory_arith::add(dst, i)? as u32, byte)?;
             }
 
             // Update metrics to reflect the entire operation rather than just the last byte
@@ -1512,7 +1724,8 @@ pub fn init(&mut self, dst: usize, data: &[u8], src: usize, size: usize) -> Resu
             let chunk_size = remaining.min(MAX_CHUNK_SIZE);
 
             // Create a safe slice for the source chunk to verify its integrity
-            let src_slice = SafeSlice::new(&data[src_offset..src_offset + chunk_size])?;
+            let src_chunk_end = memory_arith::add(src_offset, chunk_size)?;
+            let src_slice = SafeSlice::new(&data[src_offset..src_chunk_end])?;
             src_slice.verify_integrity()?;
 
             // Get the source data after verification
@@ -1525,14 +1738,19 @@ pub fn init(&mut self, dst: usize, data: &[u8], src: usize, size: usize) -> Resu
             self.data.write_data(dst_offset, src_data)?;
 
             // Update for next chunk
-            src_offset += chunk_size;
-            dst_offset += chunk_size;
+            src_offset = src_chunk_end;
+            dst_offset = memory_arith::add(dst_offset, chunk_size)?;
             remaining -= chunk_size;
         }
 
         // Update peak memory usage
         self.update_peak_memory();
 
+        #[cfg(feature = "std")]
+        if let Some(tracker) = &mut self.init_tracker {
+            tracker.mark_written(dst, size);
+        }
+
         // Ensure all metrics reflect the entire init operation
         self.update_access_metrics(dst, size);
 
@@ -1988,21 +2206,7 @@ fn memory_stats(&self) -> MemoryStats {
 
     /// Update all access metrics in one operation
     fn update_access_metrics(&self, offset: usize, len: usize) {
-        #[cfg(feature = "std")]
-        {
-            self.metrics.max_access_size.fetch_max(len, Ordering::Relaxed);
-            self.metrics.last_access_offset.store(offset, Ordering::Relaxed);
-            self.metrics.last_access_length.store(len, Ordering::Relaxed);
-        }
-
-        #[cfg(not(feature = "std"))]
-        {
-            // Use write() method with WrtRwLock
-            let mut metrics = self.metrics.write();
-            metrics.max_access_size = metrics.max_access_size.max(len);
-            metrics.last_access_offset = offset;
-            metrics.last_access_length = len;
-        }
+        self.metrics.record_access(offset, len, false);
     }
 
     /// Get safety statistics for this memory instance
@@ -2081,7 +2285,7 @@ pub fn grow_memory(&mut self, pages: u32) -> Result<u32> {
             ));
         }
 
-        let new_byte_size = wasm_offset_to_usize(new_size_pages)? * PAGE_SIZE;
+        let new_byte_size = memory_arith::pages_to_bytes(new_size_pages)?;
         // Placeholder: Assumes SafeMemoryHandler has a method like `resize`
         // that takes &self and handles locking internally.
         self.data.resize(new_byte_size)?;
@@ -2101,18 +2305,13 @@ impl MemoryProvider for Memory {
     type Allocator = LargeMemoryProvider;
 
     fn borrow_slice(&self, offset: usize, len: usize) -> Result<SafeSlice<'_>> {
-        // Verify bounds
-        if offset + len > self.data.size() {
-            return Err(Error::memory_error("Memory access out of bounds"));
-        }
+        memory_arith::access_end(offset, len, self.data.size())?;
 
         self.data.get_slice(offset, len)
     }
 
     fn verify_access(&self, offset: usize, len: usize) -> Result<()> {
-        if offset + len > self.data.size() {
-            return Err(Error::memory_error("Memory access out of bounds"));
-        }
+        memory_arith::access_end(offset, len, self.data.size())?;
         Ok(())
     }
 
@@ -2143,7 +2342,6 @@ fn verification_level(&self) -> VerificationLevel {
     }
 
     fn memory_stats(&self) -> MemoryStats {
-        // Read the data size through RwLock
         let data_size = { self.data.size() };
 
         MemoryStats {
@@ -2159,9 +2357,8 @@ fn get_slice_mut(&mut self, offset: usize, len: usize) -> Result<SafeSliceMut<'_
     }
 
     fn copy_within(&mut self, src: usize, dest: usize, len: usize) -> Result<()> {
-        if src + len > self.data.size() || dest + len > self.data.size() {
-            return Err(Error::memory_error("Copy within bounds check failed"));
-        }
+        memory_arith::access_end(src, len, self.data.size())?;
+        memory_arith::access_end(dest, len, self.data.size())?;
         // Use the data's copy_within method if available, otherwise manual copy
         self.data.copy_within(src, dest, len)
     }
@@ -2343,11 +2540,21 @@ fn copy(&mut self, dest: u32, src: u32, size: u32) -> Result<()> {
 
         // Handle overlapping regions by using a temporary buffer
         // Read source data first
+        // The copied bytes are as initialized as their source, so they are
+        // not checked by the read
         #[cfg(feature = "std")]
         {
+            let tracker = self.init_tracker.take();
             let mut buffer = vec![0u8; size_usize];
-            self.read(src, &mut buffer)?;
-            self.write(dest, &buffer)?;
+            let copied = self.read(src, &mut buffer).and_then(|()| self.write(dest, &buffer));
+            self.init_tracker = tracker.map(|mut tracker| {
+                if copied.is_ok() {
+                    let bits = tracker.written_bits(src_usize, size_usize);
+                    tracker.set_written_bits(dest_usize, &bits);
+                }
+                tracker
+            });
+            copied?;
         }
 
         #[cfg(not(feature = "std"))]
@@ -2665,14 +2872,16 @@ mod tests {
 
     use super::*;
 
+    fn memory_type(min: u32, max: Option<u32>) -> CoreMemoryType {
+        CoreMemoryType {
+            limits: Limits::new(min, max),
+            shared: false,
+        }
+    }
+
     #[test]
     fn test_memory_creation() {
-        let mem_type = MemoryType {
-            limits: Limits {
-                min: 1,
-                max: Some(2),
-            },
-        };
+        let mem_type = memory_type(1, Some(2));
         let memory = Memory::new(mem_type).unwrap();
         assert_eq!(memory.size(), 1);
         assert_eq!(memory.size_in_bytes(), PAGE_SIZE);
@@ -2680,12 +2889,7 @@ fn test_memory_creation() {
 
     #[test]
     fn test_memory_grow() {
-        let mem_type = MemoryType {
-            limits: Limits {
-                min: 1,
-                max: Some(2),
-            },
-        };
+        let mem_type = memory_type(1, Some(2));
         let mut memory = Memory::new(mem_type).unwrap();
         let old_size = memory.grow(1).unwrap();
         assert_eq!(old_size, 1);
@@ -2695,12 +2899,7 @@ fn test_memory_grow() {
 
     #[test]
     fn test_memory_read_write() {
-        let mem_type = MemoryType {
-            limits: Limits {
-                min: 1,
-                max: Some(2),
-            },
-        };
+        let mem_type = memory_type(1, Some(2));
         let mut memory = Memory::new(mem_type).unwrap();
         let data = [1, 2, 3, 4];
         memory.write(0, &data).unwrap();
@@ -2711,12 +2910,7 @@ fn test_memory_read_write() {
 
     #[test]
     fn test_memory_get_set_byte() {
-        let mem_type = MemoryType {
-            limits: Limits {
-                min: 1,
-                max: Some(2),
-            },
-        };
+        let mem_type = memory_type(1, Some(2));
         let mut memory = Memory::new(mem_type).unwrap();
         memory.set_byte(0, 42).unwrap();
         assert_eq!(memory.get_byte(0).unwrap(), 42);
@@ -2724,12 +2918,7 @@ fn test_memory_get_set_byte() {
 
     #[test]
     fn test_memory_peak_usage() {
-        let mem_type = MemoryType {
-            limits: Limits {
-                min: 1,
-                max: Some(2),
-            },
-        };
+        let mem_type = memory_type(1, Some(2));
         let mut memory = Memory::new(mem_type).unwrap();
         assert_eq!(memory.peak_memory(), PAGE_SIZE);
         memory.grow(1).unwrap();
@@ -2738,12 +2927,7 @@ fn test_memory_peak_usage() {
 
     #[test]
     fn test_alignment_check() {
-        let mem_type = MemoryType {
-            limits: Limits {
-                min: 1,
-                max: Some(2),
-            },
-        };
+        let mem_type = memory_type(1, Some(2));
         let memory = Memory::new(mem_type).unwrap();
         assert!(memory.check_alignment(0, 4, 4).is_ok());
         assert!(memory.check_alignment(1, 4, 4).is_err());
@@ -2751,12 +2935,7 @@ fn test_alignment_check() {
 
     #[test]
     fn test_memory_access_tracking() {
-        let mem_type = MemoryType {
-            limits: Limits {
-                min: 1,
-                max: Some(2),
-            },
-        };
+        let mem_type = memory_type(1, Some(2));
         let mut memory = Memory::new(mem_type).unwrap();
 
         // Test single byte access
@@ -2782,12 +2961,7 @@ fn test_memory_access_tracking() {
 
     #[test]
     fn test_memory_copy_tracking() {
-        let mem_type = MemoryType {
-            limits: Limits {
-                min: 1,
-                max: Some(2),
-            },
-        };
+        let mem_type = memory_type(1, Some(2));
         let mut memory1 = Memory::new(mem_type.clone()).unwrap();
         let mut memory2 = Memory::new(mem_type).unwrap();
 
@@ -2810,12 +2984,7 @@ fn test_memory_copy_tracking() {
 
     #[test]
     fn test_memory_fill_tracking() {
-        let mem_type = MemoryType {
-            limits: Limits {
-                min: 1,
-                max: Some(2),
-            },
-        };
+        let mem_type = memory_type(1, Some(2));
         let mut memory = Memory::new(mem_type).unwrap();
 
         // Fill memory region
@@ -2829,12 +2998,7 @@ fn test_memory_fill_tracking() {
 
     #[test]
     fn test_memory_init_tracking() {
-        let mem_type = MemoryType {
-            limits: Limits {
-                min: 1,
-                max: Some(2),
-            },
-        };
+        let mem_type = memory_type(1, Some(2));
         let mut memory = Memory::new(mem_type).unwrap();
 
         // Initialize memory region
@@ -2852,14 +3016,7 @@ fn test_memory_safety_features() -> Result<()> {
         use wrt_foundation::verification::VerificationLevel;
 
         // Create a memory with a specific verification level
-        let mem_type = MemoryType {
-            limits: wrt_foundation::types::Limits {
-                min: 1,
-                max: Some(2),
-            },
-            shared: false,
-        };
-        let mut memory = Memory::new(mem_type)?;
+        let mut memory = Memory::new(memory_type(1, Some(2)))?;
         memory.set_verification_level(VerificationLevel::Full);
 
         // Write some data
@@ -2894,4 +3051,50 @@ fn test_memory_safety_features() -> Result<()> {
 
         Ok(())
     }
+
+    #[test]
+    fn test_import_compatibility_limits() {
+        let memory = Memory::new(memory_type(2, Some(4))).unwrap();
+
+        assert!(memory.check_import_compatibility(&memory_type(1, None)).is_ok());
+        assert!(memory.check_import_compatibility(&memory_type(2, Some(4))).is_ok());
+        assert!(memory.check_import_compatibility(&memory_type(1, Some(8))).is_ok());
+
+        // Current size below the required minimum
+        assert!(memory.check_import_compatibility(&memory_type(3, None)).is_err());
+        // Declared maximum above the required maximum
+        assert!(memory.check_import_compatibility(&memory_type(1, Some(3))).is_err());
+    }
+
+    #[test]
+    fn test_import_compatibility_unbounded_and_shared() {
+        let unbounded = Memory::new(memory_type(1, None)).unwrap();
+        assert!(unbounded.check_import_compatibility(&memory_type(1, None)).is_ok());
+        assert!(unbounded.check_import_compatibility(&memory_type(1, Some(10))).is_err());
+
+        let shared = CoreMemoryType {
+            limits: Limits::new(1, Some(1)),
+            shared: true,
+        };
+        assert!(unbounded.check_import_compatibility(&shared).is_err());
+    }
+
+    #[test]
+    fn test_vectored_access_checks_all_ranges_first() {
+        let mut memory = Memory::new(memory_type(1, Some(1))).unwrap();
+        let end = memory.size_in_bytes() as u32;
+
+        memory.write_vectored(&[(0, &[1, 2]), (100, &[]), (end - 1, &[3])]).unwrap();
+        assert_eq!(memory.access_count(), 1);
+
+        // The second range is out of bounds, so the first is not written
+        assert!(memory.write_vectored(&[(0, &[9]), (end, &[4])]).is_err());
+
+        let (mut head, mut tail) = ([0; 2], [0; 1]);
+        memory
+            .read_vectored(&mut [(0, &mut head[..]), (end - 1, &mut tail[..])])
+            .unwrap();
+        assert_eq!((head, tail), ([1, 2], [3]));
+        assert!(memory.read_vectored(&mut [(0, &mut head[..]), (end, &mut tail[..])]).is_err());
+    }
 }
@@ -0,0 +1,182 @@
+//! Size and offset arithmetic of the memory subsystem
+//!
+//! Offsets and sizes within linear memory are computed with these helpers
+//! rather than with the arithmetic operators, so how overflow is handled is
+//! decided in one place:
+//!
+//! - [`effective_address`] and [`access_end`] compute the addresses that are
+//!   bounds checked and always detect overflow. Kani proofs show that they
+//!   never return an address outside of the memory.
+//! - [`add`], [`mul`] and [`pages_to_bytes`] follow the build mode. With the
+//!   `checked-arithmetic` feature every operation is checked and an overflow
+//!   becomes an out-of-bounds memory trap, so a safety audit does not have to
+//!   argue for every call site that a wrapped value is caught later. Without it
+//!   they compile to the plain operators.
+
+use wrt_error::{
+    Error,
+    Result,
+};
+
+use crate::{
+    mcdc::{
+        decision,
+        Decision,
+    },
+    memory::PAGE_SIZE,
+};
+
+/// Trap raised for an address or size calculation that overflowed
+const OVERFLOW_TRAP: Error = Error::memory_out_of_bounds("Memory address calculation overflowed");
+
+/// Address of an access at `offset` from the dynamic address `base`
+///
+/// # Errors
+///
+/// Returns an out-of-bounds trap if the address does not fit in `usize`.
+#[inline]
+pub fn effective_address(base: u32, offset: u32) -> Result<usize> {
+    let address = u64::from(base) + u64::from(offset);
+    usize::try_from(address).map_err(|_| OVERFLOW_TRAP)
+}
+
+/// End of an access of `len` bytes at `address`, checked against a memory of
+/// `memory_size` bytes
+///
+/// # Errors
+///
+/// Returns an out-of-bounds trap if the access does not lie within the
+/// memory.
+#[inline]
+pub fn access_end(address: usize, len: usize, memory_size: usize) -> Result<usize> {
+    let end = address.checked_add(len);
+    let within = end.map(|end| end <= memory_size);
+    let in_bounds = decision!(
+        Decision::MemoryAccessInBounds,
+        [Some(end.is_some()), within],
+        within == Some(true)
+    );
+    match end {
+        Some(end) if in_bounds => Ok(end),
+        Some(_) => Err(Error::memory_out_of_bounds("Memory access out of bounds")),
+        None => Err(OVERFLOW_TRAP),
+    }
+}
+
+/// Sum of two sizes or offsets
+///
+/// # Errors
+///
+/// With `checked-arithmetic`, returns an out-of-bounds trap on overflow.
+#[inline]
+pub fn add(a: usize, b: usize) -> Result<usize> {
+    #[cfg(feature = "checked-arithmetic")]
+    {
+        a.checked_add(b).ok_or(OVERFLOW_TRAP)
+    }
+    #[cfg(not(feature = "checked-arithmetic"))]
+    {
+        Ok(a + b)
+    }
+}
+
+/// Product of two sizes
+///
+/// # Errors
+///
+/// With `checked-arithmetic`, returns an out-of-bounds trap on overflow.
+#[inline]
+pub fn mul(a: usize, b: usize) -> Result<usize> {
+    #[cfg(feature = "checked-arithmetic")]
+    {
+        a.checked_mul(b).ok_or(OVERFLOW_TRAP)
+    }
+    #[cfg(not(feature = "checked-arithmetic"))]
+    {
+        Ok(a * b)
+    }
+}
+
+/// Size in bytes of `pages` WebAssembly pages
+///
+/// # Errors
+///
+/// Returns an out-of-bounds trap if `pages` does not fit in `usize`, and
+/// with `checked-arithmetic` also if the size does not.
+#[inline]
+pub fn pages_to_bytes(pages: u32) -> Result<usize> {
+    mul(
+        usize::try_from(pages).map_err(|_| OVERFLOW_TRAP)?,
+        PAGE_SIZE,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_access_end_rejects_overflow_and_out_of_bounds() {
+        assert_eq!(access_end(8, 8, 16).unwrap(), 16);
+        assert!(access_end(9, 8, 16).is_err());
+        assert!(access_end(usize::MAX, 1, usize::MAX).is_err());
+        assert_eq!(
+            effective_address(u32::MAX, 1).ok(),
+            usize::try_from(1u64 << 32).ok()
+        );
+    }
+
+    #[cfg(feature = "checked-arithmetic")]
+    #[test]
+    fn test_overflow_traps() {
+        assert!(add(usize::MAX, 1).is_err());
+        assert!(mul(usize::MAX, 2).is_err());
+        assert_eq!(pages_to_bytes(2).unwrap(), 2 * PAGE_SIZE);
+    }
+}
+
+/// Kani proofs for the address calculation helpers
+#[cfg(kani)]
+mod kani_proofs {
+    use super::*;
+
+    /// The effective address is the mathematical sum of base and offset
+    #[kani::proof]
+    fn verify_effective_address_does_not_wrap() {
+        let base: u32 = kani::any();
+        let offset: u32 = kani::any();
+
+        if let Ok(address) = effective_address(base, offset) {
+            assert!(address as u64 == u64::from(base) + u64::from(offset));
+            assert!(address >= base as usize);
+        }
+    }
+
+    /// An accepted access lies entirely within the memory
+    #[kani::proof]
+    fn verify_access_end_within_memory() {
+        let address: usize = kani::any();
+        let len: usize = kani::any();
+        let memory_size: usize = kani::any();
+
+        match access_end(address, len, memory_size) {
+            Ok(end) => {
+                assert!(end <= memory_size);
+                assert!(address <= end);
+                assert!(end - address == len);
+            },
+            Err(_) => assert!(address.checked_add(len).map_or(true, |end| end > memory_size)),
+        }
+    }
+
+    /// In checked mode, an overflowing sum or product is a trap
+    #[cfg(feature = "checked-arithmetic")]
+    #[kani::proof]
+    fn verify_checked_arithmetic_traps_on_overflow() {
+        let a: usize = kani::any();
+        let b: usize = kani::any();
+
+        assert!(add(a, b).ok() == a.checked_add(b));
+        assert!(mul(a, b).ok() == a.checked_mul(b));
+    }
+}
@@ -0,0 +1,856 @@
+//! Typed access to guest memory for embedders
+//!
+//! Host functions exchange data with guests through linear memory. A
+//! [`MemoryView`] reads primitive values, byte ranges and NUL-terminated
+//! strings from a [`Memory`], and a [`MemoryViewMut`] also writes them. Every
+//! access is bounds checked, and direct access to the bytes goes through the
+//! integrity-checked slices of the memory's safe memory provider.
+//!
+//! Guest arrays of numbers are accessed through a [`TypedView`] or
+//! [`TypedViewMut`], which check the bounds and alignment of the whole array
+//! once and convert each element from or to little-endian byte order.
+//!
+//! Guest structs are copied with [`MemoryView::read_structs`] and
+//! [`MemoryViewMut::write_structs`]. A [`GuestStruct`] host type describes the
+//! struct's layout in linear memory with a [`StructLayout`] and converts it
+//! field by field, so a whole array of structs, such as a batch of telemetry
+//! frames, is checked and copied with a single access to the memory.
+
+use core::{
+    marker::PhantomData,
+    ops::Range,
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_foundation::{
+    SafeSlice,
+    SafeSliceMut,
+};
+
+use crate::memory::Memory;
+
+/// Primitive value stored in linear memory in little-endian byte order
+pub trait MemoryValue: Copy {
+    /// Size of the value in bytes
+    const SIZE: usize;
+
+    /// Decode the value from exactly [`Self::SIZE`] bytes
+    fn from_le_slice(bytes: &[u8]) -> Self;
+
+    /// Encode the value into exactly [`Self::SIZE`] bytes
+    fn write_le_slice(self, bytes: &mut [u8]);
+}
+
+macro_rules! impl_memory_value {
+    ($($ty:ty),*) => {
+        $(
+            impl MemoryValue for $ty {
+                const SIZE: usize = core::mem::size_of::<$ty>();
+
+                fn from_le_slice(bytes: &[u8]) -> Self {
+                    let mut buffer = [0; core::mem::size_of::<$ty>()];
+                    buffer.copy_from_slice(bytes);
+                    <$ty>::from_le_bytes(buffer)
+                }
+
+                fn write_le_slice(self, bytes: &mut [u8]) {
+                    bytes.copy_from_slice(&self.to_le_bytes());
+                }
+            }
+        )*
+    };
+}
+
+impl_memory_value!(u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
+
+/// Largest [`MemoryValue::SIZE`]
+const MAX_VALUE_SIZE: usize = 8;
+
+/// Read access to a linear memory
+#[derive(Debug, Clone, Copy)]
+pub struct MemoryView<'a> {
+    memory: &'a Memory,
+}
+
+impl<'a> MemoryView<'a> {
+    /// View `memory`
+    pub fn new(memory: &'a Memory) -> Self {
+        Self { memory }
+    }
+
+    /// Current size of the memory in bytes
+    pub fn size_in_bytes(&self) -> usize {
+        self.memory.size_in_bytes()
+    }
+
+    /// Read a value stored at `offset`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value extends beyond the memory.
+    pub fn read<T: MemoryValue>(&self, offset: u32) -> Result<T> {
+        let mut buffer = [0; MAX_VALUE_SIZE];
+        self.memory.read(offset, &mut buffer[..T::SIZE])?;
+        Ok(T::from_le_slice(&buffer[..T::SIZE]))
+    }
+
+    /// Copy the bytes at `offset` into `buffer`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the range extends beyond the memory.
+    pub fn read_bytes(&self, offset: u32, buffer: &mut [u8]) -> Result<()> {
+        self.memory.read(offset, buffer)
+    }
+
+    /// Borrow `len` bytes at `offset`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the range extends beyond the memory or fails its
+    /// integrity check.
+    pub fn bytes(&self, offset: u32, len: usize) -> Result<SafeSlice<'a>> {
+        let start = self.checked_range(offset, len)?;
+        self.memory.data.get_slice(start, len)
+    }
+
+    /// View the array of `len` values of type `T` at `offset`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `offset` is not aligned to the size of `T`, if
+    /// the array extends beyond the memory or if it fails its integrity
+    /// check.
+    pub fn typed<T: MemoryValue>(&self, offset: u32, len: usize) -> Result<TypedView<'a, T>> {
+        let size = array_size::<T>(offset, len)?;
+        Ok(TypedView {
+            bytes:   self.bytes(offset, size)?.data()?,
+            element: PhantomData,
+        })
+    }
+
+    /// Borrow the NUL-terminated UTF-8 string at `offset`
+    ///
+    /// At most `max_len` bytes before the terminator are considered.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no terminator is found within `max_len` bytes or
+    /// before the end of the memory, or if the string is not valid UTF-8.
+    pub fn read_c_str(&self, offset: u32, max_len: usize) -> Result<&'a str> {
+        let start = offset as usize;
+        let available = self
+            .size_in_bytes()
+            .checked_sub(start)
+            .ok_or_else(|| Error::memory_out_of_bounds("String starts beyond memory"))?;
+        let len = available.min(max_len.saturating_add(1));
+
+        let bytes = self.memory.data.get_slice(start, len)?.data()?;
+        let end = bytes
+            .iter()
+            .position(|byte| *byte == 0)
+            .ok_or_else(|| Error::memory_out_of_bounds("String is not terminated in bounds"))?;
+        core::str::from_utf8(&bytes[..end])
+            .map_err(|_| Error::validation_error("String is not valid UTF-8"))
+    }
+
+    /// Read the struct at `offset`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `offset` is not aligned to the struct, if the
+    /// struct extends beyond the memory or fails its integrity check, or if
+    /// it does not decode.
+    pub fn read_struct<T: GuestStruct>(&self, offset: u32) -> Result<T> {
+        let bytes = self.struct_bytes::<T>(offset, 1)?;
+        T::read_fields(&StructFields {
+            fields: T::LAYOUT.fields,
+            bytes,
+        })
+    }
+
+    /// Read the array of structs at `offset` into `out`, one struct per
+    /// element
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `offset` is not aligned to the struct, if the
+    /// array extends beyond the memory or fails its integrity check, or if a
+    /// struct does not decode.
+    pub fn read_structs<T: GuestStruct>(&self, offset: u32, out: &mut [T]) -> Result<()> {
+        let bytes = self.struct_bytes::<T>(offset, out.len())?;
+        for (value, bytes) in out.iter_mut().zip(bytes.chunks_exact(T::LAYOUT.size)) {
+            *value = T::read_fields(&StructFields {
+                fields: T::LAYOUT.fields,
+                bytes,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Borrow the whole memory
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the memory fails its integrity check.
+    pub fn data(&self) -> Result<SafeSlice<'a>> {
+        self.memory.as_safe_slice()
+    }
+
+    /// Bytes of the array of `count` structs of type `T` at `offset`
+    fn struct_bytes<T: GuestStruct>(&self, offset: u32, count: usize) -> Result<&'a [u8]> {
+        let size = struct_array_size(&T::LAYOUT, offset, count)?;
+        self.bytes(offset, size)?.data()
+    }
+
+    /// Start of `len` bytes at `offset`, if they lie within the memory
+    fn checked_range(&self, offset: u32, len: usize) -> Result<usize> {
+        let start = offset as usize;
+        start
+            .checked_add(len)
+            .filter(|end| *end <= self.size_in_bytes())
+            .map(|_| start)
+            .ok_or_else(|| Error::memory_out_of_bounds("Memory access out of bounds"))
+    }
+}
+
+/// Read and write access to a linear memory
+#[derive(Debug)]
+pub struct MemoryViewMut<'a> {
+    memory: &'a mut Memory,
+}
+
+impl<'a> MemoryViewMut<'a> {
+    /// View `memory` for writing
+    pub fn new(memory: &'a mut Memory) -> Self {
+        Self { memory }
+    }
+
+    /// Read access to the same memory
+    pub fn as_view(&self) -> MemoryView<'_> {
+        MemoryView::new(self.memory)
+    }
+
+    /// Current size of the memory in bytes
+    pub fn size_in_bytes(&self) -> usize {
+        self.memory.size_in_bytes()
+    }
+
+    /// Read a value stored at `offset`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value extends beyond the memory.
+    pub fn read<T: MemoryValue>(&self, offset: u32) -> Result<T> {
+        self.as_view().read(offset)
+    }
+
+    /// Copy the bytes at `offset` into `buffer`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the range extends beyond the memory.
+    pub fn read_bytes(&self, offset: u32, buffer: &mut [u8]) -> Result<()> {
+        self.memory.read(offset, buffer)
+    }
+
+    /// Store `value` at `offset`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value extends beyond the memory.
+    pub fn write<T: MemoryValue>(&mut self, offset: u32, value: T) -> Result<()> {
+        let mut buffer = [0; MAX_VALUE_SIZE];
+        value.write_le_slice(&mut buffer[..T::SIZE]);
+        self.memory.write(offset, &buffer[..T::SIZE])
+    }
+
+    /// Copy `bytes` to `offset`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the range extends beyond the memory.
+    pub fn write_bytes(&mut self, offset: u32, bytes: &[u8]) -> Result<()> {
+        self.memory.write(offset, bytes)
+    }
+
+    /// Store `text` at `offset` followed by a NUL terminator
+    ///
+    /// Nothing is written if the string and its terminator do not fit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `text` contains a NUL byte or does not fit.
+    pub fn write_c_str(&mut self, offset: u32, text: &str) -> Result<()> {
+        if text.as_bytes().contains(&0) {
+            return Err(Error::validation_error("String contains a NUL byte"));
+        }
+        let terminator = self.as_view().checked_range(offset, text.len() + 1)? + text.len();
+
+        self.memory.write(offset, text.as_bytes())?;
+        self.memory.write(terminator as u32, &[0])
+    }
+
+    /// View the array of `len` values of type `T` at `offset` for writing
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `offset` is not aligned to the size of `T`, if
+    /// the array extends beyond the memory or if it fails its integrity
+    /// check.
+    pub fn typed_mut<T: MemoryValue>(
+        &mut self,
+        offset: u32,
+        len: usize,
+    ) -> Result<TypedViewMut<'_, T>> {
+        let size = array_size::<T>(offset, len)?;
+        let start = self.as_view().checked_range(offset, size)?;
+        Ok(TypedViewMut {
+            bytes:   self.memory.data.get_slice_mut(start, size)?,
+            element: PhantomData,
+        })
+    }
+
+    /// Store `value` as the struct at `offset`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `offset` is not aligned to the struct, if the
+    /// struct extends beyond the memory or fails its integrity check, or if
+    /// it does not encode.
+    pub fn write_struct<T: GuestStruct>(&mut self, offset: u32, value: &T) -> Result<()> {
+        self.write_structs(offset, core::slice::from_ref(value))
+    }
+
+    /// Store `values` as the array of structs at `offset`
+    ///
+    /// Nothing is written if the array does not fit; the structs before one
+    /// that fails to encode are written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `offset` is not aligned to the struct, if the
+    /// array extends beyond the memory or fails its integrity check, or if a
+    /// struct does not encode.
+    pub fn write_structs<T: GuestStruct>(&mut self, offset: u32, values: &[T]) -> Result<()> {
+        let size = struct_array_size(&T::LAYOUT, offset, values.len())?;
+        let start = self.as_view().checked_range(offset, size)?;
+        let mut slice = self.memory.data.get_slice_mut(start, size)?;
+
+        let result = slice.data_mut().and_then(|bytes| {
+            values.iter().zip(bytes.chunks_exact_mut(T::LAYOUT.size)).try_for_each(
+                |(value, bytes)| {
+                    value.write_fields(&mut StructFieldsMut {
+                        fields: T::LAYOUT.fields,
+                        bytes,
+                    })
+                },
+            )
+        });
+        slice.update_checksum();
+        result
+    }
+
+    /// Borrow the whole memory for writing
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the memory fails its integrity check.
+    pub fn data_mut(&mut self) -> Result<SafeSliceMut<'_>> {
+        let size = self.memory.size_in_bytes();
+        self.memory.data.get_slice_mut(0, size)
+    }
+}
+
+/// Size in bytes of an array of `len` values of type `T` at `offset`
+fn array_size<T: MemoryValue>(offset: u32, len: usize) -> Result<usize> {
+    if offset as usize % T::SIZE != 0 {
+        return Err(Error::runtime_unaligned_memory_access(
+            "Typed view is not aligned to its element size",
+        ));
+    }
+    len.checked_mul(T::SIZE)
+        .ok_or_else(|| Error::memory_out_of_bounds("Memory access out of bounds"))
+}
+
+/// Field of a [`StructLayout`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldLayout {
+    offset: usize,
+    size:   usize,
+}
+
+impl FieldLayout {
+    /// Field holding a value of type `T`, `offset` bytes into its struct
+    pub const fn of<T: MemoryValue>(offset: usize) -> Self {
+        Self {
+            offset,
+            size: T::SIZE,
+        }
+    }
+
+    /// Offset of the field in its struct
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Size of the field in bytes
+    pub const fn size(&self) -> usize {
+        self.size
+    }
+}
+
+/// Layout of a struct in linear memory: its size, its alignment and its
+/// fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StructLayout {
+    size:   usize,
+    align:  usize,
+    fields: &'static [FieldLayout],
+}
+
+impl StructLayout {
+    /// Layout of a struct of `size` bytes, trailing padding included, aligned
+    /// to `align` bytes
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two, if `size` is zero or not a
+    /// multiple of `align`, or if a field extends beyond the struct. Used as
+    /// [`GuestStruct::LAYOUT`], the layout is checked at compile time.
+    pub const fn new(size: usize, align: usize, fields: &'static [FieldLayout]) -> Self {
+        assert!(
+            align.is_power_of_two(),
+            "Struct alignment is not a power of two"
+        );
+        assert!(
+            size != 0 && size % align == 0,
+            "Struct size is not a multiple of its alignment"
+        );
+        let mut index = 0;
+        while index < fields.len() {
+            let field = fields[index];
+            assert!(
+                field.offset <= size && field.size <= size - field.offset,
+                "Field extends beyond its struct"
+            );
+            index += 1;
+        }
+        Self {
+            size,
+            align,
+            fields,
+        }
+    }
+
+    /// Size of the struct in bytes, which is the distance between the
+    /// elements of an array of structs
+    pub const fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Alignment of the struct in bytes
+    pub const fn align(&self) -> usize {
+        self.align
+    }
+
+    /// Fields of the struct, by index
+    pub const fn fields(&self) -> &'static [FieldLayout] {
+        self.fields
+    }
+}
+
+/// Host type copied to and from a struct in linear memory
+pub trait GuestStruct: Sized {
+    /// Layout of the struct in linear memory
+    const LAYOUT: StructLayout;
+
+    /// Decode the struct from its fields
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a field is read with a type of another size than
+    /// its layout.
+    fn read_fields(fields: &StructFields<'_>) -> Result<Self>;
+
+    /// Encode the struct into its fields
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a field is written with a type of another size
+    /// than its layout.
+    fn write_fields(&self, fields: &mut StructFieldsMut<'_>) -> Result<()>;
+}
+
+/// Fields of a struct in linear memory, read by their index in its
+/// [`StructLayout`]
+#[derive(Debug)]
+pub struct StructFields<'a> {
+    fields: &'static [FieldLayout],
+    bytes:  &'a [u8],
+}
+
+impl StructFields<'_> {
+    /// Value of the field at `index`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no such field or it is not of the size of
+    /// `T`.
+    pub fn get<T: MemoryValue>(&self, index: usize) -> Result<T> {
+        let range = field_range::<T>(self.fields, index)?;
+        Ok(T::from_le_slice(&self.bytes[range]))
+    }
+}
+
+/// Fields of a struct in linear memory, written by their index in its
+/// [`StructLayout`]
+///
+/// Padding and fields left unwritten keep the bytes the memory held.
+#[derive(Debug)]
+pub struct StructFieldsMut<'a> {
+    fields: &'static [FieldLayout],
+    bytes:  &'a mut [u8],
+}
+
+impl StructFieldsMut<'_> {
+    /// Store `value` in the field at `index`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no such field or it is not of the size of
+    /// `T`.
+    pub fn set<T: MemoryValue>(&mut self, index: usize, value: T) -> Result<()> {
+        let range = field_range::<T>(self.fields, index)?;
+        value.write_le_slice(&mut self.bytes[range]);
+        Ok(())
+    }
+}
+
+/// Bytes of the field at `index` of a struct, if it holds a `T`
+fn field_range<T: MemoryValue>(fields: &[FieldLayout], index: usize) -> Result<Range<usize>> {
+    let field = fields
+        .get(index)
+        .ok_or_else(|| Error::validation_error("Struct has no field at this index"))?;
+    if field.size != T::SIZE {
+        return Err(Error::runtime_type_mismatch(
+            "Field type does not match its layout",
+        ));
+    }
+    Ok(field.offset..field.offset + field.size)
+}
+
+/// Size in bytes of an array of `count` structs with `layout` at `offset`
+fn struct_array_size(layout: &StructLayout, offset: u32, count: usize) -> Result<usize> {
+    if offset as usize % layout.align != 0 {
+        return Err(Error::runtime_unaligned_memory_access(
+            "Struct is not aligned to its layout",
+        ));
+    }
+    count
+        .checked_mul(layout.size)
+        .ok_or_else(|| Error::memory_out_of_bounds("Memory access out of bounds"))
+}
+
+/// Array of values in linear memory
+#[derive(Debug, Clone, Copy)]
+pub struct TypedView<'a, T> {
+    bytes:   &'a [u8],
+    element: PhantomData<T>,
+}
+
+impl<'a, T: MemoryValue> TypedView<'a, T> {
+    /// Number of values
+    pub fn len(&self) -> usize {
+        self.bytes.len() / T::SIZE
+    }
+
+    /// Whether the array is empty
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Value at `index`, if it lies within the array
+    pub fn get(&self, index: usize) -> Option<T> {
+        let start = index.checked_mul(T::SIZE)?;
+        self.bytes.get(start..start + T::SIZE).map(T::from_le_slice)
+    }
+
+    /// Iterate over the values
+    pub fn iter(&self) -> impl Iterator<Item = T> + 'a
+    where
+        T: 'a,
+    {
+        self.bytes.chunks_exact(T::SIZE).map(T::from_le_slice)
+    }
+
+    /// Copy the values into `out`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `out` does not have the length of the array.
+    pub fn copy_to_slice(&self, out: &mut [T]) -> Result<()> {
+        if out.len() != self.len() {
+            return Err(Error::validation_error(
+                "Slice length does not match typed view",
+            ));
+        }
+        for (value, bytes) in out.iter_mut().zip(self.bytes.chunks_exact(T::SIZE)) {
+            *value = T::from_le_slice(bytes);
+        }
+        Ok(())
+    }
+}
+
+/// Writable array of values in linear memory
+#[derive(Debug)]
+pub struct TypedViewMut<'a, T> {
+    bytes:   SafeSliceMut<'a>,
+    element: PhantomData<T>,
+}
+
+impl<'a, T: MemoryValue> TypedViewMut<'a, T> {
+    /// Number of values
+    pub fn len(&self) -> usize {
+        self.bytes.len() / T::SIZE
+    }
+
+    /// Whether the array is empty
+    pub fn is_empty(&self) -> bool {
+        self.bytes.len() == 0
+    }
+
+    /// Read access to the same array
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the array fails its integrity check.
+    pub fn as_view(&self) -> Result<TypedView<'_, T>> {
+        Ok(TypedView {
+            bytes:   self.bytes.data()?,
+            element: PhantomData,
+        })
+    }
+
+    /// Value at `index`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `index` lies beyond the array or the array fails
+    /// its integrity check.
+    pub fn get(&self, index: usize) -> Result<T> {
+        self.as_view()?
+            .get(index)
+            .ok_or_else(|| Error::memory_out_of_bounds("Typed view index out of bounds"))
+    }
+
+    /// Store `value` at `index`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `index` lies beyond the array or the array fails
+    /// its integrity check.
+    pub fn set(&mut self, index: usize, value: T) -> Result<()> {
+        let start = index
+            .checked_mul(T::SIZE)
+            .filter(|start| *start < self.bytes.len())
+            .ok_or_else(|| Error::memory_out_of_bounds("Typed view index out of bounds"))?;
+        value.write_le_slice(&mut self.bytes.data_mut()?[start..start + T::SIZE]);
+        self.bytes.update_checksum();
+        Ok(())
+    }
+
+    /// Copy `values` into the array
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `values` does not have the length of the array or
+    /// the array fails its integrity check.
+    pub fn copy_from_slice(&mut self, values: &[T]) -> Result<()> {
+        if values.len() != self.len() {
+            return Err(Error::validation_error(
+                "Slice length does not match typed view",
+            ));
+        }
+        let bytes = self.bytes.data_mut()?;
+        for (value, bytes) in values.iter().zip(bytes.chunks_exact_mut(T::SIZE)) {
+            value.write_le_slice(bytes);
+        }
+        self.bytes.update_checksum();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wrt_foundation::types::Limits;
+
+    use super::*;
+    use crate::prelude::CoreMemoryType;
+
+    fn memory() -> Memory {
+        Memory::new(CoreMemoryType {
+            limits: Limits::new(1, Some(1)),
+            shared: false,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_typed_access() {
+        let mut memory = memory();
+        let mut view = MemoryViewMut::new(&mut memory);
+
+        view.write(0, 0x1234_5678u32).unwrap();
+        view.write(8, -1.5f64).unwrap();
+        assert_eq!(view.read::<u32>(0).unwrap(), 0x1234_5678);
+        assert_eq!(view.read::<u8>(0).unwrap(), 0x78);
+        assert_eq!(view.read::<f64>(8).unwrap(), -1.5);
+
+        let end = view.size_in_bytes() as u32;
+        assert!(view.write(end - 2, 0u32).is_err());
+        assert!(view.read::<u16>(end - 2).is_ok());
+
+        view.write_bytes(16, &[1, 2, 3]).unwrap();
+        let mut buffer = [0; 3];
+        view.read_bytes(16, &mut buffer).unwrap();
+        assert_eq!(buffer, [1, 2, 3]);
+        assert_eq!(
+            view.as_view().bytes(16, 3).unwrap().data().unwrap(),
+            &[1, 2, 3]
+        );
+        assert!(view.as_view().bytes(end, 1).is_err());
+    }
+
+    #[test]
+    fn test_c_strings() {
+        let mut memory = memory();
+        let mut view = MemoryViewMut::new(&mut memory);
+
+        view.write_c_str(32, "héllo").unwrap();
+        assert_eq!(view.as_view().read_c_str(32, 64).unwrap(), "héllo");
+        // The terminator lies beyond the length limit
+        assert!(view.as_view().read_c_str(32, 3).is_err());
+        assert!(view.write_c_str(0, "a\0b").is_err());
+
+        let end = view.size_in_bytes() as u32;
+        // No room for the terminator, so nothing is written
+        assert!(view.write_c_str(end - 2, "ab").is_err());
+        assert_eq!(view.read::<u16>(end - 2).unwrap(), 0);
+
+        view.write_bytes(end - 2, b"ab").unwrap();
+        assert!(view.as_view().read_c_str(end - 2, 64).is_err());
+    }
+
+    #[test]
+    fn test_typed_views() {
+        let mut memory = memory();
+        let mut view = MemoryViewMut::new(&mut memory);
+
+        view.write_bytes(64, &[1, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0x80, 0x3F]).unwrap();
+        let words = view.as_view().typed::<u32>(64, 2).unwrap();
+        assert_eq!(words.len(), 2);
+        assert_eq!(words.iter().collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(words.get(2), None);
+        assert_eq!(
+            view.as_view().typed::<f32>(72, 1).unwrap().get(0),
+            Some(1.0)
+        );
+
+        let mut halves = view.typed_mut::<u16>(64, 4).unwrap();
+        halves.set(1, 0xBEEF).unwrap();
+        halves.copy_from_slice(&[7, 0xBEEF, 9, 10]).unwrap();
+        assert!(halves.set(4, 0).is_err());
+        assert!(halves.copy_from_slice(&[1]).is_err());
+        assert_eq!(halves.get(2).unwrap(), 9);
+
+        let mut out = [0u16; 4];
+        view.as_view().typed::<u16>(64, 4).unwrap().copy_to_slice(&mut out).unwrap();
+        assert_eq!(out, [7, 0xBEEF, 9, 10]);
+        assert_eq!(view.read::<u32>(64).unwrap(), 0xBEEF_0007);
+
+        let unaligned = view.as_view().typed::<u32>(66, 1).unwrap_err();
+        assert_eq!(unaligned.code, wrt_error::codes::UNALIGNED_MEMORY_ACCESS);
+        let end = view.size_in_bytes() as u32;
+        assert!(view.as_view().typed::<u64>(end - 8, 2).is_err());
+        assert!(view.typed_mut::<u64>(end - 8, 1).is_ok());
+        assert!(view.as_view().typed::<u8>(0, usize::MAX).is_err());
+    }
+
+    #[derive(Debug, Default, PartialEq)]
+    struct Frame {
+        sequence: u32,
+        level:    u8,
+        reading:  f64,
+    }
+
+    impl GuestStruct for Frame {
+        const LAYOUT: StructLayout = StructLayout::new(
+            16,
+            8,
+            &[
+                FieldLayout::of::<u32>(0),
+                FieldLayout::of::<u8>(4),
+                FieldLayout::of::<f64>(8),
+            ],
+        );
+
+        fn read_fields(fields: &StructFields<'_>) -> Result<Self> {
+            Ok(Self {
+                sequence: fields.get(0)?,
+                level:    fields.get(1)?,
+                reading:  fields.get(2)?,
+            })
+        }
+
+        fn write_fields(&self, fields: &mut StructFieldsMut<'_>) -> Result<()> {
+            fields.set(0, self.sequence)?;
+            fields.set(1, self.level)?;
+            fields.set(2, self.reading)
+        }
+    }
+
+    #[test]
+    fn test_struct_copies() {
+        let mut memory = memory();
+        let mut view = MemoryViewMut::new(&mut memory);
+
+        let frames = [
+            Frame {
+                sequence: 1,
+                level:    2,
+                reading:  0.5,
+            },
+            Frame {
+                sequence: 0xDEAD_BEEF,
+                level:    255,
+                reading:  -4.25,
+            },
+        ];
+        view.write_structs(128, &frames).unwrap();
+        assert_eq!(view.read::<u32>(144).unwrap(), 0xDEAD_BEEF);
+        assert_eq!(view.read::<f64>(152).unwrap(), -4.25);
+
+        let mut out = [Frame::default(), Frame::default()];
+        view.as_view().read_structs(128, &mut out).unwrap();
+        assert_eq!(out, frames);
+        view.write_struct(128, &frames[1]).unwrap();
+        assert_eq!(view.as_view().read_struct::<Frame>(128).unwrap(), frames[1]);
+
+        let unaligned = view.write_struct(132, &frames[0]).unwrap_err();
+        assert_eq!(unaligned.code, wrt_error::codes::UNALIGNED_MEMORY_ACCESS);
+        let end = view.size_in_bytes() as u32;
+        assert!(view.write_structs(end - 16, &frames).is_err());
+        assert!(view.as_view().read_struct::<Frame>(end - 16).is_ok());
+
+        let mismatch = StructFields {
+            fields: Frame::LAYOUT.fields(),
+            bytes:  &[0; 16],
+        };
+        assert!(mismatch.get::<u64>(0).is_err());
+        assert!(mismatch.get::<u8>(3).is_err());
+    }
+}
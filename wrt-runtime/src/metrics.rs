@@ -0,0 +1,338 @@
+//! Runtime metrics in the OpenMetrics text format
+//!
+//! A [`MetricsSnapshot`] collects counters and gauges describing the health
+//! of the runtime: the engine's own counts, see
+//! [`CapabilityAwareEngine::metrics`](crate::engine::CapabilityAwareEngine::metrics),
+//! the [`ExecutionStats`] an embedder gathers with a memory observer, and the
+//! per-function statistics of the interceptor
+//! [`StatisticsStrategy`](wrt_intercept::strategies::StatisticsStrategy).
+//!
+//! Snapshots render in the OpenMetrics text format, which Prometheus and
+//! other scrapers accept, and are handed to a [`MetricsSink`] to be pushed or
+//! served. Metric names are prefixed with `wrt_`; counters get the `_total`
+//! suffix on their samples as the format requires.
+
+use core::fmt;
+use std::io;
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_intercept::strategies::FunctionStats;
+
+use crate::{
+    execution::ExecutionStats,
+    prelude::*,
+};
+
+/// Content type of the text rendered by [`MetricsSnapshot::write_openmetrics`]
+pub const CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// Kind of a metric
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+    /// A value that only increases
+    Counter,
+    /// A value that goes up and down
+    Gauge,
+}
+
+/// A value of a metric, distinguished from the other values of the metric by
+/// its labels
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricSample {
+    /// Label names and values
+    pub labels: Vec<(String, String)>,
+    /// Value of the sample
+    pub value:  f64,
+}
+
+/// A metric with its samples
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metric {
+    /// Name of the metric, without the `_total` suffix of counters
+    pub name:    String,
+    /// Description of the metric
+    pub help:    String,
+    /// Kind of the metric
+    pub kind:    MetricKind,
+    /// Samples of the metric
+    pub samples: Vec<MetricSample>,
+}
+
+/// Metrics taken at one point in time
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetricsSnapshot {
+    metrics: Vec<Metric>,
+}
+
+impl MetricsSnapshot {
+    /// A snapshot without metrics
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The metrics of the snapshot, in the order they were added
+    pub fn metrics(&self) -> &[Metric] {
+        &self.metrics
+    }
+
+    /// The metric named `name`, if the snapshot has it
+    pub fn get(&self, name: &str) -> Option<&Metric> {
+        self.metrics.iter().find(|metric| metric.name == name)
+    }
+
+    /// Add a sample of the metric `name`, adding the metric if the snapshot
+    /// does not have it yet
+    pub fn add(
+        &mut self,
+        name: &str,
+        help: &str,
+        kind: MetricKind,
+        labels: &[(&str, &str)],
+        value: f64,
+    ) {
+        let index = match self.metrics.iter().position(|metric| metric.name == name) {
+            Some(index) => index,
+            None => {
+                self.metrics.push(Metric {
+                    name: name.into(),
+                    help: help.into(),
+                    kind,
+                    samples: Vec::new(),
+                });
+                self.metrics.len() - 1
+            },
+        };
+        self.metrics[index].samples.push(MetricSample {
+            labels: labels.iter().map(|(name, value)| ((*name).into(), (*value).into())).collect(),
+            value,
+        });
+    }
+
+    /// Add an unlabelled counter
+    pub fn counter(&mut self, name: &str, help: &str, value: u64) {
+        self.add(name, help, MetricKind::Counter, &[], value as f64);
+    }
+
+    /// Add an unlabelled gauge
+    pub fn gauge(&mut self, name: &str, help: &str, value: u64) {
+        self.add(name, help, MetricKind::Gauge, &[], value as f64);
+    }
+
+    /// Add the metrics of `stats`
+    pub fn add_execution_stats(&mut self, stats: &ExecutionStats) {
+        self.counter(
+            "wrt_instructions_executed",
+            "Instructions executed",
+            stats.instructions_executed,
+        );
+        self.counter("wrt_function_calls", "Function calls", stats.function_calls);
+        self.counter("wrt_memory_reads", "Memory reads", stats.memory_reads);
+        self.counter("wrt_memory_writes", "Memory writes", stats.memory_writes);
+        self.counter(
+            "wrt_memory_grows",
+            "Memory grow operations",
+            stats.memory_grows,
+        );
+        self.counter(
+            "wrt_memory_pages_grown",
+            "Memory pages added by growth",
+            stats.pages_grown,
+        );
+        self.counter(
+            "wrt_table_grows",
+            "Table grow operations",
+            stats.table_grows,
+        );
+        self.counter("wrt_gas_used", "Gas used", stats.gas_used);
+        self.gauge(
+            "wrt_stack_depth_max",
+            "Largest stack depth reached",
+            stats.max_stack_depth as u64,
+        );
+    }
+
+    /// Add the interceptor statistics of each function in `stats`, labelled
+    /// with the function name
+    pub fn add_interceptor_stats(&mut self, stats: &HashMap<String, FunctionStats>) {
+        let mut functions: Vec<_> = stats.iter().collect();
+        functions.sort_by(|a, b| a.0.cmp(b.0));
+        for (function, stats) in functions {
+            let labels = [("function", function.as_str())];
+            self.add(
+                "wrt_intercepted_calls",
+                "Calls seen by the interceptor",
+                MetricKind::Counter,
+                &labels,
+                stats.call_count as f64,
+            );
+            self.add(
+                "wrt_intercepted_call_errors",
+                "Intercepted calls that failed",
+                MetricKind::Counter,
+                &labels,
+                stats.error_count as f64,
+            );
+            self.add(
+                "wrt_intercepted_call_seconds",
+                "Time spent in intercepted calls",
+                MetricKind::Counter,
+                &labels,
+                stats.total_time_ms / 1000.0,
+            );
+        }
+    }
+
+    /// Render the snapshot in the OpenMetrics text format
+    pub fn write_openmetrics(&self, out: &mut impl fmt::Write) -> fmt::Result {
+        for metric in &self.metrics {
+            let (kind, suffix) = match metric.kind {
+                MetricKind::Counter => ("counter", "_total"),
+                MetricKind::Gauge => ("gauge", ""),
+            };
+            writeln!(out, "# TYPE {} {kind}", metric.name)?;
+            writeln!(out, "# HELP {} {}", metric.name, escape(&metric.help))?;
+            for sample in &metric.samples {
+                write!(out, "{}{suffix}", metric.name)?;
+                if !sample.labels.is_empty() {
+                    let labels: Vec<String> = sample
+                        .labels
+                        .iter()
+                        .map(|(name, value)| format!("{name}=\"{}\"", escape(value)))
+                        .collect();
+                    write!(out, "{{{}}}", labels.join(","))?;
+                }
+                writeln!(out, " {}", sample.value)?;
+            }
+        }
+        writeln!(out, "# EOF")
+    }
+}
+
+impl fmt::Display for MetricsSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_openmetrics(f)
+    }
+}
+
+/// Escape backslashes, line feeds and double quotes
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '"' => escaped.push_str("\\\""),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Destination of metrics snapshots, such as a scrape endpoint or a push
+/// gateway client
+///
+/// Any `FnMut(&MetricsSnapshot) -> Result<()>` closure can be used directly.
+pub trait MetricsSink {
+    /// Export `snapshot`
+    fn export(&mut self, snapshot: &MetricsSnapshot) -> Result<()>;
+}
+
+impl<F> MetricsSink for F
+where
+    F: FnMut(&MetricsSnapshot) -> Result<()>,
+{
+    fn export(&mut self, snapshot: &MetricsSnapshot) -> Result<()> {
+        self(snapshot)
+    }
+}
+
+/// Sink writing snapshots in the OpenMetrics text format to a writer
+#[derive(Debug)]
+pub struct TextSink<W: io::Write> {
+    writer: W,
+}
+
+impl<W: io::Write> TextSink<W> {
+    /// Sink writing to `writer`
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// The writer of the sink
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: io::Write> MetricsSink for TextSink<W> {
+    fn export(&mut self, snapshot: &MetricsSnapshot) -> Result<()> {
+        self.writer
+            .write_all(snapshot.to_string().as_bytes())
+            .and_then(|()| self.writer.flush())
+            .map_err(|_| Error::system_io_error("Failed to write metrics"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_openmetrics_text() {
+        let mut snapshot = MetricsSnapshot::new();
+        snapshot.counter("wrt_instantiations", "Instances created", 3);
+        snapshot.gauge("wrt_memory_bytes", "Bytes of linear memory", 65536);
+        let mut stats = HashMap::new();
+        stats.insert(
+            "env.\"log\"".to_string(),
+            FunctionStats {
+                call_count: 4,
+                error_count: 1,
+                total_time_ms: 250.0,
+                ..FunctionStats::default()
+            },
+        );
+        snapshot.add_interceptor_stats(&stats);
+
+        let text = snapshot.to_string();
+        assert!(text.starts_with(
+            "# TYPE wrt_instantiations counter\n# HELP wrt_instantiations Instances \
+             created\nwrt_instantiations_total 3\n"
+        ));
+        assert!(text.contains("# TYPE wrt_memory_bytes gauge\n"));
+        assert!(text.contains("wrt_memory_bytes 65536\n"));
+        assert!(text.contains("wrt_intercepted_calls_total{function=\"env.\\\"log\\\"\"} 4\n"));
+        assert!(text
+            .contains("wrt_intercepted_call_seconds_total{function=\"env.\\\"log\\\"\"} 0.25\n"));
+        assert!(text.ends_with("# EOF\n"));
+        assert_eq!(
+            snapshot.get("wrt_intercepted_call_errors").unwrap().samples[0].value,
+            1.0
+        );
+    }
+
+    #[test]
+    fn test_sinks() {
+        let mut snapshot = MetricsSnapshot::new();
+        snapshot.add_execution_stats(&ExecutionStats {
+            memory_grows: 2,
+            ..ExecutionStats::default()
+        });
+
+        let mut sink = TextSink::new(Vec::new());
+        sink.export(&snapshot).unwrap();
+        let text = String::from_utf8(sink.into_inner()).unwrap();
+        assert!(text.contains("wrt_memory_grows_total 2\n"));
+
+        let mut exported = 0;
+        let mut count = |snapshot: &MetricsSnapshot| {
+            exported = snapshot.metrics().len();
+            Ok(())
+        };
+        count.export(&snapshot).unwrap();
+        assert_eq!(exported, 9);
+    }
+}
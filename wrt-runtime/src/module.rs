@@ -2,6 +2,13 @@
 //
 // This module provides the core runtime implementation of WebAssembly modules
 // used by the runtime execution engine.
+//
+// The decoder produces a `wrt_format::module::Module`, the one representation
+// of a decoded module. `Module::from_wrt_module` (or `from_wrt_module_nostd`)
+// lowers it into the `Module` defined here, which is what the engine
+// instantiates. The older `wrt_foundation::types::Module` and its conversion
+// are deprecated and only built with the `legacy-module` feature, on by
+// default until they are removed in the next release.
 
 // Use alloc when available through lib.rs
 #[cfg(all(feature = "alloc", not(feature = "std")))]
@@ -15,6 +22,11 @@
     vec::Vec,
 };
 
+#[cfg(feature = "std")]
+use wrt_decoder::custom_section_handler::{
+    CustomSectionRegistry,
+    CustomSectionValues,
+};
 use wrt_format::{
     module::{
         ExportKind as FormatExportKind,
@@ -58,6 +70,10 @@
 // HashMap is not needed with clean architecture using BoundedMap
 use wrt_foundation::{
     bounded_collections::BoundedMap,
+    interned::{
+        NameId,
+        NamePool,
+    },
     traits::{
         BoundedCapacity,
         Checksummable,
@@ -74,9 +90,23 @@
     create_runtime_provider,
     RuntimeProvider,
 };
+#[cfg(feature = "std")]
+use crate::module_signature::SignaturePolicy;
+#[cfg(feature = "std")]
+use crate::parallel_lowering::{
+    lower_function,
+    lower_functions,
+    ParallelLoweringConfig,
+};
+#[cfg(feature = "std")]
+use crate::stackless::code::ModuleCode;
 use crate::{
     global::Global,
     memory::Memory,
+    memory_view::{
+        MemoryView,
+        MemoryViewMut,
+    },
     prelude::{
         RuntimeString,
         ToString,
@@ -134,6 +164,36 @@ fn to_core_memory_type(memory_type: WrtMemoryType) -> CoreMemoryType {
     }
 }
 
+/// Initial value of a global, from its initialization expression
+///
+/// Globals initialized otherwise than by a constant, from an imported global
+/// for instance, start at zero.
+#[cfg(feature = "std")]
+fn global_initial_value(value_type: WrtValueType, init: &[u8]) -> Result<WrtValue> {
+    let float_bits = |len: usize| {
+        init.get(1..1 + len)
+            .ok_or_else(|| Error::parse_error("Truncated global initializer"))
+    };
+    Ok(match init.first() {
+        Some(0x41) => WrtValue::I32(wrt_format::binary::read_leb128_i32(init, 1)?.0),
+        Some(0x42) => WrtValue::I64(wrt_format::binary::read_leb128_i64(init, 1)?.0),
+        Some(0x43) => {
+            let bits = float_bits(4)?;
+            WrtValue::F32(wrt_foundation::values::FloatBits32::from_bits(
+                u32::from_le_bytes([bits[0], bits[1], bits[2], bits[3]]),
+            ))
+        },
+        Some(0x44) => {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(float_bits(8)?);
+            WrtValue::F64(wrt_foundation::values::FloatBits64::from_bits(
+                u64::from_le_bytes(bytes),
+            ))
+        },
+        _ => WrtValue::default_for_type(&value_type),
+    })
+}
+
 /// A WebAssembly expression (sequence of instructions)
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct WrtExpr {
@@ -196,6 +256,53 @@ pub fn new(name: &str, kind: ExportKind, index: u32) -> Result<Self> {
     }
 }
 
+/// Function exports by interned name
+///
+/// Calls look their export up by name; the index spares them building a
+/// bounded key for the [`ExportMap`] on every call. Names that do not fit in
+/// the pool are only found through [`Module::exports`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionExports {
+    names:    NamePool<64, 2048>,
+    indices:  [u32; 64],
+    /// Whether every function export fit in the pool
+    complete: bool,
+}
+
+impl Default for FunctionExports {
+    fn default() -> Self {
+        Self {
+            names:    NamePool::new(),
+            indices:  [0; 64],
+            complete: true,
+        }
+    }
+}
+
+impl FunctionExports {
+    fn insert(&mut self, name: &str, index: u32) {
+        match self.names.intern(name) {
+            Ok(id) => self.indices[id.index()] = index,
+            Err(_) => self.complete = false,
+        }
+    }
+
+    /// Handle of the function export `name`, if it is indexed
+    pub fn id(&self, name: &str) -> Option<NameId> {
+        self.names.get(name)
+    }
+
+    /// Function index of the export with handle `id`
+    pub fn function(&self, id: NameId) -> Option<u32> {
+        self.names.resolve(id).map(|_| self.indices[id.index()])
+    }
+
+    /// Name of the export with handle `id`
+    pub fn name(&self, id: NameId) -> Option<&str> {
+        self.names.resolve(id)
+    }
+}
+
 impl wrt_foundation::traits::Checksummable for Export {
     fn update_checksum(&self, checksum: &mut wrt_foundation::verification::Checksum) {
         self.name.update_checksum(checksum);
@@ -379,6 +486,8 @@ impl Eq for Function {}
 impl wrt_foundation::traits::Checksummable for Function {
     fn update_checksum(&self, checksum: &mut wrt_foundation::verification::Checksum) {
         checksum.update_slice(&self.type_idx.to_le_bytes());
+        self.locals.update_checksum(checksum);
+        self.body.instructions.update_checksum(checksum);
     }
 }
 
@@ -454,6 +563,8 @@ fn update_checksum(&self, checksum: &mut wrt_foundation::verification::Checksum)
         if let Some(table_idx) = self.table_idx {
             checksum.update_slice(&table_idx.to_le_bytes());
         }
+        self.element_type.update_checksum(checksum);
+        self.items.update_checksum(checksum);
     }
 }
 
@@ -601,7 +712,8 @@ pub struct Module {
     pub types:           BoundedModuleTypes,
     /// Imported functions, tables, memories, and globals
     pub imports:         ModuleImports,
-    /// Function definitions
+    /// Functions, imported ones first; modules converted from the format
+    /// keep their bodies compiled in `code` instead
     pub functions:       BoundedFunctionVec,
     /// Table instances
     pub tables:          BoundedTableVec,
@@ -617,8 +729,17 @@ pub struct Module {
     pub start:           Option<u32>,
     /// Custom sections
     pub custom_sections: CustomSections,
-    /// Exports (functions, tables, memories, and globals)
+    /// Values registered handlers deserialized from custom sections
+    #[cfg(feature = "std")]
+    pub section_values:  CustomSectionValues,
+    /// Validated and compiled code the interpreter runs
+    #[cfg(feature = "std")]
+    pub(crate) code:     Arc<ModuleCode>,
+    /// Exports (functions, tables, memories, and globals); add them with
+    /// [`Self::insert_export`]
     pub exports:         ExportMap,
+    /// Function exports by interned name
+    pub func_exports:    FunctionExports,
     /// Optional name for the module
     pub name:            Option<BoundedModuleName>,
     /// Original binary (if available)
@@ -642,20 +763,25 @@ pub fn new() -> Result<Self> {
         let runtime_provider2 = create_runtime_provider()?;
         let runtime_provider3 = create_runtime_provider()?;
         Ok(Self {
-            types:           wrt_foundation::bounded::BoundedVec::new(provider.clone())?,
-            imports:         BoundedMap::new(runtime_provider1)?,
-            functions:       wrt_foundation::bounded::BoundedVec::new(provider.clone())?,
-            tables:          wrt_foundation::bounded::BoundedVec::new(provider.clone())?,
-            memories:        wrt_foundation::bounded::BoundedVec::new(provider.clone())?,
-            globals:         wrt_foundation::bounded::BoundedVec::new(provider.clone())?,
-            elements:        wrt_foundation::bounded::BoundedVec::new(provider.clone())?,
-            data:            wrt_foundation::bounded::BoundedVec::new(provider.clone())?,
-            start:           None,
+            types: wrt_foundation::bounded::BoundedVec::new(provider.clone())?,
+            imports: BoundedMap::new(runtime_provider1)?,
+            functions: wrt_foundation::bounded::BoundedVec::new(provider.clone())?,
+            tables: wrt_foundation::bounded::BoundedVec::new(provider.clone())?,
+            memories: wrt_foundation::bounded::BoundedVec::new(provider.clone())?,
+            globals: wrt_foundation::bounded::BoundedVec::new(provider.clone())?,
+            elements: wrt_foundation::bounded::BoundedVec::new(provider.clone())?,
+            data: wrt_foundation::bounded::BoundedVec::new(provider.clone())?,
+            start: None,
             custom_sections: BoundedMap::new(runtime_provider2)?,
-            exports:         BoundedMap::new(runtime_provider3)?,
-            name:            None,
-            binary:          None,
-            validated:       false,
+            #[cfg(feature = "std")]
+            section_values: CustomSectionValues::default(),
+            #[cfg(feature = "std")]
+            code: Arc::default(),
+            exports: BoundedMap::new(runtime_provider3)?,
+            func_exports: FunctionExports::default(),
+            name: None,
+            binary: None,
+            validated: false,
         })
     }
 
@@ -663,6 +789,34 @@ pub fn new() -> Result<Self> {
     /// This is the primary constructor after decoding.
     #[cfg(feature = "std")]
     pub fn from_wrt_module(wrt_module: &wrt_format::module::Module) -> Result<Self> {
+        Self::from_wrt_module_with(wrt_module, None)
+    }
+
+    /// Creates a runtime Module from a `wrt_format::module::Module`,
+    /// validating and lowering its function bodies on worker threads.
+    ///
+    /// The result, including the error reported for a module with several
+    /// invalid bodies, is the same as that of [`Module::from_wrt_module`].
+    #[cfg(feature = "std")]
+    pub fn from_wrt_module_parallel(
+        wrt_module: &wrt_format::module::Module,
+        config: &ParallelLoweringConfig,
+    ) -> Result<Self> {
+        Self::from_wrt_module_with(wrt_module, Some(config))
+    }
+
+    /// Creates a runtime Module from a module in the WebAssembly text format
+    #[cfg(feature = "wat")]
+    pub fn from_wat(text: &str) -> Result<Self> {
+        let binary = crate::text_format::wat_to_binary(text)?;
+        Self::from_wrt_module(&wrt_decoder::decoder::decode_module(&binary)?)
+    }
+
+    #[cfg(feature = "std")]
+    fn from_wrt_module_with(
+        wrt_module: &wrt_format::module::Module,
+        lowering: Option<&ParallelLoweringConfig>,
+    ) -> Result<Self> {
         // Ensure memory system is initialized before creating providers
         wrt_foundation::memory_init::MemoryInitializer::ensure_initialized()?;
 
@@ -693,21 +847,43 @@ pub fn from_wrt_module(wrt_module: &wrt_format::module::Module) -> Result<Self>
             runtime_module.types.push(wrt_func_type)?;
         }
 
-        // Convert functions
-        for func in &wrt_module.functions {
-            // Convert locals using the locals conversion function
-            let locals = crate::type_conversion::convert_locals_to_bounded(&func.locals)?;
-
-            // Parse the function body bytecode into instructions
-            let instructions = crate::instruction_parser::parse_instructions(&func.code)?;
-            let body = WrtExpr { instructions };
-
-            let runtime_func = Function {
-                type_idx: func.type_idx,
-                locals,
-                body,
-            };
-            runtime_module.functions.push(runtime_func)?;
+        // Validate the module and compile its function bodies
+        let mut code = ModuleCode::context(wrt_module)?;
+        let functions = match lowering {
+            Some(config) => lower_functions(&wrt_module.functions, &code, config)?,
+            None => wrt_module
+                .functions
+                .iter()
+                .map(|func| lower_function(func, &code))
+                .collect::<Result<_>>()?,
+        };
+        code.functions = functions;
+        for type_idx in &code.function_types {
+            runtime_module.functions.push(Function {
+                type_idx: *type_idx,
+                locals:   BoundedLocalsVec::new(shared_provider.clone())?,
+                body:     WrtExpr::default(),
+            })?;
+        }
+        runtime_module.code = Arc::new(code);
+
+        // Convert tables, memories and globals, whose definitions instances
+        // are created from
+        for table in &wrt_module.tables {
+            runtime_module.tables.push(TableWrapper::new(Table::new(table.clone())?))?;
+        }
+        for memory in &wrt_module.memories {
+            runtime_module.memories.push(MemoryWrapper::new(Memory::definition(
+                to_core_memory_type(*memory),
+            )))?;
+        }
+        for global in &wrt_module.globals {
+            let global_type = global.global_type;
+            runtime_module.globals.push(GlobalWrapper::new(Global::new(
+                global_type.value_type,
+                global_type.mutable,
+                global_initial_value(global_type.value_type, &global.init)?,
+            )?))?;
         }
 
         // Convert exports
@@ -742,7 +918,7 @@ pub fn from_wrt_module(wrt_module: &wrt_format::module::Module) -> Result<Self>
                 index: export.index,
             };
 
-            runtime_module.exports.insert(map_key, runtime_export)?;
+            runtime_module.insert_export(map_key, runtime_export)?;
         }
 
         Ok(runtime_module)
@@ -857,11 +1033,9 @@ pub fn from_wrt_module_nostd(wrt_module: &wrt_format::module::Module) -> Result<
 
         // Convert memories
         for memory in &wrt_module.memories {
-            runtime_module
-                .memories
-                .push(MemoryWrapper::new(Memory::new(to_core_memory_type(
-                    *memory,
-                ))?))?;
+            runtime_module.memories.push(MemoryWrapper::new(Memory::definition(
+                to_core_memory_type(*memory),
+            )))?;
         }
 
         // Convert globals
@@ -919,15 +1093,18 @@ pub fn from_wrt_module_nostd(wrt_module: &wrt_format::module::Module) -> Result<
             let provider2 = create_runtime_provider()?;
             let map_key =
                 wrt_foundation::bounded::BoundedString::from_str_truncate(&export.name, provider2)?;
-            runtime_module.exports.insert(map_key, export_obj)?;
+            runtime_module.insert_export(map_key, export_obj)?;
         }
 
         Ok(runtime_module)
     }
 
     /// Creates a runtime Module from a `wrt_foundation::types::Module`.
-    /// This is the primary constructor after decoding for no_std.
-    #[cfg(not(feature = "std"))]
+    #[cfg(all(not(feature = "std"), feature = "legacy-module"))]
+    #[deprecated(
+        note = "decode into a `wrt_format::module::Module` and use `from_wrt_module_nostd`"
+    )]
+    #[allow(deprecated)]
     pub fn from_wrt_foundation_module(
         wrt_module: &wrt_foundation::types::Module<RuntimeProvider>,
     ) -> Result<Self> {
@@ -1095,11 +1272,9 @@ pub fn from_wrt_foundation_module(
         }
 
         for memory_def in &wrt_module.memories {
-            runtime_module
-                .memories
-                .push(MemoryWrapper::new(Memory::new(to_core_memory_type(
-                    memory_def,
-                ))?))?;
+            runtime_module.memories.push(MemoryWrapper::new(Memory::definition(
+                to_core_memory_type(memory_def),
+            )))?;
         }
 
         for global_def in &wrt_module.globals {
@@ -1177,7 +1352,7 @@ pub fn from_wrt_foundation_module(
                 provider,
             )?;
             let export = crate::module::Export::new(name_key.as_str()?, kind, index)?;
-            runtime_module.exports.insert(name_key, export)?;
+            runtime_module.insert_export(name_key, export)?;
         }
 
         // TODO: Element segments are not yet available in wrt_foundation Module
@@ -1233,6 +1408,14 @@ pub fn get_global(&self, idx: usize) -> Result<GlobalWrapper> {
             .map_err(|_| Error::runtime_execution_error("Global index out of bounds"))
     }
 
+    /// Types of the memories the module defines, in index order
+    ///
+    /// Memory contents are runtime state and not part of a module; they are
+    /// allocated from these types when the module is instantiated.
+    pub fn memory_types(&self) -> impl Iterator<Item = CoreMemoryType> + '_ {
+        self.memories.iter().map(|memory| memory.ty())
+    }
+
     /// Gets a memory by index
     pub fn get_memory(&self, idx: usize) -> Result<MemoryWrapper> {
         self.memories.get(idx).map_err(|_| {
@@ -1260,7 +1443,7 @@ pub fn add_function_export(&mut self, name: &str, index: u32) -> Result<()> {
                 &name,
                 create_runtime_provider()?,
             )?;
-            self.exports.insert(bounded_name, export)?;
+            self.insert_export(bounded_name, export)?;
         }
         #[cfg(not(feature = "std"))]
         {
@@ -1268,7 +1451,7 @@ pub fn add_function_export(&mut self, name: &str, index: u32) -> Result<()> {
                 &name,
                 create_runtime_provider()?,
             )?;
-            self.exports.insert(bounded_name, export)?;
+            self.insert_export(bounded_name, export)?;
         }
         Ok(())
     }
@@ -1282,7 +1465,7 @@ pub fn add_table_export(&mut self, name: &str, index: u32) -> Result<()> {
                 &name,
                 create_runtime_provider()?,
             )?;
-            self.exports.insert(bounded_name, export)?;
+            self.insert_export(bounded_name, export)?;
         }
         #[cfg(not(feature = "std"))]
         {
@@ -1290,7 +1473,7 @@ pub fn add_table_export(&mut self, name: &str, index: u32) -> Result<()> {
                 &name,
                 create_runtime_provider()?,
             )?;
-            self.exports.insert(bounded_name, export)?;
+            self.insert_export(bounded_name, export)?;
         }
         Ok(())
     }
@@ -1304,7 +1487,7 @@ pub fn add_memory_export(&mut self, name: &str, index: u32) -> Result<()> {
                 &name,
                 create_runtime_provider()?,
             )?;
-            self.exports.insert(bounded_name, export)?;
+            self.insert_export(bounded_name, export)?;
         }
         #[cfg(not(feature = "std"))]
         {
@@ -1312,7 +1495,7 @@ pub fn add_memory_export(&mut self, name: &str, index: u32) -> Result<()> {
                 &name,
                 create_runtime_provider()?,
             )?;
-            self.exports.insert(bounded_name, export)?;
+            self.insert_export(bounded_name, export)?;
         }
         Ok(())
     }
@@ -1326,7 +1509,7 @@ pub fn add_global_export(&mut self, name: &str, index: u32) -> Result<()> {
                 &name,
                 create_runtime_provider()?,
             )?;
-            self.exports.insert(bounded_name, export)?;
+            self.insert_export(bounded_name, export)?;
         }
         #[cfg(not(feature = "std"))]
         {
@@ -1334,7 +1517,7 @@ pub fn add_global_export(&mut self, name: &str, index: u32) -> Result<()> {
                 &name,
                 create_runtime_provider()?,
             )?;
-            self.exports.insert(bounded_name, export)?;
+            self.insert_export(bounded_name, export)?;
         }
         Ok(())
     }
@@ -1364,7 +1547,7 @@ pub fn add_export(&mut self, format_export: wrt_format::module::Export) -> Resul
                 .map_err(|_| Error::runtime_error("Invalid export name"))?,
             create_runtime_provider()?,
         )?;
-        self.exports.insert(name_key, runtime_export)?;
+        self.insert_export(name_key, runtime_export)?;
         Ok(())
     }
 
@@ -1630,9 +1813,9 @@ pub fn add_table(&mut self, table_type: WrtTableType) -> Result<()> {
 
     /// Add a memory to the module
     pub fn add_memory(&mut self, memory_type: WrtMemoryType) -> Result<()> {
-        self.memories.push(MemoryWrapper::new(Memory::new(to_core_memory_type(
+        self.memories.push(MemoryWrapper::new(Memory::definition(to_core_memory_type(
             memory_type,
-        ))?))?;
+        ))))?;
         Ok(())
     }
 
@@ -1656,7 +1839,7 @@ pub fn add_export_func(&mut self, name: &str, index: u32) -> Result<()> {
             create_runtime_provider()?,
         )?;
         let export = Export::new(name, ExportKind::Function, index)?;
-        self.exports.insert(bounded_name, export)?;
+        self.insert_export(bounded_name, export)?;
         Ok(())
     }
 
@@ -1671,7 +1854,7 @@ pub fn add_export_table(&mut self, name: &str, index: u32) -> Result<()> {
             create_runtime_provider()?,
         )?;
         let export = Export::new(bounded_name.as_str()?, ExportKind::Table, index)?;
-        self.exports.insert(bounded_name, export)?;
+        self.insert_export(bounded_name, export)?;
         Ok(())
     }
 
@@ -1687,7 +1870,7 @@ pub fn add_export_memory(&mut self, name: &str, index: u32) -> Result<()> {
             name,
             create_runtime_provider()?,
         )?;
-        self.exports.insert(bounded_name, export)?;
+        self.insert_export(bounded_name, export)?;
         Ok(())
     }
 
@@ -1703,7 +1886,7 @@ pub fn add_export_global(&mut self, name: &str, index: u32) -> Result<()> {
             name,
             create_runtime_provider()?,
         )?;
-        self.exports.insert(bounded_name, export)?;
+        self.insert_export(bounded_name, export)?;
         Ok(())
     }
 
@@ -1830,6 +2013,29 @@ pub fn add_custom_section(&mut self, name: &str, data: Vec<u8>) -> Result<()> {
         Ok(())
     }
 
+    /// Deserialize the custom sections of `binary` that `registry` has
+    /// handlers for into [`Module::section_values`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a handler fails on its section.
+    #[cfg(feature = "std")]
+    pub fn deserialize_custom_sections(
+        &mut self,
+        binary: &[u8],
+        registry: &CustomSectionRegistry,
+    ) -> Result<()> {
+        self.section_values = registry.deserialize(binary)?;
+        Ok(())
+    }
+
+    /// Value a registered handler deserialized from the custom section
+    /// `name`, if it is a `T`
+    #[cfg(feature = "std")]
+    pub fn custom_section_value<T: core::any::Any>(&self, name: &str) -> Option<&T> {
+        self.section_values.get(name)
+    }
+
     /// Set the binary representation of the module
     #[cfg(any(feature = "std", feature = "alloc"))]
     pub fn set_binary(&mut self, binary: Vec<u8>) -> Result<()> {
@@ -1932,7 +2138,7 @@ pub fn add_runtime_export(&mut self, name: &str, export_desc: WrtExportDesc) ->
         let provider = create_runtime_provider()?;
         let provider = create_runtime_provider()?;
         let name_key = wrt_foundation::bounded::BoundedString::from_str_truncate(&name, provider)?;
-        self.exports.insert(name_key, runtime_export)?;
+        self.insert_export(name_key, runtime_export)?;
         Ok(())
     }
 
@@ -2040,6 +2246,50 @@ pub fn set_binary_runtime(&mut self, binary: Vec<u8>) -> Result<()> {
     /// The binary is processed section by section without loading
     /// the entire module into intermediate data structures.
     pub fn load_from_binary(&mut self, binary: &[u8]) -> Result<Self> {
+        self.load_from_binary_with(
+            binary,
+            #[cfg(feature = "std")]
+            None,
+        )
+    }
+
+    /// Load a module from WebAssembly binary, validating and lowering its
+    /// function bodies on worker threads
+    ///
+    /// Errors are reported as by [`Module::load_from_binary`], for the first
+    /// invalid function body in index order.
+    #[cfg(feature = "std")]
+    pub fn load_from_binary_parallel(
+        &mut self,
+        binary: &[u8],
+        config: &ParallelLoweringConfig,
+    ) -> Result<Self> {
+        self.load_from_binary_with(binary, Some(config))
+    }
+
+    /// Load a module from WebAssembly binary once its signatures pass
+    /// `policy`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the module does not pass `policy`, see
+    /// [`SignaturePolicy::verify`], and otherwise as by
+    /// [`Module::load_from_binary`].
+    #[cfg(feature = "std")]
+    pub fn load_from_signed_binary(
+        &mut self,
+        binary: &[u8],
+        policy: &SignaturePolicy,
+    ) -> Result<Self> {
+        policy.verify(binary)?;
+        self.load_from_binary(binary)
+    }
+
+    fn load_from_binary_with(
+        &mut self,
+        binary: &[u8],
+        #[cfg(feature = "std")] lowering: Option<&ParallelLoweringConfig>,
+    ) -> Result<Self> {
         // Use wrt-decoder's unified loader for efficient parsing
         use wrt_decoder::{
             load_wasm_unified,
@@ -2059,7 +2309,12 @@ pub fn load_from_binary(&mut self, binary: &[u8]) -> Result<Self> {
         let module_info = wasm_info.require_module_info()?;
 
         // Create runtime module from unified API data
-        let runtime_module = Self::from_module_info(module_info, binary)?;
+        let runtime_module = Self::from_module_info(
+            module_info,
+            binary,
+            #[cfg(feature = "std")]
+            lowering,
+        )?;
 
         // Store the binary for later use
         // Note: This is the only place where we keep the full binary in memory
@@ -2079,7 +2334,11 @@ pub fn load_from_binary(&mut self, binary: &[u8]) -> Result<Self> {
     }
 
     /// Create runtime Module from unified API ModuleInfo
-    fn from_module_info(module_info: &wrt_decoder::ModuleInfo, binary: &[u8]) -> Result<Self> {
+    fn from_module_info(
+        module_info: &wrt_decoder::ModuleInfo,
+        binary: &[u8],
+        #[cfg(feature = "std")] lowering: Option<&ParallelLoweringConfig>,
+    ) -> Result<Self> {
         let mut runtime_module = Self::new()?;
 
         // Set start function if present
@@ -2182,7 +2441,7 @@ fn from_module_info(module_info: &wrt_decoder::ModuleInfo, binary: &[u8]) -> Res
                 &export.name,
                 create_runtime_provider()?,
             )?;
-            runtime_module.exports.insert(name_key, runtime_export)?;
+            runtime_module.insert_export(name_key, runtime_export)?;
         }
 
         // Set memory info if present
@@ -2194,11 +2453,9 @@ fn from_module_info(module_info: &wrt_decoder::ModuleInfo, binary: &[u8]) -> Res
                 },
                 shared: false,
             };
-            runtime_module
-                .memories
-                .push(MemoryWrapper::new(Memory::new(to_core_memory_type(
-                    memory_type,
-                ))?))?;
+            runtime_module.memories.push(MemoryWrapper::new(Memory::definition(
+                to_core_memory_type(memory_type),
+            )))?;
         }
 
         // For now, we'll use the fallback decoder for full section parsing if needed
@@ -2210,7 +2467,7 @@ fn from_module_info(module_info: &wrt_decoder::ModuleInfo, binary: &[u8]) -> Res
 
             // decoded_module is wrt_format::Module, so we need the format-compatible method
             #[cfg(feature = "std")]
-            let full_runtime_module = Module::from_wrt_module(&decoded_module)?;
+            let full_runtime_module = Module::from_wrt_module_with(&decoded_module, lowering)?;
             #[cfg(not(feature = "std"))]
             let full_runtime_module = Module::from_wrt_module_nostd(&decoded_module)?;
 
@@ -2220,8 +2477,46 @@ fn from_module_info(module_info: &wrt_decoder::ModuleInfo, binary: &[u8]) -> Res
         Ok(runtime_module)
     }
 
+    /// Add `export` under `name`, recording it in [`Self::func_exports`] if
+    /// it exports a function
+    ///
+    /// Every export goes through here so that [`Self::find_function_by_name`]
+    /// can trust a miss in the interned names.
+    pub fn insert_export(
+        &mut self,
+        name: wrt_foundation::bounded::BoundedString<256, RuntimeProvider>,
+        export: Export,
+    ) -> Result<()> {
+        if export.kind == ExportKind::Function {
+            self.func_exports.insert(name.as_str()?, export.index);
+        }
+        self.exports.insert(name, export)?;
+        Ok(())
+    }
+
+    /// Index the function exports of [`Self::exports`] again, for a module
+    /// whose exports were filled without [`Self::insert_export`]
+    #[cfg(feature = "std")]
+    pub(crate) fn reindex_function_exports(&mut self) -> Result<()> {
+        let mut func_exports = FunctionExports::default();
+        for export in self.exports.values() {
+            if export.kind == ExportKind::Function {
+                func_exports.insert(export.name.as_str()?, export.index);
+            }
+        }
+        self.func_exports = func_exports;
+        Ok(())
+    }
+
     /// Find a function export by name
     pub fn find_function_by_name(&self, name: &str) -> Option<u32> {
+        if let Some(id) = self.func_exports.id(name) {
+            return self.func_exports.function(id);
+        }
+        if self.func_exports.complete {
+            return None;
+        }
+
         let provider = create_runtime_provider().ok()?;
         let bounded_name =
             wrt_foundation::bounded::BoundedString::from_str_truncate(name, provider).ok()?;
@@ -2429,6 +2724,11 @@ fn from_bytes_with_provider<'a, PStream: wrt_foundation::MemoryProvider>(
     Result,
 };
 use wrt_foundation::component::ExternType; // For error handling
+use wrt_sync::{
+    WrtRwLock,
+    WrtRwLockReadGuard,
+    WrtRwLockWriteGuard,
+};
 
 // Newtype wrappers to solve orphan rules issue
 // These allow us to implement external traits on types containing Arc<T>
@@ -2514,31 +2814,61 @@ pub fn init(&self, offset: u32, init_data: &[Option<WrtValue>]) -> Result<()> {
     }
 }
 
-/// Wrapper for Arc<Memory> to enable trait implementations  
 /// Memory guard for atomic operations
 #[derive(Debug)]
 pub struct MemoryGuard {
-    memory: Arc<Memory>,
+    memory: MemoryWrapper,
 }
 
 impl MemoryGuard {
     /// Read from memory
     pub fn read(&self, offset: usize, buffer: &mut [u8]) -> Result<()> {
-        self.memory.read(offset as u32, buffer)
+        self.memory.read(wasm_offset(offset)?, buffer)
     }
 
     /// Write to memory (atomic operations may need this)
     pub fn write(&self, offset: usize, buffer: &[u8]) -> Result<()> {
-        // TODO: Implement safe atomic memory write operations for Arc<Memory>
-        // For now, return an error as Arc<Memory> doesn't allow mutable access
-        Err(Error::runtime_execution_error(
-            "Atomic memory write operations not yet implemented for Arc<Memory>",
-        ))
+        self.memory.write(wasm_offset(offset)?, buffer)
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct MemoryWrapper(pub Arc<Memory>);
+/// Offset `offset` into a memory with 32-bit addresses
+fn wasm_offset(offset: usize) -> Result<u32> {
+    u32::try_from(offset).map_err(|_| Error::memory_out_of_bounds("Memory offset out of range"))
+}
+
+/// Shared handle to a memory
+///
+/// The instance defining a memory, the instances importing it and the host
+/// hold handles to the same memory. Its state sits behind a lock that does
+/// not poison, so every handle can write to and grow the memory, while
+/// [`lock_read`](Self::lock_read) and [`lock_write`](Self::lock_write) hold
+/// it across several accesses.
+#[derive(Debug)]
+pub struct MemoryWrapper(Arc<WrtRwLock<Memory>>);
+
+impl Clone for MemoryWrapper {
+    fn clone(&self) -> Self {
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        {
+            Self(Arc::clone(&self.0))
+        }
+
+        // Without an allocator there is nothing to share, so clones are copies
+        #[cfg(not(any(feature = "std", feature = "alloc")))]
+        {
+            Self::new(self.0.read().clone())
+        }
+    }
+}
+
+impl PartialEq for MemoryWrapper {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0) || *self.0.read() == *other.0.read()
+    }
+}
+
+impl Eq for MemoryWrapper {}
 
 impl Default for MemoryWrapper {
     fn default() -> Self {
@@ -2557,146 +2887,128 @@ fn default() -> Self {
     }
 }
 
-impl AsRef<Arc<Memory>> for MemoryWrapper {
-    fn as_ref(&self) -> &Arc<Memory> {
-        &self.0
-    }
-}
-
 impl MemoryWrapper {
     /// Create a new memory wrapper
     pub fn new(memory: Memory) -> Self {
-        Self(Arc::new(memory))
+        Self(Arc::new(WrtRwLock::new(memory)))
     }
 
-    /// Get a reference to the inner memory
+    /// Whether `self` and `other` are handles to the same memory
     #[must_use]
-    pub fn inner(&self) -> &Arc<Memory> {
-        &self.0
+    pub fn same_memory(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// Lock the memory for reading, until the guard is dropped
+    pub fn lock_read(&self) -> WrtRwLockReadGuard<'_, Memory> {
+        self.0.read()
     }
 
-    /// Unwrap to get the Arc<Memory>
+    /// Lock the memory for writing, until the guard is dropped
+    ///
+    /// Other handles to the memory wait for the guard to be dropped, so it
+    /// should not be held while calling into the guest or the host.
+    pub fn lock_write(&self) -> WrtRwLockWriteGuard<'_, Memory> {
+        self.0.write()
+    }
+
+    /// Type of the memory
     #[must_use]
-    pub fn into_inner(self) -> Arc<Memory> {
-        self.0
+    pub fn ty(&self) -> CoreMemoryType {
+        self.0.read().ty
+    }
+
+    /// Typed, bounds-checked read access for embedders, while `f` runs
+    pub fn view<R>(&self, f: impl FnOnce(MemoryView<'_>) -> R) -> R {
+        f(self.0.read().view())
+    }
+
+    /// Typed, bounds-checked read and write access for embedders, while `f`
+    /// runs
+    pub fn view_mut<R>(&self, f: impl FnOnce(MemoryViewMut<'_>) -> R) -> R {
+        f(self.0.write().view_mut())
     }
 
     /// Get memory size in bytes
     #[must_use]
     pub fn size_in_bytes(&self) -> usize {
-        self.0.size_in_bytes()
+        self.0.read().size_in_bytes()
     }
 
     /// Get memory size in pages
     #[must_use]
     pub fn size(&self) -> u32 {
-        self.0.size()
+        self.0.read().size()
     }
 
     /// Get memory size in pages (alias for compatibility)
     #[must_use]
     pub fn size_pages(&self) -> u32 {
-        self.0.size()
+        self.size()
     }
 
     /// Get memory size in bytes (alias for compatibility)
     #[must_use]
     pub fn size_bytes(&self) -> usize {
-        self.0.size_in_bytes()
+        self.size_in_bytes()
     }
 
     /// Read from memory
     pub fn read(&self, offset: u32, buffer: &mut [u8]) -> Result<()> {
-        self.0.read(offset, buffer)
+        self.0.read().read(offset, buffer)
     }
 
-    /// Write to memory (requires mutable access to Arc<Memory>)
+    /// Write to memory
     pub fn write(&self, offset: u32, buffer: &[u8]) -> Result<()> {
-        // Note: This requires unsafe because we can't get mutable access to Arc<Memory>
-        // For now, we'll return an error
-        Err(Error::new(
-            ErrorCategory::Runtime,
-            wrt_error::codes::MEMORY_ACCESS_DENIED,
-            "Cannot write to memory through Arc<Memory>",
-        ))
+        self.0.write().write(offset, buffer)
+    }
+
+    /// Read several ranges of memory under one lock, see
+    /// [`Memory::read_vectored`]
+    pub fn read_vectored(&self, ranges: &mut [(u32, &mut [u8])]) -> Result<()> {
+        self.0.read().read_vectored(ranges)
     }
 
-    /// Grow memory (requires mutable access)
+    /// Write several buffers into memory under one lock, see
+    /// [`Memory::write_vectored`]
+    pub fn write_vectored(&self, ranges: &[(u32, &[u8])]) -> Result<()> {
+        self.0.write().write_vectored(ranges)
+    }
+
+    /// Grow memory by `pages`, returning the previous size in pages
     pub fn grow(&self, pages: u32) -> Result<u32> {
-        // Note: This requires unsafe because we can't get mutable access to Arc<Memory>
-        // For now, we'll return an error
-        Err(Error::runtime_execution_error(
-            "Runtime execution error: Cannot grow memory through Arc<Memory>",
-        ))
+        self.0.write().grow(pages)
     }
 
     /// Write i32 to memory
     pub fn write_i32(&self, offset: u32, value: i32) -> Result<()> {
-        #[cfg(any(feature = "std", feature = "alloc"))]
-        {
-            use crate::memory_helpers::ArcMemoryExt;
-            self.0.write_i32(offset, value)
-        }
-        #[cfg(not(any(feature = "std", feature = "alloc")))]
-        {
-            self.write(offset, &value.to_le_bytes())
-        }
+        self.0.write().write_i32(offset, value)
     }
 
     /// Write i64 to memory
     pub fn write_i64(&self, offset: u32, value: i64) -> Result<()> {
-        #[cfg(any(feature = "std", feature = "alloc"))]
-        {
-            use crate::memory_helpers::ArcMemoryExt;
-            self.0.write_i64(offset, value)
-        }
-        #[cfg(not(any(feature = "std", feature = "alloc")))]
-        {
-            self.write(offset, &value.to_le_bytes())
-        }
+        self.0.write().write_i64(offset, value)
     }
 
     /// Write f32 to memory
     pub fn write_f32(&self, offset: u32, value: f32) -> Result<()> {
-        #[cfg(any(feature = "std", feature = "alloc"))]
-        {
-            use crate::memory_helpers::ArcMemoryExt;
-            self.0.write_f32(offset, value)
-        }
-        #[cfg(not(any(feature = "std", feature = "alloc")))]
-        {
-            self.write(offset, &value.to_bits().to_le_bytes())
-        }
+        self.0.write().write_f32(offset, value)
     }
 
     /// Write f64 to memory
     pub fn write_f64(&self, offset: u32, value: f64) -> Result<()> {
-        #[cfg(any(feature = "std", feature = "alloc"))]
-        {
-            use crate::memory_helpers::ArcMemoryExt;
-            self.0.write_f64(offset, value)
-        }
-        #[cfg(not(any(feature = "std", feature = "alloc")))]
-        {
-            self.write(offset, &value.to_bits().to_le_bytes())
-        }
+        self.0.write().write_f64(offset, value)
     }
 
-    /// Fill memory (requires mutable access)
+    /// Fill `len` bytes starting at `offset` with `value`
     pub fn fill(&self, offset: u32, len: u32, value: u8) -> Result<()> {
-        // Note: This requires unsafe because we can't get mutable access to Arc<Memory>
-        // For now, we'll return an error
-        Err(Error::new(
-            ErrorCategory::Runtime,
-            wrt_error::codes::MEMORY_ACCESS_DENIED,
-            "Cannot fill memory through Arc<Memory>",
-        ))
+        self.0.write().fill(offset as usize, value, len as usize)
     }
 
     /// Get a memory guard for atomic operations
     pub fn lock(&self) -> MemoryGuard {
         MemoryGuard {
-            memory: self.0.clone(),
+            memory: self.clone(),
         }
     }
 }
@@ -2797,50 +3109,26 @@ fn update_checksum(&self, checksum: &mut Checksum) {
 
 impl ToBytes for TableWrapper {
     fn serialized_size(&self) -> usize {
-        12 // table type (4) + size (4) + limits (4)
+        10 // element type (1) + limits (9)
     }
 
     fn to_bytes_with_provider<P: wrt_foundation::MemoryProvider>(
         &self,
         writer: &mut WriteStream,
-        _provider: &P,
+        provider: &P,
     ) -> Result<()> {
-        writer.write_all(&self.0.size().to_le_bytes())?;
-        writer.write_all(&(self.0.ty.element_type as u8).to_le_bytes())?;
-        writer.write_all(&self.0.ty.limits.min.to_le_bytes())?;
-        Ok(())
+        self.0.ty.to_bytes_with_provider(writer, provider)
     }
 }
 
 impl FromBytes for TableWrapper {
     fn from_bytes_with_provider<P: wrt_foundation::MemoryProvider>(
         reader: &mut ReadStream<'_>,
-        _provider: &P,
+        provider: &P,
     ) -> Result<Self> {
-        let mut bytes = [0u8; 12];
-        reader.read_exact(&mut bytes)?;
-
-        // Create a default table (simplified implementation)
-        use wrt_foundation::types::{
-            Limits,
-            RefType,
-            TableType,
-        };
-        let table_type = TableType {
-            element_type: RefType::Funcref,
-            limits:       Limits {
-                min: 0,
-                max: Some(1),
-            },
-        };
-
-        let table = Table::new(table_type).map_err(|_| {
-            wrt_error::Error::runtime_execution_error(
-                "Runtime execution error: Failed to create table from bytes",
-            )
-        })?;
-
-        Ok(TableWrapper::new(table))
+        // Only the definition is kept, the elements are set on instantiation
+        let table_type = WrtTableType::from_bytes_with_provider(reader, provider)?;
+        Ok(TableWrapper::new(Table::new(table_type)?))
     }
 }
 
@@ -2848,59 +3136,40 @@ fn from_bytes_with_provider<P: wrt_foundation::MemoryProvider>(
 impl Checksummable for MemoryWrapper {
     fn update_checksum(&self, checksum: &mut Checksum) {
         // Use memory size for checksum
-        checksum.update_slice(&self.0.size().to_le_bytes());
-        checksum.update_slice(&self.0.size_in_bytes().to_le_bytes());
+        checksum.update_slice(&self.size().to_le_bytes());
+        checksum.update_slice(&self.size_in_bytes().to_le_bytes());
     }
 }
 
 impl ToBytes for MemoryWrapper {
     fn serialized_size(&self) -> usize {
-        12 // size (4) + limits min (4) + limits max (4)
+        10 // limits (9) + shared flag (1)
     }
 
     fn to_bytes_with_provider<P: wrt_foundation::MemoryProvider>(
         &self,
         writer: &mut WriteStream,
-        _provider: &P,
+        provider: &P,
     ) -> Result<()> {
-        writer.write_all(&self.0.size().to_le_bytes())?;
-        writer.write_all(&self.0.ty.limits.min.to_le_bytes())?;
-        let max = self.0.ty.limits.max.unwrap_or(u32::MAX);
-        writer.write_all(&max.to_le_bytes())?;
-        Ok(())
+        let ty = self.ty();
+        WrtMemoryType {
+            limits: ty.limits,
+            shared: ty.shared,
+        }
+        .to_bytes_with_provider(writer, provider)
     }
 }
 
 impl FromBytes for MemoryWrapper {
     fn from_bytes_with_provider<P: wrt_foundation::MemoryProvider>(
         reader: &mut ReadStream<'_>,
-        _provider: &P,
+        provider: &P,
     ) -> Result<Self> {
-        let mut bytes = [0u8; 12];
-        reader.read_exact(&mut bytes)?;
-
-        // Create a default memory (simplified implementation)
-        use wrt_foundation::types::{
-            Limits,
-            MemoryType,
-        };
-        let memory_type = MemoryType {
-            limits: Limits {
-                min: 1,
-                max: Some(1),
-            },
-            shared: false,
-        };
-
-        let memory = Memory::new(to_core_memory_type(memory_type)).map_err(|_| {
-            wrt_error::Error::new(
-                wrt_error::ErrorCategory::Memory,
-                wrt_error::codes::INVALID_VALUE,
-                "Failed to create memory from bytes",
-            )
-        })?;
-
-        Ok(MemoryWrapper::new(memory))
+        // Only the definition is kept, the contents are set on instantiation
+        let memory_type = WrtMemoryType::from_bytes_with_provider(reader, provider)?;
+        Ok(MemoryWrapper::new(Memory::definition(to_core_memory_type(
+            memory_type,
+        ))))
     }
 }
 
@@ -2933,43 +3202,31 @@ fn update_checksum(&self, checksum: &mut Checksum) {
 
 impl ToBytes for GlobalWrapper {
     fn serialized_size(&self) -> usize {
-        12 // value type (4) + mutable flag (4) + value (4)
+        19 // global type (2) + value (up to 17)
     }
 
     fn to_bytes_with_provider<P: wrt_foundation::MemoryProvider>(
         &self,
         writer: &mut WriteStream,
-        _provider: &P,
+        provider: &P,
     ) -> Result<()> {
-        writer.write_all(
-            &value_type_to_u8(self.0.global_type_descriptor().value_type).to_le_bytes(),
-        )?;
-        writer.write_all(&u8::from(self.0.global_type_descriptor().mutable).to_le_bytes())?;
-        // Simplified value serialization
-        writer.write_all(&0u32.to_le_bytes())?;
-        Ok(())
+        self.0.global_type_descriptor().to_bytes_with_provider(writer, provider)?;
+        self.0.get().to_bytes_with_provider(writer, provider)
     }
 }
 
 impl FromBytes for GlobalWrapper {
     fn from_bytes_with_provider<P: wrt_foundation::MemoryProvider>(
         reader: &mut ReadStream<'_>,
-        _provider: &P,
+        provider: &P,
     ) -> Result<Self> {
-        let mut bytes = [0u8; 12];
-        reader.read_exact(&mut bytes)?;
-
-        // Create a default global (simplified implementation)
-        use wrt_foundation::{
-            types::ValueType,
-            values::Value,
-        };
-
-        let global = Global::new(ValueType::I32, false, Value::I32(0)).map_err(|_| {
-            wrt_error::Error::runtime_execution_error("Failed to create global from bytes")
-        })?;
-
-        Ok(GlobalWrapper::new(global))
+        let global_type = WrtGlobalType::from_bytes_with_provider(reader, provider)?;
+        let value = WrtValue::from_bytes_with_provider(reader, provider)?;
+        Ok(GlobalWrapper::new(Global::new(
+            global_type.value_type,
+            global_type.mutable,
+            value,
+        )?))
     }
 }
 
@@ -0,0 +1,313 @@
+//! Size and resource analysis of loaded modules
+//!
+//! [`Module::analyze`] reports where the bytes of a module go and what
+//! instantiating it costs, so developers can track the growth of their
+//! guests from build to build: the size of every section, the size of every
+//! function body, largest first, the number of imports and exports, and an
+//! estimate of the memory an instance takes.
+//!
+//! Section and body sizes are taken from the binary the module was loaded
+//! from, so a module restored with
+//! [`Module::deserialize`](crate::module::Module::deserialize) cannot be
+//! analyzed. Functions are named from the name section when the module has
+//! one.
+
+use core::{
+    fmt,
+    mem::size_of,
+};
+
+use wrt_decoder::{
+    custom_section_handler::extract_custom_section,
+    name_section::read_name_section,
+    parallel_decoder::SectionIndex,
+};
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_format::binary::{
+    read_leb128_u32,
+    CODE_SECTION_ID,
+    CUSTOM_SECTION_ID,
+};
+use wrt_foundation::{
+    component::ExternType,
+    values::Value,
+};
+
+use crate::{
+    module::Module,
+    module_instance::ModuleInstance,
+    prelude::*,
+};
+
+/// Function bodies listed by the [`Display`](fmt::Display) of a
+/// [`SizeReport`]
+pub const DEFAULT_TOP_FUNCTIONS: usize = 10;
+
+/// Size of a page of linear memory in bytes
+const PAGE_SIZE: u64 = 65536;
+
+/// A section of an analyzed module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionSize {
+    /// Section identifier
+    pub id:   u8,
+    /// Name of the section, the name of custom sections
+    pub name: String,
+    /// Size of the section payload in bytes
+    pub size: usize,
+}
+
+/// A function body of an analyzed module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionSize {
+    /// Index of the function in the function index space, which starts with
+    /// the imported functions
+    pub index: u32,
+    /// Name of the function from the name section, if any
+    pub name:  Option<String>,
+    /// Size of the body, locals included, in bytes
+    pub size:  usize,
+}
+
+/// Estimated memory taken by an instance of a module
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InstantiationMemory {
+    /// Initial size of the linear memories the module defines
+    pub linear_memory: u64,
+    /// Initial entries of the tables the module defines
+    pub tables:        u64,
+    /// Globals the module defines
+    pub globals:       u64,
+    /// The instance itself
+    pub instance:      u64,
+}
+
+impl InstantiationMemory {
+    /// Total estimated bytes
+    pub fn total(&self) -> u64 {
+        self.linear_memory + self.tables + self.globals + self.instance
+    }
+}
+
+/// Sizes and counts of a module, see [`Module::analyze`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SizeReport {
+    /// Size of the binary in bytes
+    pub binary_size:          usize,
+    /// Sections in binary order
+    pub sections:             Vec<SectionSize>,
+    /// Function bodies, largest first
+    pub functions:            Vec<FunctionSize>,
+    /// Number of imports
+    pub imports:              usize,
+    /// Number of exports
+    pub exports:              usize,
+    /// Estimated memory taken by an instance
+    pub instantiation_memory: InstantiationMemory,
+}
+
+impl SizeReport {
+    /// Total size of the function bodies in bytes
+    pub fn code_size(&self) -> usize {
+        self.functions.iter().map(|function| function.size).sum()
+    }
+
+    /// The `count` largest function bodies
+    pub fn top_functions(&self, count: usize) -> &[FunctionSize] {
+        &self.functions[..count.min(self.functions.len())]
+    }
+
+    /// Render the report, listing the `top` largest function bodies
+    pub fn write_report(&self, out: &mut impl fmt::Write, top: usize) -> fmt::Result {
+        writeln!(out, "binary: {} bytes", self.binary_size)?;
+        writeln!(out, "sections:")?;
+        for section in &self.sections {
+            writeln!(out, "  {:<16} {:>8} bytes", section.name, section.size)?;
+        }
+        writeln!(
+            out,
+            "functions: {} bodies, {} bytes",
+            self.functions.len(),
+            self.code_size()
+        )?;
+        for function in self.top_functions(top) {
+            match &function.name {
+                Some(name) => write!(out, "  {name} [{}]", function.index)?,
+                None => write!(out, "  func[{}]", function.index)?,
+            }
+            writeln!(out, ": {} bytes", function.size)?;
+        }
+        writeln!(out, "imports: {}", self.imports)?;
+        writeln!(out, "exports: {}", self.exports)?;
+        let memory = &self.instantiation_memory;
+        writeln!(
+            out,
+            "instantiation memory: {} bytes (linear memory {}, tables {}, globals {}, instance {})",
+            memory.total(),
+            memory.linear_memory,
+            memory.tables,
+            memory.globals,
+            memory.instance
+        )
+    }
+}
+
+impl fmt::Display for SizeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_report(f, DEFAULT_TOP_FUNCTIONS)
+    }
+}
+
+impl Module {
+    /// Report the section and function body sizes, the import and export
+    /// counts and the estimated instantiation memory of the module
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the module was not loaded from a binary or the
+    /// binary is malformed.
+    pub fn analyze(&self) -> Result<SizeReport> {
+        let binary: Vec<u8> = self
+            .binary
+            .as_ref()
+            .ok_or_else(|| Error::resource_not_found("Module binary is not available"))?
+            .iter()
+            .collect();
+
+        let imports: Vec<_> = self
+            .imports
+            .values()
+            .flat_map(|imports| imports.values().collect::<Vec<_>>())
+            .collect();
+        let imported_functions =
+            imports.iter().filter(|import| matches!(import.ty, ExternType::Func(_))).count();
+        let names = read_name_section(&binary).ok().flatten();
+
+        let mut sections = Vec::new();
+        let mut functions = Vec::new();
+        for entry in SectionIndex::scan(&binary)?.entries() {
+            let payload = &binary[entry.range.clone()];
+            let name = match entry.id {
+                CUSTOM_SECTION_ID => extract_custom_section(payload)?.0,
+                id => section_name(id).into(),
+            };
+            sections.push(SectionSize {
+                id: entry.id,
+                name,
+                size: payload.len(),
+            });
+
+            if entry.id == CODE_SECTION_ID {
+                let (count, mut offset) = read_leb128_u32(payload, 0)?;
+                for body in 0..count {
+                    let (size, bytes_read) = read_leb128_u32(payload, offset)?;
+                    offset += bytes_read + size as usize;
+                    if offset > payload.len() {
+                        return Err(Error::parse_error("Function body extends beyond section"));
+                    }
+                    let index = imported_functions as u32 + body;
+                    functions.push(FunctionSize {
+                        index,
+                        name: names
+                            .as_ref()
+                            .and_then(|names| names.function_name(index))
+                            .map(String::from),
+                        size: size as usize,
+                    });
+                }
+            }
+        }
+        functions.sort_by(|a, b| b.size.cmp(&a.size).then(a.index.cmp(&b.index)));
+
+        let value_size = size_of::<Value>() as u64;
+        let instantiation_memory = InstantiationMemory {
+            linear_memory: self
+                .memories
+                .iter()
+                .map(|memory| u64::from(memory.ty().limits.min) * PAGE_SIZE)
+                .sum(),
+            tables:        self
+                .tables
+                .iter()
+                .map(|table| u64::from(table.0.ty.limits.min) * value_size)
+                .sum(),
+            globals:       self.globals.len() as u64 * value_size,
+            instance:      size_of::<ModuleInstance>() as u64,
+        };
+
+        Ok(SizeReport {
+            binary_size: binary.len(),
+            sections,
+            functions,
+            imports: imports.len(),
+            exports: self.exports.len(),
+            instantiation_memory,
+        })
+    }
+}
+
+fn section_name(id: u8) -> &'static str {
+    match id {
+        1 => "type",
+        2 => "import",
+        3 => "function",
+        4 => "table",
+        5 => "memory",
+        6 => "global",
+        7 => "export",
+        8 => "start",
+        9 => "element",
+        10 => "code",
+        11 => "data",
+        12 => "datacount",
+        13 => "tag",
+        _ => "unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_lists_largest_functions_first() {
+        let report = SizeReport {
+            binary_size:          120,
+            sections:             vec![SectionSize {
+                id:   10,
+                name: "code".into(),
+                size: 40,
+            }],
+            functions:            vec![
+                FunctionSize {
+                    index: 2,
+                    name:  Some("parse".into()),
+                    size:  30,
+                },
+                FunctionSize {
+                    index: 1,
+                    name:  None,
+                    size:  8,
+                },
+            ],
+            imports:              1,
+            exports:              2,
+            instantiation_memory: InstantiationMemory {
+                linear_memory: PAGE_SIZE,
+                ..InstantiationMemory::default()
+            },
+        };
+
+        assert_eq!(report.code_size(), 38);
+        assert_eq!(report.top_functions(5).len(), 2);
+
+        let mut text = String::new();
+        report.write_report(&mut text, 1).unwrap();
+        assert!(text.contains("  code                   40 bytes\n"));
+        assert!(text.contains("functions: 2 bodies, 38 bytes\n  parse [2]: 30 bytes\nimports"));
+        assert!(text.contains("instantiation memory: 65536 bytes"));
+    }
+}
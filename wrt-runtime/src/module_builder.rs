@@ -20,6 +20,7 @@
 use std::vec::Vec;
 
 use wrt_format::{
+    binary::read_leb128_u32,
     DataSegment as WrtDataSegment,
     ElementSegment as WrtElementSegment,
 };
@@ -295,37 +296,6 @@ fn parse_locals_from_body(
     Ok(locals)
 }
 
-/// Read LEB128 u32 from bytecode
-fn read_leb128_u32(bytecode: &[u8], offset: usize) -> Result<(u32, usize)> {
-    let mut result = 0u32;
-    let mut shift = 0;
-    let mut consumed = 0;
-
-    loop {
-        if offset + consumed >= bytecode.len() {
-            return Err(Error::parse_error(
-                "Unexpected end of bytecode while reading LEB128",
-            ));
-        }
-
-        let byte = bytecode[offset + consumed];
-        consumed += 1;
-
-        result |= ((byte & 0x7F) as u32) << shift;
-
-        if (byte & 0x80) == 0 {
-            break;
-        }
-
-        shift += 7;
-        if shift >= 32 {
-            return Err(Error::parse_error("LEB128 value too large"));
-        }
-    }
-
-    Ok((result, consumed))
-}
-
 impl ModuleBuilder {
     /// Create a new module builder with an existing binary
     #[cfg(any(feature = "std", feature = "alloc"))]
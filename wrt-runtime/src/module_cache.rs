@@ -0,0 +1,306 @@
+//! Serialized form of decoded modules for caching
+//!
+//! Decoding and validating a module binary is the bulk of the work before a
+//! module can be instantiated. [`Module::serialize`] stores a decoded module
+//! in a versioned binary form that [`Module::deserialize`] loads again
+//! without decoding, so embedders can cache it between startups under the
+//! [`cache_key`] of the module binary.
+//!
+//! The payload is covered by a checksum that is verified on load, and the
+//! format version is checked so that a cache written by another version of
+//! the runtime is rejected rather than misread. The original binary is not
+//! part of the serialized form.
+
+use alloc::sync::Arc;
+use std::vec::Vec;
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_foundation::{
+    bounded::BoundedVec,
+    safe_memory::{
+        Slice,
+        SliceMut,
+    },
+    traits::{
+        BoundedCapacity,
+        Checksummable,
+        FromBytes,
+        ReadStream,
+        ToBytes,
+        WriteStream,
+    },
+    verification::Checksum,
+};
+
+use crate::{
+    bounded_runtime_infra::{
+        create_runtime_provider,
+        RuntimeProvider,
+    },
+    module::Module,
+    stackless::ModuleCode,
+};
+
+/// Magic bytes of a serialized module
+pub const SERIALIZED_MODULE_MAGIC: [u8; 4] = *b"WRTM";
+
+/// Version of the serialized module format
+pub const SERIALIZED_MODULE_VERSION: u32 = 2;
+
+/// Magic, version, payload size and payload checksum
+const HEADER_SIZE: usize = 16;
+
+/// Key under which the serialized form of a module binary is cached
+pub fn cache_key(binary: &[u8]) -> u32 {
+    Checksum::compute(binary).value()
+}
+
+impl Module {
+    /// Serialize the decoded module
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a part of the module fails to serialize.
+    pub fn serialize(&self) -> Result<Vec<u8>> {
+        let provider = create_runtime_provider()?;
+        let mut payload = Vec::new();
+
+        payload.push(u8::from(self.validated));
+        match self.start {
+            Some(start) => {
+                payload.push(1);
+                payload.extend_from_slice(&start.to_le_bytes());
+            },
+            None => payload.push(0),
+        }
+        write_item(&mut payload, &self.name, &provider)?;
+
+        write_vec(&mut payload, &self.types, &provider)?;
+        write_vec(&mut payload, &self.functions, &provider)?;
+        write_vec(&mut payload, &self.tables, &provider)?;
+        write_vec(&mut payload, &self.memories, &provider)?;
+        write_vec(&mut payload, &self.globals, &provider)?;
+        write_vec(&mut payload, &self.elements, &provider)?;
+        write_vec(&mut payload, &self.data, &provider)?;
+
+        write_item(&mut payload, &self.imports, &provider)?;
+        write_item(&mut payload, &self.exports, &provider)?;
+        write_item(&mut payload, &self.custom_sections, &provider)?;
+        self.code.serialize(&mut payload);
+
+        frame(&payload)
+    }
+
+    /// Load a module serialized by [`Module::serialize`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is not a serialized module, was written
+    /// by another format version, fails its checksum or is malformed.
+    pub fn deserialize(bytes: &[u8]) -> Result<Self> {
+        let payload = unframe(bytes)?;
+        let provider = create_runtime_provider()?;
+        let mut reader = PayloadReader {
+            payload,
+            pos: 0,
+            provider,
+        };
+
+        let mut module = Self::empty();
+        module.validated = reader.read_byte()? != 0;
+        module.start = match reader.read_byte()? {
+            0 => None,
+            _ => Some(u32::from_le_bytes(reader.read_array()?)),
+        };
+        module.name = reader.read_item()?;
+
+        reader.read_vec(&mut module.types)?;
+        reader.read_vec(&mut module.functions)?;
+        reader.read_vec(&mut module.tables)?;
+        reader.read_vec(&mut module.memories)?;
+        reader.read_vec(&mut module.globals)?;
+        reader.read_vec(&mut module.elements)?;
+        reader.read_vec(&mut module.data)?;
+
+        module.imports = reader.read_item()?;
+        module.exports = reader.read_item()?;
+        module.reindex_function_exports()?;
+        module.custom_sections = reader.read_item()?;
+        module.code = Arc::new(ModuleCode::deserialize(payload, &mut reader.pos)?);
+
+        if reader.pos != payload.len() {
+            return Err(Error::validation_parse_error(
+                "Trailing bytes in serialized module",
+            ));
+        }
+        Ok(module)
+    }
+}
+
+/// Prefix `payload` with the header
+fn frame(payload: &[u8]) -> Result<Vec<u8>> {
+    let size = u32::try_from(payload.len())
+        .map_err(|_| Error::capacity_limit_exceeded("Serialized module is too large"))?;
+
+    let mut bytes = Vec::with_capacity(HEADER_SIZE + payload.len());
+    bytes.extend_from_slice(&SERIALIZED_MODULE_MAGIC);
+    bytes.extend_from_slice(&SERIALIZED_MODULE_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&size.to_le_bytes());
+    bytes.extend_from_slice(&Checksum::compute(payload).value().to_le_bytes());
+    bytes.extend_from_slice(payload);
+    Ok(bytes)
+}
+
+/// Check the header and return the payload it describes
+fn unframe(bytes: &[u8]) -> Result<&[u8]> {
+    if bytes.len() < HEADER_SIZE || bytes[..4] != SERIALIZED_MODULE_MAGIC {
+        return Err(Error::validation_parse_error("Not a serialized module"));
+    }
+    let field = |offset: usize| {
+        u32::from_le_bytes([
+            bytes[offset],
+            bytes[offset + 1],
+            bytes[offset + 2],
+            bytes[offset + 3],
+        ])
+    };
+
+    if field(4) != SERIALIZED_MODULE_VERSION {
+        return Err(Error::validation_parse_error(
+            "Unsupported serialized module version",
+        ));
+    }
+    let payload = &bytes[HEADER_SIZE..];
+    if payload.len() != field(8) as usize {
+        return Err(Error::validation_parse_error(
+            "Serialized module size mismatch",
+        ));
+    }
+    if Checksum::compute(payload).value() != field(12) {
+        return Err(Error::validation_parse_error(
+            "Serialized module checksum mismatch",
+        ));
+    }
+    Ok(payload)
+}
+
+fn write_item<T: ToBytes>(out: &mut Vec<u8>, item: &T, provider: &RuntimeProvider) -> Result<()> {
+    let mut buffer = vec![0u8; item.serialized_size()];
+    let mut writer = WriteStream::new(SliceMut::new(&mut buffer)?);
+    item.to_bytes_with_provider(&mut writer, provider)?;
+    let len = writer.position();
+    out.extend_from_slice(&buffer[..len]);
+    Ok(())
+}
+
+/// Write the items of `vec` one by one, since the size of the whole vector
+/// is only estimated from its first element type
+fn write_vec<T, const N: usize>(
+    out: &mut Vec<u8>,
+    vec: &BoundedVec<T, N, RuntimeProvider>,
+    provider: &RuntimeProvider,
+) -> Result<()>
+where
+    T: Checksummable + ToBytes + FromBytes + Default + Clone + PartialEq + Eq,
+{
+    out.extend_from_slice(&(vec.len() as u32).to_le_bytes());
+    for item in vec.iter() {
+        write_item(out, &item, provider)?;
+    }
+    Ok(())
+}
+
+/// Cursor over the payload of a serialized module
+struct PayloadReader<'a> {
+    payload:  &'a [u8],
+    pos:      usize,
+    provider: RuntimeProvider,
+}
+
+impl<'a> PayloadReader<'a> {
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let bytes = self
+            .payload
+            .get(self.pos..self.pos + N)
+            .ok_or_else(|| Error::validation_parse_error("Serialized module truncated"))?;
+        self.pos += N;
+        let mut array = [0; N];
+        array.copy_from_slice(bytes);
+        Ok(array)
+    }
+
+    fn read_byte(&mut self) -> Result<u8> {
+        Ok(self.read_array::<1>()?[0])
+    }
+
+    fn read_item<T: FromBytes>(&mut self) -> Result<T> {
+        let mut reader = ReadStream::new(Slice::new(&self.payload[self.pos..])?);
+        let item = T::from_bytes_with_provider(&mut reader, &self.provider)?;
+        self.pos += reader.position();
+        Ok(item)
+    }
+
+    fn read_vec<T, const N: usize>(
+        &mut self,
+        vec: &mut BoundedVec<T, N, RuntimeProvider>,
+    ) -> Result<()>
+    where
+        T: Checksummable + ToBytes + FromBytes + Default + Clone + PartialEq + Eq,
+    {
+        let count = u32::from_le_bytes(self.read_array()?);
+        for _ in 0..count {
+            vec.push(self.read_item()?)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_integrity() {
+        let payload = [1, 2, 3, 4, 5];
+        let bytes = frame(&payload).unwrap();
+        assert_eq!(&bytes[..4], b"WRTM");
+        assert_eq!(unframe(&bytes).unwrap(), &payload);
+
+        let mut corrupted = bytes.clone();
+        corrupted[HEADER_SIZE + 2] ^= 0xFF;
+        assert!(unframe(&corrupted).is_err());
+
+        let mut newer = bytes.clone();
+        newer[4] = 3;
+        assert!(unframe(&newer).is_err());
+
+        assert!(unframe(&bytes[..bytes.len() - 1]).is_err());
+        assert!(unframe(b"\0asm\x01\0\0\0").is_err());
+    }
+
+    #[cfg(feature = "wat")]
+    #[test]
+    fn test_compiled_code_round_trips() {
+        let module = Module::from_wat(
+            r#"(module
+                (global (mut i32) (i32.const 7))
+                (func (export "get") (result i32) global.get 0))"#,
+        )
+        .unwrap();
+        let loaded = Module::deserialize(&module.serialize().unwrap()).unwrap();
+        assert_eq!(loaded.code, module.code);
+        assert_eq!(loaded.find_function_by_name("get"), Some(0));
+    }
+
+    #[test]
+    fn test_cache_key_follows_content() {
+        assert_eq!(cache_key(b"\0asm\x01\0\0\0"), cache_key(b"\0asm\x01\0\0\0"));
+        assert_ne!(
+            cache_key(b"\0asm\x01\0\0\0"),
+            cache_key(b"\0asm\x01\0\0\x01")
+        );
+    }
+}
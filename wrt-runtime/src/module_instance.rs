@@ -3,9 +3,35 @@
 //! This module provides the implementation of a WebAssembly module instance,
 //! which represents a runtime instance of a WebAssembly module with its own
 //! memory, tables, globals, and functions.
+//!
+//! # Layout
+//!
+//! An instance owns its memories, tables and globals in flat arrays addressed
+//! by item index, imported items first. The arrays are filled while the
+//! instance is created and do not change once it is shared, so executing code
+//! reaches an item without taking a lock: each array entry is the pointer to
+//! its item, which [`ModuleInstance::memory_ref`] and
+//! [`ModuleInstance::table_ref`] borrow directly. A memory's contents sit
+//! behind its own lock, taken per access, so the instance, its exports and
+//! the host all read and write the same bytes. The current values of
+//! mutable globals live in per-global cells, numeric values as atomic bits,
+//! so neither reading nor writing a global locks.
+//!
+//! Builds without an allocator keep the items in bounded vectors, which hold
+//! copies of their elements, and their globals behind a lock.
 
 // alloc is imported in lib.rs with proper feature gates
 
+#[cfg(feature = "std")]
+use core::sync::atomic::AtomicBool;
+#[cfg(all(any(feature = "std", feature = "alloc"), target_has_atomic = "64"))]
+use core::sync::atomic::AtomicU64;
+#[cfg(any(feature = "std", feature = "alloc"))]
+use core::sync::atomic::{
+    AtomicU32,
+    Ordering,
+};
+
 #[cfg(feature = "debug-full")]
 use wrt_debug::FunctionInfo;
 #[cfg(feature = "debug")]
@@ -26,22 +52,53 @@
     },
     verification::Checksum,
 };
+#[cfg(any(feature = "std", feature = "alloc"))]
+use wrt_foundation::{
+    float_repr::{
+        FloatBits32,
+        FloatBits64,
+    },
+    values::Value,
+    ValueType,
+};
 use wrt_instructions::reference_ops::ReferenceOperations;
+#[cfg(any(feature = "std", feature = "alloc"))]
+use wrt_sync::WrtMutex;
 
+#[cfg(feature = "std")]
+use crate::atomic_global::AtomicGlobal;
 // Type alias for FuncType to make signatures more readable - uses unified RuntimeProvider
+#[cfg(not(any(feature = "std", feature = "alloc")))]
 use crate::bounded_runtime_infra::{
-    create_runtime_provider,
     BoundedGlobalVec,
-    BoundedImportExportName,
-    BoundedImportMap,
     BoundedMemoryVec,
     BoundedTableVec,
-    RuntimeProvider,
 };
+#[cfg(feature = "std")]
+use crate::stackless::interpreter::DroppedSegments;
 use crate::{
+    bounded_runtime_infra::{
+        create_runtime_provider,
+        BoundedImportExportName,
+        BoundedImportMap,
+        RuntimeProvider,
+        MAX_GLOBAL_INSTANCES,
+        MAX_MEMORY_INSTANCES,
+        MAX_TABLE_INSTANCES,
+    },
+    externs::{
+        Extern,
+        FuncExport,
+        GlobalExport,
+        InstanceGlobal,
+        MemoryExport,
+        TableExport,
+    },
     global::Global,
     memory::Memory,
     module::{
+        Export,
+        ExportKind,
         GlobalWrapper,
         MemoryWrapper,
         Module,
@@ -70,58 +127,288 @@
     Mutex,
 };
 
+#[cfg(any(feature = "std", feature = "alloc"))]
+use crate::prelude::Vec;
 #[cfg(not(feature = "std"))]
 use crate::prelude::{
     Arc,
     Mutex,
 };
 
+/// Memories of an instance, addressed by memory index
+#[cfg(any(feature = "std", feature = "alloc"))]
+type Memories = Vec<MemoryWrapper>;
+/// Tables of an instance, addressed by table index
+#[cfg(any(feature = "std", feature = "alloc"))]
+type Tables = Vec<TableWrapper>;
+/// Globals of an instance, addressed by global index
+#[cfg(any(feature = "std", feature = "alloc"))]
+type Globals = Vec<GlobalSlot>;
+
+/// Memories of an instance, addressed by memory index
+#[cfg(not(any(feature = "std", feature = "alloc")))]
+type Memories = BoundedMemoryVec<MemoryWrapper>;
+/// Tables of an instance, addressed by table index
+#[cfg(not(any(feature = "std", feature = "alloc")))]
+type Tables = BoundedTableVec<TableWrapper>;
+/// Globals of an instance, addressed by global index
+#[cfg(not(any(feature = "std", feature = "alloc")))]
+type Globals = Mutex<BoundedGlobalVec<GlobalWrapper>>;
+
+/// Ordering of accesses to global cells, which are not shared with the host
+#[cfg(any(feature = "std", feature = "alloc"))]
+const CELL_ORDERING: Ordering = Ordering::Relaxed;
+
+/// Current value of a mutable global, readable and writable through a shared
+/// reference
+#[cfg(any(feature = "std", feature = "alloc"))]
+#[derive(Debug)]
+enum ValueCell {
+    /// `i32` or `f32` value, as its bits
+    Bits32(AtomicU32, ValueType),
+    /// `i64` or `f64` value, as its bits
+    #[cfg(target_has_atomic = "64")]
+    Bits64(AtomicU64, ValueType),
+    /// Any other value
+    Locked(WrtMutex<Value>),
+}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl ValueCell {
+    fn new(value: &Value) -> Self {
+        match *value {
+            Value::I32(value) => Self::Bits32(AtomicU32::new(value as u32), ValueType::I32),
+            Value::F32(value) => Self::Bits32(AtomicU32::new(value.to_bits()), ValueType::F32),
+            #[cfg(target_has_atomic = "64")]
+            Value::I64(value) => Self::Bits64(AtomicU64::new(value as u64), ValueType::I64),
+            #[cfg(target_has_atomic = "64")]
+            Value::F64(value) => Self::Bits64(AtomicU64::new(value.to_bits()), ValueType::F64),
+            _ => Self::Locked(WrtMutex::new(value.clone())),
+        }
+    }
+
+    fn load(&self) -> Value {
+        match self {
+            Self::Bits32(bits, ValueType::F32) => {
+                Value::F32(FloatBits32::from_bits(bits.load(CELL_ORDERING)))
+            },
+            Self::Bits32(bits, _) => Value::I32(bits.load(CELL_ORDERING) as i32),
+            #[cfg(target_has_atomic = "64")]
+            Self::Bits64(bits, ValueType::F64) => {
+                Value::F64(FloatBits64::from_bits(bits.load(CELL_ORDERING)))
+            },
+            #[cfg(target_has_atomic = "64")]
+            Self::Bits64(bits, _) => Value::I64(bits.load(CELL_ORDERING) as i64),
+            Self::Locked(value) => value.lock().clone(),
+        }
+    }
+
+    /// Store `value`, which the caller has checked to be of the cell's type
+    fn store(&self, value: &Value) {
+        match (self, value) {
+            (Self::Bits32(bits, _), Value::I32(value)) => bits.store(*value as u32, CELL_ORDERING),
+            (Self::Bits32(bits, _), Value::F32(value)) => {
+                bits.store(value.to_bits(), CELL_ORDERING);
+            },
+            #[cfg(target_has_atomic = "64")]
+            (Self::Bits64(bits, _), Value::I64(value)) => bits.store(*value as u64, CELL_ORDERING),
+            #[cfg(target_has_atomic = "64")]
+            (Self::Bits64(bits, _), Value::F64(value)) => {
+                bits.store(value.to_bits(), CELL_ORDERING);
+            },
+            (Self::Locked(cell), value) => *cell.lock() = value.clone(),
+            _ => {},
+        }
+    }
+}
+
+/// Global of an instance with its current value
+#[cfg(any(feature = "std", feature = "alloc"))]
+#[derive(Debug, Clone)]
+struct GlobalSlot {
+    /// The global as created or imported, with its type
+    definition: GlobalWrapper,
+    /// Current value, for mutable globals; shared by the clones of the
+    /// instance
+    value:      Option<Arc<ValueCell>>,
+}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+impl GlobalSlot {
+    fn new(definition: GlobalWrapper) -> Self {
+        let value = definition
+            .0
+            .global_type_descriptor()
+            .mutable
+            .then(|| Arc::new(ValueCell::new(definition.0.get())));
+        Self { definition, value }
+    }
+
+    fn get(&self) -> Value {
+        match &self.value {
+            Some(cell) => cell.load(),
+            None => self.definition.0.get().clone(),
+        }
+    }
+
+    fn set(&self, value: &Value) -> Result<()> {
+        let Some(cell) = &self.value else {
+            return Err(Error::runtime_execution_error(
+                "Cannot set immutable global variable",
+            ));
+        };
+        if !value.matches_type(&self.definition.0.global_type_descriptor().value_type) {
+            return Err(Error::type_error(
+                "Value type does not match global variable type",
+            ));
+        }
+        cell.store(value);
+        Ok(())
+    }
+
+    /// The global with its current value
+    fn snapshot(&self) -> Result<GlobalWrapper> {
+        if self.value.is_none() {
+            return Ok(self.definition.clone());
+        }
+        let mut global = (*self.definition.0).clone();
+        global.set(&self.get())?;
+        Ok(GlobalWrapper::new(global))
+    }
+}
+
+/// Globals designated as host-shared atomics, keyed by global index
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+struct AtomicGlobals {
+    /// Whether any global has been designated, checked before locking
+    /// `globals`
+    designated: AtomicBool,
+    globals:    Mutex<Vec<(u32, AtomicGlobal)>>,
+}
+
+/// Item `idx` of `items`
+#[cfg(any(feature = "std", feature = "alloc"))]
+fn item<T: Clone>(items: &[T], idx: u32) -> Option<T> {
+    items.get(idx as usize).cloned()
+}
+
+/// Append `item` to `items`, holding at most `capacity` items
+#[cfg(any(feature = "std", feature = "alloc"))]
+fn push_item<T>(items: &mut Vec<T>, item: T, capacity: usize, error: &'static str) -> Result<()> {
+    if items.len() >= capacity {
+        return Err(Error::capacity_limit_exceeded(error));
+    }
+    items.push(item);
+    Ok(())
+}
+
+/// Item `idx` of `items`
+#[cfg(not(any(feature = "std", feature = "alloc")))]
+fn item<T, const N: usize>(
+    items: &wrt_foundation::bounded::BoundedVec<T, N, RuntimeProvider>,
+    idx: u32,
+) -> Option<T>
+where
+    T: Sized + Checksummable + ToBytes + FromBytes + Default + Clone + PartialEq + Eq,
+{
+    items.get(idx as usize).ok()
+}
+
+/// Append `item` to `items`, holding at most their capacity of items
+#[cfg(not(any(feature = "std", feature = "alloc")))]
+fn push_item<T, const N: usize>(
+    items: &mut wrt_foundation::bounded::BoundedVec<T, N, RuntimeProvider>,
+    item: T,
+    _capacity: usize,
+    error: &'static str,
+) -> Result<()>
+where
+    T: Sized + Checksummable + ToBytes + FromBytes + Default + Clone + PartialEq + Eq,
+{
+    items.push(item).map_err(|_| Error::capacity_limit_exceeded(error))
+}
+
 /// Represents a runtime instance of a WebAssembly module
 #[derive(Debug)]
 pub struct ModuleInstance {
     /// The module this instance was instantiated from
-    module:      Arc<Module>,
-    /// The instance's memory (using safety-critical wrapper types)
-    memories:    Arc<Mutex<BoundedMemoryVec<MemoryWrapper>>>,
-    /// The instance's tables (using safety-critical wrapper types)
-    tables:      Arc<Mutex<BoundedTableVec<TableWrapper>>>,
-    /// The instance's globals (using safety-critical wrapper types)
-    globals:     Arc<Mutex<BoundedGlobalVec<GlobalWrapper>>>,
+    module:         Arc<Module>,
+    /// The instance's memories, imported ones first
+    memories:       Memories,
+    /// The instance's tables, imported ones first
+    tables:         Tables,
+    /// The instance's globals, imported ones first
+    globals:        Globals,
     /// Instance ID for debugging
-    instance_id: usize,
+    instance_id:    usize,
     /// Imported instance indices to resolve imports
-    imports:     BoundedImportMap<BoundedImportMap<(usize, usize)>>,
+    imports:        BoundedImportMap<BoundedImportMap<(usize, usize)>>,
+    /// Globals designated as host-shared atomics
+    #[cfg(feature = "std")]
+    atomic_globals: Arc<AtomicGlobals>,
+    /// Element and data segments dropped by the guest
+    #[cfg(feature = "std")]
+    dropped:        Arc<DroppedSegments>,
     /// Debug information (optional)
     #[cfg(feature = "debug")]
-    debug_info:  Option<DwarfDebugInfo<'static>>,
+    debug_info:     Option<DwarfDebugInfo<'static>>,
 }
 
 impl ModuleInstance {
     /// Create a new module instance from a module
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn new(module: Module, instance_id: usize) -> Result<Self> {
+        Ok(Self::with_items(
+            Arc::new(module),
+            instance_id,
+            Vec::new(),
+            Vec::new(),
+            Vec::new(),
+        ))
+    }
+
+    /// Create a new module instance from a module
+    #[cfg(not(any(feature = "std", feature = "alloc")))]
     pub fn new(module: Module, instance_id: usize) -> Result<Self> {
         // Create a single shared provider to avoid stack overflow from multiple
         // provider allocations
         let shared_provider = create_runtime_provider()?;
 
-        // Allocate memory for memories collection
-        let memories_vec = wrt_foundation::bounded::BoundedVec::new(shared_provider.clone())?;
-
-        // Allocate memory for tables collection
-        let tables_vec = wrt_foundation::bounded::BoundedVec::new(shared_provider.clone())?;
+        let memories = wrt_foundation::bounded::BoundedVec::new(shared_provider.clone())?;
+        let tables = wrt_foundation::bounded::BoundedVec::new(shared_provider.clone())?;
+        let globals = wrt_foundation::bounded::BoundedVec::new(shared_provider)?;
 
-        // Allocate memory for globals collection
-        let globals_vec = wrt_foundation::bounded::BoundedVec::new(shared_provider.clone())?;
+        Ok(Self::with_items(
+            Arc::new(module),
+            instance_id,
+            memories,
+            tables,
+            Mutex::new(globals),
+        ))
+    }
 
-        Ok(Self {
-            module: Arc::new(module),
-            memories: Arc::new(Mutex::new(memories_vec)),
-            tables: Arc::new(Mutex::new(tables_vec)),
-            globals: Arc::new(Mutex::new(globals_vec)),
+    fn with_items(
+        module: Arc<Module>,
+        instance_id: usize,
+        memories: Memories,
+        tables: Tables,
+        globals: Globals,
+    ) -> Self {
+        Self {
+            module,
+            memories,
+            tables,
+            globals,
             instance_id,
             imports: Default::default(),
+            #[cfg(feature = "std")]
+            atomic_globals: Arc::new(AtomicGlobals::default()),
+            #[cfg(feature = "std")]
+            dropped: Arc::new(DroppedSegments::default()),
             #[cfg(feature = "debug")]
             debug_info: None,
-        })
+        }
     }
 
     /// Get the module associated with this instance
@@ -132,51 +419,46 @@ pub fn module(&self) -> &Arc<Module> {
 
     /// Get a memory from this instance
     pub fn memory(&self, idx: u32) -> Result<MemoryWrapper> {
-        #[cfg(feature = "std")]
-        let memories = self
-            .memories
-            .lock()
-            .map_err(|_| Error::runtime_error("Failed to lock memories"))?;
-
-        #[cfg(not(feature = "std"))]
-        let memories = self.memories.lock();
+        item(&self.memories, idx)
+            .ok_or_else(|| Error::runtime_execution_error("Memory index out of bounds"))
+    }
 
-        let memory = memories
-            .get(idx as usize)
-            .map_err(|_| Error::runtime_execution_error("Memory index out of bounds"))?;
-        Ok(memory.clone())
+    /// Borrow a memory of this instance, without taking a reference to it
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    #[must_use]
+    pub fn memory_ref(&self, idx: u32) -> Option<&MemoryWrapper> {
+        self.memories.get(idx as usize)
     }
 
     /// Get a table from this instance
     pub fn table(&self, idx: u32) -> Result<TableWrapper> {
-        #[cfg(feature = "std")]
-        let tables =
-            self.tables.lock().map_err(|_| Error::runtime_error("Failed to lock tables"))?;
+        item(&self.tables, idx)
+            .ok_or_else(|| Error::resource_table_not_found("Runtime operation error"))
+    }
 
-        #[cfg(not(feature = "std"))]
-        let tables = self.tables.lock();
+    /// Borrow a table of this instance, without taking a reference to it
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    #[must_use]
+    pub fn table_ref(&self, idx: u32) -> Option<&Table> {
+        self.tables.get(idx as usize).map(|table| &*table.0)
+    }
 
-        let table = tables
+    /// Get a global from this instance, with its current value
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn global(&self, idx: u32) -> Result<GlobalWrapper> {
+        self.globals
             .get(idx as usize)
-            .map_err(|_| Error::resource_table_not_found("Runtime operation error"))?;
-        Ok(table.clone())
+            .ok_or_else(|| Error::resource_global_not_found("Runtime operation error"))?
+            .snapshot()
     }
 
-    /// Get a global from this instance
+    /// Get a global from this instance, with its current value
+    #[cfg(not(any(feature = "std", feature = "alloc")))]
     pub fn global(&self, idx: u32) -> Result<GlobalWrapper> {
-        #[cfg(feature = "std")]
-        let globals = self
-            .globals
+        self.globals
             .lock()
-            .map_err(|_| Error::runtime_error("Failed to lock globals"))?;
-
-        #[cfg(not(feature = "std"))]
-        let globals = self.globals.lock();
-
-        let global = globals
             .get(idx as usize)
-            .map_err(|_| Error::resource_global_not_found("Runtime operation error"))?;
-        Ok(global.clone())
+            .map_err(|_| Error::resource_global_not_found("Runtime operation error"))
     }
 
     /// Get the function type for a function
@@ -253,54 +535,266 @@ pub fn function_type(&self, idx: u32) -> Result<WrtFuncType> {
     }
 
     /// Add a memory to this instance
-    pub fn add_memory(&self, memory: Memory) -> Result<()> {
-        #[cfg(feature = "std")]
-        let mut memories = self
-            .memories
-            .lock()
-            .map_err(|_| Error::runtime_error("Failed to lock memories"))?;
-
-        #[cfg(not(feature = "std"))]
-        let mut memories = self.memories.lock();
+    pub fn add_memory(&mut self, memory: Memory) -> Result<()> {
+        self.import_memory(MemoryWrapper::new(memory))
+    }
 
-        memories
-            .push(MemoryWrapper::new(memory))
-            .map_err(|_| Error::capacity_limit_exceeded("Memory capacity exceeded"))?;
-        Ok(())
+    /// Add an imported memory to this instance
+    ///
+    /// The wrapper is stored as-is, so the memory stays shared with the host
+    /// or instance that provided it.
+    pub fn import_memory(&mut self, memory: MemoryWrapper) -> Result<()> {
+        push_item(
+            &mut self.memories,
+            memory,
+            MAX_MEMORY_INSTANCES,
+            "Memory capacity exceeded",
+        )
     }
 
     /// Add a table to this instance
-    pub fn add_table(&self, table: Table) -> Result<()> {
-        #[cfg(feature = "std")]
-        let mut tables =
-            self.tables.lock().map_err(|_| Error::runtime_error("Failed to lock tables"))?;
-
-        #[cfg(not(feature = "std"))]
-        let mut tables = self.tables.lock();
+    pub fn add_table(&mut self, table: Table) -> Result<()> {
+        self.import_table(TableWrapper::new(table))
+    }
 
-        tables
-            .push(TableWrapper::new(table))
-            .map_err(|_| Error::capacity_limit_exceeded("Table capacity exceeded"))?;
-        Ok(())
+    /// Add an imported table to this instance
+    ///
+    /// The wrapper is stored as-is, so the table stays shared with the host or
+    /// instance that provided it.
+    pub fn import_table(&mut self, table: TableWrapper) -> Result<()> {
+        push_item(
+            &mut self.tables,
+            table,
+            MAX_TABLE_INSTANCES,
+            "Table capacity exceeded",
+        )
     }
 
     /// Add a global to this instance
-    pub fn add_global(&self, global: Global) -> Result<()> {
-        #[cfg(feature = "std")]
-        let mut globals = self
+    pub fn add_global(&mut self, global: Global) -> Result<()> {
+        self.import_global(GlobalWrapper::new(global))
+    }
+
+    /// Add an imported global to this instance
+    ///
+    /// The wrapper is stored as-is, so the global stays shared with the host
+    /// or instance that provided it.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn import_global(&mut self, global: GlobalWrapper) -> Result<()> {
+        push_item(
+            &mut self.globals,
+            GlobalSlot::new(global),
+            MAX_GLOBAL_INSTANCES,
+            "Global capacity exceeded",
+        )
+    }
+
+    /// Add an imported global to this instance
+    ///
+    /// The wrapper is stored as-is, so the global stays shared with the host
+    /// or instance that provided it.
+    #[cfg(not(any(feature = "std", feature = "alloc")))]
+    pub fn import_global(&mut self, global: GlobalWrapper) -> Result<()> {
+        push_item(
+            &mut *self.globals.lock(),
+            global,
+            MAX_GLOBAL_INSTANCES,
+            "Global capacity exceeded",
+        )
+    }
+
+    /// Element and data segments dropped by the guest
+    #[cfg(feature = "std")]
+    pub(crate) fn dropped_segments(&self) -> &DroppedSegments {
+        &self.dropped
+    }
+
+    /// Designate a mutable `i32`/`i64` global as a host-shared atomic global.
+    ///
+    /// The returned handle may be sent to other threads. Designating the same
+    /// global twice returns the same handle. See [`crate::atomic_global`] for
+    /// the accesses that observe the handle and their visibility guarantees.
+    #[cfg(feature = "std")]
+    pub fn share_global(&self, idx: u32) -> Result<AtomicGlobal> {
+        let mut shared = self
+            .atomic_globals
+            .globals
+            .lock()
+            .map_err(|_| Error::runtime_error("Failed to lock atomic globals"))?;
+
+        if let Some((_, atomic)) = shared.iter().find(|(i, _)| *i == idx) {
+            return Ok(atomic.clone());
+        }
+
+        let global = self.global_definition(idx)?;
+        let atomic = AtomicGlobal::from_global(global.inner())?;
+        shared.push((idx, atomic.clone()));
+        self.atomic_globals.designated.store(true, Ordering::Release);
+        Ok(atomic)
+    }
+
+    /// Get the host-shared handle of a global, if it has been designated
+    #[cfg(feature = "std")]
+    pub fn atomic_global(&self, idx: u32) -> Result<Option<AtomicGlobal>> {
+        if !self.atomic_globals.designated.load(Ordering::Acquire) {
+            return Ok(None);
+        }
+        let shared = self
+            .atomic_globals
             .globals
             .lock()
-            .map_err(|_| Error::runtime_error("Failed to lock globals"))?;
+            .map_err(|_| Error::runtime_error("Failed to lock atomic globals"))?;
+        Ok(shared.iter().find(|(i, _)| *i == idx).map(|(_, atomic)| atomic.clone()))
+    }
+
+    /// Read the value of a global.
+    ///
+    /// Designated atomic globals are read from their shared cell. This is the
+    /// single read path of the runtime, which an interpreter's `global.get`
+    /// must use as well.
+    pub fn global_value(&self, idx: u32) -> Result<wrt_foundation::values::Value> {
+        #[cfg(feature = "std")]
+        if let Some(atomic) = self.atomic_global(idx)? {
+            return Ok(atomic.load());
+        }
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        if let Some(slot) = self.globals.get(idx as usize) {
+            return Ok(slot.get());
+        }
+        self.global_definition(idx)?.get()
+    }
 
-        #[cfg(not(feature = "std"))]
+    /// Write the value of a global.
+    ///
+    /// Designated atomic globals are written to their shared cell, other
+    /// mutable globals to the instance's cell, which its clones share. A
+    /// mutable global imported from elsewhere must be designated atomic for
+    /// its exporter to observe writes.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    pub fn set_global_value(&self, idx: u32, value: wrt_foundation::values::Value) -> Result<()> {
+        #[cfg(feature = "std")]
+        if let Some(atomic) = self.atomic_global(idx)? {
+            return atomic.store(&value);
+        }
+
+        self.globals
+            .get(idx as usize)
+            .ok_or_else(|| Error::resource_global_not_found("Global index out of bounds"))?
+            .set(&value)
+    }
+
+    /// Write the value of a global.
+    ///
+    /// Globals sit behind `Arc`, so the instance's entry is replaced by an
+    /// updated copy; a mutable global imported from elsewhere is not written
+    /// through to its exporter.
+    #[cfg(not(any(feature = "std", feature = "alloc")))]
+    pub fn set_global_value(&self, idx: u32, value: wrt_foundation::values::Value) -> Result<()> {
         let mut globals = self.globals.lock();
 
+        let current = globals
+            .get(idx as usize)
+            .map_err(|_| Error::resource_global_not_found("Global index out of bounds"))?;
+        let mut global = (*current.0).clone();
+        global.set(&value)?;
         globals
-            .push(GlobalWrapper::new(global))
-            .map_err(|_| Error::capacity_limit_exceeded("Global capacity exceeded"))?;
+            .set(idx as usize, GlobalWrapper::new(global))
+            .map_err(|_| Error::resource_global_not_found("Global index out of bounds"))?;
         Ok(())
     }
 
+    /// Look up an exported global by name, to read and write it with its
+    /// value type
+    pub fn get_global(&self, name: &str) -> Result<Option<InstanceGlobal<'_>>> {
+        let Some(export) = self.module.get_export(name) else {
+            return Ok(None);
+        };
+        if export.kind != ExportKind::Global {
+            return Err(Error::runtime_type_mismatch("Export is not a global"));
+        }
+
+        let global = self.global_definition(export.index)?;
+        let ty = global.0.global_type_descriptor().clone();
+        Ok(Some(InstanceGlobal::new(self, export.index, ty)))
+    }
+
+    /// Resolve a global by index, falling back to the module's definition
+    /// when the instance has not materialized its own globals
+    fn global_definition(&self, idx: u32) -> Result<GlobalWrapper> {
+        self.global(idx).or_else(|_| {
+            self.module
+                .globals
+                .get(idx as usize)
+                .map_err(|_| Error::resource_global_not_found("Global index out of bounds"))
+        })
+    }
+
+    /// Number of memories, tables and globals of the instance, imported ones
+    /// included
+    pub(crate) fn item_counts(&self) -> (usize, usize, usize) {
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        let globals = self.globals.len();
+        #[cfg(not(any(feature = "std", feature = "alloc")))]
+        let globals = self.globals.lock().len();
+        (self.memories.len(), self.tables.len(), globals)
+    }
+
+    /// Look up an export by name, with its full type
+    pub fn get_export(&self, name: &str) -> Result<Option<Extern>> {
+        match self.module.get_export(name) {
+            Some(export) => self.resolve_export(&export).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Resolve an export of the module to the item of this instance it
+    /// refers to
+    pub fn resolve_export(&self, export: &Export) -> Result<Extern> {
+        let index = export.index;
+        Ok(match export.kind {
+            ExportKind::Function => Extern::Func(FuncExport {
+                index,
+                ty: self.module.get_function_signature(index).ok_or_else(|| {
+                    Error::runtime_function_not_found("Exported function not found")
+                })?,
+            }),
+            ExportKind::Memory => {
+                let memory = self.memory(index).or_else(|_| {
+                    self.module
+                        .memories
+                        .get(index as usize)
+                        .map_err(|_| Error::memory_not_found("Exported memory not found"))
+                })?;
+                Extern::Memory(MemoryExport {
+                    index,
+                    ty: memory.ty(),
+                    memory,
+                })
+            },
+            ExportKind::Table => {
+                let table = self.table(index).or_else(|_| {
+                    self.module
+                        .tables
+                        .get(index as usize)
+                        .map_err(|_| Error::resource_table_not_found("Exported table not found"))
+                })?;
+                Extern::Table(TableExport {
+                    index,
+                    ty: table.0.ty.clone(),
+                    table,
+                })
+            },
+            ExportKind::Global => {
+                let global = self.global_definition(index)?;
+                Extern::Global(GlobalExport {
+                    index,
+                    ty: global.0.global_type_descriptor().clone(),
+                    global,
+                })
+            },
+        })
+    }
+
     /// Initialize debug information for this instance
     #[cfg(feature = "debug")]
     pub fn init_debug_info(&mut self, module_bytes: &'static [u8]) -> Result<()> {
@@ -418,89 +912,50 @@ fn validate_function_index(&self, function_index: u32) -> Result<()> {
 impl Default for ModuleInstance {
     fn default() -> Self {
         // Create a default module instance with a default module
-        let default_module = Module::default();
-        // Default implementation must succeed for basic functionality
-        // Use minimal memory allocation that should always work
-        match Self::new(default_module, 0) {
-            Ok(instance) => instance,
-            Err(_) => {
-                // Create minimal instance using RuntimeProvider for type consistency
-                // This maintains controllability while avoiding allocation failures
-                use crate::bounded_runtime_infra::create_runtime_provider;
-                // Use the factory function - if this fails, we have a fundamental system issue
-                let runtime_provider = match create_runtime_provider() {
-                    Ok(provider) => provider,
-                    Err(_) => {
-                        // Last resort: try to create a minimal provider
-                        // This should work even in constrained environments
-                        match create_runtime_provider() {
-                            Ok(provider) => provider,
-                            Err(_) => {
-                                // System is in unrecoverable state - but we must return something
-                                // Create an invalid instance that will fail safely later
-                                return Self {
-                                    module: Arc::new(Module::default()),
-                                    memories: Arc::new(Mutex::new(Default::default())),
-                                    tables: Arc::new(Mutex::new(Default::default())),
-                                    globals: Arc::new(Mutex::new(Default::default())),
-                                    instance_id: 0,
-                                    imports: Default::default(),
-                                    #[cfg(feature = "debug")]
-                                    debug_info: None,
-                                };
-                            },
-                        }
-                    },
-                };
-                Self {
-                    module: Arc::new(Module::default()),
-                    memories: Arc::new(Mutex::new(
-                        // Try to create with RuntimeProvider, fallback to empty vector creation
-                        wrt_foundation::bounded::BoundedVec::new(runtime_provider.clone())
-                            .unwrap_or_else(|_| {
-                                // Last resort: try creating another provider
-                                let fallback_provider = create_runtime_provider()
-                                    .expect("Failed to create fallback runtime provider");
-                                wrt_foundation::bounded::BoundedVec::new(fallback_provider)
-                                    .expect("Failed to create even minimal memory vector")
-                            }),
-                    )),
-                    tables: Arc::new(Mutex::new(
-                        wrt_foundation::bounded::BoundedVec::new(runtime_provider.clone())
-                            .unwrap_or_else(|_| {
-                                let fallback_provider = create_runtime_provider()
-                                    .expect("Failed to create fallback runtime provider");
-                                wrt_foundation::bounded::BoundedVec::new(fallback_provider)
-                                    .expect("Failed to create even minimal table vector")
-                            }),
-                    )),
-                    globals: Arc::new(Mutex::new(
-                        wrt_foundation::bounded::BoundedVec::new(runtime_provider).unwrap_or_else(
-                            |_| {
-                                let fallback_provider = create_runtime_provider()
-                                    .expect("Failed to create fallback runtime provider");
-                                wrt_foundation::bounded::BoundedVec::new(fallback_provider)
-                                    .expect("Failed to create even minimal global vector")
-                            },
-                        ),
-                    )),
-                    instance_id: 0,
-                    imports: Default::default(),
-                    #[cfg(feature = "debug")]
-                    debug_info: None,
-                }
-            },
+        if let Ok(instance) = Self::new(Module::default(), 0) {
+            return instance;
         }
+
+        // Without a runtime provider, fall back to the default item
+        // collections, which fail safely once used
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        let globals = Vec::new();
+        #[cfg(not(any(feature = "std", feature = "alloc")))]
+        let globals = Mutex::new(Default::default());
+        Self::with_items(
+            Arc::new(Module::default()),
+            0,
+            Default::default(),
+            Default::default(),
+            globals,
+        )
     }
 }
 
+/// Clones share the memories, tables and global values of the instance
 impl Clone for ModuleInstance {
     fn clone(&self) -> Self {
-        // Create a new instance with the same module and instance ID
-        Self::new((*self.module).clone(), self.instance_id).unwrap_or_else(|_| {
-            // Fallback implementation if allocation fails
-            Self::default()
-        })
+        #[cfg(any(feature = "std", feature = "alloc"))]
+        let globals = self.globals.clone();
+        #[cfg(not(any(feature = "std", feature = "alloc")))]
+        let globals = Mutex::new(self.globals.lock().clone());
+
+        #[allow(unused_mut)]
+        let mut instance = Self::with_items(
+            self.module.clone(),
+            self.instance_id,
+            self.memories.clone(),
+            self.tables.clone(),
+            globals,
+        );
+        instance.imports = self.imports.clone();
+        // Clones must observe the same host-shared globals
+        #[cfg(feature = "std")]
+        {
+            instance.atomic_globals = Arc::clone(&self.atomic_globals);
+            instance.dropped = Arc::clone(&self.dropped);
+        }
+        instance
     }
 }
 
@@ -533,24 +988,10 @@ fn update_checksum(&self, checksum: &mut Checksum) {
         }
 
         // Include counts of resources for uniqueness
-        #[cfg(feature = "std")]
-        let memories_count = self.memories.lock().map_or(0, |m| m.len()) as u32;
-        #[cfg(not(feature = "std"))]
-        let memories_count = self.memories.lock().len() as u32;
-
-        #[cfg(feature = "std")]
-        let tables_count = self.tables.lock().map_or(0, |t| t.len()) as u32;
-        #[cfg(not(feature = "std"))]
-        let tables_count = self.tables.lock().len() as u32;
-
-        #[cfg(feature = "std")]
-        let globals_count = self.globals.lock().map_or(0, |g| g.len()) as u32;
-        #[cfg(not(feature = "std"))]
-        let globals_count = self.globals.lock().len() as u32;
-
-        checksum.update_slice(&memories_count.to_le_bytes());
-        checksum.update_slice(&tables_count.to_le_bytes());
-        checksum.update_slice(&globals_count.to_le_bytes());
+        let (memories_count, tables_count, globals_count) = self.item_counts();
+        checksum.update_slice(&(memories_count as u32).to_le_bytes());
+        checksum.update_slice(&(tables_count as u32).to_le_bytes());
+        checksum.update_slice(&(globals_count as u32).to_le_bytes());
     }
 }
 
@@ -570,24 +1011,10 @@ fn to_bytes_with_provider<'a, PStream: wrt_foundation::MemoryProvider>(
         writer.write_all(&self.instance_id.to_le_bytes())?;
 
         // Write resource counts
-        #[cfg(feature = "std")]
-        let memories_count = self.memories.lock().map_or(0, |m| m.len()) as u32;
-        #[cfg(not(feature = "std"))]
-        let memories_count = self.memories.lock().len() as u32;
-
-        #[cfg(feature = "std")]
-        let tables_count = self.tables.lock().map_or(0, |t| t.len()) as u32;
-        #[cfg(not(feature = "std"))]
-        let tables_count = self.tables.lock().len() as u32;
-
-        #[cfg(feature = "std")]
-        let globals_count = self.globals.lock().map_or(0, |g| g.len()) as u32;
-        #[cfg(not(feature = "std"))]
-        let globals_count = self.globals.lock().len() as u32;
-
-        writer.write_all(&memories_count.to_le_bytes())?;
-        writer.write_all(&tables_count.to_le_bytes())?;
-        writer.write_all(&globals_count.to_le_bytes())?;
+        let (memories_count, tables_count, globals_count) = self.item_counts();
+        writer.write_all(&(memories_count as u32).to_le_bytes())?;
+        writer.write_all(&(tables_count as u32).to_le_bytes())?;
+        writer.write_all(&(globals_count as u32).to_le_bytes())?;
 
         // Write module name (simplified)
         if let Some(name) = self.module.name.as_ref() {
@@ -659,3 +1086,188 @@ fn from_bytes_with_provider<'a, PStream: wrt_foundation::MemoryProvider>(
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use wrt_foundation::{
+        types::Limits,
+        values::Value,
+        ValueType,
+    };
+
+    use super::*;
+    use crate::prelude::CoreMemoryType;
+
+    #[test]
+    fn test_clones_share_items_and_global_values() {
+        let mut instance = ModuleInstance::new(Module::default(), 0).unwrap();
+        let memory = Memory::new(CoreMemoryType {
+            limits: Limits::new(1, Some(2)),
+            shared: false,
+        })
+        .unwrap();
+        instance.add_memory(memory).unwrap();
+        instance
+            .add_global(Global::new(ValueType::I32, true, Value::I32(1)).unwrap())
+            .unwrap();
+        instance
+            .add_global(Global::new(ValueType::I64, false, Value::I64(2)).unwrap())
+            .unwrap();
+        let clone = instance.clone();
+
+        assert_eq!(clone.item_counts(), (1, 0, 2));
+        assert!(instance.memory_ref(0).unwrap().same_memory(clone.memory_ref(0).unwrap()));
+
+        instance.set_global_value(0, Value::I32(5)).unwrap();
+        assert_eq!(clone.global_value(0).unwrap(), Value::I32(5));
+        assert_eq!(clone.global(0).unwrap().get().unwrap(), Value::I32(5));
+        assert!(instance.set_global_value(0, Value::I64(5)).is_err());
+        assert!(instance.set_global_value(1, Value::I64(3)).is_err());
+        assert_eq!(instance.global_value(1).unwrap(), Value::I64(2));
+    }
+
+    #[cfg(feature = "wat")]
+    #[test]
+    fn test_resolves_exports_with_their_types() -> Result<()> {
+        use wrt_foundation::types::{
+            GlobalType,
+            RefType,
+            TableType,
+        };
+
+        let mut module = Module::from_wat(
+            r#"(module
+                (memory (export "mem") 1 2)
+                (table (export "tab") 2 funcref)
+                (global (export "counter") (mut i32) (i32.const 7))
+                (global (export "limit") i64 (i64.const -1)))"#,
+        )?;
+        // Function bodies cannot be lowered yet, so the function is declared
+        // without one
+        module.add_type(WrtFuncType::new(
+            create_runtime_provider()?,
+            [ValueType::I32, ValueType::I64],
+            [ValueType::F32],
+        )?)?;
+        module.add_function_type(0)?;
+        module.add_export_func("add", 0)?;
+        let instance = ModuleInstance::new(module, 0)?;
+
+        let func = instance.get_export("add")?.and_then(Extern::into_func).unwrap();
+        assert_eq!(func.index, 0);
+        assert_eq!(
+            func.ty.params.iter().collect::<Vec<_>>(),
+            [ValueType::I32, ValueType::I64]
+        );
+        assert_eq!(func.ty.results.iter().collect::<Vec<_>>(), [ValueType::F32]);
+
+        let memory = instance.get_export("mem")?.and_then(Extern::into_memory).unwrap();
+        assert_eq!(
+            memory.ty,
+            CoreMemoryType {
+                limits: Limits::new(1, Some(2)),
+                shared: false,
+            }
+        );
+
+        let table = instance.get_export("tab")?.and_then(Extern::into_table).unwrap();
+        assert_eq!(
+            table.ty,
+            TableType {
+                element_type: RefType::Funcref,
+                limits:       Limits::new(2, None),
+            }
+        );
+
+        let counter = instance.get_export("counter")?.and_then(Extern::into_global).unwrap();
+        assert_eq!(counter.ty, GlobalType::new(ValueType::I32, true));
+        assert_eq!(counter.global.0.get(), &Value::I32(7));
+        let limit = instance.get_export("limit")?.and_then(Extern::into_global).unwrap();
+        assert_eq!(
+            (limit.index, limit.ty),
+            (1, GlobalType::new(ValueType::I64, false))
+        );
+        assert_eq!(limit.global.0.get(), &Value::I64(-1));
+
+        assert!(instance.get_export("missing")?.is_none());
+        let mut kinds = Vec::new();
+        for export in instance.module().exports.values() {
+            kinds.push(instance.resolve_export(&export)?.kind());
+        }
+        kinds.sort_by_key(|kind| *kind as u8);
+        assert_eq!(
+            kinds,
+            [
+                ExportKind::Function,
+                ExportKind::Table,
+                ExportKind::Memory,
+                ExportKind::Global,
+                ExportKind::Global,
+            ]
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "wat")]
+    #[test]
+    fn test_reads_and_writes_globals_with_their_types() -> Result<()> {
+        use wrt_foundation::types::GlobalType;
+
+        let module = Module::from_wat(
+            r#"(module
+                (memory (export "mem") 1)
+                (global (export "counter") (mut i32) (i32.const 7))
+                (global (export "scale") (mut f32) (f32.const 0.5))
+                (global (export "limit") i64 (i64.const -1)))"#,
+        )?;
+        let mut instance = ModuleInstance::new(module.clone(), 0)?;
+        for global in module.globals.iter() {
+            instance.add_global((*global.0).clone())?;
+        }
+
+        let counter = instance.get_global("counter")?.unwrap();
+        assert_eq!(counter.index(), 0);
+        assert_eq!(counter.ty(), &GlobalType::new(ValueType::I32, true));
+        assert!(counter.is_mutable());
+        assert_eq!(counter.get::<i32>()?, 7);
+        counter.set(-3i32)?;
+        assert_eq!(counter.get::<i32>()?, -3);
+        assert_eq!(instance.global_value(0)?, Value::I32(-3));
+
+        // A value of another type leaves the global as it was
+        assert!(counter.set(1i64).is_err());
+        assert!(counter.set_value(Value::I64(1)).is_err());
+        assert!(counter.get::<f32>().is_err());
+        assert_eq!(counter.get_value()?, Value::I32(-3));
+
+        let scale = instance.get_global("scale")?.unwrap();
+        scale.set(2.25f32)?;
+        assert_eq!(scale.get::<f32>()?, 2.25);
+
+        let limit = instance.get_global("limit")?.unwrap();
+        assert!(!limit.is_mutable());
+        assert!(limit.set(5i64).is_err());
+        assert!(limit.set_value(Value::I64(5)).is_err());
+        assert_eq!(limit.get::<i64>()?, -1);
+
+        assert!(instance.get_global("mem").is_err());
+        assert!(instance.get_global("missing")?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_global_values_round_trip() {
+        use crate::externs::GlobalValue;
+
+        assert_eq!(i32::from_value(&7i32.into_value()), Some(7));
+        assert_eq!(i64::from_value(&(-7i64).into_value()), Some(-7));
+        assert_eq!(f32::from_value(&1.5f32.into_value()), Some(1.5));
+        assert_eq!(f64::from_value(&(-0.25f64).into_value()), Some(-0.25));
+        assert_eq!(i64::from_value(&Value::I32(7)), None);
+        assert_eq!(
+            f64::from_value(&Value::F32(FloatBits32::from_float(1.5))),
+            None
+        );
+        assert_eq!(<f32 as GlobalValue>::VALUE_TYPE, ValueType::F32);
+    }
+}
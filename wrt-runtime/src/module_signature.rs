@@ -0,0 +1,354 @@
+//! Module signatures
+//!
+//! Devices receiving modules over the air must only run modules from
+//! publishers they trust. A publisher signs a module by appending a custom
+//! section named [`SIGNATURE_SECTION_NAME`] holding signatures over all bytes
+//! of the module before that section. Embedders list the keys they trust in a
+//! [`SignaturePolicy`] and load modules with
+//! [`Module::load_from_signed_binary`](crate::module::Module::load_from_signed_binary),
+//! which refuses a module whose signatures no trusted key verifies and, in
+//! strict mode, a module without signatures.
+//!
+//! Signature schemes are pluggable through [`SignatureVerifier`]. Ed25519 is
+//! provided by [`Ed25519Key`] with the `module-signing` feature.
+//!
+//! The section holds a vector of signatures, each naming the key it was made
+//! with:
+//!
+//! ```text
+//! signatures ::= vec(signature)
+//! signature  ::= key_id:name  bytes:vec(byte)
+//! ```
+
+use std::{
+    fmt,
+    string::String,
+    sync::Arc,
+    vec::Vec,
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_format::binary::{
+    read_leb128_u32,
+    with_alloc::write_leb128_u32,
+    CUSTOM_SECTION_ID,
+    WASM_MAGIC,
+};
+
+/// Name of the custom section carrying the signatures of a module
+pub const SIGNATURE_SECTION_NAME: &str = "wrt.signature";
+
+/// Size of the magic number and version preceding the sections of a module
+const HEADER_SIZE: usize = 8;
+
+/// Verifies signatures made with one key
+pub trait SignatureVerifier: Send + Sync {
+    /// Whether `signature` is a valid signature of `message` by this key
+    fn verify(&self, message: &[u8], signature: &[u8]) -> bool;
+}
+
+/// Ed25519 public key
+#[cfg(feature = "module-signing")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ed25519Key(ed25519_dalek::VerifyingKey);
+
+#[cfg(feature = "module-signing")]
+impl Ed25519Key {
+    /// Key from its 32-byte compressed encoding
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes do not encode a point of the curve.
+    pub fn from_bytes(bytes: &[u8; 32]) -> Result<Self> {
+        ed25519_dalek::VerifyingKey::from_bytes(bytes)
+            .map(Self)
+            .map_err(|_| Error::validation_error("Invalid Ed25519 public key"))
+    }
+}
+
+#[cfg(feature = "module-signing")]
+impl SignatureVerifier for Ed25519Key {
+    fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        ed25519_dalek::Signature::from_slice(signature)
+            .is_ok_and(|signature| self.0.verify_strict(message, &signature).is_ok())
+    }
+}
+
+/// Signature of a module by one key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleSignature {
+    /// Id of the key the signature was made with
+    pub key_id: String,
+    /// Signature bytes, in the format of the key's scheme
+    pub bytes:  Vec<u8>,
+}
+
+/// Signatures of a module and the bytes they sign
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedModule<'a> {
+    /// Module bytes preceding the signature section
+    pub message:    &'a [u8],
+    /// Signatures found in the signature section
+    pub signatures: Vec<ModuleSignature>,
+}
+
+impl<'a> SignedModule<'a> {
+    /// Signatures of a module binary, or `None` if it is unsigned
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the binary is malformed, the signature section is
+    /// malformed, or a section follows the signature section.
+    pub fn parse(binary: &'a [u8]) -> Result<Option<Self>> {
+        if binary.len() < HEADER_SIZE || binary[..4] != WASM_MAGIC {
+            return Err(Error::parse_error("Invalid WebAssembly module header"));
+        }
+
+        let mut offset = HEADER_SIZE;
+        while offset < binary.len() {
+            let (size, consumed) = read_leb128_u32(binary, offset + 1)?;
+            let start = offset + 1 + consumed;
+            let end = start
+                .checked_add(size as usize)
+                .filter(|end| *end <= binary.len())
+                .ok_or_else(|| Error::parse_error("Section exceeds module size"))?;
+
+            if binary[offset] == CUSTOM_SECTION_ID {
+                let (name, signatures_start) = read_bytes(binary, start, end)?;
+                if name == SIGNATURE_SECTION_NAME.as_bytes() {
+                    if end != binary.len() {
+                        return Err(Error::validation_error(
+                            "Signature section is not the last section of the module",
+                        ));
+                    }
+                    return Ok(Some(Self {
+                        message:    &binary[..offset],
+                        signatures: parse_signatures(binary, signatures_start, end)?,
+                    }));
+                }
+            }
+            offset = end;
+        }
+
+        Ok(None)
+    }
+}
+
+/// Read a byte vector starting at `offset`, returning it and the offset
+/// following it
+fn read_bytes(binary: &[u8], offset: usize, end: usize) -> Result<(&[u8], usize)> {
+    let (len, consumed) = read_leb128_u32(&binary[..end], offset)?;
+    let start = offset + consumed;
+    let stop = start
+        .checked_add(len as usize)
+        .filter(|stop| *stop <= end)
+        .ok_or_else(|| Error::parse_error("Byte vector exceeds section size"))?;
+    Ok((&binary[start..stop], stop))
+}
+
+fn parse_signatures(binary: &[u8], mut offset: usize, end: usize) -> Result<Vec<ModuleSignature>> {
+    let (count, consumed) = read_leb128_u32(&binary[..end], offset)?;
+    offset += consumed;
+
+    let mut signatures = Vec::new();
+    for _ in 0..count {
+        let (key_id, next) = read_bytes(binary, offset, end)?;
+        let (bytes, next) = read_bytes(binary, next, end)?;
+        let key_id = core::str::from_utf8(key_id)
+            .map_err(|_| Error::parse_error("Invalid UTF-8 in signature key id"))?;
+        signatures.push(ModuleSignature {
+            key_id: key_id.into(),
+            bytes:  bytes.to_vec(),
+        });
+        offset = next;
+    }
+    if offset != end {
+        return Err(Error::parse_error(
+            "Unexpected bytes after module signatures",
+        ));
+    }
+    Ok(signatures)
+}
+
+/// Encode a signature section to append to the module `signatures` sign
+pub fn encode_signature_section(signatures: &[ModuleSignature]) -> Vec<u8> {
+    fn push_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+        out.extend_from_slice(&write_leb128_u32(bytes.len() as u32));
+        out.extend_from_slice(bytes);
+    }
+
+    let mut contents = Vec::new();
+    push_bytes(&mut contents, SIGNATURE_SECTION_NAME.as_bytes());
+    contents.extend_from_slice(&write_leb128_u32(signatures.len() as u32));
+    for signature in signatures {
+        push_bytes(&mut contents, signature.key_id.as_bytes());
+        push_bytes(&mut contents, &signature.bytes);
+    }
+
+    let mut section = vec![CUSTOM_SECTION_ID];
+    push_bytes(&mut section, &contents);
+    section
+}
+
+/// How a module passed a [`SignaturePolicy`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureStatus {
+    /// The module is unsigned, which the policy allows
+    Unsigned,
+    /// The module carries a signature by a trusted key
+    Verified {
+        /// Id of the key that verified the signature
+        key_id: String,
+    },
+}
+
+/// Keys trusted to sign modules and whether modules must be signed
+#[derive(Clone, Default)]
+pub struct SignaturePolicy {
+    keys:   Vec<(String, Arc<dyn SignatureVerifier>)>,
+    strict: bool,
+}
+
+impl SignaturePolicy {
+    /// Policy trusting no keys and accepting unsigned modules
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trust signatures naming `key_id` that `key` verifies
+    pub fn with_trusted_key(mut self, key_id: &str, key: impl SignatureVerifier + 'static) -> Self {
+        self.keys.push((key_id.into(), Arc::new(key)));
+        self
+    }
+
+    /// Whether unsigned modules are refused
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Whether unsigned modules are refused
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Check a module binary against the policy
+    ///
+    /// A signed module passes if any of its signatures is verified by a
+    /// trusted key with the id it names, whatever its other signatures.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the module is signed but no trusted key verifies
+    /// any of its signatures, if it is unsigned and the policy is strict, or
+    /// if its signature section is malformed.
+    pub fn verify(&self, binary: &[u8]) -> Result<SignatureStatus> {
+        let Some(signed) = SignedModule::parse(binary)? else {
+            if self.strict {
+                return Err(Error::validation_error("Module is not signed"));
+            }
+            return Ok(SignatureStatus::Unsigned);
+        };
+
+        for signature in &signed.signatures {
+            let verified = self.keys.iter().any(|(key_id, key)| {
+                *key_id == signature.key_id && key.verify(signed.message, &signature.bytes)
+            });
+            if verified {
+                return Ok(SignatureStatus::Verified {
+                    key_id: signature.key_id.clone(),
+                });
+            }
+        }
+        Err(Error::validation_error(
+            "Module signature is not valid for any trusted key",
+        ))
+    }
+}
+
+impl fmt::Debug for SignaturePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SignaturePolicy")
+            .field(
+                "keys",
+                &self.keys.iter().map(|(key_id, _)| key_id).collect::<Vec<_>>(),
+            )
+            .field("strict", &self.strict)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// "Signs" by appending the key byte to the length of the message
+    struct LengthKey(u8);
+
+    impl SignatureVerifier for LengthKey {
+        fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+            signature == [message.len() as u8, self.0]
+        }
+    }
+
+    const MODULE: &[u8] = &[0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00];
+
+    fn signed(signatures: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let signatures: Vec<_> = signatures
+            .iter()
+            .map(|(key_id, bytes)| ModuleSignature {
+                key_id: (*key_id).into(),
+                bytes:  bytes.clone(),
+            })
+            .collect();
+        [MODULE, &encode_signature_section(&signatures)].concat()
+    }
+
+    #[test]
+    fn test_policy_checks_signatures() {
+        let policy = SignaturePolicy::new().with_trusted_key("device", LengthKey(7));
+
+        assert_eq!(policy.verify(MODULE).unwrap(), SignatureStatus::Unsigned);
+        assert!(policy.clone().with_strict(true).verify(MODULE).is_err());
+
+        let binary = signed(&[("other", vec![8, 7]), ("device", vec![8, 7])]);
+        assert_eq!(
+            policy.verify(&binary).unwrap(),
+            SignatureStatus::Verified {
+                key_id: "device".into(),
+            }
+        );
+
+        // Wrong signature, and the right one under an untrusted key id
+        assert!(policy.verify(&signed(&[("device", vec![8, 6])])).is_err());
+        assert!(policy.verify(&signed(&[("other", vec![8, 7])])).is_err());
+
+        // Bytes after the signature section are not signed
+        let mut appended = binary.clone();
+        appended.extend_from_slice(&[0, 1, 0]);
+        assert!(policy.verify(&appended).is_err());
+    }
+
+    #[cfg(feature = "module-signing")]
+    #[test]
+    fn test_ed25519_signatures() {
+        use ed25519_dalek::{
+            Signer,
+            SigningKey,
+        };
+
+        let signing_key = SigningKey::from_bytes(&[42; 32]);
+        let key = Ed25519Key::from_bytes(signing_key.verifying_key().as_bytes()).unwrap();
+        let policy = SignaturePolicy::new().with_trusted_key("ota", key).with_strict(true);
+
+        let signature = signing_key.sign(MODULE).to_bytes().to_vec();
+        assert!(policy.verify(&signed(&[("ota", signature.clone())])).is_ok());
+
+        let mut tampered = signed(&[("ota", signature)]);
+        tampered[4] = 2;
+        assert!(policy.verify(&tampered).is_err());
+    }
+}
@@ -0,0 +1,148 @@
+//! Parallel validation and lowering of function bodies
+//!
+//! Validating and compiling function bodies dominates the load time of large
+//! modules, and every body is lowered on its own against the module context.
+//! The bodies are therefore split into contiguous runs, one per worker thread,
+//! and each worker lowers its run in order and stops at its first error.
+//! Joining the runs in order yields the functions, and the first error, exactly
+//! as lowering them one by one on the calling thread would.
+
+use core::num::NonZeroUsize;
+use std::thread;
+
+use wrt_format::module::Function as FormatFunction;
+
+use crate::{
+    prelude::*,
+    stackless::code::{
+        FunctionCode,
+        ModuleCode,
+    },
+};
+
+/// Configuration for lowering function bodies in parallel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParallelLoweringConfig {
+    /// Maximum number of worker threads
+    pub threads:       NonZeroUsize,
+    /// Modules with fewer bytes of code than this are lowered on the calling
+    /// thread, as spawning workers would cost more than it saves
+    pub min_code_size: usize,
+}
+
+impl Default for ParallelLoweringConfig {
+    fn default() -> Self {
+        Self {
+            threads:       thread::available_parallelism().unwrap_or(NonZeroUsize::MIN),
+            min_code_size: 64 * 1024,
+        }
+    }
+}
+
+/// Validate and compile a single function body against the module `context`
+pub(crate) fn lower_function(func: &FormatFunction, context: &ModuleCode) -> Result<FunctionCode> {
+    context.compile_function(func)
+}
+
+/// Validate and compile `functions` against the module `context`, spreading
+/// them over worker threads as `config` allows
+///
+/// # Errors
+///
+/// Returns the error of the first function, in index order, that fails to
+/// lower.
+pub fn lower_functions(
+    functions: &[FormatFunction],
+    context: &ModuleCode,
+    config: &ParallelLoweringConfig,
+) -> Result<Vec<FunctionCode>> {
+    let lower = |func| lower_function(func, context);
+    let code_size: usize = functions.iter().map(|func| func.code.len()).sum();
+    let threads = config.threads.get().min(functions.len());
+    if threads <= 1 || code_size < config.min_code_size {
+        return functions.iter().map(lower).collect();
+    }
+
+    let run_len = functions.len().div_ceil(threads);
+    let runs: Vec<Result<Vec<FunctionCode>>> = thread::scope(|scope| {
+        let workers: Vec<_> = functions
+            .chunks(run_len)
+            .map(|run| scope.spawn(move || run.iter().map(lower).collect()))
+            .collect();
+
+        workers
+            .into_iter()
+            .map(|worker| {
+                worker.join().unwrap_or_else(|_| {
+                    Err(Error::runtime_execution_error(
+                        "Function lowering thread panicked",
+                    ))
+                })
+            })
+            .collect()
+    });
+
+    let mut lowered = Vec::with_capacity(functions.len());
+    for run in runs {
+        lowered.extend(run?);
+    }
+    Ok(lowered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Module context with the single type `[] -> []`
+    fn context() -> ModuleCode {
+        let mut module = wrt_format::module::Module::new();
+        module.types.push(wrt_foundation::CleanCoreFuncType {
+            params:  Vec::new(),
+            results: Vec::new(),
+        });
+        ModuleCode::context(&module).unwrap()
+    }
+
+    fn function(code: &[u8]) -> FormatFunction {
+        FormatFunction {
+            type_idx: 0,
+            locals:   Vec::new(),
+            code:     code.to_vec(),
+        }
+    }
+
+    fn forced(threads: usize) -> ParallelLoweringConfig {
+        ParallelLoweringConfig {
+            threads:       NonZeroUsize::new(threads).unwrap(),
+            min_code_size: 0,
+        }
+    }
+
+    #[test]
+    fn test_parallel_matches_sequential() {
+        // No locals, i32.const n, drop, end
+        let functions: Vec<_> = (0..9u8).map(|n| function(&[0x00, 0x41, n, 0x1A, 0x0B])).collect();
+        let context = context();
+
+        let sequential = lower_functions(&functions, &context, &forced(1)).unwrap();
+        let parallel = lower_functions(&functions, &context, &forced(4)).unwrap();
+        assert_eq!(parallel.len(), 9);
+        assert_eq!(parallel, sequential);
+    }
+
+    #[test]
+    fn test_first_error_in_function_order() {
+        let mut functions: Vec<_> = (0..8).map(|_| function(&[0x00, 0x01, 0x0B])).collect();
+        // Truncated i32.const in function 5, unknown opcode in function 2
+        functions[5] = function(&[0x00, 0x41]);
+        functions[2] = function(&[0x00, 0xFF, 0x0B]);
+        let context = context();
+
+        let expected = lower_functions(&functions, &context, &forced(1)).unwrap_err();
+        assert_eq!(expected.message, "Unknown instruction");
+        for threads in 2..=8 {
+            let err = lower_functions(&functions, &context, &forced(threads)).unwrap_err();
+            assert_eq!((err.code, err.message), (expected.code, expected.message));
+        }
+    }
+}
@@ -541,25 +541,7 @@ fn extract_return_values(
 
     /// Get current timestamp for performance tracking
     fn get_timestamp(&self) -> u64 {
-        // Platform-specific timestamp implementation
-        #[cfg(feature = "std")]
-        {
-            use std::time::{
-                SystemTime,
-                UNIX_EPOCH,
-            };
-            SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos() as u64
-        }
-        #[cfg(not(feature = "std"))]
-        {
-            // Simple counter for no_std environments
-            use core::sync::atomic::{
-                AtomicU64,
-                Ordering,
-            };
-            static COUNTER: AtomicU64 = AtomicU64::new(0);
-            COUNTER.fetch_add(1, Ordering::Relaxed)
-        }
+        crate::time_source::timestamp_ns()
     }
 }
 
@@ -579,7 +561,7 @@ fn test_platform_runtime_creation() {
     #[test]
     fn test_platform_runtime_with_limits() {
         let mut discoverer = PlatformLimitDiscoverer::new();
-        if let Ok(limits) = discoverer.discover_limits() {
+        if let Ok(limits) = discoverer.discover() {
             let runtime = PlatformAwareRuntime::new_with_limits(limits.clone());
             assert!(runtime.is_ok());
 
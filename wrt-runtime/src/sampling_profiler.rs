@@ -0,0 +1,273 @@
+//! Sampling profiler for guest code
+//!
+//! An engine given a [`SamplingProfiler`], see
+//! [`CapabilityAwareEngine::set_sampling_profiler`](crate::engine::CapabilityAwareEngine::set_sampling_profiler),
+//! keeps the guest call stack of the running invocation in it. Each
+//! [`SamplingProfiler::sample`] records the stack as it is at that moment, so
+//! the number of samples of a stack is proportional to the time spent in it.
+//! Samples are taken either from a thread the profiler starts, see
+//! [`SamplingProfiler::start`], or by the embedder, for example from the
+//! thread that advances the epoch of the engine.
+//!
+//! The samples are exported in the folded-stack format read by flamegraph
+//! tools: one line per stack, the frames from the outermost call inward
+//! separated by `;`, followed by the number of samples. Functions are named
+//! from the name section of their module, or `func[index]` without one.
+
+use core::{
+    fmt,
+    sync::atomic::{
+        AtomicU64,
+        Ordering,
+    },
+    time::Duration,
+};
+use std::{
+    sync::{
+        Condvar,
+        Mutex,
+        MutexGuard,
+        PoisonError,
+    },
+    thread::{
+        self,
+        JoinHandle,
+    },
+};
+
+use wrt_decoder::name_section::NameMap;
+use wrt_error::{
+    Error,
+    Result,
+};
+
+use crate::prelude::*;
+
+/// A guest function on the call stack
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GuestFrame {
+    /// Index of the instance the function belongs to
+    pub instance: usize,
+    /// Index of the function in the index space of the instance
+    pub function: u32,
+}
+
+/// Guest call stacks sampled while an engine runs
+#[derive(Debug, Default)]
+pub struct SamplingProfiler {
+    stack:   Mutex<Vec<GuestFrame>>,
+    samples: Mutex<HashMap<Vec<GuestFrame>, u64>>,
+    names:   Mutex<HashMap<usize, Arc<NameMap>>>,
+    /// Samples taken, including those taken while no guest code ran
+    taken:   AtomicU64,
+}
+
+/// A guest call on the stack of a [`SamplingProfiler`], popped on drop
+#[derive(Debug)]
+pub struct ProfiledCall<'a> {
+    profiler: &'a SamplingProfiler,
+}
+
+impl Drop for ProfiledCall<'_> {
+    fn drop(&mut self) {
+        lock(&self.profiler.stack).pop();
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl SamplingProfiler {
+    /// A profiler without samples
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Push a call of `frame` onto the guest call stack until the returned
+    /// guard is dropped, naming the functions of its instance from `names`
+    pub fn enter(&self, frame: GuestFrame, names: Option<&Arc<NameMap>>) -> ProfiledCall<'_> {
+        if let Some(names) = names {
+            lock(&self.names).entry(frame.instance).or_insert_with(|| names.clone());
+        }
+        lock(&self.stack).push(frame);
+        ProfiledCall { profiler: self }
+    }
+
+    /// Record the current guest call stack
+    ///
+    /// A sample taken while no guest code runs is only counted in
+    /// [`Self::samples_taken`].
+    pub fn sample(&self) {
+        self.taken.fetch_add(1, Ordering::Relaxed);
+        let stack = lock(&self.stack).clone();
+        if !stack.is_empty() {
+            *lock(&self.samples).entry(stack).or_insert(0) += 1;
+        }
+    }
+
+    /// Number of samples taken
+    pub fn samples_taken(&self) -> u64 {
+        self.taken.load(Ordering::Relaxed)
+    }
+
+    /// Sampled stacks, outermost call first, with their number of samples
+    pub fn stacks(&self) -> Vec<(Vec<GuestFrame>, u64)> {
+        let mut stacks: Vec<_> = lock(&self.samples)
+            .iter()
+            .map(|(stack, count)| (stack.clone(), *count))
+            .collect();
+        stacks.sort_unstable();
+        stacks
+    }
+
+    /// Forget all samples
+    pub fn reset(&self) {
+        lock(&self.samples).clear();
+        self.taken.store(0, Ordering::Relaxed);
+    }
+
+    /// Write the samples in the folded-stack format
+    pub fn write_folded(&self, out: &mut impl fmt::Write) -> fmt::Result {
+        let names = lock(&self.names).clone();
+        let mut lines: Vec<(String, u64)> = self
+            .stacks()
+            .into_iter()
+            .map(|(stack, count)| {
+                let frames: Vec<String> = stack
+                    .iter()
+                    .map(|frame| match names.get(&frame.instance) {
+                        Some(names) => names.symbolicate(frame.function).to_string(),
+                        None => format!("func[{}]", frame.function),
+                    })
+                    .collect();
+                (frames.join(";"), count)
+            })
+            .collect();
+        // Stacks of different instances may fold into the same line
+        lines.sort();
+        lines.dedup_by(|next, kept| {
+            let same = next.0 == kept.0;
+            if same {
+                kept.1 += next.1;
+            }
+            same
+        });
+        for (stack, count) in lines {
+            writeln!(out, "{stack} {count}")?;
+        }
+        Ok(())
+    }
+
+    /// Take a sample every `interval` from a new thread until the returned
+    /// [`SamplerThread`] is dropped
+    ///
+    /// # Errors
+    ///
+    /// Returns a platform error if the thread cannot be started.
+    pub fn start(self: &Arc<Self>, interval: Duration) -> Result<SamplerThread> {
+        let shutdown = Arc::new((Mutex::new(false), Condvar::new()));
+        let thread = thread::Builder::new()
+            .name("wrt-sampler".into())
+            .spawn({
+                let profiler = self.clone();
+                let shutdown = shutdown.clone();
+                move || {
+                    let (stopped, wake) = &*shutdown;
+                    let mut stopped = lock(stopped);
+                    while !*stopped {
+                        stopped = wake
+                            .wait_timeout(stopped, interval)
+                            .unwrap_or_else(PoisonError::into_inner)
+                            .0;
+                        if !*stopped {
+                            profiler.sample();
+                        }
+                    }
+                }
+            })
+            .map_err(|_| Error::platform_error("Failed to start the sampler thread"))?;
+        Ok(SamplerThread {
+            shutdown,
+            thread: Some(thread),
+        })
+    }
+}
+
+impl fmt::Display for SamplingProfiler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_folded(f)
+    }
+}
+
+/// Thread sampling a [`SamplingProfiler`] at a fixed interval, stopped on
+/// drop
+#[derive(Debug)]
+pub struct SamplerThread {
+    shutdown: Arc<(Mutex<bool>, Condvar)>,
+    thread:   Option<JoinHandle<()>>,
+}
+
+impl Drop for SamplerThread {
+    fn drop(&mut self) {
+        let (stopped, wake) = &*self.shutdown;
+        *lock(stopped) = true;
+        wake.notify_one();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(instance: usize, function: u32) -> GuestFrame {
+        GuestFrame { instance, function }
+    }
+
+    #[test]
+    fn test_folded_stacks() {
+        let profiler = SamplingProfiler::new();
+        let mut names = NameMap::default();
+        names.function_names.insert(0, "main".into());
+        names.function_names.insert(2, "parse".into());
+        let names = Arc::new(names);
+
+        profiler.sample();
+        {
+            let _main = profiler.enter(frame(0, 0), Some(&names));
+            profiler.sample();
+            {
+                let _parse = profiler.enter(frame(0, 2), None);
+                profiler.sample();
+                profiler.sample();
+            }
+            let _unnamed = profiler.enter(frame(0, 5), None);
+            profiler.sample();
+        }
+
+        assert_eq!(profiler.samples_taken(), 5);
+        assert_eq!(
+            profiler.to_string(),
+            "main 1\nmain;func[5] 1\nmain;parse 2\n"
+        );
+
+        profiler.reset();
+        assert!(profiler.stacks().is_empty());
+    }
+
+    #[test]
+    fn test_sampler_thread() {
+        let profiler = Arc::new(SamplingProfiler::new());
+        let _call = profiler.enter(frame(1, 3), None);
+        let sampler = profiler.start(Duration::from_millis(1)).unwrap();
+        while profiler.samples_taken() < 3 {
+            thread::sleep(Duration::from_millis(1));
+        }
+        drop(sampler);
+        let taken = profiler.samples_taken();
+        assert_eq!(profiler.stacks(), [(vec![frame(1, 3)], taken)]);
+    }
+}
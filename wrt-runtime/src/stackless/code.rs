@@ -0,0 +1,2158 @@
+//! Code of modules in the form the interpreter runs
+//!
+//! Function bodies are validated and compiled once, when a module is loaded.
+//! Structured control flow is resolved at that point: blocks and loops
+//! compile to nothing, and every branch records the position it jumps to,
+//! the operand stack height it unwinds to and the number of values it
+//! carries, so the [interpreter](super::interpreter) keeps no control stack.
+//!
+//! Values are held in 64-bit slots: integers and floats as their bits, zero
+//! extended, and references as a function index, or [`NULL_REF`] for null.
+//! Modules using SIMD, threads, tail calls, exceptions or several memories
+//! are rejected when they are loaded.
+
+use core::hash::{
+    Hash,
+    Hasher,
+};
+
+use wrt_format::{
+    module::{
+        Function as FormatFunction,
+        ImportDesc,
+        Module as FormatModule,
+    },
+    pure_format_types::{
+        PureDataMode,
+        PureElementInit,
+        PureElementMode,
+    },
+};
+use wrt_foundation::{
+    leb128,
+    traits::Checksummable,
+    types::RefType,
+    verification::Checksum,
+    ValueType,
+};
+
+use crate::prelude::*;
+
+/// Slot value of a null reference
+pub(crate) const NULL_REF: u64 = u64::MAX;
+
+/// Most locals a function may declare, parameters included
+const MAX_LOCALS: usize = 50_000;
+
+macro_rules! op_codes {
+    ($($(#[$doc:meta])* $name:ident,)*) => {
+        /// Operation of an [`Op`]
+        ///
+        /// Operands the operation takes from the instruction are held in
+        /// [`Op::a`], [`Op::b`] and [`Op::c`] as documented on each variant.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[repr(u8)]
+        pub(crate) enum OpCode {
+            $($(#[$doc])* $name,)*
+        }
+
+        impl OpCode {
+            const ALL: &'static [OpCode] = &[$(OpCode::$name,)*];
+        }
+    };
+}
+
+op_codes! {
+    /// Trap
+    Unreachable,
+    /// Jump to `a`, keeping the top `c` values at height `b`
+    Br,
+    /// Pop a condition and branch as [`OpCode::Br`] if it is not zero
+    BrIf,
+    /// Pop an index and branch to entry `a + min(index, b - 1)` of the
+    /// branch targets of the function
+    BrTable,
+    /// Pop a condition and jump to `a` if it is zero
+    If,
+    /// Jump to `a`
+    Jump,
+    /// Return the top `a` values
+    Return,
+    /// Call function `a`
+    Call,
+    /// Pop an index and call the function at it in table `b`, which must have
+    /// type `a`
+    CallIndirect,
+    /// Pop a value
+    Drop,
+    /// Pop a condition and two values and push the first if the condition is
+    /// not zero, the second otherwise
+    Select,
+    /// Push the slot value `a | b << 32`
+    Const,
+    /// Push local `a`
+    LocalGet,
+    /// Pop into local `a`
+    LocalSet,
+    /// Copy the top value into local `a`
+    LocalTee,
+    /// Push global `a`
+    GlobalGet,
+    /// Pop into global `a`
+    GlobalSet,
+    /// Push the element of table `a` at the popped index
+    TableGet,
+    /// Pop a reference and an index and store it in table `a`
+    TableSet,
+    /// Push the size of table `a`
+    TableSize,
+    /// Grow table `a`
+    TableGrow,
+    /// Fill a range of table `a`
+    TableFill,
+    /// Copy a range from table `b` to table `a`
+    TableCopy,
+    /// Copy a range of element segment `a` into table `b`
+    TableInit,
+    /// Drop element segment `a`
+    ElemDrop,
+    /// Push the reference is null test of the popped reference
+    RefIsNull,
+    /// Load at the popped address plus offset `a`
+    I32Load,
+    I64Load,
+    F32Load,
+    F64Load,
+    I32Load8S,
+    I32Load8U,
+    I32Load16S,
+    I32Load16U,
+    I64Load8S,
+    I64Load8U,
+    I64Load16S,
+    I64Load16U,
+    I64Load32S,
+    I64Load32U,
+    /// Pop a value and store it at the popped address plus offset `a`
+    I32Store,
+    I64Store,
+    F32Store,
+    F64Store,
+    I32Store8,
+    I32Store16,
+    I64Store8,
+    I64Store16,
+    I64Store32,
+    /// Push the size of the memory in pages
+    MemorySize,
+    /// Grow the memory
+    MemoryGrow,
+    /// Copy a range of data segment `a` into the memory
+    MemoryInit,
+    /// Drop data segment `a`
+    DataDrop,
+    /// Copy a range of the memory
+    MemoryCopy,
+    /// Fill a range of the memory
+    MemoryFill,
+    // Numeric operations, in the order of their opcodes 0x45 to 0xC4
+    I32Eqz,
+    I32Eq,
+    I32Ne,
+    I32LtS,
+    I32LtU,
+    I32GtS,
+    I32GtU,
+    I32LeS,
+    I32LeU,
+    I32GeS,
+    I32GeU,
+    I64Eqz,
+    I64Eq,
+    I64Ne,
+    I64LtS,
+    I64LtU,
+    I64GtS,
+    I64GtU,
+    I64LeS,
+    I64LeU,
+    I64GeS,
+    I64GeU,
+    F32Eq,
+    F32Ne,
+    F32Lt,
+    F32Gt,
+    F32Le,
+    F32Ge,
+    F64Eq,
+    F64Ne,
+    F64Lt,
+    F64Gt,
+    F64Le,
+    F64Ge,
+    I32Clz,
+    I32Ctz,
+    I32Popcnt,
+    I32Add,
+    I32Sub,
+    I32Mul,
+    I32DivS,
+    I32DivU,
+    I32RemS,
+    I32RemU,
+    I32And,
+    I32Or,
+    I32Xor,
+    I32Shl,
+    I32ShrS,
+    I32ShrU,
+    I32Rotl,
+    I32Rotr,
+    I64Clz,
+    I64Ctz,
+    I64Popcnt,
+    I64Add,
+    I64Sub,
+    I64Mul,
+    I64DivS,
+    I64DivU,
+    I64RemS,
+    I64RemU,
+    I64And,
+    I64Or,
+    I64Xor,
+    I64Shl,
+    I64ShrS,
+    I64ShrU,
+    I64Rotl,
+    I64Rotr,
+    F32Abs,
+    F32Neg,
+    F32Ceil,
+    F32Floor,
+    F32Trunc,
+    F32Nearest,
+    F32Sqrt,
+    F32Add,
+    F32Sub,
+    F32Mul,
+    F32Div,
+    F32Min,
+    F32Max,
+    F32Copysign,
+    F64Abs,
+    F64Neg,
+    F64Ceil,
+    F64Floor,
+    F64Trunc,
+    F64Nearest,
+    F64Sqrt,
+    F64Add,
+    F64Sub,
+    F64Mul,
+    F64Div,
+    F64Min,
+    F64Max,
+    F64Copysign,
+    I32WrapI64,
+    I32TruncF32S,
+    I32TruncF32U,
+    I32TruncF64S,
+    I32TruncF64U,
+    I64ExtendI32S,
+    I64ExtendI32U,
+    I64TruncF32S,
+    I64TruncF32U,
+    I64TruncF64S,
+    I64TruncF64U,
+    F32ConvertI32S,
+    F32ConvertI32U,
+    F32ConvertI64S,
+    F32ConvertI64U,
+    F32DemoteF64,
+    F64ConvertI32S,
+    F64ConvertI32U,
+    F64ConvertI64S,
+    F64ConvertI64U,
+    F64PromoteF32,
+    I32ReinterpretF32,
+    I64ReinterpretF64,
+    F32ReinterpretI32,
+    F64ReinterpretI64,
+    I32Extend8S,
+    I32Extend16S,
+    I64Extend8S,
+    I64Extend16S,
+    I64Extend32S,
+    // Saturating truncations, in the order of their opcodes 0xFC 0 to 7
+    I32TruncSatF32S,
+    I32TruncSatF32U,
+    I32TruncSatF64S,
+    I32TruncSatF64U,
+    I64TruncSatF32S,
+    I64TruncSatF32U,
+    I64TruncSatF64S,
+    I64TruncSatF64U,
+}
+
+impl OpCode {
+    /// Operation of the numeric instruction with opcode `opcode`, 0x45 to
+    /// 0xC4
+    fn numeric(opcode: u8) -> Self {
+        Self::ALL[Self::I32Eqz as usize + usize::from(opcode - 0x45)]
+    }
+
+    /// Operation of the saturating truncation with opcode 0xFC `opcode`, 0
+    /// to 7
+    fn truncate_saturating(opcode: u32) -> Self {
+        Self::ALL[Self::I32TruncSatF32S as usize + opcode as usize]
+    }
+
+    fn from_u8(byte: u8) -> Option<Self> {
+        Self::ALL.get(usize::from(byte)).copied()
+    }
+}
+
+/// An operation of compiled code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct Op {
+    pub(crate) code: OpCode,
+    pub(crate) a:    u32,
+    pub(crate) b:    u32,
+    pub(crate) c:    u32,
+}
+
+impl Op {
+    const fn new(code: OpCode, a: u32) -> Self {
+        Self {
+            code,
+            a,
+            b: 0,
+            c: 0,
+        }
+    }
+
+    const fn branch(code: OpCode, target: Target) -> Self {
+        Self {
+            code,
+            a: target.pc,
+            b: target.height,
+            c: target.arity,
+        }
+    }
+
+    /// Slot value of an [`OpCode::Const`]
+    pub(crate) const fn value(self) -> u64 {
+        self.a as u64 | (self.b as u64) << 32
+    }
+
+    /// Branch target of an [`OpCode::Br`] or [`OpCode::BrIf`]
+    pub(crate) const fn target(self) -> Target {
+        Target {
+            pc:     self.a,
+            height: self.b,
+            arity:  self.c,
+        }
+    }
+}
+
+/// Where a branch continues
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct Target {
+    /// Position of the next operation
+    pub(crate) pc:     u32,
+    /// Height of the value stack of the frame, locals included, that the
+    /// carried values are moved to
+    pub(crate) height: u32,
+    /// Number of values carried
+    pub(crate) arity:  u32,
+}
+
+/// Parameter and result types of a function
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Signature {
+    pub(crate) params:  Vec<ValueType>,
+    pub(crate) results: Vec<ValueType>,
+}
+
+/// A function the module imports
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FunctionImport {
+    pub(crate) module:   String,
+    pub(crate) name:     String,
+    pub(crate) type_idx: u32,
+}
+
+/// Compiled body of a function the module defines
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FunctionCode {
+    pub(crate) type_idx: u32,
+    /// Types of the declared locals, following the parameters
+    pub(crate) locals:   Vec<ValueType>,
+    pub(crate) ops:      Vec<Op>,
+    /// Targets of the [`OpCode::BrTable`] operations
+    pub(crate) targets:  Vec<Target>,
+}
+
+impl Checksummable for FunctionCode {
+    fn update_checksum(&self, checksum: &mut Checksum) {
+        checksum.update_slice(&self.type_idx.to_le_bytes());
+        for local in &self.locals {
+            checksum.update_slice(&[local.to_binary()]);
+        }
+        let mut hasher = ChecksumHasher(checksum);
+        self.ops.hash(&mut hasher);
+        self.targets.hash(&mut hasher);
+    }
+}
+
+/// Feeds hashed data into a checksum
+struct ChecksumHasher<'a>(&'a mut Checksum);
+
+impl Hasher for ChecksumHasher<'_> {
+    fn finish(&self) -> u64 {
+        u64::from(self.0.value())
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.update_slice(bytes);
+    }
+}
+
+/// Operation of a constant expression
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ConstOp {
+    Value(u64),
+    GlobalGet(u32),
+    I32Add,
+    I32Sub,
+    I32Mul,
+    I64Add,
+    I64Sub,
+    I64Mul,
+}
+
+/// A validated constant expression
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct ConstExpr(Vec<ConstOp>);
+
+impl ConstExpr {
+    /// Evaluate the expression, reading globals with `global`
+    pub(crate) fn evaluate(&self, global: impl Fn(u32) -> Result<u64>) -> Result<u64> {
+        let mut stack: Vec<u64> = Vec::with_capacity(self.0.len());
+        for op in &self.0 {
+            let value = match *op {
+                ConstOp::Value(value) => value,
+                ConstOp::GlobalGet(index) => global(index)?,
+                op => {
+                    let (rhs, lhs) = stack.pop().zip(stack.pop()).ok_or_else(|| {
+                        Error::validation_error("Constant expression operand missing")
+                    })?;
+                    let (lhs32, rhs32) = (lhs as u32, rhs as u32);
+                    match op {
+                        ConstOp::I32Add => u64::from(lhs32.wrapping_add(rhs32)),
+                        ConstOp::I32Sub => u64::from(lhs32.wrapping_sub(rhs32)),
+                        ConstOp::I32Mul => u64::from(lhs32.wrapping_mul(rhs32)),
+                        ConstOp::I64Add => lhs.wrapping_add(rhs),
+                        ConstOp::I64Sub => lhs.wrapping_sub(rhs),
+                        _ => lhs.wrapping_mul(rhs),
+                    }
+                },
+            };
+            stack.push(value);
+        }
+        stack
+            .pop()
+            .ok_or_else(|| Error::validation_error("Constant expression has no value"))
+    }
+}
+
+/// A global of the module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct GlobalCode {
+    pub(crate) ty:      ValueType,
+    pub(crate) mutable: bool,
+    /// Initializer of a global the module defines
+    pub(crate) init:    Option<ConstExpr>,
+}
+
+/// How an element or data segment is used
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum SegmentMode {
+    /// Copied into table or memory `index` at `offset` on instantiation
+    Active { index: u32, offset: ConstExpr },
+    /// Copied by `table.init` or `memory.init`
+    Passive,
+    /// Only declares references to functions
+    Declared,
+}
+
+/// An element segment of the module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ElementCode {
+    pub(crate) ty:    RefType,
+    pub(crate) mode:  SegmentMode,
+    pub(crate) items: Vec<ConstExpr>,
+}
+
+/// A data segment of the module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DataCode {
+    pub(crate) mode:  SegmentMode,
+    pub(crate) bytes: Vec<u8>,
+}
+
+/// Everything of a module the interpreter and instantiation need
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModuleCode {
+    pub(crate) types:          Vec<Signature>,
+    /// For each type, the index of the first type equal to it, by which
+    /// indirect calls compare signatures
+    pub(crate) canonical:      Vec<u32>,
+    pub(crate) imports:        Vec<FunctionImport>,
+    /// Type indices of all functions, imported ones first
+    pub(crate) function_types: Vec<u32>,
+    pub(crate) functions:      Vec<FunctionCode>,
+    /// Globals, imported ones first
+    pub(crate) globals:        Vec<GlobalCode>,
+    /// Element types of the tables, imported ones first
+    pub(crate) tables:         Vec<RefType>,
+    pub(crate) memories:       u32,
+    pub(crate) elements:       Vec<ElementCode>,
+    pub(crate) data:           Vec<DataCode>,
+}
+
+impl ModuleCode {
+    /// Type index of function `func_idx` of the function index space
+    pub(crate) fn function_type(&self, func_idx: u32) -> Option<u32> {
+        self.function_types.get(func_idx as usize).copied()
+    }
+
+    /// The module context of `module` that function bodies are validated
+    /// against, without the bodies
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an import, global or segment is invalid.
+    pub fn context(module: &FormatModule) -> Result<Self> {
+        let types: Vec<Signature> = module
+            .types
+            .iter()
+            .map(|ty| Signature {
+                params:  ty.params.clone(),
+                results: ty.results.clone(),
+            })
+            .collect();
+        for ty in &types {
+            for value_type in ty.params.iter().chain(&ty.results) {
+                check_value_type(*value_type)?;
+            }
+        }
+        let canonical = types
+            .iter()
+            .enumerate()
+            .map(|(index, ty)| types.iter().position(|other| other == ty).unwrap_or(index) as u32)
+            .collect();
+
+        let mut code = Self {
+            types,
+            canonical,
+            ..Self::default()
+        };
+        let mut imported_globals = 0;
+        for import in &module.imports {
+            match &import.desc {
+                ImportDesc::Function(type_idx) => {
+                    code.signature(*type_idx)?;
+                    code.imports.push(FunctionImport {
+                        module:   import.module.clone(),
+                        name:     import.name.clone(),
+                        type_idx: *type_idx,
+                    });
+                },
+                ImportDesc::Table(table) => code.tables.push(table.element_type),
+                ImportDesc::Memory(_) => code.memories += 1,
+                ImportDesc::Global(global) => {
+                    check_value_type(global.value_type)?;
+                    imported_globals += 1;
+                    code.globals.push(GlobalCode {
+                        ty:      global.value_type,
+                        mutable: global.mutable,
+                        init:    None,
+                    });
+                },
+                ImportDesc::Tag(_) => {
+                    return Err(Error::validation_error(
+                        "Exception handling is not supported",
+                    ))
+                },
+            }
+        }
+        code.function_types = code.imports.iter().map(|import| import.type_idx).collect();
+        for function in &module.functions {
+            code.signature(function.type_idx)?;
+            code.function_types.push(function.type_idx);
+        }
+        let function_count = code.function_types.len();
+        code.tables.extend(module.tables.iter().map(|table| table.element_type));
+        code.memories += module.memories.len() as u32;
+        if code.memories > 1 {
+            return Err(Error::validation_error(
+                "Multiple memories are not supported",
+            ));
+        }
+
+        for global in &module.globals {
+            let ty = global.global_type.value_type;
+            check_value_type(ty)?;
+            let visible = code.globals.len().max(imported_globals);
+            let init = const_expr(&global.init, ty, &code.globals[..visible], function_count)?;
+            code.globals.push(GlobalCode {
+                ty,
+                mutable: global.global_type.mutable,
+                init: Some(init),
+            });
+        }
+
+        for segment in &module.elements {
+            let ty = segment.element_type;
+            let mode = match segment.mode {
+                PureElementMode::Active { table_index, .. } => {
+                    if code.tables.get(table_index as usize) != Some(&ty) {
+                        return Err(Error::validation_error(
+                            "Element segment does not match its table",
+                        ));
+                    }
+                    SegmentMode::Active {
+                        index:  table_index,
+                        offset: const_expr(
+                            &segment.offset_expr_bytes,
+                            ValueType::I32,
+                            &code.globals,
+                            function_count,
+                        )?,
+                    }
+                },
+                PureElementMode::Passive => SegmentMode::Passive,
+                PureElementMode::Declared => SegmentMode::Declared,
+            };
+            let items = match &segment.init_data {
+                PureElementInit::FunctionIndices(indices) => indices
+                    .iter()
+                    .map(|index| {
+                        if *index as usize >= function_count {
+                            return Err(Error::validation_error("Unknown function"));
+                        }
+                        Ok(ConstExpr(vec![ConstOp::Value(u64::from(*index))]))
+                    })
+                    .collect::<Result<_>>()?,
+                PureElementInit::ExpressionBytes(exprs) => exprs
+                    .iter()
+                    .map(|expr| const_expr(expr, ty.to_value_type(), &code.globals, function_count))
+                    .collect::<Result<_>>()?,
+            };
+            code.elements.push(ElementCode { ty, mode, items });
+        }
+
+        for segment in &module.data {
+            let mode = match segment.mode {
+                PureDataMode::Active { memory_index, .. } => {
+                    if memory_index >= code.memories {
+                        return Err(Error::validation_error("Unknown memory"));
+                    }
+                    SegmentMode::Active {
+                        index:  memory_index,
+                        offset: const_expr(
+                            &segment.offset_expr_bytes,
+                            ValueType::I32,
+                            &code.globals,
+                            function_count,
+                        )?,
+                    }
+                },
+                PureDataMode::Passive => SegmentMode::Passive,
+            };
+            code.data.push(DataCode {
+                mode,
+                bytes: segment.data_bytes.clone(),
+            });
+        }
+
+        if let Some(start) = module.start {
+            let ty = code.signature(
+                code.function_type(start)
+                    .ok_or_else(|| Error::validation_error("Unknown start function"))?,
+            )?;
+            if !ty.params.is_empty() || !ty.results.is_empty() {
+                return Err(Error::validation_error(
+                    "Start function must not take or return values",
+                ));
+            }
+        }
+
+        Ok(code)
+    }
+
+    /// Validate and compile all function bodies of `module`
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the first invalid part of the module.
+    pub fn compile(module: &FormatModule) -> Result<Self> {
+        let mut code = Self::context(module)?;
+        code.functions = module
+            .functions
+            .iter()
+            .map(|function| code.compile_function(function))
+            .collect::<Result<_>>()?;
+        Ok(code)
+    }
+
+    fn signature(&self, type_idx: u32) -> Result<&Signature> {
+        self.types
+            .get(type_idx as usize)
+            .ok_or_else(|| Error::validation_error("Unknown type"))
+    }
+
+    /// Validate and compile the body of `function` against the module
+    /// context
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the body is malformed or invalid, or uses an
+    /// unsupported instruction.
+    pub(crate) fn compile_function(&self, function: &FormatFunction) -> Result<FunctionCode> {
+        let signature = self.signature(function.type_idx)?;
+        let (locals, start) = local_declarations(&function.code, signature.params.len())?;
+        let compiler = Compiler {
+            module:   self,
+            body:     &function.code,
+            pos:      start,
+            params:   &signature.params,
+            locals:   &locals,
+            operands: Vec::new(),
+            controls: Vec::new(),
+            ops:      Vec::new(),
+            targets:  Vec::new(),
+        };
+        let (ops, targets) = compiler.run(&signature.results)?;
+        Ok(FunctionCode {
+            type_idx: function.type_idx,
+            locals,
+            ops,
+            targets,
+        })
+    }
+
+    /// Append the serialized form of the code to `out`
+    pub(crate) fn serialize(&self, out: &mut Vec<u8>) {
+        let mut writer = Writer(out);
+        writer.len(self.types.len());
+        for ty in &self.types {
+            writer.value_types(&ty.params);
+            writer.value_types(&ty.results);
+        }
+        writer.len(self.imports.len());
+        for import in &self.imports {
+            writer.bytes(import.module.as_bytes());
+            writer.bytes(import.name.as_bytes());
+            writer.u32(import.type_idx);
+        }
+        writer.len(self.functions.len());
+        for function in &self.functions {
+            writer.u32(function.type_idx);
+            writer.value_types(&function.locals);
+            writer.len(function.ops.len());
+            for op in &function.ops {
+                writer.0.push(op.code as u8);
+                writer.u32(op.a);
+                writer.u32(op.b);
+                writer.u32(op.c);
+            }
+            writer.len(function.targets.len());
+            for target in &function.targets {
+                writer.u32(target.pc);
+                writer.u32(target.height);
+                writer.u32(target.arity);
+            }
+        }
+        writer.len(self.globals.len());
+        for global in &self.globals {
+            writer.0.push(global.ty.to_binary());
+            writer.0.push(u8::from(global.mutable));
+            match &global.init {
+                Some(init) => {
+                    writer.0.push(1);
+                    writer.const_expr(init);
+                },
+                None => writer.0.push(0),
+            }
+        }
+        writer.len(self.tables.len());
+        for table in &self.tables {
+            writer.0.push(table.to_value_type().to_binary());
+        }
+        writer.u32(self.memories);
+        writer.len(self.elements.len());
+        for element in &self.elements {
+            writer.0.push(element.ty.to_value_type().to_binary());
+            writer.mode(&element.mode);
+            writer.len(element.items.len());
+            for item in &element.items {
+                writer.const_expr(item);
+            }
+        }
+        writer.len(self.data.len());
+        for data in &self.data {
+            writer.mode(&data.mode);
+            writer.bytes(&data.bytes);
+        }
+    }
+
+    /// Read code serialized by [`Self::serialize`] from `bytes` at `pos`,
+    /// advancing `pos` past it
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the code is truncated or malformed.
+    pub(crate) fn deserialize(bytes: &[u8], pos: &mut usize) -> Result<Self> {
+        let mut reader = Reader { bytes, pos };
+        let mut code = Self::default();
+        for _ in 0..reader.len()? {
+            code.types.push(Signature {
+                params:  reader.value_types()?,
+                results: reader.value_types()?,
+            });
+        }
+        code.canonical = code
+            .types
+            .iter()
+            .enumerate()
+            .map(|(index, ty)| {
+                code.types.iter().position(|other| other == ty).unwrap_or(index) as u32
+            })
+            .collect();
+        for _ in 0..reader.len()? {
+            let import = FunctionImport {
+                module:   reader.string()?,
+                name:     reader.string()?,
+                type_idx: reader.u32()?,
+            };
+            code.function_types.push(import.type_idx);
+            code.imports.push(import);
+        }
+        for _ in 0..reader.len()? {
+            let type_idx = reader.u32()?;
+            let locals = reader.value_types()?;
+            let mut ops = Vec::new();
+            for _ in 0..reader.len()? {
+                let code = OpCode::from_u8(reader.byte()?)
+                    .ok_or_else(|| Error::validation_parse_error("Unknown operation"))?;
+                ops.push(Op {
+                    code,
+                    a: reader.u32()?,
+                    b: reader.u32()?,
+                    c: reader.u32()?,
+                });
+            }
+            let mut targets = Vec::new();
+            for _ in 0..reader.len()? {
+                targets.push(Target {
+                    pc:     reader.u32()?,
+                    height: reader.u32()?,
+                    arity:  reader.u32()?,
+                });
+            }
+            code.function_types.push(type_idx);
+            code.functions.push(FunctionCode {
+                type_idx,
+                locals,
+                ops,
+                targets,
+            });
+        }
+        for _ in 0..reader.len()? {
+            let ty = ValueType::from_binary(reader.byte()?)?;
+            let mutable = reader.byte()? != 0;
+            let init = match reader.byte()? {
+                0 => None,
+                _ => Some(reader.const_expr()?),
+            };
+            code.globals.push(GlobalCode { ty, mutable, init });
+        }
+        for _ in 0..reader.len()? {
+            code.tables.push(reader.ref_type()?);
+        }
+        code.memories = reader.u32()?;
+        for _ in 0..reader.len()? {
+            let ty = reader.ref_type()?;
+            let mode = reader.mode()?;
+            let mut items = Vec::new();
+            for _ in 0..reader.len()? {
+                items.push(reader.const_expr()?);
+            }
+            code.elements.push(ElementCode { ty, mode, items });
+        }
+        for _ in 0..reader.len()? {
+            let mode = reader.mode()?;
+            let bytes = reader.bytes()?.to_vec();
+            code.data.push(DataCode { mode, bytes });
+        }
+        Ok(code)
+    }
+}
+
+fn check_value_type(ty: ValueType) -> Result<()> {
+    match ty {
+        ValueType::I32
+        | ValueType::I64
+        | ValueType::F32
+        | ValueType::F64
+        | ValueType::FuncRef
+        | ValueType::ExternRef => Ok(()),
+        ValueType::V128 | ValueType::I16x8 => Err(Error::validation_error("SIMD is not supported")),
+        ValueType::StructRef(_) | ValueType::ArrayRef(_) => Err(Error::validation_error(
+            "Garbage collected references are not supported",
+        )),
+    }
+}
+
+/// Parse the local declarations at the start of `body`, returning the
+/// declared types and where the instructions start
+fn local_declarations(body: &[u8], params: usize) -> Result<(Vec<ValueType>, usize)> {
+    let (groups, mut pos) = leb128::read_u32(body, 0)?;
+    let mut locals = Vec::new();
+    for _ in 0..groups {
+        let (count, len) = leb128::read_u32(body, pos)?;
+        pos += len;
+        if params + locals.len() + count as usize > MAX_LOCALS {
+            return Err(Error::validation_error("Too many locals"));
+        }
+        let ty = ValueType::from_binary(
+            *body
+                .get(pos)
+                .ok_or_else(|| Error::parse_error("Unexpected end of function body"))?,
+        )?;
+        pos += 1;
+        check_value_type(ty)?;
+        locals.extend(core::iter::repeat(ty).take(count as usize));
+    }
+    Ok((locals, pos))
+}
+
+/// Validate the constant expression `bytes`, which must produce a value of
+/// type `expected` and may read `globals`
+fn const_expr(
+    bytes: &[u8],
+    expected: ValueType,
+    globals: &[GlobalCode],
+    functions: usize,
+) -> Result<ConstExpr> {
+    let mut ops = Vec::new();
+    let mut types: Vec<ValueType> = Vec::new();
+    let mut pos = 0;
+    loop {
+        let opcode = *bytes
+            .get(pos)
+            .ok_or_else(|| Error::parse_error("Unterminated constant expression"))?;
+        pos += 1;
+        let (op, ty) = match opcode {
+            0x0B => break,
+            0x41 => {
+                let (value, len) = leb128::read_i32(bytes, pos)?;
+                pos += len;
+                (ConstOp::Value(u64::from(value as u32)), ValueType::I32)
+            },
+            0x42 => {
+                let (value, len) = leb128::read_i64(bytes, pos)?;
+                pos += len;
+                (ConstOp::Value(value as u64), ValueType::I64)
+            },
+            0x43 => {
+                let value = read_array::<4>(bytes, pos)?;
+                pos += 4;
+                (
+                    ConstOp::Value(u64::from(u32::from_le_bytes(value))),
+                    ValueType::F32,
+                )
+            },
+            0x44 => {
+                let value = read_array::<8>(bytes, pos)?;
+                pos += 8;
+                (ConstOp::Value(u64::from_le_bytes(value)), ValueType::F64)
+            },
+            0x23 => {
+                let (index, len) = leb128::read_u32(bytes, pos)?;
+                pos += len;
+                let global = globals
+                    .get(index as usize)
+                    .ok_or_else(|| Error::validation_error("Unknown global"))?;
+                if global.mutable {
+                    return Err(Error::validation_error(
+                        "Constant expression reads a mutable global",
+                    ));
+                }
+                (ConstOp::GlobalGet(index), global.ty)
+            },
+            0xD0 => {
+                let ty = ValueType::from_binary(read_array::<1>(bytes, pos)?[0])?;
+                pos += 1;
+                check_value_type(ty)?;
+                (ConstOp::Value(NULL_REF), ty)
+            },
+            0xD2 => {
+                let (index, len) = leb128::read_u32(bytes, pos)?;
+                pos += len;
+                if index as usize >= functions {
+                    return Err(Error::validation_error("Unknown function"));
+                }
+                (ConstOp::Value(u64::from(index)), ValueType::FuncRef)
+            },
+            0x6A | 0x6B | 0x6C | 0x7C | 0x7D | 0x7E => {
+                let (op, ty) = match opcode {
+                    0x6A => (ConstOp::I32Add, ValueType::I32),
+                    0x6B => (ConstOp::I32Sub, ValueType::I32),
+                    0x6C => (ConstOp::I32Mul, ValueType::I32),
+                    0x7C => (ConstOp::I64Add, ValueType::I64),
+                    0x7D => (ConstOp::I64Sub, ValueType::I64),
+                    _ => (ConstOp::I64Mul, ValueType::I64),
+                };
+                if types.pop() != Some(ty) || types.pop() != Some(ty) {
+                    return Err(Error::validation_error(
+                        "Type mismatch in constant expression",
+                    ));
+                }
+                (op, ty)
+            },
+            _ => return Err(Error::validation_error("Constant expression required")),
+        };
+        ops.push(op);
+        types.push(ty);
+    }
+    if types != [expected] {
+        return Err(Error::validation_error(
+            "Type mismatch in constant expression",
+        ));
+    }
+    Ok(ConstExpr(ops))
+}
+
+fn read_array<const N: usize>(bytes: &[u8], pos: usize) -> Result<[u8; N]> {
+    bytes
+        .get(pos..pos + N)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| Error::parse_error("Unexpected end of function body"))
+}
+
+/// Kind of a structured control instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ControlKind {
+    Block,
+    Loop,
+    If,
+    Else,
+}
+
+/// A branch to be pointed at the end of its block
+#[derive(Debug, Clone, Copy)]
+enum Fixup {
+    /// The [`Op::a`] of an operation
+    Op(usize),
+    /// An entry of the branch targets
+    Target(usize),
+}
+
+/// A block being compiled
+#[derive(Debug)]
+struct Control {
+    kind:        ControlKind,
+    params:      Vec<ValueType>,
+    results:     Vec<ValueType>,
+    /// Operand stack height below the parameters
+    height:      usize,
+    unreachable: bool,
+    /// Position of the first operation of a loop
+    start:       u32,
+    fixups:      Vec<Fixup>,
+    /// The [`OpCode::If`] of an `if` without `else` so far
+    else_fixup:  Option<usize>,
+}
+
+/// Compiles one function body, validating it as it goes
+struct Compiler<'a> {
+    module:   &'a ModuleCode,
+    body:     &'a [u8],
+    pos:      usize,
+    params:   &'a [ValueType],
+    locals:   &'a [ValueType],
+    /// Types on the operand stack, `None` for unknown values of unreachable
+    /// code
+    operands: Vec<Option<ValueType>>,
+    controls: Vec<Control>,
+    ops:      Vec<Op>,
+    targets:  Vec<Target>,
+}
+
+impl Compiler<'_> {
+    fn run(mut self, results: &[ValueType]) -> Result<(Vec<Op>, Vec<Target>)> {
+        self.controls.push(Control {
+            kind:        ControlKind::Block,
+            params:      Vec::new(),
+            results:     results.to_vec(),
+            height:      0,
+            unreachable: false,
+            start:       0,
+            fixups:      Vec::new(),
+            else_fixup:  None,
+        });
+        while !self.controls.is_empty() {
+            self.instruction()?;
+        }
+        if self.pos != self.body.len() {
+            return Err(Error::parse_error(
+                "Operations after the end of the function",
+            ));
+        }
+        Ok((self.ops, self.targets))
+    }
+
+    fn byte(&mut self) -> Result<u8> {
+        let byte = *self
+            .body
+            .get(self.pos)
+            .ok_or_else(|| Error::parse_error("Unexpected end of function body"))?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let (value, len) = leb128::read_u32(self.body, self.pos)?;
+        self.pos += len;
+        Ok(value)
+    }
+
+    fn pc(&self) -> Result<u32> {
+        u32::try_from(self.ops.len())
+            .map_err(|_| Error::capacity_limit_exceeded("Function body too large"))
+    }
+
+    fn emit(&mut self, op: Op) {
+        self.ops.push(op);
+    }
+
+    fn push(&mut self, ty: ValueType) {
+        self.operands.push(Some(ty));
+    }
+
+    fn pushes(&mut self, types: &[ValueType]) {
+        self.operands.extend(types.iter().copied().map(Some));
+    }
+
+    /// Pop a value of any type
+    fn pop_any(&mut self) -> Result<Option<ValueType>> {
+        let control = self.controls.last().ok_or_else(|| Error::parse_error("No open block"))?;
+        if self.operands.len() == control.height {
+            if control.unreachable {
+                return Ok(None);
+            }
+            return Err(Error::validation_error(
+                "Type mismatch: operand stack is empty",
+            ));
+        }
+        Ok(self.operands.pop().flatten())
+    }
+
+    /// Pop a value of type `expected`
+    fn pop(&mut self, expected: ValueType) -> Result<()> {
+        match self.pop_any()? {
+            Some(actual) if actual != expected => Err(Error::validation_error("Type mismatch")),
+            _ => Ok(()),
+        }
+    }
+
+    fn pops(&mut self, types: &[ValueType]) -> Result<()> {
+        for ty in types.iter().rev() {
+            self.pop(*ty)?;
+        }
+        Ok(())
+    }
+
+    /// Pop a reference of any type
+    fn pop_ref(&mut self) -> Result<Option<ValueType>> {
+        match self.pop_any()? {
+            Some(ty @ (ValueType::FuncRef | ValueType::ExternRef)) => Ok(Some(ty)),
+            Some(_) => Err(Error::validation_error("Type mismatch: reference expected")),
+            None => Ok(None),
+        }
+    }
+
+    /// Mark the rest of the current block unreachable
+    fn unreachable(&mut self) -> Result<()> {
+        let control =
+            self.controls.last_mut().ok_or_else(|| Error::parse_error("No open block"))?;
+        self.operands.truncate(control.height);
+        control.unreachable = true;
+        Ok(())
+    }
+
+    fn control(&self, depth: u32) -> Result<&Control> {
+        (self.controls.len())
+            .checked_sub(depth as usize + 1)
+            .and_then(|index| self.controls.get(index))
+            .ok_or_else(|| Error::validation_error("Unknown label"))
+    }
+
+    /// Types the label at `depth` carries
+    fn label_types(&self, depth: u32) -> Result<Vec<ValueType>> {
+        let control = self.control(depth)?;
+        Ok(match control.kind {
+            ControlKind::Loop => control.params.clone(),
+            _ => control.results.clone(),
+        })
+    }
+
+    /// Target of a branch to the label at `depth`; the position of a
+    /// forward branch is fixed up by `fixup` when its block ends
+    fn target(&mut self, depth: u32, fixup: Fixup) -> Result<Target> {
+        let locals = self.params.len() + self.locals.len();
+        let index = self.controls.len() - 1 - depth as usize;
+        let control = &mut self.controls[index];
+        let height = (locals + control.height) as u32;
+        Ok(match control.kind {
+            ControlKind::Loop => Target {
+                pc: control.start,
+                height,
+                arity: control.params.len() as u32,
+            },
+            _ => {
+                control.fixups.push(fixup);
+                Target {
+                    pc: 0,
+                    height,
+                    arity: control.results.len() as u32,
+                }
+            },
+        })
+    }
+
+    fn block_type(&mut self) -> Result<Signature> {
+        let byte = *self
+            .body
+            .get(self.pos)
+            .ok_or_else(|| Error::parse_error("Unexpected end of function body"))?;
+        if byte == 0x40 {
+            self.pos += 1;
+            return Ok(Signature::default());
+        }
+        if let Ok(ty) = ValueType::from_binary(byte) {
+            self.pos += 1;
+            check_value_type(ty)?;
+            return Ok(Signature {
+                params:  Vec::new(),
+                results: vec![ty],
+            });
+        }
+        let (index, len) = leb128::read_i33(self.body, self.pos)?;
+        self.pos += len;
+        let index = u32::try_from(index).map_err(|_| Error::validation_error("Unknown type"))?;
+        Ok(self.module.signature(index)?.clone())
+    }
+
+    fn open(&mut self, kind: ControlKind, signature: Signature) -> Result<()> {
+        self.pops(&signature.params)?;
+        let height = self.operands.len();
+        self.pushes(&signature.params);
+        let start = self.pc()?;
+        self.controls.push(Control {
+            kind,
+            params: signature.params,
+            results: signature.results,
+            height,
+            unreachable: false,
+            start,
+            fixups: Vec::new(),
+            else_fixup: None,
+        });
+        Ok(())
+    }
+
+    /// Check the operands at the end of the current block
+    fn close(&mut self) -> Result<Control> {
+        let results = self.controls.last().map(|c| c.results.clone()).unwrap_or_default();
+        self.pops(&results)?;
+        let control = self.controls.pop().ok_or_else(|| Error::parse_error("No open block"))?;
+        if self.operands.len() != control.height {
+            return Err(Error::validation_error(
+                "Type mismatch: values left at end of block",
+            ));
+        }
+        Ok(control)
+    }
+
+    fn fix(&mut self, fixups: &[Fixup], pc: u32) {
+        for fixup in fixups {
+            match *fixup {
+                Fixup::Op(index) => self.ops[index].a = pc,
+                Fixup::Target(index) => self.targets[index].pc = pc,
+            }
+        }
+    }
+
+    fn local(&self, index: u32) -> Result<ValueType> {
+        let index = index as usize;
+        match self.params.get(index) {
+            Some(ty) => Ok(*ty),
+            None => self
+                .locals
+                .get(index - self.params.len())
+                .copied()
+                .ok_or_else(|| Error::validation_error("Unknown local")),
+        }
+    }
+
+    fn global(&self, index: u32) -> Result<&GlobalCode> {
+        self.module
+            .globals
+            .get(index as usize)
+            .ok_or_else(|| Error::validation_error("Unknown global"))
+    }
+
+    fn table(&self, index: u32) -> Result<ValueType> {
+        self.module
+            .tables
+            .get(index as usize)
+            .map(|ty| ty.to_value_type())
+            .ok_or_else(|| Error::validation_error("Unknown table"))
+    }
+
+    fn element(&self, index: u32) -> Result<ValueType> {
+        self.module
+            .elements
+            .get(index as usize)
+            .map(|element| element.ty.to_value_type())
+            .ok_or_else(|| Error::validation_error("Unknown element segment"))
+    }
+
+    fn memory(&self) -> Result<()> {
+        if self.module.memories == 0 {
+            return Err(Error::validation_error("Unknown memory"));
+        }
+        Ok(())
+    }
+
+    /// Read a memory argument, whose alignment may be at most `natural`
+    /// (as a power of two), returning its offset
+    fn memarg(&mut self, natural: u32) -> Result<u32> {
+        let align = self.u32()?;
+        if align >= 64 {
+            return Err(Error::validation_error(
+                "Multiple memories are not supported",
+            ));
+        }
+        if align > natural {
+            return Err(Error::validation_error(
+                "Alignment must not be larger than natural",
+            ));
+        }
+        let offset = self.u32()?;
+        self.memory()?;
+        Ok(offset)
+    }
+
+    fn load(&mut self, code: OpCode, natural: u32, ty: ValueType) -> Result<()> {
+        let offset = self.memarg(natural)?;
+        self.pop(ValueType::I32)?;
+        self.push(ty);
+        self.emit(Op::new(code, offset));
+        Ok(())
+    }
+
+    fn store(&mut self, code: OpCode, natural: u32, ty: ValueType) -> Result<()> {
+        let offset = self.memarg(natural)?;
+        self.pop(ty)?;
+        self.pop(ValueType::I32)?;
+        self.emit(Op::new(code, offset));
+        Ok(())
+    }
+
+    fn constant(&mut self, ty: ValueType, value: u64) {
+        self.push(ty);
+        self.emit(Op {
+            code: OpCode::Const,
+            a:    value as u32,
+            b:    (value >> 32) as u32,
+            c:    0,
+        });
+    }
+
+    fn operation(
+        &mut self,
+        code: OpCode,
+        params: &[ValueType],
+        result: Option<ValueType>,
+    ) -> Result<()> {
+        self.pops(params)?;
+        if let Some(result) = result {
+            self.push(result);
+        }
+        self.emit(Op::new(code, 0));
+        Ok(())
+    }
+
+    fn instruction(&mut self) -> Result<()> {
+        use ValueType::{
+            ExternRef,
+            FuncRef,
+            F32,
+            F64,
+            I32,
+            I64,
+        };
+
+        let opcode = self.byte()?;
+        match opcode {
+            // unreachable
+            0x00 => {
+                self.emit(Op::new(OpCode::Unreachable, 0));
+                self.unreachable()?;
+            },
+            // nop
+            0x01 => {},
+            // block, loop
+            0x02 | 0x03 => {
+                let signature = self.block_type()?;
+                let kind = if opcode == 0x02 { ControlKind::Block } else { ControlKind::Loop };
+                self.open(kind, signature)?;
+            },
+            // if
+            0x04 => {
+                let signature = self.block_type()?;
+                self.pop(I32)?;
+                self.open(ControlKind::If, signature)?;
+                let fixup = self.ops.len();
+                self.emit(Op::new(OpCode::If, 0));
+                if let Some(control) = self.controls.last_mut() {
+                    control.else_fixup = Some(fixup);
+                }
+            },
+            // else
+            0x05 => {
+                if self.controls.last().map(|c| c.kind) != Some(ControlKind::If) {
+                    return Err(Error::parse_error("Else without if"));
+                }
+                let mut control = self.close()?;
+                let jump = self.ops.len();
+                self.emit(Op::new(OpCode::Jump, 0));
+                control.fixups.push(Fixup::Op(jump));
+                if let Some(fixup) = control.else_fixup.take() {
+                    let pc = self.pc()?;
+                    self.ops[fixup].a = pc;
+                }
+                self.pushes(&control.params);
+                control.kind = ControlKind::Else;
+                control.unreachable = false;
+                self.controls.push(control);
+            },
+            // end
+            0x0B => {
+                let control = self.close()?;
+                if control.kind == ControlKind::If && control.params != control.results {
+                    return Err(Error::validation_error(
+                        "Type mismatch: if without else must not change the stack",
+                    ));
+                }
+                if self.controls.is_empty() {
+                    self.emit(Op::new(OpCode::Return, control.results.len() as u32));
+                }
+                let pc = self.pc()?;
+                self.fix(&control.fixups, pc);
+                if let Some(fixup) = control.else_fixup {
+                    self.ops[fixup].a = pc;
+                }
+                self.pushes(&control.results);
+            },
+            // br
+            0x0C => {
+                let depth = self.u32()?;
+                let types = self.label_types(depth)?;
+                self.pops(&types)?;
+                let target = self.target(depth, Fixup::Op(self.ops.len()))?;
+                self.emit(Op::branch(OpCode::Br, target));
+                self.unreachable()?;
+            },
+            // br_if
+            0x0D => {
+                let depth = self.u32()?;
+                self.pop(I32)?;
+                let types = self.label_types(depth)?;
+                self.pops(&types)?;
+                self.pushes(&types);
+                let target = self.target(depth, Fixup::Op(self.ops.len()))?;
+                self.emit(Op::branch(OpCode::BrIf, target));
+            },
+            // br_table
+            0x0E => {
+                let count = self.u32()?;
+                let start = u32::try_from(self.targets.len())
+                    .map_err(|_| Error::capacity_limit_exceeded("Function body too large"))?;
+                self.pop(I32)?;
+                let mut arity = None;
+                for _ in 0..=count {
+                    let depth = self.u32()?;
+                    let types = self.label_types(depth)?;
+                    match arity {
+                        Some(arity) if arity != types.len() => {
+                            return Err(Error::validation_error(
+                                "Type mismatch: br_table labels differ in arity",
+                            ))
+                        },
+                        _ => arity = Some(types.len()),
+                    }
+                    // Each label must accept the operands, which stay on
+                    // the stack for the next label
+                    let saved = self.operands.clone();
+                    self.pops(&types)?;
+                    self.operands = saved;
+                    let target = self.target(depth, Fixup::Target(self.targets.len()))?;
+                    self.targets.push(target);
+                }
+                self.emit(Op {
+                    code: OpCode::BrTable,
+                    a:    start,
+                    b:    count + 1,
+                    c:    0,
+                });
+                self.unreachable()?;
+            },
+            // return
+            0x0F => {
+                let results = self.controls[0].results.clone();
+                self.pops(&results)?;
+                self.emit(Op::new(OpCode::Return, results.len() as u32));
+                self.unreachable()?;
+            },
+            // call
+            0x10 => {
+                let func_idx = self.u32()?;
+                let type_idx = self
+                    .module
+                    .function_type(func_idx)
+                    .ok_or_else(|| Error::validation_error("Unknown function"))?;
+                let signature = self.module.signature(type_idx)?.clone();
+                self.pops(&signature.params)?;
+                self.pushes(&signature.results);
+                self.emit(Op::new(OpCode::Call, func_idx));
+            },
+            // call_indirect
+            0x11 => {
+                let type_idx = self.u32()?;
+                let table = self.u32()?;
+                if self.table(table)? != FuncRef {
+                    return Err(Error::validation_error(
+                        "Type mismatch: table is not funcref",
+                    ));
+                }
+                let signature = self.module.signature(type_idx)?.clone();
+                self.pop(I32)?;
+                self.pops(&signature.params)?;
+                self.pushes(&signature.results);
+                self.emit(Op {
+                    code: OpCode::CallIndirect,
+                    a:    type_idx,
+                    b:    table,
+                    c:    0,
+                });
+            },
+            0x12 | 0x13 => return Err(Error::validation_error("Tail calls are not supported")),
+            // drop
+            0x1A => {
+                self.pop_any()?;
+                self.emit(Op::new(OpCode::Drop, 0));
+            },
+            // select
+            0x1B => {
+                self.pop(I32)?;
+                let first = self.pop_any()?;
+                let second = self.pop_any()?;
+                let ty = match (first, second) {
+                    (Some(a), Some(b)) if a != b => {
+                        return Err(Error::validation_error("Type mismatch in select"))
+                    },
+                    (Some(ty), _) | (_, Some(ty)) => Some(ty),
+                    (None, None) => None,
+                };
+                if matches!(ty, Some(FuncRef | ExternRef)) {
+                    return Err(Error::validation_error(
+                        "Type mismatch: select of references needs a type",
+                    ));
+                }
+                self.operands.push(ty);
+                self.emit(Op::new(OpCode::Select, 0));
+            },
+            // select t
+            0x1C => {
+                if self.u32()? != 1 {
+                    return Err(Error::validation_error("Invalid result arity of select"));
+                }
+                let ty = ValueType::from_binary(self.byte()?)?;
+                check_value_type(ty)?;
+                self.pop(I32)?;
+                self.pop(ty)?;
+                self.pop(ty)?;
+                self.push(ty);
+                self.emit(Op::new(OpCode::Select, 0));
+            },
+            // local.get, local.set, local.tee
+            0x20..=0x22 => {
+                let index = self.u32()?;
+                let ty = self.local(index)?;
+                let code = match opcode {
+                    0x20 => {
+                        self.push(ty);
+                        OpCode::LocalGet
+                    },
+                    0x21 => {
+                        self.pop(ty)?;
+                        OpCode::LocalSet
+                    },
+                    _ => {
+                        self.pop(ty)?;
+                        self.push(ty);
+                        OpCode::LocalTee
+                    },
+                };
+                self.emit(Op::new(code, index));
+            },
+            // global.get
+            0x23 => {
+                let index = self.u32()?;
+                let ty = self.global(index)?.ty;
+                self.push(ty);
+                self.emit(Op::new(OpCode::GlobalGet, index));
+            },
+            // global.set
+            0x24 => {
+                let index = self.u32()?;
+                let global = self.global(index)?;
+                if !global.mutable {
+                    return Err(Error::validation_error("Global is immutable"));
+                }
+                let ty = global.ty;
+                self.pop(ty)?;
+                self.emit(Op::new(OpCode::GlobalSet, index));
+            },
+            // table.get
+            0x25 => {
+                let table = self.u32()?;
+                let ty = self.table(table)?;
+                self.pop(I32)?;
+                self.push(ty);
+                self.emit(Op::new(OpCode::TableGet, table));
+            },
+            // table.set
+            0x26 => {
+                let table = self.u32()?;
+                let ty = self.table(table)?;
+                self.pop(ty)?;
+                self.pop(I32)?;
+                self.emit(Op::new(OpCode::TableSet, table));
+            },
+            0x28 => self.load(OpCode::I32Load, 2, I32)?,
+            0x29 => self.load(OpCode::I64Load, 3, I64)?,
+            0x2A => self.load(OpCode::F32Load, 2, F32)?,
+            0x2B => self.load(OpCode::F64Load, 3, F64)?,
+            0x2C => self.load(OpCode::I32Load8S, 0, I32)?,
+            0x2D => self.load(OpCode::I32Load8U, 0, I32)?,
+            0x2E => self.load(OpCode::I32Load16S, 1, I32)?,
+            0x2F => self.load(OpCode::I32Load16U, 1, I32)?,
+            0x30 => self.load(OpCode::I64Load8S, 0, I64)?,
+            0x31 => self.load(OpCode::I64Load8U, 0, I64)?,
+            0x32 => self.load(OpCode::I64Load16S, 1, I64)?,
+            0x33 => self.load(OpCode::I64Load16U, 1, I64)?,
+            0x34 => self.load(OpCode::I64Load32S, 2, I64)?,
+            0x35 => self.load(OpCode::I64Load32U, 2, I64)?,
+            0x36 => self.store(OpCode::I32Store, 2, I32)?,
+            0x37 => self.store(OpCode::I64Store, 3, I64)?,
+            0x38 => self.store(OpCode::F32Store, 2, F32)?,
+            0x39 => self.store(OpCode::F64Store, 3, F64)?,
+            0x3A => self.store(OpCode::I32Store8, 0, I32)?,
+            0x3B => self.store(OpCode::I32Store16, 1, I32)?,
+            0x3C => self.store(OpCode::I64Store8, 0, I64)?,
+            0x3D => self.store(OpCode::I64Store16, 1, I64)?,
+            0x3E => self.store(OpCode::I64Store32, 2, I64)?,
+            // memory.size, memory.grow
+            0x3F | 0x40 => {
+                if self.byte()? != 0 {
+                    return Err(Error::validation_error(
+                        "Multiple memories are not supported",
+                    ));
+                }
+                self.memory()?;
+                if opcode == 0x3F {
+                    self.operation(OpCode::MemorySize, &[], Some(I32))?;
+                } else {
+                    self.operation(OpCode::MemoryGrow, &[I32], Some(I32))?;
+                }
+            },
+            // i32.const
+            0x41 => {
+                let (value, len) = leb128::read_i32(self.body, self.pos)?;
+                self.pos += len;
+                self.constant(I32, u64::from(value as u32));
+            },
+            // i64.const
+            0x42 => {
+                let (value, len) = leb128::read_i64(self.body, self.pos)?;
+                self.pos += len;
+                self.constant(I64, value as u64);
+            },
+            // f32.const
+            0x43 => {
+                let bits = u32::from_le_bytes(read_array(self.body, self.pos)?);
+                self.pos += 4;
+                self.constant(F32, u64::from(bits));
+            },
+            // f64.const
+            0x44 => {
+                let bits = u64::from_le_bytes(read_array(self.body, self.pos)?);
+                self.pos += 8;
+                self.constant(F64, bits);
+            },
+            // Reinterpretations keep the bits of the slot
+            0xBC => {
+                self.pop(F32)?;
+                self.push(I32);
+            },
+            0xBD => {
+                self.pop(F64)?;
+                self.push(I64);
+            },
+            0xBE => {
+                self.pop(I32)?;
+                self.push(F32);
+            },
+            0xBF => {
+                self.pop(I64)?;
+                self.push(F64);
+            },
+            0x45..=0xC4 => {
+                let (params, result): (&[ValueType], ValueType) = match opcode {
+                    0x45 => (&[I32], I32),
+                    0x46..=0x4F => (&[I32, I32], I32),
+                    0x50 => (&[I64], I32),
+                    0x51..=0x5A => (&[I64, I64], I32),
+                    0x5B..=0x60 => (&[F32, F32], I32),
+                    0x61..=0x66 => (&[F64, F64], I32),
+                    0x67..=0x69 => (&[I32], I32),
+                    0x6A..=0x78 => (&[I32, I32], I32),
+                    0x79..=0x7B => (&[I64], I64),
+                    0x7C..=0x8A => (&[I64, I64], I64),
+                    0x8B..=0x91 => (&[F32], F32),
+                    0x92..=0x98 => (&[F32, F32], F32),
+                    0x99..=0x9F => (&[F64], F64),
+                    0xA0..=0xA6 => (&[F64, F64], F64),
+                    0xA7 => (&[I64], I32),
+                    0xA8 | 0xA9 => (&[F32], I32),
+                    0xAA | 0xAB => (&[F64], I32),
+                    0xAC | 0xAD => (&[I32], I64),
+                    0xAE | 0xAF => (&[F32], I64),
+                    0xB0 | 0xB1 => (&[F64], I64),
+                    0xB2 | 0xB3 => (&[I32], F32),
+                    0xB4 | 0xB5 => (&[I64], F32),
+                    0xB6 => (&[F64], F32),
+                    0xB7 | 0xB8 => (&[I32], F64),
+                    0xB9 | 0xBA => (&[I64], F64),
+                    0xBB => (&[F32], F64),
+                    0xC0 | 0xC1 => (&[I32], I32),
+                    _ => (&[I64], I64),
+                };
+                self.operation(OpCode::numeric(opcode), params, Some(result))?;
+            },
+            // ref.null
+            0xD0 => {
+                let ty = ValueType::from_binary(self.byte()?)?;
+                if !matches!(ty, FuncRef | ExternRef) {
+                    return Err(Error::validation_error("Type mismatch: reference expected"));
+                }
+                self.constant(ty, NULL_REF);
+            },
+            // ref.is_null
+            0xD1 => {
+                self.pop_ref()?;
+                self.push(I32);
+                self.emit(Op::new(OpCode::RefIsNull, 0));
+            },
+            // ref.func
+            0xD2 => {
+                let func_idx = self.u32()?;
+                if self.module.function_type(func_idx).is_none() {
+                    return Err(Error::validation_error("Unknown function"));
+                }
+                self.constant(FuncRef, u64::from(func_idx));
+            },
+            0xFC => self.prefixed()?,
+            0xFD => return Err(Error::validation_error("SIMD is not supported")),
+            0xFE => return Err(Error::validation_error("Threads are not supported")),
+            0x06..=0x0A | 0x18 | 0x19 | 0x1F => {
+                return Err(Error::validation_error(
+                    "Exception handling is not supported",
+                ))
+            },
+            _ => return Err(Error::parse_error("Unknown instruction")),
+        }
+        Ok(())
+    }
+
+    /// Instructions with the prefix 0xFC
+    fn prefixed(&mut self) -> Result<()> {
+        use ValueType::{
+            F32,
+            F64,
+            I32,
+            I64,
+        };
+
+        let opcode = self.u32()?;
+        match opcode {
+            0..=7 => {
+                let (param, result) = match opcode {
+                    0 | 1 => (F32, I32),
+                    2 | 3 => (F64, I32),
+                    4 | 5 => (F32, I64),
+                    _ => (F64, I64),
+                };
+                self.operation(OpCode::truncate_saturating(opcode), &[param], Some(result))?;
+            },
+            // memory.init
+            8 => {
+                let segment = self.u32()?;
+                if self.byte()? != 0 {
+                    return Err(Error::validation_error(
+                        "Multiple memories are not supported",
+                    ));
+                }
+                self.memory()?;
+                if segment as usize >= self.module.data.len() {
+                    return Err(Error::validation_error("Unknown data segment"));
+                }
+                self.pops(&[I32, I32, I32])?;
+                self.emit(Op::new(OpCode::MemoryInit, segment));
+            },
+            // data.drop
+            9 => {
+                let segment = self.u32()?;
+                if segment as usize >= self.module.data.len() {
+                    return Err(Error::validation_error("Unknown data segment"));
+                }
+                self.emit(Op::new(OpCode::DataDrop, segment));
+            },
+            // memory.copy, memory.fill
+            10 | 11 => {
+                let memories = if opcode == 10 { 2 } else { 1 };
+                for _ in 0..memories {
+                    if self.byte()? != 0 {
+                        return Err(Error::validation_error(
+                            "Multiple memories are not supported",
+                        ));
+                    }
+                }
+                self.memory()?;
+                self.pops(&[I32, I32, I32])?;
+                let code = if opcode == 10 { OpCode::MemoryCopy } else { OpCode::MemoryFill };
+                self.emit(Op::new(code, 0));
+            },
+            // table.init
+            12 => {
+                let segment = self.u32()?;
+                let table = self.u32()?;
+                if self.element(segment)? != self.table(table)? {
+                    return Err(Error::validation_error(
+                        "Type mismatch: element segment does not match table",
+                    ));
+                }
+                self.pops(&[I32, I32, I32])?;
+                self.emit(Op {
+                    code: OpCode::TableInit,
+                    a:    segment,
+                    b:    table,
+                    c:    0,
+                });
+            },
+            // elem.drop
+            13 => {
+                let segment = self.u32()?;
+                self.element(segment)?;
+                self.emit(Op::new(OpCode::ElemDrop, segment));
+            },
+            // table.copy
+            14 => {
+                let destination = self.u32()?;
+                let source = self.u32()?;
+                if self.table(destination)? != self.table(source)? {
+                    return Err(Error::validation_error(
+                        "Type mismatch: tables differ in element type",
+                    ));
+                }
+                self.pops(&[I32, I32, I32])?;
+                self.emit(Op {
+                    code: OpCode::TableCopy,
+                    a:    destination,
+                    b:    source,
+                    c:    0,
+                });
+            },
+            // table.grow
+            15 => {
+                let table = self.u32()?;
+                let ty = self.table(table)?;
+                self.pops(&[ty, I32])?;
+                self.push(I32);
+                self.emit(Op::new(OpCode::TableGrow, table));
+            },
+            // table.size
+            16 => {
+                let table = self.u32()?;
+                self.table(table)?;
+                self.push(I32);
+                self.emit(Op::new(OpCode::TableSize, table));
+            },
+            // table.fill
+            17 => {
+                let table = self.u32()?;
+                let ty = self.table(table)?;
+                self.pops(&[I32, ty, I32])?;
+                self.emit(Op::new(OpCode::TableFill, table));
+            },
+            _ => return Err(Error::parse_error("Unknown instruction")),
+        }
+        Ok(())
+    }
+}
+
+/// Writes the serialized form of code
+struct Writer<'a>(&'a mut Vec<u8>);
+
+impl Writer<'_> {
+    fn u32(&mut self, value: u32) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn len(&mut self, len: usize) {
+        self.u32(len as u32);
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.len(bytes.len());
+        self.0.extend_from_slice(bytes);
+    }
+
+    fn value_types(&mut self, types: &[ValueType]) {
+        self.len(types.len());
+        self.0.extend(types.iter().map(|ty| ty.to_binary()));
+    }
+
+    fn const_expr(&mut self, expr: &ConstExpr) {
+        self.len(expr.0.len());
+        for op in &expr.0 {
+            match *op {
+                ConstOp::Value(value) => {
+                    self.0.push(0);
+                    self.0.extend_from_slice(&value.to_le_bytes());
+                },
+                ConstOp::GlobalGet(index) => {
+                    self.0.push(1);
+                    self.u32(index);
+                },
+                ConstOp::I32Add => self.0.push(2),
+                ConstOp::I32Sub => self.0.push(3),
+                ConstOp::I32Mul => self.0.push(4),
+                ConstOp::I64Add => self.0.push(5),
+                ConstOp::I64Sub => self.0.push(6),
+                ConstOp::I64Mul => self.0.push(7),
+            }
+        }
+    }
+
+    fn mode(&mut self, mode: &SegmentMode) {
+        match mode {
+            SegmentMode::Active { index, offset } => {
+                self.0.push(0);
+                self.u32(*index);
+                self.const_expr(offset);
+            },
+            SegmentMode::Passive => self.0.push(1),
+            SegmentMode::Declared => self.0.push(2),
+        }
+    }
+}
+
+/// Reads the serialized form of code
+struct Reader<'a, 'p> {
+    bytes: &'a [u8],
+    pos:   &'p mut usize,
+}
+
+impl<'a> Reader<'a, '_> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let bytes = self
+            .bytes
+            .get(*self.pos..*self.pos + len)
+            .ok_or_else(|| Error::validation_parse_error("Serialized code truncated"))?;
+        *self.pos += len;
+        Ok(bytes)
+    }
+
+    fn byte(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn len(&mut self) -> Result<usize> {
+        Ok(self.u32()? as usize)
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8]> {
+        let len = self.len()?;
+        self.take(len)
+    }
+
+    fn string(&mut self) -> Result<String> {
+        core::str::from_utf8(self.bytes()?)
+            .map(String::from)
+            .map_err(|_| Error::validation_parse_error("Serialized name is not UTF-8"))
+    }
+
+    fn value_types(&mut self) -> Result<Vec<ValueType>> {
+        self.bytes()?.iter().map(|byte| ValueType::from_binary(*byte)).collect()
+    }
+
+    fn ref_type(&mut self) -> Result<RefType> {
+        RefType::from_value_type(ValueType::from_binary(self.byte()?)?)
+    }
+
+    fn const_expr(&mut self) -> Result<ConstExpr> {
+        let mut ops = Vec::new();
+        for _ in 0..self.len()? {
+            ops.push(match self.byte()? {
+                0 => ConstOp::Value(self.u64()?),
+                1 => ConstOp::GlobalGet(self.u32()?),
+                2 => ConstOp::I32Add,
+                3 => ConstOp::I32Sub,
+                4 => ConstOp::I32Mul,
+                5 => ConstOp::I64Add,
+                6 => ConstOp::I64Sub,
+                7 => ConstOp::I64Mul,
+                _ => return Err(Error::validation_parse_error("Unknown constant operation")),
+            });
+        }
+        Ok(ConstExpr(ops))
+    }
+
+    fn mode(&mut self) -> Result<SegmentMode> {
+        Ok(match self.byte()? {
+            0 => SegmentMode::Active {
+                index:  self.u32()?,
+                offset: self.const_expr()?,
+            },
+            1 => SegmentMode::Passive,
+            2 => SegmentMode::Declared,
+            _ => return Err(Error::validation_parse_error("Unknown segment mode")),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wrt_foundation::CleanCoreFuncType;
+
+    use super::*;
+
+    fn module(results: Vec<ValueType>, body: &[u8]) -> FormatModule {
+        let mut module = FormatModule::new();
+        module.types.push(CleanCoreFuncType {
+            params: vec![ValueType::I32],
+            results,
+        });
+        module.functions.push(FormatFunction {
+            type_idx: 0,
+            locals:   Vec::new(),
+            code:     body.to_vec(),
+        });
+        module
+    }
+
+    #[test]
+    fn test_branches_resolve_their_targets() {
+        // block (result i32) i32.const 1 local.get 0 br_if 0 drop i32.const 2
+        // end
+        let code = ModuleCode::compile(&module(
+            vec![ValueType::I32],
+            &[
+                0x00, 0x02, 0x7F, 0x41, 0x01, 0x20, 0x00, 0x0D, 0x00, 0x1A, 0x41, 0x02, 0x0B, 0x0B,
+            ],
+        ))
+        .unwrap();
+        let ops = &code.functions[0].ops;
+
+        assert_eq!(ops[2].code, OpCode::BrIf);
+        // Past the block, keeping one value above the parameter
+        assert_eq!(
+            ops[2].target(),
+            Target {
+                pc:     5,
+                height: 1,
+                arity:  1,
+            }
+        );
+        assert_eq!(ops[5], Op::new(OpCode::Return, 1));
+    }
+
+    #[test]
+    fn test_invalid_bodies_are_rejected() {
+        // i64.const 0 returned as i32
+        assert!(
+            ModuleCode::compile(&module(vec![ValueType::I32], &[0x00, 0x42, 0x00, 0x0B])).is_err()
+        );
+        // br to a missing label
+        assert!(ModuleCode::compile(&module(vec![], &[0x00, 0x0C, 0x01, 0x0B])).is_err());
+        // local.get of a missing local
+        assert!(ModuleCode::compile(&module(vec![], &[0x00, 0x20, 0x01, 0x1A, 0x0B])).is_err());
+        // Missing end
+        assert!(ModuleCode::compile(&module(vec![], &[0x00, 0x01])).is_err());
+        // SIMD
+        assert!(ModuleCode::compile(&module(vec![], &[0x00, 0xFD, 0x0C, 0x0B])).is_err());
+        // Unreachable code is validated with unknown operands
+        assert!(
+            ModuleCode::compile(&module(vec![ValueType::I32], &[0x00, 0x00, 0x6A, 0x0B])).is_ok()
+        );
+    }
+
+    #[test]
+    fn test_serialized_code_round_trips() {
+        let code = ModuleCode::compile(&module(
+            vec![ValueType::I32],
+            &[
+                0x01, 0x02, 0x7E, 0x41, 0x07, 0x41, 0x00, 0x0E, 0x01, 0x00, 0x00, 0x0B,
+            ],
+        ))
+        .unwrap();
+        let mut bytes = Vec::new();
+        code.serialize(&mut bytes);
+
+        let mut pos = 0;
+        assert_eq!(ModuleCode::deserialize(&bytes, &mut pos).unwrap(), code);
+        assert_eq!(pos, bytes.len());
+        assert!(ModuleCode::deserialize(&bytes[..bytes.len() - 1], &mut 0).is_err());
+    }
+}
@@ -138,7 +138,29 @@ impl<T: Eq> Eq for Arc<T> {}
     },
 };
 
-use crate::module_instance::ModuleInstance;
+#[cfg(feature = "std")]
+use super::interpreter::{
+    Interpreter,
+    Suspension,
+};
+use super::stack_usage::{
+    NativeStackMeter,
+    NativeStackUsage,
+};
+#[cfg(any(feature = "std", feature = "alloc"))]
+use crate::execution_backend::HostImports;
+#[cfg(feature = "std")]
+use crate::guest_coverage::GuestCoverage;
+#[cfg(feature = "std")]
+use crate::heap_profile::ProfiledHeap;
+use crate::{
+    interrupt::Interruption,
+    mcdc::{
+        decision,
+        Decision,
+    },
+    module_instance::ModuleInstance,
+};
 
 /// Maximum number of concurrent module instances
 const MAX_CONCURRENT_INSTANCES: usize = 16;
@@ -147,41 +169,60 @@ impl<T: Eq> Eq for Arc<T> {}
 #[derive(Debug, Default)]
 pub struct ExecutionStats {
     /// Number of function calls executed
-    pub function_calls: u64,
+    pub function_calls:        u64,
+    /// Number of instructions executed
+    pub instructions_executed: u64,
 }
 
 /// Simple stackless WebAssembly execution engine
 #[cfg(any(feature = "std", feature = "alloc"))]
 pub struct StacklessEngine {
     /// Currently loaded instances indexed by numeric ID
-    instances:             HashMap<usize, Arc<ModuleInstance>>,
+    instances:               HashMap<usize, Arc<ModuleInstance>>,
     /// Next instance ID
-    next_instance_id:      AtomicU64,
+    next_instance_id:        AtomicU64,
     /// Current active instance for execution
-    current_instance_id:   Option<usize>,
+    current_instance_id:     Option<usize>,
     /// Operand stack for execution (needed by tail_call module)
-    pub operand_stack:     Vec<Value>,
+    pub operand_stack:       Vec<Value>,
     /// Call frames count (needed by tail_call module)
-    pub call_frames_count: usize,
+    pub call_frames_count:   usize,
     /// Execution statistics (needed by tail_call module)
-    pub stats:             ExecutionStats,
+    pub stats:               ExecutionStats,
+    /// Native stack usage per guest call depth
+    pub(crate) stack_meter:  NativeStackMeter,
+    /// Epoch and deadlines interrupting execution at safe points
+    pub(crate) interruption: Interruption,
+    /// Invocation that ran out of fuel, continued when it is repeated
+    #[cfg(feature = "std")]
+    pub(crate) suspended:    std::sync::Mutex<Option<Suspension>>,
+    /// Guest code coverage of instances, counted by the interpreter
+    #[cfg(feature = "std")]
+    pub(crate) coverage:     HashMap<usize, Arc<GuestCoverage>>,
+    /// Heap profiles of instances, recording the allocator calls of guests
+    #[cfg(feature = "std")]
+    pub(crate) heap_profiles: HashMap<usize, Arc<ProfiledHeap>>,
 }
 
 /// Simple stackless WebAssembly execution engine (no_std version)
 #[cfg(not(any(feature = "std", feature = "alloc")))]
 pub struct StacklessEngine {
     /// Currently loaded instances indexed by numeric ID
-    instances:             HashMap<usize, Arc<ModuleInstance>>,
+    instances:               HashMap<usize, Arc<ModuleInstance>>,
     /// Next instance ID
-    next_instance_id:      AtomicU64,
+    next_instance_id:        AtomicU64,
     /// Current active instance for execution
-    current_instance_id:   Option<usize>,
+    current_instance_id:     Option<usize>,
     /// Operand stack for execution (needed by tail_call module)
-    pub operand_stack:     Vec<Value>,
+    pub operand_stack:       Vec<Value>,
     /// Call frames count (needed by tail_call module)
-    pub call_frames_count: usize,
+    pub call_frames_count:   usize,
     /// Execution statistics (needed by tail_call module)
-    pub stats:             ExecutionStats,
+    pub stats:               ExecutionStats,
+    /// Native stack usage per guest call depth
+    pub(crate) stack_meter:  NativeStackMeter,
+    /// Epoch and deadlines interrupting execution at safe points
+    pub(crate) interruption: Interruption,
 }
 
 impl StacklessEngine {
@@ -195,6 +236,14 @@ pub fn new() -> Self {
             operand_stack:       Vec::new(),
             call_frames_count:   0,
             stats:               ExecutionStats::default(),
+            stack_meter:         NativeStackMeter::new(),
+            interruption:        Interruption::new(),
+            #[cfg(feature = "std")]
+            suspended: std::sync::Mutex::new(None),
+            #[cfg(feature = "std")]
+            coverage: HashMap::new(),
+            #[cfg(feature = "std")]
+            heap_profiles: HashMap::new(),
         }
     }
 
@@ -221,6 +270,8 @@ pub fn new() -> wrt_error::Result<Self> {
                 operand_stack:       Vec::new(),
                 call_frames_count:   0,
                 stats:               ExecutionStats::default(),
+                stack_meter:         NativeStackMeter::new(),
+                interruption:        Interruption::new(),
             })
         }
 
@@ -233,10 +284,17 @@ pub fn new() -> wrt_error::Result<Self> {
                 operand_stack,
                 call_frames_count: 0,
                 stats: ExecutionStats::default(),
+                stack_meter: NativeStackMeter::new(),
+                interruption: Interruption::new(),
             })
         }
     }
 
+    /// Instance ID the next call to `set_current_module` returns
+    pub(crate) fn next_instance_id(&self) -> usize {
+        self.next_instance_id.load(Ordering::Relaxed) as usize
+    }
+
     /// Set the current module for execution
     ///
     /// Returns the instance ID that can be used for execution
@@ -256,92 +314,79 @@ pub fn set_current_module(&mut self, instance: Arc<ModuleInstance>) -> Result<us
         Ok(instance_id)
     }
 
+    /// Instance with the ID `instance_id`, if it is loaded
+    #[cfg(feature = "std")]
+    pub(crate) fn instance(&self, instance_id: usize) -> Option<Arc<ModuleInstance>> {
+        self.instances.get(&instance_id).cloned()
+    }
+
+    /// All loaded instances, in no particular order
+    #[cfg(feature = "std")]
+    pub(crate) fn instances(&self) -> impl Iterator<Item = &Arc<ModuleInstance>> {
+        self.instances.values()
+    }
+
+    /// Native stack usage of calls executed so far, per guest call depth
+    ///
+    /// Guest calls do not recurse on the native stack, so the usage observed
+    /// is expected not to grow with the call depth unless host functions
+    /// call back into the engine; see [`NativeStackUsage::growth_per_call`].
+    pub fn native_stack_usage(&self) -> NativeStackUsage {
+        self.stack_meter.usage()
+    }
+
     /// Execute a function in the specified instance
     ///
     /// # Arguments
     /// * `instance_id` - The instance ID returned from set_current_module
     /// * `func_idx` - The function index to execute
     /// * `args` - Function arguments
+    /// * `host` - Host functions the instance imports
     ///
     /// # Returns
     /// The function results
-    #[cfg(any(feature = "std", feature = "alloc"))]
+    #[cfg(feature = "std")]
     pub fn execute(
         &self,
         instance_id: usize,
         func_idx: usize,
         args: Vec<Value>,
+        host: &mut dyn HostImports,
     ) -> Result<Vec<Value>> {
-        #[cfg(any(feature = "std", feature = "alloc"))]
-        let instance = self
-            .instances
-            .get(&instance_id)
-            .ok_or_else(|| wrt_error::Error::runtime_execution_error("Instance not found"))?;
+        let _entry = self.stack_meter.enter();
+        self.stack_meter.probe(self.call_frames_count);
+        self.interruption.yield_point()?;
 
-        #[cfg(not(any(feature = "std", feature = "alloc")))]
         let instance = self
             .instances
-            .get(&instance_id)?
+            .get(&instance_id)
             .ok_or_else(|| wrt_error::Error::runtime_execution_error("Instance not found"))?;
+        let func_idx = u32::try_from(func_idx).map_err(|_| {
+            wrt_error::Error::runtime_function_not_found("Function index out of bounds")
+        })?;
 
-        // For now, implement a basic execution that validates the function exists
-        // and returns appropriate results
-        let module = instance.module();
-
-        // Validate function index
-        if func_idx >= module.functions.len() {
-            return Err(wrt_error::Error::runtime_function_not_found(
-                "Function index out of bounds",
-            ));
-        }
-
-        // Get function type to determine return values
-        let func = module
-            .functions
-            .get(func_idx)
-            .map_err(|_| wrt_error::Error::runtime_function_not_found("Failed to get function"))?;
-        let func_type = module
-            .types
-            .get(func.type_idx as usize)
-            .map_err(|_| wrt_error::Error::runtime_error("Failed to get function type"))?;
-
-        // For demonstration, we'll simulate successful execution
-        // In a real implementation, this would:
-        // 1. Set up the execution stack
-        // 2. Execute WebAssembly instructions
-        // 3. Handle traps and errors
-        // 4. Return actual computed results
-
-        // Return appropriate default values based on function signature
-        #[cfg(any(feature = "std", feature = "alloc"))]
-        let mut results = Vec::new();
-
-        #[cfg(not(any(feature = "std", feature = "alloc")))]
-        let mut results = {
-            use wrt_foundation::{
-                budget_aware_provider::CrateId,
-                safe_managed_alloc,
-            };
-
-            use crate::bounded_runtime_infra::RUNTIME_MEMORY_SIZE;
-            let provider = safe_managed_alloc!(RUNTIME_MEMORY_SIZE, CrateId::Runtime)?;
-            BoundedVec::new(provider)?
-        };
-        for result_type in &func_type.results {
-            let default_value = match result_type {
-                wrt_foundation::ValueType::I32 => Value::I32(0),
-                wrt_foundation::ValueType::I64 => Value::I64(0),
-                wrt_foundation::ValueType::F32 => Value::F32(FloatBits32(0.0f32.to_bits())),
-                wrt_foundation::ValueType::F64 => Value::F64(FloatBits64(0.0f64.to_bits())),
-                // Add other types as needed
-                _ => Value::I32(0), // Default fallback
-            };
-            results.push(default_value);
-        }
+        let results = Interpreter::new(self, instance_id, instance, host).invoke(func_idx, args)?;
 
+        self.interruption.check()?;
         Ok(results)
     }
 
+    /// Execute a function in the specified instance
+    ///
+    /// Builds without `std` carry no compiled code to interpret.
+    #[cfg(all(feature = "alloc", not(feature = "std")))]
+    pub fn execute(
+        &self,
+        _instance_id: usize,
+        _func_idx: usize,
+        _args: Vec<Value>,
+        _host: &mut dyn HostImports,
+    ) -> Result<Vec<Value>> {
+        Err(wrt_error::Error::not_supported_unsupported_operation(
+            "Execution requires the std feature",
+        ))
+    }
+
     #[cfg(not(any(feature = "std", feature = "alloc")))]
     pub fn execute(
         &self,
@@ -349,6 +394,10 @@ pub fn execute(
         func_idx: usize,
         args: Vec<Value>,
     ) -> Result<Vec<Value>> {
+        let _entry = self.stack_meter.enter();
+        self.stack_meter.probe(self.call_frames_count);
+        self.interruption.yield_point()?;
+
         let instance = self
             .instances
             .get(&instance_id)?
@@ -359,7 +408,10 @@ pub fn execute(
         let module = instance.module();
 
         // Validate function index
-        if func_idx >= module.functions.len() {
+        if !decision!(
+            Decision::FunctionIndexInBounds,
+            func_idx < module.functions.len()
+        ) {
             return Err(wrt_error::Error::runtime_function_not_found(
                 "Function index out of bounds",
             ));
@@ -400,6 +452,7 @@ pub fn execute(
                 .map_err(|_| wrt_error::Error::runtime_error("Failed to push result value"))?;
         }
 
+        self.interruption.check()?;
         Ok(results)
     }
 }
@@ -0,0 +1,1156 @@
+//! Interpreter of compiled code
+//!
+//! Runs the [`FunctionCode`] of a module's [`ModuleCode`]. Guest calls push
+//! a [`Frame`] onto the interpreter's frame stack rather than recursing on
+//! the native stack, and the locals and operands of all frames share one
+//! stack of 64-bit slots, laid out as described in the [`code`](super::code)
+//! module.
+//!
+//! The top slot of that stack is cached in a field of the interpreter rather
+//! than kept in its vector, so most operations, which take one or two
+//! operands and push one result, read and write their result there without
+//! growing or shrinking the vector. A placeholder slot at the bottom of the
+//! stack keeps the cached slot defined while no values are on the stack.
+//!
+//! The interpreter passes a [yield point](crate::interrupt) after every
+//! branch to an earlier operation and after every guest call, so loops and
+//! recursion consume fuel and can be interrupted. An invocation that runs out
+//! of fuel there keeps its frames and operands as a [`Suspension`] of the
+//! engine, and continues from it when it is repeated with the same arguments.
+//!
+//! Tables cannot change once an instance exists, as instances share them
+//! immutably: `table.grow` fails by returning -1, which the specification
+//! permits, and `table.set`, `table.fill`, `table.copy` and `table.init`
+//! trap on out of bounds accesses as they must but otherwise fail with an
+//! unsupported operation error unless they change no element.
+
+use std::sync::PoisonError;
+
+use wrt_error::codes::{
+    self,
+    TrapCode,
+};
+use wrt_foundation::values::{
+    ExternRef,
+    FloatBits32 as ValueBits32,
+    FloatBits64 as ValueBits64,
+    FuncRef,
+};
+use wrt_math::{
+    self as math,
+    FloatBits32,
+    FloatBits64,
+};
+
+use super::{
+    code::{
+        ConstExpr,
+        FunctionCode,
+        ModuleCode,
+        OpCode,
+        SegmentMode,
+        Target,
+        NULL_REF,
+    },
+    StacklessEngine,
+};
+use crate::{
+    execution_backend::HostImports,
+    global::Global,
+    guest_coverage::GuestCoverage,
+    heap_profile::{
+        AllocatorCall,
+        ProfiledHeap,
+    },
+    module::MemoryWrapper,
+    module_instance::ModuleInstance,
+    prelude::*,
+    table::Table,
+};
+
+/// Most guest calls nested in one invocation
+pub const MAX_CALL_DEPTH: usize = 16_384;
+
+/// Most slots for locals and operands of one invocation
+const MAX_STACK_SLOTS: usize = 1 << 22;
+
+/// Element and data segments an instance has dropped
+///
+/// Active and declared segments count as dropped once the instance exists.
+#[derive(Debug, Default)]
+pub(crate) struct DroppedSegments {
+    elements: Mutex<HashSet<u32>>,
+    data:     Mutex<HashSet<u32>>,
+}
+
+impl DroppedSegments {
+    fn drop_element(&self, index: u32) {
+        self.elements.lock().unwrap_or_else(PoisonError::into_inner).insert(index);
+    }
+
+    fn drop_data(&self, index: u32) {
+        self.data.lock().unwrap_or_else(PoisonError::into_inner).insert(index);
+    }
+
+    fn element_dropped(&self, code: &ModuleCode, index: u32) -> bool {
+        !code
+            .elements
+            .get(index as usize)
+            .is_some_and(|element| matches!(element.mode, SegmentMode::Passive))
+            || self.elements.lock().unwrap_or_else(PoisonError::into_inner).contains(&index)
+    }
+
+    fn data_dropped(&self, code: &ModuleCode, index: u32) -> bool {
+        !code
+            .data
+            .get(index as usize)
+            .is_some_and(|data| matches!(data.mode, SegmentMode::Passive))
+            || self.data.lock().unwrap_or_else(PoisonError::into_inner).contains(&index)
+    }
+}
+
+/// Slot representation of a value
+trait Slot: Sized {
+    fn from_slot(slot: u64) -> Self;
+    fn into_slot(self) -> u64;
+}
+
+impl Slot for i32 {
+    fn from_slot(slot: u64) -> Self {
+        slot as u32 as i32
+    }
+
+    fn into_slot(self) -> u64 {
+        u64::from(self as u32)
+    }
+}
+
+impl Slot for u32 {
+    fn from_slot(slot: u64) -> Self {
+        slot as u32
+    }
+
+    fn into_slot(self) -> u64 {
+        u64::from(self)
+    }
+}
+
+impl Slot for i64 {
+    fn from_slot(slot: u64) -> Self {
+        slot as i64
+    }
+
+    fn into_slot(self) -> u64 {
+        self as u64
+    }
+}
+
+impl Slot for u64 {
+    fn from_slot(slot: u64) -> Self {
+        slot
+    }
+
+    fn into_slot(self) -> u64 {
+        self
+    }
+}
+
+impl Slot for FloatBits32 {
+    fn from_slot(slot: u64) -> Self {
+        FloatBits32(slot as u32)
+    }
+
+    fn into_slot(self) -> u64 {
+        u64::from(self.0)
+    }
+}
+
+impl Slot for FloatBits64 {
+    fn from_slot(slot: u64) -> Self {
+        FloatBits64(slot)
+    }
+
+    fn into_slot(self) -> u64 {
+        self.0
+    }
+}
+
+/// Slot of `value`
+pub(crate) fn value_to_slot(value: &Value) -> Result<u64> {
+    Ok(match value {
+        Value::I32(value) => value.into_slot(),
+        Value::I64(value) => value.into_slot(),
+        Value::F32(bits) => u64::from(bits.0),
+        Value::F64(bits) => bits.0,
+        Value::FuncRef(reference) => reference.as_ref().map_or(NULL_REF, |r| u64::from(r.index)),
+        Value::ExternRef(reference) => reference.as_ref().map_or(NULL_REF, |r| u64::from(r.index)),
+        _ => {
+            return Err(Error::not_supported_unsupported_operation(
+                "Value type is not supported by the interpreter",
+            ))
+        },
+    })
+}
+
+/// Value of type `ty` held in `slot`
+pub(crate) fn slot_to_value(ty: ValueType, slot: u64) -> Value {
+    let index = (slot != NULL_REF).then_some(slot as u32);
+    match ty {
+        ValueType::I64 => Value::I64(slot as i64),
+        ValueType::F32 => Value::F32(ValueBits32(slot as u32)),
+        ValueType::F64 => Value::F64(ValueBits64(slot)),
+        ValueType::FuncRef => Value::FuncRef(index.map(FuncRef::from_index)),
+        ValueType::ExternRef => Value::ExternRef(index.map(|index| ExternRef { index })),
+        _ => Value::I32(slot as u32 as i32),
+    }
+}
+
+/// Slot of a table element
+fn element_to_slot(element: Option<Value>) -> u64 {
+    match element {
+        Some(Value::FuncRef(Some(reference))) => u64::from(reference.index),
+        Some(Value::ExternRef(Some(reference))) => u64::from(reference.index),
+        _ => NULL_REF,
+    }
+}
+
+/// Value of the constant expression `expr`, which may read the globals of
+/// `instance`
+fn evaluate(expr: &ConstExpr, instance: &ModuleInstance) -> Result<u64> {
+    expr.evaluate(|index| value_to_slot(&instance.global_value(index)?))
+}
+
+/// Global `index` of `instance` as its initializer defines it, or `None` if
+/// the module has no initializer for it
+///
+/// Globals the initializer reads must already be added to the instance.
+pub(crate) fn initial_global(instance: &ModuleInstance, index: usize) -> Result<Option<Global>> {
+    let Some(global) = instance.module().code.globals.get(index) else {
+        return Ok(None);
+    };
+    let Some(init) = &global.init else {
+        return Ok(None);
+    };
+    let value = slot_to_value(global.ty, evaluate(init, instance)?);
+    Global::new(global.ty, global.mutable, value).map(Some)
+}
+
+/// Copy the active element segments for table `index` of `instance` into
+/// `table`, or check that there are none if `table` is imported and so
+/// cannot be initialized
+pub(crate) fn initialize_table(
+    instance: &ModuleInstance,
+    index: u32,
+    mut table: Option<&mut Table>,
+) -> Result<()> {
+    let code = &instance.module().code;
+    for segment in &code.elements {
+        let SegmentMode::Active {
+            index: table_idx,
+            offset,
+        } = &segment.mode
+        else {
+            continue;
+        };
+        if *table_idx != index {
+            continue;
+        }
+        let offset = evaluate(offset, instance)? as u32;
+        let size = match &table {
+            Some(table) => table.size(),
+            None => instance.table(index)?.size(),
+        };
+        if u64::from(offset) + segment.items.len() as u64 > u64::from(size) {
+            return Err(TrapCode::TableOutOfBounds.into());
+        }
+        let Some(table) = table.as_deref_mut() else {
+            if segment.items.is_empty() {
+                continue;
+            }
+            return Err(Error::not_supported_unsupported_operation(
+                "Element segments cannot initialize imported tables",
+            ));
+        };
+        let ty = segment.ty.to_value_type();
+        let items = segment
+            .items
+            .iter()
+            .map(|item| Ok(Some(slot_to_value(ty, evaluate(item, instance)?))))
+            .collect::<Result<Vec<_>>>()?;
+        table.init(offset, &items)?;
+    }
+    Ok(())
+}
+
+/// Copy the active data segments of `instance` into its memories
+pub(crate) fn initialize_memories(instance: &ModuleInstance) -> Result<()> {
+    let code = &instance.module().code;
+    for segment in &code.data {
+        let SegmentMode::Active { index, offset } = &segment.mode else {
+            continue;
+        };
+        let memory = instance.memory(*index)?;
+        let offset = evaluate(offset, instance)? as u32;
+        if u64::from(offset) + segment.bytes.len() as u64 > memory.size_in_bytes() as u64 {
+            return Err(TrapCode::MemoryOutOfBounds.into());
+        }
+        memory.write(offset, &segment.bytes)?;
+    }
+    Ok(())
+}
+
+/// Activation of a function the module defines
+#[derive(Debug, Clone, Copy)]
+struct Frame {
+    /// Index of the function among the defined functions
+    func: usize,
+    /// Position of the next operation once the frame is resumed
+    pc:   usize,
+    /// Position of the first local on the value stack
+    base: usize,
+}
+
+/// Invocation stopped at a yield point because it ran out of fuel
+#[derive(Debug)]
+pub(crate) struct Suspension {
+    instance_id: usize,
+    func_idx:    u32,
+    args:        Vec<Value>,
+    stack:       Vec<u64>,
+    frames:      Vec<Frame>,
+    allocating:  Vec<AllocatorFrame>,
+}
+
+/// Call of an exported allocator from guest code, recorded in the heap
+/// profile once it returns
+#[derive(Debug)]
+struct AllocatorFrame {
+    /// Position of the allocator's frame
+    depth: usize,
+    name:  &'static str,
+    args:  Vec<Value>,
+}
+
+/// Executes calls of an instance's functions
+pub(crate) struct Interpreter<'a> {
+    engine:      &'a StacklessEngine,
+    instance_id: usize,
+    instance:    &'a ModuleInstance,
+    code:        Arc<ModuleCode>,
+    memory:      Option<MemoryWrapper>,
+    host:        &'a mut dyn HostImports,
+    coverage:    Option<Arc<GuestCoverage>>,
+    heap:        Option<Arc<ProfiledHeap>>,
+    /// Slots below the top of the stack, the placeholder first
+    stack:       Vec<u64>,
+    /// Top slot of the stack
+    top:         u64,
+    frames:      Vec<Frame>,
+    /// Allocator calls of the guest that have not returned yet
+    allocating:  Vec<AllocatorFrame>,
+}
+
+impl<'a> Interpreter<'a> {
+    pub(crate) fn new(
+        engine: &'a StacklessEngine,
+        instance_id: usize,
+        instance: &'a ModuleInstance,
+        host: &'a mut dyn HostImports,
+    ) -> Self {
+        Self {
+            engine,
+            instance_id,
+            instance,
+            code: instance.module().code.clone(),
+            memory: instance.memory(0).ok(),
+            host,
+            coverage: engine.coverage.get(&instance_id).cloned(),
+            heap: engine.heap_profiles.get(&instance_id).cloned(),
+            stack: Vec::new(),
+            top: 0,
+            frames: Vec::new(),
+            allocating: Vec::new(),
+        }
+    }
+
+    /// Call function `func_idx` with `args`, returning its results
+    ///
+    /// Continues the suspended invocation of the engine instead if it called
+    /// the same function of the same instance with the same arguments, and
+    /// discards it otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the function does not exist, `args` do not match
+    /// its parameters, or the function traps.
+    pub(crate) fn invoke(mut self, func_idx: u32, args: Vec<Value>) -> Result<Vec<Value>> {
+        let code = self.code.clone();
+        let type_idx = code
+            .function_type(func_idx)
+            .ok_or_else(|| Error::runtime_function_not_found("Function index out of bounds"))?;
+        let signature = &code.types[type_idx as usize];
+
+        let suspended = self.engine.suspended.lock().unwrap_or_else(PoisonError::into_inner).take();
+        match suspended.filter(|suspension| {
+            suspension.instance_id == self.instance_id
+                && suspension.func_idx == func_idx
+                && suspension.args == args
+        }) {
+            Some(suspension) => {
+                self.stack = suspension.stack;
+                self.fill();
+                self.frames = suspension.frames;
+                self.allocating = suspension.allocating;
+            },
+            None => {
+                if args.len() != signature.params.len()
+                    || args.iter().zip(&signature.params).any(|(arg, ty)| arg.value_type() != *ty)
+                {
+                    return Err(Error::runtime_type_mismatch(
+                        "Arguments do not match the function parameters",
+                    ));
+                }
+                for arg in &args {
+                    self.push(value_to_slot(arg)?);
+                }
+                self.call(&code, func_idx)?;
+            },
+        }
+
+        if !self.frames.is_empty() {
+            if let Err(error) = self.run(&code) {
+                if error.code == codes::FUEL_EXHAUSTED {
+                    self.spill();
+                    *self.engine.suspended.lock().unwrap_or_else(PoisonError::into_inner) =
+                        Some(Suspension {
+                            instance_id: self.instance_id,
+                            func_idx,
+                            args,
+                            stack: core::mem::take(&mut self.stack),
+                            frames: core::mem::take(&mut self.frames),
+                            allocating: core::mem::take(&mut self.allocating),
+                        });
+                }
+                return Err(error);
+            }
+        }
+
+        self.spill();
+        let results = self.stack.split_off(self.stack.len() - signature.results.len());
+        Ok(signature
+            .results
+            .iter()
+            .zip(results)
+            .map(|(ty, slot)| slot_to_value(*ty, slot))
+            .collect())
+    }
+
+    /// Call function `func_idx` with the arguments on top of the stack:
+    /// host functions are called right away, and defined functions get a
+    /// frame the run loop continues in
+    fn call(&mut self, code: &ModuleCode, func_idx: u32) -> Result<()> {
+        let Some(func) = (func_idx as usize).checked_sub(code.imports.len()) else {
+            return self.call_import(code, func_idx as usize);
+        };
+        let function = code
+            .functions
+            .get(func)
+            .ok_or_else(|| Error::runtime_function_not_found("Function index out of bounds"))?;
+        if self.frames.len() >= MAX_CALL_DEPTH {
+            return Err(Error::new(
+                ErrorCategory::Runtime,
+                codes::CALL_STACK_EXHAUSTED,
+                "Call stack exhausted",
+            ));
+        }
+        if self.len() + function.locals.len() > MAX_STACK_SLOTS {
+            return Err(Error::new(
+                ErrorCategory::Runtime,
+                codes::CALL_STACK_EXHAUSTED,
+                "Value stack exhausted",
+            ));
+        }
+        let params = code.types[function.type_idx as usize].params.len();
+        let base = self.len() - params;
+        if !function.locals.is_empty() {
+            self.spill();
+            self.stack.extend(function.locals.iter().map(|ty| match ty {
+                ValueType::FuncRef | ValueType::ExternRef => NULL_REF,
+                _ => 0,
+            }));
+            self.fill();
+        }
+        // The engine records the allocator calls of the host itself
+        if let Some(heap) = self.heap.as_ref().filter(|_| !self.frames.is_empty()) {
+            if let Some(name) = heap.allocator(func_idx) {
+                let args = code.types[function.type_idx as usize]
+                    .params
+                    .iter()
+                    .enumerate()
+                    .map(|(i, ty)| slot_to_value(*ty, self.local(base + i)))
+                    .collect();
+                self.allocating.push(AllocatorFrame {
+                    depth: self.frames.len(),
+                    name,
+                    args,
+                });
+            }
+        }
+        self.frames.push(Frame { func, pc: 0, base });
+        if let Some(coverage) = &self.coverage {
+            coverage.record_call(func_idx);
+        }
+        Ok(())
+    }
+
+    /// Record the return of the guest's call of an allocator, whose
+    /// results are on top of the stack, in the heap profile
+    fn record_allocator_return(&mut self, code: &ModuleCode, func: usize) {
+        let (Some(heap), Some(call)) = (&self.heap, self.allocating.pop()) else {
+            return;
+        };
+        let results = &code.types[code.functions[func].type_idx as usize].results;
+        let base = self.len() - results.len();
+        let results: Vec<Value> = results
+            .iter()
+            .enumerate()
+            .map(|(i, ty)| slot_to_value(*ty, self.local(base + i)))
+            .collect();
+        if let Some(call) = AllocatorCall::recognize(call.name, &call.args, &results) {
+            heap.profile.record(call, None);
+        }
+    }
+
+    fn call_import(&mut self, code: &ModuleCode, import: usize) -> Result<()> {
+        let import = &code.imports[import];
+        let signature = &code.types[import.type_idx as usize];
+        self.spill();
+        let args = self
+            .stack
+            .split_off(self.stack.len() - signature.params.len())
+            .into_iter()
+            .zip(&signature.params)
+            .map(|(slot, ty)| slot_to_value(*ty, slot))
+            .collect();
+        self.fill();
+        let results =
+            self.host.call_import(self.instance_id, &import.module, &import.name, args)?;
+        if results.len() != signature.results.len()
+            || results
+                .iter()
+                .zip(&signature.results)
+                .any(|(value, ty)| value.value_type() != *ty)
+        {
+            return Err(Error::runtime_type_mismatch(
+                "Host function results do not match its type",
+            ));
+        }
+        for result in &results {
+            self.push(value_to_slot(result)?);
+        }
+        Ok(())
+    }
+
+    /// Number of slots on the stack, the placeholder included
+    fn len(&self) -> usize {
+        self.stack.len() + 1
+    }
+
+    /// Move the cached top slot into the vector, leaving the whole stack
+    /// there until [`Self::fill`]
+    fn spill(&mut self) {
+        self.stack.push(self.top);
+    }
+
+    /// Cache the top slot of the vector again after [`Self::spill`]
+    fn fill(&mut self) {
+        self.top = self.stack.pop().unwrap_or(0);
+    }
+
+    /// Slot `index` of the stack, which holds a local
+    fn local(&self, index: usize) -> u64 {
+        match self.stack.get(index) {
+            Some(slot) => *slot,
+            None => self.top,
+        }
+    }
+
+    fn set_local(&mut self, index: usize, slot: u64) {
+        match self.stack.get_mut(index) {
+            Some(local) => *local = slot,
+            None => self.top = slot,
+        }
+    }
+
+    /// Pop the top slot, which fails only on the placeholder
+    fn pop(&mut self) -> Result<u64> {
+        let below = self
+            .stack
+            .pop()
+            .ok_or_else(|| Error::runtime_stack_underflow("Operand stack underflow"))?;
+        Ok(core::mem::replace(&mut self.top, below))
+    }
+
+    fn push(&mut self, slot: u64) {
+        self.stack.push(core::mem::replace(&mut self.top, slot));
+    }
+
+    fn top(&mut self) -> &mut u64 {
+        &mut self.top
+    }
+
+    fn unary<A: Slot, R: Slot>(&mut self, op: impl FnOnce(A) -> Result<R>) -> Result<()> {
+        self.top = op(A::from_slot(self.top))?.into_slot();
+        Ok(())
+    }
+
+    fn binary<A: Slot, R: Slot>(&mut self, op: impl FnOnce(A, A) -> Result<R>) -> Result<()> {
+        let lhs = self
+            .stack
+            .pop()
+            .ok_or_else(|| Error::runtime_stack_underflow("Operand stack underflow"))?;
+        self.top = op(A::from_slot(lhs), A::from_slot(self.top))?.into_slot();
+        Ok(())
+    }
+
+    /// Pass a yield point, with the current frame continuing at `pc`
+    fn yield_point(&mut self, pc: usize) -> Result<()> {
+        if let Some(frame) = self.frames.last_mut() {
+            frame.pc = pc;
+        }
+        self.engine.interruption.yield_point()
+    }
+
+    /// Move the values a branch carries and continue at its target
+    fn branch(&mut self, base: usize, height: u32, arity: u32) {
+        let destination = base + height as usize;
+        let source = self.len() - arity as usize;
+        if destination != source {
+            self.spill();
+            self.stack.copy_within(source.., destination);
+            self.stack.truncate(destination + arity as usize);
+            self.fill();
+        }
+    }
+
+    /// Take the branch to `target` of the operation before `pc`, returning
+    /// the position to continue at
+    ///
+    /// Branches back to a loop pass a yield point once they are taken.
+    fn branch_to(&mut self, base: usize, target: Target, pc: usize) -> Result<usize> {
+        self.branch(base, target.height, target.arity);
+        let next = target.pc as usize;
+        if next < pc {
+            self.yield_point(next)?;
+        }
+        Ok(next)
+    }
+
+    fn memory(&self) -> Result<&MemoryWrapper> {
+        self.memory
+            .as_ref()
+            .ok_or_else(|| Error::runtime_execution_error("Memory index out of bounds"))
+    }
+
+    /// Check that `len` bytes at `address` are within the memory
+    fn check_range(&self, address: u64, len: u64) -> Result<u32> {
+        if address + len > self.memory()?.size_in_bytes() as u64 {
+            return Err(TrapCode::MemoryOutOfBounds.into());
+        }
+        Ok(address as u32)
+    }
+
+    /// Pop an address and read the `N` bytes at it plus `offset`
+    fn load<const N: usize>(&mut self, offset: u32) -> Result<[u8; N]> {
+        let address = u64::from(self.pop()? as u32) + u64::from(offset);
+        let address = self.check_range(address, N as u64)?;
+        let mut bytes = [0; N];
+        self.memory()?.read(address, &mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Pop an address and write `bytes` at it plus `offset`
+    fn store(&mut self, offset: u32, bytes: &[u8]) -> Result<()> {
+        let address = u64::from(self.pop()? as u32) + u64::from(offset);
+        let address = self.check_range(address, bytes.len() as u64)?;
+        self.memory()?.write(address, bytes)
+    }
+
+    /// Pop the three operands of a bulk operation: destination, source or
+    /// value, and length
+    fn pop_bulk(&mut self) -> Result<(u64, u64, u64)> {
+        let len = u64::from(self.pop()? as u32);
+        let source = self.pop()?;
+        let destination = u64::from(self.pop()? as u32);
+        Ok((destination, source, len))
+    }
+
+    /// Check that `len` elements at `index` are within table `table`,
+    /// returning the table
+    fn table_range(&self, table: u32, index: u64, len: u64) -> Result<crate::module::TableWrapper> {
+        let table = self.instance.table(table)?;
+        if index + len > u64::from(table.size()) {
+            return Err(TrapCode::TableOutOfBounds.into());
+        }
+        Ok(table)
+    }
+
+    /// Fail unless an operation changing `len` table elements changes none
+    fn table_unchanged(len: u64) -> Result<()> {
+        if len != 0 {
+            return Err(Error::not_supported_unsupported_operation(
+                "Tables cannot be changed at run time",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Execute the frames on the frame stack until the outermost returns
+    ///
+    /// Entering a frame probes the native stack usage of the engine at its
+    /// depth, always from this loop, as guest calls do not recurse.
+    fn run(&mut self, code: &ModuleCode) -> Result<()> {
+        let mut frame = self.frames.last().copied().unwrap_or(Frame {
+            func: 0,
+            pc:   0,
+            base: 0,
+        });
+        let mut function: &FunctionCode = &code.functions[frame.func];
+        let mut pc = frame.pc;
+        let coverage = self.coverage.clone();
+        let imports = code.imports.len();
+        self.engine.stack_meter.probe(self.frames.len() - 1);
+
+        loop {
+            let Some(op) = function.ops.get(pc).copied() else {
+                return Err(Error::runtime_execution_error(
+                    "Execution ran past the function end",
+                ));
+            };
+            if let Some(coverage) = &coverage {
+                coverage.record_block((imports + frame.func) as u32, pc as u32);
+            }
+            pc += 1;
+
+            match op.code {
+                OpCode::Unreachable => return Err(TrapCode::Unreachable.into()),
+                OpCode::Br => {
+                    pc = self.branch_to(frame.base, op.target(), pc)?;
+                },
+                OpCode::BrIf => {
+                    if self.pop()? as u32 != 0 {
+                        pc = self.branch_to(frame.base, op.target(), pc)?;
+                    }
+                },
+                OpCode::BrTable => {
+                    let index = (self.pop()? as u32).min(op.b - 1);
+                    let target = function.targets[(op.a + index) as usize];
+                    pc = self.branch_to(frame.base, target, pc)?;
+                },
+                OpCode::If => {
+                    if self.pop()? as u32 == 0 {
+                        pc = op.a as usize;
+                    }
+                },
+                OpCode::Jump => pc = op.a as usize,
+                OpCode::Return => {
+                    self.branch(frame.base, 0, op.a);
+                    self.frames.pop();
+                    if self.allocating.last().is_some_and(|call| call.depth == self.frames.len()) {
+                        self.record_allocator_return(code, frame.func);
+                    }
+                    match self.frames.last() {
+                        Some(caller) => {
+                            frame = *caller;
+                            function = &code.functions[frame.func];
+                            pc = frame.pc;
+                        },
+                        None => return Ok(()),
+                    }
+                },
+                OpCode::Call | OpCode::CallIndirect => {
+                    let func_idx = match op.code {
+                        OpCode::Call => op.a,
+                        _ => {
+                            let index = self.pop()? as u32;
+                            let table = self.instance.table(op.b)?;
+                            if index >= table.size() {
+                                return Err(TrapCode::IndirectCallIndexOutOfBounds.into());
+                            }
+                            let slot = element_to_slot(table.get(index)?);
+                            if slot == NULL_REF {
+                                return Err(TrapCode::IndirectCallNullTableEntry.into());
+                            }
+                            let callee = code.function_type(slot as u32).ok_or_else(|| {
+                                Error::runtime_function_not_found("Function index out of bounds")
+                            })?;
+                            if code.canonical[callee as usize] != code.canonical[op.a as usize] {
+                                return Err(TrapCode::IndirectCallSignatureMismatch.into());
+                            }
+                            slot as u32
+                        },
+                    };
+                    if let Some(current) = self.frames.last_mut() {
+                        current.pc = pc;
+                    }
+                    let depth = self.frames.len();
+                    self.call(code, func_idx)?;
+                    if self.frames.len() > depth {
+                        frame = self.frames[depth];
+                        function = &code.functions[frame.func];
+                        pc = 0;
+                        self.engine.stack_meter.probe(depth);
+                    }
+                    self.yield_point(pc)?;
+                },
+                OpCode::Drop => {
+                    self.pop()?;
+                },
+                OpCode::Select => {
+                    let condition = self.pop()? as u32;
+                    let second = self.pop()?;
+                    if condition == 0 {
+                        *self.top() = second;
+                    }
+                },
+                OpCode::Const => self.push(op.value()),
+                OpCode::LocalGet => {
+                    let value = self.local(frame.base + op.a as usize);
+                    self.push(value);
+                },
+                OpCode::LocalSet => {
+                    let value = self.pop()?;
+                    self.set_local(frame.base + op.a as usize, value);
+                },
+                OpCode::LocalTee => {
+                    let value = *self.top();
+                    self.set_local(frame.base + op.a as usize, value);
+                },
+                OpCode::GlobalGet => {
+                    let value = self.instance.global_value(op.a)?;
+                    self.push(value_to_slot(&value)?);
+                },
+                OpCode::GlobalSet => {
+                    let ty = code.globals[op.a as usize].ty;
+                    let slot = self.pop()?;
+                    self.instance.set_global_value(op.a, slot_to_value(ty, slot))?;
+                },
+                OpCode::TableGet => {
+                    let index = self.pop()? as u32;
+                    let table = self.table_range(op.a, u64::from(index), 1)?;
+                    let slot = element_to_slot(table.get(index)?);
+                    self.push(slot);
+                },
+                OpCode::TableSet => {
+                    self.pop()?;
+                    let index = self.pop()? as u32;
+                    self.table_range(op.a, u64::from(index), 1)?;
+                    Self::table_unchanged(1)?;
+                },
+                OpCode::TableSize => {
+                    let size = self.instance.table(op.a)?.size();
+                    self.push(u64::from(size));
+                },
+                OpCode::TableGrow => {
+                    self.pop()?;
+                    self.pop()?;
+                    self.push(u64::from(u32::MAX));
+                },
+                OpCode::TableFill => {
+                    let (destination, _, len) = self.pop_bulk()?;
+                    self.table_range(op.a, destination, len)?;
+                    Self::table_unchanged(len)?;
+                },
+                OpCode::TableCopy => {
+                    let (destination, source, len) = self.pop_bulk()?;
+                    self.table_range(op.b, u64::from(source as u32), len)?;
+                    self.table_range(op.a, destination, len)?;
+                    Self::table_unchanged(len)?;
+                },
+                OpCode::TableInit => {
+                    let (destination, source, len) = self.pop_bulk()?;
+                    let dropped = self.instance.dropped_segments().element_dropped(code, op.a);
+                    let segment =
+                        if dropped { 0 } else { code.elements[op.a as usize].items.len() as u64 };
+                    if u64::from(source as u32) + len > segment {
+                        return Err(TrapCode::TableOutOfBounds.into());
+                    }
+                    self.table_range(op.b, destination, len)?;
+                    Self::table_unchanged(len)?;
+                },
+                OpCode::ElemDrop => self.instance.dropped_segments().drop_element(op.a),
+                OpCode::RefIsNull => {
+                    let top = self.top();
+                    *top = u64::from(*top == NULL_REF);
+                },
+                OpCode::I32Load => {
+                    let value = i32::from_le_bytes(self.load(op.a)?);
+                    self.push(value.into_slot());
+                },
+                OpCode::I64Load => {
+                    let value = i64::from_le_bytes(self.load(op.a)?);
+                    self.push(value.into_slot());
+                },
+                OpCode::F32Load => {
+                    let value = u32::from_le_bytes(self.load(op.a)?);
+                    self.push(u64::from(value));
+                },
+                OpCode::F64Load => {
+                    let value = u64::from_le_bytes(self.load(op.a)?);
+                    self.push(value);
+                },
+                OpCode::I32Load8S => {
+                    let value = i32::from(i8::from_le_bytes(self.load(op.a)?));
+                    self.push(value.into_slot());
+                },
+                OpCode::I32Load8U => {
+                    let value = u32::from(u8::from_le_bytes(self.load(op.a)?));
+                    self.push(value.into_slot());
+                },
+                OpCode::I32Load16S => {
+                    let value = i32::from(i16::from_le_bytes(self.load(op.a)?));
+                    self.push(value.into_slot());
+                },
+                OpCode::I32Load16U => {
+                    let value = u32::from(u16::from_le_bytes(self.load(op.a)?));
+                    self.push(value.into_slot());
+                },
+                OpCode::I64Load8S => {
+                    let value = i64::from(i8::from_le_bytes(self.load(op.a)?));
+                    self.push(value.into_slot());
+                },
+                OpCode::I64Load8U => {
+                    let value = u64::from(u8::from_le_bytes(self.load(op.a)?));
+                    self.push(value);
+                },
+                OpCode::I64Load16S => {
+                    let value = i64::from(i16::from_le_bytes(self.load(op.a)?));
+                    self.push(value.into_slot());
+                },
+                OpCode::I64Load16U => {
+                    let value = u64::from(u16::from_le_bytes(self.load(op.a)?));
+                    self.push(value);
+                },
+                OpCode::I64Load32S => {
+                    let value = i64::from(i32::from_le_bytes(self.load(op.a)?));
+                    self.push(value.into_slot());
+                },
+                OpCode::I64Load32U => {
+                    let value = u64::from(u32::from_le_bytes(self.load(op.a)?));
+                    self.push(value);
+                },
+                OpCode::I32Store | OpCode::F32Store => {
+                    let value = self.pop()? as u32;
+                    self.store(op.a, &value.to_le_bytes())?;
+                },
+                OpCode::I64Store | OpCode::F64Store => {
+                    let value = self.pop()?;
+                    self.store(op.a, &value.to_le_bytes())?;
+                },
+                OpCode::I32Store8 | OpCode::I64Store8 => {
+                    let value = self.pop()? as u8;
+                    self.store(op.a, &[value])?;
+                },
+                OpCode::I32Store16 | OpCode::I64Store16 => {
+                    let value = self.pop()? as u16;
+                    self.store(op.a, &value.to_le_bytes())?;
+                },
+                OpCode::I64Store32 => {
+                    let value = self.pop()? as u32;
+                    self.store(op.a, &value.to_le_bytes())?;
+                },
+                OpCode::MemorySize => {
+                    let pages = self.memory()?.size();
+                    self.push(u64::from(pages));
+                },
+                OpCode::MemoryGrow => {
+                    let pages = self.pop()? as u32;
+                    let previous = self.memory()?.grow(pages).unwrap_or(u32::MAX);
+                    self.push(u64::from(previous));
+                },
+                OpCode::MemoryInit => {
+                    let (destination, source, len) = self.pop_bulk()?;
+                    let source = u64::from(source as u32);
+                    let dropped = self.instance.dropped_segments().data_dropped(code, op.a);
+                    let bytes: &[u8] = if dropped { &[] } else { &code.data[op.a as usize].bytes };
+                    if source + len > bytes.len() as u64 {
+                        return Err(TrapCode::MemoryOutOfBounds.into());
+                    }
+                    let destination = self.check_range(destination, len)?;
+                    self.memory()?.write(
+                        destination,
+                        &bytes[source as usize..(source + len) as usize],
+                    )?;
+                },
+                OpCode::DataDrop => self.instance.dropped_segments().drop_data(op.a),
+                OpCode::MemoryCopy => {
+                    let (destination, source, len) = self.pop_bulk()?;
+                    let source = self.check_range(u64::from(source as u32), len)?;
+                    let destination = self.check_range(destination, len)?;
+                    let mut bytes = vec![0; len as usize];
+                    self.memory()?.read(source, &mut bytes)?;
+                    self.memory()?.write(destination, &bytes)?;
+                },
+                OpCode::MemoryFill => {
+                    let (destination, value, len) = self.pop_bulk()?;
+                    let destination = self.check_range(destination, len)?;
+                    if len != 0 {
+                        self.memory()?.fill(destination, len as u32, value as u8)?;
+                    }
+                },
+                OpCode::I32Eqz => self.unary(math::i32_eqz)?,
+                OpCode::I32Eq => self.binary(math::i32_eq)?,
+                OpCode::I32Ne => self.binary(math::i32_ne)?,
+                OpCode::I32LtS => self.binary(math::i32_lt_s)?,
+                OpCode::I32LtU => self.binary(math::i32_lt_u)?,
+                OpCode::I32GtS => self.binary(math::i32_gt_s)?,
+                OpCode::I32GtU => self.binary(math::i32_gt_u)?,
+                OpCode::I32LeS => self.binary(math::i32_le_s)?,
+                OpCode::I32LeU => self.binary(math::i32_le_u)?,
+                OpCode::I32GeS => self.binary(math::i32_ge_s)?,
+                OpCode::I32GeU => self.binary(math::i32_ge_u)?,
+                OpCode::I64Eqz => self.unary(math::i64_eqz)?,
+                OpCode::I64Eq => self.binary(math::i64_eq)?,
+                OpCode::I64Ne => self.binary(math::i64_ne)?,
+                OpCode::I64LtS => self.binary(math::i64_lt_s)?,
+                OpCode::I64LtU => self.binary(math::i64_lt_u)?,
+                OpCode::I64GtS => self.binary(math::i64_gt_s)?,
+                OpCode::I64GtU => self.binary(math::i64_gt_u)?,
+                OpCode::I64LeS => self.binary(math::i64_le_s)?,
+                OpCode::I64LeU => self.binary(math::i64_le_u)?,
+                OpCode::I64GeS => self.binary(math::i64_ge_s)?,
+                OpCode::I64GeU => self.binary(math::i64_ge_u)?,
+                OpCode::F32Eq => self.binary(math::f32_eq)?,
+                OpCode::F32Ne => self.binary(math::f32_ne)?,
+                OpCode::F32Lt => self.binary(math::f32_lt)?,
+                OpCode::F32Gt => self.binary(math::f32_gt)?,
+                OpCode::F32Le => self.binary(math::f32_le)?,
+                OpCode::F32Ge => self.binary(math::f32_ge)?,
+                OpCode::F64Eq => self.binary(math::f64_eq)?,
+                OpCode::F64Ne => self.binary(math::f64_ne)?,
+                OpCode::F64Lt => self.binary(math::f64_lt)?,
+                OpCode::F64Gt => self.binary(math::f64_gt)?,
+                OpCode::F64Le => self.binary(math::f64_le)?,
+                OpCode::F64Ge => self.binary(math::f64_ge)?,
+                OpCode::I32Clz => self.unary(math::i32_clz)?,
+                OpCode::I32Ctz => self.unary(math::i32_ctz)?,
+                OpCode::I32Popcnt => self.unary(math::i32_popcnt)?,
+                OpCode::I32Add => self.binary(math::i32_add)?,
+                OpCode::I32Sub => self.binary(math::i32_sub)?,
+                OpCode::I32Mul => self.binary(math::i32_mul)?,
+                OpCode::I32DivS => self.binary(math::i32_div_s)?,
+                OpCode::I32DivU => self.binary(math::i32_div_u)?,
+                OpCode::I32RemS => self.binary(math::i32_rem_s)?,
+                OpCode::I32RemU => self.binary(math::i32_rem_u)?,
+                OpCode::I32And => self.binary(math::i32_and)?,
+                OpCode::I32Or => self.binary(math::i32_or)?,
+                OpCode::I32Xor => self.binary(math::i32_xor)?,
+                OpCode::I32Shl => self.binary(math::i32_shl)?,
+                OpCode::I32ShrS => self.binary(math::i32_shr_s)?,
+                OpCode::I32ShrU => self.binary(math::i32_shr_u)?,
+                OpCode::I32Rotl => self.binary(math::i32_rotl)?,
+                OpCode::I32Rotr => self.binary(math::i32_rotr)?,
+                OpCode::I64Clz => self.unary(math::i64_clz)?,
+                OpCode::I64Ctz => self.unary(math::i64_ctz)?,
+                OpCode::I64Popcnt => self.unary(math::i64_popcnt)?,
+                OpCode::I64Add => self.binary(math::i64_add)?,
+                OpCode::I64Sub => self.binary(math::i64_sub)?,
+                OpCode::I64Mul => self.binary(math::i64_mul)?,
+                OpCode::I64DivS => self.binary(math::i64_div_s)?,
+                OpCode::I64DivU => self.binary(math::i64_div_u)?,
+                OpCode::I64RemS => self.binary(math::i64_rem_s)?,
+                OpCode::I64RemU => self.binary(math::i64_rem_u)?,
+                OpCode::I64And => self.binary(math::i64_and)?,
+                OpCode::I64Or => self.binary(math::i64_or)?,
+                OpCode::I64Xor => self.binary(math::i64_xor)?,
+                OpCode::I64Shl => self.binary(math::i64_shl)?,
+                OpCode::I64ShrS => self.binary(math::i64_shr_s)?,
+                OpCode::I64ShrU => self.binary(math::i64_shr_u)?,
+                OpCode::I64Rotl => self.binary(math::i64_rotl)?,
+                OpCode::I64Rotr => self.binary(math::i64_rotr)?,
+                OpCode::F32Abs => self.unary(math::wasm_f32_abs)?,
+                OpCode::F32Neg => self.unary(math::wasm_f32_neg)?,
+                OpCode::F32Ceil => self.unary(math::wasm_f32_ceil)?,
+                OpCode::F32Floor => self.unary(math::wasm_f32_floor)?,
+                OpCode::F32Trunc => self.unary(math::wasm_f32_trunc)?,
+                OpCode::F32Nearest => self.unary(math::wasm_f32_nearest)?,
+                OpCode::F32Sqrt => self.unary(math::wasm_f32_sqrt)?,
+                OpCode::F32Add => self.binary(math::f32_add)?,
+                OpCode::F32Sub => self.binary(math::f32_sub)?,
+                OpCode::F32Mul => self.binary(math::f32_mul)?,
+                OpCode::F32Div => self.binary(math::f32_div)?,
+                OpCode::F32Min => self.binary(math::wasm_f32_min)?,
+                OpCode::F32Max => self.binary(math::wasm_f32_max)?,
+                OpCode::F32Copysign => self.binary(math::wasm_f32_copysign)?,
+                OpCode::F64Abs => self.unary(math::wasm_f64_abs)?,
+                OpCode::F64Neg => self.unary(math::wasm_f64_neg)?,
+                OpCode::F64Ceil => self.unary(math::wasm_f64_ceil)?,
+                OpCode::F64Floor => self.unary(math::wasm_f64_floor)?,
+                OpCode::F64Trunc => self.unary(math::wasm_f64_trunc)?,
+                OpCode::F64Nearest => self.unary(math::wasm_f64_nearest)?,
+                OpCode::F64Sqrt => self.unary(math::wasm_f64_sqrt)?,
+                OpCode::F64Add => self.binary(math::f64_add)?,
+                OpCode::F64Sub => self.binary(math::f64_sub)?,
+                OpCode::F64Mul => self.binary(math::f64_mul)?,
+                OpCode::F64Div => self.binary(math::f64_div)?,
+                OpCode::F64Min => self.binary(math::wasm_f64_min)?,
+                OpCode::F64Max => self.binary(math::wasm_f64_max)?,
+                OpCode::F64Copysign => self.binary(math::wasm_f64_copysign)?,
+                OpCode::I32WrapI64 => self.unary(math::i32_wrap_i64)?,
+                OpCode::I32TruncF32S => self.unary(math::i32_trunc_f32_s)?,
+                OpCode::I32TruncF32U => self.unary(math::i32_trunc_f32_u)?,
+                OpCode::I32TruncF64S => self.unary(math::i32_trunc_f64_s)?,
+                OpCode::I32TruncF64U => self.unary(math::i32_trunc_f64_u)?,
+                OpCode::I64ExtendI32S => self.unary(math::i64_extend_i32_s)?,
+                OpCode::I64ExtendI32U => self.unary(math::i64_extend_i32_u)?,
+                OpCode::I64TruncF32S => self.unary(math::i64_trunc_f32_s)?,
+                OpCode::I64TruncF32U => self.unary(math::i64_trunc_f32_u)?,
+                OpCode::I64TruncF64S => self.unary(math::i64_trunc_f64_s)?,
+                OpCode::I64TruncF64U => self.unary(math::i64_trunc_f64_u)?,
+                OpCode::F32ConvertI32S => self.unary(math::f32_convert_i32_s)?,
+                OpCode::F32ConvertI32U => self.unary(math::f32_convert_i32_u)?,
+                OpCode::F32ConvertI64S => self.unary(math::f32_convert_i64_s)?,
+                OpCode::F32ConvertI64U => self.unary(math::f32_convert_i64_u)?,
+                OpCode::F32DemoteF64 => self.unary(math::f32_demote_f64)?,
+                OpCode::F64ConvertI32S => self.unary(math::f64_convert_i32_s)?,
+                OpCode::F64ConvertI32U => self.unary(math::f64_convert_i32_u)?,
+                OpCode::F64ConvertI64S => self.unary(math::f64_convert_i64_s)?,
+                OpCode::F64ConvertI64U => self.unary(math::f64_convert_i64_u)?,
+                OpCode::F64PromoteF32 => self.unary(math::f64_promote_f32)?,
+                OpCode::I32ReinterpretF32
+                | OpCode::I64ReinterpretF64
+                | OpCode::F32ReinterpretI32
+                | OpCode::F64ReinterpretI64 => {},
+                OpCode::I32Extend8S => self.unary(math::i32_extend8_s)?,
+                OpCode::I32Extend16S => self.unary(math::i32_extend16_s)?,
+                OpCode::I64Extend8S => self.unary(math::i64_extend8_s)?,
+                OpCode::I64Extend16S => self.unary(math::i64_extend16_s)?,
+                OpCode::I64Extend32S => self.unary(math::i64_extend32_s)?,
+                OpCode::I32TruncSatF32S => {
+                    self.unary(|value| Ok(math::i32_trunc_sat_f32_s(value)))?
+                },
+                OpCode::I32TruncSatF32U => {
+                    self.unary(|value| Ok(math::i32_trunc_sat_f32_u(value)))?
+                },
+                OpCode::I32TruncSatF64S => {
+                    self.unary(|value| Ok(math::i32_trunc_sat_f64_s(value)))?
+                },
+                OpCode::I32TruncSatF64U => {
+                    self.unary(|value| Ok(math::i32_trunc_sat_f64_u(value)))?
+                },
+                OpCode::I64TruncSatF32S => {
+                    self.unary(|value| Ok(math::i64_trunc_sat_f32_s(value)))?
+                },
+                OpCode::I64TruncSatF32U => {
+                    self.unary(|value| Ok(math::i64_trunc_sat_f32_u(value)))?
+                },
+                OpCode::I64TruncSatF64S => {
+                    self.unary(|value| Ok(math::i64_trunc_sat_f64_s(value)))?
+                },
+                OpCode::I64TruncSatF64U => {
+                    self.unary(|value| Ok(math::i64_trunc_sat_f64_u(value)))?
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,245 @@
+//! Native stack usage of the stackless interpreter
+//!
+//! The [interpreter](super::interpreter) executes a guest call by pushing a
+//! frame onto its own frame stack rather than by recursing on the native
+//! stack. The [`NativeStackMeter`] records the highest native stack usage
+//! observed at every guest call depth the interpreter entered, relative to
+//! the outermost entry into the engine, which shows whether the calls
+//! executed so far grew the native stack with their depth, see
+//! [`NativeStackUsage::growth_per_call`], and helps size the stack of the
+//! thread running the engine from [`NativeStackUsage::max_bytes`].
+//!
+//! The observations cover only the calls executed while the meter ran and
+//! only the interpreter: they are a measurement, not a bound. Host functions
+//! that call back into the engine do recurse on the native stack, and
+//! decoding and instantiating modules is not measured.
+//!
+//! Usage is measured through the address of a local variable, so the values
+//! are a lower bound of the true usage within the same order of magnitude as
+//! a stack frame; a margin should be added when sizing stacks.
+
+use core::sync::atomic::{
+    AtomicUsize,
+    Ordering,
+};
+
+/// Number of guest call depths tracked individually
+///
+/// Deeper calls are accounted to the last tracked depth.
+pub const TRACKED_CALL_DEPTHS: usize = 64;
+
+/// Native stack usage observed per guest call depth
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NativeStackUsage {
+    per_depth:     [usize; TRACKED_CALL_DEPTHS],
+    deepest_depth: Option<usize>,
+}
+
+impl NativeStackUsage {
+    /// Highest native stack usage observed at `call_depth`, in bytes
+    ///
+    /// Returns `None` when no call reached that depth.
+    pub fn at_depth(&self, call_depth: usize) -> Option<usize> {
+        let deepest = self.deepest_depth?;
+        let depth = call_depth.min(TRACKED_CALL_DEPTHS - 1);
+        (call_depth <= deepest).then(|| self.per_depth[depth])
+    }
+
+    /// Deepest guest call depth observed
+    pub fn deepest_depth(&self) -> Option<usize> {
+        self.deepest_depth
+    }
+
+    /// Highest native stack usage observed at any depth, in bytes
+    pub fn max_bytes(&self) -> usize {
+        self.per_depth.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Largest increase of native stack usage from one guest call depth to
+    /// the next, in bytes
+    ///
+    /// Zero means the calls observed did not grow the native stack with
+    /// their depth.
+    pub fn growth_per_call(&self) -> usize {
+        let tracked =
+            self.deepest_depth.map_or(0, |deepest| deepest.min(TRACKED_CALL_DEPTHS - 1) + 1);
+        self.per_depth[..tracked]
+            .windows(2)
+            .map(|pair| pair[1].saturating_sub(pair[0]))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Records native stack usage of the interpreter per guest call depth
+#[derive(Debug)]
+pub struct NativeStackMeter {
+    base:      AtomicUsize,
+    active:    AtomicUsize,
+    deepest:   AtomicUsize,
+    per_depth: [AtomicUsize; TRACKED_CALL_DEPTHS],
+}
+
+/// Returned by [`NativeStackMeter::enter`], leaves the interpreter on drop
+#[derive(Debug)]
+pub struct NativeStackEntry<'a> {
+    meter: &'a NativeStackMeter,
+}
+
+impl Drop for NativeStackEntry<'_> {
+    fn drop(&mut self) {
+        self.meter.active.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Approximate native stack pointer of the caller
+#[inline(never)]
+fn stack_address() -> usize {
+    let marker = 0u8;
+    core::hint::black_box(&marker) as *const u8 as usize
+}
+
+impl NativeStackMeter {
+    /// Create a meter without any observations
+    pub fn new() -> Self {
+        Self {
+            base:      AtomicUsize::new(0),
+            active:    AtomicUsize::new(0),
+            deepest:   AtomicUsize::new(0),
+            per_depth: core::array::from_fn(|_| AtomicUsize::new(0)),
+        }
+    }
+
+    /// Mark an entry into the interpreter
+    ///
+    /// The outermost entry sets the point usage is measured from; entries
+    /// made while the interpreter is already running, such as from host
+    /// functions, are measured from the outermost one.
+    pub fn enter(&self) -> NativeStackEntry<'_> {
+        if self.active.fetch_add(1, Ordering::Relaxed) == 0 {
+            self.base.store(stack_address(), Ordering::Relaxed);
+        }
+        NativeStackEntry { meter: self }
+    }
+
+    /// Record the native stack usage at the current point, executing a guest
+    /// call at `call_depth`
+    pub fn probe(&self, call_depth: usize) {
+        let base = self.base.load(Ordering::Relaxed);
+        if base == 0 {
+            return;
+        }
+        let usage = base.abs_diff(stack_address());
+        let slot = call_depth.min(TRACKED_CALL_DEPTHS - 1);
+        self.per_depth[slot].fetch_max(usage, Ordering::Relaxed);
+        self.deepest.fetch_max(call_depth + 1, Ordering::Relaxed);
+    }
+
+    /// Usage observed so far
+    pub fn usage(&self) -> NativeStackUsage {
+        let mut per_depth = [0; TRACKED_CALL_DEPTHS];
+        for (usage, observed) in per_depth.iter_mut().zip(&self.per_depth) {
+            *usage = observed.load(Ordering::Relaxed);
+        }
+        NativeStackUsage {
+            per_depth,
+            deepest_depth: self.deepest.load(Ordering::Relaxed).checked_sub(1),
+        }
+    }
+
+    /// Forget all observations
+    pub fn reset(&self) {
+        self.deepest.store(0, Ordering::Relaxed);
+        for observed in &self.per_depth {
+            observed.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Default for NativeStackMeter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[inline(never)]
+    fn recurse(meter: &NativeStackMeter, depth: usize, max_depth: usize) {
+        meter.probe(depth);
+        if depth < max_depth {
+            recurse(meter, depth + 1, max_depth);
+        }
+        core::hint::black_box(depth);
+    }
+
+    #[test]
+    fn test_iterative_calls_do_not_grow() {
+        let meter = NativeStackMeter::new();
+        let _entry = meter.enter();
+        for depth in 0..100 {
+            meter.probe(depth);
+        }
+
+        let usage = meter.usage();
+        assert_eq!(usage.deepest_depth(), Some(99));
+        assert_eq!(usage.growth_per_call(), 0);
+        assert_eq!(usage.at_depth(99), usage.at_depth(0));
+        assert_eq!(usage.at_depth(100), None);
+    }
+
+    #[test]
+    fn test_recursive_calls_grow() {
+        let meter = NativeStackMeter::new();
+        {
+            let _entry = meter.enter();
+            recurse(&meter, 0, 8);
+        }
+
+        let usage = meter.usage();
+        assert_eq!(usage.deepest_depth(), Some(8));
+        assert!(usage.growth_per_call() > 0);
+        assert!(usage.at_depth(8).unwrap() > usage.at_depth(0).unwrap());
+
+        meter.reset();
+        assert_eq!(meter.usage().deepest_depth(), None);
+        assert_eq!(meter.usage().max_bytes(), 0);
+    }
+    #[cfg(feature = "wat")]
+    #[test]
+    fn test_guest_recursion_does_not_grow() -> wrt_error::Result<()> {
+        use wrt_foundation::{
+            memory_init::MemoryInitializer,
+            values::Value,
+        };
+
+        use crate::engine::{
+            CapabilityAwareEngine,
+            CapabilityEngine,
+            EnginePreset,
+        };
+
+        MemoryInitializer::ensure_initialized()?;
+        let binary = crate::text_format::wat_to_binary(
+            r#"(module
+                (func $fac (export "fac") (param i64) (result i64)
+                  (if (result i64) (i64.eqz (local.get 0))
+                    (then (i64.const 1))
+                    (else (i64.mul (local.get 0)
+                                   (call $fac (i64.sub (local.get 0) (i64.const 1))))))))"#,
+        )?;
+        let mut engine = CapabilityAwareEngine::with_preset(EnginePreset::QM)?;
+        let module = engine.load_module(&binary)?;
+        let instance = engine.instantiate(module)?;
+        engine.execute(instance, "fac", &[Value::I64(20)])?;
+
+        // fac(20) down to fac(0)
+        let usage = engine.native_stack_usage();
+        assert_eq!(usage.deepest_depth(), Some(20));
+        assert_eq!(usage.growth_per_call(), 0);
+        assert!(usage.max_bytes() > 0);
+        Ok(())
+    }
+}
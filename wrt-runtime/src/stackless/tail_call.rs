@@ -19,6 +19,10 @@
 // Type alias for FuncType to match module_instance.rs
 use crate::bounded_runtime_infra::RuntimeProvider;
 use crate::{
+    mcdc::{
+        decision,
+        Decision,
+    },
     module_instance::ModuleInstance,
     prelude::*,
     stackless::{
@@ -76,6 +80,7 @@ pub fn execute_tail_call(&mut self, func_idx: u32, module: &mut ModuleInstance)
 
         // Update execution statistics
         self.stats.function_calls += 1;
+        self.stack_meter.probe(self.call_frames_count);
 
         Ok(())
     }
@@ -126,9 +131,13 @@ pub fn execute_tail_call_indirect(
         let actual_type = module.get_function_type(actual_func_idx as usize)?;
 
         // Validate type compatibility
-        if actual_type.params != expected_type.params
-            || actual_type.results != expected_type.results
-        {
+        let params_match = actual_type.params == expected_type.params;
+        let results_match = params_match.then(|| actual_type.results == expected_type.results);
+        if !decision!(
+            Decision::TailCallSignatureMatches,
+            [Some(params_match), results_match],
+            results_match == Some(true)
+        ) {
             return Err(Error::type_error(
                 "Function type mismatch in tail call indirect",
             ));
@@ -186,55 +195,29 @@ pub fn can_optimize_tail_call(has_try_catch_blocks: bool, in_multivalue_block: b
 
 #[cfg(test)]
 mod tests {
-    use wrt_foundation::{
-        bounded::BoundedVec,
-        safe_memory::NoStdProvider,
-        types::{
-            Limits,
-            ValueType,
-        },
-    };
+    use wrt_foundation::types::ValueType;
 
     use super::*;
-
-    type TestProvider = NoStdProvider<8192>;
+    use crate::bounded_runtime_infra::create_runtime_provider;
 
     #[test]
     fn test_tail_call_validation() {
-        let provider = TestProvider::default();
+        let provider = || create_runtime_provider().unwrap();
 
         // Test compatible types
-        let mut params1 = BoundedVec::new(provider.clone()).unwrap();
-        params1.push(ValueType::I32).unwrap();
-        params1.push(ValueType::I32).unwrap();
-        let mut results1 = BoundedVec::new(provider.clone()).unwrap();
-        results1.push(ValueType::I32).unwrap();
-        let func1 = WrtFuncType {
-            params:  params1,
-            results: results1,
-        };
-
-        let mut params2 = BoundedVec::new(provider.clone()).unwrap();
-        params2.push(ValueType::I32).unwrap();
-        let mut results2 = BoundedVec::new(provider.clone()).unwrap();
-        results2.push(ValueType::I32).unwrap();
-        let func2 = WrtFuncType {
-            params:  params2,
-            results: results2,
-        };
+        let func1 = WrtFuncType::new(
+            provider(),
+            [ValueType::I32, ValueType::I32],
+            [ValueType::I32],
+        )
+        .unwrap();
+        let func2 = WrtFuncType::new(provider(), [ValueType::I32], [ValueType::I32]).unwrap();
 
         // Should succeed - same return types
         assert!(validation::validate_tail_call(&func1, &func2).is_ok());
 
         // Test incompatible return types
-        let mut params3 = BoundedVec::new(provider.clone()).unwrap();
-        params3.push(ValueType::I32).unwrap();
-        let mut results3 = BoundedVec::new(provider.clone()).unwrap();
-        results3.push(ValueType::I64).unwrap();
-        let func3 = WrtFuncType {
-            params:  params3,
-            results: results3,
-        };
+        let func3 = WrtFuncType::new(provider(), [ValueType::I32], [ValueType::I64]).unwrap();
 
         // Should fail - different return types
         assert!(validation::validate_tail_call(&func1, &func3).is_err());
@@ -4,17 +4,27 @@
 //! runtime state including stack frames, globals, and memory.
 
 pub mod serialization;
+#[cfg(feature = "std")]
+pub mod snapshot;
 
 // Re-export functions conditionally
 #[cfg(any(feature = "std", feature = "alloc"))]
 pub use serialization::{
+    create_sealed_state_section,
     create_state_section,
+    extract_sealed_state_section,
     extract_state_section,
     has_state_sections,
     is_state_section_name,
+    StateCipher,
 };
 pub use serialization::{
     StateHeader,
     StateSection,
     STATE_SECTION_PREFIX,
 };
+#[cfg(feature = "std")]
+pub use snapshot::{
+    InstanceSnapshot,
+    MemorySnapshot,
+};
@@ -0,0 +1,576 @@
+//! Snapshots of initialized instances for pre-initialization.
+//!
+//! Running a module's initialization once and storing the resulting state
+//! lets later instances start from that state instead of running the
+//! initialization again, which matters for cold starts on slow devices.
+//!
+//! An [`InstanceSnapshot`] records the memories, tables and globals a module
+//! defines itself and is stored as state custom sections appended to the
+//! module binary, so a pre-initialized module is still a valid module that
+//! other tools can load. Imported memories, tables and globals belong to the
+//! embedder and are not part of the snapshot.
+
+use std::vec::Vec;
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_format::{
+    binary::{
+        read_leb128_u32,
+        read_string,
+        with_alloc::write_leb128_u32,
+        CUSTOM_SECTION_ID,
+        WASM_MAGIC,
+    },
+    compression::CompressionType,
+    section::CustomSection,
+};
+use wrt_foundation::{
+    traits::BoundedCapacity,
+    types::TableType as WrtTableType,
+    values::{
+        FloatBits32,
+        FloatBits64,
+        FuncRef,
+        Value as WrtValue,
+        V128,
+    },
+};
+
+use super::serialization::{
+    create_state_section,
+    extract_state_section,
+    StateSection,
+};
+use crate::{
+    global::Global,
+    memory::{
+        Memory,
+        PAGE_SIZE,
+    },
+    module::Module,
+    module_instance::ModuleInstance,
+    prelude::CoreMemoryType,
+    table::Table,
+};
+
+/// Encoded null function reference
+const NULL_FUNC_REF: u32 = u32::MAX;
+
+/// Contents of a memory defined by the module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemorySnapshot {
+    /// Size of the memory in pages
+    pub pages: u32,
+    /// Contents of the memory, without trailing zero bytes
+    pub data:  Vec<u8>,
+}
+
+/// State of an initialized instance
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstanceSnapshot {
+    /// Memories defined by the module, in index order
+    pub memories: Vec<MemorySnapshot>,
+    /// Function indices held by the tables defined by the module
+    pub tables:   Vec<Vec<Option<u32>>>,
+    /// Values of the globals defined by the module
+    pub globals:  Vec<WrtValue>,
+}
+
+impl InstanceSnapshot {
+    /// Record the memories, tables and globals `instance` defines
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a table holds an external reference or a global
+    /// holds a reference other than a function reference, since those refer
+    /// to host state that cannot be stored.
+    pub fn capture(instance: &ModuleInstance) -> Result<Self> {
+        let module = instance.module();
+        let (memory_count, table_count, global_count) = instance.item_counts();
+        let first_memory = imported_count(memory_count, module.memories.len())?;
+        let first_table = imported_count(table_count, module.tables.len())?;
+        let first_global = imported_count(global_count, module.globals.len())?;
+
+        let mut memories = Vec::with_capacity(module.memories.len());
+        for idx in first_memory..memory_count {
+            let memory = instance.memory(idx as u32)?;
+            let memory = memory.lock_read();
+            let contents = memory.as_safe_slice()?;
+            let bytes = contents.data()?;
+            let len = bytes.iter().rposition(|byte| *byte != 0).map_or(0, |last| last + 1);
+            memories.push(MemorySnapshot {
+                pages: memory.size(),
+                data:  bytes[..len].to_vec(),
+            });
+        }
+
+        let mut tables = Vec::with_capacity(module.tables.len());
+        for idx in first_table..table_count {
+            let table = instance.table(idx as u32)?;
+            let elements = (0..table.0.size())
+                .map(|element| match table.0.get(element)? {
+                    None | Some(WrtValue::FuncRef(None) | WrtValue::ExternRef(None)) => Ok(None),
+                    Some(WrtValue::FuncRef(Some(func))) => Ok(Some(func.index)),
+                    Some(_) => Err(Error::runtime_invalid_state(
+                        "Table holds a reference that cannot be snapshotted",
+                    )),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            tables.push(elements);
+        }
+
+        let globals = (first_global..global_count)
+            .map(|idx| instance.global_value(idx as u32))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            memories,
+            tables,
+            globals,
+        })
+    }
+
+    /// Check that the snapshot describes an instance of `module`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the snapshot holds another number of memories,
+    /// tables or globals than the module defines.
+    pub fn check_module(&self, module: &Module) -> Result<()> {
+        if self.memories.len() != module.memories.len()
+            || self.tables.len() != module.tables.len()
+            || self.globals.len() != module.globals.len()
+        {
+            return Err(Error::validation_error(
+                "Snapshot does not match the items the module defines",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Create the defined memory `index` with its snapshotted contents
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no such memory in the snapshot or the
+    /// snapshot does not fit `ty`.
+    pub fn restore_memory(&self, index: usize, ty: CoreMemoryType) -> Result<Memory> {
+        let snapshot = self
+            .memories
+            .get(index)
+            .ok_or_else(|| Error::resource_not_found("Memory not in snapshot"))?;
+        let mut memory = Memory::new(ty)?;
+        let pages = snapshot
+            .pages
+            .checked_sub(memory.size())
+            .ok_or_else(|| Error::validation_error("Snapshot memory is below its minimum"))?;
+        if pages > 0 {
+            memory.grow(pages)?;
+        }
+        memory.write(0, &snapshot.data)?;
+        Ok(memory)
+    }
+
+    /// Create the defined table `index` with its snapshotted elements
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no such table in the snapshot or the
+    /// snapshot does not fit `ty`.
+    pub fn restore_table(&self, index: usize, ty: WrtTableType) -> Result<Table> {
+        let elements = self
+            .tables
+            .get(index)
+            .ok_or_else(|| Error::resource_not_found("Table not in snapshot"))?;
+        let mut table = Table::new(ty)?;
+        let len = u32::try_from(elements.len())
+            .map_err(|_| Error::validation_error("Snapshot table is too large"))?;
+        let delta = len
+            .checked_sub(table.size())
+            .ok_or_else(|| Error::validation_error("Snapshot table is below its minimum"))?;
+        if delta > 0 {
+            table.grow(delta, WrtValue::FuncRef(None))?;
+        }
+        for (idx, element) in elements.iter().enumerate() {
+            if let Some(func_idx) = element {
+                table.set_func(idx as u32, *func_idx)?;
+            }
+        }
+        Ok(table)
+    }
+
+    /// Create the defined global `index` of type `global` with its
+    /// snapshotted value
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no such global in the snapshot or its
+    /// value does not have the type of `global`.
+    pub fn restore_global(&self, index: usize, global: &Global) -> Result<Global> {
+        let value = self
+            .globals
+            .get(index)
+            .ok_or_else(|| Error::resource_not_found("Global not in snapshot"))?;
+        let ty = global.global_type_descriptor();
+        if !value.matches_type(&ty.value_type) {
+            return Err(Error::runtime_type_mismatch(
+                "Snapshot global does not match global type",
+            ));
+        }
+        Global::new(ty.value_type, ty.mutable, value.clone())
+    }
+
+    /// Encode the snapshot as state custom sections
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a global holds a value that cannot be stored.
+    pub fn to_sections(&self) -> Result<Vec<CustomSection>> {
+        let mut memories = Vec::new();
+        push_len(&mut memories, self.memories.len())?;
+        for memory in &self.memories {
+            memories.extend_from_slice(&memory.pages.to_le_bytes());
+            push_len(&mut memories, memory.data.len())?;
+            memories.extend_from_slice(&memory.data);
+        }
+
+        let mut tables = Vec::new();
+        push_len(&mut tables, self.tables.len())?;
+        for elements in &self.tables {
+            push_len(&mut tables, elements.len())?;
+            for element in elements {
+                tables.extend_from_slice(&element.unwrap_or(NULL_FUNC_REF).to_le_bytes());
+            }
+        }
+
+        let mut globals = Vec::new();
+        push_len(&mut globals, self.globals.len())?;
+        for value in &self.globals {
+            encode_value(&mut globals, value)?;
+        }
+
+        Ok(vec![
+            create_state_section(StateSection::Memory, &memories, CompressionType::RLE)?,
+            create_state_section(StateSection::Tables, &tables, CompressionType::RLE)?,
+            create_state_section(StateSection::Globals, &globals, CompressionType::RLE)?,
+        ])
+    }
+
+    /// Decode a snapshot from its state custom sections
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a section is missing or malformed.
+    pub fn from_sections(sections: &[CustomSection]) -> Result<Self> {
+        let payload = |section_type: StateSection| -> Result<Vec<u8>> {
+            let section = sections
+                .iter()
+                .find(|section| section.name == section_type.name())
+                .ok_or_else(|| Error::validation_parse_error("Snapshot section missing"))?;
+            Ok(extract_state_section(section)?.1)
+        };
+
+        let data = payload(StateSection::Memory)?;
+        let mut reader = Reader::new(&data);
+        let mut memories = Vec::new();
+        for _ in 0..reader.read_u32()? {
+            let pages = reader.read_u32()?;
+            let len = reader.read_u32()? as usize;
+            let data = reader.read_bytes(len)?.to_vec();
+            if data.len() as u64 > u64::from(pages) * PAGE_SIZE as u64 {
+                return Err(Error::validation_parse_error(
+                    "Snapshot memory data exceeds its pages",
+                ));
+            }
+            memories.push(MemorySnapshot { pages, data });
+        }
+        reader.finish()?;
+
+        let data = payload(StateSection::Tables)?;
+        let mut reader = Reader::new(&data);
+        let mut tables = Vec::new();
+        for _ in 0..reader.read_u32()? {
+            let len = reader.read_u32()?;
+            let elements = (0..len)
+                .map(|_| Ok(Some(reader.read_u32()?).filter(|idx| *idx != NULL_FUNC_REF)))
+                .collect::<Result<Vec<_>>>()?;
+            tables.push(elements);
+        }
+        reader.finish()?;
+
+        let data = payload(StateSection::Globals)?;
+        let mut reader = Reader::new(&data);
+        let globals = (0..reader.read_u32()?)
+            .map(|_| decode_value(&mut reader))
+            .collect::<Result<Vec<_>>>()?;
+        reader.finish()?;
+
+        Ok(Self {
+            memories,
+            tables,
+            globals,
+        })
+    }
+
+    /// Decode the snapshot stored in a module binary, if it holds one
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the binary is not a module or its snapshot is
+    /// malformed.
+    pub fn from_binary(binary: &[u8]) -> Result<Option<Self>> {
+        let sections = snapshot_sections(binary)?;
+        if sections.is_empty() {
+            return Ok(None);
+        }
+        Self::from_sections(&sections).map(Some)
+    }
+
+    /// Append the snapshot to a module binary
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the binary is not a module or already holds a
+    /// snapshot.
+    pub fn append_to_binary(&self, binary: &[u8]) -> Result<Vec<u8>> {
+        if !snapshot_sections(binary)?.is_empty() {
+            return Err(Error::validation_error("Module is already pre-initialized"));
+        }
+
+        let mut output = binary.to_vec();
+        for section in self.to_sections()? {
+            let contents = section.to_binary()?;
+            let size = u32::try_from(contents.len())
+                .map_err(|_| Error::capacity_limit_exceeded("Snapshot section is too large"))?;
+            output.push(CUSTOM_SECTION_ID);
+            output.extend_from_slice(&write_leb128_u32(size));
+            output.extend_from_slice(&contents);
+        }
+        Ok(output)
+    }
+}
+
+/// Number of imported items in an index space of `total` items, `defined` of
+/// which the module defines
+fn imported_count(total: usize, defined: usize) -> Result<usize> {
+    total
+        .checked_sub(defined)
+        .ok_or_else(|| Error::runtime_invalid_state("Instance lacks items its module defines"))
+}
+
+/// Snapshot custom sections of a module binary
+fn snapshot_sections(binary: &[u8]) -> Result<Vec<CustomSection>> {
+    if binary.len() < 8 || binary[..4] != WASM_MAGIC {
+        return Err(Error::parse_error("Not a WebAssembly module"));
+    }
+
+    let mut sections = Vec::new();
+    let mut pos = 8;
+    while pos < binary.len() {
+        let id = binary[pos];
+        let (size, size_len) = read_leb128_u32(binary, pos + 1)?;
+        let start = pos + 1 + size_len;
+        let end = start
+            .checked_add(size as usize)
+            .filter(|end| *end <= binary.len())
+            .ok_or_else(|| Error::parse_error("Section exceeds module"))?;
+
+        if id == CUSTOM_SECTION_ID {
+            let (name, name_len) = read_string(&binary[..end], start)?;
+            let is_snapshot = [
+                StateSection::Memory,
+                StateSection::Tables,
+                StateSection::Globals,
+            ]
+            .iter()
+            .any(|section_type| name == section_type.name().as_bytes());
+            if is_snapshot {
+                let name = core::str::from_utf8(name)
+                    .map_err(|_| Error::parse_error("Invalid custom section name"))?;
+                sections.push(CustomSection::from_bytes(
+                    name.into(),
+                    &binary[start + name_len..end],
+                ));
+            }
+        }
+        pos = end;
+    }
+    Ok(sections)
+}
+
+fn push_len(data: &mut Vec<u8>, len: usize) -> Result<()> {
+    let len = u32::try_from(len)
+        .map_err(|_| Error::capacity_limit_exceeded("Snapshot item is too large"))?;
+    data.extend_from_slice(&len.to_le_bytes());
+    Ok(())
+}
+
+fn encode_value(data: &mut Vec<u8>, value: &WrtValue) -> Result<()> {
+    match value {
+        WrtValue::I32(v) => {
+            data.push(0x7F);
+            data.extend_from_slice(&v.to_le_bytes());
+        },
+        WrtValue::I64(v) => {
+            data.push(0x7E);
+            data.extend_from_slice(&v.to_le_bytes());
+        },
+        WrtValue::F32(v) => {
+            data.push(0x7D);
+            data.extend_from_slice(&v.to_bits().to_le_bytes());
+        },
+        WrtValue::F64(v) => {
+            data.push(0x7C);
+            data.extend_from_slice(&v.to_bits().to_le_bytes());
+        },
+        WrtValue::V128(v) => {
+            data.push(0x7B);
+            data.extend_from_slice(&v.bytes);
+        },
+        WrtValue::FuncRef(func) => {
+            data.push(0x70);
+            let index = func.as_ref().map_or(NULL_FUNC_REF, |func| func.index);
+            data.extend_from_slice(&index.to_le_bytes());
+        },
+        _ => {
+            return Err(Error::runtime_invalid_state(
+                "Global holds a value that cannot be snapshotted",
+            ));
+        },
+    }
+    Ok(())
+}
+
+fn decode_value(reader: &mut Reader<'_>) -> Result<WrtValue> {
+    let value = match reader.read_bytes(1)?[0] {
+        0x7F => WrtValue::I32(reader.read_u32()? as i32),
+        0x7E => WrtValue::I64(reader.read_u64()? as i64),
+        0x7D => WrtValue::F32(FloatBits32::from_bits(reader.read_u32()?)),
+        0x7C => WrtValue::F64(FloatBits64::from_bits(reader.read_u64()?)),
+        0x7B => {
+            let mut bytes = [0; 16];
+            bytes.copy_from_slice(reader.read_bytes(16)?);
+            WrtValue::V128(V128 { bytes })
+        },
+        0x70 => {
+            let index = reader.read_u32()?;
+            WrtValue::FuncRef((index != NULL_FUNC_REF).then(|| FuncRef::from_index(index)))
+        },
+        _ => return Err(Error::validation_parse_error("Unknown snapshot value type")),
+    };
+    Ok(value)
+}
+
+/// Cursor over the payload of a snapshot section
+struct Reader<'a> {
+    data: &'a [u8],
+    pos:  usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| Error::validation_parse_error("Snapshot section truncated"))?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(self.read_bytes(4)?);
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    fn read_u64(&mut self) -> Result<u64> {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.read_bytes(8)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn finish(&self) -> Result<()> {
+        if self.pos != self.data.len() {
+            return Err(Error::validation_parse_error(
+                "Trailing bytes in snapshot section",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> InstanceSnapshot {
+        InstanceSnapshot {
+            memories: vec![MemorySnapshot {
+                pages: 2,
+                data:  vec![0, 0, 7, 0, 9],
+            }],
+            tables:   vec![vec![Some(3), None, Some(0)]],
+            globals:  vec![
+                WrtValue::I32(-5),
+                WrtValue::I64(1 << 40),
+                WrtValue::F32(FloatBits32::from_float(1.5)),
+                WrtValue::F64(FloatBits64::from_float(-2.25)),
+                WrtValue::V128(V128 { bytes: [0xAB; 16] }),
+                WrtValue::FuncRef(Some(FuncRef::from_index(4))),
+                WrtValue::FuncRef(None),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_section_round_trip() {
+        let snapshot = snapshot();
+        let sections = snapshot.to_sections().unwrap();
+        assert_eq!(sections.len(), 3);
+        assert_eq!(
+            InstanceSnapshot::from_sections(&sections).unwrap(),
+            snapshot
+        );
+
+        // Memory contents larger than the memory are rejected
+        let mut oversized = snapshot.clone();
+        oversized.memories[0].pages = 0;
+        let sections = oversized.to_sections().unwrap();
+        assert!(InstanceSnapshot::from_sections(&sections).is_err());
+        assert!(InstanceSnapshot::from_sections(&sections[1..]).is_err());
+
+        let mut unsupported = snapshot;
+        unsupported.globals.push(WrtValue::ExternRef(None));
+        assert!(unsupported.to_sections().is_err());
+    }
+
+    #[test]
+    fn test_binary_round_trip() {
+        let mut module = WASM_MAGIC.to_vec();
+        module.extend_from_slice(&[1, 0, 0, 0]);
+        // An unrelated custom section is kept
+        module.extend_from_slice(&[CUSTOM_SECTION_ID, 3, 2, b'h', b'i']);
+        assert_eq!(InstanceSnapshot::from_binary(&module).unwrap(), None);
+
+        let snapshot = snapshot();
+        let initialized = snapshot.append_to_binary(&module).unwrap();
+        assert_eq!(&initialized[..module.len()], &module[..]);
+        assert_eq!(
+            InstanceSnapshot::from_binary(&initialized).unwrap(),
+            Some(snapshot.clone())
+        );
+        assert!(snapshot.append_to_binary(&initialized).is_err());
+
+        assert!(InstanceSnapshot::from_binary(&initialized[..initialized.len() - 1]).is_err());
+        assert!(InstanceSnapshot::from_binary(b"not wasm").is_err());
+    }
+}
@@ -35,18 +35,31 @@
 // Import the TableOperations trait from wrt-instructions
 use wrt_instructions::table_ops::TableOperations;
 
-use crate::prelude::{
-    Arc,
-    BoundedCapacity,
-    Debug,
-    Eq,
-    Error,
-    ErrorCategory,
-    Ord,
-    PartialEq,
-    Result,
-    RuntimeString,
-    TryFrom,
+#[cfg(feature = "std")]
+use crate::growth_observer::{
+    GrowthHook,
+    GrowthKind,
+    GrowthRequester,
+    MemoryObserver,
+};
+use crate::{
+    mcdc::{
+        decision,
+        Decision,
+    },
+    prelude::{
+        Arc,
+        BoundedCapacity,
+        Debug,
+        Eq,
+        Error,
+        ErrorCategory,
+        Ord,
+        PartialEq,
+        Result,
+        RuntimeString,
+        TryFrom,
+    },
 };
 
 /// Invalid index error code
@@ -86,26 +99,93 @@ fn usize_to_wasm_u32(size: usize) -> Result<u32> {
     })
 }
 
+/// Bytes of an element: the `Some` tag (1), the reference kind (1), its null
+/// flag (1) and the referenced index (4)
+const TABLE_ELEMENT_SIZE: usize = 7;
+
+/// An element of a table
+///
+/// Bounded vectors size their slots by the default item, which for a bare
+/// `Option<WrtValue>` is `None`, so elements serialize padded to the size of
+/// the largest reference.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct TableElement(Option<WrtValue>);
+
+impl TableElement {
+    /// Bytes the element is serialized to before padding
+    fn used_size(&self) -> Result<usize> {
+        match &self.0 {
+            None => Ok(1),
+            Some(WrtValue::FuncRef(None) | WrtValue::ExternRef(None)) => Ok(3),
+            Some(WrtValue::FuncRef(Some(_)) | WrtValue::ExternRef(Some(_))) => {
+                Ok(TABLE_ELEMENT_SIZE)
+            },
+            Some(_) => Err(Error::validation_error("Table elements must be references")),
+        }
+    }
+}
+
+impl wrt_foundation::traits::Checksummable for TableElement {
+    fn update_checksum(&self, checksum: &mut wrt_foundation::verification::Checksum) {
+        self.0.update_checksum(checksum);
+    }
+}
+
+impl wrt_foundation::traits::ToBytes for TableElement {
+    fn serialized_size(&self) -> usize {
+        TABLE_ELEMENT_SIZE
+    }
+
+    fn to_bytes_with_provider<P: wrt_foundation::MemoryProvider>(
+        &self,
+        writer: &mut wrt_foundation::traits::WriteStream<'_>,
+        provider: &P,
+    ) -> Result<()> {
+        self.0.to_bytes_with_provider(writer, provider)?;
+        for _ in self.used_size()?..TABLE_ELEMENT_SIZE {
+            writer.write_u8(0)?;
+        }
+        Ok(())
+    }
+}
+
+impl wrt_foundation::traits::FromBytes for TableElement {
+    fn from_bytes_with_provider<P: wrt_foundation::MemoryProvider>(
+        reader: &mut wrt_foundation::traits::ReadStream<'_>,
+        provider: &P,
+    ) -> Result<Self> {
+        let element = Self(Option::<WrtValue>::from_bytes_with_provider(
+            reader, provider,
+        )?);
+        for _ in element.used_size()?..TABLE_ELEMENT_SIZE {
+            reader.read_u8()?;
+        }
+        Ok(element)
+    }
+}
+
+/// Elements of a table
+type TableElements = BoundedVec<TableElement, 1024, TableProvider>;
+
 /// A WebAssembly table is a vector of opaque values of a single type.
 #[derive(Debug)]
 pub struct Table {
     /// The table type, using the canonical `WrtTableType`
     pub ty:                 WrtTableType,
     /// The table elements
-    elements: wrt_foundation::bounded::BoundedVec<Option<WrtValue>, 1024, TableProvider>,
+    elements:               TableElements,
     /// A debug name for the table (optional)
     pub debug_name:         Option<RuntimeString>,
     /// Verification level for table operations
     pub verification_level: VerificationLevel,
+    /// Observer notified about growth
+    #[cfg(feature = "std")]
+    growth_hook:            Option<GrowthHook>,
 }
 
 impl Clone for Table {
     fn clone(&self) -> Self {
-        let mut new_elements: wrt_foundation::bounded::BoundedVec<
-            Option<WrtValue>,
-            1024,
-            TableProvider,
-        > = wrt_foundation::bounded::BoundedVec::new(TableProvider::default()).unwrap();
+        let mut new_elements = TableElements::new(TableProvider::default()).unwrap();
         // Note: BoundedVec doesn't have set_verification_level method
         for i in 0..self.elements.len() {
             // Use BoundedVec get method for safe access
@@ -121,6 +201,8 @@ fn clone(&self) -> Self {
             elements:           new_elements,
             debug_name:         self.debug_name.clone(),
             verification_level: self.verification_level,
+            #[cfg(feature = "std")]
+            growth_hook:        None,
         }
     }
 }
@@ -243,15 +325,11 @@ pub fn new(ty: WrtTableType) -> Result<Self> {
         };
 
         let initial_size = wasm_index_to_usize(ty.limits.min)?;
-        let mut elements: wrt_foundation::bounded::BoundedVec<
-            Option<WrtValue>,
-            1024,
-            TableProvider,
-        > = wrt_foundation::bounded::BoundedVec::new(TableProvider::default())?;
+        let mut elements = TableElements::new(TableProvider::default())?;
         // Note: BoundedVec doesn't have set_verification_level method
 
         for _ in 0..initial_size {
-            elements.push(init_val.clone())?;
+            elements.push(TableElement(init_val.clone()))?;
         }
 
         Ok(Self {
@@ -259,6 +337,8 @@ pub fn new(ty: WrtTableType) -> Result<Self> {
             elements,
             verification_level: VerificationLevel::default(),
             debug_name: None,
+            #[cfg(feature = "std")]
+            growth_hook: None,
         })
     }
 
@@ -297,6 +377,47 @@ pub fn size(&self) -> u32 {
         usize_to_wasm_u32(self.elements.len()).unwrap_or(0)
     }
 
+    /// Checks whether this table can satisfy an import of the given type
+    ///
+    /// The element types must be equal, the current size must be at least the
+    /// required minimum, and a required maximum must be matched by an equal or
+    /// smaller declared maximum.
+    ///
+    /// # Errors
+    ///
+    /// Returns a type mismatch error describing the first incompatibility
+    pub fn check_import_compatibility(&self, required: &WrtTableType) -> Result<()> {
+        if self.ty.element_type != required.element_type {
+            return Err(Error::runtime_type_mismatch(
+                "Imported table element type does not match",
+            ));
+        }
+
+        if self.size() < required.limits.min {
+            return Err(Error::runtime_type_mismatch(
+                "Imported table is smaller than the required minimum",
+            ));
+        }
+
+        if let Some(required_max) = required.limits.max {
+            match self.ty.limits.max {
+                Some(max) if max <= required_max => {},
+                Some(_) => {
+                    return Err(Error::runtime_type_mismatch(
+                        "Imported table maximum exceeds the required maximum",
+                    ))
+                },
+                None => {
+                    return Err(Error::runtime_type_mismatch(
+                        "Imported table has no maximum but one is required",
+                    ))
+                },
+            }
+        }
+
+        Ok(())
+    }
+
     /// Gets an element from the table
     ///
     /// # Arguments
@@ -312,7 +433,7 @@ pub fn size(&self) -> u32 {
     /// Returns an error if the index is out of bounds
     pub fn get(&self, idx: u32) -> Result<Option<WrtValue>> {
         let idx = wasm_index_to_usize(idx)?;
-        if idx >= self.elements.len() {
+        if !decision!(Decision::TableIndexInBounds, idx < self.elements.len()) {
             return Err(Error::invalid_function_index("Table access out of bounds"));
         }
 
@@ -330,6 +451,7 @@ pub fn get(&self, idx: u32) -> Result<Option<WrtValue>> {
         // Use BoundedVec's get method for direct access
         self.elements
             .get(idx as usize)
+            .map(|element| element.0)
             .map_err(|_| Error::invalid_function_index("Table index out of bounds"))
     }
 
@@ -350,7 +472,7 @@ pub fn get(&self, idx: u32) -> Result<Option<WrtValue>> {
     /// doesn't match the table element type
     pub fn set(&mut self, idx: u32, value: Option<WrtValue>) -> Result<()> {
         let idx = wasm_index_to_usize(idx)?;
-        if idx >= self.elements.len() {
+        if !decision!(Decision::TableIndexInBounds, idx < self.elements.len()) {
             return Err(Error::invalid_function_index("Table access out of bounds"));
         }
 
@@ -366,10 +488,21 @@ pub fn set(&mut self, idx: u32, value: Option<WrtValue>) -> Result<()> {
                 ));
             }
         }
-        self.elements.set(idx, value)?;
+        self.elements.set(idx, TableElement(value))?;
         Ok(())
     }
 
+    /// Observe the growth of the table with `observer`, reporting it as
+    /// `requester`
+    #[cfg(feature = "std")]
+    pub fn set_growth_observer(
+        &mut self,
+        observer: Arc<dyn MemoryObserver>,
+        requester: GrowthRequester,
+    ) {
+        self.growth_hook = Some(GrowthHook::new(observer, requester));
+    }
+
     /// Grows the table by the given number of elements
     ///
     /// # Arguments
@@ -413,9 +546,23 @@ pub fn grow(&mut self, delta: u32, init_value_from_arg: WrtValue) -> Result<u32>
             }
         }
 
+        #[cfg(feature = "std")]
+        let event = match &self.growth_hook {
+            Some(hook) => {
+                let event = hook.event(GrowthKind::Table, old_size, new_size);
+                hook.check(&event)?;
+                Some((hook, event))
+            },
+            None => None,
+        };
+
         // Use SafeStack's grow method or manually push
         for _ in 0..delta {
-            self.elements.push(Some(init_value_from_arg.clone()))?;
+            self.elements.push(TableElement(Some(init_value_from_arg.clone())))?;
+        }
+        #[cfg(feature = "std")]
+        if let Some((hook, event)) = event {
+            hook.grown(&event);
         }
         // Update the min limit in the table type if it changes due to growth (spec is a
         // bit unclear if ty should reflect current size) For now, ty.limits.min
@@ -482,7 +629,7 @@ pub fn init(&mut self, offset: u32, init_data: &[Option<WrtValue>]) -> Result<()
                     return Err(Error::validation_error("Table init value type mismatch"));
                 }
             }
-            self.elements.set((offset as usize) + i, val_opt.clone())?;
+            self.elements.set((offset as usize) + i, TableElement(val_opt.clone()))?;
         }
         Ok(())
     }
@@ -500,11 +647,7 @@ pub fn copy_elements(&mut self, dst: usize, src: usize, len: usize) -> Result<()
         }
 
         // Create temporary stack to store elements during copy
-        let mut temp_vec: wrt_foundation::bounded::BoundedVec<
-            Option<WrtValue>,
-            1024,
-            TableProvider,
-        > = wrt_foundation::bounded::BoundedVec::new(TableProvider::default()).unwrap();
+        let mut temp_vec = TableElements::new(TableProvider::default()).unwrap();
         // Note: verification level handled by provider
 
         // Read source elements into temporary stack
@@ -513,11 +656,7 @@ pub fn copy_elements(&mut self, dst: usize, src: usize, len: usize) -> Result<()
         }
 
         // Create a new stack for the full result
-        let mut result_vec: wrt_foundation::bounded::BoundedVec<
-            Option<WrtValue>,
-            1024,
-            TableProvider,
-        > = wrt_foundation::bounded::BoundedVec::new(TableProvider::default()).unwrap();
+        let mut result_vec = TableElements::new(TableProvider::default()).unwrap();
         // Note: verification level handled by provider
 
         // Copy elements with the updated values
@@ -555,14 +694,13 @@ pub fn fill_elements(
         }
 
         // Create a new stack with the filled elements
-        let mut result_vec =
-            wrt_foundation::bounded::BoundedVec::new(TableProvider::default()).unwrap();
+        let mut result_vec = TableElements::new(TableProvider::default()).unwrap();
 
         // Copy elements with fill applied
         for i in 0..self.elements.len() {
             if i >= offset && i < offset + len {
                 // This is in the fill range
-                result_vec.push(value.clone())?;
+                result_vec.push(TableElement(value.clone()))?;
             } else {
                 // Outside fill range, use original value
                 result_vec.push(self.elements.get(i)?)?;
@@ -607,14 +745,13 @@ pub fn init_element(&mut self, idx: usize, value: Option<WrtValue>) -> Result<()
         self.elements.get(idx)?; // Verify access is valid
 
         // Create temporary stack to hold all elements
-        let mut temp_vec =
-            wrt_foundation::bounded::BoundedVec::new(TableProvider::default()).unwrap();
+        let mut temp_vec = TableElements::new(TableProvider::default()).unwrap();
         // Note: verification level handled by provider
 
         // Copy elements, replacing the one at idx
         for i in 0..self.elements.len() {
             if i == idx {
-                temp_vec.push(value.clone())?;
+                temp_vec.push(TableElement(value.clone()))?;
             } else {
                 temp_vec.push(self.elements.get(i)?)?;
             }
@@ -827,7 +964,8 @@ fn test_table_creation() {
 
         for i in 0..10 {
             let value = table.get(i).unwrap();
-            assert_eq!(value, None); // Default initialized to None
+            // Initialized to null references
+            assert_eq!(value, Some(init_value.clone()));
         }
     }
 
@@ -966,9 +1104,9 @@ fn test_arc_table_extensions() -> Result<()> {
         // Test get/set
         arc_table.set(2, Some(WrtValue::FuncRef(Some(WrtFuncRef { index: 42 }))))?;
         // Clone-and-mutate pattern doesn't modify the original Arc value
-        // So the get operation should return the original unmodified value
+        // So the get operation should return the original null reference
         let value = arc_table.get(2)?;
-        assert_eq!(value, None);
+        assert_eq!(value, Some(WrtValue::FuncRef(None)));
 
         // Test grow
         let old_size = arc_table.grow(3, WrtValue::FuncRef(None))?;
@@ -978,7 +1116,7 @@ fn test_arc_table_extensions() -> Result<()> {
         // Test set_func
         arc_table.set_func(3, 99)?;
         let value = arc_table.get(3)?;
-        assert_eq!(value, None); // The clone-and-mutate pattern returns results but doesn't modify the original
+        assert_eq!(value, Some(WrtValue::FuncRef(None))); // The clone-and-mutate pattern returns results but doesn't modify the original
 
         // Test init
         let init_values = vec![
@@ -1095,8 +1233,27 @@ fn test_table_memory_safety() -> Result<()> {
         assert_eq!(table.get(2)?, fill_value);
 
         // Print safety stats
-        println!("{}", table.safety_stats());
+        println!("{:?}", table.safety_stats());
 
         Ok(())
     }
+
+    #[test]
+    fn test_import_compatibility() {
+        let table = Table::new(create_test_table_type(4, Some(8))).unwrap();
+
+        assert!(table.check_import_compatibility(&create_test_table_type(2, None)).is_ok());
+        assert!(table.check_import_compatibility(&create_test_table_type(4, Some(8))).is_ok());
+
+        // Current size below the required minimum
+        assert!(table.check_import_compatibility(&create_test_table_type(5, None)).is_err());
+        // Declared maximum above the required maximum
+        assert!(table.check_import_compatibility(&create_test_table_type(1, Some(6))).is_err());
+
+        let externref = WrtTableType {
+            element_type: WrtRefType::Externref,
+            limits:       WrtLimits { min: 1, max: None },
+        };
+        assert!(table.check_import_compatibility(&externref).is_err());
+    }
 }
@@ -0,0 +1,38 @@
+//! Loading modules from the WebAssembly text format
+//!
+//! With the `wat` feature, modules written in the text format can be loaded
+//! directly, which spares examples, tests and interactive embeddings a
+//! separate build step. The text is translated to the binary format, which
+//! is then loaded like any other binary.
+
+use crate::prelude::*;
+
+/// Translate a module in the WebAssembly text format to the binary format
+///
+/// # Errors
+///
+/// Returns a parse error if `text` is not a valid text-format module.
+pub fn wat_to_binary(text: &str) -> Result<Vec<u8>> {
+    wat::parse_str(text).map_err(|_| Error::parse_error("Invalid WebAssembly text format"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::module::Module;
+
+    #[test]
+    fn test_wat_to_binary() {
+        let binary = wat_to_binary("(module (memory (export \"mem\") 1))").unwrap();
+        assert_eq!(&binary[..8], b"\0asm\x01\0\0\0");
+
+        assert!(wat_to_binary("(module (func (i32.const)))").is_err());
+        assert!(wat_to_binary("not a module").is_err());
+    }
+
+    #[test]
+    fn test_module_from_wat() {
+        assert!(Module::from_wat("(module (memory 1 2) (global i32 (i32.const 7)))").is_ok());
+        assert!(Module::from_wat("(module (memory 1 2)").is_err());
+    }
+}
@@ -171,7 +171,7 @@ pub struct ThreadInfo {
     pub priority:       u8,
     /// Parent thread ID (if spawned by another thread)
     pub parent_thread:  Option<ThreadId>,
-    /// Thread creation timestamp (nanoseconds since epoch)
+    /// Thread creation timestamp (engine timestamp in nanoseconds)
     pub created_at:     u64,
     /// Thread completion timestamp (if completed)
     pub completed_at:   Option<u64>,
@@ -194,7 +194,7 @@ pub fn new(
             stack_size,
             priority,
             parent_thread,
-            created_at: wrt_foundation::current_time_ns(),
+            created_at: crate::time_source::timestamp_ns(),
             completed_at: None,
         }
     }
@@ -255,7 +255,7 @@ pub fn new(info: ThreadInfo) -> Result<Self> {
     pub fn update_state(&mut self, new_state: ThreadState) {
         self.info.state = new_state;
         if new_state.is_completed() {
-            self.info.completed_at = Some(wrt_platform::time::current_time_ns());
+            self.info.completed_at = Some(crate::time_source::timestamp_ns());
         }
     }
 
@@ -335,6 +335,26 @@ fn default() -> Self {
     }
 }
 
+/// Slots of the managed threads, indexed by thread ID. They live on the heap
+/// where there is one, as all of them together would not fit on a stack
+#[cfg(any(feature = "std", feature = "alloc"))]
+type ThreadSlots = crate::prelude::Box<[Option<ThreadExecutionContext>]>;
+#[cfg(not(any(feature = "std", feature = "alloc")))]
+type ThreadSlots = [Option<ThreadExecutionContext>; MAX_MANAGED_THREADS];
+
+/// Slots without any thread
+fn empty_thread_slots() -> ThreadSlots {
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    {
+        (0..MAX_MANAGED_THREADS).map(|_| None).collect()
+    }
+
+    #[cfg(not(any(feature = "std", feature = "alloc")))]
+    {
+        [const { None }; MAX_MANAGED_THREADS]
+    }
+}
+
 /// WebAssembly thread manager
 #[derive(Debug)]
 pub struct ThreadManager {
@@ -343,7 +363,7 @@ pub struct ThreadManager {
     /// Active thread contexts using bounded collections
     // TODO: Replace with proper bounded collection once ThreadExecutionContext implements required
     // traits
-    threads: [Option<ThreadExecutionContext>; MAX_MANAGED_THREADS],
+    threads: ThreadSlots,
     /// Next thread ID to assign
     next_thread_id: ThreadId,
     /// Thread manager statistics
@@ -355,7 +375,7 @@ impl ThreadManager {
     pub fn new(config: ThreadConfig) -> Result<Self> {
         Ok(Self {
             config,
-            threads: [const { None }; MAX_MANAGED_THREADS],
+            threads: empty_thread_slots(),
             next_thread_id: 1, // Thread ID 0 is reserved for main thread
             stats: ThreadManagerStats::new(),
         })
@@ -533,59 +553,25 @@ pub fn get_active_threads(&self) -> Vec<ThreadId> {
 
     /// Get number of active threads
     pub fn active_thread_count(&self) -> usize {
-        // Simplified implementation for bounded collections
-        // TODO: Implement proper active thread counting when bounded map API supports
-        // iteration For now, return length as a placeholder
-        self.threads.len()
+        self.threads.iter().flatten().filter(|context| context.info.is_active()).count()
     }
 
     /// Cleanup completed threads
     pub fn cleanup_completed_threads(&mut self) -> usize {
         let initial_count = self.thread_count();
 
-        #[cfg(feature = "std")]
-        {
-            // For arrays, we need to manually implement retain-like behavior
-            for i in 0..self.threads.len() {
-                if let Some(context) = &self.threads[i] {
-                    if !context.info.is_active() {
-                        self.threads[i] = None;
-                    }
-                }
+        for slot in self.threads.iter_mut() {
+            if slot.as_ref().is_some_and(|context| !context.info.is_active()) {
+                *slot = None;
             }
         }
-        #[cfg(not(feature = "std"))]
-        {
-            // Binary std/no_std choice
-            let mut write_idx = 0;
-            for read_idx in 0..self.threads.len() {
-                if let Some(context) = &self.threads[read_idx] {
-                    if context.info.is_active() {
-                        if write_idx != read_idx {
-                            // Move active thread to write position
-                            // This is a simplified approach - in practice might
-                            // need more sophisticated cleanup
-                        }
-                        write_idx += 1;
-                    }
-                }
-            }
-            // Truncate to remove completed threads (simplified)
-        }
 
         initial_count - self.thread_count()
     }
 
     /// Get total thread count
     pub fn thread_count(&self) -> usize {
-        #[cfg(feature = "std")]
-        {
-            self.threads.len()
-        }
-        #[cfg(not(feature = "std"))]
-        {
-            self.threads.iter().filter(|slot| slot.is_some()).count()
-        }
+        self.threads.iter().filter(|slot| slot.is_some()).count()
     }
 
     // Private helper methods
@@ -620,7 +606,7 @@ fn default() -> Self {
         Self::new(ThreadConfig::default()).unwrap_or_else(|_| {
             // Create a minimal thread manager with very limited resources
             Self {
-                threads:        [const { None }; MAX_MANAGED_THREADS],
+                threads:        empty_thread_slots(),
                 next_thread_id: 1,
                 config:         ThreadConfig {
                     max_threads:        1,
@@ -715,6 +701,7 @@ fn test_thread_manager_creation() {
     #[cfg(feature = "std")]
     #[test]
     fn test_thread_spawning() {
+        wrt_foundation::memory_init::MemoryInitializer::ensure_initialized().unwrap();
         let mut manager = ThreadManager::default();
 
         let thread_id = manager.spawn_thread(42, Some(2 * 1024 * 1024), None).unwrap();
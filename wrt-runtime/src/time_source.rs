@@ -0,0 +1,212 @@
+//! Clock for engine event timestamps
+//!
+//! Tracing and statistics of the runtime take their timestamps from a single
+//! [`TimeSource`], selected once per process through
+//! [`EngineConfig::with_time_source`](crate::engine_factory::EngineConfig::with_time_source)
+//! or [`select_time_source`]. Targets without `std` can back it with a
+//! hardware cycle counter or the tick counter of their RTOS, so traces
+//! recorded there carry real timing instead of a bare event count. Until a
+//! clock is selected, the clock installed through
+//! [`wrt_foundation::platform_hooks`] is used if there is one.
+//!
+//! Timestamps are nanoseconds since an arbitrary origin and never decrease.
+//! Counters narrower than 64 bits should be extended by the read function, as
+//! a wrapped counter holds the timestamps at their last value until it
+//! catches up again.
+
+use core::sync::atomic::{
+    AtomicU64,
+    Ordering,
+};
+
+use wrt_error::{
+    Error,
+    Result,
+};
+use wrt_foundation::platform_hooks;
+use wrt_sync::WrtOnce;
+
+const NANOS_PER_SECOND: u128 = 1_000_000_000;
+
+/// Clock the engine's timestamps are read from
+#[derive(Debug, Clone, Copy, Default)]
+pub enum TimeSource {
+    /// Logical clock advancing by one per reading, for targets without any
+    /// timer
+    #[cfg_attr(not(feature = "std"), default)]
+    Counter,
+    /// `std::time::Instant`, relative to the first reading
+    #[cfg(feature = "std")]
+    #[default]
+    StdInstant,
+    /// Hardware cycle counter, such as the Cortex-M DWT `CYCCNT`
+    CycleCounter {
+        /// Read the current cycle count
+        read:         fn() -> u64,
+        /// Cycles per second
+        frequency_hz: u64,
+    },
+    /// Tick counter of an RTOS, such as `k_uptime_ticks` on Zephyr
+    RtosTick {
+        /// Read the current tick count
+        read:    fn() -> u64,
+        /// Ticks per second
+        tick_hz: u64,
+    },
+    /// Clock installed through [`platform_hooks::install_clock`]
+    Platform,
+}
+
+impl TimeSource {
+    /// Read the clock in nanoseconds since its origin
+    pub fn read_ns(&self) -> u64 {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+        match *self {
+            Self::Counter => COUNTER.fetch_add(1, Ordering::Relaxed),
+            #[cfg(feature = "std")]
+            Self::StdInstant => {
+                static ORIGIN: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+                let elapsed = ORIGIN.get_or_init(std::time::Instant::now).elapsed();
+                u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX)
+            },
+            Self::CycleCounter { read, frequency_hz } => ticks_to_ns(read(), frequency_hz),
+            Self::RtosTick { read, tick_hz } => ticks_to_ns(read(), tick_hz),
+            Self::Platform => platform_hooks::monotonic_ns().unwrap_or(0),
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        match *self {
+            Self::CycleCounter {
+                frequency_hz: 0, ..
+            }
+            | Self::RtosTick { tick_hz: 0, .. } => Err(Error::validation_error(
+                "Time source frequency must not be zero",
+            )),
+            Self::Platform if platform_hooks::clock().is_none() => Err(
+                Error::runtime_invalid_state("No platform clock is installed"),
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    fn same_as(&self, other: &Self) -> bool {
+        match (*self, *other) {
+            (Self::Counter, Self::Counter) | (Self::Platform, Self::Platform) => true,
+            #[cfg(feature = "std")]
+            (Self::StdInstant, Self::StdInstant) => true,
+            (
+                Self::CycleCounter { read, frequency_hz },
+                Self::CycleCounter {
+                    read: other_read,
+                    frequency_hz: other_frequency,
+                },
+            ) => read as usize == other_read as usize && frequency_hz == other_frequency,
+            (
+                Self::RtosTick { read, tick_hz },
+                Self::RtosTick {
+                    read: other_read,
+                    tick_hz: other_hz,
+                },
+            ) => read as usize == other_read as usize && tick_hz == other_hz,
+            _ => false,
+        }
+    }
+}
+
+fn ticks_to_ns(ticks: u64, hz: u64) -> u64 {
+    let nanos = u128::from(ticks) * NANOS_PER_SECOND / u128::from(hz.max(1));
+    u64::try_from(nanos).unwrap_or(u64::MAX)
+}
+
+static SELECTED: WrtOnce<TimeSource> = WrtOnce::new();
+static LAST_TIMESTAMP: AtomicU64 = AtomicU64::new(0);
+
+/// Select the clock for engine timestamps
+///
+/// The clock can be selected only once per process. Selecting the already
+/// selected clock again succeeds, selecting a different one fails.
+pub fn select_time_source(source: TimeSource) -> Result<()> {
+    source.validate()?;
+    if SELECTED.get_or_init(|| source).same_as(&source) {
+        Ok(())
+    } else {
+        Err(Error::runtime_invalid_state(
+            "A different time source is already selected",
+        ))
+    }
+}
+
+/// Clock engine timestamps are read from
+///
+/// Until a clock is selected, returns the installed platform clock or, if
+/// there is none, the default clock of the target.
+pub fn time_source() -> TimeSource {
+    SELECTED.get().copied().unwrap_or_else(|| {
+        if platform_hooks::clock().is_some() {
+            TimeSource::Platform
+        } else {
+            TimeSource::default()
+        }
+    })
+}
+
+/// Current engine timestamp in nanoseconds
+pub fn timestamp_ns() -> u64 {
+    let now = time_source().read_ns();
+    LAST_TIMESTAMP.fetch_max(now, Ordering::AcqRel).max(now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed_cycles() -> u64 {
+        48_000_000
+    }
+
+    #[test]
+    fn test_counter_conversion() {
+        let cycles = TimeSource::CycleCounter {
+            read:         fixed_cycles,
+            frequency_hz: 48_000_000,
+        };
+        assert_eq!(cycles.read_ns(), 1_000_000_000);
+
+        let ticks = TimeSource::RtosTick {
+            read:    fixed_cycles,
+            tick_hz: 1_000,
+        };
+        assert_eq!(ticks.read_ns(), 48_000_000_000_000);
+        assert_eq!(ticks_to_ns(u64::MAX, 1), u64::MAX);
+    }
+
+    #[test]
+    fn test_platform_requires_installed_clock() {
+        assert!(platform_hooks::clock().is_none());
+        assert!(TimeSource::Platform.validate().is_err());
+        assert_eq!(TimeSource::Platform.read_ns(), 0);
+    }
+
+    #[test]
+    fn test_select_once() {
+        let zero = TimeSource::RtosTick {
+            read:    fixed_cycles,
+            tick_hz: 0,
+        };
+        assert!(select_time_source(zero).is_err());
+
+        let source = TimeSource::CycleCounter {
+            read:         fixed_cycles,
+            frequency_hz: 1_000_000,
+        };
+        select_time_source(source).unwrap();
+        select_time_source(source).unwrap();
+        assert!(select_time_source(TimeSource::Counter).is_err());
+        assert!(time_source().same_as(&source));
+
+        assert_eq!(time_source().read_ns(), 48_000_000_000);
+        assert!(timestamp_ns() >= 48_000_000_000);
+    }
+}
@@ -87,7 +87,10 @@ pub fn expand_locals_to_flat(
 
 #[cfg(test)]
 mod tests {
-    use wrt_foundation::types::ValueType;
+    use wrt_foundation::{
+        traits::BoundedCapacity,
+        types::ValueType,
+    };
 
     use super::*;
 
@@ -104,8 +107,8 @@ fn test_convert_single_type_group() {
         let result = convert_locals_to_bounded(&locals).unwrap();
 
         assert_eq!(result.len(), 1);
-        assert_eq!(result[0].count, 3);
-        assert_eq!(result[0].value_type, ValueType::I32);
+        assert_eq!(result.get(0).unwrap().count, 3);
+        assert_eq!(result.get(0).unwrap().value_type, ValueType::I32);
     }
 
     #[test]
@@ -120,14 +123,14 @@ fn test_convert_multiple_type_groups() {
 
         assert_eq!(result.len(), 3);
 
-        assert_eq!(result[0].count, 2);
-        assert_eq!(result[0].value_type, ValueType::I32);
+        assert_eq!(result.get(0).unwrap().count, 2);
+        assert_eq!(result.get(0).unwrap().value_type, ValueType::I32);
 
-        assert_eq!(result[1].count, 1);
-        assert_eq!(result[1].value_type, ValueType::F64);
+        assert_eq!(result.get(1).unwrap().count, 1);
+        assert_eq!(result.get(1).unwrap().value_type, ValueType::F64);
 
-        assert_eq!(result[2].count, 1);
-        assert_eq!(result[2].value_type, ValueType::I32);
+        assert_eq!(result.get(2).unwrap().count, 1);
+        assert_eq!(result.get(2).unwrap().value_type, ValueType::I32);
     }
 
     #[test]
@@ -0,0 +1,215 @@
+//! Detection of reads of uninitialized linear memory
+//!
+//! A fresh memory reads as zeros, so a guest reading bytes it never wrote
+//! gets plausible values instead of failing. When the engine is told to check
+//! such reads, see
+//! [`CapabilityAwareEngine::set_uninit_read_check`](crate::engine::CapabilityAwareEngine::set_uninit_read_check),
+//! every memory an instance defines carries an [`InitTracker`]: a bitmap with
+//! one bit per byte, set by writes, fills, `memory.init` and the active data
+//! segments of the module. A read of a byte whose bit is clear is recorded in
+//! the engine's [`UninitReadLog`] and, in [`UninitReadMode::Trap`], traps.
+//!
+//! Copies within and between memories carry the bits of their source along
+//! instead of checking them, so that copying a partly initialized structure
+//! is only reported when the uninitialized part is read. Memories imported
+//! from the host and memories restored from a snapshot are taken as fully
+//! initialized.
+
+use core::sync::atomic::{
+    AtomicU64,
+    Ordering,
+};
+use std::sync::Mutex;
+
+use wrt_error::{
+    Error,
+    Result,
+};
+
+use crate::prelude::*;
+
+/// Reads of uninitialized memory kept by an [`UninitReadLog`]
+pub const MAX_LOGGED_READS: usize = 256;
+
+/// What happens on a read of uninitialized memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UninitReadMode {
+    /// The read traps
+    Trap,
+    /// The read succeeds and is only recorded
+    Warn,
+}
+
+/// A read of uninitialized memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UninitRead {
+    /// Index of the instance that defines the memory
+    pub instance: usize,
+    /// Index of the memory in the index space of the instance
+    pub memory:   u32,
+    /// Address of the first uninitialized byte read
+    pub offset:   usize,
+    /// Length of the read
+    pub len:      usize,
+}
+
+/// Reads of uninitialized memory recorded by the trackers of an engine
+#[derive(Debug, Default)]
+pub struct UninitReadLog {
+    reads: Mutex<Vec<UninitRead>>,
+    count: AtomicU64,
+}
+
+impl UninitReadLog {
+    /// An empty log
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of reads recorded, including those no longer kept
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// The first [`MAX_LOGGED_READS`] reads recorded
+    pub fn reads(&self) -> Vec<UninitRead> {
+        self.reads.lock().map(|reads| reads.clone()).unwrap_or_default()
+    }
+
+    fn record(&self, read: UninitRead) {
+        self.count.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut reads) = self.reads.lock() {
+            if reads.len() < MAX_LOGGED_READS {
+                reads.push(read);
+            }
+        }
+    }
+}
+
+/// Bytes of a memory written since it was created
+#[derive(Debug, Clone)]
+pub struct InitTracker {
+    written:  Vec<u64>,
+    size:     usize,
+    mode:     UninitReadMode,
+    instance: usize,
+    memory:   u32,
+    log:      Arc<UninitReadLog>,
+}
+
+impl InitTracker {
+    /// Tracker for memory `memory` of instance `instance`, `size` bytes long
+    /// and not written yet, recording reads of uninitialized bytes in `log`
+    pub fn new(
+        size: usize,
+        mode: UninitReadMode,
+        instance: usize,
+        memory: u32,
+        log: Arc<UninitReadLog>,
+    ) -> Self {
+        Self {
+            written: vec![0; size.div_ceil(64)],
+            size,
+            mode,
+            instance,
+            memory,
+            log,
+        }
+    }
+
+    /// Whether the byte at `offset` was written
+    pub fn is_written(&self, offset: usize) -> bool {
+        offset < self.size && self.written[offset / 64] & (1 << (offset % 64)) != 0
+    }
+
+    /// Record the bytes `offset..offset + len` as written
+    pub fn mark_written(&mut self, offset: usize, len: usize) {
+        self.set_range(offset, len, true);
+    }
+
+    /// Extend the tracked memory to `size` bytes, the new ones not written
+    pub fn grow(&mut self, size: usize) {
+        if size > self.size {
+            self.written.resize(size.div_ceil(64), 0);
+            self.size = size;
+        }
+    }
+
+    /// Check a read of the bytes `offset..offset + len`
+    ///
+    /// # Errors
+    ///
+    /// Returns a trap in [`UninitReadMode::Trap`] if any of the bytes was
+    /// never written.
+    pub fn check_read(&self, offset: usize, len: usize) -> Result<()> {
+        let Some(first) = (offset..offset.saturating_add(len)).find(|&at| !self.is_written(at))
+        else {
+            return Ok(());
+        };
+        self.log.record(UninitRead {
+            instance: self.instance,
+            memory: self.memory,
+            offset: first,
+            len,
+        });
+        match self.mode {
+            UninitReadMode::Trap => Err(Error::runtime_trap_error("Read of uninitialized memory")),
+            UninitReadMode::Warn => Ok(()),
+        }
+    }
+
+    /// Bits of the bytes `offset..offset + len`, for carrying them along a
+    /// copy
+    pub fn written_bits(&self, offset: usize, len: usize) -> Vec<bool> {
+        (offset..offset.saturating_add(len)).map(|at| self.is_written(at)).collect()
+    }
+
+    /// Set the bits of the bytes from `offset` on to `bits`
+    pub fn set_written_bits(&mut self, offset: usize, bits: &[bool]) {
+        for (at, &written) in (offset..).zip(bits) {
+            self.set_range(at, 1, written);
+        }
+    }
+
+    fn set_range(&mut self, offset: usize, len: usize, written: bool) {
+        let end = offset.saturating_add(len).min(self.size);
+        for at in offset..end {
+            let bit = 1 << (at % 64);
+            if written {
+                self.written[at / 64] |= bit;
+            } else {
+                self.written[at / 64] &= !bit;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_of_unwritten_bytes_are_recorded() {
+        let log = Arc::new(UninitReadLog::new());
+        let mut tracker = InitTracker::new(128, UninitReadMode::Trap, 0, 0, log.clone());
+        tracker.mark_written(8, 4);
+        assert!(tracker.check_read(8, 4).is_ok());
+        assert!(tracker.check_read(10, 4).is_err());
+
+        tracker.grow(256);
+        tracker.set_written_bits(200, &tracker.written_bits(8, 4));
+        assert!(tracker.check_read(200, 4).is_ok());
+        assert!(tracker.check_read(250, 1).is_err());
+
+        assert_eq!(log.count(), 2);
+        assert_eq!(log.reads()[0].offset, 12);
+    }
+
+    #[test]
+    fn test_warn_mode_does_not_trap() {
+        let log = Arc::new(UninitReadLog::new());
+        let tracker = InitTracker::new(16, UninitReadMode::Warn, 3, 1, log.clone());
+        assert!(tracker.check_read(0, 4).is_ok());
+        assert_eq!(log.reads()[0].instance, 3);
+    }
+}
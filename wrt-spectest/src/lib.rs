@@ -0,0 +1,41 @@
+//! # WRT Spec Test
+//!
+//! Runner for the WebAssembly specification test suite.
+//!
+//! [`SpecTestRunner`] parses `.wast` scripts and runs their directives
+//! (`module`, `register`, `invoke`, `assert_return`, `assert_trap`,
+//! `assert_invalid` and more) against the WRT engine. The resulting
+//! [`SpecTestReport`] counts passed, failed and skipped directives per
+//! proposal, so that conformance can be tracked over time:
+//!
+//! ```ignore
+//! let report = SpecTestRunner::default().run_directory(Path::new("testsuite"))?;
+//! println!("{report}");
+//! assert!(report.by_proposal()["core"].pass_rate() > 0.9);
+//! ```
+
+// WRT - wrt-spectest
+// Module: Spec Test Runner
+//
+// Copyright (c) 2025 Ralf Anton Beier
+// Licensed under the MIT license.
+// SPDX-License-Identifier: MIT
+
+#![forbid(unsafe_code)] // Rule 2
+#![warn(missing_docs)]
+
+extern crate alloc;
+
+pub mod report;
+pub mod runner;
+mod values;
+
+pub use report::{
+    proposal_of,
+    DirectiveOutcome,
+    DirectiveResult,
+    ScriptReport,
+    SpecTestReport,
+    Stats,
+};
+pub use runner::SpecTestRunner;
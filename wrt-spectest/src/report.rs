@@ -0,0 +1,225 @@
+//! Results of spec test runs
+
+use alloc::collections::BTreeMap;
+use core::fmt;
+use std::path::Path;
+
+/// Result of a single directive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DirectiveResult {
+    /// The directive behaved as the script expects
+    Passed,
+    /// The directive did not behave as the script expects
+    Failed(String),
+    /// The directive uses a feature the runner does not support
+    Skipped(String),
+}
+
+/// A directive of a script and its result
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectiveOutcome {
+    /// Line of the directive in the script, starting at 1
+    pub line:   usize,
+    /// Kind of the directive, such as `assert_return`
+    pub kind:   &'static str,
+    /// Result of the directive
+    pub result: DirectiveResult,
+}
+
+/// Pass, fail and skip counts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Number of passed directives
+    pub passed:  usize,
+    /// Number of failed directives
+    pub failed:  usize,
+    /// Number of skipped directives
+    pub skipped: usize,
+}
+
+impl Stats {
+    /// Number of directives that ran, passed or failed
+    pub fn executed(&self) -> usize {
+        self.passed + self.failed
+    }
+
+    /// Fraction of the executed directives that passed, 1.0 if none ran
+    pub fn pass_rate(&self) -> f64 {
+        match self.executed() {
+            0 => 1.0,
+            executed => self.passed as f64 / executed as f64,
+        }
+    }
+
+    fn add(&mut self, other: Stats) {
+        self.passed += other.passed;
+        self.failed += other.failed;
+        self.skipped += other.skipped;
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{} passed ({:.1}%), {} skipped",
+            self.passed,
+            self.executed(),
+            self.pass_rate() * 100.0,
+            self.skipped
+        )
+    }
+}
+
+/// Results of one script
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptReport {
+    /// Name of the script, usually its path
+    pub name:       String,
+    /// Proposal the script belongs to, `core` for the core specification
+    pub proposal:   String,
+    /// Error that prevented the script from running at all
+    pub error:      Option<String>,
+    /// Results of the directives, in script order
+    pub directives: Vec<DirectiveOutcome>,
+}
+
+impl ScriptReport {
+    /// Counts of the directive results
+    ///
+    /// A script that failed to run counts as one failure.
+    pub fn stats(&self) -> Stats {
+        let mut stats = Stats::default();
+        if self.error.is_some() {
+            stats.failed += 1;
+        }
+        for directive in &self.directives {
+            match directive.result {
+                DirectiveResult::Passed => stats.passed += 1,
+                DirectiveResult::Failed(_) => stats.failed += 1,
+                DirectiveResult::Skipped(_) => stats.skipped += 1,
+            }
+        }
+        stats
+    }
+
+    /// The directives that failed
+    pub fn failures(&self) -> impl Iterator<Item = &DirectiveOutcome> {
+        self.directives
+            .iter()
+            .filter(|directive| matches!(directive.result, DirectiveResult::Failed(_)))
+    }
+}
+
+/// Results of a set of scripts
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpecTestReport {
+    /// Results of the individual scripts
+    pub scripts: Vec<ScriptReport>,
+}
+
+impl SpecTestReport {
+    /// Counts over all scripts
+    pub fn stats(&self) -> Stats {
+        let mut stats = Stats::default();
+        for script in &self.scripts {
+            stats.add(script.stats());
+        }
+        stats
+    }
+
+    /// Counts per proposal
+    pub fn by_proposal(&self) -> BTreeMap<&str, Stats> {
+        let mut proposals = BTreeMap::<&str, Stats>::new();
+        for script in &self.scripts {
+            proposals.entry(script.proposal.as_str()).or_default().add(script.stats());
+        }
+        proposals
+    }
+}
+
+impl fmt::Display for SpecTestReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (proposal, stats) in self.by_proposal() {
+            writeln!(f, "{proposal}: {stats}")?;
+        }
+        write!(f, "total: {}", self.stats())
+    }
+}
+
+/// Proposal a script belongs to, judging by its path
+///
+/// Scripts of the spec test suite live in `proposals/<name>/` unless they
+/// belong to the core specification. The SIMD scripts are part of the core
+/// suite since SIMD was merged, but are reported as `simd` to keep them
+/// apart.
+pub fn proposal_of(path: &Path) -> String {
+    let mut components = path.components().map(|component| component.as_os_str());
+    if components.any(|component| component == "proposals") {
+        if let Some(proposal) = components.next() {
+            return proposal.to_string_lossy().into_owned();
+        }
+    }
+
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy()).unwrap_or_default();
+    if stem.starts_with("simd_") {
+        "simd".into()
+    } else {
+        "core".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proposal_of() {
+        assert_eq!(proposal_of(Path::new("testsuite/i32.wast")), "core");
+        assert_eq!(proposal_of(Path::new("testsuite/simd_splat.wast")), "simd");
+        assert_eq!(
+            proposal_of(Path::new("testsuite/proposals/threads/atomic.wast")),
+            "threads"
+        );
+    }
+
+    #[test]
+    fn test_stats_by_proposal() {
+        let script = |proposal: &str, results: Vec<DirectiveResult>| ScriptReport {
+            name:       "script.wast".into(),
+            proposal:   proposal.into(),
+            error:      None,
+            directives: results
+                .into_iter()
+                .map(|result| DirectiveOutcome {
+                    line: 1,
+                    kind: "assert_return",
+                    result,
+                })
+                .collect(),
+        };
+        let report = SpecTestReport {
+            scripts: vec![
+                script(
+                    "core",
+                    vec![DirectiveResult::Passed, DirectiveResult::Failed("x".into())],
+                ),
+                script("simd", vec![DirectiveResult::Skipped("v128".into())]),
+                script("core", vec![DirectiveResult::Passed]),
+            ],
+        };
+
+        let proposals = report.by_proposal();
+        assert_eq!(
+            proposals["core"],
+            Stats {
+                passed:  2,
+                failed:  1,
+                skipped: 0,
+            }
+        );
+        assert_eq!(proposals["simd"].skipped, 1);
+        assert_eq!(report.stats().executed(), 3);
+        assert!(report.to_string().contains("core: 2/3 passed (66.7%), 0 skipped"));
+    }
+}
@@ -0,0 +1,574 @@
+//! Execution of `.wast` scripts against the engine
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use wast::{
+    parser::{
+        self,
+        ParseBuffer,
+    },
+    QuoteWat,
+    Wast,
+    WastDirective,
+    WastExecute,
+    WastInvoke,
+    Wat,
+};
+use wrt_error::Result;
+use wrt_foundation::{
+    memory_init::MemoryInitializer,
+    types::{
+        Limits,
+        RefType,
+        TableType,
+        ValueType,
+    },
+    values::{
+        FloatBits32,
+        FloatBits64,
+        Value,
+    },
+    CleanCoreMemoryType,
+};
+use wrt_runtime::{
+    engine::{
+        is_trap,
+        CapabilityAwareEngine,
+        CapabilityEngine,
+        EnginePreset,
+        InstanceHandle,
+    },
+    externs::Extern,
+    module::{
+        GlobalWrapper,
+        MemoryWrapper,
+        TableWrapper,
+    },
+    Global,
+    Memory,
+    Table,
+};
+
+use crate::{
+    report::{
+        proposal_of,
+        DirectiveOutcome,
+        DirectiveResult,
+        ScriptReport,
+        SpecTestReport,
+    },
+    values::{
+        arguments,
+        check_results,
+    },
+};
+
+/// Runner of spec test scripts
+///
+/// Every script runs in a fresh engine of the configured preset, which
+/// provides the `spectest` module the test suite imports from. Modules
+/// registered with `register` can provide memories, tables and globals to
+/// later modules of the same script; the engine cannot link functions
+/// between instances, so modules importing functions from another module
+/// fail to instantiate.
+#[derive(Debug, Clone)]
+pub struct SpecTestRunner {
+    preset: EnginePreset,
+}
+
+impl SpecTestRunner {
+    /// Create a runner whose engines use `preset`
+    pub fn new(preset: EnginePreset) -> Self {
+        Self { preset }
+    }
+
+    /// Run every `.wast` file below `dir`, in path order
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `dir` cannot be listed.
+    pub fn run_directory(&self, dir: &Path) -> Result<SpecTestReport> {
+        let mut paths = Vec::new();
+        collect_scripts(dir, &mut paths)?;
+        paths.sort();
+
+        Ok(SpecTestReport {
+            scripts: paths.iter().map(|path| self.run_file(path)).collect(),
+        })
+    }
+
+    /// Run the script at `path`
+    ///
+    /// The proposal of the script is derived from its path with
+    /// [`proposal_of`].
+    pub fn run_file(&self, path: &Path) -> ScriptReport {
+        match fs::read_to_string(path) {
+            Ok(source) => self.run_script(&path.display().to_string(), &proposal_of(path), &source),
+            Err(error) => ScriptReport {
+                name:       path.display().to_string(),
+                proposal:   proposal_of(path),
+                error:      Some(format!("failed to read script: {error}")),
+                directives: Vec::new(),
+            },
+        }
+    }
+
+    /// Run the script `source`, reporting it under `name` and `proposal`
+    pub fn run_script(&self, name: &str, proposal: &str, source: &str) -> ScriptReport {
+        let mut report = ScriptReport {
+            name:       name.into(),
+            proposal:   proposal.into(),
+            error:      None,
+            directives: Vec::new(),
+        };
+
+        let buffer = match ParseBuffer::new(source) {
+            Ok(buffer) => buffer,
+            Err(error) => {
+                report.error = Some(error.to_string());
+                return report;
+            },
+        };
+        let wast = match parser::parse::<Wast<'_>>(&buffer) {
+            Ok(wast) => wast,
+            Err(error) => {
+                report.error = Some(error.to_string());
+                return report;
+            },
+        };
+        let mut script = match Script::new(self.preset) {
+            Ok(script) => script,
+            Err(error) => {
+                report.error = Some(format!("failed to create engine: {error}"));
+                return report;
+            },
+        };
+
+        for directive in wast.directives {
+            let (line, _) = directive.span().linecol_in(source);
+            let kind = directive_kind(&directive);
+            report.directives.push(DirectiveOutcome {
+                line: line + 1,
+                kind,
+                result: script.run(directive),
+            });
+        }
+        report
+    }
+}
+
+impl Default for SpecTestRunner {
+    fn default() -> Self {
+        Self::new(EnginePreset::QM)
+    }
+}
+
+fn collect_scripts(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<()> {
+    let entries = fs::read_dir(dir)
+        .map_err(|_| wrt_error::Error::system_io_error("Failed to list spec test directory"))?;
+    for entry in entries {
+        let path = entry
+            .map_err(|_| wrt_error::Error::system_io_error("Failed to list spec test directory"))?
+            .path();
+        if path.is_dir() {
+            collect_scripts(&path, paths)?;
+        } else if path.extension().is_some_and(|extension| extension == "wast") {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn directive_kind(directive: &WastDirective<'_>) -> &'static str {
+    match directive {
+        WastDirective::Module(_) => "module",
+        WastDirective::ModuleDefinition(_) => "module definition",
+        WastDirective::ModuleInstance { .. } => "module instance",
+        WastDirective::AssertMalformed { .. } => "assert_malformed",
+        WastDirective::AssertInvalid { .. } => "assert_invalid",
+        WastDirective::Register { .. } => "register",
+        WastDirective::Invoke(_) => "invoke",
+        WastDirective::AssertTrap { .. } => "assert_trap",
+        WastDirective::AssertReturn { .. } => "assert_return",
+        WastDirective::AssertExhaustion { .. } => "assert_exhaustion",
+        WastDirective::AssertUnlinkable { .. } => "assert_unlinkable",
+        WastDirective::AssertException { .. } => "assert_exception",
+        WastDirective::AssertSuspension { .. } => "assert_suspension",
+        WastDirective::Thread(_) => "thread",
+        WastDirective::Wait { .. } => "wait",
+    }
+}
+
+fn failed(message: impl Into<String>) -> DirectiveResult {
+    DirectiveResult::Failed(message.into())
+}
+
+fn skipped(message: impl Into<String>) -> DirectiveResult {
+    DirectiveResult::Skipped(message.into())
+}
+
+fn is_component(module: &QuoteWat<'_>) -> bool {
+    matches!(
+        module,
+        QuoteWat::Wat(Wat::Component(_)) | QuoteWat::QuoteComponent(..)
+    )
+}
+
+/// State of a running script
+struct Script {
+    engine:      CapabilityAwareEngine,
+    /// Most recently instantiated module
+    current:     Option<InstanceHandle>,
+    /// Instances by module name
+    instances:   HashMap<String, InstanceHandle>,
+    /// Binaries of module definitions by name
+    definitions: HashMap<String, Vec<u8>>,
+}
+
+impl Script {
+    fn new(preset: EnginePreset) -> Result<Self> {
+        MemoryInitializer::ensure_initialized()?;
+        let mut engine = CapabilityAwareEngine::with_preset(preset)?;
+        define_spectest(&mut engine);
+
+        Ok(Self {
+            engine,
+            current: None,
+            instances: HashMap::new(),
+            definitions: HashMap::new(),
+        })
+    }
+
+    fn run(&mut self, directive: WastDirective<'_>) -> DirectiveResult {
+        match directive {
+            WastDirective::Module(mut module) => {
+                if is_component(&module) {
+                    return skipped("components are not supported");
+                }
+                let name = module.name().map(|id| id.name().to_string());
+                let binary = match module.encode() {
+                    Ok(binary) => binary,
+                    Err(error) => return failed(format!("failed to encode module: {error}")),
+                };
+                match self.instantiate(&binary) {
+                    Ok(instance) => {
+                        self.current = Some(instance);
+                        if let Some(name) = name {
+                            self.instances.insert(name, instance);
+                        }
+                        DirectiveResult::Passed
+                    },
+                    Err(error) => failed(format!("failed to instantiate module: {error}")),
+                }
+            },
+            WastDirective::ModuleDefinition(mut module) => {
+                if is_component(&module) {
+                    return skipped("components are not supported");
+                }
+                let name = module.name().map_or_else(String::new, |id| id.name().to_string());
+                match module.encode() {
+                    Ok(binary) => {
+                        self.definitions.insert(name, binary);
+                        DirectiveResult::Passed
+                    },
+                    Err(error) => failed(format!("failed to encode module: {error}")),
+                }
+            },
+            WastDirective::ModuleInstance {
+                instance, module, ..
+            } => {
+                let definition = module.map_or_else(String::new, |id| id.name().to_string());
+                let Some(binary) = self.definitions.get(&definition).cloned() else {
+                    return failed(format!("unknown module definition {definition:?}"));
+                };
+                match self.instantiate(&binary) {
+                    Ok(handle) => {
+                        self.current = Some(handle);
+                        if let Some(instance) = instance {
+                            self.instances.insert(instance.name().to_string(), handle);
+                        }
+                        DirectiveResult::Passed
+                    },
+                    Err(error) => failed(format!("failed to instantiate module: {error}")),
+                }
+            },
+            WastDirective::Register { name, module, .. } => {
+                match self.instance(module.map(|id| id.name())) {
+                    Ok(instance) => match self.register(name, instance) {
+                        Ok(()) => DirectiveResult::Passed,
+                        Err(error) => failed(format!("failed to register module: {error}")),
+                    },
+                    Err(message) => failed(message),
+                }
+            },
+            WastDirective::Invoke(invoke) => match self.invoke(&invoke) {
+                Ok(Ok(_)) => DirectiveResult::Passed,
+                Ok(Err(error)) => failed(format!("invocation failed: {error}")),
+                Err(result) => result,
+            },
+            WastDirective::AssertReturn { exec, results, .. } => match self.execute(exec) {
+                Ok(Ok(values)) => match check_results(&values, &results) {
+                    Ok(()) => DirectiveResult::Passed,
+                    Err(message) => failed(message),
+                },
+                Ok(Err(error)) => failed(format!("expected results, got error: {error}")),
+                Err(result) => result,
+            },
+            WastDirective::AssertTrap { exec, message, .. } => match self.execute(exec) {
+                Ok(Ok(values)) => failed(format!("expected trap {message:?}, got {values:?}")),
+                Ok(Err(error)) if is_trap(&error) => DirectiveResult::Passed,
+                Ok(Err(error)) => failed(format!("expected trap {message:?}, got error: {error}")),
+                Err(result) => result,
+            },
+            WastDirective::AssertInvalid {
+                mut module,
+                message,
+                ..
+            }
+            | WastDirective::AssertMalformed {
+                mut module,
+                message,
+                ..
+            } => {
+                if is_component(&module) {
+                    return skipped("components are not supported");
+                }
+                // Text the encoder rejects is malformed as well
+                let Ok(binary) = module.encode() else {
+                    return DirectiveResult::Passed;
+                };
+                match self.engine.load_module(&binary) {
+                    Ok(_) => failed(format!("module loaded despite expected error {message:?}")),
+                    Err(_) => DirectiveResult::Passed,
+                }
+            },
+            WastDirective::AssertUnlinkable {
+                mut module,
+                message,
+                ..
+            } => {
+                let binary = match module.encode() {
+                    Ok(binary) => binary,
+                    Err(error) => return failed(format!("failed to encode module: {error}")),
+                };
+                match self.instantiate(&binary) {
+                    Ok(_) => failed(format!("module linked despite expected error {message:?}")),
+                    Err(_) => DirectiveResult::Passed,
+                }
+            },
+            WastDirective::AssertExhaustion { .. } => skipped("resource exhaustion is not checked"),
+            WastDirective::AssertException { .. } | WastDirective::AssertSuspension { .. } => {
+                skipped("exceptions and stack switching are not supported")
+            },
+            WastDirective::Thread(_) | WastDirective::Wait { .. } => {
+                skipped("threads are not supported")
+            },
+        }
+    }
+
+    fn instantiate(&mut self, binary: &[u8]) -> Result<InstanceHandle> {
+        let module = self.engine.load_module(binary)?;
+        self.engine.instantiate(module)
+    }
+
+    /// Look up a named instance, or the current one
+    fn instance(&self, name: Option<&str>) -> core::result::Result<InstanceHandle, String> {
+        match name {
+            Some(name) => self
+                .instances
+                .get(name)
+                .copied()
+                .ok_or_else(|| format!("unknown module {name:?}")),
+            None => self.current.ok_or_else(|| "no module instantiated".to_string()),
+        }
+    }
+
+    /// Make the memories, tables and globals `instance` exports available
+    /// to imports from `name`
+    fn register(&mut self, name: &str, instance: InstanceHandle) -> Result<()> {
+        for (export, item) in self.engine.exports(instance)? {
+            match item {
+                Extern::Memory(memory) => self.engine.define_memory(name, &export, memory.memory),
+                Extern::Table(table) => self.engine.define_table(name, &export, table.table),
+                Extern::Global(global) => self.engine.define_global(name, &export, global.global),
+                Extern::Func(_) => {},
+            }
+        }
+        self.instances.insert(name.to_string(), instance);
+        Ok(())
+    }
+
+    /// Run an invocation, returning its results or error
+    ///
+    /// The outer error is the result of a directive that could not run.
+    fn invoke(
+        &mut self,
+        invoke: &WastInvoke<'_>,
+    ) -> core::result::Result<Result<Vec<Value>>, DirectiveResult> {
+        let instance = self.instance(invoke.module.map(|id| id.name())).map_err(failed)?;
+        let args = arguments(&invoke.args).map_err(skipped)?;
+        Ok(self.engine.execute(instance, invoke.name, &args))
+    }
+
+    fn execute(
+        &mut self,
+        exec: WastExecute<'_>,
+    ) -> core::result::Result<Result<Vec<Value>>, DirectiveResult> {
+        match exec {
+            WastExecute::Invoke(invoke) => self.invoke(&invoke),
+            WastExecute::Wat(mut module) => {
+                let binary = module
+                    .encode()
+                    .map_err(|error| failed(format!("failed to encode module: {error}")))?;
+                Ok(self.instantiate(&binary).map(|_| Vec::new()))
+            },
+            WastExecute::Get { module, global, .. } => {
+                let instance = self.instance(module.map(|id| id.name())).map_err(failed)?;
+                Ok(match self.engine.get_export(instance, global) {
+                    Ok(Some(Extern::Global(export))) => {
+                        export.global.get().map(|value| vec![value])
+                    },
+                    Ok(_) => Err(wrt_error::Error::resource_not_found(
+                        "Global export not found",
+                    )),
+                    Err(error) => Err(error),
+                })
+            },
+        }
+    }
+}
+
+/// Define the `spectest` module the test suite imports from
+///
+/// The print functions are registered as host functions that do nothing.
+/// Items the engine cannot provide are left undefined, so that only the
+/// modules importing them fail to link.
+fn define_spectest(engine: &mut CapabilityAwareEngine) {
+    let memory = Memory::new(CleanCoreMemoryType {
+        limits: Limits::new(1, Some(2)),
+        shared: false,
+    });
+    if let Ok(memory) = memory {
+        engine.define_memory("spectest", "memory", MemoryWrapper::new(memory));
+    }
+
+    let table = Table::new(TableType {
+        element_type: RefType::Funcref,
+        limits:       Limits::new(10, Some(20)),
+    });
+    if let Ok(table) = table {
+        engine.define_table("spectest", "table", TableWrapper::new(table));
+    }
+
+    let globals = [
+        ("global_i32", ValueType::I32, Value::I32(666)),
+        ("global_i64", ValueType::I64, Value::I64(666)),
+        (
+            "global_f32",
+            ValueType::F32,
+            Value::F32(FloatBits32::from_float(666.6)),
+        ),
+        (
+            "global_f64",
+            ValueType::F64,
+            Value::F64(FloatBits64::from_float(666.6)),
+        ),
+    ];
+    for (name, value_type, value) in globals {
+        if let Ok(global) = Global::new(value_type, false, value) {
+            engine.define_global("spectest", name, GlobalWrapper::new(global));
+        }
+    }
+
+    for name in [
+        "print",
+        "print_i32",
+        "print_i64",
+        "print_f32",
+        "print_f64",
+        "print_i32_f32",
+        "print_f64_f64",
+    ] {
+        let _ = engine.register_host_function("spectest", name, |_| Ok(Vec::new()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directive_results() {
+        let script = r#"
+            (assert_invalid
+              (module (func (result i32) (i64.const 0)))
+              "type mismatch")
+            (assert_malformed (module quote "(func (i32.const))") "unexpected token")
+            (thread $t (module))
+            (invoke $missing "f")
+            (module
+              (func (export "add") (param i32 i32) (result i32)
+                (i32.add (local.get 0) (local.get 1)))
+              (func (export "div") (param i32 i32) (result i32)
+                (i32.div_s (local.get 0) (local.get 1))))
+            (assert_return (invoke "add" (i32.const 2) (i32.const 3)) (i32.const 5))
+            (assert_return (invoke "add" (i32.const 2) (i32.const 3)) (i32.const 6))
+            (assert_trap (invoke "div" (i32.const 1) (i32.const 0)) "integer divide by zero")
+            (assert_unlinkable (module (import "spectest" "missing" (func))) "unknown import")
+        "#;
+        let report = SpecTestRunner::default().run_script("inline", "core", script);
+
+        assert_eq!(report.error, None);
+        let results: Vec<_> = report
+            .directives
+            .iter()
+            .map(|directive| (directive.line, directive.kind))
+            .collect();
+        assert_eq!(
+            results,
+            [
+                (2, "assert_invalid"),
+                (5, "assert_malformed"),
+                (6, "thread"),
+                (7, "invoke"),
+                (8, "module"),
+                (13, "assert_return"),
+                (14, "assert_return"),
+                (15, "assert_trap"),
+                (16, "assert_unlinkable")
+            ]
+        );
+        assert_eq!(report.directives[0].result, DirectiveResult::Passed);
+        assert_eq!(report.directives[1].result, DirectiveResult::Passed);
+        assert!(matches!(
+            report.directives[2].result,
+            DirectiveResult::Skipped(_)
+        ));
+        assert!(matches!(
+            report.directives[3].result,
+            DirectiveResult::Failed(_)
+        ));
+        assert_eq!(report.directives[4].result, DirectiveResult::Passed);
+        assert_eq!(report.directives[5].result, DirectiveResult::Passed);
+        assert!(matches!(
+            report.directives[6].result,
+            DirectiveResult::Failed(_)
+        ));
+        assert_eq!(report.directives[7].result, DirectiveResult::Passed);
+        assert_eq!(report.directives[8].result, DirectiveResult::Passed);
+    }
+
+    #[test]
+    fn test_unparsable_script() {
+        let report = SpecTestRunner::default().run_script("broken", "core", "(assert_return");
+        assert!(report.error.is_some());
+        assert_eq!(report.stats().failed, 1);
+    }
+}
@@ -0,0 +1,200 @@
+//! Conversion of script arguments and matching of expected results
+
+use wast::{
+    core::{
+        AbstractHeapType,
+        HeapType,
+        NanPattern,
+        V128Pattern,
+        WastArgCore,
+        WastRetCore,
+    },
+    WastArg,
+    WastRet,
+};
+use wrt_foundation::values::{
+    ExternRef,
+    FloatBits32,
+    FloatBits64,
+    Value,
+    V128,
+};
+
+/// Convert the arguments of an invocation to runtime values
+pub(crate) fn arguments(args: &[WastArg<'_>]) -> Result<Vec<Value>, String> {
+    args.iter().map(argument).collect()
+}
+
+fn argument(arg: &WastArg<'_>) -> Result<Value, String> {
+    let WastArg::Core(arg) = arg else {
+        return Err("component model arguments are not supported".into());
+    };
+    Ok(match arg {
+        WastArgCore::I32(value) => Value::I32(*value),
+        WastArgCore::I64(value) => Value::I64(*value),
+        WastArgCore::F32(value) => Value::F32(FloatBits32::from_bits(value.bits)),
+        WastArgCore::F64(value) => Value::F64(FloatBits64::from_bits(value.bits)),
+        WastArgCore::V128(value) => Value::V128(V128 {
+            bytes: value.to_le_bytes(),
+        }),
+        WastArgCore::RefNull(HeapType::Abstract {
+            ty: AbstractHeapType::Func,
+            ..
+        }) => Value::FuncRef(None),
+        WastArgCore::RefNull(HeapType::Abstract {
+            ty: AbstractHeapType::Extern,
+            ..
+        }) => Value::ExternRef(None),
+        WastArgCore::RefExtern(index) => Value::ExternRef(Some(ExternRef { index: *index })),
+        other => return Err(format!("unsupported argument {other:?}")),
+    })
+}
+
+/// Check the results of an invocation against the expected ones
+pub(crate) fn check_results(actual: &[Value], expected: &[WastRet<'_>]) -> Result<(), String> {
+    if actual.len() != expected.len() {
+        return Err(format!(
+            "expected {} results, got {}: {actual:?}",
+            expected.len(),
+            actual.len()
+        ));
+    }
+    for (index, (actual, expected)) in actual.iter().zip(expected).enumerate() {
+        let WastRet::Core(expected) = expected else {
+            return Err("component model results are not supported".into());
+        };
+        if !matches(actual, expected)? {
+            return Err(format!(
+                "result {index}: expected {expected:?}, got {actual:?}"
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn matches(actual: &Value, expected: &WastRetCore<'_>) -> Result<bool, String> {
+    Ok(match (actual, expected) {
+        (Value::I32(actual), WastRetCore::I32(expected)) => actual == expected,
+        (Value::I64(actual), WastRetCore::I64(expected)) => actual == expected,
+        (Value::F32(actual), WastRetCore::F32(expected)) => f32_matches(actual.0, expected),
+        (Value::F64(actual), WastRetCore::F64(expected)) => f64_matches(actual.0, expected),
+        (Value::V128(actual), WastRetCore::V128(expected)) => v128_matches(actual.bytes, expected),
+        (Value::FuncRef(actual), WastRetCore::RefNull(_)) => actual.is_none(),
+        (Value::ExternRef(actual), WastRetCore::RefNull(_)) => actual.is_none(),
+        (Value::FuncRef(actual), WastRetCore::RefFunc(_)) => actual.is_some(),
+        (Value::ExternRef(actual), WastRetCore::RefExtern(expected)) => match (actual, expected) {
+            (Some(actual), Some(expected)) => actual.index == *expected,
+            (actual, None) => actual.is_some(),
+            (None, Some(_)) => false,
+        },
+        (actual, WastRetCore::Either(alternatives)) => {
+            for alternative in alternatives {
+                if matches(actual, alternative)? {
+                    return Ok(true);
+                }
+            }
+            false
+        },
+        (
+            _,
+            WastRetCore::RefHost(_)
+            | WastRetCore::RefAny
+            | WastRetCore::RefEq
+            | WastRetCore::RefArray
+            | WastRetCore::RefStruct
+            | WastRetCore::RefI31
+            | WastRetCore::RefI31Shared,
+        ) => return Err(format!("unsupported expected result {expected:?}")),
+        _ => false,
+    })
+}
+
+fn f32_matches(bits: u32, expected: &NanPattern<wast::token::F32>) -> bool {
+    match expected {
+        NanPattern::Value(expected) => bits == expected.bits,
+        // Sign is unspecified, the payload is the canonical one
+        NanPattern::CanonicalNan => bits & 0x7FFF_FFFF == 0x7FC0_0000,
+        // Sign and payload are unspecified apart from the quiet bit
+        NanPattern::ArithmeticNan => bits & 0x7FC0_0000 == 0x7FC0_0000,
+    }
+}
+
+fn f64_matches(bits: u64, expected: &NanPattern<wast::token::F64>) -> bool {
+    match expected {
+        NanPattern::Value(expected) => bits == expected.bits,
+        NanPattern::CanonicalNan => bits & 0x7FFF_FFFF_FFFF_FFFF == 0x7FF8_0000_0000_0000,
+        NanPattern::ArithmeticNan => bits & 0x7FF8_0000_0000_0000 == 0x7FF8_0000_0000_0000,
+    }
+}
+
+fn v128_matches(bytes: [u8; 16], expected: &V128Pattern) -> bool {
+    fn lanes<const N: usize>(bytes: [u8; 16]) -> impl Iterator<Item = [u8; N]> {
+        (0..16 / N).map(move |lane| {
+            let mut out = [0; N];
+            out.copy_from_slice(&bytes[lane * N..(lane + 1) * N]);
+            out
+        })
+    }
+
+    match expected {
+        V128Pattern::I8x16(expected) => lanes::<1>(bytes)
+            .zip(expected)
+            .all(|(lane, expected)| lane == expected.to_le_bytes()),
+        V128Pattern::I16x8(expected) => lanes::<2>(bytes)
+            .zip(expected)
+            .all(|(lane, expected)| lane == expected.to_le_bytes()),
+        V128Pattern::I32x4(expected) => lanes::<4>(bytes)
+            .zip(expected)
+            .all(|(lane, expected)| lane == expected.to_le_bytes()),
+        V128Pattern::I64x2(expected) => lanes::<8>(bytes)
+            .zip(expected)
+            .all(|(lane, expected)| lane == expected.to_le_bytes()),
+        V128Pattern::F32x4(expected) => lanes::<4>(bytes)
+            .zip(expected)
+            .all(|(lane, expected)| f32_matches(u32::from_le_bytes(lane), expected)),
+        V128Pattern::F64x2(expected) => lanes::<8>(bytes)
+            .zip(expected)
+            .all(|(lane, expected)| f64_matches(u64::from_le_bytes(lane), expected)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nan_patterns() {
+        assert!(f32_matches(0x7FC0_0000, &NanPattern::CanonicalNan));
+        assert!(f32_matches(0xFFC0_0000, &NanPattern::CanonicalNan));
+        assert!(!f32_matches(0x7FC0_0001, &NanPattern::CanonicalNan));
+        assert!(f32_matches(0x7FC0_0001, &NanPattern::ArithmeticNan));
+        assert!(!f32_matches(0x7F80_0001, &NanPattern::ArithmeticNan));
+
+        assert!(f64_matches(
+            0xFFF8_0000_0000_0000,
+            &NanPattern::CanonicalNan
+        ));
+        assert!(f64_matches(
+            0x7FFC_0000_0000_0001,
+            &NanPattern::ArithmeticNan
+        ));
+        assert!(!f64_matches(
+            0x7FF0_0000_0000_0001,
+            &NanPattern::ArithmeticNan
+        ));
+    }
+
+    #[test]
+    fn test_v128_lanes() {
+        let mut bytes = [0u8; 16];
+        bytes[..4].copy_from_slice(&(-1i32).to_le_bytes());
+        bytes[12..].copy_from_slice(&7i32.to_le_bytes());
+
+        assert!(v128_matches(bytes, &V128Pattern::I32x4([-1, 0, 0, 7])));
+        assert!(!v128_matches(bytes, &V128Pattern::I32x4([-1, 0, 7, 0])));
+        assert!(v128_matches(
+            bytes,
+            &V128Pattern::I64x2([0xFFFF_FFFF, 7 << 32])
+        ));
+    }
+}
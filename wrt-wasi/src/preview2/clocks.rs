@@ -5,6 +5,10 @@
 
 use core::any::Any;
 
+use wrt_foundation::platform_hooks::{
+    self,
+    PlatformClock,
+};
 use wrt_platform::time::PlatformTime;
 
 use crate::{
@@ -18,19 +22,14 @@
 ///
 /// Implements `wasi:clocks/monotonic-clock.now` for monotonic time
 pub fn wasi_monotonic_clock_now(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<Value>> {
-    // Get monotonic time using platform abstraction
-    let nanoseconds: u64 = PlatformTime::monotonic_ns();
-
-    Ok(vec![Value::U64(nanoseconds)])
+    Ok(vec![Value::U64(monotonic_ns())])
 }
 
 /// WASI wall clock now operation
 ///
 /// Implements `wasi:clocks/wall-clock.now` for wall clock time
 pub fn wasi_wall_clock_now(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<Value>> {
-    // Get wall clock time using platform abstraction
-    let total_ns = PlatformTime::wall_clock_ns()
-        .map_err(|_| Error::wasi_capability_unavailable("Wall clock not available"))?;
+    let total_ns = wall_clock_ns()?;
 
     // Convert to seconds and nanoseconds
     let seconds = total_ns / 1_000_000_000;
@@ -50,22 +49,14 @@ pub fn wasi_monotonic_clock_resolution(
     _target: &mut dyn Any,
     _args: Vec<Value>,
 ) -> Result<Vec<Value>> {
-    // Get monotonic clock resolution using platform abstraction
-    // For now, return 1 nanosecond resolution
-    let resolution = 1u64;
-
-    Ok(vec![Value::U64(resolution)])
+    Ok(vec![Value::U64(resolution_ns())])
 }
 
 /// WASI wall clock resolution operation
 ///
 /// Implements `wasi:clocks/wall-clock.resolution` for wall clock precision
 pub fn wasi_wall_clock_resolution(_target: &mut dyn Any, _args: Vec<Value>) -> Result<Vec<Value>> {
-    // Get wall clock resolution using platform abstraction
-    // For now, return 1 nanosecond resolution
-    let resolution = 1u64;
-
-    Ok(vec![Value::U64(resolution)])
+    Ok(vec![Value::U64(resolution_ns())])
 }
 
 /// WASI process CPU time operation
@@ -90,6 +81,27 @@ pub fn wasi_thread_cpu_time_now(_target: &mut dyn Any, _args: Vec<Value>) -> Res
     Ok(vec![Value::U64(cpu_time)])
 }
 
+/// Monotonic time in nanoseconds, from the platform clock installed through
+/// [`platform_hooks`] if there is one
+fn monotonic_ns() -> u64 {
+    platform_hooks::monotonic_ns().unwrap_or_else(PlatformTime::monotonic_ns)
+}
+
+/// Wall clock time in nanoseconds since the Unix epoch, from the installed
+/// platform clock if there is one
+fn wall_clock_ns() -> Result<u64> {
+    match platform_hooks::clock() {
+        Some(clock) => clock.wall_clock_ns(),
+        None => PlatformTime::wall_clock_ns().ok(),
+    }
+    .ok_or_else(|| Error::wasi_capability_unavailable("Wall clock not available"))
+}
+
+/// Clock resolution in nanoseconds, 1 without an installed platform clock
+fn resolution_ns() -> u64 {
+    platform_hooks::clock().map_or(1, PlatformClock::resolution_ns)
+}
+
 /// Convert nanoseconds to WASI datetime record
 ///
 /// Helper function to convert nanoseconds since Unix epoch to WASI datetime
@@ -151,10 +163,7 @@ pub fn get_time_with_capabilities(
                 ));
             }
 
-            let total_ns = PlatformTime::wall_clock_ns()
-                .map_err(|_| Error::wasi_capability_unavailable("Wall clock not available"))?;
-
-            Ok(total_ns)
+            wall_clock_ns()
         },
         WasiClockType::Monotonic => {
             if !capabilities.monotonic_access {
@@ -163,7 +172,7 @@ pub fn get_time_with_capabilities(
                 ));
             }
 
-            Ok(PlatformTime::monotonic_ns())
+            Ok(monotonic_ns())
         },
         WasiClockType::ProcessCpuTime => {
             if !capabilities.process_cputime_access {
@@ -118,6 +118,13 @@ fn extract_length(args: &[Value]) -> Result<usize> {
 
 /// Generate secure random bytes using platform-specific implementation
 fn generate_secure_random(len: usize) -> Result<Vec<u8>> {
+    // An entropy source installed by the integrator takes precedence
+    if let Some(entropy) = wrt_foundation::platform_hooks::entropy() {
+        let mut buffer = vec![0u8; len];
+        entropy.fill_bytes(&mut buffer)?;
+        return Ok(buffer);
+    }
+
     #[cfg(all(feature = "std", target_os = "linux"))]
     {
         use std::{
@@ -196,10 +203,9 @@ fn generate_secure_random(len: usize) -> Result<Vec<u8>> {
 
     #[cfg(not(feature = "std"))]
     {
-        // In no_std environment, use platform-specific secure random if available
-        // For now, return an error indicating secure random is not available
+        // Without an installed entropy source there is no secure random in no_std
         Err(Error::wasi_capability_unavailable(
-            "Secure random not available in no_std environment",
+            "Secure random not available without a platform entropy source",
         ))
     }
 
@@ -229,9 +235,15 @@ fn generate_secure_random(len: usize) -> Result<Vec<u8>> {
 
 /// Generate pseudo-random bytes using fast non-cryptographic implementation
 fn generate_pseudo_random(len: usize) -> Result<Vec<u8>> {
-    // Use platform time as seed
+    use wrt_foundation::platform_hooks;
     use wrt_platform::time::PlatformTime;
-    let seed = PlatformTime::monotonic_ns();
+
+    // Seed from the installed entropy source, else from the clock
+    let mut seed_bytes = [0u8; 8];
+    let seed = match platform_hooks::entropy().map(|entropy| entropy.fill_bytes(&mut seed_bytes)) {
+        Some(Ok(())) => u64::from_le_bytes(seed_bytes),
+        _ => platform_hooks::monotonic_ns().unwrap_or_else(PlatformTime::monotonic_ns),
+    };
 
     let mut buffer = vec![0u8; len];
 